@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// `export` archives the user's personal data; `deletion` deletes their
+/// account and anonymizes what they authored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, TS)]
+#[sqlx(type_name = "account_data_job_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AccountDataJobKind {
+    Export,
+    Deletion,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, TS)]
+#[sqlx(type_name = "account_data_job_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AccountDataJobStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct AccountDataJob {
+    pub id: Uuid,
+    pub kind: AccountDataJobKind,
+    pub status: AccountDataJobStatus,
+    /// Why the job failed.
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// A short-lived link to a completed export's archive, until it expires.
+    pub download_url: Option<String>,
+    pub archive_expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListAccountDataJobsResponse {
+    pub jobs: Vec<AccountDataJob>,
+}
+
+/// The account's email address, to confirm the deletion.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DeleteAccountRequest {
+    pub email: String,
+}
@@ -15,6 +15,10 @@ pub struct Attachment {
 }
 
 /// An attachment with its associated blob data (for API responses).
+///
+/// `mime_type` is detected from the file's content where it is recognized,
+/// `width` and `height` are set for images and video, and `duration_ms` for
+/// audio and video, so clients can pick a preview or player up front.
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct AttachmentWithBlob {
     pub id: Uuid,
@@ -32,6 +36,8 @@ pub struct AttachmentWithBlob {
     pub hash: String,
     pub width: Option<i32>,
     pub height: Option<i32>,
+    #[ts(type = "number | null")]
+    pub duration_ms: Option<i64>,
 }
 
 /// An attachment with blob data and a presigned file URL.
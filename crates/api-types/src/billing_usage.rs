@@ -0,0 +1,34 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A finished agent run, reported by the local client that executed it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportAgentUsageRequest {
+    pub execution_process_id: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executor: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberAgentUsage {
+    pub user_id: Uuid,
+    pub agent_runs: i64,
+    pub agent_minutes: i64,
+}
+
+/// Metered usage of an organization for its current billing period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganizationUsageResponse {
+    pub organization_id: Uuid,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub agent_minutes: i64,
+    /// Members who ran at least one agent during the period.
+    pub active_seats: i64,
+    /// Agent minutes already sent to the payments provider.
+    pub reported_agent_minutes: i64,
+    pub members: Vec<MemberAgentUsage>,
+}
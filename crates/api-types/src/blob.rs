@@ -15,6 +15,9 @@ pub struct Blob {
     pub hash: String,
     pub width: Option<i32>,
     pub height: Option<i32>,
+    /// Playback length of audio and video, in milliseconds.
+    #[ts(type = "number | null")]
+    pub duration_ms: Option<i64>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CalendarFeedResponse {
+    /// Secret iCalendar URL of the user's assigned issues' due dates.
+    pub url: String,
+}
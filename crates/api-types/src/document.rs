@@ -0,0 +1,105 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::some_if_present;
+
+/// A markdown page in a project.
+///
+/// Issues mentioned by simple ID (`APP-12`) are linked to the document, and
+/// attachments embedded as `attachment://<id>` are kept for as long as the
+/// content embeds them.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct Document {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub title: String,
+    pub content: String,
+    /// Incremented on every save.
+    pub revision: i32,
+    pub created_by: Option<Uuid>,
+    pub updated_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub issue_ids: Vec<Uuid>,
+    pub attachment_ids: Vec<Uuid>,
+}
+
+/// A document without its content, for listings.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DocumentSummary {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub title: String,
+    pub revision: i32,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CreateDocumentRequest {
+    pub project_id: Uuid,
+    pub title: String,
+    #[ts(optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UpdateDocumentRequest {
+    /// The revision the edit was made against. The update is rejected if the
+    /// document has been saved since.
+    pub revision: i32,
+    #[serde(
+        default,
+        deserialize_with = "some_if_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub title: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "some_if_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListDocumentsResponse {
+    pub documents: Vec<DocumentSummary>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SearchDocumentsRequest {
+    pub project_id: Uuid,
+    /// Matched against titles and content.
+    pub search: String,
+    #[ts(optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DocumentRevision {
+    pub document_id: Uuid,
+    pub revision: i32,
+    pub title: String,
+    pub content: String,
+    pub author_user_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DocumentRevisionSummary {
+    pub revision: i32,
+    pub title: String,
+    pub author_user_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListDocumentRevisionsResponse {
+    /// Newest first.
+    pub revisions: Vec<DocumentRevisionSummary>,
+}
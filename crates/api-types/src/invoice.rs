@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Cursor pagination over billing documents, newest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListBillingDocumentsQuery {
+    #[serde(default)]
+    pub limit: Option<u32>,
+    /// ID of the last document of the previous page.
+    #[serde(default)]
+    pub starting_after: Option<String>,
+}
+
+/// An invoice issued by the payments provider. Amounts are in the smallest
+/// currency unit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BillingInvoice {
+    pub id: String,
+    pub number: Option<String>,
+    pub status: Option<String>,
+    pub currency: String,
+    pub total: i64,
+    pub amount_paid: i64,
+    pub amount_due: i64,
+    pub created_at: DateTime<Utc>,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub hosted_invoice_url: Option<String>,
+    /// Whether the PDF can be downloaded from the invoice's `pdf` endpoint.
+    pub has_pdf: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListInvoicesResponse {
+    pub invoices: Vec<BillingInvoice>,
+    pub has_more: bool,
+}
+
+/// A receipt for a payment. Amounts are in the smallest currency unit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BillingReceipt {
+    pub id: String,
+    pub receipt_number: Option<String>,
+    pub amount: i64,
+    pub amount_refunded: i64,
+    pub currency: String,
+    pub paid: bool,
+    pub created_at: DateTime<Utc>,
+    pub receipt_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListReceiptsResponse {
+    pub receipts: Vec<BillingReceipt>,
+    pub has_more: bool,
+}
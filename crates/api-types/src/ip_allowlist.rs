@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// CIDR ranges, such as `203.0.113.0/24`, the organization's data may be
+/// reached from. Empty allows any address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct OrganizationIpAllowlist {
+    pub organization_id: Uuid,
+    pub ranges: Vec<String>,
+}
+
+/// Single addresses are accepted as ranges of one.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UpdateIpAllowlistRequest {
+    pub ranges: Vec<String>,
+}
+
+/// Sent with a 403 when the request's address isn't on the allowlist of the
+/// organization it reaches.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct IpNotAllowedError {
+    pub organization_id: Uuid,
+    /// `None` when the address couldn't be determined.
+    pub ip_address: Option<String>,
+}
@@ -144,11 +144,39 @@ pub struct UpdateIssueRequest {
         skip_serializing_if = "Option::is_none"
     )]
     pub extension_metadata: Option<Value>,
+    /// `updated_at` of the issue the update was made against. If the issue
+    /// has changed since, the update is rejected with a `MutationConflict`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_updated_at: Option<DateTime<Utc>>,
+}
+
+/// Move an issue within or between status columns. The server picks the new
+/// `sort_order` from the issue's neighbours in the target column.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ReorderIssueRequest {
+    /// Column to move the issue to; defaults to its current status.
+    #[ts(optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_id: Option<Uuid>,
+    /// Issue to place it directly after.
+    #[ts(optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_issue_id: Option<Uuid>,
+    /// Issue to place it directly before, used when `previous_issue_id` is
+    /// not set. With neither, the issue goes to the end of the column.
+    #[ts(optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_issue_id: Option<Uuid>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct ListIssuesQuery {
     pub project_id: Uuid,
+    /// Comma-separated issue fields to return, e.g. `title,status_id`. All
+    /// fields are returned when unset.
+    #[ts(optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fields: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -193,6 +221,11 @@ pub struct SearchIssuesRequest {
     #[ts(optional)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub offset: Option<i32>,
+    /// Comma-separated issue fields to return, e.g. `title,status_id`. All
+    /// fields are returned when unset.
+    #[ts(optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fields: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -21,7 +21,7 @@ pub struct CreateIssueAssigneeRequest {
     pub user_id: Uuid,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct ListIssueAssigneesQuery {
     pub issue_id: Uuid,
 }
@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, TS)]
+#[sqlx(type_name = "issue_code_reference_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum IssueCodeReferenceKind {
+    Commit,
+    PullRequest,
+}
+
+/// A commit or pull request on GitHub whose message, title or body mentions
+/// the issue by simple ID.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct IssueCodeReference {
+    pub id: Uuid,
+    pub issue_id: Uuid,
+    pub kind: IssueCodeReferenceKind,
+    /// `owner/name` of the repository.
+    pub repo_full_name: String,
+    /// The commit SHA or pull request number.
+    pub reference: String,
+    pub url: String,
+    /// The commit's first line or the pull request's title.
+    pub title: String,
+    pub author_login: Option<String>,
+    /// When the commit was made or the pull request opened.
+    pub referenced_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListIssueCodeReferencesResponse {
+    /// Most recent first.
+    pub references: Vec<IssueCodeReference>,
+}
@@ -27,15 +27,20 @@ pub struct CreateIssueCommentRequest {
     pub parent_id: Option<Uuid>,
 }
 
-#[derive(Debug, Clone, Deserialize, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct UpdateIssueCommentRequest {
     #[serde(default, deserialize_with = "some_if_present")]
     pub message: Option<String>,
     #[serde(default, deserialize_with = "some_if_present")]
     pub parent_id: Option<Option<Uuid>>,
+    /// `updated_at` of the comment the update was made against. If the
+    /// comment has changed since, the update is rejected with a
+    /// `MutationConflict`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_updated_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct ListIssueCommentsQuery {
     pub issue_id: Uuid,
 }
@@ -30,7 +30,7 @@ pub struct UpdateIssueCommentReactionRequest {
     pub emoji: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct ListIssueCommentReactionsQuery {
     pub comment_id: Uuid,
 }
@@ -19,7 +19,7 @@ pub struct CreateIssueFollowerRequest {
     pub user_id: Uuid,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct ListIssueFollowersQuery {
     pub issue_id: Uuid,
 }
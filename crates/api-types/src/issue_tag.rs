@@ -19,7 +19,7 @@ pub struct CreateIssueTagRequest {
     pub tag_id: Uuid,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct ListIssueTagsQuery {
     pub issue_id: Uuid,
 }
@@ -28,3 +28,31 @@ pub struct ListIssueTagsQuery {
 pub struct ListIssueTagsResponse {
     pub issue_tags: Vec<IssueTag>,
 }
+
+/// An issue tagged with an organization tag.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct IssueOrganizationTag {
+    pub id: Uuid,
+    pub issue_id: Uuid,
+    pub organization_tag_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CreateIssueOrganizationTagRequest {
+    /// Optional client-generated ID. If not provided, server generates one.
+    /// Using client-generated IDs enables stable optimistic updates.
+    #[ts(optional)]
+    pub id: Option<Uuid>,
+    pub issue_id: Uuid,
+    pub organization_tag_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListIssueOrganizationTagsQuery {
+    pub issue_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListIssueOrganizationTagsResponse {
+    pub issue_organization_tags: Vec<IssueOrganizationTag>,
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::IssuePriority;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct IssueTriageRequest {
+    pub project_id: Uuid,
+    pub title: String,
+    #[ts(optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Set when triaging an issue that already exists, so it isn't suggested
+    /// as a duplicate of itself.
+    #[ts(optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue_id: Option<Uuid>,
+}
+
+/// Suggestions for the user to confirm; nothing is applied to the issue.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct IssueTriageSuggestions {
+    pub tags: Vec<SuggestedTag>,
+    pub assignee: Option<SuggestedAssignee>,
+    pub priority: Option<SuggestedPriority>,
+    pub duplicates: Vec<SuggestedDuplicate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct SuggestedTag {
+    pub tag_id: Uuid,
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct SuggestedAssignee {
+    pub user_id: Uuid,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct SuggestedPriority {
+    pub priority: IssuePriority,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct SuggestedDuplicate {
+    pub issue_id: Uuid,
+    pub simple_id: String,
+    pub title: String,
+    pub reason: String,
+}
@@ -7,53 +7,91 @@
 
 use serde::{Deserialize, Deserializer};
 
+pub mod account_data;
 pub mod attachment;
 pub mod auth;
+pub mod billing_usage;
 pub mod blob;
+pub mod calendar;
+pub mod document;
 pub mod export;
+pub mod invoice;
+pub mod ip_allowlist;
 pub mod issue;
 pub mod issue_assignee;
+pub mod issue_code_reference;
 pub mod issue_comment;
 pub mod issue_comment_reaction;
 pub mod issue_follower;
 pub mod issue_relationship;
 pub mod issue_tag;
+pub mod issue_triage;
+pub mod markdown;
+pub mod mutation_batch;
 pub mod notification;
+pub mod notification_template;
 pub mod oauth;
 pub mod organization_member;
 pub mod organizations;
+pub mod plan;
 pub mod project;
 pub mod project_status;
 pub mod pull_request;
 pub mod pull_requests_local;
+pub mod query_subscription;
+pub mod release_notes;
 pub mod response;
+pub mod retention;
+pub mod session_anomaly;
+pub mod sla;
 pub mod tag;
+pub mod timeline;
 pub mod user;
+pub mod work_log;
 pub mod workspace;
 pub mod workspaces;
 
+pub use account_data::*;
 pub use attachment::*;
 pub use auth::*;
+pub use billing_usage::*;
 pub use blob::*;
+pub use calendar::*;
+pub use document::*;
 pub use export::*;
+pub use invoice::*;
+pub use ip_allowlist::*;
 pub use issue::*;
 pub use issue_assignee::*;
+pub use issue_code_reference::*;
 pub use issue_comment::*;
 pub use issue_comment_reaction::*;
 pub use issue_follower::*;
 pub use issue_relationship::*;
 pub use issue_tag::*;
+pub use issue_triage::*;
+pub use markdown::*;
+pub use mutation_batch::*;
 pub use notification::*;
+pub use notification_template::*;
 pub use oauth::*;
 pub use organization_member::*;
 pub use organizations::*;
+pub use plan::*;
 pub use project::*;
 pub use project_status::*;
 pub use pull_request::*;
 pub use pull_requests_local::*;
+pub use query_subscription::*;
+pub use release_notes::*;
 pub use response::*;
+pub use retention::*;
+pub use session_anomaly::*;
+pub use sla::*;
 pub use tag::*;
+pub use timeline::*;
 pub use user::*;
+pub use work_log::*;
 pub use workspace::*;
 pub use workspaces::*;
 
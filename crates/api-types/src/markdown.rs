@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Markdown to render for display in a project.
+///
+/// `#123` links to the project's issue number 123 and `@username` mentions an
+/// organization member. References that don't resolve are left as text.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RenderMarkdownRequest {
+    pub project_id: Uuid,
+    pub markdown: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RenderedMarkdown {
+    /// Sanitized HTML. Raw HTML in the source is escaped, and code blocks are
+    /// highlighted with `hl-`-prefixed classes for the client's theme.
+    pub html: String,
+    /// Issues linked from `#123` references.
+    pub issue_ids: Vec<Uuid>,
+    /// Users mentioned with `@username`.
+    pub mentioned_user_ids: Vec<Uuid>,
+}
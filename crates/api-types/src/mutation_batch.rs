@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    Issue, Project, ProjectStatus, UpdateIssueRequest, UpdateProjectRequest,
+    UpdateProjectStatusRequest,
+};
+
+/// One update in a batch, tagged with the table it applies to.
+#[derive(Debug, Clone, Deserialize, TS)]
+#[serde(tag = "table", rename_all = "snake_case")]
+pub enum BatchMutation {
+    Issues {
+        id: Uuid,
+        changes: UpdateIssueRequest,
+    },
+    Projects {
+        id: Uuid,
+        changes: UpdateProjectRequest,
+    },
+    ProjectStatuses {
+        id: Uuid,
+        changes: UpdateProjectStatusRequest,
+    },
+}
+
+/// Mutations applied in one transaction: either all of them succeed or none
+/// are applied.
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct BatchMutationRequest {
+    pub mutations: Vec<BatchMutation>,
+}
+
+/// The updated row of one mutation, in request order.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "table", content = "data", rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
+pub enum BatchMutationResult {
+    Issues(Issue),
+    Projects(Project),
+    ProjectStatuses(ProjectStatus),
+}
@@ -6,7 +6,7 @@ use uuid::Uuid;
 
 use crate::{IssuePriority, some_if_present};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, TS)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type, TS)]
 #[serde(rename_all = "snake_case")]
 #[sqlx(type_name = "notification_type", rename_all = "snake_case")]
 pub enum NotificationType {
@@ -19,6 +19,10 @@ pub enum NotificationType {
     IssueDeleted,
     IssueTitleChanged,
     IssueDescriptionChanged,
+    IssueEnteredFilter,
+    IssueLeftFilter,
+    IssueDueSoon,
+    IssueSlaBreached,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
@@ -78,6 +82,16 @@ pub struct NotificationPayload {
     pub assignee_user_id: Option<Uuid>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub emoji: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subscription_id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subscription_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_date: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sla_policy_id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sla_policy_name: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, TS)]
@@ -85,3 +99,58 @@ pub struct UpdateNotificationRequest {
     #[serde(default, deserialize_with = "some_if_present")]
     pub seen: Option<bool>,
 }
+
+/// Unread notifications of a user in one organization, kept up to date by the
+/// database as notifications are created, read or deleted.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct NotificationUnreadCount {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub organization_id: Uuid,
+    pub unread_count: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UnreadNotificationCountQuery {
+    /// Count only this organization's notifications; all organizations if
+    /// not provided.
+    #[ts(optional)]
+    pub organization_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UnreadNotificationCountResponse {
+    pub unread_count: i32,
+}
+
+/// Mark the caller's notifications read or unread. Without
+/// `notification_ids`, applies to every notification matching the filters.
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct MarkNotificationsRequest {
+    pub seen: bool,
+    #[ts(optional)]
+    pub notification_ids: Option<Vec<Uuid>>,
+    #[ts(optional)]
+    pub organization_id: Option<Uuid>,
+    #[ts(optional)]
+    pub issue_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct MarkNotificationsResponse {
+    pub updated_count: usize,
+}
+
+/// An issue the user gets no notifications about.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct NotificationIssueMute {
+    pub issue_id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListNotificationIssueMutesResponse {
+    pub mutes: Vec<NotificationIssueMute>,
+}
@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::NotificationType;
+
+/// A project's copy for one notification type in one locale, used in place
+/// of the built-in English text for recipients whose locale selects it.
+///
+/// Templates may use `{actor}`, `{issue}` and `{issue_title}`, plus
+/// placeholders specific to the notification type: `{comment}` for
+/// comments, `{old_status}` and `{new_status}`, `{old_priority}` and
+/// `{new_priority}`, `{new_title}`, `{emoji}` for reactions, `{filter}`
+/// for saved filters, `{due_date}` for reminders and `{sla_policy}` for SLA
+/// breaches. Write `{{` and `}}` for literal braces.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct NotificationTemplate {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub notification_type: NotificationType,
+    /// BCP 47 tag such as `de` or `pt-BR`.
+    pub locale: String,
+    pub title: String,
+    pub body: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create the template for the type and locale, or replace it.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UpsertNotificationTemplateRequest {
+    pub notification_type: NotificationType,
+    pub locale: String,
+    pub title: String,
+    #[ts(optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListNotificationTemplatesResponse {
+    /// Locale for members who have not chosen one.
+    pub default_locale: Option<String>,
+    pub templates: Vec<NotificationTemplate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UpdateProjectNotificationLocaleRequest {
+    /// `null` clears the default.
+    pub default_locale: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ProjectNotificationLocale {
+    pub project_id: Uuid,
+    pub default_locale: Option<String>,
+}
@@ -139,6 +139,7 @@ pub struct OrganizationMemberWithProfile {
     pub last_name: Option<String>,
     pub username: Option<String>,
     pub email: Option<String>,
+    pub display_name: Option<String>,
     pub avatar_url: Option<String>,
 }
 
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// The billing plan an organization's entitlements come from. `unlimited`
+/// when billing is not enabled on the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum BillingPlan {
+    Free,
+    Paid,
+    Unlimited,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanEntitlement {
+    MaxProjects,
+    MaxMembers,
+    Webhooks,
+}
+
+/// What an organization's plan allows. Limits are absent when unlimited.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PlanEntitlements {
+    pub plan: BillingPlan,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub max_projects: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub max_members: Option<i32>,
+    pub webhooks: bool,
+}
+
+/// Sent as `upgrade` in the body of a 402 response when an action needs a
+/// higher plan.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UpgradeRequiredError {
+    pub entitlement: PlanEntitlement,
+    pub plan: BillingPlan,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub limit: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub current: Option<i32>,
+}
@@ -37,7 +37,15 @@ pub struct UpdateProjectRequest {
     pub sort_order: Option<i32>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// How a project guards against concurrent edits.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ProjectConcurrencySettings {
+    /// Reject issue and comment updates that don't name the version they
+    /// were made against, through `If-Match` or `expected_updated_at`.
+    pub require_if_match: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct ListProjectsQuery {
     pub organization_id: Uuid,
 }
@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::Type;
 use ts_rs::TS;
 use uuid::Uuid;
 
@@ -41,7 +42,7 @@ pub struct UpdateProjectStatusRequest {
     pub hidden: Option<bool>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct ListProjectStatusesQuery {
     pub project_id: Uuid,
 }
@@ -50,3 +51,51 @@ pub struct ListProjectStatusesQuery {
 pub struct ListProjectStatusesResponse {
     pub project_statuses: Vec<ProjectStatus>,
 }
+
+/// Where a status sits in a project's workflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, TS)]
+#[sqlx(type_name = "project_status_category", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectStatusCategory {
+    Backlog,
+    InProgress,
+    Done,
+}
+
+/// Workflow rules of a status.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ProjectStatusWorkflow {
+    pub status_id: Uuid,
+    pub category: Option<ProjectStatusCategory>,
+    /// Statuses issues in this status may move to. Empty allows any status.
+    pub allowed_next_status_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListProjectStatusWorkflowsQuery {
+    pub project_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListProjectStatusWorkflowsResponse {
+    pub workflows: Vec<ProjectStatusWorkflow>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateProjectStatusWorkflowRequest {
+    #[serde(default, deserialize_with = "some_if_present")]
+    pub category: Option<Option<ProjectStatusCategory>>,
+    /// Replaces the allowed transitions; an empty list allows any status.
+    #[serde(default, deserialize_with = "some_if_present")]
+    pub allowed_next_status_ids: Option<Vec<Uuid>>,
+}
+
+/// Sent as `transition` in the body of a 422 response when an issue is
+/// moved to a status its current status doesn't lead to.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct StatusTransitionError {
+    pub issue_id: Uuid,
+    pub from_status_id: Uuid,
+    pub to_status_id: Uuid,
+    pub allowed_status_ids: Vec<Uuid>,
+}
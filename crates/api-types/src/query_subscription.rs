@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::some_if_present;
+
+/// A saved issue filter; its owner is notified when issues enter or leave
+/// its results.
+///
+/// Filters are space-separated terms that all have to match: `tag:`,
+/// `status:`, `priority:` and `assignee:` take comma-separated values of
+/// which any may match, `is:open` and `is:completed` match on completion, and
+/// other words match the title or ID. Quote values with spaces
+/// (`status:"In review"`) and prefix a term with `-` to negate it.
+/// `assignee:` takes a username, an email, `me` or `none`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct QuerySubscription {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub query: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CreateQuerySubscriptionRequest {
+    pub project_id: Uuid,
+    pub name: String,
+    pub query: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UpdateQuerySubscriptionRequest {
+    #[serde(
+        default,
+        deserialize_with = "some_if_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub name: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "some_if_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub query: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListQuerySubscriptionsQuery {
+    #[ts(optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListQuerySubscriptionsResponse {
+    pub subscriptions: Vec<QuerySubscription>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct QuerySubscriptionIssuesResponse {
+    pub issue_ids: Vec<Uuid>,
+}
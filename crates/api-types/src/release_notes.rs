@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ReleaseNotesRequest {
+    pub project_id: Uuid,
+    /// Issues completed at or after this time are included.
+    pub from: DateTime<Utc>,
+    /// Issues completed before this time are included.
+    pub to: DateTime<Utc>,
+    /// Tags to group by, in section order. When left out, each issue goes
+    /// under its first tag by name.
+    #[ts(optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ReleaseNotes {
+    pub sections: Vec<ReleaseNotesSection>,
+    /// The notes rendered as Markdown.
+    pub markdown: String,
+}
+
+/// Issues grouped under a tag; `tag_id` is unset for issues without one.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ReleaseNotesSection {
+    pub tag_id: Option<Uuid>,
+    pub title: String,
+    pub issues: Vec<ReleaseNotesIssue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ReleaseNotesIssue {
+    pub issue_id: Uuid,
+    pub simple_id: String,
+    pub title: String,
+    pub completed_at: DateTime<Utc>,
+    /// Pull requests linked to the issue, leaving out closed ones.
+    pub pull_requests: Vec<ReleaseNotesPullRequest>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ReleaseNotesPullRequest {
+    pub number: i32,
+    pub url: String,
+}
@@ -9,6 +9,19 @@ pub struct MutationResponse<T> {
     pub txid: i64,
 }
 
+/// Sent as `conflict` in the body of a 409 response when an update was made
+/// against an outdated version of a row, so the client can merge instead of
+/// overwriting.
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct MutationConflict<T> {
+    /// The row as currently stored.
+    pub server: T,
+    /// The update as sent.
+    pub client: serde_json::Value,
+    /// Fields the update sets to values that differ from the stored row.
+    pub conflicting_fields: Vec<String>,
+}
+
 /// Response wrapper for delete endpoints.
 #[derive(Debug, Serialize, Deserialize, TS)]
 pub struct DeleteResponse {
@@ -0,0 +1,72 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// How long an organization keeps data, in years. Closed issues are deleted
+/// that long after they were completed, attachments that long after they were
+/// uploaded; `None` keeps them forever, which is also the default.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct OrganizationRetentionPolicy {
+    pub organization_id: Uuid,
+    pub closed_issue_retention_years: Option<i16>,
+    pub attachment_retention_years: Option<i16>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UpdateRetentionPolicyRequest {
+    #[serde(default)]
+    pub closed_issue_retention_years: Option<i16>,
+    #[serde(default)]
+    pub attachment_retention_years: Option<i16>,
+}
+
+/// Nothing in a project on legal hold is deleted, by retention policies or
+/// by anyone, until the hold is released.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct LegalHold {
+    pub project_id: Uuid,
+    pub reason: String,
+    pub placed_by: Option<Uuid>,
+    pub placed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PlaceLegalHoldRequest {
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListLegalHoldsResponse {
+    pub holds: Vec<LegalHold>,
+}
+
+/// What the retention policy would delete from a project now. Attachments
+/// include those of the closed issues being deleted. Nothing is deleted
+/// from projects on legal hold.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RetentionProjectReport {
+    pub project_id: Uuid,
+    pub project_name: String,
+    pub legal_hold: bool,
+    #[ts(type = "number")]
+    pub closed_issues: i64,
+    #[ts(type = "number")]
+    pub attachments: i64,
+}
+
+/// A dry run of the retention policy. Closed issues completed and
+/// attachments uploaded before the cutoffs are due for deletion; the totals
+/// leave out projects on legal hold.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RetentionReport {
+    pub policy: OrganizationRetentionPolicy,
+    pub generated_at: DateTime<Utc>,
+    pub closed_issues_cutoff: Option<DateTime<Utc>>,
+    pub attachments_cutoff: Option<DateTime<Utc>>,
+    #[ts(type = "number")]
+    pub closed_issues: i64,
+    #[ts(type = "number")]
+    pub attachments: i64,
+    pub projects: Vec<RetentionProjectReport>,
+}
@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// `location_change` is a session used from a country it wasn't used from
+/// before; `rate_spike` is a session making far more requests than usual.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, TS)]
+#[sqlx(type_name = "auth_session_anomaly_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SessionAnomalyKind {
+    LocationChange,
+    RateSpike,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, TS)]
+#[sqlx(type_name = "auth_session_flag_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SessionFlagStatus {
+    Open,
+    Dismissed,
+    Revoked,
+}
+
+/// Unusual use of a member's session, open until an admin reviews it.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SessionFlag {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    pub user_id: Uuid,
+    pub kind: SessionAnomalyKind,
+    pub status: SessionFlagStatus,
+    pub description: String,
+    /// Whether the session was suspended until the flag is reviewed.
+    pub suspended: bool,
+    pub detected_at: DateTime<Utc>,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListSessionFlagsQuery {
+    /// Only flags with this status; all flags if not provided.
+    #[ts(optional)]
+    pub status: Option<SessionFlagStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListSessionFlagsResponse {
+    pub flags: Vec<SessionFlag>,
+}
+
+/// `dismiss` lifts the suspension the flag caused; `revoke` signs the
+/// session out for good.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionFlagResolution {
+    Dismiss,
+    Revoke,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ReviewSessionFlagRequest {
+    pub resolution: SessionFlagResolution,
+}
+
+/// With `auto_suspend`, a member's flagged session stops working until an
+/// admin reviews the flag.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct SessionAnomalySettings {
+    pub organization_id: Uuid,
+    pub auto_suspend: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UpdateSessionAnomalySettingsRequest {
+    pub auto_suspend: bool,
+}
@@ -0,0 +1,149 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::some_if_present;
+
+/// The hours SLA targets are counted in. Organizations that never set them
+/// work Monday to Friday, 09:00 to 17:00 UTC.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct OrganizationWorkingHours {
+    pub organization_id: Uuid,
+    /// IANA time zone such as `Europe/Berlin`.
+    pub timezone: String,
+    /// Minutes after local midnight the working day starts.
+    pub start_minute: i32,
+    /// Minutes after local midnight the working day ends, at most 1440.
+    pub end_minute: i32,
+    /// ISO weekdays, 1 for Monday through 7 for Sunday.
+    pub working_days: Vec<i16>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UpdateOrganizationWorkingHoursRequest {
+    pub timezone: String,
+    pub start_minute: i32,
+    pub end_minute: i32,
+    pub working_days: Vec<i16>,
+}
+
+/// `first_response` is met when someone other than the creator comments or
+/// the issue is completed; `resolution` when it is completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, TS)]
+#[sqlx(type_name = "sla_metric", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SlaMetric {
+    FirstResponse,
+    Resolution,
+}
+
+/// A target for issues matching `query`, written in the saved filter syntax
+/// (see [`crate::QuerySubscription`]); `assignee:me` matches nothing. The
+/// target is counted in working minutes from when the issue was created.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SlaPolicy {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    pub query: String,
+    pub metric: SlaMetric,
+    pub target_minutes: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CreateSlaPolicyRequest {
+    pub name: String,
+    pub query: String,
+    pub metric: SlaMetric,
+    pub target_minutes: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UpdateSlaPolicyRequest {
+    #[serde(
+        default,
+        deserialize_with = "some_if_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub name: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "some_if_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub query: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "some_if_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub metric: Option<SlaMetric>,
+    #[serde(
+        default,
+        deserialize_with = "some_if_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub target_minutes: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListSlaPoliciesResponse {
+    pub policies: Vec<SlaPolicy>,
+}
+
+/// Issues created in `[from, to)` are reported; the last 30 days when not
+/// given.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SlaReportQuery {
+    #[ts(optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<DateTime<Utc>>,
+    #[ts(optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// `pending` issues haven't met the target, and their deadline hasn't passed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum SlaState {
+    Met,
+    Breached,
+    Pending,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SlaIssueStatus {
+    pub issue_id: Uuid,
+    pub simple_id: String,
+    pub title: String,
+    pub created_at: DateTime<Utc>,
+    pub deadline: DateTime<Utc>,
+    /// When the issue was first responded to or completed, per the metric.
+    pub met_at: Option<DateTime<Utc>>,
+    pub state: SlaState,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SlaPolicyReport {
+    pub policy: SlaPolicy,
+    pub met: u32,
+    pub breached: u32,
+    pub pending: u32,
+    pub issues: Vec<SlaIssueStatus>,
+}
+
+/// Policies are evaluated against the issues' current tags, status and
+/// priority.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SlaReport {
+    pub project_id: Uuid,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub working_hours: OrganizationWorkingHours,
+    pub policies: Vec<SlaPolicyReport>,
+}
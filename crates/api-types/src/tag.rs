@@ -31,7 +31,7 @@ pub struct UpdateTagRequest {
     pub color: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct ListTagsQuery {
     pub project_id: Uuid,
 }
@@ -40,3 +40,80 @@ pub struct ListTagsQuery {
 pub struct ListTagsResponse {
     pub tags: Vec<Tag>,
 }
+
+/// A tag shared by every project in an organization.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct OrganizationTag {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub color: String,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateOrganizationTagRequest {
+    /// Optional client-generated ID. If not provided, server generates one.
+    /// Using client-generated IDs enables stable optimistic updates.
+    #[ts(optional)]
+    pub id: Option<Uuid>,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub color: String,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateOrganizationTagRequest {
+    #[serde(default, deserialize_with = "some_if_present")]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "some_if_present")]
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListOrganizationTagsQuery {
+    pub organization_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListOrganizationTagsResponse {
+    pub organization_tags: Vec<OrganizationTag>,
+}
+
+/// Project tags sharing a name across more than one project of an
+/// organization - candidates for merging into an organization tag.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DuplicateTagGroup {
+    pub name: String,
+    pub tags: Vec<Tag>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListDuplicateTagsResponse {
+    pub groups: Vec<DuplicateTagGroup>,
+}
+
+/// Replace project tags with one organization tag. Issues tagged with any of
+/// the project tags are tagged with the organization tag instead.
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct MergeTagsRequest {
+    pub organization_id: Uuid,
+    /// Project tags to merge; they may belong to any project of the
+    /// organization.
+    pub tag_ids: Vec<Uuid>,
+    /// Organization tag to merge into. If not provided, one is created (or
+    /// reused, if it exists) named after the first project tag.
+    #[ts(optional)]
+    pub organization_tag_id: Option<Uuid>,
+    /// Color for a newly created organization tag. Defaults to the color of
+    /// the first project tag.
+    #[ts(optional)]
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct MergeTagsResponse {
+    pub organization_tag: OrganizationTag,
+    pub removed_tag_ids: Vec<Uuid>,
+    /// Issues newly tagged with the organization tag.
+    pub retagged_issue_count: usize,
+}
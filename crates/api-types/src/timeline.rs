@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Scheduling data for a project's roadmap view.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ProjectTimeline {
+    pub project_id: Uuid,
+    /// When the schedule was computed; open issues without a start date
+    /// are scheduled from then.
+    pub computed_at: DateTime<Utc>,
+    pub items: Vec<TimelineItem>,
+    pub dependencies: Vec<TimelineDependency>,
+    /// Open issues that would delay the project's end if they slipped, in
+    /// schedule order.
+    pub critical_path: Vec<Uuid>,
+    /// Open issues in or behind a cycle of blocking relationships, which
+    /// can't be scheduled.
+    pub cyclic_issue_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TimelineItem {
+    pub issue_id: Uuid,
+    pub simple_id: String,
+    pub title: String,
+    pub parent_issue_id: Option<Uuid>,
+    pub start_date: Option<DateTime<Utc>>,
+    pub target_date: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Earliest the issue can start given its start date and blockers.
+    /// Scheduling fields are unset for completed and cyclic issues.
+    pub scheduled_start: Option<DateTime<Utc>>,
+    pub scheduled_end: Option<DateTime<Utc>>,
+    /// Latest the issue can start without delaying the project's end.
+    pub latest_start: Option<DateTime<Utc>>,
+    pub critical: bool,
+    /// The scheduled end is after the target date.
+    pub late: bool,
+}
+
+/// `blocker_id` has to be done before `blocked_id` can start.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct TimelineDependency {
+    pub blocker_id: Uuid,
+    pub blocked_id: Uuid,
+}
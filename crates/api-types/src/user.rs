@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 use uuid::Uuid;
 
+use crate::some_if_present;
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, TS)]
 pub struct User {
     pub id: Uuid,
@@ -21,3 +23,54 @@ pub struct UserData {
     pub last_name: Option<String>,
     pub username: Option<String>,
 }
+
+/// The requester's profile settings.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UserProfile {
+    pub user_id: Uuid,
+    pub email: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub username: Option<String>,
+    /// Shown instead of the first and last name when set.
+    pub display_name: Option<String>,
+    pub avatar_attachment_id: Option<Uuid>,
+    /// Signed URL of the uploaded avatar, or the OAuth provider's avatar.
+    pub avatar_url: Option<String>,
+    /// IANA time zone such as `Europe/Berlin`. Due-date reminders are sent
+    /// in it, or in UTC when unset.
+    pub timezone: Option<String>,
+    /// BCP 47 tag selecting notification and email templates.
+    pub locale: Option<String>,
+}
+
+/// Fields left out are unchanged; `null` clears them.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UpdateUserProfileRequest {
+    #[serde(
+        default,
+        deserialize_with = "some_if_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub display_name: Option<Option<String>>,
+    /// An image uploaded through the attachments API and not yet attached
+    /// to anything.
+    #[serde(
+        default,
+        deserialize_with = "some_if_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub avatar_attachment_id: Option<Option<Uuid>>,
+    #[serde(
+        default,
+        deserialize_with = "some_if_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub timezone: Option<Option<String>>,
+    #[serde(
+        default,
+        deserialize_with = "some_if_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub locale: Option<Option<String>>,
+}
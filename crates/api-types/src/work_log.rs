@@ -0,0 +1,115 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::some_if_present;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct WorkLog {
+    pub id: Uuid,
+    pub issue_id: Uuid,
+    pub user_id: Uuid,
+    pub minutes: i32,
+    pub note: Option<String>,
+    /// Day the work was done.
+    pub work_date: NaiveDate,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CreateWorkLogRequest {
+    pub minutes: i32,
+    #[ts(optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Defaults to today (UTC).
+    #[ts(optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub work_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UpdateWorkLogRequest {
+    #[serde(
+        default,
+        deserialize_with = "some_if_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub minutes: Option<i32>,
+    #[serde(
+        default,
+        deserialize_with = "some_if_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub note: Option<Option<String>>,
+    #[serde(
+        default,
+        deserialize_with = "some_if_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub work_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ListWorkLogsResponse {
+    pub work_logs: Vec<WorkLog>,
+    pub total_minutes: i64,
+}
+
+/// Filters for summing logged time across a project. `from` and `to` are
+/// inclusive work dates, e.g. a sprint's first and last day.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct WorkLogSummaryQuery {
+    pub project_id: Uuid,
+    #[ts(optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<NaiveDate>,
+    #[ts(optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<NaiveDate>,
+    /// Only time logged by this user.
+    #[ts(optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct WorkLogSummary {
+    pub total_minutes: i64,
+    /// Most time first.
+    pub by_issue: Vec<IssueWorkTotal>,
+    /// Most time first.
+    pub by_user: Vec<UserWorkTotal>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct IssueWorkTotal {
+    pub issue_id: Uuid,
+    pub simple_id: String,
+    pub title: String,
+    pub minutes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UserWorkTotal {
+    pub user_id: Uuid,
+    pub name: String,
+    pub minutes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct WorkTimer {
+    pub user_id: Uuid,
+    pub issue_id: Uuid,
+    pub note: Option<String>,
+    pub started_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct StartWorkTimerRequest {
+    #[ts(optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
@@ -0,0 +1,14 @@
+-- Append-only record of destructive actions performed through the local API.
+-- Rows are never updated or deleted by the application.
+CREATE TABLE audit_log (
+    id           BLOB PRIMARY KEY,
+    action       TEXT NOT NULL,
+    actor        TEXT NOT NULL,
+    workspace_id BLOB,
+    target_id    BLOB,
+    details      TEXT,  -- JSON object with action-specific context
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_audit_log_created_at ON audit_log(created_at);
+CREATE INDEX idx_audit_log_workspace_id ON audit_log(workspace_id);
@@ -0,0 +1,21 @@
+-- Full-text index over execution log lines (stdout/stderr). Rows are appended
+-- by the log writer as lines are persisted; execution_id is stored as the
+-- hyphenated UUID string because FTS5 columns are text-oriented.
+CREATE VIRTUAL TABLE execution_log_search USING fts5(
+    content,
+    execution_id UNINDEXED,
+    line_number UNINDEXED,
+    byte_offset UNINDEXED,
+    stream UNINDEXED,
+    tokenize = 'unicode61'
+);
+
+-- How far into each execution's JSONL log file the index has caught up, so
+-- indexing can resume after a restart without duplicating lines.
+CREATE TABLE execution_log_index_state (
+    execution_id  BLOB PRIMARY KEY,
+    indexed_lines INTEGER NOT NULL DEFAULT 0,
+    indexed_bytes INTEGER NOT NULL DEFAULT 0,
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+-- Per-repo opt-out for redacting secrets from persisted process logs
+ALTER TABLE repos ADD COLUMN log_redaction_enabled INTEGER NOT NULL DEFAULT 1;
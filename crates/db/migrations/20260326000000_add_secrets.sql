@@ -0,0 +1,11 @@
+-- Named secrets referenced from scripts as ${secret:NAME}. Values are
+-- encrypted with a per-machine key (AES-256-GCM); only the ciphertext and
+-- nonce are stored.
+CREATE TABLE secrets (
+    id          BLOB PRIMARY KEY,
+    name        TEXT NOT NULL UNIQUE,
+    nonce       BLOB NOT NULL,
+    ciphertext  BLOB NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
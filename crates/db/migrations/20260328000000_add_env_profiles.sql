@@ -0,0 +1,25 @@
+-- Named sets of environment variables attached to a repo. A workspace selects
+-- any number of its repos' profiles; their variables are injected into every
+-- script and agent process started in that workspace.
+CREATE TABLE env_profiles (
+    id                  BLOB PRIMARY KEY,
+    repo_id             BLOB NOT NULL REFERENCES repos(id) ON DELETE CASCADE,
+    name                TEXT NOT NULL,
+    -- JSON object of variable name -> value
+    variables           TEXT NOT NULL DEFAULT '{}',
+    -- JSON array of variable names that must resolve to a non-empty value
+    required_variables  TEXT NOT NULL DEFAULT '[]',
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    UNIQUE (repo_id, name)
+);
+
+CREATE TABLE workspace_env_profiles (
+    workspace_id    BLOB NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    env_profile_id  BLOB NOT NULL REFERENCES env_profiles(id) ON DELETE CASCADE,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (workspace_id, env_profile_id)
+);
+
+CREATE INDEX idx_workspace_env_profiles_env_profile_id
+    ON workspace_env_profiles(env_profile_id);
@@ -0,0 +1,9 @@
+-- Per-repo limits for setup/cleanup/archive scripts. A NULL timeout means the
+-- script may run indefinitely.
+ALTER TABLE repos ADD COLUMN setup_script_timeout_secs INTEGER;
+ALTER TABLE repos ADD COLUMN cleanup_script_timeout_secs INTEGER;
+ALTER TABLE repos ADD COLUMN archive_script_timeout_secs INTEGER;
+-- What to do with the rest of an action chain when one of these scripts fails
+ALTER TABLE repos ADD COLUMN script_on_failure TEXT NOT NULL DEFAULT 'abort'
+    CHECK (script_on_failure IN ('abort', 'continue', 'retry'));
+ALTER TABLE repos ADD COLUMN script_max_retries INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,22 @@
+-- Named scripts defined once and referenced from many repos. A repo's own
+-- script of the same kind, when set, overrides the referenced one.
+CREATE TABLE library_scripts (
+    id          BLOB PRIMARY KEY,
+    name        TEXT NOT NULL UNIQUE,
+    description TEXT,
+    script      TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE TABLE repo_script_refs (
+    repo_id             BLOB NOT NULL REFERENCES repos(id) ON DELETE CASCADE,
+    kind                TEXT NOT NULL
+        CHECK (kind IN ('setup', 'cleanup', 'archive', 'dev_server')),
+    library_script_id   BLOB NOT NULL REFERENCES library_scripts(id) ON DELETE CASCADE,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (repo_id, kind)
+);
+
+CREATE INDEX idx_repo_script_refs_library_script_id
+    ON repo_script_refs(library_script_id);
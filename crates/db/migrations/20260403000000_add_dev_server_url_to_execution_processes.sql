@@ -0,0 +1,4 @@
+-- Local URL a dev server announced in its output, detected while it runs and
+-- used to route the workspace preview proxy.
+ALTER TABLE execution_processes ADD COLUMN dev_server_url TEXT;
+ALTER TABLE execution_processes ADD COLUMN dev_server_port INTEGER;
@@ -0,0 +1,18 @@
+-- Named dev server commands per repo (e.g. frontend, backend, storybook),
+-- started and stopped independently of each other and of the repo's default
+-- dev server script.
+CREATE TABLE repo_dev_servers (
+    id          BLOB PRIMARY KEY,
+    repo_id     BLOB NOT NULL REFERENCES repos(id) ON DELETE CASCADE,
+    name        TEXT NOT NULL,
+    script      TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    UNIQUE (repo_id, name)
+);
+
+-- The named dev server an execution process runs; NULL for the default one.
+ALTER TABLE execution_processes ADD COLUMN dev_server_id BLOB;
+
+CREATE INDEX idx_execution_processes_dev_server_id
+    ON execution_processes(dev_server_id);
@@ -0,0 +1,29 @@
+-- Recurring agent runs: on each cron tick a workspace is created for the repo
+-- and the prompt is started with the configured executor.
+CREATE TABLE schedules (
+    id              BLOB PRIMARY KEY,
+    name            TEXT NOT NULL,
+    cron_expression TEXT NOT NULL,
+    repo_id         BLOB NOT NULL REFERENCES repos(id) ON DELETE CASCADE,
+    target_branch   TEXT NOT NULL,
+    prompt          TEXT NOT NULL,
+    executor_config TEXT NOT NULL,
+    paused          INTEGER NOT NULL DEFAULT 0,
+    next_run_at     TEXT,
+    last_run_at     TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_schedules_next_run_at ON schedules(next_run_at) WHERE paused = 0;
+
+CREATE TABLE schedule_runs (
+    id           BLOB PRIMARY KEY,
+    schedule_id  BLOB NOT NULL REFERENCES schedules(id) ON DELETE CASCADE,
+    workspace_id BLOB REFERENCES workspaces(id) ON DELETE SET NULL,
+    status       TEXT NOT NULL CHECK (status IN ('started', 'failed')),
+    error        TEXT,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_schedule_runs_schedule_id ON schedule_runs(schedule_id, created_at);
@@ -0,0 +1,38 @@
+-- Pipelines chain workspaces: when the session of one stage's workspace
+-- finishes, the next stage starts in a new workspace branched from it.
+CREATE TABLE pipelines (
+    id          BLOB PRIMARY KEY,
+    name        TEXT NOT NULL UNIQUE,
+    description TEXT,
+    stages      TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE TABLE pipeline_runs (
+    id              BLOB PRIMARY KEY,
+    pipeline_id     BLOB NOT NULL REFERENCES pipelines(id) ON DELETE CASCADE,
+    status          TEXT NOT NULL DEFAULT 'running'
+        CHECK (status IN ('running', 'completed', 'failed', 'cancelled')),
+    prompt          TEXT NOT NULL,
+    repos           TEXT NOT NULL,
+    executor_config TEXT NOT NULL,
+    error           TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_pipeline_runs_pipeline_id ON pipeline_runs(pipeline_id);
+
+CREATE TABLE pipeline_run_stages (
+    pipeline_run_id BLOB NOT NULL REFERENCES pipeline_runs(id) ON DELETE CASCADE,
+    stage_index     INTEGER NOT NULL,
+    workspace_id    BLOB REFERENCES workspaces(id) ON DELETE SET NULL,
+    status          TEXT NOT NULL DEFAULT 'running'
+        CHECK (status IN ('running', 'completed', 'failed', 'cancelled')),
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (pipeline_run_id, stage_index)
+);
+
+CREATE INDEX idx_pipeline_run_stages_workspace_id ON pipeline_run_stages(workspace_id);
@@ -0,0 +1,19 @@
+-- Attempt groups fan the same prompt out to several executors, one sibling
+-- workspace each, so the results can be compared side by side.
+CREATE TABLE attempt_groups (
+    id         BLOB PRIMARY KEY,
+    name       TEXT NOT NULL,
+    prompt     TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE TABLE attempt_group_workspaces (
+    attempt_group_id BLOB NOT NULL REFERENCES attempt_groups(id) ON DELETE CASCADE,
+    workspace_id     BLOB NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    executor_config  TEXT NOT NULL,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (attempt_group_id, workspace_id)
+);
+
+CREATE INDEX idx_attempt_group_workspaces_workspace_id
+    ON attempt_group_workspaces(workspace_id);
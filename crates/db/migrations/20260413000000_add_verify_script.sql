@@ -0,0 +1,46 @@
+-- Verify scripts run after the coding agent (and cleanup scripts) finish.
+-- Pass/fail counts parsed from their output are stored on the process.
+ALTER TABLE repos ADD COLUMN verify_script TEXT;
+
+ALTER TABLE execution_processes ADD COLUMN tests_passed INTEGER;
+ALTER TABLE execution_processes ADD COLUMN tests_failed INTEGER;
+ALTER TABLE execution_processes ADD COLUMN tests_skipped INTEGER;
+
+-- Add 'verifyscript' to the run_reason CHECK constraint, following
+-- 20260203000000_add_archive_script_to_repos.sql
+
+-- 1. Add the replacement column with the wider CHECK
+ALTER TABLE execution_processes
+  ADD COLUMN run_reason_new TEXT NOT NULL DEFAULT 'setupscript'
+    CHECK (run_reason_new IN ('setupscript',
+                               'cleanupscript',
+                               'archivescript',
+                               'verifyscript',
+                               'codingagent',
+                               'devserver'));
+
+-- 2. Copy existing values across
+UPDATE execution_processes
+  SET run_reason_new = run_reason;
+
+-- 3. Drop any indexes that reference run_reason
+DROP INDEX IF EXISTS idx_execution_processes_run_reason;
+DROP INDEX IF EXISTS idx_execution_processes_session_status_run_reason;
+DROP INDEX IF EXISTS idx_execution_processes_session_run_reason_created;
+
+-- 4. Remove the old column
+ALTER TABLE execution_processes DROP COLUMN run_reason;
+
+-- 5. Rename the new column back to the canonical name
+ALTER TABLE execution_processes
+  RENAME COLUMN run_reason_new TO run_reason;
+
+-- 6. Re-create all indexes
+CREATE INDEX idx_execution_processes_run_reason
+        ON execution_processes(run_reason);
+
+CREATE INDEX idx_execution_processes_session_status_run_reason
+        ON execution_processes (session_id, status, run_reason);
+
+CREATE INDEX idx_execution_processes_session_run_reason_created
+        ON execution_processes (session_id, run_reason, created_at DESC);
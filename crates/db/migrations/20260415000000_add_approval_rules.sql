@@ -0,0 +1,48 @@
+-- Approval rules decide tool approval requests without prompting: calls can
+-- be auto-approved, denied, or always sent to the user. Rules without a
+-- repo_id apply to every repo.
+CREATE TABLE approval_rules (
+    id             BLOB PRIMARY KEY,
+    repo_id        BLOB REFERENCES repos(id) ON DELETE CASCADE,
+    tool_name      TEXT,
+    command_prefix TEXT,
+    action         TEXT NOT NULL
+        CHECK (action IN ('auto_approve', 'require_approval', 'deny')),
+    description    TEXT,
+    created_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    CHECK (tool_name IS NOT NULL OR command_prefix IS NOT NULL)
+);
+
+CREATE INDEX idx_approval_rules_repo_id ON approval_rules(repo_id);
+
+-- Default rules: read-only tools and commands are approved automatically,
+-- while deletions, privilege escalation and network access always ask.
+INSERT INTO approval_rules (id, tool_name, command_prefix, action, description) VALUES
+    (randomblob(16), 'Read', NULL, 'auto_approve', 'Read-only tool'),
+    (randomblob(16), 'Glob', NULL, 'auto_approve', 'Read-only tool'),
+    (randomblob(16), 'Grep', NULL, 'auto_approve', 'Read-only tool'),
+    (randomblob(16), 'LS', NULL, 'auto_approve', 'Read-only tool'),
+    (randomblob(16), 'WebFetch', NULL, 'require_approval', 'Network access'),
+    (randomblob(16), 'WebSearch', NULL, 'require_approval', 'Network access'),
+    (randomblob(16), NULL, 'ls', 'auto_approve', 'Read-only command'),
+    (randomblob(16), NULL, 'cat', 'auto_approve', 'Read-only command'),
+    (randomblob(16), NULL, 'head', 'auto_approve', 'Read-only command'),
+    (randomblob(16), NULL, 'tail', 'auto_approve', 'Read-only command'),
+    (randomblob(16), NULL, 'wc', 'auto_approve', 'Read-only command'),
+    (randomblob(16), NULL, 'pwd', 'auto_approve', 'Read-only command'),
+    (randomblob(16), NULL, 'cd', 'auto_approve', 'Read-only command'),
+    (randomblob(16), NULL, 'grep', 'auto_approve', 'Read-only command'),
+    (randomblob(16), NULL, 'rg', 'auto_approve', 'Read-only command'),
+    (randomblob(16), NULL, 'git status', 'auto_approve', 'Read-only command'),
+    (randomblob(16), NULL, 'git log', 'auto_approve', 'Read-only command'),
+    (randomblob(16), NULL, 'git diff', 'auto_approve', 'Read-only command'),
+    (randomblob(16), NULL, 'git show', 'auto_approve', 'Read-only command'),
+    (randomblob(16), NULL, 'rm', 'require_approval', 'Deletes files'),
+    (randomblob(16), NULL, 'rmdir', 'require_approval', 'Deletes files'),
+    (randomblob(16), NULL, 'sudo', 'require_approval', 'Privilege escalation'),
+    (randomblob(16), NULL, 'curl', 'require_approval', 'Network access'),
+    (randomblob(16), NULL, 'wget', 'require_approval', 'Network access'),
+    (randomblob(16), NULL, 'ssh', 'require_approval', 'Network access'),
+    (randomblob(16), NULL, 'scp', 'require_approval', 'Network access'),
+    (randomblob(16), NULL, 'git push', 'require_approval', 'Network access');
@@ -0,0 +1,19 @@
+-- Files and URLs attached to a workspace as agent context. They are written
+-- to the worktree's .vibe-context/ directory when the agent is launched.
+CREATE TABLE workspace_context_items (
+    id            BLOB PRIMARY KEY,
+    workspace_id  BLOB NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    kind          TEXT NOT NULL CHECK (kind IN ('file', 'url')),
+    name          TEXT NOT NULL,
+    attachment_id BLOB REFERENCES attachments(id) ON DELETE CASCADE,
+    url           TEXT,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    UNIQUE (workspace_id, name),
+    CHECK (
+        (kind = 'file' AND attachment_id IS NOT NULL)
+        OR (kind = 'url' AND url IS NOT NULL)
+    )
+);
+
+CREATE INDEX idx_workspace_context_items_workspace_id
+    ON workspace_context_items(workspace_id);
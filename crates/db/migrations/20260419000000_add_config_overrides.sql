@@ -0,0 +1,14 @@
+-- Settings that override the global config for one repo or one workspace.
+-- Each row holds a JSON object of the keys it sets; absent keys fall through
+-- to the next layer (workspace -> repo -> global).
+CREATE TABLE repo_config_overrides (
+    repo_id     BLOB PRIMARY KEY REFERENCES repos(id) ON DELETE CASCADE,
+    overrides   TEXT NOT NULL DEFAULT '{}',
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE TABLE workspace_config_overrides (
+    workspace_id  BLOB PRIMARY KEY REFERENCES workspaces(id) ON DELETE CASCADE,
+    overrides     TEXT NOT NULL DEFAULT '{}',
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
@@ -0,0 +1,3 @@
+-- Cost reported by the agent for a turn, in USD. NULL when the agent
+-- doesn't report one.
+ALTER TABLE coding_agent_turns ADD COLUMN cost_usd REAL;
@@ -0,0 +1,12 @@
+-- Read-only public links to a session's transcript and diff. The link token
+-- is signed over the share id and expiry; revoking or deleting the row
+-- invalidates it before it expires.
+CREATE TABLE session_shares (
+    id          BLOB PRIMARY KEY,
+    session_id  BLOB NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    expires_at  TEXT NOT NULL,
+    revoked_at  TEXT,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_session_shares_session_id ON session_shares(session_id);
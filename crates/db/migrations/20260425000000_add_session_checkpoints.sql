@@ -0,0 +1,14 @@
+-- Named restore points of a session. A checkpoint marks the state right after
+-- an execution process: the conversation up to and including it, and the head
+-- commit it left every workspace repo at (a JSON array of
+-- {"repo_id", "head_commit"}).
+CREATE TABLE session_checkpoints (
+    id                   BLOB PRIMARY KEY,
+    session_id           BLOB NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    execution_process_id BLOB NOT NULL REFERENCES execution_processes(id) ON DELETE CASCADE,
+    label                TEXT NOT NULL,
+    repo_states          TEXT NOT NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_session_checkpoints_session_id ON session_checkpoints(session_id);
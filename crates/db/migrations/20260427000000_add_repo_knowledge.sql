@@ -0,0 +1,16 @@
+-- Learnings about a repo (build commands, gotchas, architecture notes) kept
+-- across sessions. Approved entries are added to the first prompt of every
+-- coding agent started on the repo; entries suggested by an agent wait for
+-- approval.
+CREATE TABLE repo_knowledge (
+    id          BLOB PRIMARY KEY,
+    repo_id     BLOB NOT NULL REFERENCES repos(id) ON DELETE CASCADE,
+    category    TEXT NOT NULL CHECK (category IN ('build', 'gotcha', 'architecture', 'other')),
+    content     TEXT NOT NULL,
+    status      TEXT NOT NULL CHECK (status IN ('approved', 'suggested')),
+    session_id  BLOB REFERENCES sessions(id) ON DELETE SET NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_repo_knowledge_repo_id ON repo_knowledge(repo_id);
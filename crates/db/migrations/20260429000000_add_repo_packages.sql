@@ -0,0 +1,17 @@
+-- Packages of a monorepo (pnpm/npm workspaces, cargo workspace members,
+-- bazel packages). A workspace can pick one package per repo: agents then
+-- start in the package directory and the package's dev script, if set,
+-- replaces the repo's.
+CREATE TABLE repo_packages (
+    id                 BLOB PRIMARY KEY,
+    repo_id            BLOB NOT NULL REFERENCES repos(id) ON DELETE CASCADE,
+    path               TEXT NOT NULL,
+    name               TEXT NOT NULL,
+    dev_server_script  TEXT,
+    created_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    UNIQUE (repo_id, path)
+);
+
+ALTER TABLE workspace_repos
+    ADD COLUMN repo_package_id BLOB REFERENCES repo_packages(id) ON DELETE SET NULL;
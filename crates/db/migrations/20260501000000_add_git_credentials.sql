@@ -0,0 +1,20 @@
+-- Git accounts (SSH keys, personal access tokens, OAuth tokens) for hosts
+-- like GitHub or GitLab. Several accounts per host are allowed; each repo
+-- picks the one its fetches, pushes and PR operations use. Tokens are
+-- encrypted with the per-machine secrets key; SSH keys are referenced by path.
+CREATE TABLE git_credentials (
+    id            BLOB PRIMARY KEY,
+    name          TEXT NOT NULL UNIQUE,
+    host          TEXT NOT NULL,
+    kind          TEXT NOT NULL
+                     CHECK (kind IN ('ssh_key', 'personal_access_token', 'oauth_token')),
+    username      TEXT,
+    ssh_key_path  TEXT,
+    nonce         BLOB,
+    ciphertext    BLOB,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+ALTER TABLE repos
+    ADD COLUMN git_credential_id BLOB REFERENCES git_credentials(id) ON DELETE SET NULL;
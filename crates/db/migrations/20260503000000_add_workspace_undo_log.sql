@@ -0,0 +1,18 @@
+-- Tombstones of destructive workspace actions (deletion, git resets) that can
+-- still be undone. The commits they need are kept alive by git refs under
+-- refs/vibe-kanban/undo/ until the entry expires. `workspace_id` has no
+-- foreign key, since entries of deleted workspaces are the point.
+CREATE TABLE workspace_undo_log (
+    id           BLOB PRIMARY KEY,
+    workspace_id BLOB NOT NULL,
+    action       TEXT NOT NULL
+                    CHECK (action IN ('workspace_deleted', 'git_reset')),
+    payload      TEXT NOT NULL,  -- JSON, see UndoPayload
+    undone_at    TEXT,
+    expires_at   TEXT NOT NULL,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_workspace_undo_log_workspace_id
+    ON workspace_undo_log(workspace_id, created_at);
+CREATE INDEX idx_workspace_undo_log_expires_at ON workspace_undo_log(expires_at);
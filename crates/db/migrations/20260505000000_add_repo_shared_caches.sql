@@ -0,0 +1,16 @@
+-- Dependency caches shared by every worktree of a repo, so setup scripts
+-- don't re-download dependencies for each workspace. In-tree caches (cargo's
+-- target dir, custom paths) are symlinked into each worktree; tool caches
+-- (cargo registry, pnpm store, pip cache) are handed to processes through
+-- environment variables. The cache directories live in the app's data dir.
+CREATE TABLE repo_shared_caches (
+    id         BLOB PRIMARY KEY,
+    repo_id    BLOB NOT NULL REFERENCES repos(id) ON DELETE CASCADE,
+    kind       TEXT NOT NULL
+                  CHECK (kind IN ('cargo_target', 'cargo_registry', 'pnpm_store', 'pip_cache', 'custom')),
+    path       TEXT,  -- worktree-relative path of custom caches
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE UNIQUE INDEX idx_repo_shared_caches_repo_kind_path
+    ON repo_shared_caches(repo_id, kind, COALESCE(path, ''));
@@ -0,0 +1,19 @@
+-- Pre-provisioned worktrees of frequently used repos, with the setup script
+-- already run, that new workspaces adopt instead of creating their own. Each
+-- sits on a placeholder branch that is renamed when a workspace claims it.
+CREATE TABLE warm_worktrees (
+    id            BLOB PRIMARY KEY,
+    repo_id       BLOB NOT NULL REFERENCES repos(id) ON DELETE CASCADE,
+    target_branch TEXT NOT NULL,
+    branch        TEXT NOT NULL,
+    path          TEXT NOT NULL,
+    base_commit   TEXT NOT NULL,
+    setup_script  TEXT,  -- the setup script as it ran, to spot stale entries
+    status        TEXT NOT NULL DEFAULT 'provisioning'
+                     CHECK (status IN ('provisioning', 'ready')),
+    size_bytes    INTEGER NOT NULL DEFAULT 0,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_warm_worktrees_repo_target
+    ON warm_worktrees(repo_id, target_branch, status);
@@ -0,0 +1,32 @@
+-- Branches, recent commits and contributors of registered repos, indexed in
+-- the background so branch pickers don't have to read the repo on every
+-- request. `repo_index_state` records the refs the index was built from, so a
+-- refresh with unchanged refs can be skipped.
+CREATE TABLE repo_index_state (
+    repo_id          BLOB PRIMARY KEY REFERENCES repos(id) ON DELETE CASCADE,
+    refs_fingerprint TEXT NOT NULL,
+    head_commit      TEXT,  -- newest indexed commit, where the next walk stops
+    indexed_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE TABLE repo_branches (
+    repo_id          BLOB NOT NULL REFERENCES repos(id) ON DELETE CASCADE,
+    name             TEXT NOT NULL,
+    is_remote        INTEGER NOT NULL,
+    is_current       INTEGER NOT NULL,
+    last_commit_date TEXT NOT NULL,
+    PRIMARY KEY (repo_id, is_remote, name)
+);
+
+CREATE TABLE repo_commits (
+    repo_id      BLOB NOT NULL REFERENCES repos(id) ON DELETE CASCADE,
+    sha          TEXT NOT NULL,
+    summary      TEXT NOT NULL,
+    author_name  TEXT NOT NULL,
+    author_email TEXT NOT NULL,
+    committed_at TEXT NOT NULL,
+    PRIMARY KEY (repo_id, sha)
+);
+
+CREATE INDEX idx_repo_commits_repo_committed_at
+    ON repo_commits(repo_id, committed_at DESC);
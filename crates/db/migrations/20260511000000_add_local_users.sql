@@ -0,0 +1,33 @@
+-- Lightweight identities for a small team sharing one server. There are no
+-- passwords: each session is a random token handed to its user, stored here
+-- only as a SHA-256 hash.
+CREATE TABLE local_users (
+    id         BLOB PRIMARY KEY,
+    name       TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    is_admin   INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE TABLE local_user_sessions (
+    token_hash   TEXT PRIMARY KEY,
+    user_id      BLOB NOT NULL REFERENCES local_users(id) ON DELETE CASCADE,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    last_used_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_local_user_sessions_user_id ON local_user_sessions(user_id);
+
+-- Who started a workspace or an execution. Kept out of the owning tables so
+-- rows created before users existed, or by anonymous requests, need nothing.
+CREATE TABLE workspace_owners (
+    workspace_id BLOB PRIMARY KEY REFERENCES workspaces(id) ON DELETE CASCADE,
+    user_id      BLOB NOT NULL REFERENCES local_users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_workspace_owners_user_id ON workspace_owners(user_id);
+
+CREATE TABLE execution_process_owners (
+    execution_process_id BLOB PRIMARY KEY
+        REFERENCES execution_processes(id) ON DELETE CASCADE,
+    user_id              BLOB NOT NULL REFERENCES local_users(id) ON DELETE CASCADE
+);
@@ -0,0 +1,7 @@
+-- Users who signed in with a remote account are linked to it, so signing in
+-- again finds the same local user.
+ALTER TABLE local_users ADD COLUMN remote_user_id BLOB;
+
+CREATE UNIQUE INDEX idx_local_users_remote_user_id
+    ON local_users(remote_user_id)
+    WHERE remote_user_id IS NOT NULL;
@@ -0,0 +1,10 @@
+-- Rules that require approval can name who may give it: specific users
+-- and/or everyone with a role in the remote organization, stored as JSON.
+-- NULL leaves the approval to anyone.
+ALTER TABLE approval_rules ADD COLUMN approvers TEXT;
+
+-- Where to reach a user, and their role in the remote organization if they
+-- sign in with a remote account.
+ALTER TABLE local_users ADD COLUMN email TEXT;
+ALTER TABLE local_users ADD COLUMN remote_role TEXT
+    CHECK (remote_role IN ('admin', 'member'));
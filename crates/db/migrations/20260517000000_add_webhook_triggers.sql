@@ -0,0 +1,28 @@
+-- Inbound webhooks that start agent runs. Each trigger is called at
+-- /api/hooks/{token} and may only start its own templates in its own repos.
+-- The token is stored only as a SHA-256 hash.
+CREATE TABLE webhook_triggers (
+    id                BLOB PRIMARY KEY,
+    name              TEXT NOT NULL,
+    token_hash        TEXT NOT NULL UNIQUE,
+    repo_ids          TEXT NOT NULL,
+    templates         TEXT NOT NULL,
+    executor_config   TEXT NOT NULL,
+    paused            INTEGER NOT NULL DEFAULT 0,
+    last_triggered_at TEXT,
+    created_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE TABLE webhook_trigger_runs (
+    id           BLOB PRIMARY KEY,
+    trigger_id   BLOB NOT NULL REFERENCES webhook_triggers(id) ON DELETE CASCADE,
+    template     TEXT NOT NULL,
+    workspace_id BLOB REFERENCES workspaces(id) ON DELETE SET NULL,
+    status       TEXT NOT NULL CHECK (status IN ('started', 'failed')),
+    error        TEXT,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_webhook_trigger_runs_trigger_id
+    ON webhook_trigger_runs(trigger_id, created_at);
@@ -0,0 +1,38 @@
+-- Triage of failed GitHub Actions runs. GitHub posts workflow_run events to
+-- the repo's hook, signed with the shared secret (encrypted with the machine
+-- key). Each failed run starts a triage workspace on the run's branch, whose
+-- findings are posted back to the pull request.
+CREATE TABLE ci_triage_settings (
+    repo_id         BLOB PRIMARY KEY REFERENCES repos(id) ON DELETE CASCADE,
+    enabled         INTEGER NOT NULL DEFAULT 1,
+    prompt          TEXT,
+    executor_config TEXT NOT NULL,
+    post_comment    INTEGER NOT NULL DEFAULT 1,
+    secret_nonce    BLOB NOT NULL,
+    secret          BLOB NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE TABLE ci_triage_runs (
+    id              BLOB PRIMARY KEY,
+    repo_id         BLOB NOT NULL REFERENCES repos(id) ON DELETE CASCADE,
+    workflow_run_id INTEGER NOT NULL,
+    run_attempt     INTEGER NOT NULL,
+    workflow_name   TEXT NOT NULL,
+    head_branch     TEXT NOT NULL,
+    run_url         TEXT NOT NULL,
+    pr_number       INTEGER,
+    workspace_id    BLOB REFERENCES workspaces(id) ON DELETE SET NULL,
+    status          TEXT NOT NULL DEFAULT 'pending'
+                       CHECK (status IN ('pending', 'running', 'reported', 'failed')),
+    error           TEXT,
+    comment_url     TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    -- GitHub redelivers events; each attempt of a run is triaged once.
+    UNIQUE (repo_id, workflow_run_id, run_attempt)
+);
+
+CREATE INDEX idx_ci_triage_runs_repo_id ON ci_triage_runs(repo_id, created_at);
+CREATE INDEX idx_ci_triage_runs_workspace_id ON ci_triage_runs(workspace_id);
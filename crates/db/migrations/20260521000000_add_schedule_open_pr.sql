@@ -0,0 +1,8 @@
+-- Schedules can open a pull request once a run's verify script passes, which
+-- is how the dependency update bot ships its changes.
+ALTER TABLE schedules ADD COLUMN open_pr INTEGER NOT NULL DEFAULT 0;
+
+-- The pull request a run opened, or in `error` why it didn't open one.
+ALTER TABLE schedule_runs ADD COLUMN pr_url TEXT;
+
+CREATE INDEX idx_schedule_runs_workspace_id ON schedule_runs(workspace_id);
@@ -0,0 +1,35 @@
+-- Tests that have failed in reported CI runs, with pass/fail counts to tell
+-- flaky tests apart from broken ones. Passing results are only counted for
+-- tests that are already tracked.
+CREATE TABLE flaky_tests (
+    id                   BLOB PRIMARY KEY,
+    repo_id              BLOB NOT NULL REFERENCES repos(id) ON DELETE CASCADE,
+    suite                TEXT NOT NULL,
+    name                 TEXT NOT NULL,
+    passes               INTEGER NOT NULL DEFAULT 0,
+    failures             INTEGER NOT NULL DEFAULT 0,
+    -- Times the outcome changed between consecutive reports.
+    flips                INTEGER NOT NULL DEFAULT 0,
+    last_outcome         TEXT NOT NULL CHECK (last_outcome IN ('passed', 'failed')),
+    last_failure_message TEXT,
+    last_failed_at       TEXT,
+    quarantined          INTEGER NOT NULL DEFAULT 0,
+    fix_workspace_id     BLOB REFERENCES workspaces(id) ON DELETE SET NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    UNIQUE (repo_id, suite, name)
+);
+
+CREATE INDEX idx_flaky_tests_fix_workspace_id ON flaky_tests(fix_workspace_id);
+
+-- Per-repo settings: the token CI reports with, and whether to start an
+-- agent on the flakiest test automatically.
+CREATE TABLE flaky_test_settings (
+    repo_id         BLOB PRIMARY KEY REFERENCES repos(id) ON DELETE CASCADE,
+    token_hash      TEXT NOT NULL UNIQUE,
+    auto_fix        INTEGER NOT NULL DEFAULT 0,
+    target_branch   TEXT NOT NULL,
+    executor_config TEXT NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
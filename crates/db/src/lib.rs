@@ -1,13 +1,58 @@
-use std::{str::FromStr, sync::Arc};
+use std::{future::Future, str::FromStr, sync::Arc, time::Duration};
 
 use sqlx::{
     ConnectOptions, Error, Pool, Sqlite, SqlitePool,
     migrate::MigrateError,
-    sqlite::{SqliteConnectOptions, SqliteConnection, SqliteJournalMode, SqlitePoolOptions},
+    sqlite::{
+        SqliteConnectOptions, SqliteConnection, SqliteJournalMode, SqlitePoolOptions,
+        SqliteSynchronous,
+    },
 };
 use utils::assets::asset_dir;
+use write_queue::{WriteQueue, WriteQueueStats};
 
 pub mod models;
+#[cfg(test)]
+pub(crate) mod test_support;
+pub mod write_queue;
+
+/// How connections to the local database are opened.
+#[derive(Debug, Clone)]
+pub struct ConnectionSettings {
+    /// Write-ahead logging, so reads don't block writes or the other way round.
+    pub wal: bool,
+    /// How long a connection retries while another holds the write lock,
+    /// before failing with `database is locked`.
+    pub busy_timeout: Duration,
+}
+
+impl Default for ConnectionSettings {
+    fn default() -> Self {
+        Self {
+            wal: true,
+            busy_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+fn connect_options(settings: &ConnectionSettings) -> Result<SqliteConnectOptions, Error> {
+    let database_url = format!(
+        "sqlite://{}",
+        asset_dir().join("db.v2.sqlite").to_string_lossy()
+    );
+    let options = SqliteConnectOptions::from_str(&database_url)?
+        .create_if_missing(true)
+        .busy_timeout(settings.busy_timeout);
+    Ok(if settings.wal {
+        // NORMAL is durable in WAL mode except for the last commits before a
+        // power loss, and skips an fsync per commit.
+        options
+            .journal_mode(SqliteJournalMode::Wal)
+            .synchronous(SqliteSynchronous::Normal)
+    } else {
+        options.journal_mode(SqliteJournalMode::Delete)
+    })
+}
 
 async fn run_migrations(pool: &Pool<Sqlite>) -> Result<(), Error> {
     use std::collections::HashSet;
@@ -70,38 +115,31 @@ async fn run_migrations(pool: &Pool<Sqlite>) -> Result<(), Error> {
 #[derive(Clone)]
 pub struct DBService {
     pub pool: Pool<Sqlite>,
+    settings: ConnectionSettings,
+    write_queue: Arc<WriteQueue>,
 }
 
 impl DBService {
-    pub async fn new() -> Result<DBService, Error> {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.v2.sqlite").to_string_lossy()
-        );
-        let options = SqliteConnectOptions::from_str(&database_url)?
-            .create_if_missing(true)
-            .journal_mode(SqliteJournalMode::Delete);
-        let pool = SqlitePool::connect_with(options).await?;
+    pub async fn new(settings: ConnectionSettings) -> Result<DBService, Error> {
+        let pool = SqlitePool::connect_with(connect_options(&settings)?).await?;
         run_migrations(&pool).await?;
-        Ok(DBService { pool })
+        Ok(Self::with_pool(pool, settings))
     }
 
-    pub async fn new_migration_pool() -> Result<Pool<Sqlite>, Error> {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.v2.sqlite").to_string_lossy()
-        );
-        let options = SqliteConnectOptions::from_str(&database_url)?
-            .create_if_missing(true)
-            .journal_mode(SqliteJournalMode::Delete)
-            .disable_statement_logging();
+    /// A separate pool on the same database, opened with this service's
+    /// settings.
+    pub async fn new_migration_pool(&self) -> Result<Pool<Sqlite>, Error> {
+        let options = connect_options(&self.settings)?.disable_statement_logging();
         SqlitePoolOptions::new()
             .max_connections(64)
             .connect_with(options)
             .await
     }
 
-    pub async fn new_with_after_connect<F>(after_connect: F) -> Result<DBService, Error>
+    pub async fn new_with_after_connect<F>(
+        settings: ConnectionSettings,
+        after_connect: F,
+    ) -> Result<DBService, Error>
     where
         F: for<'a> Fn(
                 &'a mut SqliteConnection,
@@ -111,11 +149,33 @@ impl DBService {
             + Sync
             + 'static,
     {
-        let pool = Self::create_pool(Some(Arc::new(after_connect))).await?;
-        Ok(DBService { pool })
+        let pool = Self::create_pool(&settings, Some(Arc::new(after_connect))).await?;
+        Ok(Self::with_pool(pool, settings))
+    }
+
+    fn with_pool(pool: Pool<Sqlite>, settings: ConnectionSettings) -> Self {
+        Self {
+            pool,
+            settings,
+            write_queue: Arc::new(WriteQueue::default()),
+        }
+    }
+
+    /// Run a hot-path write once the writes queued before it are done. See
+    /// [`write_queue`]; `write` must not queue another write itself.
+    pub async fn write<F: Future>(&self, writer: &'static str, write: F) -> F::Output {
+        let _permit = self.write_queue.acquire(writer).await;
+        write.await
+    }
+
+    pub fn write_queue_stats(&self) -> WriteQueueStats {
+        self.write_queue.stats()
     }
 
-    async fn create_pool<F>(after_connect: Option<Arc<F>>) -> Result<Pool<Sqlite>, Error>
+    async fn create_pool<F>(
+        settings: &ConnectionSettings,
+        after_connect: Option<Arc<F>>,
+    ) -> Result<Pool<Sqlite>, Error>
     where
         F: for<'a> Fn(
                 &'a mut SqliteConnection,
@@ -125,13 +185,7 @@ impl DBService {
             + Sync
             + 'static,
     {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.v2.sqlite").to_string_lossy()
-        );
-        let options = SqliteConnectOptions::from_str(&database_url)?
-            .create_if_missing(true)
-            .journal_mode(SqliteJournalMode::Delete);
+        let options = connect_options(settings)?;
 
         let pool = if let Some(hook) = after_connect {
             SqlitePoolOptions::new()
@@ -0,0 +1,336 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type, types::Json};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::local_user::{LocalUser, RemoteOrgRole};
+
+#[derive(Debug, Error)]
+pub enum ApprovalRuleError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Approval rule not found")]
+    NotFound,
+    #[error("An approval rule needs a tool name or a command prefix")]
+    Invalid,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum ApprovalRuleAction {
+    AutoApprove,
+    RequireApproval,
+    Deny,
+}
+
+/// Who may answer the approvals a rule asks for. Admins always may.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct ApprovalRuleApprovers {
+    #[serde(default)]
+    pub user_ids: Vec<Uuid>,
+    /// Everyone with this role in the remote organization; `member` covers
+    /// all members.
+    #[serde(default)]
+    pub role: Option<RemoteOrgRole>,
+}
+
+impl ApprovalRuleApprovers {
+    /// Whether `user` is one of the designated approvers. Requests without a
+    /// user act as the machine's owner, who may answer anything.
+    pub fn allows(&self, user: Option<&LocalUser>) -> bool {
+        let Some(user) = user else {
+            return true;
+        };
+        user.is_admin || self.designates(user)
+    }
+
+    /// Whether the approvers name `user`, by id or by role.
+    pub fn designates(&self, user: &LocalUser) -> bool {
+        self.user_ids.contains(&user.id)
+            || match (self.role, user.remote_role) {
+                (Some(RemoteOrgRole::Member), Some(_)) => true,
+                (Some(role), Some(user_role)) => role == user_role,
+                _ => false,
+            }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.user_ids.is_empty() && self.role.is_none()
+    }
+}
+
+/// Decides matching tool approval requests without (or always with) a prompt.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ApprovalRule {
+    pub id: Uuid,
+    /// Restricts the rule to one repo; `None` applies it everywhere.
+    pub repo_id: Option<Uuid>,
+    /// Tool the rule applies to (case-insensitive); `None` matches any tool.
+    pub tool_name: Option<String>,
+    /// Leading words of a shell command, e.g. `git push`.
+    pub command_prefix: Option<String>,
+    pub action: ApprovalRuleAction,
+    pub description: Option<String>,
+    /// Who may answer the approvals a `require_approval` rule asks for;
+    /// `None` leaves them to anyone.
+    #[ts(type = "ApprovalRuleApprovers | null")]
+    pub approvers: Option<Json<ApprovalRuleApprovers>>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateApprovalRule {
+    pub repo_id: Option<Uuid>,
+    pub tool_name: Option<String>,
+    pub command_prefix: Option<String>,
+    pub action: ApprovalRuleAction,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub approvers: Option<ApprovalRuleApprovers>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateApprovalRule {
+    pub tool_name: Option<String>,
+    pub command_prefix: Option<String>,
+    pub action: Option<ApprovalRuleAction>,
+    pub description: Option<String>,
+    /// Replaces the approvers; empty approvers leave approvals to anyone.
+    #[serde(default)]
+    pub approvers: Option<ApprovalRuleApprovers>,
+}
+
+fn approvers_column(
+    approvers: Option<&ApprovalRuleApprovers>,
+) -> Option<Json<&ApprovalRuleApprovers>> {
+    approvers
+        .filter(|approvers| !approvers.is_empty())
+        .map(Json)
+}
+
+fn normalize(value: Option<&str>) -> Option<String> {
+    value
+        .map(|value| value.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|value| !value.is_empty())
+}
+
+impl ApprovalRule {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApprovalRule,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                repo_id as "repo_id?: Uuid",
+                tool_name as "tool_name?",
+                command_prefix as "command_prefix?",
+                action as "action!: ApprovalRuleAction",
+                description as "description?",
+                approvers as "approvers?: Json<ApprovalRuleApprovers>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM approval_rules
+            ORDER BY created_at ASC
+            "#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApprovalRule,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                repo_id as "repo_id?: Uuid",
+                tool_name as "tool_name?",
+                command_prefix as "command_prefix?",
+                action as "action!: ApprovalRuleAction",
+                description as "description?",
+                approvers as "approvers?: Json<ApprovalRuleApprovers>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM approval_rules
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Global rules plus the rules of the given repos.
+    pub async fn find_applicable(
+        pool: &SqlitePool,
+        repo_ids: &[Uuid],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rules = Self::find_all(pool).await?;
+        Ok(rules
+            .into_iter()
+            .filter(|rule| {
+                rule.repo_id
+                    .is_none_or(|repo_id| repo_ids.contains(&repo_id))
+            })
+            .collect())
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateApprovalRule,
+    ) -> Result<Self, ApprovalRuleError> {
+        let tool_name = normalize(data.tool_name.as_deref());
+        let command_prefix = normalize(data.command_prefix.as_deref());
+        if tool_name.is_none() && command_prefix.is_none() {
+            return Err(ApprovalRuleError::Invalid);
+        }
+        let id = Uuid::new_v4();
+        let approvers = approvers_column(data.approvers.as_ref());
+        Ok(sqlx::query_as!(
+            ApprovalRule,
+            r#"
+            INSERT INTO approval_rules
+                (id, repo_id, tool_name, command_prefix, action, description, approvers)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            RETURNING
+                id as "id!: Uuid",
+                repo_id as "repo_id?: Uuid",
+                tool_name as "tool_name?",
+                command_prefix as "command_prefix?",
+                action as "action!: ApprovalRuleAction",
+                description as "description?",
+                approvers as "approvers?: Json<ApprovalRuleApprovers>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            id,
+            data.repo_id,
+            tool_name,
+            command_prefix,
+            data.action,
+            data.description,
+            approvers
+        )
+        .fetch_one(pool)
+        .await?)
+    }
+
+    /// Apply `data` to a rule. An empty tool name or command prefix clears it.
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateApprovalRule,
+    ) -> Result<Self, ApprovalRuleError> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(ApprovalRuleError::NotFound)?;
+        let tool_name = match &data.tool_name {
+            Some(tool_name) => normalize(Some(tool_name)),
+            None => existing.tool_name,
+        };
+        let command_prefix = match &data.command_prefix {
+            Some(command_prefix) => normalize(Some(command_prefix)),
+            None => existing.command_prefix,
+        };
+        if tool_name.is_none() && command_prefix.is_none() {
+            return Err(ApprovalRuleError::Invalid);
+        }
+        let action = data.action.unwrap_or(existing.action);
+        let description = data.description.as_ref().or(existing.description.as_ref());
+        let approvers = match &data.approvers {
+            Some(approvers) => approvers_column(Some(approvers)),
+            None => approvers_column(existing.approvers.as_deref()),
+        };
+
+        Ok(sqlx::query_as!(
+            ApprovalRule,
+            r#"
+            UPDATE approval_rules
+            SET tool_name = ?, command_prefix = ?, action = ?, description = ?,
+                approvers = ?, updated_at = datetime('now', 'subsec')
+            WHERE id = ?
+            RETURNING
+                id as "id!: Uuid",
+                repo_id as "repo_id?: Uuid",
+                tool_name as "tool_name?",
+                command_prefix as "command_prefix?",
+                action as "action!: ApprovalRuleAction",
+                description as "description?",
+                approvers as "approvers?: Json<ApprovalRuleApprovers>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            tool_name,
+            command_prefix,
+            action,
+            description,
+            approvers,
+            id
+        )
+        .fetch_one(pool)
+        .await?)
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM approval_rules WHERE id = ?", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(is_admin: bool, remote_role: Option<RemoteOrgRole>) -> LocalUser {
+        LocalUser {
+            id: Uuid::new_v4(),
+            name: "someone".to_string(),
+            is_admin,
+            remote_user_id: None,
+            email: None,
+            remote_role,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn approvers_are_named_users_roles_and_admins() {
+        let named = user(false, None);
+        let org_admin = user(false, Some(RemoteOrgRole::Admin));
+        let org_member = user(false, Some(RemoteOrgRole::Member));
+        let local_admin = user(true, None);
+
+        let by_name = ApprovalRuleApprovers {
+            user_ids: vec![named.id],
+            role: None,
+        };
+        assert!(by_name.allows(Some(&named)));
+        assert!(!by_name.allows(Some(&org_admin)));
+        assert!(by_name.allows(Some(&local_admin)));
+        assert!(by_name.allows(None), "the machine's owner");
+
+        let org_admins = ApprovalRuleApprovers {
+            user_ids: vec![],
+            role: Some(RemoteOrgRole::Admin),
+        };
+        assert!(org_admins.allows(Some(&org_admin)));
+        assert!(!org_admins.allows(Some(&org_member)));
+
+        let org_members = ApprovalRuleApprovers {
+            user_ids: vec![],
+            role: Some(RemoteOrgRole::Member),
+        };
+        assert!(org_members.allows(Some(&org_admin)));
+        assert!(org_members.allows(Some(&org_member)));
+        assert!(!org_members.allows(Some(&named)));
+    }
+}
@@ -0,0 +1,176 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorConfig;
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, types::Json};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum AttemptGroupError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Attempt group not found")]
+    NotFound,
+}
+
+/// The same prompt run by several executors, each in its own workspace.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct AttemptGroup {
+    pub id: Uuid,
+    pub name: String,
+    pub prompt: String,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// One attempt of a group: the workspace and the executor it was started with.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct AttemptGroupWorkspace {
+    pub attempt_group_id: Uuid,
+    pub workspace_id: Uuid,
+    pub executor_config: ExecutorConfig,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+struct AttemptGroupWorkspaceRow {
+    attempt_group_id: Uuid,
+    workspace_id: Uuid,
+    executor_config: Json<ExecutorConfig>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<AttemptGroupWorkspaceRow> for AttemptGroupWorkspace {
+    fn from(row: AttemptGroupWorkspaceRow) -> Self {
+        Self {
+            attempt_group_id: row.attempt_group_id,
+            workspace_id: row.workspace_id,
+            executor_config: row.executor_config.0,
+            created_at: row.created_at,
+        }
+    }
+}
+
+impl AttemptGroup {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptGroup,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                name as "name!",
+                prompt as "prompt!",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM attempt_groups
+            ORDER BY created_at DESC
+            "#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptGroup,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                name as "name!",
+                prompt as "prompt!",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM attempt_groups
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(pool: &SqlitePool, name: &str, prompt: &str) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            AttemptGroup,
+            r#"
+            INSERT INTO attempt_groups (id, name, prompt)
+            VALUES (?, ?, ?)
+            RETURNING
+                id as "id!: Uuid",
+                name as "name!",
+                prompt as "prompt!",
+                created_at as "created_at!: DateTime<Utc>"
+            "#,
+            id,
+            name,
+            prompt
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Delete the group. The workspaces of its attempts are kept.
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM attempt_groups WHERE id = ?", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl AttemptGroupWorkspace {
+    pub async fn find_by_group_id(
+        pool: &SqlitePool,
+        attempt_group_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptGroupWorkspaceRow,
+            r#"
+            SELECT
+                attempt_group_id as "attempt_group_id!: Uuid",
+                workspace_id as "workspace_id!: Uuid",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM attempt_group_workspaces
+            WHERE attempt_group_id = ?
+            ORDER BY created_at ASC
+            "#,
+            attempt_group_id
+        )
+        .fetch_all(pool)
+        .await
+        .map(|rows| rows.into_iter().map(AttemptGroupWorkspace::from).collect())
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        attempt_group_id: Uuid,
+        workspace_id: Uuid,
+        executor_config: &ExecutorConfig,
+    ) -> Result<Self, AttemptGroupError> {
+        let executor_config = serde_json::to_string(executor_config)?;
+        Ok(sqlx::query_as!(
+            AttemptGroupWorkspaceRow,
+            r#"
+            INSERT INTO attempt_group_workspaces (attempt_group_id, workspace_id, executor_config)
+            VALUES (?, ?, ?)
+            ON CONFLICT(attempt_group_id, workspace_id) DO UPDATE SET
+                executor_config = excluded.executor_config
+            RETURNING
+                attempt_group_id as "attempt_group_id!: Uuid",
+                workspace_id as "workspace_id!: Uuid",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                created_at as "created_at!: DateTime<Utc>"
+            "#,
+            attempt_group_id,
+            workspace_id,
+            executor_config
+        )
+        .fetch_one(pool)
+        .await
+        .map(AttemptGroupWorkspace::from)?)
+    }
+}
@@ -0,0 +1,215 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Maximum number of entries returned from a single audit log query.
+pub const AUDIT_LOG_MAX_LIMIT: i64 = 500;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum AuditAction {
+    WorkspaceDeleted,
+    WorkspaceStopped,
+    ProcessKilled,
+    GitReset,
+    ApprovalGranted,
+    ApprovalDenied,
+    ApprovalAutoApproved,
+    CommandExecuted,
+    UndoApplied,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct AuditLogEntry {
+    pub id: Uuid,
+    pub action: AuditAction,
+    /// Who performed the action: `user:<name>` for signed-in users, `local`
+    /// for other same-machine requests, or `relay:<signing_session_id>` for
+    /// requests arriving through the relay.
+    pub actor: String,
+    pub workspace_id: Option<Uuid>,
+    /// The primary object acted upon (execution process, session, ...), if
+    /// different from the workspace.
+    pub target_id: Option<Uuid>,
+    pub details: Option<Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateAuditLogEntry {
+    pub action: AuditAction,
+    pub actor: String,
+    pub workspace_id: Option<Uuid>,
+    pub target_id: Option<Uuid>,
+    pub details: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+pub struct AuditLogQuery {
+    pub action: Option<AuditAction>,
+    pub workspace_id: Option<Uuid>,
+    /// Only return entries created strictly before this timestamp (for paging).
+    pub before: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+}
+
+struct AuditLogRow {
+    id: Uuid,
+    action: AuditAction,
+    actor: String,
+    workspace_id: Option<Uuid>,
+    target_id: Option<Uuid>,
+    details: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<AuditLogRow> for AuditLogEntry {
+    fn from(row: AuditLogRow) -> Self {
+        AuditLogEntry {
+            id: row.id,
+            action: row.action,
+            actor: row.actor,
+            workspace_id: row.workspace_id,
+            target_id: row.target_id,
+            details: row.details.and_then(|raw| serde_json::from_str(&raw).ok()),
+            created_at: row.created_at,
+        }
+    }
+}
+
+impl AuditLogEntry {
+    /// Append an entry. The audit log is append-only; there is intentionally no
+    /// update or delete counterpart.
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateAuditLogEntry,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let details = data.details.as_ref().map(|value| value.to_string());
+
+        sqlx::query_as!(
+            AuditLogRow,
+            r#"
+            INSERT INTO audit_log (id, action, actor, workspace_id, target_id, details)
+            VALUES (?, ?, ?, ?, ?, ?)
+            RETURNING
+                id as "id!: Uuid",
+                action as "action!: AuditAction",
+                actor as "actor!",
+                workspace_id as "workspace_id?: Uuid",
+                target_id as "target_id?: Uuid",
+                details as "details?",
+                created_at as "created_at!: DateTime<Utc>"
+            "#,
+            id,
+            data.action,
+            data.actor,
+            data.workspace_id,
+            data.target_id,
+            details
+        )
+        .fetch_one(pool)
+        .await
+        .map(Into::into)
+    }
+
+    /// Newest-first listing with optional filters.
+    ///
+    /// `before` is bound as `NaiveDateTime` so it is encoded in the same
+    /// `"YYYY-MM-DD HH:MM:SS.fff"` format SQLite writes for `created_at`.
+    pub async fn find(pool: &SqlitePool, query: &AuditLogQuery) -> Result<Vec<Self>, sqlx::Error> {
+        let limit = query.limit.unwrap_or(100).clamp(1, AUDIT_LOG_MAX_LIMIT);
+        let before: Option<NaiveDateTime> = query.before.map(|ts| ts.naive_utc());
+
+        let rows = sqlx::query_as!(
+            AuditLogRow,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                action as "action!: AuditAction",
+                actor as "actor!",
+                workspace_id as "workspace_id?: Uuid",
+                target_id as "target_id?: Uuid",
+                details as "details?",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM audit_log
+            WHERE (?1 IS NULL OR action = ?1)
+              AND (?2 IS NULL OR workspace_id = ?2)
+              AND (?3 IS NULL OR created_at < ?3)
+            ORDER BY created_at DESC, rowid DESC
+            LIMIT ?4
+            "#,
+            query.action,
+            query.workspace_id,
+            before,
+            limit
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_pool;
+
+    fn entry(action: AuditAction, workspace_id: Option<Uuid>) -> CreateAuditLogEntry {
+        CreateAuditLogEntry {
+            action,
+            actor: "local".to_string(),
+            workspace_id,
+            target_id: None,
+            details: Some(serde_json::json!({ "reason": "test" })),
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn find_filters_by_action_and_workspace_newest_first() {
+        let pool = test_pool().await;
+        let workspace_id = Uuid::new_v4();
+
+        AuditLogEntry::create(
+            &pool,
+            &entry(AuditAction::ProcessKilled, Some(workspace_id)),
+        )
+        .await
+        .unwrap();
+        AuditLogEntry::create(&pool, &entry(AuditAction::GitReset, Some(workspace_id)))
+            .await
+            .unwrap();
+        AuditLogEntry::create(&pool, &entry(AuditAction::GitReset, None))
+            .await
+            .unwrap();
+
+        let all = AuditLogEntry::find(&pool, &AuditLogQuery::default())
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].workspace_id, None, "newest entry comes first");
+        assert_eq!(
+            all[0].details,
+            Some(serde_json::json!({ "reason": "test" }))
+        );
+
+        let resets_for_workspace = AuditLogEntry::find(
+            &pool,
+            &AuditLogQuery {
+                action: Some(AuditAction::GitReset),
+                workspace_id: Some(workspace_id),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(resets_for_workspace.len(), 1);
+        assert_eq!(resets_for_workspace[0].action, AuditAction::GitReset);
+    }
+}
@@ -0,0 +1,428 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorConfig;
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type, types::Json};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum CiTriageError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("CI triage is not set up for this repo")]
+    NotFound,
+}
+
+/// How failed CI runs of a repo are triaged. The webhook secret is kept out
+/// of this struct; see [`CiTriageSettings::find_secret`].
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CiTriageSettings {
+    pub repo_id: Uuid,
+    pub enabled: bool,
+    /// Replaces the default triage instructions. The failure details and
+    /// logs are appended either way.
+    pub prompt: Option<String>,
+    pub executor_config: ExecutorConfig,
+    /// Post the agent's findings to the run's pull request.
+    pub post_comment: bool,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+struct CiTriageSettingsRow {
+    repo_id: Uuid,
+    enabled: bool,
+    prompt: Option<String>,
+    executor_config: Json<ExecutorConfig>,
+    post_comment: bool,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<CiTriageSettingsRow> for CiTriageSettings {
+    fn from(row: CiTriageSettingsRow) -> Self {
+        Self {
+            repo_id: row.repo_id,
+            enabled: row.enabled,
+            prompt: row.prompt,
+            executor_config: row.executor_config.0,
+            post_comment: row.post_comment,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpsertCiTriageSettings {
+    pub enabled: bool,
+    pub prompt: Option<String>,
+    pub executor_config: ExecutorConfig,
+    pub post_comment: bool,
+}
+
+/// A run is `pending` while its logs are fetched and the workspace starts,
+/// `running` while the agent works, and `reported` once the agent finished
+/// and its findings were posted, if there was a pull request to post them to.
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum CiTriageRunStatus {
+    Pending,
+    Running,
+    Reported,
+    Failed,
+}
+
+/// One failed workflow run attempt and its triage.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CiTriageRun {
+    pub id: Uuid,
+    pub repo_id: Uuid,
+    pub workflow_run_id: i64,
+    pub run_attempt: i64,
+    pub workflow_name: String,
+    pub head_branch: String,
+    pub run_url: String,
+    pub pr_number: Option<i64>,
+    pub workspace_id: Option<Uuid>,
+    pub status: CiTriageRunStatus,
+    pub error: Option<String>,
+    pub comment_url: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A failed run to record, as reported by GitHub.
+#[derive(Debug, Clone)]
+pub struct CreateCiTriageRun {
+    pub repo_id: Uuid,
+    pub workflow_run_id: i64,
+    pub run_attempt: i64,
+    pub workflow_name: String,
+    pub head_branch: String,
+    pub run_url: String,
+    pub pr_number: Option<i64>,
+}
+
+impl CiTriageSettings {
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            CiTriageSettingsRow,
+            r#"
+            SELECT
+                repo_id as "repo_id!: Uuid",
+                enabled as "enabled!: bool",
+                prompt as "prompt?",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                post_comment as "post_comment!: bool",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM ci_triage_settings
+            WHERE repo_id = ?
+            "#,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|row| row.map(CiTriageSettings::from))
+    }
+
+    /// The encrypted webhook secret: `(nonce, ciphertext)`.
+    pub async fn find_secret(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Option<(Vec<u8>, Vec<u8>)>, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"SELECT secret_nonce as "secret_nonce!", secret as "secret!"
+               FROM ci_triage_settings
+               WHERE repo_id = ?"#,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(|row| (row.secret_nonce, row.secret)))
+    }
+
+    /// Create the repo's settings with the given encrypted secret.
+    pub async fn create(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        data: &UpsertCiTriageSettings,
+        secret_nonce: &[u8],
+        secret: &[u8],
+    ) -> Result<Self, CiTriageError> {
+        let prompt = data.prompt.as_deref();
+        let executor_config = serde_json::to_string(&data.executor_config)?;
+        Ok(sqlx::query_as!(
+            CiTriageSettingsRow,
+            r#"
+            INSERT INTO ci_triage_settings
+                (repo_id, enabled, prompt, executor_config, post_comment, secret_nonce, secret)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            RETURNING
+                repo_id as "repo_id!: Uuid",
+                enabled as "enabled!: bool",
+                prompt as "prompt?",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                post_comment as "post_comment!: bool",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            repo_id,
+            data.enabled,
+            prompt,
+            executor_config,
+            data.post_comment,
+            secret_nonce,
+            secret
+        )
+        .fetch_one(pool)
+        .await
+        .map(CiTriageSettings::from)?)
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        data: &UpsertCiTriageSettings,
+    ) -> Result<Self, CiTriageError> {
+        let prompt = data.prompt.as_deref();
+        let executor_config = serde_json::to_string(&data.executor_config)?;
+        sqlx::query_as!(
+            CiTriageSettingsRow,
+            r#"
+            UPDATE ci_triage_settings
+            SET enabled = ?, prompt = ?, executor_config = ?, post_comment = ?,
+                updated_at = datetime('now', 'subsec')
+            WHERE repo_id = ?
+            RETURNING
+                repo_id as "repo_id!: Uuid",
+                enabled as "enabled!: bool",
+                prompt as "prompt?",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                post_comment as "post_comment!: bool",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            data.enabled,
+            prompt,
+            executor_config,
+            data.post_comment,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|row| row.map(CiTriageSettings::from))?
+        .ok_or(CiTriageError::NotFound)
+    }
+
+    pub async fn set_secret(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        secret_nonce: &[u8],
+        secret: &[u8],
+    ) -> Result<(), CiTriageError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE ci_triage_settings
+            SET secret_nonce = ?, secret = ?, updated_at = datetime('now', 'subsec')
+            WHERE repo_id = ?
+            "#,
+            secret_nonce,
+            secret,
+            repo_id
+        )
+        .execute(pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(CiTriageError::NotFound);
+        }
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, repo_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM ci_triage_settings WHERE repo_id = ?", repo_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl CiTriageRun {
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            CiTriageRun,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                workflow_run_id as "workflow_run_id!",
+                run_attempt as "run_attempt!",
+                workflow_name as "workflow_name!",
+                head_branch as "head_branch!",
+                run_url as "run_url!",
+                pr_number as "pr_number?",
+                workspace_id as "workspace_id?: Uuid",
+                status as "status!: CiTriageRunStatus",
+                error as "error?",
+                comment_url as "comment_url?",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM ci_triage_runs
+            WHERE repo_id = ?
+            ORDER BY created_at DESC
+            LIMIT ?
+            "#,
+            repo_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The run whose agent is working in `workspace_id`, if any.
+    pub async fn find_running_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            CiTriageRun,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                workflow_run_id as "workflow_run_id!",
+                run_attempt as "run_attempt!",
+                workflow_name as "workflow_name!",
+                head_branch as "head_branch!",
+                run_url as "run_url!",
+                pr_number as "pr_number?",
+                workspace_id as "workspace_id?: Uuid",
+                status as "status!: CiTriageRunStatus",
+                error as "error?",
+                comment_url as "comment_url?",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM ci_triage_runs
+            WHERE workspace_id = ? AND status = 'running'
+            "#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Record a failed run. Returns `None` if this attempt was already
+    /// recorded, as happens when GitHub redelivers an event.
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateCiTriageRun,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            CiTriageRun,
+            r#"
+            INSERT INTO ci_triage_runs
+                (id, repo_id, workflow_run_id, run_attempt, workflow_name, head_branch,
+                 run_url, pr_number)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (repo_id, workflow_run_id, run_attempt) DO NOTHING
+            RETURNING
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                workflow_run_id as "workflow_run_id!",
+                run_attempt as "run_attempt!",
+                workflow_name as "workflow_name!",
+                head_branch as "head_branch!",
+                run_url as "run_url!",
+                pr_number as "pr_number?",
+                workspace_id as "workspace_id?: Uuid",
+                status as "status!: CiTriageRunStatus",
+                error as "error?",
+                comment_url as "comment_url?",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            id,
+            data.repo_id,
+            data.workflow_run_id,
+            data.run_attempt,
+            data.workflow_name,
+            data.head_branch,
+            data.run_url,
+            data.pr_number
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn mark_running(
+        pool: &SqlitePool,
+        id: Uuid,
+        workspace_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE ci_triage_runs
+            SET status = 'running', workspace_id = ?, updated_at = datetime('now', 'subsec')
+            WHERE id = ?
+            "#,
+            workspace_id,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn mark_reported(
+        pool: &SqlitePool,
+        id: Uuid,
+        comment_url: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE ci_triage_runs
+            SET status = 'reported', comment_url = ?, updated_at = datetime('now', 'subsec')
+            WHERE id = ?
+            "#,
+            comment_url,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn mark_failed(pool: &SqlitePool, id: Uuid, error: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE ci_triage_runs
+            SET status = 'failed', error = ?, updated_at = datetime('now', 'subsec')
+            WHERE id = ?
+            "#,
+            error,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
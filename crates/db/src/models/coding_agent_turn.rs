@@ -189,6 +189,64 @@ impl CodingAgentTurn {
         Ok(())
     }
 
+    /// Record the cost the agent reported for a turn.
+    pub async fn set_cost(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        cost_usd: f64,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE coding_agent_turns
+               SET cost_usd = ?, updated_at = ?
+               WHERE execution_process_id = ?"#,
+            cost_usd,
+            now,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Reported cost of the latest coding agent turn in a session.
+    pub async fn find_latest_cost_for_session(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Option<f64>, sqlx::Error> {
+        let cost: Option<Option<f64>> = sqlx::query_scalar!(
+            r#"SELECT cat.cost_usd
+               FROM coding_agent_turns cat
+               JOIN execution_processes ep ON cat.execution_process_id = ep.id
+               WHERE ep.session_id = ? AND ep.run_reason = 'codingagent'
+               ORDER BY ep.created_at DESC
+               LIMIT 1"#,
+            session_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(cost.flatten())
+    }
+
+    /// The final assistant message of the session's latest coding agent turn.
+    pub async fn find_latest_summary_for_session(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let summary: Option<Option<String>> = sqlx::query_scalar!(
+            r#"SELECT cat.summary
+               FROM coding_agent_turns cat
+               JOIN execution_processes ep ON cat.execution_process_id = ep.id
+               WHERE ep.session_id = ? AND ep.run_reason = 'codingagent'
+               ORDER BY ep.created_at DESC
+               LIMIT 1"#,
+            session_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(summary.flatten())
+    }
+
     /// Mark a coding agent turn as unseen by execution process ID.
     pub async fn mark_unseen_by_execution_process_id(
         pool: &SqlitePool,
@@ -0,0 +1,112 @@
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Config keys that can be set per repo or per workspace. `None` leaves the
+/// value to the layer below.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[serde(default)]
+pub struct ConfigOverrides {
+    pub executor_profile: Option<ExecutorProfileId>,
+    pub git_branch_prefix: Option<String>,
+    pub setup_script: Option<String>,
+    pub cleanup_script: Option<String>,
+    pub dev_server_script: Option<String>,
+}
+
+impl ConfigOverrides {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub async fn find_for_repo(pool: &SqlitePool, repo_id: Uuid) -> Result<Self, sqlx::Error> {
+        let overrides = sqlx::query_scalar!(
+            r#"SELECT overrides as "overrides!: Json<ConfigOverrides>"
+               FROM repo_config_overrides WHERE repo_id = ?"#,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(overrides
+            .map(|Json(overrides)| overrides)
+            .unwrap_or_default())
+    }
+
+    pub async fn find_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let overrides = sqlx::query_scalar!(
+            r#"SELECT overrides as "overrides!: Json<ConfigOverrides>"
+               FROM workspace_config_overrides WHERE workspace_id = ?"#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(overrides
+            .map(|Json(overrides)| overrides)
+            .unwrap_or_default())
+    }
+
+    /// Replace the repo's overrides; empty overrides remove the row.
+    pub async fn set_for_repo(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        overrides: &ConfigOverrides,
+    ) -> Result<(), sqlx::Error> {
+        if overrides.is_empty() {
+            sqlx::query!(
+                "DELETE FROM repo_config_overrides WHERE repo_id = ?",
+                repo_id
+            )
+            .execute(pool)
+            .await?;
+            return Ok(());
+        }
+        let overrides = Json(overrides);
+        sqlx::query!(
+            r#"INSERT INTO repo_config_overrides (repo_id, overrides)
+               VALUES (?, ?)
+               ON CONFLICT(repo_id) DO UPDATE SET
+                   overrides = excluded.overrides,
+                   updated_at = datetime('now', 'subsec')"#,
+            repo_id,
+            overrides
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Replace the workspace's overrides; empty overrides remove the row.
+    pub async fn set_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        overrides: &ConfigOverrides,
+    ) -> Result<(), sqlx::Error> {
+        if overrides.is_empty() {
+            sqlx::query!(
+                "DELETE FROM workspace_config_overrides WHERE workspace_id = ?",
+                workspace_id
+            )
+            .execute(pool)
+            .await?;
+            return Ok(());
+        }
+        let overrides = Json(overrides);
+        sqlx::query!(
+            r#"INSERT INTO workspace_config_overrides (workspace_id, overrides)
+               VALUES (?, ?)
+               ON CONFLICT(workspace_id) DO UPDATE SET
+                   overrides = excluded.overrides,
+                   updated_at = datetime('now', 'subsec')"#,
+            workspace_id,
+            overrides
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, types::Json};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum EnvProfileError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Environment profile not found")]
+    NotFound,
+    #[error("An environment profile with this name already exists for the repository")]
+    DuplicateName,
+}
+
+/// A named set of environment variables attached to a repo.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct EnvProfile {
+    pub id: Uuid,
+    pub repo_id: Uuid,
+    pub name: String,
+    pub variables: HashMap<String, String>,
+    /// Variables that must resolve to a non-empty value, either from this
+    /// profile or from the server's own environment, before a process starts.
+    pub required_variables: Vec<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+struct EnvProfileRow {
+    id: Uuid,
+    repo_id: Uuid,
+    name: String,
+    variables: Json<HashMap<String, String>>,
+    required_variables: Json<Vec<String>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<EnvProfileRow> for EnvProfile {
+    fn from(row: EnvProfileRow) -> Self {
+        Self {
+            id: row.id,
+            repo_id: row.repo_id,
+            name: row.name,
+            variables: row.variables.0,
+            required_variables: row.required_variables.0,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateEnvProfile {
+    pub name: String,
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    #[serde(default)]
+    pub required_variables: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateEnvProfile {
+    pub name: Option<String>,
+    pub variables: Option<HashMap<String, String>>,
+    pub required_variables: Option<Vec<String>>,
+}
+
+fn map_unique_violation(e: sqlx::Error) -> EnvProfileError {
+    match &e {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            EnvProfileError::DuplicateName
+        }
+        _ => EnvProfileError::Database(e),
+    }
+}
+
+impl EnvProfile {
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            EnvProfileRow,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                name as "name!",
+                variables as "variables!: Json<HashMap<String, String>>",
+                required_variables as "required_variables!: Json<Vec<String>>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM env_profiles
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|row| row.map(EnvProfile::from))
+    }
+
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            EnvProfileRow,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                name as "name!",
+                variables as "variables!: Json<HashMap<String, String>>",
+                required_variables as "required_variables!: Json<Vec<String>>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM env_profiles
+            WHERE repo_id = ?
+            ORDER BY name ASC
+            "#,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await
+        .map(|rows| rows.into_iter().map(EnvProfile::from).collect())
+    }
+
+    /// Profiles selected for a workspace, in selection order.
+    pub async fn find_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            EnvProfileRow,
+            r#"
+            SELECT
+                ep.id as "id!: Uuid",
+                ep.repo_id as "repo_id!: Uuid",
+                ep.name as "name!",
+                ep.variables as "variables!: Json<HashMap<String, String>>",
+                ep.required_variables as "required_variables!: Json<Vec<String>>",
+                ep.created_at as "created_at!: DateTime<Utc>",
+                ep.updated_at as "updated_at!: DateTime<Utc>"
+            FROM workspace_env_profiles wep
+            JOIN env_profiles ep ON ep.id = wep.env_profile_id
+            WHERE wep.workspace_id = ?
+            ORDER BY wep.created_at ASC, ep.name ASC
+            "#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+        .map(|rows| rows.into_iter().map(EnvProfile::from).collect())
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        data: &CreateEnvProfile,
+    ) -> Result<Self, EnvProfileError> {
+        let id = Uuid::new_v4();
+        let variables = serde_json::to_string(&data.variables)?;
+        let required_variables = serde_json::to_string(&data.required_variables)?;
+        sqlx::query_as!(
+            EnvProfileRow,
+            r#"
+            INSERT INTO env_profiles (id, repo_id, name, variables, required_variables)
+            VALUES (?, ?, ?, ?, ?)
+            RETURNING
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                name as "name!",
+                variables as "variables!: Json<HashMap<String, String>>",
+                required_variables as "required_variables!: Json<Vec<String>>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            id,
+            repo_id,
+            data.name,
+            variables,
+            required_variables
+        )
+        .fetch_one(pool)
+        .await
+        .map(EnvProfile::from)
+        .map_err(map_unique_violation)
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateEnvProfile,
+    ) -> Result<Self, EnvProfileError> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(EnvProfileError::NotFound)?;
+        let name = data.name.as_ref().unwrap_or(&existing.name);
+        let variables = data.variables.as_ref().unwrap_or(&existing.variables);
+        let required_variables = data
+            .required_variables
+            .as_ref()
+            .unwrap_or(&existing.required_variables);
+
+        let variables = serde_json::to_string(variables)?;
+        let required_variables = serde_json::to_string(required_variables)?;
+        sqlx::query_as!(
+            EnvProfileRow,
+            r#"
+            UPDATE env_profiles
+            SET name = ?, variables = ?, required_variables = ?,
+                updated_at = datetime('now', 'subsec')
+            WHERE id = ?
+            RETURNING
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                name as "name!",
+                variables as "variables!: Json<HashMap<String, String>>",
+                required_variables as "required_variables!: Json<Vec<String>>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            name,
+            variables,
+            required_variables,
+            id
+        )
+        .fetch_one(pool)
+        .await
+        .map(EnvProfile::from)
+        .map_err(map_unique_violation)
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM env_profiles WHERE id = ?", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Replace the set of profiles selected for a workspace.
+    pub async fn set_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        profile_ids: &[Uuid],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM workspace_env_profiles WHERE workspace_id = ?",
+            workspace_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        for profile_id in profile_ids {
+            sqlx::query!(
+                r#"INSERT OR IGNORE INTO workspace_env_profiles (workspace_id, env_profile_id)
+                   VALUES (?, ?)"#,
+                workspace_id,
+                profile_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+}
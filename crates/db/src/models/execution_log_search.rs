@@ -0,0 +1,184 @@
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use super::execution_process::ExecutionProcessRunReason;
+
+/// A log line queued for insertion into the search index.
+#[derive(Debug, Clone)]
+pub struct IndexedLogLine {
+    /// Zero-based line number within the execution's JSONL log file.
+    pub line_number: i64,
+    /// Byte offset of the start of the line within the JSONL log file.
+    pub byte_offset: i64,
+    pub stream: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ExecutionLogIndexState {
+    pub execution_id: Uuid,
+    pub indexed_lines: i64,
+    pub indexed_bytes: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExecutionLogSearchHit {
+    pub execution_id: Uuid,
+    pub session_id: Uuid,
+    pub workspace_id: Uuid,
+    pub run_reason: ExecutionProcessRunReason,
+    pub line_number: i64,
+    pub byte_offset: i64,
+    pub stream: String,
+    pub snippet: String,
+}
+
+pub struct ExecutionLogSearch;
+
+impl ExecutionLogSearch {
+    pub async fn find_state(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+    ) -> Result<Option<ExecutionLogIndexState>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionLogIndexState,
+            r#"
+            SELECT
+                execution_id as "execution_id!: Uuid",
+                indexed_lines as "indexed_lines!",
+                indexed_bytes as "indexed_bytes!"
+            FROM execution_log_index_state
+            WHERE execution_id = ?
+            "#,
+            execution_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Insert a batch of lines and advance the index state in one transaction,
+    /// so a crash can never leave the state pointing past un-indexed lines.
+    pub async fn append_lines(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        lines: &[IndexedLogLine],
+        indexed_lines: i64,
+        indexed_bytes: i64,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let execution_id_str = execution_id.to_string();
+
+        for line in lines {
+            sqlx::query!(
+                r#"INSERT INTO execution_log_search (content, execution_id, line_number, byte_offset, stream)
+                   VALUES (?, ?, ?, ?, ?)"#,
+                line.content,
+                execution_id_str,
+                line.line_number,
+                line.byte_offset,
+                line.stream
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query!(
+            r#"INSERT INTO execution_log_index_state (execution_id, indexed_lines, indexed_bytes)
+               VALUES (?, ?, ?)
+               ON CONFLICT(execution_id) DO UPDATE SET
+                   indexed_lines = excluded.indexed_lines,
+                   indexed_bytes = excluded.indexed_bytes,
+                   updated_at = datetime('now', 'subsec')"#,
+            execution_id,
+            indexed_lines,
+            indexed_bytes
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+
+    /// Run an FTS5 `MATCH` expression, best matches first.
+    ///
+    /// The join goes from the index to `execution_processes` via `unhex()` so
+    /// the lookup uses the primary key, and silently drops hits whose process
+    /// has since been deleted. The index is never aliased: FTS5 only resolves
+    /// `MATCH`, `snippet()` and `rank` against the real table name.
+    pub async fn search(
+        pool: &SqlitePool,
+        match_expr: &str,
+        workspace_id: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<ExecutionLogSearchHit>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionLogSearchHit,
+            r#"
+            SELECT
+                ep.id as "execution_id!: Uuid",
+                ep.session_id as "session_id!: Uuid",
+                s.workspace_id as "workspace_id!: Uuid",
+                ep.run_reason as "run_reason!: ExecutionProcessRunReason",
+                execution_log_search.line_number as "line_number!: i64",
+                execution_log_search.byte_offset as "byte_offset!: i64",
+                execution_log_search.stream as "stream!: String",
+                snippet(execution_log_search, 0, '', '', '…', 24) as "snippet!: String"
+            FROM execution_log_search
+            JOIN execution_processes ep
+                ON ep.id = unhex(replace(execution_log_search.execution_id, '-', ''))
+            JOIN sessions s ON s.id = ep.session_id
+            WHERE execution_log_search MATCH ?1
+              AND ep.dropped = FALSE
+              AND (?2 IS NULL OR s.workspace_id = ?2)
+            ORDER BY execution_log_search.rank
+            LIMIT ?3
+            "#,
+            match_expr,
+            workspace_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Executions whose logs have never been indexed (e.g. written before the
+    /// index existed), oldest first.
+    pub async fn find_unindexed_processes(
+        pool: &SqlitePool,
+        limit: i64,
+    ) -> Result<Vec<(Uuid, Uuid)>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT ep.id as "id!: Uuid", ep.session_id as "session_id!: Uuid"
+               FROM execution_processes ep
+               LEFT JOIN execution_log_index_state st ON st.execution_id = ep.id
+               WHERE st.execution_id IS NULL
+                 AND ep.status != 'running'
+               ORDER BY ep.created_at ASC
+               LIMIT ?"#,
+            limit
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.id, row.session_id))
+            .collect())
+    }
+
+    /// Remove index rows belonging to execution processes that no longer exist.
+    pub async fn delete_orphaned(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM execution_log_search
+               WHERE rowid IN (
+                   SELECT els.rowid
+                   FROM execution_log_search els
+                   LEFT JOIN execution_processes ep
+                       ON ep.id = unhex(replace(els.execution_id, '-', ''))
+                   WHERE ep.id IS NULL
+               )"#
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -54,6 +54,7 @@ pub enum ExecutionProcessRunReason {
     SetupScript,
     CleanupScript,
     ArchiveScript,
+    VerifyScript,
     CodingAgent,
     DevServer,
 }
@@ -85,7 +86,7 @@ pub struct CreateExecutionProcess {
     pub idempotency_key: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ExecutionContext {
     pub execution_process: ExecutionProcess,
     pub session: Session,
@@ -103,6 +104,22 @@ pub struct LatestProcessInfo {
     pub completed_at: Option<DateTime<Utc>>,
 }
 
+/// Where a running dev server is listening, as detected from its output.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DevServerTarget {
+    pub execution_process_id: Uuid,
+    pub url: String,
+    pub port: u16,
+}
+
+/// Test counts parsed from the output of a verify script.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct TestResults {
+    pub passed: u32,
+    pub failed: u32,
+    pub skipped: u32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ExecutorActionField {
@@ -729,6 +746,262 @@ impl ExecutionProcess {
         Ok(count > 0)
     }
 
+    /// Record the URL a dev server announced. Only the first detection is kept.
+    pub async fn set_dev_server_url(
+        pool: &SqlitePool,
+        id: Uuid,
+        url: &str,
+        port: u16,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE execution_processes
+               SET dev_server_url = ?, dev_server_port = ?
+               WHERE id = ? AND dev_server_port IS NULL"#,
+            url,
+            port,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_dev_server_target(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<DevServerTarget>, sqlx::Error> {
+        sqlx::query_as!(
+            DevServerTarget,
+            r#"
+            SELECT
+                id as "execution_process_id!: Uuid",
+                dev_server_url as "url!",
+                dev_server_port as "port!: u16"
+            FROM execution_processes
+            WHERE id = ? AND dev_server_port IS NOT NULL
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Tag a dev server process with the named dev server it runs.
+    pub async fn set_dev_server_id(
+        pool: &SqlitePool,
+        id: Uuid,
+        dev_server_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE execution_processes SET dev_server_id = ? WHERE id = ?",
+            dev_server_id,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Running processes of a named dev server.
+    pub async fn find_running_by_dev_server_id(
+        pool: &SqlitePool,
+        dev_server_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcess,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                session_id as "session_id!: Uuid",
+                run_reason as "run_reason!: ExecutionProcessRunReason",
+                executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
+                status as "status!: ExecutionProcessStatus",
+                exit_code as "exit_code?",
+                dropped as "dropped!: bool",
+                started_at as "started_at!: DateTime<Utc>",
+                completed_at as "completed_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM execution_processes
+            WHERE dev_server_id = ?
+              AND status = 'running'
+              AND run_reason = 'devserver'
+            ORDER BY created_at DESC
+            "#,
+            dev_server_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// `(execution_process_id, dev_server_id)` for every running named dev
+    /// server in a workspace.
+    pub async fn find_running_named_dev_servers_by_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<(Uuid, Uuid)>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT ep.id as "id!: Uuid", ep.dev_server_id as "dev_server_id!: Uuid"
+               FROM execution_processes ep
+               JOIN sessions s ON ep.session_id = s.id
+               WHERE s.workspace_id = ?
+                 AND ep.run_reason = 'devserver'
+                 AND ep.status = 'running'
+                 AND ep.dev_server_id IS NOT NULL"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.id, row.dev_server_id))
+            .collect())
+    }
+
+    /// The most recently started running dev server in a workspace that has
+    /// announced a URL.
+    pub async fn find_running_dev_server_target_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<DevServerTarget>, sqlx::Error> {
+        sqlx::query_as!(
+            DevServerTarget,
+            r#"
+            SELECT
+                ep.id as "execution_process_id!: Uuid",
+                ep.dev_server_url as "url!",
+                ep.dev_server_port as "port!: u16"
+            FROM execution_processes ep
+            JOIN sessions s ON ep.session_id = s.id
+            WHERE s.workspace_id = ?
+              AND ep.run_reason = 'devserver'
+              AND ep.status = 'running'
+              AND ep.dev_server_port IS NOT NULL
+            ORDER BY ep.created_at DESC
+            LIMIT 1
+            "#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// `(runs, seconds)` spent by coding agents in a workspace. Processes
+    /// that are still running count up to now.
+    pub async fn coding_agent_usage_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<(i64, f64), sqlx::Error> {
+        let row = sqlx::query!(
+            r#"SELECT COUNT(*) as "runs!: i64",
+                      COALESCE(SUM(
+                          (julianday(COALESCE(ep.completed_at, datetime('now', 'subsec')))
+                           - julianday(ep.started_at)) * 86400.0
+                      ), 0.0) as "seconds!: f64"
+               FROM execution_processes ep
+               JOIN sessions s ON ep.session_id = s.id
+               WHERE s.workspace_id = ? AND ep.run_reason = 'codingagent'"#,
+            workspace_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok((row.runs, row.seconds))
+    }
+
+    pub async fn set_test_results(
+        pool: &SqlitePool,
+        id: Uuid,
+        results: &TestResults,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE execution_processes
+               SET tests_passed = ?, tests_failed = ?, tests_skipped = ?
+               WHERE id = ?"#,
+            results.passed,
+            results.failed,
+            results.skipped,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_test_results(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<TestResults>, sqlx::Error> {
+        sqlx::query_as!(
+            TestResults,
+            r#"
+            SELECT
+                tests_passed as "passed!: u32",
+                tests_failed as "failed!: u32",
+                tests_skipped as "skipped!: u32"
+            FROM execution_processes
+            WHERE id = ? AND tests_passed IS NOT NULL
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Test counts of every verify script run since the latest coding agent
+    /// turn in a session, added together.
+    pub async fn find_latest_turn_test_results(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Option<TestResults>, sqlx::Error> {
+        sqlx::query_as!(
+            TestResults,
+            r#"
+            SELECT
+                SUM(tests_passed) as "passed!: u32",
+                SUM(tests_failed) as "failed!: u32",
+                SUM(tests_skipped) as "skipped!: u32"
+            FROM execution_processes
+            WHERE session_id = ?
+              AND run_reason = 'verifyscript'
+              AND tests_passed IS NOT NULL
+              AND created_at >= COALESCE((
+                  SELECT MAX(created_at) FROM execution_processes
+                  WHERE session_id = ? AND run_reason = 'codingagent'
+              ), '')
+            GROUP BY session_id
+            "#,
+            session_id,
+            session_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Whether every verify script run since the session's latest coding
+    /// agent turn exited cleanly. `None` if none ran.
+    pub async fn latest_turn_verify_passed(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Option<bool>, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"SELECT COUNT(*) as "runs!: i64",
+                      COALESCE(SUM(CASE WHEN status = 'completed' AND exit_code = 0
+                                        THEN 0 ELSE 1 END), 0) as "failures!: i64"
+               FROM execution_processes
+               WHERE session_id = ?
+                 AND run_reason = 'verifyscript'
+                 AND created_at >= COALESCE((
+                     SELECT MAX(created_at) FROM execution_processes
+                     WHERE session_id = ? AND run_reason = 'codingagent'
+                 ), '')"#,
+            session_id,
+            session_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok((row.runs > 0).then_some(row.failures == 0))
+    }
+
     /// Returns the completed_at timestamp of the most recent non-devserver execution process
     /// for a workspace, if any has completed.
     pub async fn latest_completed_at_for_workspace(
@@ -0,0 +1,522 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorConfig;
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type, types::Json};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::merge::MergeStatus;
+
+#[derive(Debug, Error)]
+pub enum FlakyTestError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Flaky test not found")]
+    NotFound,
+    #[error("Flaky test tracking is not set up for this repo")]
+    SettingsNotFound,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum TestOutcome {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+/// A test that has failed in a reported run. `flips` counts how often its
+/// outcome changed between reports; a test that only ever fails is broken
+/// rather than flaky.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct FlakyTest {
+    pub id: Uuid,
+    pub repo_id: Uuid,
+    pub suite: String,
+    pub name: String,
+    pub passes: i64,
+    pub failures: i64,
+    pub flips: i64,
+    pub last_outcome: TestOutcome,
+    pub last_failure_message: Option<String>,
+    #[ts(type = "Date | null")]
+    pub last_failed_at: Option<DateTime<Utc>>,
+    pub quarantined: bool,
+    /// Workspace an agent was started in to fix the test.
+    pub fix_workspace_id: Option<Uuid>,
+    /// Latest pull request opened from the fix workspace.
+    pub fix_pr_url: Option<String>,
+    pub fix_pr_status: Option<MergeStatus>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// How a repo's test reports are handled. The token CI reports with is only
+/// shown when issued.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct FlakyTestSettings {
+    pub repo_id: Uuid,
+    /// Start an agent on the flakiest quarantined test when a report comes
+    /// in and no other fix is in progress.
+    pub auto_fix: bool,
+    /// Branch fix workspaces start from.
+    pub target_branch: String,
+    pub executor_config: ExecutorConfig,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+struct FlakyTestSettingsRow {
+    repo_id: Uuid,
+    auto_fix: bool,
+    target_branch: String,
+    executor_config: Json<ExecutorConfig>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<FlakyTestSettingsRow> for FlakyTestSettings {
+    fn from(row: FlakyTestSettingsRow) -> Self {
+        Self {
+            repo_id: row.repo_id,
+            auto_fix: row.auto_fix,
+            target_branch: row.target_branch,
+            executor_config: row.executor_config.0,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpsertFlakyTestSettings {
+    pub auto_fix: bool,
+    pub target_branch: String,
+    pub executor_config: ExecutorConfig,
+}
+
+impl FlakyTest {
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            FlakyTest,
+            r#"
+            SELECT
+                ft.id as "id!: Uuid",
+                ft.repo_id as "repo_id!: Uuid",
+                ft.suite as "suite!",
+                ft.name as "name!",
+                ft.passes as "passes!",
+                ft.failures as "failures!",
+                ft.flips as "flips!",
+                ft.last_outcome as "last_outcome!: TestOutcome",
+                ft.last_failure_message as "last_failure_message?",
+                ft.last_failed_at as "last_failed_at?: DateTime<Utc>",
+                ft.quarantined as "quarantined!: bool",
+                ft.fix_workspace_id as "fix_workspace_id?: Uuid",
+                (SELECT pr.pr_url FROM pull_requests pr
+                 WHERE pr.workspace_id = ft.fix_workspace_id AND pr.repo_id = ft.repo_id
+                 ORDER BY pr.created_at DESC LIMIT 1) as "fix_pr_url?",
+                (SELECT pr.pr_status FROM pull_requests pr
+                 WHERE pr.workspace_id = ft.fix_workspace_id AND pr.repo_id = ft.repo_id
+                 ORDER BY pr.created_at DESC LIMIT 1) as "fix_pr_status?: MergeStatus",
+                ft.created_at as "created_at!: DateTime<Utc>",
+                ft.updated_at as "updated_at!: DateTime<Utc>"
+            FROM flaky_tests ft
+            WHERE ft.id = ?
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// A repo's tracked tests, flakiest first: most outcome changes, then
+    /// most failures.
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        quarantined_only: bool,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            FlakyTest,
+            r#"
+            SELECT
+                ft.id as "id!: Uuid",
+                ft.repo_id as "repo_id!: Uuid",
+                ft.suite as "suite!",
+                ft.name as "name!",
+                ft.passes as "passes!",
+                ft.failures as "failures!",
+                ft.flips as "flips!",
+                ft.last_outcome as "last_outcome!: TestOutcome",
+                ft.last_failure_message as "last_failure_message?",
+                ft.last_failed_at as "last_failed_at?: DateTime<Utc>",
+                ft.quarantined as "quarantined!: bool",
+                ft.fix_workspace_id as "fix_workspace_id?: Uuid",
+                (SELECT pr.pr_url FROM pull_requests pr
+                 WHERE pr.workspace_id = ft.fix_workspace_id AND pr.repo_id = ft.repo_id
+                 ORDER BY pr.created_at DESC LIMIT 1) as "fix_pr_url?",
+                (SELECT pr.pr_status FROM pull_requests pr
+                 WHERE pr.workspace_id = ft.fix_workspace_id AND pr.repo_id = ft.repo_id
+                 ORDER BY pr.created_at DESC LIMIT 1) as "fix_pr_status?: MergeStatus",
+                ft.created_at as "created_at!: DateTime<Utc>",
+                ft.updated_at as "updated_at!: DateTime<Utc>"
+            FROM flaky_tests ft
+            WHERE ft.repo_id = ? AND (? = 0 OR ft.quarantined = 1)
+            ORDER BY ft.flips DESC, ft.failures DESC, ft.updated_at DESC
+            LIMIT ?
+            "#,
+            repo_id,
+            quarantined_only,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The flakiest quarantined test nobody has been asked to fix yet.
+    pub async fn find_flakiest_unfixed(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            FlakyTest,
+            r#"
+            SELECT
+                ft.id as "id!: Uuid",
+                ft.repo_id as "repo_id!: Uuid",
+                ft.suite as "suite!",
+                ft.name as "name!",
+                ft.passes as "passes!",
+                ft.failures as "failures!",
+                ft.flips as "flips!",
+                ft.last_outcome as "last_outcome!: TestOutcome",
+                ft.last_failure_message as "last_failure_message?",
+                ft.last_failed_at as "last_failed_at?: DateTime<Utc>",
+                ft.quarantined as "quarantined!: bool",
+                ft.fix_workspace_id as "fix_workspace_id?: Uuid",
+                (SELECT pr.pr_url FROM pull_requests pr
+                 WHERE pr.workspace_id = ft.fix_workspace_id AND pr.repo_id = ft.repo_id
+                 ORDER BY pr.created_at DESC LIMIT 1) as "fix_pr_url?",
+                (SELECT pr.pr_status FROM pull_requests pr
+                 WHERE pr.workspace_id = ft.fix_workspace_id AND pr.repo_id = ft.repo_id
+                 ORDER BY pr.created_at DESC LIMIT 1) as "fix_pr_status?: MergeStatus",
+                ft.created_at as "created_at!: DateTime<Utc>",
+                ft.updated_at as "updated_at!: DateTime<Utc>"
+            FROM flaky_tests ft
+            WHERE ft.repo_id = ? AND ft.quarantined = 1 AND ft.fix_workspace_id IS NULL
+            ORDER BY ft.flips DESC, ft.failures DESC, ft.updated_at DESC
+            LIMIT 1
+            "#,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Whether a fix workspace of the repo has no merged or closed pull
+    /// request yet.
+    pub async fn has_fix_in_progress(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS(
+                   SELECT 1 FROM flaky_tests ft
+                   WHERE ft.repo_id = ?
+                     AND ft.fix_workspace_id IS NOT NULL
+                     AND NOT EXISTS (
+                         SELECT 1 FROM pull_requests pr
+                         WHERE pr.workspace_id = ft.fix_workspace_id
+                           AND pr.repo_id = ft.repo_id
+                           AND pr.pr_status IN ('merged', 'closed')
+                     )
+               ) as "exists!: bool""#,
+            repo_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Count a failure, starting to track the test if needed.
+    pub async fn record_failure(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        suite: &str,
+        name: &str,
+        message: Option<&str>,
+        failed_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query!(
+            r#"INSERT INTO flaky_tests
+                   (id, repo_id, suite, name, failures, last_outcome, last_failure_message,
+                    last_failed_at)
+               VALUES (?, ?, ?, ?, 1, 'failed', ?, ?)
+               ON CONFLICT (repo_id, suite, name) DO UPDATE SET
+                   failures = failures + 1,
+                   flips = flips + (last_outcome = 'passed'),
+                   last_outcome = 'failed',
+                   last_failure_message = excluded.last_failure_message,
+                   last_failed_at = excluded.last_failed_at,
+                   updated_at = datetime('now', 'subsec')"#,
+            id,
+            repo_id,
+            suite,
+            name,
+            message,
+            failed_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Count a pass of a tracked test. Untracked tests are ignored.
+    pub async fn record_pass(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        suite: &str,
+        name: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE flaky_tests
+               SET passes = passes + 1,
+                   flips = flips + (last_outcome = 'failed'),
+                   last_outcome = 'passed',
+                   updated_at = datetime('now', 'subsec')
+               WHERE repo_id = ? AND suite = ? AND name = ?"#,
+            repo_id,
+            suite,
+            name
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Quarantine the repo's tests that flipped at least `min_flips` times.
+    /// Returns how many were newly quarantined.
+    pub async fn quarantine_flipping(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        min_flips: i64,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE flaky_tests
+               SET quarantined = 1, updated_at = datetime('now', 'subsec')
+               WHERE repo_id = ? AND quarantined = 0 AND flips >= ?"#,
+            repo_id,
+            min_flips
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn set_quarantined(
+        pool: &SqlitePool,
+        id: Uuid,
+        quarantined: bool,
+    ) -> Result<(), FlakyTestError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE flaky_tests
+            SET quarantined = ?, updated_at = datetime('now', 'subsec')
+            WHERE id = ?
+            "#,
+            quarantined,
+            id
+        )
+        .execute(pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(FlakyTestError::NotFound);
+        }
+        Ok(())
+    }
+
+    pub async fn set_fix_workspace(
+        pool: &SqlitePool,
+        id: Uuid,
+        workspace_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE flaky_tests
+            SET fix_workspace_id = ?, updated_at = datetime('now', 'subsec')
+            WHERE id = ?
+            "#,
+            workspace_id,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Stop tracking a test, e.g. once its fix is merged. It is tracked
+    /// afresh if it fails again.
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM flaky_tests WHERE id = ?", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl FlakyTestSettings {
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            FlakyTestSettingsRow,
+            r#"
+            SELECT
+                repo_id as "repo_id!: Uuid",
+                auto_fix as "auto_fix!: bool",
+                target_branch as "target_branch!",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM flaky_test_settings
+            WHERE repo_id = ?
+            "#,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|row| row.map(FlakyTestSettings::from))
+    }
+
+    pub async fn find_by_token_hash(
+        pool: &SqlitePool,
+        token_hash: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            FlakyTestSettingsRow,
+            r#"
+            SELECT
+                repo_id as "repo_id!: Uuid",
+                auto_fix as "auto_fix!: bool",
+                target_branch as "target_branch!",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM flaky_test_settings
+            WHERE token_hash = ?
+            "#,
+            token_hash
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|row| row.map(FlakyTestSettings::from))
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        data: &UpsertFlakyTestSettings,
+        token_hash: &str,
+    ) -> Result<Self, FlakyTestError> {
+        let target_branch = data.target_branch.trim();
+        let executor_config = serde_json::to_string(&data.executor_config)?;
+        Ok(sqlx::query_as!(
+            FlakyTestSettingsRow,
+            r#"
+            INSERT INTO flaky_test_settings
+                (repo_id, token_hash, auto_fix, target_branch, executor_config)
+            VALUES (?, ?, ?, ?, ?)
+            RETURNING
+                repo_id as "repo_id!: Uuid",
+                auto_fix as "auto_fix!: bool",
+                target_branch as "target_branch!",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            repo_id,
+            token_hash,
+            data.auto_fix,
+            target_branch,
+            executor_config
+        )
+        .fetch_one(pool)
+        .await
+        .map(FlakyTestSettings::from)?)
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        data: &UpsertFlakyTestSettings,
+    ) -> Result<Self, FlakyTestError> {
+        let target_branch = data.target_branch.trim();
+        let executor_config = serde_json::to_string(&data.executor_config)?;
+        sqlx::query_as!(
+            FlakyTestSettingsRow,
+            r#"
+            UPDATE flaky_test_settings
+            SET auto_fix = ?, target_branch = ?, executor_config = ?,
+                updated_at = datetime('now', 'subsec')
+            WHERE repo_id = ?
+            RETURNING
+                repo_id as "repo_id!: Uuid",
+                auto_fix as "auto_fix!: bool",
+                target_branch as "target_branch!",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            data.auto_fix,
+            target_branch,
+            executor_config,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|row| row.map(FlakyTestSettings::from))?
+        .ok_or(FlakyTestError::SettingsNotFound)
+    }
+
+    /// Replace the report token, invalidating the old one.
+    pub async fn set_token_hash(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        token_hash: &str,
+    ) -> Result<Self, FlakyTestError> {
+        sqlx::query_as!(
+            FlakyTestSettingsRow,
+            r#"
+            UPDATE flaky_test_settings
+            SET token_hash = ?, updated_at = datetime('now', 'subsec')
+            WHERE repo_id = ?
+            RETURNING
+                repo_id as "repo_id!: Uuid",
+                auto_fix as "auto_fix!: bool",
+                target_branch as "target_branch!",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            token_hash,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|row| row.map(FlakyTestSettings::from))?
+        .ok_or(FlakyTestError::SettingsNotFound)
+    }
+}
@@ -0,0 +1,233 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum GitCredentialKind {
+    SshKey,
+    PersonalAccessToken,
+    OauthToken,
+}
+
+/// A git account as stored. The token is encrypted; decryption lives in the
+/// services layer, which owns the machine key.
+#[derive(Debug, Clone)]
+pub struct GitCredential {
+    pub id: Uuid,
+    pub name: String,
+    pub host: String,
+    pub kind: GitCredentialKind,
+    pub username: Option<String>,
+    pub ssh_key_path: Option<String>,
+    pub nonce: Option<Vec<u8>>,
+    pub ciphertext: Option<Vec<u8>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Credential metadata safe to return to clients; never includes the token.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct GitCredentialSummary {
+    pub id: Uuid,
+    pub name: String,
+    /// e.g. `github.com` or `gitlab.example.com`.
+    pub host: String,
+    pub kind: GitCredentialKind,
+    pub username: Option<String>,
+    pub ssh_key_path: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateGitCredential {
+    pub name: String,
+    pub host: String,
+    pub kind: GitCredentialKind,
+    #[ts(optional)]
+    pub username: Option<String>,
+    /// Required for SSH keys.
+    #[ts(optional)]
+    pub ssh_key_path: Option<String>,
+    /// Required for tokens. Write-only: never returned by any endpoint.
+    #[ts(optional)]
+    pub token: Option<String>,
+}
+
+/// A repo that selected a credential.
+#[derive(Debug, Clone)]
+pub struct RepoCredentialSelection {
+    pub repo_id: Uuid,
+    pub repo_path: String,
+    pub git_credential_id: Uuid,
+}
+
+impl GitCredential {
+    pub async fn list(pool: &SqlitePool) -> Result<Vec<GitCredentialSummary>, sqlx::Error> {
+        sqlx::query_as!(
+            GitCredentialSummary,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                name as "name!",
+                host as "host!",
+                kind as "kind!: GitCredentialKind",
+                username as "username?",
+                ssh_key_path as "ssh_key_path?",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM git_credentials
+            ORDER BY host ASC, name ASC
+            "#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            GitCredential,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                name as "name!",
+                host as "host!",
+                kind as "kind!: GitCredentialKind",
+                username as "username?",
+                ssh_key_path as "ssh_key_path?",
+                nonce as "nonce?: Vec<u8>",
+                ciphertext as "ciphertext?: Vec<u8>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM git_credentials
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The credential a repo selected, if any.
+    pub async fn find_for_repo(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Option<GitCredentialSummary>, sqlx::Error> {
+        sqlx::query_as!(
+            GitCredentialSummary,
+            r#"
+            SELECT
+                c.id as "id!: Uuid",
+                c.name as "name!",
+                c.host as "host!",
+                c.kind as "kind!: GitCredentialKind",
+                c.username as "username?",
+                c.ssh_key_path as "ssh_key_path?",
+                c.created_at as "created_at!: DateTime<Utc>",
+                c.updated_at as "updated_at!: DateTime<Utc>"
+            FROM git_credentials c
+            JOIN repos r ON r.git_credential_id = c.id
+            WHERE r.id = ?
+            "#,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Every repo that selected a credential, or only those that selected
+    /// `credential_id`.
+    pub async fn find_repo_selections(
+        pool: &SqlitePool,
+        credential_id: Option<Uuid>,
+    ) -> Result<Vec<RepoCredentialSelection>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoCredentialSelection,
+            r#"
+            SELECT
+                id as "repo_id!: Uuid",
+                path as "repo_path!",
+                git_credential_id as "git_credential_id!: Uuid"
+            FROM repos
+            WHERE git_credential_id IS NOT NULL
+              AND (?1 IS NULL OR git_credential_id = ?1)
+            "#,
+            credential_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Select the credential a repo uses, or clear it.
+    pub async fn select_for_repo(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        credential_id: Option<Uuid>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE repos
+               SET git_credential_id = ?, updated_at = datetime('now', 'subsec')
+               WHERE id = ?"#,
+            credential_id,
+            repo_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Insert a credential. `data.token` is ignored; pass its encrypted form.
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateGitCredential,
+        nonce: Option<&[u8]>,
+        ciphertext: Option<&[u8]>,
+    ) -> Result<GitCredentialSummary, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let name = data.name.trim();
+        let username = data.username.as_deref().map(str::trim);
+        let host = data.host.trim().to_ascii_lowercase();
+        let ssh_key_path = data.ssh_key_path.as_deref().map(str::trim);
+        sqlx::query_as!(
+            GitCredentialSummary,
+            r#"
+            INSERT INTO git_credentials
+                (id, name, host, kind, username, ssh_key_path, nonce, ciphertext)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING
+                id as "id!: Uuid",
+                name as "name!",
+                host as "host!",
+                kind as "kind!: GitCredentialKind",
+                username as "username?",
+                ssh_key_path as "ssh_key_path?",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            id,
+            name,
+            host,
+            data.kind,
+            username,
+            ssh_key_path,
+            nonce,
+            ciphertext
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM git_credentials WHERE id = ?", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,457 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A role in the remote organization whose members sign in here.
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum RemoteOrgRole {
+    Admin,
+    Member,
+}
+
+/// A person using this server, for telling apart whose workspaces and runs
+/// are whose when a team shares it.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct LocalUser {
+    pub id: Uuid,
+    pub name: String,
+    /// Admins manage users and may stop anyone's runs.
+    pub is_admin: bool,
+    /// The remote account this user signs in with, if any.
+    pub remote_user_id: Option<Uuid>,
+    /// Where notifications meant for this user are emailed.
+    pub email: Option<String>,
+    /// The user's role in the remote organization, as of their last sign-in.
+    pub remote_role: Option<RemoteOrgRole>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl LocalUser {
+    pub async fn count(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!("SELECT COUNT(*) FROM local_users")
+            .fetch_one(pool)
+            .await
+    }
+
+    pub async fn list(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            LocalUser,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                name as "name!",
+                is_admin as "is_admin!: bool",
+                remote_user_id as "remote_user_id?: Uuid",
+                email as "email?",
+                remote_role as "remote_role?: RemoteOrgRole",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM local_users
+            ORDER BY name COLLATE NOCASE ASC
+            "#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            LocalUser,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                name as "name!",
+                is_admin as "is_admin!: bool",
+                remote_user_id as "remote_user_id?: Uuid",
+                email as "email?",
+                remote_role as "remote_role?: RemoteOrgRole",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM local_users
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_remote_user_id(
+        pool: &SqlitePool,
+        remote_user_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            LocalUser,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                name as "name!",
+                is_admin as "is_admin!: bool",
+                remote_user_id as "remote_user_id?: Uuid",
+                email as "email?",
+                remote_role as "remote_role?: RemoteOrgRole",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM local_users
+            WHERE remote_user_id = ?
+            "#,
+            remote_user_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        id: Uuid,
+        name: &str,
+        is_admin: bool,
+        remote_user_id: Option<Uuid>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            LocalUser,
+            r#"
+            INSERT INTO local_users (id, name, is_admin, remote_user_id)
+            VALUES (?, ?, ?, ?)
+            RETURNING
+                id as "id!: Uuid",
+                name as "name!",
+                is_admin as "is_admin!: bool",
+                remote_user_id as "remote_user_id?: Uuid",
+                email as "email?",
+                remote_role as "remote_role?: RemoteOrgRole",
+                created_at as "created_at!: DateTime<Utc>"
+            "#,
+            id,
+            name,
+            is_admin,
+            remote_user_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn set_admin(pool: &SqlitePool, id: Uuid, is_admin: bool) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE local_users SET is_admin = ? WHERE id = ?",
+            is_admin,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns whether the user existed. Their sessions and ownership records
+    /// go with them.
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM local_users WHERE id = ?", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn set_email(
+        pool: &SqlitePool,
+        id: Uuid,
+        email: Option<&str>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            LocalUser,
+            r#"
+            UPDATE local_users SET email = ?
+            WHERE id = ?
+            RETURNING
+                id as "id!: Uuid",
+                name as "name!",
+                is_admin as "is_admin!: bool",
+                remote_user_id as "remote_user_id?: Uuid",
+                email as "email?",
+                remote_role as "remote_role?: RemoteOrgRole",
+                created_at as "created_at!: DateTime<Utc>"
+            "#,
+            email,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Record the email address and organization role a remote sign-in
+    /// reported.
+    pub async fn set_remote_details(
+        pool: &SqlitePool,
+        id: Uuid,
+        email: &str,
+        remote_role: RemoteOrgRole,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE local_users SET email = ?, remote_role = ? WHERE id = ?",
+            email,
+            remote_role,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn create_session(
+        pool: &SqlitePool,
+        user_id: Uuid,
+        token_hash: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO local_user_sessions (token_hash, user_id) VALUES (?, ?)",
+            token_hash,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The user a session token hash belongs to, marking the session used.
+    pub async fn find_by_session(
+        pool: &SqlitePool,
+        token_hash: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let user = sqlx::query_as!(
+            LocalUser,
+            r#"
+            SELECT
+                u.id as "id!: Uuid",
+                u.name as "name!",
+                u.is_admin as "is_admin!: bool",
+                u.remote_user_id as "remote_user_id?: Uuid",
+                u.email as "email?",
+                u.remote_role as "remote_role?: RemoteOrgRole",
+                u.created_at as "created_at!: DateTime<Utc>"
+            FROM local_user_sessions s
+            JOIN local_users u ON u.id = s.user_id
+            WHERE s.token_hash = ?
+            "#,
+            token_hash
+        )
+        .fetch_optional(pool)
+        .await?;
+        if user.is_some() {
+            sqlx::query!(
+                r#"
+                UPDATE local_user_sessions
+                SET last_used_at = datetime('now', 'subsec')
+                WHERE token_hash = ?
+                "#,
+                token_hash
+            )
+            .execute(pool)
+            .await?;
+        }
+        Ok(user)
+    }
+
+    pub async fn delete_session(pool: &SqlitePool, token_hash: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM local_user_sessions WHERE token_hash = ?",
+            token_hash
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_workspace_owner(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO workspace_owners (workspace_id, user_id) VALUES (?, ?)
+            ON CONFLICT(workspace_id) DO NOTHING
+            "#,
+            workspace_id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_process_owner(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO execution_process_owners (execution_process_id, user_id) VALUES (?, ?)
+            ON CONFLICT(execution_process_id) DO NOTHING
+            "#,
+            execution_process_id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn workspace_owner(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT user_id as "user_id!: Uuid" FROM workspace_owners WHERE workspace_id = ?"#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Who started an execution: whoever sent the request that started it,
+    /// else the owner of its workspace.
+    pub async fn process_owner(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        let owner: Option<Option<Uuid>> = sqlx::query_scalar!(
+            r#"
+            SELECT COALESCE(
+                (SELECT user_id FROM execution_process_owners
+                 WHERE execution_process_id = ep.id),
+                (SELECT wo.user_id FROM sessions s
+                 JOIN workspace_owners wo ON wo.workspace_id = s.workspace_id
+                 WHERE s.id = ep.session_id)
+            ) as "owner?: Uuid"
+            FROM execution_processes ep
+            WHERE ep.id = ?
+            "#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(owner.flatten())
+    }
+
+    /// Owners of all owned workspaces, by workspace id.
+    pub async fn workspace_owners(pool: &SqlitePool) -> Result<HashMap<Uuid, Uuid>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT workspace_id as "workspace_id!: Uuid", user_id as "user_id!: Uuid"
+               FROM workspace_owners"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.workspace_id, row.user_id))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_pool_without_foreign_keys;
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn sessions_resolve_to_their_user() {
+        let pool = test_pool_without_foreign_keys().await;
+        let alice = LocalUser::create(&pool, Uuid::new_v4(), "alice", true, None)
+            .await
+            .unwrap();
+        assert!(
+            LocalUser::create(&pool, Uuid::new_v4(), "Alice", false, None)
+                .await
+                .is_err(),
+            "names are unique regardless of case"
+        );
+
+        LocalUser::create_session(&pool, alice.id, "hash-1")
+            .await
+            .unwrap();
+        let found = LocalUser::find_by_session(&pool, "hash-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, alice.id);
+        assert!(found.is_admin);
+        assert!(
+            LocalUser::find_by_session(&pool, "hash-2")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        LocalUser::delete_session(&pool, "hash-1").await.unwrap();
+        assert!(
+            LocalUser::find_by_session(&pool, "hash-1")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn remote_accounts_link_to_one_user() {
+        let pool = test_pool_without_foreign_keys().await;
+        let remote_user_id = Uuid::new_v4();
+        let bob = LocalUser::create(&pool, Uuid::new_v4(), "bob", false, Some(remote_user_id))
+            .await
+            .unwrap();
+        LocalUser::create(&pool, Uuid::new_v4(), "carol", false, None)
+            .await
+            .unwrap();
+        LocalUser::create(&pool, Uuid::new_v4(), "dave", false, None)
+            .await
+            .unwrap();
+        assert!(
+            LocalUser::create(&pool, Uuid::new_v4(), "bob2", false, Some(remote_user_id))
+                .await
+                .is_err()
+        );
+
+        let found = LocalUser::find_by_remote_user_id(&pool, remote_user_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, bob.id);
+        assert!(!found.is_admin);
+
+        LocalUser::set_admin(&pool, bob.id, true).await.unwrap();
+        assert!(
+            LocalUser::find_by_id(&pool, bob.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .is_admin
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn workspace_owners_are_recorded_once() {
+        let pool = test_pool_without_foreign_keys().await;
+        let workspace_id = Uuid::new_v4();
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+
+        LocalUser::set_workspace_owner(&pool, workspace_id, first)
+            .await
+            .unwrap();
+        LocalUser::set_workspace_owner(&pool, workspace_id, second)
+            .await
+            .unwrap();
+        assert_eq!(
+            LocalUser::workspace_owner(&pool, workspace_id)
+                .await
+                .unwrap(),
+            Some(first)
+        );
+        assert_eq!(
+            LocalUser::workspace_owners(&pool).await.unwrap()[&workspace_id],
+            first
+        );
+    }
+}
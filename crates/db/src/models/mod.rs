@@ -1,20 +1,45 @@
+pub mod approval_rule;
+pub mod attempt_group;
+pub mod audit_log;
+pub mod ci_triage;
 pub mod coding_agent_turn;
+pub mod config_override;
+pub mod env_profile;
+pub mod execution_log_search;
 pub mod execution_process;
 pub mod execution_process_logs;
 pub mod execution_process_repo_state;
 pub mod file;
+pub mod flaky_test;
+pub mod git_credential;
 pub mod idempotency;
+pub mod local_user;
 pub mod merge;
+pub mod pipeline;
 pub mod project;
 pub mod pull_request;
 pub mod repo;
+pub mod repo_dev_server;
+pub mod repo_knowledge;
+pub mod repo_metadata;
+pub mod repo_package;
+pub mod repo_shared_cache;
 pub mod requests;
+pub mod schedule;
 pub mod scratch;
+pub mod script_library;
+pub mod secret;
 pub mod session;
+pub mod session_checkpoint;
+pub mod session_share;
 pub mod tag;
 pub mod task;
+pub mod warm_worktree;
+pub mod webhook_trigger;
 pub mod workspace;
+pub mod workspace_context;
 pub mod workspace_repo;
+pub mod workspace_undo;
 
 #[cfg(test)]
 mod idempotency_tests;
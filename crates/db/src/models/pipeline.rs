@@ -0,0 +1,515 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorConfig;
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type, types::Json};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::requests::WorkspaceRepoInput;
+
+#[derive(Debug, Error)]
+pub enum PipelineError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Pipeline not found")]
+    NotFound,
+    #[error("Pipeline run not found")]
+    RunNotFound,
+    #[error("A pipeline with this name already exists")]
+    DuplicateName,
+}
+
+/// One step of a pipeline, run in its own workspace.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PipelineStage {
+    pub name: String,
+    /// Prompt template for the stage. Supports `{{prompt}}`, `{{stage}}`,
+    /// `{{pipeline}}`, `{{previous_branch}}` and `{{previous_workspace}}`.
+    pub prompt: String,
+    /// Executor for this stage; defaults to the run's executor.
+    #[serde(default)]
+    #[ts(optional)]
+    pub executor_config: Option<ExecutorConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct Pipeline {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub stages: Vec<PipelineStage>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+struct PipelineRow {
+    id: Uuid,
+    name: String,
+    description: Option<String>,
+    stages: Json<Vec<PipelineStage>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<PipelineRow> for Pipeline {
+    fn from(row: PipelineRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            description: row.description,
+            stages: row.stages.0,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreatePipeline {
+    pub name: String,
+    pub description: Option<String>,
+    pub stages: Vec<PipelineStage>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdatePipeline {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub stages: Option<Vec<PipelineStage>>,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum PipelineStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PipelineRun {
+    pub id: Uuid,
+    pub pipeline_id: Uuid,
+    pub status: PipelineStatus,
+    pub prompt: String,
+    pub repos: Vec<WorkspaceRepoInput>,
+    pub executor_config: ExecutorConfig,
+    pub error: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+struct PipelineRunRow {
+    id: Uuid,
+    pipeline_id: Uuid,
+    status: PipelineStatus,
+    prompt: String,
+    repos: Json<Vec<WorkspaceRepoInput>>,
+    executor_config: Json<ExecutorConfig>,
+    error: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<PipelineRunRow> for PipelineRun {
+    fn from(row: PipelineRunRow) -> Self {
+        Self {
+            id: row.id,
+            pipeline_id: row.pipeline_id,
+            status: row.status,
+            prompt: row.prompt,
+            repos: row.repos.0,
+            executor_config: row.executor_config.0,
+            error: row.error,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PipelineRunStage {
+    pub pipeline_run_id: Uuid,
+    pub stage_index: i64,
+    pub workspace_id: Option<Uuid>,
+    pub status: PipelineStatus,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+fn map_unique_violation(e: sqlx::Error) -> PipelineError {
+    match &e {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            PipelineError::DuplicateName
+        }
+        _ => PipelineError::Database(e),
+    }
+}
+
+impl Pipeline {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PipelineRow,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                name as "name!",
+                description as "description?",
+                stages as "stages!: Json<Vec<PipelineStage>>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM pipelines
+            ORDER BY name ASC
+            "#
+        )
+        .fetch_all(pool)
+        .await
+        .map(|rows| rows.into_iter().map(Pipeline::from).collect())
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PipelineRow,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                name as "name!",
+                description as "description?",
+                stages as "stages!: Json<Vec<PipelineStage>>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM pipelines
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|row| row.map(Pipeline::from))
+    }
+
+    pub async fn create(pool: &SqlitePool, data: &CreatePipeline) -> Result<Self, PipelineError> {
+        let id = Uuid::new_v4();
+        let name = data.name.trim();
+        let stages = serde_json::to_string(&data.stages)?;
+        sqlx::query_as!(
+            PipelineRow,
+            r#"
+            INSERT INTO pipelines (id, name, description, stages)
+            VALUES (?, ?, ?, ?)
+            RETURNING
+                id as "id!: Uuid",
+                name as "name!",
+                description as "description?",
+                stages as "stages!: Json<Vec<PipelineStage>>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            id,
+            name,
+            data.description,
+            stages
+        )
+        .fetch_one(pool)
+        .await
+        .map(Pipeline::from)
+        .map_err(map_unique_violation)
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdatePipeline,
+    ) -> Result<Self, PipelineError> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(PipelineError::NotFound)?;
+        let stages = data.stages.as_ref().unwrap_or(&existing.stages);
+
+        let stages = serde_json::to_string(stages)?;
+        let name = data.name.as_deref().unwrap_or(&existing.name).trim();
+        let description = data.description.as_ref().or(existing.description.as_ref());
+        sqlx::query_as!(
+            PipelineRow,
+            r#"
+            UPDATE pipelines
+            SET name = ?, description = ?, stages = ?,
+                updated_at = datetime('now', 'subsec')
+            WHERE id = ?
+            RETURNING
+                id as "id!: Uuid",
+                name as "name!",
+                description as "description?",
+                stages as "stages!: Json<Vec<PipelineStage>>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            name,
+            description,
+            stages,
+            id
+        )
+        .fetch_one(pool)
+        .await
+        .map(Pipeline::from)
+        .map_err(map_unique_violation)
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM pipelines WHERE id = ?", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl PipelineRun {
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PipelineRunRow,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                pipeline_id as "pipeline_id!: Uuid",
+                status as "status!: PipelineStatus",
+                prompt as "prompt!",
+                repos as "repos!: Json<Vec<WorkspaceRepoInput>>",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                error as "error?",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM pipeline_runs
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|row| row.map(PipelineRun::from))
+    }
+
+    pub async fn find_by_pipeline_id(
+        pool: &SqlitePool,
+        pipeline_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PipelineRunRow,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                pipeline_id as "pipeline_id!: Uuid",
+                status as "status!: PipelineStatus",
+                prompt as "prompt!",
+                repos as "repos!: Json<Vec<WorkspaceRepoInput>>",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                error as "error?",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM pipeline_runs
+            WHERE pipeline_id = ?
+            ORDER BY created_at DESC
+            "#,
+            pipeline_id
+        )
+        .fetch_all(pool)
+        .await
+        .map(|rows| rows.into_iter().map(PipelineRun::from).collect())
+    }
+
+    /// Running pipelines whose latest stage has finished and which therefore
+    /// need to advance, complete or fail.
+    pub async fn find_awaiting_advance(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PipelineRunRow,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                pipeline_id as "pipeline_id!: Uuid",
+                status as "status!: PipelineStatus",
+                prompt as "prompt!",
+                repos as "repos!: Json<Vec<WorkspaceRepoInput>>",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                error as "error?",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM pipeline_runs pr
+            WHERE pr.status = 'running'
+              AND EXISTS (
+                  SELECT 1 FROM pipeline_run_stages s
+                  WHERE s.pipeline_run_id = pr.id
+                    AND s.status != 'running'
+                    AND s.stage_index = (
+                        SELECT MAX(stage_index) FROM pipeline_run_stages
+                        WHERE pipeline_run_id = pr.id
+                    )
+              )
+            ORDER BY pr.created_at ASC
+            "#
+        )
+        .fetch_all(pool)
+        .await
+        .map(|rows| rows.into_iter().map(PipelineRun::from).collect())
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        pipeline_id: Uuid,
+        prompt: &str,
+        repos: &[WorkspaceRepoInput],
+        executor_config: &ExecutorConfig,
+    ) -> Result<Self, PipelineError> {
+        let id = Uuid::new_v4();
+        let executor_config = serde_json::to_string(executor_config)?;
+        let repos = serde_json::to_string(repos)?;
+        Ok(sqlx::query_as!(
+            PipelineRunRow,
+            r#"
+            INSERT INTO pipeline_runs (id, pipeline_id, prompt, repos, executor_config)
+            VALUES (?, ?, ?, ?, ?)
+            RETURNING
+                id as "id!: Uuid",
+                pipeline_id as "pipeline_id!: Uuid",
+                status as "status!: PipelineStatus",
+                prompt as "prompt!",
+                repos as "repos!: Json<Vec<WorkspaceRepoInput>>",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                error as "error?",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            id,
+            pipeline_id,
+            prompt,
+            repos,
+            executor_config
+        )
+        .fetch_one(pool)
+        .await
+        .map(PipelineRun::from)?)
+    }
+
+    /// Move a running pipeline to a final status. Returns `false` if it had
+    /// already finished.
+    pub async fn finish(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: PipelineStatus,
+        error: Option<&str>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE pipeline_runs
+               SET status = ?, error = ?, updated_at = datetime('now', 'subsec')
+               WHERE id = ? AND status = 'running'"#,
+            status,
+            error,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+impl PipelineRunStage {
+    pub async fn find_by_run_id(
+        pool: &SqlitePool,
+        pipeline_run_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PipelineRunStage,
+            r#"
+            SELECT
+                pipeline_run_id as "pipeline_run_id!: Uuid",
+                stage_index as "stage_index!",
+                workspace_id as "workspace_id?: Uuid",
+                status as "status!: PipelineStatus",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM pipeline_run_stages
+            WHERE pipeline_run_id = ?
+            ORDER BY stage_index ASC
+            "#,
+            pipeline_run_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        pipeline_run_id: Uuid,
+        stage_index: i64,
+        workspace_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            PipelineRunStage,
+            r#"
+            INSERT INTO pipeline_run_stages (pipeline_run_id, stage_index, workspace_id)
+            VALUES (?, ?, ?)
+            ON CONFLICT(pipeline_run_id, stage_index) DO UPDATE SET
+                workspace_id = excluded.workspace_id
+            RETURNING
+                pipeline_run_id as "pipeline_run_id!: Uuid",
+                stage_index as "stage_index!",
+                workspace_id as "workspace_id?: Uuid",
+                status as "status!: PipelineStatus",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            pipeline_run_id,
+            stage_index,
+            workspace_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Record how the running stage in `workspace_id` ended. Returns `true` if
+    /// a pipeline stage was waiting on this workspace.
+    pub async fn finish_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        status: PipelineStatus,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE pipeline_run_stages
+               SET status = ?, updated_at = datetime('now', 'subsec')
+               WHERE workspace_id = ? AND status = 'running'"#,
+            status,
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Cancel every still-running stage of a run.
+    pub async fn cancel_for_run(
+        pool: &SqlitePool,
+        pipeline_run_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE pipeline_run_stages
+               SET status = 'cancelled', updated_at = datetime('now', 'subsec')
+               WHERE pipeline_run_id = ? AND status = 'running'"#,
+            pipeline_run_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
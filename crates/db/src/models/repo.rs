@@ -3,7 +3,7 @@ use std::path::{Path, PathBuf};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::rust::double_option;
-use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type};
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
@@ -25,6 +25,32 @@ pub enum SearchMatchType {
     FullPath,
 }
 
+/// What happens to the rest of an action chain when a setup, cleanup or
+/// archive script fails.
+#[derive(Debug, Clone, Copy, Default, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum ScriptFailurePolicy {
+    /// Stop the chain; later actions (e.g. the coding agent) never start.
+    #[default]
+    Abort,
+    /// Carry on with the next action as if the script had succeeded.
+    Continue,
+    /// Re-run the script up to `max_retries` times before aborting.
+    Retry,
+}
+
+/// Per-repo timeouts and failure handling for lifecycle scripts.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RepoScriptSettings {
+    pub setup_timeout_secs: Option<u32>,
+    pub cleanup_timeout_secs: Option<u32>,
+    pub archive_timeout_secs: Option<u32>,
+    pub on_failure: ScriptFailurePolicy,
+    pub max_retries: u32,
+}
+
 #[derive(Debug, Error)]
 pub enum RepoError {
     #[error(transparent)]
@@ -433,4 +459,129 @@ impl Repo {
         .await
         .map_err(RepoError::from)
     }
+
+    pub async fn log_redaction_enabled(pool: &SqlitePool, id: Uuid) -> Result<bool, RepoError> {
+        sqlx::query_scalar!(
+            r#"SELECT log_redaction_enabled as "log_redaction_enabled!: bool"
+               FROM repos WHERE id = ?"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or(RepoError::NotFound)
+    }
+
+    pub async fn set_log_redaction_enabled(
+        pool: &SqlitePool,
+        id: Uuid,
+        enabled: bool,
+    ) -> Result<(), RepoError> {
+        let result = sqlx::query!(
+            "UPDATE repos SET log_redaction_enabled = ?, updated_at = datetime('now', 'subsec') WHERE id = ?",
+            enabled,
+            id
+        )
+        .execute(pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+
+    pub async fn script_settings(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<RepoScriptSettings, RepoError> {
+        sqlx::query_as!(
+            RepoScriptSettings,
+            r#"
+            SELECT
+                setup_script_timeout_secs as "setup_timeout_secs?: u32",
+                cleanup_script_timeout_secs as "cleanup_timeout_secs?: u32",
+                archive_script_timeout_secs as "archive_timeout_secs?: u32",
+                script_on_failure as "on_failure!: ScriptFailurePolicy",
+                script_max_retries as "max_retries!: u32"
+            FROM repos
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or(RepoError::NotFound)
+    }
+
+    pub async fn set_script_settings(
+        pool: &SqlitePool,
+        id: Uuid,
+        settings: &RepoScriptSettings,
+    ) -> Result<(), RepoError> {
+        let result = sqlx::query!(
+            r#"UPDATE repos
+               SET setup_script_timeout_secs = ?,
+                   cleanup_script_timeout_secs = ?,
+                   archive_script_timeout_secs = ?,
+                   script_on_failure = ?,
+                   script_max_retries = ?,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = ?"#,
+            settings.setup_timeout_secs,
+            settings.cleanup_timeout_secs,
+            settings.archive_timeout_secs,
+            settings.on_failure,
+            settings.max_retries,
+            id
+        )
+        .execute(pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+
+    pub async fn verify_script(pool: &SqlitePool, id: Uuid) -> Result<Option<String>, RepoError> {
+        sqlx::query_scalar!("SELECT verify_script FROM repos WHERE id = ?", id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(RepoError::NotFound)
+    }
+
+    pub async fn set_verify_script(
+        pool: &SqlitePool,
+        id: Uuid,
+        script: Option<&str>,
+    ) -> Result<(), RepoError> {
+        let result = sqlx::query!(
+            "UPDATE repos SET verify_script = ?, updated_at = datetime('now', 'subsec') WHERE id = ?",
+            script,
+            id
+        )
+        .execute(pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(RepoError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Whether output from processes in this session should be redacted. A
+    /// session spans every repo in its workspace, so redaction stays on unless
+    /// all of them have opted out.
+    pub async fn log_redaction_enabled_for_session(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT (COUNT(r.id) = 0 OR MAX(r.log_redaction_enabled) = 1) as "enabled!: bool"
+               FROM sessions s
+               JOIN workspace_repos wr ON wr.workspace_id = s.workspace_id
+               JOIN repos r ON r.id = wr.repo_id
+               WHERE s.id = ?"#,
+            session_id
+        )
+        .fetch_one(pool)
+        .await
+    }
 }
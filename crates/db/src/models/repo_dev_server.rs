@@ -0,0 +1,204 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum RepoDevServerError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Dev server not found")]
+    NotFound,
+    #[error("A dev server with this name already exists for the repository")]
+    DuplicateName,
+    #[error("Dev server name and script must not be empty")]
+    Invalid,
+}
+
+/// A named dev server command attached to a repo.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RepoDevServer {
+    pub id: Uuid,
+    pub repo_id: Uuid,
+    pub name: String,
+    pub script: String,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateRepoDevServer {
+    pub name: String,
+    pub script: String,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateRepoDevServer {
+    pub name: Option<String>,
+    pub script: Option<String>,
+}
+
+fn map_unique_violation(e: sqlx::Error) -> RepoDevServerError {
+    match &e {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            RepoDevServerError::DuplicateName
+        }
+        _ => RepoDevServerError::Database(e),
+    }
+}
+
+fn validate(name: &str, script: &str) -> Result<(), RepoDevServerError> {
+    if name.trim().is_empty() || script.trim().is_empty() {
+        return Err(RepoDevServerError::Invalid);
+    }
+    Ok(())
+}
+
+impl RepoDevServer {
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoDevServer,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                name as "name!",
+                script as "script!",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM repo_dev_servers
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoDevServer,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                name as "name!",
+                script as "script!",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM repo_dev_servers
+            WHERE repo_id = ?
+            ORDER BY name ASC
+            "#,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Dev servers of every repo in a workspace.
+    pub async fn find_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoDevServer,
+            r#"
+            SELECT
+                ds.id as "id!: Uuid",
+                ds.repo_id as "repo_id!: Uuid",
+                ds.name as "name!",
+                ds.script as "script!",
+                ds.created_at as "created_at!: DateTime<Utc>",
+                ds.updated_at as "updated_at!: DateTime<Utc>"
+            FROM repo_dev_servers ds
+            JOIN workspace_repos wr ON wr.repo_id = ds.repo_id
+            WHERE wr.workspace_id = ?
+            ORDER BY ds.name ASC
+            "#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        data: &CreateRepoDevServer,
+    ) -> Result<Self, RepoDevServerError> {
+        validate(&data.name, &data.script)?;
+        let id = Uuid::new_v4();
+        let name = data.name.trim();
+        sqlx::query_as!(
+            RepoDevServer,
+            r#"
+            INSERT INTO repo_dev_servers (id, repo_id, name, script)
+            VALUES (?, ?, ?, ?)
+            RETURNING
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                name as "name!",
+                script as "script!",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            id,
+            repo_id,
+            name,
+            data.script
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(map_unique_violation)
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateRepoDevServer,
+    ) -> Result<Self, RepoDevServerError> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(RepoDevServerError::NotFound)?;
+        let name = data.name.as_deref().unwrap_or(&existing.name).trim();
+        let script = data.script.as_deref().unwrap_or(&existing.script);
+        validate(name, script)?;
+
+        sqlx::query_as!(
+            RepoDevServer,
+            r#"
+            UPDATE repo_dev_servers
+            SET name = ?, script = ?, updated_at = datetime('now', 'subsec')
+            WHERE id = ?
+            RETURNING
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                name as "name!",
+                script as "script!",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            name,
+            script,
+            id
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(map_unique_violation)
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM repo_dev_servers WHERE id = ?", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,280 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Entries longer than this are rejected, since every approved entry ends up
+/// in agent prompts.
+pub const MAX_KNOWLEDGE_CHARS: usize = 2000;
+
+#[derive(Debug, Error)]
+pub enum RepoKnowledgeError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Knowledge entry not found")]
+    NotFound,
+    #[error("Knowledge entries must be between 1 and 2000 characters")]
+    Invalid,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum RepoKnowledgeCategory {
+    Build,
+    Gotcha,
+    Architecture,
+    Other,
+}
+
+impl RepoKnowledgeCategory {
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Build => "Build and test",
+            Self::Gotcha => "Gotchas",
+            Self::Architecture => "Architecture",
+            Self::Other => "Notes",
+        }
+    }
+}
+
+/// Only approved entries are added to agent prompts. Entries suggested by
+/// agents wait for approval.
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum RepoKnowledgeStatus {
+    Approved,
+    Suggested,
+}
+
+/// A learning about a repo, kept across sessions.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RepoKnowledge {
+    pub id: Uuid,
+    pub repo_id: Uuid,
+    pub category: RepoKnowledgeCategory,
+    pub content: String,
+    pub status: RepoKnowledgeStatus,
+    /// The session the entry was saved from, if any.
+    pub session_id: Option<Uuid>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateRepoKnowledge {
+    pub category: RepoKnowledgeCategory,
+    pub content: String,
+    #[ts(optional)]
+    pub session_id: Option<Uuid>,
+    /// Saves the entry as a suggestion that must be approved before agents see
+    /// it. Used for entries proposed by agents.
+    #[ts(optional)]
+    pub suggested: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateRepoKnowledge {
+    pub category: Option<RepoKnowledgeCategory>,
+    pub content: Option<String>,
+}
+
+fn validate(content: &str) -> Result<(), RepoKnowledgeError> {
+    let length = content.trim().chars().count();
+    if length == 0 || length > MAX_KNOWLEDGE_CHARS {
+        return Err(RepoKnowledgeError::Invalid);
+    }
+    Ok(())
+}
+
+impl RepoKnowledge {
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoKnowledge,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                category as "category!: RepoKnowledgeCategory",
+                content as "content!",
+                status as "status!: RepoKnowledgeStatus",
+                session_id as "session_id?: Uuid",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM repo_knowledge
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Every entry of a repo, suggestions first, then by category.
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoKnowledge,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                category as "category!: RepoKnowledgeCategory",
+                content as "content!",
+                status as "status!: RepoKnowledgeStatus",
+                session_id as "session_id?: Uuid",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM repo_knowledge
+            WHERE repo_id = ?
+            ORDER BY status = 'approved', category, created_at
+            "#,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Approved entries of every repo in a workspace.
+    pub async fn find_approved_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoKnowledge,
+            r#"
+            SELECT
+                rk.id as "id!: Uuid",
+                rk.repo_id as "repo_id!: Uuid",
+                rk.category as "category!: RepoKnowledgeCategory",
+                rk.content as "content!",
+                rk.status as "status!: RepoKnowledgeStatus",
+                rk.session_id as "session_id?: Uuid",
+                rk.created_at as "created_at!: DateTime<Utc>",
+                rk.updated_at as "updated_at!: DateTime<Utc>"
+            FROM repo_knowledge rk
+            JOIN workspace_repos wr ON wr.repo_id = rk.repo_id
+            WHERE wr.workspace_id = ? AND rk.status = 'approved'
+            ORDER BY rk.category, rk.created_at
+            "#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        data: &CreateRepoKnowledge,
+    ) -> Result<Self, RepoKnowledgeError> {
+        validate(&data.content)?;
+        let status = if data.suggested.unwrap_or(false) {
+            RepoKnowledgeStatus::Suggested
+        } else {
+            RepoKnowledgeStatus::Approved
+        };
+        let id = Uuid::new_v4();
+        let content = data.content.trim();
+        Ok(sqlx::query_as!(
+            RepoKnowledge,
+            r#"
+            INSERT INTO repo_knowledge (id, repo_id, category, content, status, session_id)
+            VALUES (?, ?, ?, ?, ?, ?)
+            RETURNING
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                category as "category!: RepoKnowledgeCategory",
+                content as "content!",
+                status as "status!: RepoKnowledgeStatus",
+                session_id as "session_id?: Uuid",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            id,
+            repo_id,
+            data.category,
+            content,
+            status,
+            data.session_id
+        )
+        .fetch_one(pool)
+        .await?)
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateRepoKnowledge,
+    ) -> Result<Self, RepoKnowledgeError> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(RepoKnowledgeError::NotFound)?;
+        let category = data.category.unwrap_or(existing.category);
+        let content = data.content.as_deref().unwrap_or(&existing.content).trim();
+        validate(content)?;
+
+        Ok(sqlx::query_as!(
+            RepoKnowledge,
+            r#"
+            UPDATE repo_knowledge
+            SET category = ?, content = ?, updated_at = datetime('now', 'subsec')
+            WHERE id = ?
+            RETURNING
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                category as "category!: RepoKnowledgeCategory",
+                content as "content!",
+                status as "status!: RepoKnowledgeStatus",
+                session_id as "session_id?: Uuid",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            category,
+            content,
+            id
+        )
+        .fetch_one(pool)
+        .await?)
+    }
+
+    pub async fn approve(pool: &SqlitePool, id: Uuid) -> Result<Self, RepoKnowledgeError> {
+        sqlx::query_as!(
+            RepoKnowledge,
+            r#"
+            UPDATE repo_knowledge
+            SET status = 'approved', updated_at = datetime('now', 'subsec')
+            WHERE id = ?
+            RETURNING
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                category as "category!: RepoKnowledgeCategory",
+                content as "content!",
+                status as "status!: RepoKnowledgeStatus",
+                session_id as "session_id?: Uuid",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or(RepoKnowledgeError::NotFound)
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM repo_knowledge WHERE id = ?", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,331 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What a repo's metadata index was last built from.
+#[derive(Debug, Clone)]
+pub struct RepoIndexState {
+    pub repo_id: Uuid,
+    pub refs_fingerprint: String,
+    /// Newest indexed commit of HEAD, where the next incremental walk stops.
+    pub head_commit: Option<String>,
+    pub indexed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RepoBranch {
+    pub name: String,
+    pub is_remote: bool,
+    pub is_current: bool,
+    pub last_commit_date: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct RepoCommit {
+    pub sha: String,
+    pub summary: String,
+    pub author_name: String,
+    pub author_email: String,
+    #[ts(type = "Date")]
+    pub committed_at: DateTime<Utc>,
+}
+
+/// An author of the indexed commits of a repo.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct RepoContributor {
+    pub name: String,
+    pub email: String,
+    #[ts(type = "number")]
+    pub commit_count: i64,
+    #[ts(type = "Date")]
+    pub last_commit_at: DateTime<Utc>,
+}
+
+/// A refresh of a repo's index, applied in one transaction.
+#[derive(Debug, Clone)]
+pub struct RepoIndexUpdate {
+    pub refs_fingerprint: String,
+    pub head_commit: Option<String>,
+    /// Replaces all indexed branches.
+    pub branches: Vec<RepoBranch>,
+    pub commits: Vec<RepoCommit>,
+    /// Whether `commits` adds to the indexed commits rather than replacing
+    /// them.
+    pub incremental: bool,
+}
+
+pub struct RepoMetadata;
+
+impl RepoMetadata {
+    pub async fn state(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Option<RepoIndexState>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoIndexState,
+            r#"
+            SELECT
+                repo_id as "repo_id!: Uuid",
+                refs_fingerprint as "refs_fingerprint!",
+                head_commit as "head_commit?",
+                indexed_at as "indexed_at!: DateTime<Utc>"
+            FROM repo_index_state
+            WHERE repo_id = ?
+            "#,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Apply `update`, keeping the newest `max_commits` commits.
+    pub async fn apply(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        update: &RepoIndexUpdate,
+        max_commits: usize,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!("DELETE FROM repo_branches WHERE repo_id = ?", repo_id)
+            .execute(&mut *tx)
+            .await?;
+        for branch in &update.branches {
+            sqlx::query!(
+                r#"
+                INSERT OR REPLACE INTO repo_branches
+                    (repo_id, name, is_remote, is_current, last_commit_date)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+                repo_id,
+                branch.name,
+                branch.is_remote,
+                branch.is_current,
+                branch.last_commit_date
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if !update.incremental {
+            sqlx::query!("DELETE FROM repo_commits WHERE repo_id = ?", repo_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        for commit in &update.commits {
+            sqlx::query!(
+                r#"
+                INSERT OR IGNORE INTO repo_commits
+                    (repo_id, sha, summary, author_name, author_email, committed_at)
+                VALUES (?, ?, ?, ?, ?, ?)
+                "#,
+                repo_id,
+                commit.sha,
+                commit.summary,
+                commit.author_name,
+                commit.author_email,
+                commit.committed_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        let max_commits = i64::try_from(max_commits).unwrap_or(i64::MAX);
+        sqlx::query!(
+            r#"
+            DELETE FROM repo_commits
+            WHERE repo_id = ?1
+              AND sha NOT IN (
+                  SELECT sha FROM repo_commits
+                  WHERE repo_id = ?1
+                  ORDER BY committed_at DESC
+                  LIMIT ?2
+              )
+            "#,
+            repo_id,
+            max_commits
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO repo_index_state (repo_id, refs_fingerprint, head_commit, indexed_at)
+            VALUES (?, ?, ?, datetime('now', 'subsec'))
+            ON CONFLICT(repo_id) DO UPDATE SET
+                refs_fingerprint = excluded.refs_fingerprint,
+                head_commit = excluded.head_commit,
+                indexed_at = excluded.indexed_at
+            "#,
+            repo_id,
+            update.refs_fingerprint,
+            update.head_commit
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+
+    /// Indexed branches, the current one first, then by latest commit.
+    pub async fn branches(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Vec<RepoBranch>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoBranch,
+            r#"
+            SELECT
+                name as "name!",
+                is_remote as "is_remote!: bool",
+                is_current as "is_current!: bool",
+                last_commit_date as "last_commit_date!: DateTime<Utc>"
+            FROM repo_branches
+            WHERE repo_id = ?
+            ORDER BY is_current DESC, last_commit_date DESC
+            "#,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn recent_commits(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<RepoCommit>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoCommit,
+            r#"
+            SELECT
+                sha as "sha!",
+                summary as "summary!",
+                author_name as "author_name!",
+                author_email as "author_email!",
+                committed_at as "committed_at!: DateTime<Utc>"
+            FROM repo_commits
+            WHERE repo_id = ?
+            ORDER BY committed_at DESC
+            LIMIT ?
+            "#,
+            repo_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Authors of the indexed commits, most active first.
+    pub async fn contributors(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Vec<RepoContributor>, sqlx::Error> {
+        // The name of an author's latest commit wins.
+        sqlx::query_as!(
+            RepoContributor,
+            r#"
+            SELECT
+                author_name as "name!",
+                author_email as "email!",
+                COUNT(*) as "commit_count!: i64",
+                MAX(committed_at) as "last_commit_at!: DateTime<Utc>"
+            FROM repo_commits
+            WHERE repo_id = ?
+            GROUP BY author_email
+            ORDER BY 3 DESC, 4 DESC
+            "#,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::test_support::test_pool_without_foreign_keys;
+
+    fn commit(sha: &str, email: &str, secs: i64) -> RepoCommit {
+        RepoCommit {
+            sha: sha.to_string(),
+            summary: format!("commit {sha}"),
+            author_name: email.split('@').next().unwrap().to_string(),
+            author_email: email.to_string(),
+            committed_at: Utc.timestamp_opt(secs, 0).unwrap(),
+        }
+    }
+
+    fn update(commits: Vec<RepoCommit>, incremental: bool) -> RepoIndexUpdate {
+        RepoIndexUpdate {
+            refs_fingerprint: format!("refs-{}", commits.len()),
+            head_commit: commits.first().map(|c| c.sha.clone()),
+            branches: vec![RepoBranch {
+                name: "main".to_string(),
+                is_remote: false,
+                is_current: true,
+                last_commit_date: Utc.timestamp_opt(0, 0).unwrap(),
+            }],
+            commits,
+            incremental,
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn incremental_updates_add_commits_up_to_the_limit() {
+        let pool = test_pool_without_foreign_keys().await;
+        let repo_id = Uuid::new_v4();
+        assert!(RepoMetadata::state(&pool, repo_id).await.unwrap().is_none());
+
+        let initial = vec![
+            commit("b", "bob@example.com", 20),
+            commit("a", "alice@example.com", 10),
+        ];
+        RepoMetadata::apply(&pool, repo_id, &update(initial, false), 3)
+            .await
+            .unwrap();
+        let newer = vec![
+            commit("d", "alice@example.com", 40),
+            commit("c", "alice@example.com", 30),
+        ];
+        RepoMetadata::apply(&pool, repo_id, &update(newer, true), 3)
+            .await
+            .unwrap();
+
+        let state = RepoMetadata::state(&pool, repo_id).await.unwrap().unwrap();
+        assert_eq!(state.head_commit.as_deref(), Some("d"));
+        let shas: Vec<_> = RepoMetadata::recent_commits(&pool, repo_id, 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|c| c.sha)
+            .collect();
+        assert_eq!(shas, ["d", "c", "b"], "the oldest commit is dropped");
+
+        let contributors = RepoMetadata::contributors(&pool, repo_id).await.unwrap();
+        assert_eq!(contributors.len(), 2);
+        assert_eq!(contributors[0].email, "alice@example.com");
+        assert_eq!(contributors[0].commit_count, 2);
+        assert_eq!(
+            RepoMetadata::branches(&pool, repo_id).await.unwrap().len(),
+            1
+        );
+
+        // A full refresh replaces what was indexed.
+        RepoMetadata::apply(
+            &pool,
+            repo_id,
+            &update(vec![commit("e", "carol@example.com", 50)], false),
+            3,
+        )
+        .await
+        .unwrap();
+        let contributors = RepoMetadata::contributors(&pool, repo_id).await.unwrap();
+        assert_eq!(contributors.len(), 1);
+        assert_eq!(contributors[0].name, "carol");
+    }
+}
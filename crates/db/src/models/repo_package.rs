@@ -0,0 +1,254 @@
+use std::path::{Component, Path};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum RepoPackageError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Package not found")]
+    NotFound,
+    #[error("A package with this path already exists for the repository")]
+    DuplicatePath,
+    #[error("Package name must not be empty and its path must be a relative path inside the repo")]
+    Invalid,
+}
+
+/// A package of a monorepo. Workspaces that select it start agents in `path`
+/// and, if set, run `dev_server_script` there instead of the repo's script.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RepoPackage {
+    pub id: Uuid,
+    pub repo_id: Uuid,
+    /// Relative to the repo root.
+    pub path: String,
+    pub name: String,
+    pub dev_server_script: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateRepoPackage {
+    pub path: String,
+    pub name: String,
+    #[ts(optional)]
+    pub dev_server_script: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateRepoPackage {
+    pub name: Option<String>,
+    /// An empty script clears it.
+    pub dev_server_script: Option<String>,
+}
+
+fn map_unique_violation(e: sqlx::Error) -> RepoPackageError {
+    match &e {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            RepoPackageError::DuplicatePath
+        }
+        _ => RepoPackageError::Database(e),
+    }
+}
+
+/// Trim `path` and its slashes, rejecting absolute paths and `..`.
+fn normalize_path(path: &str) -> Result<String, RepoPackageError> {
+    let path = path.trim().trim_matches('/');
+    let is_relative = Path::new(path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    if path.is_empty() || !is_relative {
+        return Err(RepoPackageError::Invalid);
+    }
+    Ok(path.to_string())
+}
+
+fn non_empty_script(script: Option<&str>) -> Option<&str> {
+    script.filter(|script| !script.trim().is_empty())
+}
+
+impl RepoPackage {
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoPackage,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                path as "path!",
+                name as "name!",
+                dev_server_script as "dev_server_script?",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM repo_packages
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoPackage,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                path as "path!",
+                name as "name!",
+                dev_server_script as "dev_server_script?",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM repo_packages
+            WHERE repo_id = ?
+            ORDER BY path ASC
+            "#,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The packages a workspace selected, at most one per repo.
+    pub async fn find_selected_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoPackage,
+            r#"
+            SELECT
+                p.id as "id!: Uuid",
+                p.repo_id as "repo_id!: Uuid",
+                p.path as "path!",
+                p.name as "name!",
+                p.dev_server_script as "dev_server_script?",
+                p.created_at as "created_at!: DateTime<Utc>",
+                p.updated_at as "updated_at!: DateTime<Utc>"
+            FROM repo_packages p
+            JOIN workspace_repos wr ON wr.repo_package_id = p.id
+            WHERE wr.workspace_id = ?
+            "#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Select the package of `repo_id` a workspace works in, or clear it.
+    pub async fn select_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        package_id: Option<Uuid>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE workspace_repos
+               SET repo_package_id = ?, updated_at = datetime('now', 'subsec')
+               WHERE workspace_id = ? AND repo_id = ?"#,
+            package_id,
+            workspace_id,
+            repo_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        data: &CreateRepoPackage,
+    ) -> Result<Self, RepoPackageError> {
+        let path = normalize_path(&data.path)?;
+        if data.name.trim().is_empty() {
+            return Err(RepoPackageError::Invalid);
+        }
+        let id = Uuid::new_v4();
+        let name = data.name.trim();
+        let dev_server_script = non_empty_script(data.dev_server_script.as_deref());
+        sqlx::query_as!(
+            RepoPackage,
+            r#"
+            INSERT INTO repo_packages (id, repo_id, path, name, dev_server_script)
+            VALUES (?, ?, ?, ?, ?)
+            RETURNING
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                path as "path!",
+                name as "name!",
+                dev_server_script as "dev_server_script?",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            id,
+            repo_id,
+            path,
+            name,
+            dev_server_script
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(map_unique_violation)
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateRepoPackage,
+    ) -> Result<Self, RepoPackageError> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(RepoPackageError::NotFound)?;
+        let name = data.name.as_deref().unwrap_or(&existing.name).trim();
+        if name.is_empty() {
+            return Err(RepoPackageError::Invalid);
+        }
+        let dev_server_script = match &data.dev_server_script {
+            Some(script) => non_empty_script(Some(script)),
+            None => existing.dev_server_script.as_deref(),
+        };
+
+        Ok(sqlx::query_as!(
+            RepoPackage,
+            r#"
+            UPDATE repo_packages
+            SET name = ?, dev_server_script = ?, updated_at = datetime('now', 'subsec')
+            WHERE id = ?
+            RETURNING
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                path as "path!",
+                name as "name!",
+                dev_server_script as "dev_server_script?",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            name,
+            dev_server_script,
+            id
+        )
+        .fetch_one(pool)
+        .await?)
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM repo_packages WHERE id = ?", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,269 @@
+use std::path::{Component, Path};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum RepoSharedCacheError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Shared cache not found")]
+    NotFound,
+    #[error("This cache is already shared for the repository")]
+    Duplicate,
+    #[error("Invalid shared cache: {0}")]
+    Invalid(String),
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum SharedCacheKind {
+    /// Cargo's `target` dir, symlinked into each worktree.
+    CargoTarget,
+    /// A cargo home (registry index, crate sources and git checkouts) set as
+    /// `CARGO_HOME`. The user's cargo config and credentials are linked in.
+    CargoRegistry,
+    /// The pnpm content-addressable store, set as the `store-dir`.
+    PnpmStore,
+    /// pip's download and wheel cache, set as `PIP_CACHE_DIR`.
+    PipCache,
+    /// Any worktree-relative directory, symlinked into each worktree.
+    Custom,
+}
+
+impl SharedCacheKind {
+    /// The worktree-relative path the cache is linked at, for in-tree caches.
+    pub fn default_link_path(self) -> Option<&'static str> {
+        match self {
+            Self::CargoTarget => Some("target"),
+            Self::CargoRegistry | Self::PnpmStore | Self::PipCache | Self::Custom => None,
+        }
+    }
+
+    /// The environment variable pointing the tool at the cache, for tool
+    /// caches.
+    pub fn env_var(self) -> Option<&'static str> {
+        match self {
+            Self::CargoRegistry => Some("CARGO_HOME"),
+            Self::PnpmStore => Some("npm_config_store_dir"),
+            Self::PipCache => Some("PIP_CACHE_DIR"),
+            Self::CargoTarget | Self::Custom => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RepoSharedCache {
+    pub id: Uuid,
+    pub repo_id: Uuid,
+    pub kind: SharedCacheKind,
+    /// Worktree-relative path of custom caches.
+    pub path: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl RepoSharedCache {
+    /// The worktree-relative path the cache is symlinked at, if it is linked
+    /// into worktrees.
+    pub fn link_path(&self) -> Option<&str> {
+        match self.kind {
+            SharedCacheKind::Custom => self.path.as_deref(),
+            kind => kind.default_link_path(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateRepoSharedCache {
+    pub kind: SharedCacheKind,
+    /// Required for custom caches, e.g. `node_modules/.cache`.
+    #[ts(optional)]
+    pub path: Option<String>,
+}
+
+fn validate(data: &CreateRepoSharedCache) -> Result<Option<String>, RepoSharedCacheError> {
+    let path = data
+        .path
+        .as_deref()
+        .map(|path| path.trim().trim_end_matches('/'))
+        .filter(|path| !path.is_empty());
+    match (data.kind, path) {
+        (SharedCacheKind::Custom, None) => Err(RepoSharedCacheError::Invalid(
+            "custom caches need a path".to_string(),
+        )),
+        (SharedCacheKind::Custom, Some(path)) => {
+            let is_relative = Path::new(path)
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+            if !is_relative || path.split('/').any(|part| part == ".git") {
+                return Err(RepoSharedCacheError::Invalid(format!(
+                    "{path} must be a path inside the worktree"
+                )));
+            }
+            Ok(Some(path.to_string()))
+        }
+        (_, Some(_)) => Err(RepoSharedCacheError::Invalid(
+            "only custom caches take a path".to_string(),
+        )),
+        (_, None) => Ok(None),
+    }
+}
+
+impl RepoSharedCache {
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoSharedCache,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                kind as "kind!: SharedCacheKind",
+                path as "path?",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM repo_shared_caches
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoSharedCache,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                kind as "kind!: SharedCacheKind",
+                path as "path?",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM repo_shared_caches
+            WHERE repo_id = ?
+            ORDER BY created_at ASC
+            "#,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Shared caches of every repo in a workspace, in the order the repos
+    /// were added to it.
+    pub async fn find_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoSharedCache,
+            r#"
+            SELECT
+                c.id as "id!: Uuid",
+                c.repo_id as "repo_id!: Uuid",
+                c.kind as "kind!: SharedCacheKind",
+                c.path as "path?",
+                c.created_at as "created_at!: DateTime<Utc>"
+            FROM repo_shared_caches c
+            JOIN workspace_repos wr ON wr.repo_id = c.repo_id
+            WHERE wr.workspace_id = ?
+            ORDER BY wr.created_at ASC, c.created_at ASC
+            "#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        data: &CreateRepoSharedCache,
+    ) -> Result<Self, RepoSharedCacheError> {
+        let path = validate(data)?;
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            RepoSharedCache,
+            r#"
+            INSERT INTO repo_shared_caches (id, repo_id, kind, path)
+            VALUES (?, ?, ?, ?)
+            RETURNING
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                kind as "kind!: SharedCacheKind",
+                path as "path?",
+                created_at as "created_at!: DateTime<Utc>"
+            "#,
+            id,
+            repo_id,
+            data.kind,
+            path
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                RepoSharedCacheError::Duplicate
+            }
+            _ => RepoSharedCacheError::Database(e),
+        })
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM repo_shared_caches WHERE id = ?", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Ids of the repos that have shared caches, for pruning caches of
+    /// removed repos.
+    pub async fn find_repo_ids(pool: &SqlitePool) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT DISTINCT repo_id as "repo_id!: Uuid" FROM repo_shared_caches"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom(path: Option<&str>) -> CreateRepoSharedCache {
+        CreateRepoSharedCache {
+            kind: SharedCacheKind::Custom,
+            path: path.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn custom_caches_need_a_path_inside_the_worktree() {
+        assert_eq!(
+            validate(&custom(Some("node_modules/.cache/"))).unwrap(),
+            Some("node_modules/.cache".to_string())
+        );
+        assert!(validate(&custom(None)).is_err());
+        assert!(validate(&custom(Some("/var/cache"))).is_err());
+        assert!(validate(&custom(Some("../shared"))).is_err());
+        assert!(validate(&custom(Some(".git/objects"))).is_err());
+        assert!(
+            validate(&CreateRepoSharedCache {
+                kind: SharedCacheKind::PipCache,
+                path: Some("cache".to_string()),
+            })
+            .is_err()
+        );
+    }
+}
@@ -11,10 +11,14 @@ pub struct ContainerQuery {
     pub container_ref: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct WorkspaceRepoInput {
     pub repo_id: Uuid,
     pub target_branch: String,
+    /// The monorepo package of the repo to work in.
+    #[serde(default)]
+    #[ts(optional)]
+    pub package_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -38,6 +42,11 @@ pub struct CreateAndStartWorkspaceRequest {
     pub prompt: String,
     pub attachment_ids: Option<Vec<Uuid>>,
     pub idempotency_key: Option<String>,
+    /// Environment profiles (from the selected repos) to inject into every
+    /// process started in the workspace.
+    #[serde(default)]
+    #[ts(optional)]
+    pub env_profile_ids: Option<Vec<Uuid>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
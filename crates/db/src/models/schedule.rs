@@ -0,0 +1,497 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorConfig;
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type, types::Json};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum ScheduleError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Schedule not found")]
+    NotFound,
+}
+
+/// A recurring agent run: on every cron tick a workspace is created for the
+/// repo and the prompt is started with the executor config.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct Schedule {
+    pub id: Uuid,
+    pub name: String,
+    /// Standard five-field cron expression, evaluated in the server's local
+    /// time zone.
+    pub cron_expression: String,
+    pub repo_id: Uuid,
+    pub target_branch: String,
+    pub prompt: String,
+    pub executor_config: ExecutorConfig,
+    /// Push the run's branch and open a pull request when the repo's verify
+    /// script passes.
+    pub open_pr: bool,
+    pub paused: bool,
+    #[ts(type = "Date | null")]
+    pub next_run_at: Option<DateTime<Utc>>,
+    #[ts(type = "Date | null")]
+    pub last_run_at: Option<DateTime<Utc>>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+struct ScheduleRow {
+    id: Uuid,
+    name: String,
+    cron_expression: String,
+    repo_id: Uuid,
+    target_branch: String,
+    prompt: String,
+    executor_config: Json<ExecutorConfig>,
+    open_pr: bool,
+    paused: bool,
+    next_run_at: Option<DateTime<Utc>>,
+    last_run_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<ScheduleRow> for Schedule {
+    fn from(row: ScheduleRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            cron_expression: row.cron_expression,
+            repo_id: row.repo_id,
+            target_branch: row.target_branch,
+            prompt: row.prompt,
+            executor_config: row.executor_config.0,
+            open_pr: row.open_pr,
+            paused: row.paused,
+            next_run_at: row.next_run_at,
+            last_run_at: row.last_run_at,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateSchedule {
+    pub name: String,
+    pub cron_expression: String,
+    pub repo_id: Uuid,
+    pub target_branch: String,
+    pub prompt: String,
+    pub executor_config: ExecutorConfig,
+    #[serde(default)]
+    pub open_pr: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateSchedule {
+    pub name: Option<String>,
+    pub cron_expression: Option<String>,
+    pub target_branch: Option<String>,
+    pub prompt: Option<String>,
+    pub executor_config: Option<ExecutorConfig>,
+    pub open_pr: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum ScheduleRunStatus {
+    Started,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ScheduleRun {
+    pub id: Uuid,
+    pub schedule_id: Uuid,
+    pub workspace_id: Option<Uuid>,
+    pub status: ScheduleRunStatus,
+    /// Why the run failed to start, or why it didn't open a pull request.
+    pub error: Option<String>,
+    pub pr_url: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl Schedule {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ScheduleRow,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                name as "name!",
+                cron_expression as "cron_expression!",
+                repo_id as "repo_id!: Uuid",
+                target_branch as "target_branch!",
+                prompt as "prompt!",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                open_pr as "open_pr!: bool",
+                paused as "paused!: bool",
+                next_run_at as "next_run_at?: DateTime<Utc>",
+                last_run_at as "last_run_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM schedules
+            ORDER BY name ASC
+            "#
+        )
+        .fetch_all(pool)
+        .await
+        .map(|rows| rows.into_iter().map(Schedule::from).collect())
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ScheduleRow,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                name as "name!",
+                cron_expression as "cron_expression!",
+                repo_id as "repo_id!: Uuid",
+                target_branch as "target_branch!",
+                prompt as "prompt!",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                open_pr as "open_pr!: bool",
+                paused as "paused!: bool",
+                next_run_at as "next_run_at?: DateTime<Utc>",
+                last_run_at as "last_run_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM schedules
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|row| row.map(Schedule::from))
+    }
+
+    /// Active schedules whose next run is at or before `now`.
+    pub async fn find_due(pool: &SqlitePool, now: DateTime<Utc>) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ScheduleRow,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                name as "name!",
+                cron_expression as "cron_expression!",
+                repo_id as "repo_id!: Uuid",
+                target_branch as "target_branch!",
+                prompt as "prompt!",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                open_pr as "open_pr!: bool",
+                paused as "paused!: bool",
+                next_run_at as "next_run_at?: DateTime<Utc>",
+                last_run_at as "last_run_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM schedules
+            WHERE paused = 0 AND next_run_at IS NOT NULL AND next_run_at <= ?
+            ORDER BY next_run_at ASC
+            "#,
+            now
+        )
+        .fetch_all(pool)
+        .await
+        .map(|rows| rows.into_iter().map(Schedule::from).collect())
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateSchedule,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, ScheduleError> {
+        let id = Uuid::new_v4();
+        let name = data.name.trim();
+        let cron_expression = data.cron_expression.trim();
+        let executor_config = serde_json::to_string(&data.executor_config)?;
+        Ok(sqlx::query_as!(
+            ScheduleRow,
+            r#"
+            INSERT INTO schedules
+                (id, name, cron_expression, repo_id, target_branch, prompt,
+                 executor_config, open_pr, next_run_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING
+                id as "id!: Uuid",
+                name as "name!",
+                cron_expression as "cron_expression!",
+                repo_id as "repo_id!: Uuid",
+                target_branch as "target_branch!",
+                prompt as "prompt!",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                open_pr as "open_pr!: bool",
+                paused as "paused!: bool",
+                next_run_at as "next_run_at?: DateTime<Utc>",
+                last_run_at as "last_run_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            id,
+            name,
+            cron_expression,
+            data.repo_id,
+            data.target_branch,
+            data.prompt,
+            executor_config,
+            data.open_pr,
+            next_run_at
+        )
+        .fetch_one(pool)
+        .await
+        .map(Schedule::from)?)
+    }
+
+    /// Apply `data` to a schedule. `next_run_at` is recomputed by the caller
+    /// since it depends on the (possibly new) cron expression.
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateSchedule,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, ScheduleError> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(ScheduleError::NotFound)?;
+        let name = data.name.as_deref().unwrap_or(&existing.name).trim();
+        let cron_expression = data
+            .cron_expression
+            .as_deref()
+            .unwrap_or(&existing.cron_expression)
+            .trim();
+        let target_branch = data
+            .target_branch
+            .as_ref()
+            .unwrap_or(&existing.target_branch);
+        let prompt = data.prompt.as_ref().unwrap_or(&existing.prompt);
+        let executor_config = serde_json::to_string(
+            data.executor_config
+                .as_ref()
+                .unwrap_or(&existing.executor_config),
+        )?;
+        let open_pr = data.open_pr.unwrap_or(existing.open_pr);
+
+        Ok(sqlx::query_as!(
+            ScheduleRow,
+            r#"
+            UPDATE schedules
+            SET name = ?, cron_expression = ?, target_branch = ?, prompt = ?,
+                executor_config = ?, open_pr = ?, next_run_at = ?,
+                updated_at = datetime('now', 'subsec')
+            WHERE id = ?
+            RETURNING
+                id as "id!: Uuid",
+                name as "name!",
+                cron_expression as "cron_expression!",
+                repo_id as "repo_id!: Uuid",
+                target_branch as "target_branch!",
+                prompt as "prompt!",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                open_pr as "open_pr!: bool",
+                paused as "paused!: bool",
+                next_run_at as "next_run_at?: DateTime<Utc>",
+                last_run_at as "last_run_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            name,
+            cron_expression,
+            target_branch,
+            prompt,
+            executor_config,
+            open_pr,
+            next_run_at,
+            id
+        )
+        .fetch_one(pool)
+        .await
+        .map(Schedule::from)?)
+    }
+
+    pub async fn set_paused(
+        pool: &SqlitePool,
+        id: Uuid,
+        paused: bool,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, ScheduleError> {
+        sqlx::query_as!(
+            ScheduleRow,
+            r#"
+            UPDATE schedules
+            SET paused = ?, next_run_at = ?, updated_at = datetime('now', 'subsec')
+            WHERE id = ?
+            RETURNING
+                id as "id!: Uuid",
+                name as "name!",
+                cron_expression as "cron_expression!",
+                repo_id as "repo_id!: Uuid",
+                target_branch as "target_branch!",
+                prompt as "prompt!",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                open_pr as "open_pr!: bool",
+                paused as "paused!: bool",
+                next_run_at as "next_run_at?: DateTime<Utc>",
+                last_run_at as "last_run_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            paused,
+            next_run_at,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|row| row.map(Schedule::from))?
+        .ok_or(ScheduleError::NotFound)
+    }
+
+    /// Claim a due run by advancing `next_run_at`. Returns `false` if the
+    /// schedule was changed or claimed since it was loaded.
+    pub async fn advance(
+        pool: &SqlitePool,
+        id: Uuid,
+        expected_next_run_at: DateTime<Utc>,
+        next_run_at: Option<DateTime<Utc>>,
+        ran_at: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE schedules
+               SET next_run_at = ?, last_run_at = ?
+               WHERE id = ? AND paused = 0 AND next_run_at = ?"#,
+            next_run_at,
+            ran_at,
+            id,
+            expected_next_run_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM schedules WHERE id = ?", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl ScheduleRun {
+    pub async fn find_by_schedule_id(
+        pool: &SqlitePool,
+        schedule_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ScheduleRun,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                schedule_id as "schedule_id!: Uuid",
+                workspace_id as "workspace_id?: Uuid",
+                status as "status!: ScheduleRunStatus",
+                error as "error?",
+                pr_url as "pr_url?",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM schedule_runs
+            WHERE schedule_id = ?
+            ORDER BY created_at DESC
+            LIMIT ?
+            "#,
+            schedule_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        schedule_id: Uuid,
+        workspace_id: Option<Uuid>,
+        status: ScheduleRunStatus,
+        error: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            ScheduleRun,
+            r#"
+            INSERT INTO schedule_runs (id, schedule_id, workspace_id, status, error)
+            VALUES (?, ?, ?, ?, ?)
+            RETURNING
+                id as "id!: Uuid",
+                schedule_id as "schedule_id!: Uuid",
+                workspace_id as "workspace_id?: Uuid",
+                status as "status!: ScheduleRunStatus",
+                error as "error?",
+                pr_url as "pr_url?",
+                created_at as "created_at!: DateTime<Utc>"
+            "#,
+            id,
+            schedule_id,
+            workspace_id,
+            status,
+            error
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The run that started `workspace_id`, if a schedule started it.
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ScheduleRun,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                schedule_id as "schedule_id!: Uuid",
+                workspace_id as "workspace_id?: Uuid",
+                status as "status!: ScheduleRunStatus",
+                error as "error?",
+                pr_url as "pr_url?",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM schedule_runs
+            WHERE workspace_id = ?
+            "#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Record what came of a started run: the pull request it opened, or
+    /// why it didn't open one.
+    pub async fn set_outcome(
+        pool: &SqlitePool,
+        id: Uuid,
+        pr_url: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE schedule_runs SET pr_url = ?, error = ? WHERE id = ?",
+            pr_url,
+            error,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -246,6 +246,21 @@ impl ScratchPayload {
         ScratchType::from(self)
     }
 
+    /// The free-form text of document-like scratches, used when converting
+    /// them into templates or context files. Settings scratches have none.
+    pub fn text_content(&self) -> Option<&str> {
+        match self {
+            Self::DraftTask(text) => Some(text),
+            Self::DraftFollowUp(data) => Some(&data.message),
+            Self::DraftWorkspace(data) => Some(&data.message),
+            Self::DraftIssue(data) => data.description.as_deref(),
+            Self::WorkspaceNotes(data) => Some(&data.content),
+            Self::PreviewSettings(_) | Self::UiPreferences(_) | Self::ProjectRepoDefaults(_) => {
+                None
+            }
+        }
+    }
+
     /// Validates that the payload type matches the expected type
     pub fn validate_type(&self, expected: ScratchType) -> Result<(), ScratchError> {
         let actual = self.scratch_type();
@@ -0,0 +1,312 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::repo::Repo;
+
+#[derive(Debug, Error)]
+pub enum ScriptLibraryError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Library script not found")]
+    NotFound,
+    #[error("A library script with this name already exists")]
+    DuplicateName,
+    #[error("Library script is referenced by {0} repositories")]
+    InUse(i64),
+}
+
+/// Which of a repo's lifecycle scripts a library script stands in for.
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum RepoScriptKind {
+    Setup,
+    Cleanup,
+    Archive,
+    DevServer,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct LibraryScript {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub script: String,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateLibraryScript {
+    pub name: String,
+    pub description: Option<String>,
+    pub script: String,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateLibraryScript {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub script: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RepoScriptRef {
+    pub repo_id: Uuid,
+    pub kind: RepoScriptKind,
+    pub library_script_id: Uuid,
+}
+
+struct ResolvedScriptRef {
+    kind: RepoScriptKind,
+    script: String,
+}
+
+fn map_unique_violation(e: sqlx::Error) -> ScriptLibraryError {
+    match &e {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            ScriptLibraryError::DuplicateName
+        }
+        _ => ScriptLibraryError::Database(e),
+    }
+}
+
+impl LibraryScript {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            LibraryScript,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                name as "name!",
+                description as "description?",
+                script as "script!",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM library_scripts
+            ORDER BY name ASC
+            "#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            LibraryScript,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                name as "name!",
+                description as "description?",
+                script as "script!",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM library_scripts
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateLibraryScript,
+    ) -> Result<Self, ScriptLibraryError> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            LibraryScript,
+            r#"
+            INSERT INTO library_scripts (id, name, description, script)
+            VALUES (?, ?, ?, ?)
+            RETURNING
+                id as "id!: Uuid",
+                name as "name!",
+                description as "description?",
+                script as "script!",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            id,
+            data.name,
+            data.description,
+            data.script
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(map_unique_violation)
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateLibraryScript,
+    ) -> Result<Self, ScriptLibraryError> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(ScriptLibraryError::NotFound)?;
+
+        let name = data.name.as_ref().unwrap_or(&existing.name);
+        let description = data.description.as_ref().or(existing.description.as_ref());
+        let script = data.script.as_ref().unwrap_or(&existing.script);
+        sqlx::query_as!(
+            LibraryScript,
+            r#"
+            UPDATE library_scripts
+            SET name = ?, description = ?, script = ?,
+                updated_at = datetime('now', 'subsec')
+            WHERE id = ?
+            RETURNING
+                id as "id!: Uuid",
+                name as "name!",
+                description as "description?",
+                script as "script!",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            name,
+            description,
+            script,
+            id
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(map_unique_violation)
+    }
+
+    /// Delete a library script. Scripts still referenced by a repo are kept
+    /// unless `force` is set, in which case the references are dropped too.
+    pub async fn delete(
+        pool: &SqlitePool,
+        id: Uuid,
+        force: bool,
+    ) -> Result<(), ScriptLibraryError> {
+        let usage = RepoScriptRef::count_for_script(pool, id).await?;
+        if usage > 0 && !force {
+            return Err(ScriptLibraryError::InUse(usage));
+        }
+        let result = sqlx::query!("DELETE FROM library_scripts WHERE id = ?", id)
+            .execute(pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(ScriptLibraryError::NotFound);
+        }
+        Ok(())
+    }
+}
+
+impl RepoScriptRef {
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoScriptRef,
+            r#"
+            SELECT
+                repo_id as "repo_id!: Uuid",
+                kind as "kind!: RepoScriptKind",
+                library_script_id as "library_script_id!: Uuid"
+            FROM repo_script_refs
+            WHERE repo_id = ?
+            "#,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn count_for_script(
+        pool: &SqlitePool,
+        library_script_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM repo_script_refs WHERE library_script_id = ?",
+            library_script_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Point a repo's script of `kind` at a library script, or clear the
+    /// reference when `library_script_id` is `None`.
+    pub async fn set(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        kind: RepoScriptKind,
+        library_script_id: Option<Uuid>,
+    ) -> Result<(), sqlx::Error> {
+        match library_script_id {
+            Some(library_script_id) => {
+                sqlx::query!(
+                    r#"INSERT INTO repo_script_refs (repo_id, kind, library_script_id)
+                       VALUES (?, ?, ?)
+                       ON CONFLICT(repo_id, kind) DO UPDATE SET
+                           library_script_id = excluded.library_script_id"#,
+                    repo_id,
+                    kind,
+                    library_script_id
+                )
+                .execute(pool)
+                .await?;
+            }
+            None => {
+                sqlx::query!(
+                    "DELETE FROM repo_script_refs WHERE repo_id = ? AND kind = ?",
+                    repo_id,
+                    kind
+                )
+                .execute(pool)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Fill in each repo's lifecycle scripts from the library where the repo
+    /// references a library script and has no script of its own, which acts
+    /// as a local override.
+    pub async fn resolve_scripts(pool: &SqlitePool, repos: &mut [Repo]) -> Result<(), sqlx::Error> {
+        for repo in repos.iter_mut() {
+            let refs = sqlx::query_as!(
+                ResolvedScriptRef,
+                r#"
+                SELECT
+                    rr.kind as "kind!: RepoScriptKind",
+                    ls.script as "script!"
+                FROM repo_script_refs rr
+                JOIN library_scripts ls ON ls.id = rr.library_script_id
+                WHERE rr.repo_id = ?
+                "#,
+                repo.id
+            )
+            .fetch_all(pool)
+            .await?;
+
+            for resolved in refs {
+                let slot = match resolved.kind {
+                    RepoScriptKind::Setup => &mut repo.setup_script,
+                    RepoScriptKind::Cleanup => &mut repo.cleanup_script,
+                    RepoScriptKind::Archive => &mut repo.archive_script,
+                    RepoScriptKind::DevServer => &mut repo.dev_server_script,
+                };
+                if slot
+                    .as_deref()
+                    .is_none_or(|script| script.trim().is_empty())
+                {
+                    *slot = Some(resolved.script);
+                }
+            }
+        }
+        Ok(())
+    }
+}
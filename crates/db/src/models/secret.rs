@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// An encrypted secret as stored. Decryption lives in the services layer,
+/// which owns the machine key.
+#[derive(Debug, Clone)]
+pub struct Secret {
+    pub id: Uuid,
+    pub name: String,
+    pub nonce: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Secret metadata safe to return to clients; never includes the value.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SecretSummary {
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Secret {
+    pub async fn list(pool: &SqlitePool) -> Result<Vec<SecretSummary>, sqlx::Error> {
+        sqlx::query_as!(
+            SecretSummary,
+            r#"
+            SELECT
+                name as "name!",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM secrets
+            ORDER BY name ASC
+            "#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Secret,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                name as "name!",
+                nonce as "nonce!: Vec<u8>",
+                ciphertext as "ciphertext!: Vec<u8>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM secrets
+            "#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_name(pool: &SqlitePool, name: &str) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Secret,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                name as "name!",
+                nonce as "nonce!: Vec<u8>",
+                ciphertext as "ciphertext!: Vec<u8>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM secrets
+            WHERE name = ?
+            "#,
+            name
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Insert a secret or replace the value of an existing one with the same name.
+    pub async fn upsert(
+        pool: &SqlitePool,
+        name: &str,
+        nonce: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<SecretSummary, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            SecretSummary,
+            r#"
+            INSERT INTO secrets (id, name, nonce, ciphertext)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(name) DO UPDATE SET
+                nonce = excluded.nonce,
+                ciphertext = excluded.ciphertext,
+                updated_at = datetime('now', 'subsec')
+            RETURNING
+                name as "name!",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            id,
+            name,
+            nonce,
+            ciphertext
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete_by_name(pool: &SqlitePool, name: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM secrets WHERE name = ?", name)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -5,7 +5,7 @@ use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::workspace_repo::WorkspaceRepo;
+use super::{repo_package::RepoPackage, workspace_repo::WorkspaceRepo};
 
 #[derive(Debug, Error)]
 pub enum SessionError {
@@ -207,7 +207,17 @@ impl Session {
         }
 
         let repo = &repos[0];
-        let path = match repo.default_working_dir.as_deref() {
+        // A package selected for the workspace takes precedence over the
+        // repo's default working dir.
+        let package_path = RepoPackage::find_selected_for_workspace(pool, workspace_id)
+            .await?
+            .into_iter()
+            .find(|package| package.repo_id == repo.id)
+            .map(|package| package.path);
+        let path = match package_path
+            .as_deref()
+            .or(repo.default_working_dir.as_deref())
+        {
             Some(subdir) if !subdir.is_empty() => std::path::PathBuf::from(&repo.name).join(subdir),
             _ => std::path::PathBuf::from(&repo.name),
         };
@@ -0,0 +1,121 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// The head commit a checkpoint restores a workspace repo to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct CheckpointRepoState {
+    pub repo_id: Uuid,
+    pub head_commit: String,
+}
+
+/// A named restore point of a session, right after `execution_process_id`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SessionCheckpoint {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    pub execution_process_id: Uuid,
+    pub label: String,
+    #[ts(type = "Array<CheckpointRepoState>")]
+    pub repo_states: Json<Vec<CheckpointRepoState>>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl SessionCheckpoint {
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            SessionCheckpoint,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                session_id as "session_id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                label as "label!",
+                repo_states as "repo_states!: Json<Vec<CheckpointRepoState>>",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM session_checkpoints
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Checkpoints of a session, newest first.
+    pub async fn find_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            SessionCheckpoint,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                session_id as "session_id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                label as "label!",
+                repo_states as "repo_states!: Json<Vec<CheckpointRepoState>>",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM session_checkpoints
+            WHERE session_id = ?
+            ORDER BY created_at DESC
+            "#,
+            session_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        execution_process_id: Uuid,
+        label: &str,
+        repo_states: &[CheckpointRepoState],
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let repo_states = Json(repo_states);
+        sqlx::query_as!(
+            SessionCheckpoint,
+            r#"
+            INSERT INTO session_checkpoints
+                (id, session_id, execution_process_id, label, repo_states)
+            VALUES (?, ?, ?, ?, ?)
+            RETURNING
+                id as "id!: Uuid",
+                session_id as "session_id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                label as "label!",
+                repo_states as "repo_states!: Json<Vec<CheckpointRepoState>>",
+                created_at as "created_at!: DateTime<Utc>"
+            "#,
+            id,
+            session_id,
+            execution_process_id,
+            label,
+            repo_states
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Delete a checkpoint of `session_id`. Returns whether it existed.
+    pub async fn delete(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM session_checkpoints WHERE id = ? AND session_id = ?",
+            id,
+            session_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,115 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A read-only public link to a session's transcript and diff.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SessionShare {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    #[ts(type = "Date")]
+    pub expires_at: DateTime<Utc>,
+    #[ts(type = "Date | null")]
+    pub revoked_at: Option<DateTime<Utc>>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl SessionShare {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at > now
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            SessionShare,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                session_id as "session_id!: Uuid",
+                expires_at as "expires_at!: DateTime<Utc>",
+                revoked_at as "revoked_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM session_shares
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Shares of a session that are neither revoked nor expired, newest first.
+    pub async fn find_active_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query_as!(
+            SessionShare,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                session_id as "session_id!: Uuid",
+                expires_at as "expires_at!: DateTime<Utc>",
+                revoked_at as "revoked_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM session_shares
+            WHERE session_id = ? AND revoked_at IS NULL AND expires_at > ?
+            ORDER BY created_at DESC
+            "#,
+            session_id,
+            now
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            SessionShare,
+            r#"
+            INSERT INTO session_shares (id, session_id, expires_at)
+            VALUES (?, ?, ?)
+            RETURNING
+                id as "id!: Uuid",
+                session_id as "session_id!: Uuid",
+                expires_at as "expires_at!: DateTime<Utc>",
+                revoked_at as "revoked_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>"
+            "#,
+            id,
+            session_id,
+            expires_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Revoke a share of `session_id`. Returns whether an active share was
+    /// revoked.
+    pub async fn revoke(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE session_shares SET revoked_at = datetime('now', 'subsec')
+            WHERE id = ? AND session_id = ? AND revoked_at IS NULL
+            "#,
+            id,
+            session_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,224 @@
+use chrono::{DateTime, Utc};
+use sqlx::{SqlitePool, Type};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, PartialEq, Eq)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum WarmWorktreeStatus {
+    /// Being checked out, or running the setup script.
+    Provisioning,
+    /// Waiting for a workspace to claim it.
+    Ready,
+}
+
+/// A pre-provisioned worktree in the warm pool.
+#[derive(Debug, Clone)]
+pub struct WarmWorktree {
+    pub id: Uuid,
+    pub repo_id: Uuid,
+    pub target_branch: String,
+    /// Placeholder branch, renamed to the workspace branch on claim.
+    pub branch: String,
+    pub path: String,
+    /// Commit of `target_branch` the worktree was created from.
+    pub base_commit: String,
+    /// The setup script that ran in the worktree.
+    pub setup_script: Option<String>,
+    pub status: WarmWorktreeStatus,
+    pub size_bytes: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateWarmWorktree {
+    pub repo_id: Uuid,
+    pub target_branch: String,
+    pub branch: String,
+    pub path: String,
+    pub base_commit: String,
+    pub setup_script: Option<String>,
+}
+
+impl WarmWorktree {
+    pub async fn create(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &CreateWarmWorktree,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            WarmWorktree,
+            r#"
+            INSERT INTO warm_worktrees
+                (id, repo_id, target_branch, branch, path, base_commit, setup_script)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            RETURNING
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                target_branch as "target_branch!",
+                branch as "branch!",
+                path as "path!",
+                base_commit as "base_commit!",
+                setup_script as "setup_script?",
+                status as "status!: WarmWorktreeStatus",
+                size_bytes as "size_bytes!",
+                created_at as "created_at!: DateTime<Utc>"
+            "#,
+            id,
+            data.repo_id,
+            data.target_branch,
+            data.branch,
+            data.path,
+            data.base_commit,
+            data.setup_script
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WarmWorktree,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                target_branch as "target_branch!",
+                branch as "branch!",
+                path as "path!",
+                base_commit as "base_commit!",
+                setup_script as "setup_script?",
+                status as "status!: WarmWorktreeStatus",
+                size_bytes as "size_bytes!",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM warm_worktrees
+            ORDER BY created_at ASC
+            "#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn mark_ready(
+        pool: &SqlitePool,
+        id: Uuid,
+        size_bytes: u64,
+    ) -> Result<(), sqlx::Error> {
+        let size_bytes = i64::try_from(size_bytes).unwrap_or(i64::MAX);
+        sqlx::query!(
+            "UPDATE warm_worktrees SET status = 'ready', size_bytes = ? WHERE id = ?",
+            size_bytes,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Take the oldest ready worktree of `repo_id` on `target_branch` whose
+    /// setup ran `setup_script`, removing it from the pool.
+    pub async fn claim(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        target_branch: &str,
+        setup_script: Option<&str>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WarmWorktree,
+            r#"
+            DELETE FROM warm_worktrees
+            WHERE id = (
+                SELECT id FROM warm_worktrees
+                WHERE repo_id = ?
+                  AND target_branch = ?
+                  AND setup_script IS ?
+                  AND status = 'ready'
+                ORDER BY created_at ASC
+                LIMIT 1
+            )
+            RETURNING
+                id as "id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                target_branch as "target_branch!",
+                branch as "branch!",
+                path as "path!",
+                base_commit as "base_commit!",
+                setup_script as "setup_script?",
+                status as "status!: WarmWorktreeStatus",
+                size_bytes as "size_bytes!",
+                created_at as "created_at!: DateTime<Utc>"
+            "#,
+            repo_id,
+            target_branch,
+            setup_script
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Returns whether the record still existed, i.e. wasn't claimed.
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM warm_worktrees WHERE id = ?", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_pool_without_foreign_keys;
+
+    fn entry(repo_id: Uuid, setup_script: Option<&str>) -> CreateWarmWorktree {
+        CreateWarmWorktree {
+            repo_id,
+            target_branch: "main".to_string(),
+            branch: "vk-warm/1".to_string(),
+            path: "/tmp/warm/1".to_string(),
+            base_commit: "abc".to_string(),
+            setup_script: setup_script.map(str::to_string),
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn claims_ready_worktrees_matching_the_setup_script() {
+        let pool = test_pool_without_foreign_keys().await;
+        let repo_id = Uuid::new_v4();
+
+        let provisioning = WarmWorktree::create(&pool, Uuid::new_v4(), &entry(repo_id, None))
+            .await
+            .unwrap();
+        assert_eq!(provisioning.status, WarmWorktreeStatus::Provisioning);
+        assert!(
+            WarmWorktree::claim(&pool, repo_id, "main", None)
+                .await
+                .unwrap()
+                .is_none(),
+            "provisioning worktrees can't be claimed"
+        );
+
+        WarmWorktree::mark_ready(&pool, provisioning.id, 42)
+            .await
+            .unwrap();
+        assert!(
+            WarmWorktree::claim(&pool, repo_id, "main", Some("pnpm i"))
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            WarmWorktree::claim(&pool, repo_id, "develop", None)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let claimed = WarmWorktree::claim(&pool, repo_id, "main", None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(claimed.id, provisioning.id);
+        assert_eq!(claimed.size_bytes, 42);
+        assert!(WarmWorktree::find_all(&pool).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,402 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorConfig;
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type, types::Json};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum WebhookTriggerError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Webhook trigger not found")]
+    NotFound,
+}
+
+/// An agent run a webhook may start.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct WebhookTemplate {
+    /// Callers pick the template with `?template=`.
+    pub name: String,
+    /// Prompt with `{{path}}` placeholders filled from the JSON payload, e.g.
+    /// `{{workflow_run.head_branch}}` or `{{commits.0.message}}`.
+    pub prompt: String,
+    /// Branch the workspace starts from. Supports the same placeholders.
+    pub target_branch: String,
+    /// Executor for runs of this template; defaults to the trigger's.
+    #[serde(default)]
+    #[ts(optional)]
+    pub executor_config: Option<ExecutorConfig>,
+}
+
+/// An inbound webhook that starts agent runs, limited to its own templates
+/// and repos. The token it is called with is only shown when issued.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct WebhookTrigger {
+    pub id: Uuid,
+    pub name: String,
+    /// Repos runs may be started in.
+    pub repo_ids: Vec<Uuid>,
+    pub templates: Vec<WebhookTemplate>,
+    pub executor_config: ExecutorConfig,
+    pub paused: bool,
+    #[ts(type = "Date | null")]
+    pub last_triggered_at: Option<DateTime<Utc>>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+struct WebhookTriggerRow {
+    id: Uuid,
+    name: String,
+    repo_ids: Json<Vec<Uuid>>,
+    templates: Json<Vec<WebhookTemplate>>,
+    executor_config: Json<ExecutorConfig>,
+    paused: bool,
+    last_triggered_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<WebhookTriggerRow> for WebhookTrigger {
+    fn from(row: WebhookTriggerRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            repo_ids: row.repo_ids.0,
+            templates: row.templates.0,
+            executor_config: row.executor_config.0,
+            paused: row.paused,
+            last_triggered_at: row.last_triggered_at,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateWebhookTrigger {
+    pub name: String,
+    pub repo_ids: Vec<Uuid>,
+    pub templates: Vec<WebhookTemplate>,
+    pub executor_config: ExecutorConfig,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateWebhookTrigger {
+    pub name: Option<String>,
+    pub repo_ids: Option<Vec<Uuid>>,
+    pub templates: Option<Vec<WebhookTemplate>>,
+    pub executor_config: Option<ExecutorConfig>,
+    pub paused: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum WebhookTriggerRunStatus {
+    Started,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct WebhookTriggerRun {
+    pub id: Uuid,
+    pub trigger_id: Uuid,
+    pub template: String,
+    pub workspace_id: Option<Uuid>,
+    pub status: WebhookTriggerRunStatus,
+    pub error: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl WebhookTrigger {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookTriggerRow,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                name as "name!",
+                repo_ids as "repo_ids!: Json<Vec<Uuid>>",
+                templates as "templates!: Json<Vec<WebhookTemplate>>",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                paused as "paused!: bool",
+                last_triggered_at as "last_triggered_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM webhook_triggers
+            ORDER BY name ASC
+            "#
+        )
+        .fetch_all(pool)
+        .await
+        .map(|rows| rows.into_iter().map(WebhookTrigger::from).collect())
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookTriggerRow,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                name as "name!",
+                repo_ids as "repo_ids!: Json<Vec<Uuid>>",
+                templates as "templates!: Json<Vec<WebhookTemplate>>",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                paused as "paused!: bool",
+                last_triggered_at as "last_triggered_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM webhook_triggers
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|row| row.map(WebhookTrigger::from))
+    }
+
+    pub async fn find_by_token_hash(
+        pool: &SqlitePool,
+        token_hash: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookTriggerRow,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                name as "name!",
+                repo_ids as "repo_ids!: Json<Vec<Uuid>>",
+                templates as "templates!: Json<Vec<WebhookTemplate>>",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                paused as "paused!: bool",
+                last_triggered_at as "last_triggered_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM webhook_triggers
+            WHERE token_hash = ?
+            "#,
+            token_hash
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|row| row.map(WebhookTrigger::from))
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateWebhookTrigger,
+        token_hash: &str,
+    ) -> Result<Self, WebhookTriggerError> {
+        let id = Uuid::new_v4();
+        let repo_ids = serde_json::to_string(&data.repo_ids)?;
+        let templates = serde_json::to_string(&data.templates)?;
+        let name = data.name.trim();
+        let executor_config = serde_json::to_string(&data.executor_config)?;
+        Ok(sqlx::query_as!(
+            WebhookTriggerRow,
+            r#"
+            INSERT INTO webhook_triggers
+                (id, name, token_hash, repo_ids, templates, executor_config)
+            VALUES (?, ?, ?, ?, ?, ?)
+            RETURNING
+                id as "id!: Uuid",
+                name as "name!",
+                repo_ids as "repo_ids!: Json<Vec<Uuid>>",
+                templates as "templates!: Json<Vec<WebhookTemplate>>",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                paused as "paused!: bool",
+                last_triggered_at as "last_triggered_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            id,
+            name,
+            token_hash,
+            repo_ids,
+            templates,
+            executor_config
+        )
+        .fetch_one(pool)
+        .await
+        .map(WebhookTrigger::from)?)
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateWebhookTrigger,
+    ) -> Result<Self, WebhookTriggerError> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(WebhookTriggerError::NotFound)?;
+
+        let name = data.name.as_deref().unwrap_or(&existing.name).trim();
+        let repo_ids = serde_json::to_string(data.repo_ids.as_ref().unwrap_or(&existing.repo_ids))?;
+        let templates =
+            serde_json::to_string(data.templates.as_ref().unwrap_or(&existing.templates))?;
+        let executor_config = serde_json::to_string(
+            data.executor_config
+                .as_ref()
+                .unwrap_or(&existing.executor_config),
+        )?;
+        let paused = data.paused.unwrap_or(existing.paused);
+        Ok(sqlx::query_as!(
+            WebhookTriggerRow,
+            r#"
+            UPDATE webhook_triggers
+            SET name = ?, repo_ids = ?, templates = ?, executor_config = ?, paused = ?,
+                updated_at = datetime('now', 'subsec')
+            WHERE id = ?
+            RETURNING
+                id as "id!: Uuid",
+                name as "name!",
+                repo_ids as "repo_ids!: Json<Vec<Uuid>>",
+                templates as "templates!: Json<Vec<WebhookTemplate>>",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                paused as "paused!: bool",
+                last_triggered_at as "last_triggered_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            name,
+            repo_ids,
+            templates,
+            executor_config,
+            paused,
+            id
+        )
+        .fetch_one(pool)
+        .await?
+        .into())
+    }
+
+    /// Replace the trigger's token, invalidating the old one.
+    pub async fn set_token_hash(
+        pool: &SqlitePool,
+        id: Uuid,
+        token_hash: &str,
+    ) -> Result<Self, WebhookTriggerError> {
+        sqlx::query_as!(
+            WebhookTriggerRow,
+            r#"
+            UPDATE webhook_triggers
+            SET token_hash = ?, updated_at = datetime('now', 'subsec')
+            WHERE id = ?
+            RETURNING
+                id as "id!: Uuid",
+                name as "name!",
+                repo_ids as "repo_ids!: Json<Vec<Uuid>>",
+                templates as "templates!: Json<Vec<WebhookTemplate>>",
+                executor_config as "executor_config!: Json<ExecutorConfig>",
+                paused as "paused!: bool",
+                last_triggered_at as "last_triggered_at?: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            "#,
+            token_hash,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|row| row.map(WebhookTrigger::from))?
+        .ok_or(WebhookTriggerError::NotFound)
+    }
+
+    pub async fn mark_triggered(
+        pool: &SqlitePool,
+        id: Uuid,
+        triggered_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE webhook_triggers SET last_triggered_at = ? WHERE id = ?",
+            triggered_at,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM webhook_triggers WHERE id = ?", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl WebhookTriggerRun {
+    pub async fn find_by_trigger_id(
+        pool: &SqlitePool,
+        trigger_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookTriggerRun,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                trigger_id as "trigger_id!: Uuid",
+                template as "template!",
+                workspace_id as "workspace_id?: Uuid",
+                status as "status!: WebhookTriggerRunStatus",
+                error as "error?",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM webhook_trigger_runs
+            WHERE trigger_id = ?
+            ORDER BY created_at DESC
+            LIMIT ?
+            "#,
+            trigger_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        trigger_id: Uuid,
+        template: &str,
+        workspace_id: Option<Uuid>,
+        status: WebhookTriggerRunStatus,
+        error: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            WebhookTriggerRun,
+            r#"
+            INSERT INTO webhook_trigger_runs
+                (id, trigger_id, template, workspace_id, status, error)
+            VALUES (?, ?, ?, ?, ?, ?)
+            RETURNING
+                id as "id!: Uuid",
+                trigger_id as "trigger_id!: Uuid",
+                template as "template!",
+                workspace_id as "workspace_id?: Uuid",
+                status as "status!: WebhookTriggerRunStatus",
+                error as "error?",
+                created_at as "created_at!: DateTime<Utc>"
+            "#,
+            id,
+            trigger_id,
+            template,
+            workspace_id,
+            status,
+            error
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -180,6 +180,21 @@ impl Workspace {
         Ok(())
     }
 
+    /// Record that the setup scripts of the workspace's repos have run, e.g.
+    /// because its worktrees came from the warm pool.
+    pub async fn mark_setup_completed(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE workspaces SET setup_completed_at = datetime('now', 'subsec') WHERE id = ?",
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     /// Update the workspace's updated_at timestamp to prevent cleanup.
     /// Call this when the workspace is accessed (e.g., opened in editor).
     pub async fn touch(pool: &SqlitePool, workspace_id: Uuid) -> Result<(), sqlx::Error> {
@@ -356,6 +371,26 @@ impl Workspace {
         .await?)
     }
 
+    /// Re-insert a deleted workspace. Its worktree is recreated on next use,
+    /// so setup is marked as not completed.
+    pub async fn restore(pool: &SqlitePool, workspace: &Workspace) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO workspaces
+                   (id, task_id, branch, created_at, archived, pinned, name)
+               VALUES (?, ?, ?, ?, ?, ?, ?)"#,
+            workspace.id,
+            workspace.task_id,
+            workspace.branch,
+            workspace.created_at,
+            workspace.archived,
+            workspace.pinned,
+            workspace.name
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn update_branch_name(
         pool: &SqlitePool,
         workspace_id: Uuid,
@@ -0,0 +1,105 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum WorkspaceContextKind {
+    /// An uploaded attachment.
+    File,
+    /// A web page, fetched when the agent is launched.
+    Url,
+}
+
+/// A file or URL given to the agent as context for a workspace.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct WorkspaceContextItem {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub kind: WorkspaceContextKind,
+    /// File name inside the context directory.
+    pub name: String,
+    pub attachment_id: Option<Uuid>,
+    pub url: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl WorkspaceContextItem {
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceContextItem,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                workspace_id as "workspace_id!: Uuid",
+                kind as "kind!: WorkspaceContextKind",
+                name as "name!",
+                attachment_id as "attachment_id?: Uuid",
+                url as "url?",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM workspace_context_items
+            WHERE workspace_id = ?
+            ORDER BY created_at ASC
+            "#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        kind: WorkspaceContextKind,
+        name: &str,
+        attachment_id: Option<Uuid>,
+        url: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            WorkspaceContextItem,
+            r#"
+            INSERT INTO workspace_context_items (id, workspace_id, kind, name, attachment_id, url)
+            VALUES (?, ?, ?, ?, ?, ?)
+            RETURNING
+                id as "id!: Uuid",
+                workspace_id as "workspace_id!: Uuid",
+                kind as "kind!: WorkspaceContextKind",
+                name as "name!",
+                attachment_id as "attachment_id?: Uuid",
+                url as "url?",
+                created_at as "created_at!: DateTime<Utc>"
+            "#,
+            id,
+            workspace_id,
+            kind,
+            name,
+            attachment_id,
+            url
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM workspace_context_items WHERE id = ? AND workspace_id = ?",
+            id,
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -6,7 +6,7 @@ use sqlx::{FromRow, SqlitePool};
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::repo::Repo;
+use super::{repo::Repo, script_library::RepoScriptRef};
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct WorkspaceRepo {
@@ -104,11 +104,13 @@ impl WorkspaceRepo {
         .await
     }
 
+    /// Repos attached to a workspace, with lifecycle scripts resolved against
+    /// the script library.
     pub async fn find_repos_for_workspace(
         pool: &SqlitePool,
         workspace_id: Uuid,
     ) -> Result<Vec<Repo>, sqlx::Error> {
-        sqlx::query_as!(
+        let mut repos = sqlx::query_as!(
             Repo,
             r#"SELECT r.id as "id!: Uuid",
                       r.path,
@@ -131,7 +133,9 @@ impl WorkspaceRepo {
             workspace_id
         )
         .fetch_all(pool)
-        .await
+        .await?;
+        RepoScriptRef::resolve_scripts(pool, &mut repos).await?;
+        Ok(repos)
     }
 
     pub async fn find_repos_with_target_branch_for_workspace(
@@ -275,4 +279,30 @@ impl WorkspaceRepo {
             })
             .collect())
     }
+
+    /// The `limit` most recently used repos, most recent first, each with the
+    /// target branch of the workspace that used it last.
+    pub async fn find_recent_repo_targets(
+        pool: &SqlitePool,
+        limit: u32,
+    ) -> Result<Vec<(Uuid, String)>, sqlx::Error> {
+        // SQLite takes bare columns from the row holding the MAX()
+        let rows = sqlx::query!(
+            r#"SELECT repo_id as "repo_id!: Uuid", target_branch as "target_branch!"
+               FROM (
+                   SELECT repo_id, target_branch, MAX(updated_at) AS last_used_at
+                   FROM workspace_repos
+                   GROUP BY repo_id
+               )
+               ORDER BY last_used_at DESC
+               LIMIT ?"#,
+            limit
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.repo_id, row.target_branch))
+            .collect())
+    }
 }
@@ -0,0 +1,230 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::workspace::Workspace;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum UndoAction {
+    WorkspaceDeleted,
+    GitReset,
+}
+
+/// A repo of a deleted workspace and the commit its branch pointed at.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoRepoBranch {
+    pub repo_id: Uuid,
+    pub repo_path: String,
+    pub target_branch: String,
+    /// Unset when the branch didn't exist.
+    pub head_commit: Option<String>,
+}
+
+/// A worktree reset: the commit it moved away from, and the snapshot of the
+/// uncommitted changes it discarded, if any.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoRepoReset {
+    pub repo_id: Uuid,
+    pub repo_path: String,
+    pub previous_head: String,
+    pub snapshot_id: Option<String>,
+}
+
+/// What it takes to undo an action.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UndoPayload {
+    WorkspaceDeleted {
+        workspace: Workspace,
+        repos: Vec<UndoRepoBranch>,
+    },
+    GitReset {
+        repos: Vec<UndoRepoReset>,
+    },
+}
+
+impl UndoPayload {
+    pub fn action(&self) -> UndoAction {
+        match self {
+            Self::WorkspaceDeleted { .. } => UndoAction::WorkspaceDeleted,
+            Self::GitReset { .. } => UndoAction::GitReset,
+        }
+    }
+
+    /// Paths of the repos holding undo refs for this entry.
+    pub fn repo_paths(&self) -> Vec<&str> {
+        match self {
+            Self::WorkspaceDeleted { repos, .. } => {
+                repos.iter().map(|repo| repo.repo_path.as_str()).collect()
+            }
+            Self::GitReset { repos } => repos.iter().map(|repo| repo.repo_path.as_str()).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WorkspaceUndoEntry {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub action: UndoAction,
+    pub payload: Json<UndoPayload>,
+    pub undone_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl WorkspaceUndoEntry {
+    /// Record an undoable action, kept for `retention_days`.
+    pub async fn create(
+        pool: &SqlitePool,
+        id: Uuid,
+        workspace_id: Uuid,
+        payload: &UndoPayload,
+        retention_days: u32,
+    ) -> Result<Self, sqlx::Error> {
+        let action = payload.action();
+        let payload = Json(payload);
+        let expires_in = format!("+{retention_days} days");
+        sqlx::query_as!(
+            WorkspaceUndoEntry,
+            r#"
+            INSERT INTO workspace_undo_log (id, workspace_id, action, payload, expires_at)
+            VALUES (?, ?, ?, ?, datetime('now', 'subsec', ?))
+            RETURNING
+                id as "id!: Uuid",
+                workspace_id as "workspace_id!: Uuid",
+                action as "action!: UndoAction",
+                payload as "payload!: Json<UndoPayload>",
+                undone_at as "undone_at?: DateTime<Utc>",
+                expires_at as "expires_at!: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>"
+            "#,
+            id,
+            workspace_id,
+            action,
+            payload,
+            expires_in
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The most recent action of a workspace that can still be undone.
+    pub async fn find_latest_undoable(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceUndoEntry,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                workspace_id as "workspace_id!: Uuid",
+                action as "action!: UndoAction",
+                payload as "payload!: Json<UndoPayload>",
+                undone_at as "undone_at?: DateTime<Utc>",
+                expires_at as "expires_at!: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM workspace_undo_log
+            WHERE workspace_id = ?
+              AND undone_at IS NULL
+              AND expires_at > datetime('now', 'subsec')
+            ORDER BY created_at DESC, rowid DESC
+            LIMIT 1
+            "#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn mark_undone(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE workspace_undo_log SET undone_at = datetime('now', 'subsec') WHERE id = ?",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Delete expired entries, returning them so their refs can be dropped.
+    pub async fn delete_expired(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceUndoEntry,
+            r#"
+            DELETE FROM workspace_undo_log
+            WHERE expires_at <= datetime('now', 'subsec')
+            RETURNING
+                id as "id!: Uuid",
+                workspace_id as "workspace_id!: Uuid",
+                action as "action!: UndoAction",
+                payload as "payload!: Json<UndoPayload>",
+                undone_at as "undone_at?: DateTime<Utc>",
+                expires_at as "expires_at!: DateTime<Utc>",
+                created_at as "created_at!: DateTime<Utc>"
+            "#
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_pool;
+
+    fn reset(previous_head: &str) -> UndoPayload {
+        UndoPayload::GitReset {
+            repos: vec![UndoRepoReset {
+                repo_id: Uuid::new_v4(),
+                repo_path: "/repos/app".to_string(),
+                previous_head: previous_head.to_string(),
+                snapshot_id: None,
+            }],
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn undoes_newest_first_until_entries_expire() {
+        let pool = test_pool().await;
+        let workspace_id = Uuid::new_v4();
+
+        let first = WorkspaceUndoEntry::create(&pool, Uuid::new_v4(), workspace_id, &reset("a"), 7)
+            .await
+            .unwrap();
+        let second =
+            WorkspaceUndoEntry::create(&pool, Uuid::new_v4(), workspace_id, &reset("b"), 7)
+                .await
+                .unwrap();
+        WorkspaceUndoEntry::create(&pool, Uuid::new_v4(), workspace_id, &reset("c"), 0)
+            .await
+            .unwrap();
+
+        let latest = WorkspaceUndoEntry::find_latest_undoable(&pool, workspace_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(latest.id, second.id, "expired entries can't be undone");
+        assert_eq!(latest.action, UndoAction::GitReset);
+
+        WorkspaceUndoEntry::mark_undone(&pool, second.id)
+            .await
+            .unwrap();
+        let latest = WorkspaceUndoEntry::find_latest_undoable(&pool, workspace_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(latest.id, first.id);
+
+        let expired = WorkspaceUndoEntry::delete_expired(&pool).await.unwrap();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].payload.repo_paths(), vec!["/repos/app"]);
+    }
+}
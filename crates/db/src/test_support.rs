@@ -0,0 +1,42 @@
+//! Fixtures shared by the model tests.
+
+use std::str::FromStr;
+
+use sqlx::{
+    SqlitePool,
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions},
+};
+
+async fn migrated_pool(foreign_keys: bool) -> SqlitePool {
+    let options = SqliteConnectOptions::from_str("sqlite::memory:")
+        .unwrap()
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Memory)
+        .foreign_keys(foreign_keys);
+    // A single connection, as every connection to `sqlite::memory:` opens a
+    // database of its own.
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+        .unwrap();
+    crate::run_migrations(&pool).await.unwrap();
+    // The migrations switch foreign keys back on.
+    if !foreign_keys {
+        sqlx::query!("PRAGMA foreign_keys = OFF")
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    pool
+}
+
+/// An in-memory database with every migration applied.
+pub(crate) async fn test_pool() -> SqlitePool {
+    migrated_pool(true).await
+}
+
+/// Like [`test_pool`], for tests that insert rows without their parents.
+pub(crate) async fn test_pool_without_foreign_keys() -> SqlitePool {
+    migrated_pool(false).await
+}
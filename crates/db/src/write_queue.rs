@@ -0,0 +1,169 @@
+//! Serializes the writes parallel executions make most often.
+//!
+//! SQLite allows a single writer at a time; other connections retry until the
+//! busy timeout runs out and then fail with `database is locked`. Busy retries
+//! aren't fair, so under load a writer can lose every retry. Writers that run
+//! once per execution or per log batch queue here instead and are let through
+//! one at a time, in arrival order.
+//!
+//! Each [`crate::DBService`] owns one queue, created with its pool. Callers
+//! wrap single writes in [`crate::DBService::write`]; a permit held across
+//! another queued write would wait for itself.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use tokio::sync::MutexGuard;
+use ts_rs::TS;
+
+/// Waits longer than this are logged.
+const SLOW_WAIT: Duration = Duration::from_secs(1);
+
+#[derive(Default)]
+pub struct WriteQueue {
+    // tokio's mutex hands the lock to waiters in FIFO order.
+    lock: tokio::sync::Mutex<()>,
+    depth: AtomicU64,
+    max_depth: AtomicU64,
+    writers: Mutex<HashMap<&'static str, WriterStats>>,
+}
+
+#[derive(Default)]
+struct WriterStats {
+    writes: u64,
+    total_wait: Duration,
+    max_wait: Duration,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct QueuedWriterStats {
+    pub writer: String,
+    #[ts(type = "number")]
+    pub writes: u64,
+    #[ts(type = "number")]
+    pub total_wait_ms: u64,
+    #[ts(type = "number")]
+    pub max_wait_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct WriteQueueStats {
+    /// Writes waiting or running right now.
+    #[ts(type = "number")]
+    pub depth: u64,
+    #[ts(type = "number")]
+    pub max_depth: u64,
+    pub writers: Vec<QueuedWriterStats>,
+}
+
+/// Lets one queued write through; the next waiter goes once it's dropped.
+pub struct WritePermit<'a> {
+    _guard: MutexGuard<'a, ()>,
+    _slot: QueueSlot<'a>,
+}
+
+/// Counts a write in the queue depth until dropped, including when the
+/// waiting write is cancelled.
+struct QueueSlot<'a>(&'a AtomicU64);
+
+impl Drop for QueueSlot<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl WriteQueue {
+    /// Wait for the turn of a write by `writer`, usually the table it writes.
+    pub async fn acquire(&self, writer: &'static str) -> WritePermit<'_> {
+        let depth = self.depth.fetch_add(1, Ordering::Relaxed) + 1;
+        let slot = QueueSlot(&self.depth);
+        self.max_depth.fetch_max(depth, Ordering::Relaxed);
+
+        let started = Instant::now();
+        let guard = self.lock.lock().await;
+        let waited = started.elapsed();
+
+        {
+            let mut writers = self
+                .writers
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let stats = writers.entry(writer).or_default();
+            stats.writes += 1;
+            stats.total_wait += waited;
+            stats.max_wait = stats.max_wait.max(waited);
+        }
+        if waited > SLOW_WAIT {
+            tracing::warn!(
+                "{} write waited {:?} in the write queue ({} queued)",
+                writer,
+                waited,
+                depth
+            );
+        }
+
+        WritePermit {
+            _guard: guard,
+            _slot: slot,
+        }
+    }
+
+    pub fn stats(&self) -> WriteQueueStats {
+        let writers = self
+            .writers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut writers: Vec<_> = writers
+            .iter()
+            .map(|(writer, stats)| QueuedWriterStats {
+                writer: writer.to_string(),
+                writes: stats.writes,
+                total_wait_ms: stats.total_wait.as_millis() as u64,
+                max_wait_ms: stats.max_wait.as_millis() as u64,
+            })
+            .collect();
+        writers.sort_by(|a, b| a.writer.cmp(&b.writer));
+
+        WriteQueueStats {
+            depth: self.depth.load(Ordering::Relaxed),
+            max_depth: self.max_depth.load(Ordering::Relaxed),
+            writers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn writes_wait_for_the_previous_permit() {
+        let queue = WriteQueue::default();
+        let first = queue.acquire("write_queue_test").await;
+
+        let mut second = Box::pin(queue.acquire("write_queue_test"));
+        assert!(futures::poll!(second.as_mut()).is_pending());
+        assert_eq!(queue.stats().depth, 2);
+
+        drop(first);
+        let second = second.await;
+        drop(second);
+
+        let stats = queue.stats();
+        assert_eq!(stats.depth, 0);
+        assert_eq!(stats.max_depth, 2);
+        let writer = stats
+            .writers
+            .into_iter()
+            .find(|stats| stats.writer == "write_queue_test")
+            .unwrap();
+        assert_eq!(writer.writes, 2);
+    }
+}
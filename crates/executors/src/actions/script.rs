@@ -23,6 +23,7 @@ pub enum ScriptContext {
     SetupScript,
     CleanupScript,
     ArchiveScript,
+    VerifyScript,
     DevServer,
     ToolInstallScript,
 }
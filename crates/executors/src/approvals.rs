@@ -31,6 +31,16 @@ pub trait ExecutorApprovalService: Send + Sync {
     /// Creates a tool approval request. Returns the approval_id immediately.
     async fn create_tool_approval(&self, tool_name: &str) -> Result<String, ExecutorApprovalError>;
 
+    /// Creates a tool approval request with the tool's input, so approval
+    /// policies can decide on it (e.g. by the shell command being run).
+    async fn create_tool_approval_with_input(
+        &self,
+        tool_name: &str,
+        _input: &serde_json::Value,
+    ) -> Result<String, ExecutorApprovalError> {
+        self.create_tool_approval(tool_name).await
+    }
+
     /// Creates a question approval request. Returns the approval_id immediately.
     async fn create_question_approval(
         &self,
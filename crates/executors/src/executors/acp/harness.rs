@@ -26,6 +26,7 @@ use crate::{
     command::{CmdOverrides, CommandParts},
     env::ExecutionEnv,
     executors::{ExecutorError, ExecutorExitResult, SpawnedChild, acp::AcpEvent},
+    prompt_images::{PromptImage, load_prompt_images},
 };
 
 /// Reusable harness for ACP-based conns (Gemini, Qwen, etc.)
@@ -33,6 +34,7 @@ pub struct AcpAgentHarness {
     session_namespace: String,
     model: Option<String>,
     mode: Option<String>,
+    image_input: bool,
 }
 
 impl Default for AcpAgentHarness {
@@ -49,6 +51,7 @@ impl AcpAgentHarness {
             session_namespace: "gemini_sessions".to_string(),
             model: None,
             mode: None,
+            image_input: false,
         }
     }
 
@@ -58,6 +61,7 @@ impl AcpAgentHarness {
             session_namespace: namespace.into(),
             model: None,
             mode: None,
+            image_input: false,
         }
     }
 
@@ -71,6 +75,21 @@ impl AcpAgentHarness {
         self
     }
 
+    /// Send attachment images referenced in the prompt as image content, for
+    /// agents that accept it.
+    pub fn with_image_input(mut self) -> Self {
+        self.image_input = true;
+        self
+    }
+
+    async fn prompt_images(&self, current_dir: &Path, prompt: &str) -> Vec<PromptImage> {
+        if self.image_input {
+            load_prompt_images(current_dir, prompt).await
+        } else {
+            Vec::new()
+        }
+    }
+
     pub fn apply_overrides(&mut self, executor_config: &crate::profile::ExecutorConfig) {
         if let Some(model_id) = &executor_config.model_id {
             self.model = Some(model_id.clone());
@@ -106,6 +125,7 @@ impl AcpAgentHarness {
             .with_profile(cmd_overrides)
             .apply_to_command(&mut command);
 
+        let images = self.prompt_images(current_dir, &prompt).await;
         let mut child = command.group_spawn_no_window()?;
 
         let (exit_tx, exit_rx) = tokio::sync::oneshot::channel::<ExecutorExitResult>();
@@ -116,6 +136,7 @@ impl AcpAgentHarness {
             current_dir.to_path_buf(),
             None,
             prompt,
+            images,
             Some(exit_tx),
             self.session_namespace.clone(),
             self.model.clone(),
@@ -159,6 +180,7 @@ impl AcpAgentHarness {
             .with_profile(cmd_overrides)
             .apply_to_command(&mut command);
 
+        let images = self.prompt_images(current_dir, &prompt).await;
         let mut child = command.group_spawn_no_window()?;
 
         let (exit_tx, exit_rx) = tokio::sync::oneshot::channel::<ExecutorExitResult>();
@@ -169,6 +191,7 @@ impl AcpAgentHarness {
             current_dir.to_path_buf(),
             Some(session_id.to_string()),
             prompt,
+            images,
             Some(exit_tx),
             self.session_namespace.clone(),
             self.model.clone(),
@@ -191,6 +214,7 @@ impl AcpAgentHarness {
         cwd: PathBuf,
         existing_session: Option<String>,
         prompt: String,
+        images: Vec<PromptImage>,
         exit_signal: Option<tokio::sync::oneshot::Sender<ExecutorExitResult>>,
         session_namespace: String,
         model: Option<String>,
@@ -450,11 +474,18 @@ impl AcpAgentHarness {
                         );
 
                         // Build prompt request
+                        let mut prompt_blocks = vec![proto::ContentBlock::Text(
+                            proto::TextContent::new(prompt_to_send),
+                        )];
+                        prompt_blocks.extend(images.into_iter().map(|image| {
+                            proto::ContentBlock::Image(proto::ImageContent::new(
+                                image.data,
+                                image.mime_type,
+                            ))
+                        }));
                         let initial_req = proto::PromptRequest::new(
                             proto::SessionId::new(acp_session_id.clone()),
-                            vec![proto::ContentBlock::Text(proto::TextContent::new(
-                                prompt_to_send,
-                            ))],
+                            prompt_blocks,
                         );
 
                         let mut current_req = Some(initial_req);
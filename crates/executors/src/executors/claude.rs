@@ -53,6 +53,7 @@ use crate::{
     },
     model_selector::PermissionPolicy,
     profile::ExecutorConfig,
+    prompt_images::load_prompt_images,
     stdout_dup::create_stdout_pipe_writer,
 };
 
@@ -665,6 +666,8 @@ impl ClaudeCode {
         // Create cancellation token for graceful shutdown
         let cancel = CancellationToken::new();
 
+        let prompt_images = load_prompt_images(current_dir, &combined_prompt).await;
+
         // Spawn task to handle the SDK client with control protocol
         let prompt_clone = combined_prompt.clone();
         let approvals_clone = self.approvals_service.clone();
@@ -697,7 +700,10 @@ impl ClaudeCode {
             }
 
             // Send user message
-            if let Err(e) = protocol_peer.send_user_message(prompt_clone).await {
+            if let Err(e) = protocol_peer
+                .send_user_message(prompt_clone, prompt_images)
+                .await
+            {
                 tracing::error!("Failed to send prompt: {e}");
                 let _ = log_writer
                     .log_raw(&format!("Error: Failed to send prompt - {e}"))
@@ -1052,7 +1058,7 @@ impl ClaudeLogProcessor {
                     Self::build_tool_use_entry(tool_data, worktree_path, ToolStatus::Created);
                 Some(entry)
             }
-            ClaudeContentItem::ToolResult { .. } => {
+            ClaudeContentItem::ToolResult { .. } | ClaudeContentItem::Image { .. } => {
                 // TODO: Add proper ToolResult support to NormalizedEntry when the type system supports it
                 None
             }
@@ -1463,7 +1469,7 @@ impl ClaudeLogProcessor {
                                 patches.push(patch);
                             }
                         }
-                        ClaudeContentItem::ToolResult { .. } => {}
+                        ClaudeContentItem::ToolResult { .. } | ClaudeContentItem::Image { .. } => {}
                     }
                 }
             }
@@ -2451,6 +2457,9 @@ pub enum ClaudeContentItem {
         content: serde_json::Value,
         is_error: Option<bool>,
     },
+    /// Images attached to user messages, echoed back by `--replay-user-messages`.
+    #[serde(rename = "image")]
+    Image { source: serde_json::Value },
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
@@ -68,7 +68,10 @@ impl ClaudeAgentClient {
             .as_ref()
             .ok_or(ExecutorApprovalError::ServiceUnavailable)?;
 
-        let approval_id = match approval_service.create_tool_approval(&tool_name).await {
+        let approval_id = match approval_service
+            .create_tool_approval_with_input(&tool_name, &tool_input)
+            .await
+        {
             Ok(id) => id,
             Err(err) => {
                 self.handle_approval_error(&tool_name, &tool_use_id, &err)
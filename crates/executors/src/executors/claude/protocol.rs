@@ -17,6 +17,7 @@ use crate::{
             types::{Message, PermissionMode, SDKControlRequest, SDKControlRequestType},
         },
     },
+    prompt_images::PromptImage,
 };
 
 /// Handles bidirectional control protocol communication
@@ -195,8 +196,12 @@ impl ProtocolPeer {
         Ok(())
     }
 
-    pub async fn send_user_message(&self, content: String) -> Result<(), ExecutorError> {
-        let message = Message::new_user(content);
+    pub async fn send_user_message(
+        &self,
+        content: String,
+        images: Vec<PromptImage>,
+    ) -> Result<(), ExecutorError> {
+        let message = Message::new_user(content, images);
         self.send_json(&message).await
     }
 
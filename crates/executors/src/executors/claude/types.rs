@@ -5,6 +5,8 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::prompt_images::PromptImage;
+
 /// Top-level message types from CLI stdout
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -174,11 +176,49 @@ pub enum Message {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClaudeUserMessage {
     role: String,
-    content: String,
+    content: ClaudeUserContent,
+}
+
+/// Plain text, or content blocks when images are attached.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ClaudeUserContent {
+    Text(String),
+    Blocks(Vec<ClaudeUserContentBlock>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClaudeUserContentBlock {
+    Text { text: String },
+    Image { source: ClaudeImageSource },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClaudeImageSource {
+    Base64 { media_type: String, data: String },
 }
 
 impl Message {
-    pub fn new_user(content: String) -> Self {
+    /// A user message, with any images following the text.
+    pub fn new_user(content: String, images: Vec<PromptImage>) -> Self {
+        let content = if images.is_empty() {
+            ClaudeUserContent::Text(content)
+        } else {
+            let mut blocks = vec![ClaudeUserContentBlock::Text { text: content }];
+            blocks.extend(
+                images
+                    .into_iter()
+                    .map(|image| ClaudeUserContentBlock::Image {
+                        source: ClaudeImageSource::Base64 {
+                            media_type: image.mime_type.to_string(),
+                            data: image.data,
+                        },
+                    }),
+            );
+            ClaudeUserContent::Blocks(blocks)
+        };
         Self::User {
             message: ClaudeUserMessage {
                 role: "user".to_string(),
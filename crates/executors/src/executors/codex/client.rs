@@ -328,7 +328,7 @@ impl AppServerClient {
             ServerRequest::FileChangeRequestApproval { request_id, params } => {
                 let call_id = params.item_id.clone();
                 let status = self
-                    .request_tool_approval("edit", "codex.apply_patch", &call_id)
+                    .request_tool_approval("edit", "codex.apply_patch", &call_id, &Value::Null)
                     .await
                     .inspect_err(|err| {
                         if !matches!(
@@ -362,8 +362,12 @@ impl AppServerClient {
             }
             ServerRequest::CommandExecutionRequestApproval { request_id, params } => {
                 let call_id = params.item_id.clone();
+                let input = params.command.as_ref().map_or(
+                    Value::Null,
+                    |command| serde_json::json!({ "command": command }),
+                );
                 let status = self
-                    .request_tool_approval("bash", "codex.exec_command", &call_id)
+                    .request_tool_approval("bash", "codex.exec_command", &call_id, &input)
                     .await
                     .inspect_err(|err| {
                         if !matches!(
@@ -479,6 +483,7 @@ impl AppServerClient {
         tool_name: &str,
         display_tool_name: &str,
         tool_call_id: &str,
+        input: &Value,
     ) -> Result<ApprovalStatus, ExecutorError> {
         if self.auto_approve {
             return Ok(ApprovalStatus::Approved);
@@ -489,7 +494,7 @@ impl AppServerClient {
             .ok_or(ExecutorApprovalError::ServiceUnavailable)?;
 
         let approval_id = approval_service
-            .create_tool_approval(tool_name)
+            .create_tool_approval_with_input(tool_name, input)
             .or_else(|err| async {
                 self.handle_approval_error(display_tool_name, tool_call_id)
                     .await;
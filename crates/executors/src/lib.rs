@@ -8,4 +8,5 @@ pub mod logs;
 pub mod mcp_config;
 pub mod model_selector;
 pub mod profile;
+pub mod prompt_images;
 pub mod stdout_dup;
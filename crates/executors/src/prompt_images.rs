@@ -0,0 +1,128 @@
+//! Images referenced from prompts, for executors that accept image input.
+//!
+//! Pasted images are copied into the agent's working directory under
+//! `.vibe-attachments/` and referenced from the prompt by that relative path,
+//! usually as markdown (`![screenshot](.vibe-attachments/<file>.png)`).
+
+use std::path::{Path, PathBuf};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use workspace_utils::path::VIBE_ATTACHMENTS_DIR;
+
+/// Images larger than this are left for the agent to read from disk.
+pub const MAX_PROMPT_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+/// At most this many images are forwarded with one prompt.
+pub const MAX_PROMPT_IMAGES: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptImage {
+    /// The path as written in the prompt.
+    pub reference: String,
+    pub mime_type: &'static str,
+    /// Base64-encoded file content.
+    pub data: String,
+}
+
+fn image_mime_type(path: &str) -> Option<&'static str> {
+    let extension = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Attachment image paths referenced in `prompt`, in order of first mention.
+pub fn image_references(prompt: &str) -> Vec<String> {
+    let prefix = format!("{VIBE_ATTACHMENTS_DIR}/");
+    let mut references: Vec<String> = Vec::new();
+    let mut rest = prompt;
+    while let Some(start) = rest.find(&prefix) {
+        let candidate = &rest[start..];
+        let end = candidate
+            .find(|c: char| c.is_whitespace() || matches!(c, ')' | ']' | '"' | '\'' | '`' | '>'))
+            .unwrap_or(candidate.len());
+        let reference = candidate[..end].trim_end_matches(['.', ',', ';', ':', '!', '?']);
+        let file_name = &reference[prefix.len()..];
+        if !file_name.is_empty()
+            && !file_name.contains(['/', '\\'])
+            && image_mime_type(reference).is_some()
+            && !references.iter().any(|r| r == reference)
+        {
+            references.push(reference.to_string());
+        }
+        rest = &candidate[end.max(prefix.len())..];
+    }
+    references
+}
+
+/// Load the attachment images referenced in `prompt` from `current_dir`.
+/// Missing or oversized files are skipped; the agent still sees their paths.
+pub async fn load_prompt_images(current_dir: &Path, prompt: &str) -> Vec<PromptImage> {
+    let mut images = Vec::new();
+    for reference in image_references(prompt).into_iter().take(MAX_PROMPT_IMAGES) {
+        let path: PathBuf = current_dir.join(&reference);
+        match tokio::fs::metadata(&path).await {
+            Ok(metadata) if metadata.len() <= MAX_PROMPT_IMAGE_BYTES => {}
+            Ok(metadata) => {
+                tracing::debug!(
+                    "Not forwarding {} with the prompt: {} bytes",
+                    reference,
+                    metadata.len()
+                );
+                continue;
+            }
+            Err(e) => {
+                tracing::debug!("Not forwarding {} with the prompt: {}", reference, e);
+                continue;
+            }
+        }
+        match tokio::fs::read(&path).await {
+            Ok(bytes) => images.push(PromptImage {
+                mime_type: image_mime_type(&reference).unwrap_or("image/png"),
+                data: BASE64.encode(bytes),
+                reference,
+            }),
+            Err(e) => tracing::warn!("Failed to read prompt image {}: {}", reference, e),
+        }
+    }
+    images
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_attachment_images_in_prompt() {
+        let prompt = "Fix the layout ![shot](.vibe-attachments/abc_shot.png) and compare \
+                      with .vibe-attachments/def_old.JPG. Notes: .vibe-attachments/notes.txt, \
+                      ![shot](.vibe-attachments/abc_shot.png), .vibe-attachments/../x.png";
+        assert_eq!(
+            image_references(prompt),
+            vec![
+                ".vibe-attachments/abc_shot.png".to_string(),
+                ".vibe-attachments/def_old.JPG".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn loads_existing_images_only() {
+        let dir = std::env::temp_dir().join(format!("prompt-images-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join(VIBE_ATTACHMENTS_DIR)).unwrap();
+        std::fs::write(dir.join(VIBE_ATTACHMENTS_DIR).join("a.png"), b"\x89PNG").unwrap();
+
+        let images = load_prompt_images(
+            &dir,
+            "see .vibe-attachments/a.png and .vibe-attachments/missing.png",
+        )
+        .await;
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].mime_type, "image/png");
+        assert_eq!(images[0].data, BASE64.encode(b"\x89PNG"));
+    }
+}
@@ -5,12 +5,14 @@
 
 use std::{
     ffi::{OsStr, OsString},
+    fmt,
     path::Path,
     process::Command,
 };
 
 use chrono::{DateTime, Utc};
 use db::models::merge::MergeStatus;
+use git::credentials;
 use serde::Deserialize;
 use thiserror::Error;
 use utils::{command_ext::NoWindowExt, shell::resolve_executable_path_blocking};
@@ -123,12 +125,32 @@ pub enum AzCliError {
     UnexpectedOutput(String),
 }
 
-#[derive(Debug, Clone, Default)]
-pub struct AzCli;
+#[derive(Clone, Default)]
+pub struct AzCli {
+    /// Token of the git credential the repo selected, passed as `AZURE_DEVOPS_EXT_PAT`.
+    token: Option<String>,
+}
+
+impl fmt::Debug for AzCli {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AzCli")
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
 
 impl AzCli {
     pub fn new() -> Self {
-        Self {}
+        Self::default()
+    }
+
+    /// Authenticate as the account `repo_path` selected if it selected a
+    /// token, and as the `az login` account otherwise.
+    pub fn for_repo(repo_path: &Path) -> Self {
+        Self {
+            token: credentials::repo_credential(repo_path)
+                .and_then(|credential| credential.token().map(str::to_string)),
+        }
     }
 
     /// Ensure the Azure CLI binary is discoverable.
@@ -145,6 +167,9 @@ impl AzCli {
         self.ensure_available()?;
         let az = resolve_executable_path_blocking("az").ok_or(AzCliError::NotAvailable)?;
         let mut cmd = Command::new(&az);
+        if let Some(token) = &self.token {
+            cmd.env("AZURE_DEVOPS_EXT_PAT", token);
+        }
 
         if let Some(d) = dir {
             cmd.current_dir(d);
@@ -13,7 +13,10 @@ use tracing::info;
 
 use crate::{
     GitHostProvider,
-    types::{CreatePrRequest, GitHostError, ProviderKind, PullRequestDetail, UnifiedPrComment},
+    types::{
+        BranchProtection, CreatePrRequest, GitHostError, ProviderKind, PullRequestDetail,
+        UnifiedPrComment,
+    },
 };
 
 #[derive(Debug, Clone)]
@@ -33,7 +36,7 @@ impl AzureDevOpsProvider {
         repo_path: &Path,
         remote_url: &str,
     ) -> Result<AzureRepoInfo, GitHostError> {
-        let cli = self.az_cli.clone();
+        let cli = AzCli::for_repo(repo_path);
         let path = repo_path.to_path_buf();
         let url = remote_url.to_string();
         task::spawn_blocking(move || cli.get_repo_info(&path, &url))
@@ -86,7 +89,7 @@ impl GitHostProvider for AzureDevOpsProvider {
         let repo_info = self.get_repo_info(repo_path, remote_url).await?;
 
         (|| async {
-            let cli = self.az_cli.clone();
+            let cli = AzCli::for_repo(repo_path);
             let request_clone = request.clone();
             let organization_url = repo_info.organization_url.clone();
             let project = repo_info.project.clone();
@@ -169,7 +172,7 @@ impl GitHostProvider for AzureDevOpsProvider {
         let repo_info = self.get_repo_info(repo_path, remote_url).await?;
 
         (|| async {
-            let cli = self.az_cli.clone();
+            let cli = AzCli::for_repo(repo_path);
             let organization_url = repo_info.organization_url.clone();
             let project = repo_info.project.clone();
             let repo_name = repo_info.repo_name.clone();
@@ -213,7 +216,7 @@ impl GitHostProvider for AzureDevOpsProvider {
         let repo_info = self.get_repo_info(repo_path, remote_url).await?;
 
         (|| async {
-            let cli = self.az_cli.clone();
+            let cli = AzCli::for_repo(repo_path);
             let organization_url = repo_info.organization_url.clone();
             let project_id = repo_info.project_id.clone();
             let repo_id = repo_info.repo_id.clone();
@@ -256,6 +259,16 @@ impl GitHostProvider for AzureDevOpsProvider {
         Err(GitHostError::UnsupportedProvider)
     }
 
+    async fn get_branch_protection(
+        &self,
+        _repo_path: &Path,
+        _remote_url: &str,
+        _branch_name: &str,
+    ) -> Result<BranchProtection, GitHostError> {
+        // TODO: Read branch policies for Azure DevOps
+        Err(GitHostError::UnsupportedProvider)
+    }
+
     fn provider_kind(&self) -> ProviderKind {
         ProviderKind::AzureDevOps
     }
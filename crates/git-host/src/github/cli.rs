@@ -5,6 +5,7 @@
 
 use std::{
     ffi::{OsStr, OsString},
+    fmt,
     io::Write,
     path::Path,
     process::Command,
@@ -12,6 +13,7 @@ use std::{
 
 use chrono::{DateTime, Utc};
 use db::models::merge::MergeStatus;
+use git::credentials;
 use serde::Deserialize;
 use tempfile::NamedTempFile;
 use thiserror::Error;
@@ -19,8 +21,8 @@ use url::Url;
 use utils::{command_ext::NoWindowExt, shell::resolve_executable_path_blocking};
 
 use crate::types::{
-    CreatePrRequest, PrComment, PrCommentAuthor, PrReviewComment, PullRequestDetail,
-    ReviewCommentUser,
+    BranchProtection, CreatePrRequest, PrComment, PrCommentAuthor, PrReviewComment,
+    PullRequestDetail, ReviewCommentUser,
 };
 
 #[derive(Debug, Clone)]
@@ -119,6 +121,72 @@ struct GhPrResponse {
     updated_at: Option<DateTime<Utc>>,
 }
 
+/// Response from `repos/{owner}/{repo}/branches/{branch}`
+#[derive(Deserialize)]
+struct GhBranchResponse {
+    #[serde(default)]
+    protected: bool,
+    protection: Option<GhBranchProtectionSummary>,
+}
+
+#[derive(Deserialize)]
+struct GhBranchProtectionSummary {
+    required_status_checks: Option<GhStatusChecks>,
+}
+
+#[derive(Deserialize)]
+struct GhStatusChecks {
+    #[serde(default)]
+    contexts: Vec<String>,
+}
+
+/// Response from `repos/{owner}/{repo}/branches/{branch}/protection`
+#[derive(Deserialize)]
+struct GhProtectionResponse {
+    required_status_checks: Option<GhStatusChecks>,
+    required_pull_request_reviews: Option<GhRequiredReviews>,
+    required_signatures: Option<GhEnabled>,
+    required_linear_history: Option<GhEnabled>,
+    allow_force_pushes: Option<GhEnabled>,
+}
+
+#[derive(Deserialize)]
+struct GhRequiredReviews {
+    #[serde(default)]
+    required_approving_review_count: u32,
+    #[serde(default)]
+    require_code_owner_reviews: bool,
+}
+
+#[derive(Deserialize)]
+struct GhEnabled {
+    enabled: bool,
+}
+
+/// Item of `repos/{owner}/{repo}/rules/branches/{branch}`, the ruleset rules
+/// that apply to a branch.
+#[derive(Deserialize)]
+struct GhRule {
+    #[serde(rename = "type")]
+    rule_type: String,
+    parameters: Option<GhRuleParameters>,
+}
+
+#[derive(Deserialize)]
+struct GhRuleParameters {
+    #[serde(default)]
+    required_approving_review_count: u32,
+    #[serde(default)]
+    require_code_owner_review: bool,
+    #[serde(default)]
+    required_status_checks: Vec<GhRuleStatusCheck>,
+}
+
+#[derive(Deserialize)]
+struct GhRuleStatusCheck {
+    context: String,
+}
+
 #[derive(Debug, Error)]
 pub enum GhCliError {
     #[error("GitHub CLI (`gh`) executable not found or not runnable")]
@@ -131,12 +199,32 @@ pub enum GhCliError {
     UnexpectedOutput(String),
 }
 
-#[derive(Debug, Clone, Default)]
-pub struct GhCli;
+#[derive(Clone, Default)]
+pub struct GhCli {
+    /// Token of the git credential the repo selected, passed as `GH_TOKEN`.
+    token: Option<String>,
+}
+
+impl fmt::Debug for GhCli {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GhCli")
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
 
 impl GhCli {
     pub fn new() -> Self {
-        Self {}
+        Self::default()
+    }
+
+    /// Authenticate as the account `repo_path` selected if it selected a
+    /// token, and as the `gh auth` account otherwise.
+    pub fn for_repo(repo_path: &Path) -> Self {
+        Self {
+            token: credentials::repo_credential(repo_path)
+                .and_then(|credential| credential.token().map(str::to_string)),
+        }
     }
 
     /// Ensure the GitHub CLI binary is discoverable.
@@ -153,6 +241,10 @@ impl GhCli {
         self.ensure_available()?;
         let gh = resolve_executable_path_blocking("gh").ok_or(GhCliError::NotAvailable)?;
         let mut cmd = Command::new(&gh);
+        if let Some(token) = &self.token {
+            cmd.env("GH_TOKEN", token);
+            cmd.env("GH_ENTERPRISE_TOKEN", token);
+        }
         if let Some(d) = dir {
             cmd.current_dir(d);
         }
@@ -379,6 +471,49 @@ impl GhCli {
         Self::parse_pr_comments(&raw)
     }
 
+    /// Logs of the failed steps of a workflow run.
+    pub fn failed_run_log(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        run_id: i64,
+    ) -> Result<String, GhCliError> {
+        let repo_spec = repo_info.repo_spec();
+        self.run(
+            [
+                "run",
+                "view",
+                &run_id.to_string(),
+                "--repo",
+                &repo_spec,
+                "--log-failed",
+            ],
+            None,
+        )
+    }
+
+    /// Comment on a pull request, returning the comment's URL.
+    pub fn comment_on_pr(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<String, GhCliError> {
+        let repo_spec = repo_info.repo_spec();
+        let raw = self.run(
+            [
+                "pr",
+                "comment",
+                &pr_number.to_string(),
+                "--repo",
+                &repo_spec,
+                "--body",
+                body,
+            ],
+            None,
+        )?;
+        Ok(raw.trim().to_string())
+    }
+
     /// Fetch inline review comments for a pull request via API.
     pub fn get_pr_review_comments(
         &self,
@@ -400,6 +535,134 @@ impl GhCli {
         Self::parse_pr_review_comments(&raw)
     }
 
+    /// Read the classic protection and ruleset rules of a branch. Parts that
+    /// can't be read, usually for lack of admin access, leave the result
+    /// incomplete instead of failing.
+    pub fn get_branch_protection(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        branch: &str,
+    ) -> Result<BranchProtection, GhCliError> {
+        let base = format!("repos/{}/{}", repo_info.owner, repo_info.repo_name);
+        let mut protection = BranchProtection {
+            complete: true,
+            ..Default::default()
+        };
+
+        match self.api(repo_info, &format!("{base}/branches/{branch}")) {
+            Ok(raw) => {
+                let branch_info: GhBranchResponse =
+                    serde_json::from_str(raw.trim()).map_err(|err| {
+                        GhCliError::UnexpectedOutput(format!(
+                            "Failed to parse branch API response: {err}; raw: {raw}"
+                        ))
+                    })?;
+                if branch_info.protected {
+                    Self::apply_classic_summary(&mut protection, branch_info.protection);
+                    match self.api(repo_info, &format!("{base}/branches/{branch}/protection")) {
+                        Ok(raw) => match serde_json::from_str(raw.trim()) {
+                            Ok(details) => Self::apply_classic_protection(&mut protection, details),
+                            Err(_) => protection.complete = false,
+                        },
+                        Err(GhCliError::CommandFailed(_)) => protection.complete = false,
+                        Err(err) => return Err(err),
+                    }
+                }
+            }
+            // The branch doesn't exist on the remote yet, so only rulesets can apply.
+            Err(GhCliError::CommandFailed(msg)) if Self::is_not_found(&msg) => {}
+            Err(err) => return Err(err),
+        }
+
+        match self.api(repo_info, &format!("{base}/rules/branches/{branch}")) {
+            Ok(raw) => match serde_json::from_str::<Vec<GhRule>>(raw.trim()) {
+                Ok(rules) => Self::apply_rules(&mut protection, rules),
+                Err(_) => protection.complete = false,
+            },
+            Err(GhCliError::CommandFailed(_)) => protection.complete = false,
+            Err(err) => return Err(err),
+        }
+
+        Ok(protection)
+    }
+
+    fn api(&self, repo_info: &GitHubRepoInfo, endpoint: &str) -> Result<String, GhCliError> {
+        let mut args = vec!["api".to_string(), endpoint.to_string()];
+        if let Some(ref host) = repo_info.hostname {
+            args.push("--hostname".to_string());
+            args.push(host.clone());
+        }
+        self.run(args, None)
+    }
+
+    fn is_not_found(msg: &str) -> bool {
+        let lower = msg.to_ascii_lowercase();
+        lower.contains("404") || lower.contains("not found")
+    }
+
+    /// What the branch endpoint tells anyone with read access.
+    fn apply_classic_summary(
+        protection: &mut BranchProtection,
+        summary: Option<GhBranchProtectionSummary>,
+    ) {
+        protection.protected = true;
+        // Classic protection blocks force pushes unless explicitly allowed.
+        protection.blocks_force_pushes = true;
+        if let Some(checks) = summary.and_then(|s| s.required_status_checks) {
+            protection.required_status_checks = checks.contexts;
+        }
+    }
+
+    fn apply_classic_protection(protection: &mut BranchProtection, details: GhProtectionResponse) {
+        protection.blocks_force_pushes = !details.allow_force_pushes.is_some_and(|f| f.enabled);
+        if let Some(reviews) = details.required_pull_request_reviews {
+            protection.blocks_direct_pushes = true;
+            protection.required_approving_reviews = reviews.required_approving_review_count;
+            protection.requires_code_owner_reviews = reviews.require_code_owner_reviews;
+        }
+        protection.requires_signed_commits = details.required_signatures.is_some_and(|s| s.enabled);
+        protection.requires_linear_history =
+            details.required_linear_history.is_some_and(|l| l.enabled);
+        if let Some(checks) = details.required_status_checks {
+            protection.required_status_checks = checks.contexts;
+        }
+    }
+
+    fn apply_rules(protection: &mut BranchProtection, rules: Vec<GhRule>) {
+        for rule in rules {
+            match rule.rule_type.as_str() {
+                "non_fast_forward" => protection.blocks_force_pushes = true,
+                "update" => protection.blocks_direct_pushes = true,
+                "required_signatures" => protection.requires_signed_commits = true,
+                "required_linear_history" => protection.requires_linear_history = true,
+                "pull_request" => {
+                    protection.blocks_direct_pushes = true;
+                    if let Some(params) = rule.parameters {
+                        protection.required_approving_reviews = protection
+                            .required_approving_reviews
+                            .max(params.required_approving_review_count);
+                        protection.requires_code_owner_reviews |= params.require_code_owner_review;
+                    }
+                }
+                "required_status_checks" => {
+                    for check in rule
+                        .parameters
+                        .into_iter()
+                        .flat_map(|p| p.required_status_checks)
+                    {
+                        if !protection.required_status_checks.contains(&check.context) {
+                            protection.required_status_checks.push(check.context);
+                        }
+                    }
+                }
+                // Rules about creating, deleting or naming don't affect pushes of
+                // existing branches.
+                _ => continue,
+            }
+            protection.protected = true;
+        }
+    }
+
     pub fn pr_checkout(
         &self,
         repo_path: &Path,
@@ -566,3 +829,57 @@ impl GhCli {
             .collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_ruleset_rules_into_classic_protection() {
+        let mut protection = BranchProtection {
+            complete: true,
+            ..Default::default()
+        };
+        GhCli::apply_classic_summary(
+            &mut protection,
+            Some(GhBranchProtectionSummary {
+                required_status_checks: Some(GhStatusChecks {
+                    contexts: vec!["ci".to_string()],
+                }),
+            }),
+        );
+
+        let rules: Vec<GhRule> = serde_json::from_str(
+            r#"[
+                {"type": "deletion"},
+                {"type": "pull_request", "parameters": {
+                    "required_approving_review_count": 2,
+                    "require_code_owner_review": true,
+                    "dismiss_stale_reviews_on_push": false
+                }},
+                {"type": "required_status_checks", "parameters": {
+                    "required_status_checks": [{"context": "ci"}, {"context": "lint"}],
+                    "strict_required_status_checks_policy": false
+                }},
+                {"type": "required_signatures"}
+            ]"#,
+        )
+        .unwrap();
+        GhCli::apply_rules(&mut protection, rules);
+
+        assert_eq!(
+            protection,
+            BranchProtection {
+                protected: true,
+                blocks_force_pushes: true,
+                blocks_direct_pushes: true,
+                required_approving_reviews: 2,
+                requires_code_owner_reviews: true,
+                requires_signed_commits: true,
+                requires_linear_history: false,
+                required_status_checks: vec!["ci".to_string(), "lint".to_string()],
+                complete: true,
+            }
+        );
+    }
+}
@@ -14,8 +14,8 @@ use tracing::info;
 use crate::{
     GitHostProvider,
     types::{
-        CreatePrRequest, GitHostError, PrComment, PrReviewComment, ProviderKind, PullRequestDetail,
-        UnifiedPrComment,
+        BranchProtection, CreatePrRequest, GitHostError, PrComment, PrReviewComment, ProviderKind,
+        PullRequestDetail, UnifiedPrComment,
     },
 };
 
@@ -36,7 +36,7 @@ impl GitHubProvider {
         remote_url: &str,
         repo_path: &Path,
     ) -> Result<GitHubRepoInfo, GitHostError> {
-        let cli = self.gh_cli.clone();
+        let cli = GhCli::for_repo(repo_path);
         let url = remote_url.to_string();
         let path = repo_path.to_path_buf();
         task::spawn_blocking(move || cli.get_repo_info(&url, &path))
@@ -47,6 +47,46 @@ impl GitHubProvider {
             .map_err(Into::into)
     }
 
+    /// Logs of the failed steps of a GitHub Actions run.
+    pub async fn failed_run_log(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        run_id: i64,
+    ) -> Result<String, GitHostError> {
+        let repo_info = self.get_repo_info(remote_url, repo_path).await?;
+        let cli = GhCli::for_repo(repo_path);
+        task::spawn_blocking(move || cli.failed_run_log(&repo_info, run_id))
+            .await
+            .map_err(|err| {
+                GitHostError::Repository(format!(
+                    "Failed to execute GitHub CLI for fetching run logs: {err}"
+                ))
+            })?
+            .map_err(Into::into)
+    }
+
+    /// Comment on a pull request, returning the comment's URL.
+    pub async fn comment_on_pr(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<String, GitHostError> {
+        let repo_info = self.get_repo_info(remote_url, repo_path).await?;
+        let cli = GhCli::for_repo(repo_path);
+        let body = body.to_string();
+        task::spawn_blocking(move || cli.comment_on_pr(&repo_info, pr_number, &body))
+            .await
+            .map_err(|err| {
+                GitHostError::PullRequest(format!(
+                    "Failed to execute GitHub CLI for commenting on PR: {err}"
+                ))
+            })?
+            .map_err(Into::into)
+    }
+
     async fn fetch_general_comments(
         &self,
         cli: &GhCli,
@@ -180,7 +220,7 @@ impl GitHostProvider for GitHubProvider {
         request_clone.head_branch = head_branch;
 
         (|| async {
-            let cli = self.gh_cli.clone();
+            let cli = GhCli::for_repo(repo_path);
             let request = request_clone.clone();
             let target_repo = target_repo_info.clone();
             let repo_path = repo_path.to_path_buf();
@@ -262,7 +302,7 @@ impl GitHostProvider for GitHubProvider {
     ) -> Result<Vec<PullRequestDetail>, GitHostError> {
         let repo_info = self.get_repo_info(remote_url, repo_path).await?;
 
-        let cli = self.gh_cli.clone();
+        let cli = GhCli::for_repo(repo_path);
         let branch = branch_name.to_string();
 
         (|| async {
@@ -306,8 +346,8 @@ impl GitHostProvider for GitHubProvider {
         let repo_info = self.get_repo_info(remote_url, repo_path).await?;
 
         // Fetch both types of comments in parallel
-        let cli1 = self.gh_cli.clone();
-        let cli2 = self.gh_cli.clone();
+        let cli1 = GhCli::for_repo(repo_path);
+        let cli2 = GhCli::for_repo(repo_path);
 
         let (general_result, review_result) = tokio::join!(
             self.fetch_general_comments(&cli1, &repo_info, pr_number),
@@ -359,7 +399,7 @@ impl GitHostProvider for GitHubProvider {
     ) -> Result<Vec<PullRequestDetail>, GitHostError> {
         let repo_info = self.get_repo_info(remote_url, repo_path).await?;
 
-        let cli = self.gh_cli.clone();
+        let cli = GhCli::for_repo(repo_path);
 
         (|| async {
             let cli = cli.clone();
@@ -393,6 +433,50 @@ impl GitHostProvider for GitHubProvider {
         .await
     }
 
+    async fn get_branch_protection(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        branch_name: &str,
+    ) -> Result<BranchProtection, GitHostError> {
+        let repo_info = self.get_repo_info(remote_url, repo_path).await?;
+
+        let cli = GhCli::for_repo(repo_path);
+        let branch = branch_name.to_string();
+
+        (|| async {
+            let cli = cli.clone();
+            let repo_info = repo_info.clone();
+            let branch = branch.clone();
+
+            let protection =
+                task::spawn_blocking(move || cli.get_branch_protection(&repo_info, &branch))
+                    .await
+                    .map_err(|err| {
+                        GitHostError::Repository(format!(
+                            "Failed to execute GitHub CLI for reading branch protection: {err}"
+                        ))
+                    })?;
+            protection.map_err(GitHostError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHostError| e.should_retry())
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "GitHub API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
     fn provider_kind(&self) -> ProviderKind {
         ProviderKind::GitHub
     }
@@ -10,8 +10,8 @@ use async_trait::async_trait;
 use detection::detect_provider_from_url;
 use enum_dispatch::enum_dispatch;
 pub use types::{
-    CreatePrRequest, GitHostError, PrComment, PrCommentAuthor, PrReviewComment, ProviderKind,
-    PullRequestDetail, ReviewCommentUser, UnifiedPrComment,
+    BranchProtection, CreatePrRequest, GitHostError, PrComment, PrCommentAuthor, PrReviewComment,
+    ProviderKind, PullRequestDetail, ReviewCommentUser, UnifiedPrComment,
 };
 
 use self::{azure::AzureDevOpsProvider, github::GitHubProvider};
@@ -48,6 +48,13 @@ pub trait GitHostProvider: Send + Sync {
         remote_url: &str,
     ) -> Result<Vec<PullRequestDetail>, GitHostError>;
 
+    async fn get_branch_protection(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        branch_name: &str,
+    ) -> Result<BranchProtection, GitHostError>;
+
     fn provider_kind(&self) -> ProviderKind;
 }
 
@@ -33,6 +33,24 @@ pub struct CreatePrRequest {
     pub head_repo_url: Option<String>,
 }
 
+/// The rules a provider enforces on pushes to a branch and on PRs into it,
+/// merged from classic branch protection and rulesets.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct BranchProtection {
+    pub protected: bool,
+    pub blocks_force_pushes: bool,
+    /// Changes must go through a pull request, or updates are restricted.
+    pub blocks_direct_pushes: bool,
+    pub required_approving_reviews: u32,
+    pub requires_code_owner_reviews: bool,
+    pub requires_signed_commits: bool,
+    pub requires_linear_history: bool,
+    pub required_status_checks: Vec<String>,
+    /// False when some of the rules couldn't be read, e.g. classic protection
+    /// details need admin access to the repo.
+    pub complete: bool,
+}
+
 #[derive(Debug, Error)]
 pub enum GitHostError {
     #[error("Repository error: {0}")]
@@ -25,7 +25,7 @@ use std::{
 use thiserror::Error;
 use utils::{path::ALWAYS_SKIP_DIRS, shell::resolve_executable_path_blocking};
 
-use super::Commit;
+use super::{Commit, credentials};
 
 #[derive(Debug, Error)]
 pub enum GitCliError {
@@ -37,6 +37,8 @@ pub enum GitCliError {
     AuthFailed(String),
     #[error("push rejected: {0}")]
     PushRejected(String),
+    #[error("push declined by branch protection: {0}")]
+    ProtectedBranch(String),
     #[error("rebase in progress in this worktree")]
     RebaseInProgress,
 }
@@ -163,6 +165,68 @@ impl GitCli {
         Ok(!out.is_empty())
     }
 
+    /// Commit the worktree's uncommitted changes, untracked files included, on
+    /// top of HEAD without touching the worktree, the real index or any branch.
+    /// Returns `None` when there is nothing to keep.
+    pub fn commit_worktree_snapshot(
+        &self,
+        worktree_path: &Path,
+        message: &str,
+    ) -> Result<Option<String>, GitCliError> {
+        let tmp_dir = tempfile::TempDir::new()
+            .map_err(|e| GitCliError::CommandFailed(format!("temp dir create failed: {e}")))?;
+        let tmp_index = tmp_dir.path().join("index");
+        let envs = vec![(
+            OsString::from("GIT_INDEX_FILE"),
+            tmp_index.as_os_str().to_os_string(),
+        )];
+
+        self.git_with_env(worktree_path, ["read-tree", "HEAD"], &envs)?;
+        self.git_with_env(
+            worktree_path,
+            Self::apply_default_excludes(["add", "-A"]),
+            &envs,
+        )?;
+        let tree = self.git_with_env(worktree_path, ["write-tree"], &envs)?;
+        let tree = tree.trim();
+        let head_tree = self.git(worktree_path, ["rev-parse", "HEAD^{tree}"])?;
+        if tree == head_tree.trim() {
+            return Ok(None);
+        }
+
+        let commit = self.git(
+            worktree_path,
+            ["commit-tree", tree, "-p", "HEAD", "-m", message],
+        )?;
+        Ok(Some(commit.trim().to_string()))
+    }
+
+    /// Apply the changes `commit` made on top of its first parent to the
+    /// worktree, leaving them uncommitted. Nothing changes if they don't apply.
+    pub fn apply_commit_to_worktree(
+        &self,
+        worktree_path: &Path,
+        commit: &str,
+    ) -> Result<(), GitCliError> {
+        let parent = format!("{commit}^");
+        let patch = self.git_impl(
+            worktree_path,
+            ["diff", "--binary", parent.as_str(), commit],
+            None,
+            None,
+        )?;
+        if patch.is_empty() {
+            return Ok(());
+        }
+        self.git_with_stdin(
+            worktree_path,
+            ["apply", "--whitespace=nowarn"],
+            None,
+            &patch,
+        )?;
+        Ok(())
+    }
+
     /// Diff status vs a base branch using a temporary index (always includes untracked).
     /// Path filter limits the reported paths.
     pub fn diff_status(
@@ -349,7 +413,7 @@ impl GitCli {
         remote_url: &str,
         refspec: &str,
     ) -> Result<(), GitCliError> {
-        let envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
+        let envs = self.remote_envs(repo_path);
 
         let args = [
             OsString::from("fetch"),
@@ -377,7 +441,7 @@ impl GitCli {
         } else {
             format!("refs/heads/{branch}:refs/heads/{branch}")
         };
-        let envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
+        let envs = self.remote_envs(repo_path);
 
         let args = [
             OsString::from("push"),
@@ -392,6 +456,47 @@ impl GitCli {
         }
     }
 
+    /// Push a branch with `--force-with-lease`: the remote branch is replaced
+    /// only while it still points at `expected`, or is still missing when
+    /// `expected` is `None`. A stale lease is reported as `PushRejected`.
+    pub fn push_with_lease(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        branch: &str,
+        expected: Option<&str>,
+    ) -> Result<(), GitCliError> {
+        let envs = self.remote_envs(repo_path);
+
+        let args = [
+            OsString::from("push"),
+            OsString::from(format!(
+                "--force-with-lease=refs/heads/{branch}:{}",
+                expected.unwrap_or_default()
+            )),
+            OsString::from(remote_url),
+            OsString::from(format!("refs/heads/{branch}:refs/heads/{branch}")),
+        ];
+
+        match self.git_with_env(repo_path, args, &envs) {
+            Ok(_) => Ok(()),
+            Err(GitCliError::CommandFailed(msg)) => Err(self.classify_cli_error(msg)),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Count the commits in `base..HEAD` that have no signature. Signatures that
+    /// can't be verified locally still count as signed.
+    pub fn count_unsigned_commits(
+        &self,
+        worktree_path: &Path,
+        base: &str,
+    ) -> Result<usize, GitCliError> {
+        let range = format!("{base}..HEAD");
+        let out = self.git(worktree_path, ["log", "--format=%G?", range.as_str()])?;
+        Ok(out.lines().filter(|status| status.trim() == "N").count())
+    }
+
     /// This directly queries the remote without fetching.
     pub fn check_remote_branch_exists(
         &self,
@@ -399,7 +504,7 @@ impl GitCli {
         remote_url: &str,
         branch_name: &str,
     ) -> Result<bool, GitCliError> {
-        let envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
+        let envs = self.remote_envs(repo_path);
 
         let args = [
             OsString::from("ls-remote"),
@@ -694,6 +799,18 @@ impl GitCli {
         self.git(worktree_path, ["revert", "--abort"]).map(|_| ())
     }
 
+    /// Write `git bundle create` for `branch` and return the bundle bytes. The
+    /// bundle holds the branch's full history so it can be cloned on its own.
+    pub fn bundle_create(&self, repo_path: &Path, branch: &str) -> Result<Vec<u8>, GitCliError> {
+        self.git_impl(repo_path, ["bundle", "create", "-", branch], None, None)
+    }
+
+    /// Patch of the worktree (committed and uncommitted changes) against
+    /// `base_sha`, with binary files included.
+    pub fn diff_patch(&self, worktree_path: &Path, base_sha: &str) -> Result<String, GitCliError> {
+        self.git(worktree_path, ["diff", "--binary", base_sha])
+    }
+
     /// List files currently in a conflicted (unmerged) state in the worktree.
     pub fn get_conflicted_files(&self, worktree_path: &Path) -> Result<Vec<String>, GitCliError> {
         // `--diff-filter=U` lists paths with unresolved conflicts
@@ -711,6 +828,16 @@ impl GitCli {
 
 // Private methods
 impl GitCli {
+    /// Environment for commands that talk to a remote: never prompt, and use
+    /// the credential selected for the repository, if any.
+    fn remote_envs(&self, repo_path: &Path) -> Vec<(OsString, OsString)> {
+        let mut envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
+        if let Some(credential) = credentials::repo_credential(repo_path) {
+            envs.extend(credential.env());
+        }
+        envs
+    }
+
     fn classify_cli_error(&self, msg: String) -> GitCliError {
         let lower = msg.to_ascii_lowercase();
         if lower.contains("authentication failed")
@@ -718,6 +845,14 @@ impl GitCli {
             || lower.contains("invalid username or password")
         {
             GitCliError::AuthFailed(msg)
+        } else if lower.contains("protected branch")
+            || lower.contains("gh006")
+            || lower.contains("gh013")
+            || lower.contains("repository rule violations")
+        {
+            // Checked before `PushRejected`, as these also print "failed to push some refs"
+            // but force pushing won't help.
+            GitCliError::ProtectedBranch(msg)
         } else if lower.contains("non-fast-forward")
             || lower.contains("failed to push some refs")
             || lower.contains("fetch first")
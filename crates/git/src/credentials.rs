@@ -0,0 +1,178 @@
+//! Credentials selected per repository for network operations.
+//!
+//! Fetches, pushes and `ls-remote` calls of a repository, or of any of its
+//! worktrees, run with the environment of the credential registered for it,
+//! instead of whatever identity git would pick up implicitly. Repos without a
+//! registered credential keep using the user's git setup.
+
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    fmt,
+    path::{Path, PathBuf},
+    sync::{LazyLock, RwLock},
+};
+
+use git2::Repository;
+
+static REPO_CREDENTIALS: LazyLock<RwLock<HashMap<PathBuf, GitCredential>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+const USERNAME_ENV: &str = "VK_GIT_USERNAME";
+const TOKEN_ENV: &str = "VK_GIT_TOKEN";
+
+#[derive(Clone, PartialEq, Eq)]
+pub enum GitCredential {
+    /// Authenticate over SSH with this private key only.
+    SshKey { private_key_path: PathBuf },
+    /// Authenticate over HTTPS with a personal access or OAuth token.
+    Token { username: String, token: String },
+}
+
+impl fmt::Debug for GitCredential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SshKey { private_key_path } => f
+                .debug_struct("SshKey")
+                .field("private_key_path", private_key_path)
+                .finish(),
+            Self::Token { username, .. } => f
+                .debug_struct("Token")
+                .field("username", username)
+                .field("token", &"<redacted>")
+                .finish(),
+        }
+    }
+}
+
+impl GitCredential {
+    /// The token, for host CLIs that authenticate with one (`gh`, `az`).
+    pub fn token(&self) -> Option<&str> {
+        match self {
+            Self::Token { token, .. } => Some(token),
+            Self::SshKey { .. } => None,
+        }
+    }
+
+    /// Environment for a git process using this credential. Tokens are handed
+    /// to git by an inline credential helper reading them from the
+    /// environment, so they never appear in arguments or config files.
+    pub fn env(&self) -> Vec<(OsString, OsString)> {
+        match self {
+            Self::SshKey { private_key_path } => {
+                let path = private_key_path.to_string_lossy().replace('\'', r"'\''");
+                vec![(
+                    OsString::from("GIT_SSH_COMMAND"),
+                    OsString::from(format!("ssh -i '{path}' -o IdentitiesOnly=yes")),
+                )]
+            }
+            Self::Token { username, token } => vec![
+                // An empty helper resets the helpers configured by the user,
+                // so only the selected token is offered.
+                (OsString::from("GIT_CONFIG_COUNT"), OsString::from("2")),
+                (
+                    OsString::from("GIT_CONFIG_KEY_0"),
+                    OsString::from("credential.helper"),
+                ),
+                (OsString::from("GIT_CONFIG_VALUE_0"), OsString::new()),
+                (
+                    OsString::from("GIT_CONFIG_KEY_1"),
+                    OsString::from("credential.helper"),
+                ),
+                (
+                    OsString::from("GIT_CONFIG_VALUE_1"),
+                    OsString::from(format!(
+                        "!f() {{ test \"$1\" = get && echo \"username=${USERNAME_ENV}\" && echo \"password=${TOKEN_ENV}\"; }}; f"
+                    )),
+                ),
+                (OsString::from(USERNAME_ENV), OsString::from(username)),
+                (OsString::from(TOKEN_ENV), OsString::from(token)),
+            ],
+        }
+    }
+}
+
+/// Worktrees share the common dir of their repository, so credentials are
+/// keyed by it.
+pub(crate) fn registry_key(path: &Path) -> PathBuf {
+    let common_dir = Repository::open(path)
+        .map(|repo| repo.commondir().to_path_buf())
+        .unwrap_or_else(|_| path.to_path_buf());
+    std::fs::canonicalize(&common_dir).unwrap_or(common_dir)
+}
+
+/// Select the credential network operations of `repo_path` use, or clear it.
+pub fn set_repo_credential(repo_path: &Path, credential: Option<GitCredential>) {
+    let key = registry_key(repo_path);
+    let mut credentials = REPO_CREDENTIALS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match credential {
+        Some(credential) => {
+            credentials.insert(key, credential);
+        }
+        None => {
+            credentials.remove(&key);
+        }
+    }
+}
+
+/// The credential registered for the repository `path` belongs to. `path` may
+/// be the repository, one of its worktrees or its git dir.
+pub fn repo_credential(path: &Path) -> Option<GitCredential> {
+    let credentials = REPO_CREDENTIALS
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if credentials.is_empty() {
+        return None;
+    }
+    credentials.get(&registry_key(path)).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_env_does_not_leak_the_token_into_config() {
+        let env = GitCredential::Token {
+            username: "x-access-token".to_string(),
+            token: "ghp_secret".to_string(),
+        }
+        .env();
+
+        let helper = env
+            .iter()
+            .find(|(key, _)| key == "GIT_CONFIG_VALUE_1")
+            .map(|(_, value)| value.to_string_lossy().into_owned())
+            .unwrap();
+        assert!(!helper.contains("ghp_secret"));
+        assert!(
+            env.iter()
+                .any(|(key, value)| key == TOKEN_ENV && value == "ghp_secret")
+        );
+    }
+
+    #[test]
+    fn worktrees_share_the_credential_of_their_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo_path = dir.path().join("repo");
+        let repo = Repository::init(&repo_path).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+            .unwrap();
+        let worktree_path = dir.path().join("wt");
+        repo.worktree("wt", &worktree_path, None).unwrap();
+
+        let credential = GitCredential::SshKey {
+            private_key_path: PathBuf::from("/keys/id_work"),
+        };
+        set_repo_credential(&repo_path, Some(credential.clone()));
+        assert_eq!(repo_credential(&worktree_path), Some(credential));
+
+        set_repo_credential(&repo_path, None);
+        assert_eq!(repo_credential(&worktree_path), None);
+    }
+}
@@ -1,5 +1,7 @@
 use std::{
     collections::{HashMap, HashSet},
+    ffi::OsStr,
+    hash::{DefaultHasher, Hash, Hasher},
     path::{Path, PathBuf},
 };
 
@@ -8,13 +10,16 @@ use git2::{BranchType, DiffOptions, Error as GitError, Reference, Remote, Reposi
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use ts_rs::TS;
-use utils::diff::{Diff, DiffChangeKind};
+use utils::diff::{Diff, DiffChangeKind, DiffOmittedReason};
 
 mod cli;
+pub mod credentials;
+mod repo_lock;
 mod validation;
 
 use cli::{ChangeType, StatusDiffEntry, StatusDiffOptions};
 pub use cli::{GitCli, GitCliError, StatusEntry, WorktreeStatus};
+pub use repo_lock::{RepoLockGuard, RepoLockStats};
 pub use utils::path::ALWAYS_SKIP_DIRS;
 pub use validation::is_valid_branch_prefix;
 
@@ -52,15 +57,27 @@ pub enum GitServiceError {
     WorktreeDirty(String, String),
     #[error("Rebase in progress; resolve or abort it before retrying")]
     RebaseInProgress,
+    #[error("Snapshot not found: {0}")]
+    SnapshotNotFound(String),
+    #[error("Snapshot doesn't apply to the current worktree: {0}")]
+    SnapshotDoesNotApply(String),
+    #[error("Remote branch has {behind} commit(s) that were not pushed from here")]
+    RemoteBranchMoved { ahead: usize, behind: usize },
 }
 
 /// Service for managing Git operations in task execution workflows
 #[derive(Clone)]
 pub struct GitService {}
 
-// Max inline diff size for UI (in bytes). Files larger than this will have
-// their contents omitted from the diff stream to avoid UI crashes.
-const MAX_INLINE_DIFF_BYTES: usize = 2 * 1024 * 1024; // ~2MB
+/// Snapshots live under `refs/vibe-kanban/snapshots/<branch>/<id>` in the
+/// repository's shared refs, so they outlive the worktree they were taken in.
+const SNAPSHOT_REF_PREFIX: &str = "refs/vibe-kanban/snapshots/";
+const MAX_SNAPSHOTS_PER_BRANCH: usize = 20;
+const UNDO_REF_PREFIX: &str = "refs/vibe-kanban/undo/";
+
+// Default max inline diff size for UI (in bytes). Files larger than this will
+// have their contents omitted from the diff stream to avoid UI crashes.
+pub const MAX_INLINE_DIFF_BYTES: usize = 2 * 1024 * 1024; // ~2MB
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -81,12 +98,59 @@ pub struct GitBranch {
     pub last_commit_date: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone)]
+pub struct GitCommitSummary {
+    pub sha: String,
+    pub summary: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub committed_at: DateTime<Utc>,
+}
+
+/// Commits reachable from HEAD, newest first.
+#[derive(Debug, Clone)]
+pub struct CommitLog {
+    pub commits: Vec<GitCommitSummary>,
+    /// Whether `commits` only holds the commits added on top of the commit
+    /// the walk was asked to stop at. Otherwise HEAD moved elsewhere and
+    /// `commits` is the full log up to the limit.
+    pub incremental: bool,
+}
+
 #[derive(Debug, Clone, Serialize, TS)]
 pub struct GitRemote {
     pub name: String,
     pub url: String,
 }
 
+/// Outcome of a push with `--force-with-lease`. `ahead` and `behind` compare
+/// the pushed branch with the remote branch it replaced; `behind` counts the
+/// remote commits the push dropped.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct LeasedPush {
+    pub remote: String,
+    /// Set when upstream tracking was configured, e.g. `origin/vk/feature`.
+    pub upstream: Option<String>,
+    /// The branch did not exist on the remote before.
+    pub created: bool,
+    pub ahead: usize,
+    pub behind: usize,
+}
+
+/// Uncommitted changes of a worktree, kept as a commit on top of the HEAD they
+/// were made against.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct WorktreeSnapshot {
+    pub id: String,
+    pub branch: String,
+    pub commit: String,
+    pub base_commit: String,
+    /// Why the snapshot was taken, e.g. "Before rebase onto main".
+    pub reason: String,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone)]
 pub struct HeadInfo {
     pub branch: String,
@@ -135,10 +199,14 @@ impl WorktreeResetOptions {
     }
 }
 
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone)]
 pub struct WorktreeResetOutcome {
     pub needed: bool,
     pub applied: bool,
+    /// HEAD before an applied reset.
+    pub previous_head: Option<String>,
+    /// Uncommitted changes an applied reset discarded.
+    pub snapshot: Option<WorktreeSnapshot>,
 }
 
 impl Default for GitService {
@@ -157,6 +225,19 @@ impl GitService {
         git2::Branch::name_is_valid(name).unwrap_or(false)
     }
 
+    /// Hold the locks of the repositories `paths` belong to until the guard
+    /// is dropped, for sequences of operations that must not interleave with
+    /// other sessions'. Network operations don't take the lock: holding it
+    /// for a whole push or fetch would stall every session in the repo.
+    pub fn lock_repos(&self, paths: &[&Path]) -> RepoLockGuard {
+        repo_lock::lock_repos(paths)
+    }
+
+    /// How long operations have waited for repository locks.
+    pub fn repo_lock_stats(&self) -> Vec<RepoLockStats> {
+        repo_lock::lock_stats()
+    }
+
     /// Open the repository
     pub(crate) fn open_repo(&self, repo_path: &Path) -> Result<Repository, GitServiceError> {
         Repository::open(repo_path).map_err(GitServiceError::from)
@@ -196,6 +277,7 @@ impl GitService {
         new_branch_name: &str,
         base_branch_name: &str,
     ) -> Result<(), GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[repo_path]);
         let repo = self.open_repo(repo_path)?;
         let base_ref = Self::find_branch(&repo, base_branch_name)?.into_reference();
         let commit = base_ref.peel_to_commit()?;
@@ -203,6 +285,20 @@ impl GitService {
         Ok(())
     }
 
+    /// Create a new local branch pointing at `commit_sha`.
+    pub fn create_branch_at_commit(
+        &self,
+        repo_path: &Path,
+        new_branch_name: &str,
+        commit_sha: &str,
+    ) -> Result<(), GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[repo_path]);
+        let repo = self.open_repo(repo_path)?;
+        let commit = repo.find_commit(git2::Oid::from_str(commit_sha)?)?;
+        repo.branch(new_branch_name, &commit, false)?;
+        Ok(())
+    }
+
     /// Ensure local (repo-scoped) identity exists for CLI commits.
     /// Sets user.name/email only if missing in the repo config.
     fn ensure_cli_commit_identity(&self, repo_path: &Path) -> Result<(), GitServiceError> {
@@ -304,6 +400,7 @@ impl GitService {
     }
 
     pub fn commit(&self, path: &Path, message: &str) -> Result<bool, GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[path]);
         // Use Git CLI to respect sparse-checkout semantics for staging and commit
         let git = GitCli::new();
         let has_changes = git
@@ -329,6 +426,23 @@ impl GitService {
         worktree_path: &Path,
         base_commit: &Commit,
         path_filter: Option<&[&str]>,
+    ) -> Result<Vec<Diff>, GitServiceError> {
+        self.get_diffs_with_max_file_size(
+            worktree_path,
+            base_commit,
+            path_filter,
+            MAX_INLINE_DIFF_BYTES,
+        )
+    }
+
+    /// Like `get_diffs`, omitting the contents of files larger than
+    /// `max_file_bytes` on either side.
+    pub fn get_diffs_with_max_file_size(
+        &self,
+        worktree_path: &Path,
+        base_commit: &Commit,
+        path_filter: Option<&[&str]>,
+        max_file_bytes: usize,
     ) -> Result<Vec<Diff>, GitServiceError> {
         // Use Git CLI to compute diff vs base to avoid sparse false deletions
         let repo = Repository::open(worktree_path)?;
@@ -348,7 +462,7 @@ impl GitService {
             .map_err(|e| GitServiceError::InvalidRepository(format!("git diff failed: {e}")))?;
         Ok(entries
             .into_iter()
-            .map(|e| Self::status_entry_to_diff(&repo, &base_tree, e))
+            .map(|e| Self::status_entry_to_diff(&repo, &base_tree, e, max_file_bytes))
             .collect())
     }
 
@@ -379,23 +493,11 @@ impl GitService {
             .unwrap_or_default()
     }
 
-    /// Helper function to convert blob to string content
-    fn blob_to_string(blob: &git2::Blob) -> Option<String> {
-        if blob.is_binary() {
-            None // Skip binary files
-        } else {
-            std::str::from_utf8(blob.content())
-                .ok()
-                .map(|s| s.to_string())
-        }
-    }
-
-    /// Helper function to read file content from filesystem with safety guards
-    fn read_file_to_string(repo: &Repository, rel_path: &Path) -> Option<String> {
+    /// Helper function to read file bytes from filesystem with a size guard
+    fn read_file_bytes(repo: &Repository, rel_path: &Path, max_bytes: usize) -> Option<Vec<u8>> {
         let workdir = repo.workdir()?;
         let abs_path = workdir.join(rel_path);
 
-        // Read file from filesystem
         let bytes = match std::fs::read(&abs_path) {
             Ok(bytes) => bytes,
             Err(e) => {
@@ -404,8 +506,8 @@ impl GitService {
             }
         };
 
-        // Size guard - skip files larger than UI inline threshold
-        if bytes.len() > MAX_INLINE_DIFF_BYTES {
+        // The file may have grown since it was stat'ed
+        if bytes.len() > max_bytes {
             tracing::debug!(
                 "Skipping large file ({}KB): {:?}",
                 bytes.len() / 1024,
@@ -413,26 +515,22 @@ impl GitService {
             );
             return None;
         }
+        Some(bytes)
+    }
 
-        // Binary guard - skip files containing null bytes
-        if bytes.contains(&0) {
-            tracing::debug!("Skipping binary file: {:?}", abs_path);
-            return None;
-        }
-
-        // UTF-8 validation
-        match String::from_utf8(bytes) {
-            Ok(content) => Some(content),
-            Err(e) => {
-                tracing::debug!("File is not valid UTF-8: {:?}: {}", abs_path, e);
-                None
-            }
-        }
+    /// Same heuristic as git: a null byte in the first 8000 bytes.
+    fn is_binary_content(bytes: &[u8]) -> bool {
+        bytes[..bytes.len().min(8000)].contains(&0)
     }
 
     /// Create Diff entries from git_cli::StatusDiffEntry
     /// New Diff format is flattened with change kind, paths, and optional contents.
-    fn status_entry_to_diff(repo: &Repository, base_tree: &git2::Tree, e: StatusDiffEntry) -> Diff {
+    fn status_entry_to_diff(
+        repo: &Repository,
+        base_tree: &git2::Tree,
+        e: StatusDiffEntry,
+        max_file_bytes: usize,
+    ) -> Diff {
         // Map ChangeType to DiffChangeKind
         let mut change = match e.change {
             ChangeType::Added => DiffChangeKind::Added,
@@ -457,57 +555,56 @@ impl GitService {
             ChangeType::Unknown(_) => (e.old_path.clone(), Some(e.path.clone())),
         };
 
-        // Decide if we should omit content by size (either side)
-        let mut content_omitted = false;
         // Old side (from base tree)
-        if let Some(ref oldp) = old_path_opt {
-            let rel = std::path::Path::new(oldp);
-            if let Ok(entry) = base_tree.get_path(rel)
-                && entry.kind() == Some(git2::ObjectType::Blob)
-                && let Ok(blob) = repo.find_blob(entry.id())
-                && !blob.is_binary()
-                && blob.size() > MAX_INLINE_DIFF_BYTES
-            {
-                content_omitted = true;
+        let old_blob = old_path_opt.as_ref().and_then(|oldp| {
+            let entry = base_tree.get_path(std::path::Path::new(oldp)).ok()?;
+            if entry.kind() != Some(git2::ObjectType::Blob) {
+                return None;
             }
-        }
-        // New side (from filesystem)
-        if let Some(ref newp) = new_path_opt
-            && let Some(workdir) = repo.workdir()
-        {
-            let abs = workdir.join(newp);
-            if let Ok(md) = std::fs::metadata(&abs)
-                && (md.len() as usize) > MAX_INLINE_DIFF_BYTES
-            {
-                content_omitted = true;
+            repo.find_blob(entry.id()).ok()
+        });
+        // New side (from filesystem), only read when within the size limit
+        let new_size = new_path_opt.as_ref().and_then(|newp| {
+            let abs = repo.workdir()?.join(newp);
+            std::fs::metadata(abs).ok().map(|md| md.len() as usize)
+        });
+
+        let too_large = old_blob
+            .as_ref()
+            .is_some_and(|blob| blob.size() > max_file_bytes)
+            || new_size.is_some_and(|size| size > max_file_bytes);
+        let new_bytes = match new_path_opt {
+            Some(ref newp) if !too_large => {
+                Self::read_file_bytes(repo, std::path::Path::new(newp), max_file_bytes)
             }
-        }
+            _ => None,
+        };
+        let is_binary = old_blob.as_ref().is_some_and(|blob| blob.is_binary())
+            || new_bytes.as_deref().is_some_and(Self::is_binary_content);
+
+        let omitted_reason = if too_large {
+            Some(DiffOmittedReason::TooLarge)
+        } else if is_binary {
+            Some(DiffOmittedReason::Binary)
+        } else {
+            None
+        };
 
         // Load contents only if not omitted
-        let (old_content, new_content) = if content_omitted {
+        let (old_content, new_content) = if omitted_reason.is_some() {
             (None, None)
         } else {
-            // Load old content from base tree if possible
-            let old_content = if let Some(ref oldp) = old_path_opt {
-                let rel = std::path::Path::new(oldp);
-                match base_tree.get_path(rel) {
-                    Ok(entry) if entry.kind() == Some(git2::ObjectType::Blob) => repo
-                        .find_blob(entry.id())
-                        .ok()
-                        .and_then(|b| Self::blob_to_string(&b)),
-                    _ => None,
+            let old_content = old_blob
+                .as_ref()
+                .and_then(|blob| std::str::from_utf8(blob.content()).ok())
+                .map(|s| s.to_string());
+            let new_content = new_bytes.and_then(|bytes| match String::from_utf8(bytes) {
+                Ok(content) => Some(content),
+                Err(e) => {
+                    tracing::debug!("File is not valid UTF-8: {:?}: {}", new_path_opt, e);
+                    None
                 }
-            } else {
-                None
-            };
-
-            // Load new content from filesystem (worktree) when available
-            let new_content = if let Some(ref newp) = new_path_opt {
-                let rel = std::path::Path::new(newp);
-                Self::read_file_to_string(repo, rel)
-            } else {
-                None
-            };
+            });
             (old_content, new_content)
         };
 
@@ -543,7 +640,9 @@ impl GitService {
             new_path: new_path_opt,
             old_content,
             new_content,
-            content_omitted,
+            content_omitted: omitted_reason.is_some(),
+            omitted_reason,
+            is_binary,
             additions,
             deletions,
             repo_id: None,
@@ -580,6 +679,7 @@ impl GitService {
         base_branch_name: &str,
         commit_message: &str,
     ) -> Result<String, GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[base_worktree_path, task_worktree_path]);
         // Open the repositories
         let task_repo = self.open_repo(task_worktree_path)?;
         let base_repo = self.open_repo(base_worktree_path)?;
@@ -706,6 +806,38 @@ impl GitService {
         )
     }
 
+    /// Paths that would conflict when merging `branch_name` into
+    /// `base_branch_name`. The merge runs in memory, so no worktree is touched;
+    /// an empty list means the branch merges cleanly.
+    pub fn merge_conflicts(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        base_branch_name: &str,
+    ) -> Result<Vec<String>, GitServiceError> {
+        let repo = Repository::open(repo_path)?;
+        let branch = Self::find_branch(&repo, branch_name)?;
+        let base_branch = Self::find_branch(&repo, base_branch_name)?;
+        let index = repo.merge_commits(
+            &base_branch.get().peel_to_commit()?,
+            &branch.get().peel_to_commit()?,
+            None,
+        )?;
+        if !index.has_conflicts() {
+            return Ok(Vec::new());
+        }
+
+        let mut paths: Vec<String> = index
+            .conflicts()?
+            .filter_map(Result::ok)
+            .filter_map(|conflict| conflict.our.or(conflict.their).or(conflict.ancestor))
+            .map(|entry| String::from_utf8_lossy(&entry.path).into_owned())
+            .collect();
+        paths.sort();
+        paths.dedup();
+        Ok(paths)
+    }
+
     pub fn get_base_commit(
         &self,
         repo_path: &Path,
@@ -895,6 +1027,7 @@ impl GitService {
         target_commit_oid: &str,
         options: WorktreeResetOptions,
     ) -> WorktreeResetOutcome {
+        let _lock = repo_lock::lock_repos(&[worktree_path]);
         let WorktreeResetOptions {
             perform_reset,
             force_when_dirty,
@@ -913,14 +1046,19 @@ impl GitService {
                     if log_skip_when_dirty {
                         tracing::warn!("Worktree dirty; skipping reset as not forced");
                     }
-                } else if let Err(e) = self.reset_worktree_to_commit(
-                    worktree_path,
-                    target_commit_oid,
-                    force_when_dirty,
-                ) {
-                    tracing::error!("Failed to reset worktree: {}", e);
                 } else {
-                    outcome.applied = true;
+                    match self.reset_worktree_to_commit(
+                        worktree_path,
+                        target_commit_oid,
+                        force_when_dirty,
+                    ) {
+                        Ok(snapshot) => {
+                            outcome.applied = true;
+                            outcome.previous_head = head_oid;
+                            outcome.snapshot = snapshot;
+                        }
+                        Err(e) => tracing::error!("Failed to reset worktree: {}", e),
+                    }
                 }
             }
         }
@@ -930,17 +1068,23 @@ impl GitService {
 
     /// Reset the given worktree to the specified commit SHA.
     /// If `force` is false and the worktree is dirty, returns WorktreeDirty error.
+    /// If `force` is true, uncommitted changes are snapshotted before being
+    /// discarded, and the reset is refused if that fails. Returns the snapshot.
     pub fn reset_worktree_to_commit(
         &self,
         worktree_path: &Path,
         commit_sha: &str,
         force: bool,
-    ) -> Result<(), GitServiceError> {
+    ) -> Result<Option<WorktreeSnapshot>, GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[worktree_path]);
         let repo = self.open_repo(worktree_path)?;
-        if !force {
+        let snapshot = if !force {
             // Avoid clobbering uncommitted changes unless explicitly forced
             self.check_worktree_clean(&repo)?;
-        }
+            None
+        } else {
+            self.snapshot_worktree(worktree_path, &format!("Before reset to {commit_sha:.7}"))?
+        };
         let cli = GitCli::new();
         cli.git(worktree_path, ["reset", "--hard", commit_sha])
             .map_err(|e| {
@@ -953,9 +1097,169 @@ impl GitService {
         }
         // Reapply sparse-checkout if configured (non-fatal)
         let _ = cli.git(worktree_path, ["sparse-checkout", "reapply"]);
+        Ok(snapshot)
+    }
+
+    /// Keep `commit` reachable under the undo ref `name`, so it survives branch
+    /// deletion and garbage collection until [`Self::drop_undo_ref`].
+    pub fn keep_undo_ref(
+        &self,
+        repo_path: &Path,
+        name: &str,
+        commit: &str,
+    ) -> Result<(), GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[repo_path]);
+        let repo = self.open_repo(repo_path)?;
+        repo.reference(
+            &format!("{UNDO_REF_PREFIX}{name}"),
+            git2::Oid::from_str(commit)?,
+            true,
+            "keep for undo",
+        )?;
         Ok(())
     }
 
+    pub fn drop_undo_ref(&self, repo_path: &Path, name: &str) -> Result<(), GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[repo_path]);
+        let repo = self.open_repo(repo_path)?;
+        match repo.find_reference(&format!("{UNDO_REF_PREFIX}{name}")) {
+            Ok(mut reference) => Ok(reference.delete()?),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Keep the worktree's uncommitted changes, untracked files included, as a
+    /// snapshot of its branch. Returns `None` when there is nothing to keep.
+    /// Only the newest snapshots of each branch are kept.
+    pub fn snapshot_worktree(
+        &self,
+        worktree_path: &Path,
+        reason: &str,
+    ) -> Result<Option<WorktreeSnapshot>, GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[worktree_path]);
+        let git = GitCli::new();
+        if !git.has_changes(worktree_path)? {
+            return Ok(None);
+        }
+        self.ensure_cli_commit_identity(worktree_path)?;
+        let Some(commit) = git.commit_worktree_snapshot(worktree_path, reason)? else {
+            return Ok(None);
+        };
+
+        let repo = self.open_repo(worktree_path)?;
+        let branch = self.get_head_info(worktree_path)?.branch;
+        let id = format!("{}-{:.8}", Utc::now().timestamp_millis(), commit);
+        repo.reference(
+            &format!("{SNAPSHOT_REF_PREFIX}{branch}/{id}"),
+            git2::Oid::from_str(&commit)?,
+            false,
+            reason,
+        )?;
+        tracing::info!(
+            "Snapshotted uncommitted changes of {} as {}",
+            worktree_path.display(),
+            id
+        );
+
+        let snapshots = Self::branch_snapshots(&repo, &branch)?;
+        for (refname, _) in snapshots.into_iter().skip(MAX_SNAPSHOTS_PER_BRANCH) {
+            if let Ok(mut reference) = repo.find_reference(&refname) {
+                reference.delete()?;
+            }
+        }
+        self.find_snapshot(worktree_path, &branch, &id).map(Some)
+    }
+
+    /// Snapshots of `branch_name`, newest first.
+    pub fn list_snapshots(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<Vec<WorktreeSnapshot>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        Ok(Self::branch_snapshots(&repo, branch_name)?
+            .into_iter()
+            .map(|(_, snapshot)| snapshot)
+            .collect())
+    }
+
+    pub fn find_snapshot(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        snapshot_id: &str,
+    ) -> Result<WorktreeSnapshot, GitServiceError> {
+        self.list_snapshots(repo_path, branch_name)?
+            .into_iter()
+            .find(|snapshot| snapshot.id == snapshot_id)
+            .ok_or_else(|| GitServiceError::SnapshotNotFound(snapshot_id.to_string()))
+    }
+
+    /// Re-apply a snapshot's changes to the worktree as uncommitted changes.
+    /// The worktree must not have uncommitted changes to tracked files, and
+    /// nothing changes if the snapshot doesn't apply to the current HEAD.
+    pub fn restore_snapshot(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        snapshot_id: &str,
+    ) -> Result<WorktreeSnapshot, GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[worktree_path]);
+        let snapshot = self.find_snapshot(worktree_path, branch_name, snapshot_id)?;
+        let repo = self.open_repo(worktree_path)?;
+        self.check_worktree_clean(&repo)?;
+        GitCli::new()
+            .apply_commit_to_worktree(worktree_path, &snapshot.commit)
+            .map_err(|e| match e {
+                GitCliError::CommandFailed(msg) => GitServiceError::SnapshotDoesNotApply(msg),
+                e => e.into(),
+            })?;
+        Ok(snapshot)
+    }
+
+    /// `(refname, snapshot)` pairs of a branch, newest first.
+    fn branch_snapshots(
+        repo: &Repository,
+        branch_name: &str,
+    ) -> Result<Vec<(String, WorktreeSnapshot)>, GitServiceError> {
+        let prefix = format!("{SNAPSHOT_REF_PREFIX}{branch_name}/");
+        let mut snapshots = Vec::new();
+        for reference in repo.references_glob(&format!("{SNAPSHOT_REF_PREFIX}*"))? {
+            let reference = reference?;
+            let Some(refname) = reference.name() else {
+                continue;
+            };
+            // Branch names may contain slashes, so match the exact prefix
+            // instead of relying on the glob.
+            let Some(id) = refname.strip_prefix(&prefix) else {
+                continue;
+            };
+            if id.contains('/') {
+                continue;
+            }
+            let commit = reference.peel_to_commit()?;
+            let created_at =
+                DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or_else(Utc::now);
+            snapshots.push((
+                refname.to_string(),
+                WorktreeSnapshot {
+                    id: id.to_string(),
+                    branch: branch_name.to_string(),
+                    commit: commit.id().to_string(),
+                    base_commit: commit
+                        .parent_id(0)
+                        .map(|oid| oid.to_string())
+                        .unwrap_or_default(),
+                    reason: commit.summary().unwrap_or_default().to_string(),
+                    created_at,
+                },
+            ));
+        }
+        snapshots.sort_by(|a, b| b.1.id.cmp(&a.1.id));
+        Ok(snapshots)
+    }
+
     /// Add a worktree for a branch, optionally creating the branch
     pub fn add_worktree(
         &self,
@@ -964,12 +1268,81 @@ impl GitService {
         branch: &str,
         create_branch: bool,
     ) -> Result<(), GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[repo_path]);
         let git = GitCli::new();
         git.worktree_add(repo_path, worktree_path, branch, create_branch)
             .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?;
         Ok(())
     }
 
+    /// Register a worktree for an existing branch without checking out any
+    /// files, for callers that populate the directory themselves. Follow with
+    /// [`Self::sync_worktree_to_head`].
+    pub fn add_worktree_without_checkout(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+        branch: &str,
+    ) -> Result<(), GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[repo_path]);
+        let git = GitCli::new();
+        git.git(
+            repo_path,
+            [
+                OsStr::new("worktree"),
+                OsStr::new("add"),
+                OsStr::new("--no-checkout"),
+                worktree_path.as_os_str(),
+                OsStr::new(branch),
+            ],
+        )
+        .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Make the files of a worktree match its HEAD, rewriting only the files
+    /// that differ and removing untracked ones. Ignored files are kept.
+    pub fn sync_worktree_to_head(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[worktree_path]);
+        let git = GitCli::new();
+        // Building the index first lets the hard reset skip files that are
+        // already up to date
+        for args in [
+            &["reset", "-q", "--mixed", "HEAD"][..],
+            &["reset", "-q", "--hard", "HEAD"],
+            &["clean", "-fdq"],
+        ] {
+            git.git(worktree_path, args)
+                .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?;
+        }
+        let _ = git.git(worktree_path, ["sparse-checkout", "reapply"]);
+        Ok(())
+    }
+
+    /// Point the branch checked out in a worktree at `commit` and make its
+    /// files match, keeping ignored files such as installed dependencies.
+    pub fn move_worktree_branch_to_commit(
+        &self,
+        worktree_path: &Path,
+        commit: &str,
+    ) -> Result<(), GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[worktree_path]);
+        {
+            let repo = self.open_repo(worktree_path)?;
+            let head = repo.head()?;
+            let branch_ref = head
+                .name()
+                .filter(|name| name.starts_with("refs/heads/"))
+                .ok_or_else(|| {
+                    GitServiceError::InvalidRepository("Worktree HEAD is detached".to_string())
+                })?
+                .to_string();
+            let oid = git2::Oid::from_str(commit)?;
+            repo.reference(&branch_ref, oid, true, "vibe-kanban: move worktree branch")?;
+        }
+        self.sync_worktree_to_head(worktree_path)
+    }
+
     /// Remove a worktree
     pub fn remove_worktree(
         &self,
@@ -977,6 +1350,7 @@ impl GitService {
         worktree_path: &Path,
         force: bool,
     ) -> Result<(), GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[repo_path]);
         let git = GitCli::new();
         git.worktree_remove(repo_path, worktree_path, force)
             .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?;
@@ -990,6 +1364,7 @@ impl GitService {
         old_path: &Path,
         new_path: &Path,
     ) -> Result<(), GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[repo_path]);
         let git = GitCli::new();
         git.worktree_move(repo_path, old_path, new_path)
             .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?;
@@ -997,6 +1372,7 @@ impl GitService {
     }
 
     pub fn prune_worktrees(&self, repo_path: &Path) -> Result<(), GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[repo_path]);
         let git = GitCli::new();
         git.worktree_prune(repo_path)
             .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?;
@@ -1008,6 +1384,7 @@ impl GitService {
         repo_path: &Path,
         branch_name: &str,
     ) -> Result<(), GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[repo_path]);
         let git = GitCli::new();
         git.delete_branch(repo_path, branch_name)
             .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?;
@@ -1078,6 +1455,88 @@ impl GitService {
         Ok(branches)
     }
 
+    /// Identifies the current state of HEAD and every ref, to tell whether
+    /// anything changed since it was last taken. Only reads refs.
+    pub fn refs_fingerprint(&self, repo_path: &Path) -> Result<String, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let mut refs = Vec::new();
+        for reference in repo.references()? {
+            let reference = reference?;
+            let target = reference
+                .target()
+                .map(|oid| oid.to_string())
+                .or_else(|| reference.symbolic_target().map(str::to_string))
+                .unwrap_or_default();
+            refs.push((
+                String::from_utf8_lossy(reference.name_bytes()).into_owned(),
+                target,
+            ));
+        }
+        refs.sort();
+        let head = repo
+            .find_reference("HEAD")?
+            .symbolic_target()
+            .map(str::to_string)
+            .or_else(|| {
+                repo.head()
+                    .ok()
+                    .and_then(|h| h.target())
+                    .map(|oid| oid.to_string())
+            })
+            .unwrap_or_default();
+
+        let mut hasher = DefaultHasher::new();
+        (head, refs).hash(&mut hasher);
+        Ok(format!("{:016x}", hasher.finish()))
+    }
+
+    /// Up to `limit` commits reachable from HEAD, newest first. When `since`
+    /// is an ancestor of HEAD, the walk stops there and only returns what was
+    /// committed on top of it.
+    pub fn recent_commits(
+        &self,
+        repo_path: &Path,
+        since: Option<&str>,
+        limit: usize,
+    ) -> Result<CommitLog, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let Some(head) = repo.head().ok().and_then(|head| head.target()) else {
+            // Unborn branch, nothing committed yet.
+            return Ok(CommitLog {
+                commits: Vec::new(),
+                incremental: false,
+            });
+        };
+
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push(head)?;
+        revwalk.set_sorting(Sort::TIME)?;
+        let since = since
+            .and_then(|sha| git2::Oid::from_str(sha).ok())
+            .filter(|&oid| oid == head || repo.graph_descendant_of(head, oid).unwrap_or(false));
+        if let Some(since) = since {
+            revwalk.hide(since)?;
+        }
+
+        let mut commits = Vec::new();
+        for oid in revwalk.take(limit) {
+            let commit = repo.find_commit(oid?)?;
+            let author = commit.author();
+            commits.push(GitCommitSummary {
+                sha: commit.id().to_string(),
+                summary: commit.summary().unwrap_or_default().to_string(),
+                author_name: author.name().unwrap_or_default().to_string(),
+                author_email: author.email().unwrap_or_default().to_string(),
+                committed_at: DateTime::from_timestamp(commit.time().seconds(), 0)
+                    .unwrap_or_else(Utc::now),
+            });
+        }
+        Ok(CommitLog {
+            commits,
+            incremental: since.is_some(),
+        })
+    }
+
     /// Perform a squash merge of task branch into base branch, but fail on conflicts
     fn perform_squash_merge(
         &self,
@@ -1134,6 +1593,7 @@ impl GitService {
         old_base_branch: &str,
         task_branch: &str,
     ) -> Result<String, GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[repo_path, worktree_path]);
         let worktree_repo = Repository::open(worktree_path)?;
         let main_repo = self.open_repo(repo_path)?;
 
@@ -1148,6 +1608,10 @@ impl GitService {
         if git.is_rebase_in_progress(worktree_path).unwrap_or(false) {
             return Err(GitServiceError::RebaseInProgress);
         }
+        self.snapshot_worktree(
+            worktree_path,
+            &format!("Before rebase onto {new_base_branch}"),
+        )?;
 
         // Get the target base branch reference
         let nbr = Self::find_branch(&main_repo, new_base_branch)?.into_reference();
@@ -1259,6 +1723,7 @@ impl GitService {
         old_branch_name: &str,
         new_branch_name: &str,
     ) -> Result<(), GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[worktree_path]);
         let repo = self.open_repo(worktree_path)?;
 
         let mut branch = repo
@@ -1316,6 +1781,7 @@ impl GitService {
 
     /// Abort an in-progress rebase in this worktree (no-op if none).
     pub fn abort_rebase(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[worktree_path]);
         let git = GitCli::new();
         git.abort_rebase(worktree_path).map_err(|e| {
             GitServiceError::InvalidRepository(format!("git rebase --abort failed: {e}"))
@@ -1324,6 +1790,7 @@ impl GitService {
 
     /// Continue an in-progress rebase. Fails if there are unresolved conflicts.
     pub fn continue_rebase(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[worktree_path]);
         let git = GitCli::new();
         git.continue_rebase(worktree_path).map_err(|e| {
             GitServiceError::InvalidRepository(format!("git rebase --continue failed: {e}"))
@@ -1331,6 +1798,7 @@ impl GitService {
     }
 
     pub fn abort_conflicts(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
+        let _lock = repo_lock::lock_repos(&[worktree_path]);
         let git = GitCli::new();
         if git.is_rebase_in_progress(worktree_path).unwrap_or(false) {
             // If there are no conflicted files, prefer `git rebase --quit` to clean up metadata
@@ -1442,6 +1910,17 @@ impl GitService {
             .map_err(GitServiceError::from)
     }
 
+    /// Count the commits of the worktree's branch since `base` that aren't signed.
+    pub fn count_unsigned_commits(
+        &self,
+        worktree_path: &Path,
+        base: &str,
+    ) -> Result<usize, GitServiceError> {
+        GitCli::new()
+            .count_unsigned_commits(worktree_path, base)
+            .map_err(GitServiceError::from)
+    }
+
     pub fn resolve_remote_for_branch(
         &self,
         repo_path: &Path,
@@ -1511,6 +1990,82 @@ impl GitService {
         Ok(())
     }
 
+    /// Push `branch_name` with `--force-with-lease`, leasing the remote branch
+    /// at its remote-tracking ref, i.e. the commit last pushed or fetched from
+    /// here. Re-pushing a rewritten branch works, but commits pushed by anyone
+    /// else since then fail the push with `RemoteBranchMoved` instead of being
+    /// dropped.
+    pub fn push_with_lease(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        set_upstream: bool,
+    ) -> Result<LeasedPush, GitServiceError> {
+        let repo = Repository::open(worktree_path)?;
+        self.check_worktree_clean(&repo)?;
+
+        let remote = self.default_remote(&repo, worktree_path)?;
+        let mut branch = Self::find_branch(&repo, branch_name)?;
+        let local_oid = branch.get().peel_to_commit()?.id();
+        let tracking_ref = format!("refs/remotes/{}/{branch_name}", remote.name);
+        let lease = repo.refname_to_id(&tracking_ref).ok();
+        let (ahead, behind) = match lease {
+            Some(lease) => repo.graph_ahead_behind(local_oid, lease)?,
+            None => (0, 0),
+        };
+
+        let git_cli = GitCli::new();
+        let lease_str = lease.map(|oid| oid.to_string());
+        match git_cli.push_with_lease(
+            worktree_path,
+            &remote.url,
+            branch_name,
+            lease_str.as_deref(),
+        ) {
+            Ok(()) => {}
+            Err(GitCliError::PushRejected(msg)) => {
+                tracing::warn!("Push with lease rejected: {}", msg);
+                // Fetch into FETCH_HEAD rather than the tracking ref, so a retry
+                // keeps leasing the old commit until the user pulls.
+                git_cli.fetch_with_refspec(
+                    worktree_path,
+                    &remote.url,
+                    &format!("refs/heads/{branch_name}"),
+                )?;
+                let fetched = git_cli.git(worktree_path, ["rev-parse", "FETCH_HEAD"])?;
+                let remote_oid = git2::Oid::from_str(fetched.trim())?;
+                let (ahead, behind) = repo.graph_ahead_behind(local_oid, remote_oid)?;
+                return Err(GitServiceError::RemoteBranchMoved { ahead, behind });
+            }
+            Err(e) => {
+                tracing::error!("Push to remote failed: {}", e);
+                return Err(e.into());
+            }
+        }
+
+        repo.reference(
+            &tracking_ref,
+            local_oid,
+            true,
+            "update remote tracking branch",
+        )?;
+        let upstream = if set_upstream {
+            let upstream = format!("{}/{branch_name}", remote.name);
+            branch.set_upstream(Some(&upstream))?;
+            Some(upstream)
+        } else {
+            None
+        };
+
+        Ok(LeasedPush {
+            remote: remote.name,
+            upstream,
+            created: lease.is_none(),
+            ahead,
+            behind,
+        })
+    }
+
     /// Fetch from remote repository using native git authentication
     fn fetch_from_remote(
         &self,
@@ -0,0 +1,238 @@
+//! Per-repository locks serializing git operations that write to the index,
+//! refs or worktree metadata.
+//!
+//! Sessions running in parallel in one repository share its common dir, so
+//! two of them committing or rebasing at once can fail on `index.lock` or a
+//! ref lock. Every writing `GitService` operation takes the lock of its
+//! repository first; waiters are served in arrival order.
+//!
+//! Locks are reentrant per thread, so operations can call each other. A guard
+//! spanning several repositories takes their locks in a fixed order, so two
+//! multi-repo workspaces can't deadlock each other. Guards can't be held
+//! across an `.await`, as git operations run on the thread that locked.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap, HashSet},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::{Arc, Condvar, LazyLock, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::credentials::registry_key;
+
+/// Waits longer than this are logged.
+const SLOW_WAIT: Duration = Duration::from_secs(1);
+
+static REPO_LOCKS: LazyLock<Mutex<HashMap<PathBuf, Arc<RepoLock>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+thread_local! {
+    static HELD: RefCell<HashSet<PathBuf>> = RefCell::new(HashSet::new());
+}
+
+/// Lock wait metrics of one repository since startup.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct RepoLockStats {
+    /// The repository's common git dir.
+    pub repo: String,
+    #[ts(type = "number")]
+    pub acquisitions: u64,
+    /// Acquisitions that had to wait for another operation.
+    #[ts(type = "number")]
+    pub contended: u64,
+    #[ts(type = "number")]
+    pub total_wait_ms: u64,
+    #[ts(type = "number")]
+    pub max_wait_ms: u64,
+    /// Operations currently waiting.
+    #[ts(type = "number")]
+    pub waiting: u64,
+}
+
+#[derive(Default)]
+struct LockState {
+    next_ticket: u64,
+    now_serving: u64,
+    acquisitions: u64,
+    contended: u64,
+    total_wait: Duration,
+    max_wait: Duration,
+}
+
+/// A ticket lock, so waiters are served first come, first served.
+#[derive(Default)]
+struct RepoLock {
+    state: Mutex<LockState>,
+    released: Condvar,
+}
+
+impl RepoLock {
+    fn state(&self) -> MutexGuard<'_, LockState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn acquire(&self, key: &Path) {
+        let started = Instant::now();
+        let mut state = self.state();
+        let ticket = state.next_ticket;
+        state.next_ticket += 1;
+        let contended = state.now_serving != ticket;
+        while state.now_serving != ticket {
+            state = self
+                .released
+                .wait(state)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+
+        let waited = started.elapsed();
+        state.acquisitions += 1;
+        if contended {
+            state.contended += 1;
+            state.total_wait += waited;
+            state.max_wait = state.max_wait.max(waited);
+        }
+        drop(state);
+        if waited > SLOW_WAIT {
+            tracing::warn!("Waited {:?} for the git lock of {}", waited, key.display());
+        }
+    }
+
+    fn release(&self) {
+        self.state().now_serving += 1;
+        self.released.notify_all();
+    }
+
+    fn stats(&self, key: &Path) -> RepoLockStats {
+        let state = self.state();
+        let in_line = state.next_ticket - state.now_serving;
+        RepoLockStats {
+            repo: key.to_string_lossy().into_owned(),
+            acquisitions: state.acquisitions,
+            contended: state.contended,
+            total_wait_ms: state.total_wait.as_millis() as u64,
+            max_wait_ms: state.max_wait.as_millis() as u64,
+            waiting: in_line.saturating_sub(1),
+        }
+    }
+}
+
+/// Holds the locks of one or more repositories until dropped.
+#[must_use]
+pub struct RepoLockGuard {
+    locks: Vec<(PathBuf, Arc<RepoLock>)>,
+    // Reentrancy is tracked per thread, so the guard must stay on this one.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for RepoLockGuard {
+    fn drop(&mut self) {
+        for (key, lock) in self.locks.drain(..).rev() {
+            HELD.with(|held| held.borrow_mut().remove(&key));
+            lock.release();
+        }
+    }
+}
+
+/// Lock the repositories `paths` belong to. Paths may be repositories, their
+/// worktrees or git dirs; locks this thread already holds are skipped.
+pub fn lock_repos(paths: &[&Path]) -> RepoLockGuard {
+    // Sorted, so every caller takes the locks in the same order.
+    let keys: BTreeSet<PathBuf> = paths
+        .iter()
+        .map(|path| registry_key(path))
+        .filter(|key| !HELD.with(|held| held.borrow().contains(key)))
+        .collect();
+
+    let mut locks = Vec::with_capacity(keys.len());
+    for key in keys {
+        let lock = REPO_LOCKS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .entry(key.clone())
+            .or_default()
+            .clone();
+        lock.acquire(&key);
+        HELD.with(|held| held.borrow_mut().insert(key.clone()));
+        locks.push((key, lock));
+    }
+    RepoLockGuard {
+        locks,
+        _not_send: PhantomData,
+    }
+}
+
+/// Lock wait metrics of every repository locked since startup.
+pub fn lock_stats() -> Vec<RepoLockStats> {
+    let locks = REPO_LOCKS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut stats: Vec<_> = locks.iter().map(|(key, lock)| lock.stats(key)).collect();
+    stats.sort_by(|a, b| a.repo.cmp(&b.repo));
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicBool, Ordering},
+        thread,
+    };
+
+    use git2::Repository;
+
+    use super::*;
+
+    fn init_repo_with_worktree(dir: &Path) -> (PathBuf, PathBuf) {
+        let repo_path = dir.join("repo");
+        let repo = Repository::init(&repo_path).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+            .unwrap();
+        let worktree_path = dir.join("wt");
+        repo.worktree("wt", &worktree_path, None).unwrap();
+        (repo_path, worktree_path)
+    }
+
+    #[test]
+    fn worktrees_wait_for_their_repo_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let (repo_path, worktree_path) = init_repo_with_worktree(dir.path());
+
+        let guard = lock_repos(&[&repo_path]);
+        // Reentrant on the same thread.
+        drop(lock_repos(&[&worktree_path, &repo_path]));
+
+        let acquired = Arc::new(AtomicBool::new(false));
+        let waiter = {
+            let acquired = acquired.clone();
+            thread::spawn(move || {
+                let _guard = lock_repos(&[&worktree_path]);
+                acquired.store(true, Ordering::SeqCst);
+            })
+        };
+        thread::sleep(Duration::from_millis(50));
+        assert!(!acquired.load(Ordering::SeqCst));
+
+        drop(guard);
+        waiter.join().unwrap();
+        assert!(acquired.load(Ordering::SeqCst));
+
+        let key = registry_key(&repo_path);
+        let stats = lock_stats()
+            .into_iter()
+            .find(|stats| Path::new(&stats.repo) == key)
+            .unwrap();
+        assert_eq!(stats.acquisitions, 2);
+        assert_eq!(stats.contended, 1);
+        assert_eq!(stats.waiting, 0);
+    }
+}
@@ -4,7 +4,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use git::{GitCli, GitCliError, GitService};
+use git::{GitCli, GitCliError, GitService, GitServiceError};
 use git2::{PushOptions, Repository, build::CheckoutBuilder};
 use tempfile::TempDir;
 // Avoid direct git CLI usage in tests; exercise GitService instead.
@@ -404,6 +404,165 @@ fn push_and_fetch_roundtrip_updates_tracking_branch() {
     );
 }
 
+#[test]
+fn push_with_lease_replaces_own_commits_but_not_others() {
+    let temp_dir = TempDir::new().unwrap();
+    let remote_path = temp_dir.path().join("remote.git");
+    Repository::init_bare(&remote_path).expect("init bare remote");
+    let remote_url = remote_path.to_str().expect("remote path str");
+
+    let seed_path = temp_dir.path().join("seed");
+    let service = GitService::new();
+    service
+        .initialize_repo_with_main_branch(&seed_path)
+        .expect("init seed repo");
+    let seed_repo = Repository::open(&seed_path).expect("open seed repo");
+    configure_user(&seed_repo);
+    seed_repo.remote("origin", remote_url).expect("add remote");
+    push_ref(&seed_repo, "refs/heads/main", "refs/heads/main");
+    Repository::open_bare(&remote_path)
+        .expect("open bare remote")
+        .set_head("refs/heads/main")
+        .expect("set remote HEAD");
+
+    let local_path = temp_dir.path().join("local");
+    let local_repo = Repository::clone(remote_url, &local_path).expect("clone local");
+    configure_user(&local_repo);
+    let main_oid = local_repo.head().unwrap().target().unwrap();
+    create_branch_from_head(&local_repo, "feature");
+    checkout_branch(&local_repo, "feature");
+    write_file(&local_path, "agent.txt", "first attempt\n");
+    commit_all(&local_repo, "agent commit");
+
+    let pushed = service
+        .push_with_lease(&local_path, "feature", true)
+        .expect("first push");
+    assert!(pushed.created);
+    assert_eq!(pushed.upstream.as_deref(), Some("origin/feature"));
+
+    // Rewriting the branch and re-pushing drops only our own commit
+    local_repo
+        .reference("refs/heads/feature", main_oid, true, "rewrite")
+        .unwrap();
+    checkout_branch(&local_repo, "feature");
+    write_file(&local_path, "agent.txt", "second attempt\n");
+    commit_all(&local_repo, "agent commit, rewritten");
+
+    let pushed = service
+        .push_with_lease(&local_path, "feature", true)
+        .expect("re-push of rewritten branch");
+    assert!(!pushed.created);
+    assert_eq!((pushed.ahead, pushed.behind), (1, 1));
+
+    // Someone else pushes on top of the agent's work
+    let human_path = temp_dir.path().join("human");
+    let human_repo = Repository::clone(remote_url, &human_path).expect("clone human");
+    configure_user(&human_repo);
+    let remote_feature = human_repo
+        .find_reference("refs/remotes/origin/feature")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    human_repo.branch("feature", &remote_feature, true).unwrap();
+    checkout_branch(&human_repo, "feature");
+    write_file(&human_path, "human.txt", "review fix\n");
+    commit_all(&human_repo, "human commit");
+    push_ref(&human_repo, "refs/heads/feature", "refs/heads/feature");
+
+    local_repo
+        .reference("refs/heads/feature", main_oid, true, "rewrite")
+        .unwrap();
+    checkout_branch(&local_repo, "feature");
+    write_file(&local_path, "agent.txt", "third attempt\n");
+    commit_all(&local_repo, "agent commit, rewritten again");
+
+    match service.push_with_lease(&local_path, "feature", true) {
+        Err(GitServiceError::RemoteBranchMoved { ahead, behind }) => {
+            assert_eq!((ahead, behind), (1, 2));
+        }
+        other => panic!("expected the lease to be rejected, got {other:?}"),
+    }
+    let remote_head = Repository::open_bare(&remote_path)
+        .unwrap()
+        .find_reference("refs/heads/feature")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    assert_eq!(remote_head.summary(), Some("human commit"));
+}
+
+#[test]
+fn merge_conflicts_are_detected_without_touching_worktrees() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+    let service = GitService::new();
+
+    assert!(
+        service
+            .merge_conflicts(&repo_path, "feature", "old-base")
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        service
+            .merge_conflicts(&repo_path, "feature", "new-base")
+            .unwrap(),
+        vec!["conflict.txt".to_string()]
+    );
+    assert_eq!(
+        fs::read_to_string(worktree_path.join("conflict.txt")).unwrap(),
+        "feature version\n"
+    );
+}
+
+#[test]
+fn forced_reset_keeps_uncommitted_changes_as_a_snapshot() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let service = GitService::new();
+    let head = service.get_head_info(&worktree_path).unwrap().oid;
+
+    write_file(&worktree_path, "feat.txt", "manual edit\n");
+    write_file(&worktree_path, "notes.txt", "untracked notes\n");
+    service
+        .reset_worktree_to_commit(&worktree_path, &head, true)
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(worktree_path.join("feat.txt")).unwrap(),
+        "feat change\n"
+    );
+    assert!(!worktree_path.join("notes.txt").exists());
+
+    // Snapshots belong to the branch, so the main repo sees them too
+    let snapshots = service.list_snapshots(&repo_path, "feature").unwrap();
+    assert_eq!(snapshots.len(), 1);
+    assert_eq!(snapshots[0].base_commit, head);
+
+    service
+        .restore_snapshot(&worktree_path, "feature", &snapshots[0].id)
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(worktree_path.join("feat.txt")).unwrap(),
+        "manual edit\n"
+    );
+    assert_eq!(
+        fs::read_to_string(worktree_path.join("notes.txt")).unwrap(),
+        "untracked notes\n"
+    );
+
+    // A clean worktree has nothing to snapshot
+    service
+        .reset_worktree_to_commit(&worktree_path, &head, true)
+        .unwrap();
+    service
+        .reset_worktree_to_commit(&worktree_path, &head, true)
+        .unwrap();
+    assert_eq!(
+        service.list_snapshots(&repo_path, "feature").unwrap().len(),
+        2
+    );
+}
+
 #[test]
 fn rebase_preserves_untracked_files() {
     let td = TempDir::new().unwrap();
@@ -7,7 +7,7 @@ use std::{
 use git::{GitCli, GitService};
 use git2::{Repository, build::CheckoutBuilder};
 use tempfile::TempDir;
-use utils::diff::DiffChangeKind;
+use utils::diff::{DiffChangeKind, DiffOmittedReason};
 
 fn add_path(repo_path: &Path, path: &str) {
     let git = GitCli::new();
@@ -274,6 +274,38 @@ fn get_all_branches_lists_current_and_others() {
     assert!(main_entry.is_current);
 }
 
+#[test]
+fn recent_commits_stop_at_the_last_seen_commit() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "a.txt", "a\n");
+    s.commit(&repo_path, "add a").unwrap();
+
+    let fingerprint = s.refs_fingerprint(&repo_path).unwrap();
+    assert_eq!(s.refs_fingerprint(&repo_path).unwrap(), fingerprint);
+    let full = s.recent_commits(&repo_path, None, 100).unwrap();
+    assert!(!full.incremental);
+    assert_eq!(full.commits[0].summary, "add a");
+    assert_eq!(full.commits[0].author_email, "test@example.com");
+
+    write_file(&repo_path, "b.txt", "b\n");
+    s.commit(&repo_path, "add b").unwrap();
+    assert_ne!(s.refs_fingerprint(&repo_path).unwrap(), fingerprint);
+
+    let since = full.commits[0].sha.clone();
+    let new = s.recent_commits(&repo_path, Some(&since), 100).unwrap();
+    assert!(new.incremental);
+    let summaries: Vec<_> = new.commits.iter().map(|c| c.summary.as_str()).collect();
+    assert_eq!(summaries, ["add b"]);
+
+    // A commit HEAD doesn't descend from restarts the walk.
+    let unrelated = "0123456789012345678901234567890123456789";
+    let full = s.recent_commits(&repo_path, Some(unrelated), 100).unwrap();
+    assert!(!full.incremental);
+    assert!(full.commits.len() >= 2);
+}
+
 #[test]
 fn worktree_diff_respects_path_filter() {
     // Use git CLI status diff under the hood
@@ -372,6 +404,50 @@ fn worktree_diff_permission_only_change() {
     assert_eq!(d.old_content, d.new_content);
 }
 
+#[test]
+fn worktree_diff_marks_binary_and_oversized_files() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "big.txt", "small\n");
+    let _ = s.commit(&repo_path, "add big.txt").unwrap();
+    create_branch(&repo_path, "feature");
+
+    write_file(&repo_path, "big.txt", &"line\n".repeat(100));
+    write_file(&repo_path, "small.txt", "hello\n");
+    fs::write(
+        repo_path.join("image.bin"),
+        [0x89, b'P', b'N', b'G', 0, 0, 1],
+    )
+    .unwrap();
+
+    let base_commit = s.get_base_commit(&repo_path, "feature", "main").unwrap();
+    let diffs = s
+        .get_diffs_with_max_file_size(Path::new(&repo_path), &base_commit, None, 64)
+        .unwrap();
+    let find = |path: &str| {
+        diffs
+            .iter()
+            .find(|d| d.new_path.as_deref() == Some(path))
+            .unwrap_or_else(|| panic!("{path} diff present"))
+    };
+
+    let big = find("big.txt");
+    assert!(big.content_omitted);
+    assert_eq!(big.omitted_reason, Some(DiffOmittedReason::TooLarge));
+    assert!(big.new_content.is_none() && big.old_content.is_none());
+
+    let image = find("image.bin");
+    assert!(image.is_binary);
+    assert_eq!(image.omitted_reason, Some(DiffOmittedReason::Binary));
+
+    let small = find("small.txt");
+    assert!(!small.content_omitted && !small.is_binary);
+    assert_eq!(small.omitted_reason, None);
+    assert_eq!(small.new_content.as_deref(), Some("hello\n"));
+    assert_eq!(small.additions, Some(1));
+}
+
 #[test]
 fn squash_merge_libgit2_sets_author_without_user() {
     // Verify merge_changes (libgit2 path) uses fallback author when no config exists
@@ -7,7 +7,9 @@ use std::{
 };
 
 use anyhow::anyhow;
+use api_types::ReportAgentUsageRequest;
 use async_trait::async_trait;
+use chrono::Utc;
 use command_group::AsyncGroupChild;
 use db::{
     DBService,
@@ -29,7 +31,7 @@ use executors::{
     actions::{
         Executable, ExecutorAction, ExecutorActionType,
         coding_agent_follow_up::CodingAgentFollowUpRequest,
-        coding_agent_initial::CodingAgentInitialRequest,
+        coding_agent_initial::CodingAgentInitialRequest, script::ScriptRequest,
     },
     approvals::{ExecutorApprovalService, NoopExecutorApprovalService},
     env::{ExecutionEnv, RepoContext},
@@ -42,14 +44,21 @@ use serde_json::json;
 use services::services::{
     analytics::AnalyticsContext,
     approvals::{Approvals, executor_approvals::ExecutorApprovalBridge},
+    completion_webhook,
     config::{Config, DEFAULT_COMMIT_REMINDER_PROMPT},
-    container::{ContainerError, ContainerRef, ContainerService},
+    container::{
+        ContainerError, ContainerRef, ContainerService, ScriptFailureOutcome, script_timeout,
+    },
     diff_stream::{self, DiffStreamHandle},
+    env_profiles,
     file::FileService,
+    maintenance::MaintenanceMode,
     notification::NotificationService,
     queued_message::QueuedMessageService,
     remote_client::RemoteClient,
-    remote_sync,
+    remote_sync, secrets, shared_caches,
+    startup_tasks::{StartupTask, StartupTasks},
+    warm_pool::WarmPool,
 };
 use tokio::{sync::RwLock, task::JoinHandle};
 use tokio_util::io::ReaderStream;
@@ -60,6 +69,7 @@ use utils::{
 };
 use uuid::Uuid;
 use workspace_manager::{RepoWorkspaceInput, WorkspaceError, WorkspaceManager};
+use worktree_manager::WorktreeManager;
 
 use crate::{command, copy};
 
@@ -85,6 +95,9 @@ pub struct LocalContainerService {
     queued_message_service: QueuedMessageService,
     notification_service: NotificationService,
     remote_client: Option<RemoteClient>,
+    warm_pool: WarmPool,
+    startup_tasks: StartupTasks,
+    maintenance: MaintenanceMode,
 }
 
 impl LocalContainerService {
@@ -100,6 +113,8 @@ impl LocalContainerService {
         approvals: Approvals,
         queued_message_service: QueuedMessageService,
         remote_client: Option<RemoteClient>,
+        startup_tasks: StartupTasks,
+        maintenance: MaintenanceMode,
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
         let cancellation_tokens = Arc::new(RwLock::new(HashMap::new()));
@@ -107,6 +122,7 @@ impl LocalContainerService {
         let exit_monitor_handles = Arc::new(RwLock::new(HashMap::new()));
         let workspace_touch_times = Arc::new(RwLock::new(HashMap::new()));
         let notification_service = NotificationService::new(config.clone());
+        let warm_pool = WarmPool::new(db.clone(), git.clone(), config.clone());
 
         let container = LocalContainerService {
             db,
@@ -125,9 +141,13 @@ impl LocalContainerService {
             queued_message_service,
             notification_service,
             remote_client,
+            warm_pool,
+            startup_tasks,
+            maintenance,
         };
 
         container.spawn_workspace_cleanup();
+        container.warm_pool.spawn();
 
         container
     }
@@ -141,6 +161,7 @@ impl LocalContainerService {
             WorkspaceError::NoRepositories => {
                 ContainerError::Other(anyhow!("No repositories provided"))
             }
+            err @ WorkspaceError::PackageNotFound { .. } => ContainerError::Other(anyhow!(err)),
             WorkspaceError::Repo(err) => ContainerError::Other(anyhow!(err)),
             WorkspaceError::WorkspaceNotFound => {
                 ContainerError::Other(anyhow!("Workspace not found"))
@@ -161,6 +182,9 @@ impl LocalContainerService {
         &self,
         workspace_id: Uuid,
     ) -> Result<(Vec<Repo>, Vec<RepoWorkspaceInput>), ContainerError> {
+        // Worktree directories are named after the repos.
+        self.startup_tasks.wait_for(StartupTask::RepoNames).await;
+
         let workspace_repos =
             WorkspaceRepo::find_by_workspace_id(&self.db.pool, workspace_id).await?;
         if workspace_repos.is_empty() {
@@ -328,13 +352,18 @@ impl LocalContainerService {
             for repo in &ctx.repos {
                 let repo_path = workspace_root.join(&repo.name);
                 if let Ok(head) = self.git().get_head_info(&repo_path) {
-                    let _ = ExecutionProcessRepoState::update_after_head_commit(
-                        &self.db.pool,
-                        exec_id,
-                        repo.id,
-                        &head.oid,
-                    )
-                    .await;
+                    let _ = self
+                        .db
+                        .write(
+                            "execution_process_repo_states",
+                            ExecutionProcessRepoState::update_after_head_commit(
+                                &self.db.pool,
+                                exec_id,
+                                repo.id,
+                                &head.oid,
+                            ),
+                        )
+                        .await;
                 }
             }
         }
@@ -450,6 +479,12 @@ impl LocalContainerService {
     /// Commit changes to each repo. Logs failures but continues with other repos.
     fn commit_repos(&self, repos_with_changes: Vec<(Repo, PathBuf)>, message: &str) -> bool {
         let mut any_committed = false;
+        // Other sessions' operations can't slip in between the repos' commits.
+        let worktree_paths: Vec<&Path> = repos_with_changes
+            .iter()
+            .map(|(_, worktree_path)| worktree_path.as_path())
+            .collect();
+        let _lock = self.git().lock_repos(&worktree_paths);
 
         for (repo, worktree_path) in repos_with_changes {
             tracing::debug!(
@@ -481,6 +516,7 @@ impl LocalContainerService {
         &self,
         exec_id: &Uuid,
         exit_signal: Option<ExecutorExitSignal>,
+        timeout: Option<Duration>,
     ) -> JoinHandle<()> {
         let exec_id = *exec_id;
         let child_store = self.child_store.clone();
@@ -496,6 +532,9 @@ impl LocalContainerService {
             let mut exit_signal_future = exit_signal
                 .map(|rx| rx.boxed()) // wait for result
                 .unwrap_or_else(|| std::future::pending().boxed()); // no signal, stall forever
+            let mut timeout_future = timeout
+                .map(|duration| tokio::time::sleep(duration).boxed())
+                .unwrap_or_else(|| std::future::pending().boxed());
 
             let status_result: std::io::Result<std::process::ExitStatus>;
 
@@ -524,6 +563,23 @@ impl LocalContainerService {
                 exit_status_result = &mut process_exit_rx => {
                     status_result = exit_status_result.unwrap_or_else(|e| Err(std::io::Error::other(e)));
                 }
+                // Configured script timeout elapsed: kill the group and treat it as a failure
+                _ = &mut timeout_future => {
+                    let secs = timeout.map(|d| d.as_secs()).unwrap_or_default();
+                    tracing::warn!("Execution {} timed out after {}s", exec_id, secs);
+                    if let Some(store) = msg_stores.read().await.get(&exec_id) {
+                        store.push(LogMsg::Stderr(format!(
+                            "Script timed out after {secs}s and was terminated\n"
+                        )));
+                    }
+                    if let Some(child_lock) = child_store.read().await.get(&exec_id).cloned() {
+                        let mut child = child_lock.write().await;
+                        if let Err(err) = command::kill_process_group(&mut child).await {
+                            tracing::error!("Failed to kill process group after timeout: {} {}", exec_id, err);
+                        }
+                    }
+                    status_result = Ok(failure_exit_status());
+                }
             }
 
             let (exit_code, status) = match status_result {
@@ -547,18 +603,32 @@ impl LocalContainerService {
             }
 
             if !ExecutionProcess::was_stopped(&db.pool, exec_id).await
-                && let Err(e) =
-                    ExecutionProcess::update_completion(&db.pool, exec_id, status, exit_code).await
+                && let Err(e) = db
+                    .write(
+                        "execution_processes",
+                        ExecutionProcess::update_completion(&db.pool, exec_id, status, exit_code),
+                    )
+                    .await
             {
                 tracing::error!("Failed to update execution process completion: {}", e);
             }
 
             if let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await {
+                if ctx.execution_process.run_reason == ExecutionProcessRunReason::VerifyScript {
+                    container.record_test_results(&ctx).await;
+                }
+
                 let success = matches!(
                     ctx.execution_process.status,
                     ExecutionProcessStatus::Completed
                 ) && exit_code == Some(0);
 
+                if success
+                    && ctx.execution_process.run_reason == ExecutionProcessRunReason::SetupScript
+                {
+                    Self::spawn_warm_base_refresh(&ctx);
+                }
+
                 let cleanup_done = matches!(
                     ctx.execution_process.run_reason,
                     ExecutionProcessRunReason::CleanupScript
@@ -567,9 +637,16 @@ impl LocalContainerService {
                     ExecutionProcessStatus::Running
                 );
 
-                let mut already_finalized = false;
+                let failure_outcome = container.handle_script_failure(&ctx).await;
+                let continue_past_failure =
+                    matches!(failure_outcome, ScriptFailureOutcome::Continue);
+
+                // A retry takes over the rest of the chain, so this run is not
+                // finalized.
+                let mut already_finalized =
+                    matches!(failure_outcome, ScriptFailureOutcome::Retried);
 
-                if success || cleanup_done {
+                if !already_finalized && (success || cleanup_done || continue_past_failure) {
                     // Commit changes (if any) and get feedback about whether changes were made
                     let changes_committed = match container.try_commit_changes(&ctx).await {
                         Ok(committed) => committed,
@@ -611,13 +688,18 @@ impl LocalContainerService {
                     }
                 }
 
+                let has_chained_follow_up = ctx
+                    .execution_process
+                    .executor_action()
+                    .ok()
+                    .and_then(|action| action.next_action())
+                    .is_some();
+                if continue_past_failure && has_chained_follow_up {
+                    // The chain carries on; it is finalized when it ends.
+                    already_finalized = true;
+                }
+
                 if !already_finalized && container.should_finalize(&ctx) {
-                    let has_chained_follow_up = ctx
-                        .execution_process
-                        .executor_action()
-                        .ok()
-                        .and_then(|action| action.next_action())
-                        .is_some();
                     let mut started_queued_follow_up = false;
 
                     // Only execute queued messages if the execution succeeded
@@ -774,6 +856,12 @@ impl LocalContainerService {
                             .ok()
                             .flatten()
                             .and_then(|ws| ws.workspace.name);
+                    let usage = ReportAgentUsageRequest {
+                        execution_process_id: ctx.execution_process.id,
+                        executor: ctx.session.executor.clone(),
+                        started_at: ctx.execution_process.started_at,
+                        completed_at: ctx.execution_process.completed_at.unwrap_or_else(Utc::now),
+                    };
                     let client = client.clone();
                     let workspace_id = ctx.workspace.id;
                     let archived = ctx.workspace.archived;
@@ -786,6 +874,8 @@ impl LocalContainerService {
                             stats.as_ref(),
                         )
                         .await;
+                        remote_sync::report_agent_usage_to_remote(&client, workspace_id, &usage)
+                            .await;
                     });
                 }
             }
@@ -856,6 +946,25 @@ impl LocalContainerService {
         format!("{}-{}", short_uuid(workspace_id), task_title_id)
     }
 
+    /// Keep the worktrees of a workspace whose setup just succeeded, with the
+    /// dependencies it installed, as the warm bases new worktrees of its repos
+    /// are cloned from on copy-on-write filesystems.
+    fn spawn_warm_base_refresh(ctx: &ExecutionContext) {
+        let Some(container_ref) = ctx.workspace.container_ref.clone() else {
+            return;
+        };
+        let repos = ctx.repos.clone();
+        tokio::spawn(async move {
+            for repo in repos {
+                let worktree_path = Path::new(&container_ref).join(&repo.name);
+                if let Err(e) = WorktreeManager::refresh_warm_base(&repo.path, &worktree_path).await
+                {
+                    tracing::warn!("Failed to refresh warm base of {}: {}", repo.name, e);
+                }
+            }
+        });
+    }
+
     async fn track_child_msgs_in_store(
         &self,
         id: Uuid,
@@ -935,16 +1044,44 @@ impl LocalContainerService {
             // Only update if summary is not already set
             if turn.summary.is_none() {
                 if let Some(summary) = self.extract_last_assistant_message(exec_id) {
-                    CodingAgentTurn::update_summary(&self.db.pool, *exec_id, &summary).await?;
+                    self.db
+                        .write(
+                            "coding_agent_turns",
+                            CodingAgentTurn::update_summary(&self.db.pool, *exec_id, &summary),
+                        )
+                        .await?;
                 } else {
                     tracing::debug!("No assistant message found for execution {}", exec_id);
                 }
             }
+            if let Some(cost) = self.extract_reported_cost(exec_id) {
+                self.db
+                    .write(
+                        "coding_agent_turns",
+                        CodingAgentTurn::set_cost(&self.db.pool, *exec_id, cost),
+                    )
+                    .await?;
+            }
         }
 
         Ok(())
     }
 
+    /// Cost the agent reported in its output, if it reports one
+    fn extract_reported_cost(&self, exec_id: &Uuid) -> Option<f64> {
+        let msg_stores = self.msg_stores.try_read().ok()?;
+        let output: String = msg_stores
+            .get(exec_id)?
+            .get_history()
+            .into_iter()
+            .filter_map(|msg| match msg {
+                LogMsg::Stdout(content) => Some(content),
+                _ => None,
+            })
+            .collect();
+        completion_webhook::reported_cost_usd(&output)
+    }
+
     /// Copy project files and workspace attachments to the workspace.
     /// Skips files that already exist (fast no-op if all exist).
     async fn copy_files_and_images(
@@ -971,6 +1108,8 @@ impl LocalContainerService {
             }
         }
 
+        shared_caches::link_workspace_caches(&self.db.pool, workspace.id, workspace_dir).await?;
+
         let agent_working_dir = Session::find_latest_by_workspace_id(&self.db.pool, workspace.id)
             .await?
             .and_then(|session| session.agent_working_dir);
@@ -1083,7 +1222,7 @@ impl LocalContainerService {
 
         let repos =
             WorkspaceRepo::find_repos_for_workspace(&self.db.pool, ctx.workspace.id).await?;
-        let cleanup_action = self.cleanup_actions_for_repos(&repos);
+        let post_agent_action = self.post_agent_actions_for_repos(&repos).await;
 
         let working_dir = ctx
             .session
@@ -1108,7 +1247,7 @@ impl LocalContainerService {
             })
         };
 
-        let action = ExecutorAction::new(action_type, cleanup_action.map(Box::new));
+        let action = ExecutorAction::new(action_type, post_agent_action.map(Box::new));
 
         self.start_execution(
             &ctx.workspace,
@@ -1143,6 +1282,10 @@ impl ContainerService for LocalContainerService {
         &self.db
     }
 
+    fn config(&self) -> &Arc<RwLock<Config>> {
+        &self.config
+    }
+
     fn git(&self) -> &GitService {
         &self.git
     }
@@ -1151,6 +1294,18 @@ impl ContainerService for LocalContainerService {
         &self.notification_service
     }
 
+    fn file_service(&self) -> &FileService {
+        &self.file_service
+    }
+
+    fn startup_tasks(&self) -> &StartupTasks {
+        &self.startup_tasks
+    }
+
+    fn maintenance(&self) -> &MaintenanceMode {
+        &self.maintenance
+    }
+
     async fn touch(&self, workspace: &Workspace) -> Result<(), ContainerError> {
         let now = Instant::now();
 
@@ -1258,6 +1413,23 @@ impl ContainerService for LocalContainerService {
             WorkspaceManager::get_workspace_base_dir().join(&workspace_dir_name)
         };
 
+        // A brand-new workspace can start from worktrees of the warm pool
+        if workspace.container_ref.is_none() && !workspace_dir.exists() {
+            match self
+                .warm_pool
+                .claim_for_workspace(workspace, &workspace_dir)
+                .await
+            {
+                Ok(true) => Workspace::mark_setup_completed(&self.db.pool, workspace.id).await?,
+                Ok(false) => {}
+                Err(e) => tracing::warn!(
+                    "Failed to claim warm worktrees for workspace {}: {}",
+                    workspace.id,
+                    e
+                ),
+            }
+        }
+
         WorkspaceManager::ensure_workspace_exists(
             &workspace_dir,
             &workspace_inputs,
@@ -1368,6 +1540,45 @@ impl ContainerService for LocalContainerService {
         env.insert("VK_WORKSPACE_ID", workspace.id.to_string());
         env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
 
+        // Point package managers at the repos' shared caches
+        let cache_env = shared_caches::workspace_env(&self.db.pool, workspace.id).await?;
+        env.merge(&cache_env);
+
+        // Variables from the workspace's selected environment profiles. This
+        // fails before spawning if a required variable is unset.
+        let profile_env = env_profiles::resolve_workspace_env(&self.db.pool, workspace.id).await?;
+        env.merge(&profile_env);
+
+        // Resolve ${secret:NAME} references: the script is rewritten to read
+        // environment variables so values never appear in the action itself.
+        let resolved_action;
+        let executor_action = match executor_action.typ() {
+            ExecutorActionType::ScriptRequest(request)
+                if secrets::has_secret_references(&request.script) =>
+            {
+                let (script, secret_env) =
+                    secrets::prepare_script(&self.db.pool, &request.script).await?;
+                env.merge(&secret_env);
+                resolved_action = ExecutorAction::new(
+                    ExecutorActionType::ScriptRequest(ScriptRequest {
+                        script,
+                        ..request.clone()
+                    }),
+                    executor_action.next_action.clone(),
+                );
+                &resolved_action
+            }
+            _ => executor_action,
+        };
+
+        let timeout = match executor_action.typ() {
+            ExecutorActionType::ScriptRequest(request) => self
+                .script_settings_for(workspace.id, request)
+                .await
+                .and_then(|settings| script_timeout(&settings, &request.context)),
+            _ => None,
+        };
+
         // Create the child and stream, add to execution tracker with timeout
         let mut spawned = tokio::time::timeout(
             Duration::from_secs(30),
@@ -1398,7 +1609,7 @@ impl ContainerService for LocalContainerService {
         }
 
         // Spawn unified exit monitor: watches OS exit and optional executor signal
-        let hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
+        let hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal, timeout);
         self.add_exit_monitor_handle(execution_process.id, hn).await;
 
         Ok(())
@@ -1421,7 +1632,16 @@ impl ContainerService for LocalContainerService {
             None
         };
 
-        ExecutionProcess::update_completion(&self.db.pool, execution_process.id, status, exit_code)
+        self.db
+            .write(
+                "execution_processes",
+                ExecutionProcess::update_completion(
+                    &self.db.pool,
+                    execution_process.id,
+                    status,
+                    exit_code,
+                ),
+            )
             .await?;
 
         // Try graceful cancellation first, then force kill
@@ -1495,6 +1715,7 @@ impl ContainerService for LocalContainerService {
             WorkspaceRepo::find_repos_for_workspace(&self.db.pool, workspace.id).await?;
 
         let mut streams = Vec::new();
+        let max_file_bytes = self.config.read().await.diff.max_file_bytes();
 
         let container_ref = self.ensure_container_exists(workspace).await?;
         let workspace_root = PathBuf::from(container_ref);
@@ -1539,6 +1760,7 @@ impl ContainerService for LocalContainerService {
                     base_commit: base_commit.clone(),
                     stats_only,
                     path_prefix: Some(repo.name.clone()),
+                    max_file_bytes,
                 })
                 .await?;
 
@@ -1,12 +1,13 @@
 use std::{
     collections::HashMap,
     sync::{Arc, OnceLock},
+    time::Duration,
 };
 
 use api_types::LoginStatus;
 use async_trait::async_trait;
 use client_info::ClientInfo;
-use db::DBService;
+use db::{ConnectionSettings, DBService};
 use deployment::{Deployment, DeploymentError, RelayHostsNotConfigured, RemoteClientNotConfigured};
 use executors::profile::ExecutorConfigs;
 use git::GitService;
@@ -25,11 +26,16 @@ use services::services::{
     file::FileService,
     file_search::FileSearchCache,
     filesystem::FilesystemService,
+    maintenance::MaintenanceMode,
+    merge_readiness::MergeReadinessService,
     oauth_credentials::OAuthCredentials,
     pr_monitor::PrMonitorService,
     queued_message::QueuedMessageService,
     remote_client::{RemoteClient, RemoteClientError},
     repo::RepoService,
+    repo_index::RepoIndexService,
+    semantic_search::SemanticSearchService,
+    startup_tasks::StartupTasks,
 };
 use tokio::sync::{Notify, RwLock};
 use tokio_util::sync::CancellationToken;
@@ -62,6 +68,9 @@ pub struct LocalDeployment {
     filesystem: FilesystemService,
     events: EventService,
     file_search_cache: Arc<FileSearchCache>,
+    semantic_search: SemanticSearchService,
+    repo_index: RepoIndexService,
+    maintenance: MaintenanceMode,
     approvals: Approvals,
     queued_message_service: QueuedMessageService,
     remote_client: Result<RemoteClient, RemoteClientNotConfigured>,
@@ -82,18 +91,23 @@ pub struct LocalDeployment {
 }
 
 #[derive(Debug, Clone)]
-struct PendingHandoff {
-    provider: String,
-    app_verifier: String,
+pub struct PendingHandoff {
+    pub provider: String,
+    pub app_verifier: String,
+    /// Signs a local user in with their remote account, rather than signing
+    /// the server itself in.
+    pub user_sign_in: bool,
 }
 
 #[async_trait]
 impl Deployment for LocalDeployment {
     async fn new(shutdown: CancellationToken) -> Result<Self, DeploymentError> {
-        // Run one-time process logs migration from DB to filesystem
-        services::services::execution_process::migrate_execution_logs_to_files()
-            .await
-            .map_err(|e| DeploymentError::Other(anyhow::anyhow!("Migration failed: {}", e)))?;
+        // Apply a staged backup restore before anything opens the database
+        match services::services::backup::apply_pending_restore() {
+            Ok(true) => tracing::info!("Restored local state from backup"),
+            Ok(false) => {}
+            Err(e) => tracing::error!("Failed to restore staged backup: {}", e),
+        }
 
         let mut raw_config = load_config_from_file(&config_path()).await;
 
@@ -135,15 +149,21 @@ impl Deployment for LocalDeployment {
         // Create shared components for EventService
         let events_msg_store = Arc::new(MsgStore::new());
         let events_entry_count = Arc::new(RwLock::new(0));
+        let startup_tasks = StartupTasks::new(events_msg_store.clone());
+
+        let db_settings = ConnectionSettings {
+            wal: raw_config.database.wal_enabled,
+            busy_timeout: Duration::from_millis(raw_config.database.busy_timeout_ms.into()),
+        };
 
         // Create DB with event hooks
         let db = {
             let hook = EventService::create_hook(
                 events_msg_store.clone(),
                 events_entry_count.clone(),
-                DBService::new().await?, // Temporary DB service for the hook
+                DBService::new(db_settings.clone()).await?, // Temporary DB service for the hook
             );
-            DBService::new_with_after_connect(hook).await?
+            DBService::new_with_after_connect(db_settings, hook).await?
         };
 
         let file = FileService::new(db.clone().pool)?;
@@ -151,12 +171,32 @@ impl Deployment for LocalDeployment {
             let file_service = file.clone();
             tokio::spawn(async move {
                 tracing::info!("Starting orphaned file cleanup...");
-                if let Err(e) = file_service.delete_orphaned_files().await {
+                if let Err(e) = file_service
+                    .delete_orphaned_files(chrono::Duration::zero())
+                    .await
+                {
                     tracing::error!("Failed to clean up orphaned files: {}", e);
                 }
             });
         }
 
+        {
+            let pool = db.pool.clone();
+            tokio::spawn(async move {
+                if let Err(e) = services::services::shared_caches::prune_orphaned(&pool).await {
+                    tracing::error!("Failed to prune shared caches: {}", e);
+                }
+            });
+        }
+
+        // Before anything fetches or pushes, so repos never fall back to the
+        // default git identity.
+        if let Err(e) =
+            services::services::git_credentials::register_repo_credentials(&db.pool).await
+        {
+            tracing::warn!(?e, "failed to register repo git credentials");
+        }
+
         let approvals = Approvals::new();
         let queued_message_service = QueuedMessageService::new();
 
@@ -220,6 +260,7 @@ impl Deployment for LocalDeployment {
             analytics_service: s.clone(),
         });
         let workspace_manager = WorkspaceManager::new(db.clone());
+        let maintenance = MaintenanceMode::new();
         let container = LocalContainerService::new(
             db.clone(),
             workspace_manager.clone(),
@@ -231,12 +272,18 @@ impl Deployment for LocalDeployment {
             approvals.clone(),
             queued_message_service.clone(),
             remote_client.clone().ok(),
+            startup_tasks.clone(),
+            maintenance.clone(),
         )
         .await;
+        // Log migration and data backfills run while the server is already
+        // serving; whatever needs their results waits on `startup_tasks`.
+        startup_tasks.spawn(container.clone(), db.clone());
 
         let events = EventService::new(db.clone(), events_msg_store, events_entry_count);
 
         let file_search_cache = Arc::new(FileSearchCache::new());
+        let semantic_search = SemanticSearchService::new();
 
         let pty = PtyService::new();
         let relay_hosts = match remote_client.clone().ok() {
@@ -262,6 +309,8 @@ impl Deployment for LocalDeployment {
             let rc = remote_client.clone().ok();
             PrMonitorService::spawn(db, analytics, container, rc, pr_sync_notify.clone()).await;
         }
+        MergeReadinessService::spawn(db.clone(), git.clone(), events.msg_store().clone());
+        let repo_index = RepoIndexService::spawn(db.clone(), git.clone());
 
         let deployment = Self {
             config,
@@ -276,6 +325,9 @@ impl Deployment for LocalDeployment {
             filesystem,
             events,
             file_search_cache,
+            semantic_search,
+            repo_index,
+            maintenance,
             approvals,
             queued_message_service,
             remote_client,
@@ -456,28 +508,38 @@ impl LocalDeployment {
         handoff_id: Uuid,
         provider: String,
         app_verifier: String,
+        user_sign_in: bool,
     ) {
         self.oauth_handoffs.write().await.insert(
             handoff_id,
             PendingHandoff {
                 provider,
                 app_verifier,
+                user_sign_in,
             },
         );
     }
 
-    pub async fn take_oauth_handoff(&self, handoff_id: &Uuid) -> Option<(String, String)> {
-        self.oauth_handoffs
-            .write()
-            .await
-            .remove(handoff_id)
-            .map(|state| (state.provider, state.app_verifier))
+    pub async fn take_oauth_handoff(&self, handoff_id: &Uuid) -> Option<PendingHandoff> {
+        self.oauth_handoffs.write().await.remove(handoff_id)
     }
 
     pub fn pty(&self) -> &PtyService {
         &self.pty
     }
 
+    pub fn semantic_search(&self) -> &SemanticSearchService {
+        &self.semantic_search
+    }
+
+    pub fn repo_index(&self) -> &RepoIndexService {
+        &self.repo_index
+    }
+
+    pub fn maintenance(&self) -> &MaintenanceMode {
+        &self.maintenance
+    }
+
     pub fn ssh_config(&self) -> &Arc<russh::server::Config> {
         &self.ssh_config
     }
@@ -1,17 +1,27 @@
 use std::{
-    collections::HashMap,
-    io::{Read, Write},
+    collections::{HashMap, VecDeque},
+    fs::File,
+    io::{BufWriter, Read, Write},
     path::PathBuf,
     sync::{Arc, Mutex},
     thread,
 };
 
+use chrono::{DateTime, Utc};
 use portable_pty::{CommandBuilder, NativePtySystem, PtySize, PtySystem};
 use thiserror::Error;
-use tokio::sync::mpsc;
-use utils::shell::get_interactive_shell;
+use tokio::sync::broadcast;
+use utils::{
+    asciicast::{AsciicastHeader, AsciicastWriter},
+    execution_logs::terminal_recording_path,
+    shell::get_interactive_shell,
+};
 use uuid::Uuid;
 
+/// Output kept per session so a re-attached terminal can redraw.
+const SCROLLBACK_BYTES: usize = 64 * 1024;
+const OUTPUT_CHANNEL_CAPACITY: usize = 1024;
+
 #[derive(Debug, Error)]
 pub enum PtyError {
     #[error("Failed to create PTY: {0}")]
@@ -24,15 +34,96 @@ pub enum PtyError {
     ResizeFailed(String),
     #[error("Session already closed")]
     SessionClosed,
+    #[error("Recording not found: {0}")]
+    RecordingNotFound(Uuid),
+}
+
+/// What to start a PTY session with.
+#[derive(Debug, Clone)]
+pub struct PtySessionOptions {
+    pub working_dir: PathBuf,
+    pub cols: u16,
+    pub rows: u16,
+    /// Workspace the session belongs to. Sessions without one are not listed
+    /// and are expected to be closed by whoever created them.
+    pub workspace_id: Option<Uuid>,
+    pub name: Option<String>,
+    /// Record the session's output as an asciicast file in the workspace's
+    /// recordings directory. Only workspace sessions can be recorded.
+    pub record: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct PtySessionInfo {
+    pub id: Uuid,
+    pub workspace_id: Option<Uuid>,
+    pub name: Option<String>,
+    pub working_dir: PathBuf,
+    pub cols: u16,
+    pub rows: u16,
+    /// False once the shell has exited.
+    pub running: bool,
+    pub recording: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Output of a session: recent scrollback plus a channel for live output.
+/// The sender is dropped when the shell exits, which ends all subscriptions.
+struct PtyOutput {
+    scrollback: VecDeque<u8>,
+    tx: Option<broadcast::Sender<Vec<u8>>>,
+    recorder: Option<AsciicastWriter<BufWriter<File>>>,
+}
+
+impl PtyOutput {
+    fn push(&mut self, data: &[u8]) {
+        self.scrollback.extend(data);
+        let excess = self.scrollback.len().saturating_sub(SCROLLBACK_BYTES);
+        self.scrollback.drain(..excess);
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(data.to_vec());
+        }
+        if let Some(recorder) = &mut self.recorder
+            && let Err(e) = recorder.output(data)
+        {
+            tracing::warn!("Stopping terminal recording after write error: {}", e);
+            self.recorder = None;
+        }
+    }
+
+    fn record_resize(&mut self, cols: u16, rows: u16) {
+        if let Some(recorder) = &mut self.recorder
+            && let Err(e) = recorder.resize(cols, rows)
+        {
+            tracing::warn!("Stopping terminal recording after write error: {}", e);
+            self.recorder = None;
+        }
+    }
 }
 
 struct PtySession {
     writer: Box<dyn Write + Send>,
     master: Box<dyn portable_pty::MasterPty + Send>,
     _output_handle: thread::JoinHandle<()>,
+    output: Arc<Mutex<PtyOutput>>,
+    info: PtySessionInfo,
     closed: bool,
 }
 
+impl PtySession {
+    fn info(&self) -> PtySessionInfo {
+        let running = self
+            .output
+            .lock()
+            .map(|output| output.tx.is_some())
+            .unwrap_or(false);
+        PtySessionInfo {
+            running,
+            ..self.info.clone()
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct PtyService {
     sessions: Arc<Mutex<HashMap<Uuid, PtySession>>>,
@@ -47,13 +138,52 @@ impl PtyService {
 
     pub async fn create_session(
         &self,
-        working_dir: PathBuf,
-        cols: u16,
-        rows: u16,
-    ) -> Result<(Uuid, mpsc::UnboundedReceiver<Vec<u8>>), PtyError> {
+        options: PtySessionOptions,
+    ) -> Result<PtySessionInfo, PtyError> {
         let session_id = Uuid::new_v4();
-        let (output_tx, output_rx) = mpsc::unbounded_channel();
         let shell = get_interactive_shell().await;
+        let PtySessionOptions {
+            working_dir,
+            cols,
+            rows,
+            workspace_id,
+            name,
+            record,
+        } = options;
+
+        let recording_path = match (record, workspace_id) {
+            (false, _) => None,
+            (true, Some(workspace_id)) => Some(terminal_recording_path(workspace_id, session_id)),
+            (true, None) => {
+                return Err(PtyError::CreateFailed(
+                    "Only workspace terminals can be recorded".to_string(),
+                ));
+            }
+        };
+        let recorder = match &recording_path {
+            Some(path) => {
+                let mut header = AsciicastHeader::new(cols, rows);
+                header.timestamp = Some(Utc::now().timestamp());
+                header.title = name.clone();
+                header.env.extend([
+                    ("SHELL".to_string(), shell.to_string_lossy().to_string()),
+                    ("TERM".to_string(), "xterm-256color".to_string()),
+                ]);
+                let recorder = AsciicastWriter::create(path, &header).map_err(|e| {
+                    PtyError::CreateFailed(format!("Failed to start recording: {e}"))
+                })?;
+                Some(recorder)
+            }
+            None => None,
+        };
+        let (output_tx, _) = broadcast::channel(OUTPUT_CHANNEL_CAPACITY);
+        let output = Arc::new(Mutex::new(PtyOutput {
+            scrollback: VecDeque::new(),
+            tx: Some(output_tx),
+            recorder,
+        }));
+        let reader_output = output.clone();
+        let cwd = working_dir.clone();
 
         let result = tokio::task::spawn_blocking(move || {
             let pty_system = NativePtySystem::default();
@@ -68,7 +198,7 @@ impl PtyService {
                 .map_err(|e| PtyError::CreateFailed(e.to_string()))?;
 
             let mut cmd = CommandBuilder::new(&shell);
-            cmd.cwd(&working_dir);
+            cmd.cwd(&cwd);
 
             // Configure shell-specific options
             let shell_name = shell.file_name().and_then(|n| n.to_str()).unwrap_or("");
@@ -121,14 +251,16 @@ impl PtyService {
                 loop {
                     match reader.read(&mut buf) {
                         Ok(0) => break,
-                        Ok(n) => {
-                            if output_tx.send(buf[..n].to_vec()).is_err() {
-                                break;
-                            }
-                        }
+                        Ok(n) => match reader_output.lock() {
+                            Ok(mut output) => output.push(&buf[..n]),
+                            Err(_) => break,
+                        },
                         Err(_) => break,
                     }
                 }
+                if let Ok(mut output) = reader_output.lock() {
+                    output.tx = None;
+                }
                 drop(child);
             });
 
@@ -143,15 +275,84 @@ impl PtyService {
             writer,
             master,
             _output_handle: output_handle,
+            output,
+            info: PtySessionInfo {
+                id: session_id,
+                workspace_id,
+                name,
+                working_dir,
+                cols,
+                rows,
+                running: true,
+                recording: recording_path.is_some(),
+                created_at: Utc::now(),
+            },
             closed: false,
         };
+        let info = session.info();
 
         self.sessions
             .lock()
             .map_err(|e| PtyError::CreateFailed(e.to_string()))?
             .insert(session_id, session);
 
-        Ok((session_id, output_rx))
+        Ok(info)
+    }
+
+    /// Attach to a session's output. Returns the scrollback so far and a
+    /// receiver for everything after it; the receiver closes when the shell
+    /// exits.
+    pub fn subscribe(
+        &self,
+        session_id: Uuid,
+    ) -> Result<(Vec<u8>, broadcast::Receiver<Vec<u8>>), PtyError> {
+        let output = self
+            .sessions
+            .lock()
+            .map_err(|_| PtyError::SessionClosed)?
+            .get(&session_id)
+            .ok_or(PtyError::SessionNotFound(session_id))?
+            .output
+            .clone();
+        let output = output.lock().map_err(|_| PtyError::SessionClosed)?;
+        let rx = match &output.tx {
+            Some(tx) => tx.subscribe(),
+            // The shell has exited: hand out a receiver that is already closed.
+            None => broadcast::channel(1).1,
+        };
+        Ok((output.scrollback.iter().copied().collect(), rx))
+    }
+
+    pub fn get(&self, session_id: Uuid) -> Result<PtySessionInfo, PtyError> {
+        self.sessions
+            .lock()
+            .map_err(|_| PtyError::SessionClosed)?
+            .get(&session_id)
+            .map(PtySession::info)
+            .ok_or(PtyError::SessionNotFound(session_id))
+    }
+
+    /// Sessions of a workspace, oldest first.
+    pub fn list(&self, workspace_id: Uuid) -> Vec<PtySessionInfo> {
+        let Ok(sessions) = self.sessions.lock() else {
+            return Vec::new();
+        };
+        let mut infos: Vec<_> = sessions
+            .values()
+            .filter(|session| session.info.workspace_id == Some(workspace_id))
+            .map(PtySession::info)
+            .collect();
+        infos.sort_by_key(|info| info.created_at);
+        infos
+    }
+
+    pub fn rename(&self, session_id: Uuid, name: String) -> Result<PtySessionInfo, PtyError> {
+        let mut sessions = self.sessions.lock().map_err(|_| PtyError::SessionClosed)?;
+        let session = sessions
+            .get_mut(&session_id)
+            .ok_or(PtyError::SessionNotFound(session_id))?;
+        session.info.name = Some(name);
+        Ok(session.info())
     }
 
     pub async fn write(&self, session_id: Uuid, data: &[u8]) -> Result<(), PtyError> {
@@ -181,12 +382,12 @@ impl PtyService {
     }
 
     pub async fn resize(&self, session_id: Uuid, cols: u16, rows: u16) -> Result<(), PtyError> {
-        let sessions = self
+        let mut sessions = self
             .sessions
             .lock()
             .map_err(|e| PtyError::ResizeFailed(e.to_string()))?;
         let session = sessions
-            .get(&session_id)
+            .get_mut(&session_id)
             .ok_or(PtyError::SessionNotFound(session_id))?;
 
         if session.closed {
@@ -202,6 +403,11 @@ impl PtyService {
                 pixel_height: 0,
             })
             .map_err(|e| PtyError::ResizeFailed(e.to_string()))?;
+        session.info.cols = cols;
+        session.info.rows = rows;
+        if let Ok(mut output) = session.output.lock() {
+            output.record_resize(cols, rows);
+        }
 
         Ok(())
     }
@@ -217,6 +423,13 @@ impl PtyService {
         }
         Ok(())
     }
+
+    /// Close every session of a workspace, e.g. before it is deleted.
+    pub async fn close_workspace_sessions(&self, workspace_id: Uuid) {
+        if let Ok(mut sessions) = self.sessions.lock() {
+            sessions.retain(|_, session| session.info.workspace_id != Some(workspace_id));
+        }
+    }
 }
 
 impl Default for PtyService {
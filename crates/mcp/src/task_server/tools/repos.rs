@@ -1,4 +1,4 @@
-use db::models::repo::Repo;
+use db::models::{repo::Repo, repo_knowledge::RepoKnowledge};
 use rmcp::{
     ErrorData, handler::server::wrapper::Parameters, model::CallToolResult, schemars, tool,
     tool_router,
@@ -72,6 +72,26 @@ struct UpdateRepoScriptResponse {
     field: String,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct SuggestRepoKnowledgeRequest {
+    #[schemars(description = "The ID of the repository the knowledge is about")]
+    repo_id: Uuid,
+    #[schemars(description = "One of: build, gotcha, architecture, other")]
+    category: String,
+    #[schemars(
+        description = "A short, self-contained note, e.g. a build command or a pitfall to avoid"
+    )]
+    content: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct SuggestRepoKnowledgeResponse {
+    #[schemars(description = "The ID of the suggested knowledge entry")]
+    knowledge_id: String,
+    #[schemars(description = "Suggestions are only added to prompts once a user approves them")]
+    status: String,
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 struct ListReposResponse {
     repos: Vec<McpRepoSummary>,
@@ -212,4 +232,32 @@ impl McpServer {
             field: "dev_server_script".to_string(),
         })
     }
+
+    #[tool(
+        description = "Suggest a note about a repository (build commands, gotchas, architecture) for future sessions. Once a user approves it, the note is added to the prompts of agents working on the repository."
+    )]
+    async fn suggest_repo_knowledge(
+        &self,
+        Parameters(SuggestRepoKnowledgeRequest {
+            repo_id,
+            category,
+            content,
+        }): Parameters<SuggestRepoKnowledgeRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/repos/{}/knowledge", repo_id));
+        let payload = serde_json::json!({
+            "category": category,
+            "content": content,
+            "suggested": true,
+        });
+        let knowledge: RepoKnowledge =
+            match self.send_json(self.client.post(&url).json(&payload)).await {
+                Ok(k) => k,
+                Err(e) => return Ok(Self::tool_error(e)),
+            };
+        McpServer::success(&SuggestRepoKnowledgeResponse {
+            knowledge_id: knowledge.id.to_string(),
+            status: "suggested".to_string(),
+        })
+    }
 }
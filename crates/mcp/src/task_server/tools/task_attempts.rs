@@ -44,6 +44,10 @@ struct StartWorkspaceRequest {
         description = "Optional issue ID to link the workspace to. When provided, the workspace will be associated with this remote issue."
     )]
     issue_id: Option<Uuid>,
+    #[schemars(
+        description = "Optional environment profile IDs (attached to the selected repositories) whose variables are injected into the workspace's processes."
+    )]
+    env_profile_ids: Option<Vec<Uuid>>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -107,6 +111,7 @@ impl McpServer {
             variant,
             repositories,
             issue_id,
+            env_profile_ids,
         }): Parameters<StartWorkspaceRequest>,
     ) -> Result<CallToolResult, ErrorData> {
         if repositories.is_empty() {
@@ -151,6 +156,7 @@ impl McpServer {
             .map(|r| WorkspaceRepoInput {
                 repo_id: r.repo_id,
                 target_branch: r.branch,
+                package_id: None,
             })
             .collect();
 
@@ -197,6 +203,7 @@ impl McpServer {
             prompt: workspace_prompt,
             attachment_ids: None,
             idempotency_key: None,
+            env_profile_ids,
         };
 
         let create_and_start_url = self.url("/api/workspaces/start");
@@ -0,0 +1,4 @@
+-- Issues are ranked within their status column by sort_order; reorders read
+-- and lock a whole column in rank order.
+CREATE INDEX IF NOT EXISTS idx_issues_status_sort_order
+ON issues (status_id, sort_order);
@@ -0,0 +1,27 @@
+-- Workflow rules for project statuses: a category per status, and the
+-- statuses an issue may move to from each one. A status without outgoing
+-- transitions allows moves to any status.
+CREATE TYPE project_status_category AS ENUM ('backlog', 'in_progress', 'done');
+
+ALTER TABLE project_statuses ADD COLUMN category project_status_category;
+
+UPDATE project_statuses
+SET category = CASE name
+    WHEN 'Backlog' THEN 'backlog'::project_status_category
+    WHEN 'To do' THEN 'backlog'::project_status_category
+    WHEN 'In progress' THEN 'in_progress'::project_status_category
+    WHEN 'In review' THEN 'in_progress'::project_status_category
+    WHEN 'Done' THEN 'done'::project_status_category
+    WHEN 'Cancelled' THEN 'done'::project_status_category
+END;
+
+CREATE TABLE project_status_transitions (
+    from_status_id UUID NOT NULL REFERENCES project_statuses(id) ON DELETE CASCADE,
+    to_status_id UUID NOT NULL REFERENCES project_statuses(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (from_status_id, to_status_id),
+    CHECK (from_status_id <> to_status_id)
+);
+
+CREATE INDEX idx_project_status_transitions_to_status_id
+ON project_status_transitions (to_status_id);
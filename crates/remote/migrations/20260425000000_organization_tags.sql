@@ -0,0 +1,24 @@
+-- Tags shared by every project in an organization, alongside project tags.
+CREATE TABLE organization_tags (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    name VARCHAR(50) NOT NULL,
+    color VARCHAR(20) NOT NULL,
+
+    UNIQUE (organization_id, name)
+);
+
+CREATE TABLE issue_organization_tags (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    issue_id UUID NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    organization_tag_id UUID NOT NULL REFERENCES organization_tags(id) ON DELETE CASCADE,
+    UNIQUE (issue_id, organization_tag_id)
+);
+
+SELECT electric_sync_table('public', 'organization_tags');
+SELECT electric_sync_table('public', 'issue_organization_tags');
+
+CREATE INDEX IF NOT EXISTS idx_issue_organization_tags_issue_id
+    ON issue_organization_tags(issue_id);
+CREATE INDEX IF NOT EXISTS idx_issue_organization_tags_organization_tag_id
+    ON issue_organization_tags(organization_tag_id);
@@ -0,0 +1,64 @@
+-- Per-user, per-organization unread notification counts, maintained by
+-- trigger so badges can be polled cheaply or synced through Electric.
+CREATE TABLE notification_unread_counts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    unread_count INTEGER NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    UNIQUE (user_id, organization_id)
+);
+
+INSERT INTO notification_unread_counts (user_id, organization_id, unread_count)
+SELECT user_id, organization_id, COUNT(*)
+FROM notifications
+WHERE NOT seen
+GROUP BY user_id, organization_id;
+
+CREATE OR REPLACE FUNCTION adjust_notification_unread_count(
+    p_user_id UUID,
+    p_organization_id UUID,
+    p_delta INTEGER
+) RETURNS VOID AS $$
+BEGIN
+    INSERT INTO notification_unread_counts (user_id, organization_id, unread_count)
+    VALUES (p_user_id, p_organization_id, GREATEST(p_delta, 0))
+    ON CONFLICT (user_id, organization_id) DO UPDATE
+    SET unread_count = GREATEST(notification_unread_counts.unread_count + p_delta, 0),
+        updated_at = NOW();
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION notifications_track_unread_count() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') AND NOT OLD.seen THEN
+        PERFORM adjust_notification_unread_count(OLD.user_id, OLD.organization_id, -1);
+    END IF;
+    IF TG_OP IN ('INSERT', 'UPDATE') AND NOT NEW.seen THEN
+        PERFORM adjust_notification_unread_count(NEW.user_id, NEW.organization_id, 1);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER notifications_unread_count
+AFTER INSERT OR DELETE OR UPDATE OF seen ON notifications
+FOR EACH ROW EXECUTE FUNCTION notifications_track_unread_count();
+
+SELECT electric_sync_table('public', 'notification_unread_counts');
+
+CREATE INDEX IF NOT EXISTS idx_notifications_user_unread
+    ON notifications(user_id, organization_id) WHERE NOT seen;
+
+-- Issues a user muted; no notifications are created for them about it.
+CREATE TABLE notification_issue_mutes (
+    issue_id UUID NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (issue_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_issue_mutes_user_id
+    ON notification_issue_mutes(user_id);
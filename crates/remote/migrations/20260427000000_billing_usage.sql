@@ -0,0 +1,27 @@
+-- Agent runs reported by local clients, one row per execution process.
+CREATE TABLE agent_usage_records (
+    execution_process_id UUID PRIMARY KEY,
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    project_id UUID REFERENCES projects(id) ON DELETE SET NULL,
+    workspace_id UUID REFERENCES workspaces(id) ON DELETE SET NULL,
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    executor TEXT,
+    started_at TIMESTAMPTZ NOT NULL,
+    completed_at TIMESTAMPTZ NOT NULL,
+    duration_seconds INTEGER NOT NULL CHECK (duration_seconds >= 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_agent_usage_records_org_completed
+    ON agent_usage_records(organization_id, completed_at);
+
+-- What has already been sent to the payments provider for each billing
+-- period, so scheduled reports only send the difference.
+CREATE TABLE billing_usage_reports (
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    period_start TIMESTAMPTZ NOT NULL,
+    agent_minutes BIGINT NOT NULL DEFAULT 0,
+    active_seats INTEGER NOT NULL DEFAULT 0,
+    reported_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (organization_id, period_start)
+);
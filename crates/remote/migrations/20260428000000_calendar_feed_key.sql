@@ -0,0 +1,4 @@
+-- Key mixed into each user's signed calendar feed URL. Replacing it revokes
+-- every URL handed out before.
+ALTER TABLE users
+    ADD COLUMN calendar_feed_key UUID NOT NULL DEFAULT gen_random_uuid();
@@ -0,0 +1,4 @@
+-- Projects that reject issue and comment updates not naming the version they
+-- were made against.
+ALTER TABLE projects
+    ADD COLUMN require_if_match BOOLEAN NOT NULL DEFAULT FALSE;
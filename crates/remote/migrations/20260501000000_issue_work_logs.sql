@@ -0,0 +1,23 @@
+-- Time logged against issues, e.g. for billable hours. `work_date` is the
+-- day the work was done, which may differ from when it was logged.
+CREATE TABLE issue_work_logs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    issue_id UUID NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    minutes INTEGER NOT NULL CHECK (minutes > 0),
+    note TEXT,
+    work_date DATE NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_issue_work_logs_issue ON issue_work_logs(issue_id);
+CREATE INDEX idx_issue_work_logs_user_date ON issue_work_logs(user_id, work_date);
+
+-- A running timer; stopping it logs the elapsed time. Users run at most one.
+CREATE TABLE issue_work_timers (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    issue_id UUID NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    note TEXT,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
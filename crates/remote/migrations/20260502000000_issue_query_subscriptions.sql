@@ -0,0 +1,28 @@
+ALTER TYPE notification_type ADD VALUE 'issue_entered_filter';
+ALTER TYPE notification_type ADD VALUE 'issue_left_filter';
+
+-- Saved issue filters whose owners are notified when issues start or stop
+-- matching them.
+CREATE TABLE issue_query_subscriptions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    query TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_issue_query_subscriptions_project ON issue_query_subscriptions(project_id);
+CREATE INDEX idx_issue_query_subscriptions_user ON issue_query_subscriptions(user_id);
+
+-- Issues matching each subscription as of the last evaluation, so a mutation
+-- only re-evaluates the issue it touched.
+CREATE TABLE issue_query_subscription_matches (
+    subscription_id UUID NOT NULL REFERENCES issue_query_subscriptions(id) ON DELETE CASCADE,
+    issue_id UUID NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    PRIMARY KEY (subscription_id, issue_id)
+);
+
+CREATE INDEX idx_issue_query_subscription_matches_issue
+    ON issue_query_subscription_matches(issue_id);
@@ -0,0 +1,46 @@
+-- Markdown pages kept alongside a project's board. `revision` counts saves
+-- and guards against concurrent edits.
+CREATE TABLE project_documents (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    title TEXT NOT NULL,
+    content TEXT NOT NULL DEFAULT '',
+    revision INTEGER NOT NULL DEFAULT 1,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_project_documents_project ON project_documents(project_id, title);
+
+-- Every saved version of a document, including the current one.
+CREATE TABLE project_document_revisions (
+    document_id UUID NOT NULL REFERENCES project_documents(id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    content TEXT NOT NULL,
+    author_user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (document_id, revision)
+);
+
+-- Issues a document's current content mentions by simple ID.
+CREATE TABLE project_document_issue_links (
+    document_id UUID NOT NULL REFERENCES project_documents(id) ON DELETE CASCADE,
+    issue_id UUID NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    PRIMARY KEY (document_id, issue_id)
+);
+
+CREATE INDEX idx_project_document_issue_links_issue ON project_document_issue_links(issue_id);
+
+-- Attachments embedded in a document's current content. Linked attachments
+-- don't expire; unlinking one schedules it for cleanup.
+CREATE TABLE project_document_attachments (
+    document_id UUID NOT NULL REFERENCES project_documents(id) ON DELETE CASCADE,
+    attachment_id UUID NOT NULL REFERENCES attachments(id) ON DELETE CASCADE,
+    PRIMARY KEY (document_id, attachment_id)
+);
+
+CREATE INDEX idx_project_document_attachments_attachment
+    ON project_document_attachments(attachment_id);
@@ -0,0 +1,11 @@
+-- Server-rendered markdown, keyed by a hash of the renderer version, the
+-- source, and the issues and users its references resolved to. Entries
+-- unused for a while are deleted by a background task.
+CREATE TABLE rendered_markdown_cache (
+    key TEXT PRIMARY KEY,
+    html TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_rendered_markdown_cache_last_used ON rendered_markdown_cache(last_used_at);
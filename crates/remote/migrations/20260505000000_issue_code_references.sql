@@ -0,0 +1,20 @@
+-- Commits and pull requests on GitHub that mention an issue by simple ID,
+-- recorded from GitHub App webhooks. `reference` is the commit SHA or the
+-- pull request number.
+CREATE TYPE issue_code_reference_kind AS ENUM ('commit', 'pull_request');
+
+CREATE TABLE issue_code_references (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    issue_id UUID NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    kind issue_code_reference_kind NOT NULL,
+    repo_full_name TEXT NOT NULL,
+    reference TEXT NOT NULL,
+    url TEXT NOT NULL,
+    title TEXT NOT NULL,
+    author_login TEXT,
+    referenced_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (issue_id, kind, repo_full_name, reference)
+);
+
+CREATE INDEX idx_issue_code_references_issue ON issue_code_references(issue_id, referenced_at);
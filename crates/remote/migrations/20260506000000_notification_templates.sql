@@ -0,0 +1,23 @@
+-- Localized notification copy. Users pick a locale; projects pick a default
+-- locale for members who have not, and store title/body templates per
+-- notification type and locale. Anything without a template falls back to
+-- the built-in English copy.
+ALTER TABLE users ADD COLUMN locale TEXT;
+
+CREATE TABLE project_notification_locales (
+    project_id UUID PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+    default_locale TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE notification_templates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    notification_type notification_type NOT NULL,
+    locale TEXT NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (project_id, notification_type, locale)
+);
@@ -0,0 +1,19 @@
+-- Profile settings users manage themselves. `avatar_attachment_id` points at
+-- an uploaded image that replaces the OAuth avatar; `timezone` is an IANA
+-- name used to send due-date reminders in local time.
+ALTER TABLE users
+    ADD COLUMN display_name TEXT,
+    ADD COLUMN avatar_attachment_id UUID REFERENCES attachments(id) ON DELETE SET NULL,
+    ADD COLUMN timezone TEXT;
+
+ALTER TYPE notification_type ADD VALUE 'issue_due_soon';
+
+-- Due-date reminders already sent, per assignee and due date, so changing
+-- the date sends a new reminder.
+CREATE TABLE issue_due_date_reminders (
+    issue_id UUID NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    target_date TIMESTAMPTZ NOT NULL,
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (issue_id, user_id, target_date)
+);
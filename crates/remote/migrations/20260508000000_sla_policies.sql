@@ -0,0 +1,40 @@
+-- Working hours SLA targets are counted in. Organizations without a row work
+-- Monday to Friday, 09:00 to 17:00 UTC. Times are minutes after local
+-- midnight; days are ISO weekdays (1 = Monday).
+CREATE TABLE organization_working_hours (
+    organization_id UUID PRIMARY KEY REFERENCES organizations(id) ON DELETE CASCADE,
+    timezone TEXT NOT NULL,
+    start_minute INTEGER NOT NULL,
+    end_minute INTEGER NOT NULL,
+    working_days SMALLINT[] NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (start_minute >= 0 AND start_minute < end_minute AND end_minute <= 1440)
+);
+
+CREATE TYPE sla_metric AS ENUM ('first_response', 'resolution');
+
+-- A target for issues matching `query` (the saved filter syntax), counted in
+-- working minutes from when the issue was created.
+CREATE TABLE project_sla_policies (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    query TEXT NOT NULL,
+    metric sla_metric NOT NULL,
+    target_minutes INTEGER NOT NULL CHECK (target_minutes > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_project_sla_policies_project_id ON project_sla_policies(project_id);
+
+-- Breaches already notified, so each is reported once.
+CREATE TABLE issue_sla_breaches (
+    policy_id UUID NOT NULL REFERENCES project_sla_policies(id) ON DELETE CASCADE,
+    issue_id UUID NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    deadline TIMESTAMPTZ NOT NULL,
+    notified_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (policy_id, issue_id)
+);
+
+ALTER TYPE notification_type ADD VALUE 'issue_sla_breached';
@@ -0,0 +1,27 @@
+-- A GitHub team whose members are a project's members. A project with a
+-- team is only accessible to the synced members and the organization's
+-- admins. If the GitHub App is uninstalled the link stays and the last
+-- synced members keep access, so the project doesn't open up to everyone.
+CREATE TABLE project_github_teams (
+    project_id UUID PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+    installation_id UUID REFERENCES github_app_installations(id) ON DELETE SET NULL,
+    team_slug TEXT NOT NULL,
+    -- Team members without a GitHub account linked to an organization member.
+    unmatched_logins TEXT[] NOT NULL DEFAULT '{}',
+    last_synced_at TIMESTAMPTZ,
+    last_sync_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_project_github_teams_installation
+    ON project_github_teams(installation_id, team_slug);
+
+CREATE TABLE project_members (
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    synced_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (project_id, user_id)
+);
+
+CREATE INDEX idx_project_members_user_id ON project_members(user_id);
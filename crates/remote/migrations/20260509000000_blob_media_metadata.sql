@@ -0,0 +1,3 @@
+-- Playback length of audio and video attachments, detected on upload.
+-- width and height are now also set for video.
+ALTER TABLE blobs ADD COLUMN duration_ms BIGINT;
@@ -0,0 +1,20 @@
+-- How long an organization keeps closed issues and attachments. NULL keeps
+-- them forever.
+CREATE TABLE organization_retention_policies (
+    organization_id UUID PRIMARY KEY REFERENCES organizations(id) ON DELETE CASCADE,
+    closed_issue_retention_years SMALLINT CHECK (closed_issue_retention_years > 0),
+    attachment_retention_years SMALLINT CHECK (attachment_retention_years > 0),
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Projects whose data must not be deleted, by retention policies or by users.
+CREATE TABLE project_legal_holds (
+    project_id UUID PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+    reason TEXT NOT NULL,
+    placed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    placed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_issues_completed_at ON issues(project_id, completed_at)
+    WHERE completed_at IS NOT NULL;
@@ -0,0 +1,29 @@
+-- Exports of a user's personal data and deletions of their account, run in
+-- the background. `user_id` has no foreign key so a deletion's job outlives
+-- the user it deleted.
+CREATE TYPE account_data_job_kind AS ENUM ('export', 'deletion');
+CREATE TYPE account_data_job_status AS ENUM ('pending', 'running', 'completed', 'failed');
+
+CREATE TABLE account_data_jobs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    kind account_data_job_kind NOT NULL,
+    status account_data_job_status NOT NULL DEFAULT 'pending',
+    -- The export's archive, deleted once it expires.
+    archive_blob_path TEXT,
+    archive_expires_at TIMESTAMPTZ,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_account_data_jobs_user ON account_data_jobs(user_id, created_at DESC);
+CREATE INDEX idx_account_data_jobs_pending ON account_data_jobs(created_at)
+    WHERE status IN ('pending', 'running');
+CREATE INDEX idx_account_data_jobs_archives ON account_data_jobs(archive_expires_at)
+    WHERE archive_blob_path IS NOT NULL;
+
+-- One unfinished job of each kind per user.
+CREATE UNIQUE INDEX idx_account_data_jobs_active ON account_data_jobs(user_id, kind)
+    WHERE status IN ('pending', 'running');
@@ -0,0 +1,8 @@
+-- Address ranges an organization's data may be reached from through the API.
+-- An empty list allows any address.
+CREATE TABLE organization_ip_allowlists (
+    organization_id UUID PRIMARY KEY REFERENCES organizations(id) ON DELETE CASCADE,
+    ranges INET[] NOT NULL DEFAULT '{}',
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
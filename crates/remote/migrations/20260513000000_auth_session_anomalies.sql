@@ -0,0 +1,43 @@
+-- Requests made with each session, by minute and by the country Cloudflare
+-- reports ('XX' when unknown). Kept for a week as the baseline anomalies are
+-- measured against.
+CREATE TABLE auth_session_usage (
+    session_id UUID NOT NULL REFERENCES auth_sessions(id) ON DELETE CASCADE,
+    bucket_start TIMESTAMPTZ NOT NULL,
+    country TEXT NOT NULL,
+    ip_address INET,
+    request_count INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (session_id, bucket_start, country)
+);
+
+CREATE INDEX idx_auth_session_usage_bucket_start ON auth_session_usage(bucket_start);
+
+CREATE TYPE auth_session_anomaly_kind AS ENUM ('location_change', 'rate_spike');
+CREATE TYPE auth_session_flag_status AS ENUM ('open', 'dismissed', 'revoked');
+
+-- A session is suspended while it has an open flag that suspended it.
+CREATE TABLE auth_session_flags (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    session_id UUID NOT NULL REFERENCES auth_sessions(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind auth_session_anomaly_kind NOT NULL,
+    status auth_session_flag_status NOT NULL DEFAULT 'open',
+    description TEXT NOT NULL,
+    suspended BOOLEAN NOT NULL DEFAULT FALSE,
+    detected_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    reviewed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    reviewed_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX uniq_auth_session_flags_open
+    ON auth_session_flags(session_id, kind)
+    WHERE status = 'open';
+CREATE INDEX idx_auth_session_flags_user ON auth_session_flags(user_id, detected_at DESC);
+
+-- Whether members' flagged sessions are suspended until reviewed.
+CREATE TABLE organization_session_anomaly_settings (
+    organization_id UUID PRIMARY KEY REFERENCES organizations(id) ON DELETE CASCADE,
+    auto_suspend BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,524 @@
+//! Exports of users' personal data and deletions of their accounts.
+//!
+//! Both run as background jobs the user polls. An export archives the user's
+//! profile and organizations, the issues they created, the comments they
+//! wrote and the attachments on those, and is kept for a week. A deletion
+//! removes the account with its personal organizations and avatar; issues,
+//! comments and documents the user authored elsewhere are kept without an
+//! author.
+
+use std::{
+    collections::HashSet,
+    io::{Cursor, Write},
+    time::Duration,
+};
+
+use api_types::{AccountDataJob, AccountDataJobKind};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use thiserror::Error;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+use uuid::Uuid;
+use zip::{ZipWriter, write::SimpleFileOptions};
+
+use crate::{
+    audit::{self, AuditAction, AuditEvent},
+    azure_blob::{AzureBlobError, AzureBlobService},
+    db::account_data::{
+        AccountDataError, AccountDataJobRow, AccountDataRepository, ExportedAttachment,
+        ExportedComment, ExportedIssue, ExportedMembership, ExportedProfile,
+    },
+};
+
+const ARCHIVE_TTL: chrono::Duration = chrono::Duration::days(7);
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// Jobs running for longer were left by a server that stopped, and are
+/// started again.
+const STALE_AFTER: chrono::Duration = chrono::Duration::hours(1);
+
+#[derive(Debug, Error)]
+pub enum AccountDataJobError {
+    #[error(transparent)]
+    Database(#[from] AccountDataError),
+    #[error("attachment storage is not configured")]
+    StorageNotConfigured,
+    #[error(transparent)]
+    Storage(#[from] AzureBlobError),
+    #[error("account not found")]
+    UserNotFound,
+    #[error("failed to write archive: {0}")]
+    Archive(#[from] zip::result::ZipError),
+    #[error("failed to write archive: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("failed to write archive: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("failed to write archive: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl AccountDataJobError {
+    /// The reason shown to the user; internal failures aren't detailed.
+    fn user_message(&self) -> String {
+        match self {
+            Self::Database(AccountDataError::Database(_)) => "internal error".to_string(),
+            Self::Database(error) => error.to_string(),
+            Self::StorageNotConfigured | Self::UserNotFound => self.to_string(),
+            _ => "internal error".to_string(),
+        }
+    }
+}
+
+/// Everything exported about a user, besides attachment files.
+#[derive(Debug, Clone)]
+pub struct ExportData {
+    pub profile: ExportedProfile,
+    pub memberships: Vec<ExportedMembership>,
+    pub issues: Vec<ExportedIssue>,
+    pub comments: Vec<ExportedComment>,
+    pub attachments: Vec<ExportedAttachment>,
+}
+
+#[derive(Serialize)]
+struct ProfileDocument<'a> {
+    profile: &'a ExportedProfile,
+    organizations: &'a [ExportedMembership],
+}
+
+pub fn archive_blob_path(user_id: Uuid, job_id: Uuid) -> String {
+    format!("account-exports/{user_id}/{job_id}.zip")
+}
+
+pub fn job_response(azure: Option<&AzureBlobService>, job: AccountDataJobRow) -> AccountDataJob {
+    let download_url = job
+        .archive_blob_path
+        .as_deref()
+        .filter(|_| {
+            job.archive_expires_at
+                .is_some_and(|expires| expires > Utc::now())
+        })
+        .zip(azure)
+        .and_then(|(path, azure)| azure.create_read_url(path).ok());
+    AccountDataJob {
+        id: job.id,
+        kind: job.kind,
+        status: job.status,
+        error: job.error,
+        created_at: job.created_at,
+        started_at: job.started_at,
+        completed_at: job.completed_at,
+        archive_expires_at: download_url.as_ref().and(job.archive_expires_at),
+        download_url,
+    }
+}
+
+/// Where each attachment is stored in the archive, unique even when
+/// filenames repeat.
+pub fn attachment_paths(attachments: &[ExportedAttachment]) -> Vec<String> {
+    let mut used = HashSet::new();
+    attachments
+        .iter()
+        .map(|attachment| {
+            let folder = attachment.issue_simple_id.as_deref().unwrap_or("avatar");
+            let name = sanitize_filename(&attachment.original_name);
+            let path = format!("attachments/{folder}/{name}");
+            if used.insert(path.clone()) {
+                path
+            } else {
+                format!("attachments/{folder}/{}_{name}", attachment.id)
+            }
+        })
+        .collect()
+}
+
+fn sanitize_filename(filename: &str) -> String {
+    filename
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '.' || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(100)
+        .collect()
+}
+
+fn optional_date(date: Option<DateTime<Utc>>) -> String {
+    date.map(|d| d.to_rfc3339()).unwrap_or_default()
+}
+
+fn csv_file<const N: usize>(
+    header: [&str; N],
+    rows: impl IntoIterator<Item = [String; N]>,
+) -> Result<Vec<u8>, csv::Error> {
+    let mut wtr = csv::Writer::from_writer(Vec::new());
+    wtr.write_record(header)?;
+    for row in rows {
+        wtr.write_record(row)?;
+    }
+    wtr.into_inner().map_err(|error| error.into_error().into())
+}
+
+/// Write the archive. `files` holds the contents of the attachments that
+/// could be downloaded, by their index in `data.attachments`.
+pub fn write_archive(
+    data: &ExportData,
+    files: &[Option<Vec<u8>>],
+) -> Result<Vec<u8>, AccountDataJobError> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    zip.start_file("profile.json", options)?;
+    serde_json::to_writer_pretty(
+        &mut zip,
+        &ProfileDocument {
+            profile: &data.profile,
+            organizations: &data.memberships,
+        },
+    )?;
+
+    let issues = csv_file(
+        [
+            "Issue ID",
+            "Title",
+            "Description",
+            "Organization",
+            "Project",
+            "Created",
+            "Updated",
+            "Completed",
+        ],
+        data.issues.iter().map(|issue| {
+            [
+                issue.simple_id.clone(),
+                issue.title.clone(),
+                issue.description.clone().unwrap_or_default(),
+                issue.organization_name.clone(),
+                issue.project_name.clone(),
+                issue.created_at.to_rfc3339(),
+                issue.updated_at.to_rfc3339(),
+                optional_date(issue.completed_at),
+            ]
+        }),
+    )?;
+    zip.start_file("issues.csv", options)?;
+    zip.write_all(&issues)?;
+
+    let comments = csv_file(
+        ["Comment ID", "Issue ID", "Message", "Created", "Updated"],
+        data.comments.iter().map(|comment| {
+            [
+                comment.id.to_string(),
+                comment.issue_simple_id.clone(),
+                comment.message.clone(),
+                comment.created_at.to_rfc3339(),
+                comment.updated_at.to_rfc3339(),
+            ]
+        }),
+    )?;
+    zip.start_file("comments.csv", options)?;
+    zip.write_all(&comments)?;
+
+    let paths = attachment_paths(&data.attachments);
+    let manifest = csv_file(
+        [
+            "Issue ID",
+            "Filename",
+            "Content Type",
+            "Size (bytes)",
+            "File Path in ZIP",
+        ],
+        data.attachments
+            .iter()
+            .zip(&paths)
+            .zip(files)
+            .map(|((attachment, path), file)| {
+                [
+                    attachment.issue_simple_id.clone().unwrap_or_default(),
+                    attachment.original_name.clone(),
+                    attachment.mime_type.clone().unwrap_or_default(),
+                    attachment.size_bytes.to_string(),
+                    file.as_ref().map(|_| path.clone()).unwrap_or_default(),
+                ]
+            }),
+    )?;
+    zip.start_file("attachments.csv", options)?;
+    zip.write_all(&manifest)?;
+
+    // Attachments are usually compressed already.
+    let stored = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    for (path, file) in paths.iter().zip(files) {
+        if let Some(file) = file {
+            zip.start_file(path, stored)?;
+            zip.write_all(file)?;
+        }
+    }
+
+    Ok(zip.finish()?.into_inner())
+}
+
+async fn load_export_data(pool: &PgPool, user_id: Uuid) -> Result<ExportData, AccountDataJobError> {
+    let profile = AccountDataRepository::profile(pool, user_id)
+        .await?
+        .ok_or(AccountDataJobError::UserNotFound)?;
+    let (memberships, issues, comments, attachments) = tokio::try_join!(
+        AccountDataRepository::memberships(pool, user_id),
+        AccountDataRepository::issues(pool, user_id),
+        AccountDataRepository::comments(pool, user_id),
+        AccountDataRepository::attachments(pool, user_id),
+    )?;
+
+    Ok(ExportData {
+        profile,
+        memberships,
+        issues,
+        comments,
+        attachments,
+    })
+}
+
+async fn run_export(
+    pool: &PgPool,
+    azure: Option<&AzureBlobService>,
+    job: &AccountDataJobRow,
+) -> Result<(), AccountDataJobError> {
+    let azure = azure.ok_or(AccountDataJobError::StorageNotConfigured)?;
+    let data = load_export_data(pool, job.user_id).await?;
+
+    let mut files = Vec::with_capacity(data.attachments.len());
+    for attachment in &data.attachments {
+        match azure.download_blob(&attachment.blob_path).await {
+            Ok(file) => files.push(Some(file)),
+            Err(error) => {
+                warn!(
+                    ?error,
+                    blob_path = %attachment.blob_path,
+                    "Failed to download attachment for account export, skipping"
+                );
+                files.push(None);
+            }
+        }
+    }
+    let archive = write_archive(&data, &files)?;
+
+    let path = archive_blob_path(job.user_id, job.id);
+    azure
+        .upload_blob(&path, archive, "application/zip".to_string())
+        .await?;
+    AccountDataRepository::complete_job(pool, job.id, Some((&path, Utc::now() + ARCHIVE_TTL)))
+        .await?;
+
+    audit::emit(
+        AuditEvent::system(AuditAction::AccountDataExport)
+            .user(job.user_id, None)
+            .resource("user", Some(job.user_id))
+            .description("Exported personal data"),
+    );
+    Ok(())
+}
+
+async fn delete_files<'a>(
+    azure: Option<&AzureBlobService>,
+    paths: impl IntoIterator<Item = &'a String>,
+) {
+    let Some(azure) = azure else {
+        return;
+    };
+    for path in paths {
+        if let Err(error) = azure.delete_blob(path).await {
+            warn!(%error, blob_path = %path, "Failed to delete account file");
+        }
+    }
+}
+
+async fn run_deletion(
+    pool: &PgPool,
+    azure: Option<&AzureBlobService>,
+    job: &AccountDataJobRow,
+) -> Result<(), AccountDataJobError> {
+    let blobs = AccountDataRepository::delete_account(pool, job.user_id).await?;
+    AccountDataRepository::complete_job(pool, job.id, None).await?;
+
+    let archives = AccountDataRepository::clear_user_archives(pool, job.user_id).await?;
+    // Collected up front: a lazy iterator borrowing through a closure would
+    // be held across the deletes' awaits and keep the task from being `Send`.
+    let paths: Vec<&String> = blobs
+        .iter()
+        .flat_map(|blob| std::iter::once(&blob.blob_path).chain(&blob.thumbnail_blob_path))
+        .chain(&archives)
+        .collect();
+    delete_files(azure, paths).await;
+
+    audit::emit(
+        AuditEvent::system(AuditAction::AccountDelete)
+            .user(job.user_id, None)
+            .resource("user", Some(job.user_id))
+            .description("Deleted account and anonymized authored content"),
+    );
+    Ok(())
+}
+
+async fn run_job(pool: &PgPool, azure: Option<&AzureBlobService>, job: &AccountDataJobRow) {
+    let result = match job.kind {
+        AccountDataJobKind::Export => run_export(pool, azure, job).await,
+        AccountDataJobKind::Deletion => run_deletion(pool, azure, job).await,
+    };
+    match result {
+        Ok(()) => info!(job_id = %job.id, kind = ?job.kind, "Account data job completed"),
+        Err(error) => {
+            warn!(?error, job_id = %job.id, kind = ?job.kind, "Account data job failed");
+            if let Err(error) =
+                AccountDataRepository::fail_job(pool, job.id, &error.user_message()).await
+            {
+                warn!(?error, job_id = %job.id, "Failed to record account data job failure");
+            }
+        }
+    }
+}
+
+async fn run_pending(pool: &PgPool, azure: Option<&AzureBlobService>) {
+    match AccountDataRepository::clear_expired_archives(pool, Utc::now()).await {
+        Ok(paths) => delete_files(azure, &paths).await,
+        Err(error) => warn!(?error, "Failed to clear expired account exports"),
+    }
+
+    loop {
+        match AccountDataRepository::claim_job(pool, Utc::now() - STALE_AFTER).await {
+            Ok(Some(job)) => run_job(pool, azure, &job).await,
+            Ok(None) => break,
+            Err(error) => {
+                warn!(?error, "Failed to claim account data job");
+                break;
+            }
+        }
+    }
+}
+
+/// Spawns a background task that runs queued exports and deletions. Call
+/// once during server startup.
+pub(crate) fn spawn_account_data_task(
+    pool: PgPool,
+    azure: Option<AzureBlobService>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(POLL_INTERVAL);
+        // Skip the immediate first tick so the server can finish starting up.
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+            run_pending(&pool, azure.as_ref()).await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use api_types::MemberRole;
+    use zip::ZipArchive;
+
+    use super::*;
+
+    fn attachment(issue: Option<&str>, name: &str) -> ExportedAttachment {
+        ExportedAttachment {
+            id: Uuid::new_v4(),
+            issue_simple_id: issue.map(str::to_string),
+            original_name: name.to_string(),
+            mime_type: Some("image/png".to_string()),
+            size_bytes: 3,
+            blob_path: format!("attachments/{name}"),
+        }
+    }
+
+    #[test]
+    fn attachment_paths_are_unique() {
+        let attachments = vec![
+            attachment(Some("ABC-1"), "screen shot.png"),
+            attachment(Some("ABC-1"), "screen shot.png"),
+            attachment(None, "me.png"),
+        ];
+        let paths = attachment_paths(&attachments);
+        assert_eq!(paths[0], "attachments/ABC-1/screen_shot.png");
+        assert_eq!(
+            paths[1],
+            format!("attachments/ABC-1/{}_screen_shot.png", attachments[1].id)
+        );
+        assert_eq!(paths[2], "attachments/avatar/me.png");
+    }
+
+    #[test]
+    fn archive_holds_personal_data() {
+        let now = Utc::now();
+        let data = ExportData {
+            profile: ExportedProfile {
+                id: Uuid::new_v4(),
+                email: "ada@example.com".to_string(),
+                first_name: Some("Ada".to_string()),
+                last_name: None,
+                username: None,
+                display_name: None,
+                timezone: None,
+                locale: None,
+                created_at: now,
+            },
+            memberships: vec![ExportedMembership {
+                organization_id: Uuid::new_v4(),
+                organization_name: "Acme".to_string(),
+                role: MemberRole::Admin,
+                joined_at: now,
+            }],
+            issues: vec![ExportedIssue {
+                id: Uuid::new_v4(),
+                simple_id: "ABC-1".to_string(),
+                title: "Broken login".to_string(),
+                description: None,
+                organization_name: "Acme".to_string(),
+                project_name: "Web".to_string(),
+                created_at: now,
+                updated_at: now,
+                completed_at: None,
+            }],
+            comments: Vec::new(),
+            attachments: vec![
+                attachment(Some("ABC-1"), "a.png"),
+                attachment(Some("ABC-1"), "missing.png"),
+            ],
+        };
+
+        let archive = write_archive(&data, &[Some(b"png".to_vec()), None]).unwrap();
+        let mut zip = ZipArchive::new(Cursor::new(archive)).unwrap();
+        let mut names: Vec<_> = zip.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "attachments.csv",
+                "attachments/ABC-1/a.png",
+                "comments.csv",
+                "issues.csv",
+                "profile.json",
+            ]
+        );
+
+        let mut issues = String::new();
+        zip.by_name("issues.csv")
+            .unwrap()
+            .read_to_string(&mut issues)
+            .unwrap();
+        assert!(issues.contains("ABC-1,Broken login,,Acme,Web,"));
+
+        let mut profile = String::new();
+        zip.by_name("profile.json")
+            .unwrap()
+            .read_to_string(&mut profile)
+            .unwrap();
+        let profile: serde_json::Value = serde_json::from_str(&profile).unwrap();
+        assert_eq!(profile["profile"]["email"], "ada@example.com");
+        assert_eq!(profile["organizations"][0]["organization_name"], "Acme");
+    }
+}
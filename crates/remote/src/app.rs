@@ -20,6 +20,8 @@ use crate::{
     mail::{LoopsMailer, Mailer, NoopMailer},
     r2::R2Service,
     routes,
+    session_anomalies::SessionUsageCounter,
+    token_encryption::TokenCipher,
 };
 
 pub struct Server;
@@ -55,9 +57,13 @@ impl Server {
         }
 
         let auth_config = config.auth.clone();
+        let token_cipher =
+            TokenCipher::new(auth_config.jwt_secret(), auth_config.token_encryption())
+                .context("invalid provider token encryption keys")?;
         let jwt = Arc::new(JwtService::new(
             auth_config.jwt_secret().clone(),
             auth_config.access_token_ttl_seconds(),
+            Arc::new(token_cipher),
         ));
 
         let mut registry = ProviderRegistry::new();
@@ -171,6 +177,13 @@ impl Server {
             tracing::info!("Billing provider not configured");
         }
 
+        #[cfg(feature = "vk-billing")]
+        if let Some(meter) = billing.usage_meter() {
+            crate::billing::usage::spawn_usage_report_task(pool.clone(), meter);
+        } else {
+            tracing::info!("Billing usage reporting not configured");
+        }
+
         let analytics = match AnalyticsConfig::from_env() {
             Some(analytics_config) => {
                 tracing::info!("PostHog analytics configured");
@@ -184,6 +197,23 @@ impl Server {
             }
         };
 
+        crate::middleware::idempotency::spawn_cleanup_task(pool.clone());
+        crate::markdown::spawn_cache_cleanup_task(pool.clone());
+        crate::due_date_reminders::spawn_reminder_task(pool.clone());
+        crate::sla::spawn_breach_check_task(pool.clone());
+        if let Some(github_app) = &github_app {
+            crate::github_app::spawn_team_sync_task(pool.clone(), github_app.clone());
+        }
+        crate::retention::spawn_retention_task(pool.clone(), azure_blob.clone());
+        crate::account_data::spawn_account_data_task(pool.clone(), azure_blob.clone());
+        let session_usage = Arc::new(SessionUsageCounter::default());
+        crate::session_anomalies::spawn_usage_flush_task(pool.clone(), session_usage.clone());
+        crate::session_anomalies::spawn_anomaly_check_task(
+            pool.clone(),
+            mailer.clone(),
+            server_public_base_url.clone(),
+        );
+
         if let Some(ref azure_blob_service) = azure_blob {
             spawn_cleanup_task(pool.clone(), azure_blob_service.clone());
         }
@@ -218,6 +248,7 @@ impl Server {
             github_app,
             billing,
             analytics,
+            session_usage,
         );
 
         let router = routes::router(state);
@@ -231,7 +262,7 @@ impl Server {
 
         tracing::info!(%addr, "shared sync server listening");
 
-        let make_service = router.into_make_service();
+        let make_service = router.into_make_service_with_connect_info::<SocketAddr>();
 
         axum::serve(tcp_listener, make_service)
             .await
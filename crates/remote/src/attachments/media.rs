@@ -0,0 +1,516 @@
+//! Content-type sniffing and the metadata clients need to render an
+//! attachment inline: dimensions of images and video, and the duration of
+//! audio and video.
+//!
+//! Durations are read from MP4/QuickTime, WebM/Matroska, WAV and FLAC
+//! headers. Other audio formats are recognized but have no duration.
+
+use std::io::Cursor;
+
+const OCTET_STREAM: &str = "application/octet-stream";
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct MediaInfo {
+    pub content_type: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub duration_ms: Option<u64>,
+}
+
+/// Probe an uploaded file. The content type is the one detected from the
+/// file's content; if it isn't recognized, the declared type is kept unless
+/// it claims to be an image, video or audio, so nothing is rendered inline as
+/// a type its content doesn't match.
+pub fn probe(data: &[u8], declared: Option<&str>) -> MediaInfo {
+    let Some(content_type) = sniff(data) else {
+        let content_type = declared
+            .map(str::trim)
+            .filter(|declared| !declared.is_empty())
+            .map(|declared| {
+                if is_media_type(declared) {
+                    OCTET_STREAM.to_string()
+                } else {
+                    declared.to_string()
+                }
+            });
+        return MediaInfo {
+            content_type,
+            ..MediaInfo::default()
+        };
+    };
+
+    let mut info = MediaInfo {
+        content_type: Some(content_type.to_string()),
+        ..MediaInfo::default()
+    };
+    match content_type {
+        "image/png" | "image/jpeg" | "image/gif" | "image/webp" => {
+            if let Some((width, height)) = image_dimensions(data) {
+                info.width = Some(width);
+                info.height = Some(height);
+            }
+        }
+        "video/mp4" | "video/quicktime" | "audio/mp4" => {
+            let movie = iso_bmff::probe(data);
+            info.duration_ms = movie.duration_ms;
+            if let Some((width, height)) = movie.dimensions {
+                info.width = Some(width);
+                info.height = Some(height);
+            }
+        }
+        "video/webm" | "video/x-matroska" => {
+            let movie = matroska::probe(data);
+            info.duration_ms = movie.duration_ms;
+            if let Some((width, height)) = movie.dimensions {
+                info.width = Some(width);
+                info.height = Some(height);
+            }
+        }
+        "audio/wav" => info.duration_ms = wav_duration_ms(data),
+        "audio/flac" => info.duration_ms = flac_duration_ms(data),
+        _ => {}
+    }
+    info
+}
+
+fn is_media_type(content_type: &str) -> bool {
+    let content_type = content_type.to_ascii_lowercase();
+    ["image/", "video/", "audio/"]
+        .iter()
+        .any(|prefix| content_type.starts_with(prefix))
+}
+
+/// Detect a file's content type from its leading bytes.
+fn sniff(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some("image/png");
+    }
+    if data.starts_with(b"\xFF\xD8\xFF") {
+        return Some("image/jpeg");
+    }
+    if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        return Some("image/gif");
+    }
+    if data.starts_with(b"%PDF-") {
+        return Some("application/pdf");
+    }
+    if data.starts_with(b"RIFF") && data.len() >= 12 {
+        return match &data[8..12] {
+            b"WEBP" => Some("image/webp"),
+            b"WAVE" => Some("audio/wav"),
+            b"AVI " => Some("video/x-msvideo"),
+            _ => None,
+        };
+    }
+    if data.len() >= 12 && &data[4..8] == b"ftyp" {
+        return match &data[8..12] {
+            b"qt  " => Some("video/quicktime"),
+            b"M4A " | b"M4B " => Some("audio/mp4"),
+            b"heic" | b"heix" | b"mif1" | b"msf1" | b"avif" | b"avis" => None,
+            _ => Some("video/mp4"),
+        };
+    }
+    if data.starts_with(b"\x1A\x45\xDF\xA3") {
+        let header = &data[..data.len().min(64)];
+        let is_webm = header.windows(4).any(|window| window == b"webm");
+        return Some(if is_webm {
+            "video/webm"
+        } else {
+            "video/x-matroska"
+        });
+    }
+    if data.starts_with(b"OggS") {
+        return Some("audio/ogg");
+    }
+    if data.starts_with(b"fLaC") {
+        return Some("audio/flac");
+    }
+    if data.starts_with(b"ID3") {
+        return Some("audio/mpeg");
+    }
+    if data.len() >= 2 && data[0] == 0xFF && data[1] & 0xE0 == 0xE0 {
+        // MPEG audio frame sync; layer bits of 0 mean an AAC ADTS frame.
+        return Some(if data[1] & 0x06 == 0 {
+            "audio/aac"
+        } else {
+            "audio/mpeg"
+        });
+    }
+    None
+}
+
+fn image_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    image::ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
+
+fn read_u16_be(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32_be(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes(bytes.try_into().ok()?))
+}
+
+fn read_u64_be(data: &[u8], offset: usize) -> Option<u64> {
+    let bytes = data.get(offset..offset + 8)?;
+    Some(u64::from_be_bytes(bytes.try_into().ok()?))
+}
+
+fn read_u32_le(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn to_ms(duration: u64, units_per_second: u64) -> Option<u64> {
+    if units_per_second == 0 {
+        return None;
+    }
+    u64::try_from(u128::from(duration) * 1000 / u128::from(units_per_second)).ok()
+}
+
+#[derive(Debug, Default)]
+struct Movie {
+    duration_ms: Option<u64>,
+    dimensions: Option<(u32, u32)>,
+}
+
+/// MP4, QuickTime and M4A files (ISO base media file format).
+mod iso_bmff {
+    use super::{Movie, read_u16_be, read_u32_be, read_u64_be, to_ms};
+
+    /// Split `data` into `(box type, payload)` pairs.
+    fn boxes(mut data: &[u8]) -> impl Iterator<Item = (&[u8], &[u8])> {
+        std::iter::from_fn(move || {
+            let size = read_u32_be(data, 0)?;
+            let kind = data.get(4..8)?;
+            let (header, size) = match size {
+                0 => (8, data.len()),
+                1 => (16, usize::try_from(read_u64_be(data, 8)?).ok()?),
+                size => (8, usize::try_from(size).ok()?),
+            };
+            if size < header || size > data.len() {
+                return None;
+            }
+            let payload = &data[header..size];
+            data = &data[size..];
+            Some((kind, payload))
+        })
+    }
+
+    fn child<'a>(data: &'a [u8], kind: &[u8]) -> Option<&'a [u8]> {
+        boxes(data)
+            .find(|(k, _)| *k == kind)
+            .map(|(_, payload)| payload)
+    }
+
+    pub(super) fn probe(data: &[u8]) -> Movie {
+        let Some(moov) = child(data, b"moov") else {
+            return Movie::default();
+        };
+        Movie {
+            duration_ms: child(moov, b"mvhd").and_then(movie_duration_ms),
+            dimensions: boxes(moov)
+                .filter(|(kind, _)| *kind == b"trak")
+                .filter_map(|(_, trak)| child(trak, b"tkhd"))
+                .find_map(track_dimensions),
+        }
+    }
+
+    fn movie_duration_ms(mvhd: &[u8]) -> Option<u64> {
+        let (timescale, duration) = match mvhd.first()? {
+            0 => (read_u32_be(mvhd, 12)?, u64::from(read_u32_be(mvhd, 16)?)),
+            1 => (read_u32_be(mvhd, 20)?, read_u64_be(mvhd, 24)?),
+            _ => return None,
+        };
+        to_ms(duration, u64::from(timescale))
+    }
+
+    /// Video tracks' display size; audio tracks have none.
+    fn track_dimensions(tkhd: &[u8]) -> Option<(u32, u32)> {
+        // Width and height end the box, as 16.16 fixed-point numbers.
+        let offset = tkhd.len().checked_sub(8)?;
+        let width = u32::from(read_u16_be(tkhd, offset)?);
+        let height = u32::from(read_u16_be(tkhd, offset + 4)?);
+        (width > 0 && height > 0).then_some((width, height))
+    }
+}
+
+/// WebM and Matroska files.
+mod matroska {
+    use super::{Movie, read_u64_be};
+
+    const SEGMENT: u32 = 0x18538067;
+    const INFO: u32 = 0x1549A966;
+    const TIMECODE_SCALE: u32 = 0x2AD7B1;
+    const DURATION: u32 = 0x4489;
+    const TRACKS: u32 = 0x1654AE6B;
+    const TRACK_ENTRY: u32 = 0xAE;
+    const VIDEO: u32 = 0xE0;
+    const PIXEL_WIDTH: u32 = 0xB0;
+    const PIXEL_HEIGHT: u32 = 0xBA;
+    const CLUSTER: u32 = 0x1F43B675;
+    const DEFAULT_TIMECODE_SCALE_NS: u64 = 1_000_000;
+
+    /// Read a variable-length integer, returning it and its length. IDs keep
+    /// their length marker; sizes don't.
+    fn read_vint(data: &[u8], keep_marker: bool) -> Option<(u64, usize)> {
+        let first = *data.first()?;
+        let len = first.leading_zeros() as usize + 1;
+        if len > 8 {
+            return None;
+        }
+        let bytes = data.get(..len)?;
+        let mut value = if keep_marker {
+            u64::from(first)
+        } else {
+            u64::from(first) & ((1 << (8 - len)) - 1)
+        };
+        for byte in &bytes[1..] {
+            value = (value << 8) | u64::from(*byte);
+        }
+        Some((value, len))
+    }
+
+    /// Split `data` into `(element ID, payload)` pairs. An element of
+    /// unknown size runs to the end of `data`.
+    fn elements(mut data: &[u8]) -> impl Iterator<Item = (u32, &[u8])> {
+        std::iter::from_fn(move || {
+            let (id, id_len) = read_vint(data, true)?;
+            let (size, size_len) = read_vint(data.get(id_len..)?, false)?;
+            let start = id_len + size_len;
+            let unknown_size = size == (1 << (7 * size_len)) - 1;
+            let end = if unknown_size {
+                data.len()
+            } else {
+                start.checked_add(usize::try_from(size).ok()?)?
+            };
+            let payload = data.get(start..end)?;
+            data = &data[end..];
+            Some((u32::try_from(id).ok()?, payload))
+        })
+    }
+
+    fn read_uint(data: &[u8]) -> Option<u64> {
+        if data.is_empty() || data.len() > 8 {
+            return None;
+        }
+        Some(
+            data.iter()
+                .fold(0, |value, byte| (value << 8) | u64::from(*byte)),
+        )
+    }
+
+    fn read_float(data: &[u8]) -> Option<f64> {
+        match data.len() {
+            4 => Some(f64::from(f32::from_bits(u32::from_be_bytes(
+                data.try_into().ok()?,
+            )))),
+            8 => Some(f64::from_bits(read_u64_be(data, 0)?)),
+            _ => None,
+        }
+    }
+
+    pub(super) fn probe(data: &[u8]) -> Movie {
+        let mut movie = Movie::default();
+        let Some((_, segment)) = elements(data).find(|(id, _)| *id == SEGMENT) else {
+            return movie;
+        };
+        for (id, payload) in elements(segment) {
+            match id {
+                INFO => movie.duration_ms = duration_ms(payload),
+                TRACKS => {
+                    movie.dimensions = elements(payload)
+                        .filter(|(id, _)| *id == TRACK_ENTRY)
+                        .find_map(|(_, entry)| video_dimensions(entry));
+                }
+                // Info and Tracks come before the media data.
+                CLUSTER => break,
+                _ => {}
+            }
+        }
+        movie
+    }
+
+    fn duration_ms(info: &[u8]) -> Option<u64> {
+        let mut scale_ns = DEFAULT_TIMECODE_SCALE_NS;
+        let mut duration = None;
+        for (id, payload) in elements(info) {
+            match id {
+                TIMECODE_SCALE => scale_ns = read_uint(payload)?,
+                DURATION => duration = read_float(payload),
+                _ => {}
+            }
+        }
+        let ms = duration? * scale_ns as f64 / 1_000_000.0;
+        (ms.is_finite() && ms >= 0.0).then(|| ms.round() as u64)
+    }
+
+    fn video_dimensions(entry: &[u8]) -> Option<(u32, u32)> {
+        let (_, video) = elements(entry).find(|(id, _)| *id == VIDEO)?;
+        let mut width = None;
+        let mut height = None;
+        for (id, payload) in elements(video) {
+            match id {
+                PIXEL_WIDTH => width = read_uint(payload),
+                PIXEL_HEIGHT => height = read_uint(payload),
+                _ => {}
+            }
+        }
+        Some((u32::try_from(width?).ok()?, u32::try_from(height?).ok()?))
+    }
+}
+
+/// Length of the `data` chunk at the `fmt ` chunk's byte rate.
+fn wav_duration_ms(data: &[u8]) -> Option<u64> {
+    let mut chunks = data.get(12..)?;
+    let mut byte_rate = None;
+    while let (Some(id), Some(size)) = (chunks.get(..4), read_u32_le(chunks, 4)) {
+        match id {
+            b"fmt " => byte_rate = read_u32_le(chunks, 16),
+            // The data chunk may be cut short; its declared size still counts.
+            b"data" => return to_ms(u64::from(size), u64::from(byte_rate?)),
+            _ => {}
+        }
+        // Chunks are padded to an even length.
+        let size = usize::try_from(size).ok()?;
+        let next = 8usize.checked_add(size)?.checked_add(size % 2)?;
+        chunks = chunks.get(next..)?;
+    }
+    None
+}
+
+/// Total samples over the sample rate, from the STREAMINFO block.
+fn flac_duration_ms(data: &[u8]) -> Option<u64> {
+    // "fLaC", then the STREAMINFO block's 4-byte header.
+    let info = data.get(8..26)?;
+    let sample_rate =
+        (u32::from(info[10]) << 12) | (u32::from(info[11]) << 4) | (u32::from(info[12]) >> 4);
+    let total_samples = (u64::from(info[13] & 0x0F) << 32) | u64::from(read_u32_be(info, 14)?);
+    if total_samples == 0 {
+        return None;
+    }
+    to_ms(total_samples, u64::from(sample_rate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mp4_box(kind: &[u8], payload: &[u8]) -> Vec<u8> {
+        let mut bytes = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        bytes.extend_from_slice(kind);
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    fn ebml(id: &[u8], payload: &[u8]) -> Vec<u8> {
+        let mut bytes = id.to_vec();
+        bytes.push(0x80 | payload.len() as u8);
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    #[test]
+    fn test_sniff_overrides_declared_type() {
+        let png = b"\x89PNG\r\n\x1a\nrest";
+        let info = probe(png, Some("application/pdf"));
+        assert_eq!(info.content_type.as_deref(), Some("image/png"));
+    }
+
+    #[test]
+    fn test_unrecognized_media_is_not_rendered_inline() {
+        let svg = b"<svg xmlns=\"http://www.w3.org/2000/svg\"></svg>";
+        let info = probe(svg, Some("image/svg+xml"));
+        assert_eq!(info.content_type.as_deref(), Some(OCTET_STREAM));
+
+        let text = probe(b"hello", Some("text/plain"));
+        assert_eq!(text.content_type.as_deref(), Some("text/plain"));
+        assert_eq!(probe(b"hello", None).content_type, None);
+    }
+
+    #[test]
+    fn test_mp4_duration_and_dimensions() {
+        let mut mvhd = vec![0; 20];
+        mvhd[12..16].copy_from_slice(&600u32.to_be_bytes());
+        mvhd[16..20].copy_from_slice(&1500u32.to_be_bytes());
+
+        let audio_tkhd = vec![0; 84];
+        let mut video_tkhd = vec![0; 84];
+        video_tkhd[76..78].copy_from_slice(&1280u16.to_be_bytes());
+        video_tkhd[80..82].copy_from_slice(&720u16.to_be_bytes());
+
+        let moov = [
+            mp4_box(b"mvhd", &mvhd),
+            mp4_box(b"trak", &mp4_box(b"tkhd", &audio_tkhd)),
+            mp4_box(b"trak", &mp4_box(b"tkhd", &video_tkhd)),
+        ]
+        .concat();
+        let file = [
+            mp4_box(b"ftyp", b"isom\0\0\0\0"),
+            mp4_box(b"mdat", &[0; 16]),
+            mp4_box(b"moov", &moov),
+        ]
+        .concat();
+
+        let info = probe(&file, Some("video/mp4"));
+        assert_eq!(info.content_type.as_deref(), Some("video/mp4"));
+        assert_eq!(info.duration_ms, Some(2500));
+        assert_eq!((info.width, info.height), (Some(1280), Some(720)));
+    }
+
+    #[test]
+    fn test_webm_duration_and_dimensions() {
+        let info = [
+            ebml(&[0x2A, 0xD7, 0xB1], &[0x0F, 0x42, 0x40]),
+            ebml(&[0x44, 0x89], &4200.0f32.to_be_bytes()),
+        ]
+        .concat();
+        let video = [ebml(&[0xB0], &[0x02, 0x80]), ebml(&[0xBA], &[0x01, 0xE0])].concat();
+        let tracks = ebml(&[0xAE], &ebml(&[0xE0], &video));
+        let segment = [
+            ebml(&[0x15, 0x49, 0xA9, 0x66], &info),
+            ebml(&[0x16, 0x54, 0xAE, 0x6B], &tracks),
+        ]
+        .concat();
+        let file = [
+            ebml(&[0x1A, 0x45, 0xDF, 0xA3], &ebml(&[0x42, 0x82], b"webm")),
+            ebml(&[0x18, 0x53, 0x80, 0x67], &segment),
+        ]
+        .concat();
+
+        let info = probe(&file, None);
+        assert_eq!(info.content_type.as_deref(), Some("video/webm"));
+        assert_eq!(info.duration_ms, Some(4200));
+        assert_eq!((info.width, info.height), (Some(640), Some(480)));
+    }
+
+    #[test]
+    fn test_wav_duration() {
+        let mut fmt = vec![0; 16];
+        fmt[8..12].copy_from_slice(&16_000u32.to_le_bytes());
+        let mut file = b"RIFF\0\0\0\0WAVE".to_vec();
+        file.extend_from_slice(b"fmt ");
+        file.extend_from_slice(&16u32.to_le_bytes());
+        file.extend_from_slice(&fmt);
+        file.extend_from_slice(b"data");
+        file.extend_from_slice(&24_000u32.to_le_bytes());
+
+        let info = probe(&file, Some("audio/x-wav"));
+        assert_eq!(info.content_type.as_deref(), Some("audio/wav"));
+        assert_eq!(info.duration_ms, Some(1500));
+    }
+
+    #[test]
+    fn test_truncated_media_has_no_metadata() {
+        let info = probe(b"\0\0\0\x20ftypisom", None);
+        assert_eq!(info.content_type.as_deref(), Some("video/mp4"));
+        assert_eq!(info.duration_ms, None);
+        assert_eq!(info.width, None);
+    }
+}
@@ -1,2 +1,3 @@
 pub(crate) mod cleanup;
+pub mod media;
 pub mod thumbnail;
@@ -15,6 +15,22 @@ pub enum AuditAction {
     MemberRevokeInvite,
     MemberRemove,
     MemberRoleChange,
+
+    RetentionPolicyUpdate,
+    RetentionPurge,
+    LegalHoldPlace,
+    LegalHoldRelease,
+
+    AccountDataExport,
+    AccountDeletionRequest,
+    AccountDelete,
+
+    IpAllowlistUpdate,
+    IpAllowlistBlock,
+
+    SessionFlag,
+    SessionFlagReview,
+    SessionAnomalySettingsUpdate,
 }
 
 impl AuditAction {
@@ -30,6 +46,18 @@ impl AuditAction {
             Self::MemberRevokeInvite => "member.revoke_invite",
             Self::MemberRemove => "member.remove",
             Self::MemberRoleChange => "member.role_change",
+            Self::RetentionPolicyUpdate => "retention.policy_update",
+            Self::RetentionPurge => "retention.purge",
+            Self::LegalHoldPlace => "retention.legal_hold_place",
+            Self::LegalHoldRelease => "retention.legal_hold_release",
+            Self::AccountDataExport => "account.data_export",
+            Self::AccountDeletionRequest => "account.deletion_request",
+            Self::AccountDelete => "account.delete",
+            Self::IpAllowlistUpdate => "security.ip_allowlist_update",
+            Self::IpAllowlistBlock => "security.ip_allowlist_block",
+            Self::SessionFlag => "security.session_flag",
+            Self::SessionFlagReview => "security.session_flag_review",
+            Self::SessionAnomalySettingsUpdate => "security.session_anomaly_settings_update",
         }
     }
 }
@@ -1,23 +1,18 @@
 use std::{collections::HashSet, sync::Arc};
 
-use aes_gcm::{
-    Aes256Gcm, Key, Nonce,
-    aead::{Aead, AeadCore, KeyInit, OsRng},
-};
 use api_types::User;
-use base64::{
-    Engine as _,
-    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
-};
 use chrono::{DateTime, Duration as ChronoDuration, Utc};
 use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
 use secrecy::{ExposeSecret, SecretString};
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
 use thiserror::Error;
 use uuid::Uuid;
 
-use crate::{auth::provider::ProviderTokenDetails, db::auth::AuthSession};
+use crate::{
+    auth::provider::ProviderTokenDetails,
+    db::auth::AuthSession,
+    token_encryption::{TokenCipher, TokenEncryptionError},
+};
 
 pub const DEFAULT_ACCESS_TOKEN_TTL_SECONDS: u64 = 120;
 pub const REFRESH_TOKEN_TTL_DAYS: i64 = 365;
@@ -27,8 +22,6 @@ pub(crate) const DEFAULT_JWT_LEEWAY_SECONDS: u64 = 60;
 pub enum JwtError {
     #[error("invalid token")]
     InvalidToken,
-    #[error("invalid jwt secret")]
-    InvalidSecret,
     #[error("token expired")]
     TokenExpired,
     #[error("refresh token reused - possible theft detected")]
@@ -87,6 +80,7 @@ pub struct RefreshTokenDetails {
 pub struct JwtService {
     pub secret: Arc<SecretString>,
     access_token_ttl_seconds: u64,
+    token_cipher: Arc<TokenCipher>,
 }
 
 #[derive(Debug, Clone)]
@@ -97,10 +91,15 @@ pub struct Tokens {
 }
 
 impl JwtService {
-    pub fn new(secret: SecretString, access_token_ttl_seconds: u64) -> Self {
+    pub fn new(
+        secret: SecretString,
+        access_token_ttl_seconds: u64,
+        token_cipher: Arc<TokenCipher>,
+    ) -> Self {
         Self {
             secret: Arc::new(secret),
             access_token_ttl_seconds,
+            token_cipher,
         }
     }
 
@@ -250,7 +249,13 @@ impl JwtService {
         &self,
         provider_tokens_blob: &str,
     ) -> Result<ProviderTokenDetails, JwtError> {
-        let decrypted = self.decrypt_data(provider_tokens_blob)?;
+        let decrypted =
+            self.token_cipher
+                .decrypt(provider_tokens_blob)
+                .map_err(|error| match error {
+                    TokenEncryptionError::Encryption => JwtError::EncryptionError,
+                    _ => JwtError::InvalidToken,
+                })?;
         let decrypted_str = String::from_utf8_lossy(&decrypted);
         serde_json::from_str(&decrypted_str).map_err(|_| JwtError::InvalidToken)
     }
@@ -261,55 +266,8 @@ impl JwtService {
     ) -> Result<String, JwtError> {
         let json =
             serde_json::to_string(provider_tokens).map_err(|_| JwtError::SerializationError)?;
-        self.encrypt_data(json.as_bytes())
-    }
-
-    fn encrypt_data(&self, data: &[u8]) -> Result<String, JwtError> {
-        let key_bytes = self.derive_key()?;
-        let key = Key::<Aes256Gcm>::from(key_bytes);
-        let cipher = Aes256Gcm::new(&key);
-        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
-        let ciphertext = cipher
-            .encrypt(&nonce, data)
-            .map_err(|_| JwtError::EncryptionError)?;
-
-        let mut combined = nonce.to_vec();
-        combined.extend_from_slice(&ciphertext);
-
-        Ok(URL_SAFE_NO_PAD.encode(combined))
-    }
-
-    fn decrypt_data(&self, encrypted: &str) -> Result<Vec<u8>, JwtError> {
-        let decoded = URL_SAFE_NO_PAD
-            .decode(encrypted)
-            .map_err(|_| JwtError::InvalidToken)?;
-
-        const NONCE_SIZE: usize = 12; // 96 bits for AES-256-GCM
-        if decoded.len() < NONCE_SIZE {
-            return Err(JwtError::InvalidToken);
-        }
-
-        let key_bytes = self.derive_key()?;
-        let key = Key::<Aes256Gcm>::from(key_bytes);
-        let cipher = Aes256Gcm::new(&key);
-        let nonce_bytes: [u8; NONCE_SIZE] = decoded[..NONCE_SIZE]
-            .try_into()
-            .map_err(|_| JwtError::InvalidToken)?;
-        let nonce = Nonce::from(nonce_bytes);
-        let ciphertext = &decoded[NONCE_SIZE..];
-
-        cipher
-            .decrypt(&nonce, ciphertext)
+        self.token_cipher
+            .encrypt(json.as_bytes())
             .map_err(|_| JwtError::EncryptionError)
     }
-
-    fn derive_key(&self) -> Result<[u8; 32], JwtError> {
-        let secret_bytes = STANDARD
-            .decode(self.secret.expose_secret())
-            .map_err(|_| JwtError::InvalidSecret)?;
-
-        let mut hasher = Sha256::new();
-        hasher.update(&secret_bytes);
-        Ok(hasher.finalize().into())
-    }
 }
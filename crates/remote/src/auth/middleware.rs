@@ -20,6 +20,7 @@ use crate::{
         self,
         auth::{AuthSessionError, AuthSessionRepository, MAX_SESSION_INACTIVITY_DURATION},
         identity_errors::IdentityError,
+        session_anomalies::SessionAnomalyRepository,
         users::UserRepository,
     },
 };
@@ -120,6 +121,21 @@ pub(super) async fn request_context_from_auth_session_id(
         return Err(StatusCode::UNAUTHORIZED.into_response());
     }
 
+    match SessionAnomalyRepository::is_suspended(pool, session.id).await {
+        Ok(false) => {}
+        Ok(true) => {
+            warn!(
+                "session `{}` rejected (suspended pending review)",
+                session.id
+            );
+            return Err(StatusCode::UNAUTHORIZED.into_response());
+        }
+        Err(error) => {
+            warn!(?error, "failed to check session suspension");
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    }
+
     if session.inactivity_duration(Utc::now()) > MAX_SESSION_INACTIVITY_DURATION {
         warn!(
             "session `{}` expired due to inactivity; revoking",
@@ -1,6 +1,11 @@
 #[cfg(feature = "vk-billing")]
 use std::sync::Arc;
 
+#[cfg(feature = "vk-billing")]
+pub mod stripe_api;
+#[cfg(feature = "vk-billing")]
+pub mod usage;
+
 #[cfg(feature = "vk-billing")]
 pub use billing::{
     BillingError, BillingProvider, BillingStatus, BillingStatusResponse, CreatePortalRequest,
@@ -10,12 +15,34 @@ pub use billing::{
 pub struct BillingService {
     #[cfg(feature = "vk-billing")]
     provider: Option<Arc<dyn BillingProvider>>,
+    #[cfg(feature = "vk-billing")]
+    stripe_api: Option<stripe_api::StripeApiClient>,
+    #[cfg(feature = "vk-billing")]
+    usage_meter: Option<Arc<usage::StripeUsageMeter>>,
 }
 
 impl BillingService {
     #[cfg(feature = "vk-billing")]
     pub fn new(provider: Option<Arc<dyn BillingProvider>>) -> Self {
-        Self { provider }
+        Self {
+            provider,
+            stripe_api: None,
+            usage_meter: None,
+        }
+    }
+
+    /// Call the Stripe API directly for what the provider doesn't cover.
+    #[cfg(feature = "vk-billing")]
+    pub fn with_stripe_api(mut self, client: stripe_api::StripeApiClient) -> Self {
+        self.stripe_api = Some(client);
+        self
+    }
+
+    /// Report metered usage to the payments provider on a schedule.
+    #[cfg(feature = "vk-billing")]
+    pub fn with_usage_meter(mut self, meter: usage::StripeUsageMeter) -> Self {
+        self.usage_meter = Some(Arc::new(meter));
+        self
     }
 
     #[cfg(not(feature = "vk-billing"))]
@@ -40,6 +67,18 @@ impl BillingService {
         self.provider.clone()
     }
 
+    /// Returns the Stripe API client if a secret key is configured.
+    #[cfg(feature = "vk-billing")]
+    pub fn stripe_api(&self) -> Option<&stripe_api::StripeApiClient> {
+        self.stripe_api.as_ref()
+    }
+
+    /// Returns the usage meter if usage-based billing is configured.
+    #[cfg(feature = "vk-billing")]
+    pub fn usage_meter(&self) -> Option<Arc<usage::StripeUsageMeter>> {
+        self.usage_meter.clone()
+    }
+
     /// Returns None when billing feature is disabled.
     #[cfg(not(feature = "vk-billing"))]
     pub fn provider(&self) -> Option<std::convert::Infallible> {
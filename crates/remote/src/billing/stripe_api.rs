@@ -0,0 +1,181 @@
+//! Direct calls to the Stripe API for features the billing provider doesn't
+//! cover: usage meter events and accounting documents.
+
+use axum::body::Bytes;
+use chrono::{DateTime, Utc};
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, de::DeserializeOwned};
+use thiserror::Error;
+
+const STRIPE_API_BASE: &str = "https://api.stripe.com/v1";
+
+#[derive(Debug, Error)]
+pub enum StripeApiError {
+    #[error("request to payments provider failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("payments provider rejected request ({status}): {body}")]
+    Rejected {
+        status: reqwest::StatusCode,
+        body: String,
+    },
+}
+
+impl StripeApiError {
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Self::Rejected { status, .. } if *status == reqwest::StatusCode::NOT_FOUND)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StripeList<T> {
+    pub data: Vec<T>,
+    pub has_more: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StripeInvoice {
+    pub id: String,
+    pub customer: Option<String>,
+    pub number: Option<String>,
+    pub status: Option<String>,
+    pub currency: String,
+    pub total: i64,
+    pub amount_paid: i64,
+    pub amount_due: i64,
+    pub created: i64,
+    pub period_start: i64,
+    pub period_end: i64,
+    pub hosted_invoice_url: Option<String>,
+    pub invoice_pdf: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StripeCharge {
+    pub id: String,
+    pub amount: i64,
+    pub amount_refunded: i64,
+    pub currency: String,
+    pub created: i64,
+    pub paid: bool,
+    pub receipt_number: Option<String>,
+    pub receipt_url: Option<String>,
+}
+
+/// Converts a Stripe unix timestamp.
+pub fn stripe_timestamp(seconds: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(seconds, 0).unwrap_or_default()
+}
+
+#[derive(Clone)]
+pub struct StripeApiClient {
+    http: reqwest::Client,
+    secret_key: SecretString,
+}
+
+impl StripeApiClient {
+    /// Returns None when `STRIPE_SECRET_KEY` is not set.
+    pub fn from_env() -> Option<Self> {
+        let secret_key = std::env::var("STRIPE_SECRET_KEY").ok()?;
+        Some(Self {
+            http: reqwest::Client::new(),
+            secret_key: SecretString::from(secret_key),
+        })
+    }
+
+    async fn check(response: reqwest::Response) -> Result<reqwest::Response, StripeApiError> {
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(StripeApiError::Rejected { status, body });
+        }
+        Ok(response)
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, StripeApiError> {
+        let response = self
+            .http
+            .get(format!("{STRIPE_API_BASE}{path}"))
+            .bearer_auth(self.secret_key.expose_secret())
+            .query(query)
+            .send()
+            .await?;
+        Ok(Self::check(response).await?.json().await?)
+    }
+
+    pub async fn create_meter_event(
+        &self,
+        event_name: &str,
+        stripe_customer_id: &str,
+        value: i64,
+        identifier: &str,
+        timestamp: DateTime<Utc>,
+    ) -> Result<(), StripeApiError> {
+        let response = self
+            .http
+            .post(format!("{STRIPE_API_BASE}/billing/meter_events"))
+            .bearer_auth(self.secret_key.expose_secret())
+            .form(&[
+                ("event_name", event_name),
+                ("payload[stripe_customer_id]", stripe_customer_id),
+                ("payload[value]", &value.to_string()),
+                ("identifier", identifier),
+                ("timestamp", &timestamp.timestamp().to_string()),
+            ])
+            .send()
+            .await?;
+        Self::check(response).await?;
+        Ok(())
+    }
+
+    /// Invoices of a customer, newest first.
+    pub async fn list_invoices(
+        &self,
+        stripe_customer_id: &str,
+        limit: u32,
+        starting_after: Option<&str>,
+    ) -> Result<StripeList<StripeInvoice>, StripeApiError> {
+        let mut query = vec![
+            ("customer", stripe_customer_id.to_string()),
+            ("limit", limit.to_string()),
+        ];
+        if let Some(starting_after) = starting_after {
+            query.push(("starting_after", starting_after.to_string()));
+        }
+        self.get("/invoices", &query).await
+    }
+
+    pub async fn retrieve_invoice(
+        &self,
+        invoice_id: &str,
+    ) -> Result<StripeInvoice, StripeApiError> {
+        self.get(&format!("/invoices/{invoice_id}"), &[]).await
+    }
+
+    /// Charges of a customer, newest first. Each carries its receipt link.
+    pub async fn list_charges(
+        &self,
+        stripe_customer_id: &str,
+        limit: u32,
+        starting_after: Option<&str>,
+    ) -> Result<StripeList<StripeCharge>, StripeApiError> {
+        let mut query = vec![
+            ("customer", stripe_customer_id.to_string()),
+            ("limit", limit.to_string()),
+        ];
+        if let Some(starting_after) = starting_after {
+            query.push(("starting_after", starting_after.to_string()));
+        }
+        self.get("/charges", &query).await
+    }
+
+    /// Fetch a document Stripe hosts for an invoice, such as its PDF. These
+    /// links are pre-signed, so the secret key isn't sent along.
+    pub async fn download(&self, url: &str) -> Result<Bytes, StripeApiError> {
+        let response = self.http.get(url).send().await?;
+        Ok(Self::check(response).await?.bytes().await?)
+    }
+}
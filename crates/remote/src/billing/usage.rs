@@ -0,0 +1,214 @@
+//! Scheduled reporting of metered usage (agent minutes and active seats) to
+//! Stripe billing meters.
+
+use std::{panic::AssertUnwindSafe, sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use futures::FutureExt;
+use sqlx::PgPool;
+use thiserror::Error;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+use super::stripe_api::{StripeApiClient, StripeApiError};
+use crate::db::agent_usage::{AgentUsageError, AgentUsageRepository, MeteredOrganization};
+
+const DEFAULT_REPORT_INTERVAL: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, Error)]
+pub enum UsageReportError {
+    #[error(transparent)]
+    Database(#[from] AgentUsageError),
+    #[error(transparent)]
+    Stripe(#[from] StripeApiError),
+}
+
+/// Sends usage to the Stripe meters configured for agent minutes and active
+/// seats. The agent minutes meter should aggregate with `sum` and the seats
+/// meter with `last`.
+pub struct StripeUsageMeter {
+    stripe: StripeApiClient,
+    agent_minutes_event: Option<String>,
+    active_seats_event: Option<String>,
+    report_interval: Duration,
+}
+
+impl StripeUsageMeter {
+    /// Returns None unless at least one meter event name is set.
+    pub fn from_env(stripe: StripeApiClient) -> Option<Self> {
+        let agent_minutes_event = std::env::var("STRIPE_AGENT_MINUTES_METER_EVENT").ok();
+        let active_seats_event = std::env::var("STRIPE_ACTIVE_SEATS_METER_EVENT").ok();
+        if agent_minutes_event.is_none() && active_seats_event.is_none() {
+            return None;
+        }
+        let report_interval = std::env::var("BILLING_USAGE_REPORT_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_REPORT_INTERVAL);
+
+        Some(Self {
+            stripe,
+            agent_minutes_event,
+            active_seats_event,
+            report_interval,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct UsageReportStats {
+    pub organizations: usize,
+    pub events_sent: usize,
+    pub errors: usize,
+}
+
+pub fn spawn_usage_report_task(pool: PgPool, meter: Arc<StripeUsageMeter>) -> JoinHandle<()> {
+    info!(
+        interval_secs = meter.report_interval.as_secs(),
+        agent_minutes = meter.agent_minutes_event.is_some(),
+        active_seats = meter.active_seats_event.is_some(),
+        "Starting billing usage report background task"
+    );
+
+    tokio::spawn(async move {
+        let result = AssertUnwindSafe(report_loop(&pool, meter.as_ref()));
+
+        if let Err(panic) = result.catch_unwind().await {
+            let msg = panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            error!(panic = %msg, "Billing usage report task died — usage will not be reported until next deploy");
+        }
+    })
+}
+
+async fn report_loop(pool: &PgPool, meter: &StripeUsageMeter) {
+    loop {
+        tokio::time::sleep(meter.report_interval).await;
+
+        let lock = match AgentUsageRepository::try_acquire_report_lock(pool).await {
+            Ok(Some(lock)) => lock,
+            Ok(None) => {
+                info!("Skipping billing usage report because another instance is running it");
+                continue;
+            }
+            Err(error) => {
+                error!(error = %error, "Failed to acquire billing usage report lock");
+                continue;
+            }
+        };
+
+        match report_usage(pool, meter, Utc::now()).await {
+            Ok(stats) => info!(
+                organizations = stats.organizations,
+                events_sent = stats.events_sent,
+                errors = stats.errors,
+                "Billing usage report cycle complete"
+            ),
+            Err(error) => error!(error = %error, "Billing usage report cycle failed"),
+        }
+
+        if let Err(error) = lock.release().await {
+            warn!(error = %error, "Failed to release billing usage report lock");
+        }
+    }
+}
+
+/// Report the current period's usage of every subscribed organization.
+pub async fn report_usage(
+    pool: &PgPool,
+    meter: &StripeUsageMeter,
+    now: DateTime<Utc>,
+) -> Result<UsageReportStats, UsageReportError> {
+    let organizations = AgentUsageRepository::list_metered_organizations(pool).await?;
+    let mut stats = UsageReportStats {
+        organizations: organizations.len(),
+        ..Default::default()
+    };
+
+    for organization in &organizations {
+        match report_organization(pool, meter, organization, now).await {
+            Ok(sent) => stats.events_sent += sent,
+            Err(error) => {
+                stats.errors += 1;
+                error!(
+                    ?error,
+                    organization_id = %organization.organization_id,
+                    "Failed to report billing usage"
+                );
+            }
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Agent minutes are sent as the increase since the last report, keyed by the
+/// new running total so a retried event is deduplicated by Stripe. Seats are
+/// sent as the current count whenever it changes.
+async fn report_organization(
+    pool: &PgPool,
+    meter: &StripeUsageMeter,
+    organization: &MeteredOrganization,
+    now: DateTime<Utc>,
+) -> Result<usize, UsageReportError> {
+    let organization_id = organization.organization_id;
+    let period = organization.period(now);
+    let totals = AgentUsageRepository::totals(pool, organization_id, period).await?;
+    let previous = AgentUsageRepository::reported(pool, organization_id, period.start).await?;
+    let mut reported = previous;
+    let mut sent = 0;
+    let period_key = period.start.timestamp();
+
+    if let Some(event_name) = &meter.agent_minutes_event {
+        let increase = totals.agent_minutes - previous.agent_minutes;
+        if increase > 0 {
+            meter
+                .stripe
+                .create_meter_event(
+                    event_name,
+                    &organization.stripe_customer_id,
+                    increase,
+                    &format!(
+                        "{organization_id}-{period_key}-agent-minutes-{}",
+                        totals.agent_minutes
+                    ),
+                    now,
+                )
+                .await?;
+            reported.agent_minutes = totals.agent_minutes;
+            sent += 1;
+        }
+    }
+
+    if let Some(event_name) = &meter.active_seats_event {
+        let active_seats = totals.active_seats as i32;
+        if active_seats != previous.active_seats {
+            meter
+                .stripe
+                .create_meter_event(
+                    event_name,
+                    &organization.stripe_customer_id,
+                    active_seats.into(),
+                    &format!(
+                        "{organization_id}-{period_key}-active-seats-{}",
+                        now.timestamp()
+                    ),
+                    now,
+                )
+                .await?;
+            reported.active_seats = active_seats;
+            sent += 1;
+        }
+    }
+
+    if sent > 0 {
+        AgentUsageRepository::mark_reported(pool, organization_id, period.start, reported).await?;
+    }
+
+    Ok(sent)
+}
@@ -1,23 +1,61 @@
 use std::{env, fs, path::Path};
 
 use api_types::{
-    Attachment, AttachmentUrlResponse, AttachmentWithBlob, Blob, CreateIssueAssigneeRequest,
+    AccountDataJob, AccountDataJobKind, AccountDataJobStatus, Attachment, AttachmentUrlResponse,
+    AttachmentWithBlob, BatchMutation, BatchMutationRequest, BatchMutationResult, BillingPlan,
+    Blob, CalendarFeedResponse, CreateDocumentRequest, CreateIssueAssigneeRequest,
     CreateIssueCommentReactionRequest, CreateIssueCommentRequest, CreateIssueFollowerRequest,
-    CreateIssueRelationshipRequest, CreateIssueRequest, CreateIssueTagRequest,
-    CreateProjectRequest, CreateProjectStatusRequest, CreatePullRequestIssueRequest,
-    CreateTagRequest, ExportRequest, Issue, IssueAssignee, IssueComment, IssueCommentReaction,
-    IssueFollower, IssuePriority, IssueRelationship, IssueRelationshipType, IssueSortField,
-    IssueTag, ListIssuesQuery, ListIssuesResponse, MemberRole, Notification, NotificationGroupKind,
-    NotificationPayload, NotificationType, OrganizationMember, Project, ProjectStatus, PullRequest,
-    PullRequestIssue, PullRequestStatus, SearchIssuesRequest, SortDirection, Tag,
+    CreateIssueOrganizationTagRequest, CreateIssueRelationshipRequest, CreateIssueRequest,
+    CreateIssueTagRequest, CreateOrganizationTagRequest, CreateProjectRequest,
+    CreateProjectStatusRequest, CreatePullRequestIssueRequest, CreateQuerySubscriptionRequest,
+    CreateSlaPolicyRequest, CreateTagRequest, CreateWorkLogRequest, DeleteAccountRequest, Document,
+    DocumentRevision, DocumentRevisionSummary, DocumentSummary, DuplicateTagGroup, ExportRequest,
+    IpNotAllowedError, Issue, IssueAssignee, IssueCodeReference, IssueCodeReferenceKind,
+    IssueComment, IssueCommentReaction, IssueFollower, IssueOrganizationTag, IssuePriority,
+    IssueRelationship, IssueRelationshipType, IssueSortField, IssueTag, IssueTriageRequest,
+    IssueTriageSuggestions, IssueWorkTotal, LegalHold, ListAccountDataJobsResponse,
+    ListDocumentRevisionsResponse, ListDocumentsResponse, ListDuplicateTagsResponse,
+    ListIssueAssigneesQuery, ListIssueAssigneesResponse, ListIssueCodeReferencesResponse,
+    ListIssueCommentReactionsQuery, ListIssueCommentReactionsResponse, ListIssueCommentsQuery,
+    ListIssueCommentsResponse, ListIssueFollowersQuery, ListIssueFollowersResponse,
+    ListIssueOrganizationTagsQuery, ListIssueOrganizationTagsResponse, ListIssueRelationshipsQuery,
+    ListIssueRelationshipsResponse, ListIssueTagsQuery, ListIssueTagsResponse, ListIssuesQuery,
+    ListIssuesResponse, ListLegalHoldsResponse, ListNotificationIssueMutesResponse,
+    ListNotificationTemplatesResponse, ListOrganizationTagsQuery, ListOrganizationTagsResponse,
+    ListProjectStatusWorkflowsQuery, ListProjectStatusWorkflowsResponse, ListProjectStatusesQuery,
+    ListProjectStatusesResponse, ListProjectsQuery, ListProjectsResponse,
+    ListQuerySubscriptionsQuery, ListQuerySubscriptionsResponse, ListSessionFlagsQuery,
+    ListSessionFlagsResponse, ListSlaPoliciesResponse, ListTagsQuery, ListTagsResponse,
+    ListWorkLogsResponse, MarkNotificationsRequest, MarkNotificationsResponse, MemberRole,
+    MergeTagsRequest, MergeTagsResponse, MutationConflict, Notification, NotificationGroupKind,
+    NotificationIssueMute, NotificationPayload, NotificationTemplate, NotificationType,
+    NotificationUnreadCount, OrganizationIpAllowlist, OrganizationMember,
+    OrganizationRetentionPolicy, OrganizationTag, OrganizationWorkingHours, PlaceLegalHoldRequest,
+    PlanEntitlement, PlanEntitlements, Project, ProjectConcurrencySettings,
+    ProjectNotificationLocale, ProjectStatus, ProjectStatusCategory, ProjectStatusWorkflow,
+    ProjectTimeline, PullRequest, PullRequestIssue, PullRequestStatus, QuerySubscription,
+    QuerySubscriptionIssuesResponse, ReleaseNotes, ReleaseNotesIssue, ReleaseNotesPullRequest,
+    ReleaseNotesRequest, ReleaseNotesSection, RenderMarkdownRequest, RenderedMarkdown,
+    ReorderIssueRequest, RetentionProjectReport, RetentionReport, ReviewSessionFlagRequest,
+    SearchDocumentsRequest, SearchIssuesRequest, SessionAnomalyKind, SessionAnomalySettings,
+    SessionFlag, SessionFlagResolution, SessionFlagStatus, SlaIssueStatus, SlaMetric, SlaPolicy,
+    SlaPolicyReport, SlaReport, SlaReportQuery, SlaState, SortDirection, StartWorkTimerRequest,
+    StatusTransitionError, SuggestedAssignee, SuggestedDuplicate, SuggestedPriority, SuggestedTag,
+    Tag, TimelineDependency, TimelineItem, UnreadNotificationCountQuery,
+    UnreadNotificationCountResponse, UpdateDocumentRequest, UpdateIpAllowlistRequest,
     UpdateIssueCommentReactionRequest, UpdateIssueCommentRequest, UpdateIssueRequest,
-    UpdateNotificationRequest, UpdateProjectRequest, UpdateProjectStatusRequest, UpdateTagRequest,
-    User, UserData, Workspace,
+    UpdateNotificationRequest, UpdateOrganizationTagRequest, UpdateOrganizationWorkingHoursRequest,
+    UpdateProjectNotificationLocaleRequest, UpdateProjectRequest, UpdateProjectStatusRequest,
+    UpdateProjectStatusWorkflowRequest, UpdateQuerySubscriptionRequest,
+    UpdateRetentionPolicyRequest, UpdateSessionAnomalySettingsRequest, UpdateSlaPolicyRequest,
+    UpdateTagRequest, UpdateUserProfileRequest, UpdateWorkLogRequest, UpgradeRequiredError,
+    UpsertNotificationTemplateRequest, User, UserData, UserProfile, UserWorkTotal, WorkLog,
+    WorkLogSummary, WorkLogSummaryQuery, WorkTimer, Workspace,
 };
 use relay_types::{CreateRemoteSessionResponse, ListRelayHostsResponse, RelayHost};
 use remote::{
     routes::{
-        all_mutation_definitions,
+        all_mutation_definitions, all_query_definitions,
         attachments::{
             CommitAttachmentsRequest, CommitAttachmentsResponse, ConfirmUploadRequest,
             InitUploadRequest, InitUploadResponse,
@@ -78,9 +116,11 @@ fn export_shapes() -> String {
         NotificationGroupKind::decl(),
         NotificationPayload::decl(),
         NotificationType::decl(),
+        NotificationUnreadCount::decl(),
         Workspace::decl(),
         ProjectStatus::decl(),
         Tag::decl(),
+        OrganizationTag::decl(),
         Issue::decl(),
         IssueAssignee::decl(),
         Blob::decl(),
@@ -88,6 +128,7 @@ fn export_shapes() -> String {
         AttachmentWithBlob::decl(),
         IssueFollower::decl(),
         IssueTag::decl(),
+        IssueOrganizationTag::decl(),
         IssueRelationship::decl(),
         IssueRelationshipType::decl(),
         IssueComment::decl(),
@@ -97,6 +138,41 @@ fn export_shapes() -> String {
         ListIssuesQuery::decl(),
         SearchIssuesRequest::decl(),
         ListIssuesResponse::decl(),
+        // Query params and response types
+        ListProjectsQuery::decl(),
+        ListProjectsResponse::decl(),
+        ListTagsQuery::decl(),
+        ListTagsResponse::decl(),
+        ListOrganizationTagsQuery::decl(),
+        ListOrganizationTagsResponse::decl(),
+        DuplicateTagGroup::decl(),
+        ListDuplicateTagsResponse::decl(),
+        ListProjectStatusesQuery::decl(),
+        ListProjectStatusesResponse::decl(),
+        ProjectStatusCategory::decl(),
+        ProjectStatusWorkflow::decl(),
+        ListProjectStatusWorkflowsQuery::decl(),
+        ListProjectStatusWorkflowsResponse::decl(),
+        UpdateProjectStatusWorkflowRequest::decl(),
+        StatusTransitionError::decl(),
+        ListIssueAssigneesQuery::decl(),
+        ListIssueAssigneesResponse::decl(),
+        ListIssueFollowersQuery::decl(),
+        ListIssueFollowersResponse::decl(),
+        ListIssueTagsQuery::decl(),
+        ListIssueTagsResponse::decl(),
+        ListIssueOrganizationTagsQuery::decl(),
+        ListIssueOrganizationTagsResponse::decl(),
+        ListIssueRelationshipsQuery::decl(),
+        ListIssueRelationshipsResponse::decl(),
+        ListIssueCommentsQuery::decl(),
+        ListIssueCommentsResponse::decl(),
+        ListIssueCommentReactionsQuery::decl(),
+        ListIssueCommentReactionsResponse::decl(),
+        UnreadNotificationCountQuery::decl(),
+        UnreadNotificationCountResponse::decl(),
+        NotificationIssueMute::decl(),
+        ListNotificationIssueMutesResponse::decl(),
         PullRequestStatus::decl(),
         PullRequest::decl(),
         PullRequestIssue::decl(),
@@ -104,29 +180,48 @@ fn export_shapes() -> String {
         SortDirection::decl(),
         UserData::decl(),
         User::decl(),
+        UserProfile::decl(),
+        UpdateUserProfileRequest::decl(),
         RelayHost::decl(),
         ListRelayHostsResponse::decl(),
         CreateRemoteSessionResponse::decl(),
         MemberRole::decl(),
         OrganizationMember::decl(),
+        BillingPlan::decl(),
+        PlanEntitlement::decl(),
+        PlanEntitlements::decl(),
+        UpgradeRequiredError::decl(),
         // Mutation request types
         CreateProjectRequest::decl(),
         UpdateProjectRequest::decl(),
+        ProjectConcurrencySettings::decl(),
         UpdateNotificationRequest::decl(),
+        MarkNotificationsRequest::decl(),
+        MarkNotificationsResponse::decl(),
         CreateTagRequest::decl(),
         UpdateTagRequest::decl(),
+        CreateOrganizationTagRequest::decl(),
+        UpdateOrganizationTagRequest::decl(),
+        MergeTagsRequest::decl(),
+        MergeTagsResponse::decl(),
         CreateProjectStatusRequest::decl(),
         UpdateProjectStatusRequest::decl(),
         CreateIssueRequest::decl(),
         UpdateIssueRequest::decl(),
+        ReorderIssueRequest::decl(),
         CreateIssueAssigneeRequest::decl(),
         CreateIssueFollowerRequest::decl(),
         CreateIssueTagRequest::decl(),
+        CreateIssueOrganizationTagRequest::decl(),
         CreateIssueRelationshipRequest::decl(),
         CreateIssueCommentRequest::decl(),
         UpdateIssueCommentRequest::decl(),
         CreateIssueCommentReactionRequest::decl(),
         UpdateIssueCommentReactionRequest::decl(),
+        MutationConflict::<serde_json::Value>::decl(),
+        BatchMutation::decl(),
+        BatchMutationRequest::decl(),
+        BatchMutationResult::decl(),
         // Attachment API request/response types
         InitUploadRequest::decl(),
         InitUploadResponse::decl(),
@@ -136,6 +231,107 @@ fn export_shapes() -> String {
         AttachmentUrlResponse::decl(),
         // Export API types
         ExportRequest::decl(),
+        // Calendar feed API types
+        CalendarFeedResponse::decl(),
+        // Issue triage API types
+        IssueTriageRequest::decl(),
+        IssueTriageSuggestions::decl(),
+        SuggestedTag::decl(),
+        SuggestedAssignee::decl(),
+        SuggestedPriority::decl(),
+        SuggestedDuplicate::decl(),
+        // Release notes API types
+        ReleaseNotesRequest::decl(),
+        ReleaseNotes::decl(),
+        ReleaseNotesSection::decl(),
+        ReleaseNotesIssue::decl(),
+        ReleaseNotesPullRequest::decl(),
+        // Timeline API types
+        ProjectTimeline::decl(),
+        TimelineItem::decl(),
+        TimelineDependency::decl(),
+        // Work log API types
+        WorkLog::decl(),
+        CreateWorkLogRequest::decl(),
+        UpdateWorkLogRequest::decl(),
+        ListWorkLogsResponse::decl(),
+        WorkLogSummaryQuery::decl(),
+        WorkLogSummary::decl(),
+        IssueWorkTotal::decl(),
+        UserWorkTotal::decl(),
+        WorkTimer::decl(),
+        StartWorkTimerRequest::decl(),
+        // Query subscription API types
+        QuerySubscription::decl(),
+        CreateQuerySubscriptionRequest::decl(),
+        UpdateQuerySubscriptionRequest::decl(),
+        ListQuerySubscriptionsQuery::decl(),
+        ListQuerySubscriptionsResponse::decl(),
+        QuerySubscriptionIssuesResponse::decl(),
+        // Document API types
+        Document::decl(),
+        DocumentSummary::decl(),
+        CreateDocumentRequest::decl(),
+        UpdateDocumentRequest::decl(),
+        ListDocumentsResponse::decl(),
+        SearchDocumentsRequest::decl(),
+        DocumentRevision::decl(),
+        DocumentRevisionSummary::decl(),
+        ListDocumentRevisionsResponse::decl(),
+        // Markdown API types
+        RenderMarkdownRequest::decl(),
+        RenderedMarkdown::decl(),
+        // Issue code reference API types
+        IssueCodeReferenceKind::decl(),
+        IssueCodeReference::decl(),
+        ListIssueCodeReferencesResponse::decl(),
+        // Localization API types
+        NotificationTemplate::decl(),
+        UpsertNotificationTemplateRequest::decl(),
+        ListNotificationTemplatesResponse::decl(),
+        UpdateProjectNotificationLocaleRequest::decl(),
+        ProjectNotificationLocale::decl(),
+        // SLA API types
+        OrganizationWorkingHours::decl(),
+        UpdateOrganizationWorkingHoursRequest::decl(),
+        SlaMetric::decl(),
+        SlaPolicy::decl(),
+        CreateSlaPolicyRequest::decl(),
+        UpdateSlaPolicyRequest::decl(),
+        ListSlaPoliciesResponse::decl(),
+        SlaReportQuery::decl(),
+        SlaState::decl(),
+        SlaIssueStatus::decl(),
+        SlaPolicyReport::decl(),
+        SlaReport::decl(),
+        // Retention API types
+        OrganizationRetentionPolicy::decl(),
+        UpdateRetentionPolicyRequest::decl(),
+        LegalHold::decl(),
+        PlaceLegalHoldRequest::decl(),
+        ListLegalHoldsResponse::decl(),
+        RetentionProjectReport::decl(),
+        RetentionReport::decl(),
+        // Account data API types
+        AccountDataJobKind::decl(),
+        AccountDataJobStatus::decl(),
+        AccountDataJob::decl(),
+        ListAccountDataJobsResponse::decl(),
+        DeleteAccountRequest::decl(),
+        // IP allowlist API types
+        OrganizationIpAllowlist::decl(),
+        UpdateIpAllowlistRequest::decl(),
+        IpNotAllowedError::decl(),
+        // Session anomaly API types
+        SessionAnomalyKind::decl(),
+        SessionFlagStatus::decl(),
+        SessionFlag::decl(),
+        ListSessionFlagsQuery::decl(),
+        ListSessionFlagsResponse::decl(),
+        SessionFlagResolution::decl(),
+        ReviewSessionFlagRequest::decl(),
+        SessionAnomalySettings::decl(),
+        UpdateSessionAnomalySettingsRequest::decl(),
     ];
 
     for decl in type_decls {
@@ -211,6 +407,9 @@ fn export_shapes() -> String {
     );
     output.push_str("  readonly name: string;\n");
     output.push_str("  readonly url: string;\n");
+    output.push_str(
+        "  readonly versionColumn: string | null;  // Updates must send expected_<column>\n",
+    );
     output.push_str(
         "  readonly _rowType: TRow;  // Phantom field for type inference (not present at runtime)\n",
     );
@@ -222,9 +421,12 @@ fn export_shapes() -> String {
     output.push_str("// Helper to create type-safe mutation definitions\n");
     output.push_str("function defineMutation<TRow, TCreate, TUpdate>(\n");
     output.push_str("  name: string,\n");
-    output.push_str("  url: string\n");
+    output.push_str("  url: string,\n");
+    output.push_str("  versionColumn: string | null\n");
     output.push_str("): MutationDefinition<TRow, TCreate, TUpdate> {\n");
-    output.push_str("  return { name, url } as MutationDefinition<TRow, TCreate, TUpdate>;\n");
+    output.push_str(
+        "  return { name, url, versionColumn } as MutationDefinition<TRow, TCreate, TUpdate>;\n",
+    );
     output.push_str("}\n\n");
 
     // Generate individual mutation definitions
@@ -234,10 +436,13 @@ fn export_shapes() -> String {
         let const_name = to_screaming_snake_case(ts_type);
         let create_type = mutation.create_type.as_deref().unwrap_or("unknown");
         let update_type = mutation.update_type.as_deref().unwrap_or("unknown");
+        let version_column = mutation
+            .version_column
+            .map_or("null".to_string(), |column| format!("'{column}'"));
 
         output.push_str(&format!(
-            "export const {}_MUTATION = defineMutation<{}, {}, {}>(\n  '{}',\n  '/v1/{}'\n);\n\n",
-            const_name, ts_type, create_type, update_type, ts_type, mutation.table,
+            "export const {}_MUTATION = defineMutation<{}, {}, {}>(\n  '{}',\n  '/v1/{}',\n  {}\n);\n\n",
+            const_name, ts_type, create_type, update_type, ts_type, mutation.table, version_column,
         ));
     }
 
@@ -247,6 +452,54 @@ fn export_shapes() -> String {
     output.push_str("export type MutationCreateType<M extends MutationDefinition<unknown, unknown>> = M extends MutationDefinition<unknown, infer C> ? C : never;\n");
     output.push_str("export type MutationUpdateType<M extends MutationDefinition<unknown, unknown, unknown>> = M extends MutationDefinition<unknown, unknown, infer U> ? U : never;\n");
 
+    output.push_str(
+        "\n// =============================================================================\n",
+    );
+    output.push_str("// Query Definitions\n");
+    output.push_str(
+        "// =============================================================================\n\n",
+    );
+
+    // QueryDefinition interface
+    output.push_str("// Query definition interface\n");
+    output.push_str("export interface QueryDefinition<TParams, TResponse> {\n");
+    output.push_str("  readonly name: string;\n");
+    output.push_str("  readonly method: 'GET' | 'POST';  // GET sends params as a query string, POST as a JSON body\n");
+    output.push_str("  readonly url: string;\n");
+    output.push_str("  readonly _paramsType: TParams;  // Phantom field for type inference (not present at runtime)\n");
+    output.push_str("  readonly _responseType: TResponse;  // Phantom field for type inference (not present at runtime)\n");
+    output.push_str("}\n\n");
+
+    // Helper function
+    output.push_str("// Helper to create type-safe query definitions\n");
+    output.push_str("function defineQuery<TParams, TResponse>(\n");
+    output.push_str("  name: string,\n");
+    output.push_str("  method: 'GET' | 'POST',\n");
+    output.push_str("  url: string\n");
+    output.push_str("): QueryDefinition<TParams, TResponse> {\n");
+    output.push_str("  return { name, method, url } as QueryDefinition<TParams, TResponse>;\n");
+    output.push_str("}\n\n");
+
+    // Generate individual query definitions
+    output.push_str("// Individual query definitions\n");
+    for query in all_query_definitions() {
+        let params_type = query.params_type.as_deref().unwrap_or("void");
+        output.push_str(&format!(
+            "export const {}_QUERY = defineQuery<{}, {}>(\n  '{}',\n  '{}',\n  '/v1/{}'\n);\n\n",
+            query.name.to_ascii_uppercase(),
+            params_type,
+            query.response_type,
+            query.name,
+            query.method.as_str(),
+            query.path,
+        ));
+    }
+
+    // Type helpers
+    output.push_str("// Type helpers to extract types from a query definition\n");
+    output.push_str("export type QueryParamsType<Q extends QueryDefinition<unknown, unknown>> = Q extends QueryDefinition<infer P, unknown> ? P : never;\n");
+    output.push_str("export type QueryResponseType<Q extends QueryDefinition<unknown, unknown>> = Q extends QueryDefinition<unknown, infer R> ? R : never;\n");
+
     output
 }
 
@@ -0,0 +1,45 @@
+//! Re-encrypts stored provider tokens with the active key from
+//! `PROVIDER_TOKEN_ACTIVE_KEY`. Run with the same environment as the server
+//! after changing the active key; it is safe to run while the server is up
+//! and to run again.
+
+use anyhow::{Context, bail};
+use remote::{
+    config::RemoteServerConfig,
+    db::{self, provider_tokens::ProviderTokenTable},
+    init_tracing,
+    token_encryption::{TokenCipher, rotate_table},
+};
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    init_tracing();
+
+    let config = RemoteServerConfig::from_env()?;
+    let cipher = TokenCipher::new(config.auth.jwt_secret(), config.auth.token_encryption())
+        .context("invalid provider token encryption keys")?;
+    let pool = db::create_pool(&config.database_url)
+        .await
+        .context("failed to connect to database")?;
+
+    let mut failed = 0;
+    for table in ProviderTokenTable::ALL {
+        let report = rotate_table(&pool, &cipher, table)
+            .await
+            .with_context(|| format!("failed to rotate {}", table.name()))?;
+        println!(
+            "{}: {} rotated, {} failed, {} skipped",
+            table.name(),
+            report.rotated,
+            report.failed,
+            report.skipped
+        );
+        failed += report.failed;
+    }
+
+    if failed > 0 {
+        bail!("{failed} values could not be decrypted with the configured keys");
+    }
+    println!("All provider tokens use key `{}`", cipher.active_key_id());
+    Ok(())
+}
@@ -0,0 +1,187 @@
+//! Per-user iCalendar feeds of assigned issues' due dates.
+//!
+//! Calendar apps can't send credentials, so the feed URL carries a token
+//! signed with the server secret. The signature covers the user's
+//! `calendar_feed_key`, letting them revoke old URLs by rotating it.
+
+use chrono::{DateTime, Duration, Utc};
+use utils::tokens::{hmac_sha256, verify_hmac_sha256};
+use uuid::Uuid;
+
+use crate::db::calendar::CalendarIssue;
+
+fn signed_message(user_id: Uuid, feed_key: Uuid) -> String {
+    format!("calendar-feed:{user_id}:{feed_key}")
+}
+
+/// The path segment identifying and authenticating a user's feed.
+pub fn feed_token(secret: &[u8], user_id: Uuid, feed_key: Uuid) -> String {
+    format!(
+        "{}.{}",
+        user_id.simple(),
+        hex::encode(hmac_sha256(
+            secret,
+            signed_message(user_id, feed_key).as_bytes()
+        ))
+    )
+}
+
+/// The user a token was issued for, before its signature is checked.
+pub fn token_user_id(token: &str) -> Option<Uuid> {
+    let (user_id, _) = token.split_once('.')?;
+    Uuid::parse_str(user_id).ok()
+}
+
+pub fn verify_feed_token(secret: &[u8], token: &str, feed_key: Uuid) -> bool {
+    let Some(user_id) = token_user_id(token) else {
+        return false;
+    };
+    let Some((_, provided)) = token.split_once('.') else {
+        return false;
+    };
+    let Ok(provided) = hex::decode(provided) else {
+        return false;
+    };
+    verify_hmac_sha256(
+        secret,
+        signed_message(user_id, feed_key).as_bytes(),
+        &provided,
+    )
+}
+
+pub fn feed_url(base_url: &str, token: &str) -> String {
+    format!(
+        "{}/v1/calendar/{token}/feed.ics",
+        base_url.trim_end_matches('/')
+    )
+}
+
+/// Render the issues as all-day events on their due dates (in UTC).
+pub fn render_feed(issues: &[CalendarIssue], base_url: &str, now: DateTime<Utc>) -> String {
+    let base_url = base_url.trim_end_matches('/');
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Vibe Kanban//Due Dates//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:Vibe Kanban".to_string(),
+    ];
+
+    for issue in issues {
+        let due = issue.target_date.date_naive();
+        let url = format!(
+            "{base_url}/projects/{}/issues/{}",
+            issue.project_id, issue.id
+        );
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:issue-{}@vibe-kanban", issue.id),
+            format!("DTSTAMP:{}", now.format("%Y%m%dT%H%M%SZ")),
+            format!(
+                "LAST-MODIFIED:{}",
+                issue.updated_at.format("%Y%m%dT%H%M%SZ")
+            ),
+            format!("DTSTART;VALUE=DATE:{}", due.format("%Y%m%d")),
+            format!(
+                "DTEND;VALUE=DATE:{}",
+                (due + Duration::days(1)).format("%Y%m%d")
+            ),
+            format!(
+                "SUMMARY:{}",
+                escape_text(&format!("{} {}", issue.simple_id, issue.title))
+            ),
+            format!(
+                "DESCRIPTION:{}",
+                escape_text(&format!("Due in {}\n{url}", issue.project_name))
+            ),
+            format!("URL:{url}"),
+            "TRANSP:TRANSPARENT".to_string(),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut feed = String::new();
+    for line in lines {
+        fold_line(&mut feed, &line);
+    }
+    feed
+}
+
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Append a content line, folded at 75 octets as RFC 5545 requires.
+fn fold_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for ch in line.chars() {
+        if width + ch.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += ch.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    const SECRET: &[u8] = b"test-secret";
+
+    #[test]
+    fn token_verifies_only_with_current_key() {
+        let user_id = Uuid::new_v4();
+        let key = Uuid::new_v4();
+        let token = feed_token(SECRET, user_id, key);
+
+        assert_eq!(token_user_id(&token), Some(user_id));
+        assert!(verify_feed_token(SECRET, &token, key));
+        assert!(!verify_feed_token(SECRET, &token, Uuid::new_v4()));
+        assert!(!verify_feed_token(b"other-secret", &token, key));
+
+        let forged = format!("{}.{}", Uuid::new_v4().simple(), &token[33..]);
+        assert!(!verify_feed_token(SECRET, &forged, key));
+    }
+
+    #[test]
+    fn renders_all_day_event_per_issue() {
+        let issue = CalendarIssue {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            project_name: "Web, API".to_string(),
+            simple_id: "VK-7".to_string(),
+            title: "Ship; then celebrate".to_string(),
+            target_date: Utc.with_ymd_and_hms(2026, 5, 31, 22, 0, 0).unwrap(),
+            updated_at: Utc.with_ymd_and_hms(2026, 5, 1, 9, 0, 0).unwrap(),
+        };
+        let now = Utc.with_ymd_and_hms(2026, 5, 2, 0, 0, 0).unwrap();
+
+        let feed = render_feed(&[issue], "https://vk.example/", now);
+
+        assert!(feed.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(feed.ends_with("END:VCALENDAR\r\n"));
+        assert!(feed.contains("DTSTART;VALUE=DATE:20260531\r\n"));
+        assert!(feed.contains("DTEND;VALUE=DATE:20260601\r\n"));
+        assert!(feed.contains("SUMMARY:VK-7 Ship\\; then celebrate\r\n"));
+        assert!(feed.contains("Due in Web\\, API"));
+        assert!(feed.lines().all(|line| line.len() <= 75));
+    }
+}
@@ -1,6 +1,7 @@
 use std::env;
 
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64_STANDARD};
+use ipnetwork::IpNetwork;
 use secrecy::SecretString;
 use thiserror::Error;
 
@@ -8,6 +9,9 @@ use thiserror::Error;
 pub struct RemoteServerConfig {
     pub database_url: String,
     pub listen_addr: String,
+    /// Proxies whose forwarding headers name the client's address. Requests
+    /// from any other peer are attributed to the peer itself.
+    pub trusted_proxies: Vec<IpNetwork>,
     pub server_public_base_url: Option<String>,
     pub auth: AuthConfig,
     pub refresh_token_overlap_secs: i64,
@@ -20,6 +24,7 @@ pub struct RemoteServerConfig {
     pub review_worker_base_url: Option<String>,
     pub review_disabled: bool,
     pub github_app: Option<GitHubAppConfig>,
+    pub issue_triage: Option<IssueTriageConfig>,
 }
 
 #[derive(Debug, Clone)]
@@ -202,6 +207,49 @@ impl GitHubAppConfig {
     }
 }
 
+/// LLM endpoint behind the issue triage assistant. Any OpenAI-compatible
+/// chat completions API works.
+#[derive(Debug, Clone)]
+pub struct IssueTriageConfig {
+    pub api_url: String,
+    pub api_key: Option<SecretString>,
+    pub model: String,
+}
+
+impl IssueTriageConfig {
+    pub fn from_env() -> Result<Option<Self>, ConfigError> {
+        let api_url = match env::var("ISSUE_TRIAGE_LLM_URL") {
+            Ok(v) if !v.trim().is_empty() => v,
+            _ => {
+                tracing::info!("ISSUE_TRIAGE_LLM_URL not set, issue triage assistant disabled");
+                return Ok(None);
+            }
+        };
+
+        let model = env::var("ISSUE_TRIAGE_LLM_MODEL")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .ok_or(ConfigError::MissingVar("ISSUE_TRIAGE_LLM_MODEL"))?;
+
+        let api_key = env::var("ISSUE_TRIAGE_LLM_API_KEY")
+            .ok()
+            .filter(|v| !v.is_empty())
+            .map(|v| SecretString::new(v.into()));
+
+        tracing::info!(
+            api_url = %api_url,
+            model = %model,
+            "Issue triage config loaded successfully"
+        );
+
+        Ok(Some(Self {
+            api_url,
+            api_key,
+            model,
+        }))
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum ConfigError {
     #[error("environment variable `{0}` is not set")]
@@ -221,6 +269,11 @@ impl RemoteServerConfig {
         let listen_addr =
             env::var("SERVER_LISTEN_ADDR").unwrap_or_else(|_| "0.0.0.0:8081".to_string());
 
+        let trusted_proxies = match env::var("TRUSTED_PROXIES") {
+            Ok(value) => parse_trusted_proxies(&value)?,
+            Err(_) => Vec::new(),
+        };
+
         let server_public_base_url = env::var("SERVER_PUBLIC_BASE_URL").ok();
 
         let auth = AuthConfig::from_env()?;
@@ -257,9 +310,12 @@ impl RemoteServerConfig {
 
         let github_app = GitHubAppConfig::from_env()?;
 
+        let issue_triage = IssueTriageConfig::from_env()?;
+
         Ok(Self {
             database_url,
             listen_addr,
+            trusted_proxies,
             server_public_base_url,
             auth,
             refresh_token_overlap_secs,
@@ -272,10 +328,46 @@ impl RemoteServerConfig {
             review_worker_base_url,
             review_disabled,
             github_app,
+            issue_triage,
         })
     }
 }
 
+#[cfg(test)]
+impl RemoteServerConfig {
+    /// A configuration with every optional integration turned off.
+    pub(crate) fn for_tests() -> Self {
+        Self {
+            database_url: String::new(),
+            listen_addr: "127.0.0.1:0".to_string(),
+            trusted_proxies: Vec::new(),
+            server_public_base_url: Some("http://localhost".to_string()),
+            auth: AuthConfig {
+                github: None,
+                google: None,
+                local: None,
+                jwt_secret: SecretString::new(
+                    "dGVzdC1qd3Qtc2VjcmV0LWZvci10aGUtcmVtb3RlLXRlc3Rz".into(),
+                ),
+                token_encryption: None,
+                public_base_url: "http://localhost".to_string(),
+                access_token_ttl_seconds: crate::auth::DEFAULT_ACCESS_TOKEN_TTL_SECONDS,
+            },
+            refresh_token_overlap_secs: 0,
+            electric_url: String::new(),
+            electric_secret: None,
+            electric_role_password: None,
+            electric_publication_names: Vec::new(),
+            r2: None,
+            azure_blob: None,
+            review_worker_base_url: None,
+            review_disabled: true,
+            github_app: None,
+            issue_triage: None,
+        }
+    }
+}
+
 fn parse_publication_names(value: &str) -> Result<Vec<String>, ConfigError> {
     let mut names = Vec::new();
 
@@ -293,6 +385,19 @@ fn parse_publication_names(value: &str) -> Result<Vec<String>, ConfigError> {
     Ok(names)
 }
 
+fn parse_trusted_proxies(value: &str) -> Result<Vec<IpNetwork>, ConfigError> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|range| !range.is_empty())
+        .map(|range| {
+            range
+                .parse()
+                .map_err(|_| ConfigError::InvalidVar("TRUSTED_PROXIES"))
+        })
+        .collect()
+}
+
 fn is_valid_identifier(value: &str) -> bool {
     let mut chars = value.chars();
     let Some(first) = chars.next() else {
@@ -366,12 +471,71 @@ impl LocalAuthConfig {
     }
 }
 
+/// Key-encryption keys for stored provider tokens, by id.
+#[derive(Debug, Clone)]
+pub struct TokenEncryptionConfig {
+    /// Base64-encoded 32-byte keys.
+    pub keys: Vec<(String, SecretString)>,
+    /// The key new values are encrypted with.
+    pub active_key_id: String,
+}
+
+impl TokenEncryptionConfig {
+    /// `PROVIDER_TOKEN_KEYS` lists keys as `id:base64key`, separated by
+    /// commas. `PROVIDER_TOKEN_ACTIVE_KEY` names the active one, by default
+    /// the first.
+    fn from_env() -> Result<Option<Self>, ConfigError> {
+        let Some(value) = env::var("PROVIDER_TOKEN_KEYS")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+        else {
+            return Ok(None);
+        };
+
+        let mut keys = Vec::new();
+        for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (id, key) = entry
+                .split_once(':')
+                .ok_or(ConfigError::InvalidVar("PROVIDER_TOKEN_KEYS"))?;
+            let id = id.trim();
+            let valid_id = !id.is_empty()
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            let valid_key = BASE64_STANDARD
+                .decode(key.trim().as_bytes())
+                .is_ok_and(|key| key.len() == 32);
+            if !valid_id || !valid_key {
+                return Err(ConfigError::InvalidVar("PROVIDER_TOKEN_KEYS"));
+            }
+            keys.push((id.to_string(), SecretString::new(key.trim().into())));
+        }
+
+        let active_key_id = match env::var("PROVIDER_TOKEN_ACTIVE_KEY") {
+            Ok(id) if !id.trim().is_empty() => id.trim().to_string(),
+            _ => keys
+                .first()
+                .map(|(id, _)| id.clone())
+                .ok_or(ConfigError::InvalidVar("PROVIDER_TOKEN_KEYS"))?,
+        };
+        if !keys.iter().any(|(id, _)| *id == active_key_id) {
+            return Err(ConfigError::InvalidVar("PROVIDER_TOKEN_ACTIVE_KEY"));
+        }
+
+        Ok(Some(Self {
+            keys,
+            active_key_id,
+        }))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AuthConfig {
     github: Option<OAuthProviderConfig>,
     google: Option<OAuthProviderConfig>,
     local: Option<LocalAuthConfig>,
     jwt_secret: SecretString,
+    token_encryption: Option<TokenEncryptionConfig>,
     public_base_url: String,
     access_token_ttl_seconds: u64,
 }
@@ -382,6 +546,7 @@ impl AuthConfig {
             .map_err(|_| ConfigError::MissingVar("VIBEKANBAN_REMOTE_JWT_SECRET"))?;
         validate_jwt_secret(&jwt_secret)?;
         let jwt_secret = SecretString::new(jwt_secret.into());
+        let token_encryption = TokenEncryptionConfig::from_env()?;
 
         let access_token_ttl_seconds = match env::var("ACCESS_TOKEN_TTL_SECONDS") {
             Ok(v) => match v.parse::<u64>() {
@@ -453,6 +618,7 @@ impl AuthConfig {
             google,
             local,
             jwt_secret,
+            token_encryption,
             public_base_url,
             access_token_ttl_seconds,
         })
@@ -474,6 +640,10 @@ impl AuthConfig {
         &self.jwt_secret
     }
 
+    pub fn token_encryption(&self) -> Option<&TokenEncryptionConfig> {
+        self.token_encryption.as_ref()
+    }
+
     pub fn public_base_url(&self) -> &str {
         &self.public_base_url
     }
@@ -0,0 +1,627 @@
+use api_types::{AccountDataJobKind, AccountDataJobStatus, MemberRole};
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, PgPool, Postgres};
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::retention::PurgedBlob;
+
+#[derive(Debug, Error)]
+pub enum AccountDataError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("the account is the last admin of {}", .0.join(", "))]
+    LastAdmin(Vec<String>),
+    #[error("a project in the account's personal organization is on legal hold")]
+    LegalHold,
+}
+
+#[derive(Debug, Clone)]
+pub struct AccountDataJobRow {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: AccountDataJobKind,
+    pub status: AccountDataJobStatus,
+    pub archive_blob_path: Option<String>,
+    pub archive_expires_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ExportedProfile {
+    pub id: Uuid,
+    pub email: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub username: Option<String>,
+    pub display_name: Option<String>,
+    pub timezone: Option<String>,
+    pub locale: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ExportedMembership {
+    pub organization_id: Uuid,
+    pub organization_name: String,
+    pub role: MemberRole,
+    pub joined_at: DateTime<Utc>,
+}
+
+/// An issue the user created.
+#[derive(Debug, Clone)]
+pub struct ExportedIssue {
+    pub id: Uuid,
+    pub simple_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub organization_name: String,
+    pub project_name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// A comment the user wrote.
+#[derive(Debug, Clone)]
+pub struct ExportedComment {
+    pub id: Uuid,
+    pub issue_simple_id: String,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// An attachment on the user's issues or comments, or their avatar.
+#[derive(Debug, Clone)]
+pub struct ExportedAttachment {
+    pub id: Uuid,
+    /// `None` for the avatar.
+    pub issue_simple_id: Option<String>,
+    pub original_name: String,
+    pub mime_type: Option<String>,
+    pub size_bytes: i64,
+    pub blob_path: String,
+}
+
+pub struct AccountDataRepository;
+
+impl AccountDataRepository {
+    /// Queue a job, unless one of the same kind is already unfinished.
+    pub async fn create_job(
+        pool: &PgPool,
+        user_id: Uuid,
+        kind: AccountDataJobKind,
+    ) -> Result<Option<AccountDataJobRow>, AccountDataError> {
+        let job = sqlx::query_as!(
+            AccountDataJobRow,
+            r#"
+            INSERT INTO account_data_jobs (user_id, kind)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id, kind) WHERE status IN ('pending', 'running') DO NOTHING
+            RETURNING
+                id                 AS "id!: Uuid",
+                user_id            AS "user_id!: Uuid",
+                kind               AS "kind!: AccountDataJobKind",
+                status             AS "status!: AccountDataJobStatus",
+                archive_blob_path  AS "archive_blob_path?",
+                archive_expires_at AS "archive_expires_at?: DateTime<Utc>",
+                error              AS "error?",
+                created_at         AS "created_at!: DateTime<Utc>",
+                started_at         AS "started_at?: DateTime<Utc>",
+                completed_at       AS "completed_at?: DateTime<Utc>"
+            "#,
+            user_id,
+            kind as AccountDataJobKind
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    pub async fn find_job(
+        pool: &PgPool,
+        job_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<AccountDataJobRow>, AccountDataError> {
+        let job = sqlx::query_as!(
+            AccountDataJobRow,
+            r#"
+            SELECT
+                id                 AS "id!: Uuid",
+                user_id            AS "user_id!: Uuid",
+                kind               AS "kind!: AccountDataJobKind",
+                status             AS "status!: AccountDataJobStatus",
+                archive_blob_path  AS "archive_blob_path?",
+                archive_expires_at AS "archive_expires_at?: DateTime<Utc>",
+                error              AS "error?",
+                created_at         AS "created_at!: DateTime<Utc>",
+                started_at         AS "started_at?: DateTime<Utc>",
+                completed_at       AS "completed_at?: DateTime<Utc>"
+            FROM account_data_jobs
+            WHERE id = $1 AND user_id = $2
+            "#,
+            job_id,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    pub async fn list_jobs(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<AccountDataJobRow>, AccountDataError> {
+        let jobs = sqlx::query_as!(
+            AccountDataJobRow,
+            r#"
+            SELECT
+                id                 AS "id!: Uuid",
+                user_id            AS "user_id!: Uuid",
+                kind               AS "kind!: AccountDataJobKind",
+                status             AS "status!: AccountDataJobStatus",
+                archive_blob_path  AS "archive_blob_path?",
+                archive_expires_at AS "archive_expires_at?: DateTime<Utc>",
+                error              AS "error?",
+                created_at         AS "created_at!: DateTime<Utc>",
+                started_at         AS "started_at?: DateTime<Utc>",
+                completed_at       AS "completed_at?: DateTime<Utc>"
+            FROM account_data_jobs
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(jobs)
+    }
+
+    /// Start the oldest pending job, or one left running since before
+    /// `stale_before` by a server that stopped.
+    pub async fn claim_job(
+        pool: &PgPool,
+        stale_before: DateTime<Utc>,
+    ) -> Result<Option<AccountDataJobRow>, AccountDataError> {
+        let job = sqlx::query_as!(
+            AccountDataJobRow,
+            r#"
+            UPDATE account_data_jobs
+            SET status = 'running', started_at = NOW()
+            WHERE id = (
+                SELECT id
+                FROM account_data_jobs
+                WHERE status = 'pending' OR (status = 'running' AND started_at < $1)
+                ORDER BY created_at
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING
+                id                 AS "id!: Uuid",
+                user_id            AS "user_id!: Uuid",
+                kind               AS "kind!: AccountDataJobKind",
+                status             AS "status!: AccountDataJobStatus",
+                archive_blob_path  AS "archive_blob_path?",
+                archive_expires_at AS "archive_expires_at?: DateTime<Utc>",
+                error              AS "error?",
+                created_at         AS "created_at!: DateTime<Utc>",
+                started_at         AS "started_at?: DateTime<Utc>",
+                completed_at       AS "completed_at?: DateTime<Utc>"
+            "#,
+            stale_before
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    pub async fn complete_job(
+        pool: &PgPool,
+        job_id: Uuid,
+        archive: Option<(&str, DateTime<Utc>)>,
+    ) -> Result<(), AccountDataError> {
+        let (archive_blob_path, archive_expires_at) = archive.unzip();
+        sqlx::query!(
+            r#"
+            UPDATE account_data_jobs
+            SET status = 'completed',
+                archive_blob_path = $2,
+                archive_expires_at = $3,
+                error = NULL,
+                completed_at = NOW()
+            WHERE id = $1
+            "#,
+            job_id,
+            archive_blob_path,
+            archive_expires_at
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn fail_job(
+        pool: &PgPool,
+        job_id: Uuid,
+        error: &str,
+    ) -> Result<(), AccountDataError> {
+        sqlx::query!(
+            r#"
+            UPDATE account_data_jobs
+            SET status = 'failed', error = $2, completed_at = NOW()
+            WHERE id = $1
+            "#,
+            job_id,
+            error
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Forget export archives that expired before `now`, returning their
+    /// paths so the files can be deleted.
+    pub async fn clear_expired_archives(
+        pool: &PgPool,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<String>, AccountDataError> {
+        let paths = sqlx::query_scalar!(
+            r#"
+            UPDATE account_data_jobs j
+            SET archive_blob_path = NULL
+            FROM (
+                SELECT id, archive_blob_path
+                FROM account_data_jobs
+                WHERE archive_blob_path IS NOT NULL AND archive_expires_at < $1
+                FOR UPDATE
+            ) expired
+            WHERE j.id = expired.id
+            RETURNING expired.archive_blob_path AS "archive_blob_path!"
+            "#,
+            now
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(paths)
+    }
+
+    /// Forget every export archive of the user, returning their paths.
+    pub async fn clear_user_archives(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<String>, AccountDataError> {
+        let paths = sqlx::query_scalar!(
+            r#"
+            UPDATE account_data_jobs j
+            SET archive_blob_path = NULL
+            FROM (
+                SELECT id, archive_blob_path
+                FROM account_data_jobs
+                WHERE archive_blob_path IS NOT NULL AND user_id = $1
+                FOR UPDATE
+            ) cleared
+            WHERE j.id = cleared.id
+            RETURNING cleared.archive_blob_path AS "archive_blob_path!"
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(paths)
+    }
+
+    pub async fn profile(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Option<ExportedProfile>, AccountDataError> {
+        let profile = sqlx::query_as!(
+            ExportedProfile,
+            r#"
+            SELECT
+                id           AS "id!: Uuid",
+                email        AS "email!",
+                first_name   AS "first_name?",
+                last_name    AS "last_name?",
+                username     AS "username?",
+                display_name AS "display_name?",
+                timezone     AS "timezone?",
+                locale       AS "locale?",
+                created_at   AS "created_at!: DateTime<Utc>"
+            FROM users
+            WHERE id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(profile)
+    }
+
+    pub async fn memberships(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<ExportedMembership>, AccountDataError> {
+        let memberships = sqlx::query_as!(
+            ExportedMembership,
+            r#"
+            SELECT
+                o.id          AS "organization_id!: Uuid",
+                o.name        AS "organization_name!",
+                omm.role      AS "role!: MemberRole",
+                omm.joined_at AS "joined_at!: DateTime<Utc>"
+            FROM organization_member_metadata omm
+            INNER JOIN organizations o ON o.id = omm.organization_id
+            WHERE omm.user_id = $1
+            ORDER BY o.name
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(memberships)
+    }
+
+    pub async fn issues(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<ExportedIssue>, AccountDataError> {
+        let issues = sqlx::query_as!(
+            ExportedIssue,
+            r#"
+            SELECT
+                i.id           AS "id!: Uuid",
+                i.simple_id    AS "simple_id!",
+                i.title        AS "title!",
+                i.description  AS "description?",
+                o.name         AS "organization_name!",
+                p.name         AS "project_name!",
+                i.created_at   AS "created_at!: DateTime<Utc>",
+                i.updated_at   AS "updated_at!: DateTime<Utc>",
+                i.completed_at AS "completed_at?: DateTime<Utc>"
+            FROM issues i
+            INNER JOIN projects p ON p.id = i.project_id
+            INNER JOIN organizations o ON o.id = p.organization_id
+            WHERE i.creator_user_id = $1
+            ORDER BY i.created_at
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(issues)
+    }
+
+    pub async fn comments(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<ExportedComment>, AccountDataError> {
+        let comments = sqlx::query_as!(
+            ExportedComment,
+            r#"
+            SELECT
+                c.id         AS "id!: Uuid",
+                i.simple_id  AS "issue_simple_id!",
+                c.message    AS "message!",
+                c.created_at AS "created_at!: DateTime<Utc>",
+                c.updated_at AS "updated_at!: DateTime<Utc>"
+            FROM issue_comments c
+            INNER JOIN issues i ON i.id = c.issue_id
+            WHERE c.author_id = $1
+            ORDER BY c.created_at
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(comments)
+    }
+
+    pub async fn attachments(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<ExportedAttachment>, AccountDataError> {
+        let attachments = sqlx::query_as!(
+            ExportedAttachment,
+            r#"
+            SELECT
+                a.id            AS "id!: Uuid",
+                i.simple_id     AS "issue_simple_id?",
+                b.original_name AS "original_name!",
+                b.mime_type     AS "mime_type?",
+                b.size_bytes    AS "size_bytes!",
+                b.blob_path     AS "blob_path!"
+            FROM attachments a
+            INNER JOIN blobs b ON b.id = a.blob_id
+            LEFT JOIN issue_comments c ON c.id = a.comment_id
+            LEFT JOIN issues i ON i.id = COALESCE(a.issue_id, c.issue_id)
+            WHERE (a.issue_id IS NOT NULL AND i.creator_user_id = $1)
+               OR c.author_id = $1
+               OR a.id = (SELECT avatar_attachment_id FROM users WHERE id = $1)
+            ORDER BY a.created_at
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(attachments)
+    }
+
+    /// Names of the organizations, other than personal ones, the user is the
+    /// only admin of.
+    pub async fn sole_admin_organizations<'e, E>(
+        executor: E,
+        user_id: Uuid,
+    ) -> Result<Vec<String>, AccountDataError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let names = sqlx::query_scalar!(
+            r#"
+            SELECT o.name
+            FROM organizations o
+            INNER JOIN organization_member_metadata omm
+                ON omm.organization_id = o.id AND omm.user_id = $1 AND omm.role = 'admin'
+            WHERE NOT o.is_personal
+              AND NOT EXISTS (
+                  SELECT 1
+                  FROM organization_member_metadata other
+                  WHERE other.organization_id = o.id
+                    AND other.role = 'admin'
+                    AND other.user_id <> $1
+              )
+            ORDER BY o.name
+            "#,
+            user_id
+        )
+        .fetch_all(executor)
+        .await?;
+
+        Ok(names)
+    }
+
+    /// Delete the user with their personal organizations and avatar. What
+    /// they authored elsewhere is kept without its author: the foreign keys
+    /// to the user set it to NULL. Returns the blobs whose files can be
+    /// deleted.
+    pub async fn delete_account(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<PurgedBlob>, AccountDataError> {
+        let mut tx = pool.begin().await?;
+
+        let avatar_attachment_id: Option<Option<Uuid>> = sqlx::query_scalar!(
+            "SELECT avatar_attachment_id FROM users WHERE id = $1 FOR UPDATE",
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(avatar_attachment_id) = avatar_attachment_id else {
+            // Already deleted by an earlier attempt.
+            return Ok(Vec::new());
+        };
+
+        // Lock the admins of the user's organizations, as removing a member
+        // does, so no other admin leaves meanwhile.
+        sqlx::query!(
+            r#"
+            SELECT user_id
+            FROM organization_member_metadata
+            WHERE role = 'admin'
+              AND organization_id IN (
+                  SELECT organization_id FROM organization_member_metadata WHERE user_id = $1
+              )
+            FOR UPDATE
+            "#,
+            user_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        let sole_admin_of = Self::sole_admin_organizations(&mut *tx, user_id).await?;
+        if !sole_admin_of.is_empty() {
+            return Err(AccountDataError::LastAdmin(sole_admin_of));
+        }
+
+        let personal_organization_ids: Vec<Uuid> = sqlx::query_scalar!(
+            r#"
+            SELECT o.id
+            FROM organizations o
+            INNER JOIN organization_member_metadata omm ON omm.organization_id = o.id
+            WHERE o.is_personal AND omm.user_id = $1 AND omm.role = 'admin'
+            FOR UPDATE OF o
+            "#,
+            user_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        let held: bool = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1
+                FROM project_legal_holds h
+                INNER JOIN projects p ON p.id = h.project_id
+                WHERE p.organization_id = ANY($1)
+            ) AS "held!"
+            "#,
+            &personal_organization_ids
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if held {
+            return Err(AccountDataError::LegalHold);
+        }
+
+        let mut blobs = sqlx::query_as!(
+            PurgedBlob,
+            r#"
+            SELECT
+                b.blob_path           AS "blob_path!",
+                b.thumbnail_blob_path AS "thumbnail_blob_path?"
+            FROM blobs b
+            INNER JOIN projects p ON p.id = b.project_id
+            WHERE p.organization_id = ANY($1)
+            "#,
+            &personal_organization_ids
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM organizations WHERE id = ANY($1)",
+            &personal_organization_ids
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if let Some(attachment_id) = avatar_attachment_id {
+            let blob_id: Option<Uuid> = sqlx::query_scalar!(
+                "DELETE FROM attachments WHERE id = $1 RETURNING blob_id",
+                attachment_id
+            )
+            .fetch_optional(&mut *tx)
+            .await?;
+            let avatar_blobs = sqlx::query_as!(
+                PurgedBlob,
+                r#"
+                DELETE FROM blobs b
+                WHERE b.id = $1
+                  AND NOT EXISTS (SELECT 1 FROM attachments a WHERE a.blob_id = b.id)
+                RETURNING
+                    b.blob_path           AS "blob_path!",
+                    b.thumbnail_blob_path AS "thumbnail_blob_path?"
+                "#,
+                blob_id
+            )
+            .fetch_all(&mut *tx)
+            .await?;
+            blobs.extend(avatar_blobs);
+        }
+
+        // The only reference to users without an ON DELETE action.
+        sqlx::query!("DELETE FROM oauth_handoffs WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query!("DELETE FROM users WHERE id = $1", user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(blobs)
+    }
+}
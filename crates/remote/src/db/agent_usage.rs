@@ -0,0 +1,344 @@
+use api_types::MemberAgentUsage;
+use chrono::{DateTime, Datelike, Months, TimeZone, Utc};
+use sqlx::{PgPool, Postgres, pool::PoolConnection};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum AgentUsageError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+pub struct RecordAgentUsageParams {
+    pub execution_process_id: Uuid,
+    pub organization_id: Uuid,
+    pub project_id: Uuid,
+    pub workspace_id: Uuid,
+    pub user_id: Uuid,
+    pub executor: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UsagePeriod {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UsageTotals {
+    pub agent_minutes: i64,
+    pub active_seats: i64,
+}
+
+/// Usage already sent to the payments provider for a period.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReportedUsage {
+    pub agent_minutes: i64,
+    pub active_seats: i32,
+}
+
+/// An organization with a subscription that usage is reported against.
+#[derive(Debug, Clone)]
+pub struct MeteredOrganization {
+    pub organization_id: Uuid,
+    pub stripe_customer_id: String,
+    pub current_period_start: Option<DateTime<Utc>>,
+    pub current_period_end: Option<DateTime<Utc>>,
+}
+
+impl MeteredOrganization {
+    pub fn period(&self, now: DateTime<Utc>) -> UsagePeriod {
+        usage_period(now, self.current_period_start, self.current_period_end)
+    }
+}
+
+const USAGE_REPORT_ADVISORY_LOCK_ID: i64 = 3_447_201_002;
+
+pub struct UsageReportLock {
+    connection: PoolConnection<Postgres>,
+}
+
+impl UsageReportLock {
+    pub async fn release(mut self) -> Result<(), sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT pg_advisory_unlock($1) AS "unlocked!""#,
+            USAGE_REPORT_ADVISORY_LOCK_ID
+        )
+        .fetch_one(&mut *self.connection)
+        .await?;
+
+        Ok(())
+    }
+}
+
+pub struct AgentUsageRepository;
+
+impl AgentUsageRepository {
+    pub async fn try_acquire_report_lock(
+        pool: &PgPool,
+    ) -> Result<Option<UsageReportLock>, sqlx::Error> {
+        let mut connection = pool.acquire().await?;
+        let acquired: bool = sqlx::query_scalar!(
+            r#"SELECT pg_try_advisory_lock($1) AS "acquired!""#,
+            USAGE_REPORT_ADVISORY_LOCK_ID
+        )
+        .fetch_one(&mut *connection)
+        .await?;
+
+        Ok(acquired.then_some(UsageReportLock { connection }))
+    }
+
+    /// Record a finished agent run. Reporting the same execution process
+    /// again is a no-op; returns whether a new record was stored.
+    pub async fn record(
+        pool: &PgPool,
+        params: RecordAgentUsageParams,
+    ) -> Result<bool, AgentUsageError> {
+        let duration_seconds = (params.completed_at - params.started_at)
+            .num_seconds()
+            .clamp(0, i32::MAX as i64) as i32;
+
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO agent_usage_records (
+                execution_process_id, organization_id, project_id, workspace_id,
+                user_id, executor, started_at, completed_at, duration_seconds
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (execution_process_id) DO NOTHING
+            "#,
+            params.execution_process_id,
+            params.organization_id,
+            params.project_id,
+            params.workspace_id,
+            params.user_id,
+            params.executor,
+            params.started_at,
+            params.completed_at,
+            duration_seconds
+        )
+        .execute(pool)
+        .await?
+        .rows_affected();
+
+        Ok(inserted > 0)
+    }
+
+    /// The billing period of the organization's subscription containing
+    /// `now`, or the calendar month when it has none.
+    pub async fn current_period(
+        pool: &PgPool,
+        organization_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<UsagePeriod, AgentUsageError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                current_period_start AS "current_period_start?: DateTime<Utc>",
+                current_period_end   AS "current_period_end?: DateTime<Utc>"
+            FROM organization_billing
+            WHERE organization_id = $1
+            "#,
+            organization_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        let (start, end) = row
+            .map(|row| (row.current_period_start, row.current_period_end))
+            .unwrap_or_default();
+        Ok(usage_period(now, start, end))
+    }
+
+    pub async fn totals(
+        pool: &PgPool,
+        organization_id: Uuid,
+        period: UsagePeriod,
+    ) -> Result<UsageTotals, AgentUsageError> {
+        let totals = sqlx::query_as!(
+            UsageTotals,
+            r#"
+            SELECT
+                COALESCE(SUM(duration_seconds), 0)::BIGINT / 60 AS "agent_minutes!",
+                COUNT(DISTINCT user_id)                         AS "active_seats!"
+            FROM agent_usage_records
+            WHERE organization_id = $1
+              AND completed_at >= $2
+              AND completed_at < $3
+            "#,
+            organization_id,
+            period.start,
+            period.end
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(totals)
+    }
+
+    pub async fn member_usage(
+        pool: &PgPool,
+        organization_id: Uuid,
+        period: UsagePeriod,
+    ) -> Result<Vec<MemberAgentUsage>, AgentUsageError> {
+        let usage = sqlx::query_as!(
+            MemberAgentUsage,
+            r#"
+            SELECT
+                user_id                            AS "user_id!: Uuid",
+                COUNT(*)                           AS "agent_runs!",
+                SUM(duration_seconds)::BIGINT / 60 AS "agent_minutes!"
+            FROM agent_usage_records
+            WHERE organization_id = $1
+              AND completed_at >= $2
+              AND completed_at < $3
+              AND user_id IS NOT NULL
+            GROUP BY user_id
+            ORDER BY 3 DESC, user_id
+            "#,
+            organization_id,
+            period.start,
+            period.end
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(usage)
+    }
+
+    pub async fn reported(
+        pool: &PgPool,
+        organization_id: Uuid,
+        period_start: DateTime<Utc>,
+    ) -> Result<ReportedUsage, AgentUsageError> {
+        let reported = sqlx::query_as!(
+            ReportedUsage,
+            r#"
+            SELECT
+                agent_minutes AS "agent_minutes!",
+                active_seats  AS "active_seats!"
+            FROM billing_usage_reports
+            WHERE organization_id = $1 AND period_start = $2
+            "#,
+            organization_id,
+            period_start
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(reported.unwrap_or_default())
+    }
+
+    pub async fn mark_reported(
+        pool: &PgPool,
+        organization_id: Uuid,
+        period_start: DateTime<Utc>,
+        reported: ReportedUsage,
+    ) -> Result<(), AgentUsageError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO billing_usage_reports (organization_id, period_start, agent_minutes, active_seats)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (organization_id, period_start) DO UPDATE SET
+                agent_minutes = EXCLUDED.agent_minutes,
+                active_seats = EXCLUDED.active_seats,
+                reported_at = NOW()
+            "#,
+            organization_id,
+            period_start,
+            reported.agent_minutes,
+            reported.active_seats
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Organizations with a live subscription to report usage against.
+    pub async fn list_metered_organizations(
+        pool: &PgPool,
+    ) -> Result<Vec<MeteredOrganization>, AgentUsageError> {
+        let records = sqlx::query_as!(
+            MeteredOrganization,
+            r#"
+            SELECT
+                organization_id      AS "organization_id!: Uuid",
+                stripe_customer_id   AS "stripe_customer_id!",
+                current_period_start AS "current_period_start?: DateTime<Utc>",
+                current_period_end   AS "current_period_end?: DateTime<Utc>"
+            FROM organization_billing
+            WHERE stripe_customer_id IS NOT NULL
+              AND subscription_status IN ('active', 'trialing', 'past_due')
+            "#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(records)
+    }
+}
+
+/// The subscription period containing `now`, falling back to the calendar
+/// month when the subscription period is unknown or has already ended.
+pub fn usage_period(
+    now: DateTime<Utc>,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+) -> UsagePeriod {
+    if let (Some(start), Some(end)) = (start, end)
+        && start <= now
+        && now < end
+    {
+        return UsagePeriod { start, end };
+    }
+
+    let start = Utc
+        .with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+        .single()
+        .unwrap_or(now);
+    UsagePeriod {
+        start,
+        end: start + Months::new(1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn uses_subscription_period_containing_now() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 20, 12, 0, 0).unwrap();
+        let start = Utc.with_ymd_and_hms(2026, 3, 5, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2026, 4, 5, 0, 0, 0).unwrap();
+
+        assert_eq!(
+            usage_period(now, Some(start), Some(end)),
+            UsagePeriod { start, end }
+        );
+    }
+
+    #[test]
+    fn falls_back_to_calendar_month() {
+        let now = Utc.with_ymd_and_hms(2026, 12, 20, 12, 0, 0).unwrap();
+        let expected = UsagePeriod {
+            start: Utc.with_ymd_and_hms(2026, 12, 1, 0, 0, 0).unwrap(),
+            end: Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap(),
+        };
+
+        assert_eq!(usage_period(now, None, None), expected);
+
+        // A stale subscription period (webhook not yet received) is ignored.
+        let stale_end = now - Duration::days(2);
+        assert_eq!(
+            usage_period(now, Some(stale_end - Duration::days(30)), Some(stale_end)),
+            expected
+        );
+    }
+}
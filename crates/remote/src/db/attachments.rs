@@ -65,7 +65,8 @@ impl AttachmentRepository {
                 b.size_bytes            AS "size_bytes!",
                 b.hash                  AS "hash!",
                 b.width                 AS "width?",
-                b.height                AS "height?"
+                b.height                AS "height?",
+                b.duration_ms           AS "duration_ms?"
             FROM attachments a
             INNER JOIN blobs b ON b.id = a.blob_id
             WHERE a.id = $1
@@ -99,7 +100,8 @@ impl AttachmentRepository {
                 b.size_bytes            AS "size_bytes!",
                 b.hash                  AS "hash!",
                 b.width                 AS "width?",
-                b.height                AS "height?"
+                b.height                AS "height?",
+                b.duration_ms           AS "duration_ms?"
             FROM attachments a
             INNER JOIN blobs b ON b.id = a.blob_id
             WHERE a.issue_id = $1
@@ -134,7 +136,8 @@ impl AttachmentRepository {
                 b.size_bytes            AS "size_bytes!",
                 b.hash                  AS "hash!",
                 b.width                 AS "width?",
-                b.height                AS "height?"
+                b.height                AS "height?",
+                b.duration_ms           AS "duration_ms?"
             FROM attachments a
             INNER JOIN blobs b ON b.id = a.blob_id
             WHERE a.comment_id = $1
@@ -251,7 +254,8 @@ impl AttachmentRepository {
                 b.size_bytes            AS "size_bytes!",
                 b.hash                  AS "hash!",
                 b.width                 AS "width?",
-                b.height                AS "height?"
+                b.height                AS "height?",
+                b.duration_ms           AS "duration_ms?"
             "#,
             issue_id,
             attachment_ids
@@ -292,7 +296,8 @@ impl AttachmentRepository {
                 b.size_bytes            AS "size_bytes!",
                 b.hash                  AS "hash!",
                 b.width                 AS "width?",
-                b.height                AS "height?"
+                b.height                AS "height?",
+                b.duration_ms           AS "duration_ms?"
             "#,
             comment_id,
             attachment_ids
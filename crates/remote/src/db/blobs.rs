@@ -31,6 +31,7 @@ impl BlobRepository {
                 hash                AS "hash!",
                 width               AS "width?",
                 height              AS "height?",
+                duration_ms         AS "duration_ms?",
                 created_at          AS "created_at!: DateTime<Utc>",
                 updated_at          AS "updated_at!: DateTime<Utc>"
             FROM blobs
@@ -64,6 +65,7 @@ impl BlobRepository {
                 hash                AS "hash!",
                 width               AS "width?",
                 height              AS "height?",
+                duration_ms         AS "duration_ms?",
                 created_at          AS "created_at!: DateTime<Utc>",
                 updated_at          AS "updated_at!: DateTime<Utc>"
             FROM blobs
@@ -92,6 +94,7 @@ impl BlobRepository {
         hash: String,
         width: Option<i32>,
         height: Option<i32>,
+        duration_ms: Option<i64>,
     ) -> Result<Blob, BlobError> {
         let id = id.unwrap_or_else(Uuid::new_v4);
 
@@ -100,9 +103,9 @@ impl BlobRepository {
             r#"
             INSERT INTO blobs (
                 id, project_id, blob_path, thumbnail_blob_path, original_name,
-                mime_type, size_bytes, hash, width, height
+                mime_type, size_bytes, hash, width, height, duration_ms
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
             ON CONFLICT (blob_path) DO UPDATE SET
                 updated_at = NOW()
             RETURNING
@@ -116,6 +119,7 @@ impl BlobRepository {
                 hash                AS "hash!",
                 width               AS "width?",
                 height              AS "height?",
+                duration_ms         AS "duration_ms?",
                 created_at          AS "created_at!: DateTime<Utc>",
                 updated_at          AS "updated_at!: DateTime<Utc>"
             "#,
@@ -129,6 +133,7 @@ impl BlobRepository {
             hash,
             width,
             height,
+            duration_ms,
         )
         .fetch_one(pool)
         .await?;
@@ -153,6 +158,7 @@ impl BlobRepository {
                 hash                AS "hash!",
                 width               AS "width?",
                 height              AS "height?",
+                duration_ms         AS "duration_ms?",
                 created_at          AS "created_at!: DateTime<Utc>",
                 updated_at          AS "updated_at!: DateTime<Utc>"
             "#,
@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum CalendarError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// An open issue assigned to the feed's owner that has a due date.
+#[derive(Debug, Clone)]
+pub struct CalendarIssue {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub project_name: String,
+    pub simple_id: String,
+    pub title: String,
+    pub target_date: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub struct CalendarRepository;
+
+impl CalendarRepository {
+    pub async fn feed_key(pool: &PgPool, user_id: Uuid) -> Result<Option<Uuid>, CalendarError> {
+        let key = sqlx::query_scalar!(
+            r#"SELECT calendar_feed_key AS "calendar_feed_key!: Uuid" FROM users WHERE id = $1"#,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(key)
+    }
+
+    /// Replace the user's feed key, revoking their existing feed URLs.
+    pub async fn rotate_feed_key(pool: &PgPool, user_id: Uuid) -> Result<Uuid, CalendarError> {
+        let key = sqlx::query_scalar!(
+            r#"
+            UPDATE users
+            SET calendar_feed_key = gen_random_uuid()
+            WHERE id = $1
+            RETURNING calendar_feed_key AS "calendar_feed_key!: Uuid"
+            "#,
+            user_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(key)
+    }
+
+    /// Open issues assigned to the user with a due date, limited to
+    /// organizations the user still belongs to.
+    pub async fn list_due_issues(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<CalendarIssue>, CalendarError> {
+        let issues = sqlx::query_as!(
+            CalendarIssue,
+            r#"
+            SELECT
+                i.id          AS "id!: Uuid",
+                i.project_id  AS "project_id!: Uuid",
+                p.name        AS "project_name!",
+                i.simple_id   AS "simple_id!",
+                i.title       AS "title!",
+                i.target_date AS "target_date!: DateTime<Utc>",
+                i.updated_at  AS "updated_at!: DateTime<Utc>"
+            FROM issues i
+            INNER JOIN issue_assignees ia
+                ON ia.issue_id = i.id
+               AND ia.user_id = $1
+            INNER JOIN projects p ON p.id = i.project_id
+            INNER JOIN organization_member_metadata omm
+                ON omm.organization_id = p.organization_id
+               AND omm.user_id = $1
+            WHERE i.target_date IS NOT NULL
+              AND i.completed_at IS NULL
+            ORDER BY i.target_date ASC, i.id
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(issues)
+    }
+}
@@ -0,0 +1,412 @@
+use api_types::{Document, DocumentRevision, DocumentRevisionSummary, DocumentSummary};
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, PgPool, Postgres, Transaction};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::documents::{attachment_references, issue_references};
+
+#[derive(Debug, Error)]
+pub enum DocumentError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+pub struct DocumentRepository;
+
+impl DocumentRepository {
+    fn escape_like_pattern(value: &str) -> String {
+        value
+            .replace('\\', r"\\")
+            .replace('%', r"\%")
+            .replace('_', r"\_")
+    }
+
+    pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<Document>, DocumentError> {
+        Self::find(pool, id).await
+    }
+
+    pub async fn list_by_project(
+        pool: &PgPool,
+        project_id: Uuid,
+    ) -> Result<Vec<DocumentSummary>, DocumentError> {
+        let documents = sqlx::query_as!(
+            DocumentSummary,
+            r#"
+            SELECT
+                d.id         AS "id!: Uuid",
+                d.project_id AS "project_id!: Uuid",
+                d.title      AS "title!",
+                d.revision   AS "revision!",
+                d.updated_by AS "updated_by?: Uuid",
+                d.updated_at AS "updated_at!: DateTime<Utc>"
+            FROM project_documents d
+            WHERE d.project_id = $1
+            ORDER BY d.title, d.created_at
+            "#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(documents)
+    }
+
+    /// Documents whose content mentions the issue.
+    pub async fn list_by_issue(
+        pool: &PgPool,
+        issue_id: Uuid,
+    ) -> Result<Vec<DocumentSummary>, DocumentError> {
+        let documents = sqlx::query_as!(
+            DocumentSummary,
+            r#"
+            SELECT
+                d.id         AS "id!: Uuid",
+                d.project_id AS "project_id!: Uuid",
+                d.title      AS "title!",
+                d.revision   AS "revision!",
+                d.updated_by AS "updated_by?: Uuid",
+                d.updated_at AS "updated_at!: DateTime<Utc>"
+            FROM project_documents d
+            INNER JOIN project_document_issue_links l ON l.document_id = d.id
+            WHERE l.issue_id = $1
+            ORDER BY d.title, d.created_at
+            "#,
+            issue_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(documents)
+    }
+
+    /// Documents whose title or content contains `search`, title matches
+    /// first.
+    pub async fn search(
+        pool: &PgPool,
+        project_id: Uuid,
+        search: &str,
+        limit: i64,
+    ) -> Result<Vec<DocumentSummary>, DocumentError> {
+        let pattern = format!("%{}%", Self::escape_like_pattern(search));
+        let documents = sqlx::query_as!(
+            DocumentSummary,
+            r#"
+            SELECT
+                d.id         AS "id!: Uuid",
+                d.project_id AS "project_id!: Uuid",
+                d.title      AS "title!",
+                d.revision   AS "revision!",
+                d.updated_by AS "updated_by?: Uuid",
+                d.updated_at AS "updated_at!: DateTime<Utc>"
+            FROM project_documents d
+            WHERE d.project_id = $1
+              AND (d.title ILIKE $2 ESCAPE '\' OR d.content ILIKE $2 ESCAPE '\')
+            ORDER BY d.title ILIKE $2 ESCAPE '\' DESC, d.updated_at DESC
+            LIMIT $3
+            "#,
+            project_id,
+            pattern,
+            limit
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(documents)
+    }
+
+    pub async fn create(
+        pool: &PgPool,
+        project_id: Uuid,
+        user_id: Uuid,
+        title: &str,
+        content: &str,
+    ) -> Result<Document, DocumentError> {
+        let mut tx = pool.begin().await?;
+        let id: Uuid = sqlx::query_scalar!(
+            r#"
+            INSERT INTO project_documents (project_id, title, content, created_by, updated_by)
+            VALUES ($1, $2, $3, $4, $4)
+            RETURNING id
+            "#,
+            project_id,
+            title,
+            content,
+            user_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        Self::record_revision(&mut tx, id, 1, title, content, user_id).await?;
+        Self::sync_links(&mut tx, id, project_id, content).await?;
+        let document = Self::find_in_tx(&mut tx, id).await?;
+        tx.commit().await?;
+
+        Ok(document)
+    }
+
+    /// Save a new revision, unless the document has moved past
+    /// `base_revision` or no longer exists.
+    pub async fn update(
+        pool: &PgPool,
+        id: Uuid,
+        base_revision: i32,
+        user_id: Uuid,
+        title: &str,
+        content: &str,
+    ) -> Result<Option<Document>, DocumentError> {
+        let mut tx = pool.begin().await?;
+        let Some(updated) = sqlx::query!(
+            r#"
+            UPDATE project_documents
+            SET title = $3,
+                content = $4,
+                revision = revision + 1,
+                updated_by = $5,
+                updated_at = NOW()
+            WHERE id = $1 AND revision = $2
+            RETURNING project_id, revision
+            "#,
+            id,
+            base_revision,
+            title,
+            content,
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(None);
+        };
+        Self::record_revision(&mut tx, id, updated.revision, title, content, user_id).await?;
+        Self::sync_links(&mut tx, id, updated.project_id, content).await?;
+        let document = Self::find_in_tx(&mut tx, id).await?;
+        tx.commit().await?;
+
+        Ok(Some(document))
+    }
+
+    pub async fn delete(pool: &PgPool, id: Uuid) -> Result<(), DocumentError> {
+        let mut tx = pool.begin().await?;
+        Self::release_attachments(&mut tx, id, &[]).await?;
+        sqlx::query!("DELETE FROM project_documents WHERE id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn list_revisions(
+        pool: &PgPool,
+        document_id: Uuid,
+    ) -> Result<Vec<DocumentRevisionSummary>, DocumentError> {
+        let revisions = sqlx::query_as!(
+            DocumentRevisionSummary,
+            r#"
+            SELECT
+                revision       AS "revision!",
+                title          AS "title!",
+                author_user_id AS "author_user_id?: Uuid",
+                created_at     AS "created_at!: DateTime<Utc>"
+            FROM project_document_revisions
+            WHERE document_id = $1
+            ORDER BY revision DESC
+            "#,
+            document_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(revisions)
+    }
+
+    pub async fn find_revision(
+        pool: &PgPool,
+        document_id: Uuid,
+        revision: i32,
+    ) -> Result<Option<DocumentRevision>, DocumentError> {
+        let revision = sqlx::query_as!(
+            DocumentRevision,
+            r#"
+            SELECT
+                document_id    AS "document_id!: Uuid",
+                revision       AS "revision!",
+                title          AS "title!",
+                content        AS "content!",
+                author_user_id AS "author_user_id?: Uuid",
+                created_at     AS "created_at!: DateTime<Utc>"
+            FROM project_document_revisions
+            WHERE document_id = $1 AND revision = $2
+            "#,
+            document_id,
+            revision
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(revision)
+    }
+
+    async fn find_in_tx(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<Document, DocumentError> {
+        Self::find(&mut **tx, id)
+            .await?
+            .ok_or_else(|| DocumentError::Database(sqlx::Error::RowNotFound))
+    }
+
+    async fn find<'e, E>(executor: E, id: Uuid) -> Result<Option<Document>, DocumentError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let document = sqlx::query_as!(
+            Document,
+            r#"
+            SELECT
+                d.id         AS "id!: Uuid",
+                d.project_id AS "project_id!: Uuid",
+                d.title      AS "title!",
+                d.content    AS "content!",
+                d.revision   AS "revision!",
+                d.created_by AS "created_by?: Uuid",
+                d.updated_by AS "updated_by?: Uuid",
+                d.created_at AS "created_at!: DateTime<Utc>",
+                d.updated_at AS "updated_at!: DateTime<Utc>",
+                ARRAY(
+                    SELECT l.issue_id FROM project_document_issue_links l WHERE l.document_id = d.id
+                ) AS "issue_ids!: Vec<Uuid>",
+                ARRAY(
+                    SELECT a.attachment_id
+                    FROM project_document_attachments a
+                    WHERE a.document_id = d.id
+                ) AS "attachment_ids!: Vec<Uuid>"
+            FROM project_documents d
+            WHERE d.id = $1
+            "#,
+            id
+        )
+        .fetch_optional(executor)
+        .await?;
+
+        Ok(document)
+    }
+
+    async fn record_revision(
+        tx: &mut Transaction<'_, Postgres>,
+        document_id: Uuid,
+        revision: i32,
+        title: &str,
+        content: &str,
+        user_id: Uuid,
+    ) -> Result<(), DocumentError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO project_document_revisions
+                (document_id, revision, title, content, author_user_id)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            document_id,
+            revision,
+            title,
+            content,
+            user_id
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Point the document's issue and attachment links at what `content`
+    /// references. Only the project's own issues, and attachments that are
+    /// staged or already embedded in a document, can be linked.
+    async fn sync_links(
+        tx: &mut Transaction<'_, Postgres>,
+        document_id: Uuid,
+        project_id: Uuid,
+        content: &str,
+    ) -> Result<(), DocumentError> {
+        sqlx::query!(
+            "DELETE FROM project_document_issue_links WHERE document_id = $1",
+            document_id
+        )
+        .execute(&mut **tx)
+        .await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO project_document_issue_links (document_id, issue_id)
+            SELECT $1, i.id
+            FROM issues i
+            WHERE i.project_id = $2 AND UPPER(i.simple_id) = ANY($3::text[])
+            "#,
+            document_id,
+            project_id,
+            &issue_references(content)
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        let attachment_ids = attachment_references(content);
+        Self::release_attachments(tx, document_id, &attachment_ids).await?;
+        sqlx::query!(
+            r#"
+            WITH linked AS (
+                INSERT INTO project_document_attachments (document_id, attachment_id)
+                SELECT $1, a.id
+                FROM attachments a
+                INNER JOIN blobs b ON b.id = a.blob_id
+                WHERE a.id = ANY($3::uuid[])
+                  AND b.project_id = $2
+                  AND a.issue_id IS NULL
+                  AND a.comment_id IS NULL
+                ON CONFLICT DO NOTHING
+                RETURNING attachment_id
+            )
+            UPDATE attachments
+            SET expires_at = NULL
+            WHERE id IN (SELECT attachment_id FROM linked)
+            "#,
+            document_id,
+            project_id,
+            &attachment_ids
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Unlink the document's attachments other than `keep`, scheduling those
+    /// no other document embeds for cleanup.
+    async fn release_attachments(
+        tx: &mut Transaction<'_, Postgres>,
+        document_id: Uuid,
+        keep: &[Uuid],
+    ) -> Result<(), DocumentError> {
+        sqlx::query!(
+            r#"
+            WITH released AS (
+                DELETE FROM project_document_attachments
+                WHERE document_id = $1 AND NOT (attachment_id = ANY($2::uuid[]))
+                RETURNING attachment_id
+            )
+            UPDATE attachments a
+            -- Kept for a day, so an undo can still restore it.
+            SET expires_at = NOW() + INTERVAL '24 hours'
+            WHERE a.id IN (SELECT attachment_id FROM released)
+              AND NOT EXISTS (
+                  SELECT 1
+                  FROM project_document_attachments other
+                  WHERE other.attachment_id = a.id AND other.document_id <> $1
+              )
+            "#,
+            document_id,
+            keep
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum DueDateReminderError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// An assignee of an open issue who has not been reminded of its current
+/// due date.
+#[derive(Debug, Clone)]
+pub struct ReminderCandidate {
+    pub issue_id: Uuid,
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub target_date: DateTime<Utc>,
+    pub timezone: Option<String>,
+}
+
+pub struct DueDateReminderRepository;
+
+impl DueDateReminderRepository {
+    /// Candidates for open issues due in `[from, to)`.
+    pub async fn candidates(
+        pool: &PgPool,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<ReminderCandidate>, DueDateReminderError> {
+        let candidates = sqlx::query_as!(
+            ReminderCandidate,
+            r#"
+            SELECT
+                i.id              AS "issue_id!: Uuid",
+                p.organization_id AS "organization_id!: Uuid",
+                ia.user_id        AS "user_id!: Uuid",
+                i.target_date     AS "target_date!: DateTime<Utc>",
+                u.timezone        AS "timezone?"
+            FROM issues i
+            INNER JOIN projects p ON p.id = i.project_id
+            INNER JOIN issue_assignees ia ON ia.issue_id = i.id
+            INNER JOIN users u ON u.id = ia.user_id
+            WHERE i.completed_at IS NULL
+              AND i.target_date >= $1
+              AND i.target_date < $2
+              AND NOT EXISTS (
+                  SELECT 1
+                  FROM issue_due_date_reminders r
+                  WHERE r.issue_id = i.id
+                    AND r.user_id = ia.user_id
+                    AND r.target_date = i.target_date
+              )
+            "#,
+            from,
+            to
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(candidates)
+    }
+
+    /// Record the reminder; false if it was already sent.
+    pub async fn record(
+        pool: &PgPool,
+        issue_id: Uuid,
+        user_id: Uuid,
+        target_date: DateTime<Utc>,
+    ) -> Result<bool, DueDateReminderError> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO issue_due_date_reminders (issue_id, user_id, target_date)
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            "#,
+            issue_id,
+            user_id,
+            target_date
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -200,7 +200,8 @@ impl ExportRepository {
                 b.size_bytes            AS "size_bytes!",
                 b.hash                  AS "hash!",
                 b.width                 AS "width?",
-                b.height                AS "height?"
+                b.height                AS "height?",
+                b.duration_ms           AS "duration_ms?"
             FROM attachments a
             INNER JOIN blobs b ON b.id = a.blob_id
             INNER JOIN issues i ON i.id = a.issue_id
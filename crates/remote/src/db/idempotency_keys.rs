@@ -0,0 +1,122 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// A key's stored request fingerprint and, once handled, its response.
+#[derive(Debug, Clone)]
+pub struct IdempotencyRecord {
+    pub request_hash: String,
+    pub status_code: Option<i16>,
+    pub response_body: Option<Vec<u8>>,
+}
+
+pub struct IdempotencyKeyRepository;
+
+impl IdempotencyKeyRepository {
+    /// Claim `key` for a new request. Keys past their TTL, or stuck pending
+    /// after a crash, are taken over. Returns false if the key is in use.
+    pub async fn reserve(
+        pool: &PgPool,
+        user_id: Uuid,
+        key: &str,
+        request_hash: &str,
+        ttl_hours: i32,
+    ) -> Result<bool, sqlx::Error> {
+        let reserved = sqlx::query_scalar!(
+            r#"
+            INSERT INTO idempotency_keys (user_id, key, request_hash)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, key) DO UPDATE
+            SET request_hash = EXCLUDED.request_hash,
+                status_code = NULL,
+                response_body = NULL,
+                created_at = NOW()
+            WHERE idempotency_keys.created_at < NOW() - make_interval(hours => $4)
+               OR (idempotency_keys.status_code IS NULL
+                   AND idempotency_keys.created_at < NOW() - INTERVAL '5 minutes')
+            RETURNING user_id
+            "#,
+            user_id,
+            key,
+            request_hash,
+            ttl_hours
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(reserved.is_some())
+    }
+
+    pub async fn find(
+        pool: &PgPool,
+        user_id: Uuid,
+        key: &str,
+    ) -> Result<Option<IdempotencyRecord>, sqlx::Error> {
+        sqlx::query_as!(
+            IdempotencyRecord,
+            r#"
+            SELECT
+                request_hash  AS "request_hash!",
+                status_code   AS "status_code?",
+                response_body AS "response_body?"
+            FROM idempotency_keys
+            WHERE user_id = $1 AND key = $2
+            "#,
+            user_id,
+            key
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn complete(
+        pool: &PgPool,
+        user_id: Uuid,
+        key: &str,
+        status_code: i16,
+        response_body: &[u8],
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE idempotency_keys
+            SET status_code = $3, response_body = $4
+            WHERE user_id = $1 AND key = $2
+            "#,
+            user_id,
+            key,
+            status_code,
+            response_body
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Drop a pending key whose response won't be stored, so the client can
+    /// retry with it.
+    pub async fn release(pool: &PgPool, user_id: Uuid, key: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            DELETE FROM idempotency_keys
+            WHERE user_id = $1 AND key = $2 AND status_code IS NULL
+            "#,
+            user_id,
+            key
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_expired(pool: &PgPool, ttl_hours: i32) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM idempotency_keys WHERE created_at < NOW() - make_interval(hours => $1)",
+            ttl_hours
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
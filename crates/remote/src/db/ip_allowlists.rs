@@ -0,0 +1,137 @@
+use ipnetwork::IpNetwork;
+use sqlx::PgPool;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum IpAllowlistError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct IpAllowlistRow {
+    pub organization_id: Uuid,
+    pub ranges: Vec<IpNetwork>,
+}
+
+pub struct IpAllowlistRepository;
+
+impl IpAllowlistRepository {
+    pub async fn find(
+        pool: &PgPool,
+        organization_id: Uuid,
+    ) -> Result<Option<IpAllowlistRow>, IpAllowlistError> {
+        let allowlist = sqlx::query_as!(
+            IpAllowlistRow,
+            r#"
+            SELECT
+                organization_id AS "organization_id!: Uuid",
+                ranges          AS "ranges!: Vec<IpNetwork>"
+            FROM organization_ip_allowlists
+            WHERE organization_id = $1
+            "#,
+            organization_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(allowlist)
+    }
+
+    pub async fn set(
+        pool: &PgPool,
+        organization_id: Uuid,
+        ranges: &[IpNetwork],
+        updated_by: Uuid,
+    ) -> Result<(), IpAllowlistError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO organization_ip_allowlists (organization_id, ranges, updated_by)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (organization_id) DO UPDATE
+            SET ranges = EXCLUDED.ranges,
+                updated_by = EXCLUDED.updated_by,
+                updated_at = NOW()
+            "#,
+            organization_id,
+            ranges,
+            updated_by
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The allowlist of the project's organization, if it restricts access.
+    pub async fn for_project(
+        pool: &PgPool,
+        project_id: Uuid,
+    ) -> Result<Option<IpAllowlistRow>, IpAllowlistError> {
+        let allowlist = sqlx::query_as!(
+            IpAllowlistRow,
+            r#"
+            SELECT
+                a.organization_id AS "organization_id!: Uuid",
+                a.ranges          AS "ranges!: Vec<IpNetwork>"
+            FROM projects p
+            INNER JOIN organization_ip_allowlists a ON a.organization_id = p.organization_id
+            WHERE p.id = $1 AND cardinality(a.ranges) > 0
+            "#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(allowlist)
+    }
+
+    /// The allowlist of the issue's organization, if it restricts access.
+    pub async fn for_issue(
+        pool: &PgPool,
+        issue_id: Uuid,
+    ) -> Result<Option<IpAllowlistRow>, IpAllowlistError> {
+        let allowlist = sqlx::query_as!(
+            IpAllowlistRow,
+            r#"
+            SELECT
+                a.organization_id AS "organization_id!: Uuid",
+                a.ranges          AS "ranges!: Vec<IpNetwork>"
+            FROM issues i
+            INNER JOIN projects p ON p.id = i.project_id
+            INNER JOIN organization_ip_allowlists a ON a.organization_id = p.organization_id
+            WHERE i.id = $1 AND cardinality(a.ranges) > 0
+            "#,
+            issue_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(allowlist)
+    }
+
+    /// The allowlists of the user's organizations that restrict access.
+    pub async fn for_member(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<IpAllowlistRow>, IpAllowlistError> {
+        let allowlists = sqlx::query_as!(
+            IpAllowlistRow,
+            r#"
+            SELECT
+                a.organization_id AS "organization_id!: Uuid",
+                a.ranges          AS "ranges!: Vec<IpNetwork>"
+            FROM organization_member_metadata m
+            INNER JOIN organization_ip_allowlists a ON a.organization_id = m.organization_id
+            WHERE m.user_id = $1 AND cardinality(a.ranges) > 0
+            ORDER BY a.organization_id
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(allowlists)
+    }
+}
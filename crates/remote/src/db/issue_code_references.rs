@@ -0,0 +1,95 @@
+use api_types::{IssueCodeReference, IssueCodeReferenceKind};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum IssueCodeReferenceError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// A commit or pull request and the simple IDs it mentions.
+#[derive(Debug, Clone)]
+pub struct CodeMention<'a> {
+    pub kind: IssueCodeReferenceKind,
+    pub repo_full_name: &'a str,
+    pub reference: &'a str,
+    pub url: &'a str,
+    pub title: &'a str,
+    pub author_login: Option<&'a str>,
+    pub referenced_at: DateTime<Utc>,
+    pub simple_ids: &'a [String],
+}
+
+pub struct IssueCodeReferenceRepository;
+
+impl IssueCodeReferenceRepository {
+    pub async fn list_by_issue(
+        pool: &PgPool,
+        issue_id: Uuid,
+    ) -> Result<Vec<IssueCodeReference>, IssueCodeReferenceError> {
+        let references = sqlx::query_as!(
+            IssueCodeReference,
+            r#"
+            SELECT
+                id             AS "id!: Uuid",
+                issue_id       AS "issue_id!: Uuid",
+                kind           AS "kind!: IssueCodeReferenceKind",
+                repo_full_name AS "repo_full_name!",
+                reference      AS "reference!",
+                url            AS "url!",
+                title          AS "title!",
+                author_login   AS "author_login?",
+                referenced_at  AS "referenced_at!: DateTime<Utc>",
+                created_at     AS "created_at!: DateTime<Utc>"
+            FROM issue_code_references
+            WHERE issue_id = $1
+            ORDER BY referenced_at DESC
+            "#,
+            issue_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(references)
+    }
+
+    /// Record the mention on each of the organization's issues it names.
+    /// Recording the same commit or pull request again refreshes its title
+    /// instead of duplicating it. Returns the IDs of the issues mentioned.
+    pub async fn record(
+        pool: &PgPool,
+        organization_id: Uuid,
+        mention: &CodeMention<'_>,
+    ) -> Result<Vec<Uuid>, IssueCodeReferenceError> {
+        let issue_ids = sqlx::query_scalar!(
+            r#"
+            INSERT INTO issue_code_references
+                (issue_id, kind, repo_full_name, reference, url, title, author_login,
+                 referenced_at)
+            SELECT i.id, $3, $4, $5, $6, $7, $8, $9
+            FROM issues i
+            INNER JOIN projects p ON p.id = i.project_id
+            WHERE p.organization_id = $1 AND UPPER(i.simple_id) = ANY($2::text[])
+            ON CONFLICT (issue_id, kind, repo_full_name, reference)
+            DO UPDATE SET title = EXCLUDED.title, url = EXCLUDED.url
+            RETURNING issue_id AS "issue_id!: Uuid"
+            "#,
+            organization_id,
+            mention.simple_ids,
+            mention.kind as IssueCodeReferenceKind,
+            mention.repo_full_name,
+            mention.reference,
+            mention.url,
+            mention.title,
+            mention.author_login,
+            mention.referenced_at
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(issue_ids)
+    }
+}
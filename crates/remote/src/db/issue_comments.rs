@@ -1,10 +1,10 @@
 use api_types::{DeleteResponse, IssueComment, MutationResponse};
 use chrono::{DateTime, Utc};
-use sqlx::PgPool;
+use sqlx::{Executor, PgPool, Postgres};
 use thiserror::Error;
 use uuid::Uuid;
 
-use super::get_txid;
+use super::{Tx, get_txid};
 
 #[derive(Debug, Error)]
 pub enum IssueCommentError {
@@ -15,10 +15,13 @@ pub enum IssueCommentError {
 pub struct IssueCommentRepository;
 
 impl IssueCommentRepository {
-    pub async fn find_by_id(
-        pool: &PgPool,
+    pub async fn find_by_id<'e, E>(
+        executor: E,
         id: Uuid,
-    ) -> Result<Option<IssueComment>, IssueCommentError> {
+    ) -> Result<Option<IssueComment>, IssueCommentError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
         let record = sqlx::query_as!(
             IssueComment,
             r#"
@@ -35,7 +38,7 @@ impl IssueCommentRepository {
             "#,
             id
         )
-        .fetch_optional(pool)
+        .fetch_optional(executor)
         .await?;
 
         Ok(record)
@@ -85,12 +88,11 @@ impl IssueCommentRepository {
     /// Update an issue comment with partial fields. Uses COALESCE to preserve existing values
     /// when None is provided.
     pub async fn update(
-        pool: &PgPool,
+        tx: &mut Tx<'_>,
         id: Uuid,
         message: Option<String>,
-    ) -> Result<MutationResponse<IssueComment>, IssueCommentError> {
+    ) -> Result<IssueComment, IssueCommentError> {
         let updated_at = Utc::now();
-        let mut tx = super::begin_tx(pool).await?;
         let data = sqlx::query_as!(
             IssueComment,
             r#"
@@ -112,12 +114,10 @@ impl IssueCommentRepository {
             updated_at,
             id
         )
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut **tx)
         .await?;
-        let txid = get_txid(&mut *tx).await?;
-        tx.commit().await?;
 
-        Ok(MutationResponse { data, txid })
+        Ok(data)
     }
 
     pub async fn delete(pool: &PgPool, id: Uuid) -> Result<DeleteResponse, IssueCommentError> {
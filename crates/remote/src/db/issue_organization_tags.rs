@@ -0,0 +1,113 @@
+use api_types::{DeleteResponse, IssueOrganizationTag, MutationResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::{get_txid, issue_tags::IssueTagError};
+
+pub struct IssueOrganizationTagRepository;
+
+impl IssueOrganizationTagRepository {
+    pub async fn find_by_id(
+        pool: &PgPool,
+        id: Uuid,
+    ) -> Result<Option<IssueOrganizationTag>, IssueTagError> {
+        let record = sqlx::query_as!(
+            IssueOrganizationTag,
+            r#"
+            SELECT
+                id                  AS "id!: Uuid",
+                issue_id            AS "issue_id!: Uuid",
+                organization_tag_id AS "organization_tag_id!: Uuid"
+            FROM issue_organization_tags
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    pub async fn list_by_issue(
+        pool: &PgPool,
+        issue_id: Uuid,
+    ) -> Result<Vec<IssueOrganizationTag>, IssueTagError> {
+        let records = sqlx::query_as!(
+            IssueOrganizationTag,
+            r#"
+            SELECT
+                id                  AS "id!: Uuid",
+                issue_id            AS "issue_id!: Uuid",
+                organization_tag_id AS "organization_tag_id!: Uuid"
+            FROM issue_organization_tags
+            WHERE issue_id = $1
+            "#,
+            issue_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    pub async fn list_by_project(
+        pool: &PgPool,
+        project_id: Uuid,
+    ) -> Result<Vec<IssueOrganizationTag>, IssueTagError> {
+        let records = sqlx::query_as!(
+            IssueOrganizationTag,
+            r#"
+            SELECT
+                id                  AS "id!: Uuid",
+                issue_id            AS "issue_id!: Uuid",
+                organization_tag_id AS "organization_tag_id!: Uuid"
+            FROM issue_organization_tags
+            WHERE issue_id IN (SELECT id FROM issues WHERE project_id = $1)
+            "#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    pub async fn create(
+        pool: &PgPool,
+        id: Option<Uuid>,
+        issue_id: Uuid,
+        organization_tag_id: Uuid,
+    ) -> Result<MutationResponse<IssueOrganizationTag>, IssueTagError> {
+        let mut tx = super::begin_tx(pool).await?;
+        let data = sqlx::query_as!(
+            IssueOrganizationTag,
+            r#"
+            INSERT INTO issue_organization_tags (id, issue_id, organization_tag_id)
+            VALUES ($1, $2, $3)
+            RETURNING
+                id                  AS "id!: Uuid",
+                issue_id            AS "issue_id!: Uuid",
+                organization_tag_id AS "organization_tag_id!: Uuid"
+            "#,
+            id.unwrap_or_else(Uuid::new_v4),
+            issue_id,
+            organization_tag_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        let txid = get_txid(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(MutationResponse { data, txid })
+    }
+
+    pub async fn delete(pool: &PgPool, id: Uuid) -> Result<DeleteResponse, IssueTagError> {
+        let mut tx = super::begin_tx(pool).await?;
+        sqlx::query!("DELETE FROM issue_organization_tags WHERE id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+        let txid = get_txid(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(DeleteResponse { txid })
+    }
+}
@@ -0,0 +1,80 @@
+//! Fractional ranks for ordering issues within a status column.
+//!
+//! An issue moved between two others gets the midpoint of their
+//! `sort_order`s, so a move writes a single row. Once neighbours get too
+//! close to split, the whole column is spread out again.
+
+use uuid::Uuid;
+
+/// Gap between neighbouring issues after a rebalance, and between the last
+/// issue and one appended after it.
+pub const RANK_STEP: f64 = 1024.0;
+
+/// Neighbours closer than this are rebalanced rather than split, well before
+/// `f64` runs out of precision.
+const MIN_RANK_GAP: f64 = 1e-6;
+
+/// A rank strictly between `previous` and `next`, or `None` if they are too
+/// close (or out of order) and the column needs a rebalance.
+pub fn rank_between(previous: Option<f64>, next: Option<f64>) -> Option<f64> {
+    match (previous, next) {
+        (None, None) => Some(RANK_STEP),
+        (Some(previous), None) => Some(previous + RANK_STEP),
+        (None, Some(next)) => Some(next - RANK_STEP),
+        (Some(previous), Some(next)) if next - previous > MIN_RANK_GAP => {
+            Some(previous + (next - previous) / 2.0)
+        }
+        _ => None,
+    }
+}
+
+/// Evenly spaced ranks for `column` (in board order) with `moved` inserted at
+/// `position`.
+pub fn rebalance(column: &[Uuid], moved: Uuid, position: usize) -> Vec<(Uuid, f64)> {
+    let mut ids = column.to_vec();
+    ids.insert(position.min(ids.len()), moved);
+    ids.into_iter()
+        .enumerate()
+        .map(|(index, id)| (id, (index + 1) as f64 * RANK_STEP))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_neighbours_until_they_are_too_close() {
+        assert_eq!(rank_between(None, None), Some(RANK_STEP));
+        assert_eq!(rank_between(Some(3.0), None), Some(3.0 + RANK_STEP));
+        assert_eq!(rank_between(None, Some(3.0)), Some(3.0 - RANK_STEP));
+        assert_eq!(rank_between(Some(1.0), Some(2.0)), Some(1.5));
+        assert_eq!(rank_between(Some(1.0), Some(1.0)), None);
+        assert_eq!(rank_between(Some(2.0), Some(1.0)), None);
+
+        let mut next = 2.0;
+        let mut splits = 0;
+        while let Some(rank) = rank_between(Some(1.0), Some(next)) {
+            next = rank;
+            splits += 1;
+        }
+        assert!(splits > 15);
+    }
+
+    #[test]
+    fn rebalances_with_the_moved_issue_in_place() {
+        let (a, b, moved) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        assert_eq!(
+            rebalance(&[a, b], moved, 1),
+            vec![
+                (a, RANK_STEP),
+                (moved, 2.0 * RANK_STEP),
+                (b, 3.0 * RANK_STEP)
+            ]
+        );
+        assert_eq!(
+            rebalance(&[a], moved, 5).last(),
+            Some(&(moved, 2.0 * RANK_STEP))
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use api_types::IssuePriority;
+use sqlx::PgPool;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum IssueTriageError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct TriageTag {
+    pub id: Uuid,
+    pub name: String,
+}
+
+/// An existing issue sharing words with the one being triaged.
+#[derive(Debug, Clone)]
+pub struct TriageIssue {
+    pub id: Uuid,
+    pub simple_id: String,
+    pub title: String,
+    /// The start of the description.
+    pub description: String,
+    pub priority: Option<IssuePriority>,
+    pub completed: bool,
+    pub tag_names: Vec<String>,
+    pub assignee_ids: Vec<Uuid>,
+}
+
+/// A member of the project's organization, with what hints at them owning
+/// the issue: similar issues they were assigned, and similar issues whose
+/// work they merged from their workspaces.
+#[derive(Debug, Clone)]
+pub struct TriageMember {
+    pub user_id: Uuid,
+    pub name: String,
+    pub open_assigned: i64,
+    pub similar_assigned: i64,
+    pub similar_merged: i64,
+}
+
+pub struct IssueTriageRepository;
+
+impl IssueTriageRepository {
+    fn escape_like_pattern(value: &str) -> String {
+        value
+            .replace('\\', r"\\")
+            .replace('%', r"\%")
+            .replace('_', r"\_")
+    }
+
+    pub async fn project_tags(
+        pool: &PgPool,
+        project_id: Uuid,
+    ) -> Result<Vec<TriageTag>, IssueTriageError> {
+        let tags = sqlx::query_as!(
+            TriageTag,
+            r#"
+            SELECT id AS "id!: Uuid", name AS "name!"
+            FROM tags
+            WHERE project_id = $1
+            ORDER BY name
+            "#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    /// Issues of the project whose title or description contain any of
+    /// `terms`, the ones matching most terms first.
+    pub async fn similar_issues(
+        pool: &PgPool,
+        project_id: Uuid,
+        terms: &[String],
+        exclude_issue_id: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<TriageIssue>, IssueTriageError> {
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        let patterns: Vec<String> = terms
+            .iter()
+            .map(|term| format!("%{}%", Self::escape_like_pattern(term)))
+            .collect();
+
+        let issues = sqlx::query_as!(
+            TriageIssue,
+            r#"
+            SELECT
+                id           AS "id!: Uuid",
+                simple_id    AS "simple_id!",
+                title        AS "title!",
+                description  AS "description!",
+                priority     AS "priority?: IssuePriority",
+                completed    AS "completed!",
+                tag_names    AS "tag_names!: Vec<String>",
+                assignee_ids AS "assignee_ids!: Vec<Uuid>"
+            FROM (
+                SELECT
+                    i.id,
+                    i.simple_id,
+                    i.title,
+                    LEFT(COALESCE(i.description, ''), 500) AS description,
+                    i.priority,
+                    i.completed_at IS NOT NULL AS completed,
+                    ARRAY(
+                        SELECT t.name
+                        FROM issue_tags it
+                        INNER JOIN tags t ON t.id = it.tag_id
+                        WHERE it.issue_id = i.id
+                        ORDER BY t.name
+                    ) AS tag_names,
+                    ARRAY(
+                        SELECT ia.user_id FROM issue_assignees ia WHERE ia.issue_id = i.id
+                    ) AS assignee_ids,
+                    (
+                        SELECT COUNT(*)
+                        FROM UNNEST($2::text[]) AS pattern
+                        WHERE i.title ILIKE pattern ESCAPE '\'
+                           OR COALESCE(i.description, '') ILIKE pattern ESCAPE '\'
+                    ) AS matches,
+                    i.updated_at
+                FROM issues i
+                WHERE i.project_id = $1
+                  AND ($3::uuid IS NULL OR i.id <> $3)
+            ) candidates
+            WHERE matches > 0
+            ORDER BY matches DESC, updated_at DESC
+            LIMIT $4
+            "#,
+            project_id,
+            &patterns,
+            exclude_issue_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(issues)
+    }
+
+    /// Members of the project's organization with their open issue count in
+    /// the project and their history with `similar_issue_ids`.
+    pub async fn members(
+        pool: &PgPool,
+        project_id: Uuid,
+        similar_issue_ids: &[Uuid],
+    ) -> Result<Vec<TriageMember>, IssueTriageError> {
+        let members = sqlx::query_as!(
+            TriageMember,
+            r#"
+            SELECT
+                u.id AS "user_id!: Uuid",
+                COALESCE(
+                    NULLIF(TRIM(CONCAT_WS(' ', u.first_name, u.last_name)), ''),
+                    u.username,
+                    u.email
+                ) AS "name!",
+                (
+                    SELECT COUNT(*)
+                    FROM issue_assignees ia
+                    INNER JOIN issues i ON i.id = ia.issue_id
+                    WHERE ia.user_id = u.id
+                      AND i.project_id = $1
+                      AND i.completed_at IS NULL
+                ) AS "open_assigned!",
+                (
+                    SELECT COUNT(*)
+                    FROM issue_assignees ia
+                    WHERE ia.user_id = u.id AND ia.issue_id = ANY($2)
+                ) AS "similar_assigned!",
+                (
+                    SELECT COUNT(DISTINCT pr.issue_id)
+                    FROM pull_requests pr
+                    INNER JOIN workspaces w ON w.id = pr.workspace_id
+                    WHERE w.owner_user_id = u.id
+                      AND pr.status = 'merged'
+                      AND pr.issue_id = ANY($2)
+                ) AS "similar_merged!"
+            FROM projects p
+            INNER JOIN organization_member_metadata omm
+                ON omm.organization_id = p.organization_id
+            INNER JOIN users u ON u.id = omm.user_id
+            WHERE p.id = $1
+            ORDER BY 2
+            "#,
+            project_id,
+            similar_issue_ids
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(members)
+    }
+}
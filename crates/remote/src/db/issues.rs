@@ -504,6 +504,57 @@ impl IssueRepository {
         Ok(data)
     }
 
+    /// Lock the issues in a status column, except `exclude_id`, and return
+    /// their ids and sort orders in board order.
+    pub async fn lock_status_column(
+        conn: &mut PgConnection,
+        status_id: Uuid,
+        exclude_id: Uuid,
+    ) -> Result<Vec<(Uuid, f64)>, IssueError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT id AS "id!: Uuid", sort_order AS "sort_order!"
+            FROM issues
+            WHERE status_id = $1 AND id <> $2
+            ORDER BY sort_order, id
+            FOR UPDATE
+            "#,
+            status_id,
+            exclude_id
+        )
+        .fetch_all(conn)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.id, row.sort_order))
+            .collect())
+    }
+
+    /// Set the sort orders of many issues at once, for rebalancing a column.
+    /// `updated_at` is left alone: ranks are server-maintained, so this must
+    /// not make pending client edits conflict.
+    pub async fn set_sort_orders(
+        conn: &mut PgConnection,
+        sort_orders: &[(Uuid, f64)],
+    ) -> Result<(), IssueError> {
+        let (ids, values): (Vec<Uuid>, Vec<f64>) = sort_orders.iter().copied().unzip();
+        sqlx::query!(
+            r#"
+            UPDATE issues
+            SET sort_order = ranks.sort_order
+            FROM UNNEST($1::uuid[], $2::float8[]) AS ranks(id, sort_order)
+            WHERE issues.id = ranks.id
+            "#,
+            &ids,
+            &values
+        )
+        .execute(conn)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn delete(pool: &PgPool, id: Uuid) -> Result<DeleteResponse, IssueError> {
         let mut tx = super::begin_tx(pool).await?;
 
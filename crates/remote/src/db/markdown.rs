@@ -0,0 +1,130 @@
+use sqlx::PgPool;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum MarkdownError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// An issue a `#123` reference resolved to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueReference {
+    pub id: Uuid,
+    pub issue_number: i32,
+    pub simple_id: String,
+    pub title: String,
+}
+
+/// A member an `@username` mention resolved to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MentionedUser {
+    pub id: Uuid,
+    pub username: String,
+}
+
+pub struct MarkdownRepository;
+
+impl MarkdownRepository {
+    pub async fn find_issues(
+        pool: &PgPool,
+        project_id: Uuid,
+        issue_numbers: &[i32],
+    ) -> Result<Vec<IssueReference>, MarkdownError> {
+        let issues = sqlx::query_as!(
+            IssueReference,
+            r#"
+            SELECT
+                id           AS "id!: Uuid",
+                issue_number AS "issue_number!",
+                simple_id    AS "simple_id!",
+                title        AS "title!"
+            FROM issues
+            WHERE project_id = $1 AND issue_number = ANY($2::integer[])
+            "#,
+            project_id,
+            issue_numbers
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(issues)
+    }
+
+    /// Members of the organization with one of the usernames, matched
+    /// case-insensitively.
+    pub async fn find_members(
+        pool: &PgPool,
+        organization_id: Uuid,
+        usernames: &[String],
+    ) -> Result<Vec<MentionedUser>, MarkdownError> {
+        let users = sqlx::query_as!(
+            MentionedUser,
+            r#"
+            SELECT
+                u.id       AS "id!: Uuid",
+                u.username AS "username!"
+            FROM users u
+            INNER JOIN organization_member_metadata om ON om.user_id = u.id
+            WHERE om.organization_id = $1
+              AND LOWER(u.username) = ANY($2::text[])
+            "#,
+            organization_id,
+            usernames
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(users)
+    }
+
+    /// The cached HTML for `key`, marking the entry as recently used.
+    pub async fn find_cached(pool: &PgPool, key: &str) -> Result<Option<String>, MarkdownError> {
+        let html = sqlx::query_scalar!(
+            r#"
+            WITH touched AS (
+                UPDATE rendered_markdown_cache
+                SET last_used_at = NOW()
+                WHERE key = $1 AND last_used_at < NOW() - INTERVAL '1 day'
+            )
+            SELECT html FROM rendered_markdown_cache WHERE key = $1
+            "#,
+            key
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(html)
+    }
+
+    pub async fn store(pool: &PgPool, key: &str, html: &str) -> Result<(), MarkdownError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO rendered_markdown_cache (key, html)
+            VALUES ($1, $2)
+            ON CONFLICT (key) DO UPDATE SET last_used_at = NOW()
+            "#,
+            key,
+            html
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_unused(pool: &PgPool, unused_days: i32) -> Result<u64, MarkdownError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM rendered_markdown_cache
+            WHERE last_used_at < NOW() - make_interval(days => $1)
+            "#,
+            unused_days
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -1,35 +1,61 @@
+pub mod account_data;
+pub mod agent_usage;
 pub mod attachments;
 pub mod auth;
 pub mod blobs;
+pub mod calendar;
 pub mod digest;
+pub mod documents;
+pub mod due_date_reminders;
 pub mod electric_publications;
 pub mod export;
 pub mod github_app;
 pub mod hosts;
+pub mod idempotency_keys;
 pub mod identity_errors;
 pub mod invitations;
+pub mod ip_allowlists;
 pub mod issue_assignees;
+pub mod issue_code_references;
 pub mod issue_comment_reactions;
 pub mod issue_comments;
 pub mod issue_followers;
+pub mod issue_organization_tags;
+pub mod issue_ranks;
 pub mod issue_relationships;
 pub mod issue_tags;
+pub mod issue_triage;
 pub mod issues;
+pub mod markdown;
+pub mod notification_templates;
 pub mod notifications;
 pub mod oauth;
 pub mod oauth_accounts;
 pub mod organization_members;
+pub mod organization_tags;
 pub mod organizations;
 pub mod pending_uploads;
+pub mod plans;
+pub mod project_github_teams;
 pub mod project_notification_preferences;
+pub mod project_status_workflows;
 pub mod project_statuses;
 pub mod projects;
+pub mod provider_tokens;
 pub mod pull_request_issues;
 pub mod pull_requests;
+pub mod query_subscriptions;
+pub mod release_notes;
+pub mod retention;
 pub mod reviews;
+pub mod session_anomalies;
+pub mod sla;
 pub mod tags;
+pub mod timeline;
 pub mod types;
+pub mod user_profiles;
 pub mod users;
+pub mod work_logs;
 pub mod workspaces;
 
 use sqlx::{
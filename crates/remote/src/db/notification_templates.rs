@@ -0,0 +1,200 @@
+use api_types::{NotificationTemplate, NotificationType};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum NotificationTemplateError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+pub struct NotificationTemplateRepository;
+
+impl NotificationTemplateRepository {
+    pub async fn find_by_id(
+        pool: &PgPool,
+        id: Uuid,
+    ) -> Result<Option<NotificationTemplate>, NotificationTemplateError> {
+        let template = sqlx::query_as!(
+            NotificationTemplate,
+            r#"
+            SELECT
+                id                AS "id!: Uuid",
+                project_id        AS "project_id!: Uuid",
+                notification_type AS "notification_type!: NotificationType",
+                locale            AS "locale!",
+                title             AS "title!",
+                body              AS "body?",
+                created_at        AS "created_at!: DateTime<Utc>",
+                updated_at        AS "updated_at!: DateTime<Utc>"
+            FROM notification_templates
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(template)
+    }
+
+    pub async fn list_by_projects(
+        pool: &PgPool,
+        project_ids: &[Uuid],
+    ) -> Result<Vec<NotificationTemplate>, NotificationTemplateError> {
+        let templates = sqlx::query_as!(
+            NotificationTemplate,
+            r#"
+            SELECT
+                id                AS "id!: Uuid",
+                project_id        AS "project_id!: Uuid",
+                notification_type AS "notification_type!: NotificationType",
+                locale            AS "locale!",
+                title             AS "title!",
+                body              AS "body?",
+                created_at        AS "created_at!: DateTime<Utc>",
+                updated_at        AS "updated_at!: DateTime<Utc>"
+            FROM notification_templates
+            WHERE project_id = ANY($1)
+            ORDER BY notification_type, locale
+            "#,
+            project_ids
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(templates)
+    }
+
+    pub async fn upsert(
+        pool: &PgPool,
+        project_id: Uuid,
+        notification_type: NotificationType,
+        locale: &str,
+        title: &str,
+        body: Option<&str>,
+    ) -> Result<NotificationTemplate, NotificationTemplateError> {
+        let template = sqlx::query_as!(
+            NotificationTemplate,
+            r#"
+            INSERT INTO notification_templates (project_id, notification_type, locale, title, body)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (project_id, notification_type, locale)
+            DO UPDATE SET title = EXCLUDED.title, body = EXCLUDED.body, updated_at = NOW()
+            RETURNING
+                id                AS "id!: Uuid",
+                project_id        AS "project_id!: Uuid",
+                notification_type AS "notification_type!: NotificationType",
+                locale            AS "locale!",
+                title             AS "title!",
+                body              AS "body?",
+                created_at        AS "created_at!: DateTime<Utc>",
+                updated_at        AS "updated_at!: DateTime<Utc>"
+            "#,
+            project_id,
+            notification_type as NotificationType,
+            locale,
+            title,
+            body
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(template)
+    }
+
+    pub async fn delete(pool: &PgPool, id: Uuid) -> Result<(), NotificationTemplateError> {
+        sqlx::query!("DELETE FROM notification_templates WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// `(project_id, default_locale)` for the projects that set one.
+    pub async fn default_locales(
+        pool: &PgPool,
+        project_ids: &[Uuid],
+    ) -> Result<Vec<(Uuid, String)>, NotificationTemplateError> {
+        let locales = sqlx::query!(
+            r#"
+            SELECT project_id, default_locale
+            FROM project_notification_locales
+            WHERE project_id = ANY($1)
+            "#,
+            project_ids
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(locales
+            .into_iter()
+            .map(|row| (row.project_id, row.default_locale))
+            .collect())
+    }
+
+    pub async fn set_default_locale(
+        pool: &PgPool,
+        project_id: Uuid,
+        default_locale: Option<&str>,
+    ) -> Result<(), NotificationTemplateError> {
+        match default_locale {
+            Some(default_locale) => {
+                sqlx::query!(
+                    r#"
+                    INSERT INTO project_notification_locales (project_id, default_locale)
+                    VALUES ($1, $2)
+                    ON CONFLICT (project_id)
+                    DO UPDATE SET default_locale = EXCLUDED.default_locale, updated_at = NOW()
+                    "#,
+                    project_id,
+                    default_locale
+                )
+                .execute(pool)
+                .await?;
+            }
+            None => {
+                sqlx::query!(
+                    "DELETE FROM project_notification_locales WHERE project_id = $1",
+                    project_id
+                )
+                .execute(pool)
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// `(issue_id, project_id)` for the issues that still exist.
+    pub async fn issue_projects(
+        pool: &PgPool,
+        issue_ids: &[Uuid],
+    ) -> Result<Vec<(Uuid, Uuid)>, NotificationTemplateError> {
+        let projects = sqlx::query!(
+            "SELECT id, project_id FROM issues WHERE id = ANY($1)",
+            issue_ids
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(projects
+            .into_iter()
+            .map(|row| (row.id, row.project_id))
+            .collect())
+    }
+
+    pub async fn user_locale(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Option<String>, NotificationTemplateError> {
+        let locale = sqlx::query_scalar!("SELECT locale FROM users WHERE id = $1", user_id)
+            .fetch_optional(pool)
+            .await?
+            .flatten();
+
+        Ok(locale)
+    }
+}
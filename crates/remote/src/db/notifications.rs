@@ -1,4 +1,7 @@
-use api_types::{Notification, NotificationPayload, NotificationType};
+use api_types::{
+    Notification, NotificationIssueMute, NotificationPayload, NotificationType,
+    NotificationUnreadCount,
+};
 use chrono::{DateTime, Utc};
 use sqlx::{Executor, FromRow, Postgres};
 use thiserror::Error;
@@ -334,4 +337,192 @@ impl NotificationRepository {
             .await?;
         Ok(())
     }
+
+    /// Mark the user's notifications matching the filters read or unread,
+    /// returning how many changed. Marking read also dismisses; marking unread
+    /// brings dismissed notifications back.
+    pub async fn mark<'e, E>(
+        executor: E,
+        user_id: Uuid,
+        seen: bool,
+        notification_ids: Option<&[Uuid]>,
+        organization_id: Option<Uuid>,
+        issue_id: Option<Uuid>,
+    ) -> Result<u64, NotificationError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let result = sqlx::query!(
+            r#"
+            UPDATE notifications
+            SET seen = $2,
+                dismissed_at = CASE WHEN $2 THEN COALESCE(dismissed_at, NOW()) ELSE NULL END
+            WHERE user_id = $1
+              AND seen <> $2
+              AND ($3::uuid[] IS NULL OR id = ANY($3))
+              AND ($4::uuid IS NULL OR organization_id = $4)
+              AND ($5::uuid IS NULL OR issue_id = $5)
+            "#,
+            user_id,
+            seen,
+            notification_ids,
+            organization_id,
+            issue_id
+        )
+        .execute(executor)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Unread notifications of the user, in one organization or all of them.
+    pub async fn unread_count<'e, E>(
+        executor: E,
+        user_id: Uuid,
+        organization_id: Option<Uuid>,
+    ) -> Result<i32, NotificationError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COALESCE(SUM(unread_count), 0)::INTEGER AS "count!"
+            FROM notification_unread_counts
+            WHERE user_id = $1 AND ($2::uuid IS NULL OR organization_id = $2)
+            "#,
+            user_id,
+            organization_id
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(count)
+    }
+
+    pub async fn list_unread_counts<'e, E>(
+        executor: E,
+        user_id: Uuid,
+    ) -> Result<Vec<NotificationUnreadCount>, NotificationError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let records = sqlx::query_as!(
+            NotificationUnreadCount,
+            r#"
+            SELECT
+                id              AS "id!: Uuid",
+                user_id         AS "user_id!: Uuid",
+                organization_id AS "organization_id!: Uuid",
+                unread_count    AS "unread_count!",
+                updated_at      AS "updated_at!: DateTime<Utc>"
+            FROM notification_unread_counts
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_all(executor)
+        .await?;
+
+        Ok(records)
+    }
+}
+
+pub struct NotificationMuteRepository;
+
+impl NotificationMuteRepository {
+    pub async fn mute<'e, E>(
+        executor: E,
+        user_id: Uuid,
+        issue_id: Uuid,
+    ) -> Result<NotificationIssueMute, NotificationError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let record = sqlx::query_as!(
+            NotificationIssueMute,
+            r#"
+            INSERT INTO notification_issue_mutes (issue_id, user_id)
+            VALUES ($1, $2)
+            ON CONFLICT (issue_id, user_id) DO UPDATE SET issue_id = EXCLUDED.issue_id
+            RETURNING
+                issue_id   AS "issue_id!: Uuid",
+                user_id    AS "user_id!: Uuid",
+                created_at AS "created_at!: DateTime<Utc>"
+            "#,
+            issue_id,
+            user_id
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(record)
+    }
+
+    pub async fn unmute<'e, E>(
+        executor: E,
+        user_id: Uuid,
+        issue_id: Uuid,
+    ) -> Result<(), NotificationError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        sqlx::query!(
+            "DELETE FROM notification_issue_mutes WHERE issue_id = $1 AND user_id = $2",
+            issue_id,
+            user_id
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn list_by_user<'e, E>(
+        executor: E,
+        user_id: Uuid,
+    ) -> Result<Vec<NotificationIssueMute>, NotificationError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let records = sqlx::query_as!(
+            NotificationIssueMute,
+            r#"
+            SELECT
+                issue_id   AS "issue_id!: Uuid",
+                user_id    AS "user_id!: Uuid",
+                created_at AS "created_at!: DateTime<Utc>"
+            FROM notification_issue_mutes
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(executor)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// Which of `user_ids` muted `issue_id`.
+    pub async fn muted_users<'e, E>(
+        executor: E,
+        issue_id: Uuid,
+        user_ids: &[Uuid],
+    ) -> Result<Vec<Uuid>, NotificationError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let records = sqlx::query_scalar!(
+            r#"
+            SELECT user_id
+            FROM notification_issue_mutes
+            WHERE issue_id = $1 AND user_id = ANY($2)
+            "#,
+            issue_id,
+            user_ids
+        )
+        .fetch_all(executor)
+        .await?;
+
+        Ok(records)
+    }
 }
@@ -89,6 +89,79 @@ pub(crate) async fn assert_membership(
     }
 }
 
+/// Access beyond organization membership: a project synced from a GitHub
+/// team is limited to the team's members and the organization's admins.
+async fn is_project_member(
+    pool: &PgPool,
+    project_id: Uuid,
+    user_id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    let allowed = sqlx::query_scalar!(
+        r#"
+        SELECT (
+            NOT EXISTS (SELECT 1 FROM project_github_teams t WHERE t.project_id = p.id)
+            OR EXISTS (
+                SELECT 1 FROM project_members pm
+                WHERE pm.project_id = p.id AND pm.user_id = $2
+            )
+            OR EXISTS (
+                SELECT 1 FROM organization_member_metadata omm
+                WHERE omm.organization_id = p.organization_id
+                  AND omm.user_id = $2
+                  AND omm.role = 'admin'
+            )
+        ) AS "allowed!"
+        FROM projects p
+        WHERE p.id = $1
+        "#,
+        project_id,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(allowed.unwrap_or(false))
+}
+
+/// Membership of the project's organization, and of the project itself if
+/// it is synced from a GitHub team.
+pub(crate) async fn assert_project_membership(
+    pool: &PgPool,
+    organization_id: Uuid,
+    project_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), IdentityError> {
+    assert_membership(pool, organization_id, user_id).await?;
+    if is_project_member(pool, project_id, user_id).await? {
+        Ok(())
+    } else {
+        Err(IdentityError::NotFound)
+    }
+}
+
+/// Like [`assert_project_membership`] for the project of an issue.
+pub(crate) async fn assert_issue_membership(
+    pool: &PgPool,
+    organization_id: Uuid,
+    issue_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), IdentityError> {
+    assert_membership(pool, organization_id, user_id).await?;
+    let project_id = sqlx::query_scalar!(
+        r#"SELECT project_id AS "project_id!: Uuid" FROM issues WHERE id = $1"#,
+        issue_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or(IdentityError::NotFound)?;
+
+    if is_project_member(pool, project_id, user_id).await? {
+        Ok(())
+    } else {
+        Err(IdentityError::NotFound)
+    }
+}
+
 pub(crate) async fn assert_issue_access(
     pool: &PgPool,
     issue_id: Uuid,
@@ -107,7 +180,7 @@ pub(crate) async fn assert_issue_access(
     .await?
     .ok_or(IdentityError::NotFound)?;
 
-    assert_membership(pool, org_id, user_id).await
+    assert_issue_membership(pool, org_id, issue_id, user_id).await
 }
 
 pub(crate) async fn assert_project_access(
@@ -123,7 +196,7 @@ pub(crate) async fn assert_project_access(
     .await?
     .ok_or(IdentityError::NotFound)?;
 
-    assert_membership(pool, org_id, user_id).await
+    assert_project_membership(pool, org_id, project_id, user_id).await
 }
 
 pub(crate) async fn list_by_organization(
@@ -0,0 +1,314 @@
+use std::collections::{BTreeMap, HashSet};
+
+use api_types::{DeleteResponse, DuplicateTagGroup, MutationResponse, OrganizationTag, Tag};
+use sqlx::{Executor, PgConnection, PgPool, Postgres};
+use uuid::Uuid;
+
+use super::{get_txid, tags::TagError};
+
+pub struct OrganizationTagRepository;
+
+impl OrganizationTagRepository {
+    pub async fn find_by_id<'e, E>(
+        executor: E,
+        id: Uuid,
+    ) -> Result<Option<OrganizationTag>, TagError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let record = sqlx::query_as!(
+            OrganizationTag,
+            r#"
+            SELECT
+                id              AS "id!: Uuid",
+                organization_id AS "organization_id!: Uuid",
+                name            AS "name!",
+                color           AS "color!"
+            FROM organization_tags
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(executor)
+        .await?;
+
+        Ok(record)
+    }
+
+    pub async fn list_by_organization(
+        pool: &PgPool,
+        organization_id: Uuid,
+    ) -> Result<Vec<OrganizationTag>, TagError> {
+        let records = sqlx::query_as!(
+            OrganizationTag,
+            r#"
+            SELECT
+                id              AS "id!: Uuid",
+                organization_id AS "organization_id!: Uuid",
+                name            AS "name!",
+                color           AS "color!"
+            FROM organization_tags
+            WHERE organization_id = $1
+            ORDER BY name
+            "#,
+            organization_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    pub async fn create(
+        pool: &PgPool,
+        id: Option<Uuid>,
+        organization_id: Uuid,
+        name: String,
+        color: String,
+    ) -> Result<MutationResponse<OrganizationTag>, TagError> {
+        let mut tx = super::begin_tx(pool).await?;
+
+        let data = sqlx::query_as!(
+            OrganizationTag,
+            r#"
+            INSERT INTO organization_tags (id, organization_id, name, color)
+            VALUES ($1, $2, $3, $4)
+            RETURNING
+                id              AS "id!: Uuid",
+                organization_id AS "organization_id!: Uuid",
+                name            AS "name!",
+                color           AS "color!"
+            "#,
+            id.unwrap_or_else(Uuid::new_v4),
+            organization_id,
+            name,
+            color
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let txid = get_txid(&mut *tx).await?;
+        tx.commit().await?;
+
+        Ok(MutationResponse { data, txid })
+    }
+
+    /// Update an organization tag with partial fields. Uses COALESCE to
+    /// preserve existing values when None is provided.
+    pub async fn update(
+        pool: &PgPool,
+        id: Uuid,
+        name: Option<String>,
+        color: Option<String>,
+    ) -> Result<MutationResponse<OrganizationTag>, TagError> {
+        let mut tx = super::begin_tx(pool).await?;
+
+        let data = sqlx::query_as!(
+            OrganizationTag,
+            r#"
+            UPDATE organization_tags
+            SET
+                name = COALESCE($1, name),
+                color = COALESCE($2, color)
+            WHERE id = $3
+            RETURNING
+                id              AS "id!: Uuid",
+                organization_id AS "organization_id!: Uuid",
+                name            AS "name!",
+                color           AS "color!"
+            "#,
+            name,
+            color,
+            id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let txid = get_txid(&mut *tx).await?;
+        tx.commit().await?;
+
+        Ok(MutationResponse { data, txid })
+    }
+
+    pub async fn delete(pool: &PgPool, id: Uuid) -> Result<DeleteResponse, TagError> {
+        let mut tx = super::begin_tx(pool).await?;
+
+        sqlx::query!("DELETE FROM organization_tags WHERE id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+
+        let txid = get_txid(&mut *tx).await?;
+        tx.commit().await?;
+
+        Ok(DeleteResponse { txid })
+    }
+
+    /// The organization tag named `name`, created with `color` if missing.
+    pub async fn find_or_create(
+        conn: &mut PgConnection,
+        organization_id: Uuid,
+        name: &str,
+        color: &str,
+    ) -> Result<OrganizationTag, TagError> {
+        let record = sqlx::query_as!(
+            OrganizationTag,
+            r#"
+            INSERT INTO organization_tags (organization_id, name, color)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (organization_id, name) DO UPDATE SET name = EXCLUDED.name
+            RETURNING
+                id              AS "id!: Uuid",
+                organization_id AS "organization_id!: Uuid",
+                name            AS "name!",
+                color           AS "color!"
+            "#,
+            organization_id,
+            name,
+            color
+        )
+        .fetch_one(conn)
+        .await?;
+
+        Ok(record)
+    }
+
+    /// Project tags of the organization, in board order.
+    pub async fn list_project_tags(
+        pool: &PgPool,
+        organization_id: Uuid,
+    ) -> Result<Vec<Tag>, TagError> {
+        let records = sqlx::query_as!(
+            Tag,
+            r#"
+            SELECT
+                t.id         AS "id!: Uuid",
+                t.project_id AS "project_id!: Uuid",
+                t.name       AS "name!",
+                t.color      AS "color!"
+            FROM tags t
+            JOIN projects p ON p.id = t.project_id
+            WHERE p.organization_id = $1
+            ORDER BY p.sort_order, t.name
+            "#,
+            organization_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// Lock the given project tags, keeping only those in the organization.
+    pub async fn lock_project_tags(
+        conn: &mut PgConnection,
+        organization_id: Uuid,
+        tag_ids: &[Uuid],
+    ) -> Result<Vec<Tag>, TagError> {
+        let mut records = sqlx::query_as!(
+            Tag,
+            r#"
+            SELECT
+                t.id         AS "id!: Uuid",
+                t.project_id AS "project_id!: Uuid",
+                t.name       AS "name!",
+                t.color      AS "color!"
+            FROM tags t
+            JOIN projects p ON p.id = t.project_id
+            WHERE t.id = ANY($1) AND p.organization_id = $2
+            FOR UPDATE OF t
+            "#,
+            tag_ids,
+            organization_id
+        )
+        .fetch_all(conn)
+        .await?;
+
+        // Keep the caller's order, so "the first tag" means what they sent.
+        records.sort_by_key(|tag| tag_ids.iter().position(|id| *id == tag.id));
+        Ok(records)
+    }
+
+    /// Tag every issue carrying one of `tag_ids` with `organization_tag_id`,
+    /// then delete the project tags. Returns how many issues were newly
+    /// tagged.
+    pub async fn merge_project_tags(
+        conn: &mut PgConnection,
+        organization_tag_id: Uuid,
+        tag_ids: &[Uuid],
+    ) -> Result<u64, TagError> {
+        let retagged = sqlx::query!(
+            r#"
+            INSERT INTO issue_organization_tags (issue_id, organization_tag_id)
+            SELECT DISTINCT issue_id, $1::uuid FROM issue_tags WHERE tag_id = ANY($2)
+            ON CONFLICT DO NOTHING
+            "#,
+            organization_tag_id,
+            tag_ids
+        )
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+
+        sqlx::query!("DELETE FROM tags WHERE id = ANY($1)", tag_ids)
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(retagged)
+    }
+}
+
+/// Group `tags` by case-insensitive name, keeping names used by more than one
+/// project.
+pub fn duplicate_groups(tags: Vec<Tag>) -> Vec<DuplicateTagGroup> {
+    let mut by_name: BTreeMap<String, Vec<Tag>> = BTreeMap::new();
+    for tag in tags {
+        by_name
+            .entry(tag.name.trim().to_lowercase())
+            .or_default()
+            .push(tag);
+    }
+    by_name
+        .into_values()
+        .filter(|tags| {
+            tags.iter()
+                .map(|tag| tag.project_id)
+                .collect::<HashSet<_>>()
+                .len()
+                > 1
+        })
+        .map(|tags| DuplicateTagGroup {
+            name: tags[0].name.clone(),
+            tags,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(project_id: Uuid, name: &str) -> Tag {
+        Tag {
+            id: Uuid::new_v4(),
+            project_id,
+            name: name.to_string(),
+            color: "0 0% 50%".to_string(),
+        }
+    }
+
+    #[test]
+    fn groups_names_shared_across_projects() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let groups = duplicate_groups(vec![
+            tag(a, "bug"),
+            tag(b, "Bug"),
+            tag(a, "feature"),
+            tag(a, "docs"),
+            tag(a, "Docs"),
+        ]);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].name, "bug");
+        assert_eq!(groups[0].tags.len(), 2);
+    }
+}
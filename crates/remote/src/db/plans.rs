@@ -0,0 +1,56 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub struct PlanRepository;
+
+impl PlanRepository {
+    /// Whether the organization has a subscription in good enough standing
+    /// to get paid entitlements.
+    pub async fn has_active_subscription(
+        pool: &PgPool,
+        organization_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1
+                FROM organization_billing
+                WHERE organization_id = $1
+                  AND subscription_status IN ('active', 'trialing', 'past_due')
+            ) AS "exists!"
+            "#,
+            organization_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn count_projects(pool: &PgPool, organization_id: Uuid) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM projects WHERE organization_id = $1"#,
+            organization_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Members plus invitations that can still be accepted.
+    pub async fn count_seats(pool: &PgPool, organization_id: Uuid) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM organization_member_metadata WHERE organization_id = $1)
+                + (
+                    SELECT COUNT(*)
+                    FROM organization_invitations
+                    WHERE organization_id = $1
+                      AND status = 'pending'
+                      AND expires_at > NOW()
+                ) AS "seats!"
+            "#,
+            organization_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -0,0 +1,281 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum ProjectGitHubTeamError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// A project's GitHub team, with the installation it is read through.
+#[derive(Debug, Clone)]
+pub struct ProjectGitHubTeam {
+    pub project_id: Uuid,
+    pub organization_id: Uuid,
+    pub installation_id: Option<Uuid>,
+    /// The installation's GitHub ID, if it is still installed.
+    pub github_installation_id: Option<i64>,
+    /// The GitHub organization the team belongs to.
+    pub github_account_login: Option<String>,
+    pub installation_suspended: bool,
+    pub team_slug: String,
+    pub unmatched_logins: Vec<String>,
+    pub last_synced_at: Option<DateTime<Utc>>,
+    pub last_sync_error: Option<String>,
+    pub member_user_ids: Vec<Uuid>,
+}
+
+/// An organization member's GitHub account.
+#[derive(Debug, Clone)]
+pub struct GitHubMember {
+    pub user_id: Uuid,
+    pub github_user_id: String,
+}
+
+pub struct ProjectGitHubTeamRepository;
+
+impl ProjectGitHubTeamRepository {
+    pub async fn find(
+        pool: &PgPool,
+        project_id: Uuid,
+    ) -> Result<Option<ProjectGitHubTeam>, ProjectGitHubTeamError> {
+        let team = sqlx::query_as!(
+            ProjectGitHubTeam,
+            r#"
+            SELECT
+                t.project_id               AS "project_id!: Uuid",
+                p.organization_id          AS "organization_id!: Uuid",
+                t.installation_id          AS "installation_id?: Uuid",
+                i.github_installation_id   AS "github_installation_id?",
+                i.github_account_login     AS "github_account_login?",
+                i.suspended_at IS NOT NULL AS "installation_suspended!",
+                t.team_slug                AS "team_slug!",
+                t.unmatched_logins         AS "unmatched_logins!: Vec<String>",
+                t.last_synced_at           AS "last_synced_at?: DateTime<Utc>",
+                t.last_sync_error          AS "last_sync_error?",
+                ARRAY(
+                    SELECT pm.user_id FROM project_members pm WHERE pm.project_id = t.project_id
+                ) AS "member_user_ids!: Vec<Uuid>"
+            FROM project_github_teams t
+            INNER JOIN projects p ON p.id = t.project_id
+            LEFT JOIN github_app_installations i ON i.id = t.installation_id
+            WHERE t.project_id = $1
+            "#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(team)
+    }
+
+    pub async fn list_all(pool: &PgPool) -> Result<Vec<ProjectGitHubTeam>, ProjectGitHubTeamError> {
+        let teams = sqlx::query_as!(
+            ProjectGitHubTeam,
+            r#"
+            SELECT
+                t.project_id               AS "project_id!: Uuid",
+                p.organization_id          AS "organization_id!: Uuid",
+                t.installation_id          AS "installation_id?: Uuid",
+                i.github_installation_id   AS "github_installation_id?",
+                i.github_account_login     AS "github_account_login?",
+                i.suspended_at IS NOT NULL AS "installation_suspended!",
+                t.team_slug                AS "team_slug!",
+                t.unmatched_logins         AS "unmatched_logins!: Vec<String>",
+                t.last_synced_at           AS "last_synced_at?: DateTime<Utc>",
+                t.last_sync_error          AS "last_sync_error?",
+                ARRAY(
+                    SELECT pm.user_id FROM project_members pm WHERE pm.project_id = t.project_id
+                ) AS "member_user_ids!: Vec<Uuid>"
+            FROM project_github_teams t
+            INNER JOIN projects p ON p.id = t.project_id
+            LEFT JOIN github_app_installations i ON i.id = t.installation_id
+            "#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(teams)
+    }
+
+    /// Projects synced through the installation, from `team_slug` or, if
+    /// not given, from any of its teams.
+    pub async fn list_by_installation(
+        pool: &PgPool,
+        github_installation_id: i64,
+        team_slug: Option<&str>,
+    ) -> Result<Vec<ProjectGitHubTeam>, ProjectGitHubTeamError> {
+        let teams = sqlx::query_as!(
+            ProjectGitHubTeam,
+            r#"
+            SELECT
+                t.project_id               AS "project_id!: Uuid",
+                p.organization_id          AS "organization_id!: Uuid",
+                t.installation_id          AS "installation_id?: Uuid",
+                i.github_installation_id   AS "github_installation_id?",
+                i.github_account_login     AS "github_account_login?",
+                i.suspended_at IS NOT NULL AS "installation_suspended!",
+                t.team_slug                AS "team_slug!",
+                t.unmatched_logins         AS "unmatched_logins!: Vec<String>",
+                t.last_synced_at           AS "last_synced_at?: DateTime<Utc>",
+                t.last_sync_error          AS "last_sync_error?",
+                ARRAY(
+                    SELECT pm.user_id FROM project_members pm WHERE pm.project_id = t.project_id
+                ) AS "member_user_ids!: Vec<Uuid>"
+            FROM project_github_teams t
+            INNER JOIN projects p ON p.id = t.project_id
+            LEFT JOIN github_app_installations i ON i.id = t.installation_id
+            WHERE i.github_installation_id = $1
+              AND ($2::text IS NULL OR LOWER(t.team_slug) = LOWER($2))
+            "#,
+            github_installation_id,
+            team_slug
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(teams)
+    }
+
+    /// Link the project to a team. Members are kept until the next sync.
+    pub async fn link(
+        pool: &PgPool,
+        project_id: Uuid,
+        installation_id: Uuid,
+        team_slug: &str,
+    ) -> Result<(), ProjectGitHubTeamError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO project_github_teams (project_id, installation_id, team_slug)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (project_id) DO UPDATE
+            SET installation_id = EXCLUDED.installation_id,
+                team_slug = EXCLUDED.team_slug,
+                unmatched_logins = '{}',
+                last_synced_at = NULL,
+                last_sync_error = NULL,
+                updated_at = NOW()
+            "#,
+            project_id,
+            installation_id,
+            team_slug
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Remove the link and its synced members, opening the project to the
+    /// whole organization again.
+    pub async fn unlink(pool: &PgPool, project_id: Uuid) -> Result<(), ProjectGitHubTeamError> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM project_members WHERE project_id = $1",
+            project_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM project_github_teams WHERE project_id = $1",
+            project_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// GitHub accounts of the organization's members.
+    pub async fn github_members(
+        pool: &PgPool,
+        organization_id: Uuid,
+    ) -> Result<Vec<GitHubMember>, ProjectGitHubTeamError> {
+        let members = sqlx::query_as!(
+            GitHubMember,
+            r#"
+            SELECT
+                oa.user_id          AS "user_id!: Uuid",
+                oa.provider_user_id AS "github_user_id!"
+            FROM oauth_accounts oa
+            INNER JOIN organization_member_metadata omm
+                ON omm.user_id = oa.user_id AND omm.organization_id = $1
+            WHERE oa.provider = 'github'
+            "#,
+            organization_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(members)
+    }
+
+    /// Replace the project's members with a successful sync's result.
+    pub async fn replace_members(
+        pool: &PgPool,
+        project_id: Uuid,
+        user_ids: &[Uuid],
+        unmatched_logins: &[String],
+    ) -> Result<(), ProjectGitHubTeamError> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM project_members WHERE project_id = $1 AND user_id <> ALL($2)",
+            project_id,
+            user_ids
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO project_members (project_id, user_id)
+            SELECT $1, UNNEST($2::uuid[])
+            ON CONFLICT (project_id, user_id) DO UPDATE SET synced_at = NOW()
+            "#,
+            project_id,
+            user_ids
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"
+            UPDATE project_github_teams
+            SET unmatched_logins = $2,
+                last_synced_at = NOW(),
+                last_sync_error = NULL,
+                updated_at = NOW()
+            WHERE project_id = $1
+            "#,
+            project_id,
+            unmatched_logins
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Record a failed sync; members are left as they were.
+    pub async fn record_error(
+        pool: &PgPool,
+        project_id: Uuid,
+        error: &str,
+    ) -> Result<(), ProjectGitHubTeamError> {
+        sqlx::query!(
+            r#"
+            UPDATE project_github_teams
+            SET last_sync_error = $2, updated_at = NOW()
+            WHERE project_id = $1
+            "#,
+            project_id,
+            error
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
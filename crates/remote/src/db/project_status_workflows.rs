@@ -0,0 +1,181 @@
+use api_types::{ProjectStatusCategory, ProjectStatusWorkflow};
+use sqlx::{Executor, PgConnection, Postgres};
+use uuid::Uuid;
+
+use super::project_statuses::ProjectStatusError;
+
+/// Categories of the default statuses, by name.
+pub const DEFAULT_STATUS_CATEGORIES: &[(&str, ProjectStatusCategory)] = &[
+    ("Backlog", ProjectStatusCategory::Backlog),
+    ("To do", ProjectStatusCategory::Backlog),
+    ("In progress", ProjectStatusCategory::InProgress),
+    ("In review", ProjectStatusCategory::InProgress),
+    ("Done", ProjectStatusCategory::Done),
+    ("Cancelled", ProjectStatusCategory::Done),
+];
+
+pub struct ProjectStatusWorkflowRepository;
+
+impl ProjectStatusWorkflowRepository {
+    pub async fn list_by_project<'e, E>(
+        executor: E,
+        project_id: Uuid,
+    ) -> Result<Vec<ProjectStatusWorkflow>, ProjectStatusError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let workflows = sqlx::query_as!(
+            ProjectStatusWorkflow,
+            r#"
+            SELECT
+                s.id       AS "status_id!: Uuid",
+                s.category AS "category?: ProjectStatusCategory",
+                COALESCE(
+                    ARRAY_AGG(t.to_status_id ORDER BY t.created_at)
+                        FILTER (WHERE t.to_status_id IS NOT NULL),
+                    '{}'
+                ) AS "allowed_next_status_ids!: Vec<Uuid>"
+            FROM project_statuses s
+            LEFT JOIN project_status_transitions t ON t.from_status_id = s.id
+            WHERE s.project_id = $1
+            GROUP BY s.id
+            ORDER BY s.sort_order
+            "#,
+            project_id
+        )
+        .fetch_all(executor)
+        .await?;
+
+        Ok(workflows)
+    }
+
+    pub async fn find<'e, E>(
+        executor: E,
+        status_id: Uuid,
+    ) -> Result<Option<ProjectStatusWorkflow>, ProjectStatusError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let workflow = sqlx::query_as!(
+            ProjectStatusWorkflow,
+            r#"
+            SELECT
+                s.id       AS "status_id!: Uuid",
+                s.category AS "category?: ProjectStatusCategory",
+                COALESCE(
+                    ARRAY_AGG(t.to_status_id ORDER BY t.created_at)
+                        FILTER (WHERE t.to_status_id IS NOT NULL),
+                    '{}'
+                ) AS "allowed_next_status_ids!: Vec<Uuid>"
+            FROM project_statuses s
+            LEFT JOIN project_status_transitions t ON t.from_status_id = s.id
+            WHERE s.id = $1
+            GROUP BY s.id
+            "#,
+            status_id
+        )
+        .fetch_optional(executor)
+        .await?;
+
+        Ok(workflow)
+    }
+
+    pub async fn set_category(
+        conn: &mut PgConnection,
+        status_id: Uuid,
+        category: Option<ProjectStatusCategory>,
+    ) -> Result<(), ProjectStatusError> {
+        sqlx::query!(
+            "UPDATE project_statuses SET category = $1 WHERE id = $2",
+            category as Option<ProjectStatusCategory>,
+            status_id
+        )
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Replace the statuses issues in `status_id` may move to.
+    pub async fn set_transitions(
+        conn: &mut PgConnection,
+        status_id: Uuid,
+        to_status_ids: &[Uuid],
+    ) -> Result<(), ProjectStatusError> {
+        sqlx::query!(
+            "DELETE FROM project_status_transitions WHERE from_status_id = $1",
+            status_id
+        )
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO project_status_transitions (from_status_id, to_status_id)
+            SELECT $1, to_status_id FROM UNNEST($2::uuid[]) AS t(to_status_id)
+            ON CONFLICT DO NOTHING
+            "#,
+            status_id,
+            to_status_ids
+        )
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Categorize the default statuses of a new project.
+    pub async fn apply_default_categories<'e, E>(
+        executor: E,
+        project_id: Uuid,
+    ) -> Result<(), ProjectStatusError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let (names, categories): (Vec<&str>, Vec<ProjectStatusCategory>) =
+            DEFAULT_STATUS_CATEGORIES.iter().copied().unzip();
+        sqlx::query!(
+            r#"
+            UPDATE project_statuses s
+            SET category = d.category
+            FROM UNNEST($2::text[], $3::project_status_category[]) AS d(name, category)
+            WHERE s.project_id = $1 AND s.name = d.name
+            "#,
+            project_id,
+            &names as &[&str],
+            &categories as &[ProjectStatusCategory]
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    /// The statuses an issue in `from_status_id` may move to, if moving it to
+    /// `to_status_id` isn't allowed.
+    pub async fn disallowed_transition<'e, E>(
+        executor: E,
+        from_status_id: Uuid,
+        to_status_id: Uuid,
+    ) -> Result<Option<Vec<Uuid>>, ProjectStatusError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        if from_status_id == to_status_id {
+            return Ok(None);
+        }
+        let allowed: Vec<Uuid> = sqlx::query_scalar!(
+            r#"
+            SELECT to_status_id
+            FROM project_status_transitions
+            WHERE from_status_id = $1
+            ORDER BY created_at
+            "#,
+            from_status_id
+        )
+        .fetch_all(executor)
+        .await?;
+
+        if allowed.is_empty() || allowed.contains(&to_status_id) {
+            Ok(None)
+        } else {
+            Ok(Some(allowed))
+        }
+    }
+}
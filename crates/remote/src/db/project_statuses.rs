@@ -139,6 +139,26 @@ impl ProjectStatusRepository {
         hidden: Option<bool>,
     ) -> Result<MutationResponse<ProjectStatus>, ProjectStatusError> {
         let mut tx = super::begin_tx(pool).await?;
+        let data = Self::update_partial(&mut *tx, id, name, color, sort_order, hidden).await?;
+
+        let txid = get_txid(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(MutationResponse { data, txid })
+    }
+
+    /// Updates project status fields using a provided executor (used by batch
+    /// mutation transactions).
+    pub async fn update_partial<'e, E>(
+        executor: E,
+        id: Uuid,
+        name: Option<String>,
+        color: Option<String>,
+        sort_order: Option<i32>,
+        hidden: Option<bool>,
+    ) -> Result<ProjectStatus, ProjectStatusError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
         let data = sqlx::query_as!(
             ProjectStatus,
             r#"
@@ -164,12 +184,10 @@ impl ProjectStatusRepository {
             hidden,
             id
         )
-        .fetch_one(&mut *tx)
+        .fetch_one(executor)
         .await?;
 
-        let txid = get_txid(&mut *tx).await?;
-        tx.commit().await?;
-        Ok(MutationResponse { data, txid })
+        Ok(data)
     }
 
     pub async fn delete(pool: &PgPool, id: Uuid) -> Result<DeleteResponse, ProjectStatusError> {
@@ -4,7 +4,10 @@ use sqlx::{Executor, PgPool, Postgres};
 use thiserror::Error;
 use uuid::Uuid;
 
-use super::{get_txid, project_statuses::ProjectStatusRepository, tags::TagRepository};
+use super::{
+    get_txid, project_status_workflows::ProjectStatusWorkflowRepository,
+    project_statuses::ProjectStatusRepository, tags::TagRepository,
+};
 
 /// Default color for the initial project created with personal organizations
 /// HSL format: "H S% L%" (blue - matches "To do" status)
@@ -227,6 +230,62 @@ impl ProjectRepository {
         .map_err(ProjectError::from)
     }
 
+    pub async fn require_if_match<'e, E>(
+        executor: E,
+        project_id: Uuid,
+    ) -> Result<Option<bool>, ProjectError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        sqlx::query_scalar!(
+            "SELECT require_if_match FROM projects WHERE id = $1",
+            project_id
+        )
+        .fetch_optional(executor)
+        .await
+        .map_err(ProjectError::from)
+    }
+
+    /// Whether updates to the issue, or its comments, must name the version
+    /// they were made against.
+    pub async fn require_if_match_for_issue<'e, E>(
+        executor: E,
+        issue_id: Uuid,
+    ) -> Result<bool, ProjectError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let required = sqlx::query_scalar!(
+            r#"
+            SELECT p.require_if_match
+            FROM issues i
+            JOIN projects p ON p.id = i.project_id
+            WHERE i.id = $1
+            "#,
+            issue_id
+        )
+        .fetch_optional(executor)
+        .await?;
+
+        Ok(required.unwrap_or(false))
+    }
+
+    pub async fn set_require_if_match(
+        pool: &PgPool,
+        project_id: Uuid,
+        require_if_match: bool,
+    ) -> Result<(), ProjectError> {
+        sqlx::query!(
+            "UPDATE projects SET require_if_match = $2 WHERE id = $1",
+            project_id,
+            require_if_match
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
     /// Creates the initial project for a newly created personal organization.
     /// Includes default tags and statuses. Designed for use within transactions.
     pub async fn create_initial_project_tx(
@@ -249,6 +308,9 @@ impl ProjectRepository {
         ProjectStatusRepository::create_default_statuses(&mut **tx, project.id)
             .await
             .map_err(|e| ProjectError::DefaultStatusesFailed(e.to_string()))?;
+        ProjectStatusWorkflowRepository::apply_default_categories(&mut **tx, project.id)
+            .await
+            .map_err(|e| ProjectError::DefaultStatusesFailed(e.to_string()))?;
 
         Ok(project)
     }
@@ -272,6 +334,9 @@ impl ProjectRepository {
         ProjectStatusRepository::create_default_statuses(&mut *tx, project.id)
             .await
             .map_err(|e| ProjectError::DefaultStatusesFailed(e.to_string()))?;
+        ProjectStatusWorkflowRepository::apply_default_categories(&mut *tx, project.id)
+            .await
+            .map_err(|e| ProjectError::DefaultStatusesFailed(e.to_string()))?;
 
         let txid = get_txid(&mut *tx).await?;
         tx.commit().await?;
@@ -0,0 +1,136 @@
+use sqlx::PgPool;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum ProviderTokenError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Tables holding encrypted provider tokens in `encrypted_provider_tokens`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderTokenTable {
+    OAuthAccounts,
+    OAuthHandoffs,
+}
+
+impl ProviderTokenTable {
+    pub const ALL: [Self; 2] = [Self::OAuthAccounts, Self::OAuthHandoffs];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::OAuthAccounts => "oauth_accounts",
+            Self::OAuthHandoffs => "oauth_handoffs",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EncryptedProviderTokens {
+    pub id: Uuid,
+    pub encrypted_provider_tokens: String,
+}
+
+pub struct ProviderTokenRepository;
+
+impl ProviderTokenRepository {
+    /// Values not starting with `current_prefix`, in id order after `after`.
+    pub async fn stale(
+        pool: &PgPool,
+        table: ProviderTokenTable,
+        current_prefix: &str,
+        after: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<EncryptedProviderTokens>, ProviderTokenError> {
+        let rows = match table {
+            ProviderTokenTable::OAuthAccounts => {
+                sqlx::query_as!(
+                    EncryptedProviderTokens,
+                    r#"
+                    SELECT
+                        id                        AS "id!: Uuid",
+                        encrypted_provider_tokens AS "encrypted_provider_tokens!"
+                    FROM oauth_accounts
+                    WHERE encrypted_provider_tokens IS NOT NULL
+                      AND NOT starts_with(encrypted_provider_tokens, $1)
+                      AND ($2::uuid IS NULL OR id > $2)
+                    ORDER BY id
+                    LIMIT $3
+                    "#,
+                    current_prefix,
+                    after,
+                    limit
+                )
+                .fetch_all(pool)
+                .await?
+            }
+            ProviderTokenTable::OAuthHandoffs => {
+                sqlx::query_as!(
+                    EncryptedProviderTokens,
+                    r#"
+                    SELECT
+                        id                        AS "id!: Uuid",
+                        encrypted_provider_tokens AS "encrypted_provider_tokens!"
+                    FROM oauth_handoffs
+                    WHERE encrypted_provider_tokens IS NOT NULL
+                      AND NOT starts_with(encrypted_provider_tokens, $1)
+                      AND ($2::uuid IS NULL OR id > $2)
+                    ORDER BY id
+                    LIMIT $3
+                    "#,
+                    current_prefix,
+                    after,
+                    limit
+                )
+                .fetch_all(pool)
+                .await?
+            }
+        };
+
+        Ok(rows)
+    }
+
+    /// Replace the value unless it changed since it was read. Returns
+    /// whether it was replaced.
+    pub async fn replace(
+        pool: &PgPool,
+        table: ProviderTokenTable,
+        id: Uuid,
+        previous: &str,
+        encrypted_provider_tokens: &str,
+    ) -> Result<bool, ProviderTokenError> {
+        let result = match table {
+            ProviderTokenTable::OAuthAccounts => {
+                sqlx::query!(
+                    r#"
+                    UPDATE oauth_accounts
+                    SET encrypted_provider_tokens = $3
+                    WHERE id = $1 AND encrypted_provider_tokens = $2
+                    "#,
+                    id,
+                    previous,
+                    encrypted_provider_tokens
+                )
+                .execute(pool)
+                .await?
+            }
+            ProviderTokenTable::OAuthHandoffs => {
+                sqlx::query!(
+                    r#"
+                    UPDATE oauth_handoffs
+                    SET encrypted_provider_tokens = $3
+                    WHERE id = $1 AND encrypted_provider_tokens = $2
+                    "#,
+                    id,
+                    previous,
+                    encrypted_provider_tokens
+                )
+                .execute(pool)
+                .await?
+            }
+        };
+
+        Ok(result.rows_affected() > 0)
+    }
+}
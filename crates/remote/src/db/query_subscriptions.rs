@@ -0,0 +1,330 @@
+use api_types::{IssuePriority, QuerySubscription};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum QuerySubscriptionError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// What subscription filters can match an issue on.
+#[derive(Debug, Clone)]
+pub struct IssueFacts {
+    pub id: Uuid,
+    pub simple_id: String,
+    pub title: String,
+    pub priority: Option<IssuePriority>,
+    pub completed: bool,
+    pub status_name: String,
+    pub tag_names: Vec<String>,
+    pub assignee_ids: Vec<Uuid>,
+    /// Usernames and emails of the assignees.
+    pub assignee_handles: Vec<String>,
+}
+
+pub struct QuerySubscriptionRepository;
+
+impl QuerySubscriptionRepository {
+    pub async fn find_by_id(
+        pool: &PgPool,
+        id: Uuid,
+    ) -> Result<Option<QuerySubscription>, QuerySubscriptionError> {
+        let subscription = sqlx::query_as!(
+            QuerySubscription,
+            r#"
+            SELECT
+                id         AS "id!: Uuid",
+                project_id AS "project_id!: Uuid",
+                user_id    AS "user_id!: Uuid",
+                name       AS "name!",
+                query      AS "query!",
+                created_at AS "created_at!: DateTime<Utc>",
+                updated_at AS "updated_at!: DateTime<Utc>"
+            FROM issue_query_subscriptions
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(subscription)
+    }
+
+    pub async fn list_by_user(
+        pool: &PgPool,
+        user_id: Uuid,
+        project_id: Option<Uuid>,
+    ) -> Result<Vec<QuerySubscription>, QuerySubscriptionError> {
+        let subscriptions = sqlx::query_as!(
+            QuerySubscription,
+            r#"
+            SELECT
+                id         AS "id!: Uuid",
+                project_id AS "project_id!: Uuid",
+                user_id    AS "user_id!: Uuid",
+                name       AS "name!",
+                query      AS "query!",
+                created_at AS "created_at!: DateTime<Utc>",
+                updated_at AS "updated_at!: DateTime<Utc>"
+            FROM issue_query_subscriptions
+            WHERE user_id = $1 AND ($2::uuid IS NULL OR project_id = $2)
+            ORDER BY name, created_at
+            "#,
+            user_id,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(subscriptions)
+    }
+
+    pub async fn list_by_project(
+        pool: &PgPool,
+        project_id: Uuid,
+    ) -> Result<Vec<QuerySubscription>, QuerySubscriptionError> {
+        let subscriptions = sqlx::query_as!(
+            QuerySubscription,
+            r#"
+            SELECT
+                id         AS "id!: Uuid",
+                project_id AS "project_id!: Uuid",
+                user_id    AS "user_id!: Uuid",
+                name       AS "name!",
+                query      AS "query!",
+                created_at AS "created_at!: DateTime<Utc>",
+                updated_at AS "updated_at!: DateTime<Utc>"
+            FROM issue_query_subscriptions
+            WHERE project_id = $1
+            "#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(subscriptions)
+    }
+
+    pub async fn create(
+        pool: &PgPool,
+        project_id: Uuid,
+        user_id: Uuid,
+        name: &str,
+        query: &str,
+    ) -> Result<QuerySubscription, QuerySubscriptionError> {
+        let subscription = sqlx::query_as!(
+            QuerySubscription,
+            r#"
+            INSERT INTO issue_query_subscriptions (project_id, user_id, name, query)
+            VALUES ($1, $2, $3, $4)
+            RETURNING
+                id         AS "id!: Uuid",
+                project_id AS "project_id!: Uuid",
+                user_id    AS "user_id!: Uuid",
+                name       AS "name!",
+                query      AS "query!",
+                created_at AS "created_at!: DateTime<Utc>",
+                updated_at AS "updated_at!: DateTime<Utc>"
+            "#,
+            project_id,
+            user_id,
+            name,
+            query
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(subscription)
+    }
+
+    pub async fn update(
+        pool: &PgPool,
+        id: Uuid,
+        name: &str,
+        query: &str,
+    ) -> Result<QuerySubscription, QuerySubscriptionError> {
+        let subscription = sqlx::query_as!(
+            QuerySubscription,
+            r#"
+            UPDATE issue_query_subscriptions
+            SET name = $2, query = $3, updated_at = NOW()
+            WHERE id = $1
+            RETURNING
+                id         AS "id!: Uuid",
+                project_id AS "project_id!: Uuid",
+                user_id    AS "user_id!: Uuid",
+                name       AS "name!",
+                query      AS "query!",
+                created_at AS "created_at!: DateTime<Utc>",
+                updated_at AS "updated_at!: DateTime<Utc>"
+            "#,
+            id,
+            name,
+            query
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(subscription)
+    }
+
+    pub async fn delete(pool: &PgPool, id: Uuid) -> Result<(), QuerySubscriptionError> {
+        sqlx::query!("DELETE FROM issue_query_subscriptions WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The project's issues, or just `issue_id`, with what filters match on.
+    pub async fn issue_facts(
+        pool: &PgPool,
+        project_id: Uuid,
+        issue_id: Option<Uuid>,
+    ) -> Result<Vec<IssueFacts>, QuerySubscriptionError> {
+        let issues = sqlx::query_as!(
+            IssueFacts,
+            r#"
+            SELECT
+                i.id                       AS "id!: Uuid",
+                i.simple_id                AS "simple_id!",
+                i.title                    AS "title!",
+                i.priority                 AS "priority?: IssuePriority",
+                i.completed_at IS NOT NULL AS "completed!",
+                ps.name                    AS "status_name!",
+                ARRAY(
+                    SELECT t.name
+                    FROM issue_tags it
+                    INNER JOIN tags t ON t.id = it.tag_id
+                    WHERE it.issue_id = i.id
+                ) AS "tag_names!: Vec<String>",
+                ARRAY(
+                    SELECT ia.user_id FROM issue_assignees ia WHERE ia.issue_id = i.id
+                ) AS "assignee_ids!: Vec<Uuid>",
+                ARRAY(
+                    SELECT handle
+                    FROM issue_assignees ia
+                    INNER JOIN users u ON u.id = ia.user_id
+                    CROSS JOIN LATERAL (VALUES (u.username), (u.email)) AS handles(handle)
+                    WHERE ia.issue_id = i.id AND handle IS NOT NULL
+                ) AS "assignee_handles!: Vec<String>"
+            FROM issues i
+            INNER JOIN project_statuses ps ON ps.id = i.status_id
+            WHERE i.project_id = $1
+              AND ($2::uuid IS NULL OR i.id = $2)
+            "#,
+            project_id,
+            issue_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(issues)
+    }
+
+    /// Subscriptions whose results included the issue when last evaluated.
+    pub async fn matching_subscriptions(
+        pool: &PgPool,
+        issue_id: Uuid,
+    ) -> Result<Vec<Uuid>, QuerySubscriptionError> {
+        let ids = sqlx::query_scalar!(
+            "SELECT subscription_id FROM issue_query_subscription_matches WHERE issue_id = $1",
+            issue_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    pub async fn list_matches(
+        pool: &PgPool,
+        subscription_id: Uuid,
+    ) -> Result<Vec<Uuid>, QuerySubscriptionError> {
+        let ids = sqlx::query_scalar!(
+            r#"
+            SELECT m.issue_id
+            FROM issue_query_subscription_matches m
+            INNER JOIN issues i ON i.id = m.issue_id
+            WHERE m.subscription_id = $1
+            ORDER BY i.issue_number
+            "#,
+            subscription_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    pub async fn add_match(
+        pool: &PgPool,
+        subscription_id: Uuid,
+        issue_id: Uuid,
+    ) -> Result<(), QuerySubscriptionError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO issue_query_subscription_matches (subscription_id, issue_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            subscription_id,
+            issue_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_match(
+        pool: &PgPool,
+        subscription_id: Uuid,
+        issue_id: Uuid,
+    ) -> Result<(), QuerySubscriptionError> {
+        sqlx::query!(
+            r#"
+            DELETE FROM issue_query_subscription_matches
+            WHERE subscription_id = $1 AND issue_id = $2
+            "#,
+            subscription_id,
+            issue_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Replace the subscription's results, without notifying anyone.
+    pub async fn replace_matches(
+        pool: &PgPool,
+        subscription_id: Uuid,
+        issue_ids: &[Uuid],
+    ) -> Result<(), QuerySubscriptionError> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM issue_query_subscription_matches WHERE subscription_id = $1",
+            subscription_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO issue_query_subscription_matches (subscription_id, issue_id)
+            SELECT $1, issue_id FROM UNNEST($2::uuid[]) AS issue_id
+            "#,
+            subscription_id,
+            issue_ids
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum ReleaseNotesError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// An issue completed in the release window, with its tags ordered by name
+/// and its linked pull requests that weren't closed unmerged.
+#[derive(Debug, Clone)]
+pub struct CompletedIssue {
+    pub id: Uuid,
+    pub simple_id: String,
+    pub title: String,
+    pub completed_at: DateTime<Utc>,
+    pub tag_ids: Vec<Uuid>,
+    pub tag_names: Vec<String>,
+    pub pr_numbers: Vec<i32>,
+    pub pr_urls: Vec<String>,
+}
+
+pub struct ReleaseNotesRepository;
+
+impl ReleaseNotesRepository {
+    pub async fn completed_issues(
+        pool: &PgPool,
+        project_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<CompletedIssue>, ReleaseNotesError> {
+        let issues = sqlx::query_as!(
+            CompletedIssue,
+            r#"
+            SELECT
+                i.id           AS "id!: Uuid",
+                i.simple_id    AS "simple_id!",
+                i.title        AS "title!",
+                i.completed_at AS "completed_at!: DateTime<Utc>",
+                ARRAY(
+                    SELECT t.id
+                    FROM issue_tags it
+                    INNER JOIN tags t ON t.id = it.tag_id
+                    WHERE it.issue_id = i.id
+                    ORDER BY t.name, t.id
+                ) AS "tag_ids!: Vec<Uuid>",
+                ARRAY(
+                    SELECT t.name
+                    FROM issue_tags it
+                    INNER JOIN tags t ON t.id = it.tag_id
+                    WHERE it.issue_id = i.id
+                    ORDER BY t.name, t.id
+                ) AS "tag_names!: Vec<String>",
+                ARRAY(
+                    SELECT pr.number
+                    FROM pull_request_issues pri
+                    INNER JOIN pull_requests pr ON pr.id = pri.pull_request_id
+                    WHERE pri.issue_id = i.id AND pr.status <> 'closed'
+                    ORDER BY pr.number, pr.id
+                ) AS "pr_numbers!: Vec<i32>",
+                ARRAY(
+                    SELECT pr.url
+                    FROM pull_request_issues pri
+                    INNER JOIN pull_requests pr ON pr.id = pri.pull_request_id
+                    WHERE pri.issue_id = i.id AND pr.status <> 'closed'
+                    ORDER BY pr.number, pr.id
+                ) AS "pr_urls!: Vec<String>"
+            FROM issues i
+            WHERE i.project_id = $1
+              AND i.completed_at >= $2
+              AND i.completed_at < $3
+            ORDER BY i.completed_at, i.issue_number
+            "#,
+            project_id,
+            from,
+            to
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(issues)
+    }
+}
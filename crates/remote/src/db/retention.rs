@@ -0,0 +1,380 @@
+use api_types::{LegalHold, OrganizationRetentionPolicy, RetentionProjectReport};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum RetentionError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// A blob left without attachments by a purge, whose files can be deleted.
+#[derive(Debug, Clone)]
+pub struct PurgedBlob {
+    pub blob_path: String,
+    pub thumbnail_blob_path: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct PurgeResult {
+    pub closed_issues: u64,
+    pub attachments: u64,
+    pub blobs: Vec<PurgedBlob>,
+}
+
+pub struct RetentionRepository;
+
+impl RetentionRepository {
+    pub async fn policy(
+        pool: &PgPool,
+        organization_id: Uuid,
+    ) -> Result<Option<OrganizationRetentionPolicy>, RetentionError> {
+        let policy = sqlx::query_as!(
+            OrganizationRetentionPolicy,
+            r#"
+            SELECT
+                organization_id              AS "organization_id!: Uuid",
+                closed_issue_retention_years AS "closed_issue_retention_years?",
+                attachment_retention_years   AS "attachment_retention_years?"
+            FROM organization_retention_policies
+            WHERE organization_id = $1
+            "#,
+            organization_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(policy)
+    }
+
+    pub async fn set_policy(
+        pool: &PgPool,
+        policy: &OrganizationRetentionPolicy,
+        updated_by: Uuid,
+    ) -> Result<(), RetentionError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO organization_retention_policies
+                (organization_id, closed_issue_retention_years, attachment_retention_years,
+                 updated_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (organization_id) DO UPDATE
+            SET closed_issue_retention_years = EXCLUDED.closed_issue_retention_years,
+                attachment_retention_years = EXCLUDED.attachment_retention_years,
+                updated_by = EXCLUDED.updated_by,
+                updated_at = NOW()
+            "#,
+            policy.organization_id,
+            policy.closed_issue_retention_years,
+            policy.attachment_retention_years,
+            updated_by
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Policies that delete anything.
+    pub async fn active_policies(
+        pool: &PgPool,
+    ) -> Result<Vec<OrganizationRetentionPolicy>, RetentionError> {
+        let policies = sqlx::query_as!(
+            OrganizationRetentionPolicy,
+            r#"
+            SELECT
+                organization_id              AS "organization_id!: Uuid",
+                closed_issue_retention_years AS "closed_issue_retention_years?",
+                attachment_retention_years   AS "attachment_retention_years?"
+            FROM organization_retention_policies
+            WHERE closed_issue_retention_years IS NOT NULL
+               OR attachment_retention_years IS NOT NULL
+            "#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(policies)
+    }
+
+    pub async fn legal_holds(
+        pool: &PgPool,
+        organization_id: Uuid,
+    ) -> Result<Vec<LegalHold>, RetentionError> {
+        let holds = sqlx::query_as!(
+            LegalHold,
+            r#"
+            SELECT
+                h.project_id AS "project_id!: Uuid",
+                h.reason     AS "reason!",
+                h.placed_by  AS "placed_by?: Uuid",
+                h.placed_at  AS "placed_at!: DateTime<Utc>"
+            FROM project_legal_holds h
+            INNER JOIN projects p ON p.id = h.project_id
+            WHERE p.organization_id = $1
+            ORDER BY h.placed_at
+            "#,
+            organization_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(holds)
+    }
+
+    pub async fn legal_hold(
+        pool: &PgPool,
+        project_id: Uuid,
+    ) -> Result<Option<LegalHold>, RetentionError> {
+        let hold = sqlx::query_as!(
+            LegalHold,
+            r#"
+            SELECT
+                project_id AS "project_id!: Uuid",
+                reason     AS "reason!",
+                placed_by  AS "placed_by?: Uuid",
+                placed_at  AS "placed_at!: DateTime<Utc>"
+            FROM project_legal_holds
+            WHERE project_id = $1
+            "#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(hold)
+    }
+
+    /// Whether the issue's project is on legal hold.
+    pub async fn issue_on_hold(pool: &PgPool, issue_id: Uuid) -> Result<bool, RetentionError> {
+        let held = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1
+                FROM issues i
+                INNER JOIN project_legal_holds h ON h.project_id = i.project_id
+                WHERE i.id = $1
+            ) AS "held!"
+            "#,
+            issue_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(held)
+    }
+
+    /// Whether any of the organization's projects are on legal hold.
+    pub async fn organization_has_hold(
+        pool: &PgPool,
+        organization_id: Uuid,
+    ) -> Result<bool, RetentionError> {
+        let held = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1
+                FROM project_legal_holds h
+                INNER JOIN projects p ON p.id = h.project_id
+                WHERE p.organization_id = $1
+            ) AS "held!"
+            "#,
+            organization_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(held)
+    }
+
+    /// Place a hold, or update the reason of an existing one.
+    pub async fn place_legal_hold(
+        pool: &PgPool,
+        project_id: Uuid,
+        reason: &str,
+        placed_by: Uuid,
+    ) -> Result<LegalHold, RetentionError> {
+        let hold = sqlx::query_as!(
+            LegalHold,
+            r#"
+            INSERT INTO project_legal_holds (project_id, reason, placed_by)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (project_id) DO UPDATE SET reason = EXCLUDED.reason
+            RETURNING
+                project_id AS "project_id!: Uuid",
+                reason     AS "reason!",
+                placed_by  AS "placed_by?: Uuid",
+                placed_at  AS "placed_at!: DateTime<Utc>"
+            "#,
+            project_id,
+            reason,
+            placed_by
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(hold)
+    }
+
+    /// Release a hold; false if there was none.
+    pub async fn release_legal_hold(
+        pool: &PgPool,
+        project_id: Uuid,
+    ) -> Result<bool, RetentionError> {
+        let result = sqlx::query!(
+            "DELETE FROM project_legal_holds WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn project_ids(
+        pool: &PgPool,
+        organization_id: Uuid,
+    ) -> Result<Vec<Uuid>, RetentionError> {
+        let ids = sqlx::query_scalar!(
+            "SELECT id FROM projects WHERE organization_id = $1",
+            organization_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// What each of the organization's projects has due for deletion.
+    pub async fn report(
+        pool: &PgPool,
+        organization_id: Uuid,
+        closed_issues_cutoff: Option<DateTime<Utc>>,
+        attachments_cutoff: Option<DateTime<Utc>>,
+    ) -> Result<Vec<RetentionProjectReport>, RetentionError> {
+        let projects = sqlx::query_as!(
+            RetentionProjectReport,
+            r#"
+            SELECT
+                p.id   AS "project_id!: Uuid",
+                p.name AS "project_name!",
+                EXISTS (
+                    SELECT 1 FROM project_legal_holds h WHERE h.project_id = p.id
+                ) AS "legal_hold!",
+                (
+                    SELECT COUNT(*)
+                    FROM issues i
+                    WHERE i.project_id = p.id AND i.completed_at < $2
+                ) AS "closed_issues!",
+                (
+                    SELECT COUNT(*)
+                    FROM attachments a
+                    LEFT JOIN issue_comments c ON c.id = a.comment_id
+                    INNER JOIN issues i ON i.id = COALESCE(a.issue_id, c.issue_id)
+                    WHERE a.expires_at IS NULL
+                      AND (a.created_at < $3 OR i.completed_at < $2)
+                      AND i.project_id = p.id
+                ) AS "attachments!"
+            FROM projects p
+            WHERE p.organization_id = $1
+            ORDER BY p.name, p.id
+            "#,
+            organization_id,
+            closed_issues_cutoff,
+            attachments_cutoff
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(projects)
+    }
+
+    /// Delete what is due from a project, unless it is on legal hold, and
+    /// the blobs left without attachments. Their files are left to the
+    /// caller.
+    pub async fn purge_project(
+        pool: &PgPool,
+        project_id: Uuid,
+        closed_issues_cutoff: Option<DateTime<Utc>>,
+        attachments_cutoff: Option<DateTime<Utc>>,
+    ) -> Result<PurgeResult, RetentionError> {
+        let mut tx = pool.begin().await?;
+
+        // Locking the project makes a hold being placed wait for the purge,
+        // or the purge wait for the hold.
+        let held: Option<bool> = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM project_legal_holds h WHERE h.project_id = p.id
+            ) AS "held!"
+            FROM projects p
+            WHERE p.id = $1
+            FOR UPDATE
+            "#,
+            project_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        if held != Some(false) {
+            return Ok(PurgeResult::default());
+        }
+
+        let blob_ids: Vec<Uuid> = sqlx::query_scalar!(
+            r#"
+            WITH due AS (
+                -- Uploaded before $3, or belonging, directly or through a
+                -- comment, to an issue completed before $2. Either may be NULL.
+                SELECT a.id
+                FROM attachments a
+                LEFT JOIN issue_comments c ON c.id = a.comment_id
+                INNER JOIN issues i ON i.id = COALESCE(a.issue_id, c.issue_id)
+                WHERE a.expires_at IS NULL
+                  AND (a.created_at < $3 OR i.completed_at < $2)
+                  AND i.project_id = $1
+            )
+            DELETE FROM attachments a
+            USING due
+            WHERE a.id = due.id
+            RETURNING a.blob_id AS "blob_id!: Uuid"
+            "#,
+            project_id,
+            closed_issues_cutoff,
+            attachments_cutoff
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let closed_issues = sqlx::query!(
+            "DELETE FROM issues WHERE project_id = $1 AND completed_at < $2",
+            project_id,
+            closed_issues_cutoff
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        let blobs = sqlx::query_as!(
+            PurgedBlob,
+            r#"
+            DELETE FROM blobs b
+            WHERE b.id = ANY($1)
+              AND NOT EXISTS (SELECT 1 FROM attachments a WHERE a.blob_id = b.id)
+            RETURNING
+                b.blob_path           AS "blob_path!",
+                b.thumbnail_blob_path AS "thumbnail_blob_path?"
+            "#,
+            &blob_ids
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(PurgeResult {
+            closed_issues,
+            attachments: blob_ids.len() as u64,
+            blobs,
+        })
+    }
+}
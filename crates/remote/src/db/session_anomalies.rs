@@ -0,0 +1,469 @@
+use std::net::IpAddr;
+
+use api_types::{SessionAnomalyKind, SessionFlag, SessionFlagStatus};
+use chrono::{DateTime, Utc};
+use ipnetwork::IpNetwork;
+use sqlx::PgPool;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum SessionAnomalyError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// A session's requests in the recent window and in the baseline before it.
+#[derive(Debug, Clone)]
+pub struct SessionUsageSummary {
+    pub session_id: Uuid,
+    pub user_id: Uuid,
+    pub recent_requests: i64,
+    pub baseline_requests: i64,
+    pub first_seen_at: DateTime<Utc>,
+    pub recent_countries: Vec<String>,
+    pub known_countries: Vec<String>,
+}
+
+/// Requests a session made from one country in one minute.
+#[derive(Debug, Clone)]
+pub struct SessionUsageCount {
+    pub session_id: Uuid,
+    pub bucket_start: DateTime<Utc>,
+    pub country: String,
+    pub ip: Option<IpAddr>,
+    pub requests: i32,
+}
+
+/// An admin to tell about a flag on a member of their organization.
+#[derive(Debug, Clone)]
+pub struct FlagRecipient {
+    pub organization_id: Uuid,
+    pub organization_name: String,
+    pub admin_email: String,
+    pub member_email: String,
+}
+
+pub struct SessionAnomalyRepository;
+
+impl SessionAnomalyRepository {
+    /// Add counted requests to their sessions' buckets.
+    pub async fn record_usage(
+        pool: &PgPool,
+        counts: &[SessionUsageCount],
+    ) -> Result<(), SessionAnomalyError> {
+        if counts.is_empty() {
+            return Ok(());
+        }
+        let session_ids: Vec<Uuid> = counts.iter().map(|count| count.session_id).collect();
+        let bucket_starts: Vec<DateTime<Utc>> =
+            counts.iter().map(|count| count.bucket_start).collect();
+        let countries: Vec<String> = counts.iter().map(|count| count.country.clone()).collect();
+        let ips: Vec<Option<IpNetwork>> = counts
+            .iter()
+            .map(|count| count.ip.map(IpNetwork::from))
+            .collect();
+        let requests: Vec<i32> = counts.iter().map(|count| count.requests).collect();
+        sqlx::query!(
+            r#"
+            INSERT INTO auth_session_usage
+                (session_id, bucket_start, country, ip_address, request_count)
+            SELECT * FROM UNNEST($1::uuid[], $2::timestamptz[], $3::text[], $4::inet[], $5::int[])
+            ON CONFLICT (session_id, bucket_start, country) DO UPDATE
+            SET request_count = auth_session_usage.request_count + EXCLUDED.request_count,
+                ip_address = COALESCE(EXCLUDED.ip_address, auth_session_usage.ip_address)
+            "#,
+            &session_ids,
+            &bucket_starts,
+            &countries,
+            &ips as &[Option<IpNetwork>],
+            &requests
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Sessions used since `recent_start` that aren't revoked, with their
+    /// usage back to `baseline_start`.
+    pub async fn usage_summaries(
+        pool: &PgPool,
+        recent_start: DateTime<Utc>,
+        baseline_start: DateTime<Utc>,
+    ) -> Result<Vec<SessionUsageSummary>, SessionAnomalyError> {
+        let summaries = sqlx::query_as!(
+            SessionUsageSummary,
+            r#"
+            SELECT
+                u.session_id AS "session_id!: Uuid",
+                s.user_id    AS "user_id!: Uuid",
+                COALESCE(SUM(u.request_count) FILTER (WHERE u.bucket_start >= $1), 0)::BIGINT
+                    AS "recent_requests!",
+                COALESCE(SUM(u.request_count) FILTER (WHERE u.bucket_start < $1), 0)::BIGINT
+                    AS "baseline_requests!",
+                MIN(u.bucket_start) AS "first_seen_at!: DateTime<Utc>",
+                COALESCE(
+                    array_agg(DISTINCT u.country) FILTER (WHERE u.bucket_start >= $1),
+                    '{}'
+                ) AS "recent_countries!: Vec<String>",
+                COALESCE(
+                    array_agg(DISTINCT u.country) FILTER (WHERE u.bucket_start < $1),
+                    '{}'
+                ) AS "known_countries!: Vec<String>"
+            FROM auth_session_usage u
+            INNER JOIN auth_sessions s ON s.id = u.session_id
+            WHERE u.bucket_start >= $2 AND s.revoked_at IS NULL
+            GROUP BY u.session_id, s.user_id
+            HAVING COUNT(*) FILTER (WHERE u.bucket_start >= $1) > 0
+            "#,
+            recent_start,
+            baseline_start
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(summaries)
+    }
+
+    pub async fn prune_usage(
+        pool: &PgPool,
+        before: DateTime<Utc>,
+    ) -> Result<u64, SessionAnomalyError> {
+        let result = sqlx::query!(
+            "DELETE FROM auth_session_usage WHERE bucket_start < $1",
+            before
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Flag the session unless it has an open flag of this kind or one
+    /// detected since `cooldown_start`.
+    pub async fn create_flag(
+        pool: &PgPool,
+        session_id: Uuid,
+        user_id: Uuid,
+        kind: SessionAnomalyKind,
+        description: &str,
+        suspended: bool,
+        cooldown_start: DateTime<Utc>,
+    ) -> Result<Option<SessionFlag>, SessionAnomalyError> {
+        let flag = sqlx::query_as!(
+            SessionFlag,
+            r#"
+            INSERT INTO auth_session_flags (session_id, user_id, kind, description, suspended)
+            SELECT $1, $2, $3, $4, $5
+            WHERE NOT EXISTS (
+                SELECT 1 FROM auth_session_flags
+                WHERE session_id = $1
+                  AND kind = $3
+                  AND (status = 'open' OR detected_at >= $6)
+            )
+            ON CONFLICT DO NOTHING
+            RETURNING
+                id          AS "id!: Uuid",
+                session_id  AS "session_id!: Uuid",
+                user_id     AS "user_id!: Uuid",
+                kind        AS "kind!: SessionAnomalyKind",
+                status      AS "status!: SessionFlagStatus",
+                description AS "description!",
+                suspended   AS "suspended!",
+                detected_at AS "detected_at!: DateTime<Utc>",
+                reviewed_by AS "reviewed_by?: Uuid",
+                reviewed_at AS "reviewed_at?: DateTime<Utc>"
+            "#,
+            session_id,
+            user_id,
+            kind as SessionAnomalyKind,
+            description,
+            suspended,
+            cooldown_start
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(flag)
+    }
+
+    /// Whether any organization the user belongs to suspends flagged
+    /// sessions.
+    pub async fn auto_suspend_enabled(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<bool, SessionAnomalyError> {
+        let enabled = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1
+                FROM organization_member_metadata m
+                INNER JOIN organization_session_anomaly_settings s
+                    ON s.organization_id = m.organization_id
+                WHERE m.user_id = $1 AND s.auto_suspend
+            ) AS "enabled!"
+            "#,
+            user_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(enabled)
+    }
+
+    /// The admins of every organization the user belongs to.
+    pub async fn flag_recipients(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<FlagRecipient>, SessionAnomalyError> {
+        let recipients = sqlx::query_as!(
+            FlagRecipient,
+            r#"
+            SELECT
+                o.id         AS "organization_id!: Uuid",
+                o.name       AS "organization_name!",
+                admin.email  AS "admin_email!",
+                member.email AS "member_email!"
+            FROM organization_member_metadata m
+            INNER JOIN organizations o ON o.id = m.organization_id
+            INNER JOIN users member ON member.id = m.user_id
+            INNER JOIN organization_member_metadata a
+                ON a.organization_id = m.organization_id AND a.role = 'admin'
+            INNER JOIN users admin ON admin.id = a.user_id
+            WHERE m.user_id = $1
+            ORDER BY o.name, admin.email
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(recipients)
+    }
+
+    pub async fn is_suspended(
+        pool: &PgPool,
+        session_id: Uuid,
+    ) -> Result<bool, SessionAnomalyError> {
+        let suspended = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM auth_session_flags
+                WHERE session_id = $1 AND status = 'open' AND suspended
+            ) AS "suspended!"
+            "#,
+            session_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(suspended)
+    }
+
+    /// Flags on the sessions of the organization's members, newest first.
+    pub async fn list_for_organization(
+        pool: &PgPool,
+        organization_id: Uuid,
+        status: Option<SessionFlagStatus>,
+    ) -> Result<Vec<SessionFlag>, SessionAnomalyError> {
+        let flags = sqlx::query_as!(
+            SessionFlag,
+            r#"
+            SELECT
+                f.id          AS "id!: Uuid",
+                f.session_id  AS "session_id!: Uuid",
+                f.user_id     AS "user_id!: Uuid",
+                f.kind        AS "kind!: SessionAnomalyKind",
+                f.status      AS "status!: SessionFlagStatus",
+                f.description AS "description!",
+                f.suspended   AS "suspended!",
+                f.detected_at AS "detected_at!: DateTime<Utc>",
+                f.reviewed_by AS "reviewed_by?: Uuid",
+                f.reviewed_at AS "reviewed_at?: DateTime<Utc>"
+            FROM auth_session_flags f
+            INNER JOIN organization_member_metadata m
+                ON m.user_id = f.user_id AND m.organization_id = $1
+            WHERE $2::auth_session_flag_status IS NULL OR f.status = $2
+            ORDER BY f.detected_at DESC
+            LIMIT 200
+            "#,
+            organization_id,
+            status as Option<SessionFlagStatus>
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(flags)
+    }
+
+    /// The flag, if it is on the session of one of the organization's
+    /// members.
+    pub async fn find_for_organization(
+        pool: &PgPool,
+        organization_id: Uuid,
+        flag_id: Uuid,
+    ) -> Result<Option<SessionFlag>, SessionAnomalyError> {
+        let flag = sqlx::query_as!(
+            SessionFlag,
+            r#"
+            SELECT
+                f.id          AS "id!: Uuid",
+                f.session_id  AS "session_id!: Uuid",
+                f.user_id     AS "user_id!: Uuid",
+                f.kind        AS "kind!: SessionAnomalyKind",
+                f.status      AS "status!: SessionFlagStatus",
+                f.description AS "description!",
+                f.suspended   AS "suspended!",
+                f.detected_at AS "detected_at!: DateTime<Utc>",
+                f.reviewed_by AS "reviewed_by?: Uuid",
+                f.reviewed_at AS "reviewed_at?: DateTime<Utc>"
+            FROM auth_session_flags f
+            INNER JOIN organization_member_metadata m
+                ON m.user_id = f.user_id AND m.organization_id = $1
+            WHERE f.id = $2
+            "#,
+            organization_id,
+            flag_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(flag)
+    }
+
+    /// Close an open flag. Returns `None` if it was already reviewed.
+    pub async fn dismiss(
+        pool: &PgPool,
+        flag_id: Uuid,
+        reviewed_by: Uuid,
+    ) -> Result<Option<SessionFlag>, SessionAnomalyError> {
+        let flag = sqlx::query_as!(
+            SessionFlag,
+            r#"
+            UPDATE auth_session_flags
+            SET status = 'dismissed', reviewed_by = $2, reviewed_at = NOW()
+            WHERE id = $1 AND status = 'open'
+            RETURNING
+                id          AS "id!: Uuid",
+                session_id  AS "session_id!: Uuid",
+                user_id     AS "user_id!: Uuid",
+                kind        AS "kind!: SessionAnomalyKind",
+                status      AS "status!: SessionFlagStatus",
+                description AS "description!",
+                suspended   AS "suspended!",
+                detected_at AS "detected_at!: DateTime<Utc>",
+                reviewed_by AS "reviewed_by?: Uuid",
+                reviewed_at AS "reviewed_at?: DateTime<Utc>"
+            "#,
+            flag_id,
+            reviewed_by
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(flag)
+    }
+
+    /// Revoke the session and close every open flag on it.
+    pub async fn revoke_session(
+        pool: &PgPool,
+        session_id: Uuid,
+        reviewed_by: Uuid,
+    ) -> Result<(), SessionAnomalyError> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "UPDATE auth_sessions SET revoked_at = NOW() WHERE id = $1",
+            session_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"
+            UPDATE auth_session_flags
+            SET status = 'revoked', reviewed_by = $2, reviewed_at = NOW()
+            WHERE session_id = $1 AND status = 'open'
+            "#,
+            session_id,
+            reviewed_by
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn find_flag(
+        pool: &PgPool,
+        flag_id: Uuid,
+    ) -> Result<Option<SessionFlag>, SessionAnomalyError> {
+        let flag = sqlx::query_as!(
+            SessionFlag,
+            r#"
+            SELECT
+                id          AS "id!: Uuid",
+                session_id  AS "session_id!: Uuid",
+                user_id     AS "user_id!: Uuid",
+                kind        AS "kind!: SessionAnomalyKind",
+                status      AS "status!: SessionFlagStatus",
+                description AS "description!",
+                suspended   AS "suspended!",
+                detected_at AS "detected_at!: DateTime<Utc>",
+                reviewed_by AS "reviewed_by?: Uuid",
+                reviewed_at AS "reviewed_at?: DateTime<Utc>"
+            FROM auth_session_flags
+            WHERE id = $1
+            "#,
+            flag_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(flag)
+    }
+
+    pub async fn auto_suspend(
+        pool: &PgPool,
+        organization_id: Uuid,
+    ) -> Result<bool, SessionAnomalyError> {
+        let auto_suspend = sqlx::query_scalar!(
+            r#"
+            SELECT auto_suspend
+            FROM organization_session_anomaly_settings
+            WHERE organization_id = $1
+            "#,
+            organization_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(auto_suspend.unwrap_or(false))
+    }
+
+    pub async fn set_auto_suspend(
+        pool: &PgPool,
+        organization_id: Uuid,
+        auto_suspend: bool,
+        updated_by: Uuid,
+    ) -> Result<(), SessionAnomalyError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO organization_session_anomaly_settings
+                (organization_id, auto_suspend, updated_by)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (organization_id) DO UPDATE
+            SET auto_suspend = EXCLUDED.auto_suspend,
+                updated_by = EXCLUDED.updated_by,
+                updated_at = NOW()
+            "#,
+            organization_id,
+            auto_suspend,
+            updated_by
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,317 @@
+use api_types::{OrganizationWorkingHours, SlaMetric, SlaPolicy};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum SlaError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("query subscription error: {0}")]
+    QuerySubscription(#[from] super::query_subscriptions::QuerySubscriptionError),
+}
+
+/// When an issue was created and when it was first responded to and
+/// completed, for checking it against SLA targets.
+#[derive(Debug, Clone)]
+pub struct IssueTiming {
+    pub id: Uuid,
+    pub simple_id: String,
+    pub title: String,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// The first comment by someone other than the issue's creator.
+    pub first_response_at: Option<DateTime<Utc>>,
+}
+
+/// A project with SLA policies, and its organization.
+#[derive(Debug, Clone)]
+pub struct SlaProject {
+    pub project_id: Uuid,
+    pub organization_id: Uuid,
+}
+
+pub struct SlaRepository;
+
+impl SlaRepository {
+    pub async fn working_hours(
+        pool: &PgPool,
+        organization_id: Uuid,
+    ) -> Result<Option<OrganizationWorkingHours>, SlaError> {
+        let hours = sqlx::query_as!(
+            OrganizationWorkingHours,
+            r#"
+            SELECT
+                organization_id AS "organization_id!: Uuid",
+                timezone        AS "timezone!",
+                start_minute    AS "start_minute!",
+                end_minute      AS "end_minute!",
+                working_days    AS "working_days!: Vec<i16>"
+            FROM organization_working_hours
+            WHERE organization_id = $1
+            "#,
+            organization_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(hours)
+    }
+
+    pub async fn set_working_hours(
+        pool: &PgPool,
+        hours: &OrganizationWorkingHours,
+    ) -> Result<(), SlaError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO organization_working_hours
+                (organization_id, timezone, start_minute, end_minute, working_days)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (organization_id) DO UPDATE
+            SET timezone = EXCLUDED.timezone,
+                start_minute = EXCLUDED.start_minute,
+                end_minute = EXCLUDED.end_minute,
+                working_days = EXCLUDED.working_days,
+                updated_at = NOW()
+            "#,
+            hours.organization_id,
+            &hours.timezone,
+            hours.start_minute,
+            hours.end_minute,
+            &hours.working_days
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_policy(pool: &PgPool, id: Uuid) -> Result<Option<SlaPolicy>, SlaError> {
+        let policy = sqlx::query_as!(
+            SlaPolicy,
+            r#"
+            SELECT
+                id             AS "id!: Uuid",
+                project_id     AS "project_id!: Uuid",
+                name           AS "name!",
+                query          AS "query!",
+                metric         AS "metric!: SlaMetric",
+                target_minutes AS "target_minutes!",
+                created_at     AS "created_at!: DateTime<Utc>",
+                updated_at     AS "updated_at!: DateTime<Utc>"
+            FROM project_sla_policies
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(policy)
+    }
+
+    pub async fn list_policies(
+        pool: &PgPool,
+        project_id: Uuid,
+    ) -> Result<Vec<SlaPolicy>, SlaError> {
+        let policies = sqlx::query_as!(
+            SlaPolicy,
+            r#"
+            SELECT
+                id             AS "id!: Uuid",
+                project_id     AS "project_id!: Uuid",
+                name           AS "name!",
+                query          AS "query!",
+                metric         AS "metric!: SlaMetric",
+                target_minutes AS "target_minutes!",
+                created_at     AS "created_at!: DateTime<Utc>",
+                updated_at     AS "updated_at!: DateTime<Utc>"
+            FROM project_sla_policies
+            WHERE project_id = $1
+            ORDER BY name, created_at
+            "#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(policies)
+    }
+
+    pub async fn create_policy(
+        pool: &PgPool,
+        project_id: Uuid,
+        name: &str,
+        query: &str,
+        metric: SlaMetric,
+        target_minutes: i32,
+    ) -> Result<SlaPolicy, SlaError> {
+        let policy = sqlx::query_as!(
+            SlaPolicy,
+            r#"
+            INSERT INTO project_sla_policies (project_id, name, query, metric, target_minutes)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING
+                id             AS "id!: Uuid",
+                project_id     AS "project_id!: Uuid",
+                name           AS "name!",
+                query          AS "query!",
+                metric         AS "metric!: SlaMetric",
+                target_minutes AS "target_minutes!",
+                created_at     AS "created_at!: DateTime<Utc>",
+                updated_at     AS "updated_at!: DateTime<Utc>"
+            "#,
+            project_id,
+            name,
+            query,
+            metric as SlaMetric,
+            target_minutes
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(policy)
+    }
+
+    pub async fn update_policy(
+        pool: &PgPool,
+        id: Uuid,
+        name: &str,
+        query: &str,
+        metric: SlaMetric,
+        target_minutes: i32,
+    ) -> Result<SlaPolicy, SlaError> {
+        let policy = sqlx::query_as!(
+            SlaPolicy,
+            r#"
+            UPDATE project_sla_policies
+            SET name = $2, query = $3, metric = $4, target_minutes = $5, updated_at = NOW()
+            WHERE id = $1
+            RETURNING
+                id             AS "id!: Uuid",
+                project_id     AS "project_id!: Uuid",
+                name           AS "name!",
+                query          AS "query!",
+                metric         AS "metric!: SlaMetric",
+                target_minutes AS "target_minutes!",
+                created_at     AS "created_at!: DateTime<Utc>",
+                updated_at     AS "updated_at!: DateTime<Utc>"
+            "#,
+            id,
+            name,
+            query,
+            metric as SlaMetric,
+            target_minutes
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(policy)
+    }
+
+    pub async fn delete_policy(pool: &PgPool, id: Uuid) -> Result<(), SlaError> {
+        sqlx::query!("DELETE FROM project_sla_policies WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn projects_with_policies(pool: &PgPool) -> Result<Vec<SlaProject>, SlaError> {
+        let projects = sqlx::query_as!(
+            SlaProject,
+            r#"
+            SELECT
+                DISTINCT p.id     AS "project_id!: Uuid",
+                p.organization_id AS "organization_id!: Uuid"
+            FROM project_sla_policies sp
+            INNER JOIN projects p ON p.id = sp.project_id
+            "#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(projects)
+    }
+
+    /// The project's issues created in `[from, to)`, either bound optional,
+    /// and only open ones if `open_only`.
+    pub async fn issue_timings(
+        pool: &PgPool,
+        project_id: Uuid,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        open_only: bool,
+    ) -> Result<Vec<IssueTiming>, SlaError> {
+        let issues = sqlx::query_as!(
+            IssueTiming,
+            r#"
+            SELECT
+                i.id           AS "id!: Uuid",
+                i.simple_id    AS "simple_id!",
+                i.title        AS "title!",
+                i.created_at   AS "created_at!: DateTime<Utc>",
+                i.completed_at AS "completed_at?: DateTime<Utc>",
+                (
+                    SELECT MIN(c.created_at)
+                    FROM issue_comments c
+                    WHERE c.issue_id = i.id
+                      AND c.author_id IS DISTINCT FROM i.creator_user_id
+                ) AS "first_response_at?: DateTime<Utc>"
+            FROM issues i
+            WHERE i.project_id = $1
+              AND ($2::timestamptz IS NULL OR i.created_at >= $2)
+              AND ($3::timestamptz IS NULL OR i.created_at < $3)
+              AND (NOT $4 OR i.completed_at IS NULL)
+            ORDER BY i.created_at
+            "#,
+            project_id,
+            from,
+            to,
+            open_only
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(issues)
+    }
+
+    /// Record a breach; false if it was already recorded.
+    pub async fn record_breach(
+        pool: &PgPool,
+        policy_id: Uuid,
+        issue_id: Uuid,
+        deadline: DateTime<Utc>,
+    ) -> Result<bool, SlaError> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO issue_sla_breaches (policy_id, issue_id, deadline)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (policy_id, issue_id) DO NOTHING
+            "#,
+            policy_id,
+            issue_id,
+            deadline
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn admin_ids(pool: &PgPool, organization_id: Uuid) -> Result<Vec<Uuid>, SlaError> {
+        let ids = sqlx::query_scalar!(
+            r#"
+            SELECT user_id
+            FROM organization_member_metadata
+            WHERE organization_id = $1 AND role = 'admin'
+            "#,
+            organization_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(ids)
+    }
+}
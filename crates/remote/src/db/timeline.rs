@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum TimelineError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct TimelineIssue {
+    pub id: Uuid,
+    pub simple_id: String,
+    pub title: String,
+    pub parent_issue_id: Option<Uuid>,
+    pub start_date: Option<DateTime<Utc>>,
+    pub target_date: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+pub struct TimelineRepository;
+
+impl TimelineRepository {
+    pub async fn issues(
+        pool: &PgPool,
+        project_id: Uuid,
+    ) -> Result<Vec<TimelineIssue>, TimelineError> {
+        let issues = sqlx::query_as!(
+            TimelineIssue,
+            r#"
+            SELECT
+                id              AS "id!: Uuid",
+                simple_id       AS "simple_id!",
+                title           AS "title!",
+                parent_issue_id AS "parent_issue_id?: Uuid",
+                start_date      AS "start_date?: DateTime<Utc>",
+                target_date     AS "target_date?: DateTime<Utc>",
+                completed_at    AS "completed_at?: DateTime<Utc>"
+            FROM issues
+            WHERE project_id = $1
+            ORDER BY issue_number
+            "#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(issues)
+    }
+}
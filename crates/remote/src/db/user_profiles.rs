@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+
+use sqlx::PgPool;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum UserProfileError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct UserProfileRow {
+    pub id: Uuid,
+    pub email: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub username: Option<String>,
+    pub display_name: Option<String>,
+    pub avatar_attachment_id: Option<Uuid>,
+    /// Thumbnail, or else the original, of the uploaded avatar.
+    pub avatar_blob_path: Option<String>,
+    /// Avatar of the first linked OAuth account.
+    pub oauth_avatar_url: Option<String>,
+    pub timezone: Option<String>,
+    pub locale: Option<String>,
+}
+
+/// New values for every profile setting.
+#[derive(Debug, Clone)]
+pub struct ProfileUpdate<'a> {
+    pub display_name: Option<&'a str>,
+    pub avatar_attachment_id: Option<Uuid>,
+    pub timezone: Option<&'a str>,
+    pub locale: Option<&'a str>,
+}
+
+pub struct UserProfileRepository;
+
+impl UserProfileRepository {
+    pub async fn find(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Option<UserProfileRow>, UserProfileError> {
+        let profile = sqlx::query_as!(
+            UserProfileRow,
+            r#"
+            SELECT
+                u.id                                         AS "id!: Uuid",
+                u.email                                      AS "email!",
+                u.first_name                                 AS "first_name?",
+                u.last_name                                  AS "last_name?",
+                u.username                                   AS "username?",
+                u.display_name                               AS "display_name?",
+                u.avatar_attachment_id                       AS "avatar_attachment_id?: Uuid",
+                COALESCE(b.thumbnail_blob_path, b.blob_path) AS "avatar_blob_path?",
+                oa.avatar_url                                AS "oauth_avatar_url?",
+                u.timezone                                   AS "timezone?",
+                u.locale                                     AS "locale?"
+            FROM users u
+            LEFT JOIN attachments a ON a.id = u.avatar_attachment_id
+            LEFT JOIN blobs b ON b.id = a.blob_id
+            LEFT JOIN LATERAL (
+                SELECT avatar_url
+                FROM oauth_accounts
+                WHERE user_id = u.id
+                ORDER BY created_at ASC
+                LIMIT 1
+            ) oa ON true
+            WHERE u.id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(profile)
+    }
+
+    /// Store the settings. A newly chosen avatar stops expiring like a staged
+    /// upload, and the one it replaces expires so attachment cleanup removes
+    /// it.
+    pub async fn update(
+        pool: &PgPool,
+        user_id: Uuid,
+        update: &ProfileUpdate<'_>,
+    ) -> Result<(), UserProfileError> {
+        let mut tx = pool.begin().await?;
+        let previous_avatar: Option<Uuid> = sqlx::query_scalar!(
+            "SELECT avatar_attachment_id FROM users WHERE id = $1 FOR UPDATE",
+            user_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE users
+            SET display_name = $2,
+                avatar_attachment_id = $3,
+                timezone = $4,
+                locale = $5,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+            user_id,
+            update.display_name,
+            update.avatar_attachment_id,
+            update.timezone,
+            update.locale
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if previous_avatar != update.avatar_attachment_id {
+            sqlx::query!(
+                r#"
+                UPDATE attachments
+                SET expires_at = CASE WHEN id = $1 THEN NULL ELSE NOW() END
+                WHERE id = $1 OR id = $2
+                "#,
+                update.avatar_attachment_id,
+                previous_avatar
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Blob paths of the users' uploaded avatars, for those who have one.
+    pub async fn avatar_blob_paths(
+        pool: &PgPool,
+        user_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, String>, UserProfileError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                u.id                                          AS "id!: Uuid",
+                COALESCE(b.thumbnail_blob_path, b.blob_path) AS "blob_path!"
+            FROM users u
+            INNER JOIN attachments a ON a.id = u.avatar_attachment_id
+            INNER JOIN blobs b ON b.id = a.blob_id
+            WHERE u.id = ANY($1)
+            "#,
+            user_ids
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.id, row.blob_path))
+            .collect())
+    }
+}
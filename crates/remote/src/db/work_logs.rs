@@ -0,0 +1,306 @@
+use api_types::{IssueWorkTotal, UserWorkTotal, WorkLog, WorkLogSummaryQuery, WorkTimer};
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::PgPool;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum WorkLogError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+pub struct WorkLogRepository;
+
+impl WorkLogRepository {
+    pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<WorkLog>, WorkLogError> {
+        let work_log = sqlx::query_as!(
+            WorkLog,
+            r#"
+            SELECT
+                id         AS "id!: Uuid",
+                issue_id   AS "issue_id!: Uuid",
+                user_id    AS "user_id!: Uuid",
+                minutes    AS "minutes!",
+                note       AS "note?",
+                work_date  AS "work_date!: NaiveDate",
+                created_at AS "created_at!: DateTime<Utc>",
+                updated_at AS "updated_at!: DateTime<Utc>"
+            FROM issue_work_logs
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(work_log)
+    }
+
+    pub async fn list_by_issue(
+        pool: &PgPool,
+        issue_id: Uuid,
+    ) -> Result<Vec<WorkLog>, WorkLogError> {
+        let work_logs = sqlx::query_as!(
+            WorkLog,
+            r#"
+            SELECT
+                id         AS "id!: Uuid",
+                issue_id   AS "issue_id!: Uuid",
+                user_id    AS "user_id!: Uuid",
+                minutes    AS "minutes!",
+                note       AS "note?",
+                work_date  AS "work_date!: NaiveDate",
+                created_at AS "created_at!: DateTime<Utc>",
+                updated_at AS "updated_at!: DateTime<Utc>"
+            FROM issue_work_logs
+            WHERE issue_id = $1
+            ORDER BY work_date DESC, created_at DESC
+            "#,
+            issue_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(work_logs)
+    }
+
+    pub async fn create(
+        pool: &PgPool,
+        issue_id: Uuid,
+        user_id: Uuid,
+        minutes: i32,
+        note: Option<&str>,
+        work_date: NaiveDate,
+    ) -> Result<WorkLog, WorkLogError> {
+        let work_log = sqlx::query_as!(
+            WorkLog,
+            r#"
+            INSERT INTO issue_work_logs (issue_id, user_id, minutes, note, work_date)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING
+                id         AS "id!: Uuid",
+                issue_id   AS "issue_id!: Uuid",
+                user_id    AS "user_id!: Uuid",
+                minutes    AS "minutes!",
+                note       AS "note?",
+                work_date  AS "work_date!: NaiveDate",
+                created_at AS "created_at!: DateTime<Utc>",
+                updated_at AS "updated_at!: DateTime<Utc>"
+            "#,
+            issue_id,
+            user_id,
+            minutes,
+            note,
+            work_date
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(work_log)
+    }
+
+    pub async fn update(
+        pool: &PgPool,
+        id: Uuid,
+        minutes: i32,
+        note: Option<&str>,
+        work_date: NaiveDate,
+    ) -> Result<WorkLog, WorkLogError> {
+        let work_log = sqlx::query_as!(
+            WorkLog,
+            r#"
+            UPDATE issue_work_logs
+            SET minutes = $2, note = $3, work_date = $4, updated_at = NOW()
+            WHERE id = $1
+            RETURNING
+                id         AS "id!: Uuid",
+                issue_id   AS "issue_id!: Uuid",
+                user_id    AS "user_id!: Uuid",
+                minutes    AS "minutes!",
+                note       AS "note?",
+                work_date  AS "work_date!: NaiveDate",
+                created_at AS "created_at!: DateTime<Utc>",
+                updated_at AS "updated_at!: DateTime<Utc>"
+            "#,
+            id,
+            minutes,
+            note,
+            work_date
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(work_log)
+    }
+
+    pub async fn delete(pool: &PgPool, id: Uuid) -> Result<(), WorkLogError> {
+        sqlx::query!("DELETE FROM issue_work_logs WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Time logged on the project's issues matching `query`, per issue and
+    /// per user.
+    pub async fn summarize(
+        pool: &PgPool,
+        query: &WorkLogSummaryQuery,
+    ) -> Result<(Vec<IssueWorkTotal>, Vec<UserWorkTotal>), WorkLogError> {
+        let by_issue = sqlx::query_as!(
+            IssueWorkTotal,
+            r#"
+            SELECT
+                i.id                    AS "issue_id!: Uuid",
+                i.simple_id             AS "simple_id!",
+                i.title                 AS "title!",
+                SUM(wl.minutes)::bigint AS "minutes!"
+            FROM issue_work_logs wl
+            INNER JOIN issues i ON i.id = wl.issue_id
+            WHERE i.project_id = $1
+              AND ($2::date IS NULL OR wl.work_date >= $2)
+              AND ($3::date IS NULL OR wl.work_date <= $3)
+              AND ($4::uuid IS NULL OR wl.user_id = $4)
+            GROUP BY i.id
+            ORDER BY 4 DESC, i.issue_number
+            "#,
+            query.project_id,
+            query.from,
+            query.to,
+            query.user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let by_user = sqlx::query_as!(
+            UserWorkTotal,
+            r#"
+            SELECT
+                u.id                    AS "user_id!: Uuid",
+                COALESCE(
+                    NULLIF(TRIM(CONCAT_WS(' ', u.first_name, u.last_name)), ''),
+                    u.username,
+                    u.email
+                ) AS "name!",
+                SUM(wl.minutes)::bigint AS "minutes!"
+            FROM issue_work_logs wl
+            INNER JOIN issues i ON i.id = wl.issue_id
+            INNER JOIN users u ON u.id = wl.user_id
+            WHERE i.project_id = $1
+              AND ($2::date IS NULL OR wl.work_date >= $2)
+              AND ($3::date IS NULL OR wl.work_date <= $3)
+              AND ($4::uuid IS NULL OR wl.user_id = $4)
+            GROUP BY u.id
+            ORDER BY 3 DESC, 2
+            "#,
+            query.project_id,
+            query.from,
+            query.to,
+            query.user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok((by_issue, by_user))
+    }
+
+    pub async fn find_timer(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Option<WorkTimer>, WorkLogError> {
+        let timer = sqlx::query_as!(
+            WorkTimer,
+            r#"
+            SELECT
+                user_id    AS "user_id!: Uuid",
+                issue_id   AS "issue_id!: Uuid",
+                note       AS "note?",
+                started_at AS "started_at!: DateTime<Utc>"
+            FROM issue_work_timers
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(timer)
+    }
+
+    /// Start the user's timer, unless one is already running.
+    pub async fn start_timer(
+        pool: &PgPool,
+        user_id: Uuid,
+        issue_id: Uuid,
+        note: Option<&str>,
+    ) -> Result<Option<WorkTimer>, WorkLogError> {
+        let timer = sqlx::query_as!(
+            WorkTimer,
+            r#"
+            INSERT INTO issue_work_timers (user_id, issue_id, note)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id) DO NOTHING
+            RETURNING
+                user_id    AS "user_id!: Uuid",
+                issue_id   AS "issue_id!: Uuid",
+                note       AS "note?",
+                started_at AS "started_at!: DateTime<Utc>"
+            "#,
+            user_id,
+            issue_id,
+            note
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(timer)
+    }
+
+    /// Stop the user's timer and log the time it ran, rounded up to the
+    /// minute, on the day it was started.
+    pub async fn stop_timer(pool: &PgPool, user_id: Uuid) -> Result<Option<WorkLog>, WorkLogError> {
+        let work_log = sqlx::query_as!(
+            WorkLog,
+            r#"
+            WITH timer AS (
+                DELETE FROM issue_work_timers
+                WHERE user_id = $1
+                RETURNING user_id, issue_id, note, started_at
+            )
+            INSERT INTO issue_work_logs (issue_id, user_id, minutes, note, work_date)
+            SELECT
+                issue_id,
+                user_id,
+                GREATEST(CEIL(EXTRACT(EPOCH FROM NOW() - started_at) / 60), 1)::integer,
+                note,
+                (started_at AT TIME ZONE 'UTC')::date
+            FROM timer
+            RETURNING
+                id         AS "id!: Uuid",
+                issue_id   AS "issue_id!: Uuid",
+                user_id    AS "user_id!: Uuid",
+                minutes    AS "minutes!",
+                note       AS "note?",
+                work_date  AS "work_date!: NaiveDate",
+                created_at AS "created_at!: DateTime<Utc>",
+                updated_at AS "updated_at!: DateTime<Utc>"
+            "#,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(work_log)
+    }
+
+    /// Stop the user's timer without logging the time. Returns whether one
+    /// was running.
+    pub async fn discard_timer(pool: &PgPool, user_id: Uuid) -> Result<bool, WorkLogError> {
+        let result = sqlx::query!("DELETE FROM issue_work_timers WHERE user_id = $1", user_id)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
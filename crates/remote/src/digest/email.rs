@@ -5,12 +5,14 @@ use uuid::Uuid;
 
 use crate::{
     db::digest::NotificationDigestRow,
+    localization::{RecipientTemplates, render_template},
     mail::{DIGEST_PREVIEW_COUNT, DigestNotificationItem},
 };
 
 pub fn build_digest_items(
     rows: &[NotificationDigestRow],
     base_url: &str,
+    templates: &RecipientTemplates,
 ) -> Vec<DigestNotificationItem> {
     let preview_rows = select_preview_rows(rows);
 
@@ -19,7 +21,8 @@ pub fn build_digest_items(
         .map(|row| {
             let payload = &row.payload.0;
             let deeplink = absolute_url(base_url, payload.deeplink_path.as_deref().unwrap_or(""));
-            let copy = build_digest_copy(row);
+            let copy =
+                build_localized_copy(row, templates).unwrap_or_else(|| build_digest_copy(row));
 
             DigestNotificationItem {
                 title: copy.title,
@@ -89,7 +92,7 @@ fn build_preview_groups(rows: &[NotificationDigestRow]) -> Vec<PreviewGroup<'_>>
     groups
 }
 
-fn preview_issue_id(row: &NotificationDigestRow) -> Option<Uuid> {
+pub(crate) fn preview_issue_id(row: &NotificationDigestRow) -> Option<Uuid> {
     row.payload.0.issue_id.or(row.issue_id)
 }
 
@@ -98,6 +101,86 @@ struct DigestCopy {
     body: Option<String>,
 }
 
+/// Copy from the project's template for the recipient's locale, if any.
+fn build_localized_copy(
+    row: &NotificationDigestRow,
+    templates: &RecipientTemplates,
+) -> Option<DigestCopy> {
+    let template = templates.resolve(preview_issue_id(row), row.notification_type)?;
+    let values = template_values(row);
+
+    Some(DigestCopy {
+        title: render_template(&template.title, &values),
+        body: template
+            .body
+            .as_deref()
+            .map(|body| render_template(body, &values))
+            .filter(|body| !body.is_empty())
+            .map(|body| truncate_text(&body, 180)),
+    })
+}
+
+/// Values for the placeholders listed in [`crate::localization::placeholders`].
+fn template_values(row: &NotificationDigestRow) -> Vec<(&'static str, Option<String>)> {
+    let payload = &row.payload.0;
+    vec![
+        ("actor", Some(row.actor_name.clone())),
+        ("issue", Some(issue_label(payload))),
+        (
+            "issue_title",
+            clean_optional_text(payload.issue_title.as_deref()),
+        ),
+        (
+            "comment",
+            payload
+                .comment_preview
+                .as_deref()
+                .map(clean_preview_text)
+                .filter(|value| !value.is_empty())
+                .map(|value| truncate_text(&value, 177)),
+        ),
+        (
+            "old_status",
+            clean_optional_text(payload.old_status_name.as_deref()),
+        ),
+        (
+            "new_status",
+            clean_optional_text(payload.new_status_name.as_deref()),
+        ),
+        (
+            "old_priority",
+            payload
+                .old_priority
+                .map(|value| priority_label(value).to_string()),
+        ),
+        (
+            "new_priority",
+            payload
+                .new_priority
+                .map(|value| priority_label(value).to_string()),
+        ),
+        ("emoji", clean_optional_text(payload.emoji.as_deref())),
+        (
+            "new_title",
+            clean_optional_text(payload.new_title.as_deref()),
+        ),
+        (
+            "filter",
+            clean_optional_text(payload.subscription_name.as_deref()),
+        ),
+        (
+            "due_date",
+            payload
+                .target_date
+                .map(|date| date.format("%Y-%m-%d").to_string()),
+        ),
+        (
+            "sla_policy",
+            clean_optional_text(payload.sla_policy_name.as_deref()),
+        ),
+    ]
+}
+
 fn build_digest_copy(row: &NotificationDigestRow) -> DigestCopy {
     let payload = &row.payload.0;
     let actor_name = &row.actor_name;
@@ -192,6 +275,34 @@ fn build_digest_copy(row: &NotificationDigestRow) -> DigestCopy {
             format!("{actor_name} changed the description on {issue_label}"),
             issue_context(payload).map(|issue| format!("Updated the description on {issue}.")),
         ),
+        NotificationType::IssueEnteredFilter => {
+            let title = match clean_optional_text(payload.subscription_name.as_deref()) {
+                Some(name) => format!("{issue_label} now matches your filter {name}"),
+                None => format!("{issue_label} now matches one of your filters"),
+            };
+            (title, issue_context(payload))
+        }
+        NotificationType::IssueLeftFilter => {
+            let title = match clean_optional_text(payload.subscription_name.as_deref()) {
+                Some(name) => format!("{issue_label} no longer matches your filter {name}"),
+                None => format!("{issue_label} no longer matches one of your filters"),
+            };
+            (title, issue_context(payload))
+        }
+        NotificationType::IssueDueSoon => {
+            let title = match payload.target_date {
+                Some(date) => format!("{issue_label} is due on {}", date.format("%B %-d")),
+                None => format!("{issue_label} is due soon"),
+            };
+            (title, issue_context(payload))
+        }
+        NotificationType::IssueSlaBreached => {
+            let title = match clean_optional_text(payload.sla_policy_name.as_deref()) {
+                Some(name) => format!("{issue_label} breached the SLA {name}"),
+                None => format!("{issue_label} breached an SLA"),
+            };
+            (title, issue_context(payload))
+        }
     };
 
     DigestCopy {
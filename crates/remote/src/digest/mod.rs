@@ -10,6 +10,7 @@ use tracing::{info, warn};
 
 use crate::{
     db::digest::DigestRepository,
+    localization::RecipientTemplates,
     mail::{DIGEST_PREVIEW_COUNT, DigestContact, Mailer},
 };
 
@@ -107,13 +108,25 @@ async fn process_user_digest(
         .map(|row| row.id)
         .collect::<Vec<_>>();
 
-    let items = email::build_digest_items(&notification_rows, base_url);
+    let issue_ids = notification_rows
+        .iter()
+        .filter_map(email::preview_issue_id)
+        .collect::<Vec<_>>();
+    let templates = RecipientTemplates::load(pool, user.id, &issue_ids)
+        .await
+        .unwrap_or_else(|error| {
+            warn!(user_id = %user.id, ?error, "Digest: failed to load notification templates");
+            RecipientTemplates::default()
+        });
+
+    let items = email::build_digest_items(&notification_rows, base_url, &templates);
     let notifications_url = email::notifications_url(base_url);
     let contact = DigestContact {
         email: &user.email,
         user_id: &user.id.to_string(),
         first_name: user.first_name.as_deref(),
         last_name: user.last_name.as_deref(),
+        locale: templates.locale(notification_rows.first().and_then(email::preview_issue_id)),
     };
 
     mailer
@@ -0,0 +1,65 @@
+//! References a project document's markdown makes to issues and attachments.
+//!
+//! Links are derived from the content on every save rather than edited
+//! separately, so they always reflect what the page actually says.
+
+use uuid::Uuid;
+
+const ATTACHMENT_SCHEME: &str = "attachment://";
+
+/// Simple IDs (`APP-12`) mentioned in the content, uppercased and
+/// deduplicated.
+pub fn issue_references(content: &str) -> Vec<String> {
+    let mut references: Vec<String> = content
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .filter_map(|word| {
+            let (prefix, number) = word.trim_matches('-').rsplit_once('-')?;
+            let prefix = prefix.rsplit('-').next()?;
+            let valid_prefix =
+                prefix.len() <= 10 && prefix.starts_with(|c: char| c.is_ascii_alphabetic());
+            let valid_number = !number.is_empty() && number.chars().all(|c| c.is_ascii_digit());
+            (valid_prefix && valid_number)
+                .then(|| format!("{}-{number}", prefix.to_ascii_uppercase()))
+        })
+        .collect();
+    references.sort();
+    references.dedup();
+    references
+}
+
+/// Attachments embedded as `attachment://<id>`, deduplicated.
+pub fn attachment_references(content: &str) -> Vec<Uuid> {
+    let mut references: Vec<Uuid> = content
+        .match_indices(ATTACHMENT_SCHEME)
+        .filter_map(|(index, _)| {
+            let id = content.get(index + ATTACHMENT_SCHEME.len()..)?.get(..36)?;
+            Uuid::parse_str(id).ok()
+        })
+        .collect();
+    references.sort();
+    references.dedup();
+    references
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_issue_references() {
+        let content = "See APP-12 and (app-3), not 12-APP or v1.2-beta. Blocked on APP-12.\n\
+                       Rolled out in web-APP-7 - done";
+        assert_eq!(issue_references(content), vec!["APP-12", "APP-3", "APP-7"]);
+    }
+
+    #[test]
+    fn finds_attachment_references() {
+        let id = Uuid::new_v4();
+        let content = format!(
+            "![diagram](attachment://{id}) [spec](attachment://{id}) \
+             ![broken](attachment://not-a-uuid) ![cut](attachment://{})",
+            &id.to_string()[..8]
+        );
+        assert_eq!(attachment_references(&content), vec![id]);
+    }
+}
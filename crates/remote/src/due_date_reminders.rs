@@ -0,0 +1,161 @@
+//! Reminders sent to assignees before an issue is due.
+//!
+//! A due date is a calendar day, the UTC date of `target_date` as in the
+//! calendar feed. Each assignee is reminded once per due date, from
+//! [`REMINDER_HOUR`] on the day before in their own time zone until the due
+//! day ends there, so a date set at the last minute still gets a reminder.
+
+use std::time::Duration;
+
+use api_types::{NotificationPayload, NotificationType};
+use chrono::{DateTime, Days, NaiveDate, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::{
+    db::{
+        due_date_reminders::{DueDateReminderError, DueDateReminderRepository},
+        issues::IssueRepository,
+    },
+    notifications::notify_user_without_actor,
+    user_profiles::timezone_or_utc,
+};
+
+/// Local hour on the day before the due date when reminders start.
+pub const REMINDER_HOUR: u32 = 9;
+const REMINDER_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// The UTC instant of `hour` o'clock on `date` in `tz`. A time skipped by a
+/// daylight saving change moves to the hour after it.
+fn local_time(tz: Tz, date: NaiveDate, hour: u32) -> DateTime<Utc> {
+    let naive = date.and_hms_opt(hour, 0, 0).expect("hour is in range");
+    tz.from_local_datetime(&naive)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(naive + TimeDelta::hours(1)))
+                .earliest()
+        })
+        .map(|time| time.with_timezone(&Utc))
+        .unwrap_or_else(|| naive.and_utc())
+}
+
+/// When the reminder for a due date may be sent to someone in `tz`.
+pub fn reminder_window(due: NaiveDate, tz: Tz) -> (DateTime<Utc>, DateTime<Utc>) {
+    (
+        local_time(tz, due - Days::new(1), REMINDER_HOUR),
+        local_time(tz, due + Days::new(1), 0),
+    )
+}
+
+/// Notify assignees whose reminder window is open. Returns how many
+/// reminders were sent.
+pub async fn send_due_reminders(
+    pool: &PgPool,
+    now: DateTime<Utc>,
+) -> Result<u32, DueDateReminderError> {
+    // Wide enough for any time zone's window to be covered.
+    let candidates = DueDateReminderRepository::candidates(
+        pool,
+        now - TimeDelta::days(2),
+        now + TimeDelta::days(3),
+    )
+    .await?;
+
+    let mut sent = 0;
+    for candidate in candidates {
+        let tz = timezone_or_utc(candidate.timezone.as_deref());
+        let (start, end) = reminder_window(candidate.target_date.date_naive(), tz);
+        if now < start || now >= end {
+            continue;
+        }
+        if !DueDateReminderRepository::record(
+            pool,
+            candidate.issue_id,
+            candidate.user_id,
+            candidate.target_date,
+        )
+        .await?
+        {
+            continue;
+        }
+
+        let issue = match IssueRepository::find_by_id(pool, candidate.issue_id).await {
+            Ok(Some(issue)) => issue,
+            Ok(None) => continue,
+            Err(error) => {
+                warn!(?error, issue_id = %candidate.issue_id, "failed to load issue for reminder");
+                continue;
+            }
+        };
+        notify_user_without_actor(
+            pool,
+            candidate.organization_id,
+            candidate.user_id,
+            &issue,
+            NotificationType::IssueDueSoon,
+            NotificationPayload {
+                target_date: Some(candidate.target_date),
+                ..Default::default()
+            },
+        )
+        .await;
+        sent += 1;
+    }
+
+    Ok(sent)
+}
+
+/// Spawns a background task that sends due-date reminders. Call once during
+/// server startup.
+pub(crate) fn spawn_reminder_task(pool: PgPool) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(REMINDER_INTERVAL);
+        // Skip the immediate first tick so the server can finish starting up.
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+            match send_due_reminders(&pool, Utc::now()).await {
+                Ok(sent) => info!(sent, "Due-date reminders sent"),
+                Err(error) => warn!(?error, "Due-date reminders failed"),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn opens_the_day_before_in_local_time() {
+        let due = NaiveDate::from_ymd_opt(2026, 6, 10).unwrap();
+
+        assert_eq!(
+            reminder_window(due, Tz::UTC),
+            (at("2026-06-09T09:00:00Z"), at("2026-06-11T00:00:00Z"))
+        );
+        assert_eq!(
+            reminder_window(due, chrono_tz::Asia::Tokyo),
+            (at("2026-06-09T00:00:00Z"), at("2026-06-10T15:00:00Z"))
+        );
+        assert_eq!(
+            reminder_window(due, chrono_tz::America::Los_Angeles),
+            (at("2026-06-09T16:00:00Z"), at("2026-06-11T07:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn skips_past_missing_local_times() {
+        // Clocks in Santiago jump from 00:00 to 01:00 on 2026-09-06.
+        let due = NaiveDate::from_ymd_opt(2026, 9, 5).unwrap();
+        let (_, end) = reminder_window(due, chrono_tz::America::Santiago);
+        assert_eq!(end, at("2026-09-06T04:00:00Z"));
+    }
+}
@@ -0,0 +1,173 @@
+//! Issue mentions in commits and pull requests, read from webhook payloads.
+//!
+//! Issues are referenced by simple ID (`APP-12`); a bare `#12` is GitHub's
+//! own issue numbering and is left alone.
+
+use api_types::IssueCodeReferenceKind;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::{db::issue_code_references::CodeMention, documents::issue_references};
+
+/// A commit or pull request that mentions at least one simple ID.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeMentionEvent {
+    pub kind: IssueCodeReferenceKind,
+    pub repo_full_name: String,
+    pub reference: String,
+    pub url: String,
+    pub title: String,
+    pub author_login: Option<String>,
+    pub referenced_at: DateTime<Utc>,
+    pub simple_ids: Vec<String>,
+}
+
+impl CodeMentionEvent {
+    pub fn as_mention(&self) -> CodeMention<'_> {
+        CodeMention {
+            kind: self.kind,
+            repo_full_name: &self.repo_full_name,
+            reference: &self.reference,
+            url: &self.url,
+            title: &self.title,
+            author_login: self.author_login.as_deref(),
+            referenced_at: self.referenced_at,
+            simple_ids: &self.simple_ids,
+        }
+    }
+}
+
+fn timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    value
+        .as_str()
+        .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+        .map(|value| value.with_timezone(&Utc))
+}
+
+/// Commits in a `push` event that mention issues. Commits already pushed to
+/// another branch of the repository are skipped.
+pub fn from_push(payload: &Value) -> Vec<CodeMentionEvent> {
+    let Some(repo_full_name) = payload["repository"]["full_name"].as_str() else {
+        return vec![];
+    };
+    payload["commits"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|commit| commit["distinct"].as_bool().unwrap_or(true))
+        .filter_map(|commit| {
+            let message = commit["message"].as_str()?;
+            let simple_ids = issue_references(message);
+            if simple_ids.is_empty() {
+                return None;
+            }
+            Some(CodeMentionEvent {
+                kind: IssueCodeReferenceKind::Commit,
+                repo_full_name: repo_full_name.to_string(),
+                reference: commit["id"].as_str()?.to_string(),
+                url: commit["url"].as_str()?.to_string(),
+                title: message.lines().next().unwrap_or_default().to_string(),
+                author_login: commit["author"]["username"].as_str().map(str::to_string),
+                referenced_at: timestamp(&commit["timestamp"]).unwrap_or_else(Utc::now),
+                simple_ids,
+            })
+        })
+        .collect()
+}
+
+/// The pull request in a `pull_request` event, if its title or body mentions
+/// issues.
+pub fn from_pull_request(payload: &Value) -> Option<CodeMentionEvent> {
+    let pull_request = &payload["pull_request"];
+    let title = pull_request["title"].as_str().unwrap_or_default();
+    let body = pull_request["body"].as_str().unwrap_or_default();
+    let simple_ids = issue_references(&format!("{title}\n{body}"));
+    if simple_ids.is_empty() {
+        return None;
+    }
+    Some(CodeMentionEvent {
+        kind: IssueCodeReferenceKind::PullRequest,
+        repo_full_name: payload["repository"]["full_name"].as_str()?.to_string(),
+        reference: pull_request["number"].as_u64()?.to_string(),
+        url: pull_request["html_url"].as_str()?.to_string(),
+        title: title.to_string(),
+        author_login: pull_request["user"]["login"].as_str().map(str::to_string),
+        referenced_at: timestamp(&pull_request["created_at"]).unwrap_or_else(Utc::now),
+        simple_ids,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn reads_mentions_from_push_commits() {
+        let payload = json!({
+            "repository": { "full_name": "acme/web" },
+            "commits": [
+                {
+                    "id": "abc123",
+                    "distinct": true,
+                    "message": "Fix login redirect (APP-12)\n\nAlso touches app-3, see #40",
+                    "url": "https://github.com/acme/web/commit/abc123",
+                    "timestamp": "2026-05-01T10:00:00+02:00",
+                    "author": { "username": "octocat" }
+                },
+                {
+                    "id": "def456",
+                    "distinct": true,
+                    "message": "Bump dependencies",
+                    "url": "https://github.com/acme/web/commit/def456",
+                    "timestamp": "2026-05-01T11:00:00Z",
+                    "author": {}
+                },
+                {
+                    "id": "0a0b0c",
+                    "distinct": false,
+                    "message": "APP-12 cherry-pick",
+                    "url": "https://github.com/acme/web/commit/0a0b0c",
+                    "timestamp": "2026-05-01T12:00:00Z",
+                    "author": {}
+                }
+            ]
+        });
+
+        let mentions = from_push(&payload);
+        assert_eq!(mentions.len(), 1);
+        assert_eq!(mentions[0].reference, "abc123");
+        assert_eq!(mentions[0].title, "Fix login redirect (APP-12)");
+        assert_eq!(mentions[0].simple_ids, vec!["APP-12", "APP-3"]);
+        assert_eq!(mentions[0].author_login.as_deref(), Some("octocat"));
+        assert_eq!(
+            mentions[0].referenced_at,
+            "2026-05-01T08:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+    }
+
+    #[test]
+    fn reads_mentions_from_pull_requests() {
+        let payload = json!({
+            "repository": { "full_name": "acme/web" },
+            "pull_request": {
+                "number": 7,
+                "title": "Rework sessions",
+                "body": "Closes APP-4 and relates to OPS-9.",
+                "html_url": "https://github.com/acme/web/pull/7",
+                "created_at": "2026-05-02T09:30:00Z",
+                "user": { "login": "hubot" }
+            }
+        });
+
+        let mention = from_pull_request(&payload).unwrap();
+        assert_eq!(mention.kind, IssueCodeReferenceKind::PullRequest);
+        assert_eq!(mention.reference, "7");
+        assert_eq!(mention.simple_ids, vec!["APP-4", "OPS-9"]);
+
+        let mut unrelated = payload.clone();
+        unrelated["pull_request"]["body"] = json!("No tracked issue, fixes #12");
+        assert_eq!(from_pull_request(&unrelated), None);
+    }
+}
@@ -1,9 +1,16 @@
+mod cross_references;
 mod jwt;
 mod pr_review;
 mod service;
+mod team_sync;
 mod webhook;
 
+pub use cross_references::{CodeMentionEvent, from_pull_request, from_push};
 pub use jwt::GitHubAppJwt;
 pub use pr_review::{PrReviewError, PrReviewParams, PrReviewService};
-pub use service::{GitHubAppService, InstallationInfo, PrDetails, PrRef, Repository};
+pub use service::{
+    GitHubAppError, GitHubAppService, InstallationInfo, PrDetails, PrRef, Repository, TeamMember,
+};
+pub(crate) use team_sync::spawn_team_sync_task;
+pub use team_sync::{TeamSyncError, match_members, sync_installation, sync_project};
 pub use webhook::verify_webhook_signature;
@@ -63,6 +63,13 @@ struct RepositoriesResponse {
     repositories: Vec<Repository>,
 }
 
+/// A member of a GitHub team
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamMember {
+    pub id: i64,
+    pub login: String,
+}
+
 /// Details about a pull request
 #[derive(Debug, Clone, Deserialize)]
 pub struct PrDetails {
@@ -450,4 +457,55 @@ impl GitHubAppService {
         let pr: PrDetails = response.json().await?;
         Ok(pr)
     }
+
+    /// List the members of a team, including members of its child teams
+    /// (handles pagination for 100+ members)
+    pub async fn list_team_members(
+        &self,
+        installation_id: i64,
+        org: &str,
+        team_slug: &str,
+    ) -> Result<Vec<TeamMember>, GitHubAppError> {
+        let token = self.get_installation_token(installation_id).await?;
+        let url = format!(
+            "{}/orgs/{}/teams/{}/members",
+            GITHUB_API_BASE,
+            org,
+            urlencoding::encode(team_slug)
+        );
+
+        let mut all_members = Vec::new();
+        let mut page = 1u32;
+
+        loop {
+            let response = self
+                .client
+                .get(&url)
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Accept", "application/vnd.github+json")
+                .header("User-Agent", USER_AGENT)
+                .header("X-GitHub-Api-Version", "2022-11-28")
+                .query(&[("per_page", "100"), ("page", &page.to_string())])
+                .send()
+                .await?;
+
+            if !response.status().is_success() {
+                let status = response.status().as_u16();
+                let message = response.text().await.unwrap_or_default();
+                return Err(GitHubAppError::Api { status, message });
+            }
+
+            let members: Vec<TeamMember> = response.json().await?;
+            let count = members.len();
+            all_members.extend(members);
+
+            // If we got fewer than 100, we've reached the last page
+            if count < 100 {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(all_members)
+    }
 }
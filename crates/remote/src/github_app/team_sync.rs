@@ -0,0 +1,192 @@
+//! Project membership synced from GitHub teams.
+//!
+//! A project linked to a team is limited to those of the team's members who
+//! signed in with GitHub and belong to the project's organization, plus the
+//! organization's admins. Teams are re-synced when GitHub reports a
+//! membership change and on a schedule, in case a webhook was missed. A
+//! failed sync is recorded on the link and leaves the members as they were.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use sqlx::PgPool;
+use thiserror::Error;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::service::{GitHubAppError, GitHubAppService, TeamMember};
+use crate::db::project_github_teams::{
+    GitHubMember, ProjectGitHubTeam, ProjectGitHubTeamError, ProjectGitHubTeamRepository,
+};
+
+const SYNC_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Error)]
+pub enum TeamSyncError {
+    #[error(transparent)]
+    Database(#[from] ProjectGitHubTeamError),
+    #[error(transparent)]
+    GitHub(#[from] GitHubAppError),
+    #[error("the GitHub App is no longer installed")]
+    NotInstalled,
+    #[error("the GitHub App installation is suspended")]
+    Suspended,
+}
+
+/// Split a team's members into the organization's users with their GitHub
+/// account, and the logins of the rest.
+pub fn match_members(team: &[TeamMember], accounts: &[GitHubMember]) -> (Vec<Uuid>, Vec<String>) {
+    let users: HashMap<&str, Uuid> = accounts
+        .iter()
+        .map(|account| (account.github_user_id.as_str(), account.user_id))
+        .collect();
+    let mut user_ids = Vec::new();
+    let mut unmatched_logins = Vec::new();
+    for member in team {
+        match users.get(member.id.to_string().as_str()) {
+            Some(user_id) if !user_ids.contains(user_id) => user_ids.push(*user_id),
+            Some(_) => {}
+            None => unmatched_logins.push(member.login.clone()),
+        }
+    }
+    unmatched_logins.sort_unstable_by_key(|login| login.to_lowercase());
+    (user_ids, unmatched_logins)
+}
+
+async fn try_sync_project(
+    pool: &PgPool,
+    github_app: &GitHubAppService,
+    team: &ProjectGitHubTeam,
+) -> Result<usize, TeamSyncError> {
+    let (Some(installation_id), Some(org)) = (
+        team.github_installation_id,
+        team.github_account_login.as_deref(),
+    ) else {
+        return Err(TeamSyncError::NotInstalled);
+    };
+    if team.installation_suspended {
+        return Err(TeamSyncError::Suspended);
+    }
+
+    let members = github_app
+        .list_team_members(installation_id, org, &team.team_slug)
+        .await?;
+    let accounts = ProjectGitHubTeamRepository::github_members(pool, team.organization_id).await?;
+    let (user_ids, unmatched_logins) = match_members(&members, &accounts);
+    ProjectGitHubTeamRepository::replace_members(
+        pool,
+        team.project_id,
+        &user_ids,
+        &unmatched_logins,
+    )
+    .await?;
+
+    Ok(user_ids.len())
+}
+
+/// Sync one project's members from its team. Returns how many members it
+/// has now.
+pub async fn sync_project(
+    pool: &PgPool,
+    github_app: &GitHubAppService,
+    team: &ProjectGitHubTeam,
+) -> Result<usize, TeamSyncError> {
+    let result = try_sync_project(pool, github_app, team).await;
+    match &result {
+        Ok(members) => info!(project_id = %team.project_id, members, "Synced GitHub team"),
+        Err(TeamSyncError::Database(_)) => {}
+        Err(error) => {
+            warn!(?error, project_id = %team.project_id, "GitHub team sync failed");
+            ProjectGitHubTeamRepository::record_error(pool, team.project_id, &error.to_string())
+                .await?;
+        }
+    }
+    result
+}
+
+async fn sync_all(pool: &PgPool, github_app: &GitHubAppService, teams: &[ProjectGitHubTeam]) {
+    for team in teams {
+        if let Err(TeamSyncError::Database(error)) = sync_project(pool, github_app, team).await {
+            warn!(?error, project_id = %team.project_id, "Failed to store GitHub team sync");
+        }
+    }
+}
+
+/// Re-sync the projects linked to `team_slug` through the installation, or
+/// to any of its teams if not given.
+pub async fn sync_installation(
+    pool: &PgPool,
+    github_app: &GitHubAppService,
+    github_installation_id: i64,
+    team_slug: Option<&str>,
+) {
+    match ProjectGitHubTeamRepository::list_by_installation(pool, github_installation_id, team_slug)
+        .await
+    {
+        Ok(teams) => sync_all(pool, github_app, &teams).await,
+        Err(error) => warn!(
+            ?error,
+            github_installation_id, "Failed to load GitHub teams"
+        ),
+    }
+}
+
+/// Spawns a background task that re-syncs every linked team. Call once
+/// during server startup.
+pub(crate) fn spawn_team_sync_task(
+    pool: PgPool,
+    github_app: Arc<GitHubAppService>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(SYNC_INTERVAL);
+        // Skip the immediate first tick so the server can finish starting up.
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+            match ProjectGitHubTeamRepository::list_all(&pool).await {
+                Ok(teams) => sync_all(&pool, &github_app, &teams).await,
+                Err(error) => warn!(?error, "Failed to load GitHub teams"),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_team_members_to_users() {
+        let alice = Uuid::new_v4();
+        let bob = Uuid::new_v4();
+        let accounts = vec![
+            GitHubMember {
+                user_id: alice,
+                github_user_id: "101".to_string(),
+            },
+            GitHubMember {
+                user_id: bob,
+                github_user_id: "102".to_string(),
+            },
+        ];
+        let team = vec![
+            TeamMember {
+                id: 101,
+                login: "alice".to_string(),
+            },
+            TeamMember {
+                id: 300,
+                login: "Zed".to_string(),
+            },
+            TeamMember {
+                id: 200,
+                login: "carol".to_string(),
+            },
+        ];
+
+        let (user_ids, unmatched) = match_members(&team, &accounts);
+        assert_eq!(user_ids, vec![alice]);
+        assert_eq!(unmatched, vec!["carol", "Zed"]);
+    }
+}
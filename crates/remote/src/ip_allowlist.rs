@@ -0,0 +1,105 @@
+//! Organization IP allowlists.
+//!
+//! An organization can limit the addresses its data is reached from through
+//! the API. Middleware enforces the allowlist on every request that names
+//! the organization, or one of its projects or issues. Requests that name
+//! none of them must pass the allowlists of all the caller's organizations.
+
+use std::net::IpAddr;
+
+use api_types::OrganizationIpAllowlist;
+use ipnetwork::IpNetwork;
+use thiserror::Error;
+use uuid::Uuid;
+
+pub const MAX_RANGES: usize = 100;
+
+#[derive(Debug, Error)]
+pub enum InvalidIpAllowlist {
+    #[error("`{0}` is not an IP address or CIDR range")]
+    InvalidRange(String),
+    #[error("allowlists are limited to {MAX_RANGES} ranges")]
+    TooManyRanges,
+}
+
+/// Parse CIDR ranges or single addresses, dropping host bits and repeats.
+pub fn parse_ranges(ranges: &[String]) -> Result<Vec<IpNetwork>, InvalidIpAllowlist> {
+    let mut parsed: Vec<IpNetwork> = Vec::new();
+    for range in ranges {
+        let range = range.trim();
+        let network = range
+            .parse::<IpNetwork>()
+            .and_then(|network| IpNetwork::new(network.network(), network.prefix()))
+            .map_err(|_| InvalidIpAllowlist::InvalidRange(range.to_string()))?;
+        if !parsed.contains(&network) {
+            parsed.push(network);
+        }
+    }
+    if parsed.len() > MAX_RANGES {
+        return Err(InvalidIpAllowlist::TooManyRanges);
+    }
+    Ok(parsed)
+}
+
+/// Whether `ip` may reach an organization with these ranges. An unknown
+/// address is only allowed when the list is empty.
+pub fn is_allowed(ranges: &[IpNetwork], ip: Option<IpAddr>) -> bool {
+    if ranges.is_empty() {
+        return true;
+    }
+    let Some(ip) = ip.map(|ip| ip.to_canonical()) else {
+        return false;
+    };
+    ranges.iter().any(|range| range.contains(ip))
+}
+
+pub fn allowlist_response(organization_id: Uuid, ranges: &[IpNetwork]) -> OrganizationIpAllowlist {
+    OrganizationIpAllowlist {
+        organization_id,
+        ranges: ranges.iter().map(ToString::to_string).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn ip(value: &str) -> Option<IpAddr> {
+        Some(value.parse().unwrap())
+    }
+
+    #[test]
+    fn normalizes_ranges() {
+        let parsed = parse_ranges(&ranges(&[
+            " 203.0.113.7/24",
+            "203.0.113.0/24",
+            "198.51.100.4",
+            "2001:db8::1/32",
+        ]))
+        .unwrap();
+        let parsed: Vec<String> = parsed.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            parsed,
+            ["203.0.113.0/24", "198.51.100.4/32", "2001:db8::/32"]
+        );
+
+        assert!(parse_ranges(&ranges(&["10.0.0.0/33"])).is_err());
+        assert!(parse_ranges(&ranges(&["office"])).is_err());
+    }
+
+    #[test]
+    fn checks_addresses_against_ranges() {
+        let allowlist = parse_ranges(&ranges(&["203.0.113.0/24", "2001:db8::/32"])).unwrap();
+        assert!(is_allowed(&allowlist, ip("203.0.113.9")));
+        assert!(is_allowed(&allowlist, ip("::ffff:203.0.113.9")));
+        assert!(is_allowed(&allowlist, ip("2001:db8::5")));
+        assert!(!is_allowed(&allowlist, ip("198.51.100.1")));
+        assert!(!is_allowed(&allowlist, None));
+
+        assert!(is_allowed(&[], None));
+    }
+}
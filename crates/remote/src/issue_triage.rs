@@ -0,0 +1,409 @@
+//! Issue triage assistant.
+//!
+//! Given a new issue, asks the configured LLM to suggest tags, an assignee,
+//! a priority and likely duplicates. The model only sees the project's tags,
+//! its organization's members with their history on similar issues, and
+//! existing issues that share words with the new one, and only picks from
+//! those; anything else in its answer is dropped. Nothing is applied: the
+//! suggestions go back to the UI for the user to confirm.
+
+use std::collections::HashSet;
+
+use api_types::{
+    IssuePriority, IssueTriageRequest, IssueTriageSuggestions, SuggestedAssignee,
+    SuggestedDuplicate, SuggestedPriority, SuggestedTag,
+};
+use secrecy::ExposeSecret;
+use serde::Deserialize;
+use serde_json::json;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::{
+    config::IssueTriageConfig,
+    db::issue_triage::{TriageIssue, TriageMember, TriageTag},
+};
+
+/// Search terms taken from the issue to find similar ones.
+const MAX_TERMS: usize = 10;
+const MIN_TERM_CHARS: usize = 4;
+const MAX_DESCRIPTION_CHARS: usize = 4_000;
+
+const STOP_WORDS: &[&str] = &[
+    "about", "after", "again", "also", "when", "where", "which", "while", "with", "without",
+    "this", "that", "these", "those", "there", "their", "then", "than", "from", "into", "should",
+    "would", "could", "does", "doesn't", "have", "make", "some", "more", "only", "just", "like",
+    "what", "they", "them", "being", "been", "were", "will", "your", "other", "issue",
+];
+
+const SYSTEM_PROMPT: &str = "You triage new issues for a software team. Using only the \
+     tags, members and existing issues listed, suggest tags for the new issue, who should \
+     work on it, its priority, and which existing issues it duplicates. Prefer members who \
+     worked on similar issues, and spread work away from members with many open issues. \
+     Only name a duplicate if it describes the same problem or request. Leave out any \
+     suggestion you aren't fairly sure of. Answer with a JSON object of this shape: \
+     {\"tags\": [{\"name\": string, \"reason\": string}], \
+     \"assignee\": {\"user_id\": string, \"reason\": string} | null, \
+     \"priority\": {\"level\": \"urgent\" | \"high\" | \"medium\" | \"low\", \
+     \"reason\": string} | null, \
+     \"duplicates\": [{\"simple_id\": string, \"reason\": string}]}. \
+     Keep each reason to one short sentence.";
+
+#[derive(Debug, Error)]
+pub enum IssueTriageError {
+    #[error("issue triage is not configured")]
+    NotConfigured,
+    #[error("LLM request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("LLM returned an unusable answer: {0}")]
+    InvalidResponse(String),
+}
+
+/// What the triage is based on, loaded from the database.
+pub struct TriageContext {
+    pub tags: Vec<TriageTag>,
+    pub members: Vec<TriageMember>,
+    pub similar_issues: Vec<TriageIssue>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct LlmSuggestions {
+    #[serde(default)]
+    tags: Vec<LlmTag>,
+    #[serde(default)]
+    assignee: Option<LlmAssignee>,
+    #[serde(default)]
+    priority: Option<LlmPriority>,
+    #[serde(default)]
+    duplicates: Vec<LlmDuplicate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LlmTag {
+    name: String,
+    #[serde(default)]
+    reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LlmAssignee {
+    user_id: String,
+    #[serde(default)]
+    reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LlmPriority {
+    level: String,
+    #[serde(default)]
+    reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LlmDuplicate {
+    simple_id: String,
+    #[serde(default)]
+    reason: String,
+}
+
+fn truncate(text: &str, max: usize) -> &str {
+    match text.char_indices().nth(max) {
+        Some((index, _)) => &text[..index],
+        None => text,
+    }
+}
+
+/// Distinctive words of the issue, in order of appearance, to look for in
+/// existing issues.
+pub fn search_terms(title: &str, description: Option<&str>) -> Vec<String> {
+    let text = format!("{title} {}", description.map_or("", |d| truncate(d, 500)));
+    let mut seen = HashSet::new();
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-' || c == '\''))
+        .map(|word| {
+            word.trim_matches(|c: char| c == '-' || c == '\'')
+                .to_lowercase()
+        })
+        .filter(|word| word.chars().count() >= MIN_TERM_CHARS)
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()))
+        .filter(|word| seen.insert(word.clone()))
+        .take(MAX_TERMS)
+        .collect()
+}
+
+fn priority_name(priority: IssuePriority) -> &'static str {
+    match priority {
+        IssuePriority::Urgent => "urgent",
+        IssuePriority::High => "high",
+        IssuePriority::Medium => "medium",
+        IssuePriority::Low => "low",
+    }
+}
+
+fn parse_priority(level: &str) -> Option<IssuePriority> {
+    match level.trim().to_ascii_lowercase().as_str() {
+        "urgent" => Some(IssuePriority::Urgent),
+        "high" => Some(IssuePriority::High),
+        "medium" => Some(IssuePriority::Medium),
+        "low" => Some(IssuePriority::Low),
+        _ => None,
+    }
+}
+
+pub fn build_prompt(request: &IssueTriageRequest, context: &TriageContext) -> String {
+    let mut prompt = format!("New issue: {}\n", request.title.trim());
+    if let Some(description) = request.description.as_deref().map(str::trim)
+        && !description.is_empty()
+    {
+        prompt.push_str(&format!(
+            "\n{}\n",
+            truncate(description, MAX_DESCRIPTION_CHARS)
+        ));
+    }
+
+    prompt.push_str("\nTags:\n");
+    if context.tags.is_empty() {
+        prompt.push_str("(none)\n");
+    }
+    for tag in &context.tags {
+        prompt.push_str(&format!("- {}\n", tag.name));
+    }
+
+    prompt.push_str("\nMembers (user_id: name, open issues, similar issues assigned, ");
+    prompt.push_str("similar issues whose work they merged):\n");
+    for member in &context.members {
+        prompt.push_str(&format!(
+            "- {}: {}, {} open, {} assigned, {} merged\n",
+            member.user_id,
+            member.name,
+            member.open_assigned,
+            member.similar_assigned,
+            member.similar_merged
+        ));
+    }
+
+    prompt.push_str("\nExisting issues sharing words with the new one:\n");
+    if context.similar_issues.is_empty() {
+        prompt.push_str("(none)\n");
+    }
+    for issue in &context.similar_issues {
+        prompt.push_str(&format!(
+            "- {}{}: {}",
+            issue.simple_id,
+            if issue.completed { " (done)" } else { "" },
+            issue.title
+        ));
+        if let Some(priority) = issue.priority {
+            prompt.push_str(&format!(" [priority: {}]", priority_name(priority)));
+        }
+        if !issue.tag_names.is_empty() {
+            prompt.push_str(&format!(" [tags: {}]", issue.tag_names.join(", ")));
+        }
+        let assignees: Vec<&str> = context
+            .members
+            .iter()
+            .filter(|member| issue.assignee_ids.contains(&member.user_id))
+            .map(|member| member.name.as_str())
+            .collect();
+        if !assignees.is_empty() {
+            prompt.push_str(&format!(" [assigned: {}]", assignees.join(", ")));
+        }
+        let description = issue.description.trim();
+        if !description.is_empty() {
+            prompt.push_str(&format!("\n  {}", description.replace('\n', " ")));
+        }
+        prompt.push('\n');
+    }
+    prompt
+}
+
+/// Keep the suggestions that name tags, members and issues from `context`.
+fn validate(suggestions: LlmSuggestions, context: &TriageContext) -> IssueTriageSuggestions {
+    let mut tags: Vec<SuggestedTag> = Vec::new();
+    for suggested in suggestions.tags {
+        let Some(tag) = context
+            .tags
+            .iter()
+            .find(|tag| tag.name.eq_ignore_ascii_case(suggested.name.trim()))
+        else {
+            continue;
+        };
+        if tags.iter().all(|existing| existing.tag_id != tag.id) {
+            tags.push(SuggestedTag {
+                tag_id: tag.id,
+                name: tag.name.clone(),
+                reason: suggested.reason,
+            });
+        }
+    }
+
+    let assignee = suggestions.assignee.and_then(|suggested| {
+        let user_id = Uuid::parse_str(suggested.user_id.trim()).ok()?;
+        context
+            .members
+            .iter()
+            .any(|member| member.user_id == user_id)
+            .then_some(SuggestedAssignee {
+                user_id,
+                reason: suggested.reason,
+            })
+    });
+
+    let priority = suggestions.priority.and_then(|suggested| {
+        Some(SuggestedPriority {
+            priority: parse_priority(&suggested.level)?,
+            reason: suggested.reason,
+        })
+    });
+
+    let mut duplicates: Vec<SuggestedDuplicate> = Vec::new();
+    for suggested in suggestions.duplicates {
+        let Some(issue) = context.similar_issues.iter().find(|issue| {
+            issue
+                .simple_id
+                .eq_ignore_ascii_case(suggested.simple_id.trim())
+        }) else {
+            continue;
+        };
+        if duplicates
+            .iter()
+            .all(|existing| existing.issue_id != issue.id)
+        {
+            duplicates.push(SuggestedDuplicate {
+                issue_id: issue.id,
+                simple_id: issue.simple_id.clone(),
+                title: issue.title.clone(),
+                reason: suggested.reason,
+            });
+        }
+    }
+
+    IssueTriageSuggestions {
+        tags,
+        assignee,
+        priority,
+        duplicates,
+    }
+}
+
+/// Parse the model's answer, tolerating a Markdown code fence around it.
+fn parse_answer(content: &str) -> Result<LlmSuggestions, IssueTriageError> {
+    let content = content.trim();
+    let content = content
+        .strip_prefix("```json")
+        .or_else(|| content.strip_prefix("```"))
+        .and_then(|inner| inner.strip_suffix("```"))
+        .unwrap_or(content);
+    serde_json::from_str(content.trim())
+        .map_err(|error| IssueTriageError::InvalidResponse(error.to_string()))
+}
+
+/// Ask the LLM for suggestions.
+pub async fn suggest(
+    http_client: &reqwest::Client,
+    config: &IssueTriageConfig,
+    request: &IssueTriageRequest,
+    context: &TriageContext,
+) -> Result<IssueTriageSuggestions, IssueTriageError> {
+    let body = json!({
+        "model": config.model,
+        "temperature": 0,
+        "response_format": { "type": "json_object" },
+        "messages": [
+            { "role": "system", "content": SYSTEM_PROMPT },
+            { "role": "user", "content": build_prompt(request, context) },
+        ],
+    });
+    let mut builder = http_client.post(&config.api_url).json(&body);
+    if let Some(api_key) = &config.api_key {
+        builder = builder.bearer_auth(api_key.expose_secret());
+    }
+    let response: serde_json::Value = builder.send().await?.error_for_status()?.json().await?;
+    let content = response["choices"][0]["message"]["content"]
+        .as_str()
+        .ok_or_else(|| IssueTriageError::InvalidResponse("no message content".to_string()))?;
+    Ok(validate(parse_answer(content)?, context))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> TriageContext {
+        TriageContext {
+            tags: vec![TriageTag {
+                id: Uuid::from_u128(1),
+                name: "Bug".to_string(),
+            }],
+            members: vec![TriageMember {
+                user_id: Uuid::from_u128(2),
+                name: "Sam".to_string(),
+                open_assigned: 1,
+                similar_assigned: 2,
+                similar_merged: 1,
+            }],
+            similar_issues: vec![TriageIssue {
+                id: Uuid::from_u128(3),
+                simple_id: "VK-12".to_string(),
+                title: "Login fails with SSO".to_string(),
+                description: String::new(),
+                priority: Some(IssuePriority::High),
+                completed: false,
+                tag_names: vec!["Bug".to_string()],
+                assignee_ids: vec![Uuid::from_u128(2)],
+            }],
+        }
+    }
+
+    #[test]
+    fn search_terms_skip_short_and_common_words() {
+        assert_eq!(
+            search_terms(
+                "Login fails when SSO session expires",
+                Some("The login page")
+            ),
+            vec!["login", "fails", "session", "expires", "page"]
+        );
+    }
+
+    #[test]
+    fn drops_suggestions_outside_the_context() {
+        let answer = format!(
+            "```json\n{}\n```",
+            json!({
+                "tags": [
+                    { "name": "bug", "reason": "Broken login" },
+                    { "name": "Frontend", "reason": "Made up" },
+                ],
+                "assignee": { "user_id": Uuid::from_u128(9).to_string(), "reason": "?" },
+                "priority": { "level": "High", "reason": "Blocks sign in" },
+                "duplicates": [
+                    { "simple_id": "vk-12", "reason": "Same failure" },
+                    { "simple_id": "VK-99", "reason": "Made up" },
+                ],
+            })
+        );
+        let suggestions = validate(parse_answer(&answer).unwrap(), &context());
+
+        assert_eq!(
+            suggestions.tags,
+            vec![SuggestedTag {
+                tag_id: Uuid::from_u128(1),
+                name: "Bug".to_string(),
+                reason: "Broken login".to_string(),
+            }]
+        );
+        assert_eq!(suggestions.assignee, None);
+        assert_eq!(
+            suggestions.priority.map(|p| p.priority),
+            Some(IssuePriority::High)
+        );
+        assert_eq!(
+            suggestions
+                .duplicates
+                .iter()
+                .map(|d| d.simple_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["VK-12"]
+        );
+    }
+}
@@ -1,3 +1,4 @@
+pub mod account_data;
 mod analytics;
 mod app;
 pub mod attachments;
@@ -5,27 +6,47 @@ pub mod audit;
 mod auth;
 pub mod azure_blob;
 mod billing;
+pub mod calendar;
 pub mod config;
 pub mod db;
 pub mod digest;
+pub mod documents;
+pub mod due_date_reminders;
 pub mod github_app;
+pub mod ip_allowlist;
+pub mod issue_triage;
+pub mod localization;
 pub mod mail;
+pub mod markdown;
 mod middleware;
 pub mod mutation_definition;
 pub mod notifications;
+pub mod query_definition;
+pub mod query_subscriptions;
 pub mod r2;
+pub mod release_notes;
+pub mod retention;
 pub mod routes;
+pub mod session_anomalies;
 pub mod shape_definition;
 pub mod shape_route;
 pub mod shape_routes;
 pub mod shapes;
 mod shared_key_auth;
+pub mod sla;
 mod state;
+#[cfg(test)]
+pub(crate) mod test_support;
+pub mod timeline;
+pub mod token_encryption;
+pub mod user_profiles;
 
 use std::env;
 
 pub use app::Server;
 pub use billing::BillingService;
+#[cfg(feature = "vk-billing")]
+pub use billing::{stripe_api::StripeApiClient, usage::StripeUsageMeter};
 use opentelemetry::trace::TracerProvider as _;
 pub use state::AppState;
 use tracing_error::ErrorLayer;
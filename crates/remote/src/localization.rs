@@ -0,0 +1,415 @@
+//! Locale selection and rendering for project notification templates.
+//!
+//! A recipient's locales are tried most specific first: their own locale,
+//! then its language alone (`pt-BR`, then `pt`), then the project's default
+//! locale the same way. The first locale the project has a template for
+//! wins; without one the built-in English copy is used. See
+//! [`api_types::NotificationTemplate`] for the placeholders.
+
+use std::collections::HashMap;
+
+use api_types::{NotificationTemplate, NotificationType};
+use sqlx::PgPool;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::db::notification_templates::{
+    NotificationTemplateError, NotificationTemplateRepository,
+};
+
+/// Locale of the built-in copy.
+pub const DEFAULT_LOCALE: &str = "en";
+
+const MAX_TEMPLATE_LENGTH: usize = 500;
+
+const COMMON_PLACEHOLDERS: &[&str] = &["actor", "issue", "issue_title"];
+
+#[derive(Debug, Error)]
+#[error("invalid locale `{0}`")]
+pub struct InvalidLocale(String);
+
+#[derive(Debug, Error)]
+pub enum InvalidTemplate {
+    #[error("templates must not be empty")]
+    Empty,
+    #[error("templates are limited to {MAX_TEMPLATE_LENGTH} characters")]
+    TooLong,
+    #[error("unknown placeholder `{{{name}}}`; available: {available}")]
+    UnknownPlaceholder { name: String, available: String },
+}
+
+/// Normalize a BCP 47 tag, so `PT_br` is stored as `pt-BR`.
+pub fn normalize_locale(tag: &str) -> Result<String, InvalidLocale> {
+    let invalid = || InvalidLocale(tag.to_string());
+    let mut subtags = tag.trim().split(['-', '_']);
+    let language = subtags.next().unwrap_or_default();
+    if !(2..=3).contains(&language.len()) || !language.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(invalid());
+    }
+
+    let mut normalized = language.to_ascii_lowercase();
+    for (index, subtag) in subtags.enumerate() {
+        if index >= 3 || !subtag.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(invalid());
+        }
+        let alphabetic = subtag.chars().all(|c| c.is_ascii_alphabetic());
+        let numeric = subtag.chars().all(|c| c.is_ascii_digit());
+        let subtag = match subtag.len() {
+            // Script, e.g. `Latn`.
+            4 if alphabetic => {
+                let (first, rest) = subtag.split_at(1);
+                format!(
+                    "{}{}",
+                    first.to_ascii_uppercase(),
+                    rest.to_ascii_lowercase()
+                )
+            }
+            // Region, e.g. `BR` or `419`.
+            2 if alphabetic => subtag.to_ascii_uppercase(),
+            3 if numeric => subtag.to_string(),
+            // Variant.
+            5..=8 => subtag.to_ascii_lowercase(),
+            _ => return Err(invalid()),
+        };
+        normalized.push('-');
+        normalized.push_str(&subtag);
+    }
+
+    Ok(normalized)
+}
+
+/// Locales to look templates up in, most specific first.
+pub fn fallback_chain(user_locale: Option<&str>, project_locale: Option<&str>) -> Vec<String> {
+    let mut chain: Vec<String> = Vec::new();
+    for locale in [user_locale, project_locale].into_iter().flatten() {
+        let mut candidate = locale;
+        loop {
+            if !chain.iter().any(|existing| existing == candidate) {
+                chain.push(candidate.to_string());
+            }
+            match candidate.rsplit_once('-') {
+                Some((parent, _)) => candidate = parent,
+                None => break,
+            }
+        }
+    }
+    chain
+}
+
+/// Placeholders a template for the notification type may use.
+pub fn placeholders(notification_type: NotificationType) -> Vec<&'static str> {
+    let specific: &[&str] = match notification_type {
+        NotificationType::IssueCommentAdded => &["comment"],
+        NotificationType::IssueStatusChanged => &["old_status", "new_status"],
+        NotificationType::IssuePriorityChanged => &["old_priority", "new_priority"],
+        NotificationType::IssueCommentReaction => &["emoji"],
+        NotificationType::IssueTitleChanged => &["new_title"],
+        NotificationType::IssueEnteredFilter | NotificationType::IssueLeftFilter => &["filter"],
+        NotificationType::IssueDueSoon => &["due_date"],
+        NotificationType::IssueSlaBreached => &["sla_policy"],
+        NotificationType::IssueAssigneeChanged
+        | NotificationType::IssueUnassigned
+        | NotificationType::IssueDeleted
+        | NotificationType::IssueDescriptionChanged => &[],
+    };
+    COMMON_PLACEHOLDERS
+        .iter()
+        .chain(specific)
+        .copied()
+        .collect()
+}
+
+enum Segment<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Split a template into text and `{name}` placeholders. `{{` and `}}` are
+/// literal braces, and braces around anything but a name are kept as text.
+fn segments(template: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+    while let Some(index) = rest.find(['{', '}']) {
+        segments.push(Segment::Text(&rest[..index]));
+        let tail = &rest[index..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            segments.push(Segment::Text(&tail[..1]));
+            rest = &tail[2..];
+            continue;
+        }
+        let name = tail[1..].split_once('}').map(|(name, _)| name);
+        match name {
+            Some(name)
+                if tail.starts_with('{')
+                    && !name.is_empty()
+                    && name.chars().all(|c| c.is_ascii_lowercase() || c == '_') =>
+            {
+                segments.push(Segment::Placeholder(name));
+                rest = &tail[name.len() + 2..];
+            }
+            _ => {
+                segments.push(Segment::Text(&tail[..1]));
+                rest = &tail[1..];
+            }
+        }
+    }
+    segments.push(Segment::Text(rest));
+    segments
+}
+
+/// Check a title or body before storing it.
+pub fn validate_template(
+    notification_type: NotificationType,
+    template: &str,
+) -> Result<(), InvalidTemplate> {
+    if template.trim().is_empty() {
+        return Err(InvalidTemplate::Empty);
+    }
+    if template.chars().count() > MAX_TEMPLATE_LENGTH {
+        return Err(InvalidTemplate::TooLong);
+    }
+    let available = placeholders(notification_type);
+    let unknown = segments(template)
+        .into_iter()
+        .find_map(|segment| match segment {
+            Segment::Placeholder(name) if !available.contains(&name) => Some(name),
+            _ => None,
+        });
+    match unknown {
+        Some(name) => Err(InvalidTemplate::UnknownPlaceholder {
+            name: name.to_string(),
+            available: available
+                .iter()
+                .map(|name| format!("{{{name}}}"))
+                .collect::<Vec<_>>()
+                .join(", "),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Fill in a template. Placeholders without a value render as nothing, and
+/// the whitespace left around them is collapsed.
+pub fn render_template(template: &str, values: &[(&str, Option<String>)]) -> String {
+    let rendered: String = segments(template)
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => text,
+            Segment::Placeholder(name) => values
+                .iter()
+                .find(|(key, _)| *key == name)
+                .and_then(|(_, value)| value.as_deref())
+                .unwrap_or_default(),
+        })
+        .collect();
+    rendered.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[derive(Debug, Default)]
+struct ProjectTemplates {
+    default_locale: Option<String>,
+    templates: HashMap<(NotificationType, String), NotificationTemplate>,
+}
+
+/// The templates that apply to one recipient's notifications.
+#[derive(Debug, Default)]
+pub struct RecipientTemplates {
+    user_locale: Option<String>,
+    issue_projects: HashMap<Uuid, Uuid>,
+    projects: HashMap<Uuid, ProjectTemplates>,
+}
+
+impl RecipientTemplates {
+    pub fn new(
+        user_locale: Option<String>,
+        issue_projects: impl IntoIterator<Item = (Uuid, Uuid)>,
+        default_locales: impl IntoIterator<Item = (Uuid, String)>,
+        templates: impl IntoIterator<Item = NotificationTemplate>,
+    ) -> Self {
+        let mut projects: HashMap<Uuid, ProjectTemplates> = HashMap::new();
+        for (project_id, default_locale) in default_locales {
+            projects.entry(project_id).or_default().default_locale = Some(default_locale);
+        }
+        for template in templates {
+            projects
+                .entry(template.project_id)
+                .or_default()
+                .templates
+                .insert(
+                    (template.notification_type, template.locale.clone()),
+                    template,
+                );
+        }
+        Self {
+            user_locale,
+            issue_projects: issue_projects.into_iter().collect(),
+            projects,
+        }
+    }
+
+    /// Load the recipient's locale and the templates of the projects the
+    /// issues belong to.
+    pub async fn load(
+        pool: &PgPool,
+        user_id: Uuid,
+        issue_ids: &[Uuid],
+    ) -> Result<Self, NotificationTemplateError> {
+        let user_locale = NotificationTemplateRepository::user_locale(pool, user_id).await?;
+        let issue_projects =
+            NotificationTemplateRepository::issue_projects(pool, issue_ids).await?;
+        let mut project_ids: Vec<Uuid> = issue_projects.iter().map(|(_, id)| *id).collect();
+        project_ids.sort_unstable();
+        project_ids.dedup();
+        let default_locales =
+            NotificationTemplateRepository::default_locales(pool, &project_ids).await?;
+        let templates =
+            NotificationTemplateRepository::list_by_projects(pool, &project_ids).await?;
+
+        Ok(Self::new(
+            user_locale,
+            issue_projects,
+            default_locales,
+            templates,
+        ))
+    }
+
+    fn project(&self, issue_id: Option<Uuid>) -> Option<&ProjectTemplates> {
+        issue_id
+            .and_then(|issue_id| self.issue_projects.get(&issue_id))
+            .and_then(|project_id| self.projects.get(project_id))
+    }
+
+    /// The template for a notification about the issue, if its project has
+    /// one along the recipient's fallback chain.
+    pub fn resolve(
+        &self,
+        issue_id: Option<Uuid>,
+        notification_type: NotificationType,
+    ) -> Option<&NotificationTemplate> {
+        let project = self.project(issue_id)?;
+        fallback_chain(
+            self.user_locale.as_deref(),
+            project.default_locale.as_deref(),
+        )
+        .into_iter()
+        .find_map(|locale| project.templates.get(&(notification_type, locale)))
+    }
+
+    /// The locale notifications about the issue are written in: the first
+    /// locale along the fallback chain the project has any template for.
+    pub fn locale(&self, issue_id: Option<Uuid>) -> &str {
+        let Some(project) = self.project(issue_id) else {
+            return DEFAULT_LOCALE;
+        };
+        fallback_chain(
+            self.user_locale.as_deref(),
+            project.default_locale.as_deref(),
+        )
+        .into_iter()
+        .find_map(|locale| {
+            project
+                .templates
+                .keys()
+                .find(|(_, template_locale)| *template_locale == locale)
+                .map(|(_, template_locale)| template_locale.as_str())
+        })
+        .unwrap_or(DEFAULT_LOCALE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    #[test]
+    fn normalizes_locales() {
+        assert_eq!(normalize_locale("PT_br").unwrap(), "pt-BR");
+        assert_eq!(normalize_locale(" de ").unwrap(), "de");
+        assert_eq!(normalize_locale("zh-hant-tw").unwrap(), "zh-Hant-TW");
+        assert_eq!(normalize_locale("es-419").unwrap(), "es-419");
+        for tag in ["", "e", "english", "de-", "de-B", "pt-BR-x-y-z", "fr-1234"] {
+            assert!(normalize_locale(tag).is_err(), "{tag:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn falls_back_from_region_to_language_to_project() {
+        assert_eq!(
+            fallback_chain(Some("pt-BR"), Some("de")),
+            vec!["pt-BR", "pt", "de"]
+        );
+        assert_eq!(fallback_chain(None, Some("de-AT")), vec!["de-AT", "de"]);
+        assert_eq!(
+            fallback_chain(Some("de-CH"), Some("de-AT")),
+            vec!["de-CH", "de", "de-AT"]
+        );
+        assert!(fallback_chain(None, None).is_empty());
+    }
+
+    #[test]
+    fn validates_and_renders_placeholders() {
+        let status = NotificationType::IssueStatusChanged;
+        assert!(validate_template(status, "{actor} hat {issue} auf {new_status} gesetzt").is_ok());
+        assert!(validate_template(status, "{{literal}} and {not a placeholder}").is_ok());
+        assert!(matches!(
+            validate_template(status, "{actor} reagierte mit {emoji}"),
+            Err(InvalidTemplate::UnknownPlaceholder { .. })
+        ));
+        assert!(matches!(
+            validate_template(status, "  "),
+            Err(InvalidTemplate::Empty)
+        ));
+
+        let values = [
+            ("actor", Some("Ana".to_string())),
+            ("issue", Some("APP-12".to_string())),
+            ("old_status", None),
+        ];
+        assert_eq!(
+            render_template("{actor} moveu {issue} de {old_status} {{ok}}", &values),
+            "Ana moveu APP-12 de {ok}"
+        );
+    }
+
+    #[test]
+    fn resolves_templates_along_the_chain() {
+        let project_id = Uuid::new_v4();
+        let issue_id = Uuid::new_v4();
+        let template = |locale: &str| NotificationTemplate {
+            id: Uuid::new_v4(),
+            project_id,
+            notification_type: NotificationType::IssueDeleted,
+            locale: locale.to_string(),
+            title: format!("{locale} title"),
+            body: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let templates = |user_locale: Option<&str>| {
+            RecipientTemplates::new(
+                user_locale.map(str::to_string),
+                [(issue_id, project_id)],
+                [(project_id, "de".to_string())],
+                [template("pt"), template("de")],
+            )
+        };
+
+        let resolve = |templates: &RecipientTemplates| {
+            templates
+                .resolve(Some(issue_id), NotificationType::IssueDeleted)
+                .map(|template| template.locale.clone())
+        };
+        assert_eq!(resolve(&templates(Some("pt-BR"))).as_deref(), Some("pt"));
+        assert_eq!(resolve(&templates(Some("fr"))).as_deref(), Some("de"));
+        assert_eq!(resolve(&templates(None)).as_deref(), Some("de"));
+        assert_eq!(templates(Some("pt-BR")).locale(Some(issue_id)), "pt");
+        assert!(
+            templates(None)
+                .resolve(Some(issue_id), NotificationType::IssueCommentAdded)
+                .is_none()
+        );
+        assert_eq!(templates(Some("pt")).locale(None), DEFAULT_LOCALE);
+    }
+}
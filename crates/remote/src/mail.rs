@@ -25,6 +25,8 @@ pub struct DigestContact<'a> {
     pub user_id: &'a str,
     pub first_name: Option<&'a str>,
     pub last_name: Option<&'a str>,
+    /// Locale the digest items are written in, for the email's own copy.
+    pub locale: &'a str,
 }
 
 #[derive(Debug, Clone)]
@@ -56,6 +58,17 @@ pub trait Mailer: Send + Sync {
         items: &[DigestNotificationItem],
         notifications_url: &str,
     ) -> Result<(), DigestError>;
+
+    /// Tell an admin that a member's session was flagged for unusual use.
+    async fn send_session_flagged(
+        &self,
+        email: &str,
+        org_name: &str,
+        member_email: &str,
+        description: &str,
+        suspended: bool,
+        review_url: &str,
+    );
 }
 
 /// No-op mailer used when `LOOPS_EMAIL_API_KEY` is not configured.
@@ -109,6 +122,23 @@ impl Mailer for NoopMailer {
 
         Ok(())
     }
+
+    async fn send_session_flagged(
+        &self,
+        email: &str,
+        org_name: &str,
+        member_email: &str,
+        _description: &str,
+        _suspended: bool,
+        _review_url: &str,
+    ) {
+        tracing::warn!(
+            email = %email,
+            org_name = %org_name,
+            member_email = %member_email,
+            "Email service not configured — skipping session flagged email. Set LOOPS_EMAIL_API_KEY to enable."
+        );
+    }
 }
 
 pub struct LoopsMailer {
@@ -314,6 +344,7 @@ impl Mailer for LoopsMailer {
         let mut event_properties = serde_json::Map::new();
         event_properties.insert("notificationCount".into(), json!(notification_count));
         event_properties.insert("notificationsUrl".into(), json!(notifications_url));
+        event_properties.insert("locale".into(), json!(contact.locale));
 
         for (i, item) in items.iter().take(DIGEST_PREVIEW_COUNT).enumerate() {
             event_properties.insert(format!("notification{i}Title"), json!(item.title));
@@ -356,4 +387,59 @@ impl Mailer for LoopsMailer {
             Err(err) => Err(DigestError::LoopsRequest(err)),
         }
     }
+
+    async fn send_session_flagged(
+        &self,
+        email: &str,
+        org_name: &str,
+        member_email: &str,
+        description: &str,
+        suspended: bool,
+        review_url: &str,
+    ) {
+        if cfg!(debug_assertions) {
+            tracing::info!(
+                "Firing sessionFlagged event for {email}\n\
+                 Organization: {org_name}\n\
+                 Member: {member_email}\n\
+                 Anomaly: {description}\n\
+                 Suspended: {suspended}\n\
+                 Review URL: {review_url}"
+            );
+        }
+
+        let payload = json!({
+            "email": email,
+            "eventName": "sessionFlagged",
+            "eventProperties": {
+                "orgName": org_name,
+                "memberEmail": member_email,
+                "anomaly": description,
+                "suspended": suspended,
+                "reviewUrl": review_url,
+            }
+        });
+
+        let res = self
+            .client
+            .post("https://app.loops.so/api/v1/events/send")
+            .bearer_auth(&self.api_key)
+            .json(&payload)
+            .send()
+            .await;
+
+        match res {
+            Ok(resp) if resp.status().is_success() => {
+                tracing::debug!("Session flagged event fired via Loops for {email}");
+            }
+            Ok(resp) => {
+                let status = resp.status();
+                let body = resp.text().await.unwrap_or_default();
+                tracing::warn!(status = %status, body = %body, "Loops send failed for session flagged");
+            }
+            Err(err) => {
+                tracing::error!(error = ?err, "Loops request error for session flagged");
+            }
+        }
+    }
 }
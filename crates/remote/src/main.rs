@@ -32,7 +32,14 @@ async fn main() -> anyhow::Result<()> {
                     billing_config.stripe_webhook_secret,
                     Some(billing_config.free_seat_limit),
                 ));
-                BillingService::new(Some(provider))
+                let mut billing = BillingService::new(Some(provider));
+                if let Some(stripe) = remote::StripeApiClient::from_env() {
+                    if let Some(meter) = remote::StripeUsageMeter::from_env(stripe.clone()) {
+                        billing = billing.with_usage_meter(meter);
+                    }
+                    billing = billing.with_stripe_api(stripe);
+                }
+                billing
             }
             None => BillingService::new(None),
         }
@@ -0,0 +1,514 @@
+//! Server-side markdown rendering, so every client displays issue text the
+//! same way.
+//!
+//! Rendering is strict: raw HTML in the source is escaped rather than passed
+//! through, and links and images are limited to safe URL schemes. Fenced
+//! code blocks in a known language are highlighted with `hl-`-prefixed CSS
+//! classes. `#123` references and `@username` mentions that resolve are
+//! linkified. Output is cached under a hash of the source and what its
+//! references resolved to, so a cached render is never stale.
+
+use std::{collections::HashMap, sync::LazyLock, time::Duration};
+
+use api_types::RenderedMarkdown;
+use pulldown_cmark::{CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd, html};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use syntect::{
+    html::{ClassStyle, ClassedHTMLGenerator},
+    parsing::SyntaxSet,
+    util::LinesWithEndings,
+};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::db::markdown::{IssueReference, MarkdownError, MarkdownRepository, MentionedUser};
+
+/// Bump when the rendered output changes, so cached renders are not reused.
+const RENDERER_VERSION: u32 = 1;
+const CACHE_UNUSED_DAYS: i32 = 30;
+const CACHE_CLEANUP_INTERVAL: Duration = Duration::from_secs(24 * 3600);
+const HIGHLIGHT_CLASS_PREFIX: &str = "hl-";
+
+static SYNTAXES: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Reference<'a> {
+    Issue(i32),
+    User(&'a str),
+}
+
+/// What a source's references resolved to.
+#[derive(Debug, Default)]
+pub struct ResolvedReferences {
+    issues: HashMap<i32, IssueReference>,
+    /// Keyed by lowercased username.
+    users: HashMap<String, MentionedUser>,
+}
+
+impl ResolvedReferences {
+    pub fn new(issues: Vec<IssueReference>, users: Vec<MentionedUser>) -> Self {
+        Self {
+            issues: issues
+                .into_iter()
+                .map(|issue| (issue.issue_number, issue))
+                .collect(),
+            users: users
+                .into_iter()
+                .map(|user| (user.username.to_lowercase(), user))
+                .collect(),
+        }
+    }
+
+    /// Stable description of the resolution, for the cache key.
+    fn fingerprint(&self) -> String {
+        let mut entries: Vec<String> = self
+            .issues
+            .values()
+            .map(|issue| format!("#{}={}", issue.issue_number, issue.id))
+            .chain(
+                self.users
+                    .iter()
+                    .map(|(username, user)| format!("@{username}={}", user.id)),
+            )
+            .collect();
+        entries.sort();
+        entries.join("\n")
+    }
+}
+
+fn is_reference_boundary(c: char) -> bool {
+    !(c.is_alphanumeric() || c == '_')
+}
+
+fn is_username_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')
+}
+
+/// `#123` and `@username` references in plain text, with their byte ranges.
+/// A reference must not directly follow a word character, so emails and
+/// anchors like `page#12` are skipped.
+fn scan_references(text: &str) -> Vec<(usize, usize, Reference<'_>)> {
+    let mut references = Vec::new();
+    let mut previous = None;
+    for (start, c) in text.char_indices() {
+        let at_boundary = previous.is_none_or(is_reference_boundary);
+        previous = Some(c);
+        if !at_boundary || !matches!(c, '#' | '@') {
+            continue;
+        }
+        let rest = &text[start + 1..];
+        let reference = if c == '#' {
+            let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            let followed_by_word = rest[digits..]
+                .chars()
+                .next()
+                .is_some_and(|c| !is_reference_boundary(c));
+            if digits == 0 || followed_by_word {
+                continue;
+            }
+            rest[..digits]
+                .parse()
+                .ok()
+                .map(|number| (digits, Reference::Issue(number)))
+        } else {
+            let length = rest.len() - rest.trim_start_matches(is_username_char).len();
+            let username = rest[..length].trim_end_matches('.');
+            (!username.is_empty()).then_some((username.len(), Reference::User(username)))
+        };
+        if let Some((length, reference)) = reference {
+            references.push((start, start + 1 + length, reference));
+        }
+    }
+    references
+}
+
+fn parser(markdown: &str) -> Parser<'_> {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+    Parser::new_ext(markdown, options)
+}
+
+/// Issue numbers and lowercased usernames referenced outside code and links.
+pub fn collect_references(markdown: &str) -> (Vec<i32>, Vec<String>) {
+    let mut issue_numbers = Vec::new();
+    let mut usernames = Vec::new();
+    let mut depth = 0usize;
+    for event in parser(markdown) {
+        match event {
+            Event::Start(Tag::CodeBlock(_) | Tag::Link { .. } | Tag::Image { .. }) => depth += 1,
+            Event::End(TagEnd::CodeBlock | TagEnd::Link | TagEnd::Image) => {
+                depth = depth.saturating_sub(1)
+            }
+            Event::Text(text) if depth == 0 => {
+                for (_, _, reference) in scan_references(&text) {
+                    match reference {
+                        Reference::Issue(number) => issue_numbers.push(number),
+                        Reference::User(username) => usernames.push(username.to_lowercase()),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    issue_numbers.sort_unstable();
+    issue_numbers.dedup();
+    usernames.sort();
+    usernames.dedup();
+    (issue_numbers, usernames)
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Relative URLs and an allowlist of schemes. Anything else, including
+/// `javascript:` and `data:`, is dropped.
+fn is_safe_url(url: &str, image: bool) -> bool {
+    let Some((scheme, _)) = url.trim().split_once(':') else {
+        return true;
+    };
+    if scheme.contains(['/', '?', '#']) {
+        return true;
+    }
+    match scheme.to_ascii_lowercase().as_str() {
+        "http" | "https" => true,
+        "mailto" => !image,
+        "attachment" => image,
+        _ => false,
+    }
+}
+
+fn highlight(code: &str, language: &str) -> String {
+    let language = language.split_whitespace().next().unwrap_or_default();
+    let highlighted = SYNTAXES
+        .find_syntax_by_token(language)
+        .filter(|_| !language.is_empty())
+        .and_then(|syntax| {
+            let mut generator = ClassedHTMLGenerator::new_with_class_style(
+                syntax,
+                &SYNTAXES,
+                ClassStyle::SpacedPrefixed {
+                    prefix: HIGHLIGHT_CLASS_PREFIX,
+                },
+            );
+            for line in LinesWithEndings::from(code) {
+                generator
+                    .parse_html_for_line_which_includes_newline(line)
+                    .ok()?;
+            }
+            Some(generator.finalize())
+        });
+    match highlighted {
+        Some(html) => format!(
+            "<pre><code class=\"language-{}\">{html}</code></pre>\n",
+            escape_html(language)
+        ),
+        None => format!("<pre><code>{}</code></pre>\n", escape_html(code)),
+    }
+}
+
+fn linkify(
+    text: &str,
+    project_id: Uuid,
+    resolved: &ResolvedReferences,
+    events: &mut Vec<Event<'static>>,
+) {
+    let mut html = String::new();
+    let mut last = 0;
+    for (start, end, reference) in scan_references(text) {
+        let link = match reference {
+            Reference::Issue(number) => resolved.issues.get(&number).map(|issue| {
+                format!(
+                    "<a class=\"issue-reference\" href=\"/projects/{project_id}/issues/{}\" \
+                     data-issue-id=\"{}\" title=\"{}\">{}</a>",
+                    issue.id,
+                    issue.id,
+                    escape_html(&format!("{}: {}", issue.simple_id, issue.title)),
+                    escape_html(&text[start..end]),
+                )
+            }),
+            Reference::User(username) => resolved.users.get(&username.to_lowercase()).map(|user| {
+                format!(
+                    "<span class=\"mention\" data-user-id=\"{}\">{}</span>",
+                    user.id,
+                    escape_html(&text[start..end]),
+                )
+            }),
+        };
+        if let Some(link) = link {
+            html.push_str(&escape_html(&text[last..start]));
+            html.push_str(&link);
+            last = end;
+        }
+    }
+    if last == 0 {
+        events.push(Event::Text(CowStr::from(text.to_string())));
+        return;
+    }
+    html.push_str(&escape_html(&text[last..]));
+    events.push(Event::InlineHtml(CowStr::from(html)));
+}
+
+/// Render `markdown` to sanitized HTML, linking the references in
+/// `resolved`.
+pub fn render(markdown: &str, project_id: Uuid, resolved: &ResolvedReferences) -> String {
+    let mut events: Vec<Event<'static>> = Vec::new();
+    let mut code_block: Option<(String, String)> = None;
+    // Link and image text is left alone: nested links are invalid, and
+    // image alt text is written out as plain text.
+    let mut link_depth = 0usize;
+
+    for event in parser(markdown) {
+        if let Some((language, code)) = &mut code_block {
+            match event {
+                Event::End(TagEnd::CodeBlock) => {
+                    events.push(Event::Html(CowStr::from(highlight(code, language))));
+                    code_block = None;
+                }
+                Event::Text(text) => code.push_str(&text),
+                _ => {}
+            }
+            continue;
+        }
+
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(language) => language.to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                code_block = Some((language, String::new()));
+            }
+            Event::Html(raw) | Event::InlineHtml(raw) => {
+                events.push(Event::Text(CowStr::from(raw.to_string())));
+            }
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => {
+                link_depth += 1;
+                let dest_url = if is_safe_url(&dest_url, false) {
+                    dest_url
+                } else {
+                    CowStr::from("")
+                };
+                events.push(
+                    Event::Start(Tag::Link {
+                        link_type,
+                        dest_url,
+                        title,
+                        id,
+                    })
+                    .into_static(),
+                );
+            }
+            Event::End(end @ (TagEnd::Link | TagEnd::Image)) => {
+                link_depth = link_depth.saturating_sub(1);
+                events.push(Event::End(end));
+            }
+            Event::Start(Tag::Image {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => {
+                link_depth += 1;
+                let dest_url = if is_safe_url(&dest_url, true) {
+                    dest_url
+                } else {
+                    CowStr::from("")
+                };
+                events.push(
+                    Event::Start(Tag::Image {
+                        link_type,
+                        dest_url,
+                        title,
+                        id,
+                    })
+                    .into_static(),
+                );
+            }
+            Event::Text(text) if link_depth == 0 => {
+                linkify(&text, project_id, resolved, &mut events);
+            }
+            event => events.push(event.into_static()),
+        }
+    }
+
+    let mut output = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut output, events.into_iter());
+    output
+}
+
+fn cache_key(project_id: Uuid, markdown: &str, resolved: &ResolvedReferences) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(RENDERER_VERSION.to_be_bytes());
+    hasher.update(project_id.as_bytes());
+    hasher.update(resolved.fingerprint());
+    hasher.update(b"\n");
+    hasher.update(markdown);
+    hex::encode(hasher.finalize())
+}
+
+/// Resolve the references in `markdown` and render it, reusing a cached
+/// render when the same source resolved the same way before.
+pub async fn render_cached(
+    pool: &PgPool,
+    organization_id: Uuid,
+    project_id: Uuid,
+    markdown: &str,
+) -> Result<RenderedMarkdown, MarkdownError> {
+    let (issue_numbers, usernames) = collect_references(markdown);
+    let issues = if issue_numbers.is_empty() {
+        vec![]
+    } else {
+        MarkdownRepository::find_issues(pool, project_id, &issue_numbers).await?
+    };
+    let users = if usernames.is_empty() {
+        vec![]
+    } else {
+        MarkdownRepository::find_members(pool, organization_id, &usernames).await?
+    };
+    let mut issue_ids: Vec<Uuid> = issues.iter().map(|issue| issue.id).collect();
+    let mut mentioned_user_ids: Vec<Uuid> = users.iter().map(|user| user.id).collect();
+    issue_ids.sort();
+    mentioned_user_ids.sort();
+    let resolved = ResolvedReferences::new(issues, users);
+
+    let key = cache_key(project_id, markdown, &resolved);
+    let html = match MarkdownRepository::find_cached(pool, &key).await? {
+        Some(html) => html,
+        None => {
+            let html = render(markdown, project_id, &resolved);
+            MarkdownRepository::store(pool, &key, &html).await?;
+            html
+        }
+    };
+
+    Ok(RenderedMarkdown {
+        html,
+        issue_ids,
+        mentioned_user_ids,
+    })
+}
+
+/// Spawns a background task that deletes cached renders nobody has requested
+/// in a while. Call once during server startup.
+pub(crate) fn spawn_cache_cleanup_task(pool: PgPool) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(CACHE_CLEANUP_INTERVAL);
+        // Skip the immediate first tick so the server can finish starting up.
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+            match MarkdownRepository::delete_unused(&pool, CACHE_UNUSED_DAYS).await {
+                Ok(count) => info!(deleted = count, "Rendered markdown cache cleanup complete"),
+                Err(error) => warn!(?error, "Rendered markdown cache cleanup failed"),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolved(project_issue: Uuid, user: Uuid) -> ResolvedReferences {
+        ResolvedReferences::new(
+            vec![IssueReference {
+                id: project_issue,
+                issue_number: 12,
+                simple_id: "APP-12".to_string(),
+                title: "Fix <login>".to_string(),
+            }],
+            vec![MentionedUser {
+                id: user,
+                username: "Alice".to_string(),
+            }],
+        )
+    }
+
+    #[test]
+    fn collects_references_outside_code_and_links() {
+        let markdown = "Fixes #12 and #7, cc @Alice @bob. and mail@example.com\n\n\
+                        `#3` [#4](https://example.com) page#5 #6a\n\n```\n@carol #8\n```";
+        assert_eq!(
+            collect_references(markdown),
+            (vec![7, 12], vec!["alice".to_string(), "bob".to_string()])
+        );
+    }
+
+    #[test]
+    fn escapes_raw_html_and_unsafe_urls() {
+        let html = render(
+            "<script>alert(1)</script>\n\n<b onclick=\"x()\">hi</b> \
+             [a](javascript:alert(1)) [b](/projects/x) ![c](data:text/html;base64,AA)",
+            Uuid::nil(),
+            &ResolvedReferences::default(),
+        );
+        assert!(!html.contains("<script"), "{html}");
+        assert!(!html.contains("<b "), "{html}");
+        assert!(html.contains("&lt;script&gt;"), "{html}");
+        assert!(!html.contains("javascript:"), "{html}");
+        assert!(!html.contains("data:"), "{html}");
+        assert!(html.contains("href=\"/projects/x\""), "{html}");
+    }
+
+    #[test]
+    fn links_resolved_references_only() {
+        let (issue, user) = (Uuid::new_v4(), Uuid::new_v4());
+        let project = Uuid::new_v4();
+        let html = render(
+            "See #12, #13 and @alice <@eve>",
+            project,
+            &resolved(issue, user),
+        );
+        assert!(
+            html.contains(&format!("href=\"/projects/{project}/issues/{issue}\"")),
+            "{html}"
+        );
+        assert!(
+            html.contains("title=\"APP-12: Fix &lt;login&gt;\""),
+            "{html}"
+        );
+        assert!(html.contains(", #13 and "), "{html}");
+        assert!(
+            html.contains(&format!("data-user-id=\"{user}\">@alice</span>")),
+            "{html}"
+        );
+        assert!(html.contains("&lt;@eve&gt;"), "{html}");
+    }
+
+    #[test]
+    fn highlights_known_languages() {
+        let html = render(
+            "```rust\nfn main() {}\n```\n\n```nope\n<x>\n```",
+            Uuid::nil(),
+            &ResolvedReferences::default(),
+        );
+        assert!(html.contains("<code class=\"language-rust\">"), "{html}");
+        assert!(html.contains("class=\"hl-"), "{html}");
+        assert!(
+            html.contains("<pre><code>&lt;x&gt;\n</code></pre>"),
+            "{html}"
+        );
+    }
+}
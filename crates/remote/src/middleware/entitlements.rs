@@ -0,0 +1,272 @@
+//! Plan entitlements, enforced on the routes that create limited resources.
+
+use std::collections::HashMap;
+
+use api_types::{BillingPlan, PlanEntitlement, PlanEntitlements, UpgradeRequiredError};
+use axum::{
+    body::{Body, to_bytes},
+    extract::{MatchedPath, Path, State},
+    http::{Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    AppState,
+    auth::RequestContext,
+    db::{organization_members, plans::PlanRepository},
+    routes::error::ErrorResponse,
+};
+
+pub(crate) const FREE_PLAN_MAX_PROJECTS: i32 = 3;
+pub(crate) const FREE_PLAN_MAX_MEMBERS: i32 = 5;
+
+/// Same limit as the `Json` extractor the handlers use.
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Where a gated route names its organization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OrganizationSource {
+    /// The `org_id` path parameter.
+    Path,
+    /// The `organization_id` field of the JSON body.
+    Body,
+}
+
+/// Routes that consume an entitlement, by method and matched path below
+/// `/v1`.
+const GATED_ROUTES: &[(Method, &str, PlanEntitlement, OrganizationSource)] = &[
+    (
+        Method::POST,
+        "/projects",
+        PlanEntitlement::MaxProjects,
+        OrganizationSource::Body,
+    ),
+    (
+        Method::POST,
+        "/organizations/{org_id}/invitations",
+        PlanEntitlement::MaxMembers,
+        OrganizationSource::Path,
+    ),
+    (
+        Method::GET,
+        "/organizations/{org_id}/github-app/install-url",
+        PlanEntitlement::Webhooks,
+        OrganizationSource::Path,
+    ),
+];
+
+fn gate_for(method: &Method, path: &str) -> Option<(PlanEntitlement, OrganizationSource)> {
+    let path = path.strip_prefix("/v1").unwrap_or(path);
+    GATED_ROUTES
+        .iter()
+        .find(|(gated_method, gated_path, ..)| gated_method == method && *gated_path == path)
+        .map(|(_, _, entitlement, source)| (*entitlement, *source))
+}
+
+pub(crate) fn entitlements_for(plan: BillingPlan) -> PlanEntitlements {
+    match plan {
+        BillingPlan::Free => PlanEntitlements {
+            plan,
+            max_projects: Some(FREE_PLAN_MAX_PROJECTS),
+            max_members: Some(FREE_PLAN_MAX_MEMBERS),
+            webhooks: false,
+        },
+        BillingPlan::Paid | BillingPlan::Unlimited => PlanEntitlements {
+            plan,
+            max_projects: None,
+            max_members: None,
+            webhooks: true,
+        },
+    }
+}
+
+/// Entitlements of the organization's current plan. Nothing is limited when
+/// billing isn't configured on this server.
+pub(crate) async fn organization_entitlements(
+    state: &AppState,
+    organization_id: Uuid,
+) -> Result<PlanEntitlements, sqlx::Error> {
+    let plan = if !state.billing().is_configured() {
+        BillingPlan::Unlimited
+    } else if PlanRepository::has_active_subscription(state.pool(), organization_id).await? {
+        BillingPlan::Paid
+    } else {
+        BillingPlan::Free
+    };
+    Ok(entitlements_for(plan))
+}
+
+/// Rejects the request with a 402 when the organization's plan doesn't
+/// allow one more of `entitlement`.
+async fn check_entitlement(
+    state: &AppState,
+    organization_id: Uuid,
+    entitlement: PlanEntitlement,
+) -> Result<(), ErrorResponse> {
+    let internal_error = |error: sqlx::Error| {
+        tracing::error!(?error, %organization_id, "failed to check plan entitlements");
+        ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+    };
+
+    let entitlements = organization_entitlements(state, organization_id)
+        .await
+        .map_err(internal_error)?;
+
+    let (limit, current, message) = match entitlement {
+        PlanEntitlement::MaxProjects => {
+            let Some(limit) = entitlements.max_projects else {
+                return Ok(());
+            };
+            let current = PlanRepository::count_projects(state.pool(), organization_id)
+                .await
+                .map_err(internal_error)?;
+            (
+                Some(limit),
+                Some(current),
+                format!("Your plan allows up to {limit} projects"),
+            )
+        }
+        PlanEntitlement::MaxMembers => {
+            let Some(limit) = entitlements.max_members else {
+                return Ok(());
+            };
+            let current = PlanRepository::count_seats(state.pool(), organization_id)
+                .await
+                .map_err(internal_error)?;
+            (
+                Some(limit),
+                Some(current),
+                format!("Your plan allows up to {limit} members"),
+            )
+        }
+        PlanEntitlement::Webhooks => {
+            if entitlements.webhooks {
+                return Ok(());
+            }
+            (None, None, "Your plan doesn't include webhooks".to_string())
+        }
+    };
+
+    if let (Some(limit), Some(current)) = (limit, current)
+        && current < limit as i64
+    {
+        return Ok(());
+    }
+
+    let upgrade = UpgradeRequiredError {
+        entitlement,
+        plan: entitlements.plan,
+        limit,
+        current: current.map(|current| current.min(i32::MAX as i64) as i32),
+    };
+    Err(ErrorResponse::upgrade_required(
+        message,
+        serde_json::to_value(upgrade).unwrap_or_default(),
+    ))
+}
+
+#[derive(Deserialize)]
+struct OrganizationRef {
+    organization_id: Uuid,
+}
+
+/// Checks the entitlement of gated routes before their handler runs. Requests
+/// it can't attribute to an organization the user belongs to are passed
+/// through for the handler to reject.
+pub(crate) async fn require_plan_entitlements(
+    State(state): State<AppState>,
+    matched_path: Option<MatchedPath>,
+    path_params: Option<Path<HashMap<String, String>>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some((entitlement, source)) = matched_path
+        .as_ref()
+        .and_then(|matched| gate_for(request.method(), matched.as_str()))
+    else {
+        return next.run(request).await;
+    };
+    let Some(user_id) = request
+        .extensions()
+        .get::<RequestContext>()
+        .map(|ctx| ctx.user.id)
+    else {
+        return next.run(request).await;
+    };
+
+    let (request, organization_id) = match source {
+        OrganizationSource::Path => {
+            let organization_id =
+                path_params.and_then(|Path(params)| params.get("org_id")?.parse::<Uuid>().ok());
+            (request, organization_id)
+        }
+        OrganizationSource::Body => {
+            let (parts, body) = request.into_parts();
+            let bytes = match to_bytes(body, MAX_BODY_BYTES).await {
+                Ok(bytes) => bytes,
+                Err(_) => {
+                    return ErrorResponse::new(StatusCode::PAYLOAD_TOO_LARGE, "request too large")
+                        .into_response();
+                }
+            };
+            let organization_id = serde_json::from_slice::<OrganizationRef>(&bytes)
+                .ok()
+                .map(|body| body.organization_id);
+            (
+                Request::from_parts(parts, Body::from(bytes)),
+                organization_id,
+            )
+        }
+    };
+
+    let Some(organization_id) = organization_id else {
+        return next.run(request).await;
+    };
+    if organization_members::assert_membership(state.pool(), organization_id, user_id)
+        .await
+        .is_err()
+    {
+        return next.run(request).await;
+    }
+
+    match check_entitlement(&state, organization_id, entitlement).await {
+        Ok(()) => next.run(request).await,
+        Err(error) => error.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_gated_routes_below_v1() {
+        assert_eq!(
+            gate_for(&Method::POST, "/v1/projects"),
+            Some((PlanEntitlement::MaxProjects, OrganizationSource::Body))
+        );
+        assert_eq!(
+            gate_for(&Method::POST, "/v1/organizations/{org_id}/invitations"),
+            Some((PlanEntitlement::MaxMembers, OrganizationSource::Path))
+        );
+        assert_eq!(gate_for(&Method::GET, "/v1/projects"), None);
+        assert_eq!(gate_for(&Method::PATCH, "/v1/projects/{id}"), None);
+    }
+
+    #[test]
+    fn only_free_plan_is_limited() {
+        let free = entitlements_for(BillingPlan::Free);
+        assert_eq!(free.max_projects, Some(FREE_PLAN_MAX_PROJECTS));
+        assert!(!free.webhooks);
+
+        for plan in [BillingPlan::Paid, BillingPlan::Unlimited] {
+            let entitlements = entitlements_for(plan);
+            assert_eq!(entitlements.max_projects, None);
+            assert_eq!(entitlements.max_members, None);
+            assert!(entitlements.webhooks);
+        }
+    }
+}
@@ -0,0 +1,261 @@
+//! `Idempotency-Key` support for POST routes.
+//!
+//! The first request sent with a key is handled normally and its response
+//! stored. Retries with the same key and request get the stored response
+//! back, marked with `Idempotent-Replayed: true`, instead of creating a
+//! second issue or comment. Reusing a key for a different request is an
+//! error. Keys are scoped to the user and expire after a day.
+
+use std::time::Duration;
+
+use axum::{
+    body::{Body, Bytes, HttpBody, to_bytes},
+    extract::State,
+    http::{HeaderValue, Method, Request, StatusCode, Uri, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    AppState, auth::RequestContext, db::idempotency_keys::IdempotencyKeyRepository,
+    routes::error::ErrorResponse,
+};
+
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+const REPLAYED_HEADER: &str = "idempotent-replayed";
+const MAX_KEY_LEN: usize = 255;
+const MAX_REQUEST_BYTES: usize = 2 * 1024 * 1024;
+/// Larger responses, like exports, are passed through without being stored.
+const MAX_STORED_RESPONSE_BYTES: u64 = 256 * 1024;
+const KEY_TTL_HOURS: i32 = 24;
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(3600);
+
+pub(crate) async fn replay_idempotent_requests(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if request.method() != Method::POST {
+        return next.run(request).await;
+    }
+    let Some(key) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return next.run(request).await;
+    };
+    let key = match key.to_str() {
+        Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LEN => key.to_string(),
+        _ => {
+            return ErrorResponse::new(
+                StatusCode::BAD_REQUEST,
+                "Idempotency-Key must be 1 to 255 visible ASCII characters",
+            )
+            .into_response();
+        }
+    };
+    let Some(user_id) = request
+        .extensions()
+        .get::<RequestContext>()
+        .map(|ctx| ctx.user.id)
+    else {
+        return next.run(request).await;
+    };
+
+    let (parts, body) = request.into_parts();
+    let Ok(body) = to_bytes(body, MAX_REQUEST_BYTES).await else {
+        return ErrorResponse::new(StatusCode::PAYLOAD_TOO_LARGE, "request body too large")
+            .into_response();
+    };
+    let request_hash = request_hash(&parts.method, &parts.uri, &body);
+
+    let pool = state.pool();
+    match IdempotencyKeyRepository::reserve(pool, user_id, &key, &request_hash, KEY_TTL_HOURS).await
+    {
+        Ok(true) => {}
+        Ok(false) => return replay(pool, user_id, &key, &request_hash).await,
+        Err(error) => {
+            tracing::error!(?error, "failed to reserve idempotency key");
+            return internal_error();
+        }
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    store(pool, user_id, &key, response).await
+}
+
+fn request_hash(method: &Method, uri: &Uri, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str());
+    hasher.update(b"\n");
+    hasher.update(uri.path_and_query().map_or("", |pq| pq.as_str()));
+    hasher.update(b"\n");
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+fn internal_error() -> Response {
+    ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error").into_response()
+}
+
+/// Whether `response` can be stored for replay: a handled (non-5xx) JSON
+/// response of known, modest size.
+fn is_storable(response: &Response) -> bool {
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    !response.status().is_server_error()
+        && is_json
+        && response
+            .body()
+            .size_hint()
+            .exact()
+            .is_some_and(|len| len <= MAX_STORED_RESPONSE_BYTES)
+}
+
+async fn store(pool: &PgPool, user_id: Uuid, key: &str, response: Response) -> Response {
+    if !is_storable(&response) {
+        if let Err(error) = IdempotencyKeyRepository::release(pool, user_id, key).await {
+            warn!(?error, "failed to release idempotency key");
+        }
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, MAX_STORED_RESPONSE_BYTES as usize).await {
+        Ok(body) => body,
+        Err(error) => {
+            tracing::error!(?error, "failed to buffer response for idempotency key");
+            let _ = IdempotencyKeyRepository::release(pool, user_id, key).await;
+            return internal_error();
+        }
+    };
+    if let Err(error) =
+        IdempotencyKeyRepository::complete(pool, user_id, key, parts.status.as_u16() as i16, &body)
+            .await
+    {
+        // The request went through; a retry will be refused as in progress
+        // until the key is taken over.
+        warn!(?error, "failed to store idempotent response");
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+async fn replay(pool: &PgPool, user_id: Uuid, key: &str, request_hash: &str) -> Response {
+    let record = match IdempotencyKeyRepository::find(pool, user_id, key).await {
+        Ok(Some(record)) => record,
+        Ok(None) => {
+            return ErrorResponse::new(
+                StatusCode::CONFLICT,
+                "Idempotency-Key expired while in use; retry the request",
+            )
+            .into_response();
+        }
+        Err(error) => {
+            tracing::error!(?error, "failed to load idempotency key");
+            return internal_error();
+        }
+    };
+
+    if record.request_hash != request_hash {
+        return ErrorResponse::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Idempotency-Key was already used for a different request",
+        )
+        .into_response();
+    }
+    let (Some(status_code), Some(body)) = (record.status_code, record.response_body) else {
+        return ErrorResponse::new(
+            StatusCode::CONFLICT,
+            "A request with this Idempotency-Key is still being processed",
+        )
+        .into_response();
+    };
+    let Ok(status) = StatusCode::from_u16(status_code as u16) else {
+        return internal_error();
+    };
+
+    (
+        status,
+        [
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            ),
+            (
+                header::HeaderName::from_static(REPLAYED_HEADER),
+                HeaderValue::from_static("true"),
+            ),
+        ],
+        Bytes::from(body),
+    )
+        .into_response()
+}
+
+/// Spawns a background task that deletes expired idempotency keys. Call once
+/// during server startup.
+pub(crate) fn spawn_cleanup_task(pool: PgPool) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(CLEANUP_INTERVAL);
+        // Skip the immediate first tick so the server can finish starting up.
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+            match IdempotencyKeyRepository::delete_expired(&pool, KEY_TTL_HOURS).await {
+                Ok(count) => info!(deleted = count, "Expired idempotency key cleanup complete"),
+                Err(error) => warn!(?error, "Expired idempotency key cleanup failed"),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Json;
+
+    use super::*;
+
+    #[test]
+    fn request_hash_covers_method_path_and_body() {
+        let uri: Uri = "/v1/issues".parse().unwrap();
+        let hash = request_hash(&Method::POST, &uri, b"{\"title\":\"a\"}");
+
+        assert_eq!(
+            hash,
+            request_hash(&Method::POST, &uri, b"{\"title\":\"a\"}")
+        );
+        assert_ne!(
+            hash,
+            request_hash(&Method::POST, &uri, b"{\"title\":\"b\"}")
+        );
+        assert_ne!(
+            hash,
+            request_hash(
+                &Method::POST,
+                &"/v1/issue_comments".parse().unwrap(),
+                b"{\"title\":\"a\"}"
+            )
+        );
+    }
+
+    #[test]
+    fn stores_only_handled_json_responses() {
+        let json = Json(serde_json::json!({ "ok": true })).into_response();
+        assert!(is_storable(&json));
+
+        let rejected = ErrorResponse::new(StatusCode::BAD_REQUEST, "invalid title").into_response();
+        assert!(is_storable(&rejected));
+
+        let failed = internal_error();
+        assert!(!is_storable(&failed));
+
+        let file = ([(header::CONTENT_TYPE, "application/zip")], vec![0u8; 16]).into_response();
+        assert!(!is_storable(&file));
+    }
+}
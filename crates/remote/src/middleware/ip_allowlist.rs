@@ -0,0 +1,396 @@
+//! Organization IP allowlists, enforced once the session is resolved.
+
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+};
+
+use api_types::IpNotAllowedError;
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, MatchedPath, Path, State},
+    http::{Extensions, HeaderMap, Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use ipnetwork::IpNetwork;
+use url::form_urlencoded;
+use uuid::Uuid;
+
+use crate::{
+    AppState,
+    audit::{self, AuditAction, AuditEvent},
+    auth::RequestContext,
+    db::ip_allowlists::{IpAllowlistError, IpAllowlistRepository, IpAllowlistRow},
+    ip_allowlist::is_allowed,
+    routes::error::ErrorResponse,
+};
+
+/// What a request reaches, naming its organization directly or not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Target {
+    Organization(Uuid),
+    Project(Uuid),
+    Issue(Uuid),
+}
+
+/// The client's address: the connection's peer, or the address a trusted
+/// proxy in front of the server reports for it.
+pub(crate) fn client_ip(
+    state: &AppState,
+    extensions: &Extensions,
+    headers: &HeaderMap,
+) -> Option<IpAddr> {
+    forwarded_client_ip(&state.config.trusted_proxies, peer_ip(extensions), headers)
+}
+
+/// Whether the request came straight from one of `trusted_proxies`, so
+/// headers set by the proxy can be believed.
+pub(crate) fn from_trusted_proxy(state: &AppState, extensions: &Extensions) -> bool {
+    peer_ip(extensions).is_some_and(|peer| is_trusted(&state.config.trusted_proxies, peer))
+}
+
+fn peer_ip(extensions: &Extensions) -> Option<IpAddr> {
+    extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+}
+
+fn is_trusted(trusted_proxies: &[IpNetwork], ip: IpAddr) -> bool {
+    let ip = ip.to_canonical();
+    trusted_proxies.iter().any(|range| range.contains(ip))
+}
+
+/// Forwarding headers are only believed when the peer is one of
+/// `trusted_proxies`; anyone else could write them.
+fn forwarded_client_ip(
+    trusted_proxies: &[IpNetwork],
+    peer: Option<IpAddr>,
+    headers: &HeaderMap,
+) -> Option<IpAddr> {
+    let is_trusted = |ip: IpAddr| is_trusted(trusted_proxies, ip);
+    let peer = peer?;
+    if !is_trusted(peer) {
+        return Some(peer);
+    }
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+
+    // Cloudflare (production)
+    if let Some(ip) = header("CF-Connecting-IP").and_then(|s| s.trim().parse().ok()) {
+        return Some(ip);
+    }
+
+    // Each proxy appends the address it received the request from, so the
+    // client is the last hop that isn't one of ours. Earlier entries are
+    // whatever the client sent.
+    if let Some(forwarded_for) = header("X-Forwarded-For") {
+        let mut client = None;
+        for hop in forwarded_for.rsplit(',') {
+            let Ok(ip) = hop.trim().parse::<IpAddr>() else {
+                break;
+            };
+            client = Some(ip);
+            if !is_trusted(ip) {
+                break;
+            }
+        }
+        if client.is_some() {
+            return client;
+        }
+    }
+
+    header("X-Real-IP")
+        .and_then(|s| s.trim().parse().ok())
+        .or(Some(peer))
+}
+
+/// GET routes that read what they reach from the query string, by matched
+/// path below `/v1` and the parameter they read. No other route's query
+/// string is believed, as its handler never looks at it.
+const QUERY_TARGET_ROUTES: &[(&str, QueryTarget)] = &[
+    ("/projects", QueryTarget::Organization),
+    ("/organization_tags", QueryTarget::Organization),
+    ("/organization_tags/duplicates", QueryTarget::Organization),
+    ("/notifications/unread_count", QueryTarget::Organization),
+    ("/issues", QueryTarget::Project),
+    ("/tags", QueryTarget::Project),
+    ("/project_statuses", QueryTarget::Project),
+    ("/project_status_workflows", QueryTarget::Project),
+    ("/work-logs/summary", QueryTarget::Project),
+    ("/query-subscriptions", QueryTarget::Project),
+];
+
+/// The query parameter a route in [`QUERY_TARGET_ROUTES`] reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum QueryTarget {
+    /// `organization_id`
+    Organization,
+    /// `project_id`
+    Project,
+}
+
+/// Attribute a request to what it reaches, from its path parameters, then
+/// from the query parameter its route declares in [`QUERY_TARGET_ROUTES`].
+fn target_for(
+    method: &Method,
+    matched_path: &str,
+    params: &HashMap<String, String>,
+    query: Option<&str>,
+) -> Option<Target> {
+    let uuid = |value: &str| value.parse::<Uuid>().ok();
+    let param = |name: &str| params.get(name).and_then(|value| uuid(value));
+    let path = matched_path
+        .strip_prefix("/v1")
+        .or_else(|| matched_path.strip_prefix("/v2"))
+        .unwrap_or(matched_path);
+
+    if let Some(id) = param("org_id").or_else(|| param("organization_id")) {
+        return Some(Target::Organization(id));
+    }
+    if let Some(id) = param("project_id") {
+        return Some(Target::Project(id));
+    }
+    if let Some(id) = param("issue_id") {
+        return Some(Target::Issue(id));
+    }
+    if let Some(id) = param("id") {
+        if path.starts_with("/projects/") {
+            return Some(Target::Project(id));
+        }
+        if path.starts_with("/issues/") {
+            return Some(Target::Issue(id));
+        }
+    }
+
+    if *method != Method::GET {
+        return None;
+    }
+    let (_, query_target) = QUERY_TARGET_ROUTES
+        .iter()
+        .find(|(route, _)| *route == path)?;
+    let query: HashMap<_, _> = form_urlencoded::parse(query?.as_bytes()).collect();
+    let query_param = |name: &str| query.get(name).and_then(|value| uuid(value));
+    match query_target {
+        QueryTarget::Organization => query_param("organization_id").map(Target::Organization),
+        QueryTarget::Project => query_param("project_id").map(Target::Project),
+    }
+}
+
+async fn restricting_allowlist(
+    state: &AppState,
+    target: Target,
+) -> Result<Option<IpAllowlistRow>, IpAllowlistError> {
+    let pool = state.pool();
+    match target {
+        Target::Organization(organization_id) => {
+            Ok(IpAllowlistRepository::find(pool, organization_id)
+                .await?
+                .filter(|allowlist| !allowlist.ranges.is_empty()))
+        }
+        Target::Project(project_id) => IpAllowlistRepository::for_project(pool, project_id).await,
+        Target::Issue(issue_id) => IpAllowlistRepository::for_issue(pool, issue_id).await,
+    }
+}
+
+/// Rejects with a 403 requests from addresses outside the allowlist of the
+/// organization they reach. Requests it can't attribute to an organization
+/// must pass the allowlist of every organization the user belongs to.
+pub(crate) async fn require_allowed_ip(
+    State(state): State<AppState>,
+    matched_path: Option<MatchedPath>,
+    path_params: Option<Path<HashMap<String, String>>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(ctx) = request.extensions().get::<RequestContext>().cloned() else {
+        return next.run(request).await;
+    };
+    let params = path_params.map(|Path(params)| params).unwrap_or_default();
+    let target = target_for(
+        request.method(),
+        matched_path.as_ref().map_or("", MatchedPath::as_str),
+        &params,
+        request.uri().query(),
+    );
+
+    let allowlists = match target {
+        Some(target) => restricting_allowlist(&state, target)
+            .await
+            .map(|allowlist| allowlist.into_iter().collect()),
+        None => IpAllowlistRepository::for_member(state.pool(), ctx.user.id).await,
+    };
+    let allowlists: Vec<IpAllowlistRow> = match allowlists {
+        Ok(allowlists) => allowlists,
+        Err(error) => {
+            tracing::error!(?error, ?target, "failed to load IP allowlist");
+            return ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+                .into_response();
+        }
+    };
+    let ip = client_ip(&state, request.extensions(), request.headers());
+    let Some(allowlist) = allowlists
+        .into_iter()
+        .find(|allowlist| !is_allowed(&allowlist.ranges, ip))
+    else {
+        return next.run(request).await;
+    };
+
+    let address = ip.map_or_else(|| "an unknown address".to_string(), |ip| ip.to_string());
+    audit::emit(
+        AuditEvent::from_request(&ctx, AuditAction::IpAllowlistBlock)
+            .organization(allowlist.organization_id)
+            .http(request.method().as_str(), request.uri().path(), 403)
+            .description(format!("Blocked request from {address}")),
+    );
+
+    let blocked = IpNotAllowedError {
+        organization_id: allowlist.organization_id,
+        ip_address: ip.map(|ip| ip.to_string()),
+    };
+    ErrorResponse::ip_not_allowed(
+        format!("This organization doesn't allow access from {address}"),
+        serde_json::to_value(blocked).unwrap_or_default(),
+    )
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(values: &[(&str, Uuid)]) -> HashMap<String, String> {
+        values
+            .iter()
+            .map(|(name, id)| (name.to_string(), id.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn attributes_requests_to_what_they_reach() {
+        let id = Uuid::new_v4();
+        assert_eq!(
+            target_for(
+                &Method::GET,
+                "/v1/organizations/{org_id}/members",
+                &params(&[("org_id", id)]),
+                None
+            ),
+            Some(Target::Organization(id))
+        );
+        assert_eq!(
+            target_for(
+                &Method::GET,
+                "/v1/shape/project/{project_id}/issues",
+                &params(&[("project_id", id)]),
+                Some("offset=-1")
+            ),
+            Some(Target::Project(id))
+        );
+        assert_eq!(
+            target_for(
+                &Method::GET,
+                "/v1/issues/{id}",
+                &params(&[("id", id)]),
+                None
+            ),
+            Some(Target::Issue(id))
+        );
+        assert_eq!(
+            target_for(
+                &Method::GET,
+                "/v1/attachments/{id}",
+                &params(&[("id", id)]),
+                None
+            ),
+            None
+        );
+        assert_eq!(
+            target_for(
+                &Method::GET,
+                "/v1/issues",
+                &HashMap::new(),
+                Some(format!("project_id={id}&limit=50").as_str())
+            ),
+            Some(Target::Project(id))
+        );
+        assert_eq!(
+            target_for(
+                &Method::GET,
+                "/v1/projects",
+                &HashMap::new(),
+                Some(format!("organization_id={id}").as_str())
+            ),
+            Some(Target::Organization(id))
+        );
+        assert_eq!(
+            target_for(&Method::GET, "/v1/identity", &HashMap::new(), None),
+            None
+        );
+    }
+
+    #[test]
+    fn ignores_query_parameters_the_route_does_not_read() {
+        // Naming an organization without an allowlist must not exempt a
+        // request whose handler acts on another organization's data.
+        let id = Uuid::new_v4();
+        let query = format!("organization_id={id}&project_id={id}");
+        for (method, path) in [
+            (Method::POST, "/v1/issues"),
+            (Method::POST, "/v1/issues/search"),
+            (Method::POST, "/v1/issues/bulk"),
+            (Method::POST, "/v1/mutations/batch"),
+            (Method::GET, "/v1/identity"),
+        ] {
+            assert_eq!(
+                target_for(&method, path, &HashMap::new(), Some(query.as_str())),
+                None,
+                "{method} {path}"
+            );
+        }
+        // `/issues` reads `project_id`, not `organization_id`.
+        assert_eq!(
+            target_for(
+                &Method::GET,
+                "/v1/issues",
+                &HashMap::new(),
+                Some(format!("organization_id={id}").as_str())
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn reads_the_proxied_client_address() {
+        let trusted: Vec<IpNetwork> = vec!["10.0.0.0/8".parse().unwrap()];
+        let proxy = "10.0.0.2".parse().ok();
+        let mut headers = HeaderMap::new();
+        assert_eq!(forwarded_client_ip(&trusted, None, &headers), None);
+        assert_eq!(forwarded_client_ip(&trusted, proxy, &headers), proxy);
+
+        headers.insert(
+            "X-Forwarded-For",
+            "192.0.2.1, 203.0.113.9, 10.0.0.1".parse().unwrap(),
+        );
+        assert_eq!(
+            forwarded_client_ip(&trusted, proxy, &headers),
+            "203.0.113.9".parse().ok()
+        );
+        headers.insert("CF-Connecting-IP", "198.51.100.2".parse().unwrap());
+        assert_eq!(
+            forwarded_client_ip(&trusted, proxy, &headers),
+            "198.51.100.2".parse().ok()
+        );
+    }
+
+    #[test]
+    fn ignores_forwarding_headers_from_untrusted_peers() {
+        let trusted: Vec<IpNetwork> = vec!["10.0.0.0/8".parse().unwrap()];
+        let peer = "203.0.113.50".parse().ok();
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Forwarded-For", "198.51.100.2".parse().unwrap());
+        headers.insert("CF-Connecting-IP", "198.51.100.2".parse().unwrap());
+        headers.insert("X-Real-IP", "198.51.100.2".parse().unwrap());
+        assert_eq!(forwarded_client_ip(&trusted, peer, &headers), peer);
+        assert_eq!(forwarded_client_ip(&[], peer, &headers), peer);
+    }
+}
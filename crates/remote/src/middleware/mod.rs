@@ -1 +1,5 @@
+pub(crate) mod entitlements;
+pub(crate) mod idempotency;
+pub(crate) mod ip_allowlist;
+pub(crate) mod session_usage;
 pub(crate) mod version;
@@ -0,0 +1,73 @@
+//! Session usage, counted for anomaly detection once the session is
+//! resolved.
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{HeaderMap, Request},
+    middleware::Next,
+    response::Response,
+};
+use chrono::Utc;
+
+use crate::{
+    AppState,
+    auth::RequestContext,
+    middleware::ip_allowlist::{client_ip, from_trusted_proxy},
+    session_anomalies::UNKNOWN_COUNTRY,
+};
+
+/// The client's country as reported by Cloudflare. Only a trusted proxy's
+/// header is believed; anyone else could write it.
+fn client_country(headers: &HeaderMap, from_trusted_proxy: bool) -> String {
+    if !from_trusted_proxy {
+        return UNKNOWN_COUNTRY.to_string();
+    }
+    headers
+        .get("CF-IPCountry")
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|country| country.len() == 2 && country.bytes().all(|b| b.is_ascii_alphanumeric()))
+        .map_or_else(|| UNKNOWN_COUNTRY.to_string(), str::to_ascii_uppercase)
+}
+
+/// Counts the request against its session without holding it up.
+pub(crate) async fn record_session_usage(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if let Some(ctx) = request.extensions().get::<RequestContext>() {
+        let trusted = from_trusted_proxy(&state, request.extensions());
+        let country = client_country(request.headers(), trusted);
+        let ip = client_ip(&state, request.extensions(), request.headers());
+        state
+            .session_usage()
+            .record(ctx.session_id, country, ip, Utc::now());
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_reported_country() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(client_country(&headers, true), "XX");
+        headers.insert("CF-IPCountry", "fr".parse().unwrap());
+        assert_eq!(client_country(&headers, true), "FR");
+        headers.insert("CF-IPCountry", "T1".parse().unwrap());
+        assert_eq!(client_country(&headers, true), "T1");
+        headers.insert("CF-IPCountry", "France".parse().unwrap());
+        assert_eq!(client_country(&headers, true), "XX");
+    }
+
+    #[test]
+    fn ignores_the_country_from_untrusted_peers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("CF-IPCountry", "FR".parse().unwrap());
+        assert_eq!(client_country(&headers, false), "XX");
+    }
+}
@@ -1,17 +1,290 @@
+//! API versioning.
+//!
+//! Every endpoint lives under a version prefix. A breaking change ships as a
+//! new `/v2` route next to the `/v1` one, which keeps working and is marked
+//! with a [`DeprecatedRoute`] naming the new route as its successor. Clients
+//! see `Deprecation`, `Sunset` and `Link` headers until the sunset date, after
+//! which the old route answers `410 Gone`.
+
 use axum::{
     body::Body,
-    http::{Request, header::HeaderValue},
-    middleware::Next,
-    response::Response,
+    extract::State,
+    http::{HeaderMap, Method, Request, StatusCode, header::HeaderValue},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::MethodRouter,
 };
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::routes::error::ErrorResponse;
+
+/// API versions served side by side, by path prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiVersion {
+    V1,
+    V2,
+}
+
+impl ApiVersion {
+    pub const ALL: [ApiVersion; 2] = [ApiVersion::V1, ApiVersion::V2];
+
+    pub fn prefix(self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "/v1",
+            ApiVersion::V2 => "/v2",
+        }
+    }
+
+    fn header_value(self) -> HeaderValue {
+        match self {
+            ApiVersion::V1 => HeaderValue::from_static("1"),
+            ApiVersion::V2 => HeaderValue::from_static("2"),
+        }
+    }
+
+    pub fn from_path(path: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|version| {
+            path.strip_prefix(version.prefix())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+    }
+}
+
+/// A route clients should move off, and when it goes away.
+#[derive(Debug)]
+pub struct DeprecatedRoute {
+    pub method: Method,
+    /// Full path, including the version prefix.
+    pub path: &'static str,
+    /// `YYYY-MM-DD` the route was deprecated on.
+    pub deprecated_on: &'static str,
+    /// `YYYY-MM-DD` the route stops working on, once decided.
+    pub sunset_on: Option<&'static str>,
+    /// Path of the route replacing this one.
+    pub successor: Option<&'static str>,
+}
+
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|datetime| datetime.and_utc())
+}
+
+impl DeprecatedRoute {
+    pub fn deprecated_at(&self) -> Option<DateTime<Utc>> {
+        parse_date(self.deprecated_on)
+    }
+
+    pub fn sunset_at(&self) -> Option<DateTime<Utc>> {
+        self.sunset_on.and_then(parse_date)
+    }
+
+    pub fn is_sunset(&self, now: DateTime<Utc>) -> bool {
+        self.sunset_at().is_some_and(|sunset| now >= sunset)
+    }
+
+    /// Add the RFC 9745 `Deprecation`, RFC 8594 `Sunset` and successor `Link`
+    /// headers.
+    pub fn insert_headers(&self, headers: &mut HeaderMap) {
+        if let Some(deprecated_at) = self.deprecated_at()
+            && let Ok(value) = HeaderValue::from_str(&format!("@{}", deprecated_at.timestamp()))
+        {
+            headers.insert("Deprecation", value);
+        }
+        if let Some(sunset_at) = self.sunset_at()
+            && let Ok(value) =
+                HeaderValue::from_str(&sunset_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string())
+        {
+            headers.insert("Sunset", value);
+        }
+        if let Some(successor) = self.successor
+            && let Ok(value) =
+                HeaderValue::from_str(&format!("<{successor}>; rel=\"successor-version\""))
+        {
+            headers.insert("Link", value);
+        }
+    }
+}
+
+/// Mark a route deprecated, adding the lifecycle headers to its responses.
+pub fn deprecated<S>(
+    route: MethodRouter<S>,
+    deprecation: &'static DeprecatedRoute,
+) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    route.route_layer(middleware::from_fn_with_state(
+        deprecation,
+        deprecation_headers,
+    ))
+}
+
+async fn deprecation_headers(
+    State(deprecation): State<&'static DeprecatedRoute>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if deprecation.is_sunset(Utc::now()) {
+        let mut message = format!(
+            "{} {} was removed on {}",
+            deprecation.method,
+            deprecation.path,
+            deprecation.sunset_on.unwrap_or_default()
+        );
+        if let Some(successor) = deprecation.successor {
+            message.push_str(&format!("; use {successor} instead"));
+        }
+        return ErrorResponse::new(StatusCode::GONE, message).into_response();
+    }
+
+    let mut response = next.run(request).await;
+    deprecation.insert_headers(response.headers_mut());
+    response
+}
 
 pub(crate) async fn add_version_headers(request: Request<Body>, next: Next) -> Response {
+    let api_version = ApiVersion::from_path(request.uri().path());
     let mut response = next.run(request).await;
 
     response.headers_mut().insert(
         "X-Server-Version",
         HeaderValue::from_static(env!("CARGO_PKG_VERSION")),
     );
+    if let Some(api_version) = api_version {
+        response
+            .headers_mut()
+            .insert("X-Api-Version", api_version.header_value());
+    }
 
     response
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use axum::{
+        Router,
+        routing::{get, post},
+    };
+    use chrono::TimeZone;
+
+    use super::*;
+
+    const RETIRED: DeprecatedRoute = DeprecatedRoute {
+        method: Method::POST,
+        path: "/v1/widgets",
+        deprecated_on: "2026-05-01",
+        sunset_on: Some("2026-11-01"),
+        successor: Some("/v2/widgets"),
+    };
+
+    #[test]
+    fn resolves_version_from_path_prefix() {
+        assert_eq!(ApiVersion::from_path("/v1/issues"), Some(ApiVersion::V1));
+        assert_eq!(ApiVersion::from_path("/v2"), Some(ApiVersion::V2));
+        assert_eq!(ApiVersion::from_path("/v10/issues"), None);
+        assert_eq!(ApiVersion::from_path("/projects"), None);
+    }
+
+    #[test]
+    fn renders_lifecycle_headers() {
+        let mut headers = HeaderMap::new();
+        RETIRED.insert_headers(&mut headers);
+
+        assert_eq!(headers["Deprecation"], "@1777593600");
+        assert_eq!(headers["Sunset"], "Sun, 01 Nov 2026 00:00:00 GMT");
+        assert_eq!(headers["Link"], "</v2/widgets>; rel=\"successor-version\"");
+
+        let before = Utc.with_ymd_and_hms(2026, 10, 31, 23, 59, 59).unwrap();
+        let after = Utc.with_ymd_and_hms(2026, 11, 1, 0, 0, 0).unwrap();
+        assert!(!RETIRED.is_sunset(before));
+        assert!(RETIRED.is_sunset(after));
+    }
+
+    #[tokio::test]
+    async fn deprecated_routes_answer_with_lifecycle_headers() {
+        static DEPRECATED: DeprecatedRoute = DeprecatedRoute {
+            method: Method::POST,
+            path: "/v1/widgets",
+            deprecated_on: "2026-05-01",
+            sunset_on: None,
+            successor: Some("/v2/widgets"),
+        };
+        static REMOVED: DeprecatedRoute = DeprecatedRoute {
+            method: Method::POST,
+            path: "/v1/gadgets",
+            deprecated_on: "2025-01-01",
+            sunset_on: Some("2025-07-01"),
+            successor: None,
+        };
+        async fn ok() -> &'static str {
+            "ok"
+        }
+
+        let v1 = Router::new()
+            .route("/widgets", get(ok).merge(deprecated(post(ok), &DEPRECATED)))
+            .route("/gadgets", deprecated(post(ok), &REMOVED));
+        let router = Router::new()
+            .nest(ApiVersion::V1.prefix(), v1)
+            .layer(middleware::from_fn(add_version_headers));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        let client = reqwest::Client::new();
+        let url = |path: &str| format!("http://{addr}{path}");
+
+        let response = client.post(url("/v1/widgets")).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let headers = response.headers();
+        assert_eq!(headers["Deprecation"], "@1777593600");
+        assert_eq!(headers["Link"], "</v2/widgets>; rel=\"successor-version\"");
+        assert!(!headers.contains_key("Sunset"));
+        assert_eq!(headers["X-Api-Version"], "1");
+
+        // Only the deprecated method is marked.
+        let response = client.get(url("/v1/widgets")).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!response.headers().contains_key("Deprecation"));
+        assert_eq!(response.headers()["X-Api-Version"], "1");
+
+        let response = client.post(url("/v1/gadgets")).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::GONE);
+    }
+
+    /// Guards the compatibility promise: deprecated routes stay versioned,
+    /// dated, and pointed at a versioned successor.
+    #[test]
+    fn every_deprecation_is_well_formed() {
+        let mut seen = HashSet::new();
+        for route in crate::routes::all_deprecated_routes() {
+            let name = format!("{} {}", route.method, route.path);
+            assert!(seen.insert(name.clone()), "{name} is deprecated twice");
+            assert!(
+                ApiVersion::from_path(route.path).is_some(),
+                "{name} has no version prefix"
+            );
+            let deprecated_at = route
+                .deprecated_at()
+                .unwrap_or_else(|| panic!("{name} has an invalid deprecation date"));
+            if let Some(sunset_on) = route.sunset_on {
+                let sunset_at = route
+                    .sunset_at()
+                    .unwrap_or_else(|| panic!("{name} has an invalid sunset date {sunset_on}"));
+                assert!(
+                    sunset_at > deprecated_at,
+                    "{name} sunsets before deprecation"
+                );
+            }
+            if let Some(successor) = route.successor {
+                assert!(
+                    ApiVersion::from_path(successor).is_some(),
+                    "{name} points at unversioned successor {successor}"
+                );
+                assert_ne!(successor, route.path, "{name} succeeds itself");
+            }
+        }
+    }
+}
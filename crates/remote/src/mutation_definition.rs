@@ -4,6 +4,8 @@
 //! - Generates axum routers for CRUD mutation routes
 //! - Captures type information for TypeScript generation
 //! - Uses `HasJsonPayload` to ensure handler signatures match declared C/U types
+//! - Declares the version column of tables with optimistic concurrency, and
+//!   provides the conflict check their update handlers run
 //!
 //! # Example
 //!
@@ -17,6 +19,7 @@
 //!         .create(create_tag)
 //!         .update(update_tag)
 //!         .delete(delete_tag)
+//!         .versioned("updated_at")
 //! }
 //!
 //! pub fn router() -> Router<AppState> {
@@ -26,10 +29,20 @@
 
 use std::marker::PhantomData;
 
-use axum::{Json, handler::Handler, routing::MethodRouter};
+use api_types::MutationConflict;
+use axum::{
+    Json,
+    handler::Handler,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    routing::MethodRouter,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
 use ts_rs::TS;
+use uuid::Uuid;
 
-use crate::AppState;
+use crate::{AppState, db::Tx, routes::error::ErrorResponse};
 
 type MutationMarker<E, C, U> = fn() -> (E, C, U);
 
@@ -64,6 +77,9 @@ pub struct MutationDefinition {
     pub row_type: String,
     pub create_type: Option<String>,
     pub update_type: Option<String>,
+    /// Column updates must name the current value of (as `expected_<column>`)
+    /// to be applied; `None` for last-write-wins tables.
+    pub version_column: Option<&'static str>,
 }
 
 // =============================================================================
@@ -78,6 +94,7 @@ pub struct MutationDefinition {
 /// - `U`: The update request type, or `NoUpdate` if no update
 pub struct MutationBuilder<E, C = (), U = ()> {
     table: &'static str,
+    version_column: Option<&'static str>,
     base_route: MethodRouter<AppState>,
     id_route: MethodRouter<AppState>,
     _phantom: PhantomData<MutationMarker<E, C, U>>,
@@ -88,6 +105,7 @@ impl<E: TS + Send + Sync + 'static> MutationBuilder<E, NoCreate, NoUpdate> {
     pub fn new(table: &'static str) -> Self {
         Self {
             table,
+            version_column: None,
             base_route: MethodRouter::new(),
             id_route: MethodRouter::new(),
             _phantom: PhantomData,
@@ -126,6 +144,13 @@ impl<E: TS, C, U> MutationBuilder<E, C, U> {
         self
     }
 
+    /// Use optimistic concurrency on `column`: the update handler rejects
+    /// updates made against an outdated row with a `MutationConflict`.
+    pub fn versioned(mut self, column: &'static str) -> Self {
+        self.version_column = Some(column);
+        self
+    }
+
     /// Build the axum router from the registered handlers.
     pub fn router(self) -> axum::Router<AppState> {
         let base_path = format!("/{}", self.table);
@@ -150,6 +175,7 @@ impl<E: TS, U> MutationBuilder<E, NoCreate, U> {
     {
         MutationBuilder {
             table: self.table,
+            version_column: self.version_column,
             base_route: self.base_route.post(handler),
             id_route: self.id_route,
             _phantom: PhantomData,
@@ -170,6 +196,7 @@ impl<E: TS, C> MutationBuilder<E, C, NoUpdate> {
     {
         MutationBuilder {
             table: self.table,
+            version_column: self.version_column,
             base_route: self.base_route,
             id_route: self.id_route.patch(handler),
             _phantom: PhantomData,
@@ -190,6 +217,7 @@ impl<E: TS, C: TS, U: TS> MutationBuilder<E, C, U> {
         MutationDefinition {
             table: self.table,
             row_type: E::name(),
+            version_column: self.version_column,
             create_type: Some(C::name()),
             update_type: Some(U::name()),
         }
@@ -201,6 +229,7 @@ impl<E: TS, U: TS> MutationBuilder<E, NoCreate, U> {
         MutationDefinition {
             table: self.table,
             row_type: E::name(),
+            version_column: self.version_column,
             create_type: None,
             update_type: Some(U::name()),
         }
@@ -212,6 +241,7 @@ impl<E: TS, C: TS> MutationBuilder<E, C, NoUpdate> {
         MutationDefinition {
             table: self.table,
             row_type: E::name(),
+            version_column: self.version_column,
             create_type: Some(C::name()),
             update_type: None,
         }
@@ -223,8 +253,179 @@ impl<E: TS> MutationBuilder<E, NoCreate, NoUpdate> {
         MutationDefinition {
             table: self.table,
             row_type: E::name(),
+            version_column: self.version_column,
             create_type: None,
             update_type: None,
         }
     }
 }
+
+// =============================================================================
+// Optimistic concurrency
+// =============================================================================
+
+/// Strong entity tag of a row whose version column is at `version`.
+pub(crate) fn etag(version: DateTime<Utc>) -> String {
+    format!("\"{}\"", version.timestamp_micros())
+}
+
+/// Headers sending a row's `ETag`.
+pub(crate) fn etag_headers(version: DateTime<Utc>) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Ok(value) = HeaderValue::from_str(&etag(version)) {
+        headers.insert(header::ETAG, value);
+    }
+    headers
+}
+
+pub(crate) fn if_match(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::IF_MATCH)
+        .and_then(|value| value.to_str().ok())
+}
+
+/// Check an update's `If-Match` against a row now at `current`, returning
+/// 412 with the stored row when it names another version. When `required`,
+/// updates sending neither `If-Match` nor an expected version get 428.
+pub(crate) fn check_if_match<T: Serialize>(
+    server: &T,
+    current: DateTime<Utc>,
+    if_match: Option<&str>,
+    has_expected_version: bool,
+    required: bool,
+) -> Result<(), ErrorResponse> {
+    let Some(if_match) = if_match else {
+        if required && !has_expected_version {
+            return Err(ErrorResponse::new(
+                StatusCode::PRECONDITION_REQUIRED,
+                "this project requires updates to send If-Match",
+            ));
+        }
+        return Ok(());
+    };
+    let etag = etag(current);
+    if if_match
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag == etag)
+    {
+        return Ok(());
+    }
+    Err(ErrorResponse::precondition_failed(
+        "the row was changed since it was read",
+        serde_json::to_value(server).unwrap_or_default(),
+    ))
+}
+
+/// Lock a row for the rest of `tx`, so its version can't change between the
+/// check and the update.
+pub(crate) async fn lock_row(
+    tx: &mut Tx<'_>,
+    table: &'static str,
+    id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(&format!("SELECT 1 FROM {table} WHERE id = $1 FOR UPDATE"))
+        .bind(id)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+/// Fields `client` sets to a value that differs from the stored row.
+fn conflicting_fields(server: &Value, client: &Value) -> Vec<String> {
+    let (Some(server), Some(client)) = (server.as_object(), client.as_object()) else {
+        return Vec::new();
+    };
+    client
+        .iter()
+        .filter(|(field, value)| server.get(*field).is_some_and(|stored| stored != *value))
+        .map(|(field, _)| field.clone())
+        .collect()
+}
+
+/// Reject an update made against version `expected` of a row that is now at
+/// `current`, returning the stored row and the update so the client can
+/// merge them.
+pub(crate) fn check_version<T: Serialize, P: Serialize>(
+    server: &T,
+    current: DateTime<Utc>,
+    expected: Option<DateTime<Utc>>,
+    client: &P,
+) -> Result<(), ErrorResponse> {
+    let Some(expected) = expected else {
+        return Ok(());
+    };
+    if current == expected {
+        return Ok(());
+    }
+    let server = serde_json::to_value(server).unwrap_or_default();
+    let client = serde_json::to_value(client).unwrap_or_default();
+    let conflict = MutationConflict {
+        conflicting_fields: conflicting_fields(&server, &client),
+        server,
+        client,
+    };
+    Err(ErrorResponse::conflict(
+        "the row was changed since this update was made",
+        serde_json::to_value(conflict).unwrap_or_default(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::response::IntoResponse;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn reports_fields_that_differ_from_the_stored_row() {
+        let server = json!({ "title": "Server", "priority": "high", "sort_order": 1.0 });
+        let client = json!({
+            "title": "Client",
+            "priority": "high",
+            "expected_updated_at": "2026-01-01T00:00:00Z",
+        });
+        assert_eq!(conflicting_fields(&server, &client), vec!["title"]);
+
+        let now = Utc::now();
+        assert!(check_version(&server, now, None, &client).is_ok());
+        assert!(check_version(&server, now, Some(now), &client).is_ok());
+        assert!(
+            check_version(
+                &server,
+                now,
+                Some(now - chrono::Duration::seconds(1)),
+                &client
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn if_match_must_name_the_current_version() {
+        let row = json!({ "title": "Server" });
+        let now = Utc::now();
+        let current = etag(now);
+        let stale = etag(now - chrono::Duration::microseconds(1));
+
+        assert!(check_if_match(&row, now, Some(&current), false, true).is_ok());
+        assert!(check_if_match(&row, now, Some("*"), false, true).is_ok());
+        assert!(
+            check_if_match(&row, now, Some(&format!("{stale}, {current}")), false, true).is_ok()
+        );
+        assert!(check_if_match(&row, now, None, false, false).is_ok());
+        assert!(check_if_match(&row, now, None, true, true).is_ok());
+
+        let failed = check_if_match(&row, now, Some(&stale), false, false).unwrap_err();
+        assert_eq!(
+            failed.into_response().status(),
+            StatusCode::PRECONDITION_FAILED
+        );
+        let missing = check_if_match(&row, now, None, false, true).unwrap_err();
+        assert_eq!(
+            missing.into_response().status(),
+            StatusCode::PRECONDITION_REQUIRED
+        );
+    }
+}
@@ -5,8 +5,10 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::db::{
-    issue_assignees::IssueAssigneeRepository, issue_followers::IssueFollowerRepository,
-    notifications::NotificationRepository, organization_members::is_member,
+    issue_assignees::IssueAssigneeRepository,
+    issue_followers::IssueFollowerRepository,
+    notifications::{NotificationMuteRepository, NotificationRepository},
+    organization_members::is_member,
 };
 
 pub async fn notify_issue_subscribers(
@@ -61,9 +63,10 @@ pub async fn send_issue_notifications(
         return;
     }
 
-    let payload = build_payload(issue, actor_user_id, notification_type, extra_payload);
+    let recipients = without_muted(pool, issue.id, recipients).await;
+    let payload = build_payload(issue, Some(actor_user_id), notification_type, extra_payload);
 
-    for &recipient_id in recipients {
+    for &recipient_id in &recipients {
         if let Err(e) = NotificationRepository::create(
             pool,
             organization_id,
@@ -96,9 +99,10 @@ pub async fn send_debounced_issue_notifications(
         return;
     }
 
-    let payload = build_payload(issue, actor_user_id, notification_type, extra_payload);
+    let recipients = without_muted(pool, issue.id, recipients).await;
+    let payload = build_payload(issue, Some(actor_user_id), notification_type, extra_payload);
 
-    for &recipient_id in recipients {
+    for &recipient_id in &recipients {
         if let Err(e) = NotificationRepository::upsert_recent(
             pool,
             organization_id,
@@ -145,6 +149,41 @@ pub async fn notify_user(
     .await;
 }
 
+/// Like `notify_user` for notifications nobody caused, such as reminders.
+pub async fn notify_user_without_actor(
+    pool: &PgPool,
+    organization_id: Uuid,
+    recipient_user_id: Uuid,
+    issue: &Issue,
+    notification_type: NotificationType,
+    extra_payload: NotificationPayload,
+) {
+    if !is_member(pool, organization_id, recipient_user_id)
+        .await
+        .unwrap_or(false)
+        || without_muted(pool, issue.id, &[recipient_user_id])
+            .await
+            .is_empty()
+    {
+        return;
+    }
+
+    let payload = build_payload(issue, None, notification_type, extra_payload);
+    if let Err(e) = NotificationRepository::create(
+        pool,
+        organization_id,
+        recipient_user_id,
+        notification_type,
+        payload,
+        Some(issue.id),
+        None,
+    )
+    .await
+    {
+        tracing::warn!(?e, %recipient_user_id, issue_id = %issue.id, "failed to create notification");
+    }
+}
+
 pub async fn collect_issue_recipients(
     pool: &PgPool,
     organization_id: Uuid,
@@ -171,9 +210,24 @@ pub async fn collect_issue_recipients(
     Ok(recipients)
 }
 
+/// `recipients` minus those who muted the issue.
+async fn without_muted(pool: &PgPool, issue_id: Uuid, recipients: &[Uuid]) -> Vec<Uuid> {
+    match NotificationMuteRepository::muted_users(pool, issue_id, recipients).await {
+        Ok(muted) => recipients
+            .iter()
+            .copied()
+            .filter(|user_id| !muted.contains(user_id))
+            .collect(),
+        Err(e) => {
+            tracing::warn!(?e, %issue_id, "failed to load notification mutes");
+            recipients.to_vec()
+        }
+    }
+}
+
 fn build_payload(
     issue: &Issue,
-    actor_user_id: Uuid,
+    actor_user_id: Option<Uuid>,
     notification_type: NotificationType,
     extra_payload: NotificationPayload,
 ) -> NotificationPayload {
@@ -187,7 +241,7 @@ fn build_payload(
         issue_id: Some(issue.id),
         issue_simple_id: Some(issue.simple_id.clone()),
         issue_title: Some(issue.title.clone()),
-        actor_user_id: Some(actor_user_id),
+        actor_user_id,
         comment_preview: extra_payload.comment_preview,
         old_status_id: extra_payload.old_status_id,
         new_status_id: extra_payload.new_status_id,
@@ -198,5 +252,10 @@ fn build_payload(
         new_priority: extra_payload.new_priority,
         assignee_user_id: extra_payload.assignee_user_id,
         emoji: extra_payload.emoji,
+        subscription_id: extra_payload.subscription_id,
+        subscription_name: extra_payload.subscription_name,
+        target_date: extra_payload.target_date,
+        sla_policy_id: extra_payload.sla_policy_id,
+        sla_policy_name: extra_payload.sla_policy_name,
     }
 }
@@ -0,0 +1,215 @@
+//! Query definition builder for read endpoints.
+//!
+//! `QueryBuilder` is the read-side counterpart of `MutationBuilder`. It:
+//! - Mounts a GET handler taking `Query<P>`, or a POST handler taking `Json<P>`
+//!   for reads whose parameters don't fit in a query string
+//! - Captures the path, params type and response type for TypeScript
+//!   generation
+//! - Uses `HasQueryParams` / `HasJsonPayload` to ensure the declared params
+//!   type matches what the handler actually accepts
+//!
+//! # Example
+//!
+//! ```ignore
+//! use crate::query_definition::{QueryBuilder, QueryRoute};
+//!
+//! pub fn queries() -> Vec<QueryRoute> {
+//!     vec![QueryBuilder::<ListTagsResponse>::new("list_tags", "tags").get(list_tags)]
+//! }
+//!
+//! pub fn router() -> Router<AppState> {
+//!     mutation().router().merge(query_router(queries()))
+//! }
+//! ```
+
+use std::marker::PhantomData;
+
+use axum::{
+    extract::Query,
+    handler::Handler,
+    routing::{MethodRouter, get, post},
+};
+use ts_rs::TS;
+
+use crate::{AppState, mutation_definition::HasJsonPayload};
+
+// =============================================================================
+// HasQueryParams - Structural trait linking handlers to their params types
+// =============================================================================
+
+/// Marker trait implemented for extractor tuples that include `Query<T>` as
+/// their last extractor.
+pub trait HasQueryParams<T> {}
+
+impl<T> HasQueryParams<T> for (Query<T>,) {}
+impl<A, T> HasQueryParams<T> for (A, Query<T>) {}
+impl<A, B, T> HasQueryParams<T> for (A, B, Query<T>) {}
+impl<A, B, C, T> HasQueryParams<T> for (A, B, C, Query<T>) {}
+impl<A, B, C, D, T> HasQueryParams<T> for (A, B, C, D, Query<T>) {}
+impl<A, B, C, D, E0, T> HasQueryParams<T> for (A, B, C, D, E0, Query<T>) {}
+impl<A, B, C, D, E0, F, T> HasQueryParams<T> for (A, B, C, D, E0, F, Query<T>) {}
+impl<A, B, C, D, E0, F, G, T> HasQueryParams<T> for (A, B, C, D, E0, F, G, Query<T>) {}
+
+// =============================================================================
+// QueryDefinition - Metadata for TypeScript generation
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryMethod {
+    Get,
+    Post,
+}
+
+impl QueryMethod {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            QueryMethod::Get => "GET",
+            QueryMethod::Post => "POST",
+        }
+    }
+}
+
+/// Metadata extracted from a QueryBuilder for TypeScript code generation.
+#[derive(Debug, Clone)]
+pub struct QueryDefinition {
+    pub name: &'static str,
+    pub method: QueryMethod,
+    /// Path under `/v1`, without a leading slash.
+    pub path: &'static str,
+    /// Query string type for GET, body type for POST; `None` if the endpoint
+    /// takes no parameters.
+    pub params_type: Option<String>,
+    pub response_type: String,
+}
+
+// =============================================================================
+// QueryBuilder
+// =============================================================================
+
+/// Builder for a read endpoint returning `R`.
+pub struct QueryBuilder<R> {
+    name: &'static str,
+    path: &'static str,
+    _phantom: PhantomData<fn() -> R>,
+}
+
+impl<R: TS> QueryBuilder<R> {
+    /// `name` becomes the TypeScript constant (`list_tags` -> `LIST_TAGS_QUERY`).
+    pub fn new(name: &'static str, path: &'static str) -> Self {
+        Self {
+            name,
+            path,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// GET /{path}, with parameters in the query string.
+    pub fn get<P, H, T>(self, handler: H) -> QueryRoute
+    where
+        P: TS,
+        H: Handler<T, AppState> + Clone + Send + 'static,
+        T: HasQueryParams<P> + 'static,
+    {
+        self.build(QueryMethod::Get, Some(P::name()), get(handler))
+    }
+
+    /// GET /{path}, without parameters.
+    pub fn get_without_params<H, T>(self, handler: H) -> QueryRoute
+    where
+        H: Handler<T, AppState> + Clone + Send + 'static,
+        T: 'static,
+    {
+        self.build(QueryMethod::Get, None, get(handler))
+    }
+
+    /// POST /{path}, with parameters in the JSON body.
+    pub fn post<P, H, T>(self, handler: H) -> QueryRoute
+    where
+        P: TS,
+        H: Handler<T, AppState> + Clone + Send + 'static,
+        T: HasJsonPayload<P> + 'static,
+    {
+        self.build(QueryMethod::Post, Some(P::name()), post(handler))
+    }
+
+    fn build(
+        self,
+        method: QueryMethod,
+        params_type: Option<String>,
+        route: MethodRouter<AppState>,
+    ) -> QueryRoute {
+        QueryRoute {
+            definition: QueryDefinition {
+                name: self.name,
+                method,
+                path: self.path,
+                params_type,
+                response_type: R::name(),
+            },
+            route,
+        }
+    }
+}
+
+/// A read endpoint with its metadata.
+pub struct QueryRoute {
+    definition: QueryDefinition,
+    route: MethodRouter<AppState>,
+}
+
+impl QueryRoute {
+    pub fn definition(&self) -> QueryDefinition {
+        self.definition.clone()
+    }
+
+    pub fn router(self) -> axum::Router<AppState> {
+        axum::Router::new().route(&format!("/{}", self.definition.path), self.route)
+    }
+}
+
+/// Build one router for `queries`.
+pub fn query_router(queries: Vec<QueryRoute>) -> axum::Router<AppState> {
+    queries
+        .into_iter()
+        .fold(axum::Router::new(), |router, query| {
+            router.merge(query.router())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use api_types::{ListIssuesResponse, ListTagsQuery, ListTagsResponse, SearchIssuesRequest};
+    use axum::Json;
+
+    use super::*;
+
+    async fn list_tags(Query(_): Query<ListTagsQuery>) -> Json<ListTagsResponse> {
+        Json(ListTagsResponse { tags: Vec::new() })
+    }
+
+    async fn search_issues(Json(_): Json<SearchIssuesRequest>) -> Json<ListIssuesResponse> {
+        Json(ListIssuesResponse {
+            issues: Vec::new(),
+            total_count: 0,
+            limit: 0,
+            offset: 0,
+        })
+    }
+
+    #[test]
+    fn records_params_and_response_types() {
+        let list = QueryBuilder::<ListTagsResponse>::new("list_tags", "tags")
+            .get(list_tags)
+            .definition();
+        assert_eq!(list.method, QueryMethod::Get);
+        assert_eq!(list.path, "tags");
+        assert_eq!(list.params_type.as_deref(), Some("ListTagsQuery"));
+        assert_eq!(list.response_type, "ListTagsResponse");
+
+        let search = QueryBuilder::<ListIssuesResponse>::new("search_issues", "issues/search")
+            .post(search_issues)
+            .definition();
+        assert_eq!(search.method, QueryMethod::Post);
+        assert_eq!(search.params_type.as_deref(), Some("SearchIssuesRequest"));
+    }
+}
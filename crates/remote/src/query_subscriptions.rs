@@ -0,0 +1,369 @@
+//! Issue filters users subscribe to, and their incremental evaluation.
+//!
+//! Each subscription keeps the set of issues it matched when last
+//! evaluated. A mutation re-evaluates only the issue it touched against the
+//! project's subscriptions, and owners are notified when the issue enters or
+//! leaves their filter's results. See [`api_types::QuerySubscription`] for
+//! the filter syntax.
+
+use api_types::{Issue, IssuePriority, NotificationPayload, NotificationType};
+use sqlx::PgPool;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        issues::IssueRepository,
+        organization_members::is_member,
+        query_subscriptions::{IssueFacts, QuerySubscriptionError, QuerySubscriptionRepository},
+    },
+    notifications::send_issue_notifications,
+};
+
+const MAX_QUERY_LENGTH: usize = 500;
+
+#[derive(Debug, Error)]
+#[error("invalid filter: {0}")]
+pub struct InvalidFilter(String);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Assignee {
+    Me,
+    Nobody,
+    /// A username or email, lowercased.
+    Handle(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Condition {
+    Tag(Vec<String>),
+    Status(Vec<String>),
+    Priority(Vec<Option<IssuePriority>>),
+    Assignee(Vec<Assignee>),
+    Completed(bool),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Term {
+    negated: bool,
+    condition: Condition,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueFilter {
+    terms: Vec<Term>,
+}
+
+/// Split on whitespace outside double quotes, dropping the quotes.
+fn tokenize(query: &str) -> Result<Vec<String>, InvalidFilter> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in query.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if quoted {
+        return Err(InvalidFilter("unclosed quote".to_string()));
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    Ok(tokens)
+}
+
+fn values(key: &str, value: &str) -> Result<Vec<String>, InvalidFilter> {
+    let values: Vec<String> = value
+        .split(',')
+        .map(|value| value.trim().to_lowercase())
+        .filter(|value| !value.is_empty())
+        .collect();
+    if values.is_empty() {
+        return Err(InvalidFilter(format!("`{key}:` needs a value")));
+    }
+    Ok(values)
+}
+
+fn priority(value: &str) -> Result<Option<IssuePriority>, InvalidFilter> {
+    match value {
+        "urgent" => Ok(Some(IssuePriority::Urgent)),
+        "high" => Ok(Some(IssuePriority::High)),
+        "medium" => Ok(Some(IssuePriority::Medium)),
+        "low" => Ok(Some(IssuePriority::Low)),
+        "none" => Ok(None),
+        other => Err(InvalidFilter(format!("unknown priority `{other}`"))),
+    }
+}
+
+impl IssueFilter {
+    pub fn parse(query: &str) -> Result<Self, InvalidFilter> {
+        if query.len() > MAX_QUERY_LENGTH {
+            return Err(InvalidFilter(format!(
+                "filters are limited to {MAX_QUERY_LENGTH} characters"
+            )));
+        }
+        let mut terms = Vec::new();
+        for token in tokenize(query)? {
+            let (negated, token) = match token.strip_prefix('-') {
+                Some(rest) if !rest.is_empty() => (true, rest),
+                _ => (false, token.as_str()),
+            };
+            let condition = match token.split_once(':') {
+                Some((key, value)) => {
+                    let key = key.to_lowercase();
+                    let values = values(&key, value)?;
+                    match key.as_str() {
+                        "tag" => Condition::Tag(values),
+                        "status" => Condition::Status(values),
+                        "priority" => Condition::Priority(
+                            values
+                                .iter()
+                                .map(|value| priority(value))
+                                .collect::<Result<_, _>>()?,
+                        ),
+                        "assignee" => Condition::Assignee(
+                            values
+                                .into_iter()
+                                .map(|value| match value.as_str() {
+                                    "me" => Assignee::Me,
+                                    "none" => Assignee::Nobody,
+                                    _ => Assignee::Handle(value),
+                                })
+                                .collect(),
+                        ),
+                        "is" => match values.as_slice() {
+                            [value] if value == "open" => Condition::Completed(false),
+                            [value] if value == "completed" => Condition::Completed(true),
+                            _ => {
+                                return Err(InvalidFilter(
+                                    "`is:` takes `open` or `completed`".to_string(),
+                                ));
+                            }
+                        },
+                        other => return Err(InvalidFilter(format!("unknown key `{other}:`"))),
+                    }
+                }
+                None => Condition::Text(token.to_lowercase()),
+            };
+            terms.push(Term { negated, condition });
+        }
+        if terms.is_empty() {
+            return Err(InvalidFilter("filter is empty".to_string()));
+        }
+        Ok(Self { terms })
+    }
+
+    /// Whether the issue matches for the subscription of `subscriber_id`,
+    /// who `assignee:me` refers to.
+    pub fn matches(&self, issue: &IssueFacts, subscriber_id: Uuid) -> bool {
+        self.terms.iter().all(|term| {
+            let matched = match &term.condition {
+                Condition::Tag(tags) => issue
+                    .tag_names
+                    .iter()
+                    .any(|name| tags.contains(&name.to_lowercase())),
+                Condition::Status(statuses) => statuses.contains(&issue.status_name.to_lowercase()),
+                Condition::Priority(priorities) => priorities.contains(&issue.priority),
+                Condition::Assignee(assignees) => assignees.iter().any(|assignee| match assignee {
+                    Assignee::Me => issue.assignee_ids.contains(&subscriber_id),
+                    Assignee::Nobody => issue.assignee_ids.is_empty(),
+                    Assignee::Handle(handle) => issue
+                        .assignee_handles
+                        .iter()
+                        .any(|candidate| candidate.eq_ignore_ascii_case(handle)),
+                }),
+                Condition::Completed(completed) => issue.completed == *completed,
+                Condition::Text(text) => {
+                    issue.title.to_lowercase().contains(text)
+                        || issue.simple_id.to_lowercase() == *text
+                }
+            };
+            matched != term.negated
+        })
+    }
+}
+
+/// The project's issues currently matching the filter, for seeding a new or
+/// changed subscription's results.
+pub async fn current_matches(
+    pool: &PgPool,
+    project_id: Uuid,
+    subscriber_id: Uuid,
+    filter: &IssueFilter,
+) -> Result<Vec<Uuid>, QuerySubscriptionError> {
+    let issues = QuerySubscriptionRepository::issue_facts(pool, project_id, None).await?;
+    Ok(issues
+        .iter()
+        .filter(|issue| filter.matches(issue, subscriber_id))
+        .map(|issue| issue.id)
+        .collect())
+}
+
+/// Re-evaluate one issue against its project's subscriptions and notify
+/// owners whose results it entered or left. Failures are logged, not
+/// returned, so they never fail the mutation that triggered them.
+pub async fn evaluate_issue(
+    pool: &PgPool,
+    organization_id: Uuid,
+    actor_user_id: Uuid,
+    issue: &Issue,
+) {
+    if let Err(error) = try_evaluate_issue(pool, organization_id, actor_user_id, issue).await {
+        tracing::warn!(?error, issue_id = %issue.id, "failed to evaluate query subscriptions");
+    }
+}
+
+/// Like [`evaluate_issue`], for mutations that only have the issue's ID.
+pub async fn evaluate_issue_by_id(
+    pool: &PgPool,
+    organization_id: Uuid,
+    actor_user_id: Uuid,
+    issue_id: Uuid,
+) {
+    match IssueRepository::find_by_id(pool, issue_id).await {
+        Ok(Some(issue)) => evaluate_issue(pool, organization_id, actor_user_id, &issue).await,
+        Ok(None) => {}
+        Err(error) => {
+            tracing::warn!(?error, %issue_id, "failed to load issue for query subscriptions");
+        }
+    }
+}
+
+async fn try_evaluate_issue(
+    pool: &PgPool,
+    organization_id: Uuid,
+    actor_user_id: Uuid,
+    issue: &Issue,
+) -> Result<(), QuerySubscriptionError> {
+    let subscriptions =
+        QuerySubscriptionRepository::list_by_project(pool, issue.project_id).await?;
+    if subscriptions.is_empty() {
+        return Ok(());
+    }
+    let Some(facts) =
+        QuerySubscriptionRepository::issue_facts(pool, issue.project_id, Some(issue.id))
+            .await?
+            .into_iter()
+            .next()
+    else {
+        return Ok(());
+    };
+    let previously_matching =
+        QuerySubscriptionRepository::matching_subscriptions(pool, issue.id).await?;
+
+    for subscription in subscriptions {
+        let filter = match IssueFilter::parse(&subscription.query) {
+            Ok(filter) => filter,
+            Err(error) => {
+                tracing::warn!(?error, subscription_id = %subscription.id, "skipping subscription");
+                continue;
+            }
+        };
+        let matches = filter.matches(&facts, subscription.user_id);
+        if matches == previously_matching.contains(&subscription.id) {
+            continue;
+        }
+
+        let notification_type = if matches {
+            QuerySubscriptionRepository::add_match(pool, subscription.id, issue.id).await?;
+            NotificationType::IssueEnteredFilter
+        } else {
+            QuerySubscriptionRepository::remove_match(pool, subscription.id, issue.id).await?;
+            NotificationType::IssueLeftFilter
+        };
+
+        if subscription.user_id == actor_user_id
+            || !is_member(pool, organization_id, subscription.user_id)
+                .await
+                .unwrap_or(false)
+        {
+            continue;
+        }
+        send_issue_notifications(
+            pool,
+            organization_id,
+            actor_user_id,
+            &[subscription.user_id],
+            issue,
+            notification_type,
+            NotificationPayload {
+                subscription_id: Some(subscription.id),
+                subscription_name: Some(subscription.name.clone()),
+                ..Default::default()
+            },
+            None,
+            Some(issue.id),
+        )
+        .await;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn facts() -> IssueFacts {
+        IssueFacts {
+            id: Uuid::new_v4(),
+            simple_id: "APP-12".to_string(),
+            title: "Sanitize SQL in search".to_string(),
+            priority: Some(IssuePriority::High),
+            completed: false,
+            status_name: "In review".to_string(),
+            tag_names: vec!["Security".to_string(), "backend".to_string()],
+            assignee_ids: vec![],
+            assignee_handles: vec![],
+        }
+    }
+
+    #[test]
+    fn matches_all_terms() {
+        let issue = facts();
+        let subscriber = Uuid::new_v4();
+        let matches = |query: &str| {
+            IssueFilter::parse(query)
+                .unwrap()
+                .matches(&issue, subscriber)
+        };
+
+        assert!(matches("tag:security"));
+        assert!(matches(
+            "tag:frontend,security priority:urgent,high is:open"
+        ));
+        assert!(matches(r#"status:"in review" sql"#));
+        assert!(matches("assignee:none -tag:frontend"));
+        assert!(matches("app-12"));
+        assert!(!matches("tag:security assignee:me"));
+        assert!(!matches("-tag:security"));
+        assert!(!matches("priority:none"));
+        assert!(!matches("is:completed"));
+    }
+
+    #[test]
+    fn rejects_invalid_filters() {
+        for query in [
+            "",
+            "  ",
+            "owner:me",
+            "priority:someday",
+            "is:stale",
+            "tag:",
+            r#"status:"open"#,
+        ] {
+            assert!(
+                IssueFilter::parse(query).is_err(),
+                "{query:?} should be rejected"
+            );
+        }
+    }
+}
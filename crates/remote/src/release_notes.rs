@@ -0,0 +1,203 @@
+//! Release notes compiled from the issues completed in a window.
+//!
+//! Each issue is listed once: under the first of the requested tags it has,
+//! or its first tag by name when no tags were requested. Issues without a
+//! matching tag are listed last, under "Other".
+
+use api_types::{ReleaseNotes, ReleaseNotesIssue, ReleaseNotesPullRequest, ReleaseNotesSection};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::db::release_notes::CompletedIssue;
+
+const OTHER_SECTION: &str = "Other";
+
+fn release_notes_issue(issue: CompletedIssue) -> ReleaseNotesIssue {
+    ReleaseNotesIssue {
+        issue_id: issue.id,
+        simple_id: issue.simple_id,
+        title: issue.title,
+        completed_at: issue.completed_at,
+        pull_requests: issue
+            .pr_numbers
+            .into_iter()
+            .zip(issue.pr_urls)
+            .map(|(number, url)| ReleaseNotesPullRequest { number, url })
+            .collect(),
+    }
+}
+
+/// Group the issues into sections, in `tag_ids` order when given and by tag
+/// name otherwise.
+pub fn group(issues: Vec<CompletedIssue>, tag_ids: Option<&[Uuid]>) -> Vec<ReleaseNotesSection> {
+    let mut sections: Vec<ReleaseNotesSection> = Vec::new();
+    let mut other: Vec<ReleaseNotesIssue> = Vec::new();
+
+    for issue in issues {
+        let tag = match tag_ids {
+            Some(tag_ids) => tag_ids.iter().find_map(|tag_id| {
+                issue
+                    .tag_ids
+                    .iter()
+                    .position(|id| id == tag_id)
+                    .map(|index| (*tag_id, issue.tag_names[index].clone()))
+            }),
+            None => issue
+                .tag_ids
+                .first()
+                .copied()
+                .zip(issue.tag_names.first().cloned()),
+        };
+        let Some((tag_id, name)) = tag else {
+            other.push(release_notes_issue(issue));
+            continue;
+        };
+        let issue = release_notes_issue(issue);
+        match sections
+            .iter_mut()
+            .find(|section| section.tag_id == Some(tag_id))
+        {
+            Some(section) => section.issues.push(issue),
+            None => sections.push(ReleaseNotesSection {
+                tag_id: Some(tag_id),
+                title: name,
+                issues: vec![issue],
+            }),
+        }
+    }
+
+    match tag_ids {
+        Some(tag_ids) => sections
+            .sort_by_key(|section| tag_ids.iter().position(|id| Some(*id) == section.tag_id)),
+        None => sections.sort_by(|a, b| a.title.cmp(&b.title)),
+    }
+    if !other.is_empty() {
+        sections.push(ReleaseNotesSection {
+            tag_id: None,
+            title: OTHER_SECTION.to_string(),
+            issues: other,
+        });
+    }
+    sections
+}
+
+pub fn render_markdown(
+    project_name: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    sections: &[ReleaseNotesSection],
+) -> String {
+    let mut markdown = format!(
+        "# {project_name} release notes\n\n_{} to {}_\n",
+        from.format("%Y-%m-%d"),
+        to.format("%Y-%m-%d")
+    );
+    if sections.is_empty() {
+        markdown.push_str("\nNo issues were completed in this period.\n");
+        return markdown;
+    }
+    for section in sections {
+        markdown.push_str(&format!("\n## {}\n\n", section.title));
+        for issue in &section.issues {
+            markdown.push_str(&format!("- {} ({})", issue.title.trim(), issue.simple_id));
+            if !issue.pull_requests.is_empty() {
+                let links: Vec<String> = issue
+                    .pull_requests
+                    .iter()
+                    .map(|pr| format!("[#{}]({})", pr.number, pr.url))
+                    .collect();
+                markdown.push_str(&format!(" {}", links.join(", ")));
+            }
+            markdown.push('\n');
+        }
+    }
+    markdown
+}
+
+pub fn compile(
+    project_name: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    issues: Vec<CompletedIssue>,
+    tag_ids: Option<&[Uuid]>,
+) -> ReleaseNotes {
+    let sections = group(issues, tag_ids);
+    let markdown = render_markdown(project_name, from, to, &sections);
+    ReleaseNotes { sections, markdown }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn issue(simple_id: &str, tags: &[(Uuid, &str)], prs: &[i32]) -> CompletedIssue {
+        CompletedIssue {
+            id: Uuid::new_v4(),
+            simple_id: simple_id.to_string(),
+            title: format!("Issue {simple_id}"),
+            completed_at: Utc.with_ymd_and_hms(2026, 5, 2, 12, 0, 0).unwrap(),
+            tag_ids: tags.iter().map(|(id, _)| *id).collect(),
+            tag_names: tags.iter().map(|(_, name)| name.to_string()).collect(),
+            pr_numbers: prs.to_vec(),
+            pr_urls: prs
+                .iter()
+                .map(|number| format!("https://github.com/acme/app/pull/{number}"))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn groups_by_requested_tags_then_other() {
+        let bug = (Uuid::new_v4(), "bug");
+        let feature = (Uuid::new_v4(), "feature");
+        let ui = (Uuid::new_v4(), "ui");
+        let issues = vec![
+            issue("APP-1", &[bug, ui], &[]),
+            issue("APP-2", &[feature], &[]),
+            issue("APP-3", &[ui], &[]),
+            issue("APP-4", &[], &[]),
+        ];
+
+        let by_name = group(issues.clone(), None);
+        let titles: Vec<_> = by_name.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, vec!["bug", "feature", "ui", "Other"]);
+
+        let requested = group(issues, Some(&[feature.0, bug.0][..]));
+        let sections: Vec<_> = requested
+            .iter()
+            .map(|s| {
+                let ids: Vec<_> = s.issues.iter().map(|i| i.simple_id.as_str()).collect();
+                (s.title.as_str(), ids)
+            })
+            .collect();
+        assert_eq!(
+            sections,
+            vec![
+                ("feature", vec!["APP-2"]),
+                ("bug", vec!["APP-1"]),
+                ("Other", vec!["APP-3", "APP-4"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_markdown_with_pr_links() {
+        let from = Utc.with_ymd_and_hms(2026, 5, 1, 0, 0, 0).unwrap();
+        let to = Utc.with_ymd_and_hms(2026, 5, 15, 0, 0, 0).unwrap();
+        let notes = compile("App", from, to, vec![issue("APP-7", &[], &[12, 13])], None);
+
+        assert_eq!(
+            notes.markdown,
+            "# App release notes\n\n_2026-05-01 to 2026-05-15_\n\n## Other\n\n\
+             - Issue APP-7 (APP-7) [#12](https://github.com/acme/app/pull/12), \
+             [#13](https://github.com/acme/app/pull/13)\n"
+        );
+        assert!(
+            compile("App", from, to, Vec::new(), None)
+                .markdown
+                .contains("No issues")
+        );
+    }
+}
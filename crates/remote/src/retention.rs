@@ -0,0 +1,237 @@
+//! Organization retention policies and legal holds.
+//!
+//! A policy deletes closed issues some years after they were completed and
+//! attachments some years after they were uploaded. A daily task applies the
+//! policies, skipping projects on legal hold; a hold also stops users from
+//! deleting the project, its issues, comments or attachments, or its
+//! organization.
+
+use std::time::Duration;
+
+use api_types::{OrganizationRetentionPolicy, RetentionReport, UpdateRetentionPolicyRequest};
+use chrono::{DateTime, Months, Utc};
+use sqlx::PgPool;
+use thiserror::Error;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    audit::{self, AuditAction, AuditEvent},
+    azure_blob::AzureBlobService,
+    db::retention::{PurgeResult, RetentionError, RetentionRepository},
+};
+
+const MAX_RETENTION_YEARS: i16 = 100;
+const PURGE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Error)]
+#[error("invalid retention policy: {0}")]
+pub struct InvalidRetentionPolicy(String);
+
+fn validate_years(field: &str, years: Option<i16>) -> Result<(), InvalidRetentionPolicy> {
+    match years {
+        Some(years) if !(1..=MAX_RETENTION_YEARS).contains(&years) => Err(InvalidRetentionPolicy(
+            format!("{field} must be between 1 and {MAX_RETENTION_YEARS}"),
+        )),
+        _ => Ok(()),
+    }
+}
+
+pub fn normalize_policy(
+    organization_id: Uuid,
+    request: &UpdateRetentionPolicyRequest,
+) -> Result<OrganizationRetentionPolicy, InvalidRetentionPolicy> {
+    validate_years(
+        "closed_issue_retention_years",
+        request.closed_issue_retention_years,
+    )?;
+    validate_years(
+        "attachment_retention_years",
+        request.attachment_retention_years,
+    )?;
+    Ok(OrganizationRetentionPolicy {
+        organization_id,
+        closed_issue_retention_years: request.closed_issue_retention_years,
+        attachment_retention_years: request.attachment_retention_years,
+    })
+}
+
+/// The policy of organizations that never set one: keep everything.
+pub fn default_policy(organization_id: Uuid) -> OrganizationRetentionPolicy {
+    OrganizationRetentionPolicy {
+        organization_id,
+        closed_issue_retention_years: None,
+        attachment_retention_years: None,
+    }
+}
+
+pub async fn policy(
+    pool: &PgPool,
+    organization_id: Uuid,
+) -> Result<OrganizationRetentionPolicy, RetentionError> {
+    Ok(RetentionRepository::policy(pool, organization_id)
+        .await?
+        .unwrap_or_else(|| default_policy(organization_id)))
+}
+
+/// Data from before the returned time is due for deletion.
+pub fn cutoff(now: DateTime<Utc>, years: Option<i16>) -> Option<DateTime<Utc>> {
+    let months = u32::try_from(years?).ok()? * 12;
+    now.checked_sub_months(Months::new(months))
+}
+
+/// What the policy would delete if it ran at `now`.
+pub async fn report(
+    pool: &PgPool,
+    policy: OrganizationRetentionPolicy,
+    now: DateTime<Utc>,
+) -> Result<RetentionReport, RetentionError> {
+    let closed_issues_cutoff = cutoff(now, policy.closed_issue_retention_years);
+    let attachments_cutoff = cutoff(now, policy.attachment_retention_years);
+    let projects = RetentionRepository::report(
+        pool,
+        policy.organization_id,
+        closed_issues_cutoff,
+        attachments_cutoff,
+    )
+    .await?;
+
+    let deletable = projects.iter().filter(|project| !project.legal_hold);
+    Ok(RetentionReport {
+        closed_issues: deletable.clone().map(|project| project.closed_issues).sum(),
+        attachments: deletable.map(|project| project.attachments).sum(),
+        policy,
+        generated_at: now,
+        closed_issues_cutoff,
+        attachments_cutoff,
+        projects,
+    })
+}
+
+async fn delete_files(azure: Option<&AzureBlobService>, result: &PurgeResult) {
+    let Some(azure) = azure else {
+        return;
+    };
+    for blob in &result.blobs {
+        let paths = std::iter::once(&blob.blob_path).chain(&blob.thumbnail_blob_path);
+        for path in paths {
+            if let Err(error) = azure.delete_blob(path).await {
+                warn!(%error, blob_path = %path, "Failed to delete purged attachment file");
+            }
+        }
+    }
+}
+
+/// Apply one organization's policy at `now`.
+async fn purge_organization(
+    pool: &PgPool,
+    azure: Option<&AzureBlobService>,
+    policy: &OrganizationRetentionPolicy,
+    now: DateTime<Utc>,
+) -> Result<(), RetentionError> {
+    let closed_issues_cutoff = cutoff(now, policy.closed_issue_retention_years);
+    let attachments_cutoff = cutoff(now, policy.attachment_retention_years);
+    let project_ids = RetentionRepository::project_ids(pool, policy.organization_id).await?;
+
+    for project_id in project_ids {
+        let result = RetentionRepository::purge_project(
+            pool,
+            project_id,
+            closed_issues_cutoff,
+            attachments_cutoff,
+        )
+        .await?;
+        if result.closed_issues == 0 && result.attachments == 0 {
+            continue;
+        }
+        delete_files(azure, &result).await;
+
+        info!(
+            %project_id,
+            closed_issues = result.closed_issues,
+            attachments = result.attachments,
+            "Retention policy applied"
+        );
+        audit::emit(
+            AuditEvent::system(AuditAction::RetentionPurge)
+                .resource("project", Some(project_id))
+                .organization(policy.organization_id)
+                .description(format!(
+                    "Deleted {} closed issues and {} attachments",
+                    result.closed_issues, result.attachments
+                )),
+        );
+    }
+    Ok(())
+}
+
+/// Spawns a background task that applies retention policies daily. Call
+/// once during server startup.
+pub(crate) fn spawn_retention_task(
+    pool: PgPool,
+    azure: Option<AzureBlobService>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(PURGE_INTERVAL);
+        // Skip the immediate first tick so the server can finish starting up.
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+            let policies = match RetentionRepository::active_policies(&pool).await {
+                Ok(policies) => policies,
+                Err(error) => {
+                    warn!(?error, "Failed to load retention policies");
+                    continue;
+                }
+            };
+            for policy in &policies {
+                if let Err(error) =
+                    purge_organization(&pool, azure.as_ref(), policy, Utc::now()).await
+                {
+                    warn!(
+                        ?error,
+                        organization_id = %policy.organization_id,
+                        "Retention purge failed"
+                    );
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn cutoff_is_whole_years_back() {
+        let now = at("2031-06-15T12:00:00Z");
+        assert_eq!(cutoff(now, None), None);
+        assert_eq!(cutoff(now, Some(3)), Some(at("2028-06-15T12:00:00Z")));
+        // A leap day goes back to the last day of February.
+        assert_eq!(
+            cutoff(at("2028-02-29T00:00:00Z"), Some(1)),
+            Some(at("2027-02-28T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn rejects_out_of_range_years() {
+        let org = Uuid::nil();
+        let request = |years| UpdateRetentionPolicyRequest {
+            closed_issue_retention_years: Some(years),
+            attachment_retention_years: None,
+        };
+        assert!(normalize_policy(org, &request(0)).is_err());
+        assert!(normalize_policy(org, &request(101)).is_err());
+        let policy = normalize_policy(org, &request(7)).unwrap();
+        assert_eq!(policy.closed_issue_retention_years, Some(7));
+        assert_eq!(policy.attachment_retention_years, None);
+    }
+}
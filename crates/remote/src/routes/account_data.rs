@@ -0,0 +1,149 @@
+use api_types::{
+    AccountDataJob, AccountDataJobKind, DeleteAccountRequest, ListAccountDataJobsResponse,
+};
+use axum::{
+    Json, Router,
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    routing::{get, post},
+};
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::error::ErrorResponse;
+use crate::{
+    AppState,
+    account_data::job_response,
+    audit::{self, AuditAction, AuditEvent},
+    auth::RequestContext,
+    db::account_data::AccountDataRepository,
+};
+
+pub(super) fn router() -> Router<AppState> {
+    Router::new()
+        .route("/identity/data-export", post(request_export))
+        .route("/identity/deletion", post(request_deletion))
+        .route("/identity/data-jobs", get(list_jobs))
+        .route("/identity/data-jobs/{job_id}", get(get_job))
+}
+
+fn internal_error(error: &dyn std::fmt::Debug, message: &str) -> ErrorResponse {
+    tracing::error!(?error, "{message}");
+    ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+}
+
+fn bad_request(error: impl ToString) -> ErrorResponse {
+    ErrorResponse::new(StatusCode::BAD_REQUEST, error.to_string())
+}
+
+async fn queue_job(
+    state: &AppState,
+    ctx: &RequestContext,
+    kind: AccountDataJobKind,
+) -> Result<AccountDataJob, ErrorResponse> {
+    let job = AccountDataRepository::create_job(state.pool(), ctx.user.id, kind)
+        .await
+        .map_err(|error| internal_error(&error, "failed to queue account data job"))?
+        .ok_or_else(|| {
+            let message = match kind {
+                AccountDataJobKind::Export => "an export is already in progress",
+                AccountDataJobKind::Deletion => "the account is already being deleted",
+            };
+            ErrorResponse::new(StatusCode::CONFLICT, message)
+        })?;
+
+    Ok(job_response(state.azure_blob(), job))
+}
+
+#[instrument(
+    name = "account_data.request_export",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id)
+)]
+async fn request_export(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+) -> Result<(StatusCode, Json<AccountDataJob>), ErrorResponse> {
+    if state.azure_blob().is_none() {
+        return Err(ErrorResponse::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Attachment storage not available",
+        ));
+    }
+    let job = queue_job(&state, &ctx, AccountDataJobKind::Export).await?;
+
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+#[instrument(
+    name = "account_data.request_deletion",
+    skip(state, ctx, payload),
+    fields(user_id = %ctx.user.id)
+)]
+async fn request_deletion(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Json(payload): Json<DeleteAccountRequest>,
+) -> Result<(StatusCode, Json<AccountDataJob>), ErrorResponse> {
+    if !payload.email.trim().eq_ignore_ascii_case(&ctx.user.email) {
+        return Err(bad_request("email does not match the account"));
+    }
+
+    // Checked again when the job runs; this fails early in the common case.
+    let sole_admin_of = AccountDataRepository::sole_admin_organizations(state.pool(), ctx.user.id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to check organization admins"))?;
+    if !sole_admin_of.is_empty() {
+        return Err(ErrorResponse::new(
+            StatusCode::CONFLICT,
+            format!(
+                "make someone else an admin of {} or delete it first",
+                sole_admin_of.join(", ")
+            ),
+        ));
+    }
+
+    let job = queue_job(&state, &ctx, AccountDataJobKind::Deletion).await?;
+
+    audit::emit(
+        AuditEvent::from_request(&ctx, AuditAction::AccountDeletionRequest)
+            .resource("user", Some(ctx.user.id))
+            .http("POST", "/v1/identity/deletion", 202)
+            .description("Requested account deletion"),
+    );
+
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+#[instrument(name = "account_data.list_jobs", skip(state, ctx), fields(user_id = %ctx.user.id))]
+async fn list_jobs(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+) -> Result<Json<ListAccountDataJobsResponse>, ErrorResponse> {
+    let jobs = AccountDataRepository::list_jobs(state.pool(), ctx.user.id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to list account data jobs"))?
+        .into_iter()
+        .map(|job| job_response(state.azure_blob(), job))
+        .collect();
+
+    Ok(Json(ListAccountDataJobsResponse { jobs }))
+}
+
+#[instrument(
+    name = "account_data.get_job",
+    skip(state, ctx),
+    fields(job_id = %job_id, user_id = %ctx.user.id)
+)]
+async fn get_job(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<AccountDataJob>, ErrorResponse> {
+    let job = AccountDataRepository::find_job(state.pool(), job_id, ctx.user.id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load account data job"))?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "job not found"))?;
+
+    Ok(Json(job_response(state.azure_blob(), job)))
+}
@@ -19,13 +19,14 @@ use super::organization_members::{
 };
 use crate::{
     AppState,
-    attachments::thumbnail::ThumbnailService,
+    attachments::{media, thumbnail::ThumbnailService},
     auth::RequestContext,
     azure_blob::AzureBlobError,
     db::{
         attachments::{AttachmentError, AttachmentRepository},
         blobs::{BlobError, BlobRepository},
         pending_uploads::{PendingUploadError, PendingUploadRepository},
+        retention::{RetentionError, RetentionRepository},
     },
 };
 
@@ -122,6 +123,10 @@ pub enum RouteError {
     PendingUpload(#[from] PendingUploadError),
     #[error("thumbnail generation failed: {0}")]
     ThumbnailError(String),
+    #[error("retention error: {0}")]
+    Retention(#[from] RetentionError),
+    #[error("project is on legal hold")]
+    LegalHold,
 }
 
 impl IntoResponse for RouteError {
@@ -161,6 +166,11 @@ impl IntoResponse for RouteError {
                     "Thumbnail generation failed",
                 )
             }
+            RouteError::Retention(e) => {
+                tracing::error!(error = %e, "Retention error");
+                (StatusCode::INTERNAL_SERVER_ERROR, "Database error")
+            }
+            RouteError::LegalHold => (StatusCode::CONFLICT, "Project is on legal hold"),
         };
 
         let body = serde_json::json!({ "error": message });
@@ -268,25 +278,22 @@ async fn confirm_upload(
         }
 
         let blob_data = azure.download_blob(blob_path).await?;
+        let metadata = media::probe(&blob_data, payload.content_type.as_deref());
         let thumbnail_result =
-            ThumbnailService::generate(&blob_data, payload.content_type.as_deref())
+            ThumbnailService::generate(&blob_data, metadata.content_type.as_deref())
                 .map_err(|e| RouteError::ThumbnailError(e.to_string()))?;
 
         let _ = PendingUploadRepository::delete(state.pool(), pending.id).await;
 
-        let (thumbnail_blob_path, width, height) = match thumbnail_result {
+        let thumbnail_blob_path = match thumbnail_result {
             Some(thumb) => {
                 let thumb_path = format!("thumbnails/{}", blob_path);
                 azure
                     .upload_blob(&thumb_path, thumb.bytes, thumb.mime_type)
                     .await?;
-                (
-                    Some(thumb_path),
-                    Some(thumb.original_width as i32),
-                    Some(thumb.original_height as i32),
-                )
+                Some(thumb_path)
             }
-            None => (None, None, None),
+            None => None,
         };
 
         BlobRepository::create(
@@ -296,11 +303,12 @@ async fn confirm_upload(
             blob_path.clone(),
             thumbnail_blob_path,
             payload.filename.clone(),
-            payload.content_type.clone(),
+            metadata.content_type,
             payload.size_bytes,
             payload.hash.clone(),
-            width,
-            height,
+            metadata.width.and_then(|w| i32::try_from(w).ok()),
+            metadata.height.and_then(|h| i32::try_from(h).ok()),
+            metadata.duration_ms.and_then(|d| i64::try_from(d).ok()),
         )
         .await?
     };
@@ -460,6 +468,13 @@ async fn delete_attachment(
         .ok_or(RouteError::NotFound)?;
 
     ensure_attachment_access(&state, ctx.user.id, &attachment).await?;
+    if let Some(project_id) = AttachmentRepository::project_id(state.pool(), id).await?
+        && RetentionRepository::legal_hold(state.pool(), project_id)
+            .await?
+            .is_some()
+    {
+        return Err(RouteError::LegalHold);
+    }
 
     let blob_id = attachment.blob_id;
     AttachmentRepository::delete(state.pool(), id).await?;
@@ -1,11 +1,16 @@
+use api_types::{
+    BillingInvoice, BillingReceipt, ListBillingDocumentsQuery, ListInvoicesResponse,
+    ListReceiptsResponse, OrganizationUsageResponse,
+};
 use axum::{
     Json, Router,
     body::Bytes,
-    extract::{Extension, Path, State},
-    http::{HeaderMap, StatusCode},
+    extract::{Extension, Path, Query, State},
+    http::{HeaderMap, StatusCode, header},
     response::IntoResponse,
     routing::{get, post},
 };
+use chrono::Utc;
 use serde_json::{Value, json};
 use sqlx::PgPool;
 use uuid::Uuid;
@@ -14,8 +19,14 @@ use super::{error::ErrorResponse, organization_members::ensure_admin_access};
 use crate::{
     AppState,
     auth::RequestContext,
-    billing::{BillingError, BillingStatus, BillingStatusResponse, CreatePortalRequest},
-    db::organization_members,
+    billing::{
+        BillingError, BillingStatus, BillingStatusResponse, CreatePortalRequest,
+        stripe_api::{StripeApiClient, StripeApiError, StripeInvoice, stripe_timestamp},
+    },
+    db::{
+        agent_usage::{AgentUsageError, AgentUsageRepository},
+        organization_members,
+    },
 };
 
 pub fn public_router() -> Router<AppState> {
@@ -25,6 +36,19 @@ pub fn public_router() -> Router<AppState> {
 pub fn protected_router() -> Router<AppState> {
     Router::new()
         .route("/organizations/{org_id}/billing", get(get_billing_status))
+        .route("/organizations/{org_id}/billing/usage", get(get_usage))
+        .route(
+            "/organizations/{org_id}/billing/invoices",
+            get(list_invoices),
+        )
+        .route(
+            "/organizations/{org_id}/billing/invoices/{invoice_id}/pdf",
+            get(download_invoice_pdf),
+        )
+        .route(
+            "/organizations/{org_id}/billing/receipts",
+            get(list_receipts),
+        )
         .route(
             "/organizations/{org_id}/billing/portal",
             post(create_portal_session),
@@ -74,6 +98,46 @@ pub async fn get_billing_status(
     }
 }
 
+/// Agent minutes and active seats of the organization's current billing
+/// period.
+pub async fn get_usage(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<OrganizationUsageResponse>, ErrorResponse> {
+    organization_members::assert_membership(&state.pool, org_id, ctx.user.id)
+        .await
+        .map_err(|_| ErrorResponse::new(StatusCode::FORBIDDEN, "Access denied"))?;
+
+    let usage_error = |error: AgentUsageError| {
+        tracing::error!(?error, %org_id, "failed to load billing usage");
+        ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error")
+    };
+
+    let period = AgentUsageRepository::current_period(&state.pool, org_id, Utc::now())
+        .await
+        .map_err(usage_error)?;
+    let totals = AgentUsageRepository::totals(&state.pool, org_id, period)
+        .await
+        .map_err(usage_error)?;
+    let reported = AgentUsageRepository::reported(&state.pool, org_id, period.start)
+        .await
+        .map_err(usage_error)?;
+    let members = AgentUsageRepository::member_usage(&state.pool, org_id, period)
+        .await
+        .map_err(usage_error)?;
+
+    Ok(Json(OrganizationUsageResponse {
+        organization_id: org_id,
+        period_start: period.start,
+        period_end: period.end,
+        agent_minutes: totals.agent_minutes,
+        active_seats: totals.active_seats,
+        reported_agent_minutes: reported.agent_minutes,
+        members,
+    }))
+}
+
 pub async fn create_portal_session(
     State(state): State<AppState>,
     Extension(ctx): Extension<RequestContext>,
@@ -106,6 +170,177 @@ pub async fn create_portal_session(
     Ok(Json(session))
 }
 
+const DEFAULT_DOCUMENTS_PAGE_SIZE: u32 = 20;
+const MAX_DOCUMENTS_PAGE_SIZE: u32 = 100;
+
+/// The organization's payments customer, for admins only.
+async fn billing_customer(
+    state: &AppState,
+    org_id: Uuid,
+    user_id: Uuid,
+) -> Result<(Option<String>, &StripeApiClient), ErrorResponse> {
+    ensure_admin_access(&state.pool, org_id, user_id)
+        .await
+        .map_err(|_| ErrorResponse::new(StatusCode::FORBIDDEN, "Admin access required"))?;
+
+    let stripe = state.billing().stripe_api().ok_or_else(|| {
+        ErrorResponse::new(StatusCode::SERVICE_UNAVAILABLE, "Billing not configured")
+    })?;
+
+    let customer = organization_stripe_customer(&state.pool, org_id)
+        .await
+        .map_err(|_| ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    Ok((customer, stripe))
+}
+
+fn page_size(query: &ListBillingDocumentsQuery) -> u32 {
+    query
+        .limit
+        .unwrap_or(DEFAULT_DOCUMENTS_PAGE_SIZE)
+        .clamp(1, MAX_DOCUMENTS_PAGE_SIZE)
+}
+
+pub async fn list_invoices(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(org_id): Path<Uuid>,
+    Query(query): Query<ListBillingDocumentsQuery>,
+) -> Result<Json<ListInvoicesResponse>, ErrorResponse> {
+    let (customer, stripe) = billing_customer(&state, org_id, ctx.user.id).await?;
+    let Some(customer) = customer else {
+        return Ok(Json(ListInvoicesResponse {
+            invoices: Vec::new(),
+            has_more: false,
+        }));
+    };
+
+    let page = stripe
+        .list_invoices(
+            &customer,
+            page_size(&query),
+            query.starting_after.as_deref(),
+        )
+        .await
+        .map_err(stripe_error)?;
+
+    Ok(Json(ListInvoicesResponse {
+        invoices: page.data.into_iter().map(billing_invoice).collect(),
+        has_more: page.has_more,
+    }))
+}
+
+pub async fn list_receipts(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(org_id): Path<Uuid>,
+    Query(query): Query<ListBillingDocumentsQuery>,
+) -> Result<Json<ListReceiptsResponse>, ErrorResponse> {
+    let (customer, stripe) = billing_customer(&state, org_id, ctx.user.id).await?;
+    let Some(customer) = customer else {
+        return Ok(Json(ListReceiptsResponse {
+            receipts: Vec::new(),
+            has_more: false,
+        }));
+    };
+
+    let page = stripe
+        .list_charges(
+            &customer,
+            page_size(&query),
+            query.starting_after.as_deref(),
+        )
+        .await
+        .map_err(stripe_error)?;
+
+    Ok(Json(ListReceiptsResponse {
+        receipts: page
+            .data
+            .into_iter()
+            .map(|charge| BillingReceipt {
+                id: charge.id,
+                receipt_number: charge.receipt_number,
+                amount: charge.amount,
+                amount_refunded: charge.amount_refunded,
+                currency: charge.currency,
+                paid: charge.paid,
+                created_at: stripe_timestamp(charge.created),
+                receipt_url: charge.receipt_url,
+            })
+            .collect(),
+        has_more: page.has_more,
+    }))
+}
+
+pub async fn download_invoice_pdf(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path((org_id, invoice_id)): Path<(Uuid, String)>,
+) -> Result<impl IntoResponse, ErrorResponse> {
+    let not_found = || ErrorResponse::new(StatusCode::NOT_FOUND, "Invoice not found");
+
+    let (customer, stripe) = billing_customer(&state, org_id, ctx.user.id).await?;
+    let customer = customer.ok_or_else(not_found)?;
+    // Stripe IDs are alphanumeric; anything else can't be one of ours.
+    if !invoice_id
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return Err(not_found());
+    }
+
+    let invoice = stripe
+        .retrieve_invoice(&invoice_id)
+        .await
+        .map_err(|error| {
+            if error.is_not_found() {
+                not_found()
+            } else {
+                stripe_error(error)
+            }
+        })?;
+    if invoice.customer.as_deref() != Some(customer.as_str()) {
+        return Err(not_found());
+    }
+    let pdf_url = invoice.invoice_pdf.ok_or_else(not_found)?;
+
+    let pdf = stripe.download(&pdf_url).await.map_err(stripe_error)?;
+    let filename = invoice.number.unwrap_or(invoice.id);
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}.pdf\""),
+            ),
+        ],
+        pdf,
+    ))
+}
+
+fn billing_invoice(invoice: StripeInvoice) -> BillingInvoice {
+    BillingInvoice {
+        has_pdf: invoice.invoice_pdf.is_some(),
+        id: invoice.id,
+        number: invoice.number,
+        status: invoice.status,
+        currency: invoice.currency,
+        total: invoice.total,
+        amount_paid: invoice.amount_paid,
+        amount_due: invoice.amount_due,
+        created_at: stripe_timestamp(invoice.created),
+        period_start: stripe_timestamp(invoice.period_start),
+        period_end: stripe_timestamp(invoice.period_end),
+        hosted_invoice_url: invoice.hosted_invoice_url,
+    }
+}
+
+fn stripe_error(error: StripeApiError) -> ErrorResponse {
+    tracing::error!(?error, "Stripe API error");
+    ErrorResponse::new(StatusCode::BAD_GATEWAY, "Payment provider error")
+}
+
 pub async fn create_checkout_session() -> Result<StatusCode, ErrorResponse> {
     Err(ErrorResponse::new(
         StatusCode::GONE,
@@ -179,6 +414,19 @@ fn augment_billing_status(status: BillingStatusResponse, can_manage_billing: boo
     value
 }
 
+async fn organization_stripe_customer(
+    pool: &PgPool,
+    org_id: Uuid,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT stripe_customer_id FROM organization_billing WHERE organization_id = $1",
+        org_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map(Option::flatten)
+}
+
 async fn organization_has_billing_history(
     pool: &PgPool,
     org_id: Uuid,
@@ -0,0 +1,99 @@
+use api_types::CalendarFeedResponse;
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use chrono::Utc;
+use secrecy::ExposeSecret;
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::error::ErrorResponse;
+use crate::{AppState, auth::RequestContext, calendar, db::calendar::CalendarRepository};
+
+pub(super) fn public_router() -> Router<AppState> {
+    Router::new().route("/calendar/{token}/feed.ics", get(get_feed))
+}
+
+pub(super) fn protected_router() -> Router<AppState> {
+    Router::new()
+        .route("/calendar/feed", get(get_feed_url))
+        .route("/calendar/feed/rotate", post(rotate_feed_url))
+}
+
+fn feed_response(state: &AppState, user_id: Uuid, key: Uuid) -> CalendarFeedResponse {
+    let token = calendar::feed_token(state.jwt().secret.expose_secret().as_bytes(), user_id, key);
+    CalendarFeedResponse {
+        url: calendar::feed_url(&state.server_public_base_url, &token),
+    }
+}
+
+#[instrument(name = "calendar.get_feed_url", skip(state, ctx), fields(user_id = %ctx.user.id))]
+async fn get_feed_url(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+) -> Result<Json<CalendarFeedResponse>, ErrorResponse> {
+    let key = CalendarRepository::feed_key(state.pool(), ctx.user.id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, "failed to load calendar feed key");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+        })?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "user not found"))?;
+
+    Ok(Json(feed_response(&state, ctx.user.id, key)))
+}
+
+/// Issue a new feed URL, so calendars subscribed to the old one stop updating.
+#[instrument(name = "calendar.rotate_feed_url", skip(state, ctx), fields(user_id = %ctx.user.id))]
+async fn rotate_feed_url(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+) -> Result<Json<CalendarFeedResponse>, ErrorResponse> {
+    let key = CalendarRepository::rotate_feed_key(state.pool(), ctx.user.id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, "failed to rotate calendar feed key");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+        })?;
+
+    Ok(Json(feed_response(&state, ctx.user.id, key)))
+}
+
+#[instrument(name = "calendar.get_feed", skip(state, token))]
+async fn get_feed(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<Response, ErrorResponse> {
+    let internal_error = |error: &dyn std::fmt::Debug, message: &str| {
+        tracing::error!(?error, "{message}");
+        ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+    };
+    let not_found = || ErrorResponse::new(StatusCode::NOT_FOUND, "calendar feed not found");
+
+    let user_id = calendar::token_user_id(&token).ok_or_else(not_found)?;
+    let key = CalendarRepository::feed_key(state.pool(), user_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load calendar feed key"))?
+        .ok_or_else(not_found)?;
+    if !calendar::verify_feed_token(state.jwt().secret.expose_secret().as_bytes(), &token, key) {
+        return Err(not_found());
+    }
+
+    let issues = CalendarRepository::list_due_issues(state.pool(), user_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to list due issues"))?;
+    let feed = calendar::render_feed(&issues, &state.server_public_base_url, Utc::now());
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/calendar; charset=utf-8"),
+            (header::CACHE_CONTROL, "private, max-age=900"),
+        ],
+        feed,
+    )
+        .into_response())
+}
@@ -0,0 +1,291 @@
+use api_types::{
+    CreateDocumentRequest, Document, DocumentRevision, ListDocumentRevisionsResponse,
+    ListDocumentsResponse, SearchDocumentsRequest, UpdateDocumentRequest,
+};
+use axum::{
+    Json, Router,
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    routing::{get, post},
+};
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{
+    error::ErrorResponse,
+    organization_members::{ensure_issue_access, ensure_project_access},
+};
+use crate::{AppState, auth::RequestContext, db::documents::DocumentRepository};
+
+const MAX_TITLE_LENGTH: usize = 200;
+const MAX_CONTENT_LENGTH: usize = 1024 * 1024;
+const DEFAULT_SEARCH_LIMIT: i64 = 20;
+const MAX_SEARCH_LIMIT: i64 = 100;
+
+pub(super) fn router() -> Router<AppState> {
+    Router::new()
+        .route("/projects/{project_id}/documents", get(list_documents))
+        .route("/issues/{issue_id}/documents", get(list_issue_documents))
+        .route("/documents", post(create_document))
+        .route("/documents/search", post(search_documents))
+        .route(
+            "/documents/{document_id}",
+            get(get_document)
+                .patch(update_document)
+                .delete(delete_document),
+        )
+        .route(
+            "/documents/{document_id}/revisions",
+            get(list_document_revisions),
+        )
+        .route(
+            "/documents/{document_id}/revisions/{revision}",
+            get(get_document_revision),
+        )
+}
+
+fn internal_error(error: &dyn std::fmt::Debug, message: &str) -> ErrorResponse {
+    tracing::error!(?error, "{message}");
+    ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+}
+
+fn validate_title(title: &str) -> Result<&str, ErrorResponse> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "title must not be empty",
+        ));
+    }
+    if title.chars().count() > MAX_TITLE_LENGTH {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            format!("title must be at most {MAX_TITLE_LENGTH} characters"),
+        ));
+    }
+    Ok(title)
+}
+
+fn validate_content(content: &str) -> Result<(), ErrorResponse> {
+    if content.len() > MAX_CONTENT_LENGTH {
+        return Err(ErrorResponse::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "document content is limited to 1 MiB",
+        ));
+    }
+    Ok(())
+}
+
+/// Load a document in a project the requester can access.
+async fn accessible_document(
+    state: &AppState,
+    ctx: &RequestContext,
+    document_id: Uuid,
+) -> Result<Document, ErrorResponse> {
+    let document = DocumentRepository::find_by_id(state.pool(), document_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load document"))?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "document not found"))?;
+    ensure_project_access(state.pool(), ctx.user.id, document.project_id).await?;
+    Ok(document)
+}
+
+#[instrument(
+    name = "documents.list_documents",
+    skip(state, ctx),
+    fields(project_id = %project_id, user_id = %ctx.user.id)
+)]
+async fn list_documents(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+) -> Result<Json<ListDocumentsResponse>, ErrorResponse> {
+    ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
+
+    let documents = DocumentRepository::list_by_project(state.pool(), project_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to list documents"))?;
+
+    Ok(Json(ListDocumentsResponse { documents }))
+}
+
+/// Documents that mention the issue.
+#[instrument(
+    name = "documents.list_issue_documents",
+    skip(state, ctx),
+    fields(issue_id = %issue_id, user_id = %ctx.user.id)
+)]
+async fn list_issue_documents(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(issue_id): Path<Uuid>,
+) -> Result<Json<ListDocumentsResponse>, ErrorResponse> {
+    ensure_issue_access(state.pool(), ctx.user.id, issue_id).await?;
+
+    let documents = DocumentRepository::list_by_issue(state.pool(), issue_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to list issue documents"))?;
+
+    Ok(Json(ListDocumentsResponse { documents }))
+}
+
+#[instrument(
+    name = "documents.search_documents",
+    skip(state, ctx, payload),
+    fields(project_id = %payload.project_id, user_id = %ctx.user.id)
+)]
+async fn search_documents(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Json(payload): Json<SearchDocumentsRequest>,
+) -> Result<Json<ListDocumentsResponse>, ErrorResponse> {
+    ensure_project_access(state.pool(), ctx.user.id, payload.project_id).await?;
+    let search = payload.search.trim();
+    if search.is_empty() {
+        return Ok(Json(ListDocumentsResponse { documents: vec![] }));
+    }
+    let limit = payload
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    let documents = DocumentRepository::search(state.pool(), payload.project_id, search, limit)
+        .await
+        .map_err(|error| internal_error(&error, "failed to search documents"))?;
+
+    Ok(Json(ListDocumentsResponse { documents }))
+}
+
+#[instrument(
+    name = "documents.create_document",
+    skip(state, ctx, payload),
+    fields(project_id = %payload.project_id, user_id = %ctx.user.id)
+)]
+async fn create_document(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Json(payload): Json<CreateDocumentRequest>,
+) -> Result<Json<Document>, ErrorResponse> {
+    ensure_project_access(state.pool(), ctx.user.id, payload.project_id).await?;
+    let title = validate_title(&payload.title)?;
+    let content = payload.content.as_deref().unwrap_or_default();
+    validate_content(content)?;
+
+    let document = DocumentRepository::create(
+        state.pool(),
+        payload.project_id,
+        ctx.user.id,
+        title,
+        content,
+    )
+    .await
+    .map_err(|error| internal_error(&error, "failed to create document"))?;
+
+    Ok(Json(document))
+}
+
+#[instrument(
+    name = "documents.get_document",
+    skip(state, ctx),
+    fields(document_id = %document_id, user_id = %ctx.user.id)
+)]
+async fn get_document(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(document_id): Path<Uuid>,
+) -> Result<Json<Document>, ErrorResponse> {
+    Ok(Json(accessible_document(&state, &ctx, document_id).await?))
+}
+
+#[instrument(
+    name = "documents.update_document",
+    skip(state, ctx, payload),
+    fields(document_id = %document_id, user_id = %ctx.user.id)
+)]
+async fn update_document(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(document_id): Path<Uuid>,
+    Json(payload): Json<UpdateDocumentRequest>,
+) -> Result<Json<Document>, ErrorResponse> {
+    let existing = accessible_document(&state, &ctx, document_id).await?;
+    let title = validate_title(payload.title.as_deref().unwrap_or(&existing.title))?;
+    let content = payload.content.as_deref().unwrap_or(&existing.content);
+    validate_content(content)?;
+
+    let document = DocumentRepository::update(
+        state.pool(),
+        document_id,
+        payload.revision,
+        ctx.user.id,
+        title,
+        content,
+    )
+    .await
+    .map_err(|error| internal_error(&error, "failed to update document"))?
+    .ok_or_else(|| {
+        ErrorResponse::new(
+            StatusCode::CONFLICT,
+            "document was changed since this revision; reload and reapply your edits",
+        )
+    })?;
+
+    Ok(Json(document))
+}
+
+#[instrument(
+    name = "documents.delete_document",
+    skip(state, ctx),
+    fields(document_id = %document_id, user_id = %ctx.user.id)
+)]
+async fn delete_document(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(document_id): Path<Uuid>,
+) -> Result<StatusCode, ErrorResponse> {
+    accessible_document(&state, &ctx, document_id).await?;
+    DocumentRepository::delete(state.pool(), document_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to delete document"))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[instrument(
+    name = "documents.list_document_revisions",
+    skip(state, ctx),
+    fields(document_id = %document_id, user_id = %ctx.user.id)
+)]
+async fn list_document_revisions(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(document_id): Path<Uuid>,
+) -> Result<Json<ListDocumentRevisionsResponse>, ErrorResponse> {
+    accessible_document(&state, &ctx, document_id).await?;
+
+    let revisions = DocumentRepository::list_revisions(state.pool(), document_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to list document revisions"))?;
+
+    Ok(Json(ListDocumentRevisionsResponse { revisions }))
+}
+
+#[instrument(
+    name = "documents.get_document_revision",
+    skip(state, ctx),
+    fields(document_id = %document_id, user_id = %ctx.user.id)
+)]
+async fn get_document_revision(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path((document_id, revision)): Path<(Uuid, i32)>,
+) -> Result<Json<DocumentRevision>, ErrorResponse> {
+    accessible_document(&state, &ctx, document_id).await?;
+
+    let revision = DocumentRepository::find_revision(state.pool(), document_id, revision)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load document revision"))?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "revision not found"))?;
+
+    Ok(Json(revision))
+}
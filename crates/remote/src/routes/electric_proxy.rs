@@ -9,7 +9,7 @@ use axum::{
 use futures::TryStreamExt;
 use secrecy::ExposeSecret;
 use serde::Deserialize;
-use tracing::error;
+use tracing::{error, warn};
 use uuid::Uuid;
 
 use crate::{AppState, shape_definition::ShapeExport};
@@ -27,9 +27,192 @@ pub(crate) struct ShapeQuery {
     pub params: HashMap<String, String>,
 }
 
-const ELECTRIC_PARAMS: &[&str] = &["offset", "handle", "live", "cursor", "columns"];
+const ELECTRIC_PARAMS: &[&str] = &["offset", "handle", "live", "cursor"];
 const ELECTRIC_STICKY_HEADER: &str = "x-vk-electric-sticky";
 
+/// Params that define which rows a shape covers. They are only ever set by
+/// the proxy; a request that tries to set them is rejected.
+const SCOPE_PARAMS: &[&str] = &["table", "where", "params", "replica", "secret"];
+
+/// Tables that may be synced, and the columns each may expose. Requests for
+/// other tables or columns are rejected, and shapes sync only these columns,
+/// so columns added to a table later stay private until listed here.
+const TABLE_COLUMNS: &[(&str, &[&str])] = &[
+    (
+        "projects",
+        &[
+            "id",
+            "organization_id",
+            "name",
+            "color",
+            "sort_order",
+            "created_at",
+            "updated_at",
+        ],
+    ),
+    (
+        "notifications",
+        &[
+            "id",
+            "organization_id",
+            "user_id",
+            "notification_type",
+            "payload",
+            "issue_id",
+            "comment_id",
+            "seen",
+            "dismissed_at",
+            "created_at",
+        ],
+    ),
+    (
+        "organization_member_metadata",
+        &[
+            "organization_id",
+            "user_id",
+            "role",
+            "joined_at",
+            "last_seen_at",
+        ],
+    ),
+    (
+        "users",
+        &[
+            "id",
+            "email",
+            "first_name",
+            "last_name",
+            "username",
+            "created_at",
+            "updated_at",
+        ],
+    ),
+    (
+        "notification_unread_counts",
+        &[
+            "id",
+            "user_id",
+            "organization_id",
+            "unread_count",
+            "updated_at",
+        ],
+    ),
+    ("tags", &["id", "project_id", "name", "color"]),
+    (
+        "organization_tags",
+        &["id", "organization_id", "name", "color"],
+    ),
+    (
+        "project_statuses",
+        &[
+            "id",
+            "project_id",
+            "name",
+            "color",
+            "sort_order",
+            "hidden",
+            "created_at",
+        ],
+    ),
+    (
+        "issues",
+        &[
+            "id",
+            "project_id",
+            "issue_number",
+            "simple_id",
+            "status_id",
+            "title",
+            "description",
+            "priority",
+            "start_date",
+            "target_date",
+            "completed_at",
+            "sort_order",
+            "parent_issue_id",
+            "parent_issue_sort_order",
+            "extension_metadata",
+            "creator_user_id",
+            "created_at",
+            "updated_at",
+        ],
+    ),
+    (
+        "workspaces",
+        &[
+            "id",
+            "project_id",
+            "owner_user_id",
+            "issue_id",
+            "local_workspace_id",
+            "name",
+            "archived",
+            "files_changed",
+            "lines_added",
+            "lines_removed",
+            "created_at",
+            "updated_at",
+        ],
+    ),
+    (
+        "issue_assignees",
+        &["id", "issue_id", "user_id", "assigned_at"],
+    ),
+    ("issue_followers", &["id", "issue_id", "user_id"]),
+    ("issue_tags", &["id", "issue_id", "tag_id"]),
+    (
+        "issue_organization_tags",
+        &["id", "issue_id", "organization_tag_id"],
+    ),
+    (
+        "issue_relationships",
+        &[
+            "id",
+            "issue_id",
+            "related_issue_id",
+            "relationship_type",
+            "created_at",
+        ],
+    ),
+    (
+        "pull_requests",
+        &[
+            "id",
+            "url",
+            "number",
+            "status",
+            "merged_at",
+            "merge_commit_sha",
+            "target_branch_name",
+            "project_id",
+            "issue_id",
+            "workspace_id",
+            "created_at",
+            "updated_at",
+        ],
+    ),
+    (
+        "pull_request_issues",
+        &["id", "pull_request_id", "issue_id"],
+    ),
+    (
+        "issue_comments",
+        &[
+            "id",
+            "issue_id",
+            "author_id",
+            "parent_id",
+            "message",
+            "created_at",
+            "updated_at",
+        ],
+    ),
+    (
+        "issue_comment_reactions",
+        &["id", "comment_id", "user_id", "emoji", "created_at"],
+    ),
+];
+
 pub(crate) fn router() -> Router<AppState> {
     let mut router = Router::new();
     for route in crate::shape_routes::all_shape_routes() {
@@ -38,10 +221,73 @@ pub(crate) fn router() -> Router<AppState> {
     router
 }
 
+fn allowed_columns(table: &str) -> Option<&'static [&'static str]> {
+    TABLE_COLUMNS
+        .iter()
+        .find(|(name, _)| *name == table)
+        .map(|(_, columns)| *columns)
+}
+
+/// Whether `key` would set one of the scope params, including the indexed
+/// `params[n]` form.
+fn is_scope_param(key: &str) -> bool {
+    let name = key.split('[').next().unwrap_or(key);
+    SCOPE_PARAMS.contains(&name)
+}
+
+/// The `columns` param to send: the client's selection if every column is
+/// allowed for the table, otherwise all allowed columns.
+fn resolve_columns(allowed: &[&str], requested: Option<&str>) -> Result<String, ProxyError> {
+    let Some(requested) = requested else {
+        return Ok(allowed.join(","));
+    };
+    let mut columns = Vec::new();
+    for column in requested.split(',') {
+        let column = column.trim().trim_matches('"');
+        if !allowed.contains(&column) {
+            return Err(ProxyError::BadRequest(format!(
+                "column '{column}' is not available"
+            )));
+        }
+        columns.push(column);
+    }
+    Ok(columns.join(","))
+}
+
+/// Check that the shape's where clause uses every scope param, so no row
+/// outside the caller's org, project, issue or user can match.
+fn check_scope_predicates(shape: &dyn ShapeExport, params: &[String]) -> Result<(), ProxyError> {
+    if params.is_empty() || params.len() != shape.params().len() {
+        return Err(ProxyError::InvalidConfig(format!(
+            "shape {} binds {} params but its scope provides {}",
+            shape.name(),
+            shape.params().len(),
+            params.len()
+        )));
+    }
+    let where_clause = shape.where_clause();
+    for i in 1..=params.len() {
+        let placeholder = format!("${i}");
+        let used = where_clause.match_indices(&placeholder).any(|(at, _)| {
+            !where_clause[at + placeholder.len()..].starts_with(|c: char| c.is_ascii_digit())
+        });
+        if !used {
+            return Err(ProxyError::InvalidConfig(format!(
+                "shape {} does not filter on {}",
+                shape.name(),
+                placeholder
+            )));
+        }
+    }
+    Ok(())
+}
+
 /// Proxy a Shape request to Electric for a specific table.
 ///
-/// The table and where clause are set server-side (not from client params)
-/// to prevent unauthorized access to other tables or data.
+/// The table, where clause, params and columns are set server-side (not
+/// from client params) to prevent unauthorized access to other tables or
+/// data. `electric_params` come from the authenticated request and fill the
+/// where clause's scope predicates.
 pub(crate) async fn proxy_table(
     state: &AppState,
     shape: &dyn ShapeExport,
@@ -49,6 +295,17 @@ pub(crate) async fn proxy_table(
     electric_params: &[String],
     session_id: Uuid,
 ) -> Result<Response, ProxyError> {
+    if let Some(key) = client_params.keys().find(|key| is_scope_param(key)) {
+        return Err(ProxyError::BadRequest(format!(
+            "'{key}' is set by the server"
+        )));
+    }
+    let allowed = allowed_columns(shape.table()).ok_or_else(|| {
+        ProxyError::Authorization(format!("table {} is not allowed", shape.table()))
+    })?;
+    check_scope_predicates(shape, electric_params)?;
+    let columns = resolve_columns(allowed, client_params.get("columns").map(String::as_str))?;
+
     // Build the Electric URL
     let mut origin_url = url::Url::parse(&state.config.electric_url)
         .map_err(|e| ProxyError::InvalidConfig(format!("invalid electric_url: {e}")))?;
@@ -72,6 +329,10 @@ pub(crate) async fn proxy_table(
             .append_pair(&format!("params[{}]", i + 1), param);
     }
 
+    origin_url
+        .query_pairs_mut()
+        .append_pair("columns", &columns);
+
     // Forward safe client params
     for (key, value) in client_params {
         if ELECTRIC_PARAMS.contains(&key.as_str()) {
@@ -120,6 +381,7 @@ pub(crate) enum ProxyError {
     Connection(reqwest::Error),
     InvalidConfig(String),
     Authorization(String),
+    BadRequest(String),
 }
 
 impl IntoResponse for ProxyError {
@@ -141,6 +403,49 @@ impl IntoResponse for ProxyError {
                 error!(%msg, "authorization failed for Electric proxy");
                 (StatusCode::FORBIDDEN, "forbidden").into_response()
             }
+            ProxyError::BadRequest(msg) => {
+                warn!(%msg, "rejected Electric shape request");
+                (StatusCode::BAD_REQUEST, msg).into_response()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_shape_is_allowlisted_and_scoped() {
+        for route in crate::shape_routes::all_shape_routes() {
+            let shape = route.shape;
+            let columns = allowed_columns(shape.table())
+                .unwrap_or_else(|| panic!("{} is not allowlisted", shape.table()));
+            assert!(!columns.is_empty());
+            let params: Vec<String> = shape.params().iter().map(|p| p.to_string()).collect();
+            assert!(
+                check_scope_predicates(shape, &params).is_ok(),
+                "{} is not scoped by its params",
+                shape.name()
+            );
         }
     }
+
+    #[test]
+    fn restricts_columns_and_scope_params() {
+        let allowed = allowed_columns("users").unwrap();
+        assert_eq!(resolve_columns(allowed, None).unwrap(), allowed.join(","));
+        assert_eq!(
+            resolve_columns(allowed, Some("id, \"email\"")).unwrap(),
+            "id,email"
+        );
+        assert!(matches!(
+            resolve_columns(allowed, Some("id,password_hash")),
+            Err(ProxyError::BadRequest(_))
+        ));
+
+        assert!(is_scope_param("where"));
+        assert!(is_scope_param("params[1]"));
+        assert!(!is_scope_param("offset"));
+    }
 }
@@ -3,7 +3,7 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
 };
-use serde_json::json;
+use serde_json::{Value, json};
 
 use crate::db::identity_errors::IdentityError;
 
@@ -11,6 +11,8 @@ use crate::db::identity_errors::IdentityError;
 pub struct ErrorResponse {
     status: StatusCode,
     message: String,
+    /// Structured detail sent next to `error`, under the given key.
+    details: Option<(&'static str, Value)>,
 }
 
 impl ErrorResponse {
@@ -18,13 +20,66 @@ impl ErrorResponse {
         Self {
             status,
             message: message.into(),
+            details: None,
+        }
+    }
+
+    /// A 409 carrying a `MutationConflict` for the client to merge.
+    pub fn conflict(message: impl Into<String>, conflict: Value) -> Self {
+        Self {
+            status: StatusCode::CONFLICT,
+            message: message.into(),
+            details: Some(("conflict", conflict)),
+        }
+    }
+
+    /// A 402 carrying an `UpgradeRequiredError` naming the missing
+    /// entitlement.
+    pub fn upgrade_required(message: impl Into<String>, upgrade: Value) -> Self {
+        Self {
+            status: StatusCode::PAYMENT_REQUIRED,
+            message: message.into(),
+            details: Some(("upgrade", upgrade)),
+        }
+    }
+
+    /// A 403 carrying an `IpNotAllowedError` naming the organization whose
+    /// allowlist rejected the request.
+    pub fn ip_not_allowed(message: impl Into<String>, blocked: Value) -> Self {
+        Self {
+            status: StatusCode::FORBIDDEN,
+            message: message.into(),
+            details: Some(("ip_not_allowed", blocked)),
+        }
+    }
+
+    /// A 412 carrying the stored row, for an `If-Match` naming an older
+    /// version of it.
+    pub fn precondition_failed(message: impl Into<String>, current: Value) -> Self {
+        Self {
+            status: StatusCode::PRECONDITION_FAILED,
+            message: message.into(),
+            details: Some(("current", current)),
+        }
+    }
+
+    /// A 422 carrying a `StatusTransitionError` naming the allowed statuses.
+    pub fn transition(message: impl Into<String>, transition: Value) -> Self {
+        Self {
+            status: StatusCode::UNPROCESSABLE_ENTITY,
+            message: message.into(),
+            details: Some(("transition", transition)),
         }
     }
 }
 
 impl IntoResponse for ErrorResponse {
     fn into_response(self) -> Response {
-        (self.status, Json(json!({ "error": self.message }))).into_response()
+        let mut body = json!({ "error": self.message });
+        if let Some((key, details)) = self.details {
+            body[key] = details;
+        }
+        (self.status, Json(body)).into_response()
     }
 }
 
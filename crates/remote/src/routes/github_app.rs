@@ -12,15 +12,25 @@ use serde::{Deserialize, Serialize};
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
-use super::error::ErrorResponse;
+use super::{
+    error::ErrorResponse,
+    organization_members::{ensure_admin_access, ensure_project_access},
+};
 use crate::{
     AppState,
     auth::RequestContext,
     db::{
-        github_app::GitHubAppRepository2, identity_errors::IdentityError,
-        organizations::OrganizationRepository, reviews::ReviewRepository,
+        github_app::GitHubAppRepository2,
+        identity_errors::IdentityError,
+        issue_code_references::IssueCodeReferenceRepository,
+        organizations::OrganizationRepository,
+        project_github_teams::{ProjectGitHubTeam, ProjectGitHubTeamRepository},
+        reviews::ReviewRepository,
+    },
+    github_app::{
+        CodeMentionEvent, GitHubAppError, PrReviewParams, PrReviewService, TeamSyncError,
+        from_pull_request, from_push, sync_installation, sync_project, verify_webhook_signature,
     },
-    github_app::{PrReviewParams, PrReviewService, verify_webhook_signature},
 };
 
 // ========== Public Routes ==========
@@ -53,6 +63,16 @@ pub(super) fn protected_router() -> Router<AppState> {
             "/organizations/{org_id}/github-app/repositories/{repo_id}/review-enabled",
             patch(update_repo_review_enabled),
         )
+        .route(
+            "/projects/{project_id}/github-team",
+            get(get_project_team)
+                .put(link_project_team)
+                .delete(unlink_project_team),
+        )
+        .route(
+            "/projects/{project_id}/github-team/sync",
+            post(sync_project_team),
+        )
         .route("/debug/pr-review/trigger", post(trigger_pr_review))
 }
 
@@ -117,6 +137,38 @@ struct BulkUpdateReviewEnabledResponse {
     pub updated_count: u64,
 }
 
+#[derive(Debug, Deserialize)]
+struct LinkProjectTeamRequest {
+    /// Slug of a team in the GitHub organization the App is installed on
+    pub team_slug: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ProjectTeamResponse {
+    pub project_id: Uuid,
+    pub github_account_login: Option<String>,
+    pub team_slug: String,
+    pub member_user_ids: Vec<Uuid>,
+    /// Team members without a GitHub-linked account in the organization
+    pub unmatched_logins: Vec<String>,
+    pub last_synced_at: Option<String>,
+    pub last_sync_error: Option<String>,
+}
+
+impl From<ProjectGitHubTeam> for ProjectTeamResponse {
+    fn from(team: ProjectGitHubTeam) -> Self {
+        Self {
+            project_id: team.project_id,
+            github_account_login: team.github_account_login,
+            team_slug: team.team_slug,
+            member_user_ids: team.member_user_ids,
+            unmatched_logins: team.unmatched_logins,
+            last_synced_at: team.last_synced_at.map(|t| t.to_rfc3339()),
+            last_sync_error: team.last_sync_error,
+        }
+    }
+}
+
 // ========== Protected Route Handlers ==========
 
 /// GET /v1/organizations/:org_id/github-app/install-url
@@ -459,6 +511,167 @@ async fn bulk_update_review_enabled(
     Ok(Json(BulkUpdateReviewEnabledResponse { updated_count }))
 }
 
+// ========== Project Team Handlers ==========
+
+async fn load_project_team(
+    state: &AppState,
+    project_id: Uuid,
+) -> Result<ProjectGitHubTeam, ErrorResponse> {
+    ProjectGitHubTeamRepository::find(state.pool(), project_id)
+        .await
+        .map_err(|e| {
+            error!(?e, "Failed to get project GitHub team");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error")
+        })?
+        .ok_or_else(|| {
+            ErrorResponse::new(
+                StatusCode::NOT_FOUND,
+                "Project is not synced from a GitHub team",
+            )
+        })
+}
+
+/// Sync the project's team, returning its state afterwards. GitHub failures
+/// are recorded on the link rather than returned.
+async fn sync_and_reload(
+    state: &AppState,
+    github_app: &crate::github_app::GitHubAppService,
+    project_id: Uuid,
+) -> Result<ProjectTeamResponse, ErrorResponse> {
+    let team = load_project_team(state, project_id).await?;
+    if let Err(TeamSyncError::Database(e)) = sync_project(state.pool(), github_app, &team).await {
+        error!(?e, "Failed to store GitHub team sync");
+        return Err(ErrorResponse::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error",
+        ));
+    }
+    Ok(load_project_team(state, project_id).await?.into())
+}
+
+/// GET /v1/projects/:project_id/github-team
+/// Returns the GitHub team the project's members are synced from
+async fn get_project_team(
+    State(state): State<AppState>,
+    axum::extract::Extension(ctx): axum::extract::Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ErrorResponse> {
+    ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
+    let team = load_project_team(&state, project_id).await?;
+
+    Ok(Json(ProjectTeamResponse::from(team)))
+}
+
+/// PUT /v1/projects/:project_id/github-team
+/// Limits the project to a GitHub team's members and syncs them
+async fn link_project_team(
+    State(state): State<AppState>,
+    axum::extract::Extension(ctx): axum::extract::Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+    Json(payload): Json<LinkProjectTeamRequest>,
+) -> Result<impl IntoResponse, ErrorResponse> {
+    let github_app = state.github_app().ok_or_else(|| {
+        ErrorResponse::new(StatusCode::NOT_IMPLEMENTED, "GitHub App not configured")
+    })?;
+
+    let org_id = ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
+    ensure_admin_access(state.pool(), org_id, ctx.user.id).await?;
+
+    let team_slug = payload.team_slug.trim();
+    if team_slug.is_empty() {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "team_slug must not be empty",
+        ));
+    }
+
+    let gh_repo = GitHubAppRepository2::new(state.pool());
+    let installation = gh_repo
+        .get_by_organization(org_id)
+        .await
+        .map_err(|_| ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "GitHub App not installed"))?;
+
+    if installation.github_account_type != "Organization" {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "GitHub App must be installed on a GitHub organization to use teams",
+        ));
+    }
+
+    // Check the team exists before restricting the project to it
+    github_app
+        .list_team_members(
+            installation.github_installation_id,
+            &installation.github_account_login,
+            team_slug,
+        )
+        .await
+        .map_err(|e| match e {
+            GitHubAppError::Api { status: 404, .. } => {
+                ErrorResponse::new(StatusCode::BAD_REQUEST, "GitHub team not found")
+            }
+            e => {
+                error!(?e, "Failed to fetch GitHub team members");
+                ErrorResponse::new(StatusCode::BAD_GATEWAY, "Failed to fetch GitHub team")
+            }
+        })?;
+
+    ProjectGitHubTeamRepository::link(state.pool(), project_id, installation.id, team_slug)
+        .await
+        .map_err(|e| {
+            error!(?e, "Failed to link project GitHub team");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error")
+        })?;
+
+    info!(
+        project_id = %project_id,
+        team_slug,
+        user_id = %ctx.user.id,
+        "Project linked to GitHub team"
+    );
+
+    Ok(Json(sync_and_reload(&state, github_app, project_id).await?))
+}
+
+/// DELETE /v1/projects/:project_id/github-team
+/// Stops syncing from the team and opens the project to the whole organization
+async fn unlink_project_team(
+    State(state): State<AppState>,
+    axum::extract::Extension(ctx): axum::extract::Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ErrorResponse> {
+    let org_id = ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
+    ensure_admin_access(state.pool(), org_id, ctx.user.id).await?;
+
+    ProjectGitHubTeamRepository::unlink(state.pool(), project_id)
+        .await
+        .map_err(|e| {
+            error!(?e, "Failed to unlink project GitHub team");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error")
+        })?;
+
+    info!(project_id = %project_id, user_id = %ctx.user.id, "Project unlinked from GitHub team");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /v1/projects/:project_id/github-team/sync
+/// Re-syncs the project's members from its GitHub team now
+async fn sync_project_team(
+    State(state): State<AppState>,
+    axum::extract::Extension(ctx): axum::extract::Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ErrorResponse> {
+    let github_app = state.github_app().ok_or_else(|| {
+        ErrorResponse::new(StatusCode::NOT_IMPLEMENTED, "GitHub App not configured")
+    })?;
+
+    let org_id = ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
+    ensure_admin_access(state.pool(), org_id, ctx.user.id).await?;
+
+    Ok(Json(sync_and_reload(&state, github_app, project_id).await?))
+}
+
 // ========== Public Route Handlers ==========
 
 /// GET /v1/github/app/callback
@@ -655,6 +868,9 @@ async fn handle_webhook(
         "installation_repositories" => handle_installation_repos_event(&state, &payload).await,
         "pull_request" => handle_pull_request_event(&state, github_app, &payload).await,
         "issue_comment" => handle_issue_comment_event(&state, github_app, &payload).await,
+        "push" => handle_push_event(&state, &payload).await,
+        "membership" => handle_membership_event(&state, github_app, &payload),
+        "organization" => handle_organization_event(&state, github_app, &payload),
         _ => {
             info!(event_type, "Ignoring unhandled webhook event");
             StatusCode::OK.into_response()
@@ -943,6 +1159,15 @@ async fn handle_pull_request_event(
 ) -> Response {
     let action = payload["action"].as_str().unwrap_or("");
 
+    if matches!(action, "opened" | "edited" | "reopened") {
+        record_code_mentions(
+            state,
+            payload,
+            from_pull_request(payload).into_iter().collect(),
+        )
+        .await;
+    }
+
     if action != "opened" {
         return StatusCode::OK.into_response();
     }
@@ -1047,6 +1272,105 @@ async fn handle_issue_comment_event(
     StatusCode::OK.into_response()
 }
 
+/// Re-sync projects linked to a team when someone is added to or removed
+/// from it.
+fn handle_membership_event(
+    state: &AppState,
+    github_app: &crate::github_app::GitHubAppService,
+    payload: &serde_json::Value,
+) -> Response {
+    let installation_id = payload["installation"]["id"].as_i64().unwrap_or(0);
+    let Some(team_slug) = payload["team"]["slug"].as_str().map(str::to_string) else {
+        return StatusCode::OK.into_response();
+    };
+
+    info!(installation_id, team_slug, "Processing membership event");
+
+    let pool = state.pool().clone();
+    let github_app = github_app.clone();
+    tokio::spawn(async move {
+        sync_installation(&pool, &github_app, installation_id, Some(&team_slug)).await;
+    });
+
+    StatusCode::OK.into_response()
+}
+
+/// Re-sync every team of the installation when someone joins or leaves the
+/// GitHub organization, since that also changes their team memberships.
+fn handle_organization_event(
+    state: &AppState,
+    github_app: &crate::github_app::GitHubAppService,
+    payload: &serde_json::Value,
+) -> Response {
+    let action = payload["action"].as_str().unwrap_or("");
+    if !matches!(action, "member_added" | "member_removed") {
+        return StatusCode::OK.into_response();
+    }
+
+    let installation_id = payload["installation"]["id"].as_i64().unwrap_or(0);
+    info!(action, installation_id, "Processing organization event");
+
+    let pool = state.pool().clone();
+    let github_app = github_app.clone();
+    tokio::spawn(async move {
+        sync_installation(&pool, &github_app, installation_id, None).await;
+    });
+
+    StatusCode::OK.into_response()
+}
+
+async fn handle_push_event(state: &AppState, payload: &serde_json::Value) -> Response {
+    record_code_mentions(state, payload, from_push(payload)).await;
+    StatusCode::OK.into_response()
+}
+
+/// Record issue mentions from commits or pull requests against the issues of
+/// the organization the installation belongs to.
+async fn record_code_mentions(
+    state: &AppState,
+    payload: &serde_json::Value,
+    mentions: Vec<CodeMentionEvent>,
+) {
+    if mentions.is_empty() {
+        return;
+    }
+
+    let installation_id = payload["installation"]["id"].as_i64().unwrap_or(0);
+    let gh_repo = GitHubAppRepository2::new(state.pool());
+    let installation = match gh_repo.get_by_github_id(installation_id).await {
+        Ok(Some(installation)) if installation.suspended_at.is_none() => installation,
+        Ok(_) => {
+            info!(
+                installation_id,
+                "No active installation, ignoring issue mentions"
+            );
+            return;
+        }
+        Err(e) => {
+            error!(?e, "Failed to get installation");
+            return;
+        }
+    };
+
+    for mention in &mentions {
+        match IssueCodeReferenceRepository::record(
+            state.pool(),
+            installation.organization_id,
+            &mention.as_mention(),
+        )
+        .await
+        {
+            Ok(issue_ids) => info!(
+                installation_id,
+                reference = %mention.reference,
+                issues = issue_ids.len(),
+                "Recorded issue mentions"
+            ),
+            Err(e) => error!(?e, reference = %mention.reference, "Failed to record issue mentions"),
+        }
+    }
+}
+
 // ========== Debug Endpoint ==========
 
 /// Parse a GitHub PR URL into (owner, repo, pr_number)
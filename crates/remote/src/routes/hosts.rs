@@ -2,15 +2,26 @@ use axum::{
     Json, Router,
     extract::{Extension, State},
     http::StatusCode,
-    routing::get,
 };
 use relay_types::ListRelayHostsResponse;
 
 use super::error::ErrorResponse;
-use crate::{AppState, auth::RequestContext, db::hosts::HostRepository};
+use crate::{
+    AppState,
+    auth::RequestContext,
+    db::hosts::HostRepository,
+    query_definition::{QueryBuilder, QueryRoute, query_router},
+};
+
+pub fn queries() -> Vec<QueryRoute> {
+    vec![
+        QueryBuilder::<ListRelayHostsResponse>::new("list_relay_hosts", "hosts")
+            .get_without_params(list_hosts),
+    ]
+}
 
 pub fn router() -> Router<AppState> {
-    Router::new().route("/hosts", get(list_hosts))
+    query_router(queries())
 }
 
 async fn list_hosts(
@@ -1,9 +1,20 @@
-use axum::{Extension, Json, Router, routing::get};
+use api_types::{UpdateUserProfileRequest, UserProfile};
+use axum::{Extension, Json, Router, extract::State, http::StatusCode, routing::get};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use uuid::Uuid;
 
-use crate::{AppState, auth::RequestContext};
+use super::{error::ErrorResponse, organization_members::ensure_project_access};
+use crate::{
+    AppState,
+    auth::RequestContext,
+    db::{
+        attachments::AttachmentRepository,
+        user_profiles::{ProfileUpdate, UserProfileRepository, UserProfileRow},
+    },
+    localization::normalize_locale,
+    user_profiles::{self, normalize_display_name, normalize_timezone},
+};
 
 #[derive(Debug, Serialize, Deserialize)]
 struct IdentityResponse {
@@ -13,7 +24,9 @@ struct IdentityResponse {
 }
 
 pub(super) fn router() -> Router<AppState> {
-    Router::new().route("/identity", get(get_identity))
+    Router::new()
+        .route("/identity", get(get_identity))
+        .route("/identity/profile", get(get_profile).patch(update_profile))
 }
 
 #[instrument(name = "identity.get_identity", skip(ctx), fields(user_id = %ctx.user.id))]
@@ -25,3 +38,131 @@ async fn get_identity(Extension(ctx): Extension<RequestContext>) -> Json<Identit
         email: user.email,
     })
 }
+
+fn internal_error(error: &dyn std::fmt::Debug, message: &str) -> ErrorResponse {
+    tracing::error!(?error, "{message}");
+    ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+}
+
+fn bad_request(error: impl ToString) -> ErrorResponse {
+    ErrorResponse::new(StatusCode::BAD_REQUEST, error.to_string())
+}
+
+async fn load_profile(state: &AppState, user_id: Uuid) -> Result<UserProfileRow, ErrorResponse> {
+    UserProfileRepository::find(state.pool(), user_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load user profile"))?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "user not found"))
+}
+
+fn profile_response(state: &AppState, profile: UserProfileRow) -> UserProfile {
+    let avatar_url = user_profiles::avatar_url(state.azure_blob(), &profile);
+    UserProfile {
+        user_id: profile.id,
+        email: profile.email,
+        first_name: profile.first_name,
+        last_name: profile.last_name,
+        username: profile.username,
+        display_name: profile.display_name,
+        avatar_attachment_id: profile.avatar_attachment_id,
+        avatar_url,
+        timezone: profile.timezone,
+        locale: profile.locale,
+    }
+}
+
+/// Check that a newly chosen avatar is an image the requester uploaded to a
+/// project they can access, and that nothing else uses it yet.
+async fn ensure_avatar_upload(
+    state: &AppState,
+    ctx: &RequestContext,
+    attachment_id: Uuid,
+) -> Result<(), ErrorResponse> {
+    let not_found = || ErrorResponse::new(StatusCode::NOT_FOUND, "attachment not found");
+    let attachment = AttachmentRepository::find_by_id_with_blob(state.pool(), attachment_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load attachment"))?
+        .ok_or_else(not_found)?;
+    let project_id = AttachmentRepository::project_id(state.pool(), attachment_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load attachment project"))?
+        .ok_or_else(not_found)?;
+    ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
+
+    if attachment.issue_id.is_some()
+        || attachment.comment_id.is_some()
+        || attachment.expires_at.is_none()
+    {
+        return Err(bad_request("avatar must be a new upload"));
+    }
+    if !attachment
+        .mime_type
+        .as_deref()
+        .is_some_and(|mime_type| mime_type.starts_with("image/"))
+    {
+        return Err(bad_request("avatar must be an image"));
+    }
+    Ok(())
+}
+
+#[instrument(name = "identity.get_profile", skip(state, ctx), fields(user_id = %ctx.user.id))]
+async fn get_profile(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+) -> Result<Json<UserProfile>, ErrorResponse> {
+    let profile = load_profile(&state, ctx.user.id).await?;
+    Ok(Json(profile_response(&state, profile)))
+}
+
+#[instrument(
+    name = "identity.update_profile",
+    skip(state, ctx, payload),
+    fields(user_id = %ctx.user.id)
+)]
+async fn update_profile(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Json(payload): Json<UpdateUserProfileRequest>,
+) -> Result<Json<UserProfile>, ErrorResponse> {
+    let existing = load_profile(&state, ctx.user.id).await?;
+
+    let display_name = match payload.display_name {
+        Some(Some(name)) => normalize_display_name(&name).map_err(bad_request)?,
+        Some(None) => None,
+        None => existing.display_name,
+    };
+    let timezone = match payload.timezone {
+        Some(Some(timezone)) => Some(normalize_timezone(&timezone).map_err(bad_request)?),
+        Some(None) => None,
+        None => existing.timezone,
+    };
+    let locale = match payload.locale {
+        Some(Some(locale)) => Some(normalize_locale(&locale).map_err(bad_request)?),
+        Some(None) => None,
+        None => existing.locale,
+    };
+    let avatar_attachment_id = match payload.avatar_attachment_id {
+        Some(Some(attachment_id)) if Some(attachment_id) != existing.avatar_attachment_id => {
+            ensure_avatar_upload(&state, &ctx, attachment_id).await?;
+            Some(attachment_id)
+        }
+        Some(attachment_id) => attachment_id,
+        None => existing.avatar_attachment_id,
+    };
+
+    UserProfileRepository::update(
+        state.pool(),
+        ctx.user.id,
+        &ProfileUpdate {
+            display_name: display_name.as_deref(),
+            avatar_attachment_id,
+            timezone: timezone.as_deref(),
+            locale: locale.as_deref(),
+        },
+    )
+    .await
+    .map_err(|error| internal_error(&error, "failed to update user profile"))?;
+
+    let profile = load_profile(&state, ctx.user.id).await?;
+    Ok(Json(profile_response(&state, profile)))
+}
@@ -0,0 +1,104 @@
+use api_types::{OrganizationIpAllowlist, UpdateIpAllowlistRequest};
+use axum::{
+    Json, Router,
+    extract::{Extension, Path, State},
+    http::{Extensions, HeaderMap, StatusCode},
+    routing::get,
+};
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{error::ErrorResponse, organization_members::ensure_admin_access};
+use crate::{
+    AppState,
+    audit::{self, AuditAction, AuditEvent},
+    auth::RequestContext,
+    db::ip_allowlists::IpAllowlistRepository,
+    ip_allowlist::{allowlist_response, is_allowed, parse_ranges},
+    middleware::ip_allowlist::client_ip,
+};
+
+pub(super) fn router() -> Router<AppState> {
+    Router::new().route(
+        "/organizations/{org_id}/ip-allowlist",
+        get(get_allowlist).put(update_allowlist),
+    )
+}
+
+fn internal_error(error: &dyn std::fmt::Debug, message: &str) -> ErrorResponse {
+    tracing::error!(?error, "{message}");
+    ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+}
+
+fn bad_request(error: impl ToString) -> ErrorResponse {
+    ErrorResponse::new(StatusCode::BAD_REQUEST, error.to_string())
+}
+
+#[instrument(
+    name = "ip_allowlist.get_allowlist",
+    skip(state, ctx),
+    fields(org_id = %org_id, user_id = %ctx.user.id)
+)]
+async fn get_allowlist(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<OrganizationIpAllowlist>, ErrorResponse> {
+    ensure_admin_access(state.pool(), org_id, ctx.user.id).await?;
+    let ranges = IpAllowlistRepository::find(state.pool(), org_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load IP allowlist"))?
+        .map(|allowlist| allowlist.ranges)
+        .unwrap_or_default();
+
+    Ok(Json(allowlist_response(org_id, &ranges)))
+}
+
+#[instrument(
+    name = "ip_allowlist.update_allowlist",
+    skip(state, ctx, extensions, headers, payload),
+    fields(org_id = %org_id, user_id = %ctx.user.id)
+)]
+async fn update_allowlist(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(org_id): Path<Uuid>,
+    extensions: Extensions,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateIpAllowlistRequest>,
+) -> Result<Json<OrganizationIpAllowlist>, ErrorResponse> {
+    ensure_admin_access(state.pool(), org_id, ctx.user.id).await?;
+    let ranges = parse_ranges(&payload.ranges).map_err(bad_request)?;
+
+    // Keep admins from locking themselves out.
+    let ip = client_ip(&state, &extensions, &headers);
+    if !is_allowed(&ranges, ip) {
+        let address = ip.map_or_else(|| "unknown".to_string(), |ip| ip.to_string());
+        return Err(bad_request(format!(
+            "the allowlist must include your current address ({address})"
+        )));
+    }
+
+    IpAllowlistRepository::set(state.pool(), org_id, &ranges, ctx.user.id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to update IP allowlist"))?;
+    let allowlist = allowlist_response(org_id, &ranges);
+
+    audit::emit(
+        AuditEvent::from_request(&ctx, AuditAction::IpAllowlistUpdate)
+            .resource("organization", Some(org_id))
+            .organization(org_id)
+            .http(
+                "PUT",
+                format!("/v1/organizations/{org_id}/ip-allowlist"),
+                200,
+            )
+            .description(if allowlist.ranges.is_empty() {
+                "Removed the IP allowlist".to_string()
+            } else {
+                format!("Set the IP allowlist to {}", allowlist.ranges.join(", "))
+            }),
+    );
+
+    Ok(Json(allowlist))
+}
@@ -20,19 +20,28 @@ use crate::{
     db::{issue_assignees::IssueAssigneeRepository, issues::IssueRepository},
     mutation_definition::{MutationBuilder, NoUpdate},
     notifications::notify_user,
+    query_definition::{QueryBuilder, QueryRoute, query_router},
+    query_subscriptions,
 };
 
 /// Mutation definition for IssueAssignee - provides both router and TypeScript metadata.
 pub fn mutation() -> MutationBuilder<IssueAssignee, CreateIssueAssigneeRequest, NoUpdate> {
     MutationBuilder::new("issue_assignees")
-        .list(list_issue_assignees)
         .get(get_issue_assignee)
         .create(create_issue_assignee)
         .delete(delete_issue_assignee)
 }
 
+/// Read endpoints for issue assignees.
+pub fn queries() -> Vec<QueryRoute> {
+    vec![
+        QueryBuilder::<ListIssueAssigneesResponse>::new("list_issue_assignees", "issue_assignees")
+            .get(list_issue_assignees),
+    ]
+}
+
 pub fn router() -> axum::Router<AppState> {
-    mutation().router()
+    mutation().router().merge(query_router(queries()))
 }
 
 #[instrument(
@@ -128,6 +137,14 @@ async fn create_issue_assignee(
         .await;
     }
 
+    query_subscriptions::evaluate_issue_by_id(
+        state.pool(),
+        organization_id,
+        ctx.user.id,
+        payload.issue_id,
+    )
+    .await;
+
     Ok(Json(response))
 }
 
@@ -179,5 +196,13 @@ async fn delete_issue_assignee(
         .await;
     }
 
+    query_subscriptions::evaluate_issue_by_id(
+        state.pool(),
+        organization_id,
+        ctx.user.id,
+        assignee.issue_id,
+    )
+    .await;
+
     Ok(Json(response))
 }
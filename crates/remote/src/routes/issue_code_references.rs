@@ -0,0 +1,45 @@
+use api_types::ListIssueCodeReferencesResponse;
+use axum::{
+    Json, Router,
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    routing::get,
+};
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{error::ErrorResponse, organization_members::ensure_issue_access};
+use crate::{
+    AppState, auth::RequestContext, db::issue_code_references::IssueCodeReferenceRepository,
+};
+
+pub(super) fn router() -> Router<AppState> {
+    Router::new().route(
+        "/issues/{issue_id}/code-references",
+        get(list_issue_code_references),
+    )
+}
+
+/// Commits and pull requests that mention the issue, as recorded from
+/// GitHub App webhooks.
+#[instrument(
+    name = "issue_code_references.list_issue_code_references",
+    skip(state, ctx),
+    fields(issue_id = %issue_id, user_id = %ctx.user.id)
+)]
+async fn list_issue_code_references(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(issue_id): Path<Uuid>,
+) -> Result<Json<ListIssueCodeReferencesResponse>, ErrorResponse> {
+    ensure_issue_access(state.pool(), ctx.user.id, issue_id).await?;
+
+    let references = IssueCodeReferenceRepository::list_by_issue(state.pool(), issue_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, "failed to list issue code references");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+        })?;
+
+    Ok(Json(ListIssueCodeReferencesResponse { references }))
+}
@@ -25,6 +25,7 @@ use crate::{
     },
     mutation_definition::MutationBuilder,
     notifications::send_issue_notifications,
+    query_definition::{QueryBuilder, QueryRoute, query_router},
 };
 
 /// Mutation definition for IssueCommentReaction - provides both router and TypeScript metadata.
@@ -34,15 +35,25 @@ pub fn mutation() -> MutationBuilder<
     UpdateIssueCommentReactionRequest,
 > {
     MutationBuilder::new("issue_comment_reactions")
-        .list(list_issue_comment_reactions)
         .get(get_issue_comment_reaction)
         .create(create_issue_comment_reaction)
         .update(update_issue_comment_reaction)
         .delete(delete_issue_comment_reaction)
 }
 
+/// Read endpoints for issue comment reactions.
+pub fn queries() -> Vec<QueryRoute> {
+    vec![
+        QueryBuilder::<ListIssueCommentReactionsResponse>::new(
+            "list_issue_comment_reactions",
+            "issue_comment_reactions",
+        )
+        .get(list_issue_comment_reactions),
+    ]
+}
+
 pub fn router() -> axum::Router<AppState> {
-    mutation().router()
+    mutation().router().merge(query_router(queries()))
 }
 
 async fn notify_comment_author_about_reaction(
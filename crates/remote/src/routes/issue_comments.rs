@@ -6,7 +6,7 @@ use api_types::{
 use axum::{
     Json,
     extract::{Extension, Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
 };
 use tracing::instrument;
 use uuid::Uuid;
@@ -14,31 +14,41 @@ use uuid::Uuid;
 use super::{
     error::{ErrorResponse, db_error},
     organization_members::ensure_issue_access,
+    retention::ensure_issue_not_on_legal_hold,
 };
 use crate::{
     AppState,
     auth::RequestContext,
     db::{
-        issue_comments::IssueCommentRepository, issues::IssueRepository,
-        organization_members::check_user_role,
+        get_txid, issue_comments::IssueCommentRepository, issues::IssueRepository,
+        organization_members::check_user_role, projects::ProjectRepository,
     },
-    mutation_definition::MutationBuilder,
+    mutation_definition::{self, MutationBuilder},
     notifications::notify_issue_subscribers,
+    query_definition::{QueryBuilder, QueryRoute, query_router},
 };
 
 /// Mutation definition for IssueComment - provides both router and TypeScript metadata.
 pub fn mutation()
 -> MutationBuilder<IssueComment, CreateIssueCommentRequest, UpdateIssueCommentRequest> {
     MutationBuilder::new("issue_comments")
-        .list(list_issue_comments)
         .get(get_issue_comment)
         .create(create_issue_comment)
         .update(update_issue_comment)
         .delete(delete_issue_comment)
+        .versioned("updated_at")
+}
+
+/// Read endpoints for issue comments.
+pub fn queries() -> Vec<QueryRoute> {
+    vec![
+        QueryBuilder::<ListIssueCommentsResponse>::new("list_issue_comments", "issue_comments")
+            .get(list_issue_comments),
+    ]
 }
 
 pub fn router() -> axum::Router<AppState> {
-    mutation().router()
+    mutation().router().merge(query_router(queries()))
 }
 
 #[instrument(
@@ -75,7 +85,7 @@ async fn get_issue_comment(
     State(state): State<AppState>,
     Extension(ctx): Extension<RequestContext>,
     Path(issue_comment_id): Path<Uuid>,
-) -> Result<Json<IssueComment>, ErrorResponse> {
+) -> Result<(HeaderMap, Json<IssueComment>), ErrorResponse> {
     let comment = IssueCommentRepository::find_by_id(state.pool(), issue_comment_id)
         .await
         .map_err(|error| {
@@ -89,7 +99,10 @@ async fn get_issue_comment(
 
     ensure_issue_access(state.pool(), ctx.user.id, comment.issue_id).await?;
 
-    Ok(Json(comment))
+    Ok((
+        mutation_definition::etag_headers(comment.updated_at),
+        Json(comment),
+    ))
 }
 
 #[instrument(
@@ -163,8 +176,9 @@ async fn update_issue_comment(
     State(state): State<AppState>,
     Extension(ctx): Extension<RequestContext>,
     Path(issue_comment_id): Path<Uuid>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateIssueCommentRequest>,
-) -> Result<Json<MutationResponse<IssueComment>>, ErrorResponse> {
+) -> Result<(HeaderMap, Json<MutationResponse<IssueComment>>), ErrorResponse> {
     let comment = IssueCommentRepository::find_by_id(state.pool(), issue_comment_id)
         .await
         .map_err(|error| {
@@ -198,14 +212,53 @@ async fn update_issue_comment(
         ));
     }
 
-    let response = IssueCommentRepository::update(state.pool(), issue_comment_id, payload.message)
+    let internal_error = |error: &dyn std::fmt::Debug, message: &str| {
+        tracing::error!(?error, %issue_comment_id, "{message}");
+        ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+    };
+    let require_if_match =
+        ProjectRepository::require_if_match_for_issue(state.pool(), comment.issue_id)
+            .await
+            .map_err(|error| internal_error(&error, "failed to load project settings"))?;
+
+    let mut tx = crate::db::begin_tx(state.pool())
         .await
-        .map_err(|error| {
-            tracing::error!(?error, "failed to update issue comment");
-            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
-        })?;
+        .map_err(|error| internal_error(&error, "failed to begin transaction"))?;
+    mutation_definition::lock_row(&mut tx, "issue_comments", issue_comment_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to lock issue comment"))?;
+    let comment = IssueCommentRepository::find_by_id(&mut *tx, issue_comment_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load issue comment"))?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "issue comment not found"))?;
+    mutation_definition::check_if_match(
+        &comment,
+        comment.updated_at,
+        mutation_definition::if_match(&headers),
+        payload.expected_updated_at.is_some(),
+        require_if_match,
+    )?;
+    mutation_definition::check_version(
+        &comment,
+        comment.updated_at,
+        payload.expected_updated_at,
+        &payload,
+    )?;
 
-    Ok(Json(response))
+    let data = IssueCommentRepository::update(&mut tx, issue_comment_id, payload.message)
+        .await
+        .map_err(|error| internal_error(&error, "failed to update issue comment"))?;
+    let txid = get_txid(&mut *tx)
+        .await
+        .map_err(|error| internal_error(&error, "failed to get txid"))?;
+    tx.commit()
+        .await
+        .map_err(|error| internal_error(&error, "failed to commit transaction"))?;
+
+    Ok((
+        mutation_definition::etag_headers(data.updated_at),
+        Json(MutationResponse { data, txid }),
+    ))
 }
 
 #[instrument(
@@ -250,6 +303,7 @@ async fn delete_issue_comment(
             "you do not have permission to delete this comment",
         ));
     }
+    ensure_issue_not_on_legal_hold(state.pool(), comment.issue_id).await?;
 
     let response = IssueCommentRepository::delete(state.pool(), issue_comment_id)
         .await
@@ -19,19 +19,27 @@ use crate::{
     auth::RequestContext,
     db::issue_followers::IssueFollowerRepository,
     mutation_definition::{MutationBuilder, NoUpdate},
+    query_definition::{QueryBuilder, QueryRoute, query_router},
 };
 
 /// Mutation definition for IssueFollower - provides both router and TypeScript metadata.
 pub fn mutation() -> MutationBuilder<IssueFollower, CreateIssueFollowerRequest, NoUpdate> {
     MutationBuilder::new("issue_followers")
-        .list(list_issue_followers)
         .get(get_issue_follower)
         .create(create_issue_follower)
         .delete(delete_issue_follower)
 }
 
+/// Read endpoints for issue followers.
+pub fn queries() -> Vec<QueryRoute> {
+    vec![
+        QueryBuilder::<ListIssueFollowersResponse>::new("list_issue_followers", "issue_followers")
+            .get(list_issue_followers),
+    ]
+}
+
 pub fn router() -> axum::Router<AppState> {
-    mutation().router()
+    mutation().router().merge(query_router(queries()))
 }
 
 #[instrument(
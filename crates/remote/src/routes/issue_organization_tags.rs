@@ -0,0 +1,178 @@
+use api_types::{
+    CreateIssueOrganizationTagRequest, DeleteResponse, IssueOrganizationTag,
+    ListIssueOrganizationTagsQuery, ListIssueOrganizationTagsResponse, MutationResponse,
+};
+use axum::{
+    Json,
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+};
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{
+    error::{ErrorResponse, db_error},
+    organization_members::ensure_issue_access,
+};
+use crate::{
+    AppState,
+    auth::RequestContext,
+    db::{
+        issue_organization_tags::IssueOrganizationTagRepository,
+        organization_tags::OrganizationTagRepository,
+    },
+    mutation_definition::{MutationBuilder, NoUpdate},
+    query_definition::{QueryBuilder, QueryRoute, query_router},
+};
+
+/// Mutation definition for IssueOrganizationTag - provides both router and
+/// TypeScript metadata.
+pub fn mutation()
+-> MutationBuilder<IssueOrganizationTag, CreateIssueOrganizationTagRequest, NoUpdate> {
+    MutationBuilder::new("issue_organization_tags")
+        .get(get_issue_organization_tag)
+        .create(create_issue_organization_tag)
+        .delete(delete_issue_organization_tag)
+}
+
+/// Read endpoints for issue organization tags.
+pub fn queries() -> Vec<QueryRoute> {
+    vec![
+        QueryBuilder::<ListIssueOrganizationTagsResponse>::new(
+            "list_issue_organization_tags",
+            "issue_organization_tags",
+        )
+        .get(list_issue_organization_tags),
+    ]
+}
+
+pub fn router() -> axum::Router<AppState> {
+    mutation().router().merge(query_router(queries()))
+}
+
+async fn load_issue_organization_tag(
+    state: &AppState,
+    issue_organization_tag_id: Uuid,
+) -> Result<IssueOrganizationTag, ErrorResponse> {
+    IssueOrganizationTagRepository::find_by_id(state.pool(), issue_organization_tag_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, %issue_organization_tag_id, "failed to load issue organization tag");
+            ErrorResponse::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "failed to load issue organization tag",
+            )
+        })?
+        .ok_or_else(|| {
+            ErrorResponse::new(StatusCode::NOT_FOUND, "issue organization tag not found")
+        })
+}
+
+#[instrument(
+    name = "issue_organization_tags.list_issue_organization_tags",
+    skip(state, ctx),
+    fields(issue_id = %query.issue_id, user_id = %ctx.user.id)
+)]
+async fn list_issue_organization_tags(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Query(query): Query<ListIssueOrganizationTagsQuery>,
+) -> Result<Json<ListIssueOrganizationTagsResponse>, ErrorResponse> {
+    ensure_issue_access(state.pool(), ctx.user.id, query.issue_id).await?;
+
+    let issue_organization_tags =
+        IssueOrganizationTagRepository::list_by_issue(state.pool(), query.issue_id)
+            .await
+            .map_err(|error| {
+                tracing::error!(?error, issue_id = %query.issue_id, "failed to list issue organization tags");
+                ErrorResponse::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "failed to list issue organization tags",
+                )
+            })?;
+
+    Ok(Json(ListIssueOrganizationTagsResponse {
+        issue_organization_tags,
+    }))
+}
+
+#[instrument(
+    name = "issue_organization_tags.get_issue_organization_tag",
+    skip(state, ctx),
+    fields(issue_organization_tag_id = %issue_organization_tag_id, user_id = %ctx.user.id)
+)]
+async fn get_issue_organization_tag(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(issue_organization_tag_id): Path<Uuid>,
+) -> Result<Json<IssueOrganizationTag>, ErrorResponse> {
+    let issue_organization_tag =
+        load_issue_organization_tag(&state, issue_organization_tag_id).await?;
+    ensure_issue_access(state.pool(), ctx.user.id, issue_organization_tag.issue_id).await?;
+
+    Ok(Json(issue_organization_tag))
+}
+
+#[instrument(
+    name = "issue_organization_tags.create_issue_organization_tag",
+    skip(state, ctx, payload),
+    fields(issue_id = %payload.issue_id, user_id = %ctx.user.id)
+)]
+async fn create_issue_organization_tag(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Json(payload): Json<CreateIssueOrganizationTagRequest>,
+) -> Result<Json<MutationResponse<IssueOrganizationTag>>, ErrorResponse> {
+    let organization_id = ensure_issue_access(state.pool(), ctx.user.id, payload.issue_id).await?;
+
+    // Only tags of the issue's own organization can be applied.
+    OrganizationTagRepository::find_by_id(state.pool(), payload.organization_tag_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, "failed to load organization tag");
+            ErrorResponse::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "failed to load organization tag",
+            )
+        })?
+        .filter(|tag| tag.organization_id == organization_id)
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "organization tag not found"))?;
+
+    let response = IssueOrganizationTagRepository::create(
+        state.pool(),
+        payload.id,
+        payload.issue_id,
+        payload.organization_tag_id,
+    )
+    .await
+    .map_err(|error| {
+        tracing::error!(?error, "failed to create issue organization tag");
+        db_error(error, "failed to create issue organization tag")
+    })?;
+
+    Ok(Json(response))
+}
+
+#[instrument(
+    name = "issue_organization_tags.delete_issue_organization_tag",
+    skip(state, ctx),
+    fields(issue_organization_tag_id = %issue_organization_tag_id, user_id = %ctx.user.id)
+)]
+async fn delete_issue_organization_tag(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(issue_organization_tag_id): Path<Uuid>,
+) -> Result<Json<DeleteResponse>, ErrorResponse> {
+    let issue_organization_tag =
+        load_issue_organization_tag(&state, issue_organization_tag_id).await?;
+    ensure_issue_access(state.pool(), ctx.user.id, issue_organization_tag.issue_id).await?;
+
+    let response = IssueOrganizationTagRepository::delete(state.pool(), issue_organization_tag_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, "failed to delete issue organization tag");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+        })?;
+
+    Ok(Json(response))
+}
@@ -19,19 +19,30 @@ use crate::{
     auth::RequestContext,
     db::issue_relationships::IssueRelationshipRepository,
     mutation_definition::{MutationBuilder, NoUpdate},
+    query_definition::{QueryBuilder, QueryRoute, query_router},
 };
 
 /// Mutation definition for IssueRelationship - provides both router and TypeScript metadata.
 pub fn mutation() -> MutationBuilder<IssueRelationship, CreateIssueRelationshipRequest, NoUpdate> {
     MutationBuilder::new("issue_relationships")
-        .list(list_issue_relationships)
         .get(get_issue_relationship)
         .create(create_issue_relationship)
         .delete(delete_issue_relationship)
 }
 
+/// Read endpoints for issue relationships.
+pub fn queries() -> Vec<QueryRoute> {
+    vec![
+        QueryBuilder::<ListIssueRelationshipsResponse>::new(
+            "list_issue_relationships",
+            "issue_relationships",
+        )
+        .get(list_issue_relationships),
+    ]
+}
+
 pub fn router() -> axum::Router<AppState> {
-    mutation().router()
+    mutation().router().merge(query_router(queries()))
 }
 
 #[instrument(
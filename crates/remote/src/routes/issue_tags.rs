@@ -19,19 +19,28 @@ use crate::{
     auth::RequestContext,
     db::issue_tags::IssueTagRepository,
     mutation_definition::{MutationBuilder, NoUpdate},
+    query_definition::{QueryBuilder, QueryRoute, query_router},
+    query_subscriptions,
 };
 
 /// Mutation definition for IssueTag - provides both router and TypeScript metadata.
 pub fn mutation() -> MutationBuilder<IssueTag, CreateIssueTagRequest, NoUpdate> {
     MutationBuilder::new("issue_tags")
-        .list(list_issue_tags)
         .get(get_issue_tag)
         .create(create_issue_tag)
         .delete(delete_issue_tag)
 }
 
+/// Read endpoints for issue tags.
+pub fn queries() -> Vec<QueryRoute> {
+    vec![
+        QueryBuilder::<ListIssueTagsResponse>::new("list_issue_tags", "issue_tags")
+            .get(list_issue_tags),
+    ]
+}
+
 pub fn router() -> axum::Router<AppState> {
-    mutation().router()
+    mutation().router().merge(query_router(queries()))
 }
 
 #[instrument(
@@ -95,7 +104,7 @@ async fn create_issue_tag(
     Extension(ctx): Extension<RequestContext>,
     Json(payload): Json<CreateIssueTagRequest>,
 ) -> Result<Json<MutationResponse<IssueTag>>, ErrorResponse> {
-    ensure_issue_access(state.pool(), ctx.user.id, payload.issue_id).await?;
+    let organization_id = ensure_issue_access(state.pool(), ctx.user.id, payload.issue_id).await?;
 
     let response =
         IssueTagRepository::create(state.pool(), payload.id, payload.issue_id, payload.tag_id)
@@ -105,6 +114,14 @@ async fn create_issue_tag(
                 db_error(error, "failed to create issue tag")
             })?;
 
+    query_subscriptions::evaluate_issue_by_id(
+        state.pool(),
+        organization_id,
+        ctx.user.id,
+        payload.issue_id,
+    )
+    .await;
+
     Ok(Json(response))
 }
 
@@ -129,7 +146,8 @@ async fn delete_issue_tag(
         })?
         .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "issue tag not found"))?;
 
-    ensure_issue_access(state.pool(), ctx.user.id, issue_tag.issue_id).await?;
+    let organization_id =
+        ensure_issue_access(state.pool(), ctx.user.id, issue_tag.issue_id).await?;
 
     let response = IssueTagRepository::delete(state.pool(), issue_tag_id)
         .await
@@ -138,5 +156,13 @@ async fn delete_issue_tag(
             ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
         })?;
 
+    query_subscriptions::evaluate_issue_by_id(
+        state.pool(),
+        organization_id,
+        ctx.user.id,
+        issue_tag.issue_id,
+    )
+    .await;
+
     Ok(Json(response))
 }
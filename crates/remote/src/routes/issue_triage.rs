@@ -0,0 +1,95 @@
+use api_types::{IssueTriageRequest, IssueTriageSuggestions};
+use axum::{
+    Json, Router,
+    extract::{Extension, State},
+    http::StatusCode,
+    routing::post,
+};
+use tracing::instrument;
+
+use super::{error::ErrorResponse, organization_members::ensure_project_access};
+use crate::{
+    AppState,
+    auth::RequestContext,
+    db::issue_triage::IssueTriageRepository,
+    issue_triage::{self, IssueTriageError, TriageContext},
+};
+
+const MAX_SIMILAR_ISSUES: i64 = 20;
+
+pub(super) fn router() -> Router<AppState> {
+    Router::new().route("/issues/triage", post(triage_issue))
+}
+
+/// Suggest tags, an assignee, a priority and duplicates for an issue. The
+/// issue may not exist yet; nothing is changed either way.
+#[instrument(
+    name = "issue_triage.triage_issue",
+    skip(state, ctx, payload),
+    fields(project_id = %payload.project_id, user_id = %ctx.user.id)
+)]
+async fn triage_issue(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Json(payload): Json<IssueTriageRequest>,
+) -> Result<Json<IssueTriageSuggestions>, ErrorResponse> {
+    ensure_project_access(state.pool(), ctx.user.id, payload.project_id).await?;
+    if payload.title.trim().is_empty() {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "title must not be empty",
+        ));
+    }
+    let config = state
+        .config()
+        .issue_triage
+        .as_ref()
+        .ok_or_else(|| triage_error(IssueTriageError::NotConfigured))?;
+
+    let internal_error = |error: &dyn std::fmt::Debug, message: &str| {
+        tracing::error!(?error, "{message}");
+        ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to triage issue")
+    };
+    let terms = issue_triage::search_terms(&payload.title, payload.description.as_deref());
+    let similar_issues = IssueTriageRepository::similar_issues(
+        state.pool(),
+        payload.project_id,
+        &terms,
+        payload.issue_id,
+        MAX_SIMILAR_ISSUES,
+    )
+    .await
+    .map_err(|error| internal_error(&error, "failed to find similar issues"))?;
+    let similar_issue_ids: Vec<_> = similar_issues.iter().map(|issue| issue.id).collect();
+    let members =
+        IssueTriageRepository::members(state.pool(), payload.project_id, &similar_issue_ids)
+            .await
+            .map_err(|error| internal_error(&error, "failed to load project members"))?;
+    let tags = IssueTriageRepository::project_tags(state.pool(), payload.project_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load project tags"))?;
+
+    let context = TriageContext {
+        tags,
+        members,
+        similar_issues,
+    };
+    let suggestions = issue_triage::suggest(&state.http_client, config, &payload, &context)
+        .await
+        .map_err(triage_error)?;
+
+    Ok(Json(suggestions))
+}
+
+fn triage_error(error: IssueTriageError) -> ErrorResponse {
+    match error {
+        IssueTriageError::NotConfigured => ErrorResponse::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "issue triage assistant not available",
+        ),
+        IssueTriageError::Request(_) | IssueTriageError::InvalidResponse(_) => {
+            tracing::error!(?error, "issue triage LLM call failed");
+            ErrorResponse::new(StatusCode::BAD_GATEWAY, "failed to get triage suggestions")
+        }
+    }
+}
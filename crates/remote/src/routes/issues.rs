@@ -1,60 +1,159 @@
 use api_types::{
     CreateIssueRequest, DeleteResponse, Issue, ListIssuesQuery, ListIssuesResponse,
-    MutationResponse, NotificationPayload, NotificationType, SearchIssuesRequest,
-    UpdateIssueRequest,
+    MutationResponse, NotificationPayload, NotificationType, ReorderIssueRequest,
+    SearchIssuesRequest, StatusTransitionError, UpdateIssueRequest,
 };
 use axum::{
     Json,
     extract::{Extension, Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     routing::post,
 };
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use tracing::instrument;
+use utils::fields::FieldSelection;
 use uuid::Uuid;
 
 use super::{
     error::{ErrorResponse, db_error},
     organization_members::ensure_project_access,
+    retention::ensure_no_legal_hold,
 };
 use crate::{
     AppState,
     auth::RequestContext,
     db::{
-        get_txid, issue_followers::IssueFollowerRepository, issues::IssueRepository,
-        project_statuses::ProjectStatusRepository,
+        Tx, get_txid, issue_followers::IssueFollowerRepository, issue_ranks,
+        issues::IssueRepository, project_status_workflows::ProjectStatusWorkflowRepository,
+        project_statuses::ProjectStatusRepository, projects::ProjectRepository,
     },
-    mutation_definition::MutationBuilder,
+    mutation_definition::{self, MutationBuilder},
     notifications::{
         collect_issue_recipients, send_debounced_issue_notifications, send_issue_notifications,
     },
+    query_definition::{QueryBuilder, QueryRoute, query_router},
+    query_subscriptions,
 };
 
 /// Mutation definition for Issue - provides both router and TypeScript metadata.
 pub fn mutation() -> MutationBuilder<Issue, CreateIssueRequest, UpdateIssueRequest> {
     MutationBuilder::new("issues")
-        .list(list_issues)
         .get(get_issue)
         .create(create_issue)
         .update(update_issue)
         .delete(delete_issue)
+        .versioned("updated_at")
+}
+
+/// Read endpoints for issues.
+pub fn queries() -> Vec<QueryRoute> {
+    vec![
+        QueryBuilder::<ListIssuesResponse>::new("list_issues", "issues").get(list_issues),
+        QueryBuilder::<ListIssuesResponse>::new("search_issues", "issues/search")
+            .post(search_issues),
+    ]
 }
 
 /// Router for issue endpoints including bulk update
 pub fn router() -> axum::Router<AppState> {
     mutation()
         .router()
-        .route("/issues/search", post(search_issues))
+        .merge(query_router(queries()))
         .route("/issues/bulk", post(bulk_update_issues))
+        .route("/issues/{id}/reorder", post(reorder_issue))
 }
 
-async fn notify_issue_update_changes(
+/// Load an issue for update, locking it for the rest of `tx`. Fails with a
+/// conflict if the update names an `expected_updated_at` and the issue has
+/// changed since, and with 412 if `if_match` names another version.
+pub(super) async fn lock_issue_for_update(
+    tx: &mut Tx<'_>,
+    issue_id: Uuid,
+    changes: &UpdateIssueRequest,
+    if_match: Option<&str>,
+) -> Result<Issue, ErrorResponse> {
+    mutation_definition::lock_row(tx, "issues", issue_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, %issue_id, "failed to lock issue");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to find issue")
+        })?;
+    let issue = IssueRepository::find_by_id(&mut **tx, issue_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, %issue_id, "failed to find issue");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to find issue")
+        })?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "issue not found"))?;
+    let require_if_match = ProjectRepository::require_if_match(&mut **tx, issue.project_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, %issue_id, "failed to load project settings");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to find issue")
+        })?
+        .unwrap_or(false);
+    mutation_definition::check_if_match(
+        &issue,
+        issue.updated_at,
+        if_match,
+        changes.expected_updated_at.is_some(),
+        require_if_match,
+    )?;
+    mutation_definition::check_version(
+        &issue,
+        issue.updated_at,
+        changes.expected_updated_at,
+        changes,
+    )?;
+    if let Some(to_status_id) = changes.status_id {
+        ensure_status_transition(tx, &issue, to_status_id).await?;
+    }
+    Ok(issue)
+}
+
+/// Reject moving `issue` to a status its current status doesn't lead to,
+/// naming the statuses it may move to instead.
+pub(super) async fn ensure_status_transition(
+    tx: &mut Tx<'_>,
+    issue: &Issue,
+    to_status_id: Uuid,
+) -> Result<(), ErrorResponse> {
+    let allowed_status_ids = ProjectStatusWorkflowRepository::disallowed_transition(
+        &mut **tx,
+        issue.status_id,
+        to_status_id,
+    )
+    .await
+    .map_err(|error| {
+        tracing::error!(?error, issue_id = %issue.id, "failed to check status transition");
+        ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+    })?;
+    let Some(allowed_status_ids) = allowed_status_ids else {
+        return Ok(());
+    };
+    let transition = StatusTransitionError {
+        issue_id: issue.id,
+        from_status_id: issue.status_id,
+        to_status_id,
+        allowed_status_ids,
+    };
+    Err(ErrorResponse::transition(
+        "the issue can't move to this status from its current one",
+        serde_json::to_value(transition).unwrap_or_default(),
+    ))
+}
+
+pub(super) async fn notify_issue_update_changes(
     state: &AppState,
     organization_id: Uuid,
     actor_user_id: Uuid,
     old_issue: &Issue,
     new_issue: &Issue,
 ) {
+    query_subscriptions::evaluate_issue(state.pool(), organization_id, actor_user_id, new_issue)
+        .await;
+
     let status_changed = old_issue.status_id != new_issue.status_id;
     let title_changed = old_issue.title != new_issue.title;
     let description_changed = old_issue.description != new_issue.description;
@@ -181,7 +280,7 @@ async fn list_issues(
     State(state): State<AppState>,
     Extension(ctx): Extension<RequestContext>,
     Query(query): Query<ListIssuesQuery>,
-) -> Result<Json<ListIssuesResponse>, ErrorResponse> {
+) -> Result<Json<Value>, ErrorResponse> {
     let project_id = query.project_id;
     ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
     let request = SearchIssuesRequest {
@@ -199,6 +298,7 @@ async fn list_issues(
         sort_direction: None,
         limit: None,
         offset: None,
+        fields: None,
     };
 
     let response = IssueRepository::search(state.pool(), &request)
@@ -208,7 +308,7 @@ async fn list_issues(
             ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to list issues")
         })?;
 
-    Ok(Json(response))
+    sparse_issues(response, query.fields.as_deref())
 }
 
 #[instrument(
@@ -220,7 +320,7 @@ async fn search_issues(
     State(state): State<AppState>,
     Extension(ctx): Extension<RequestContext>,
     Json(payload): Json<SearchIssuesRequest>,
-) -> Result<Json<ListIssuesResponse>, ErrorResponse> {
+) -> Result<Json<Value>, ErrorResponse> {
     ensure_project_access(state.pool(), ctx.user.id, payload.project_id).await?;
 
     let response = IssueRepository::search(state.pool(), &payload)
@@ -230,7 +330,23 @@ async fn search_issues(
             ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to search issues")
         })?;
 
-    Ok(Json(response))
+    sparse_issues(response, payload.fields.as_deref())
+}
+
+/// Trim each listed issue to the requested `fields`, letting the board fetch
+/// only what it renders.
+fn sparse_issues(
+    response: ListIssuesResponse,
+    fields: Option<&str>,
+) -> Result<Json<Value>, ErrorResponse> {
+    let mut body = serde_json::to_value(&response).map_err(|error| {
+        tracing::error!(?error, "failed to serialize issues");
+        ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to list issues")
+    })?;
+    if let Some(selection) = FieldSelection::parse(fields) {
+        selection.retain(&mut body["issues"]);
+    }
+    Ok(Json(body))
 }
 
 #[instrument(
@@ -242,7 +358,7 @@ async fn get_issue(
     State(state): State<AppState>,
     Extension(ctx): Extension<RequestContext>,
     Path(issue_id): Path<Uuid>,
-) -> Result<Json<Issue>, ErrorResponse> {
+) -> Result<(HeaderMap, Json<Issue>), ErrorResponse> {
     let issue = IssueRepository::find_by_id(state.pool(), issue_id)
         .await
         .map_err(|error| {
@@ -253,7 +369,10 @@ async fn get_issue(
 
     ensure_project_access(state.pool(), ctx.user.id, issue.project_id).await?;
 
-    Ok(Json(issue))
+    Ok((
+        mutation_definition::etag_headers(issue.updated_at),
+        Json(issue),
+    ))
 }
 
 #[instrument(
@@ -304,6 +423,9 @@ async fn create_issue(
         tracing::warn!(?e, issue_id = %response.data.id, "failed to auto-follow issue for creator");
     }
 
+    query_subscriptions::evaluate_issue(state.pool(), organization_id, ctx.user.id, &response.data)
+        .await;
+
     if let Some(analytics) = state.analytics() {
         analytics.track(
             ctx.user.id,
@@ -344,8 +466,9 @@ async fn update_issue(
     State(state): State<AppState>,
     Extension(ctx): Extension<RequestContext>,
     Path(issue_id): Path<Uuid>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateIssueRequest>,
-) -> Result<Json<MutationResponse<Issue>>, ErrorResponse> {
+) -> Result<(HeaderMap, Json<MutationResponse<Issue>>), ErrorResponse> {
     let issue = IssueRepository::find_by_id(state.pool(), issue_id)
         .await
         .map_err(|error| {
@@ -361,6 +484,13 @@ async fn update_issue(
         tracing::error!(?error, "failed to begin transaction");
         ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
     })?;
+    let issue = lock_issue_for_update(
+        &mut tx,
+        issue_id,
+        &payload,
+        mutation_definition::if_match(&headers),
+    )
+    .await?;
 
     let data = IssueRepository::update(
         &mut *tx,
@@ -395,6 +525,120 @@ async fn update_issue(
 
     notify_issue_update_changes(&state, organization_id, ctx.user.id, &issue, &data).await;
 
+    Ok((
+        mutation_definition::etag_headers(data.updated_at),
+        Json(MutationResponse { data, txid }),
+    ))
+}
+
+/// Move an issue to a position in a status column, ranking it between its
+/// new neighbours. The column is locked so concurrent moves within it are
+/// applied one after another.
+#[instrument(
+    name = "issues.reorder_issue",
+    skip(state, ctx, payload),
+    fields(issue_id = %issue_id, user_id = %ctx.user.id)
+)]
+async fn reorder_issue(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(issue_id): Path<Uuid>,
+    Json(payload): Json<ReorderIssueRequest>,
+) -> Result<Json<MutationResponse<Issue>>, ErrorResponse> {
+    let internal_error = |error: &dyn std::fmt::Debug, message: &str| {
+        tracing::error!(?error, %issue_id, "{message}");
+        ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+    };
+
+    let mut tx = crate::db::begin_tx(state.pool())
+        .await
+        .map_err(|error| internal_error(&error, "failed to begin transaction"))?;
+    mutation_definition::lock_row(&mut tx, "issues", issue_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to lock issue"))?;
+    let issue = IssueRepository::find_by_id(&mut *tx, issue_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load issue"))?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "issue not found"))?;
+    let organization_id =
+        ensure_project_access(state.pool(), ctx.user.id, issue.project_id).await?;
+
+    let status_id = payload.status_id.unwrap_or(issue.status_id);
+    if status_id != issue.status_id {
+        let status = ProjectStatusRepository::find_by_id(&mut *tx, status_id)
+            .await
+            .map_err(|error| internal_error(&error, "failed to load project status"))?;
+        if status.is_none_or(|status| status.project_id != issue.project_id) {
+            return Err(ErrorResponse::new(
+                StatusCode::BAD_REQUEST,
+                "status does not belong to the issue's project",
+            ));
+        }
+        ensure_status_transition(&mut tx, &issue, status_id).await?;
+    }
+
+    let column = IssueRepository::lock_status_column(&mut tx, status_id, issue_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to lock status column"))?;
+    let neighbour_index = |neighbour_id: Uuid| {
+        column
+            .iter()
+            .position(|(id, _)| *id == neighbour_id)
+            .ok_or_else(|| {
+                ErrorResponse::new(
+                    StatusCode::BAD_REQUEST,
+                    "neighbouring issue is not in the target column",
+                )
+            })
+    };
+    let position = match (payload.previous_issue_id, payload.next_issue_id) {
+        (Some(previous_id), _) => neighbour_index(previous_id)? + 1,
+        (None, Some(next_id)) => neighbour_index(next_id)?,
+        (None, None) => column.len(),
+    };
+
+    let previous = position.checked_sub(1).map(|index| column[index].1);
+    let next = column.get(position).map(|(_, sort_order)| *sort_order);
+    let sort_order = match issue_ranks::rank_between(previous, next) {
+        Some(sort_order) => sort_order,
+        None => {
+            let ids: Vec<Uuid> = column.iter().map(|(id, _)| *id).collect();
+            let ranks = issue_ranks::rebalance(&ids, issue_id, position);
+            IssueRepository::set_sort_orders(&mut tx, &ranks)
+                .await
+                .map_err(|error| internal_error(&error, "failed to rebalance status column"))?;
+            tracing::info!(%status_id, issues = ranks.len(), "rebalanced issue ranks");
+            ranks[position].1
+        }
+    };
+
+    let data = IssueRepository::update(
+        &mut *tx,
+        issue_id,
+        Some(status_id),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(sort_order),
+        None,
+        None,
+        None,
+    )
+    .await
+    .map_err(|error| internal_error(&error, "failed to update issue"))?;
+
+    let txid = get_txid(&mut *tx)
+        .await
+        .map_err(|error| internal_error(&error, "failed to get txid"))?;
+    tx.commit()
+        .await
+        .map_err(|error| internal_error(&error, "failed to commit transaction"))?;
+
+    notify_issue_update_changes(&state, organization_id, ctx.user.id, &issue, &data).await;
+
     Ok(Json(MutationResponse { data, txid }))
 }
 
@@ -418,6 +662,7 @@ async fn delete_issue(
 
     let organization_id =
         ensure_project_access(state.pool(), ctx.user.id, issue.project_id).await?;
+    ensure_no_legal_hold(state.pool(), issue.project_id).await?;
 
     let recipients = match collect_issue_recipients(
         state.pool(),
@@ -522,13 +767,7 @@ async fn bulk_update_issues(
 
     for item in payload.updates {
         // Verify issue belongs to the same project
-        let issue = IssueRepository::find_by_id(&mut *tx, item.id)
-            .await
-            .map_err(|error| {
-                tracing::error!(?error, issue_id = %item.id, "failed to find issue");
-                ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to find issue")
-            })?
-            .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "issue not found"))?;
+        let issue = lock_issue_for_update(&mut tx, item.id, &item.changes, None).await?;
 
         if issue.project_id != project_id {
             return Err(ErrorResponse::new(
@@ -582,3 +821,188 @@ async fn bulk_update_issues(
         txid,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    use super::*;
+    use crate::test_support::{
+        Member, create_issue, create_member, error_body, test_pool, test_state,
+    };
+
+    /// Let issues in `from` move only to `to`.
+    async fn restrict(pool: &PgPool, from: Uuid, to: &[Uuid]) {
+        let mut conn = pool.acquire().await.unwrap();
+        ProjectStatusWorkflowRepository::set_transitions(&mut conn, from, to)
+            .await
+            .unwrap();
+    }
+
+    async fn update(
+        pool: &PgPool,
+        member: &Member,
+        issue_id: Uuid,
+        status_id: Uuid,
+    ) -> Result<Issue, ErrorResponse> {
+        let payload = serde_json::from_value(json!({ "status_id": status_id })).unwrap();
+        update_issue(
+            State(test_state(pool.clone())),
+            Extension(member.ctx.clone()),
+            Path(issue_id),
+            HeaderMap::new(),
+            Json(payload),
+        )
+        .await
+        .map(|(_, Json(response))| response.data)
+    }
+
+    async fn assert_transition_rejected(
+        error: ErrorResponse,
+        issue: &Issue,
+        to_status_id: Uuid,
+        allowed_status_ids: &[Uuid],
+    ) {
+        let (status, body) = error_body(error).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            body["transition"],
+            json!({
+                "issue_id": issue.id,
+                "from_status_id": issue.status_id,
+                "to_status_id": to_status_id,
+                "allowed_status_ids": allowed_status_ids,
+            })
+        );
+    }
+
+    async fn status_of(pool: &PgPool, issue_id: Uuid) -> Uuid {
+        IssueRepository::find_by_id(pool, issue_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .status_id
+    }
+
+    #[tokio::test]
+    async fn disallowed_transition_names_the_allowed_statuses() {
+        let pool = test_pool().await;
+        let member = create_member(&pool).await;
+        let (todo, in_progress, done) = (
+            member.status("To do"),
+            member.status("In progress"),
+            member.status("Done"),
+        );
+        restrict(&pool, todo, &[in_progress]).await;
+
+        let check =
+            |from, to| ProjectStatusWorkflowRepository::disallowed_transition(&pool, from, to);
+        assert_eq!(check(todo, done).await.unwrap(), Some(vec![in_progress]));
+        assert_eq!(check(todo, in_progress).await.unwrap(), None);
+        assert_eq!(check(todo, todo).await.unwrap(), None);
+        assert_eq!(check(in_progress, todo).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn update_rejects_a_disallowed_move() {
+        let pool = test_pool().await;
+        let member = create_member(&pool).await;
+        let (todo, in_progress, done) = (
+            member.status("To do"),
+            member.status("In progress"),
+            member.status("Done"),
+        );
+        restrict(&pool, todo, &[in_progress]).await;
+        let issue = create_issue(&pool, &member, todo).await;
+
+        let error = update(&pool, &member, issue.id, done).await.unwrap_err();
+        assert_transition_rejected(error, &issue, done, &[in_progress]).await;
+        assert_eq!(status_of(&pool, issue.id).await, todo);
+
+        let moved = update(&pool, &member, issue.id, in_progress).await.unwrap();
+        assert_eq!(moved.status_id, in_progress);
+    }
+
+    #[tokio::test]
+    async fn a_status_without_rules_allows_any_move() {
+        let pool = test_pool().await;
+        let member = create_member(&pool).await;
+        let (todo, in_progress) = (member.status("To do"), member.status("In progress"));
+        restrict(&pool, todo, &[in_progress]).await;
+        let issue = create_issue(&pool, &member, in_progress).await;
+
+        // None of these statuses has rules of its own.
+        for name in ["Backlog", "Done", "To do"] {
+            let moved = update(&pool, &member, issue.id, member.status(name))
+                .await
+                .unwrap();
+            assert_eq!(moved.status_id, member.status(name));
+        }
+    }
+
+    #[tokio::test]
+    async fn bulk_update_rejects_a_disallowed_move() {
+        let pool = test_pool().await;
+        let member = create_member(&pool).await;
+        let (todo, in_progress, done) = (
+            member.status("To do"),
+            member.status("In progress"),
+            member.status("Done"),
+        );
+        restrict(&pool, todo, &[in_progress]).await;
+        let allowed = create_issue(&pool, &member, todo).await;
+        let disallowed = create_issue(&pool, &member, todo).await;
+        let payload = serde_json::from_value(json!({
+            "updates": [
+                { "id": allowed.id, "status_id": in_progress },
+                { "id": disallowed.id, "status_id": done },
+            ]
+        }))
+        .unwrap();
+
+        let error = bulk_update_issues(
+            State(test_state(pool.clone())),
+            Extension(member.ctx.clone()),
+            Json(payload),
+        )
+        .await
+        .unwrap_err();
+
+        assert_transition_rejected(error, &disallowed, done, &[in_progress]).await;
+        assert_eq!(status_of(&pool, allowed.id).await, todo);
+        assert_eq!(status_of(&pool, disallowed.id).await, todo);
+    }
+
+    #[tokio::test]
+    async fn reorder_rejects_a_disallowed_move() {
+        let pool = test_pool().await;
+        let member = create_member(&pool).await;
+        let (todo, in_progress, done) = (
+            member.status("To do"),
+            member.status("In progress"),
+            member.status("Done"),
+        );
+        restrict(&pool, todo, &[in_progress]).await;
+        let issue = create_issue(&pool, &member, todo).await;
+        let reorder = |status_id| {
+            reorder_issue(
+                State(test_state(pool.clone())),
+                Extension(member.ctx.clone()),
+                Path(issue.id),
+                Json(ReorderIssueRequest {
+                    status_id: Some(status_id),
+                    previous_issue_id: None,
+                    next_issue_id: None,
+                }),
+            )
+        };
+
+        let error = reorder(done).await.unwrap_err();
+        assert_transition_rejected(error, &issue, done, &[in_progress]).await;
+        assert_eq!(status_of(&pool, issue.id).await, todo);
+
+        let Json(moved) = reorder(in_progress).await.unwrap();
+        assert_eq!(moved.data.status_id, in_progress);
+    }
+}
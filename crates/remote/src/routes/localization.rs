@@ -0,0 +1,181 @@
+use api_types::{
+    ListNotificationTemplatesResponse, NotificationTemplate, ProjectNotificationLocale,
+    UpdateProjectNotificationLocaleRequest, UpsertNotificationTemplateRequest,
+};
+use axum::{
+    Json, Router,
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    routing::{delete, get, put},
+};
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{
+    error::ErrorResponse,
+    organization_members::{ensure_admin_access, ensure_project_access},
+};
+use crate::{
+    AppState,
+    auth::RequestContext,
+    db::notification_templates::NotificationTemplateRepository,
+    localization::{normalize_locale, validate_template},
+};
+
+pub(super) fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/projects/{project_id}/notification-templates",
+            get(list_templates).put(upsert_template),
+        )
+        .route(
+            "/projects/{project_id}/notification-locale",
+            put(update_project_locale),
+        )
+        .route(
+            "/notification-templates/{template_id}",
+            delete(delete_template),
+        )
+}
+
+fn internal_error(error: &dyn std::fmt::Debug, message: &str) -> ErrorResponse {
+    tracing::error!(?error, "{message}");
+    ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+}
+
+fn parse_locale(locale: Option<&str>) -> Result<Option<String>, ErrorResponse> {
+    locale
+        .map(str::trim)
+        .filter(|locale| !locale.is_empty())
+        .map(|locale| {
+            normalize_locale(locale)
+                .map_err(|error| ErrorResponse::new(StatusCode::BAD_REQUEST, error.to_string()))
+        })
+        .transpose()
+}
+
+/// Require the requester to administer the project's organization.
+async fn ensure_project_admin(
+    state: &AppState,
+    ctx: &RequestContext,
+    project_id: Uuid,
+) -> Result<(), ErrorResponse> {
+    let organization_id = ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
+    ensure_admin_access(state.pool(), organization_id, ctx.user.id).await
+}
+
+#[instrument(
+    name = "localization.list_templates",
+    skip(state, ctx),
+    fields(project_id = %project_id, user_id = %ctx.user.id)
+)]
+async fn list_templates(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+) -> Result<Json<ListNotificationTemplatesResponse>, ErrorResponse> {
+    ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
+    let default_locale =
+        NotificationTemplateRepository::default_locales(state.pool(), &[project_id])
+            .await
+            .map_err(|error| internal_error(&error, "failed to load project locale"))?
+            .into_iter()
+            .next()
+            .map(|(_, locale)| locale);
+    let templates = NotificationTemplateRepository::list_by_projects(state.pool(), &[project_id])
+        .await
+        .map_err(|error| internal_error(&error, "failed to list notification templates"))?;
+
+    Ok(Json(ListNotificationTemplatesResponse {
+        default_locale,
+        templates,
+    }))
+}
+
+#[instrument(
+    name = "localization.upsert_template",
+    skip(state, ctx, payload),
+    fields(project_id = %project_id, user_id = %ctx.user.id)
+)]
+async fn upsert_template(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+    Json(payload): Json<UpsertNotificationTemplateRequest>,
+) -> Result<Json<NotificationTemplate>, ErrorResponse> {
+    ensure_project_admin(&state, &ctx, project_id).await?;
+    let locale = parse_locale(Some(&payload.locale))?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::BAD_REQUEST, "locale is required"))?;
+    let title = payload.title.trim();
+    let body = payload
+        .body
+        .as_deref()
+        .map(str::trim)
+        .filter(|body| !body.is_empty());
+    for template in std::iter::once(title).chain(body) {
+        validate_template(payload.notification_type, template)
+            .map_err(|error| ErrorResponse::new(StatusCode::BAD_REQUEST, error.to_string()))?;
+    }
+
+    let template = NotificationTemplateRepository::upsert(
+        state.pool(),
+        project_id,
+        payload.notification_type,
+        &locale,
+        title,
+        body,
+    )
+    .await
+    .map_err(|error| internal_error(&error, "failed to store notification template"))?;
+
+    Ok(Json(template))
+}
+
+#[instrument(
+    name = "localization.update_project_locale",
+    skip(state, ctx, payload),
+    fields(project_id = %project_id, user_id = %ctx.user.id)
+)]
+async fn update_project_locale(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+    Json(payload): Json<UpdateProjectNotificationLocaleRequest>,
+) -> Result<Json<ProjectNotificationLocale>, ErrorResponse> {
+    ensure_project_admin(&state, &ctx, project_id).await?;
+    let default_locale = parse_locale(payload.default_locale.as_deref())?;
+    NotificationTemplateRepository::set_default_locale(
+        state.pool(),
+        project_id,
+        default_locale.as_deref(),
+    )
+    .await
+    .map_err(|error| internal_error(&error, "failed to update project locale"))?;
+
+    Ok(Json(ProjectNotificationLocale {
+        project_id,
+        default_locale,
+    }))
+}
+
+#[instrument(
+    name = "localization.delete_template",
+    skip(state, ctx),
+    fields(template_id = %template_id, user_id = %ctx.user.id)
+)]
+async fn delete_template(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(template_id): Path<Uuid>,
+) -> Result<StatusCode, ErrorResponse> {
+    let template = NotificationTemplateRepository::find_by_id(state.pool(), template_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load notification template"))?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "template not found"))?;
+    ensure_project_admin(&state, &ctx, template.project_id).await?;
+    NotificationTemplateRepository::delete(state.pool(), template_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to delete notification template"))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
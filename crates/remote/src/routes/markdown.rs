@@ -0,0 +1,51 @@
+use api_types::{RenderMarkdownRequest, RenderedMarkdown};
+use axum::{
+    Json, Router,
+    extract::{Extension, State},
+    http::StatusCode,
+    routing::post,
+};
+use tracing::instrument;
+
+use super::{error::ErrorResponse, organization_members::ensure_project_access};
+use crate::{AppState, auth::RequestContext, markdown};
+
+const MAX_MARKDOWN_LENGTH: usize = 1024 * 1024;
+
+pub(super) fn router() -> Router<AppState> {
+    Router::new().route("/markdown/render", post(render_markdown))
+}
+
+#[instrument(
+    name = "markdown.render_markdown",
+    skip(state, ctx, payload),
+    fields(project_id = %payload.project_id, user_id = %ctx.user.id)
+)]
+async fn render_markdown(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Json(payload): Json<RenderMarkdownRequest>,
+) -> Result<Json<RenderedMarkdown>, ErrorResponse> {
+    let organization_id =
+        ensure_project_access(state.pool(), ctx.user.id, payload.project_id).await?;
+    if payload.markdown.len() > MAX_MARKDOWN_LENGTH {
+        return Err(ErrorResponse::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "markdown is limited to 1 MiB",
+        ));
+    }
+
+    let rendered = markdown::render_cached(
+        state.pool(),
+        organization_id,
+        payload.project_id,
+        &payload.markdown,
+    )
+    .await
+    .map_err(|error| {
+        tracing::error!(?error, "failed to render markdown");
+        ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+    })?;
+
+    Ok(Json(rendered))
+}
@@ -9,8 +9,9 @@ use tower_http::{
 };
 use tracing::{Level, Span, field};
 
-use crate::{AppState, auth::require_session};
+use crate::{AppState, auth::require_session, middleware::version::ApiVersion};
 
+mod account_data;
 #[cfg(feature = "vk-billing")]
 mod billing;
 #[cfg(not(feature = "vk-billing"))]
@@ -26,30 +27,47 @@ mod billing {
     }
 }
 pub mod attachments;
+mod calendar;
+mod documents;
 pub(crate) mod electric_proxy;
 pub(crate) mod error;
 mod export;
 mod github_app;
 pub mod hosts;
 mod identity;
+mod ip_allowlist;
 pub mod issue_assignees;
+mod issue_code_references;
 pub mod issue_comment_reactions;
 pub mod issue_comments;
 pub mod issue_followers;
+pub mod issue_organization_tags;
 pub mod issue_relationships;
 pub mod issue_tags;
+mod issue_triage;
 pub mod issues;
+mod localization;
+mod markdown;
+pub mod mutations;
 pub mod notifications;
 mod oauth;
 pub(crate) mod organization_members;
+pub mod organization_tags;
 mod organizations;
 pub mod project_statuses;
 pub mod projects;
 pub mod pull_request_issues;
 mod pull_requests;
+mod query_subscriptions;
+mod release_notes;
+mod retention;
 mod review;
+mod session_anomalies;
+mod sla;
 pub mod tags;
+mod timeline;
 mod tokens;
+mod work_logs;
 mod workspaces;
 
 pub fn router(state: AppState) -> Router {
@@ -105,12 +123,19 @@ pub fn router(state: AppState) -> Router {
         .merge(oauth::public_router())
         .merge(organization_members::public_router())
         .merge(tokens::public_router())
+        .merge(calendar::public_router())
         .merge(review::public_router())
         .merge(github_app::public_router())
         .merge(billing::public_router());
 
+    // Routes whose breaking replacements ship under /v2. Unchanged endpoints
+    // stay on /v1 only.
+    let v2_public = Router::<AppState>::new();
+    let v2_protected = Router::<AppState>::new();
+
     let v1_protected = Router::<AppState>::new()
         .merge(identity::router())
+        .merge(account_data::router())
         .merge(hosts::router())
         .merge(projects::router())
         .merge(organizations::router())
@@ -120,13 +145,29 @@ pub fn router(state: AppState) -> Router {
         .merge(github_app::protected_router())
         .merge(project_statuses::router())
         .merge(tags::router())
+        .merge(organization_tags::router())
         .merge(issue_comments::router())
         .merge(issue_comment_reactions::router())
         .merge(issues::router())
+        .merge(issue_triage::router())
+        .merge(release_notes::router())
+        .merge(timeline::router())
+        .merge(work_logs::router())
+        .merge(query_subscriptions::router())
+        .merge(documents::router())
+        .merge(markdown::router())
+        .merge(issue_code_references::router())
+        .merge(localization::router())
+        .merge(sla::router())
+        .merge(retention::router())
+        .merge(ip_allowlist::router())
+        .merge(session_anomalies::router())
+        .merge(mutations::router())
         .merge(issue_assignees::router())
         .merge(attachments::router())
         .merge(issue_followers::router())
         .merge(issue_tags::router())
+        .merge(issue_organization_tags::router())
         .merge(issue_relationships::router())
         .merge(pull_request_issues::router())
         .merge(pull_requests::router())
@@ -134,18 +175,17 @@ pub fn router(state: AppState) -> Router {
         .merge(workspaces::router())
         .merge(billing::protected_router())
         .merge(export::router())
-        .layer(middleware::from_fn_with_state(
-            state.clone(),
-            require_session,
-        ));
+        .merge(calendar::protected_router());
 
     let static_dir = "/srv/static";
     let spa =
         ServeDir::new(static_dir).fallback(ServeFile::new(format!("{static_dir}/index.html")));
 
     Router::<AppState>::new()
-        .nest("/v1", v1_public)
-        .nest("/v1", v1_protected)
+        .nest(ApiVersion::V1.prefix(), v1_public)
+        .nest(ApiVersion::V1.prefix(), protected(v1_protected, &state))
+        .nest(ApiVersion::V2.prefix(), v2_public)
+        .nest(ApiVersion::V2.prefix(), protected(v2_protected, &state))
         .fallback_service(spa)
         .layer(CompressionLayer::new())
         .layer(middleware::from_fn(
@@ -169,6 +209,33 @@ pub fn router(state: AppState) -> Router {
         .with_state(state)
 }
 
+/// Require a session, an address the organization allows and the plan
+/// entitlements on every route of `router`, count the session's usage, and
+/// honour `Idempotency-Key` on its POST routes.
+fn protected(router: Router<AppState>, state: &AppState) -> Router<AppState> {
+    router
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::idempotency::replay_idempotent_requests,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::entitlements::require_plan_entitlements,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::ip_allowlist::require_allowed_ip,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::session_usage::record_session_usage,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_session,
+        ))
+}
+
 #[derive(Serialize)]
 struct HealthResponse {
     status: &'static str,
@@ -182,20 +249,53 @@ async fn health() -> Json<HealthResponse> {
     })
 }
 
+/// Collect every deprecated route, for the versioning compatibility checks.
+#[cfg(test)]
+pub(crate) fn all_deprecated_routes() -> Vec<&'static crate::middleware::version::DeprecatedRoute> {
+    vec![&pull_requests::CREATE_PULL_REQUEST_DEPRECATION]
+}
+
 /// Collect all mutation definitions for TypeScript generation.
 pub fn all_mutation_definitions() -> Vec<crate::mutation_definition::MutationDefinition> {
     vec![
         projects::mutation().definition(),
         notifications::mutation().definition(),
         tags::mutation().definition(),
+        organization_tags::mutation().definition(),
         project_statuses::mutation().definition(),
         issues::mutation().definition(),
         issue_assignees::mutation().definition(),
         issue_followers::mutation().definition(),
         issue_tags::mutation().definition(),
+        issue_organization_tags::mutation().definition(),
         issue_relationships::mutation().definition(),
         issue_comments::mutation().definition(),
         issue_comment_reactions::mutation().definition(),
         pull_request_issues::mutation().definition(),
+        mutations::mutation().definition(),
+    ]
+}
+
+/// Collect all query definitions for TypeScript generation.
+pub fn all_query_definitions() -> Vec<crate::query_definition::QueryDefinition> {
+    [
+        projects::queries(),
+        tags::queries(),
+        organization_tags::queries(),
+        project_statuses::queries(),
+        issues::queries(),
+        issue_assignees::queries(),
+        issue_followers::queries(),
+        issue_tags::queries(),
+        issue_organization_tags::queries(),
+        issue_relationships::queries(),
+        issue_comments::queries(),
+        issue_comment_reactions::queries(),
+        notifications::queries(),
+        hosts::queries(),
     ]
+    .iter()
+    .flatten()
+    .map(|query| query.definition())
+    .collect()
 }
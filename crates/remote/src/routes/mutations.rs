@@ -0,0 +1,328 @@
+use std::collections::{HashMap, HashSet};
+
+use api_types::{
+    BatchMutation, BatchMutationRequest, BatchMutationResult, Issue, MutationResponse,
+};
+use axum::{
+    Json,
+    extract::{Extension, State},
+    http::StatusCode,
+};
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{
+    error::ErrorResponse,
+    issues::{lock_issue_for_update, notify_issue_update_changes},
+    organization_members::{ensure_member_access, ensure_project_access},
+};
+use crate::{
+    AppState,
+    auth::RequestContext,
+    db::{
+        Tx, get_txid, issues::IssueRepository, project_statuses::ProjectStatusRepository,
+        projects::ProjectRepository, types::is_valid_hsl_color,
+    },
+    mutation_definition::{MutationBuilder, NoUpdate},
+};
+
+/// Upper bound on mutations per batch, to keep transactions short.
+const MAX_BATCH_MUTATIONS: usize = 500;
+
+/// Mutation definition for batches - exposes `/v1/mutations/batch` to the
+/// TypeScript client.
+pub fn mutation() -> MutationBuilder<BatchMutationResult, BatchMutationRequest, NoUpdate> {
+    MutationBuilder::new("mutations/batch").create(apply_batch)
+}
+
+pub fn router() -> axum::Router<AppState> {
+    mutation().router()
+}
+
+/// Access checks already passed in this batch.
+#[derive(Default)]
+struct AccessCache {
+    projects: HashMap<Uuid, Uuid>,
+    organizations: HashSet<Uuid>,
+}
+
+impl AccessCache {
+    async fn project(
+        &mut self,
+        state: &AppState,
+        user_id: Uuid,
+        project_id: Uuid,
+    ) -> Result<Uuid, ErrorResponse> {
+        if let Some(organization_id) = self.projects.get(&project_id) {
+            return Ok(*organization_id);
+        }
+        let organization_id = ensure_project_access(state.pool(), user_id, project_id).await?;
+        self.projects.insert(project_id, organization_id);
+        self.organizations.insert(organization_id);
+        Ok(organization_id)
+    }
+
+    async fn organization(
+        &mut self,
+        state: &AppState,
+        user_id: Uuid,
+        organization_id: Uuid,
+    ) -> Result<(), ErrorResponse> {
+        if !self.organizations.contains(&organization_id) {
+            ensure_member_access(state.pool(), organization_id, user_id).await?;
+            self.organizations.insert(organization_id);
+        }
+        Ok(())
+    }
+}
+
+fn internal_error(error: impl std::fmt::Debug, message: &'static str) -> ErrorResponse {
+    tracing::error!(?error, "{message}");
+    ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, message)
+}
+
+fn check_color(color: Option<&String>) -> Result<(), ErrorResponse> {
+    if let Some(color) = color
+        && !is_valid_hsl_color(color)
+    {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "Invalid color format. Expected HSL format: 'H S% L%'",
+        ));
+    }
+    Ok(())
+}
+
+/// Apply one mutation within `tx`. Issue updates also return the issue as
+/// it was, for change notifications.
+async fn apply_mutation(
+    state: &AppState,
+    tx: &mut Tx<'_>,
+    access: &mut AccessCache,
+    user_id: Uuid,
+    mutation: BatchMutation,
+) -> Result<(BatchMutationResult, Option<(Uuid, Issue)>), ErrorResponse> {
+    match mutation {
+        BatchMutation::Issues { id, changes } => {
+            let issue = lock_issue_for_update(tx, id, &changes, None).await?;
+            let organization_id = access.project(state, user_id, issue.project_id).await?;
+            let updated = IssueRepository::update(
+                &mut **tx,
+                id,
+                changes.status_id,
+                changes.title,
+                changes.description,
+                changes.priority,
+                changes.start_date,
+                changes.target_date,
+                changes.completed_at,
+                changes.sort_order,
+                changes.parent_issue_id,
+                changes.parent_issue_sort_order,
+                changes.extension_metadata,
+            )
+            .await
+            .map_err(|error| internal_error(error, "failed to update issue"))?;
+            Ok((
+                BatchMutationResult::Issues(updated),
+                Some((organization_id, issue)),
+            ))
+        }
+        BatchMutation::Projects { id, changes } => {
+            let project = ProjectRepository::find_by_id(&mut **tx, id)
+                .await
+                .map_err(|error| internal_error(error, "failed to load project"))?
+                .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "project not found"))?;
+            access
+                .organization(state, user_id, project.organization_id)
+                .await?;
+            check_color(changes.color.as_ref())?;
+            let updated = ProjectRepository::update_partial(
+                &mut **tx,
+                id,
+                changes.name,
+                changes.color,
+                changes.sort_order,
+            )
+            .await
+            .map_err(|error| internal_error(error, "failed to update project"))?;
+            Ok((BatchMutationResult::Projects(updated), None))
+        }
+        BatchMutation::ProjectStatuses { id, changes } => {
+            let status = ProjectStatusRepository::find_by_id(&mut **tx, id)
+                .await
+                .map_err(|error| internal_error(error, "failed to load project status"))?
+                .ok_or_else(|| {
+                    ErrorResponse::new(StatusCode::NOT_FOUND, "project status not found")
+                })?;
+            access.project(state, user_id, status.project_id).await?;
+            check_color(changes.color.as_ref())?;
+            let updated = ProjectStatusRepository::update_partial(
+                &mut **tx,
+                id,
+                changes.name,
+                changes.color,
+                changes.sort_order,
+                changes.hidden,
+            )
+            .await
+            .map_err(|error| internal_error(error, "failed to update project status"))?;
+            Ok((BatchMutationResult::ProjectStatuses(updated), None))
+        }
+    }
+}
+
+/// Apply every mutation in one transaction. The first failure rolls back the
+/// whole batch and is returned as the response.
+#[instrument(
+    name = "mutations.batch",
+    skip(state, ctx, payload),
+    fields(user_id = %ctx.user.id, count = payload.mutations.len())
+)]
+async fn apply_batch(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Json(payload): Json<BatchMutationRequest>,
+) -> Result<Json<MutationResponse<Vec<BatchMutationResult>>>, ErrorResponse> {
+    if payload.mutations.is_empty() {
+        return Ok(Json(MutationResponse {
+            data: vec![],
+            txid: 0,
+        }));
+    }
+    if payload.mutations.len() > MAX_BATCH_MUTATIONS {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            format!("a batch can hold at most {MAX_BATCH_MUTATIONS} mutations"),
+        ));
+    }
+
+    let mut tx = crate::db::begin_tx(state.pool())
+        .await
+        .map_err(|error| internal_error(error, "internal server error"))?;
+
+    let mut access = AccessCache::default();
+    let mut results = Vec::with_capacity(payload.mutations.len());
+    let mut issue_changes = Vec::new();
+    for mutation in payload.mutations {
+        let (result, old_issue) =
+            apply_mutation(&state, &mut tx, &mut access, ctx.user.id, mutation).await?;
+        if let (Some((organization_id, old)), BatchMutationResult::Issues(new)) =
+            (old_issue, &result)
+        {
+            issue_changes.push((organization_id, old, new.clone()));
+        }
+        results.push(result);
+    }
+
+    let txid = get_txid(&mut *tx)
+        .await
+        .map_err(|error| internal_error(error, "internal server error"))?;
+    tx.commit()
+        .await
+        .map_err(|error| internal_error(error, "internal server error"))?;
+
+    for (organization_id, old_issue, new_issue) in &issue_changes {
+        notify_issue_update_changes(&state, *organization_id, ctx.user.id, old_issue, new_issue)
+            .await;
+    }
+
+    Ok(Json(MutationResponse {
+        data: results,
+        txid,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        db::project_status_workflows::ProjectStatusWorkflowRepository,
+        test_support::{create_issue, create_member, error_body, test_pool, test_state},
+    };
+
+    #[tokio::test]
+    async fn a_failing_mutation_rolls_back_the_whole_batch() {
+        let pool = test_pool().await;
+        let member = create_member(&pool).await;
+        let issue = create_issue(&pool, &member, member.status("To do")).await;
+        let project = ProjectRepository::find_by_id(&pool, member.project_id)
+            .await
+            .unwrap()
+            .unwrap();
+        let payload: BatchMutationRequest = serde_json::from_value(json!({
+            "mutations": [
+                { "table": "issues", "id": issue.id, "changes": { "title": "Renamed" } },
+                { "table": "projects", "id": project.id, "changes": { "name": "Renamed" } },
+                { "table": "issues", "id": Uuid::new_v4(), "changes": { "title": "Missing" } },
+            ]
+        }))
+        .unwrap();
+
+        let error = apply_batch(
+            State(test_state(pool.clone())),
+            Extension(member.ctx),
+            Json(payload),
+        )
+        .await
+        .unwrap_err();
+
+        let (status, _) = error_body(error).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let issue_after = IssueRepository::find_by_id(&pool, issue.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(issue_after.title, issue.title);
+        assert_eq!(issue_after.updated_at, issue.updated_at);
+        let project_after = ProjectRepository::find_by_id(&pool, project.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(project_after.name, project.name);
+    }
+
+    #[tokio::test]
+    async fn a_disallowed_status_move_fails_the_batch() {
+        let pool = test_pool().await;
+        let member = create_member(&pool).await;
+        let (todo, in_progress, done) = (
+            member.status("To do"),
+            member.status("In progress"),
+            member.status("Done"),
+        );
+        let mut conn = pool.acquire().await.unwrap();
+        ProjectStatusWorkflowRepository::set_transitions(&mut conn, todo, &[in_progress])
+            .await
+            .unwrap();
+        let issue = create_issue(&pool, &member, todo).await;
+        let payload: BatchMutationRequest = serde_json::from_value(json!({
+            "mutations": [
+                { "table": "issues", "id": issue.id, "changes": { "status_id": done } },
+            ]
+        }))
+        .unwrap();
+
+        let error = apply_batch(
+            State(test_state(pool.clone())),
+            Extension(member.ctx),
+            Json(payload),
+        )
+        .await
+        .unwrap_err();
+
+        let (status, body) = error_body(error).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            body["transition"],
+            json!({
+                "issue_id": issue.id,
+                "from_status_id": todo,
+                "to_status_id": done,
+                "allowed_status_ids": [in_progress],
+            })
+        );
+    }
+}
@@ -1,20 +1,28 @@
-use api_types::{DeleteResponse, MutationResponse, Notification, UpdateNotificationRequest};
+use api_types::{
+    DeleteResponse, ListNotificationIssueMutesResponse, MarkNotificationsRequest,
+    MarkNotificationsResponse, MutationResponse, Notification, NotificationIssueMute,
+    UnreadNotificationCountQuery, UnreadNotificationCountResponse, UpdateNotificationRequest,
+};
 use axum::{
     Json, Router,
     extract::{Extension, Path, Query, State},
     http::StatusCode,
-    routing::post,
+    routing::{post, put},
 };
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use uuid::Uuid;
 
-use super::error::ErrorResponse;
+use super::{error::ErrorResponse, organization_members::ensure_issue_access};
 use crate::{
     AppState,
     auth::RequestContext,
-    db::{get_txid, notifications::NotificationRepository},
+    db::{
+        get_txid,
+        notifications::{NotificationMuteRepository, NotificationRepository},
+    },
     mutation_definition::{MutationBuilder, NoCreate},
+    query_definition::{QueryBuilder, QueryRoute, query_router},
 };
 
 #[derive(Debug, Serialize)]
@@ -54,10 +62,37 @@ pub fn mutation() -> MutationBuilder<Notification, NoCreate, UpdateNotificationR
         .delete(delete_notification)
 }
 
+/// Read endpoints for notification state.
+pub fn queries() -> Vec<QueryRoute> {
+    vec![
+        QueryBuilder::<UnreadNotificationCountResponse>::new(
+            "unread_notification_count",
+            "notifications/unread_count",
+        )
+        .get(unread_notification_count),
+        QueryBuilder::<ListNotificationIssueMutesResponse>::new(
+            "list_notification_issue_mutes",
+            "notifications/mutes",
+        )
+        .get_without_params(list_notification_issue_mutes),
+    ]
+}
+
 pub fn router() -> Router<AppState> {
     mutation()
         .router()
+        .merge(query_router(queries()))
         .route("/notifications/bulk", post(bulk_update_notifications))
+        .route("/notifications/mark", post(mark_notifications))
+        .route(
+            "/notifications/mutes/{issue_id}",
+            put(mute_issue_notifications).delete(unmute_issue_notifications),
+        )
+}
+
+fn internal_error(error: impl std::fmt::Debug, message: &'static str) -> ErrorResponse {
+    tracing::error!(?error, "{message}");
+    ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
 }
 
 #[instrument(
@@ -309,3 +344,118 @@ async fn bulk_update_notifications(
         txid,
     }))
 }
+
+/// Unread notifications of the caller. Reads a maintained counter, so it is
+/// cheap enough to poll.
+#[instrument(
+    name = "notifications.unread_count",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id)
+)]
+async fn unread_notification_count(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Query(query): Query<UnreadNotificationCountQuery>,
+) -> Result<Json<UnreadNotificationCountResponse>, ErrorResponse> {
+    let unread_count =
+        NotificationRepository::unread_count(state.pool(), ctx.user.id, query.organization_id)
+            .await
+            .map_err(|error| internal_error(error, "failed to count unread notifications"))?;
+
+    Ok(Json(UnreadNotificationCountResponse { unread_count }))
+}
+
+#[instrument(
+    name = "notifications.mark",
+    skip(state, ctx, payload),
+    fields(user_id = %ctx.user.id, seen = payload.seen)
+)]
+async fn mark_notifications(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Json(payload): Json<MarkNotificationsRequest>,
+) -> Result<Json<MutationResponse<MarkNotificationsResponse>>, ErrorResponse> {
+    let mut tx = state
+        .pool()
+        .begin()
+        .await
+        .map_err(|error| internal_error(error, "failed to begin transaction"))?;
+
+    let updated_count = NotificationRepository::mark(
+        &mut *tx,
+        ctx.user.id,
+        payload.seen,
+        payload.notification_ids.as_deref(),
+        payload.organization_id,
+        payload.issue_id,
+    )
+    .await
+    .map_err(|error| internal_error(error, "failed to mark notifications"))?;
+
+    let txid = get_txid(&mut *tx)
+        .await
+        .map_err(|error| internal_error(error, "failed to get txid"))?;
+    tx.commit()
+        .await
+        .map_err(|error| internal_error(error, "failed to commit transaction"))?;
+
+    Ok(Json(MutationResponse {
+        data: MarkNotificationsResponse {
+            updated_count: updated_count as usize,
+        },
+        txid,
+    }))
+}
+
+#[instrument(
+    name = "notifications.list_mutes",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id)
+)]
+async fn list_notification_issue_mutes(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+) -> Result<Json<ListNotificationIssueMutesResponse>, ErrorResponse> {
+    let mutes = NotificationMuteRepository::list_by_user(state.pool(), ctx.user.id)
+        .await
+        .map_err(|error| internal_error(error, "failed to list notification mutes"))?;
+
+    Ok(Json(ListNotificationIssueMutesResponse { mutes }))
+}
+
+/// Stop notifying the caller about an issue.
+#[instrument(
+    name = "notifications.mute_issue",
+    skip(state, ctx),
+    fields(issue_id = %issue_id, user_id = %ctx.user.id)
+)]
+async fn mute_issue_notifications(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(issue_id): Path<Uuid>,
+) -> Result<Json<NotificationIssueMute>, ErrorResponse> {
+    ensure_issue_access(state.pool(), ctx.user.id, issue_id).await?;
+
+    let mute = NotificationMuteRepository::mute(state.pool(), ctx.user.id, issue_id)
+        .await
+        .map_err(|error| internal_error(error, "failed to mute issue notifications"))?;
+
+    Ok(Json(mute))
+}
+
+#[instrument(
+    name = "notifications.unmute_issue",
+    skip(state, ctx),
+    fields(issue_id = %issue_id, user_id = %ctx.user.id)
+)]
+async fn unmute_issue_notifications(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(issue_id): Path<Uuid>,
+) -> Result<StatusCode, ErrorResponse> {
+    NotificationMuteRepository::unmute(state.pool(), ctx.user.id, issue_id)
+        .await
+        .map_err(|error| internal_error(error, "failed to unmute issue notifications"))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -28,6 +28,7 @@ use crate::{
         organization_members,
         organizations::OrganizationRepository,
         projects::ProjectRepository,
+        user_profiles::UserProfileRepository,
     },
 };
 
@@ -327,7 +328,7 @@ async fn list_members(
     let user = ctx.user;
     ensure_member_access(&state.pool, org_id, user.id).await?;
 
-    let members = sqlx::query_as!(
+    let mut members = sqlx::query_as!(
         OrganizationMemberWithProfile,
         r#"
         SELECT
@@ -338,6 +339,7 @@ async fn list_members(
             u.last_name AS "last_name?",
             u.username AS "username?",
             u.email AS "email?",
+            u.display_name AS "display_name?",
             oa.avatar_url AS "avatar_url?"
         FROM organization_member_metadata omm
         INNER JOIN users u ON omm.user_id = u.id
@@ -357,6 +359,22 @@ async fn list_members(
     .await
     .map_err(|_| ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
 
+    // Uploaded avatars take precedence over OAuth ones.
+    if let Some(azure) = state.azure_blob() {
+        let user_ids: Vec<Uuid> = members.iter().map(|member| member.user_id).collect();
+        let avatars = UserProfileRepository::avatar_blob_paths(&state.pool, &user_ids)
+            .await
+            .map_err(|_| ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+        for member in &mut members {
+            if let Some(url) = avatars
+                .get(&member.user_id)
+                .and_then(|path| azure.create_read_url(path).ok())
+            {
+                member.avatar_url = Some(url);
+            }
+        }
+    }
+
     Ok(Json(ListMembersResponse { members }))
 }
 
@@ -621,7 +639,7 @@ pub(crate) async fn ensure_project_access(
             ErrorResponse::new(StatusCode::NOT_FOUND, "project not found")
         })?;
 
-    organization_members::assert_membership(pool, organization_id, user_id)
+    organization_members::assert_project_membership(pool, organization_id, project_id, user_id)
         .await
         .map_err(|err| {
             if let IdentityError::Database(error) = &err {
@@ -666,7 +684,7 @@ pub(crate) async fn ensure_issue_access(
             ErrorResponse::new(StatusCode::NOT_FOUND, "issue not found")
         })?;
 
-    organization_members::assert_membership(pool, organization_id, user_id)
+    organization_members::assert_issue_membership(pool, organization_id, issue_id, user_id)
         .await
         .map_err(|err| {
             if let IdentityError::Database(error) = &err {
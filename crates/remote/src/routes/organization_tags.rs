@@ -0,0 +1,336 @@
+use std::collections::HashSet;
+
+use api_types::{
+    CreateOrganizationTagRequest, DeleteResponse, ListDuplicateTagsResponse,
+    ListOrganizationTagsQuery, ListOrganizationTagsResponse, MergeTagsRequest, MergeTagsResponse,
+    MutationResponse, OrganizationTag, UpdateOrganizationTagRequest,
+};
+use axum::{
+    Json,
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    routing::post,
+};
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{
+    error::{ErrorResponse, db_error},
+    organization_members::ensure_member_access,
+};
+use crate::{
+    AppState,
+    auth::RequestContext,
+    db::{
+        get_txid,
+        organization_tags::{OrganizationTagRepository, duplicate_groups},
+        types::is_valid_hsl_color,
+    },
+    mutation_definition::MutationBuilder,
+    query_definition::{QueryBuilder, QueryRoute, query_router},
+};
+
+/// Mutation definition for organization tags - provides both router and
+/// TypeScript metadata.
+pub fn mutation()
+-> MutationBuilder<OrganizationTag, CreateOrganizationTagRequest, UpdateOrganizationTagRequest> {
+    MutationBuilder::new("organization_tags")
+        .get(get_organization_tag)
+        .create(create_organization_tag)
+        .update(update_organization_tag)
+        .delete(delete_organization_tag)
+}
+
+/// Read endpoints for organization tags.
+pub fn queries() -> Vec<QueryRoute> {
+    vec![
+        QueryBuilder::<ListOrganizationTagsResponse>::new(
+            "list_organization_tags",
+            "organization_tags",
+        )
+        .get(list_organization_tags),
+        QueryBuilder::<ListDuplicateTagsResponse>::new(
+            "list_duplicate_tags",
+            "organization_tags/duplicates",
+        )
+        .get(list_duplicate_tags),
+    ]
+}
+
+pub fn router() -> axum::Router<AppState> {
+    mutation()
+        .router()
+        .merge(query_router(queries()))
+        .route("/organization_tags/merge", post(merge_tags))
+}
+
+fn invalid_color() -> ErrorResponse {
+    ErrorResponse::new(
+        StatusCode::BAD_REQUEST,
+        "Invalid color format. Expected HSL format: 'H S% L%'",
+    )
+}
+
+async fn load_organization_tag(
+    state: &AppState,
+    organization_tag_id: Uuid,
+) -> Result<OrganizationTag, ErrorResponse> {
+    OrganizationTagRepository::find_by_id(state.pool(), organization_tag_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, %organization_tag_id, "failed to load organization tag");
+            ErrorResponse::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "failed to load organization tag",
+            )
+        })?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "organization tag not found"))
+}
+
+#[instrument(
+    name = "organization_tags.list_organization_tags",
+    skip(state, ctx),
+    fields(organization_id = %query.organization_id, user_id = %ctx.user.id)
+)]
+async fn list_organization_tags(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Query(query): Query<ListOrganizationTagsQuery>,
+) -> Result<Json<ListOrganizationTagsResponse>, ErrorResponse> {
+    ensure_member_access(state.pool(), query.organization_id, ctx.user.id).await?;
+
+    let organization_tags =
+        OrganizationTagRepository::list_by_organization(state.pool(), query.organization_id)
+            .await
+            .map_err(|error| {
+                tracing::error!(?error, organization_id = %query.organization_id, "failed to list organization tags");
+                ErrorResponse::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "failed to list organization tags",
+                )
+            })?;
+
+    Ok(Json(ListOrganizationTagsResponse { organization_tags }))
+}
+
+/// Project tags whose name is used in more than one project.
+#[instrument(
+    name = "organization_tags.list_duplicate_tags",
+    skip(state, ctx),
+    fields(organization_id = %query.organization_id, user_id = %ctx.user.id)
+)]
+async fn list_duplicate_tags(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Query(query): Query<ListOrganizationTagsQuery>,
+) -> Result<Json<ListDuplicateTagsResponse>, ErrorResponse> {
+    ensure_member_access(state.pool(), query.organization_id, ctx.user.id).await?;
+
+    let tags = OrganizationTagRepository::list_project_tags(state.pool(), query.organization_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, organization_id = %query.organization_id, "failed to list project tags");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to list tags")
+        })?;
+
+    Ok(Json(ListDuplicateTagsResponse {
+        groups: duplicate_groups(tags),
+    }))
+}
+
+#[instrument(
+    name = "organization_tags.get_organization_tag",
+    skip(state, ctx),
+    fields(organization_tag_id = %organization_tag_id, user_id = %ctx.user.id)
+)]
+async fn get_organization_tag(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(organization_tag_id): Path<Uuid>,
+) -> Result<Json<OrganizationTag>, ErrorResponse> {
+    let tag = load_organization_tag(&state, organization_tag_id).await?;
+    ensure_member_access(state.pool(), tag.organization_id, ctx.user.id).await?;
+
+    Ok(Json(tag))
+}
+
+#[instrument(
+    name = "organization_tags.create_organization_tag",
+    skip(state, ctx, payload),
+    fields(organization_id = %payload.organization_id, user_id = %ctx.user.id)
+)]
+async fn create_organization_tag(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Json(payload): Json<CreateOrganizationTagRequest>,
+) -> Result<Json<MutationResponse<OrganizationTag>>, ErrorResponse> {
+    ensure_member_access(state.pool(), payload.organization_id, ctx.user.id).await?;
+
+    if !is_valid_hsl_color(&payload.color) {
+        return Err(invalid_color());
+    }
+
+    let response = OrganizationTagRepository::create(
+        state.pool(),
+        payload.id,
+        payload.organization_id,
+        payload.name,
+        payload.color,
+    )
+    .await
+    .map_err(|error| {
+        tracing::error!(?error, "failed to create organization tag");
+        db_error(error, "failed to create organization tag")
+    })?;
+
+    Ok(Json(response))
+}
+
+#[instrument(
+    name = "organization_tags.update_organization_tag",
+    skip(state, ctx, payload),
+    fields(organization_tag_id = %organization_tag_id, user_id = %ctx.user.id)
+)]
+async fn update_organization_tag(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(organization_tag_id): Path<Uuid>,
+    Json(payload): Json<UpdateOrganizationTagRequest>,
+) -> Result<Json<MutationResponse<OrganizationTag>>, ErrorResponse> {
+    let tag = load_organization_tag(&state, organization_tag_id).await?;
+    ensure_member_access(state.pool(), tag.organization_id, ctx.user.id).await?;
+
+    if let Some(ref color) = payload.color
+        && !is_valid_hsl_color(color)
+    {
+        return Err(invalid_color());
+    }
+
+    let response = OrganizationTagRepository::update(
+        state.pool(),
+        organization_tag_id,
+        payload.name,
+        payload.color,
+    )
+    .await
+    .map_err(|error| {
+        tracing::error!(?error, "failed to update organization tag");
+        db_error(error, "internal server error")
+    })?;
+
+    Ok(Json(response))
+}
+
+#[instrument(
+    name = "organization_tags.delete_organization_tag",
+    skip(state, ctx),
+    fields(organization_tag_id = %organization_tag_id, user_id = %ctx.user.id)
+)]
+async fn delete_organization_tag(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(organization_tag_id): Path<Uuid>,
+) -> Result<Json<DeleteResponse>, ErrorResponse> {
+    let tag = load_organization_tag(&state, organization_tag_id).await?;
+    ensure_member_access(state.pool(), tag.organization_id, ctx.user.id).await?;
+
+    let response = OrganizationTagRepository::delete(state.pool(), organization_tag_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, "failed to delete organization tag");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+        })?;
+
+    Ok(Json(response))
+}
+
+/// Consolidate project tags into one organization tag, moving their issues
+/// over and deleting the project tags.
+#[instrument(
+    name = "organization_tags.merge_tags",
+    skip(state, ctx, payload),
+    fields(organization_id = %payload.organization_id, user_id = %ctx.user.id, count = payload.tag_ids.len())
+)]
+async fn merge_tags(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Json(payload): Json<MergeTagsRequest>,
+) -> Result<Json<MutationResponse<MergeTagsResponse>>, ErrorResponse> {
+    let organization_id = payload.organization_id;
+    let internal_error = |error: &dyn std::fmt::Debug, message: &str| {
+        tracing::error!(?error, %organization_id, "{message}");
+        ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+    };
+
+    ensure_member_access(state.pool(), organization_id, ctx.user.id).await?;
+
+    let mut tag_ids = payload.tag_ids;
+    let mut seen = HashSet::new();
+    tag_ids.retain(|id| seen.insert(*id));
+    if tag_ids.is_empty() {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "at least one tag is required",
+        ));
+    }
+    if let Some(ref color) = payload.color
+        && !is_valid_hsl_color(color)
+    {
+        return Err(invalid_color());
+    }
+
+    let mut tx = crate::db::begin_tx(state.pool())
+        .await
+        .map_err(|error| internal_error(&error, "failed to begin transaction"))?;
+
+    let tags = OrganizationTagRepository::lock_project_tags(&mut tx, organization_id, &tag_ids)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load tags"))?;
+    if tags.len() != tag_ids.len() {
+        return Err(ErrorResponse::new(StatusCode::NOT_FOUND, "tag not found"));
+    }
+
+    let organization_tag = match payload.organization_tag_id {
+        Some(organization_tag_id) => {
+            OrganizationTagRepository::find_by_id(&mut *tx, organization_tag_id)
+                .await
+                .map_err(|error| internal_error(&error, "failed to load organization tag"))?
+                .filter(|tag| tag.organization_id == organization_id)
+                .ok_or_else(|| {
+                    ErrorResponse::new(StatusCode::NOT_FOUND, "organization tag not found")
+                })?
+        }
+        None => {
+            let first = &tags[0];
+            OrganizationTagRepository::find_or_create(
+                &mut tx,
+                organization_id,
+                &first.name,
+                payload.color.as_deref().unwrap_or(&first.color),
+            )
+            .await
+            .map_err(|error| internal_error(&error, "failed to create organization tag"))?
+        }
+    };
+
+    let retagged =
+        OrganizationTagRepository::merge_project_tags(&mut tx, organization_tag.id, &tag_ids)
+            .await
+            .map_err(|error| internal_error(&error, "failed to merge tags"))?;
+
+    let txid = get_txid(&mut *tx)
+        .await
+        .map_err(|error| internal_error(&error, "failed to get txid"))?;
+    tx.commit()
+        .await
+        .map_err(|error| internal_error(&error, "failed to commit transaction"))?;
+
+    Ok(Json(MutationResponse {
+        data: MergeTagsResponse {
+            organization_tag,
+            removed_tag_ids: tag_ids,
+            retagged_issue_count: retagged as usize,
+        },
+        txid,
+    }))
+}
@@ -1,6 +1,6 @@
 use api_types::{
     CreateOrganizationRequest, CreateOrganizationResponse, GetOrganizationResponse,
-    ListOrganizationsResponse, MemberRole, UpdateOrganizationRequest,
+    ListOrganizationsResponse, MemberRole, PlanEntitlements, UpdateOrganizationRequest,
 };
 use axum::{
     Json, Router,
@@ -11,13 +11,15 @@ use axum::{
 };
 use uuid::Uuid;
 
-use super::error::ErrorResponse;
+use super::{error::ErrorResponse, organization_members::ensure_admin_access};
 use crate::{
     AppState,
     auth::RequestContext,
     db::{
-        identity_errors::IdentityError, organization_members, organizations::OrganizationRepository,
+        identity_errors::IdentityError, organization_members,
+        organizations::OrganizationRepository, retention::RetentionRepository,
     },
+    middleware::entitlements::organization_entitlements,
 };
 
 pub(super) fn router() -> Router<AppState> {
@@ -27,6 +29,10 @@ pub(super) fn router() -> Router<AppState> {
         .route("/organizations/{org_id}", get(get_organization))
         .route("/organizations/{org_id}", patch(update_organization))
         .route("/organizations/{org_id}", delete(delete_organization))
+        .route(
+            "/organizations/{org_id}/entitlements",
+            get(get_entitlements),
+        )
 }
 
 async fn create_organization(
@@ -92,6 +98,31 @@ async fn list_organizations(
     Ok(Json(ListOrganizationsResponse { organizations }))
 }
 
+/// What the organization's billing plan allows.
+async fn get_entitlements(
+    State(state): State<AppState>,
+    axum::extract::Extension(ctx): axum::extract::Extension<RequestContext>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<PlanEntitlements>, ErrorResponse> {
+    organization_members::assert_membership(&state.pool, org_id, ctx.user.id)
+        .await
+        .map_err(|e| match e {
+            IdentityError::NotFound => {
+                ErrorResponse::new(StatusCode::NOT_FOUND, "Organization not found")
+            }
+            _ => ErrorResponse::new(StatusCode::FORBIDDEN, "Access denied"),
+        })?;
+
+    let entitlements = organization_entitlements(&state, org_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, %org_id, "failed to load plan entitlements");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error")
+        })?;
+
+    Ok(Json(entitlements))
+}
+
 async fn get_organization(
     State(state): State<AppState>,
     axum::extract::Extension(ctx): axum::extract::Extension<RequestContext>,
@@ -171,6 +202,17 @@ async fn delete_organization(
     axum::extract::Extension(ctx): axum::extract::Extension<RequestContext>,
     Path(org_id): Path<Uuid>,
 ) -> Result<impl IntoResponse, ErrorResponse> {
+    ensure_admin_access(&state.pool, org_id, ctx.user.id).await?;
+    let held = RetentionRepository::organization_has_hold(&state.pool, org_id)
+        .await
+        .map_err(|_| ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    if held {
+        return Err(ErrorResponse::new(
+            StatusCode::CONFLICT,
+            "Organization has projects on legal hold",
+        ));
+    }
+
     let org_repo = OrganizationRepository::new(&state.pool);
 
     org_repo
@@ -1,12 +1,14 @@
 use api_types::{
-    CreateProjectStatusRequest, DeleteResponse, ListProjectStatusesQuery,
-    ListProjectStatusesResponse, MutationResponse, ProjectStatus, UpdateProjectStatusRequest,
+    CreateProjectStatusRequest, DeleteResponse, ListProjectStatusWorkflowsQuery,
+    ListProjectStatusWorkflowsResponse, ListProjectStatusesQuery, ListProjectStatusesResponse,
+    MutationResponse, ProjectStatus, ProjectStatusWorkflow, UpdateProjectStatusRequest,
+    UpdateProjectStatusWorkflowRequest,
 };
 use axum::{
     Json,
     extract::{Extension, Path, Query, State},
     http::StatusCode,
-    routing::post,
+    routing::{post, put},
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -20,26 +22,50 @@ use super::{
 use crate::{
     AppState,
     auth::RequestContext,
-    db::{get_txid, project_statuses::ProjectStatusRepository, types::is_valid_hsl_color},
+    db::{
+        get_txid, project_status_workflows::ProjectStatusWorkflowRepository,
+        project_statuses::ProjectStatusRepository, types::is_valid_hsl_color,
+    },
     mutation_definition::MutationBuilder,
+    query_definition::{QueryBuilder, QueryRoute, query_router},
 };
 
 /// Mutation definition for ProjectStatus - provides both router and TypeScript metadata.
 pub fn mutation()
 -> MutationBuilder<ProjectStatus, CreateProjectStatusRequest, UpdateProjectStatusRequest> {
     MutationBuilder::new("project_statuses")
-        .list(list_project_statuses)
         .get(get_project_status)
         .create(create_project_status)
         .update(update_project_status)
         .delete(delete_project_status)
 }
 
+/// Read endpoints for project statuses.
+pub fn queries() -> Vec<QueryRoute> {
+    vec![
+        QueryBuilder::<ListProjectStatusesResponse>::new(
+            "list_project_statuses",
+            "project_statuses",
+        )
+        .get(list_project_statuses),
+        QueryBuilder::<ListProjectStatusWorkflowsResponse>::new(
+            "list_project_status_workflows",
+            "project_status_workflows",
+        )
+        .get(list_project_status_workflows),
+    ]
+}
+
 /// Router for project status endpoints including bulk update
 pub fn router() -> axum::Router<AppState> {
     mutation()
         .router()
+        .merge(query_router(queries()))
         .route("/project_statuses/bulk", post(bulk_update_project_statuses))
+        .route(
+            "/project_statuses/{id}/workflow",
+            put(update_project_status_workflow),
+        )
 }
 
 #[instrument(
@@ -180,6 +206,110 @@ async fn update_project_status(
     Ok(Json(response))
 }
 
+#[instrument(
+    name = "project_statuses.list_project_status_workflows",
+    skip(state, ctx),
+    fields(project_id = %query.project_id, user_id = %ctx.user.id)
+)]
+async fn list_project_status_workflows(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Query(query): Query<ListProjectStatusWorkflowsQuery>,
+) -> Result<Json<ListProjectStatusWorkflowsResponse>, ErrorResponse> {
+    ensure_project_access(state.pool(), ctx.user.id, query.project_id).await?;
+
+    let workflows = ProjectStatusWorkflowRepository::list_by_project(
+        state.pool(),
+        query.project_id,
+    )
+    .await
+    .map_err(|error| {
+        tracing::error!(?error, project_id = %query.project_id, "failed to list status workflows");
+        ErrorResponse::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "failed to list status workflows",
+        )
+    })?;
+
+    Ok(Json(ListProjectStatusWorkflowsResponse { workflows }))
+}
+
+/// Set a status's category and the statuses issues may move to from it.
+#[instrument(
+    name = "project_statuses.update_project_status_workflow",
+    skip(state, ctx, payload),
+    fields(project_status_id = %project_status_id, user_id = %ctx.user.id)
+)]
+async fn update_project_status_workflow(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(project_status_id): Path<Uuid>,
+    Json(payload): Json<UpdateProjectStatusWorkflowRequest>,
+) -> Result<Json<MutationResponse<ProjectStatusWorkflow>>, ErrorResponse> {
+    let internal_error = |error: &dyn std::fmt::Debug, message: &str| {
+        tracing::error!(?error, %project_status_id, "{message}");
+        ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+    };
+
+    let status = ProjectStatusRepository::find_by_id(state.pool(), project_status_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load project status"))?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "project status not found"))?;
+
+    ensure_project_access(state.pool(), ctx.user.id, status.project_id).await?;
+
+    if let Some(to_status_ids) = &payload.allowed_next_status_ids {
+        if to_status_ids.contains(&project_status_id) {
+            return Err(ErrorResponse::new(
+                StatusCode::BAD_REQUEST,
+                "a status can't transition to itself",
+            ));
+        }
+        let project_status_ids: Vec<Uuid> =
+            ProjectStatusRepository::list_by_project(state.pool(), status.project_id)
+                .await
+                .map_err(|error| internal_error(&error, "failed to list project statuses"))?
+                .into_iter()
+                .map(|status| status.id)
+                .collect();
+        if to_status_ids
+            .iter()
+            .any(|id| !project_status_ids.contains(id))
+        {
+            return Err(ErrorResponse::new(
+                StatusCode::BAD_REQUEST,
+                "allowed statuses must belong to the same project",
+            ));
+        }
+    }
+
+    let mut tx = crate::db::begin_tx(state.pool())
+        .await
+        .map_err(|error| internal_error(&error, "failed to begin transaction"))?;
+    if let Some(category) = payload.category {
+        ProjectStatusWorkflowRepository::set_category(&mut tx, project_status_id, category)
+            .await
+            .map_err(|error| internal_error(&error, "failed to set status category"))?;
+    }
+    if let Some(to_status_ids) = &payload.allowed_next_status_ids {
+        ProjectStatusWorkflowRepository::set_transitions(&mut tx, project_status_id, to_status_ids)
+            .await
+            .map_err(|error| internal_error(&error, "failed to set status transitions"))?;
+    }
+    let data = ProjectStatusWorkflowRepository::find(&mut *tx, project_status_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load status workflow"))?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "project status not found"))?;
+    let txid = get_txid(&mut *tx)
+        .await
+        .map_err(|error| internal_error(&error, "failed to get txid"))?;
+    tx.commit()
+        .await
+        .map_err(|error| internal_error(&error, "failed to commit transaction"))?;
+
+    Ok(Json(MutationResponse { data, txid }))
+}
+
 #[instrument(
     name = "project_statuses.delete_project_status",
     skip(state, ctx),
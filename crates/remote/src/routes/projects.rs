@@ -1,41 +1,119 @@
 use api_types::{
     BulkUpdateProjectsRequest, BulkUpdateProjectsResponse, CreateProjectRequest, DeleteResponse,
-    ListProjectsQuery, ListProjectsResponse, MutationResponse, Project, UpdateProjectRequest,
+    ListProjectsQuery, ListProjectsResponse, MutationResponse, Project, ProjectConcurrencySettings,
+    UpdateProjectRequest,
 };
 use axum::{
     Json,
     extract::{Extension, Path, Query, State},
     http::StatusCode,
-    routing::post,
+    routing::{get, post},
 };
 use tracing::instrument;
 use uuid::Uuid;
 
 use super::{
     error::{ErrorResponse, db_error},
-    organization_members::ensure_member_access,
+    organization_members::{ensure_admin_access, ensure_member_access},
+    retention::ensure_no_legal_hold,
 };
 use crate::{
     AppState,
     auth::RequestContext,
     db::{get_txid, projects::ProjectRepository, types::is_valid_hsl_color},
     mutation_definition::MutationBuilder,
+    query_definition::{QueryBuilder, QueryRoute, query_router},
 };
 
 /// Mutation definition for Projects - provides both router and TypeScript metadata.
 pub fn mutation() -> MutationBuilder<Project, CreateProjectRequest, UpdateProjectRequest> {
     MutationBuilder::new("projects")
-        .list(list_projects)
         .get(get_project)
         .create(create_project)
         .update(update_project)
         .delete(delete_project)
 }
 
+/// Read endpoints for projects.
+pub fn queries() -> Vec<QueryRoute> {
+    vec![QueryBuilder::<ListProjectsResponse>::new("list_projects", "projects").get(list_projects)]
+}
+
 pub fn router() -> axum::Router<AppState> {
     mutation()
         .router()
+        .merge(query_router(queries()))
         .route("/projects/bulk", post(bulk_update_projects))
+        .route(
+            "/projects/{project_id}/concurrency",
+            get(get_concurrency_settings).put(update_concurrency_settings),
+        )
+}
+
+async fn project_organization_id(
+    state: &AppState,
+    project_id: Uuid,
+) -> Result<Uuid, ErrorResponse> {
+    ProjectRepository::organization_id(state.pool(), project_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, %project_id, "failed to load project");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to load project")
+        })?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "project not found"))
+}
+
+#[instrument(
+    name = "projects.get_concurrency_settings",
+    skip(state, ctx),
+    fields(project_id = %project_id, user_id = %ctx.user.id)
+)]
+async fn get_concurrency_settings(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+) -> Result<Json<ProjectConcurrencySettings>, ErrorResponse> {
+    let organization_id = project_organization_id(&state, project_id).await?;
+    ensure_member_access(state.pool(), organization_id, ctx.user.id).await?;
+
+    let require_if_match = ProjectRepository::require_if_match(state.pool(), project_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, %project_id, "failed to load project settings");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to load project")
+        })?
+        .unwrap_or(false);
+
+    Ok(Json(ProjectConcurrencySettings { require_if_match }))
+}
+
+/// Only organization admins may change how a project handles concurrent
+/// edits, since requiring `If-Match` rejects updates from older clients.
+#[instrument(
+    name = "projects.update_concurrency_settings",
+    skip(state, ctx, payload),
+    fields(project_id = %project_id, user_id = %ctx.user.id)
+)]
+async fn update_concurrency_settings(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+    Json(payload): Json<ProjectConcurrencySettings>,
+) -> Result<Json<ProjectConcurrencySettings>, ErrorResponse> {
+    let organization_id = project_organization_id(&state, project_id).await?;
+    ensure_admin_access(state.pool(), organization_id, ctx.user.id).await?;
+
+    ProjectRepository::set_require_if_match(state.pool(), project_id, payload.require_if_match)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, %project_id, "failed to update project settings");
+            ErrorResponse::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "failed to update project",
+            )
+        })?;
+
+    Ok(Json(payload))
 }
 
 #[instrument(
@@ -288,6 +366,7 @@ async fn delete_project(
         .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "project not found"))?;
 
     ensure_member_access(state.pool(), project.organization_id, ctx.user.id).await?;
+    ensure_no_legal_hold(state.pool(), project_id).await?;
 
     let response = ProjectRepository::delete(state.pool(), project_id)
         .await
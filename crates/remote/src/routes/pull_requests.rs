@@ -5,8 +5,8 @@ use api_types::{
 use axum::{
     Json, Router,
     extract::{Extension, Query, State},
-    http::StatusCode,
-    routing::get,
+    http::{Method, StatusCode},
+    routing::{get, post},
 };
 use chrono::{DateTime, Utc};
 use serde::Deserialize;
@@ -24,6 +24,15 @@ use crate::{
         get_txid, issues::IssueRepository, pull_request_issues::PullRequestIssueRepository,
         pull_requests::PullRequestRepository, workspaces::WorkspaceRepository,
     },
+    middleware::version::{DeprecatedRoute, deprecated},
+};
+
+pub(super) static CREATE_PULL_REQUEST_DEPRECATION: DeprecatedRoute = DeprecatedRoute {
+    method: Method::POST,
+    path: "/v1/pull_requests",
+    deprecated_on: "2026-05-01",
+    sunset_on: None,
+    successor: Some("/v1/pull_request_issues"),
 };
 
 /// Deprecated: use `POST /v1/pull_request_issues` instead for linking PRs to
@@ -54,9 +63,12 @@ pub(super) fn router() -> Router<AppState> {
     Router::new().route(
         "/pull_requests",
         get(list_pull_requests)
-            .post(create_pull_request)
             .patch(update_pull_request)
-            .put(upsert_pull_request),
+            .put(upsert_pull_request)
+            .merge(deprecated(
+                post(create_pull_request),
+                &CREATE_PULL_REQUEST_DEPRECATION,
+            )),
     )
 }
 
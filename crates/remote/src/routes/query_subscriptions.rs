@@ -0,0 +1,221 @@
+use api_types::{
+    CreateQuerySubscriptionRequest, ListQuerySubscriptionsQuery, ListQuerySubscriptionsResponse,
+    QuerySubscription, QuerySubscriptionIssuesResponse, UpdateQuerySubscriptionRequest,
+};
+use axum::{
+    Json, Router,
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    routing::get,
+};
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{error::ErrorResponse, organization_members::ensure_project_access};
+use crate::{
+    AppState,
+    auth::RequestContext,
+    db::query_subscriptions::QuerySubscriptionRepository,
+    query_subscriptions::{self, IssueFilter},
+};
+
+pub(super) fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/query-subscriptions",
+            get(list_subscriptions).post(create_subscription),
+        )
+        .route(
+            "/query-subscriptions/{subscription_id}",
+            get(get_subscription)
+                .patch(update_subscription)
+                .delete(delete_subscription),
+        )
+        .route(
+            "/query-subscriptions/{subscription_id}/issues",
+            get(list_subscription_issues),
+        )
+}
+
+fn internal_error(error: &dyn std::fmt::Debug, message: &str) -> ErrorResponse {
+    tracing::error!(?error, "{message}");
+    ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+}
+
+fn parse_filter(query: &str) -> Result<IssueFilter, ErrorResponse> {
+    IssueFilter::parse(query)
+        .map_err(|error| ErrorResponse::new(StatusCode::BAD_REQUEST, error.to_string()))
+}
+
+fn validate_name(name: &str) -> Result<&str, ErrorResponse> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "name must not be empty",
+        ));
+    }
+    Ok(name)
+}
+
+/// Load a subscription owned by the requester in a project they can still
+/// access.
+async fn own_subscription(
+    state: &AppState,
+    ctx: &RequestContext,
+    subscription_id: Uuid,
+) -> Result<QuerySubscription, ErrorResponse> {
+    let subscription = QuerySubscriptionRepository::find_by_id(state.pool(), subscription_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load query subscription"))?
+        .filter(|subscription| subscription.user_id == ctx.user.id)
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "subscription not found"))?;
+    ensure_project_access(state.pool(), ctx.user.id, subscription.project_id).await?;
+    Ok(subscription)
+}
+
+/// Record which issues match now, so only later changes notify.
+async fn seed_matches(
+    state: &AppState,
+    subscription: &QuerySubscription,
+    filter: &IssueFilter,
+) -> Result<(), ErrorResponse> {
+    let issue_ids = query_subscriptions::current_matches(
+        state.pool(),
+        subscription.project_id,
+        subscription.user_id,
+        filter,
+    )
+    .await
+    .map_err(|error| internal_error(&error, "failed to evaluate query subscription"))?;
+    QuerySubscriptionRepository::replace_matches(state.pool(), subscription.id, &issue_ids)
+        .await
+        .map_err(|error| internal_error(&error, "failed to store query subscription matches"))
+}
+
+#[instrument(
+    name = "query_subscriptions.list_subscriptions",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id)
+)]
+async fn list_subscriptions(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Query(query): Query<ListQuerySubscriptionsQuery>,
+) -> Result<Json<ListQuerySubscriptionsResponse>, ErrorResponse> {
+    if let Some(project_id) = query.project_id {
+        ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
+    }
+    let subscriptions =
+        QuerySubscriptionRepository::list_by_user(state.pool(), ctx.user.id, query.project_id)
+            .await
+            .map_err(|error| internal_error(&error, "failed to list query subscriptions"))?;
+
+    Ok(Json(ListQuerySubscriptionsResponse { subscriptions }))
+}
+
+#[instrument(
+    name = "query_subscriptions.create_subscription",
+    skip(state, ctx, payload),
+    fields(project_id = %payload.project_id, user_id = %ctx.user.id)
+)]
+async fn create_subscription(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Json(payload): Json<CreateQuerySubscriptionRequest>,
+) -> Result<Json<QuerySubscription>, ErrorResponse> {
+    ensure_project_access(state.pool(), ctx.user.id, payload.project_id).await?;
+    let name = validate_name(&payload.name)?;
+    let query = payload.query.trim();
+    let filter = parse_filter(query)?;
+
+    let subscription = QuerySubscriptionRepository::create(
+        state.pool(),
+        payload.project_id,
+        ctx.user.id,
+        name,
+        query,
+    )
+    .await
+    .map_err(|error| internal_error(&error, "failed to create query subscription"))?;
+    seed_matches(&state, &subscription, &filter).await?;
+
+    Ok(Json(subscription))
+}
+
+#[instrument(
+    name = "query_subscriptions.get_subscription",
+    skip(state, ctx),
+    fields(subscription_id = %subscription_id, user_id = %ctx.user.id)
+)]
+async fn get_subscription(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(subscription_id): Path<Uuid>,
+) -> Result<Json<QuerySubscription>, ErrorResponse> {
+    Ok(Json(own_subscription(&state, &ctx, subscription_id).await?))
+}
+
+#[instrument(
+    name = "query_subscriptions.update_subscription",
+    skip(state, ctx, payload),
+    fields(subscription_id = %subscription_id, user_id = %ctx.user.id)
+)]
+async fn update_subscription(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(subscription_id): Path<Uuid>,
+    Json(payload): Json<UpdateQuerySubscriptionRequest>,
+) -> Result<Json<QuerySubscription>, ErrorResponse> {
+    let existing = own_subscription(&state, &ctx, subscription_id).await?;
+    let name = validate_name(payload.name.as_deref().unwrap_or(&existing.name))?;
+    let query = payload.query.as_deref().unwrap_or(&existing.query).trim();
+    let filter = parse_filter(query)?;
+
+    let subscription =
+        QuerySubscriptionRepository::update(state.pool(), subscription_id, name, query)
+            .await
+            .map_err(|error| internal_error(&error, "failed to update query subscription"))?;
+    if subscription.query != existing.query {
+        seed_matches(&state, &subscription, &filter).await?;
+    }
+
+    Ok(Json(subscription))
+}
+
+#[instrument(
+    name = "query_subscriptions.delete_subscription",
+    skip(state, ctx),
+    fields(subscription_id = %subscription_id, user_id = %ctx.user.id)
+)]
+async fn delete_subscription(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(subscription_id): Path<Uuid>,
+) -> Result<StatusCode, ErrorResponse> {
+    own_subscription(&state, &ctx, subscription_id).await?;
+    QuerySubscriptionRepository::delete(state.pool(), subscription_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to delete query subscription"))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Issues in the subscription's results as of its last evaluation.
+#[instrument(
+    name = "query_subscriptions.list_subscription_issues",
+    skip(state, ctx),
+    fields(subscription_id = %subscription_id, user_id = %ctx.user.id)
+)]
+async fn list_subscription_issues(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(subscription_id): Path<Uuid>,
+) -> Result<Json<QuerySubscriptionIssuesResponse>, ErrorResponse> {
+    own_subscription(&state, &ctx, subscription_id).await?;
+    let issue_ids = QuerySubscriptionRepository::list_matches(state.pool(), subscription_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to list query subscription issues"))?;
+
+    Ok(Json(QuerySubscriptionIssuesResponse { issue_ids }))
+}
@@ -0,0 +1,69 @@
+use api_types::{ReleaseNotes, ReleaseNotesRequest};
+use axum::{
+    Json, Router,
+    extract::{Extension, State},
+    http::StatusCode,
+    routing::post,
+};
+use tracing::instrument;
+
+use super::{error::ErrorResponse, organization_members::ensure_project_access};
+use crate::{
+    AppState,
+    auth::RequestContext,
+    db::{projects::ProjectRepository, release_notes::ReleaseNotesRepository},
+    release_notes,
+};
+
+pub(super) fn router() -> Router<AppState> {
+    Router::new().route("/release-notes", post(compile_release_notes))
+}
+
+/// Compile release notes from the project's issues completed between `from`
+/// and `to`, grouped by tag, with links to their pull requests.
+#[instrument(
+    name = "release_notes.compile",
+    skip(state, ctx, payload),
+    fields(project_id = %payload.project_id, user_id = %ctx.user.id)
+)]
+async fn compile_release_notes(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Json(payload): Json<ReleaseNotesRequest>,
+) -> Result<Json<ReleaseNotes>, ErrorResponse> {
+    ensure_project_access(state.pool(), ctx.user.id, payload.project_id).await?;
+    if payload.from >= payload.to {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "from must be before to",
+        ));
+    }
+
+    let internal_error = |error: &dyn std::fmt::Debug, message: &str| {
+        tracing::error!(?error, "{message}");
+        ErrorResponse::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "failed to compile release notes",
+        )
+    };
+    let project = ProjectRepository::find_by_id(state.pool(), payload.project_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load project"))?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "project not found"))?;
+    let issues = ReleaseNotesRepository::completed_issues(
+        state.pool(),
+        payload.project_id,
+        payload.from,
+        payload.to,
+    )
+    .await
+    .map_err(|error| internal_error(&error, "failed to load completed issues"))?;
+
+    Ok(Json(release_notes::compile(
+        &project.name,
+        payload.from,
+        payload.to,
+        issues,
+        payload.tag_ids.as_deref(),
+    )))
+}
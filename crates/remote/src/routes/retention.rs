@@ -0,0 +1,275 @@
+use api_types::{
+    LegalHold, ListLegalHoldsResponse, OrganizationRetentionPolicy, PlaceLegalHoldRequest,
+    RetentionReport, UpdateRetentionPolicyRequest,
+};
+use axum::{
+    Json, Router,
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    routing::get,
+};
+use chrono::Utc;
+use sqlx::PgPool;
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{
+    error::ErrorResponse,
+    organization_members::{ensure_admin_access, ensure_project_access},
+};
+use crate::{
+    AppState,
+    audit::{self, AuditAction, AuditEvent},
+    auth::RequestContext,
+    db::retention::RetentionRepository,
+    retention::{self, normalize_policy},
+};
+
+pub(super) fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/organizations/{org_id}/retention-policy",
+            get(get_policy).put(update_policy),
+        )
+        .route("/organizations/{org_id}/retention-report", get(get_report))
+        .route("/organizations/{org_id}/legal-holds", get(list_legal_holds))
+        .route(
+            "/projects/{project_id}/legal-hold",
+            get(get_legal_hold)
+                .put(place_legal_hold)
+                .delete(release_legal_hold),
+        )
+}
+
+fn internal_error(error: &dyn std::fmt::Debug, message: &str) -> ErrorResponse {
+    tracing::error!(?error, "{message}");
+    ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+}
+
+fn bad_request(error: impl ToString) -> ErrorResponse {
+    ErrorResponse::new(StatusCode::BAD_REQUEST, error.to_string())
+}
+
+/// Refuse to delete anything from a project on legal hold.
+pub(super) async fn ensure_no_legal_hold(
+    pool: &PgPool,
+    project_id: Uuid,
+) -> Result<(), ErrorResponse> {
+    let hold = RetentionRepository::legal_hold(pool, project_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to check legal hold"))?;
+    if hold.is_some() {
+        return Err(ErrorResponse::new(
+            StatusCode::CONFLICT,
+            "project is on legal hold",
+        ));
+    }
+    Ok(())
+}
+
+/// Refuse to delete anything from an issue whose project is on legal hold.
+pub(super) async fn ensure_issue_not_on_legal_hold(
+    pool: &PgPool,
+    issue_id: Uuid,
+) -> Result<(), ErrorResponse> {
+    let held = RetentionRepository::issue_on_hold(pool, issue_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to check legal hold"))?;
+    if held {
+        return Err(ErrorResponse::new(
+            StatusCode::CONFLICT,
+            "project is on legal hold",
+        ));
+    }
+    Ok(())
+}
+
+/// Require the requester to administer the project's organization.
+async fn ensure_project_admin(
+    state: &AppState,
+    ctx: &RequestContext,
+    project_id: Uuid,
+) -> Result<Uuid, ErrorResponse> {
+    let organization_id = ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
+    ensure_admin_access(state.pool(), organization_id, ctx.user.id).await?;
+    Ok(organization_id)
+}
+
+#[instrument(
+    name = "retention.get_policy",
+    skip(state, ctx),
+    fields(org_id = %org_id, user_id = %ctx.user.id)
+)]
+async fn get_policy(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<OrganizationRetentionPolicy>, ErrorResponse> {
+    ensure_admin_access(state.pool(), org_id, ctx.user.id).await?;
+    let policy = retention::policy(state.pool(), org_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load retention policy"))?;
+
+    Ok(Json(policy))
+}
+
+#[instrument(
+    name = "retention.update_policy",
+    skip(state, ctx, payload),
+    fields(org_id = %org_id, user_id = %ctx.user.id)
+)]
+async fn update_policy(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(org_id): Path<Uuid>,
+    Json(payload): Json<UpdateRetentionPolicyRequest>,
+) -> Result<Json<OrganizationRetentionPolicy>, ErrorResponse> {
+    ensure_admin_access(state.pool(), org_id, ctx.user.id).await?;
+    let policy = normalize_policy(org_id, &payload).map_err(bad_request)?;
+    RetentionRepository::set_policy(state.pool(), &policy, ctx.user.id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to update retention policy"))?;
+
+    audit::emit(
+        AuditEvent::from_request(&ctx, AuditAction::RetentionPolicyUpdate)
+            .resource("organization", Some(org_id))
+            .organization(org_id)
+            .http(
+                "PUT",
+                format!("/v1/organizations/{org_id}/retention-policy"),
+                200,
+            )
+            .description(format!(
+                "Set retention to {:?} years for closed issues and {:?} years for attachments",
+                policy.closed_issue_retention_years, policy.attachment_retention_years
+            )),
+    );
+
+    Ok(Json(policy))
+}
+
+#[instrument(
+    name = "retention.get_report",
+    skip(state, ctx),
+    fields(org_id = %org_id, user_id = %ctx.user.id)
+)]
+async fn get_report(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<RetentionReport>, ErrorResponse> {
+    ensure_admin_access(state.pool(), org_id, ctx.user.id).await?;
+    let policy = retention::policy(state.pool(), org_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load retention policy"))?;
+    let report = retention::report(state.pool(), policy, Utc::now())
+        .await
+        .map_err(|error| internal_error(&error, "failed to build retention report"))?;
+
+    Ok(Json(report))
+}
+
+#[instrument(
+    name = "retention.list_legal_holds",
+    skip(state, ctx),
+    fields(org_id = %org_id, user_id = %ctx.user.id)
+)]
+async fn list_legal_holds(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<ListLegalHoldsResponse>, ErrorResponse> {
+    ensure_admin_access(state.pool(), org_id, ctx.user.id).await?;
+    let holds = RetentionRepository::legal_holds(state.pool(), org_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to list legal holds"))?;
+
+    Ok(Json(ListLegalHoldsResponse { holds }))
+}
+
+#[instrument(
+    name = "retention.get_legal_hold",
+    skip(state, ctx),
+    fields(project_id = %project_id, user_id = %ctx.user.id)
+)]
+async fn get_legal_hold(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+) -> Result<Json<LegalHold>, ErrorResponse> {
+    ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
+    let hold = RetentionRepository::legal_hold(state.pool(), project_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load legal hold"))?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "project is not on legal hold"))?;
+
+    Ok(Json(hold))
+}
+
+#[instrument(
+    name = "retention.place_legal_hold",
+    skip(state, ctx, payload),
+    fields(project_id = %project_id, user_id = %ctx.user.id)
+)]
+async fn place_legal_hold(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+    Json(payload): Json<PlaceLegalHoldRequest>,
+) -> Result<Json<LegalHold>, ErrorResponse> {
+    let organization_id = ensure_project_admin(&state, &ctx, project_id).await?;
+    let reason = payload.reason.trim();
+    if reason.is_empty() {
+        return Err(bad_request("reason must not be empty"));
+    }
+
+    let hold = RetentionRepository::place_legal_hold(state.pool(), project_id, reason, ctx.user.id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to place legal hold"))?;
+
+    audit::emit(
+        AuditEvent::from_request(&ctx, AuditAction::LegalHoldPlace)
+            .resource("project", Some(project_id))
+            .organization(organization_id)
+            .http("PUT", format!("/v1/projects/{project_id}/legal-hold"), 200)
+            .description(format!("Placed legal hold: {reason}")),
+    );
+
+    Ok(Json(hold))
+}
+
+#[instrument(
+    name = "retention.release_legal_hold",
+    skip(state, ctx),
+    fields(project_id = %project_id, user_id = %ctx.user.id)
+)]
+async fn release_legal_hold(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+) -> Result<StatusCode, ErrorResponse> {
+    let organization_id = ensure_project_admin(&state, &ctx, project_id).await?;
+    let released = RetentionRepository::release_legal_hold(state.pool(), project_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to release legal hold"))?;
+    if !released {
+        return Err(ErrorResponse::new(
+            StatusCode::NOT_FOUND,
+            "project is not on legal hold",
+        ));
+    }
+
+    audit::emit(
+        AuditEvent::from_request(&ctx, AuditAction::LegalHoldRelease)
+            .resource("project", Some(project_id))
+            .organization(organization_id)
+            .http(
+                "DELETE",
+                format!("/v1/projects/{project_id}/legal-hold"),
+                204,
+            )
+            .description("Released legal hold"),
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
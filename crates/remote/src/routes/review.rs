@@ -4,7 +4,7 @@ use axum::{
     Json, Router,
     body::Body,
     extract::{Path, State},
-    http::{HeaderMap, StatusCode},
+    http::{Extensions, HeaderMap, StatusCode},
     response::{IntoResponse, Response},
     routing::{get, post},
 };
@@ -15,6 +15,7 @@ use uuid::Uuid;
 use crate::{
     AppState,
     db::reviews::{CreateReviewParams, ReviewRepository},
+    middleware::ip_allowlist::client_ip,
     r2::R2Error,
 };
 
@@ -142,38 +143,14 @@ fn normalize_github_url(url: &str) -> String {
     }
 }
 
-/// Extract client IP from headers, with fallbacks for local development
-fn extract_client_ip(headers: &HeaderMap) -> Option<IpAddr> {
-    // Try Cloudflare header first (production)
-    if let Some(ip) = headers
-        .get("CF-Connecting-IP")
-        .and_then(|v| v.to_str().ok())
-        .and_then(|s| s.parse().ok())
-    {
-        return Some(ip);
-    }
-
-    // Fallback to X-Forwarded-For (common proxy header)
-    if let Some(ip) = headers
-        .get("X-Forwarded-For")
-        .and_then(|v| v.to_str().ok())
-        .and_then(|s| s.split(',').next()) // Take first IP in chain
-        .and_then(|s| s.trim().parse().ok())
-    {
-        return Some(ip);
-    }
-
-    // Fallback to X-Real-IP
-    if let Some(ip) = headers
-        .get("X-Real-IP")
-        .and_then(|v| v.to_str().ok())
-        .and_then(|s| s.parse().ok())
-    {
-        return Some(ip);
-    }
-
+/// Extract the client IP, with a fallback for local development
+fn extract_client_ip(
+    state: &AppState,
+    extensions: &Extensions,
+    headers: &HeaderMap,
+) -> Option<IpAddr> {
     // For local development, use localhost
-    Some(IpAddr::V4(std::net::Ipv4Addr::LOCALHOST))
+    client_ip(state, extensions, headers).or(Some(IpAddr::V4(std::net::Ipv4Addr::LOCALHOST)))
 }
 
 /// Check rate limits for the given IP address.
@@ -200,6 +177,7 @@ async fn check_rate_limit(repo: &ReviewRepository<'_>, ip: IpAddr) -> Result<(),
 
 async fn init_review_upload(
     State(state): State<AppState>,
+    extensions: Extensions,
     headers: HeaderMap,
     Json(payload): Json<InitReviewRequest>,
 ) -> Result<Json<InitReviewResponse>, ReviewError> {
@@ -211,7 +189,8 @@ async fn init_review_upload(
     let review_id = Uuid::new_v4();
 
     // 2. Extract IP (required for rate limiting)
-    let ip = extract_client_ip(&headers).ok_or(ReviewError::MissingClientIp)?;
+    let ip =
+        extract_client_ip(&state, &extensions, &headers).ok_or(ReviewError::MissingClientIp)?;
 
     // 3. Check rate limits
     let repo = ReviewRepository::new(state.pool());
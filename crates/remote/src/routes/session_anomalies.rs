@@ -0,0 +1,183 @@
+use api_types::{
+    ListSessionFlagsQuery, ListSessionFlagsResponse, ReviewSessionFlagRequest,
+    SessionAnomalySettings, SessionFlag, SessionFlagResolution, SessionFlagStatus,
+    UpdateSessionAnomalySettingsRequest,
+};
+use axum::{
+    Json, Router,
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    routing::{get, post},
+};
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{error::ErrorResponse, organization_members::ensure_admin_access};
+use crate::{
+    AppState,
+    audit::{self, AuditAction, AuditEvent},
+    auth::RequestContext,
+    db::session_anomalies::SessionAnomalyRepository,
+    session_anomalies::settings_response,
+};
+
+pub(super) fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/organizations/{org_id}/session-flags",
+            get(list_session_flags),
+        )
+        .route(
+            "/organizations/{org_id}/session-flags/{flag_id}/review",
+            post(review_session_flag),
+        )
+        .route(
+            "/organizations/{org_id}/session-anomaly-settings",
+            get(get_settings).put(update_settings),
+        )
+}
+
+fn internal_error(error: &dyn std::fmt::Debug, message: &str) -> ErrorResponse {
+    tracing::error!(?error, "{message}");
+    ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+}
+
+#[instrument(
+    name = "session_anomalies.list_session_flags",
+    skip(state, ctx, query),
+    fields(org_id = %org_id, user_id = %ctx.user.id)
+)]
+async fn list_session_flags(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(org_id): Path<Uuid>,
+    Query(query): Query<ListSessionFlagsQuery>,
+) -> Result<Json<ListSessionFlagsResponse>, ErrorResponse> {
+    ensure_admin_access(state.pool(), org_id, ctx.user.id).await?;
+    let flags = SessionAnomalyRepository::list_for_organization(state.pool(), org_id, query.status)
+        .await
+        .map_err(|error| internal_error(&error, "failed to list session flags"))?;
+
+    Ok(Json(ListSessionFlagsResponse { flags }))
+}
+
+#[instrument(
+    name = "session_anomalies.review_session_flag",
+    skip(state, ctx, payload),
+    fields(org_id = %org_id, flag_id = %flag_id, user_id = %ctx.user.id)
+)]
+async fn review_session_flag(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path((org_id, flag_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<ReviewSessionFlagRequest>,
+) -> Result<Json<SessionFlag>, ErrorResponse> {
+    let pool = state.pool();
+    ensure_admin_access(pool, org_id, ctx.user.id).await?;
+    let flag = SessionAnomalyRepository::find_for_organization(pool, org_id, flag_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load session flag"))?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "session flag not found"))?;
+
+    let already_reviewed =
+        || ErrorResponse::new(StatusCode::CONFLICT, "this flag has already been reviewed");
+    let flag = match payload.resolution {
+        SessionFlagResolution::Dismiss => {
+            SessionAnomalyRepository::dismiss(pool, flag.id, ctx.user.id)
+                .await
+                .map_err(|error| internal_error(&error, "failed to dismiss session flag"))?
+                .ok_or_else(already_reviewed)?
+        }
+        SessionFlagResolution::Revoke => {
+            if flag.status != SessionFlagStatus::Open {
+                return Err(already_reviewed());
+            }
+            SessionAnomalyRepository::revoke_session(pool, flag.session_id, ctx.user.id)
+                .await
+                .map_err(|error| internal_error(&error, "failed to revoke flagged session"))?;
+            SessionAnomalyRepository::find_flag(pool, flag.id)
+                .await
+                .map_err(|error| internal_error(&error, "failed to load session flag"))?
+                .ok_or_else(|| {
+                    ErrorResponse::new(StatusCode::NOT_FOUND, "session flag not found")
+                })?
+        }
+    };
+
+    audit::emit(
+        AuditEvent::from_request(&ctx, AuditAction::SessionFlagReview)
+            .resource("auth_session", Some(flag.session_id))
+            .organization(org_id)
+            .http(
+                "POST",
+                format!("/v1/organizations/{org_id}/session-flags/{flag_id}/review"),
+                200,
+            )
+            .description(match payload.resolution {
+                SessionFlagResolution::Dismiss => format!("Dismissed flag: {}", flag.description),
+                SessionFlagResolution::Revoke => {
+                    format!("Revoked session flagged for: {}", flag.description)
+                }
+            }),
+    );
+
+    Ok(Json(flag))
+}
+
+#[instrument(
+    name = "session_anomalies.get_settings",
+    skip(state, ctx),
+    fields(org_id = %org_id, user_id = %ctx.user.id)
+)]
+async fn get_settings(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<SessionAnomalySettings>, ErrorResponse> {
+    ensure_admin_access(state.pool(), org_id, ctx.user.id).await?;
+    let auto_suspend = SessionAnomalyRepository::auto_suspend(state.pool(), org_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load session anomaly settings"))?;
+
+    Ok(Json(settings_response(org_id, auto_suspend)))
+}
+
+#[instrument(
+    name = "session_anomalies.update_settings",
+    skip(state, ctx, payload),
+    fields(org_id = %org_id, user_id = %ctx.user.id)
+)]
+async fn update_settings(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(org_id): Path<Uuid>,
+    Json(payload): Json<UpdateSessionAnomalySettingsRequest>,
+) -> Result<Json<SessionAnomalySettings>, ErrorResponse> {
+    ensure_admin_access(state.pool(), org_id, ctx.user.id).await?;
+    SessionAnomalyRepository::set_auto_suspend(
+        state.pool(),
+        org_id,
+        payload.auto_suspend,
+        ctx.user.id,
+    )
+    .await
+    .map_err(|error| internal_error(&error, "failed to update session anomaly settings"))?;
+
+    audit::emit(
+        AuditEvent::from_request(&ctx, AuditAction::SessionAnomalySettingsUpdate)
+            .resource("organization", Some(org_id))
+            .organization(org_id)
+            .http(
+                "PUT",
+                format!("/v1/organizations/{org_id}/session-anomaly-settings"),
+                200,
+            )
+            .description(if payload.auto_suspend {
+                "Enabled suspending flagged sessions"
+            } else {
+                "Disabled suspending flagged sessions"
+            }),
+    );
+
+    Ok(Json(settings_response(org_id, payload.auto_suspend)))
+}
@@ -0,0 +1,250 @@
+use api_types::{
+    CreateSlaPolicyRequest, ListSlaPoliciesResponse, OrganizationWorkingHours, SlaPolicy,
+    SlaReport, SlaReportQuery, UpdateOrganizationWorkingHoursRequest, UpdateSlaPolicyRequest,
+};
+use axum::{
+    Json, Router,
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    routing::{get, patch},
+};
+use chrono::{TimeDelta, Utc};
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{
+    error::ErrorResponse,
+    organization_members::{ensure_admin_access, ensure_member_access, ensure_project_access},
+};
+use crate::{
+    AppState,
+    auth::RequestContext,
+    db::sla::SlaRepository,
+    query_subscriptions::IssueFilter,
+    sla::{self, normalize_working_hours, validate_target},
+};
+
+const DEFAULT_REPORT_DAYS: i64 = 30;
+
+pub(super) fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/organizations/{org_id}/working-hours",
+            get(get_working_hours).put(update_working_hours),
+        )
+        .route(
+            "/projects/{project_id}/sla-policies",
+            get(list_policies).post(create_policy),
+        )
+        .route(
+            "/sla-policies/{policy_id}",
+            patch(update_policy).delete(delete_policy),
+        )
+        .route("/projects/{project_id}/sla-report", get(get_report))
+}
+
+fn internal_error(error: &dyn std::fmt::Debug, message: &str) -> ErrorResponse {
+    tracing::error!(?error, "{message}");
+    ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+}
+
+fn bad_request(error: impl ToString) -> ErrorResponse {
+    ErrorResponse::new(StatusCode::BAD_REQUEST, error.to_string())
+}
+
+fn validate_policy<'a>(
+    name: &'a str,
+    query: &'a str,
+    target_minutes: i32,
+) -> Result<(&'a str, &'a str), ErrorResponse> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(bad_request("name must not be empty"));
+    }
+    let query = query.trim();
+    IssueFilter::parse(query).map_err(bad_request)?;
+    validate_target(target_minutes).map_err(bad_request)?;
+    Ok((name, query))
+}
+
+/// Require the requester to administer the project's organization.
+async fn ensure_project_admin(
+    state: &AppState,
+    ctx: &RequestContext,
+    project_id: Uuid,
+) -> Result<(), ErrorResponse> {
+    let organization_id = ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
+    ensure_admin_access(state.pool(), organization_id, ctx.user.id).await
+}
+
+async fn load_policy(state: &AppState, policy_id: Uuid) -> Result<SlaPolicy, ErrorResponse> {
+    SlaRepository::find_policy(state.pool(), policy_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load SLA policy"))?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "SLA policy not found"))
+}
+
+#[instrument(
+    name = "sla.get_working_hours",
+    skip(state, ctx),
+    fields(org_id = %org_id, user_id = %ctx.user.id)
+)]
+async fn get_working_hours(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<OrganizationWorkingHours>, ErrorResponse> {
+    ensure_member_access(state.pool(), org_id, ctx.user.id).await?;
+    let hours = sla::working_hours(state.pool(), org_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load working hours"))?;
+
+    Ok(Json(hours))
+}
+
+#[instrument(
+    name = "sla.update_working_hours",
+    skip(state, ctx, payload),
+    fields(org_id = %org_id, user_id = %ctx.user.id)
+)]
+async fn update_working_hours(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(org_id): Path<Uuid>,
+    Json(payload): Json<UpdateOrganizationWorkingHoursRequest>,
+) -> Result<Json<OrganizationWorkingHours>, ErrorResponse> {
+    ensure_admin_access(state.pool(), org_id, ctx.user.id).await?;
+    let hours = normalize_working_hours(org_id, &payload).map_err(bad_request)?;
+    SlaRepository::set_working_hours(state.pool(), &hours)
+        .await
+        .map_err(|error| internal_error(&error, "failed to update working hours"))?;
+
+    Ok(Json(hours))
+}
+
+#[instrument(
+    name = "sla.list_policies",
+    skip(state, ctx),
+    fields(project_id = %project_id, user_id = %ctx.user.id)
+)]
+async fn list_policies(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+) -> Result<Json<ListSlaPoliciesResponse>, ErrorResponse> {
+    ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
+    let policies = SlaRepository::list_policies(state.pool(), project_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to list SLA policies"))?;
+
+    Ok(Json(ListSlaPoliciesResponse { policies }))
+}
+
+#[instrument(
+    name = "sla.create_policy",
+    skip(state, ctx, payload),
+    fields(project_id = %project_id, user_id = %ctx.user.id)
+)]
+async fn create_policy(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+    Json(payload): Json<CreateSlaPolicyRequest>,
+) -> Result<Json<SlaPolicy>, ErrorResponse> {
+    ensure_project_admin(&state, &ctx, project_id).await?;
+    let (name, query) = validate_policy(&payload.name, &payload.query, payload.target_minutes)?;
+
+    let policy = SlaRepository::create_policy(
+        state.pool(),
+        project_id,
+        name,
+        query,
+        payload.metric,
+        payload.target_minutes,
+    )
+    .await
+    .map_err(|error| internal_error(&error, "failed to create SLA policy"))?;
+
+    Ok(Json(policy))
+}
+
+#[instrument(
+    name = "sla.update_policy",
+    skip(state, ctx, payload),
+    fields(policy_id = %policy_id, user_id = %ctx.user.id)
+)]
+async fn update_policy(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(policy_id): Path<Uuid>,
+    Json(payload): Json<UpdateSlaPolicyRequest>,
+) -> Result<Json<SlaPolicy>, ErrorResponse> {
+    let existing = load_policy(&state, policy_id).await?;
+    ensure_project_admin(&state, &ctx, existing.project_id).await?;
+    let target_minutes = payload.target_minutes.unwrap_or(existing.target_minutes);
+    let (name, query) = validate_policy(
+        payload.name.as_deref().unwrap_or(&existing.name),
+        payload.query.as_deref().unwrap_or(&existing.query),
+        target_minutes,
+    )?;
+
+    let policy = SlaRepository::update_policy(
+        state.pool(),
+        policy_id,
+        name,
+        query,
+        payload.metric.unwrap_or(existing.metric),
+        target_minutes,
+    )
+    .await
+    .map_err(|error| internal_error(&error, "failed to update SLA policy"))?;
+
+    Ok(Json(policy))
+}
+
+#[instrument(
+    name = "sla.delete_policy",
+    skip(state, ctx),
+    fields(policy_id = %policy_id, user_id = %ctx.user.id)
+)]
+async fn delete_policy(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(policy_id): Path<Uuid>,
+) -> Result<StatusCode, ErrorResponse> {
+    let policy = load_policy(&state, policy_id).await?;
+    ensure_project_admin(&state, &ctx, policy.project_id).await?;
+    SlaRepository::delete_policy(state.pool(), policy_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to delete SLA policy"))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[instrument(
+    name = "sla.get_report",
+    skip(state, ctx),
+    fields(project_id = %project_id, user_id = %ctx.user.id)
+)]
+async fn get_report(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+    Query(query): Query<SlaReportQuery>,
+) -> Result<Json<SlaReport>, ErrorResponse> {
+    let organization_id = ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
+    let now = Utc::now();
+    let to = query.to.unwrap_or(now);
+    let from = query
+        .from
+        .unwrap_or(to - TimeDelta::days(DEFAULT_REPORT_DAYS));
+    if from >= to {
+        return Err(bad_request("`from` must be before `to`"));
+    }
+
+    let report = sla::report(state.pool(), organization_id, project_id, from, to, now)
+        .await
+        .map_err(|error| internal_error(&error, "failed to build SLA report"))?;
+
+    Ok(Json(report))
+}
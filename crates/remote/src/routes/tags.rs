@@ -19,20 +19,25 @@ use crate::{
     auth::RequestContext,
     db::{tags::TagRepository, types::is_valid_hsl_color},
     mutation_definition::MutationBuilder,
+    query_definition::{QueryBuilder, QueryRoute, query_router},
 };
 
 /// Mutation definition for Tags - provides both router and TypeScript metadata.
 pub fn mutation() -> MutationBuilder<Tag, CreateTagRequest, UpdateTagRequest> {
     MutationBuilder::new("tags")
-        .list(list_tags)
         .get(get_tag)
         .create(create_tag)
         .update(update_tag)
         .delete(delete_tag)
 }
 
+/// Read endpoints for tags.
+pub fn queries() -> Vec<QueryRoute> {
+    vec![QueryBuilder::<ListTagsResponse>::new("list_tags", "tags").get(list_tags)]
+}
+
 pub fn router() -> axum::Router<AppState> {
-    mutation().router()
+    mutation().router().merge(query_router(queries()))
 }
 
 #[instrument(
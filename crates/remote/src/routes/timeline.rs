@@ -0,0 +1,55 @@
+use api_types::ProjectTimeline;
+use axum::{
+    Json, Router,
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    routing::get,
+};
+use chrono::Utc;
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{error::ErrorResponse, organization_members::ensure_project_access};
+use crate::{
+    AppState,
+    auth::RequestContext,
+    db::{issue_relationships::IssueRelationshipRepository, timeline::TimelineRepository},
+    timeline,
+};
+
+pub(super) fn router() -> Router<AppState> {
+    Router::new().route("/projects/{project_id}/timeline", get(get_timeline))
+}
+
+/// The project's issues scheduled around their dates and blocking
+/// relationships, for the roadmap view.
+#[instrument(
+    name = "timeline.get_timeline",
+    skip(state, ctx),
+    fields(project_id = %project_id, user_id = %ctx.user.id)
+)]
+async fn get_timeline(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+) -> Result<Json<ProjectTimeline>, ErrorResponse> {
+    ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
+
+    let internal_error = |error: &dyn std::fmt::Debug, message: &str| {
+        tracing::error!(?error, "{message}");
+        ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to load timeline")
+    };
+    let issues = TimelineRepository::issues(state.pool(), project_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load issues"))?;
+    let relationships = IssueRelationshipRepository::list_by_project(state.pool(), project_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load issue relationships"))?;
+
+    Ok(Json(timeline::schedule(
+        project_id,
+        Utc::now(),
+        issues,
+        &relationships,
+    )))
+}
@@ -16,6 +16,7 @@ use crate::{
         auth::{AuthSessionError, AuthSessionRepository},
         identity_errors::IdentityError,
         oauth_accounts::{OAuthAccountError, OAuthAccountRepository},
+        session_anomalies::{SessionAnomalyError, SessionAnomalyRepository},
         users::UserRepository,
     },
 };
@@ -30,6 +31,8 @@ pub(super) enum TokenRefreshError {
     InvalidToken,
     #[error("session has been revoked")]
     SessionRevoked,
+    #[error("session is suspended pending review")]
+    SessionSuspended,
     #[error("refresh token expired")]
     TokenExpired,
     #[error("refresh token reused - possible token theft")]
@@ -73,6 +76,14 @@ impl From<OAuthAccountError> for TokenRefreshError {
     }
 }
 
+impl From<SessionAnomalyError> for TokenRefreshError {
+    fn from(err: SessionAnomalyError) -> Self {
+        match err {
+            SessionAnomalyError::Database(db_err) => TokenRefreshError::Database(db_err),
+        }
+    }
+}
+
 fn current_session_tokens_response(
     jwt_service: &crate::auth::JwtService,
     session: &api_types::AuthSession,
@@ -123,6 +134,10 @@ async fn refresh_token(
         return Err(TokenRefreshError::SessionRevoked);
     }
 
+    if SessionAnomalyRepository::is_suspended(state.pool(), session.id).await? {
+        return Err(TokenRefreshError::SessionSuspended);
+    }
+
     let is_current_refresh_token = session.refresh_token_id == Some(token_details.refresh_token_id);
     let is_previous_refresh_token = session_repo
         .is_previous_refresh_token_within_grace(&session, token_details.refresh_token_id);
@@ -280,6 +295,7 @@ impl IntoResponse for TokenRefreshError {
             TokenRefreshError::InvalidToken => (StatusCode::UNAUTHORIZED, "invalid_token"),
             TokenRefreshError::TokenExpired => (StatusCode::UNAUTHORIZED, "expired_token"),
             TokenRefreshError::SessionRevoked => (StatusCode::UNAUTHORIZED, "session_revoked"),
+            TokenRefreshError::SessionSuspended => (StatusCode::UNAUTHORIZED, "session_suspended"),
             TokenRefreshError::TokenReuseDetected => {
                 (StatusCode::UNAUTHORIZED, "token_reuse_detected")
             }
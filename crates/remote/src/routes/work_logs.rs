@@ -0,0 +1,288 @@
+use api_types::{
+    CreateWorkLogRequest, ListWorkLogsResponse, StartWorkTimerRequest, UpdateWorkLogRequest,
+    WorkLog, WorkLogSummary, WorkLogSummaryQuery, WorkTimer,
+};
+use axum::{
+    Json, Router,
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    routing::{get, patch, post},
+};
+use chrono::Utc;
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{
+    error::ErrorResponse,
+    organization_members::{ensure_issue_access, ensure_project_access},
+};
+use crate::{AppState, auth::RequestContext, db::work_logs::WorkLogRepository};
+
+/// A single entry covers at most a day.
+const MAX_MINUTES: i32 = 24 * 60;
+
+pub(super) fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/issues/{issue_id}/work-logs",
+            get(list_work_logs).post(create_work_log),
+        )
+        .route("/issues/{issue_id}/work-timer", post(start_timer))
+        .route(
+            "/work-logs/{work_log_id}",
+            patch(update_work_log).delete(delete_work_log),
+        )
+        .route("/work-logs/summary", get(summarize_work_logs))
+        .route("/work-logs/timer", get(get_timer).delete(discard_timer))
+        .route("/work-logs/timer/stop", post(stop_timer))
+}
+
+fn internal_error(error: &dyn std::fmt::Debug, message: &str) -> ErrorResponse {
+    tracing::error!(?error, "{message}");
+    ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+}
+
+fn validate_minutes(minutes: i32) -> Result<(), ErrorResponse> {
+    if !(1..=MAX_MINUTES).contains(&minutes) {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            format!("minutes must be between 1 and {MAX_MINUTES}"),
+        ));
+    }
+    Ok(())
+}
+
+fn normalize_note(note: Option<&str>) -> Option<&str> {
+    note.map(str::trim).filter(|note| !note.is_empty())
+}
+
+/// Load a work log the requester may change: their own, on an issue they
+/// can still access.
+async fn own_work_log(
+    state: &AppState,
+    ctx: &RequestContext,
+    work_log_id: Uuid,
+) -> Result<WorkLog, ErrorResponse> {
+    let work_log = WorkLogRepository::find_by_id(state.pool(), work_log_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load work log"))?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "work log not found"))?;
+    ensure_issue_access(state.pool(), ctx.user.id, work_log.issue_id).await?;
+    if work_log.user_id != ctx.user.id {
+        return Err(ErrorResponse::new(
+            StatusCode::FORBIDDEN,
+            "only the author can change a work log",
+        ));
+    }
+    Ok(work_log)
+}
+
+#[instrument(
+    name = "work_logs.list_work_logs",
+    skip(state, ctx),
+    fields(issue_id = %issue_id, user_id = %ctx.user.id)
+)]
+async fn list_work_logs(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(issue_id): Path<Uuid>,
+) -> Result<Json<ListWorkLogsResponse>, ErrorResponse> {
+    ensure_issue_access(state.pool(), ctx.user.id, issue_id).await?;
+
+    let work_logs = WorkLogRepository::list_by_issue(state.pool(), issue_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to list work logs"))?;
+    let total_minutes = work_logs.iter().map(|log| i64::from(log.minutes)).sum();
+
+    Ok(Json(ListWorkLogsResponse {
+        work_logs,
+        total_minutes,
+    }))
+}
+
+#[instrument(
+    name = "work_logs.create_work_log",
+    skip(state, ctx, payload),
+    fields(issue_id = %issue_id, user_id = %ctx.user.id)
+)]
+async fn create_work_log(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(issue_id): Path<Uuid>,
+    Json(payload): Json<CreateWorkLogRequest>,
+) -> Result<Json<WorkLog>, ErrorResponse> {
+    ensure_issue_access(state.pool(), ctx.user.id, issue_id).await?;
+    validate_minutes(payload.minutes)?;
+
+    let work_log = WorkLogRepository::create(
+        state.pool(),
+        issue_id,
+        ctx.user.id,
+        payload.minutes,
+        normalize_note(payload.note.as_deref()),
+        payload.work_date.unwrap_or_else(|| Utc::now().date_naive()),
+    )
+    .await
+    .map_err(|error| internal_error(&error, "failed to create work log"))?;
+
+    Ok(Json(work_log))
+}
+
+#[instrument(
+    name = "work_logs.update_work_log",
+    skip(state, ctx, payload),
+    fields(work_log_id = %work_log_id, user_id = %ctx.user.id)
+)]
+async fn update_work_log(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(work_log_id): Path<Uuid>,
+    Json(payload): Json<UpdateWorkLogRequest>,
+) -> Result<Json<WorkLog>, ErrorResponse> {
+    let existing = own_work_log(&state, &ctx, work_log_id).await?;
+    let minutes = payload.minutes.unwrap_or(existing.minutes);
+    validate_minutes(minutes)?;
+    let note = match &payload.note {
+        Some(note) => normalize_note(note.as_deref()),
+        None => existing.note.as_deref(),
+    };
+
+    let work_log = WorkLogRepository::update(
+        state.pool(),
+        work_log_id,
+        minutes,
+        note,
+        payload.work_date.unwrap_or(existing.work_date),
+    )
+    .await
+    .map_err(|error| internal_error(&error, "failed to update work log"))?;
+
+    Ok(Json(work_log))
+}
+
+#[instrument(
+    name = "work_logs.delete_work_log",
+    skip(state, ctx),
+    fields(work_log_id = %work_log_id, user_id = %ctx.user.id)
+)]
+async fn delete_work_log(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(work_log_id): Path<Uuid>,
+) -> Result<StatusCode, ErrorResponse> {
+    own_work_log(&state, &ctx, work_log_id).await?;
+    WorkLogRepository::delete(state.pool(), work_log_id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to delete work log"))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Time logged in a project per issue and per user, optionally within a
+/// date range such as a sprint.
+#[instrument(
+    name = "work_logs.summarize_work_logs",
+    skip(state, ctx),
+    fields(project_id = %query.project_id, user_id = %ctx.user.id)
+)]
+async fn summarize_work_logs(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Query(query): Query<WorkLogSummaryQuery>,
+) -> Result<Json<WorkLogSummary>, ErrorResponse> {
+    ensure_project_access(state.pool(), ctx.user.id, query.project_id).await?;
+    if let (Some(from), Some(to)) = (query.from, query.to)
+        && from > to
+    {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "from must not be after to",
+        ));
+    }
+
+    let (by_issue, by_user) = WorkLogRepository::summarize(state.pool(), &query)
+        .await
+        .map_err(|error| internal_error(&error, "failed to summarize work logs"))?;
+    let total_minutes = by_issue.iter().map(|total| total.minutes).sum();
+
+    Ok(Json(WorkLogSummary {
+        total_minutes,
+        by_issue,
+        by_user,
+    }))
+}
+
+#[instrument(name = "work_logs.get_timer", skip(state, ctx), fields(user_id = %ctx.user.id))]
+async fn get_timer(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+) -> Result<Json<Option<WorkTimer>>, ErrorResponse> {
+    let timer = WorkLogRepository::find_timer(state.pool(), ctx.user.id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to load work timer"))?;
+
+    Ok(Json(timer))
+}
+
+#[instrument(
+    name = "work_logs.start_timer",
+    skip(state, ctx, payload),
+    fields(issue_id = %issue_id, user_id = %ctx.user.id)
+)]
+async fn start_timer(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(issue_id): Path<Uuid>,
+    Json(payload): Json<StartWorkTimerRequest>,
+) -> Result<Json<WorkTimer>, ErrorResponse> {
+    ensure_issue_access(state.pool(), ctx.user.id, issue_id).await?;
+
+    let timer = WorkLogRepository::start_timer(
+        state.pool(),
+        ctx.user.id,
+        issue_id,
+        normalize_note(payload.note.as_deref()),
+    )
+    .await
+    .map_err(|error| internal_error(&error, "failed to start work timer"))?
+    .ok_or_else(|| {
+        ErrorResponse::new(
+            StatusCode::CONFLICT,
+            "a timer is already running; stop it first",
+        )
+    })?;
+
+    Ok(Json(timer))
+}
+
+/// Stop the running timer and log its time on the issue it was started on.
+#[instrument(name = "work_logs.stop_timer", skip(state, ctx), fields(user_id = %ctx.user.id))]
+async fn stop_timer(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+) -> Result<Json<WorkLog>, ErrorResponse> {
+    let work_log = WorkLogRepository::stop_timer(state.pool(), ctx.user.id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to stop work timer"))?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "no timer is running"))?;
+
+    Ok(Json(work_log))
+}
+
+#[instrument(name = "work_logs.discard_timer", skip(state, ctx), fields(user_id = %ctx.user.id))]
+async fn discard_timer(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+) -> Result<StatusCode, ErrorResponse> {
+    let discarded = WorkLogRepository::discard_timer(state.pool(), ctx.user.id)
+        .await
+        .map_err(|error| internal_error(&error, "failed to discard work timer"))?;
+    if !discarded {
+        return Err(ErrorResponse::new(
+            StatusCode::NOT_FOUND,
+            "no timer is running",
+        ));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,4 +1,6 @@
-use api_types::{DeleteWorkspaceRequest, UpdateWorkspaceRequest, Workspace};
+use api_types::{
+    DeleteWorkspaceRequest, ReportAgentUsageRequest, UpdateWorkspaceRequest, Workspace,
+};
 use axum::{
     Json, Router,
     extract::{Extension, Path, State},
@@ -17,6 +19,7 @@ use crate::{
     AppState,
     auth::RequestContext,
     db::{
+        agent_usage::{AgentUsageRepository, RecordAgentUsageParams},
         issues::IssueRepository,
         workspaces::{CreateWorkspaceParams, WorkspaceRepository},
     },
@@ -47,6 +50,10 @@ pub(super) fn router() -> Router<AppState> {
             "/workspaces/{local_workspace_id}/sync_issue_status_from_local_merge",
             post(sync_issue_status_from_local_merge),
         )
+        .route(
+            "/workspaces/{local_workspace_id}/agent_usage",
+            post(report_agent_usage),
+        )
         .route(
             "/workspaces/by-local-id/{local_workspace_id}",
             get(get_workspace_by_local_id),
@@ -193,6 +200,58 @@ async fn sync_issue_status_from_local_merge(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Record a finished agent run in the workspace for usage metering.
+#[instrument(
+    name = "workspaces.report_agent_usage",
+    skip(state, ctx, payload),
+    fields(local_workspace_id = %local_workspace_id, execution_process_id = %payload.execution_process_id, user_id = %ctx.user.id)
+)]
+async fn report_agent_usage(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(local_workspace_id): Path<Uuid>,
+    Json(payload): Json<ReportAgentUsageRequest>,
+) -> Result<StatusCode, ErrorResponse> {
+    if payload.completed_at < payload.started_at {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "completed_at must not be before started_at",
+        ));
+    }
+
+    let workspace = WorkspaceRepository::find_by_local_id(state.pool(), local_workspace_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, local_workspace_id = %local_workspace_id, "failed to find workspace");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to find workspace")
+        })?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "workspace not found"))?;
+
+    let organization_id =
+        ensure_project_access(state.pool(), ctx.user.id, workspace.project_id).await?;
+
+    AgentUsageRepository::record(
+        state.pool(),
+        RecordAgentUsageParams {
+            execution_process_id: payload.execution_process_id,
+            organization_id,
+            project_id: workspace.project_id,
+            workspace_id: workspace.id,
+            user_id: ctx.user.id,
+            executor: payload.executor,
+            started_at: payload.started_at,
+            completed_at: payload.completed_at,
+        },
+    )
+    .await
+    .map_err(|error| {
+        tracing::error!(?error, "failed to record agent usage");
+        ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+    })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
 #[instrument(
     name = "workspaces.delete_workspace",
     skip(state, ctx, payload),
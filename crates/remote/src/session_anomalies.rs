@@ -0,0 +1,441 @@
+//! Anomaly detection on session usage.
+//!
+//! Every authenticated request is counted against its session by minute and
+//! by country, in memory until the counts are flushed. A background task compares each session's recent use with
+//! its past week and flags it when it is used from a new country or makes
+//! far more requests than usual. Admins of the member's organizations are
+//! told, and if one of those organizations asks for it the session is
+//! suspended until an admin reviews the flag.
+
+use std::{
+    collections::{HashMap, HashSet},
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use api_types::{SessionAnomalyKind, SessionAnomalySettings, SessionFlag};
+use chrono::{DateTime, DurationRound, Utc};
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    audit::{self, AuditAction, AuditEvent},
+    db::session_anomalies::{
+        SessionAnomalyError, SessionAnomalyRepository, SessionUsageCount, SessionUsageSummary,
+    },
+    mail::Mailer,
+};
+
+/// Cloudflare's code for a country it couldn't determine.
+pub const UNKNOWN_COUNTRY: &str = "XX";
+
+const USAGE_FLUSH_INTERVAL: Duration = Duration::from_secs(15);
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const RECENT_WINDOW: chrono::Duration = chrono::Duration::minutes(15);
+/// How far back usage is kept and compared against.
+const BASELINE_WINDOW: chrono::Duration = chrono::Duration::days(7);
+/// A session isn't flagged twice for the same kind of anomaly within a day.
+const FLAG_COOLDOWN: chrono::Duration = chrono::Duration::hours(24);
+/// Fewer requests than this in the recent window are never a spike.
+const MIN_SPIKE_REQUESTS: i64 = 900;
+/// How many times its usual rate a session must reach to spike.
+const SPIKE_FACTOR: i64 = 10;
+
+/// A session's minute bucket from one country.
+type UsageKey = (Uuid, DateTime<Utc>, String);
+
+/// Requests counted since the last flush, with the latest address seen.
+#[derive(Default)]
+pub struct SessionUsageCounter {
+    counts: Mutex<HashMap<UsageKey, (i32, Option<IpAddr>)>>,
+}
+
+impl SessionUsageCounter {
+    pub fn record(&self, session_id: Uuid, country: String, ip: Option<IpAddr>, at: DateTime<Utc>) {
+        let bucket_start = at
+            .duration_trunc(chrono::Duration::minutes(1))
+            .unwrap_or(at);
+        let mut counts = self.counts.lock().unwrap();
+        let (requests, last_ip) = counts
+            .entry((session_id, bucket_start, country))
+            .or_default();
+        *requests = requests.saturating_add(1);
+        if ip.is_some() {
+            *last_ip = ip;
+        }
+    }
+
+    /// Everything counted so far, leaving the counter empty.
+    fn take(&self) -> Vec<SessionUsageCount> {
+        let counts = std::mem::take(&mut *self.counts.lock().unwrap());
+        counts
+            .into_iter()
+            .map(
+                |((session_id, bucket_start, country), (requests, ip))| SessionUsageCount {
+                    session_id,
+                    bucket_start,
+                    country,
+                    ip,
+                    requests,
+                },
+            )
+            .collect()
+    }
+}
+
+/// An anomaly found in a session's usage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Anomaly {
+    pub kind: SessionAnomalyKind,
+    pub description: String,
+}
+
+/// Countries the session was used from recently but not before. A session
+/// with no known country yet has nothing to change from.
+pub fn new_countries<'a>(recent: &'a [String], known: &[String]) -> Vec<&'a str> {
+    let known: HashSet<&str> = known
+        .iter()
+        .map(String::as_str)
+        .filter(|country| *country != UNKNOWN_COUNTRY)
+        .collect();
+    if known.is_empty() {
+        return Vec::new();
+    }
+    let mut countries: Vec<&str> = recent
+        .iter()
+        .map(String::as_str)
+        .filter(|country| *country != UNKNOWN_COUNTRY && !known.contains(country))
+        .collect();
+    countries.sort_unstable();
+    countries
+}
+
+/// The session's usual number of requests per recent window, from its
+/// baseline. `None` when it has no baseline yet.
+fn usual_requests(summary: &SessionUsageSummary, recent_start: DateTime<Utc>) -> Option<i64> {
+    let baseline_start = summary.first_seen_at.max(recent_start - BASELINE_WINDOW);
+    let windows = (recent_start - baseline_start).num_seconds() / RECENT_WINDOW.num_seconds();
+    (summary.baseline_requests > 0 && windows > 0).then(|| summary.baseline_requests / windows)
+}
+
+pub fn detect(summary: &SessionUsageSummary, recent_start: DateTime<Utc>) -> Vec<Anomaly> {
+    let mut anomalies = Vec::new();
+
+    let countries = new_countries(&summary.recent_countries, &summary.known_countries);
+    if !countries.is_empty() {
+        let mut known: Vec<&str> = summary
+            .known_countries
+            .iter()
+            .map(String::as_str)
+            .filter(|country| *country != UNKNOWN_COUNTRY)
+            .collect();
+        known.sort_unstable();
+        anomalies.push(Anomaly {
+            kind: SessionAnomalyKind::LocationChange,
+            description: format!(
+                "Used from {} after only {} in the past week",
+                countries.join(", "),
+                known.join(", ")
+            ),
+        });
+    }
+
+    let usual = usual_requests(summary, recent_start);
+    let threshold = usual.map_or(MIN_SPIKE_REQUESTS, |usual| {
+        (usual * SPIKE_FACTOR).max(MIN_SPIKE_REQUESTS)
+    });
+    if summary.recent_requests >= threshold {
+        let minutes = RECENT_WINDOW.num_minutes();
+        anomalies.push(Anomaly {
+            kind: SessionAnomalyKind::RateSpike,
+            description: match usual {
+                Some(usual) => format!(
+                    "Made {} requests in {minutes} minutes, against about {usual} usually",
+                    summary.recent_requests
+                ),
+                None => format!(
+                    "Made {} requests in {minutes} minutes",
+                    summary.recent_requests
+                ),
+            },
+        });
+    }
+
+    anomalies
+}
+
+pub fn settings_response(organization_id: Uuid, auto_suspend: bool) -> SessionAnomalySettings {
+    SessionAnomalySettings {
+        organization_id,
+        auto_suspend,
+    }
+}
+
+/// Email the admins of the member's organizations, once per admin.
+async fn notify_admins(
+    pool: &PgPool,
+    mailer: &dyn Mailer,
+    base_url: &str,
+    flag: &SessionFlag,
+) -> Result<(), SessionAnomalyError> {
+    let recipients = SessionAnomalyRepository::flag_recipients(pool, flag.user_id).await?;
+    let mut notified = HashSet::new();
+    for recipient in &recipients {
+        if !notified.insert(recipient.admin_email.as_str()) {
+            continue;
+        }
+        let review_url = format!(
+            "{}/account/organizations/{}",
+            base_url.trim_end_matches('/'),
+            recipient.organization_id
+        );
+        mailer
+            .send_session_flagged(
+                &recipient.admin_email,
+                &recipient.organization_name,
+                &recipient.member_email,
+                &flag.description,
+                flag.suspended,
+                &review_url,
+            )
+            .await;
+    }
+    Ok(())
+}
+
+async fn flag_session(
+    pool: &PgPool,
+    mailer: &dyn Mailer,
+    base_url: &str,
+    summary: &SessionUsageSummary,
+    anomaly: &Anomaly,
+    now: DateTime<Utc>,
+) -> Result<(), SessionAnomalyError> {
+    let suspend = SessionAnomalyRepository::auto_suspend_enabled(pool, summary.user_id).await?;
+    let Some(flag) = SessionAnomalyRepository::create_flag(
+        pool,
+        summary.session_id,
+        summary.user_id,
+        anomaly.kind,
+        &anomaly.description,
+        suspend,
+        now - FLAG_COOLDOWN,
+    )
+    .await?
+    else {
+        return Ok(());
+    };
+
+    info!(
+        session_id = %flag.session_id,
+        user_id = %flag.user_id,
+        kind = ?flag.kind,
+        suspended = flag.suspended,
+        "Flagged session for unusual use"
+    );
+    audit::emit(
+        AuditEvent::system(AuditAction::SessionFlag)
+            .user(flag.user_id, Some(flag.session_id))
+            .resource("auth_session", Some(flag.session_id))
+            .description(if flag.suspended {
+                format!("{}; session suspended pending review", flag.description)
+            } else {
+                flag.description.clone()
+            }),
+    );
+
+    notify_admins(pool, mailer, base_url, &flag).await
+}
+
+async fn run_check(pool: &PgPool, mailer: &dyn Mailer, base_url: &str, now: DateTime<Utc>) {
+    if let Err(error) = SessionAnomalyRepository::prune_usage(pool, now - BASELINE_WINDOW).await {
+        warn!(?error, "Failed to prune session usage");
+    }
+
+    let recent_start = now - RECENT_WINDOW;
+    let summaries =
+        match SessionAnomalyRepository::usage_summaries(pool, recent_start, now - BASELINE_WINDOW)
+            .await
+        {
+            Ok(summaries) => summaries,
+            Err(error) => {
+                warn!(?error, "Failed to load session usage");
+                return;
+            }
+        };
+
+    for summary in &summaries {
+        for anomaly in detect(summary, recent_start) {
+            if let Err(error) = flag_session(pool, mailer, base_url, summary, &anomaly, now).await {
+                warn!(?error, session_id = %summary.session_id, "Failed to flag session");
+            }
+        }
+    }
+}
+
+/// Spawns a background task that checks session usage for anomalies. Call
+/// once during server startup.
+pub(crate) fn spawn_usage_flush_task(
+    pool: PgPool,
+    counter: Arc<SessionUsageCounter>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(USAGE_FLUSH_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let counts = counter.take();
+            if let Err(error) = SessionAnomalyRepository::record_usage(&pool, &counts).await {
+                warn!(
+                    ?error,
+                    sessions = counts.len(),
+                    "Failed to record session usage"
+                );
+            }
+        }
+    })
+}
+
+pub(crate) fn spawn_anomaly_check_task(
+    pool: PgPool,
+    mailer: Arc<dyn Mailer>,
+    base_url: String,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+        // Skip the immediate first tick so the server can finish starting up.
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+            run_check(&pool, mailer.as_ref(), &base_url, Utc::now()).await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn countries(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn summary(recent: i64, baseline: i64, first_seen_at: DateTime<Utc>) -> SessionUsageSummary {
+        SessionUsageSummary {
+            session_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            recent_requests: recent,
+            baseline_requests: baseline,
+            first_seen_at,
+            recent_countries: countries(&["US"]),
+            known_countries: countries(&["US"]),
+        }
+    }
+
+    #[test]
+    fn finds_new_countries() {
+        assert_eq!(
+            new_countries(&countries(&["US", "FR", "XX"]), &countries(&["US", "DE"])),
+            ["FR"]
+        );
+        assert!(new_countries(&countries(&["US"]), &countries(&["US", "XX"])).is_empty());
+        // The first country a session is seen in isn't a change.
+        assert!(new_countries(&countries(&["FR"]), &countries(&["XX"])).is_empty());
+        assert!(new_countries(&countries(&["FR"]), &[]).is_empty());
+    }
+
+    #[test]
+    fn flags_rate_spikes_against_the_baseline() {
+        let now = Utc::now();
+        let recent_start = now - RECENT_WINDOW;
+        let week_old = now - BASELINE_WINDOW;
+        // About 100 requests per window over the past week.
+        let windows = (BASELINE_WINDOW - RECENT_WINDOW).num_minutes() / 15;
+
+        let busy = summary(999, 100 * windows, week_old);
+        assert!(detect(&busy, recent_start).is_empty());
+
+        let spiking = summary(1_000, 50 * windows, week_old);
+        let anomalies = detect(&spiking, recent_start);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].kind, SessionAnomalyKind::RateSpike);
+        assert_eq!(
+            anomalies[0].description,
+            "Made 1000 requests in 15 minutes, against about 50 usually"
+        );
+
+        // New sessions spike once they pass the minimum.
+        assert!(detect(&summary(899, 0, recent_start), recent_start).is_empty());
+        assert_eq!(
+            detect(&summary(900, 0, recent_start), recent_start).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn describes_location_changes() {
+        let now = Utc::now();
+        let mut moved = summary(10, 100, now - BASELINE_WINDOW);
+        moved.recent_countries = countries(&["US", "FR"]);
+        moved.known_countries = countries(&["US", "DE", "XX"]);
+
+        assert_eq!(
+            detect(&moved, now - RECENT_WINDOW),
+            [Anomaly {
+                kind: SessionAnomalyKind::LocationChange,
+                description: "Used from FR after only DE, US in the past week".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn counts_requests_by_minute_and_country_until_taken() {
+        let counter = SessionUsageCounter::default();
+        let session_id = Uuid::new_v4();
+        let at = DateTime::parse_from_rfc3339("2026-05-13T10:00:05Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let ip: IpAddr = "203.0.113.7".parse().unwrap();
+        counter.record(session_id, "DE".to_string(), None, at);
+        counter.record(
+            session_id,
+            "DE".to_string(),
+            Some(ip),
+            at + chrono::Duration::seconds(30),
+        );
+        counter.record(session_id, "FR".to_string(), None, at);
+        counter.record(
+            session_id,
+            "DE".to_string(),
+            None,
+            at + chrono::Duration::minutes(1),
+        );
+
+        let mut counts = counter.take();
+        counts.sort_by(|a, b| (a.bucket_start, &a.country).cmp(&(b.bucket_start, &b.country)));
+        let bucket = at - chrono::Duration::seconds(5);
+        let summary: Vec<_> = counts
+            .iter()
+            .map(|count| {
+                (
+                    count.bucket_start,
+                    count.country.as_str(),
+                    count.requests,
+                    count.ip,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (bucket, "DE", 2, Some(ip)),
+                (bucket, "FR", 1, None),
+                (bucket + chrono::Duration::minutes(1), "DE", 1, None),
+            ]
+        );
+        assert!(counter.take().is_empty());
+    }
+}
@@ -116,6 +116,17 @@ pub enum ShapeScope {
     User,
 }
 
+impl ShapeScope {
+    /// Number of Electric params the scope binds from the authenticated
+    /// request.
+    pub fn param_count(self) -> usize {
+        match self {
+            ShapeScope::Org | ShapeScope::Project | ShapeScope::Issue | ShapeScope::User => 1,
+            ShapeScope::OrgWithUser => 2,
+        }
+    }
+}
+
 // =============================================================================
 // ShapeRoute
 // =============================================================================
@@ -147,6 +158,12 @@ impl ShapeRoute {
         H: Handler<HT, AppState> + Clone + Send + 'static,
         HT: HasQueryParams<Q> + 'static,
     {
+        assert_eq!(
+            shape.params.len(),
+            scope.param_count(),
+            "shape {} must filter on exactly the params its scope provides",
+            shape.name
+        );
         let proxy_handler = build_proxy_handler(shape, scope);
         let router = axum::Router::new()
             .route(shape.url(), proxy_handler)
@@ -2,9 +2,10 @@
 
 use api_types::{
     ListIssueAssigneesResponse, ListIssueCommentReactionsResponse, ListIssueCommentsResponse,
-    ListIssueFollowersResponse, ListIssueRelationshipsResponse, ListIssueTagsResponse,
-    ListIssuesResponse, ListProjectStatusesResponse, ListProjectsResponse,
-    ListPullRequestIssuesResponse, ListPullRequestsResponse, ListTagsResponse, Notification,
+    ListIssueFollowersResponse, ListIssueOrganizationTagsResponse, ListIssueRelationshipsResponse,
+    ListIssueTagsResponse, ListIssuesResponse, ListOrganizationTagsResponse,
+    ListProjectStatusesResponse, ListProjectsResponse, ListPullRequestIssuesResponse,
+    ListPullRequestsResponse, ListTagsResponse, Notification, NotificationUnreadCount,
     OrganizationMember, SearchIssuesRequest, User, Workspace,
 };
 use axum::{
@@ -21,11 +22,12 @@ use crate::{
         issue_assignees::IssueAssigneeRepository,
         issue_comment_reactions::IssueCommentReactionRepository,
         issue_comments::IssueCommentRepository, issue_followers::IssueFollowerRepository,
+        issue_organization_tags::IssueOrganizationTagRepository,
         issue_relationships::IssueRelationshipRepository, issue_tags::IssueTagRepository,
         issues::IssueRepository, notifications::NotificationRepository, organization_members,
-        project_statuses::ProjectStatusRepository, projects::ProjectRepository,
-        pull_request_issues::PullRequestIssueRepository, pull_requests::PullRequestRepository,
-        tags::TagRepository, workspaces::WorkspaceRepository,
+        organization_tags::OrganizationTagRepository, project_statuses::ProjectStatusRepository,
+        projects::ProjectRepository, pull_request_issues::PullRequestIssueRepository,
+        pull_requests::PullRequestRepository, tags::TagRepository, workspaces::WorkspaceRepository,
     },
     routes::{
         error::ErrorResponse,
@@ -47,6 +49,11 @@ struct ListNotificationsResponse {
     notifications: Vec<Notification>,
 }
 
+#[derive(Debug, Serialize)]
+struct ListNotificationUnreadCountsResponse {
+    notification_unread_counts: Vec<NotificationUnreadCount>,
+}
+
 #[derive(Debug, Serialize)]
 struct ListOrganizationMembersResponse {
     organization_member_metadata: Vec<OrganizationMember>,
@@ -84,6 +91,12 @@ pub fn all_shape_routes() -> Vec<ShapeRoute> {
             "/fallback/notifications",
             fallback_list_notifications,
         ),
+        ShapeRoute::new(
+            &shapes::NOTIFICATION_UNREAD_COUNTS_SHAPE,
+            ShapeScope::User,
+            "/fallback/notification_unread_counts",
+            fallback_list_notification_unread_counts,
+        ),
         ShapeRoute::new(
             &shapes::ORGANIZATION_MEMBERS_SHAPE,
             ShapeScope::Org,
@@ -96,6 +109,12 @@ pub fn all_shape_routes() -> Vec<ShapeRoute> {
             "/fallback/users",
             fallback_list_users,
         ),
+        ShapeRoute::new(
+            &shapes::ORGANIZATION_TAGS_SHAPE,
+            ShapeScope::Org,
+            "/fallback/organization_tags",
+            fallback_list_organization_tags,
+        ),
         // Project-scoped
         ShapeRoute::new(
             &shapes::PROJECT_TAGS_SHAPE,
@@ -146,6 +165,12 @@ pub fn all_shape_routes() -> Vec<ShapeRoute> {
             "/fallback/issue_tags",
             fallback_list_issue_tags,
         ),
+        ShapeRoute::new(
+            &shapes::PROJECT_ISSUE_ORGANIZATION_TAGS_SHAPE,
+            ShapeScope::Project,
+            "/fallback/issue_organization_tags",
+            fallback_list_issue_organization_tags,
+        ),
         ShapeRoute::new(
             &shapes::PROJECT_ISSUE_RELATIONSHIPS_SHAPE,
             ShapeScope::Project,
@@ -223,6 +248,31 @@ async fn fallback_list_notifications(
     Ok(Json(ListNotificationsResponse { notifications }))
 }
 
+async fn fallback_list_notification_unread_counts(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Query(_query): Query<NoQueryParams>,
+) -> Result<Json<ListNotificationUnreadCountsResponse>, ErrorResponse> {
+    let notification_unread_counts =
+        NotificationRepository::list_unread_counts(state.pool(), ctx.user.id)
+            .await
+            .map_err(|error| {
+                tracing::error!(
+                    ?error,
+                    user_id = %ctx.user.id,
+                    "failed to list notification unread counts (fallback)"
+                );
+                ErrorResponse::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "failed to list notification unread counts",
+                )
+            })?;
+
+    Ok(Json(ListNotificationUnreadCountsResponse {
+        notification_unread_counts,
+    }))
+}
+
 async fn fallback_list_organization_members(
     State(state): State<AppState>,
     Extension(ctx): Extension<RequestContext>,
@@ -264,6 +314,27 @@ async fn fallback_list_users(
     Ok(Json(ListUsersResponse { users }))
 }
 
+async fn fallback_list_organization_tags(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Query(query): Query<OrgFallbackQuery>,
+) -> Result<Json<ListOrganizationTagsResponse>, ErrorResponse> {
+    ensure_member_access(state.pool(), query.organization_id, ctx.user.id).await?;
+
+    let organization_tags =
+        OrganizationTagRepository::list_by_organization(state.pool(), query.organization_id)
+            .await
+            .map_err(|error| {
+                tracing::error!(?error, organization_id = %query.organization_id, "failed to list organization tags (fallback)");
+                ErrorResponse::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "failed to list organization tags",
+                )
+            })?;
+
+    Ok(Json(ListOrganizationTagsResponse { organization_tags }))
+}
+
 // =============================================================================
 // Project-scoped fallback handlers
 // =============================================================================
@@ -330,6 +401,7 @@ async fn fallback_list_issues(
             sort_direction: None,
             limit: None,
             offset: None,
+            fields: None,
         },
     )
     .await
@@ -420,6 +492,29 @@ async fn fallback_list_issue_tags(
     Ok(Json(ListIssueTagsResponse { issue_tags }))
 }
 
+async fn fallback_list_issue_organization_tags(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Query(query): Query<ProjectFallbackQuery>,
+) -> Result<Json<ListIssueOrganizationTagsResponse>, ErrorResponse> {
+    ensure_project_access(state.pool(), ctx.user.id, query.project_id).await?;
+
+    let issue_organization_tags =
+        IssueOrganizationTagRepository::list_by_project(state.pool(), query.project_id)
+            .await
+            .map_err(|error| {
+                tracing::error!(?error, project_id = %query.project_id, "failed to list issue organization tags (fallback)");
+                ErrorResponse::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "failed to list issue organization tags",
+                )
+            })?;
+
+    Ok(Json(ListIssueOrganizationTagsResponse {
+        issue_organization_tags,
+    }))
+}
+
 async fn fallback_list_issue_relationships(
     State(state): State<AppState>,
     Extension(ctx): Extension<RequestContext>,
@@ -1,9 +1,9 @@
 //! All shape constant instances for realtime streaming.
 
 use api_types::{
-    Issue, IssueAssignee, IssueComment, IssueCommentReaction, IssueFollower, IssueRelationship,
-    IssueTag, Notification, OrganizationMember, Project, ProjectStatus, PullRequest,
-    PullRequestIssue, Tag, User, Workspace,
+    Issue, IssueAssignee, IssueComment, IssueCommentReaction, IssueFollower, IssueOrganizationTag,
+    IssueRelationship, IssueTag, Notification, NotificationUnreadCount, OrganizationMember,
+    OrganizationTag, Project, ProjectStatus, PullRequest, PullRequestIssue, Tag, User, Workspace,
 };
 
 use crate::shape_definition::ShapeDefinition;
@@ -28,6 +28,14 @@ pub const NOTIFICATIONS_SHAPE: ShapeDefinition<Notification> = crate::define_sha
     params: ["user_id"],
 );
 
+pub const NOTIFICATION_UNREAD_COUNTS_SHAPE: ShapeDefinition<NotificationUnreadCount> = crate::define_shape!(
+    name: "NOTIFICATION_UNREAD_COUNTS_SHAPE",
+    table: "notification_unread_counts",
+    where_clause: r#""user_id" = $1"#,
+    url: "/shape/notification_unread_counts",
+    params: ["user_id"],
+);
+
 pub const ORGANIZATION_MEMBERS_SHAPE: ShapeDefinition<OrganizationMember> = crate::define_shape!(
     name: "ORGANIZATION_MEMBERS_SHAPE",
     table: "organization_member_metadata",
@@ -44,6 +52,14 @@ pub const USERS_SHAPE: ShapeDefinition<User> = crate::define_shape!(
     params: ["organization_id"],
 );
 
+pub const ORGANIZATION_TAGS_SHAPE: ShapeDefinition<OrganizationTag> = crate::define_shape!(
+    name: "ORGANIZATION_TAGS_SHAPE",
+    table: "organization_tags",
+    where_clause: r#""organization_id" = $1"#,
+    url: "/shape/organization_tags",
+    params: ["organization_id"],
+);
+
 // =============================================================================
 // Project-scoped shapes
 // =============================================================================
@@ -116,6 +132,14 @@ pub const PROJECT_ISSUE_TAGS_SHAPE: ShapeDefinition<IssueTag> = crate::define_sh
     params: ["project_id"],
 );
 
+pub const PROJECT_ISSUE_ORGANIZATION_TAGS_SHAPE: ShapeDefinition<IssueOrganizationTag> = crate::define_shape!(
+    name: "PROJECT_ISSUE_ORGANIZATION_TAGS_SHAPE",
+    table: "issue_organization_tags",
+    where_clause: r#""issue_id" IN (SELECT id FROM issues WHERE "project_id" = $1)"#,
+    url: "/shape/project/{project_id}/issue_organization_tags",
+    params: ["project_id"],
+);
+
 pub const PROJECT_ISSUE_RELATIONSHIPS_SHAPE: ShapeDefinition<IssueRelationship> = crate::define_shape!(
     name: "PROJECT_ISSUE_RELATIONSHIPS_SHAPE",
     table: "issue_relationships",
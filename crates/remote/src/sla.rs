@@ -0,0 +1,537 @@
+//! SLA targets counted in an organization's working hours.
+//!
+//! A policy applies to the project's issues matching its filter and sets how
+//! many working minutes may pass between an issue's creation and its first
+//! response or its completion. A background task notifies the assignees of
+//! an issue that missed a target, or the organization's admins when it has
+//! none; breaches from before the policy existed are only reported.
+
+use std::{collections::HashMap, time::Duration};
+
+use api_types::{
+    NotificationPayload, NotificationType, OrganizationWorkingHours, SlaIssueStatus, SlaMetric,
+    SlaPolicy, SlaPolicyReport, SlaReport, SlaState, UpdateOrganizationWorkingHoursRequest,
+};
+use chrono::{DateTime, Datelike, NaiveDate, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+use sqlx::PgPool;
+use thiserror::Error;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        issues::IssueRepository,
+        query_subscriptions::{IssueFacts, QuerySubscriptionRepository},
+        sla::{IssueTiming, SlaError, SlaRepository},
+    },
+    notifications::notify_user_without_actor,
+    query_subscriptions::IssueFilter,
+    user_profiles::{normalize_timezone, timezone_or_utc},
+};
+
+const DEFAULT_START_MINUTE: i32 = 9 * 60;
+const DEFAULT_END_MINUTE: i32 = 17 * 60;
+const DEFAULT_WORKING_DAYS: [i16; 5] = [1, 2, 3, 4, 5];
+const MINUTES_PER_DAY: i32 = 24 * 60;
+const MAX_TARGET_MINUTES: i32 = 365 * MINUTES_PER_DAY;
+/// Deadlines further out than this are treated as never reached.
+const MAX_DEADLINE_DAYS: u32 = 10 * 366;
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Error)]
+#[error("invalid SLA settings: {0}")]
+pub struct InvalidSla(String);
+
+/// The hours used for organizations that haven't set any.
+pub fn default_working_hours(organization_id: Uuid) -> OrganizationWorkingHours {
+    OrganizationWorkingHours {
+        organization_id,
+        timezone: Tz::UTC.name().to_string(),
+        start_minute: DEFAULT_START_MINUTE,
+        end_minute: DEFAULT_END_MINUTE,
+        working_days: DEFAULT_WORKING_DAYS.to_vec(),
+    }
+}
+
+/// Validate working hours, canonicalizing the time zone and sorting the days.
+pub fn normalize_working_hours(
+    organization_id: Uuid,
+    request: &UpdateOrganizationWorkingHoursRequest,
+) -> Result<OrganizationWorkingHours, InvalidSla> {
+    let timezone =
+        normalize_timezone(&request.timezone).map_err(|error| InvalidSla(error.to_string()))?;
+    if request.start_minute < 0
+        || request.end_minute > MINUTES_PER_DAY
+        || request.start_minute >= request.end_minute
+    {
+        return Err(InvalidSla(
+            "working hours must start before they end, within one day".to_string(),
+        ));
+    }
+    if let Some(day) = request
+        .working_days
+        .iter()
+        .find(|day| !(1..=7).contains(*day))
+    {
+        return Err(InvalidSla(format!("`{day}` is not an ISO weekday")));
+    }
+    let mut working_days = request.working_days.clone();
+    working_days.sort_unstable();
+    working_days.dedup();
+    if working_days.is_empty() {
+        return Err(InvalidSla("at least one working day is needed".to_string()));
+    }
+
+    Ok(OrganizationWorkingHours {
+        organization_id,
+        timezone,
+        start_minute: request.start_minute,
+        end_minute: request.end_minute,
+        working_days,
+    })
+}
+
+pub fn validate_target(target_minutes: i32) -> Result<(), InvalidSla> {
+    if target_minutes <= 0 || target_minutes > MAX_TARGET_MINUTES {
+        return Err(InvalidSla(format!(
+            "targets must be between 1 and {MAX_TARGET_MINUTES} minutes"
+        )));
+    }
+    Ok(())
+}
+
+/// Working hours resolved for computing deadlines.
+#[derive(Debug, Clone)]
+pub struct WorkingSchedule {
+    tz: Tz,
+    start_minute: i32,
+    end_minute: i32,
+    /// Indexed by days from Monday.
+    days: [bool; 7],
+}
+
+impl WorkingSchedule {
+    pub fn new(hours: &OrganizationWorkingHours) -> Self {
+        let mut days = [false; 7];
+        for day in &hours.working_days {
+            if (1..=7).contains(day) {
+                days[(*day - 1) as usize] = true;
+            }
+        }
+        Self {
+            tz: timezone_or_utc(Some(&hours.timezone)),
+            start_minute: hours.start_minute,
+            end_minute: hours.end_minute,
+            days,
+        }
+    }
+
+    /// The UTC instant `minute` minutes after midnight on `date`, local time.
+    /// A time skipped by a daylight saving change moves to the hour after it.
+    fn local_instant(&self, date: NaiveDate, minute: i32) -> DateTime<Utc> {
+        let naive =
+            date.and_hms_opt(0, 0, 0).expect("midnight exists") + TimeDelta::minutes(minute.into());
+        self.tz
+            .from_local_datetime(&naive)
+            .earliest()
+            .or_else(|| {
+                self.tz
+                    .from_local_datetime(&(naive + TimeDelta::hours(1)))
+                    .earliest()
+            })
+            .map(|time| time.with_timezone(&Utc))
+            .unwrap_or_else(|| naive.and_utc())
+    }
+
+    /// When work starts and ends on `date`, if it is a working day.
+    fn window(&self, date: NaiveDate) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        if !self.days[date.weekday().num_days_from_monday() as usize] {
+            return None;
+        }
+        Some((
+            self.local_instant(date, self.start_minute),
+            self.local_instant(date, self.end_minute),
+        ))
+    }
+
+    /// The instant `target_minutes` working minutes after `start`.
+    pub fn deadline(&self, start: DateTime<Utc>, target_minutes: i32) -> Option<DateTime<Utc>> {
+        let mut remaining = TimeDelta::minutes(target_minutes.into());
+        let mut date = start.with_timezone(&self.tz).date_naive();
+        for _ in 0..MAX_DEADLINE_DAYS {
+            if let Some((open, close)) = self.window(date) {
+                let from = open.max(start);
+                if from < close {
+                    let available = close - from;
+                    if remaining <= available {
+                        return Some(from + remaining);
+                    }
+                    remaining -= available;
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+}
+
+/// Where an issue stands against a policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlaStatus {
+    pub deadline: DateTime<Utc>,
+    pub met_at: Option<DateTime<Utc>>,
+    pub state: SlaState,
+}
+
+pub fn status(
+    schedule: &WorkingSchedule,
+    policy: &SlaPolicy,
+    issue: &IssueTiming,
+    now: DateTime<Utc>,
+) -> Option<SlaStatus> {
+    let met_at = match policy.metric {
+        SlaMetric::FirstResponse => match (issue.first_response_at, issue.completed_at) {
+            (Some(response), Some(completed)) => Some(response.min(completed)),
+            (response, completed) => response.or(completed),
+        },
+        SlaMetric::Resolution => issue.completed_at,
+    };
+    let deadline = schedule.deadline(issue.created_at, policy.target_minutes)?;
+    let state = match met_at {
+        Some(met_at) if met_at <= deadline => SlaState::Met,
+        Some(_) => SlaState::Breached,
+        None if now >= deadline => SlaState::Breached,
+        None => SlaState::Pending,
+    };
+    Some(SlaStatus {
+        deadline,
+        met_at,
+        state,
+    })
+}
+
+/// The organization's working hours, or the defaults.
+pub async fn working_hours(
+    pool: &PgPool,
+    organization_id: Uuid,
+) -> Result<OrganizationWorkingHours, SlaError> {
+    Ok(SlaRepository::working_hours(pool, organization_id)
+        .await?
+        .unwrap_or_else(|| default_working_hours(organization_id)))
+}
+
+/// The project's policies with their filters; policies whose filter no
+/// longer parses are skipped.
+async fn policies_with_filters(
+    pool: &PgPool,
+    project_id: Uuid,
+) -> Result<Vec<(SlaPolicy, IssueFilter)>, SlaError> {
+    let policies = SlaRepository::list_policies(pool, project_id).await?;
+    Ok(policies
+        .into_iter()
+        .filter_map(|policy| match IssueFilter::parse(&policy.query) {
+            Ok(filter) => Some((policy, filter)),
+            Err(error) => {
+                warn!(?error, policy_id = %policy.id, "skipping SLA policy");
+                None
+            }
+        })
+        .collect())
+}
+
+async fn facts_by_issue(
+    pool: &PgPool,
+    project_id: Uuid,
+) -> Result<HashMap<Uuid, IssueFacts>, SlaError> {
+    Ok(
+        QuerySubscriptionRepository::issue_facts(pool, project_id, None)
+            .await?
+            .into_iter()
+            .map(|facts| (facts.id, facts))
+            .collect(),
+    )
+}
+
+/// How the project's issues created in `[from, to)` did against each policy.
+pub async fn report(
+    pool: &PgPool,
+    organization_id: Uuid,
+    project_id: Uuid,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<SlaReport, SlaError> {
+    let working_hours = working_hours(pool, organization_id).await?;
+    let schedule = WorkingSchedule::new(&working_hours);
+    let policies = policies_with_filters(pool, project_id).await?;
+    let facts = facts_by_issue(pool, project_id).await?;
+    let issues =
+        SlaRepository::issue_timings(pool, project_id, Some(from), Some(to), false).await?;
+
+    let policies = policies
+        .into_iter()
+        .map(|(policy, filter)| {
+            let issues: Vec<SlaIssueStatus> = issues
+                .iter()
+                .filter(|issue| {
+                    facts
+                        .get(&issue.id)
+                        .is_some_and(|facts| filter.matches(facts, Uuid::nil()))
+                })
+                .filter_map(|issue| {
+                    let status = status(&schedule, &policy, issue, now)?;
+                    Some(SlaIssueStatus {
+                        issue_id: issue.id,
+                        simple_id: issue.simple_id.clone(),
+                        title: issue.title.clone(),
+                        created_at: issue.created_at,
+                        deadline: status.deadline,
+                        met_at: status.met_at,
+                        state: status.state,
+                    })
+                })
+                .collect();
+            let count = |state| issues.iter().filter(|issue| issue.state == state).count() as u32;
+            SlaPolicyReport {
+                met: count(SlaState::Met),
+                breached: count(SlaState::Breached),
+                pending: count(SlaState::Pending),
+                policy,
+                issues,
+            }
+        })
+        .collect();
+
+    Ok(SlaReport {
+        project_id,
+        from,
+        to,
+        working_hours,
+        policies,
+    })
+}
+
+/// Notify about open issues that missed a target since the last check.
+/// Returns how many breaches were found.
+pub async fn notify_breaches(pool: &PgPool, now: DateTime<Utc>) -> Result<u32, SlaError> {
+    let mut breaches = 0;
+    for project in SlaRepository::projects_with_policies(pool).await? {
+        match notify_project_breaches(pool, project.organization_id, project.project_id, now).await
+        {
+            Ok(count) => breaches += count,
+            Err(error) => {
+                warn!(?error, project_id = %project.project_id, "failed to check SLA breaches");
+            }
+        }
+    }
+    Ok(breaches)
+}
+
+async fn notify_project_breaches(
+    pool: &PgPool,
+    organization_id: Uuid,
+    project_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<u32, SlaError> {
+    let schedule = WorkingSchedule::new(&working_hours(pool, organization_id).await?);
+    let policies = policies_with_filters(pool, project_id).await?;
+    let facts = facts_by_issue(pool, project_id).await?;
+    let issues = SlaRepository::issue_timings(pool, project_id, None, None, true).await?;
+    let mut admin_ids = None;
+
+    let mut breaches = 0;
+    for (policy, filter) in &policies {
+        for issue in &issues {
+            let Some(facts) = facts
+                .get(&issue.id)
+                .filter(|facts| filter.matches(facts, Uuid::nil()))
+            else {
+                continue;
+            };
+            let Some(status) = status(&schedule, policy, issue, now) else {
+                continue;
+            };
+            if status.state != SlaState::Breached
+                || status.met_at.is_some()
+                || status.deadline < policy.created_at
+                || !SlaRepository::record_breach(pool, policy.id, issue.id, status.deadline).await?
+            {
+                continue;
+            }
+            breaches += 1;
+
+            if facts.assignee_ids.is_empty() && admin_ids.is_none() {
+                admin_ids = Some(SlaRepository::admin_ids(pool, organization_id).await?);
+            }
+            let recipients = if facts.assignee_ids.is_empty() {
+                admin_ids.as_deref().unwrap_or_default()
+            } else {
+                facts.assignee_ids.as_slice()
+            };
+            let issue = match IssueRepository::find_by_id(pool, issue.id).await {
+                Ok(Some(issue)) => issue,
+                Ok(None) => continue,
+                Err(error) => {
+                    warn!(?error, issue_id = %issue.id, "failed to load issue for SLA breach");
+                    continue;
+                }
+            };
+            for &user_id in recipients {
+                notify_user_without_actor(
+                    pool,
+                    organization_id,
+                    user_id,
+                    &issue,
+                    NotificationType::IssueSlaBreached,
+                    NotificationPayload {
+                        sla_policy_id: Some(policy.id),
+                        sla_policy_name: Some(policy.name.clone()),
+                        ..Default::default()
+                    },
+                )
+                .await;
+            }
+        }
+    }
+
+    Ok(breaches)
+}
+
+/// Spawns a background task that notifies SLA breaches. Call once during
+/// server startup.
+pub(crate) fn spawn_breach_check_task(pool: PgPool) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+        // Skip the immediate first tick so the server can finish starting up.
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+            match notify_breaches(&pool, Utc::now()).await {
+                Ok(breaches) => info!(breaches, "SLA breaches checked"),
+                Err(error) => warn!(?error, "SLA breach check failed"),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    fn berlin_schedule() -> WorkingSchedule {
+        WorkingSchedule::new(&OrganizationWorkingHours {
+            timezone: "Europe/Berlin".to_string(),
+            ..default_working_hours(Uuid::nil())
+        })
+    }
+
+    #[test]
+    fn counts_only_working_minutes() {
+        let schedule = berlin_schedule();
+
+        // Friday 16:00 in Berlin: one hour left that day, three on Monday.
+        assert_eq!(
+            schedule.deadline(at("2026-06-12T14:00:00Z"), 4 * 60),
+            Some(at("2026-06-15T10:00:00Z"))
+        );
+        // Created on Saturday, the clock starts Monday at 09:00.
+        assert_eq!(
+            schedule.deadline(at("2026-06-13T10:00:00Z"), 4 * 60),
+            Some(at("2026-06-15T11:00:00Z"))
+        );
+        // Created before opening on a working day.
+        assert_eq!(
+            schedule.deadline(at("2026-06-15T05:00:00Z"), 30),
+            Some(at("2026-06-15T07:30:00Z"))
+        );
+    }
+
+    #[test]
+    fn classifies_issues_against_a_policy() {
+        let schedule = WorkingSchedule::new(&default_working_hours(Uuid::nil()));
+        let policy = SlaPolicy {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            name: "Urgent response".to_string(),
+            query: "priority:urgent".to_string(),
+            metric: SlaMetric::FirstResponse,
+            target_minutes: 4 * 60,
+            created_at: at("2026-06-01T00:00:00Z"),
+            updated_at: at("2026-06-01T00:00:00Z"),
+        };
+        let issue = IssueTiming {
+            id: Uuid::new_v4(),
+            simple_id: "APP-1".to_string(),
+            title: "Checkout is down".to_string(),
+            created_at: at("2026-06-15T09:00:00Z"),
+            completed_at: None,
+            first_response_at: None,
+        };
+        let state = |issue: &IssueTiming, now| status(&schedule, &policy, issue, now).unwrap();
+
+        assert_eq!(
+            state(&issue, at("2026-06-15T12:00:00Z")).state,
+            SlaState::Pending
+        );
+        assert_eq!(
+            state(&issue, at("2026-06-15T13:00:00Z")).state,
+            SlaState::Breached
+        );
+
+        let answered = IssueTiming {
+            first_response_at: Some(at("2026-06-15T15:00:00Z")),
+            completed_at: Some(at("2026-06-15T11:00:00Z")),
+            ..issue.clone()
+        };
+        let answered = state(&answered, at("2026-06-16T00:00:00Z"));
+        assert_eq!(answered.met_at, Some(at("2026-06-15T11:00:00Z")));
+        assert_eq!(answered.state, SlaState::Met);
+
+        let late = IssueTiming {
+            first_response_at: Some(at("2026-06-15T14:00:00Z")),
+            ..issue
+        };
+        assert_eq!(
+            state(&late, at("2026-06-16T00:00:00Z")).state,
+            SlaState::Breached
+        );
+    }
+
+    #[test]
+    fn validates_working_hours() {
+        let request = UpdateOrganizationWorkingHoursRequest {
+            timezone: "america/new_york".to_string(),
+            start_minute: 8 * 60 + 30,
+            end_minute: MINUTES_PER_DAY,
+            working_days: vec![5, 1, 1, 3],
+        };
+        let hours = normalize_working_hours(Uuid::nil(), &request).unwrap();
+        assert_eq!(hours.timezone, "America/New_York");
+        assert_eq!(hours.working_days, vec![1, 3, 5]);
+
+        for invalid in [
+            UpdateOrganizationWorkingHoursRequest {
+                start_minute: 18 * 60,
+                end_minute: 9 * 60,
+                ..request.clone()
+            },
+            UpdateOrganizationWorkingHoursRequest {
+                working_days: vec![0, 1],
+                ..request.clone()
+            },
+            UpdateOrganizationWorkingHoursRequest {
+                working_days: vec![],
+                ..request.clone()
+            },
+        ] {
+            assert!(normalize_working_hours(Uuid::nil(), &invalid).is_err());
+        }
+    }
+}
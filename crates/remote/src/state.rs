@@ -11,6 +11,7 @@ use crate::{
     github_app::GitHubAppService,
     mail::Mailer,
     r2::R2Service,
+    session_anomalies::SessionUsageCounter,
 };
 
 #[derive(Clone)]
@@ -28,6 +29,7 @@ pub struct AppState {
     github_app: Option<Arc<GitHubAppService>>,
     billing: BillingService,
     analytics: Option<AnalyticsService>,
+    session_usage: Arc<SessionUsageCounter>,
 }
 
 impl AppState {
@@ -46,6 +48,7 @@ impl AppState {
         github_app: Option<Arc<GitHubAppService>>,
         billing: BillingService,
         analytics: Option<AnalyticsService>,
+        session_usage: Arc<SessionUsageCounter>,
     ) -> Self {
         Self {
             pool,
@@ -61,6 +64,7 @@ impl AppState {
             github_app,
             billing,
             analytics,
+            session_usage,
         }
     }
 
@@ -107,4 +111,8 @@ impl AppState {
     pub fn analytics(&self) -> Option<&AnalyticsService> {
         self.analytics.as_ref()
     }
+
+    pub fn session_usage(&self) -> &SessionUsageCounter {
+        &self.session_usage
+    }
 }
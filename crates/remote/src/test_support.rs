@@ -0,0 +1,170 @@
+//! Fixtures for tests that call route handlers against the Postgres database
+//! at `DATABASE_URL`. Each test seeds its own organization, so tests can
+//! share the database and run in parallel.
+
+use std::sync::Arc;
+
+use api_types::{Issue, ProjectStatus};
+use axum::{body::to_bytes, http::StatusCode, response::IntoResponse};
+use chrono::Utc;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    AppState, BillingService,
+    auth::{
+        JwtService, OAuthHandoffService, OAuthTokenValidator, ProviderRegistry, RequestContext,
+    },
+    config::RemoteServerConfig,
+    db::{
+        self,
+        issues::IssueRepository,
+        organizations::OrganizationRepository,
+        project_statuses::ProjectStatusRepository,
+        projects::ProjectRepository,
+        users::{UpsertUser, UserRepository},
+    },
+    mail::NoopMailer,
+    routes::error::ErrorResponse,
+    session_anomalies::SessionUsageCounter,
+    token_encryption::TokenCipher,
+};
+
+/// A pool on the database at `DATABASE_URL`, migrated to the latest schema.
+pub(crate) async fn test_pool() -> PgPool {
+    let database_url =
+        std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for database tests");
+    let pool = db::create_pool(&database_url).await.unwrap();
+    db::migrate(&pool).await.unwrap();
+    pool
+}
+
+pub(crate) fn test_state(pool: PgPool) -> AppState {
+    let config = RemoteServerConfig::for_tests();
+    let token_cipher = TokenCipher::new(config.auth.jwt_secret(), None).unwrap();
+    let jwt = Arc::new(JwtService::new(
+        config.auth.jwt_secret().clone(),
+        config.auth.access_token_ttl_seconds(),
+        Arc::new(token_cipher),
+    ));
+    let providers = Arc::new(ProviderRegistry::new());
+    let handoff = Arc::new(OAuthHandoffService::new(
+        pool.clone(),
+        providers.clone(),
+        jwt.clone(),
+        config.auth.public_base_url().to_string(),
+    ));
+    let oauth_token_validator = Arc::new(OAuthTokenValidator::new(
+        pool.clone(),
+        providers,
+        jwt.clone(),
+    ));
+    #[cfg(feature = "vk-billing")]
+    let billing = BillingService::new(None);
+    #[cfg(not(feature = "vk-billing"))]
+    let billing = BillingService::new();
+
+    AppState::new(
+        pool,
+        config,
+        jwt,
+        handoff,
+        oauth_token_validator,
+        Arc::new(NoopMailer),
+        "http://localhost".to_string(),
+        reqwest::Client::new(),
+        None,
+        None,
+        None,
+        billing,
+        None,
+        Arc::new(SessionUsageCounter::default()),
+    )
+}
+
+/// A member of a new organization, with the organization's initial project.
+pub(crate) struct Member {
+    pub ctx: RequestContext,
+    pub project_id: Uuid,
+    /// The project's statuses, in board order.
+    pub statuses: Vec<ProjectStatus>,
+}
+
+impl Member {
+    pub(crate) fn status(&self, name: &str) -> Uuid {
+        self.statuses
+            .iter()
+            .find(|status| status.name == name)
+            .unwrap_or_else(|| panic!("no status named {name}"))
+            .id
+    }
+}
+
+pub(crate) async fn create_member(pool: &PgPool) -> Member {
+    let id = Uuid::new_v4();
+    let email = format!("{id}@example.com");
+    let user = UserRepository::new(pool)
+        .upsert_user(UpsertUser {
+            id,
+            email: &email,
+            first_name: None,
+            last_name: None,
+            username: None,
+        })
+        .await
+        .unwrap();
+    let organization = OrganizationRepository::new(pool)
+        .create_organization("Test organization", &format!("test-{id}"), user.id)
+        .await
+        .unwrap();
+    let project = ProjectRepository::list_by_organization(pool, organization.id)
+        .await
+        .unwrap()
+        .remove(0);
+    let mut statuses = ProjectStatusRepository::list_by_project(pool, project.id)
+        .await
+        .unwrap();
+    statuses.sort_by_key(|status| status.sort_order);
+
+    Member {
+        ctx: RequestContext {
+            user,
+            session_id: Uuid::new_v4(),
+            access_token_expires_at: Utc::now(),
+        },
+        project_id: project.id,
+        statuses,
+    }
+}
+
+pub(crate) async fn create_issue(pool: &PgPool, member: &Member, status_id: Uuid) -> Issue {
+    IssueRepository::create(
+        pool,
+        None,
+        member.project_id,
+        status_id,
+        "Test issue".to_string(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        0.0,
+        None,
+        None,
+        json!({}),
+        member.ctx.user.id,
+    )
+    .await
+    .unwrap()
+    .data
+}
+
+/// The status and JSON body a handler's error is sent as.
+pub(crate) async fn error_body(error: ErrorResponse) -> (StatusCode, Value) {
+    let response = error.into_response();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
@@ -0,0 +1,249 @@
+//! Dependency-aware scheduling for a project's roadmap.
+//!
+//! Open issues are scheduled with the critical path method over their
+//! blocking relationships. An issue starts at its start date (or now) but
+//! not before its open blockers end, and takes as long as its start and
+//! target dates are apart. An issue with only a target date is expected to
+//! end on it, and one without dates ends as soon as it starts. Completed
+//! issues don't block anything.
+
+use std::collections::{HashMap, VecDeque};
+
+use api_types::{
+    IssueRelationship, IssueRelationshipType, ProjectTimeline, TimelineDependency, TimelineItem,
+};
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::db::timeline::TimelineIssue;
+
+pub fn schedule(
+    project_id: Uuid,
+    now: DateTime<Utc>,
+    issues: Vec<TimelineIssue>,
+    relationships: &[IssueRelationship],
+) -> ProjectTimeline {
+    let index: HashMap<Uuid, usize> = issues
+        .iter()
+        .enumerate()
+        .map(|(i, issue)| (issue.id, i))
+        .collect();
+    let dependencies: Vec<TimelineDependency> = relationships
+        .iter()
+        .filter(|relationship| {
+            relationship.relationship_type == IssueRelationshipType::Blocking
+                && relationship.issue_id != relationship.related_issue_id
+                && index.contains_key(&relationship.issue_id)
+                && index.contains_key(&relationship.related_issue_id)
+        })
+        .map(|relationship| TimelineDependency {
+            blocker_id: relationship.issue_id,
+            blocked_id: relationship.related_issue_id,
+        })
+        .collect();
+
+    let count = issues.len();
+    let open = |i: usize| issues[i].completed_at.is_none();
+    let mut blockers: Vec<Vec<usize>> = vec![Vec::new(); count];
+    let mut blocked: Vec<Vec<usize>> = vec![Vec::new(); count];
+    for dependency in &dependencies {
+        let (blocker, dependent) = (index[&dependency.blocker_id], index[&dependency.blocked_id]);
+        if open(blocker) && open(dependent) {
+            blockers[dependent].push(blocker);
+            blocked[blocker].push(dependent);
+        }
+    }
+
+    // Topological order of the open issues; those left out are in or behind
+    // a cycle.
+    let mut pending: Vec<usize> = blockers.iter().map(Vec::len).collect();
+    let mut queue: VecDeque<usize> = (0..count).filter(|&i| open(i) && pending[i] == 0).collect();
+    let mut order = Vec::with_capacity(count);
+    while let Some(i) = queue.pop_front() {
+        order.push(i);
+        for &dependent in &blocked[i] {
+            pending[dependent] -= 1;
+            if pending[dependent] == 0 {
+                queue.push_back(dependent);
+            }
+        }
+    }
+
+    // Forward pass: earliest start and end.
+    let mut start: Vec<Option<DateTime<Utc>>> = vec![None; count];
+    let mut end: Vec<Option<DateTime<Utc>>> = vec![None; count];
+    for &i in &order {
+        let issue = &issues[i];
+        let earliest = issue.start_date.unwrap_or(now);
+        let issue_start = blockers[i]
+            .iter()
+            .filter_map(|&blocker| end[blocker])
+            .fold(earliest, Ord::max);
+        let issue_end = match (issue.start_date, issue.target_date) {
+            (Some(start_date), Some(target_date)) => {
+                issue_start + (target_date - start_date).max(Duration::zero())
+            }
+            (None, Some(target_date)) => target_date.max(issue_start),
+            _ => issue_start,
+        };
+        start[i] = Some(issue_start);
+        end[i] = Some(issue_end);
+    }
+
+    // Backward pass: latest start that doesn't delay the project's end.
+    let project_end = order.iter().filter_map(|&i| end[i]).max();
+    let mut latest_start: Vec<Option<DateTime<Utc>>> = vec![None; count];
+    for &i in order.iter().rev() {
+        let (Some(issue_start), Some(issue_end)) = (start[i], end[i]) else {
+            continue;
+        };
+        let Some(latest_end) = blocked[i]
+            .iter()
+            .filter_map(|&dependent| latest_start[dependent])
+            .min()
+            .or(project_end)
+        else {
+            continue;
+        };
+        latest_start[i] = Some(latest_end - (issue_end - issue_start));
+    }
+
+    let critical = |i: usize| matches!((start[i], latest_start[i]), (Some(s), Some(l)) if s == l);
+    let mut critical_path: Vec<usize> = order.iter().copied().filter(|&i| critical(i)).collect();
+    critical_path.sort_by_key(|&i| (start[i], end[i]));
+    let critical_path = critical_path.into_iter().map(|i| issues[i].id).collect();
+    let cyclic_issue_ids = (0..count)
+        .filter(|&i| open(i) && start[i].is_none())
+        .map(|i| issues[i].id)
+        .collect();
+
+    let items = issues
+        .iter()
+        .enumerate()
+        .map(|(i, issue)| TimelineItem {
+            issue_id: issue.id,
+            simple_id: issue.simple_id.clone(),
+            title: issue.title.clone(),
+            parent_issue_id: issue.parent_issue_id,
+            start_date: issue.start_date,
+            target_date: issue.target_date,
+            completed_at: issue.completed_at,
+            scheduled_start: start[i],
+            scheduled_end: end[i],
+            latest_start: latest_start[i],
+            critical: critical(i),
+            late: matches!((end[i], issue.target_date), (Some(e), Some(t)) if e > t),
+        })
+        .collect();
+
+    ProjectTimeline {
+        project_id,
+        computed_at: now,
+        items,
+        dependencies,
+        critical_path,
+        cyclic_issue_ids,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn day(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 6, day, 0, 0, 0).unwrap()
+    }
+
+    fn issue(simple_id: &str, start: Option<u32>, target: Option<u32>) -> TimelineIssue {
+        TimelineIssue {
+            id: Uuid::new_v4(),
+            simple_id: simple_id.to_string(),
+            title: simple_id.to_string(),
+            parent_issue_id: None,
+            start_date: start.map(day),
+            target_date: target.map(day),
+            completed_at: None,
+        }
+    }
+
+    fn blocks(blocker: &TimelineIssue, blocked: &TimelineIssue) -> IssueRelationship {
+        IssueRelationship {
+            id: Uuid::new_v4(),
+            issue_id: blocker.id,
+            related_issue_id: blocked.id,
+            relationship_type: IssueRelationshipType::Blocking,
+            created_at: day(1),
+        }
+    }
+
+    #[test]
+    fn finds_critical_path_through_blockers() {
+        let design = issue("A-1", Some(1), Some(5));
+        let build = issue("A-2", Some(2), Some(8));
+        let docs = issue("A-3", Some(3), Some(4));
+        let release = issue("A-4", None, Some(10));
+        let relationships = vec![
+            blocks(&design, &build),
+            blocks(&build, &release),
+            blocks(&docs, &release),
+        ];
+        let ids = [design.id, build.id, docs.id, release.id];
+
+        let timeline = schedule(
+            Uuid::new_v4(),
+            day(1),
+            vec![design, build, docs, release],
+            &relationships,
+        );
+        let item = |id: Uuid| {
+            timeline
+                .items
+                .iter()
+                .find(|item| item.issue_id == id)
+                .unwrap()
+        };
+
+        // Build can't start before design ends, so it ends on day 11 and
+        // pushes the release past its target.
+        assert_eq!(item(ids[1]).scheduled_start, Some(day(5)));
+        assert_eq!(item(ids[1]).scheduled_end, Some(day(11)));
+        assert_eq!(item(ids[3]).scheduled_end, Some(day(11)));
+        assert!(item(ids[3]).late);
+        assert_eq!(timeline.critical_path, vec![ids[0], ids[1], ids[3]]);
+        assert!(!item(ids[2]).critical);
+        assert_eq!(item(ids[2]).latest_start, Some(day(10)));
+        assert!(timeline.cyclic_issue_ids.is_empty());
+    }
+
+    #[test]
+    fn leaves_cycles_and_completed_issues_unscheduled() {
+        let first = issue("A-1", Some(1), Some(2));
+        let second = issue("A-2", Some(1), Some(2));
+        let behind = issue("A-3", Some(1), Some(2));
+        let mut done = issue("A-4", Some(1), Some(20));
+        done.completed_at = Some(day(3));
+        let free = issue("A-5", Some(1), Some(2));
+        let relationships = vec![
+            blocks(&first, &second),
+            blocks(&second, &first),
+            blocks(&second, &behind),
+            blocks(&done, &free),
+        ];
+        let ids = [first.id, second.id, behind.id, done.id, free.id];
+
+        let timeline = schedule(
+            Uuid::new_v4(),
+            day(1),
+            vec![first, second, behind, done, free],
+            &relationships,
+        );
+
+        assert_eq!(timeline.cyclic_issue_ids, vec![ids[0], ids[1], ids[2]]);
+        assert_eq!(timeline.dependencies.len(), 4);
+        assert_eq!(timeline.critical_path, vec![ids[4]]);
+        assert!(timeline.items[3].scheduled_start.is_none());
+        assert_eq!(timeline.items[4].scheduled_end, Some(day(2)));
+    }
+}
@@ -0,0 +1,341 @@
+//! Envelope encryption of stored provider OAuth tokens.
+//!
+//! Each value is encrypted with its own data key, which is stored next to it
+//! wrapped by a key-encryption key from `PROVIDER_TOKEN_KEYS`. Rotating means
+//! adding a key, making it active and running `remote-rotate-token-keys`,
+//! which re-wraps every stored value with the active key; the old key can be
+//! dropped once it reports nothing left to rotate.
+//!
+//! Without configured keys, a key derived from the JWT secret is used. It is
+//! always kept in the ring so values written before keys were configured,
+//! and values in the older single-key format, can still be read.
+
+use std::collections::HashMap;
+
+use aes_gcm::{
+    Aes256Gcm, Key, Nonce,
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+};
+use base64::{
+    Engine as _,
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+};
+use secrecy::{ExposeSecret, SecretString};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use thiserror::Error;
+use tracing::{info, warn};
+
+use crate::{
+    config::TokenEncryptionConfig,
+    db::provider_tokens::{ProviderTokenError, ProviderTokenRepository, ProviderTokenTable},
+};
+
+/// Id of the key derived from the JWT secret.
+pub const JWT_DERIVED_KEY_ID: &str = "jwt";
+
+const FORMAT_PREFIX: &str = "ev1";
+const NONCE_SIZE: usize = 12; // 96 bits for AES-256-GCM
+const ROTATION_BATCH_SIZE: i64 = 500;
+
+#[derive(Debug, Error)]
+pub enum TokenEncryptionError {
+    #[error("invalid jwt secret")]
+    InvalidSecret,
+    #[error("`{0}` is not a base64-encoded 32-byte key")]
+    InvalidKey(String),
+    #[error("`{0}` is configured more than once")]
+    DuplicateKey(String),
+    #[error("the active key `{0}` is not configured")]
+    UnknownActiveKey(String),
+    #[error("encrypted with unknown key `{0}`")]
+    UnknownKey(String),
+    #[error("malformed encrypted value")]
+    Malformed,
+    #[error("encryption error")]
+    Encryption,
+}
+
+/// Encrypts and decrypts values with a ring of key-encryption keys.
+pub struct TokenCipher {
+    keys: HashMap<String, [u8; 32]>,
+    active_key_id: String,
+}
+
+fn aes(key: &[u8; 32]) -> Aes256Gcm {
+    Aes256Gcm::new(&Key::<Aes256Gcm>::from(*key))
+}
+
+/// Nonce followed by ciphertext.
+fn seal(key: &[u8; 32], data: &[u8]) -> Result<Vec<u8>, TokenEncryptionError> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = aes(key)
+        .encrypt(&nonce, data)
+        .map_err(|_| TokenEncryptionError::Encryption)?;
+
+    let mut combined = nonce.to_vec();
+    combined.extend_from_slice(&ciphertext);
+    Ok(combined)
+}
+
+fn open(key: &[u8; 32], sealed: &[u8]) -> Result<Vec<u8>, TokenEncryptionError> {
+    if sealed.len() < NONCE_SIZE {
+        return Err(TokenEncryptionError::Malformed);
+    }
+    let nonce_bytes: [u8; NONCE_SIZE] = sealed[..NONCE_SIZE]
+        .try_into()
+        .map_err(|_| TokenEncryptionError::Malformed)?;
+    aes(key)
+        .decrypt(&Nonce::from(nonce_bytes), &sealed[NONCE_SIZE..])
+        .map_err(|_| TokenEncryptionError::Encryption)
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, TokenEncryptionError> {
+    URL_SAFE_NO_PAD
+        .decode(value)
+        .map_err(|_| TokenEncryptionError::Malformed)
+}
+
+impl TokenCipher {
+    pub fn new(
+        jwt_secret: &SecretString,
+        config: Option<&TokenEncryptionConfig>,
+    ) -> Result<Self, TokenEncryptionError> {
+        let secret_bytes = STANDARD
+            .decode(jwt_secret.expose_secret())
+            .map_err(|_| TokenEncryptionError::InvalidSecret)?;
+        let mut keys: HashMap<String, [u8; 32]> = HashMap::from([(
+            JWT_DERIVED_KEY_ID.to_string(),
+            Sha256::digest(&secret_bytes).into(),
+        )]);
+
+        let Some(config) = config else {
+            return Ok(Self {
+                keys,
+                active_key_id: JWT_DERIVED_KEY_ID.to_string(),
+            });
+        };
+        for (id, key) in &config.keys {
+            let key = STANDARD
+                .decode(key.expose_secret())
+                .ok()
+                .and_then(|key| <[u8; 32]>::try_from(key).ok())
+                .ok_or_else(|| TokenEncryptionError::InvalidKey(id.clone()))?;
+            if keys.insert(id.clone(), key).is_some() {
+                return Err(TokenEncryptionError::DuplicateKey(id.clone()));
+            }
+        }
+        if !keys.contains_key(&config.active_key_id) {
+            return Err(TokenEncryptionError::UnknownActiveKey(
+                config.active_key_id.clone(),
+            ));
+        }
+
+        Ok(Self {
+            keys,
+            active_key_id: config.active_key_id.clone(),
+        })
+    }
+
+    pub fn active_key_id(&self) -> &str {
+        &self.active_key_id
+    }
+
+    /// `ev1.<key id>.<wrapped data key>.<ciphertext>`, in URL-safe base64.
+    pub fn encrypt(&self, data: &[u8]) -> Result<String, TokenEncryptionError> {
+        let data_key: [u8; 32] = Aes256Gcm::generate_key(&mut OsRng).into();
+        let wrapped_key = seal(&self.keys[&self.active_key_id], &data_key)?;
+        let ciphertext = seal(&data_key, data)?;
+
+        Ok(format!(
+            "{FORMAT_PREFIX}.{}.{}.{}",
+            self.active_key_id,
+            URL_SAFE_NO_PAD.encode(wrapped_key),
+            URL_SAFE_NO_PAD.encode(ciphertext)
+        ))
+    }
+
+    pub fn decrypt(&self, encrypted: &str) -> Result<Vec<u8>, TokenEncryptionError> {
+        let envelope = encrypted
+            .strip_prefix(FORMAT_PREFIX)
+            .and_then(|rest| rest.strip_prefix('.'));
+        let Some(envelope) = envelope else {
+            // Written before envelope encryption, directly with the JWT-derived key.
+            return open(&self.keys[JWT_DERIVED_KEY_ID], &decode(encrypted)?);
+        };
+
+        let mut parts = envelope.split('.');
+        let (Some(key_id), Some(wrapped_key), Some(ciphertext), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(TokenEncryptionError::Malformed);
+        };
+        let key = self
+            .keys
+            .get(key_id)
+            .ok_or_else(|| TokenEncryptionError::UnknownKey(key_id.to_string()))?;
+        let data_key: [u8; 32] = open(key, &decode(wrapped_key)?)?
+            .try_into()
+            .map_err(|_| TokenEncryptionError::Malformed)?;
+        open(&data_key, &decode(ciphertext)?)
+    }
+
+    /// The prefix of values encrypted with the active key.
+    pub fn current_prefix(&self) -> String {
+        format!("{FORMAT_PREFIX}.{}.", self.active_key_id)
+    }
+
+    /// Re-encrypt a value with the active key.
+    pub fn rotate(&self, encrypted: &str) -> Result<String, TokenEncryptionError> {
+        self.encrypt(&self.decrypt(encrypted)?)
+    }
+}
+
+/// What a rotation changed in one table.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RotationReport {
+    pub rotated: u64,
+    /// Values that couldn't be decrypted, such as ones wrapped with a key
+    /// that is no longer configured.
+    pub failed: u64,
+    /// Values the server replaced while they were being rotated.
+    pub skipped: u64,
+}
+
+/// Re-encrypt every value in `table` not yet encrypted with the active key.
+pub async fn rotate_table(
+    pool: &PgPool,
+    cipher: &TokenCipher,
+    table: ProviderTokenTable,
+) -> Result<RotationReport, ProviderTokenError> {
+    let prefix = cipher.current_prefix();
+    let mut report = RotationReport::default();
+    let mut after = None;
+
+    loop {
+        let rows = ProviderTokenRepository::stale(pool, table, &prefix, after, ROTATION_BATCH_SIZE)
+            .await?;
+        let Some(last) = rows.last() else {
+            break;
+        };
+        after = Some(last.id);
+
+        for row in &rows {
+            let rotated = match cipher.rotate(&row.encrypted_provider_tokens) {
+                Ok(rotated) => rotated,
+                Err(error) => {
+                    warn!(
+                        %error,
+                        table = table.name(),
+                        id = %row.id,
+                        "Failed to decrypt provider token"
+                    );
+                    report.failed += 1;
+                    continue;
+                }
+            };
+            if ProviderTokenRepository::replace(
+                pool,
+                table,
+                row.id,
+                &row.encrypted_provider_tokens,
+                &rotated,
+            )
+            .await?
+            {
+                report.rotated += 1;
+            } else {
+                report.skipped += 1;
+            }
+        }
+    }
+
+    info!(
+        table = table.name(),
+        rotated = report.rotated,
+        failed = report.failed,
+        skipped = report.skipped,
+        key_id = cipher.active_key_id(),
+        "Rotated provider tokens"
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JWT_SECRET: &str = "MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=";
+
+    fn config(keys: &[(&str, u8)], active: &str) -> TokenEncryptionConfig {
+        TokenEncryptionConfig {
+            keys: keys
+                .iter()
+                .map(|(id, byte)| {
+                    let key = STANDARD.encode([*byte; 32]);
+                    (id.to_string(), SecretString::new(key.into()))
+                })
+                .collect(),
+            active_key_id: active.to_string(),
+        }
+    }
+
+    fn secret() -> SecretString {
+        SecretString::new(JWT_SECRET.into())
+    }
+
+    #[test]
+    fn round_trips_through_the_active_key() {
+        let cipher = TokenCipher::new(&secret(), Some(&config(&[("k1", 1)], "k1"))).unwrap();
+        let encrypted = cipher.encrypt(b"gho_token").unwrap();
+        assert!(encrypted.starts_with(&cipher.current_prefix()));
+        assert_eq!(cipher.decrypt(&encrypted).unwrap(), b"gho_token");
+
+        // Each value gets its own data key.
+        assert_ne!(cipher.encrypt(b"gho_token").unwrap(), encrypted);
+    }
+
+    #[test]
+    fn rotates_to_a_new_key() {
+        let old = TokenCipher::new(&secret(), Some(&config(&[("k1", 1)], "k1"))).unwrap();
+        let encrypted = old.encrypt(b"gho_token").unwrap();
+
+        let new =
+            TokenCipher::new(&secret(), Some(&config(&[("k2", 2), ("k1", 1)], "k2"))).unwrap();
+        let rotated = new.rotate(&encrypted).unwrap();
+        assert!(rotated.starts_with("ev1.k2."));
+
+        let without_old = TokenCipher::new(&secret(), Some(&config(&[("k2", 2)], "k2"))).unwrap();
+        assert_eq!(without_old.decrypt(&rotated).unwrap(), b"gho_token");
+        assert!(matches!(
+            without_old.decrypt(&encrypted),
+            Err(TokenEncryptionError::UnknownKey(id)) if id == "k1"
+        ));
+    }
+
+    #[test]
+    fn reads_values_from_before_envelope_encryption() {
+        let key: [u8; 32] = Sha256::digest(STANDARD.decode(JWT_SECRET).unwrap()).into();
+        let legacy = URL_SAFE_NO_PAD.encode(seal(&key, b"gho_token").unwrap());
+
+        let cipher = TokenCipher::new(&secret(), Some(&config(&[("k1", 1)], "k1"))).unwrap();
+        assert_eq!(cipher.decrypt(&legacy).unwrap(), b"gho_token");
+        assert!(cipher.rotate(&legacy).unwrap().starts_with("ev1.k1."));
+
+        let unconfigured = TokenCipher::new(&secret(), None).unwrap();
+        assert_eq!(unconfigured.active_key_id(), JWT_DERIVED_KEY_ID);
+        assert_eq!(unconfigured.decrypt(&legacy).unwrap(), b"gho_token");
+    }
+
+    #[test]
+    fn rejects_invalid_key_rings() {
+        assert!(matches!(
+            TokenCipher::new(&secret(), Some(&config(&[("k1", 1)], "k2"))),
+            Err(TokenEncryptionError::UnknownActiveKey(_))
+        ));
+        assert!(matches!(
+            TokenCipher::new(&secret(), Some(&config(&[("jwt", 1)], "jwt"))),
+            Err(TokenEncryptionError::DuplicateKey(_))
+        ));
+    }
+}
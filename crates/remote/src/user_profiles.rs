@@ -0,0 +1,75 @@
+//! Validation of the settings users manage on their profile.
+
+use std::str::FromStr;
+
+use chrono_tz::Tz;
+use thiserror::Error;
+
+use crate::{azure_blob::AzureBlobService, db::user_profiles::UserProfileRow};
+
+const MAX_DISPLAY_NAME_LENGTH: usize = 100;
+
+#[derive(Debug, Error)]
+pub enum InvalidProfile {
+    #[error("display names are limited to {MAX_DISPLAY_NAME_LENGTH} characters")]
+    DisplayNameTooLong,
+    #[error("unknown time zone `{0}`")]
+    UnknownTimezone(String),
+}
+
+/// Trim a display name; blank clears it.
+pub fn normalize_display_name(name: &str) -> Result<Option<String>, InvalidProfile> {
+    let name = name.trim();
+    if name.chars().count() > MAX_DISPLAY_NAME_LENGTH {
+        return Err(InvalidProfile::DisplayNameTooLong);
+    }
+    Ok(Some(name.to_string()).filter(|name| !name.is_empty()))
+}
+
+/// Resolve an IANA time zone name, ignoring case, to its canonical spelling.
+pub fn normalize_timezone(name: &str) -> Result<String, InvalidProfile> {
+    let name = name.trim();
+    Tz::from_str(name)
+        .or_else(|_| Tz::from_str_insensitive(name))
+        .map(|tz| tz.name().to_string())
+        .map_err(|_| InvalidProfile::UnknownTimezone(name.to_string()))
+}
+
+/// The user's time zone, or UTC when unset or no longer known.
+pub fn timezone_or_utc(name: Option<&str>) -> Tz {
+    name.and_then(|name| Tz::from_str(name).ok())
+        .unwrap_or(Tz::UTC)
+}
+
+/// A signed URL for the uploaded avatar, or else the OAuth avatar.
+pub fn avatar_url(azure: Option<&AzureBlobService>, profile: &UserProfileRow) -> Option<String> {
+    profile
+        .avatar_blob_path
+        .as_deref()
+        .zip(azure)
+        .and_then(|(path, azure)| azure.create_read_url(path).ok())
+        .or_else(|| profile.oauth_avatar_url.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_profile_settings() {
+        assert_eq!(
+            normalize_timezone(" europe/berlin ").unwrap(),
+            "Europe/Berlin"
+        );
+        assert_eq!(normalize_timezone("UTC").unwrap(), "UTC");
+        assert!(normalize_timezone("Mars/Olympus_Mons").is_err());
+        assert_eq!(timezone_or_utc(Some("Nowhere")), Tz::UTC);
+
+        assert_eq!(
+            normalize_display_name("  Ana  ").unwrap().as_deref(),
+            Some("Ana")
+        );
+        assert_eq!(normalize_display_name("   ").unwrap(), None);
+        assert!(normalize_display_name(&"a".repeat(101)).is_err());
+    }
+}
@@ -22,6 +22,9 @@ fn generate_types_content() -> String {
         db::models::tag::Tag::decl(),
         db::models::tag::CreateTag::decl(),
         db::models::tag::UpdateTag::decl(),
+        db::models::audit_log::AuditAction::decl(),
+        db::models::audit_log::AuditLogEntry::decl(),
+        db::models::audit_log::AuditLogQuery::decl(),
         db::models::scratch::DraftFollowUpData::decl(),
         db::models::scratch::DraftWorkspaceData::decl(),
         db::models::scratch::DraftWorkspaceAttachment::decl(),
@@ -43,12 +46,28 @@ fn generate_types_content() -> String {
         db::models::scratch::Scratch::decl(),
         db::models::scratch::CreateScratch::decl(),
         db::models::scratch::UpdateScratch::decl(),
+        server::routes::scratch::DuplicateScratch::decl(),
+        server::routes::scratch::ScratchToTemplate::decl(),
+        server::routes::scratch::AttachScratchToWorkspace::decl(),
+        server::routes::scratch::ScratchExportFormat::decl(),
         db::models::workspace::Workspace::decl(),
         db::models::workspace::WorkspaceWithStatus::decl(),
         db::models::session::Session::decl(),
+        services::services::session_transcript::TranscriptFormat::decl(),
+        db::models::session_share::SessionShare::decl(),
+        services::services::session_shares::CreateSessionShareRequest::decl(),
+        services::services::session_shares::SessionShareLink::decl(),
+        db::models::session_checkpoint::CheckpointRepoState::decl(),
+        db::models::session_checkpoint::SessionCheckpoint::decl(),
+        services::services::session_checkpoints::CreateSessionCheckpointRequest::decl(),
+        services::services::session_checkpoints::RestoreSessionCheckpointRequest::decl(),
+        db::models::workspace_undo::UndoAction::decl(),
+        services::services::workspace_undo::UndoneAction::decl(),
         db::models::execution_process::ExecutionProcess::decl(),
         db::models::execution_process::ExecutionProcessStatus::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
+        db::models::execution_process::DevServerTarget::decl(),
+        db::models::execution_process::TestResults::decl(),
         db::models::execution_process_repo_state::ExecutionProcessRepoState::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
@@ -56,6 +75,11 @@ fn generate_types_content() -> String {
         db::models::merge::MergeStatus::decl(),
         db::models::merge::PullRequestInfo::decl(),
         services::services::approvals::ApprovalInfo::decl(),
+        db::models::approval_rule::ApprovalRuleAction::decl(),
+        db::models::approval_rule::ApprovalRuleApprovers::decl(),
+        db::models::approval_rule::ApprovalRule::decl(),
+        db::models::approval_rule::CreateApprovalRule::decl(),
+        db::models::approval_rule::UpdateApprovalRule::decl(),
         utils::approvals::ApprovalStatus::decl(),
         utils::approvals::QuestionAnswer::decl(),
         utils::approvals::QuestionStatus::decl(),
@@ -63,6 +87,7 @@ fn generate_types_content() -> String {
         utils::approvals::ApprovalResponse::decl(),
         utils::diff::Diff::decl(),
         utils::diff::DiffChangeKind::decl(),
+        utils::diff::DiffOmittedReason::decl(),
         utils::response::ApiResponse::<()>::decl(),
         api_types::LoginStatus::decl(),
         api_types::ProfileResponse::decl(),
@@ -90,8 +115,119 @@ fn generate_types_content() -> String {
         api_types::UpdateMemberRoleRequest::decl(),
         api_types::UpdateMemberRoleResponse::decl(),
         server::routes::repo::RegisterRepoRequest::decl(),
+        server::routes::repo::RepoLogRedaction::decl(),
+        db::models::env_profile::EnvProfile::decl(),
+        db::models::env_profile::CreateEnvProfile::decl(),
+        db::models::env_profile::UpdateEnvProfile::decl(),
+        db::models::workspace_context::WorkspaceContextKind::decl(),
+        db::models::workspace_context::WorkspaceContextItem::decl(),
+        services::services::workspace_context::AddWorkspaceContext::decl(),
+        db::models::repo::ScriptFailurePolicy::decl(),
+        db::models::repo::RepoScriptSettings::decl(),
+        server::routes::repo::RepoVerifyScript::decl(),
+        server::routes::repo::SetRepoGitCredential::decl(),
+        services::services::repo_analyzer::RepoAnalysis::decl(),
+        services::services::repo_analyzer::PackageKind::decl(),
+        services::services::repo_analyzer::DetectedPackage::decl(),
+        db::models::script_library::RepoScriptKind::decl(),
+        db::models::script_library::LibraryScript::decl(),
+        db::models::script_library::CreateLibraryScript::decl(),
+        db::models::script_library::UpdateLibraryScript::decl(),
+        db::models::script_library::RepoScriptRef::decl(),
+        server::routes::script_library::SetRepoScriptRefRequest::decl(),
+        db::models::repo_dev_server::RepoDevServer::decl(),
+        db::models::repo_dev_server::CreateRepoDevServer::decl(),
+        db::models::repo_dev_server::UpdateRepoDevServer::decl(),
+        db::models::repo_knowledge::RepoKnowledgeCategory::decl(),
+        db::models::repo_knowledge::RepoKnowledgeStatus::decl(),
+        db::models::repo_knowledge::RepoKnowledge::decl(),
+        db::models::repo_knowledge::CreateRepoKnowledge::decl(),
+        db::models::repo_knowledge::UpdateRepoKnowledge::decl(),
+        db::models::repo_metadata::RepoCommit::decl(),
+        db::models::repo_metadata::RepoContributor::decl(),
+        db::models::repo_package::RepoPackage::decl(),
+        db::models::repo_package::CreateRepoPackage::decl(),
+        db::models::repo_package::UpdateRepoPackage::decl(),
+        db::models::repo_shared_cache::SharedCacheKind::decl(),
+        db::models::repo_shared_cache::RepoSharedCache::decl(),
+        db::models::repo_shared_cache::CreateRepoSharedCache::decl(),
+        services::services::shared_caches::SharedCacheUsage::decl(),
+        server::routes::containers::WorkspaceDevServer::decl(),
+        server::routes::containers::ExecRequest::decl(),
+        server::routes::containers::ExecResult::decl(),
+        services::services::file_tree::GitFileStatus::decl(),
+        services::services::file_tree::FileTreeEntry::decl(),
+        services::services::file_tree::FileTreeListing::decl(),
+        db::models::schedule::Schedule::decl(),
+        db::models::schedule::CreateSchedule::decl(),
+        db::models::schedule::UpdateSchedule::decl(),
+        db::models::schedule::ScheduleRunStatus::decl(),
+        db::models::schedule::ScheduleRun::decl(),
+        services::services::dependency_updates::DependencyEcosystem::decl(),
+        services::services::dependency_updates::CreateDependencyUpdateSchedule::decl(),
+        db::models::webhook_trigger::WebhookTemplate::decl(),
+        db::models::webhook_trigger::WebhookTrigger::decl(),
+        db::models::webhook_trigger::CreateWebhookTrigger::decl(),
+        db::models::webhook_trigger::UpdateWebhookTrigger::decl(),
+        db::models::webhook_trigger::WebhookTriggerRunStatus::decl(),
+        db::models::webhook_trigger::WebhookTriggerRun::decl(),
+        services::services::webhook_triggers::WebhookTriggerToken::decl(),
+        db::models::ci_triage::CiTriageSettings::decl(),
+        db::models::ci_triage::UpsertCiTriageSettings::decl(),
+        db::models::ci_triage::CiTriageRunStatus::decl(),
+        db::models::ci_triage::CiTriageRun::decl(),
+        services::services::ci_triage::SavedCiTriageSettings::decl(),
+        db::models::flaky_test::TestOutcome::decl(),
+        db::models::flaky_test::FlakyTest::decl(),
+        db::models::flaky_test::FlakyTestSettings::decl(),
+        db::models::flaky_test::UpsertFlakyTestSettings::decl(),
+        services::services::flaky_tests::SavedFlakyTestSettings::decl(),
+        services::services::flaky_tests::TestReport::decl(),
+        services::services::flaky_tests::TestCaseResult::decl(),
+        services::services::flaky_tests::TestReportSummary::decl(),
+        server::routes::flaky_tests::UpdateFlakyTest::decl(),
+        server::routes::flaky_tests::FixFlakyTestRequest::decl(),
+        services::services::code_owners::CodeOwnersRule::decl(),
+        services::services::code_owners::CodeOwnersFile::decl(),
+        services::services::code_owners::PathOwners::decl(),
+        services::services::code_owners::OwnerSuggestion::decl(),
+        services::services::code_owners::CodeOwnersLookup::decl(),
+        services::services::code_owners::CodeOwnersMatch::decl(),
+        db::models::pipeline::PipelineStage::decl(),
+        db::models::pipeline::Pipeline::decl(),
+        db::models::pipeline::CreatePipeline::decl(),
+        db::models::pipeline::UpdatePipeline::decl(),
+        db::models::pipeline::PipelineStatus::decl(),
+        db::models::pipeline::PipelineRun::decl(),
+        db::models::pipeline::PipelineRunStage::decl(),
+        server::routes::pipelines::StartPipelineRunRequest::decl(),
+        server::routes::pipelines::PipelineRunWithStages::decl(),
+        db::models::attempt_group::AttemptGroup::decl(),
+        db::models::attempt_group::AttemptGroupWorkspace::decl(),
+        server::routes::attempt_groups::CreateAttemptGroupRequest::decl(),
+        server::routes::attempt_groups::AttemptGroupWithWorkspaces::decl(),
+        server::routes::attempt_groups::AttemptChecks::decl(),
+        server::routes::attempt_groups::AttemptComparison::decl(),
+        server::routes::terminal::CreateTerminalRequest::decl(),
+        server::routes::terminal::UpdateTerminalRequest::decl(),
+        server::routes::terminal::TerminalInfo::decl(),
+        server::routes::terminal::TerminalRecording::decl(),
+        services::services::backup::BackupInfo::decl(),
+        server::routes::migration::RestoreBackupRequest::decl(),
+        services::services::maintenance::MaintenanceStatus::decl(),
+        server::routes::maintenance::SetMaintenanceRequest::decl(),
+        db::models::local_user::RemoteOrgRole::decl(),
+        db::models::local_user::LocalUser::decl(),
+        services::services::users::LocalUserToken::decl(),
+        services::services::users::CreateLocalUserRequest::decl(),
+        services::services::users::UpdateLocalUserRequest::decl(),
+        server::routes::users::UserSignInRequest::decl(),
+        server::routes::workspaces::core::WorkspaceOwnerFilter::decl(),
         server::routes::repo::InitRepoRequest::decl(),
         server::routes::tags::TagSearchParams::decl(),
+        server::routes::execution_processes::RawLogPageQuery::decl(),
+        server::routes::execution_processes::RawLogLine::decl(),
+        server::routes::execution_processes::RawLogPage::decl(),
         server::routes::oauth::TokenResponse::decl(),
         server::routes::config::UserSystemInfo::decl(),
         server::routes::config::Environment::decl(),
@@ -99,6 +235,19 @@ fn generate_types_content() -> String {
         server::routes::config::UpdateMcpServersBody::decl(),
         server::routes::config::GetMcpServerResponse::decl(),
         server::routes::config::CheckEditorAvailabilityQuery::decl(),
+        db::models::secret::SecretSummary::decl(),
+        server::routes::config::SetSecretRequest::decl(),
+        db::models::git_credential::GitCredentialKind::decl(),
+        db::models::git_credential::GitCredentialSummary::decl(),
+        db::models::git_credential::CreateGitCredential::decl(),
+        db::models::config_override::ConfigOverrides::decl(),
+        services::services::config_overrides::ConfigSource::decl(),
+        services::services::config_overrides::EffectiveConfigSources::decl(),
+        services::services::config_overrides::EffectiveConfig::decl(),
+        services::services::config_bundle::RepoConfigExport::decl(),
+        services::services::config_bundle::TemplateExport::decl(),
+        services::services::config_bundle::ConfigBundle::decl(),
+        services::services::config_bundle::ConfigImportSummary::decl(),
         server::routes::config::CheckEditorAvailabilityResponse::decl(),
         server::routes::config::CheckAgentAvailabilityQuery::decl(),
         server::routes::config::AgentPresetOptionsQuery::decl(),
@@ -114,12 +263,15 @@ fn generate_types_content() -> String {
         relay_types::RefreshRelaySigningSessionResponse::decl(),
         server::routes::sessions::CreateFollowUpAttempt::decl(),
         server::routes::sessions::ResetProcessRequest::decl(),
+        server::routes::sessions::fork::ForkSessionRequest::decl(),
         server::routes::workspaces::git::ChangeTargetBranchRequest::decl(),
         server::routes::workspaces::git::ChangeTargetBranchResponse::decl(),
         server::routes::workspaces::repos::AddWorkspaceRepoRequest::decl(),
+        server::routes::workspaces::env_profiles::SetWorkspaceEnvProfilesRequest::decl(),
         server::routes::workspaces::repos::AddWorkspaceRepoResponse::decl(),
         server::routes::workspaces::git::MergeWorkspaceRequest::decl(),
         server::routes::workspaces::git::PushWorkspaceRequest::decl(),
+        server::routes::workspaces::git::PushWithLeaseRequest::decl(),
         server::routes::workspaces::git::RenameBranchRequest::decl(),
         server::routes::workspaces::git::RenameBranchResponse::decl(),
         server::routes::sessions::review::StartReviewRequest::decl(),
@@ -147,6 +299,22 @@ fn generate_types_content() -> String {
         server::routes::workspaces::git::AbortConflictsRequest::decl(),
         server::routes::workspaces::git::GitOperationError::decl(),
         server::routes::workspaces::git::PushError::decl(),
+        server::routes::workspaces::git::PushPreflightQuery::decl(),
+        server::routes::workspaces::git::WorktreeSnapshotsQuery::decl(),
+        server::routes::workspaces::git::DiffFileQuery::decl(),
+        server::routes::workspaces::git::RestoreWorktreeSnapshotRequest::decl(),
+        services::services::push_preflight::PreflightFinding::decl(),
+        services::services::push_preflight::PushPreflight::decl(),
+        services::services::merge_readiness::MergeReadinessState::decl(),
+        services::services::merge_readiness::RepoMergeReadiness::decl(),
+        services::services::merge_readiness::WorkspaceMergeReadiness::decl(),
+        services::services::startup_tasks::StartupTask::decl(),
+        services::services::startup_tasks::StartupTaskState::decl(),
+        services::services::startup_tasks::StartupTaskProgress::decl(),
+        services::services::startup_tasks::StartupStatus::decl(),
+        git::RepoLockStats::decl(),
+        db::write_queue::QueuedWriterStats::decl(),
+        db::write_queue::WriteQueueStats::decl(),
         server::routes::workspaces::pr::PrError::decl(),
         server::routes::workspaces::execution::RunScriptError::decl(),
         server::routes::workspaces::attachments::AssociateWorkspaceAttachmentsRequest::decl(),
@@ -163,6 +331,8 @@ fn generate_types_content() -> String {
         git_host::ProviderKind::decl(),
         git_host::PullRequestDetail::decl(),
         git::GitRemote::decl(),
+        git::LeasedPush::decl(),
+        git::WorktreeSnapshot::decl(),
         server::routes::repo::ListPrsError::decl(),
         server::routes::remote::pull_requests::LinkPrToIssueRequest::decl(),
         server::routes::workspaces::pr::CreateWorkspaceFromPrBody::decl(),
@@ -177,7 +347,19 @@ fn generate_types_content() -> String {
         server::routes::workspaces::workspace_summary::DiffStats::decl(),
         services::services::filesystem::DirectoryEntry::decl(),
         services::services::filesystem::DirectoryListResponse::decl(),
+        services::services::workspace_files::WorkspaceFile::decl(),
+        services::services::workspace_files::WriteWorkspaceFile::decl(),
+        services::services::workspace_files::FileEdit::decl(),
+        services::services::workspace_files::PatchWorkspaceFile::decl(),
+        services::services::filesystem_watcher::FileChangeKind::decl(),
+        services::services::filesystem_watcher::FileChange::decl(),
         services::services::file_search::SearchMode::decl(),
+        services::services::log_search::LogSearchMatch::decl(),
+        services::services::log_search::LogSearchResult::decl(),
+        services::services::semantic_search::SemanticSearchResult::decl(),
+        services::services::code_search::CodeSearchRange::decl(),
+        services::services::code_search::CodeSearchMatch::decl(),
+        services::services::code_search::CodeSearchResponse::decl(),
         services::services::config::Config::decl(),
         services::services::config::NotificationConfig::decl(),
         services::services::config::ThemeMode::decl(),
@@ -189,6 +371,22 @@ fn generate_types_content() -> String {
         services::services::config::UiLanguage::decl(),
         services::services::config::ShowcaseState::decl(),
         services::services::config::SendMessageShortcut::decl(),
+        services::services::config::LogRedactionConfig::decl(),
+        services::services::config::RemoteApprovalConfig::decl(),
+        services::services::config::EmbeddingProvider::decl(),
+        services::services::config::SemanticSearchConfig::decl(),
+        services::services::config::NotificationChannel::decl(),
+        services::services::config::NotificationEvent::decl(),
+        services::services::config::NotificationChannelsConfig::decl(),
+        services::services::config::QuietHoursConfig::decl(),
+        services::services::config::SessionSharingConfig::decl(),
+        services::services::config::WarmPoolConfig::decl(),
+        services::services::config::LogFsyncPolicy::decl(),
+        services::services::config::LogWritePolicy::decl(),
+        services::services::config::LogWritesConfig::decl(),
+        services::services::config::DiffConfig::decl(),
+        services::services::config::DatabaseConfig::decl(),
+        services::services::config::UsersConfig::decl(),
         git::GitBranch::decl(),
         services::services::queued_message::QueuedMessage::decl(),
         services::services::queued_message::QueueStatus::decl(),
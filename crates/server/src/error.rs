@@ -5,11 +5,19 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use db::models::{
-    execution_process::ExecutionProcessError, repo::RepoError, scratch::ScratchError,
-    session::SessionError, workspace::WorkspaceError,
+    approval_rule::ApprovalRuleError, attempt_group::AttemptGroupError,
+    ci_triage::CiTriageError as CiTriageModelError,
+    env_profile::EnvProfileError as EnvProfileModelError, execution_process::ExecutionProcessError,
+    flaky_test::FlakyTestError as FlakyTestModelError,
+    pipeline::PipelineError as PipelineModelError, repo::RepoError,
+    repo_dev_server::RepoDevServerError, repo_knowledge::RepoKnowledgeError,
+    repo_package::RepoPackageError, repo_shared_cache::RepoSharedCacheError,
+    schedule::ScheduleError as ScheduleModelError, scratch::ScratchError,
+    script_library::ScriptLibraryError, session::SessionError,
+    webhook_trigger::WebhookTriggerError as WebhookTriggerModelError, workspace::WorkspaceError,
 };
 use deployment::{DeploymentError, RelayHostsNotConfigured, RemoteClientNotConfigured};
-use executors::{command::CommandBuildError, executors::ExecutorError};
+use executors::{command::CommandBuildError, executors::ExecutorError, profile::ProfileError};
 use git::GitServiceError;
 use git_host::GitHostError;
 use local_deployment::pty::PtyError;
@@ -18,11 +26,33 @@ use relay_hosts::{
 };
 use relay_webrtc::WebRtcError;
 use services::services::{
+    backup::BackupError,
+    ci_triage::CiTriageError,
+    code_search::CodeSearchError,
     config::{ConfigError, EditorOpenError},
+    config_bundle::ConfigBundleError,
+    config_overrides::ConfigOverrideError,
     container::ContainerError,
+    env_profiles::EnvProfileError,
     file::FileError,
+    flaky_tests::FlakyTestError,
+    git_credentials::GitCredentialError,
+    pipelines::PipelineError,
     remote_client::RemoteClientError,
     repo::RepoError as RepoServiceError,
+    repo_index::RepoIndexError,
+    schedules::ScheduleError,
+    secrets::SecretError,
+    semantic_search::SemanticSearchError,
+    session_checkpoints::SessionCheckpointError,
+    session_fork::SessionForkError,
+    session_shares::SessionShareError,
+    users::UserError,
+    webhook_triggers::WebhookTriggerError,
+    workspace_context::WorkspaceContextError,
+    workspace_export::WorkspaceExportError,
+    workspace_files::WorkspaceFileError,
+    workspace_undo::WorkspaceUndoError,
 };
 use thiserror::Error;
 use trusted_key_auth::error::TrustedKeyAuthError;
@@ -66,6 +96,56 @@ pub enum ApiError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
     #[error(transparent)]
+    Secret(#[from] SecretError),
+    #[error(transparent)]
+    GitCredential(#[from] GitCredentialError),
+    #[error(transparent)]
+    EnvProfile(#[from] EnvProfileError),
+    #[error(transparent)]
+    ScriptLibrary(#[from] ScriptLibraryError),
+    #[error(transparent)]
+    RepoDevServer(#[from] RepoDevServerError),
+    #[error(transparent)]
+    RepoKnowledge(#[from] RepoKnowledgeError),
+    #[error(transparent)]
+    RepoPackage(#[from] RepoPackageError),
+    #[error(transparent)]
+    RepoSharedCache(#[from] RepoSharedCacheError),
+    #[error(transparent)]
+    Schedule(#[from] ScheduleError),
+    #[error(transparent)]
+    WebhookTrigger(#[from] WebhookTriggerError),
+    #[error(transparent)]
+    CiTriage(#[from] CiTriageError),
+    #[error(transparent)]
+    FlakyTest(#[from] FlakyTestError),
+    #[error(transparent)]
+    Pipeline(#[from] PipelineError),
+    #[error(transparent)]
+    AttemptGroup(#[from] AttemptGroupError),
+    #[error(transparent)]
+    ApprovalRule(#[from] ApprovalRuleError),
+    #[error(transparent)]
+    WorkspaceFile(#[from] WorkspaceFileError),
+    #[error(transparent)]
+    SessionCheckpoint(#[from] SessionCheckpointError),
+    #[error(transparent)]
+    WorkspaceUndo(#[from] WorkspaceUndoError),
+    #[error(transparent)]
+    SessionShare(#[from] SessionShareError),
+    #[error(transparent)]
+    User(#[from] UserError),
+    #[error(transparent)]
+    SemanticSearch(#[from] SemanticSearchError),
+    #[error(transparent)]
+    CodeSearch(#[from] CodeSearchError),
+    #[error(transparent)]
+    WorkspaceContext(#[from] WorkspaceContextError),
+    #[error(transparent)]
+    ConfigOverride(#[from] ConfigOverrideError),
+    #[error(transparent)]
+    ConfigBundle(#[from] ConfigBundleError),
+    #[error(transparent)]
     EditorOpen(#[from] EditorOpenError),
     #[error(transparent)]
     RemoteClient(#[from] RemoteClientError),
@@ -134,6 +214,9 @@ impl From<WorkspaceManagerError> for ApiError {
             WorkspaceManagerError::NoRepositories => {
                 ApiError::BadRequest("Workspace has no repositories configured".to_string())
             }
+            err @ WorkspaceManagerError::PackageNotFound { .. } => {
+                ApiError::BadRequest(err.to_string())
+            }
             WorkspaceManagerError::PartialCreation(msg) => ApiError::Conflict(msg),
         }
     }
@@ -157,6 +240,11 @@ impl From<ContainerError> for ApiError {
             ContainerError::ExecutionProcess(e) => ApiError::ExecutionProcess(e),
             ContainerError::ExecutorError(e) => ApiError::Executor(e),
             ContainerError::Worktree(e) => e.into(),
+            ContainerError::Secret(e) => ApiError::Secret(e),
+            ContainerError::EnvProfile(e) => ApiError::EnvProfile(e),
+            ContainerError::Maintenance(reason) => {
+                ApiError::ServiceUnavailable(format!("Maintenance mode: {reason}"))
+            }
             other => ApiError::Container(other),
         }
     }
@@ -395,6 +483,22 @@ impl IntoResponse for ApiError {
                     msg
                 ),
             ),
+            ApiError::GitService(GitServiceError::SnapshotNotFound(id)) => {
+                ErrorInfo::not_found("GitServiceError", format!("Snapshot '{}' not found.", id))
+            }
+            ApiError::GitService(GitServiceError::SnapshotDoesNotApply(_)) => ErrorInfo::conflict(
+                "GitServiceError",
+                "The snapshot doesn't apply to the workspace's current files. Check out the commit it was taken on, then retry.",
+            ),
+            ApiError::GitService(GitServiceError::RemoteBranchMoved { behind, .. }) => {
+                ErrorInfo::conflict(
+                    "GitServiceError",
+                    format!(
+                        "The remote branch has {} commit(s) that were not pushed from here. Pull them before pushing again.",
+                        behind
+                    ),
+                )
+            }
             ApiError::GitService(GitServiceError::WorktreeDirty(branch, files)) => {
                 ErrorInfo::conflict(
                     "GitServiceError",
@@ -414,6 +518,13 @@ impl IntoResponse for ApiError {
                     ),
                 )
             }
+            ApiError::GitService(GitServiceError::GitCLI(git::GitCliError::ProtectedBranch(
+                msg,
+            ))) => ErrorInfo::with_status(
+                StatusCode::FORBIDDEN,
+                "GitServiceError",
+                format!("The remote's branch protection declined the push: {}", msg),
+            ),
             ApiError::GitService(e) => ErrorInfo::with_status(
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "GitServiceError",
@@ -454,6 +565,9 @@ impl IntoResponse for ApiError {
             ApiError::Pty(PtyError::SessionClosed) => {
                 ErrorInfo::with_status(StatusCode::GONE, "PtyError", "PTY session closed.")
             }
+            ApiError::Pty(PtyError::RecordingNotFound(_)) => {
+                ErrorInfo::not_found("PtyError", "Terminal recording not found.")
+            }
             ApiError::Pty(_) => ErrorInfo::internal("PtyError"),
 
             ApiError::Unauthorized => ErrorInfo::with_status(
@@ -501,6 +615,320 @@ impl IntoResponse for ApiError {
             ),
             ApiError::Config(_) => ErrorInfo::internal("ConfigError"),
             ApiError::Io(_) => ErrorInfo::internal("IoError"),
+            ApiError::Secret(SecretError::NotFound(_)) => {
+                ErrorInfo::not_found("SecretError", format!("{}", self))
+            }
+            ApiError::Secret(
+                SecretError::Database(_) | SecretError::Io(_) | SecretError::Encrypt,
+            ) => ErrorInfo::internal("SecretError"),
+            ApiError::Secret(_) => ErrorInfo::bad_request("SecretError", format!("{}", self)),
+            ApiError::GitCredential(err) => match err {
+                GitCredentialError::Database(_)
+                | GitCredentialError::Secret(
+                    SecretError::Database(_) | SecretError::Io(_) | SecretError::Encrypt,
+                ) => ErrorInfo::internal("GitCredentialError"),
+                GitCredentialError::NotFound => {
+                    ErrorInfo::not_found("GitCredentialError", "Git credential not found.")
+                }
+                GitCredentialError::DuplicateName => {
+                    ErrorInfo::conflict("GitCredentialError", err.to_string())
+                }
+                GitCredentialError::Secret(_) | GitCredentialError::Invalid(_) => {
+                    ErrorInfo::bad_request("GitCredentialError", err.to_string())
+                }
+            },
+            ApiError::EnvProfile(
+                EnvProfileError::Database(_)
+                | EnvProfileError::Profile(
+                    EnvProfileModelError::Database(_) | EnvProfileModelError::Json(_),
+                ),
+            ) => ErrorInfo::internal("EnvProfileError"),
+            ApiError::EnvProfile(EnvProfileError::Profile(EnvProfileModelError::NotFound)) => {
+                ErrorInfo::not_found("EnvProfileError", "Environment profile not found.")
+            }
+            ApiError::EnvProfile(EnvProfileError::Profile(EnvProfileModelError::DuplicateName)) => {
+                ErrorInfo::conflict("EnvProfileError", format!("{}", self))
+            }
+            ApiError::EnvProfile(_) => {
+                ErrorInfo::bad_request("EnvProfileError", format!("{}", self))
+            }
+            ApiError::ScriptLibrary(err) => match err {
+                ScriptLibraryError::Database(_) => ErrorInfo::internal("ScriptLibraryError"),
+                ScriptLibraryError::NotFound => {
+                    ErrorInfo::not_found("ScriptLibraryError", "Library script not found.")
+                }
+                ScriptLibraryError::DuplicateName | ScriptLibraryError::InUse(_) => {
+                    ErrorInfo::conflict("ScriptLibraryError", err.to_string())
+                }
+            },
+            ApiError::RepoDevServer(err) => match err {
+                RepoDevServerError::Database(_) => ErrorInfo::internal("RepoDevServerError"),
+                RepoDevServerError::NotFound => {
+                    ErrorInfo::not_found("RepoDevServerError", "Dev server not found.")
+                }
+                RepoDevServerError::DuplicateName => {
+                    ErrorInfo::conflict("RepoDevServerError", err.to_string())
+                }
+                RepoDevServerError::Invalid => {
+                    ErrorInfo::bad_request("RepoDevServerError", err.to_string())
+                }
+            },
+            ApiError::RepoKnowledge(err) => match err {
+                RepoKnowledgeError::Database(_) => ErrorInfo::internal("RepoKnowledgeError"),
+                RepoKnowledgeError::NotFound => {
+                    ErrorInfo::not_found("RepoKnowledgeError", "Knowledge entry not found.")
+                }
+                RepoKnowledgeError::Invalid => {
+                    ErrorInfo::bad_request("RepoKnowledgeError", err.to_string())
+                }
+            },
+            ApiError::RepoPackage(err) => match err {
+                RepoPackageError::Database(_) => ErrorInfo::internal("RepoPackageError"),
+                RepoPackageError::NotFound => {
+                    ErrorInfo::not_found("RepoPackageError", "Package not found.")
+                }
+                RepoPackageError::DuplicatePath => {
+                    ErrorInfo::conflict("RepoPackageError", err.to_string())
+                }
+                RepoPackageError::Invalid => {
+                    ErrorInfo::bad_request("RepoPackageError", err.to_string())
+                }
+            },
+            ApiError::RepoSharedCache(err) => match err {
+                RepoSharedCacheError::Database(_) => ErrorInfo::internal("RepoSharedCacheError"),
+                RepoSharedCacheError::NotFound => {
+                    ErrorInfo::not_found("RepoSharedCacheError", "Shared cache not found.")
+                }
+                RepoSharedCacheError::Duplicate => {
+                    ErrorInfo::conflict("RepoSharedCacheError", err.to_string())
+                }
+                RepoSharedCacheError::Invalid(_) => {
+                    ErrorInfo::bad_request("RepoSharedCacheError", err.to_string())
+                }
+            },
+            ApiError::Schedule(err) => match err {
+                ScheduleError::Database(_)
+                | ScheduleError::Repo(RepoError::Database(_))
+                | ScheduleError::Schedule(
+                    ScheduleModelError::Database(_) | ScheduleModelError::Json(_),
+                ) => ErrorInfo::internal("ScheduleError"),
+                ScheduleError::Schedule(ScheduleModelError::NotFound) => {
+                    ErrorInfo::not_found("ScheduleError", "Schedule not found.")
+                }
+                ScheduleError::Repo(RepoError::NotFound) => {
+                    ErrorInfo::not_found("ScheduleError", "Repository not found.")
+                }
+                _ => ErrorInfo::bad_request("ScheduleError", err.to_string()),
+            },
+            ApiError::WebhookTrigger(err) => match err {
+                WebhookTriggerError::Database(_)
+                | WebhookTriggerError::Trigger(
+                    WebhookTriggerModelError::Database(_) | WebhookTriggerModelError::Json(_),
+                ) => ErrorInfo::internal("WebhookTriggerError"),
+                WebhookTriggerError::Trigger(WebhookTriggerModelError::NotFound)
+                | WebhookTriggerError::InvalidToken => {
+                    ErrorInfo::not_found("WebhookTriggerError", err.to_string())
+                }
+                WebhookTriggerError::Paused => {
+                    ErrorInfo::conflict("WebhookTriggerError", err.to_string())
+                }
+                WebhookTriggerError::RepoNotAllowed(_) => ErrorInfo::with_status(
+                    StatusCode::FORBIDDEN,
+                    "WebhookTriggerError",
+                    err.to_string(),
+                ),
+                _ => ErrorInfo::bad_request("WebhookTriggerError", err.to_string()),
+            },
+            ApiError::CiTriage(err) => match err {
+                CiTriageError::Settings(CiTriageModelError::NotFound) => {
+                    ErrorInfo::not_found("CiTriageError", err.to_string())
+                }
+                CiTriageError::InvalidSignature => ErrorInfo::with_status(
+                    StatusCode::UNAUTHORIZED,
+                    "CiTriageError",
+                    err.to_string(),
+                ),
+                CiTriageError::InvalidPayload(_) | CiTriageError::NotGitHub => {
+                    ErrorInfo::bad_request("CiTriageError", err.to_string())
+                }
+                _ => ErrorInfo::internal("CiTriageError"),
+            },
+            ApiError::FlakyTest(err) => match err {
+                FlakyTestError::Database(_)
+                | FlakyTestError::Test(
+                    FlakyTestModelError::Database(_) | FlakyTestModelError::Json(_),
+                ) => ErrorInfo::internal("FlakyTestError"),
+                FlakyTestError::Test(
+                    FlakyTestModelError::NotFound | FlakyTestModelError::SettingsNotFound,
+                )
+                | FlakyTestError::InvalidToken => {
+                    ErrorInfo::not_found("FlakyTestError", err.to_string())
+                }
+                FlakyTestError::NothingToFix => {
+                    ErrorInfo::conflict("FlakyTestError", err.to_string())
+                }
+                FlakyTestError::InvalidReport(_) | FlakyTestError::MissingTargetBranch => {
+                    ErrorInfo::bad_request("FlakyTestError", err.to_string())
+                }
+            },
+            ApiError::Pipeline(err) => match err {
+                PipelineError::Database(_)
+                | PipelineError::Pipeline(
+                    PipelineModelError::Database(_) | PipelineModelError::Json(_),
+                ) => ErrorInfo::internal("PipelineError"),
+                PipelineError::Pipeline(PipelineModelError::NotFound) => {
+                    ErrorInfo::not_found("PipelineError", "Pipeline not found.")
+                }
+                PipelineError::Pipeline(PipelineModelError::RunNotFound) => {
+                    ErrorInfo::not_found("PipelineError", "Pipeline run not found.")
+                }
+                PipelineError::Pipeline(PipelineModelError::DuplicateName) => {
+                    ErrorInfo::conflict("PipelineError", err.to_string())
+                }
+                _ => ErrorInfo::bad_request("PipelineError", err.to_string()),
+            },
+            ApiError::AttemptGroup(err) => match err {
+                AttemptGroupError::Database(_) | AttemptGroupError::Json(_) => {
+                    ErrorInfo::internal("AttemptGroupError")
+                }
+                AttemptGroupError::NotFound => {
+                    ErrorInfo::not_found("AttemptGroupError", "Attempt group not found.")
+                }
+            },
+            ApiError::ApprovalRule(err) => match err {
+                ApprovalRuleError::Database(_) => ErrorInfo::internal("ApprovalRuleError"),
+                ApprovalRuleError::NotFound => {
+                    ErrorInfo::not_found("ApprovalRuleError", "Approval rule not found.")
+                }
+                ApprovalRuleError::Invalid => {
+                    ErrorInfo::bad_request("ApprovalRuleError", err.to_string())
+                }
+            },
+            ApiError::SessionCheckpoint(err) => match err {
+                SessionCheckpointError::Database(_) | SessionCheckpointError::Container(_) => {
+                    ErrorInfo::internal("SessionCheckpointError")
+                }
+                SessionCheckpointError::NotFound | SessionCheckpointError::ProcessNotFound => {
+                    ErrorInfo::not_found("SessionCheckpointError", err.to_string())
+                }
+                SessionCheckpointError::InvalidLabel
+                | SessionCheckpointError::ProcessNotFinished => {
+                    ErrorInfo::bad_request("SessionCheckpointError", err.to_string())
+                }
+                SessionCheckpointError::Discarded | SessionCheckpointError::UncommittedChanges => {
+                    ErrorInfo::conflict("SessionCheckpointError", err.to_string())
+                }
+            },
+            ApiError::WorkspaceUndo(err) => match err {
+                WorkspaceUndoError::NothingToUndo => {
+                    ErrorInfo::not_found("WorkspaceUndoError", err.to_string())
+                }
+                WorkspaceUndoError::ProcessesRunning
+                | WorkspaceUndoError::Git(GitServiceError::SnapshotDoesNotApply(_)) => {
+                    ErrorInfo::conflict("WorkspaceUndoError", err.to_string())
+                }
+                WorkspaceUndoError::Database(_)
+                | WorkspaceUndoError::Git(_)
+                | WorkspaceUndoError::Container(_) => ErrorInfo::internal("WorkspaceUndoError"),
+            },
+            ApiError::SessionShare(err) => match err {
+                SessionShareError::Database(_) | SessionShareError::Io(_) => {
+                    ErrorInfo::internal("SessionShareError")
+                }
+                SessionShareError::NotFound => {
+                    ErrorInfo::not_found("SessionShareError", "Share not found.")
+                }
+                SessionShareError::Disabled | SessionShareError::InvalidExpiry => {
+                    ErrorInfo::bad_request("SessionShareError", err.to_string())
+                }
+            },
+            ApiError::User(err) => match err {
+                UserError::Database(_) => ErrorInfo::internal("UserError"),
+                UserError::NotFound => ErrorInfo::not_found("UserError", err.to_string()),
+                UserError::InvalidName | UserError::InvalidEmail => {
+                    ErrorInfo::bad_request("UserError", err.to_string())
+                }
+                UserError::NameTaken(_) => ErrorInfo::conflict("UserError", err.to_string()),
+                UserError::Forbidden(_) | UserError::NotOrganizationMember => {
+                    ErrorInfo::with_status(StatusCode::FORBIDDEN, "UserError", err.to_string())
+                }
+                UserError::Remote(err) => remote_client_error(err),
+                UserError::RemoteSignInDisabled => {
+                    ErrorInfo::bad_request("UserError", err.to_string())
+                }
+            },
+            ApiError::WorkspaceFile(err) => match err {
+                WorkspaceFileError::Io(_) => ErrorInfo::internal("WorkspaceFileError"),
+                WorkspaceFileError::NotFound(_) => {
+                    ErrorInfo::not_found("WorkspaceFileError", err.to_string())
+                }
+                WorkspaceFileError::Modified => {
+                    ErrorInfo::conflict("WorkspaceFileError", err.to_string())
+                }
+                WorkspaceFileError::TooLarge(_, _) => ErrorInfo::with_status(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    "WorkspaceFileError",
+                    err.to_string(),
+                ),
+                WorkspaceFileError::InvalidPath(_)
+                | WorkspaceFileError::NotText
+                | WorkspaceFileError::EditNotFound(_)
+                | WorkspaceFileError::EditAmbiguous(_, _) => {
+                    ErrorInfo::bad_request("WorkspaceFileError", err.to_string())
+                }
+            },
+            ApiError::SemanticSearch(err) => match err {
+                SemanticSearchError::NotConfigured(_) => {
+                    ErrorInfo::bad_request("SemanticSearchError", err.to_string())
+                }
+                SemanticSearchError::Request(_) | SemanticSearchError::InvalidResponse(_) => {
+                    ErrorInfo::with_status(
+                        StatusCode::BAD_GATEWAY,
+                        "SemanticSearchError",
+                        err.to_string(),
+                    )
+                }
+                SemanticSearchError::Io(_) | SemanticSearchError::Join(_) => {
+                    ErrorInfo::internal("SemanticSearchError")
+                }
+            },
+            ApiError::CodeSearch(err) => match err {
+                CodeSearchError::InvalidPattern(_) | CodeSearchError::InvalidGlob(_) => {
+                    ErrorInfo::bad_request("CodeSearchError", err.to_string())
+                }
+                CodeSearchError::Join(_) => ErrorInfo::internal("CodeSearchError"),
+            },
+            ApiError::WorkspaceContext(err) => match err {
+                WorkspaceContextError::NotFound | WorkspaceContextError::AttachmentNotFound => {
+                    ErrorInfo::not_found("WorkspaceContextError", err.to_string())
+                }
+                WorkspaceContextError::InvalidUrl(_) | WorkspaceContextError::MissingSource => {
+                    ErrorInfo::bad_request("WorkspaceContextError", err.to_string())
+                }
+                WorkspaceContextError::NameTaken(_) => {
+                    ErrorInfo::conflict("WorkspaceContextError", err.to_string())
+                }
+                WorkspaceContextError::Database(_) => ErrorInfo::internal("WorkspaceContextError"),
+            },
+            ApiError::ConfigOverride(err) => match err {
+                ConfigOverrideError::InvalidBranchPrefix | ConfigOverrideError::RepoScripts => {
+                    ErrorInfo::bad_request("ConfigOverrideError", err.to_string())
+                }
+                ConfigOverrideError::Database(_) => ErrorInfo::internal("ConfigOverrideError"),
+            },
+            ApiError::ConfigBundle(err) => match err {
+                ConfigBundleError::UnsupportedVersion(_)
+                | ConfigBundleError::Profiles(ProfileError::Validation(_))
+                | ConfigBundleError::Overrides(ConfigOverrideError::InvalidBranchPrefix)
+                | ConfigBundleError::Overrides(ConfigOverrideError::RepoScripts) => {
+                    ErrorInfo::bad_request("ConfigBundleError", err.to_string())
+                }
+                ConfigBundleError::Database(_)
+                | ConfigBundleError::Repo(_)
+                | ConfigBundleError::Overrides(ConfigOverrideError::Database(_))
+                | ConfigBundleError::Profiles(_)
+                | ConfigBundleError::Json(_) => ErrorInfo::internal("ConfigBundleError"),
+            },
             ApiError::WebRtc(err) => match err {
                 WebRtcError::SessionNotFound { .. } => {
                     ErrorInfo::not_found("WebRtcError", err.to_string())
@@ -625,3 +1053,48 @@ impl From<RelayPairingClientError> for ApiError {
         }
     }
 }
+
+impl From<WorkspaceExportError> for ApiError {
+    fn from(err: WorkspaceExportError) -> Self {
+        match err {
+            WorkspaceExportError::Io(e) => ApiError::Io(e),
+            WorkspaceExportError::Database(e) => ApiError::Database(e),
+            WorkspaceExportError::GitService(e) => ApiError::GitService(e),
+            WorkspaceExportError::Json(e) => ApiError::Io(std::io::Error::other(e)),
+        }
+    }
+}
+
+impl From<SessionForkError> for ApiError {
+    fn from(err: SessionForkError) -> Self {
+        match err {
+            SessionForkError::Database(e) => ApiError::Database(e),
+            SessionForkError::GitService(e) => ApiError::GitService(e),
+            SessionForkError::ProcessNotFound
+            | SessionForkError::NotCodingAgent
+            | SessionForkError::MissingRepoState(_) => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+impl From<BackupError> for ApiError {
+    fn from(err: BackupError) -> Self {
+        match err {
+            BackupError::Io(e) => ApiError::Io(e),
+            BackupError::Database(e) => ApiError::Database(e),
+            BackupError::Json(_) | BackupError::InvalidArchive(_) => {
+                ApiError::BadRequest(err.to_string())
+            }
+        }
+    }
+}
+
+impl From<RepoIndexError> for ApiError {
+    fn from(err: RepoIndexError) -> Self {
+        match err {
+            RepoIndexError::Database(e) => ApiError::Database(e),
+            RepoIndexError::Git(e) => ApiError::GitService(e),
+            RepoIndexError::Join(e) => ApiError::Io(std::io::Error::other(e)),
+        }
+    }
+}
@@ -2,7 +2,10 @@ use anyhow::{self, Error as AnyhowError};
 use axum::Router;
 use deployment::{Deployment, DeploymentError};
 use server::{
-    DeploymentImpl, middleware::origin::validate_origin, routes, runtime::relay_registration,
+    DeploymentImpl,
+    middleware::origin::validate_origin,
+    routes,
+    runtime::{pipeline_orchestrator, relay_registration, scheduler},
 };
 use services::services::container::ContainerService;
 use sqlx::Error as SqlxError;
@@ -76,16 +79,6 @@ async fn main() -> Result<(), VibeKanbanError> {
         .cleanup_orphan_executions()
         .await
         .map_err(DeploymentError::from)?;
-    deployment
-        .container()
-        .backfill_before_head_commits()
-        .await
-        .map_err(DeploymentError::from)?;
-    deployment
-        .container()
-        .backfill_repo_names()
-        .await
-        .map_err(DeploymentError::from)?;
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
@@ -181,6 +174,8 @@ async fn main() -> Result<(), VibeKanbanError> {
     });
 
     relay_registration::spawn_relay(&deployment).await;
+    scheduler::spawn_scheduler(&deployment, shutdown_token.clone());
+    pipeline_orchestrator::spawn_pipeline_orchestrator(&deployment, shutdown_token.clone());
 
     tokio::select! {
         _ = shutdown_signal() => {
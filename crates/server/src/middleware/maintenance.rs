@@ -0,0 +1,56 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// POST routes that only read, kept available in maintenance mode. These are
+/// route templates as matched by the top-level router, under `/api`.
+const READ_ONLY_POSTS: &[&str] = &[
+    "/api/repos/batch",
+    "/api/remote/issues/search",
+    "/api/workspaces/summaries",
+    "/api/execution-processes/wait",
+];
+
+fn is_read_only(method: &Method, matched_path: Option<&str>) -> bool {
+    match *method {
+        Method::GET | Method::HEAD | Method::OPTIONS => true,
+        Method::POST => matched_path.is_some_and(|path| READ_ONLY_POSTS.contains(&path)),
+        _ => false,
+    }
+}
+
+/// Rejects mutating requests with 503 while maintenance mode is on.
+pub async fn reject_writes_in_maintenance(
+    State(deployment): State<DeploymentImpl>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let matched_path = request.extensions().get::<MatchedPath>();
+    if !is_read_only(request.method(), matched_path.map(MatchedPath::as_str))
+        && let Some(reason) = deployment.maintenance().reason()
+    {
+        return Err(ApiError::ServiceUnavailable(format!(
+            "Maintenance mode: {reason}"
+        )));
+    }
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_listed_post_routes_count_as_reads() {
+        assert!(is_read_only(&Method::GET, None));
+        assert!(is_read_only(&Method::POST, Some("/api/repos/batch")));
+        assert!(!is_read_only(&Method::POST, Some("/api/hooks/repos/batch")));
+        assert!(!is_read_only(&Method::POST, None));
+        assert!(!is_read_only(&Method::PUT, Some("/api/repos/batch")));
+    }
+}
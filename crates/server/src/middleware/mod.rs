@@ -1,10 +1,14 @@
 pub mod error_logging;
+pub mod maintenance;
 pub mod model_loaders;
 pub mod origin;
 pub mod relay_request_signature;
 pub mod signed_ws;
+pub mod user_session;
 
 pub use error_logging::*;
+pub use maintenance::*;
 pub use model_loaders::*;
 pub use origin::*;
 pub use relay_request_signature::*;
+pub use user_session::*;
@@ -0,0 +1,137 @@
+use std::convert::Infallible;
+
+use axum::{
+    extract::{FromRequestParts, Request, State},
+    http::{HeaderMap, Method, header, request::Parts},
+    middleware::Next,
+    response::Response,
+};
+use db::models::local_user::LocalUser;
+use deployment::Deployment;
+use services::services::users::{self, USER_SESSION_COOKIE, USER_SESSION_HEADER, UserError};
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// The user a request is signed in as, or `None` for anonymous requests,
+/// which act as the machine's owner.
+#[derive(Debug, Clone, Default)]
+pub struct CurrentUser(pub Option<LocalUser>);
+
+impl<S> FromRequestParts<S> for CurrentUser
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<CurrentUser>()
+            .cloned()
+            .unwrap_or_default())
+    }
+}
+
+impl CurrentUser {
+    pub fn user(&self) -> Option<&LocalUser> {
+        self.0.as_ref()
+    }
+
+    pub fn id(&self) -> Option<uuid::Uuid> {
+        self.0.as_ref().map(|user| user.id)
+    }
+}
+
+/// The session token a request carries, from the header or else the cookie.
+pub fn user_session_token(headers: &HeaderMap) -> Option<String> {
+    if let Some(token) = headers
+        .get(USER_SESSION_HEADER)
+        .and_then(|value| value.to_str().ok())
+    {
+        return Some(token.trim().to_string());
+    }
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .find_map(|cookie| {
+            let (name, value) = cookie.trim().split_once('=')?;
+            (name == USER_SESSION_COOKIE).then(|| value.to_string())
+        })
+}
+
+/// Routes that work without a valid session: creating the first user has to
+/// work before anyone can sign in, signing in with a remote account goes
+/// through the OAuth handoff, and a stale cookie mustn't lock anyone out.
+const SESSION_ROUTES: &[(Method, &str)] = &[
+    (Method::POST, "/users"),
+    (Method::POST, "/users/sign-in"),
+    (Method::POST, "/users/sign-in/remote"),
+    (Method::POST, "/users/sign-out"),
+    (Method::GET, "/auth/methods"),
+    (Method::POST, "/auth/handoff/init"),
+    (Method::GET, "/auth/handoff/complete"),
+];
+
+fn is_session_route(request: &Request) -> bool {
+    SESSION_ROUTES
+        .iter()
+        .any(|(method, path)| request.method() == method && request.uri().path().ends_with(path))
+}
+
+/// Resolves the request's user session into a [`CurrentUser`]. Requests with
+/// an unknown token are rejected, and so are anonymous ones when sign-in is
+/// required.
+pub async fn resolve_user_session(
+    State(deployment): State<DeploymentImpl>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let session_route = is_session_route(&request);
+    let user = match user_session_token(request.headers()) {
+        Some(token) => match users::authenticate(&deployment.db().pool, &token).await? {
+            Some(user) => Some(user),
+            None if session_route => None,
+            None => return Err(ApiError::Unauthorized),
+        },
+        None => None,
+    };
+
+    if user.is_none() && !session_route && deployment.config().read().await.users.require_sign_in {
+        return Err(ApiError::Unauthorized);
+    }
+
+    request.extensions_mut().insert(CurrentUser(user));
+    Ok(next.run(request).await)
+}
+
+/// Reject the request unless it comes from an admin. Anonymous requests act
+/// as the machine's owner only while sign-in isn't required.
+pub(crate) async fn ensure_admin(
+    deployment: &DeploymentImpl,
+    current: &CurrentUser,
+    action: &'static str,
+) -> Result<(), ApiError> {
+    let is_admin = match current.user() {
+        Some(user) => user.is_admin,
+        None => !deployment.config().read().await.users.require_sign_in,
+    };
+    if is_admin {
+        Ok(())
+    } else {
+        Err(UserError::Forbidden(action).into())
+    }
+}
+
+/// Restricts the routes it is layered on to admins. Must run after
+/// [`resolve_user_session`].
+pub async fn require_admin(
+    State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    ensure_admin(&deployment, &current, "manage this server").await?;
+    Ok(next.run(request).await)
+}
@@ -0,0 +1,224 @@
+//! Pages behind the one-time approval links sent to Slack or email.
+//!
+//! Opening a link only shows the request; the approval is answered by the
+//! form it contains. Link previews (chat apps fetch URLs they see) therefore
+//! can't use a link up. These routes sit outside the relay signature and
+//! origin checks: the signed token is the credential.
+
+use axum::{
+    Form, Router,
+    extract::{Path, State},
+    http::{Response, StatusCode},
+    routing::get,
+};
+use db::models::{audit_log::AuditAction, execution_process::ExecutionProcess};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::approvals::{ApprovalInfo, links::ApprovalLinkError};
+use utils::approvals::{ApprovalOutcome, ApprovalResponse};
+
+use crate::{
+    DeploymentImpl,
+    routes::{
+        audit::{self, AuditActor},
+        oauth::{APP_ICON_BASE64, AUTH_PAGE_STYLES},
+    },
+};
+
+/// Audit log actor for decisions made through an approval link.
+const LINK_ACTOR: &str = "approval_link";
+
+const LINK_PAGE_STYLES: &str = r#"<style>
+  form { display: flex; gap: 12px; margin-top: 16px; }
+  button {
+    font: inherit;
+    font-size: 15px;
+    font-weight: 500;
+    padding: 12px 28px;
+    border-radius: 6px;
+    border: 1px solid #d4d4d4;
+    background: #fff;
+    color: #0d0d0d;
+  }
+  button.approve { background: #0d0d0d; border-color: #0d0d0d; color: #fff; }
+  .title { font-size: 15px; }
+  .subtitle { font-size: 13px; text-align: center; }
+</style>"#;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum LinkDecision {
+    Approve,
+    Deny,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinkDecisionForm {
+    decision: LinkDecision,
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// `title` and `subtitle` are inserted as is; `body` is raw HTML.
+fn link_page(status: StatusCode, title: &str, subtitle: &str, body: &str) -> Response<String> {
+    let page = format!(
+        r#"<!doctype html>
+<html>
+  <head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <meta name="robots" content="noindex">
+    <title>Approval Request</title>
+    {AUTH_PAGE_STYLES}
+    {LINK_PAGE_STYLES}
+  </head>
+  <body>
+    <div class="container">
+      <img class="logo" src="data:image/png;base64,{APP_ICON_BASE64}" alt="Vibe Kanban">
+      <div class="content">
+        <p class="title">{title}</p>
+        <p class="subtitle">{subtitle}</p>
+        {body}
+      </div>
+    </div>
+  </body>
+</html>"#
+    );
+    Response::builder()
+        .status(status)
+        .header("content-type", "text/html; charset=utf-8")
+        .header("cache-control", "no-store")
+        .header("referrer-policy", "no-referrer")
+        .body(page)
+        .unwrap()
+}
+
+fn link_error_page(err: ApprovalLinkError) -> Response<String> {
+    let status = match err {
+        ApprovalLinkError::Invalid => StatusCode::NOT_FOUND,
+        ApprovalLinkError::Expired | ApprovalLinkError::AlreadyUsed => StatusCode::GONE,
+    };
+    link_page(
+        status,
+        &err.to_string(),
+        "Open Vibe Kanban to see the approval.",
+        "",
+    )
+}
+
+fn not_pending_page() -> Response<String> {
+    link_page(
+        StatusCode::GONE,
+        "This approval has already been answered or has timed out.",
+        "Open Vibe Kanban to see the current state.",
+        "",
+    )
+}
+
+async fn workspace_label(deployment: &DeploymentImpl, info: &ApprovalInfo) -> String {
+    ExecutionProcess::load_context(&deployment.db().pool, info.execution_process_id)
+        .await
+        .map(|ctx| ctx.workspace.name.unwrap_or(ctx.workspace.branch))
+        .unwrap_or_else(|_| "Unknown workspace".to_string())
+}
+
+async fn show_approval_link(
+    State(deployment): State<DeploymentImpl>,
+    Path(token): Path<String>,
+) -> Response<String> {
+    let claims = match deployment.approvals().links().verify(&token) {
+        Ok(claims) => claims,
+        Err(err) => return link_error_page(err),
+    };
+    let Some(info) = deployment.approvals().pending_info(&claims.approval_id) else {
+        return not_pending_page();
+    };
+
+    let workspace = workspace_label(&deployment, &info).await;
+    link_page(
+        StatusCode::OK,
+        &format!("Tool '{}' requires approval", escape_html(&info.tool_name)),
+        &format!(
+            "{} &middot; expires {}",
+            escape_html(&workspace),
+            info.timeout_at.format("%H:%M UTC")
+        ),
+        r#"<form method="post">
+          <button class="approve" type="submit" name="decision" value="approve">Approve</button>
+          <button type="submit" name="decision" value="deny">Deny</button>
+        </form>"#,
+    )
+}
+
+async fn answer_approval_link(
+    State(deployment): State<DeploymentImpl>,
+    Path(token): Path<String>,
+    Form(form): Form<LinkDecisionForm>,
+) -> Response<String> {
+    let approvals = deployment.approvals();
+    let claims = match approvals.links().consume(&token) {
+        Ok(claims) => claims,
+        Err(err) => return link_error_page(err),
+    };
+    let Some(info) = approvals.pending_info(&claims.approval_id) else {
+        return not_pending_page();
+    };
+
+    let (status, action, verdict) = match form.decision {
+        LinkDecision::Approve => (
+            ApprovalOutcome::Approved,
+            AuditAction::ApprovalGranted,
+            "Approved",
+        ),
+        LinkDecision::Deny => (
+            ApprovalOutcome::Denied {
+                reason: Some("Denied from an approval link".to_string()),
+            },
+            AuditAction::ApprovalDenied,
+            "Denied",
+        ),
+    };
+    let response = ApprovalResponse {
+        execution_process_id: info.execution_process_id,
+        status,
+    };
+    if let Err(e) = approvals.respond(&claims.approval_id, response, None).await {
+        tracing::warn!("Failed to answer approval from link: {:?}", e);
+        return not_pending_page();
+    }
+
+    let workspace_id =
+        audit::workspace_id_for_process(&deployment, info.execution_process_id).await;
+    audit::record(
+        &deployment,
+        &AuditActor(LINK_ACTOR.to_string()),
+        action,
+        workspace_id,
+        Some(info.execution_process_id),
+        Some(serde_json::json!({
+            "approval_id": &claims.approval_id,
+            "tool_name": &info.tool_name,
+        })),
+    )
+    .await;
+
+    link_page(
+        StatusCode::OK,
+        &format!("{verdict}: '{}'", escape_html(&info.tool_name)),
+        "You can close this page.",
+        "",
+    )
+}
+
+pub(super) fn router() -> Router<DeploymentImpl> {
+    Router::new().route(
+        "/approval-links/{token}",
+        get(show_approval_link).post(answer_approval_link),
+    )
+}
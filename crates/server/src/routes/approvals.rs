@@ -1,32 +1,76 @@
+use std::convert::Infallible;
+
 use axum::{
-    Router,
-    extract::{State, ws::Message},
+    Json, Router,
+    extract::{Path, State, ws::Message},
     http::StatusCode,
-    response::{IntoResponse, Json as ResponseJson},
-    routing::{get, post},
+    middleware::from_fn_with_state,
+    response::{
+        IntoResponse, Json as ResponseJson, Sse,
+        sse::{Event, KeepAlive},
+    },
+    routing::{get, post, put},
+};
+use db::models::{
+    approval_rule::{ApprovalRule, ApprovalRuleError, CreateApprovalRule, UpdateApprovalRule},
+    audit_log::AuditAction,
+    repo::Repo,
 };
 use deployment::Deployment;
-use futures_util::StreamExt;
+use futures_util::{Stream, StreamExt};
+use services::services::approvals::{ApprovalError, ApprovalInfo};
 use utils::{
     approvals::{ApprovalOutcome, ApprovalResponse},
     log_msg::LogMsg,
     response::ApiResponse,
 };
+use uuid::Uuid;
 
 use crate::{
     DeploymentImpl,
-    middleware::signed_ws::{MaybeSignedWebSocket, SignedWsUpgrade},
+    error::ApiError,
+    middleware::{
+        CurrentUser, require_admin,
+        signed_ws::{MaybeSignedWebSocket, SignedWsUpgrade},
+    },
+    routes::audit::{self, AuditActor},
 };
 
 async fn respond_to_approval(
     State(deployment): State<DeploymentImpl>,
     axum::extract::Path(id): axum::extract::Path<String>,
+    actor: AuditActor,
+    current: CurrentUser,
     ResponseJson(request): ResponseJson<ApprovalResponse>,
 ) -> Result<ResponseJson<ApiResponse<ApprovalOutcome>>, StatusCode> {
     let service = deployment.approvals();
 
-    match service.respond(&id, request).await {
+    match service.respond(&id, request, current.user()).await {
         Ok((outcome, context)) => {
+            let action = match outcome {
+                ApprovalOutcome::Approved => Some(AuditAction::ApprovalGranted),
+                ApprovalOutcome::Denied { .. } => Some(AuditAction::ApprovalDenied),
+                _ => None,
+            };
+            if let Some(action) = action {
+                let workspace_id =
+                    audit::workspace_id_for_process(&deployment, context.execution_process_id)
+                        .await;
+                audit::record(
+                    &deployment,
+                    &actor,
+                    action,
+                    workspace_id,
+                    Some(context.execution_process_id),
+                    Some(serde_json::json!({
+                        "approval_id": &id,
+                        "tool_name": &context.tool_name,
+                        "approver_id": current.id(),
+                    })),
+                )
+                .await;
+            }
+
             deployment
                 .track_if_analytics_allowed(
                     "approval_responded",
@@ -41,6 +85,7 @@ async fn respond_to_approval(
 
             Ok(ResponseJson(ApiResponse::success(outcome)))
         }
+        Err(ApprovalError::NotAnApprover) => Err(StatusCode::FORBIDDEN),
         Err(e) => {
             tracing::error!("Failed to respond to approval: {:?}", e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
@@ -106,8 +151,84 @@ async fn handle_approvals_ws(
     Ok(())
 }
 
-pub(super) fn router() -> Router<DeploymentImpl> {
+/// Approvals waiting for a response, oldest first.
+async fn list_pending_approvals(
+    State(deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<Vec<ApprovalInfo>>> {
+    let mut pending = deployment.approvals().pending_infos();
+    pending.sort_by_key(|info| info.created_at);
+    ResponseJson(ApiResponse::success(pending))
+}
+
+/// Server-sent events variant of the approvals stream, for headless clients
+/// that cannot hold a signed WebSocket open.
+async fn stream_approvals_sse(
+    State(deployment): State<DeploymentImpl>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = deployment
+        .approvals()
+        .patch_stream()
+        .map(|patch| Ok(LogMsg::JsonPatch(patch).to_sse_event()));
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+async fn list_approval_rules(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApprovalRule>>>, ApiError> {
+    let rules = ApprovalRule::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(rules)))
+}
+
+async fn create_approval_rule(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateApprovalRule>,
+) -> Result<ResponseJson<ApiResponse<ApprovalRule>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if let Some(repo_id) = payload.repo_id
+        && Repo::find_by_id(pool, repo_id).await?.is_none()
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Repository {repo_id} not found"
+        )));
+    }
+    let rule = ApprovalRule::create(pool, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(rule)))
+}
+
+async fn update_approval_rule(
+    State(deployment): State<DeploymentImpl>,
+    Path(rule_id): Path<Uuid>,
+    Json(payload): Json<UpdateApprovalRule>,
+) -> Result<ResponseJson<ApiResponse<ApprovalRule>>, ApiError> {
+    let rule = ApprovalRule::update(&deployment.db().pool, rule_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(rule)))
+}
+
+async fn delete_approval_rule(
+    State(deployment): State<DeploymentImpl>,
+    Path(rule_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if ApprovalRule::delete(&deployment.db().pool, rule_id).await? == 0 {
+        return Err(ApprovalRuleError::NotFound.into());
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub(super) fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    // Rules decide who may answer approvals, so only admins change them.
+    let admin_router = Router::new()
+        .route("/approvals/rules", post(create_approval_rule))
+        .route(
+            "/approvals/rules/{rule_id}",
+            put(update_approval_rule).delete(delete_approval_rule),
+        )
+        .route_layer(from_fn_with_state(deployment.clone(), require_admin));
+
     Router::new()
+        .route("/approvals/pending", get(list_pending_approvals))
         .route("/approvals/{id}/respond", post(respond_to_approval))
+        .route("/approvals/stream", get(stream_approvals_sse))
         .route("/approvals/stream/ws", get(stream_approvals_ws))
+        .route("/approvals/rules", get(list_approval_rules))
+        .merge(admin_router)
 }
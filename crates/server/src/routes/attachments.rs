@@ -46,6 +46,8 @@ fn is_safe_inline_attachment_mime_type(mime_type: &str) -> bool {
 pub struct AttachmentResponse {
     pub id: Uuid,
     pub file_path: String, // relative path to display in markdown
+    /// Stable URL serving the file, independent of any workspace.
+    pub url: String,
     pub original_name: String,
     pub mime_type: Option<String>,
     pub size_bytes: i64,
@@ -60,6 +62,7 @@ impl AttachmentResponse {
         Self {
             id: file.id,
             file_path: markdown_path,
+            url: format!("/api/attachments/{}/file", file.id),
             original_name: file.original_name,
             mime_type: file.mime_type,
             size_bytes: file.size_bytes,
@@ -0,0 +1,274 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::models::{
+    attempt_group::{AttemptGroup, AttemptGroupError, AttemptGroupWorkspace},
+    execution_process::{
+        ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus, TestResults,
+    },
+    requests::{CreateAndStartWorkspaceRequest, WorkspaceRepoInput},
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use executors::profile::ExecutorConfig;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::workspaces::{
+        create::create_and_start,
+        workspace_summary::{DiffStats, compute_workspace_diff_stats},
+    },
+};
+
+const MAX_ATTEMPTS: usize = 8;
+const DEFAULT_NAME_LEN: usize = 60;
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateAttemptGroupRequest {
+    /// Defaults to the first line of the prompt.
+    #[serde(default)]
+    #[ts(optional)]
+    pub name: Option<String>,
+    pub repos: Vec<WorkspaceRepoInput>,
+    pub prompt: String,
+    /// One attempt is started per executor config.
+    pub executor_configs: Vec<ExecutorConfig>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub env_profile_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct AttemptGroupWithWorkspaces {
+    pub group: AttemptGroup,
+    pub attempts: Vec<AttemptGroupWorkspace>,
+}
+
+/// Outcome of the latest cleanup script run, which is where repos run their
+/// tests and checks.
+#[derive(Debug, Serialize, TS)]
+pub struct AttemptChecks {
+    pub status: ExecutionProcessStatus,
+    pub exit_code: Option<i64>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct AttemptComparison {
+    pub workspace_id: Uuid,
+    pub workspace_name: Option<String>,
+    pub branch: String,
+    pub executor_config: ExecutorConfig,
+    /// Status of the latest coding agent run.
+    pub agent_status: Option<ExecutionProcessStatus>,
+    /// Number of coding agent runs, including follow-ups.
+    pub agent_runs: i64,
+    /// Wall-clock seconds spent by coding agents; the cost of the attempt.
+    pub agent_seconds: f64,
+    pub diff_stats: Option<DiffStats>,
+    pub checks: Option<AttemptChecks>,
+    /// Test counts from the verify scripts of the latest turn.
+    pub tests: Option<TestResults>,
+}
+
+fn default_name(prompt: &str) -> String {
+    let first_line = prompt.lines().next().unwrap_or_default().trim();
+    first_line.chars().take(DEFAULT_NAME_LEN).collect()
+}
+
+fn attempt_name(group: &AttemptGroup, config: &ExecutorConfig) -> String {
+    match &config.variant {
+        Some(variant) => format!("{} ({} {})", group.name, config.executor, variant),
+        None => format!("{} ({})", group.name, config.executor),
+    }
+}
+
+async fn load_group(deployment: &DeploymentImpl, id: Uuid) -> Result<AttemptGroup, ApiError> {
+    AttemptGroup::find_by_id(&deployment.db().pool, id)
+        .await?
+        .ok_or_else(|| AttemptGroupError::NotFound.into())
+}
+
+pub async fn list_attempt_groups(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<AttemptGroup>>>, ApiError> {
+    let groups = AttemptGroup::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(groups)))
+}
+
+/// Start the same prompt in one sibling workspace per executor config. If an
+/// attempt fails to start, the attempts already started stay in the group.
+pub async fn create_attempt_group(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateAttemptGroupRequest>,
+) -> Result<ResponseJson<ApiResponse<AttemptGroupWithWorkspaces>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if payload.repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one repository is required".to_string(),
+        ));
+    }
+    if payload.prompt.trim().is_empty() {
+        return Err(ApiError::BadRequest("Prompt must not be empty".to_string()));
+    }
+    if payload.executor_configs.len() < 2 || payload.executor_configs.len() > MAX_ATTEMPTS {
+        return Err(ApiError::BadRequest(format!(
+            "Between 2 and {MAX_ATTEMPTS} executors are required"
+        )));
+    }
+
+    let name = payload
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| default_name(&payload.prompt));
+    let group = AttemptGroup::create(pool, &name, &payload.prompt).await?;
+
+    let mut attempts = Vec::with_capacity(payload.executor_configs.len());
+    for (index, executor_config) in payload.executor_configs.iter().enumerate() {
+        let response = create_and_start(
+            &deployment,
+            CreateAndStartWorkspaceRequest {
+                name: Some(attempt_name(&group, executor_config)),
+                repos: payload.repos.clone(),
+                linked_issue: None,
+                executor_config: executor_config.clone(),
+                prompt: payload.prompt.clone(),
+                attachment_ids: None,
+                idempotency_key: Some(format!("attempt:{}:{}", group.id, index)),
+                env_profile_ids: payload.env_profile_ids.clone(),
+            },
+        )
+        .await?;
+        attempts.push(
+            AttemptGroupWorkspace::create(pool, group.id, response.workspace.id, executor_config)
+                .await?,
+        );
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "attempt_group_created",
+            serde_json::json!({
+                "attempt_group_id": group.id.to_string(),
+                "executors": payload
+                    .executor_configs
+                    .iter()
+                    .map(|config| config.executor.to_string())
+                    .collect::<Vec<_>>(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(
+        AttemptGroupWithWorkspaces { group, attempts },
+    )))
+}
+
+pub async fn get_attempt_group(
+    State(deployment): State<DeploymentImpl>,
+    Path(group_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<AttemptGroupWithWorkspaces>>, ApiError> {
+    let group = load_group(&deployment, group_id).await?;
+    let attempts = AttemptGroupWorkspace::find_by_group_id(&deployment.db().pool, group.id).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        AttemptGroupWithWorkspaces { group, attempts },
+    )))
+}
+
+/// Delete the group. The attempts' workspaces are left in place.
+pub async fn delete_attempt_group(
+    State(deployment): State<DeploymentImpl>,
+    Path(group_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if AttemptGroup::delete(&deployment.db().pool, group_id).await? == 0 {
+        return Err(AttemptGroupError::NotFound.into());
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Diff stats, agent time and check results of every attempt, side by side.
+/// Attempts whose workspace has been deleted are left out.
+pub async fn compare_attempts(
+    State(deployment): State<DeploymentImpl>,
+    Path(group_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<AttemptComparison>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let group = load_group(&deployment, group_id).await?;
+
+    let mut comparisons = Vec::new();
+    for attempt in AttemptGroupWorkspace::find_by_group_id(pool, group.id).await? {
+        let Some(workspace) = Workspace::find_by_id(pool, attempt.workspace_id).await? else {
+            continue;
+        };
+        let agent_status = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+            pool,
+            workspace.id,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?
+        .map(|process| process.status);
+        let checks = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+            pool,
+            workspace.id,
+            &ExecutionProcessRunReason::CleanupScript,
+        )
+        .await?
+        .map(|process| AttemptChecks {
+            status: process.status,
+            exit_code: process.exit_code,
+        });
+        let tests = match ExecutionProcess::find_latest_by_workspace_and_run_reason(
+            pool,
+            workspace.id,
+            &ExecutionProcessRunReason::VerifyScript,
+        )
+        .await?
+        {
+            Some(process) => {
+                ExecutionProcess::find_latest_turn_test_results(pool, process.session_id).await?
+            }
+            None => None,
+        };
+        let (agent_runs, agent_seconds) =
+            ExecutionProcess::coding_agent_usage_for_workspace(pool, workspace.id).await?;
+        let diff_stats = compute_workspace_diff_stats(&deployment, &workspace).await;
+
+        comparisons.push(AttemptComparison {
+            workspace_id: workspace.id,
+            workspace_name: workspace.name,
+            branch: workspace.branch,
+            executor_config: attempt.executor_config,
+            agent_status,
+            agent_runs,
+            agent_seconds,
+            diff_stats,
+            checks,
+            tests,
+        });
+    }
+
+    Ok(ResponseJson(ApiResponse::success(comparisons)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            "/attempt-groups",
+            get(list_attempt_groups).post(create_attempt_group),
+        )
+        .route(
+            "/attempt-groups/{group_id}",
+            get(get_attempt_group).delete(delete_attempt_group),
+        )
+        .route("/attempt-groups/{group_id}/compare", get(compare_attempts))
+}
@@ -0,0 +1,105 @@
+use std::convert::Infallible;
+
+use axum::{
+    Router,
+    extract::{FromRequestParts, Query, State},
+    http::request::Parts,
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::models::{
+    audit_log::{AuditAction, AuditLogEntry, AuditLogQuery, CreateAuditLogEntry},
+    execution_process::ExecutionProcess,
+    session::Session,
+};
+use deployment::Deployment;
+use serde_json::Value;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{CurrentUser, RelayRequestSignatureContext, require_admin},
+};
+
+/// Identifies who issued a request, for attribution in the audit log.
+///
+/// Requests from a signed-in user are attributed to them. Otherwise, requests
+/// that arrived through the relay carry a verified signing session; everything
+/// else originated on the machine running the server.
+#[derive(Debug, Clone)]
+pub struct AuditActor(pub String);
+
+impl<S> FromRequestParts<S> for AuditActor
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(CurrentUser(Some(user))) = parts.extensions.get::<CurrentUser>() {
+            return Ok(AuditActor(format!("user:{}", user.name)));
+        }
+        let actor = match parts.extensions.get::<RelayRequestSignatureContext>() {
+            Some(signature) => format!("relay:{}", signature.signing_session_id),
+            None => "local".to_string(),
+        };
+        Ok(AuditActor(actor))
+    }
+}
+
+/// Append an entry to the audit log. Failures are logged rather than returned:
+/// the action being audited has already happened by the time this is called.
+pub async fn record(
+    deployment: &DeploymentImpl,
+    actor: &AuditActor,
+    action: AuditAction,
+    workspace_id: Option<Uuid>,
+    target_id: Option<Uuid>,
+    details: Option<Value>,
+) {
+    let entry = CreateAuditLogEntry {
+        action,
+        actor: actor.0.clone(),
+        workspace_id,
+        target_id,
+        details,
+    };
+    if let Err(e) = AuditLogEntry::create(&deployment.db().pool, &entry).await {
+        tracing::error!("Failed to write audit log entry {:?}: {}", action, e);
+    }
+}
+
+/// Resolve the workspace an execution process belongs to, for attributing
+/// process-scoped actions.
+pub async fn workspace_id_for_process(
+    deployment: &DeploymentImpl,
+    execution_process_id: Uuid,
+) -> Option<Uuid> {
+    let pool = &deployment.db().pool;
+    let process = ExecutionProcess::find_by_id(pool, execution_process_id)
+        .await
+        .ok()
+        .flatten()?;
+    Session::find_by_id(pool, process.session_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|session| session.workspace_id)
+}
+
+pub async fn get_audit_log(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<AuditLogQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<AuditLogEntry>>>, ApiError> {
+    let entries = AuditLogEntry::find(&deployment.db().pool, &query).await?;
+    Ok(ResponseJson(ApiResponse::success(entries)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/audit", get(get_audit_log))
+        .route_layer(from_fn_with_state(deployment.clone(), require_admin))
+}
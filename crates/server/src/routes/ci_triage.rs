@@ -0,0 +1,209 @@
+use axum::{
+    Json, Router,
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::{
+    ci_triage::{
+        CiTriageError as CiTriageModelError, CiTriageRun, CiTriageSettings, UpsertCiTriageSettings,
+    },
+    requests::{CreateAndStartWorkspaceRequest, WorkspaceRepoInput},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::ci_triage::{self, CiTriageError, SavedCiTriageSettings};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{CurrentUser, ensure_admin},
+    routes::workspaces::create::create_and_start,
+};
+
+const DEFAULT_RUNS_LIMIT: i64 = 50;
+const MAX_RUNS_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct ListCiTriageRunsQuery {
+    pub limit: Option<i64>,
+}
+
+pub async fn get_ci_triage_settings(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Option<CiTriageSettings>>>, ApiError> {
+    let settings = CiTriageSettings::find_by_repo_id(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+/// Triage runs start agents from GitHub events, so only admins set it up. The
+/// webhook secret is returned when the settings are first saved.
+pub async fn save_ci_triage_settings(
+    State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
+    Path(repo_id): Path<Uuid>,
+    Json(payload): Json<UpsertCiTriageSettings>,
+) -> Result<ResponseJson<ApiResponse<SavedCiTriageSettings>>, ApiError> {
+    ensure_admin(&deployment, &current, "manage CI triage").await?;
+    deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+    let saved = ci_triage::save_settings(&deployment.db().pool, repo_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(saved)))
+}
+
+pub async fn delete_ci_triage_settings(
+    State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ensure_admin(&deployment, &current, "manage CI triage").await?;
+    if CiTriageSettings::delete(&deployment.db().pool, repo_id).await? == 0 {
+        return Err(CiTriageError::from(CiTriageModelError::NotFound).into());
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Issue a new webhook secret, revoking the old one.
+pub async fn rotate_ci_triage_secret(
+    State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<SavedCiTriageSettings>>, ApiError> {
+    ensure_admin(&deployment, &current, "manage CI triage").await?;
+    let rotated = ci_triage::rotate_secret(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(rotated)))
+}
+
+/// Most recent triaged runs of a repo, newest first.
+pub async fn list_ci_triage_runs(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    Query(query): Query<ListCiTriageRunsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<CiTriageRun>>>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_RUNS_LIMIT)
+        .clamp(1, MAX_RUNS_LIMIT);
+    let runs = CiTriageRun::find_by_repo_id(&deployment.db().pool, repo_id, limit).await?;
+    Ok(ResponseJson(ApiResponse::success(runs)))
+}
+
+/// Fetch the failed run's logs and start the triage workspace.
+async fn start_triage(
+    deployment: &DeploymentImpl,
+    settings: &CiTriageSettings,
+    run: &CiTriageRun,
+) -> Result<Uuid, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, run.repo_id)
+        .await?;
+    let log = ci_triage::fetch_failed_log(deployment.git(), &repo, run.workflow_run_id).await?;
+    let request = CreateAndStartWorkspaceRequest {
+        name: Some(format!(
+            "CI triage: {} on {}",
+            run.workflow_name, run.head_branch
+        )),
+        repos: vec![WorkspaceRepoInput {
+            repo_id: run.repo_id,
+            target_branch: run.head_branch.clone(),
+            package_id: None,
+        }],
+        linked_issue: None,
+        executor_config: settings.executor_config.clone(),
+        prompt: ci_triage::build_prompt(settings.prompt.as_deref(), run, &log),
+        attachment_ids: None,
+        idempotency_key: Some(format!(
+            "ci-triage:{}:{}:{}",
+            run.repo_id, run.workflow_run_id, run.run_attempt
+        )),
+        env_profile_ids: None,
+    };
+    let response = create_and_start(deployment, request).await?;
+    Ok(response.workspace.id)
+}
+
+/// Receive a GitHub webhook delivery for a repo. Deliveries are
+/// authenticated by their signature; failed `workflow_run` events start a
+/// triage in the background, and anything else is acknowledged and ignored.
+pub async fn github_webhook(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, ResponseJson<ApiResponse<Option<CiTriageRun>>>), ApiError> {
+    let pool = &deployment.db().pool;
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    ci_triage::verify_delivery(pool, repo_id, &body, header("x-hub-signature-256")).await?;
+
+    let ignored = Ok((StatusCode::OK, ResponseJson(ApiResponse::success(None))));
+    if header("x-github-event") != Some("workflow_run") {
+        return ignored;
+    }
+    let settings = CiTriageSettings::find_by_repo_id(pool, repo_id)
+        .await?
+        .ok_or(CiTriageError::from(CiTriageModelError::NotFound))?;
+    let event = ci_triage::parse_event(&body)?;
+    let Some(failed) = ci_triage::failed_run(repo_id, &event).filter(|_| settings.enabled) else {
+        return ignored;
+    };
+    // Redeliveries of an attempt that was already recorded are acknowledged.
+    let Some(run) = CiTriageRun::create(pool, &failed).await? else {
+        return ignored;
+    };
+
+    tracing::info!(
+        "Triaging failed run {} of {} on {}",
+        run.workflow_run_id,
+        run.workflow_name,
+        run.head_branch
+    );
+    let background = deployment.clone();
+    let started = run.clone();
+    tokio::spawn(async move {
+        let pool = &background.db().pool;
+        let result = match start_triage(&background, &settings, &started).await {
+            Ok(workspace_id) => CiTriageRun::mark_running(pool, started.id, workspace_id).await,
+            Err(e) => {
+                tracing::warn!("CI triage of run {} failed: {}", started.workflow_run_id, e);
+                CiTriageRun::mark_failed(pool, started.id, &e.to_string()).await
+            }
+        };
+        if let Err(e) = result {
+            tracing::error!("Failed to record CI triage run {}: {}", started.id, e);
+        }
+    });
+
+    Ok((
+        StatusCode::ACCEPTED,
+        ResponseJson(ApiResponse::success(Some(run))),
+    ))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            "/repos/{repo_id}/ci-triage",
+            get(get_ci_triage_settings)
+                .put(save_ci_triage_settings)
+                .delete(delete_ci_triage_settings),
+        )
+        .route(
+            "/repos/{repo_id}/ci-triage/rotate-secret",
+            post(rotate_ci_triage_secret),
+        )
+        .route("/repos/{repo_id}/ci-triage/runs", get(list_ci_triage_runs))
+}
+
+/// GitHub's webhook deliveries come from outside, so like the other inbound
+/// webhooks this sits outside the relay signature and origin checks.
+pub fn hooks_router() -> Router<DeploymentImpl> {
+    Router::new().route("/hooks/github/{repo_id}", post(github_webhook))
+}
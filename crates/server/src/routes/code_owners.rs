@@ -0,0 +1,56 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use deployment::Deployment;
+use services::services::code_owners::{
+    CodeOwners, CodeOwnersFile, CodeOwnersLookup, CodeOwnersMatch,
+};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+async fn load_code_owners(
+    deployment: &DeploymentImpl,
+    repo_id: Uuid,
+) -> Result<CodeOwners, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+    Ok(CodeOwners::load(&repo.path).await?)
+}
+
+/// The repo's CODEOWNERS rules, in file order.
+pub async fn get_code_owners(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<CodeOwnersFile>>, ApiError> {
+    let code_owners = load_code_owners(&deployment, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(code_owners.file())))
+}
+
+/// Owners of the given paths and of paths mentioned in the given text, e.g.
+/// to suggest assignees for an issue about those files.
+pub async fn lookup_code_owners(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    Json(payload): Json<CodeOwnersLookup>,
+) -> Result<ResponseJson<ApiResponse<CodeOwnersMatch>>, ApiError> {
+    let code_owners = load_code_owners(&deployment, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        code_owners.lookup(&payload),
+    )))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/repos/{repo_id}/code-owners", get(get_code_owners))
+        .route(
+            "/repos/{repo_id}/code-owners/lookup",
+            post(lookup_code_owners),
+        )
+}
@@ -6,8 +6,16 @@ use axum::{
     body::Body,
     extract::{Path, Query, State, ws::Message},
     http,
+    middleware::from_fn_with_state,
     response::{IntoResponse, Json as ResponseJson, Response},
-    routing::{get, put},
+    routing::{delete, get, post, put},
+};
+use db::models::{
+    config_override::ConfigOverrides,
+    git_credential::{CreateGitCredential, GitCredentialSummary},
+    repo::{Repo, RepoError},
+    secret::SecretSummary,
+    workspace::{Workspace, WorkspaceError},
 };
 use deployment::{Deployment, DeploymentError};
 use executors::{
@@ -25,8 +33,12 @@ use services::services::{
         editor::{EditorConfig, EditorType},
         save_config_to_file,
     },
+    config_bundle::{self, ConfigBundle, ConfigImportSummary},
+    config_overrides::{self, EffectiveConfig},
     container::ContainerService,
+    git_credentials, log_redaction,
     remote_client::RemoteClientError,
+    secrets,
 };
 use tokio::fs;
 use ts_rs::TS;
@@ -36,17 +48,53 @@ use uuid::Uuid;
 use crate::{
     DeploymentImpl,
     error::ApiError,
-    middleware::signed_ws::{MaybeSignedWebSocket, SignedWsUpgrade},
+    middleware::{
+        require_admin,
+        signed_ws::{MaybeSignedWebSocket, SignedWsUpgrade},
+    },
     runtime::relay_registration,
 };
 
-pub fn router() -> Router<DeploymentImpl> {
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    // Settings that apply to everyone using the server, and the secrets and
+    // credentials runs are given.
+    let admin_router = Router::new()
+        .route("/config", put(update_config))
+        .route("/config/export", get(export_config))
+        .route("/config/import", post(import_config))
+        .route("/config/overrides/repos/{repo_id}", put(set_repo_overrides))
+        .route(
+            "/config/overrides/workspaces/{workspace_id}",
+            put(set_workspace_overrides),
+        )
+        .route("/mcp-config", post(update_mcp_servers))
+        .route("/profiles", put(update_profiles))
+        .route("/config/secrets", get(list_secrets))
+        .route(
+            "/config/secrets/{name}",
+            put(set_secret).delete(delete_secret),
+        )
+        .route(
+            "/config/git-credentials",
+            get(list_git_credentials).post(create_git_credential),
+        )
+        .route(
+            "/config/git-credentials/{credential_id}",
+            delete(delete_git_credential),
+        )
+        .route_layer(from_fn_with_state(deployment.clone(), require_admin));
+
     Router::new()
         .route("/info", get(get_user_system_info))
-        .route("/config", put(update_config))
+        .route("/config/effective", get(get_effective_config))
+        .route("/config/overrides/repos/{repo_id}", get(get_repo_overrides))
+        .route(
+            "/config/overrides/workspaces/{workspace_id}",
+            get(get_workspace_overrides),
+        )
         .route("/sounds/{sound}", get(get_sound))
-        .route("/mcp-config", get(get_mcp_servers).post(update_mcp_servers))
-        .route("/profiles", get(get_profiles).put(update_profiles))
+        .route("/mcp-config", get(get_mcp_servers))
+        .route("/profiles", get(get_profiles))
         .route(
             "/editors/check-availability",
             get(check_editor_availability),
@@ -57,6 +105,7 @@ pub fn router() -> Router<DeploymentImpl> {
             "/agents/discovered-options/ws",
             get(stream_executor_discovered_options_ws),
         )
+        .merge(admin_router)
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -190,6 +239,57 @@ async fn update_config(
         ));
     }
 
+    if let Err(message) = log_redaction::validate_patterns(&new_config.log_redaction.patterns) {
+        return ResponseJson(ApiResponse::error(&message));
+    }
+
+    if let Some(public_url) = &new_config.remote_approvals.public_url
+        && !url::Url::parse(public_url).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+    {
+        return ResponseJson(ApiResponse::error(
+            "Invalid public URL for remote approvals. Must be an http(s) URL.",
+        ));
+    }
+
+    if let Some(public_url) = &new_config.session_sharing.public_url
+        && !url::Url::parse(public_url).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+    {
+        return ResponseJson(ApiResponse::error(
+            "Invalid public URL for session sharing. Must be an http(s) URL.",
+        ));
+    }
+
+    if let Some(endpoint_url) = &new_config.semantic_search.endpoint_url
+        && !url::Url::parse(endpoint_url).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+    {
+        return ResponseJson(ApiResponse::error(
+            "Invalid embeddings endpoint URL. Must be an http(s) URL.",
+        ));
+    }
+
+    if new_config.quiet_hours.enabled && new_config.quiet_hours.window().is_none() {
+        return ResponseJson(ApiResponse::error(
+            "Invalid quiet hours. Start and end must be times in HH:MM format.",
+        ));
+    }
+
+    let channels = &new_config.notification_channels;
+    for url in [
+        &channels.webhook_url,
+        &channels.slack_webhook_url,
+        &channels.completion_webhook_url,
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|url| !url.is_empty())
+    {
+        if !url::Url::parse(url).is_ok_and(|url| matches!(url.scheme(), "http" | "https")) {
+            return ResponseJson(ApiResponse::error(
+                "Invalid notification webhook URL. Must be an http(s) URL.",
+            ));
+        }
+    }
+
     // Get old config state before updating
     let old_config = deployment.config().read().await.clone();
 
@@ -208,6 +308,163 @@ async fn update_config(
     }
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct SetSecretRequest {
+    pub value: String,
+}
+
+async fn list_secrets(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<SecretSummary>>>, ApiError> {
+    let secrets = secrets::list_secrets(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(secrets)))
+}
+
+/// Create or replace a secret. The value is write-only: it is never returned
+/// by any endpoint.
+async fn set_secret(
+    State(deployment): State<DeploymentImpl>,
+    Path(name): Path<String>,
+    Json(payload): Json<SetSecretRequest>,
+) -> Result<ResponseJson<ApiResponse<SecretSummary>>, ApiError> {
+    let summary = secrets::set_secret(&deployment.db().pool, &name, &payload.value).await?;
+    Ok(ResponseJson(ApiResponse::success(summary)))
+}
+
+async fn delete_secret(
+    State(deployment): State<DeploymentImpl>,
+    Path(name): Path<String>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    secrets::delete_secret(&deployment.db().pool, &name).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+async fn list_git_credentials(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<GitCredentialSummary>>>, ApiError> {
+    let credentials = git_credentials::list_credentials(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(credentials)))
+}
+
+/// Add a git account. Tokens are write-only, like secret values.
+async fn create_git_credential(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateGitCredential>,
+) -> Result<ResponseJson<ApiResponse<GitCredentialSummary>>, ApiError> {
+    let credential = git_credentials::create_credential(&deployment.db().pool, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(credential)))
+}
+
+async fn delete_git_credential(
+    State(deployment): State<DeploymentImpl>,
+    Path(credential_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    git_credentials::delete_credential(&deployment.db().pool, credential_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Executor profiles, repo settings and templates as one JSON bundle, for
+/// setting up another machine. Secrets are left out.
+async fn export_config(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ConfigBundle>>, ApiError> {
+    let bundle = config_bundle::export_config(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(bundle)))
+}
+
+async fn import_config(
+    State(deployment): State<DeploymentImpl>,
+    Json(bundle): Json<ConfigBundle>,
+) -> Result<ResponseJson<ApiResponse<ConfigImportSummary>>, ApiError> {
+    let summary = config_bundle::import_config(&deployment.db().pool, &bundle).await?;
+    Ok(ResponseJson(ApiResponse::success(summary)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EffectiveConfigQuery {
+    pub repo_id: Option<Uuid>,
+    pub workspace_id: Option<Uuid>,
+}
+
+/// The merged global, repo and workspace config, with the layer each value
+/// came from. Scripts are only resolved when a repo is given.
+async fn get_effective_config(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<EffectiveConfigQuery>,
+) -> Result<ResponseJson<ApiResponse<EffectiveConfig>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let repo = match query.repo_id {
+        Some(repo_id) => Some(
+            Repo::find_by_id(pool, repo_id)
+                .await?
+                .ok_or(RepoError::NotFound)?,
+        ),
+        None => None,
+    };
+    if let Some(workspace_id) = query.workspace_id {
+        Workspace::find_by_id(pool, workspace_id)
+            .await?
+            .ok_or(WorkspaceError::WorkspaceNotFound)?;
+    }
+    let config = deployment.config().read().await.clone();
+    let effective =
+        config_overrides::resolve_config(pool, &config, repo.as_ref(), query.workspace_id).await?;
+    Ok(ResponseJson(ApiResponse::success(effective)))
+}
+
+async fn get_repo_overrides(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<ConfigOverrides>>, ApiError> {
+    let pool = &deployment.db().pool;
+    Repo::find_by_id(pool, repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+    let overrides = ConfigOverrides::find_for_repo(pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(overrides)))
+}
+
+/// Replace the repo's overrides. Keys left null fall back to the global config.
+async fn set_repo_overrides(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    Json(payload): Json<ConfigOverrides>,
+) -> Result<ResponseJson<ApiResponse<ConfigOverrides>>, ApiError> {
+    let pool = &deployment.db().pool;
+    Repo::find_by_id(pool, repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+    let overrides = config_overrides::set_repo_overrides(pool, repo_id, payload).await?;
+    Ok(ResponseJson(ApiResponse::success(overrides)))
+}
+
+async fn get_workspace_overrides(
+    State(deployment): State<DeploymentImpl>,
+    Path(workspace_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<ConfigOverrides>>, ApiError> {
+    let pool = &deployment.db().pool;
+    Workspace::find_by_id(pool, workspace_id)
+        .await?
+        .ok_or(WorkspaceError::WorkspaceNotFound)?;
+    let overrides = ConfigOverrides::find_for_workspace(pool, workspace_id).await?;
+    Ok(ResponseJson(ApiResponse::success(overrides)))
+}
+
+/// Replace the workspace's overrides. Keys left null fall back to the repo
+/// and global config. Script overrides apply to every repo in the workspace.
+async fn set_workspace_overrides(
+    State(deployment): State<DeploymentImpl>,
+    Path(workspace_id): Path<Uuid>,
+    Json(payload): Json<ConfigOverrides>,
+) -> Result<ResponseJson<ApiResponse<ConfigOverrides>>, ApiError> {
+    let pool = &deployment.db().pool;
+    Workspace::find_by_id(pool, workspace_id)
+        .await?
+        .ok_or(WorkspaceError::WorkspaceNotFound)?;
+    let overrides = config_overrides::set_workspace_overrides(pool, workspace_id, payload).await?;
+    Ok(ResponseJson(ApiResponse::success(overrides)))
+}
+
 /// Track config events when fields transition from false → true
 async fn track_config_events(deployment: &DeploymentImpl, old: &Config, new: &Config) {
     let events = [
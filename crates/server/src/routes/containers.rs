@@ -1,19 +1,53 @@
+use std::{path::Path as FsPath, process::Stdio, time::Duration};
+
 use axum::{
-    Router,
-    extract::{Query, State},
-    response::Json as ResponseJson,
-    routing::get,
+    Json, Router,
+    extract::{Path, Query, Request, State, ws::rejection::WebSocketUpgradeRejection},
+    http::StatusCode,
+    response::{IntoResponse, Json as ResponseJson, Response},
+    routing::{any, get, post},
 };
 use db::models::{
+    audit_log::AuditAction,
+    execution_process::{
+        DevServerTarget, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+    },
+    repo::Repo,
+    repo_dev_server::{RepoDevServer, RepoDevServerError},
     requests::ContainerQuery,
-    workspace::{Workspace, WorkspaceContext},
+    workspace::{Workspace, WorkspaceContext, WorkspaceError},
+    workspace_repo::WorkspaceRepo,
 };
 use deployment::Deployment;
-use serde::Serialize;
-use utils::response::ApiResponse;
+use executors::actions::{
+    ExecutorAction, ExecutorActionType,
+    script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+};
+use serde::{Deserialize, Serialize};
+use services::services::{
+    container::ContainerService,
+    file_tree::{self, FileTreeListing, GitStatusIndex},
+};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use ts_rs::TS;
+use utils::{
+    command_ext::GroupSpawnNoWindowExt, process::kill_process_group, response::ApiResponse,
+    shell::get_shell_command,
+};
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use super::{
+    audit::{self, AuditActor},
+    preview::proxy_to_port,
+    terminal::{resolve_cwd, workspace_dirs},
+    workspaces::execution::dev_server_session,
+};
+use crate::{DeploymentImpl, error::ApiError, middleware::signed_ws::SignedWsUpgrade};
+
+const DEFAULT_EXEC_TIMEOUT_SECS: u64 = 60;
+const MAX_EXEC_TIMEOUT_SECS: u64 = 600;
+/// Output kept per stream; anything past this is read and discarded.
+const MAX_EXEC_OUTPUT_BYTES: usize = 1024 * 1024;
 
 #[derive(Debug, Serialize)]
 struct ContainerInfo {
@@ -47,6 +81,403 @@ async fn get_context(
     Ok(ResponseJson(ApiResponse::success(ctx)))
 }
 
+/// A named dev server of one of the workspace's repos and the process
+/// running it, if any.
+#[derive(Debug, Serialize, TS)]
+pub struct WorkspaceDevServer {
+    pub dev_server: RepoDevServer,
+    pub repo_name: String,
+    pub execution_process_id: Option<Uuid>,
+}
+
+async fn load_workspace(
+    deployment: &DeploymentImpl,
+    workspace_id: Uuid,
+) -> Result<Workspace, ApiError> {
+    Workspace::find_by_id(&deployment.db().pool, workspace_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::WorkspaceNotFound))
+}
+
+/// Look up a dev server, treating one whose repo is not part of the
+/// workspace as missing.
+async fn find_workspace_dev_server(
+    deployment: &DeploymentImpl,
+    workspace_id: Uuid,
+    dev_server_id: Uuid,
+) -> Result<RepoDevServer, ApiError> {
+    RepoDevServer::find_for_workspace(&deployment.db().pool, workspace_id)
+        .await?
+        .into_iter()
+        .find(|dev_server| dev_server.id == dev_server_id)
+        .ok_or_else(|| RepoDevServerError::NotFound.into())
+}
+
+async fn stop_named_dev_server(
+    deployment: &DeploymentImpl,
+    dev_server_id: Uuid,
+) -> Result<(), ApiError> {
+    let running =
+        ExecutionProcess::find_running_by_dev_server_id(&deployment.db().pool, dev_server_id)
+            .await?;
+    for process in running {
+        if let Err(e) = deployment
+            .container()
+            .stop_execution(&process, ExecutionProcessStatus::Killed)
+            .await
+        {
+            tracing::error!("Failed to stop dev server {}: {}", process.id, e);
+        }
+    }
+    Ok(())
+}
+
+async fn list_dev_servers(
+    State(deployment): State<DeploymentImpl>,
+    Path(workspace_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<WorkspaceDevServer>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let workspace = load_workspace(&deployment, workspace_id).await?;
+    let running =
+        ExecutionProcess::find_running_named_dev_servers_by_workspace(pool, workspace.id).await?;
+
+    let mut dev_servers = Vec::new();
+    for dev_server in RepoDevServer::find_for_workspace(pool, workspace.id).await? {
+        let repo_name = Repo::find_by_id(pool, dev_server.repo_id)
+            .await?
+            .map(|repo| repo.name)
+            .unwrap_or_default();
+        let execution_process_id = running
+            .iter()
+            .find(|(_, dev_server_id)| *dev_server_id == dev_server.id)
+            .map(|(execution_process_id, _)| *execution_process_id);
+        dev_servers.push(WorkspaceDevServer {
+            dev_server,
+            repo_name,
+            execution_process_id,
+        });
+    }
+    Ok(ResponseJson(ApiResponse::success(dev_servers)))
+}
+
+/// Start a named dev server, restarting it if it is already running. Other
+/// dev servers in the workspace are left alone.
+async fn start_named_dev_server(
+    State(deployment): State<DeploymentImpl>,
+    Path((workspace_id, dev_server_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let workspace = load_workspace(&deployment, workspace_id).await?;
+    let dev_server = find_workspace_dev_server(&deployment, workspace.id, dev_server_id).await?;
+    let repo = deployment
+        .repo()
+        .get_by_id(pool, dev_server.repo_id)
+        .await?;
+
+    stop_named_dev_server(&deployment, dev_server.id).await?;
+
+    let session = dev_server_session(pool, workspace.id).await?;
+    let executor_action = ExecutorAction::new(
+        ExecutorActionType::ScriptRequest(ScriptRequest {
+            script: dev_server.script.clone(),
+            language: ScriptRequestLanguage::Bash,
+            context: ScriptContext::DevServer,
+            working_dir: Some(repo.name.clone()),
+        }),
+        None,
+    );
+    let execution_process = deployment
+        .container()
+        .start_execution(
+            &workspace,
+            &session,
+            &executor_action,
+            &ExecutionProcessRunReason::DevServer,
+        )
+        .await?;
+    ExecutionProcess::set_dev_server_id(pool, execution_process.id, dev_server.id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "dev_server_started",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "named": true,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
+
+async fn stop_named_dev_server_route(
+    State(deployment): State<DeploymentImpl>,
+    Path((workspace_id, dev_server_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let workspace = load_workspace(&deployment, workspace_id).await?;
+    let dev_server = find_workspace_dev_server(&deployment, workspace.id, dev_server_id).await?;
+    stop_named_dev_server(&deployment, dev_server.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ExecRequest {
+    /// Run through the platform shell (`sh -c`, `cmd /C`).
+    pub command: String,
+    /// Directory to run in, relative to the workspace directory. Defaults to
+    /// the repo directory for single-repo workspaces.
+    #[ts(optional)]
+    pub cwd: Option<String>,
+    /// Defaults to 60 seconds, at most 600.
+    #[ts(optional, type = "number")]
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct ExecResult {
+    /// None if the command was killed by a signal or timed out.
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub timed_out: bool,
+    /// True if either stream exceeded the output limit and was cut short.
+    pub truncated: bool,
+    #[ts(type = "number")]
+    pub duration_ms: u64,
+}
+
+/// Read a stream to the end, keeping at most `MAX_EXEC_OUTPUT_BYTES` of it.
+/// The rest is drained so the command doesn't block on a full pipe.
+async fn read_capped(
+    mut reader: impl AsyncRead + Unpin,
+    buf: &mut Vec<u8>,
+    truncated: &mut bool,
+) -> std::io::Result<()> {
+    let mut chunk = [0u8; 8192];
+    loop {
+        let n = reader.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        let room = MAX_EXEC_OUTPUT_BYTES.saturating_sub(buf.len());
+        if n > room {
+            *truncated = true;
+        }
+        buf.extend_from_slice(&chunk[..n.min(room)]);
+    }
+}
+
+/// Run a one-off command in the workspace and return its output. For
+/// interactive use, open a terminal instead.
+async fn exec_in_workspace(
+    State(deployment): State<DeploymentImpl>,
+    actor: AuditActor,
+    Path(workspace_id): Path<Uuid>,
+    Json(payload): Json<ExecRequest>,
+) -> Result<ResponseJson<ApiResponse<ExecResult>>, ApiError> {
+    if payload.command.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Command must not be empty".to_string(),
+        ));
+    }
+    let timeout_secs = payload
+        .timeout_secs
+        .unwrap_or(DEFAULT_EXEC_TIMEOUT_SECS)
+        .clamp(1, MAX_EXEC_TIMEOUT_SECS);
+    let (base_dir, default_dir) = workspace_dirs(&deployment, workspace_id).await?;
+    let working_dir = match payload.cwd.as_deref().filter(|cwd| !cwd.is_empty()) {
+        Some(cwd) => resolve_cwd(&base_dir, cwd)?,
+        None => default_dir,
+    };
+
+    let (shell, shell_arg) = get_shell_command();
+    let mut command = tokio::process::Command::new(shell);
+    command
+        .arg(shell_arg)
+        .arg(&payload.command)
+        .current_dir(&working_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let started = std::time::Instant::now();
+    let mut child = command.group_spawn_no_window()?;
+    let stdout = child.inner().stdout.take();
+    let stderr = child.inner().stderr.take();
+    let (mut stdout_buf, mut stderr_buf) = (Vec::new(), Vec::new());
+    let mut truncated = false;
+    let mut stderr_truncated = false;
+
+    let run = async {
+        let read_stdout = async {
+            match stdout {
+                Some(stdout) => read_capped(stdout, &mut stdout_buf, &mut truncated).await,
+                None => Ok(()),
+            }
+        };
+        let read_stderr = async {
+            match stderr {
+                Some(stderr) => read_capped(stderr, &mut stderr_buf, &mut stderr_truncated).await,
+                None => Ok(()),
+            }
+        };
+        let (stdout, stderr, status) = tokio::join!(read_stdout, read_stderr, child.wait());
+        stdout?;
+        stderr?;
+        status
+    };
+    let (exit_code, timed_out) =
+        match tokio::time::timeout(Duration::from_secs(timeout_secs), run).await {
+            Ok(status) => (status?.code(), false),
+            Err(_) => {
+                kill_process_group(&mut child).await?;
+                (None, true)
+            }
+        };
+
+    let result = ExecResult {
+        exit_code,
+        stdout: String::from_utf8_lossy(&stdout_buf).into_owned(),
+        stderr: String::from_utf8_lossy(&stderr_buf).into_owned(),
+        timed_out,
+        truncated: truncated || stderr_truncated,
+        duration_ms: started.elapsed().as_millis() as u64,
+    };
+
+    audit::record(
+        &deployment,
+        &actor,
+        AuditAction::CommandExecuted,
+        Some(workspace_id),
+        None,
+        Some(serde_json::json!({
+            "command": &payload.command,
+            "cwd": working_dir.to_string_lossy(),
+            "exit_code": result.exit_code,
+            "timed_out": result.timed_out,
+        })),
+    )
+    .await;
+
+    Ok(ResponseJson(ApiResponse::success(result)))
+}
+
+async fn get_dev_server_target(
+    State(deployment): State<DeploymentImpl>,
+    Path(workspace_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Option<DevServerTarget>>>, ApiError> {
+    let target = ExecutionProcess::find_running_dev_server_target_for_workspace(
+        &deployment.db().pool,
+        workspace_id,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(target)))
+}
+
+async fn proxy_workspace_preview_no_tail(
+    State(deployment): State<DeploymentImpl>,
+    Path(workspace_id): Path<Uuid>,
+    ws_upgrade: Result<SignedWsUpgrade, WebSocketUpgradeRejection>,
+    request: Request,
+) -> Response {
+    proxy_workspace_preview(deployment, workspace_id, String::new(), ws_upgrade, request).await
+}
+
+async fn proxy_workspace_preview_with_tail(
+    State(deployment): State<DeploymentImpl>,
+    Path((workspace_id, tail)): Path<(Uuid, String)>,
+    ws_upgrade: Result<SignedWsUpgrade, WebSocketUpgradeRejection>,
+    request: Request,
+) -> Response {
+    proxy_workspace_preview(deployment, workspace_id, tail, ws_upgrade, request).await
+}
+
+/// Proxy to the port announced by the workspace's running dev server.
+async fn proxy_workspace_preview(
+    deployment: DeploymentImpl,
+    workspace_id: Uuid,
+    tail: String,
+    ws_upgrade: Result<SignedWsUpgrade, WebSocketUpgradeRejection>,
+    request: Request,
+) -> Response {
+    let target = match ExecutionProcess::find_running_dev_server_target_for_workspace(
+        &deployment.db().pool,
+        workspace_id,
+    )
+    .await
+    {
+        Ok(Some(target)) => target,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                "No running dev server URL detected for this workspace",
+            )
+                .into_response();
+        }
+        Err(e) => return ApiError::Database(e).into_response(),
+    };
+    proxy_to_port(&deployment, target.port, tail, ws_upgrade, request).await
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkspaceTreeQuery {
+    /// Directory to list, relative to the workspace directory. Defaults to
+    /// the workspace directory itself.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Include gitignored entries, flagged as ignored.
+    #[serde(default)]
+    pub include_ignored: bool,
+}
+
+/// List one level of the workspace directory tree. Directories are expanded
+/// by requesting them as `path`.
+async fn get_workspace_tree(
+    State(deployment): State<DeploymentImpl>,
+    Path(workspace_id): Path<Uuid>,
+    Query(query): Query<WorkspaceTreeQuery>,
+) -> Result<ResponseJson<ApiResponse<FileTreeListing>>, ApiError> {
+    let (base_dir, _) = workspace_dirs(&deployment, workspace_id).await?;
+    let requested = query.path.as_deref().unwrap_or("").trim_matches('/');
+    let dir = resolve_cwd(
+        &base_dir,
+        if requested.is_empty() { "." } else { requested },
+    )?;
+    let base_dir = std::fs::canonicalize(&base_dir)?;
+    let relative = dir
+        .strip_prefix(&base_dir)
+        .unwrap_or(FsPath::new(""))
+        .to_string_lossy()
+        .replace('\\', "/");
+
+    // Only repos the listed directory is in or contains need their status.
+    let repos = WorkspaceRepo::find_repos_for_workspace(&deployment.db().pool, workspace_id)
+        .await?
+        .into_iter()
+        .filter(|repo| {
+            relative.is_empty()
+                || relative == repo.name
+                || relative.starts_with(&format!("{}/", repo.name))
+        })
+        .collect::<Vec<_>>();
+    let git = deployment.git().clone();
+    let listing = tokio::task::spawn_blocking(move || {
+        let mut statuses = GitStatusIndex::default();
+        for repo in &repos {
+            match git.get_worktree_status(&base_dir.join(&repo.name)) {
+                Ok(status) => statuses.add_repo(&repo.name, &status),
+                Err(e) => tracing::debug!("No git status for repo '{}': {}", repo.name, e),
+            }
+        }
+        file_tree::list_tree_level(&base_dir, &relative, query.include_ignored, &statuses)
+    })
+    .await
+    .map_err(std::io::Error::other)??;
+
+    Ok(ResponseJson(ApiResponse::success(listing)))
+}
+
 pub(super) fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     Router::new()
         // NOTE: /containers/info is required by the VSCode extension (vibe-kanban-vscode)
@@ -54,4 +485,30 @@ pub(super) fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         // Do not remove this endpoint without updating the extension.
         .route("/containers/info", get(get_container_info))
         .route("/containers/attempt-context", get(get_context))
+        .route(
+            "/containers/{workspace_id}/dev-server",
+            get(get_dev_server_target),
+        )
+        .route("/containers/{workspace_id}/exec", post(exec_in_workspace))
+        .route("/containers/{workspace_id}/tree", get(get_workspace_tree))
+        .route(
+            "/containers/{workspace_id}/dev-servers",
+            get(list_dev_servers),
+        )
+        .route(
+            "/containers/{workspace_id}/dev-servers/{dev_server_id}/start",
+            post(start_named_dev_server),
+        )
+        .route(
+            "/containers/{workspace_id}/dev-servers/{dev_server_id}/stop",
+            post(stop_named_dev_server_route),
+        )
+        .route(
+            "/containers/{workspace_id}/preview",
+            any(proxy_workspace_preview_no_tail),
+        )
+        .route(
+            "/containers/{workspace_id}/preview/{*tail}",
+            any(proxy_workspace_preview_with_tail),
+        )
 }
@@ -8,6 +8,7 @@ use axum::{
 };
 use chrono::{DateTime, Utc};
 use db::models::{
+    audit_log::AuditAction,
     coding_agent_turn::CodingAgentTurn,
     execution_process::{ExecutionProcess, ExecutionProcessStatus},
     execution_process_repo_state::ExecutionProcessRepoState,
@@ -17,16 +18,29 @@ use futures_util::{StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
 use services::services::container::ContainerService;
 use sqlx::SqlitePool;
-use utils::{log_msg::LogMsg, response::ApiResponse};
+use ts_rs::TS;
+use utils::{
+    execution_logs::{
+        ExecutionLogChunk, process_log_file_path, read_execution_log_lines_after,
+        read_execution_log_lines_before,
+    },
+    fields::FieldSelection,
+    log_msg::LogMsg,
+    response::ApiResponse,
+};
 use uuid::Uuid;
 
 use crate::{
     DeploymentImpl,
     error::ApiError,
     middleware::{
-        load_execution_process_middleware,
+        CurrentUser, load_execution_process_middleware,
         signed_ws::{MaybeSignedWebSocket, SignedWsUpgrade},
     },
+    routes::{
+        audit::{self, AuditActor},
+        users::ensure_can_control_process,
+    },
 };
 
 #[derive(Debug, Deserialize)]
@@ -37,6 +51,36 @@ struct SessionExecutionProcessQuery {
     pub show_soft_deleted: Option<bool>,
 }
 
+#[derive(Debug, Deserialize)]
+struct ListExecutionProcessesQuery {
+    pub session_id: Uuid,
+    #[serde(default)]
+    pub show_soft_deleted: Option<bool>,
+    /// Comma-separated fields to return, e.g. `status,executor_action.typ.type`.
+    /// All fields are returned when unset.
+    #[serde(default)]
+    pub fields: Option<String>,
+}
+
+/// List a session's execution processes. Clients that only show status can
+/// pass `fields` to skip the executor actions, which carry full prompts.
+async fn list_execution_processes(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ListExecutionProcessesQuery>,
+) -> Result<ResponseJson<ApiResponse<serde_json::Value>>, ApiError> {
+    let processes = ExecutionProcess::find_by_session_id(
+        &deployment.db().pool,
+        query.session_id,
+        query.show_soft_deleted.unwrap_or(false),
+    )
+    .await?;
+    let mut processes = serde_json::to_value(processes).map_err(std::io::Error::from)?;
+    if let Some(selection) = FieldSelection::parse(query.fields.as_deref()) {
+        selection.retain(&mut processes);
+    }
+    Ok(ResponseJson(ApiResponse::success(processes)))
+}
+
 async fn get_execution_process_by_id(
     Extension(execution_process): Extension<ExecutionProcess>,
     State(_deployment): State<DeploymentImpl>,
@@ -62,6 +106,130 @@ async fn get_execution_summary(
     )))
 }
 
+/// Default and maximum number of lines returned by one raw-log page.
+const RAW_LOG_PAGE_DEFAULT_LINES: usize = 1000;
+const RAW_LOG_PAGE_MAX_LINES: usize = 5000;
+/// Upper bound on bytes read from disk for one raw-log page.
+const RAW_LOG_PAGE_MAX_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Paging parameters for the raw-log endpoint. Offsets are byte positions in
+/// the persisted log and must come from a previous response (or be 0). With
+/// neither `after` nor `before`, the tail of the log is returned.
+#[derive(Debug, Deserialize, TS)]
+pub struct RawLogPageQuery {
+    /// Return lines starting at this byte offset.
+    #[ts(type = "number | null")]
+    pub after: Option<u64>,
+    /// Return lines ending just before this byte offset.
+    #[ts(type = "number | null")]
+    pub before: Option<u64>,
+    #[ts(type = "number | null")]
+    pub limit: Option<usize>,
+    /// Remove ANSI escape sequences from line content.
+    #[serde(default)]
+    pub strip_ansi: bool,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RawLogLine {
+    /// Byte offset of this line in the log; usable as `before`/`after`.
+    #[ts(type = "number")]
+    pub offset: u64,
+    pub stream: String,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RawLogPage {
+    pub lines: Vec<RawLogLine>,
+    #[ts(type = "number")]
+    pub start_offset: u64,
+    #[ts(type = "number")]
+    pub end_offset: u64,
+    /// Size of the log when it was read; grows while the process is running.
+    #[ts(type = "number")]
+    pub total_bytes: u64,
+    pub has_more_before: bool,
+    pub has_more_after: bool,
+    pub running: bool,
+}
+
+/// Serve one page of the persisted raw log without loading the whole file, so
+/// very long logs (e.g. setup scripts) can be rendered lazily.
+async fn get_raw_logs_page(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    Query(query): Query<RawLogPageQuery>,
+) -> Result<ResponseJson<ApiResponse<RawLogPage>>, ApiError> {
+    if query.after.is_some() && query.before.is_some() {
+        return Err(ApiError::BadRequest(
+            "Specify at most one of `after` and `before`".to_string(),
+        ));
+    }
+    let limit = query
+        .limit
+        .unwrap_or(RAW_LOG_PAGE_DEFAULT_LINES)
+        .clamp(1, RAW_LOG_PAGE_MAX_LINES);
+    let path = process_log_file_path(execution_process.session_id, execution_process.id);
+
+    let chunk = match query.after {
+        Some(after) => {
+            read_execution_log_lines_after(&path, after, limit, RAW_LOG_PAGE_MAX_BYTES).await
+        }
+        None => {
+            read_execution_log_lines_before(
+                &path,
+                query.before.unwrap_or(u64::MAX),
+                limit,
+                RAW_LOG_PAGE_MAX_BYTES,
+            )
+            .await
+        }
+    };
+    let chunk = match chunk {
+        Ok(chunk) => chunk,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => ExecutionLogChunk {
+            lines: Vec::new(),
+            start_offset: 0,
+            end_offset: 0,
+            file_len: 0,
+        },
+        Err(e) => return Err(ApiError::Io(e)),
+    };
+
+    let running = execution_process.status == ExecutionProcessStatus::Running;
+    let lines = chunk
+        .lines
+        .into_iter()
+        .filter_map(|line| {
+            let (stream, content) = match serde_json::from_str::<LogMsg>(&line.line).ok()? {
+                LogMsg::Stdout(content) => ("stdout", content),
+                LogMsg::Stderr(content) => ("stderr", content),
+                _ => return None,
+            };
+            let content = if query.strip_ansi {
+                strip_ansi_escapes::strip_str(&content)
+            } else {
+                content
+            };
+            Some(RawLogLine {
+                offset: line.offset,
+                stream: stream.to_string(),
+                content,
+            })
+        })
+        .collect();
+
+    Ok(ResponseJson(ApiResponse::success(RawLogPage {
+        lines,
+        start_offset: chunk.start_offset,
+        end_offset: chunk.end_offset,
+        total_bytes: chunk.file_len,
+        has_more_before: chunk.start_offset > 0,
+        has_more_after: running || chunk.end_offset < chunk.file_len,
+        running,
+    })))
+}
+
 async fn stream_raw_logs_ws(
     ws: SignedWsUpgrade,
     State(deployment): State<DeploymentImpl>,
@@ -224,12 +392,28 @@ async fn handle_normalized_logs_ws(
 async fn stop_execution_process(
     Extension(execution_process): Extension<ExecutionProcess>,
     State(deployment): State<DeploymentImpl>,
+    actor: AuditActor,
+    current: CurrentUser,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ensure_can_control_process(&deployment, &current, execution_process.id).await?;
     deployment
         .container()
         .stop_execution(&execution_process, ExecutionProcessStatus::Killed)
         .await?;
 
+    let workspace_id = audit::workspace_id_for_process(&deployment, execution_process.id).await;
+    audit::record(
+        &deployment,
+        &actor,
+        AuditAction::ProcessKilled,
+        workspace_id,
+        Some(execution_process.id),
+        Some(serde_json::json!({
+            "run_reason": execution_process.run_reason,
+        })),
+    )
+    .await;
+
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
@@ -470,6 +654,7 @@ pub(super) fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/stop", post(stop_execution_process))
         .route("/repo-states", get(get_execution_process_repo_states))
         .route("/summary", get(get_execution_summary))
+        .route("/raw-logs", get(get_raw_logs_page))
         .route("/raw-logs/ws", get(stream_raw_logs_ws))
         .route("/normalized-logs/ws", get(stream_normalized_logs_ws))
         .layer(from_fn_with_state(
@@ -478,6 +663,7 @@ pub(super) fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         ));
 
     let workspaces_router = Router::new()
+        .route("/", get(list_execution_processes))
         .route("/wait", post(wait_for_executions))
         .route(
             "/stream/session/ws",
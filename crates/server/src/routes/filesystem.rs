@@ -1,13 +1,22 @@
+use std::path::PathBuf;
+
 use axum::{
-    Router,
-    extract::{Query, State},
+    Json, Router,
+    extract::{DefaultBodyLimit, Path, Query, State},
     response::Json as ResponseJson,
     routing::get,
 };
+use db::models::workspace::{Workspace, WorkspaceError};
 use deployment::Deployment;
 use serde::Deserialize;
-use services::services::filesystem::{DirectoryEntry, DirectoryListResponse, FilesystemError};
+use services::services::{
+    filesystem::{DirectoryEntry, DirectoryListResponse, FilesystemError},
+    workspace_files::{
+        self, MAX_WORKSPACE_FILE_BYTES, PatchWorkspaceFile, WorkspaceFile, WriteWorkspaceFile,
+    },
+};
 use utils::response::ApiResponse;
+use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError};
 
@@ -71,8 +80,64 @@ pub async fn list_git_repos(
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub struct WorkspaceFileQuery {
+    path: String,
+}
+
+async fn workspace_root(
+    deployment: &DeploymentImpl,
+    workspace_id: Uuid,
+) -> Result<PathBuf, ApiError> {
+    let workspace = Workspace::find_by_id(&deployment.db().pool, workspace_id)
+        .await?
+        .ok_or(WorkspaceError::WorkspaceNotFound)?;
+    let container_ref = workspace
+        .container_ref
+        .ok_or_else(|| ApiError::BadRequest("Workspace has no directory".to_string()))?;
+    Ok(PathBuf::from(container_ref))
+}
+
+pub async fn read_workspace_file(
+    State(deployment): State<DeploymentImpl>,
+    Path(workspace_id): Path<Uuid>,
+    Query(query): Query<WorkspaceFileQuery>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceFile>>, ApiError> {
+    let root = workspace_root(&deployment, workspace_id).await?;
+    let file = workspace_files::read_file(&root, &query.path).await?;
+    Ok(ResponseJson(ApiResponse::success(file)))
+}
+
+pub async fn write_workspace_file(
+    State(deployment): State<DeploymentImpl>,
+    Path(workspace_id): Path<Uuid>,
+    Json(payload): Json<WriteWorkspaceFile>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceFile>>, ApiError> {
+    let root = workspace_root(&deployment, workspace_id).await?;
+    let file = workspace_files::write_file(&root, payload).await?;
+    Ok(ResponseJson(ApiResponse::success(file)))
+}
+
+pub async fn patch_workspace_file(
+    State(deployment): State<DeploymentImpl>,
+    Path(workspace_id): Path<Uuid>,
+    Json(payload): Json<PatchWorkspaceFile>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceFile>>, ApiError> {
+    let root = workspace_root(&deployment, workspace_id).await?;
+    let file = workspace_files::patch_file(&root, payload).await?;
+    Ok(ResponseJson(ApiResponse::success(file)))
+}
+
 pub fn router() -> Router<DeploymentImpl> {
     Router::new()
         .route("/filesystem/directory", get(list_directory))
         .route("/filesystem/git-repos", get(list_git_repos))
+        .route(
+            "/filesystem/workspaces/{workspace_id}/file",
+            get(read_workspace_file)
+                .put(write_workspace_file)
+                .patch(patch_workspace_file)
+                // Leave room for JSON escaping of a file at the size limit.
+                .layer(DefaultBodyLimit::max(2 * MAX_WORKSPACE_FILE_BYTES as usize)),
+        )
 }
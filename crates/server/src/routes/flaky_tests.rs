@@ -0,0 +1,267 @@
+use axum::{
+    Json, Router,
+    body::Bytes,
+    extract::{DefaultBodyLimit, Path, Query, State},
+    response::Json as ResponseJson,
+    routing::{get, post, put},
+};
+use db::models::{
+    flaky_test::{
+        FlakyTest, FlakyTestError as FlakyTestModelError, FlakyTestSettings,
+        UpsertFlakyTestSettings,
+    },
+    requests::{CreateAndStartWorkspaceRequest, WorkspaceRepoInput},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::flaky_tests::{
+    self, FlakyTestError, SavedFlakyTestSettings, TestReportSummary,
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{CurrentUser, ensure_admin},
+    routes::workspaces::create::create_and_start,
+};
+
+const DEFAULT_LIST_LIMIT: i64 = 100;
+const MAX_LIST_LIMIT: i64 = 1000;
+/// JUnit reports of large suites run to several megabytes.
+const MAX_REPORT_BYTES: usize = 20 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+pub struct ListFlakyTestsQuery {
+    #[serde(default)]
+    pub quarantined: bool,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateFlakyTest {
+    pub quarantined: bool,
+}
+
+#[derive(Debug, Default, Deserialize, TS)]
+pub struct FixFlakyTestRequest {
+    /// Test to fix; the flakiest quarantined test nobody is fixing yet if
+    /// left out.
+    #[serde(default)]
+    #[ts(optional)]
+    pub test_id: Option<Uuid>,
+}
+
+/// A repo's tracked tests, flakiest first.
+pub async fn list_flaky_tests(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    Query(query): Query<ListFlakyTestsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<FlakyTest>>>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_LIST_LIMIT)
+        .clamp(1, MAX_LIST_LIMIT);
+    let tests =
+        FlakyTest::find_by_repo_id(&deployment.db().pool, repo_id, query.quarantined, limit)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(tests)))
+}
+
+/// Quarantine a test by hand, or release it.
+pub async fn update_flaky_test(
+    State(deployment): State<DeploymentImpl>,
+    Path(test_id): Path<Uuid>,
+    Json(payload): Json<UpdateFlakyTest>,
+) -> Result<ResponseJson<ApiResponse<FlakyTest>>, ApiError> {
+    let pool = &deployment.db().pool;
+    FlakyTest::set_quarantined(pool, test_id, payload.quarantined)
+        .await
+        .map_err(FlakyTestError::from)?;
+    let test = FlakyTest::find_by_id(pool, test_id)
+        .await?
+        .ok_or(FlakyTestError::from(FlakyTestModelError::NotFound))?;
+    Ok(ResponseJson(ApiResponse::success(test)))
+}
+
+/// Stop tracking a test, e.g. once its fix is merged.
+pub async fn delete_flaky_test(
+    State(deployment): State<DeploymentImpl>,
+    Path(test_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if FlakyTest::delete(&deployment.db().pool, test_id).await? == 0 {
+        return Err(FlakyTestError::from(FlakyTestModelError::NotFound).into());
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn get_flaky_test_settings(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Option<FlakyTestSettings>>>, ApiError> {
+    let settings = FlakyTestSettings::find_by_repo_id(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+/// The report token lets CI start agents when auto-fix is on, so only admins
+/// set it up. The token is returned when the settings are first saved.
+pub async fn save_flaky_test_settings(
+    State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
+    Path(repo_id): Path<Uuid>,
+    Json(payload): Json<UpsertFlakyTestSettings>,
+) -> Result<ResponseJson<ApiResponse<SavedFlakyTestSettings>>, ApiError> {
+    ensure_admin(&deployment, &current, "manage flaky test tracking").await?;
+    deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+    let saved = flaky_tests::save_settings(&deployment.db().pool, repo_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(saved)))
+}
+
+/// Issue a new report token, revoking the old one.
+pub async fn rotate_flaky_test_token(
+    State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<SavedFlakyTestSettings>>, ApiError> {
+    ensure_admin(&deployment, &current, "manage flaky test tracking").await?;
+    let rotated = flaky_tests::rotate_token(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(rotated)))
+}
+
+/// Start a workspace whose agent fixes `test`, and link it to the test so
+/// its pull request shows up on the record.
+async fn start_fix(
+    deployment: &DeploymentImpl,
+    settings: &FlakyTestSettings,
+    test: &FlakyTest,
+) -> Result<Uuid, ApiError> {
+    let request = CreateAndStartWorkspaceRequest {
+        name: Some(format!("Fix flaky test: {}", test.name)),
+        repos: vec![WorkspaceRepoInput {
+            repo_id: test.repo_id,
+            target_branch: settings.target_branch.clone(),
+            package_id: None,
+        }],
+        linked_issue: None,
+        executor_config: settings.executor_config.clone(),
+        prompt: flaky_tests::build_fix_prompt(test),
+        attachment_ids: None,
+        idempotency_key: Some(format!("flaky-test:{}", test.id)),
+        env_profile_ids: None,
+    };
+    let response = create_and_start(deployment, request).await?;
+    FlakyTest::set_fix_workspace(&deployment.db().pool, test.id, response.workspace.id).await?;
+    Ok(response.workspace.id)
+}
+
+/// Start an agent on a flaky test, by default the flakiest one waiting.
+pub async fn fix_flaky_test(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    Json(payload): Json<FixFlakyTestRequest>,
+) -> Result<ResponseJson<ApiResponse<FlakyTest>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let settings = FlakyTestSettings::find_by_repo_id(pool, repo_id)
+        .await?
+        .ok_or(FlakyTestError::from(FlakyTestModelError::SettingsNotFound))?;
+    let test = flaky_tests::pick_test_to_fix(pool, repo_id, payload.test_id).await?;
+    start_fix(&deployment, &settings, &test).await?;
+    let test = FlakyTest::find_by_id(pool, test.id)
+        .await?
+        .ok_or(FlakyTestError::from(FlakyTestModelError::NotFound))?;
+    Ok(ResponseJson(ApiResponse::success(test)))
+}
+
+/// Record a report, then start an agent on the flakiest test if auto-fix is
+/// on and no other fix is in progress.
+async fn ingest_report(
+    deployment: &DeploymentImpl,
+    repo_id: Uuid,
+    body: &[u8],
+) -> Result<TestReportSummary, ApiError> {
+    let pool = &deployment.db().pool;
+    let results = flaky_tests::parse_report(body)?;
+    let summary = flaky_tests::ingest(pool, repo_id, &results).await?;
+
+    let Some(settings) = FlakyTestSettings::find_by_repo_id(pool, repo_id)
+        .await?
+        .filter(|settings| settings.auto_fix)
+    else {
+        return Ok(summary);
+    };
+    if FlakyTest::has_fix_in_progress(pool, repo_id).await? {
+        return Ok(summary);
+    }
+    let Some(test) = FlakyTest::find_flakiest_unfixed(pool, repo_id).await? else {
+        return Ok(summary);
+    };
+    tracing::info!("Starting agent to fix flaky test {}", test.name);
+    let background = deployment.clone();
+    tokio::spawn(async move {
+        if let Err(e) = start_fix(&background, &settings, &test).await {
+            tracing::warn!("Failed to start fix for flaky test {}: {}", test.id, e);
+        }
+    });
+    Ok(summary)
+}
+
+/// Upload a test report, as JUnit XML or JSON.
+pub async fn upload_test_report(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    body: Bytes,
+) -> Result<ResponseJson<ApiResponse<TestReportSummary>>, ApiError> {
+    deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+    let summary = ingest_report(&deployment, repo_id, &body).await?;
+    Ok(ResponseJson(ApiResponse::success(summary)))
+}
+
+/// CI's upload URL. The token names the repo.
+pub async fn report_webhook(
+    State(deployment): State<DeploymentImpl>,
+    Path(token): Path<String>,
+    body: Bytes,
+) -> Result<ResponseJson<ApiResponse<TestReportSummary>>, ApiError> {
+    let settings = flaky_tests::resolve_token(&deployment.db().pool, &token).await?;
+    let summary = ingest_report(&deployment, settings.repo_id, &body).await?;
+    Ok(ResponseJson(ApiResponse::success(summary)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/repos/{repo_id}/flaky-tests", get(list_flaky_tests))
+        .route(
+            "/repos/{repo_id}/flaky-tests/settings",
+            get(get_flaky_test_settings).put(save_flaky_test_settings),
+        )
+        .route(
+            "/repos/{repo_id}/flaky-tests/settings/rotate-token",
+            post(rotate_flaky_test_token),
+        )
+        .route("/repos/{repo_id}/flaky-tests/fix", post(fix_flaky_test))
+        .route(
+            "/repos/{repo_id}/test-reports",
+            post(upload_test_report).layer(DefaultBodyLimit::max(MAX_REPORT_BYTES)),
+        )
+        .route(
+            "/flaky-tests/{test_id}",
+            put(update_flaky_test).delete(delete_flaky_test),
+        )
+}
+
+/// CI uploads reports from other machines, so like the other inbound
+/// webhooks this sits outside the relay signature and origin checks.
+pub fn hooks_router() -> Router<DeploymentImpl> {
+    Router::new().route(
+        "/hooks/test-reports/{token}",
+        post(report_webhook).layer(DefaultBodyLimit::max(MAX_REPORT_BYTES)),
+    )
+}
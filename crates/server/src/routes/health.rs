@@ -1,6 +1,35 @@
-use axum::response::Json;
+use axum::{extract::State, response::Json};
+use db::write_queue::WriteQueueStats;
+use deployment::Deployment;
+use git::RepoLockStats;
+use services::services::{container::ContainerService, startup_tasks::StartupStatus};
 use utils::response::ApiResponse;
 
+use crate::DeploymentImpl;
+
 pub(super) async fn health_check() -> Json<ApiResponse<String>> {
     Json(ApiResponse::success("OK".to_string()))
 }
+
+/// Progress of the migrations and backfills still running after startup.
+pub(super) async fn health_detail(
+    State(deployment): State<DeploymentImpl>,
+) -> Json<ApiResponse<StartupStatus>> {
+    Json(ApiResponse::success(
+        deployment.container().startup_tasks().status(),
+    ))
+}
+
+/// How long git operations have waited for each repository's lock.
+pub(super) async fn health_git_locks(
+    State(deployment): State<DeploymentImpl>,
+) -> Json<ApiResponse<Vec<RepoLockStats>>> {
+    Json(ApiResponse::success(deployment.git().repo_lock_stats()))
+}
+
+/// Depth of the database write queue and how long writes waited in it.
+pub(super) async fn health_db_writes(
+    State(deployment): State<DeploymentImpl>,
+) -> Json<ApiResponse<WriteQueueStats>> {
+    Json(ApiResponse::success(deployment.db().write_queue_stats()))
+}
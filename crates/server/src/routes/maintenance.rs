@@ -0,0 +1,57 @@
+use axum::{
+    Json, Router,
+    extract::State,
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use serde::Deserialize;
+use services::services::maintenance::MaintenanceStatus;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, middleware::require_admin};
+
+const DEFAULT_REASON: &str = "Maintenance in progress";
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SetMaintenanceRequest {
+    pub enabled: bool,
+    /// Returned with every rejected request.
+    pub reason: Option<String>,
+}
+
+pub async fn get_maintenance(
+    State(deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<MaintenanceStatus>> {
+    ResponseJson(ApiResponse::success(deployment.maintenance().status()))
+}
+
+pub async fn set_maintenance(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetMaintenanceRequest>,
+) -> ResponseJson<ApiResponse<MaintenanceStatus>> {
+    let maintenance = deployment.maintenance();
+    if payload.enabled {
+        let reason = payload
+            .reason
+            .map(|reason| reason.trim().to_string())
+            .filter(|reason| !reason.is_empty())
+            .unwrap_or_else(|| DEFAULT_REASON.to_string());
+        maintenance.enable(reason);
+    } else {
+        maintenance.disable();
+    }
+    ResponseJson(ApiResponse::success(maintenance.status()))
+}
+
+/// Stays reachable in maintenance mode, so it can be turned off again.
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let admin_router = Router::new()
+        .route("/maintenance", put(set_maintenance))
+        .route_layer(from_fn_with_state(deployment.clone(), require_admin));
+
+    Router::new()
+        .route("/maintenance", get(get_maintenance))
+        .merge(admin_router)
+}
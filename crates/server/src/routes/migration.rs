@@ -0,0 +1,53 @@
+use axum::{
+    Json, Router, extract::State, middleware::from_fn_with_state, response::Json as ResponseJson,
+    routing::post,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::backup::{self, BackupInfo};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::require_admin};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct RestoreBackupRequest {
+    /// Path to a backup archive on the machine running the server.
+    pub path: String,
+}
+
+pub async fn create_backup(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<BackupInfo>>, ApiError> {
+    let _maintenance = deployment.maintenance().begin("Creating a backup");
+    let info = backup::create_backup(&deployment.db().pool).await?;
+    tracing::info!(
+        "Created backup at {} ({} bytes)",
+        info.path,
+        info.size_bytes
+    );
+    Ok(ResponseJson(ApiResponse::success(info)))
+}
+
+/// Validate a backup and stage it; it is applied the next time the server
+/// starts.
+pub async fn restore_backup(
+    Json(payload): Json<RestoreBackupRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let path = utils::path::expand_tilde(&payload.path);
+    if !path.is_file() {
+        return Err(ApiError::BadRequest(format!(
+            "Backup archive not found: {}",
+            path.display()
+        )));
+    }
+    backup::stage_restore(path).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/migration/backup", post(create_backup))
+        .route("/migration/restore", post(restore_backup))
+        .route_layer(from_fn_with_state(deployment.clone(), require_admin))
+}
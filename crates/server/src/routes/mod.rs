@@ -6,10 +6,16 @@ use tower_http::{compression::CompressionLayer, validate_request::ValidateReques
 
 use crate::{DeploymentImpl, middleware};
 
+pub mod approval_links;
 pub mod approvals;
+pub mod attempt_groups;
+pub mod audit;
+pub mod ci_triage;
+pub mod code_owners;
 pub mod config;
 pub mod containers;
 pub mod filesystem;
+pub mod flaky_tests;
 // pub mod github;
 pub mod attachments;
 pub mod events;
@@ -17,26 +23,37 @@ pub mod execution_processes;
 pub mod frontend;
 pub mod health;
 pub mod host_relay;
+pub mod maintenance;
+pub mod migration;
 pub mod oauth;
 pub mod organizations;
+pub mod pipelines;
 pub mod preview;
 pub mod relay_auth;
 pub mod releases;
 pub mod remote;
 pub mod repo;
+pub mod schedules;
 pub mod scratch;
+pub mod script_library;
 pub mod search;
 pub mod sessions;
+pub mod shared_sessions;
 pub mod ssh_session;
 pub mod tags;
 pub mod terminal;
+pub mod users;
+pub mod webhook_triggers;
 pub mod webrtc;
 pub mod workspaces;
 
 pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
     let relay_signed_routes = Router::new()
         .route("/health", get(health::health_check))
-        .merge(config::router())
+        .route("/health/detail", get(health::health_detail))
+        .route("/health/git-locks", get(health::health_git_locks))
+        .route("/health/db-writes", get(health::health_db_writes))
+        .merge(config::router(&deployment))
         .merge(containers::router(&deployment))
         .merge(workspaces::router(&deployment))
         .merge(execution_processes::router(&deployment))
@@ -45,8 +62,18 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(organizations::router())
         .merge(filesystem::router())
         .merge(repo::router())
+        .merge(script_library::router())
+        .merge(schedules::router())
+        .merge(webhook_triggers::router())
+        .merge(ci_triage::router())
+        .merge(flaky_tests::router())
+        .merge(code_owners::router())
+        .merge(pipelines::router())
+        .merge(attempt_groups::router())
         .merge(events::router(&deployment))
-        .merge(approvals::router())
+        .merge(approvals::router(&deployment))
+        .merge(audit::router(&deployment))
+        .merge(users::router())
         .merge(scratch::router(&deployment))
         .merge(search::router(&deployment))
         .merge(preview::api_router())
@@ -57,6 +84,17 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .nest("/remote", remote::router())
         .merge(webrtc::router())
         .nest("/attachments", attachments::routes())
+        .layer(axum::middleware::from_fn_with_state(
+            deployment.clone(),
+            middleware::reject_writes_in_maintenance,
+        ))
+        // Maintenance and backups work while maintenance mode is on.
+        .merge(maintenance::router(&deployment))
+        .merge(migration::router(&deployment))
+        .layer(axum::middleware::from_fn_with_state(
+            deployment.clone(),
+            middleware::resolve_user_session,
+        ))
         .layer(axum::middleware::from_fn_with_state(
             deployment.clone(),
             middleware::sign_relay_response,
@@ -67,6 +105,24 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         ))
         .with_state(deployment.clone());
 
+    // Inbound webhooks start runs, so maintenance mode holds them off too.
+    let hook_routes = webhook_triggers::hooks_router()
+        .merge(ci_triage::hooks_router())
+        .merge(flaky_tests::hooks_router())
+        .layer(axum::middleware::from_fn_with_state(
+            deployment.clone(),
+            middleware::reject_writes_in_maintenance,
+        ));
+
+    // Approval links are opened from other devices, possibly through a proxy
+    // that rewrites the host, so they skip the origin check. The signed token
+    // in the path authenticates them. Session share links and inbound
+    // webhooks work the same way.
+    let approval_link_routes = approval_links::router()
+        .merge(shared_sessions::router())
+        .merge(hook_routes)
+        .with_state(deployment.clone());
+
     let api_routes = Router::new()
         .merge(relay_auth::router())
         .merge(host_relay::router(&deployment))
@@ -80,7 +136,7 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
     Router::new()
         .route("/", get(frontend::serve_frontend_root))
         .route("/{*path}", get(frontend::serve_frontend))
-        .nest("/api", api_routes)
+        .nest("/api", api_routes.merge(approval_link_routes))
         .layer(CompressionLayer::new())
         .into_make_service()
 }
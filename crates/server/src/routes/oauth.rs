@@ -5,7 +5,7 @@ use api_types::{
 use axum::{
     Router,
     extract::{Json, Query, State},
-    http::{Response, StatusCode},
+    http::{Response, StatusCode, header},
     response::Json as ResponseJson,
     routing::{get, post},
 };
@@ -14,22 +14,31 @@ use deployment::Deployment;
 use rand::{Rng, distributions::Alphanumeric};
 use serde::{Deserialize, Serialize};
 use services::services::{
-    config::save_config_to_file, oauth_credentials::Credentials, remote_sync,
+    config::save_config_to_file,
+    oauth_credentials::Credentials,
+    remote_sync,
+    users::{self, UserError},
 };
 use sha2::{Digest, Sha256};
 use ts_rs::TS;
 use utils::{assets::config_path, jwt::extract_expiration, response::ApiResponse};
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, runtime::relay_registration};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{CurrentUser, ensure_admin},
+    routes::users::{SESSION_COOKIE_MAX_AGE_SECS, session_cookie},
+    runtime::relay_registration,
+};
 
 /// Base64-encoded 32x32 app icon (from `crates/tauri-app/icons/32x32.png`).
-const APP_ICON_BASE64: &str = "iVBORw0KGgoAAAANSUhEUgAAACAAAAAgCAYAAABzenr0AAAABGdBTUEAALGPC/xhBQAAACBjSFJNAAB6JgAAgIQAAPoAAACA6AAAdTAAAOpgAAA6mAAAF3CculE8AAAAeGVYSWZNTQAqAAAACAAEARoABQAAAAEAAAA+ARsABQAAAAEAAABGASgAAwAAAAEAAgAAh2kABAAAAAEAAABOAAAAAAAAASAAAAABAAABIAAAAAEAA6ABAAMAAAABAAEAAKACAAQAAAABAAAAIKADAAQAAAABAAAAIAAAAAA5NwgRAAAACXBIWXMAACxLAAAsSwGlPZapAAABWWlUWHRYTUw6Y29tLmFkb2JlLnhtcAAAAAAAPHg6eG1wbWV0YSB4bWxuczp4PSJhZG9iZTpuczptZXRhLyIgeDp4bXB0az0iWE1QIENvcmUgNi4wLjAiPgogICA8cmRmOlJERiB4bWxuczpyZGY9Imh0dHA6Ly93d3cudzMub3JnLzE5OTkvMDIvMjItcmRmLXN5bnRheC1ucyMiPgogICAgICA8cmRmOkRlc2NyaXB0aW9uIHJkZjphYm91dD0iIgogICAgICAgICAgICB4bWxuczp4bXA9Imh0dHA6Ly9ucy5hZG9iZS5jb20veGFwLzEuMC8iPgogICAgICAgICA8eG1wOkNyZWF0b3JUb29sPkZpZ21hPC94bXA6Q3JlYXRvclRvb2w+CiAgICAgIDwvcmRmOkRlc2NyaXB0aW9uPgogICA8L3JkZjpSREY+CjwveDp4bXBtZXRhPgoE/1zIAAAFUElEQVRYCe1Vy2tcVRj/3cfcmZt5ZPKibRK1bVrpg1YplIq0vhAqVkEqVVxapNpF/wGhO3cuXCmI4tpSXIkLi9KHm1KktVXsC5omNWk6ycRkJjN35r6Ov+/eO5mZDoIbySaHOXPvPb/vfN/vfK+jlT7eFQLQONdkmFBrZ1xOLATWdKwTWPdArwcCP05KjZWpG70JGgaASjJXcJHrXDNkT0dVd2Fmj75uApoOY2RrZFj5DYSLM9SltzfRsF7YCC2T45pCILjfhF4cg2bZXAoRlB/EhIQYhz4wDi2VIRYkGNtOggneJiCb7QIGTn4DIz8Ed/YOlr48Ad1ZFrloBG4TmcOnkNv/pthH+auT8G5eQv/R07B3PA/lNlD+4jgw82ck7wcBiu98gszEPoSNFZQ/fx/a3N1EW/zQoz4gHuX0xLuGBc20EGb6Ee58ld6mBxI8CBR8zYxwGCbC7Yfg5TYgUFq0JnuDHa9A2QPQGErfcRBAjzA9W4z0hekCPUQvJDo7PBCTaMXXr1fQqK3AZjwNEZYRbYo/FA3UF0vUJQTjtdB34SwtIB0qWAzLwLEPkd64BcvTtzF74SysxhL6eAC9pY8quzwQGYgsSZg0gi2jjz0TGV1i2aFMoq4JGc+Fmyogd/BdpPpH0Jy5A0z9hky2wFQQN7f19XogUb76aAnLQoexCH/sW9N1pArDTOTNCPMjzMmQOerQhxr6RsZRu/IDcmEtTuxkbw+BVZ3y0jlbBLoEOmT4qmey2HTkBLTXP+AXzeoabn59GurRPQw/sYUhWIRpWV0HaROQS5mblBbXfuD7CFwXCKRsCIlrmyxNxjkaZhohk49/FAkh2+XEhpmKYOHp+x6Kg8NSa/BKD2BL31g9QCTWUYZivFHH3JVzyD+9D5mhURRfOAbv0S1gfgoqN4js66dgbtuH5UczmP35DIylOZgkHJIkCwQBCd47/x28yiLSgxsx/uJbyB94A7VfzkLdvQw9zwp4jEA7CUnAbNZRu/w9nPlZGIUh2LsPQu09DJXJw2U5mofeQ2psGxoPJ+H9cRFW2o5OHbLbBSThOTU4V3+CunMZ7uR1klLom9iL7P4jSG99lgy7E1DItAnwI2WaMCev8dTTaDp1ePUVONkRBJt2QPVvgN9w0KxWGZom+kZJ5Pp5YImlyFAEVC5hy45vg1Vn85qfhlNZhks9DhuMO7qbUZSQSazas50DssiyKsBD+eIZrNQdjL38NorPHQEOvBZt0llG97/9DM0bFzC4fQ8KTpn6aFiyXQjI9DzYzt/wGLZbp4/iyY8+RWH7M/CHR+FOXYe5MEVdJMKfDD1i1GLFp8HLJFudg1GZh2JHZPECdj/Qx86om8gV+jEwsZv3xBw3M9OpjDcEFDFlpHhKfjHZ8kaI8WYJRuhB8S5Qdh6NnS8hsHiPMHFbnmh7ICZEZjoyThW1qz9ifvMeOLOTZCwMaUya0/x9pGVO34Bh25Gu6t1rWCwvwJ+bhFWrROcSYiaJVG9fQ/nhXwiYN2alBIudMKqzJAy9BAiYzIV8bQFLv56DqpTZOoM4eXk6c2gTLJZanxWXm0FS4e+X4A09Ra+VYI5NsNuRsOjhX8hw+cVRGNUFWGNb250wObBWOr5LuPQMOXPFzCFkvUvHjTTSCyHdl3GryCrpBwIo1LUUHCtP1zMUvCNybgVWRFkwCw1i0pQUkzRPLKW1TfZ6QGxxyD1QoLBqRp9df9EdELPiugZbeUjXy5GMUBIi8SAWusQWurG2/c5GlOzpeOhCI8nWjuX4tUOJ9HoJxer4j5jI/6sHVpX9zy/rBNY9sOYe+AcCwIEbenVoBQAAAABJRU5ErkJggg==";
+pub(super) const APP_ICON_BASE64: &str = "iVBORw0KGgoAAAANSUhEUgAAACAAAAAgCAYAAABzenr0AAAABGdBTUEAALGPC/xhBQAAACBjSFJNAAB6JgAAgIQAAPoAAACA6AAAdTAAAOpgAAA6mAAAF3CculE8AAAAeGVYSWZNTQAqAAAACAAEARoABQAAAAEAAAA+ARsABQAAAAEAAABGASgAAwAAAAEAAgAAh2kABAAAAAEAAABOAAAAAAAAASAAAAABAAABIAAAAAEAA6ABAAMAAAABAAEAAKACAAQAAAABAAAAIKADAAQAAAABAAAAIAAAAAA5NwgRAAAACXBIWXMAACxLAAAsSwGlPZapAAABWWlUWHRYTUw6Y29tLmFkb2JlLnhtcAAAAAAAPHg6eG1wbWV0YSB4bWxuczp4PSJhZG9iZTpuczptZXRhLyIgeDp4bXB0az0iWE1QIENvcmUgNi4wLjAiPgogICA8cmRmOlJERiB4bWxuczpyZGY9Imh0dHA6Ly93d3cudzMub3JnLzE5OTkvMDIvMjItcmRmLXN5bnRheC1ucyMiPgogICAgICA8cmRmOkRlc2NyaXB0aW9uIHJkZjphYm91dD0iIgogICAgICAgICAgICB4bWxuczp4bXA9Imh0dHA6Ly9ucy5hZG9iZS5jb20veGFwLzEuMC8iPgogICAgICAgICA8eG1wOkNyZWF0b3JUb29sPkZpZ21hPC94bXA6Q3JlYXRvclRvb2w+CiAgICAgIDwvcmRmOkRlc2NyaXB0aW9uPgogICA8L3JkZjpSREY+CjwveDp4bXBtZXRhPgoE/1zIAAAFUElEQVRYCe1Vy2tcVRj/3cfcmZt5ZPKibRK1bVrpg1YplIq0vhAqVkEqVVxapNpF/wGhO3cuXCmI4tpSXIkLi9KHm1KktVXsC5omNWk6ycRkJjN35r6Ov+/eO5mZDoIbySaHOXPvPb/vfN/vfK+jlT7eFQLQONdkmFBrZ1xOLATWdKwTWPdArwcCP05KjZWpG70JGgaASjJXcJHrXDNkT0dVd2Fmj75uApoOY2RrZFj5DYSLM9SltzfRsF7YCC2T45pCILjfhF4cg2bZXAoRlB/EhIQYhz4wDi2VIRYkGNtOggneJiCb7QIGTn4DIz8Ed/YOlr48Ad1ZFrloBG4TmcOnkNv/pthH+auT8G5eQv/R07B3PA/lNlD+4jgw82ck7wcBiu98gszEPoSNFZQ/fx/a3N1EW/zQoz4gHuX0xLuGBc20EGb6Ee58ld6mBxI8CBR8zYxwGCbC7Yfg5TYgUFq0JnuDHa9A2QPQGErfcRBAjzA9W4z0hekCPUQvJDo7PBCTaMXXr1fQqK3AZjwNEZYRbYo/FA3UF0vUJQTjtdB34SwtIB0qWAzLwLEPkd64BcvTtzF74SysxhL6eAC9pY8quzwQGYgsSZg0gi2jjz0TGV1i2aFMoq4JGc+Fmyogd/BdpPpH0Jy5A0z9hky2wFQQN7f19XogUb76aAnLQoexCH/sW9N1pArDTOTNCPMjzMmQOerQhxr6RsZRu/IDcmEtTuxkbw+BVZ3y0jlbBLoEOmT4qmey2HTkBLTXP+AXzeoabn59GurRPQw/sYUhWIRpWV0HaROQS5mblBbXfuD7CFwXCKRsCIlrmyxNxjkaZhohk49/FAkh2+XEhpmKYOHp+x6Kg8NSa/BKD2BL31g9QCTWUYZivFHH3JVzyD+9D5mhURRfOAbv0S1gfgoqN4js66dgbtuH5UczmP35DIylOZgkHJIkCwQBCd47/x28yiLSgxsx/uJbyB94A7VfzkLdvQw9zwp4jEA7CUnAbNZRu/w9nPlZGIUh2LsPQu09DJXJw2U5mofeQ2psGxoPJ+H9cRFW2o5OHbLbBSThOTU4V3+CunMZ7uR1klLom9iL7P4jSG99lgy7E1DItAnwI2WaMCev8dTTaDp1ePUVONkRBJt2QPVvgN9w0KxWGZom+kZJ5Pp5YImlyFAEVC5hy45vg1Vn85qfhlNZhks9DhuMO7qbUZSQSazas50DssiyKsBD+eIZrNQdjL38NorPHQEOvBZt0llG97/9DM0bFzC4fQ8KTpn6aFiyXQjI9DzYzt/wGLZbp4/iyY8+RWH7M/CHR+FOXYe5MEVdJMKfDD1i1GLFp8HLJFudg1GZh2JHZPECdj/Qx86om8gV+jEwsZv3xBw3M9OpjDcEFDFlpHhKfjHZ8kaI8WYJRuhB8S5Qdh6NnS8hsHiPMHFbnmh7ICZEZjoyThW1qz9ifvMeOLOTZCwMaUya0/x9pGVO34Bh25Gu6t1rWCwvwJ+bhFWrROcSYiaJVG9fQ/nhXwiYN2alBIudMKqzJAy9BAiYzIV8bQFLv56DqpTZOoM4eXk6c2gTLJZanxWXm0FS4e+X4A09Ra+VYI5NsNuRsOjhX8hw+cVRGNUFWGNb250wObBWOr5LuPQMOXPFzCFkvUvHjTTSCyHdl3GryCrpBwIo1LUUHCtP1zMUvCNybgVWRFkwCw1i0pQUkzRPLKW1TfZ6QGxxyD1QoLBqRp9df9EdELPiugZbeUjXy5GMUBIi8SAWusQWurG2/c5GlOzpeOhCI8nWjuX4tUOJ9HoJxer4j5jI/6sHVpX9zy/rBNY9sOYe+AcCwIEbenVoBQAAAABJRU5ErkJggg==";
 
 /// Shared CSS styles for standalone OAuth HTML pages (success & error).
 /// Colors and typography match the app's design system (light mode defaults
 /// from `packages/web-core/src/app/styles/new/index.css`).
-const AUTH_PAGE_STYLES: &str = r#"<style>
+pub(super) const AUTH_PAGE_STYLES: &str = r#"<style>
   @import url('https://fonts.googleapis.com/css2?family=IBM+Plex+Sans:wght@400;500;600&display=swap');
   *, *::before, *::after { box-sizing: border-box; margin: 0; padding: 0; }
   body {
@@ -103,6 +112,10 @@ async fn auth_methods(
 struct HandoffInitPayload {
     provider: String,
     return_to: String,
+    /// Sign the requesting user in with their remote account instead of
+    /// signing the server itself in.
+    #[serde(default)]
+    user_sign_in: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -113,8 +126,21 @@ struct HandoffInitResponseBody {
 
 async fn handoff_init(
     State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
     Json(payload): Json<HandoffInitPayload>,
 ) -> Result<ResponseJson<ApiResponse<HandoffInitResponseBody>>, ApiError> {
+    if !payload.user_sign_in {
+        ensure_admin(&deployment, &current, "sign the server in").await?;
+    } else if deployment
+        .config()
+        .read()
+        .await
+        .users
+        .remote_organization_id
+        .is_none()
+    {
+        return Err(UserError::RemoteSignInDisabled.into());
+    }
     let client = deployment.remote_client()?;
 
     let app_verifier = generate_secret();
@@ -129,7 +155,12 @@ async fn handoff_init(
     let response = client.handoff_init(&request).await?;
 
     deployment
-        .store_oauth_handoff(response.handoff_id, payload.provider, app_verifier)
+        .store_oauth_handoff(
+            response.handoff_id,
+            payload.provider,
+            app_verifier,
+            payload.user_sign_in,
+        )
         .await;
 
     Ok(ResponseJson(ApiResponse::success(
@@ -171,8 +202,8 @@ async fn handoff_complete(
         ));
     };
 
-    let (provider, app_verifier) = match deployment.take_oauth_handoff(&query.handoff_id).await {
-        Some(state) => state,
+    let handoff = match deployment.take_oauth_handoff(&query.handoff_id).await {
+        Some(handoff) => handoff,
         None => {
             tracing::warn!(
                 handoff_id = %query.handoff_id,
@@ -190,10 +221,49 @@ async fn handoff_complete(
     let redeem_request = HandoffRedeemRequest {
         handoff_id: query.handoff_id,
         app_code,
-        app_verifier,
+        app_verifier: handoff.app_verifier,
     };
 
     let redeem = client.handoff_redeem(&redeem_request).await?;
+    let provider = handoff.provider;
+    let is_desktop = query.source.as_deref() == Some("desktop");
+
+    if handoff.user_sign_in {
+        let organization_id = deployment
+            .config()
+            .read()
+            .await
+            .users
+            .remote_organization_id;
+        let signed_in = match users::sign_in_with_remote(
+            &deployment.db().pool,
+            &client,
+            organization_id,
+            &redeem.access_token,
+        )
+        .await
+        {
+            Ok(signed_in) => signed_in,
+            Err(e @ (UserError::NotOrganizationMember | UserError::RemoteSignInDisabled)) => {
+                return Ok(simple_html_response(StatusCode::FORBIDDEN, e.to_string()));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let mut response = close_window_response(
+            format!(
+                "Signed in as {} with {provider}. You can return to the app.",
+                signed_in.user.name
+            ),
+            is_desktop,
+        );
+        response.headers_mut().insert(
+            header::SET_COOKIE,
+            session_cookie(&signed_in.token, SESSION_COOKIE_MAX_AGE_SECS)
+                .parse()
+                .map_err(|_| ApiError::BadRequest("Invalid session token".to_string()))?,
+        );
+        return Ok(response);
+    }
 
     finalize_login(
         &deployment,
@@ -205,7 +275,6 @@ async fn handoff_complete(
     )
     .await?;
 
-    let is_desktop = query.source.as_deref() == Some("desktop");
     Ok(close_window_response(
         format!("Signed in with {provider}. You can return to the app."),
         is_desktop,
@@ -214,8 +283,10 @@ async fn handoff_complete(
 
 async fn local_login(
     State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
     Json(payload): Json<LocalLoginRequest>,
 ) -> Result<ResponseJson<ApiResponse<ProfileResponse>>, ApiError> {
+    ensure_admin(&deployment, &current, "sign the server in").await?;
     let client = deployment.remote_client()?;
     let response = client.local_login(&payload).await?;
     let profile = finalize_login(
@@ -231,7 +302,11 @@ async fn local_login(
     Ok(ResponseJson(ApiResponse::success(profile)))
 }
 
-async fn logout(State(deployment): State<DeploymentImpl>) -> Result<StatusCode, ApiError> {
+async fn logout(
+    State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
+) -> Result<StatusCode, ApiError> {
+    ensure_admin(&deployment, &current, "sign the server out").await?;
     let auth_context = deployment.auth_context();
 
     if let Ok(client) = deployment.remote_client() {
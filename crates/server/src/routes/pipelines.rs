@@ -0,0 +1,197 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{get, post, put},
+};
+use db::models::{
+    pipeline::{
+        CreatePipeline, Pipeline, PipelineError as PipelineModelError, PipelineRun,
+        PipelineRunStage, PipelineStatus, UpdatePipeline,
+    },
+    requests::WorkspaceRepoInput,
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use executors::profile::ExecutorConfig;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    container::ContainerService,
+    pipelines::{self, PipelineError},
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, runtime::pipeline_orchestrator::start_stage};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct StartPipelineRunRequest {
+    pub repos: Vec<WorkspaceRepoInput>,
+    /// Substituted for `{{prompt}}` in every stage.
+    pub prompt: String,
+    /// Executor for stages that do not set their own.
+    pub executor_config: ExecutorConfig,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct PipelineRunWithStages {
+    pub run: PipelineRun,
+    pub stages: Vec<PipelineRunStage>,
+}
+
+pub async fn list_pipelines(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Pipeline>>>, ApiError> {
+    let pipelines = Pipeline::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(pipelines)))
+}
+
+pub async fn create_pipeline(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreatePipeline>,
+) -> Result<ResponseJson<ApiResponse<Pipeline>>, ApiError> {
+    let pipeline = pipelines::create_pipeline(&deployment.db().pool, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(pipeline)))
+}
+
+/// Update a pipeline. Runs already in progress pick up stage changes for the
+/// stages they have not started yet.
+pub async fn update_pipeline(
+    State(deployment): State<DeploymentImpl>,
+    Path(pipeline_id): Path<Uuid>,
+    Json(payload): Json<UpdatePipeline>,
+) -> Result<ResponseJson<ApiResponse<Pipeline>>, ApiError> {
+    let pipeline = pipelines::update_pipeline(&deployment.db().pool, pipeline_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(pipeline)))
+}
+
+pub async fn delete_pipeline(
+    State(deployment): State<DeploymentImpl>,
+    Path(pipeline_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if Pipeline::delete(&deployment.db().pool, pipeline_id).await? == 0 {
+        return Err(PipelineError::from(PipelineModelError::NotFound).into());
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn list_pipeline_runs(
+    State(deployment): State<DeploymentImpl>,
+    Path(pipeline_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<PipelineRun>>>, ApiError> {
+    let runs = PipelineRun::find_by_pipeline_id(&deployment.db().pool, pipeline_id).await?;
+    Ok(ResponseJson(ApiResponse::success(runs)))
+}
+
+/// Start a pipeline run: the first stage starts immediately, later stages as
+/// each one finishes.
+pub async fn start_pipeline_run(
+    State(deployment): State<DeploymentImpl>,
+    Path(pipeline_id): Path<Uuid>,
+    Json(payload): Json<StartPipelineRunRequest>,
+) -> Result<ResponseJson<ApiResponse<PipelineRunWithStages>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if payload.repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one repository is required".to_string(),
+        ));
+    }
+    let pipeline = Pipeline::find_by_id(pool, pipeline_id)
+        .await?
+        .ok_or(PipelineError::from(PipelineModelError::NotFound))?;
+
+    let run = PipelineRun::create(
+        pool,
+        pipeline.id,
+        &payload.prompt,
+        &payload.repos,
+        &payload.executor_config,
+    )
+    .await
+    .map_err(PipelineError::from)?;
+
+    let stage = match start_stage(&deployment, &pipeline, &run, 0, None).await {
+        Ok(stage) => stage,
+        Err(e) => {
+            PipelineRun::finish(pool, run.id, PipelineStatus::Failed, Some(&e.to_string())).await?;
+            return Err(e);
+        }
+    };
+
+    deployment
+        .track_if_analytics_allowed(
+            "pipeline_run_started",
+            serde_json::json!({
+                "pipeline_id": pipeline.id.to_string(),
+                "stage_count": pipeline.stages.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(PipelineRunWithStages {
+        run,
+        stages: vec![stage],
+    })))
+}
+
+async fn load_run(deployment: &DeploymentImpl, run_id: Uuid) -> Result<PipelineRun, ApiError> {
+    PipelineRun::find_by_id(&deployment.db().pool, run_id)
+        .await?
+        .ok_or_else(|| PipelineError::from(PipelineModelError::RunNotFound).into())
+}
+
+pub async fn get_pipeline_run(
+    State(deployment): State<DeploymentImpl>,
+    Path(run_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<PipelineRunWithStages>>, ApiError> {
+    let run = load_run(&deployment, run_id).await?;
+    let stages = PipelineRunStage::find_by_run_id(&deployment.db().pool, run.id).await?;
+    Ok(ResponseJson(ApiResponse::success(PipelineRunWithStages {
+        run,
+        stages,
+    })))
+}
+
+/// Cancel a run and stop the workspace of its current stage.
+pub async fn cancel_pipeline_run(
+    State(deployment): State<DeploymentImpl>,
+    Path(run_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let run = load_run(&deployment, run_id).await?;
+    if !PipelineRun::finish(pool, run.id, PipelineStatus::Cancelled, None).await? {
+        return Err(ApiError::Conflict(
+            "Pipeline run has already finished".to_string(),
+        ));
+    }
+
+    let running: Vec<_> = PipelineRunStage::find_by_run_id(pool, run.id)
+        .await?
+        .into_iter()
+        .filter(|stage| stage.status == PipelineStatus::Running)
+        .filter_map(|stage| stage.workspace_id)
+        .collect();
+    PipelineRunStage::cancel_for_run(pool, run.id).await?;
+    for workspace_id in running {
+        if let Some(workspace) = Workspace::find_by_id(pool, workspace_id).await? {
+            deployment.container().try_stop(&workspace, false).await;
+        }
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/pipelines", get(list_pipelines).post(create_pipeline))
+        .route(
+            "/pipelines/{pipeline_id}",
+            put(update_pipeline).delete(delete_pipeline),
+        )
+        .route(
+            "/pipelines/{pipeline_id}/runs",
+            get(list_pipeline_runs).post(start_pipeline_run),
+        )
+        .route("/pipeline-runs/{run_id}", get(get_pipeline_run))
+        .route("/pipeline-runs/{run_id}/cancel", post(cancel_pipeline_run))
+}
@@ -28,19 +28,7 @@ async fn proxy_preview_request_no_tail(
     ws_upgrade: Result<SignedWsUpgrade, WebSocketUpgradeRejection>,
     request: Request,
 ) -> Response {
-    match ws_upgrade {
-        Ok(ws) => forward_preview_ws(ws, target_port, String::new(), request).await,
-        Err(rejection) => {
-            preview_proxy::api::proxy_api_request(
-                deployment.preview_proxy(),
-                target_port,
-                String::new(),
-                Err(rejection),
-                request,
-            )
-            .await
-        }
-    }
+    proxy_to_port(&deployment, target_port, String::new(), ws_upgrade, request).await
 }
 
 async fn proxy_preview_request(
@@ -48,6 +36,17 @@ async fn proxy_preview_request(
     Path((target_port, tail)): Path<(u16, String)>,
     ws_upgrade: Result<SignedWsUpgrade, WebSocketUpgradeRejection>,
     request: Request,
+) -> Response {
+    proxy_to_port(&deployment, target_port, tail, ws_upgrade, request).await
+}
+
+/// Proxy an HTTP or WebSocket request to a server on a local port.
+pub(super) async fn proxy_to_port(
+    deployment: &DeploymentImpl,
+    target_port: u16,
+    tail: String,
+    ws_upgrade: Result<SignedWsUpgrade, WebSocketUpgradeRejection>,
+    request: Request,
 ) -> Response {
     match ws_upgrade {
         Ok(ws) => forward_preview_ws(ws, target_port, tail, request).await,
@@ -5,14 +5,33 @@ use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
     response::Json as ResponseJson,
-    routing::{get, post},
+    routing::{delete, get, post, put},
+};
+use db::models::{
+    env_profile::{
+        CreateEnvProfile, EnvProfile, EnvProfileError as EnvProfileModelError, UpdateEnvProfile,
+    },
+    git_credential::{GitCredential, GitCredentialSummary},
+    repo::{Repo, RepoScriptSettings, SearchResult, UpdateRepo},
+    repo_dev_server::{
+        CreateRepoDevServer, RepoDevServer, RepoDevServerError, UpdateRepoDevServer,
+    },
+    repo_knowledge::{CreateRepoKnowledge, RepoKnowledge, RepoKnowledgeError, UpdateRepoKnowledge},
+    repo_metadata::{RepoCommit, RepoContributor},
+    repo_package::{CreateRepoPackage, RepoPackage, RepoPackageError, UpdateRepoPackage},
+    repo_shared_cache::{CreateRepoSharedCache, RepoSharedCache, RepoSharedCacheError},
 };
-use db::models::repo::{Repo, SearchResult, UpdateRepo};
 use deployment::Deployment;
 use git::{GitBranch, GitRemote};
 use git_host::{GitHostError, GitHostProvider, GitHostService, ProviderKind, PullRequestDetail};
 use serde::{Deserialize, Serialize};
-use services::services::file_search::SearchQuery;
+use services::services::{
+    env_profiles,
+    file_search::SearchQuery,
+    git_credentials,
+    repo_analyzer::{self, RepoAnalysis},
+    shared_caches::{self, SharedCacheUsage},
+};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
@@ -47,6 +66,24 @@ pub struct BatchRepoRequest {
     pub ids: Vec<Uuid>,
 }
 
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct RepoLogRedaction {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SetRepoGitCredential {
+    /// `null` goes back to the default git identity.
+    pub git_credential_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct RepoVerifyScript {
+    /// Runs after the coding agent and cleanup scripts; test counts are parsed
+    /// from its output.
+    pub script: Option<String>,
+}
+
 pub async fn register_repo(
     State(deployment): State<DeploymentImpl>,
     ResponseJson(payload): ResponseJson<RegisterRepoRequest>,
@@ -89,10 +126,46 @@ pub async fn get_repo_branches(
         .get_by_id(&deployment.db().pool, repo_id)
         .await?;
 
-    let branches = deployment.git().get_all_branches(&repo.path)?;
+    let branches = deployment.repo_index().branches(&repo).await?;
     Ok(ResponseJson(ApiResponse::success(branches)))
 }
 
+/// Most recent commits listed by default.
+const DEFAULT_RECENT_COMMITS: i64 = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct RecentCommitsQuery {
+    pub limit: Option<i64>,
+}
+
+pub async fn get_repo_commits(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    Query(query): Query<RecentCommitsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<RepoCommit>>>, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_RECENT_COMMITS).max(1);
+    let commits = deployment.repo_index().recent_commits(&repo, limit).await?;
+    Ok(ResponseJson(ApiResponse::success(commits)))
+}
+
+pub async fn get_repo_contributors(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<RepoContributor>>>, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+
+    let contributors = deployment.repo_index().contributors(&repo).await?;
+    Ok(ResponseJson(ApiResponse::success(contributors)))
+}
+
 pub async fn get_repo_remotes(
     State(deployment): State<DeploymentImpl>,
     Path(repo_id): Path<Uuid>,
@@ -148,6 +221,408 @@ pub async fn update_repo(
     Ok(ResponseJson(ApiResponse::success(repo)))
 }
 
+pub async fn get_repo_log_redaction(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<RepoLogRedaction>>, ApiError> {
+    let enabled = Repo::log_redaction_enabled(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(RepoLogRedaction {
+        enabled,
+    })))
+}
+
+/// Toggle log redaction for a repo. Takes effect for processes started after
+/// the change.
+pub async fn update_repo_log_redaction(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<RepoLogRedaction>,
+) -> Result<ResponseJson<ApiResponse<RepoLogRedaction>>, ApiError> {
+    Repo::set_log_redaction_enabled(&deployment.db().pool, repo_id, payload.enabled).await?;
+    Ok(ResponseJson(ApiResponse::success(payload)))
+}
+
+/// Upper bound on automatic retries of a failing script.
+const MAX_SCRIPT_RETRIES: u32 = 10;
+
+pub async fn get_repo_script_settings(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<RepoScriptSettings>>, ApiError> {
+    let settings = Repo::script_settings(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+/// Update script timeouts and the failure policy. Timeouts apply to scripts
+/// started after the change; a timeout of 0 is treated as no timeout.
+pub async fn update_repo_script_settings(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<RepoScriptSettings>,
+) -> Result<ResponseJson<ApiResponse<RepoScriptSettings>>, ApiError> {
+    if payload.max_retries > MAX_SCRIPT_RETRIES {
+        return Err(ApiError::BadRequest(format!(
+            "max_retries must be at most {MAX_SCRIPT_RETRIES}"
+        )));
+    }
+    Repo::set_script_settings(&deployment.db().pool, repo_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(payload)))
+}
+
+/// Suggest setup, dev server and cleanup scripts from the repo's manifests,
+/// lockfiles, Makefile and CI config. Nothing is saved.
+pub async fn analyze_repo(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<RepoAnalysis>>, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+    let analysis = tokio::task::spawn_blocking(move || repo_analyzer::analyze_repo(&repo.path))
+        .await
+        .map_err(std::io::Error::other)??;
+    Ok(ResponseJson(ApiResponse::success(analysis)))
+}
+
+pub async fn get_repo_verify_script(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<RepoVerifyScript>>, ApiError> {
+    let script = Repo::verify_script(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(RepoVerifyScript {
+        script,
+    })))
+}
+
+/// Set or clear the verify script. An empty script clears it.
+pub async fn update_repo_verify_script(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<RepoVerifyScript>,
+) -> Result<ResponseJson<ApiResponse<RepoVerifyScript>>, ApiError> {
+    let script = payload.script.filter(|script| !script.trim().is_empty());
+    Repo::set_verify_script(&deployment.db().pool, repo_id, script.as_deref()).await?;
+    Ok(ResponseJson(ApiResponse::success(RepoVerifyScript {
+        script,
+    })))
+}
+
+/// The git account the repo's fetches, pushes and PR operations use, if one
+/// was selected.
+pub async fn get_repo_git_credential(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Option<GitCredentialSummary>>>, ApiError> {
+    let credential = GitCredential::find_for_repo(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(credential)))
+}
+
+pub async fn set_repo_git_credential(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<SetRepoGitCredential>,
+) -> Result<ResponseJson<ApiResponse<Option<GitCredentialSummary>>>, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+    let credential = git_credentials::select_repo_credential(
+        &deployment.db().pool,
+        repo.id,
+        &repo.path,
+        payload.git_credential_id,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(credential)))
+}
+
+pub async fn list_env_profiles(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<EnvProfile>>>, ApiError> {
+    let profiles = EnvProfile::find_by_repo_id(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(profiles)))
+}
+
+pub async fn create_env_profile(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<CreateEnvProfile>,
+) -> Result<ResponseJson<ApiResponse<EnvProfile>>, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+    let profile = env_profiles::create_profile(&deployment.db().pool, repo.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(profile)))
+}
+
+/// Look up a profile, treating one that belongs to a different repo as missing.
+async fn find_repo_env_profile(
+    deployment: &DeploymentImpl,
+    repo_id: Uuid,
+    profile_id: Uuid,
+) -> Result<EnvProfile, ApiError> {
+    EnvProfile::find_by_id(&deployment.db().pool, profile_id)
+        .await?
+        .filter(|profile| profile.repo_id == repo_id)
+        .ok_or_else(|| env_profiles::EnvProfileError::from(EnvProfileModelError::NotFound).into())
+}
+
+pub async fn update_env_profile(
+    State(deployment): State<DeploymentImpl>,
+    Path((repo_id, profile_id)): Path<(Uuid, Uuid)>,
+    ResponseJson(payload): ResponseJson<UpdateEnvProfile>,
+) -> Result<ResponseJson<ApiResponse<EnvProfile>>, ApiError> {
+    let profile = find_repo_env_profile(&deployment, repo_id, profile_id).await?;
+    let profile = env_profiles::update_profile(&deployment.db().pool, profile.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(profile)))
+}
+
+pub async fn delete_env_profile(
+    State(deployment): State<DeploymentImpl>,
+    Path((repo_id, profile_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let profile = find_repo_env_profile(&deployment, repo_id, profile_id).await?;
+    EnvProfile::delete(&deployment.db().pool, profile.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn list_repo_dev_servers(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<RepoDevServer>>>, ApiError> {
+    let dev_servers = RepoDevServer::find_by_repo_id(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(dev_servers)))
+}
+
+pub async fn create_repo_dev_server(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<CreateRepoDevServer>,
+) -> Result<ResponseJson<ApiResponse<RepoDevServer>>, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+    let dev_server = RepoDevServer::create(&deployment.db().pool, repo.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(dev_server)))
+}
+
+/// Look up a dev server, treating one that belongs to a different repo as missing.
+async fn find_repo_dev_server(
+    deployment: &DeploymentImpl,
+    repo_id: Uuid,
+    dev_server_id: Uuid,
+) -> Result<RepoDevServer, ApiError> {
+    RepoDevServer::find_by_id(&deployment.db().pool, dev_server_id)
+        .await?
+        .filter(|dev_server| dev_server.repo_id == repo_id)
+        .ok_or_else(|| RepoDevServerError::NotFound.into())
+}
+
+pub async fn update_repo_dev_server(
+    State(deployment): State<DeploymentImpl>,
+    Path((repo_id, dev_server_id)): Path<(Uuid, Uuid)>,
+    ResponseJson(payload): ResponseJson<UpdateRepoDevServer>,
+) -> Result<ResponseJson<ApiResponse<RepoDevServer>>, ApiError> {
+    let dev_server = find_repo_dev_server(&deployment, repo_id, dev_server_id).await?;
+    let dev_server = RepoDevServer::update(&deployment.db().pool, dev_server.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(dev_server)))
+}
+
+pub async fn delete_repo_dev_server(
+    State(deployment): State<DeploymentImpl>,
+    Path((repo_id, dev_server_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let dev_server = find_repo_dev_server(&deployment, repo_id, dev_server_id).await?;
+    RepoDevServer::delete(&deployment.db().pool, dev_server.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn list_repo_shared_caches(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<RepoSharedCache>>>, ApiError> {
+    let caches = RepoSharedCache::find_by_repo_id(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(caches)))
+}
+
+pub async fn create_repo_shared_cache(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<CreateRepoSharedCache>,
+) -> Result<ResponseJson<ApiResponse<RepoSharedCache>>, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+    let cache = RepoSharedCache::create(&deployment.db().pool, repo.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(cache)))
+}
+
+pub async fn get_repo_shared_cache_usage(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<SharedCacheUsage>>>, ApiError> {
+    let caches = RepoSharedCache::find_by_repo_id(&deployment.db().pool, repo_id).await?;
+    let usage = tokio::task::spawn_blocking(move || shared_caches::usage(caches))
+        .await
+        .map_err(std::io::Error::other)?;
+    Ok(ResponseJson(ApiResponse::success(usage)))
+}
+
+/// Look up a shared cache, treating one that belongs to a different repo as missing.
+async fn find_repo_shared_cache(
+    deployment: &DeploymentImpl,
+    repo_id: Uuid,
+    cache_id: Uuid,
+) -> Result<RepoSharedCache, ApiError> {
+    RepoSharedCache::find_by_id(&deployment.db().pool, cache_id)
+        .await?
+        .filter(|cache| cache.repo_id == repo_id)
+        .ok_or_else(|| RepoSharedCacheError::NotFound.into())
+}
+
+pub async fn delete_repo_shared_cache(
+    State(deployment): State<DeploymentImpl>,
+    Path((repo_id, cache_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let cache = find_repo_shared_cache(&deployment, repo_id, cache_id).await?;
+    RepoSharedCache::delete(&deployment.db().pool, cache.id).await?;
+    // Worktrees still linking it get a dangling symlink, which tools recreate
+    tokio::task::spawn_blocking(move || shared_caches::remove(&cache))
+        .await
+        .map_err(std::io::Error::other)??;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn prune_repo_shared_cache(
+    State(deployment): State<DeploymentImpl>,
+    Path((repo_id, cache_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let cache = find_repo_shared_cache(&deployment, repo_id, cache_id).await?;
+    tokio::task::spawn_blocking(move || shared_caches::clear(&cache))
+        .await
+        .map_err(std::io::Error::other)??;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn list_repo_knowledge(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<RepoKnowledge>>>, ApiError> {
+    let knowledge = RepoKnowledge::find_by_repo_id(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(knowledge)))
+}
+
+pub async fn create_repo_knowledge(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<CreateRepoKnowledge>,
+) -> Result<ResponseJson<ApiResponse<RepoKnowledge>>, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+    let knowledge = RepoKnowledge::create(&deployment.db().pool, repo.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(knowledge)))
+}
+
+/// Look up a knowledge entry, treating one that belongs to a different repo as missing.
+async fn find_repo_knowledge(
+    deployment: &DeploymentImpl,
+    repo_id: Uuid,
+    knowledge_id: Uuid,
+) -> Result<RepoKnowledge, ApiError> {
+    RepoKnowledge::find_by_id(&deployment.db().pool, knowledge_id)
+        .await?
+        .filter(|knowledge| knowledge.repo_id == repo_id)
+        .ok_or_else(|| RepoKnowledgeError::NotFound.into())
+}
+
+pub async fn update_repo_knowledge(
+    State(deployment): State<DeploymentImpl>,
+    Path((repo_id, knowledge_id)): Path<(Uuid, Uuid)>,
+    ResponseJson(payload): ResponseJson<UpdateRepoKnowledge>,
+) -> Result<ResponseJson<ApiResponse<RepoKnowledge>>, ApiError> {
+    let knowledge = find_repo_knowledge(&deployment, repo_id, knowledge_id).await?;
+    let knowledge = RepoKnowledge::update(&deployment.db().pool, knowledge.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(knowledge)))
+}
+
+/// Approve an agent-suggested entry so it is added to future prompts.
+pub async fn approve_repo_knowledge(
+    State(deployment): State<DeploymentImpl>,
+    Path((repo_id, knowledge_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<RepoKnowledge>>, ApiError> {
+    let knowledge = find_repo_knowledge(&deployment, repo_id, knowledge_id).await?;
+    let knowledge = RepoKnowledge::approve(&deployment.db().pool, knowledge.id).await?;
+    Ok(ResponseJson(ApiResponse::success(knowledge)))
+}
+
+pub async fn delete_repo_knowledge(
+    State(deployment): State<DeploymentImpl>,
+    Path((repo_id, knowledge_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let knowledge = find_repo_knowledge(&deployment, repo_id, knowledge_id).await?;
+    RepoKnowledge::delete(&deployment.db().pool, knowledge.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn list_repo_packages(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<RepoPackage>>>, ApiError> {
+    let packages = RepoPackage::find_by_repo_id(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(packages)))
+}
+
+pub async fn create_repo_package(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<CreateRepoPackage>,
+) -> Result<ResponseJson<ApiResponse<RepoPackage>>, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+    let package = RepoPackage::create(&deployment.db().pool, repo.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(package)))
+}
+
+/// Look up a package, treating one that belongs to a different repo as missing.
+async fn find_repo_package(
+    deployment: &DeploymentImpl,
+    repo_id: Uuid,
+    package_id: Uuid,
+) -> Result<RepoPackage, ApiError> {
+    RepoPackage::find_by_id(&deployment.db().pool, package_id)
+        .await?
+        .filter(|package| package.repo_id == repo_id)
+        .ok_or_else(|| RepoPackageError::NotFound.into())
+}
+
+pub async fn update_repo_package(
+    State(deployment): State<DeploymentImpl>,
+    Path((repo_id, package_id)): Path<(Uuid, Uuid)>,
+    ResponseJson(payload): ResponseJson<UpdateRepoPackage>,
+) -> Result<ResponseJson<ApiResponse<RepoPackage>>, ApiError> {
+    let package = find_repo_package(&deployment, repo_id, package_id).await?;
+    let package = RepoPackage::update(&deployment.db().pool, package.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(package)))
+}
+
+pub async fn delete_repo_package(
+    State(deployment): State<DeploymentImpl>,
+    Path((repo_id, package_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let package = find_repo_package(&deployment, repo_id, package_id).await?;
+    RepoPackage::delete(&deployment.db().pool, package.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 pub async fn open_repo_in_editor(
     State(deployment): State<DeploymentImpl>,
     Path(repo_id): Path<Uuid>,
@@ -376,9 +851,80 @@ pub fn router() -> Router<DeploymentImpl> {
             get(get_repo).put(update_repo).delete(delete_repo),
         )
         .route("/repos/{repo_id}/branches", get(get_repo_branches))
+        .route("/repos/{repo_id}/commits", get(get_repo_commits))
+        .route("/repos/{repo_id}/contributors", get(get_repo_contributors))
         .route("/repos/{repo_id}/remotes", get(get_repo_remotes))
         .route("/repos/{repo_id}/prs", get(list_open_prs))
         .route("/repos/pr-info", get(get_pr_info))
         .route("/repos/{repo_id}/search", get(search_repo))
         .route("/repos/{repo_id}/open-editor", post(open_repo_in_editor))
+        .route("/repos/{repo_id}/analyze", post(analyze_repo))
+        .route(
+            "/repos/{repo_id}/log-redaction",
+            get(get_repo_log_redaction).put(update_repo_log_redaction),
+        )
+        .route(
+            "/repos/{repo_id}/script-settings",
+            get(get_repo_script_settings).put(update_repo_script_settings),
+        )
+        .route(
+            "/repos/{repo_id}/verify-script",
+            get(get_repo_verify_script).put(update_repo_verify_script),
+        )
+        .route(
+            "/repos/{repo_id}/git-credential",
+            get(get_repo_git_credential).put(set_repo_git_credential),
+        )
+        .route(
+            "/repos/{repo_id}/env-profiles",
+            get(list_env_profiles).post(create_env_profile),
+        )
+        .route(
+            "/repos/{repo_id}/env-profiles/{profile_id}",
+            put(update_env_profile).delete(delete_env_profile),
+        )
+        .route(
+            "/repos/{repo_id}/dev-servers",
+            get(list_repo_dev_servers).post(create_repo_dev_server),
+        )
+        .route(
+            "/repos/{repo_id}/dev-servers/{dev_server_id}",
+            put(update_repo_dev_server).delete(delete_repo_dev_server),
+        )
+        .route(
+            "/repos/{repo_id}/shared-caches",
+            get(list_repo_shared_caches).post(create_repo_shared_cache),
+        )
+        .route(
+            "/repos/{repo_id}/shared-caches/usage",
+            get(get_repo_shared_cache_usage),
+        )
+        .route(
+            "/repos/{repo_id}/shared-caches/{cache_id}",
+            delete(delete_repo_shared_cache),
+        )
+        .route(
+            "/repos/{repo_id}/shared-caches/{cache_id}/prune",
+            post(prune_repo_shared_cache),
+        )
+        .route(
+            "/repos/{repo_id}/knowledge",
+            get(list_repo_knowledge).post(create_repo_knowledge),
+        )
+        .route(
+            "/repos/{repo_id}/knowledge/{knowledge_id}",
+            put(update_repo_knowledge).delete(delete_repo_knowledge),
+        )
+        .route(
+            "/repos/{repo_id}/knowledge/{knowledge_id}/approve",
+            post(approve_repo_knowledge),
+        )
+        .route(
+            "/repos/{repo_id}/packages",
+            get(list_repo_packages).post(create_repo_package),
+        )
+        .route(
+            "/repos/{repo_id}/packages/{package_id}",
+            put(update_repo_package).delete(delete_repo_package),
+        )
 }
@@ -0,0 +1,140 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::{get, post, put},
+};
+use db::models::schedule::{
+    CreateSchedule, Schedule, ScheduleError as ScheduleModelError, ScheduleRun, UpdateSchedule,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::{
+    dependency_updates::{self, CreateDependencyUpdateSchedule},
+    schedules,
+};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+const DEFAULT_RUNS_LIMIT: i64 = 50;
+const MAX_RUNS_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct ListScheduleRunsQuery {
+    pub limit: Option<i64>,
+}
+
+pub async fn list_schedules(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Schedule>>>, ApiError> {
+    let schedules = Schedule::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(schedules)))
+}
+
+pub async fn create_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateSchedule>,
+) -> Result<ResponseJson<ApiResponse<Schedule>>, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, payload.repo_id)
+        .await?;
+    let schedule = schedules::create_schedule(
+        &deployment.db().pool,
+        &CreateSchedule {
+            repo_id: repo.id,
+            ..payload
+        },
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+/// Create the built-in dependency update schedule for a repo: an agent
+/// upgrades its dependencies on the cadence, and a pull request is opened when
+/// the repo's verify script passes.
+pub async fn create_dependency_update_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateDependencyUpdateSchedule>,
+) -> Result<ResponseJson<ApiResponse<Schedule>>, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, payload.repo_id)
+        .await?;
+    let schedule = dependency_updates::create_schedule(
+        &deployment.db().pool,
+        &CreateDependencyUpdateSchedule {
+            repo_id: repo.id,
+            ..payload
+        },
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn update_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Path(schedule_id): Path<Uuid>,
+    Json(payload): Json<UpdateSchedule>,
+) -> Result<ResponseJson<ApiResponse<Schedule>>, ApiError> {
+    let schedule = schedules::update_schedule(&deployment.db().pool, schedule_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn delete_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Path(schedule_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if Schedule::delete(&deployment.db().pool, schedule_id).await? == 0 {
+        return Err(schedules::ScheduleError::from(ScheduleModelError::NotFound).into());
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn pause_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Path(schedule_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Schedule>>, ApiError> {
+    let schedule = schedules::set_paused(&deployment.db().pool, schedule_id, true).await?;
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn resume_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Path(schedule_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Schedule>>, ApiError> {
+    let schedule = schedules::set_paused(&deployment.db().pool, schedule_id, false).await?;
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+/// Most recent runs of a schedule, newest first.
+pub async fn list_schedule_runs(
+    State(deployment): State<DeploymentImpl>,
+    Path(schedule_id): Path<Uuid>,
+    Query(query): Query<ListScheduleRunsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ScheduleRun>>>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_RUNS_LIMIT)
+        .clamp(1, MAX_RUNS_LIMIT);
+    let runs = ScheduleRun::find_by_schedule_id(&deployment.db().pool, schedule_id, limit).await?;
+    Ok(ResponseJson(ApiResponse::success(runs)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/schedules", get(list_schedules).post(create_schedule))
+        .route(
+            "/schedules/dependency-updates",
+            post(create_dependency_update_schedule),
+        )
+        .route(
+            "/schedules/{schedule_id}",
+            put(update_schedule).delete(delete_schedule),
+        )
+        .route("/schedules/{schedule_id}/pause", post(pause_schedule))
+        .route("/schedules/{schedule_id}/resume", post(resume_schedule))
+        .route("/schedules/{schedule_id}/runs", get(list_schedule_runs))
+}
@@ -1,13 +1,25 @@
+use std::path::Path as FsPath;
+
 use axum::{
     Json, Router,
-    extract::{Path, State, ws::Message},
-    response::{IntoResponse, Json as ResponseJson},
-    routing::get,
+    body::Body,
+    extract::{Path, Query, State, ws::Message},
+    http::{StatusCode, header},
+    response::{IntoResponse, Json as ResponseJson, Response},
+    routing::{get, post},
+};
+use db::models::{
+    file::WorkspaceAttachment,
+    scratch::{CreateScratch, Scratch, ScratchError, ScratchType, UpdateScratch},
+    session::Session,
+    tag::{CreateTag, Tag},
+    workspace::{Workspace, WorkspaceError},
 };
-use db::models::scratch::{CreateScratch, Scratch, ScratchType, UpdateScratch};
 use deployment::Deployment;
 use futures_util::{StreamExt, TryStreamExt};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use services::services::file::FileError;
+use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
@@ -15,6 +27,7 @@ use crate::{
     DeploymentImpl,
     error::ApiError,
     middleware::signed_ws::{MaybeSignedWebSocket, SignedWsUpgrade},
+    routes::attachments::AttachmentResponse,
 };
 
 /// Path parameters for scratch routes with composite key
@@ -24,13 +37,79 @@ pub struct ScratchPath {
     id: Uuid,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ListScratchQuery {
+    #[serde(default)]
+    pub scratch_type: Option<ScratchType>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct DuplicateScratch {
+    /// Id of the copy; a new one is generated if omitted.
+    #[ts(optional)]
+    pub id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ScratchToTemplate {
+    pub tag_name: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct AttachScratchToWorkspace {
+    pub workspace_id: Uuid,
+    /// Defaults to `scratch.md`.
+    #[ts(optional)]
+    pub file_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ScratchExportFormat {
+    /// The scratch's text as a markdown file.
+    #[default]
+    Markdown,
+    /// The whole scratch record.
+    Json,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportScratchQuery {
+    #[serde(default)]
+    pub format: ScratchExportFormat,
+}
+
 pub async fn list_scratch(
     State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ListScratchQuery>,
 ) -> Result<ResponseJson<ApiResponse<Vec<Scratch>>>, ApiError> {
-    let scratch_items = Scratch::find_all(&deployment.db().pool).await?;
+    let mut scratch_items = Scratch::find_all(&deployment.db().pool).await?;
+    if let Some(scratch_type) = query.scratch_type {
+        scratch_items.retain(|scratch| scratch.scratch_type() == scratch_type);
+    }
     Ok(ResponseJson(ApiResponse::success(scratch_items)))
 }
 
+async fn find_scratch(
+    deployment: &DeploymentImpl,
+    id: Uuid,
+    scratch_type: &ScratchType,
+) -> Result<Scratch, ApiError> {
+    Scratch::find_by_id(&deployment.db().pool, id, scratch_type)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Scratch not found".to_string()))
+}
+
+/// The text of a scratch, for conversions that only make sense for documents.
+fn scratch_text(scratch: &Scratch) -> Result<&str, ApiError> {
+    scratch.payload.text_content().ok_or_else(|| {
+        ApiError::BadRequest(format!(
+            "{} scratches have no text content",
+            scratch.scratch_type()
+        ))
+    })
+}
+
 pub async fn get_scratch(
     State(deployment): State<DeploymentImpl>,
     Path(ScratchPath { scratch_type, id }): Path<ScratchPath>,
@@ -101,6 +180,158 @@ pub async fn delete_scratch(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+pub async fn duplicate_scratch(
+    State(deployment): State<DeploymentImpl>,
+    Path(ScratchPath { scratch_type, id }): Path<ScratchPath>,
+    Json(payload): Json<DuplicateScratch>,
+) -> Result<ResponseJson<ApiResponse<Scratch>>, ApiError> {
+    let scratch = find_scratch(&deployment, id, &scratch_type).await?;
+    let new_id = payload.id.unwrap_or_else(Uuid::new_v4);
+    let pool = &deployment.db().pool;
+    if Scratch::find_by_id(pool, new_id, &scratch_type)
+        .await?
+        .is_some()
+    {
+        return Err(ApiError::Conflict(format!(
+            "A {scratch_type} scratch with id {new_id} already exists"
+        )));
+    }
+
+    let copy = Scratch::create(
+        pool,
+        new_id,
+        &CreateScratch {
+            payload: scratch.payload,
+        },
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(copy)))
+}
+
+pub async fn export_scratch(
+    State(deployment): State<DeploymentImpl>,
+    Path(ScratchPath { scratch_type, id }): Path<ScratchPath>,
+    Query(query): Query<ExportScratchQuery>,
+) -> Result<Response, ApiError> {
+    let scratch = find_scratch(&deployment, id, &scratch_type).await?;
+    let type_name = scratch_type.to_string().to_lowercase();
+    let (body, content_type, extension) = match query.format {
+        ScratchExportFormat::Markdown => (
+            scratch_text(&scratch)?.to_string(),
+            "text/markdown; charset=utf-8",
+            "md",
+        ),
+        ScratchExportFormat::Json => (
+            serde_json::to_string_pretty(&scratch).map_err(ScratchError::from)?,
+            "application/json",
+            "json",
+        ),
+    };
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type)
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{type_name}-{id}.{extension}\""),
+        )
+        .body(Body::from(body))
+        .map_err(|e| ApiError::File(FileError::ResponseBuildError(e.to_string())))
+}
+
+/// Save a scratch's text as a tag, so it can be inserted into prompts.
+pub async fn convert_scratch_to_template(
+    State(deployment): State<DeploymentImpl>,
+    Path(ScratchPath { scratch_type, id }): Path<ScratchPath>,
+    Json(payload): Json<ScratchToTemplate>,
+) -> Result<ResponseJson<ApiResponse<Tag>>, ApiError> {
+    let tag_name = payload.tag_name.trim();
+    if tag_name.is_empty() || tag_name.contains(char::is_whitespace) {
+        return Err(ApiError::BadRequest(
+            "Template name must be non-empty and contain no whitespace".to_string(),
+        ));
+    }
+    let scratch = find_scratch(&deployment, id, &scratch_type).await?;
+    let content = scratch_text(&scratch)?;
+    let tag = Tag::create(
+        &deployment.db().pool,
+        &CreateTag {
+            tag_name: tag_name.to_string(),
+            content: content.to_string(),
+        },
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "tag_created",
+            serde_json::json!({
+                "tag_id": tag.id.to_string(),
+                "tag_name": tag.tag_name,
+                "from_scratch_type": scratch_type.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(tag)))
+}
+
+/// Store a scratch's text as a workspace attachment, which is copied into the
+/// workspace's `.vibe-attachments` directory for agents to read.
+pub async fn attach_scratch_to_workspace(
+    State(deployment): State<DeploymentImpl>,
+    Path(ScratchPath { scratch_type, id }): Path<ScratchPath>,
+    Json(payload): Json<AttachScratchToWorkspace>,
+) -> Result<ResponseJson<ApiResponse<AttachmentResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let workspace = Workspace::find_by_id(pool, payload.workspace_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::WorkspaceNotFound))?;
+    let scratch = find_scratch(&deployment, id, &scratch_type).await?;
+    let content = scratch_text(&scratch)?;
+
+    let file_name = payload
+        .file_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .unwrap_or("scratch.md");
+    let file_name = if FsPath::new(file_name).extension().is_some() {
+        file_name.to_string()
+    } else {
+        format!("{file_name}.md")
+    };
+    let file = deployment
+        .file()
+        .store_file(content.as_bytes(), &file_name)
+        .await?;
+    WorkspaceAttachment::associate_many_dedup(pool, workspace.id, std::slice::from_ref(&file.id))
+        .await?;
+
+    // Running workspaces get the file right away; others on next start.
+    if let Some(container_ref) = workspace
+        .container_ref
+        .as_deref()
+        .filter(|dir| FsPath::new(dir).exists())
+    {
+        let agent_working_dir = Session::find_latest_by_workspace_id(pool, workspace.id)
+            .await?
+            .and_then(|session| session.agent_working_dir);
+        deployment
+            .file()
+            .copy_files_by_workspace_to_worktree(
+                FsPath::new(container_ref),
+                workspace.id,
+                agent_working_dir.as_deref(),
+            )
+            .await?;
+    }
+
+    Ok(ResponseJson(ApiResponse::success(
+        AttachmentResponse::from_file(file),
+    )))
+}
+
 pub async fn stream_scratch_ws(
     ws: SignedWsUpgrade,
     State(deployment): State<DeploymentImpl>,
@@ -164,6 +395,19 @@ pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
                 .put(update_scratch)
                 .delete(delete_scratch),
         )
+        .route(
+            "/scratch/{scratch_type}/{id}/duplicate",
+            post(duplicate_scratch),
+        )
+        .route("/scratch/{scratch_type}/{id}/export", get(export_scratch))
+        .route(
+            "/scratch/{scratch_type}/{id}/template",
+            post(convert_scratch_to_template),
+        )
+        .route(
+            "/scratch/{scratch_type}/{id}/attach",
+            post(attach_scratch_to_workspace),
+        )
         .route(
             "/scratch/{scratch_type}/{id}/stream/ws",
             get(stream_scratch_ws),
@@ -0,0 +1,128 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use db::models::script_library::{
+    CreateLibraryScript, LibraryScript, RepoScriptKind, RepoScriptRef, ScriptLibraryError,
+    UpdateLibraryScript,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteLibraryScriptQuery {
+    /// Also drop references from repos still using the script.
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SetRepoScriptRefRequest {
+    /// Library script to use, or `null` to stop referencing the library.
+    pub library_script_id: Option<Uuid>,
+}
+
+fn validate_script(name: Option<&str>, script: Option<&str>) -> Result<(), ApiError> {
+    if name.is_some_and(|name| name.trim().is_empty()) {
+        return Err(ApiError::BadRequest(
+            "Library script name must not be empty".to_string(),
+        ));
+    }
+    if script.is_some_and(|script| script.trim().is_empty()) {
+        return Err(ApiError::BadRequest(
+            "Library script must not be empty".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn list_library_scripts(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<LibraryScript>>>, ApiError> {
+    let scripts = LibraryScript::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(scripts)))
+}
+
+pub async fn create_library_script(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateLibraryScript>,
+) -> Result<ResponseJson<ApiResponse<LibraryScript>>, ApiError> {
+    validate_script(Some(&payload.name), Some(&payload.script))?;
+    let script = LibraryScript::create(&deployment.db().pool, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(script)))
+}
+
+/// Update a library script. Every repo referencing it picks up the change the
+/// next time the script runs.
+pub async fn update_library_script(
+    State(deployment): State<DeploymentImpl>,
+    Path(script_id): Path<Uuid>,
+    Json(payload): Json<UpdateLibraryScript>,
+) -> Result<ResponseJson<ApiResponse<LibraryScript>>, ApiError> {
+    validate_script(payload.name.as_deref(), payload.script.as_deref())?;
+    let script = LibraryScript::update(&deployment.db().pool, script_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(script)))
+}
+
+pub async fn delete_library_script(
+    State(deployment): State<DeploymentImpl>,
+    Path(script_id): Path<Uuid>,
+    Query(query): Query<DeleteLibraryScriptQuery>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    LibraryScript::delete(&deployment.db().pool, script_id, query.force).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn get_repo_script_refs(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<RepoScriptRef>>>, ApiError> {
+    let refs = RepoScriptRef::find_by_repo_id(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(refs)))
+}
+
+/// Reference a library script for one of a repo's lifecycle scripts. The
+/// repo's own script of that kind, if set, still takes precedence.
+pub async fn set_repo_script_ref(
+    State(deployment): State<DeploymentImpl>,
+    Path((repo_id, kind)): Path<(Uuid, RepoScriptKind)>,
+    Json(payload): Json<SetRepoScriptRefRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<RepoScriptRef>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    deployment.repo().get_by_id(pool, repo_id).await?;
+    if let Some(library_script_id) = payload.library_script_id
+        && LibraryScript::find_by_id(pool, library_script_id)
+            .await?
+            .is_none()
+    {
+        return Err(ScriptLibraryError::NotFound.into());
+    }
+
+    RepoScriptRef::set(pool, repo_id, kind, payload.library_script_id).await?;
+    let refs = RepoScriptRef::find_by_repo_id(pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(refs)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            "/script-library",
+            get(list_library_scripts).post(create_library_script),
+        )
+        .route(
+            "/script-library/{script_id}",
+            put(update_library_script).delete(delete_library_script),
+        )
+        .route("/repos/{repo_id}/script-refs", get(get_repo_script_refs))
+        .route(
+            "/repos/{repo_id}/script-refs/{kind}",
+            put(set_repo_script_ref),
+        )
+}
@@ -1,13 +1,27 @@
+use std::path::PathBuf;
+
 use axum::{
     Router,
     extract::{Query, State},
     response::Json as ResponseJson,
     routing::get,
 };
-use db::models::repo::{Repo, SearchResult};
+use db::models::{
+    repo::{Repo, SearchResult},
+    workspace::{Workspace, WorkspaceError},
+    workspace_repo::WorkspaceRepo,
+};
 use deployment::Deployment;
 use serde::Deserialize;
-use services::services::file_search::{SearchMode, SearchQuery};
+use services::services::{
+    code_search::{
+        self, CODE_SEARCH_DEFAULT_LIMIT, CODE_SEARCH_MAX_CONTEXT, CodeSearchOptions,
+        CodeSearchResponse,
+    },
+    file_search::{SearchMode, SearchQuery, SearchRoot},
+    log_search::{self, LOG_SEARCH_MAX_RESULTS, LogSearchResult},
+    semantic_search::SemanticSearchResult,
+};
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
@@ -68,8 +82,184 @@ pub async fn search_files(
     Ok(ResponseJson(ApiResponse::success(results)))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct LogSearchQuery {
+    pub q: String,
+    pub workspace_id: Option<Uuid>,
+    pub limit: Option<i64>,
+}
+
+pub async fn search_logs(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<LogSearchQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<LogSearchResult>>>, ApiError> {
+    if query.q.trim().is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Query parameter 'q' is required and cannot be empty",
+        )));
+    }
+
+    let results = log_search::search_logs(
+        &deployment.db().pool,
+        &query.q,
+        query.workspace_id,
+        query.limit.unwrap_or(LOG_SEARCH_MAX_RESULTS),
+    )
+    .await?;
+
+    Ok(ResponseJson(ApiResponse::success(results)))
+}
+
+const SEMANTIC_SEARCH_DEFAULT_RESULTS: usize = 20;
+
+#[derive(Debug, Deserialize)]
+pub struct SemanticSearchQuery {
+    pub q: String,
+    /// Comma-separated repo ids, searched in their main checkouts.
+    pub repo_ids: Option<String>,
+    /// Search the worktrees of this workspace instead.
+    pub workspace_id: Option<Uuid>,
+    pub limit: Option<usize>,
+}
+
+/// The directories to search: the worktrees of a workspace, or else the main
+/// checkouts of the given comma-separated repo ids.
+async fn search_roots(
+    deployment: &DeploymentImpl,
+    repo_ids: Option<&str>,
+    workspace_id: Option<Uuid>,
+) -> Result<Vec<SearchRoot>, ApiError> {
+    let pool = &deployment.db().pool;
+    if let Some(workspace_id) = workspace_id {
+        let workspace = Workspace::find_by_id(pool, workspace_id)
+            .await?
+            .ok_or(WorkspaceError::WorkspaceNotFound)?;
+        let container_ref = workspace
+            .container_ref
+            .ok_or_else(|| ApiError::BadRequest("Workspace has no directory".to_string()))?;
+        let workspace_dir = PathBuf::from(container_ref);
+        return Ok(WorkspaceRepo::find_repos_for_workspace(pool, workspace_id)
+            .await?
+            .into_iter()
+            .map(|repo| SearchRoot {
+                repo_id: repo.id,
+                path: workspace_dir.join(&repo.name),
+                repo_name: repo.name,
+            })
+            .filter(|root| root.path.is_dir())
+            .collect());
+    }
+
+    let repo_ids: Vec<Uuid> = repo_ids
+        .unwrap_or_default()
+        .split(',')
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| s.trim().parse::<Uuid>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| ApiError::BadRequest("Invalid repo_id format".to_string()))?;
+    if repo_ids.is_empty() {
+        return Err(ApiError::BadRequest(
+            "repo_ids or workspace_id parameter is required".to_string(),
+        ));
+    }
+    Ok(Repo::find_by_ids(pool, &repo_ids)
+        .await?
+        .into_iter()
+        .map(|repo| SearchRoot {
+            repo_id: repo.id,
+            repo_name: repo.name,
+            path: repo.path,
+        })
+        .collect())
+}
+
+/// Search repo files by meaning rather than keywords. The first search of a
+/// repo or worktree indexes it, so it can take a while.
+pub async fn semantic_search(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<SemanticSearchQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<SemanticSearchResult>>>, ApiError> {
+    if query.q.trim().is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Query parameter 'q' is required and cannot be empty",
+        )));
+    }
+
+    let roots = search_roots(&deployment, query.repo_ids.as_deref(), query.workspace_id).await?;
+    let config = deployment.config().read().await.semantic_search.clone();
+    let results = deployment
+        .semantic_search()
+        .search(
+            &config,
+            &roots,
+            &query.q,
+            query.limit.unwrap_or(SEMANTIC_SEARCH_DEFAULT_RESULTS),
+        )
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(results)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CodeSearchQuery {
+    pub q: String,
+    /// Comma-separated repo ids, searched in their main checkouts.
+    pub repo_ids: Option<String>,
+    /// Search the worktrees of this workspace instead.
+    pub workspace_id: Option<Uuid>,
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub whole_word: bool,
+    /// Comma-separated globs; prefix one with `!` to exclude matching files.
+    pub globs: Option<String>,
+    pub context: Option<usize>,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+/// Search file contents for a literal string or regex.
+pub async fn search_code(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<CodeSearchQuery>,
+) -> Result<ResponseJson<ApiResponse<CodeSearchResponse>>, ApiError> {
+    if query.q.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Query parameter 'q' is required and cannot be empty",
+        )));
+    }
+
+    let roots = search_roots(&deployment, query.repo_ids.as_deref(), query.workspace_id).await?;
+    let options = CodeSearchOptions {
+        query: query.q,
+        regex: query.regex,
+        case_sensitive: query.case_sensitive,
+        whole_word: query.whole_word,
+        globs: query
+            .globs
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|glob| !glob.is_empty())
+            .map(str::to_string)
+            .collect(),
+        context: query.context.unwrap_or(0).min(CODE_SEARCH_MAX_CONTEXT),
+        offset: query.offset.unwrap_or(0),
+        limit: query.limit.unwrap_or(CODE_SEARCH_DEFAULT_LIMIT),
+    };
+    let response = code_search::search_code(roots, options).await?;
+
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     Router::new()
         .route("/search", get(search_files))
+        .route("/search/logs", get(search_logs))
+        .route("/search/code", get(search_code))
+        .route("/search/semantic", get(semantic_search))
         .with_state(deployment.clone())
 }
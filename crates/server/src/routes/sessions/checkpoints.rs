@@ -0,0 +1,88 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+};
+use db::models::{
+    audit_log::AuditAction,
+    session::{Session, SessionError},
+    session_checkpoint::SessionCheckpoint,
+};
+use deployment::Deployment;
+use services::services::session_checkpoints::{
+    self, CreateSessionCheckpointRequest, RestoreSessionCheckpointRequest,
+};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::audit::{self, AuditActor},
+};
+
+pub async fn list_session_checkpoints(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<SessionCheckpoint>>>, ApiError> {
+    let checkpoints =
+        SessionCheckpoint::find_by_session_id(&deployment.db().pool, session.id).await?;
+    Ok(ResponseJson(ApiResponse::success(checkpoints)))
+}
+
+pub async fn create_session_checkpoint(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateSessionCheckpointRequest>,
+) -> Result<ResponseJson<ApiResponse<SessionCheckpoint>>, ApiError> {
+    let checkpoint =
+        session_checkpoints::create_checkpoint(&deployment.db().pool, &session, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(checkpoint)))
+}
+
+/// Restore the session to a checkpoint, dropping the processes that ran after
+/// it and resetting the worktrees to its commits.
+pub async fn restore_session_checkpoint(
+    State(deployment): State<DeploymentImpl>,
+    actor: AuditActor,
+    Path((session_id, checkpoint_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<RestoreSessionCheckpointRequest>,
+) -> Result<ResponseJson<ApiResponse<SessionCheckpoint>>, ApiError> {
+    let session = Session::find_by_id(&deployment.db().pool, session_id)
+        .await?
+        .ok_or(ApiError::Session(SessionError::NotFound))?;
+    let force_when_dirty = payload.force_when_dirty.unwrap_or(false);
+
+    let checkpoint = session_checkpoints::restore_checkpoint(
+        deployment.container(),
+        &session,
+        checkpoint_id,
+        force_when_dirty,
+    )
+    .await?;
+
+    audit::record(
+        &deployment,
+        &actor,
+        AuditAction::GitReset,
+        Some(session.workspace_id),
+        Some(checkpoint.execution_process_id),
+        Some(serde_json::json!({
+            "session_id": session.id,
+            "checkpoint_id": checkpoint.id,
+            "force_when_dirty": force_when_dirty,
+        })),
+    )
+    .await;
+
+    Ok(ResponseJson(ApiResponse::success(checkpoint)))
+}
+
+pub async fn delete_session_checkpoint(
+    State(deployment): State<DeploymentImpl>,
+    Path((session_id, checkpoint_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    session_checkpoints::delete_checkpoint(&deployment.db().pool, session_id, checkpoint_id)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
@@ -0,0 +1,122 @@
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    requests::CreateAndStartWorkspaceResponse,
+    session::Session,
+    workspace::{Workspace, WorkspaceError},
+};
+use deployment::Deployment;
+use executors::profile::ExecutorConfig;
+use serde::Deserialize;
+use services::services::{container::ContainerService, session_fork};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::CurrentUser,
+    routes::{users::record_workspace_owner, workspaces::create::create_workspace_record},
+};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ForkSessionRequest {
+    /// The coding agent turn to fork at. The fork starts from the repo state
+    /// and conversation before it, and runs `prompt` in its place.
+    pub process_id: Uuid,
+    pub prompt: String,
+    pub executor_config: ExecutorConfig,
+    pub name: Option<String>,
+}
+
+/// Fork the session into a new workspace at one of its turns.
+pub async fn fork_session(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
+    Json(payload): Json<ForkSessionRequest>,
+) -> Result<ResponseJson<ApiResponse<CreateAndStartWorkspaceResponse>>, ApiError> {
+    if payload.prompt.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "A prompt is required to fork a session.".to_string(),
+        ));
+    }
+
+    let pool = &deployment.db().pool;
+    let source = Workspace::find_by_id(pool, session.workspace_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Workspace not found".to_string(),
+        )))?;
+    let fork_point =
+        session_fork::fork_point(deployment.container(), &session, payload.process_id).await?;
+
+    let name = payload
+        .name
+        .filter(|name| !name.trim().is_empty())
+        .or_else(|| source.name.as_ref().map(|name| format!("{name} (fork)")));
+    let workspace = create_workspace_record(&deployment, name, None).await?;
+    record_workspace_owner(&deployment, &current, workspace.id).await?;
+
+    let prepared = async {
+        let mut managed_workspace = deployment
+            .workspace_manager()
+            .load_managed_workspace(workspace.clone())
+            .await?;
+        for repo in &fork_point.repos {
+            managed_workspace
+                .add_repository(&repo.input, deployment.git())
+                .await?;
+        }
+
+        let git = deployment.git().clone();
+        let repos = fork_point.repos.clone();
+        let branch = workspace.branch.clone();
+        tokio::task::spawn_blocking(move || {
+            session_fork::create_fork_branches(&git, &repos, &branch)
+        })
+        .await
+        .map_err(std::io::Error::other)??;
+        Ok::<_, ApiError>(())
+    }
+    .await;
+    if let Err(e) = prepared {
+        if let Err(cleanup) = Workspace::delete(pool, workspace.id).await {
+            tracing::warn!(
+                "Failed to delete workspace {} after failed fork: {}",
+                workspace.id,
+                cleanup
+            );
+        }
+        return Err(e);
+    }
+
+    let execution_process = deployment
+        .container()
+        .start_workspace(
+            &workspace,
+            payload.executor_config.clone(),
+            session_fork::fork_prompt(fork_point.history.as_deref(), &payload.prompt),
+            None,
+        )
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "session_forked",
+            serde_json::json!({
+                "executor": &payload.executor_config.executor,
+                "variant": &payload.executor_config.variant,
+                "source_workspace_id": source.id.to_string(),
+                "workspace_id": workspace.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(
+        CreateAndStartWorkspaceResponse {
+            workspace,
+            execution_process,
+        },
+    )))
+}
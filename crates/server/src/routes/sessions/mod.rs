@@ -1,14 +1,20 @@
+pub mod checkpoints;
+pub mod fork;
 pub mod queue;
 pub mod review;
+pub mod shares;
 
 use axum::{
     Extension, Json, Router,
+    body::Body,
     extract::{Query, State},
+    http::{StatusCode, header},
     middleware::from_fn_with_state,
-    response::Json as ResponseJson,
-    routing::{get, post},
+    response::{Json as ResponseJson, Response},
+    routing::{delete, get, post},
 };
 use db::models::{
+    audit_log::AuditAction,
     coding_agent_turn::CodingAgentTurn,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
     idempotency::{is_unique_violation, normalize_idempotency_key},
@@ -26,14 +32,25 @@ use executors::{
     profile::ExecutorConfig,
 };
 use serde::Deserialize;
-use services::services::container::{ContainerService, ExecutionClaim};
+use services::services::{
+    container::{ContainerService, ExecutionClaim},
+    file::FileError,
+    repo_knowledge,
+    session_transcript::{self, TranscriptFormat},
+};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_session_middleware,
-    routes::workspaces::execution::RunScriptError,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{CurrentUser, load_session_middleware},
+    routes::{
+        audit::{self, AuditActor},
+        users::record_process_owner,
+        workspaces::execution::RunScriptError,
+    },
 };
 
 #[derive(Debug, Deserialize)]
@@ -64,6 +81,40 @@ pub async fn get_session(
     Ok(ResponseJson(ApiResponse::success(session)))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SessionTranscriptQuery {
+    #[serde(default)]
+    pub format: TranscriptFormat,
+}
+
+/// Render the session's conversation as a standalone document for sharing.
+pub async fn export_session_transcript(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<SessionTranscriptQuery>,
+) -> Result<Response, ApiError> {
+    let transcript = session_transcript::render_session_transcript(
+        deployment.container(),
+        &session,
+        query.format,
+        &[],
+    )
+    .await?;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, query.format.content_type())
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"{}\"",
+                session_transcript::transcript_file_name(session.id, query.format)
+            ),
+        )
+        .body(Body::from(transcript))
+        .map_err(|e| ApiError::File(FileError::ResponseBuildError(e.to_string())))
+}
+
 pub async fn create_session(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateSessionRequest>,
@@ -151,6 +202,8 @@ pub struct ResetProcessRequest {
 pub async fn follow_up(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
+    actor: AuditActor,
+    current: CurrentUser,
     Json(payload): Json<CreateFollowUpAttempt>,
 ) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
     let pool = &deployment.db().pool;
@@ -205,7 +258,10 @@ pub async fn follow_up(
     let prompt = payload.prompt.clone();
 
     let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
-    let cleanup_action = deployment.container().cleanup_actions_for_repos(&repos);
+    let post_agent_action = deployment
+        .container()
+        .post_agent_actions_for_repos(&repos)
+        .await;
 
     let working_dir = session
         .agent_working_dir
@@ -223,16 +279,17 @@ pub async fn follow_up(
             working_dir: working_dir.clone(),
         })
     } else {
+        let prompt = repo_knowledge::with_repo_knowledge(pool, workspace.id, prompt).await;
         ExecutorActionType::CodingAgentInitialRequest(
             executors::actions::coding_agent_initial::CodingAgentInitialRequest {
-                prompt: payload.prompt,
+                prompt,
                 executor_config: payload.executor_config.clone(),
                 working_dir,
             },
         )
     };
 
-    let action = ExecutorAction::new(action_type, cleanup_action.map(Box::new));
+    let action = ExecutorAction::new(action_type, post_agent_action.map(Box::new));
 
     let claim = deployment
         .container()
@@ -248,6 +305,7 @@ pub async fn follow_up(
     let execution_process = match claim {
         ExecutionClaim::Existing(execution_process) => execution_process,
         ExecutionClaim::Created(execution_process) => {
+            record_process_owner(&deployment, &current, execution_process.id).await?;
             if let Some(proc_id) = payload.retry_process_id {
                 let force_when_dirty = payload.force_when_dirty.unwrap_or(false);
                 let perform_git_reset = payload.perform_git_reset.unwrap_or(true);
@@ -267,13 +325,18 @@ pub async fn follow_up(
                         .write()
                         .await
                         .remove(&execution_process.id);
-                    if let Err(update_error) = ExecutionProcess::update_completion(
-                        pool,
-                        execution_process.id,
-                        ExecutionProcessStatus::Failed,
-                        None,
-                    )
-                    .await
+                    if let Err(update_error) = deployment
+                        .db()
+                        .write(
+                            "execution_processes",
+                            ExecutionProcess::update_completion(
+                                pool,
+                                execution_process.id,
+                                ExecutionProcessStatus::Failed,
+                                None,
+                            ),
+                        )
+                        .await
                     {
                         tracing::error!(
                             "Failed to mark execution process {} as failed after reset error: {}",
@@ -283,6 +346,20 @@ pub async fn follow_up(
                     }
                     return Err(err.into());
                 }
+                if perform_git_reset {
+                    audit::record(
+                        &deployment,
+                        &actor,
+                        AuditAction::GitReset,
+                        Some(workspace.id),
+                        Some(proc_id),
+                        Some(serde_json::json!({
+                            "session_id": session.id,
+                            "force_when_dirty": force_when_dirty,
+                        })),
+                    )
+                    .await;
+                }
             }
 
             deployment
@@ -309,6 +386,7 @@ pub async fn follow_up(
 pub async fn reset_process(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
+    actor: AuditActor,
     Json(payload): Json<ResetProcessRequest>,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
     let force_when_dirty = payload.force_when_dirty.unwrap_or(false);
@@ -324,6 +402,21 @@ pub async fn reset_process(
         )
         .await?;
 
+    if perform_git_reset {
+        audit::record(
+            &deployment,
+            &actor,
+            AuditAction::GitReset,
+            Some(session.workspace_id),
+            Some(payload.process_id),
+            Some(serde_json::json!({
+                "session_id": session.id,
+                "force_when_dirty": force_when_dirty,
+            })),
+        )
+        .await;
+    }
+
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
@@ -389,8 +482,18 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/", get(get_session).put(update_session))
         .route("/follow-up", post(follow_up))
         .route("/reset", post(reset_process))
+        .route("/fork", post(fork::fork_session))
         .route("/setup", post(run_setup_script))
         .route("/review", post(review::start_review))
+        .route("/transcript", get(export_session_transcript))
+        .route(
+            "/shares",
+            get(shares::list_session_shares).post(shares::create_session_share),
+        )
+        .route(
+            "/checkpoints",
+            get(checkpoints::list_session_checkpoints).post(checkpoints::create_session_checkpoint),
+        )
         .layer(from_fn_with_state(
             deployment.clone(),
             load_session_middleware,
@@ -399,6 +502,18 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let sessions_router = Router::new()
         .route("/", get(get_sessions).post(create_session))
         .nest("/{session_id}", session_id_router)
+        .route(
+            "/{session_id}/shares/{share_id}",
+            delete(shares::revoke_session_share),
+        )
+        .route(
+            "/{session_id}/checkpoints/{checkpoint_id}",
+            delete(checkpoints::delete_session_checkpoint),
+        )
+        .route(
+            "/{session_id}/checkpoints/{checkpoint_id}/restore",
+            post(checkpoints::restore_session_checkpoint),
+        )
         .nest("/{session_id}/queue", queue::router(deployment));
 
     Router::new().nest("/sessions", sessions_router)
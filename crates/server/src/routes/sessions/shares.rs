@@ -0,0 +1,46 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+};
+use db::models::session::Session;
+use deployment::Deployment;
+use services::services::session_shares::{self, CreateSessionShareRequest, SessionShareLink};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+pub async fn list_session_shares(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<SessionShareLink>>>, ApiError> {
+    let config = deployment.config().read().await.session_sharing.clone();
+    let shares = session_shares::list_shares(&deployment.db().pool, &config, session.id).await?;
+    Ok(ResponseJson(ApiResponse::success(shares)))
+}
+
+/// Publish the session under a signed, expiring read-only link.
+pub async fn create_session_share(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateSessionShareRequest>,
+) -> Result<ResponseJson<ApiResponse<SessionShareLink>>, ApiError> {
+    let config = deployment.config().read().await.session_sharing.clone();
+    let share =
+        session_shares::create_share(&deployment.db().pool, &config, session.id, &payload).await?;
+    tracing::info!(
+        "Shared session {} until {}",
+        session.id,
+        share.share.expires_at
+    );
+    Ok(ResponseJson(ApiResponse::success(share)))
+}
+
+pub async fn revoke_session_share(
+    State(deployment): State<DeploymentImpl>,
+    Path((session_id, share_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    session_shares::revoke_share(&deployment.db().pool, session_id, share_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
@@ -0,0 +1,96 @@
+//! Read-only pages behind session share links.
+//!
+//! Like approval links these are opened by people without access to the app,
+//! so they sit outside the relay signature and origin checks: the signed token
+//! is the credential. The page is static HTML with no scripts.
+
+use axum::{
+    Router,
+    extract::{Path, State},
+    http::{Response, StatusCode},
+    routing::get,
+};
+use deployment::Deployment;
+use services::services::session_shares::{self, ShareLinkError};
+
+use crate::{
+    DeploymentImpl,
+    routes::oauth::{APP_ICON_BASE64, AUTH_PAGE_STYLES},
+};
+
+fn share_response(status: StatusCode, page: String) -> Response<String> {
+    Response::builder()
+        .status(status)
+        .header("content-type", "text/html; charset=utf-8")
+        .header("cache-control", "no-store")
+        .header("referrer-policy", "no-referrer")
+        .header("x-robots-tag", "noindex")
+        .header(
+            "content-security-policy",
+            "default-src 'none'; img-src data:; style-src 'unsafe-inline'",
+        )
+        .body(page)
+        .unwrap()
+}
+
+fn share_error_page(status: StatusCode, message: &str) -> Response<String> {
+    let page = format!(
+        r#"<!doctype html>
+<html>
+  <head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <title>Shared Session</title>
+    {AUTH_PAGE_STYLES}
+  </head>
+  <body>
+    <div class="container">
+      <img class="logo" src="data:image/png;base64,{APP_ICON_BASE64}" alt="Vibe Kanban">
+      <div class="content">
+        <p class="title">{message}</p>
+        <p class="subtitle">Ask whoever shared it for a new link.</p>
+      </div>
+    </div>
+  </body>
+</html>"#
+    );
+    share_response(status, page)
+}
+
+async fn show_shared_session(
+    State(deployment): State<DeploymentImpl>,
+    Path(token): Path<String>,
+) -> Response<String> {
+    let config = deployment.config().read().await.session_sharing.clone();
+    let share = match session_shares::resolve_link(&deployment.db().pool, &config, &token).await {
+        Ok(share) => share,
+        Err(err) => {
+            let status = match err {
+                ShareLinkError::Invalid => StatusCode::NOT_FOUND,
+                ShareLinkError::Expired | ShareLinkError::Revoked => StatusCode::GONE,
+            };
+            return share_error_page(status, &err.to_string());
+        }
+    };
+
+    match session_shares::render_shared_session(deployment.container(), deployment.git(), &share)
+        .await
+    {
+        Ok(page) => share_response(StatusCode::OK, page),
+        Err(e) => {
+            tracing::error!(
+                "Failed to render shared session {}: {}",
+                share.session_id,
+                e
+            );
+            share_error_page(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "This session couldn't be loaded.",
+            )
+        }
+    }
+}
+
+pub(super) fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/shared-sessions/{token}", get(show_shared_session))
+}
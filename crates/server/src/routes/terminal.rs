@@ -1,15 +1,28 @@
-use std::path::PathBuf;
+use std::path::{Path as FsPath, PathBuf};
 
 use axum::{
-    Router,
-    extract::{Query, State, ws::Message},
-    response::IntoResponse,
-    routing::get,
+    Json, Router,
+    body::Body,
+    extract::{Path, Query, State, ws::Message},
+    http::{StatusCode, header},
+    response::{IntoResponse, Json as ResponseJson, Response},
+    routing::{get, patch},
 };
 use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use chrono::{DateTime, Utc};
 use db::models::{workspace::Workspace, workspace_repo::WorkspaceRepo};
 use deployment::Deployment;
+use local_deployment::pty::{PtyError, PtySessionInfo, PtySessionOptions};
 use serde::{Deserialize, Serialize};
+use services::services::file::FileError;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::io::ReaderStream;
+use ts_rs::TS;
+use utils::{
+    asciicast::AsciicastHeader,
+    execution_logs::{terminal_recording_path, terminal_recordings_dir},
+    response::ApiResponse,
+};
 use uuid::Uuid;
 
 use crate::{
@@ -18,6 +31,8 @@ use crate::{
     middleware::signed_ws::{MaybeSignedWebSocket, SignedWsUpgrade},
 };
 
+const MAX_TERMINALS_PER_WORKSPACE: usize = 16;
+
 #[derive(Debug, Deserialize)]
 struct TerminalQuery {
     pub workspace_id: Uuid,
@@ -35,6 +50,89 @@ fn default_rows() -> u16 {
     24
 }
 
+#[derive(Debug, Deserialize)]
+struct TerminalListQuery {
+    pub workspace_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateTerminalRequest {
+    pub workspace_id: Uuid,
+    /// Defaults to "Terminal N".
+    #[serde(default)]
+    #[ts(optional)]
+    pub name: Option<String>,
+    /// Directory to start in, relative to the workspace directory. Defaults
+    /// to the repo directory for single-repo workspaces.
+    #[serde(default)]
+    #[ts(optional)]
+    pub cwd: Option<String>,
+    #[serde(default = "default_cols")]
+    pub cols: u16,
+    #[serde(default = "default_rows")]
+    pub rows: u16,
+    /// Record the terminal's output as an asciicast file.
+    #[serde(default)]
+    pub record: bool,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateTerminalRequest {
+    #[serde(default)]
+    #[ts(optional)]
+    pub name: Option<String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub cols: Option<u16>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub rows: Option<u16>,
+}
+
+/// A named terminal of a workspace. Its shell keeps running while no client
+/// is attached, until the terminal is closed.
+#[derive(Debug, Serialize, TS)]
+pub struct TerminalInfo {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub name: String,
+    pub cwd: String,
+    pub cols: u16,
+    pub rows: u16,
+    /// False once the shell has exited.
+    pub running: bool,
+    pub recording: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An asciicast v2 recording of a workspace terminal. Recordings outlive the
+/// terminal they were made in.
+#[derive(Debug, Serialize, TS)]
+pub struct TerminalRecording {
+    pub terminal_id: Uuid,
+    pub workspace_id: Uuid,
+    pub title: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    #[ts(type = "number")]
+    pub size_bytes: u64,
+}
+
+impl TerminalInfo {
+    fn from_session(info: PtySessionInfo, workspace_id: Uuid) -> Self {
+        Self {
+            id: info.id,
+            workspace_id,
+            name: info.name.unwrap_or_default(),
+            cwd: info.working_dir.to_string_lossy().to_string(),
+            cols: info.cols,
+            rows: info.rows,
+            running: info.running,
+            recording: info.recording,
+            created_at: info.created_at,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 enum TerminalCommand {
@@ -49,12 +147,13 @@ enum TerminalMessage {
     Error { message: String },
 }
 
-async fn terminal_ws(
-    ws: SignedWsUpgrade,
-    State(deployment): State<DeploymentImpl>,
-    Query(query): Query<TerminalQuery>,
-) -> Result<impl IntoResponse, ApiError> {
-    let attempt = Workspace::find_by_id(&deployment.db().pool, query.workspace_id)
+/// The workspace directory, and the directory terminals start in by default:
+/// the repo directory for single-repo workspaces, the workspace otherwise.
+pub(super) async fn workspace_dirs(
+    deployment: &DeploymentImpl,
+    workspace_id: Uuid,
+) -> Result<(PathBuf, PathBuf), ApiError> {
+    let attempt = Workspace::find_by_id(&deployment.db().pool, workspace_id)
         .await?
         .ok_or_else(|| ApiError::BadRequest("Attempt not found".to_string()))?;
 
@@ -70,7 +169,7 @@ async fn terminal_ws(
     }
 
     let mut working_dir = base_dir.clone();
-    match WorkspaceRepo::find_repos_for_workspace(&deployment.db().pool, query.workspace_id).await {
+    match WorkspaceRepo::find_repos_for_workspace(&deployment.db().pool, workspace_id).await {
         Ok(repos) if repos.len() == 1 => {
             let repo_dir = base_dir.join(&repos[0].name);
             if repo_dir.exists() {
@@ -87,50 +186,293 @@ async fn terminal_ws(
         }
     }
 
-    Ok(ws.on_upgrade(move |socket| {
-        handle_terminal_ws(socket, deployment, working_dir, query.cols, query.rows)
+    Ok((base_dir, working_dir))
+}
+
+/// Resolve `cwd` against the workspace directory, refusing paths that leave it.
+pub(super) fn resolve_cwd(base_dir: &FsPath, cwd: &str) -> Result<PathBuf, ApiError> {
+    let invalid = || ApiError::BadRequest(format!("Invalid working directory: {cwd}"));
+    let base_dir = std::fs::canonicalize(base_dir).map_err(|_| invalid())?;
+    let dir = std::fs::canonicalize(base_dir.join(cwd)).map_err(|_| invalid())?;
+    if !dir.starts_with(&base_dir) || !dir.is_dir() {
+        return Err(invalid());
+    }
+    Ok(dir)
+}
+
+/// Find a workspace terminal; sessions of the ad-hoc `/terminal/ws` endpoint
+/// are not addressable here.
+fn find_terminal(
+    deployment: &DeploymentImpl,
+    terminal_id: Uuid,
+) -> Result<(PtySessionInfo, Uuid), ApiError> {
+    let info = deployment.pty().get(terminal_id)?;
+    let workspace_id = info
+        .workspace_id
+        .ok_or(PtyError::SessionNotFound(terminal_id))?;
+    Ok((info, workspace_id))
+}
+
+async fn terminal_ws(
+    ws: SignedWsUpgrade,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TerminalQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let (_, working_dir) = workspace_dirs(&deployment, query.workspace_id).await?;
+
+    Ok(ws.on_upgrade(move |mut socket| async move {
+        let session = match deployment
+            .pty()
+            .create_session(PtySessionOptions {
+                working_dir,
+                cols: query.cols,
+                rows: query.rows,
+                workspace_id: None,
+                name: None,
+                record: false,
+            })
+            .await
+        {
+            Ok(session) => session,
+            Err(e) => {
+                tracing::error!("Failed to create PTY session: {}", e);
+                let _ = send_error(&mut socket, &e.to_string()).await;
+                return;
+            }
+        };
+        handle_terminal_ws(socket, &deployment, session.id).await;
+        let _ = deployment.pty().close_session(session.id).await;
     }))
 }
 
+async fn list_terminals(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TerminalListQuery>,
+) -> ResponseJson<ApiResponse<Vec<TerminalInfo>>> {
+    let terminals = deployment
+        .pty()
+        .list(query.workspace_id)
+        .into_iter()
+        .map(|info| TerminalInfo::from_session(info, query.workspace_id))
+        .collect();
+    ResponseJson(ApiResponse::success(terminals))
+}
+
+async fn create_terminal(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTerminalRequest>,
+) -> Result<ResponseJson<ApiResponse<TerminalInfo>>, ApiError> {
+    let (base_dir, default_dir) = workspace_dirs(&deployment, payload.workspace_id).await?;
+    let working_dir = match payload.cwd.as_deref().map(str::trim) {
+        Some(cwd) if !cwd.is_empty() => resolve_cwd(&base_dir, cwd)?,
+        _ => default_dir,
+    };
+
+    let existing = deployment.pty().list(payload.workspace_id);
+    if existing.len() >= MAX_TERMINALS_PER_WORKSPACE {
+        return Err(ApiError::BadRequest(format!(
+            "A workspace can have at most {MAX_TERMINALS_PER_WORKSPACE} terminals"
+        )));
+    }
+    let name = payload
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("Terminal {}", existing.len() + 1));
+
+    let info = deployment
+        .pty()
+        .create_session(PtySessionOptions {
+            working_dir,
+            cols: payload.cols,
+            rows: payload.rows,
+            workspace_id: Some(payload.workspace_id),
+            name: Some(name),
+            record: payload.record,
+        })
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(
+        TerminalInfo::from_session(info, payload.workspace_id),
+    )))
+}
+
+/// Rename or resize a terminal.
+async fn update_terminal(
+    State(deployment): State<DeploymentImpl>,
+    Path(terminal_id): Path<Uuid>,
+    Json(payload): Json<UpdateTerminalRequest>,
+) -> Result<ResponseJson<ApiResponse<TerminalInfo>>, ApiError> {
+    let (info, workspace_id) = find_terminal(&deployment, terminal_id)?;
+    let pty = deployment.pty();
+
+    if let Some(name) = payload.name.as_deref().map(str::trim) {
+        if name.is_empty() {
+            return Err(ApiError::BadRequest(
+                "Terminal name must not be empty".to_string(),
+            ));
+        }
+        pty.rename(terminal_id, name.to_string())?;
+    }
+    if payload.cols.is_some() || payload.rows.is_some() {
+        pty.resize(
+            terminal_id,
+            payload.cols.unwrap_or(info.cols),
+            payload.rows.unwrap_or(info.rows),
+        )
+        .await?;
+    }
+
+    Ok(ResponseJson(ApiResponse::success(
+        TerminalInfo::from_session(pty.get(terminal_id)?, workspace_id),
+    )))
+}
+
+async fn close_terminal(
+    State(deployment): State<DeploymentImpl>,
+    Path(terminal_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    find_terminal(&deployment, terminal_id)?;
+    deployment.pty().close_session(terminal_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Recordings of a workspace's terminals, newest first.
+async fn list_terminal_recordings(
+    Query(query): Query<TerminalListQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TerminalRecording>>>, ApiError> {
+    let dir = terminal_recordings_dir(query.workspace_id);
+    let mut recordings = Vec::new();
+    let mut entries = match tokio::fs::read_dir(&dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(ResponseJson(ApiResponse::success(recordings)));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        let Some(terminal_id) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".cast"))
+            .and_then(|id| Uuid::parse_str(id).ok())
+        else {
+            continue;
+        };
+        let header = {
+            let path = path.clone();
+            tokio::task::spawn_blocking(move || AsciicastHeader::read(&path))
+                .await
+                .ok()
+                .and_then(Result::ok)
+        };
+        recordings.push(TerminalRecording {
+            terminal_id,
+            workspace_id: query.workspace_id,
+            title: header.as_ref().and_then(|header| header.title.clone()),
+            started_at: header
+                .and_then(|header| header.timestamp)
+                .and_then(|secs| DateTime::from_timestamp(secs, 0)),
+            size_bytes: entry.metadata().await?.len(),
+        });
+    }
+    recordings.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    Ok(ResponseJson(ApiResponse::success(recordings)))
+}
+
+async fn download_terminal_recording(
+    Path((workspace_id, terminal_id)): Path<(Uuid, Uuid)>,
+) -> Result<Response, ApiError> {
+    let path = terminal_recording_path(workspace_id, terminal_id);
+    let file = match tokio::fs::File::open(&path).await {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(PtyError::RecordingNotFound(terminal_id).into());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let length = file.metadata().await?.len();
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/x-asciicast")
+        .header(header::CONTENT_LENGTH, length)
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{terminal_id}.cast\""),
+        )
+        .header(header::X_CONTENT_TYPE_OPTIONS, "nosniff")
+        .body(Body::from_stream(ReaderStream::new(file)))
+        .map_err(|e| ApiError::File(FileError::ResponseBuildError(e.to_string())))
+}
+
+async fn delete_terminal_recording(
+    State(deployment): State<DeploymentImpl>,
+    Path((workspace_id, terminal_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if deployment
+        .pty()
+        .get(terminal_id)
+        .is_ok_and(|info| info.running && info.recording)
+    {
+        return Err(ApiError::Conflict(
+            "Close the terminal before deleting its recording".to_string(),
+        ));
+    }
+    match tokio::fs::remove_file(terminal_recording_path(workspace_id, terminal_id)).await {
+        Ok(()) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(PtyError::RecordingNotFound(terminal_id).into())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Attach to a workspace terminal. Detaching leaves the shell running.
+async fn attach_terminal_ws(
+    ws: SignedWsUpgrade,
+    State(deployment): State<DeploymentImpl>,
+    Path(terminal_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    find_terminal(&deployment, terminal_id)?;
+    Ok(ws.on_upgrade(move |socket| async move {
+        handle_terminal_ws(socket, &deployment, terminal_id).await;
+    }))
+}
+
+/// Relay a PTY session to a socket until either side closes. Scrollback is
+/// sent first so a re-attached client sees recent output.
 async fn handle_terminal_ws(
     mut socket: MaybeSignedWebSocket,
-    deployment: DeploymentImpl,
-    working_dir: PathBuf,
-    cols: u16,
-    rows: u16,
+    deployment: &DeploymentImpl,
+    session_id: Uuid,
 ) {
-    let (session_id, mut output_rx) = match deployment
-        .pty()
-        .create_session(working_dir, cols, rows)
-        .await
-    {
+    let pty_service = deployment.pty().clone();
+    let (scrollback, mut output_rx) = match pty_service.subscribe(session_id) {
         Ok(result) => result,
         Err(e) => {
-            tracing::error!("Failed to create PTY session: {}", e);
             let _ = send_error(&mut socket, &e.to_string()).await;
             return;
         }
     };
 
-    let pty_service = deployment.pty().clone();
-    let session_id_for_input = session_id;
+    if !scrollback.is_empty() && send_output(&mut socket, &scrollback).await.is_err() {
+        return;
+    }
 
     loop {
         tokio::select! {
             maybe_output = output_rx.recv() => {
-                let Some(data) = maybe_output else {
-                    break;
+                let data = match maybe_output {
+                    Ok(data) => data,
+                    // A slow client misses some output rather than stalling the shell.
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
                 };
 
-                let msg = TerminalMessage::Output {
-                    data: BASE64.encode(&data),
-                };
-                let json = match serde_json::to_string(&msg) {
-                    Ok(j) => j,
-                    Err(_) => continue,
-                };
-
-                if socket.send(Message::Text(json.into())).await.is_err() {
+                if send_output(&mut socket, &data).await.is_err() {
                     break;
                 }
             }
@@ -141,11 +483,11 @@ async fn handle_terminal_ws(
                             match cmd {
                                 TerminalCommand::Input { data } => {
                                     if let Ok(bytes) = BASE64.decode(&data) {
-                                        let _ = pty_service.write(session_id_for_input, &bytes).await;
+                                        let _ = pty_service.write(session_id, &bytes).await;
                                     }
                                 }
                                 TerminalCommand::Resize { cols, rows } => {
-                                    let _ = pty_service.resize(session_id_for_input, cols, rows).await;
+                                    let _ = pty_service.resize(session_id, cols, rows).await;
                                 }
                             }
                         }
@@ -161,8 +503,15 @@ async fn handle_terminal_ws(
             }
         }
     }
+}
 
-    let _ = deployment.pty().close_session(session_id).await;
+async fn send_output(socket: &mut MaybeSignedWebSocket, data: &[u8]) -> anyhow::Result<()> {
+    let msg = TerminalMessage::Output {
+        data: BASE64.encode(data),
+    };
+    let json = serde_json::to_string(&msg)?;
+    socket.send(Message::Text(json.into())).await?;
+    Ok(())
 }
 
 async fn send_error(socket: &mut MaybeSignedWebSocket, message: &str) -> anyhow::Result<()> {
@@ -176,5 +525,17 @@ async fn send_error(socket: &mut MaybeSignedWebSocket, message: &str) -> anyhow:
 }
 
 pub(super) fn router() -> Router<DeploymentImpl> {
-    Router::new().route("/terminal/ws", get(terminal_ws))
+    Router::new()
+        .route("/terminal/ws", get(terminal_ws))
+        .route("/terminals", get(list_terminals).post(create_terminal))
+        .route(
+            "/terminals/{terminal_id}",
+            patch(update_terminal).delete(close_terminal),
+        )
+        .route("/terminals/{terminal_id}/ws", get(attach_terminal_ws))
+        .route("/terminal-recordings", get(list_terminal_recordings))
+        .route(
+            "/terminal-recordings/{workspace_id}/{terminal_id}",
+            get(download_terminal_recording).delete(delete_terminal_recording),
+        )
 }
@@ -0,0 +1,238 @@
+use api_types::LocalLoginRequest;
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::{HeaderMap, header},
+    response::{IntoResponse, Json as ResponseJson},
+    routing::{get, patch, post},
+};
+use db::models::local_user::LocalUser;
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::users::{
+    self, CreateLocalUserRequest, LocalUserToken, USER_SESSION_COOKIE, UpdateLocalUserRequest,
+    UserError,
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{CurrentUser, user_session_token},
+};
+
+/// How long the session cookie is kept by the browser.
+pub(crate) const SESSION_COOKIE_MAX_AGE_SECS: u64 = 365 * 24 * 60 * 60;
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UserSignInRequest {
+    pub token: String,
+}
+
+pub(crate) fn session_cookie(token: &str, max_age_secs: u64) -> String {
+    format!(
+        "{USER_SESSION_COOKIE}={token}; Path=/; HttpOnly; SameSite=Strict; Max-Age={max_age_secs}"
+    )
+}
+
+pub async fn list_users(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<LocalUser>>>, ApiError> {
+    let users = LocalUser::list(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(users)))
+}
+
+pub async fn get_current_user(
+    current: CurrentUser,
+) -> ResponseJson<ApiResponse<Option<LocalUser>>> {
+    ResponseJson(ApiResponse::success(current.0))
+}
+
+pub async fn create_user(
+    State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
+    Json(payload): Json<CreateLocalUserRequest>,
+) -> Result<ResponseJson<ApiResponse<LocalUserToken>>, ApiError> {
+    let pool = &deployment.db().pool;
+    // Anonymous requests only act as the machine's owner while sign-in isn't
+    // required; then they may only create the first user.
+    if current.user().is_none()
+        && deployment.config().read().await.users.require_sign_in
+        && LocalUser::count(pool).await? > 0
+    {
+        return Err(ApiError::Unauthorized);
+    }
+
+    let created = users::create_user(pool, current.user(), &payload.name).await?;
+    tracing::info!("Created user {}", created.user.name);
+    Ok(ResponseJson(ApiResponse::success(created)))
+}
+
+pub async fn issue_user_token(
+    State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
+    Path(user_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<LocalUserToken>>, ApiError> {
+    let issued = users::issue_user_token(&deployment.db().pool, current.user(), user_id).await?;
+    Ok(ResponseJson(ApiResponse::success(issued)))
+}
+
+pub async fn update_user(
+    State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<UpdateLocalUserRequest>,
+) -> Result<ResponseJson<ApiResponse<LocalUser>>, ApiError> {
+    let user = users::set_user_email(
+        &deployment.db().pool,
+        current.user(),
+        user_id,
+        payload.email.as_deref(),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(user)))
+}
+
+pub async fn delete_user(
+    State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
+    Path(user_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    users::delete_user(&deployment.db().pool, current.user(), user_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Remember a token in a cookie, so the browser sends it with every request
+/// including WebSocket upgrades.
+pub async fn sign_in(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UserSignInRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let token = payload.token.trim();
+    let user = users::authenticate(&deployment.db().pool, token)
+        .await?
+        .ok_or(ApiError::Unauthorized)?;
+    Ok((
+        [(
+            header::SET_COOKIE,
+            session_cookie(token, SESSION_COOKIE_MAX_AGE_SECS),
+        )],
+        ResponseJson(ApiResponse::success(user)),
+    ))
+}
+
+/// Sign in with a remote account's email and password. Only members of the
+/// organization set in `users.remote_organization_id` get in.
+pub async fn sign_in_with_remote_login(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<LocalLoginRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let organization_id = deployment
+        .config()
+        .read()
+        .await
+        .users
+        .remote_organization_id;
+    if organization_id.is_none() {
+        return Err(UserError::RemoteSignInDisabled.into());
+    }
+    let client = deployment.remote_client()?;
+    let login = client.local_login(&payload).await?;
+    let signed_in = users::sign_in_with_remote(
+        &deployment.db().pool,
+        &client,
+        organization_id,
+        &login.access_token,
+    )
+    .await?;
+    Ok((
+        [(
+            header::SET_COOKIE,
+            session_cookie(&signed_in.token, SESSION_COOKIE_MAX_AGE_SECS),
+        )],
+        ResponseJson(ApiResponse::success(signed_in.user)),
+    ))
+}
+
+/// End the request's session and forget its cookie.
+pub async fn sign_out(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ApiError> {
+    if let Some(token) = user_session_token(&headers) {
+        users::sign_out(&deployment.db().pool, &token).await?;
+    }
+    Ok((
+        [(header::SET_COOKIE, session_cookie("", 0))],
+        ResponseJson(ApiResponse::<()>::success(())),
+    ))
+}
+
+/// Remember that the signed-in user started `workspace_id`. Anonymous requests
+/// leave it unowned.
+pub(crate) async fn record_workspace_owner(
+    deployment: &DeploymentImpl,
+    current: &CurrentUser,
+    workspace_id: Uuid,
+) -> Result<(), ApiError> {
+    if let Some(user_id) = current.id() {
+        LocalUser::set_workspace_owner(&deployment.db().pool, workspace_id, user_id).await?;
+    }
+    Ok(())
+}
+
+/// Remember that the signed-in user started `execution_process_id`, which
+/// may be in a teammate's workspace.
+pub(crate) async fn record_process_owner(
+    deployment: &DeploymentImpl,
+    current: &CurrentUser,
+    execution_process_id: Uuid,
+) -> Result<(), ApiError> {
+    if let Some(user_id) = current.id() {
+        LocalUser::set_process_owner(&deployment.db().pool, execution_process_id, user_id).await?;
+    }
+    Ok(())
+}
+
+fn ensure_can_control(current: &CurrentUser, owner: Option<Uuid>) -> Result<(), ApiError> {
+    if users::can_control(current.user(), owner) {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden(
+            "Only its owner or an admin can do this".to_string(),
+        ))
+    }
+}
+
+/// Reject the request unless the caller may stop or delete the workspace.
+pub(crate) async fn ensure_can_control_workspace(
+    deployment: &DeploymentImpl,
+    current: &CurrentUser,
+    workspace_id: Uuid,
+) -> Result<(), ApiError> {
+    let owner = LocalUser::workspace_owner(&deployment.db().pool, workspace_id).await?;
+    ensure_can_control(current, owner)
+}
+
+/// Reject the request unless the caller may stop the execution.
+pub(crate) async fn ensure_can_control_process(
+    deployment: &DeploymentImpl,
+    current: &CurrentUser,
+    execution_process_id: Uuid,
+) -> Result<(), ApiError> {
+    let owner = LocalUser::process_owner(&deployment.db().pool, execution_process_id).await?;
+    ensure_can_control(current, owner)
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/users", get(list_users).post(create_user))
+        .route("/users/me", get(get_current_user))
+        .route("/users/sign-in", post(sign_in))
+        .route("/users/sign-in/remote", post(sign_in_with_remote_login))
+        .route("/users/sign-out", post(sign_out))
+        .route("/users/{user_id}", patch(update_user).delete(delete_user))
+        .route("/users/{user_id}/tokens", post(issue_user_token))
+}
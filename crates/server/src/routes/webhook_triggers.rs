@@ -0,0 +1,246 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::Json as ResponseJson,
+    routing::{get, post, put},
+};
+use chrono::{Local, Utc};
+use db::models::{
+    requests::{CreateAndStartWorkspaceRequest, WorkspaceRepoInput},
+    webhook_trigger::{
+        CreateWebhookTrigger, UpdateWebhookTrigger, WebhookTrigger,
+        WebhookTriggerError as WebhookTriggerModelError, WebhookTriggerRun,
+        WebhookTriggerRunStatus,
+    },
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use serde_json::Value;
+use services::services::webhook_triggers::{self, WebhookTriggerError, WebhookTriggerToken};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{CurrentUser, ensure_admin},
+    routes::workspaces::create::create_and_start,
+};
+
+const DEFAULT_RUNS_LIMIT: i64 = 50;
+const MAX_RUNS_LIMIT: i64 = 500;
+
+/// Retries carrying the same key start the run once. GitHub's delivery id is
+/// accepted too, since it can't send custom headers.
+const IDEMPOTENCY_HEADERS: [&str; 2] = ["idempotency-key", "x-github-delivery"];
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 128;
+
+#[derive(Debug, Deserialize)]
+pub struct ListWebhookTriggerRunsQuery {
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookCallQuery {
+    /// Template to run; optional when the trigger has only one.
+    pub template: Option<String>,
+    /// Repo to run in; optional when the trigger allows only one.
+    pub repo_id: Option<Uuid>,
+}
+
+pub async fn list_webhook_triggers(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<WebhookTrigger>>>, ApiError> {
+    let triggers = WebhookTrigger::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(triggers)))
+}
+
+/// Triggers let outside systems start agents on this machine, so only admins
+/// manage them.
+pub async fn create_webhook_trigger(
+    State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
+    Json(payload): Json<CreateWebhookTrigger>,
+) -> Result<ResponseJson<ApiResponse<WebhookTriggerToken>>, ApiError> {
+    ensure_admin(&deployment, &current, "manage webhook triggers").await?;
+    for repo_id in &payload.repo_ids {
+        deployment
+            .repo()
+            .get_by_id(&deployment.db().pool, *repo_id)
+            .await?;
+    }
+    let created = webhook_triggers::create_trigger(&deployment.db().pool, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(created)))
+}
+
+pub async fn update_webhook_trigger(
+    State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
+    Path(trigger_id): Path<Uuid>,
+    Json(payload): Json<UpdateWebhookTrigger>,
+) -> Result<ResponseJson<ApiResponse<WebhookTrigger>>, ApiError> {
+    ensure_admin(&deployment, &current, "manage webhook triggers").await?;
+    for repo_id in payload.repo_ids.iter().flatten() {
+        deployment
+            .repo()
+            .get_by_id(&deployment.db().pool, *repo_id)
+            .await?;
+    }
+    let trigger =
+        webhook_triggers::update_trigger(&deployment.db().pool, trigger_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(trigger)))
+}
+
+pub async fn delete_webhook_trigger(
+    State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
+    Path(trigger_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ensure_admin(&deployment, &current, "manage webhook triggers").await?;
+    if WebhookTrigger::delete(&deployment.db().pool, trigger_id).await? == 0 {
+        return Err(WebhookTriggerError::from(WebhookTriggerModelError::NotFound).into());
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Issue a new webhook URL token, revoking the old one.
+pub async fn rotate_webhook_trigger_token(
+    State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
+    Path(trigger_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<WebhookTriggerToken>>, ApiError> {
+    ensure_admin(&deployment, &current, "manage webhook triggers").await?;
+    let rotated = webhook_triggers::rotate_token(&deployment.db().pool, trigger_id).await?;
+    Ok(ResponseJson(ApiResponse::success(rotated)))
+}
+
+/// Most recent runs of a trigger, newest first.
+pub async fn list_webhook_trigger_runs(
+    State(deployment): State<DeploymentImpl>,
+    Path(trigger_id): Path<Uuid>,
+    Query(query): Query<ListWebhookTriggerRunsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<WebhookTriggerRun>>>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_RUNS_LIMIT)
+        .clamp(1, MAX_RUNS_LIMIT);
+    let runs =
+        WebhookTriggerRun::find_by_trigger_id(&deployment.db().pool, trigger_id, limit).await?;
+    Ok(ResponseJson(ApiResponse::success(runs)))
+}
+
+fn idempotency_key(headers: &HeaderMap) -> Option<String> {
+    IDEMPOTENCY_HEADERS.iter().find_map(|name| {
+        headers
+            .get(*name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|key| !key.is_empty() && key.len() <= MAX_IDEMPOTENCY_KEY_LEN)
+            .map(str::to_string)
+    })
+}
+
+/// Start the run a webhook call asks for. The token in the path is the
+/// credential; the body is any JSON the caller sends, used to fill the
+/// template.
+pub async fn call_webhook(
+    State(deployment): State<DeploymentImpl>,
+    Path(token): Path<String>,
+    Query(query): Query<WebhookCallQuery>,
+    headers: HeaderMap,
+    Json(payload): Json<Value>,
+) -> Result<ResponseJson<ApiResponse<WebhookTriggerRun>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let trigger = webhook_triggers::resolve_token(pool, &token).await?;
+    let run =
+        webhook_triggers::plan_run(&trigger, query.template.as_deref(), query.repo_id, &payload)?;
+    WebhookTrigger::mark_triggered(pool, trigger.id, Utc::now()).await?;
+
+    tracing::info!(
+        "Starting webhook run of {} ({})",
+        trigger.name,
+        run.template
+    );
+    let request = CreateAndStartWorkspaceRequest {
+        name: Some(format!(
+            "{}: {} ({})",
+            trigger.name,
+            run.template,
+            Local::now().format("%Y-%m-%d %H:%M")
+        )),
+        repos: vec![WorkspaceRepoInput {
+            repo_id: run.repo_id,
+            target_branch: run.target_branch,
+            package_id: None,
+        }],
+        linked_issue: None,
+        executor_config: run.executor_config,
+        prompt: run.prompt,
+        attachment_ids: None,
+        idempotency_key: idempotency_key(&headers)
+            .map(|key| format!("webhook:{}:{key}", trigger.id)),
+        env_profile_ids: None,
+    };
+
+    match create_and_start(&deployment, request).await {
+        Ok(response) => {
+            let recorded = WebhookTriggerRun::create(
+                pool,
+                trigger.id,
+                &run.template,
+                Some(response.workspace.id),
+                WebhookTriggerRunStatus::Started,
+                None,
+            )
+            .await?;
+            Ok(ResponseJson(ApiResponse::success(recorded)))
+        }
+        Err(e) => {
+            tracing::warn!("Webhook run of {} failed: {}", trigger.name, e);
+            if let Err(record_error) = WebhookTriggerRun::create(
+                pool,
+                trigger.id,
+                &run.template,
+                None,
+                WebhookTriggerRunStatus::Failed,
+                Some(&e.to_string()),
+            )
+            .await
+            {
+                tracing::error!(
+                    "Failed to record run of webhook trigger {}: {}",
+                    trigger.id,
+                    record_error
+                );
+            }
+            Err(e)
+        }
+    }
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            "/webhook-triggers",
+            get(list_webhook_triggers).post(create_webhook_trigger),
+        )
+        .route(
+            "/webhook-triggers/{trigger_id}",
+            put(update_webhook_trigger).delete(delete_webhook_trigger),
+        )
+        .route(
+            "/webhook-triggers/{trigger_id}/rotate-token",
+            post(rotate_webhook_trigger_token),
+        )
+        .route(
+            "/webhook-triggers/{trigger_id}/runs",
+            get(list_webhook_trigger_runs),
+        )
+}
+
+/// The inbound webhook itself. Like approval links it is called from other
+/// machines, so it sits outside the relay signature and origin checks.
+pub fn hooks_router() -> Router<DeploymentImpl> {
+    Router::new().route("/hooks/{token}", post(call_webhook))
+}
@@ -0,0 +1,49 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{delete, get},
+};
+use db::models::{workspace::Workspace, workspace_context::WorkspaceContextItem};
+use deployment::Deployment;
+use services::services::workspace_context::{self, AddWorkspaceContext};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/", get(get_workspace_context).post(add_workspace_context))
+        .route("/{item_id}", delete(remove_workspace_context))
+}
+
+pub async fn get_workspace_context(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<WorkspaceContextItem>>>, ApiError> {
+    let items =
+        WorkspaceContextItem::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(items)))
+}
+
+/// Attach a file or URL. It is written to the worktree when the agent is
+/// next started.
+pub async fn add_workspace_context(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<AddWorkspaceContext>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceContextItem>>, ApiError> {
+    let item =
+        workspace_context::add_context_item(&deployment.db().pool, workspace.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(item)))
+}
+
+pub async fn remove_workspace_context(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_id, item_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    workspace_context::remove_context_item(&deployment.db().pool, workspace.id, item_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
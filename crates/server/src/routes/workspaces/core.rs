@@ -1,22 +1,38 @@
 use axum::{
     Extension, Json,
-    extract::{Query, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::Json as ResponseJson,
 };
 use db::models::{
+    audit_log::AuditAction,
     coding_agent_turn::CodingAgentTurn,
     execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    local_user::LocalUser,
     workspace::{Workspace, WorkspaceError},
 };
 use deployment::Deployment;
 use serde::Deserialize;
-use services::services::{container::ContainerService, diff_stream, remote_sync};
+use services::services::{
+    container::ContainerService,
+    diff_stream, remote_sync,
+    workspace_undo::{self, UndoneAction},
+};
 use sqlx::Error as SqlxError;
+use ts_rs::TS;
 use utils::response::ApiResponse;
+use uuid::Uuid;
 use workspace_manager::WorkspaceManager;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::CurrentUser,
+    routes::{
+        audit::{self, AuditActor},
+        users::ensure_can_control_workspace,
+    },
+};
 
 #[derive(Debug, Deserialize)]
 pub struct DeleteWorkspaceQuery {
@@ -26,11 +42,31 @@ pub struct DeleteWorkspaceQuery {
     pub delete_branches: bool,
 }
 
+/// Whose workspaces to list. Anonymous callers own the unowned ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceOwnerFilter {
+    Mine,
+    Others,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkspaceListQuery {
+    pub owner: Option<WorkspaceOwnerFilter>,
+}
+
 pub async fn get_workspaces(
     State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
+    Query(query): Query<WorkspaceListQuery>,
 ) -> Result<ResponseJson<ApiResponse<Vec<Workspace>>>, ApiError> {
     let pool = &deployment.db().pool;
-    let workspaces = Workspace::fetch_all(pool).await?;
+    let mut workspaces = Workspace::fetch_all(pool).await?;
+    if let Some(filter) = query.owner {
+        let owners = LocalUser::workspace_owners(pool).await?;
+        let mine = WorkspaceOwnerFilter::Mine == filter;
+        workspaces.retain(|workspace| (owners.get(&workspace.id).copied() == current.id()) == mine);
+    }
     Ok(ResponseJson(ApiResponse::success(workspaces)))
 }
 
@@ -99,8 +135,11 @@ pub async fn get_first_user_message(
 pub async fn delete_workspace(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
+    actor: AuditActor,
+    current: CurrentUser,
     Query(query): Query<DeleteWorkspaceQuery>,
 ) -> Result<(StatusCode, ResponseJson<ApiResponse<()>>), ApiError> {
+    ensure_can_control_workspace(&deployment, &current, workspace.id).await?;
     let pool = &deployment.db().pool;
     let workspace_manager = deployment.workspace_manager();
     let workspace_id = workspace.id;
@@ -138,6 +177,21 @@ pub async fn delete_workspace(
         }
     }
 
+    deployment
+        .pty()
+        .close_workspace_sessions(workspace_id)
+        .await;
+
+    if let Err(e) =
+        workspace_undo::record_workspace_deletion(pool, deployment.git(), &workspace).await
+    {
+        tracing::warn!(
+            "Failed to record undo of workspace {} deletion: {}",
+            workspace_id,
+            e
+        );
+    }
+
     let managed_workspace = workspace_manager.load_managed_workspace(workspace).await?;
     let deletion_context = managed_workspace.prepare_deletion_context().await?;
     let rows_affected = managed_workspace.delete_record().await?;
@@ -146,6 +200,19 @@ pub async fn delete_workspace(
         return Err(ApiError::Database(SqlxError::RowNotFound));
     }
 
+    audit::record(
+        &deployment,
+        &actor,
+        AuditAction::WorkspaceDeleted,
+        Some(workspace_id),
+        None,
+        Some(serde_json::json!({
+            "delete_remote": query.delete_remote,
+            "delete_branches": query.delete_branches,
+        })),
+    )
+    .await;
+
     deployment
         .track_if_analytics_allowed(
             "workspace_deleted",
@@ -178,10 +245,47 @@ pub async fn delete_workspace(
     }
 
     WorkspaceManager::spawn_workspace_deletion_cleanup(deletion_context, query.delete_branches);
+    // Attachments only referenced by the deleted workspace's sessions.
+    deployment.file().spawn_orphaned_file_cleanup();
 
     Ok((StatusCode::ACCEPTED, ResponseJson(ApiResponse::success(()))))
 }
 
+/// Undo the most recent deletion or git reset of a workspace. Mounted outside
+/// the workspace middleware, since deleted workspaces don't load.
+pub async fn undo_last_action(
+    State(deployment): State<DeploymentImpl>,
+    actor: AuditActor,
+    Path(workspace_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<UndoneAction>>, ApiError> {
+    let undone = workspace_undo::undo_latest(deployment.container(), workspace_id).await?;
+
+    audit::record(
+        &deployment,
+        &actor,
+        AuditAction::UndoApplied,
+        Some(workspace_id),
+        None,
+        Some(serde_json::json!({
+            "action": undone.action,
+            "performed_at": undone.performed_at,
+        })),
+    )
+    .await;
+
+    deployment
+        .track_if_analytics_allowed(
+            "workspace_action_undone",
+            serde_json::json!({
+                "workspace_id": workspace_id.to_string(),
+                "action": undone.action,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(undone)))
+}
+
 #[axum::debug_handler]
 pub async fn mark_seen(
     Extension(workspace): Extension<Workspace>,
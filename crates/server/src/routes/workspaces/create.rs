@@ -11,15 +11,17 @@ use db::models::{
     workspace::{CreateWorkspace, Workspace, WorkspaceError},
 };
 use deployment::Deployment;
-use services::services::container::ContainerService;
+use services::services::{container::ContainerService, env_profiles};
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
 use crate::{
     DeploymentImpl,
     error::ApiError,
-    routes::workspaces::attachments::{
-        ImportedIssueAttachment, import_issue_attachments_from_remote,
+    middleware::CurrentUser,
+    routes::{
+        users::record_workspace_owner,
+        workspaces::attachments::{ImportedIssueAttachment, import_issue_attachments_from_remote},
     },
 };
 
@@ -76,10 +78,12 @@ pub(crate) async fn create_workspace_record(
 
 pub async fn create_workspace(
     State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
     Json(payload): Json<CreateWorkspaceApiRequest>,
 ) -> Result<ResponseJson<ApiResponse<Workspace>>, ApiError> {
     let workspace =
         create_workspace_record(&deployment, payload.name, payload.idempotency_key).await?;
+    record_workspace_owner(&deployment, &current, workspace.id).await?;
 
     deployment
         .track_if_analytics_allowed(
@@ -239,8 +243,20 @@ fn rewrite_imported_issue_attachments_markdown(
 
 pub async fn create_and_start_workspace(
     State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
     Json(payload): Json<CreateAndStartWorkspaceRequest>,
 ) -> Result<ResponseJson<ApiResponse<CreateAndStartWorkspaceResponse>>, ApiError> {
+    let response = create_and_start(&deployment, payload).await?;
+    record_workspace_owner(&deployment, &current, response.workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
+/// Create a workspace, attach its repos and start the prompt in it. Shared by
+/// the API route and the scheduler.
+pub(crate) async fn create_and_start(
+    deployment: &DeploymentImpl,
+    payload: CreateAndStartWorkspaceRequest,
+) -> Result<CreateAndStartWorkspaceResponse, ApiError> {
     let CreateAndStartWorkspaceRequest {
         name,
         repos,
@@ -249,6 +265,7 @@ pub async fn create_and_start_workspace(
         prompt,
         attachment_ids,
         idempotency_key,
+        env_profile_ids,
     } = payload;
     let idempotency_key = normalize_idempotency_key(idempotency_key);
 
@@ -275,7 +292,7 @@ pub async fn create_and_start_workspace(
     let mut managed_workspace = deployment
         .workspace_manager()
         .load_managed_workspace(
-            create_workspace_record(&deployment, name, idempotency_key.clone()).await?,
+            create_workspace_record(deployment, name, idempotency_key.clone()).await?,
         )
         .await?;
     if let Some(key) = idempotency_key.as_deref() {
@@ -294,12 +311,10 @@ pub async fn create_and_start_workspace(
             )
             .await?
         {
-            return Ok(ResponseJson(ApiResponse::success(
-                CreateAndStartWorkspaceResponse {
-                    workspace: managed_workspace.workspace,
-                    execution_process,
-                },
-            )));
+            return Ok(CreateAndStartWorkspaceResponse {
+                workspace: managed_workspace.workspace,
+                execution_process,
+            });
         }
     }
 
@@ -322,6 +337,19 @@ pub async fn create_and_start_workspace(
         managed_workspace.associate_attachments(ids).await?;
     }
 
+    if let Some(ids) = &env_profile_ids {
+        env_profiles::select_for_workspace(
+            &deployment.db().pool,
+            managed_workspace.workspace.id,
+            ids,
+        )
+        .await?;
+    }
+    // Fail before creating a session if a selected profile is missing a
+    // required variable.
+    env_profiles::resolve_workspace_env(&deployment.db().pool, managed_workspace.workspace.id)
+        .await?;
+
     if let Some(linked_issue) = &linked_issue
         && let Ok(client) = deployment.remote_client()
     {
@@ -388,12 +416,10 @@ pub async fn create_and_start_workspace(
         )
         .await;
 
-    Ok(ResponseJson(ApiResponse::success(
-        CreateAndStartWorkspaceResponse {
-            workspace,
-            execution_process,
-        },
-    )))
+    Ok(CreateAndStartWorkspaceResponse {
+        workspace,
+        execution_process,
+    })
 }
 
 #[cfg(test)]
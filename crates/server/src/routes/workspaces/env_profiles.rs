@@ -0,0 +1,46 @@
+use axum::{Extension, Json, Router, extract::State, response::Json as ResponseJson, routing::get};
+use db::models::{env_profile::EnvProfile, workspace::Workspace};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::env_profiles;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct SetWorkspaceEnvProfilesRequest {
+    pub profile_ids: Vec<Uuid>,
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route(
+        "/",
+        get(get_workspace_env_profiles).put(set_workspace_env_profiles),
+    )
+}
+
+pub async fn get_workspace_env_profiles(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<EnvProfile>>>, ApiError> {
+    let profiles = EnvProfile::find_for_workspace(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(profiles)))
+}
+
+/// Replace the workspace's selected profiles. Applies to processes started
+/// after the change.
+pub async fn set_workspace_env_profiles(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetWorkspaceEnvProfilesRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<EnvProfile>>>, ApiError> {
+    let profiles = env_profiles::select_for_workspace(
+        &deployment.db().pool,
+        workspace.id,
+        &payload.profile_ids,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(profiles)))
+}
@@ -1,6 +1,8 @@
 use axum::{Extension, Router, extract::State, response::Json as ResponseJson, routing::post};
 use db::models::{
+    audit_log::AuditAction,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    repo_package::RepoPackage,
     session::{CreateSession, Session},
     workspace::Workspace,
     workspace_repo::WorkspaceRepo,
@@ -12,11 +14,20 @@ use executors::actions::{
 };
 use serde::{Deserialize, Serialize};
 use services::services::container::ContainerService;
+use sqlx::SqlitePool;
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::CurrentUser,
+    routes::{
+        audit::{self, AuditActor},
+        users::ensure_can_control_workspace,
+    },
+};
 
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -56,7 +67,18 @@ pub async fn start_dev_server(
             }
         };
 
-    for dev_server in existing_dev_servers {
+    // Named dev servers are started and stopped on their own.
+    let named_dev_servers: Vec<Uuid> =
+        ExecutionProcess::find_running_named_dev_servers_by_workspace(pool, workspace.id)
+            .await?
+            .into_iter()
+            .map(|(execution_process_id, _)| execution_process_id)
+            .collect();
+
+    for dev_server in existing_dev_servers
+        .into_iter()
+        .filter(|dev_server| !named_dev_servers.contains(&dev_server.id))
+    {
         tracing::info!(
             "Stopping existing dev server {} for workspace {}",
             dev_server.id,
@@ -73,42 +95,42 @@ pub async fn start_dev_server(
     }
 
     let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
-    let repos_with_dev_script: Vec<_> = repos
+    let packages = RepoPackage::find_selected_for_workspace(pool, workspace.id).await?;
+    // The dev script of a selected package replaces the repo's and runs in the
+    // package directory.
+    let dev_scripts: Vec<(String, String)> = repos
         .iter()
-        .filter(|r| r.dev_server_script.as_ref().is_some_and(|s| !s.is_empty()))
+        .filter_map(|repo| {
+            let package_script = packages
+                .iter()
+                .find(|package| package.repo_id == repo.id)
+                .and_then(|package| {
+                    let script = package.dev_server_script.clone()?;
+                    Some((script, format!("{}/{}", repo.name, package.path)))
+                });
+            package_script.or_else(|| {
+                let script = repo.dev_server_script.clone().filter(|s| !s.is_empty())?;
+                Some((script, repo.name.clone()))
+            })
+        })
         .collect();
 
-    if repos_with_dev_script.is_empty() {
+    if dev_scripts.is_empty() {
         return Ok(ResponseJson(ApiResponse::error(
             "No dev server script configured for any repository in this workspace",
         )));
     }
 
-    let session = match Session::find_latest_by_workspace_id(pool, workspace.id).await? {
-        Some(s) => s,
-        None => {
-            Session::create(
-                pool,
-                &CreateSession {
-                    executor: Some("dev-server".to_string()),
-                    name: None,
-                    idempotency_key: None,
-                },
-                Uuid::new_v4(),
-                workspace.id,
-            )
-            .await?
-        }
-    };
+    let session = dev_server_session(pool, workspace.id).await?;
 
     let mut execution_processes = Vec::new();
-    for repo in repos_with_dev_script {
+    for (script, working_dir) in dev_scripts {
         let executor_action = ExecutorAction::new(
             ExecutorActionType::ScriptRequest(ScriptRequest {
-                script: repo.dev_server_script.clone().unwrap(),
+                script,
                 language: ScriptRequestLanguage::Bash,
                 context: ScriptContext::DevServer,
-                working_dir: Some(repo.name.clone()),
+                working_dir: Some(working_dir),
             }),
             None,
         );
@@ -137,12 +159,47 @@ pub async fn start_dev_server(
     Ok(ResponseJson(ApiResponse::success(execution_processes)))
 }
 
+/// The session dev servers run in: the workspace's latest session, or a new
+/// one if it has none yet.
+pub(crate) async fn dev_server_session(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+) -> Result<Session, ApiError> {
+    match Session::find_latest_by_workspace_id(pool, workspace_id).await? {
+        Some(session) => Ok(session),
+        None => Ok(Session::create(
+            pool,
+            &CreateSession {
+                executor: Some("dev-server".to_string()),
+                name: None,
+                idempotency_key: None,
+            },
+            Uuid::new_v4(),
+            workspace_id,
+        )
+        .await?),
+    }
+}
+
 pub async fn stop_workspace_execution(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
+    actor: AuditActor,
+    current: CurrentUser,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ensure_can_control_workspace(&deployment, &current, workspace.id).await?;
     deployment.container().try_stop(&workspace, false).await;
 
+    audit::record(
+        &deployment,
+        &actor,
+        AuditAction::WorkspaceStopped,
+        Some(workspace.id),
+        None,
+        None,
+    )
+    .await;
+
     deployment
         .track_if_analytics_allowed(
             "task_attempt_stopped",
@@ -0,0 +1,37 @@
+use axum::{
+    Extension,
+    body::Body,
+    extract::State,
+    http::{StatusCode, header},
+    response::Response,
+};
+use db::models::workspace::Workspace;
+use deployment::Deployment;
+use services::services::{file::FileError, workspace_export};
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Download the workspace as a tarball with each repo's branch as a git
+/// bundle, the diff, and the raw and normalized logs of every run.
+pub async fn export_workspace(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<Response, ApiError> {
+    let archive =
+        workspace_export::export_workspace(deployment.container(), deployment.git(), &workspace)
+            .await?;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/gzip")
+        .header(header::CONTENT_LENGTH, archive.len())
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"{}\"",
+                workspace_export::export_file_name(&workspace)
+            ),
+        )
+        .body(Body::from(archive))
+        .map_err(|e| ApiError::File(FileError::ResponseBuildError(e.to_string())))
+}
@@ -5,7 +5,7 @@ use std::{
 
 use axum::{
     Extension, Json, Router,
-    extract::State,
+    extract::{Query, State},
     response::{IntoResponse, Json as ResponseJson},
     routing::{get, post},
 };
@@ -16,11 +16,17 @@ use db::models::{
     workspace_repo::WorkspaceRepo,
 };
 use deployment::Deployment;
-use git::{ConflictOp, GitCliError, GitServiceError};
+use git::{ConflictOp, GitCliError, GitServiceError, LeasedPush, WorktreeSnapshot};
 use serde::{Deserialize, Serialize};
-use services::services::{container::ContainerService, diff_stream, remote_sync};
+use services::services::{
+    container::ContainerService,
+    diff_stream,
+    merge_readiness::{self, WorkspaceMergeReadiness},
+    push_preflight::{self, PreflightFinding, PushPreflight},
+    remote_sync,
+};
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{diff::Diff, response::ApiResponse};
 use uuid::Uuid;
 
 use super::streams::{DiffStreamQuery, stream_workspace_diff_ws};
@@ -66,11 +72,54 @@ pub struct PushWorkspaceRequest {
     pub repo_id: Uuid,
 }
 
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct PushWithLeaseRequest {
+    pub repo_id: Uuid,
+    /// Track the pushed branch, so later pulls and status checks use it.
+    /// Defaults to true.
+    pub set_upstream: Option<bool>,
+}
+
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[ts(tag = "type", rename_all = "snake_case")]
 pub enum PushError {
     ForcePushRequired,
+    ForcePushBlocked {
+        findings: Vec<PreflightFinding>,
+    },
+    BranchProtected {
+        message: String,
+    },
+    /// The remote branch has commits that weren't pushed from this workspace,
+    /// e.g. commits a human pushed on top of the agent's work.
+    RemoteBranchMoved {
+        ahead: usize,
+        behind: usize,
+    },
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct PushPreflightQuery {
+    pub repo_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct WorktreeSnapshotsQuery {
+    pub repo_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct DiffFileQuery {
+    pub repo_id: Uuid,
+    /// Path relative to the repository root.
+    pub path: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct RestoreWorktreeSnapshotRequest {
+    pub repo_id: Uuid,
+    pub snapshot_id: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -137,10 +186,16 @@ pub enum RenameBranchError {
 pub fn router() -> Router<DeploymentImpl> {
     Router::new()
         .route("/status", get(get_workspace_branch_status))
+        .route("/merge-readiness", get(get_workspace_merge_readiness))
         .route("/diff/ws", get(stream_diff_ws))
+        .route("/diff/file", get(get_diff_file))
         .route("/merge", post(merge_workspace))
         .route("/push", post(push_workspace_branch))
         .route("/push/force", post(force_push_workspace_branch))
+        .route("/push/lease", post(push_workspace_branch_with_lease))
+        .route("/push/preflight", get(get_push_preflight))
+        .route("/snapshots", get(list_worktree_snapshots))
+        .route("/snapshots/restore", post(restore_worktree_snapshot))
         .route("/rebase", post(rebase_workspace))
         .route("/rebase/continue", post(continue_workspace_rebase))
         .route("/conflicts/abort", post(abort_workspace_conflicts))
@@ -175,6 +230,52 @@ pub async fn stream_diff_ws(
     stream_workspace_diff_ws(ws, query, workspace, deployment).await
 }
 
+/// Load one file's diff with its contents, for files the diff stream sent
+/// without them. Returns `null` when the file no longer differs.
+pub async fn get_diff_file(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<DiffFileQuery>,
+) -> Result<ResponseJson<ApiResponse<Option<Diff>>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let worktree_path = Path::new(&container_ref).join(&repo.name);
+    let base_commit = deployment.git().get_base_commit(
+        &repo.path,
+        &workspace.branch,
+        &workspace_repo.target_branch,
+    )?;
+    let max_file_bytes = deployment.config().read().await.diff.max_file_bytes();
+
+    let git = deployment.git().clone();
+    let diff = tokio::task::spawn_blocking(move || {
+        diff_stream::load_file_diff(
+            &git,
+            &worktree_path,
+            &base_commit,
+            &query.path,
+            max_file_bytes,
+            Some(&repo.name),
+            repo.id,
+        )
+    })
+    .await
+    .map_err(std::io::Error::other)??;
+    Ok(ResponseJson(ApiResponse::success(diff)))
+}
+
 #[axum::debug_handler]
 pub async fn merge_workspace(
     Extension(workspace): Extension<Workspace>,
@@ -315,6 +416,9 @@ pub async fn push_workspace_branch(
         Err(GitServiceError::GitCLI(GitCliError::PushRejected(_))) => Ok(ResponseJson(
             ApiResponse::error_with_data(PushError::ForcePushRequired),
         )),
+        Err(GitServiceError::GitCLI(GitCliError::ProtectedBranch(message))) => Ok(ResponseJson(
+            ApiResponse::error_with_data(PushError::BranchProtected { message }),
+        )),
         Err(e) => Err(ApiError::GitService(e)),
     }
 }
@@ -342,9 +446,34 @@ pub async fn force_push_workspace_branch(
     let workspace_path = Path::new(&container_ref);
     let worktree_path = workspace_path.join(&repo.name);
 
-    deployment
+    let preflight = push_preflight::preflight_push(
+        deployment.git(),
+        &repo.path,
+        &worktree_path,
+        &workspace.branch,
+        &workspace_repo.target_branch,
+    )
+    .await?;
+    if !preflight.can_force_push {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            PushError::ForcePushBlocked {
+                findings: preflight.findings,
+            },
+        )));
+    }
+
+    match deployment
         .git()
-        .push_to_remote(&worktree_path, &workspace.branch, true)?;
+        .push_to_remote(&worktree_path, &workspace.branch, true)
+    {
+        Ok(_) => {}
+        Err(GitServiceError::GitCLI(GitCliError::ProtectedBranch(message))) => {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                PushError::BranchProtected { message },
+            )));
+        }
+        Err(e) => return Err(ApiError::GitService(e)),
+    }
 
     if let Ok(client) = deployment.remote_client() {
         let pool = deployment.db().pool.clone();
@@ -360,6 +489,175 @@ pub async fn force_push_workspace_branch(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// Push the workspace branch with `--force-with-lease`, so a rewritten branch
+/// can be re-pushed without dropping commits someone else pushed to it.
+pub async fn push_workspace_branch_with_lease(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<PushWithLeaseRequest>,
+) -> Result<ResponseJson<ApiResponse<LeasedPush, PushError>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+
+    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let worktree_path = Path::new(&container_ref).join(&repo.name);
+
+    let pushed = match deployment.git().push_with_lease(
+        &worktree_path,
+        &workspace.branch,
+        request.set_upstream.unwrap_or(true),
+    ) {
+        Ok(pushed) => pushed,
+        Err(GitServiceError::RemoteBranchMoved { ahead, behind }) => {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                PushError::RemoteBranchMoved { ahead, behind },
+            )));
+        }
+        Err(GitServiceError::GitCLI(GitCliError::ProtectedBranch(message))) => {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                PushError::BranchProtected { message },
+            )));
+        }
+        Err(e) => return Err(ApiError::GitService(e)),
+    };
+
+    if let Ok(client) = deployment.remote_client() {
+        let pool = deployment.db().pool.clone();
+        let git = deployment.git().clone();
+        let mut ws = workspace.clone();
+        ws.container_ref = Some(container_ref.clone());
+        tokio::spawn(async move {
+            let stats = diff_stream::compute_diff_stats(&pool, &git, &ws).await;
+            remote_sync::sync_workspace_to_remote(&client, ws.id, None, None, stats.as_ref()).await;
+        });
+    }
+
+    Ok(ResponseJson(ApiResponse::success(pushed)))
+}
+
+/// Check the workspace branch and its target branch against the provider's
+/// branch protection before pushing or opening a PR.
+pub async fn get_push_preflight(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<PushPreflightQuery>,
+) -> Result<ResponseJson<ApiResponse<PushPreflight>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+
+    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let worktree_path = Path::new(&container_ref).join(&repo.name);
+
+    let preflight = push_preflight::preflight_push(
+        deployment.git(),
+        &repo.path,
+        &worktree_path,
+        &workspace.branch,
+        &workspace_repo.target_branch,
+    )
+    .await?;
+
+    Ok(ResponseJson(ApiResponse::success(preflight)))
+}
+
+/// Snapshots of uncommitted changes taken before risky operations on the
+/// workspace branch, newest first.
+pub async fn list_worktree_snapshots(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<WorktreeSnapshotsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<WorktreeSnapshot>>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let snapshots = deployment
+        .git()
+        .list_snapshots(&repo.path, &workspace.branch)?;
+    Ok(ResponseJson(ApiResponse::success(snapshots)))
+}
+
+/// Re-apply a snapshot's changes to the workspace as uncommitted changes.
+pub async fn restore_worktree_snapshot(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<RestoreWorktreeSnapshotRequest>,
+) -> Result<ResponseJson<ApiResponse<WorktreeSnapshot>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let worktree_path = Path::new(&container_ref).join(&repo.name);
+
+    let snapshot = deployment.git().restore_snapshot(
+        &worktree_path,
+        &workspace.branch,
+        &request.snapshot_id,
+    )?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "worktree_snapshot_restored",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_id": repo.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(snapshot)))
+}
+
+/// Ahead/behind counts and whether each repo's branch merges cleanly into its
+/// target branch. Unlike `/status`, this neither fetches nor needs a worktree.
+pub async fn get_workspace_merge_readiness(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceMergeReadiness>>, ApiError> {
+    let readiness =
+        merge_readiness::workspace_readiness(&deployment.db().pool, deployment.git(), &workspace)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(readiness)))
+}
+
 pub async fn get_workspace_branch_status(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
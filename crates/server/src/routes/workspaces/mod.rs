@@ -1,9 +1,12 @@
 pub mod attachments;
 pub mod codex_setup;
+pub mod context;
 pub mod core;
 pub mod create;
 pub mod cursor_setup;
+pub mod env_profiles;
 pub mod execution;
+pub mod export;
 pub mod gh_cli_setup;
 pub mod git;
 pub mod integration;
@@ -31,10 +34,14 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         )
         .route("/messages/first", get(core::get_first_user_message))
         .route("/seen", axum::routing::put(core::mark_seen))
+        .route("/export", get(export::export_workspace))
+        .route("/files/ws", get(streams::stream_workspace_files_ws))
         .nest("/git", git::router())
         .nest("/execution", execution::router())
         .nest("/integration", integration::router())
         .nest("/repos", repos::router())
+        .nest("/env-profiles", env_profiles::router())
+        .nest("/context", context::router())
         .nest("/pull-requests", pr::router())
         .layer(from_fn_with_state(
             deployment.clone(),
@@ -53,6 +60,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/summaries",
             post(workspace_summary::get_workspace_summaries),
         )
+        .route("/{id}/undo", post(core::undo_last_action))
         .nest("/{id}", workspace_id_router)
         .nest("/{id}/attachments", attachments::router(deployment))
         .nest("/{id}/links", links::router(deployment));
@@ -29,14 +29,19 @@ use git_host::{
 };
 use serde::{Deserialize, Serialize};
 use services::services::{
-    config::DEFAULT_PR_DESCRIPTION_PROMPT, container::ContainerService, remote_sync,
+    code_owners::{CodeOwners, CodeOwnersMatch},
+    config::DEFAULT_PR_DESCRIPTION_PROMPT,
+    container::ContainerService,
+    remote_sync,
 };
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 use workspace_manager::WorkspaceManager;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::CurrentUser, routes::users::record_workspace_owner,
+};
 
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct CreatePrApiRequest {
@@ -61,6 +66,7 @@ pub enum PrError {
     GitCliNotInstalled,
     TargetBranchNotFound { branch: String },
     UnsupportedProvider,
+    BranchProtected { message: String },
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -95,6 +101,11 @@ pub struct GetPrCommentsQuery {
     pub repo_id: Uuid,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct GetPrReviewersQuery {
+    pub repo_id: Uuid,
+}
+
 async fn trigger_pr_follow_up(
     deployment: &DeploymentImpl,
     workspace: &Workspace,
@@ -286,6 +297,11 @@ pub async fn create_pr(
                     PrError::GitCliNotInstalled,
                 )));
             }
+            GitServiceError::GitCLI(GitCliError::ProtectedBranch(message)) => {
+                return Ok(ResponseJson(ApiResponse::error_with_data(
+                    PrError::BranchProtected { message },
+                )));
+            }
             _ => return Err(ApiError::GitService(e)),
         }
     }
@@ -715,6 +731,7 @@ async fn cleanup_failed_pr_workspace(pool: &sqlx::SqlitePool, workspace: &Worksp
 #[axum::debug_handler]
 pub async fn create_workspace_from_pr(
     State(deployment): State<DeploymentImpl>,
+    current: CurrentUser,
     Json(payload): Json<CreateWorkspaceFromPrBody>,
 ) -> Result<ResponseJson<ApiResponse<CreateWorkspaceFromPrResponse, CreateFromPrError>>, ApiError> {
     let pool = &deployment.db().pool;
@@ -746,6 +763,7 @@ pub async fn create_workspace_from_pr(
         workspace_id,
     )
     .await?;
+    record_workspace_owner(&deployment, &current, workspace.id).await?;
 
     WorkspaceRepo::create_many(
         pool,
@@ -768,9 +786,9 @@ pub async fn create_workspace_from_pr(
     // Use gh pr checkout to fetch and switch to the PR branch
     // This handles SSH/HTTPS auth correctly regardless of fork URL format
     let worktree_path = PathBuf::from(&container_ref).join(&repo.name);
-    match GhCli::new().get_repo_info(&remote.url, &worktree_path) {
+    match GhCli::for_repo(&worktree_path).get_repo_info(&remote.url, &worktree_path) {
         Ok(repo_info) => {
-            if let Err(e) = GhCli::new().pr_checkout(
+            if let Err(e) = GhCli::for_repo(&worktree_path).pr_checkout(
                 &worktree_path,
                 &repo_info.owner,
                 &repo_info.repo_name,
@@ -867,9 +885,51 @@ pub async fn create_workspace_from_pr(
     )))
 }
 
+/// Owners of the files the workspace changed in a repo, going by the repo's
+/// CODEOWNERS file, to suggest as reviewers of its PR.
+pub async fn get_pr_reviewer_suggestions(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<GetPrReviewersQuery>,
+) -> Result<ResponseJson<ApiResponse<CodeOwnersMatch>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+    let base_commit = deployment.git().get_base_commit(
+        &repo.path,
+        &workspace.branch,
+        &workspace_repo.target_branch,
+    )?;
+    let git = deployment.git().clone();
+    let changed =
+        tokio::task::spawn_blocking(move || git.get_diff_file_paths(&worktree_path, &base_commit))
+            .await
+            .map_err(std::io::Error::other)??;
+    let mut changed: Vec<String> = changed.into_iter().collect();
+    changed.sort();
+
+    let code_owners = CodeOwners::load(&repo.path).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        code_owners.match_paths(changed),
+    )))
+}
+
 pub fn router() -> Router<DeploymentImpl> {
     Router::new()
         .route("/", post(create_pr))
         .route("/attach", post(attach_existing_pr))
         .route("/comments", get(get_pr_comments))
+        .route("/reviewers", get(get_pr_reviewer_suggestions))
 }
@@ -17,6 +17,9 @@ use crate::{DeploymentImpl, error::ApiError};
 pub struct AddWorkspaceRepoRequest {
     pub repo_id: Uuid,
     pub target_branch: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub package_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -53,6 +56,7 @@ pub async fn add_workspace_repo(
     let repo_input = WorkspaceRepoInput {
         repo_id: payload.repo_id,
         target_branch: payload.target_branch,
+        package_id: payload.package_id,
     };
 
     managed_workspace
@@ -1,14 +1,20 @@
+use std::path::PathBuf;
+
 use axum::{
     Extension,
     extract::{Query, State, ws::Message},
     response::IntoResponse,
 };
 use deployment::Deployment;
-use serde::Deserialize;
-use services::services::container::ContainerService;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    container::ContainerService,
+    filesystem_watcher::{self, FileChange},
+};
 
 use crate::{
     DeploymentImpl,
+    error::ApiError,
     middleware::signed_ws::{MaybeSignedWebSocket, SignedWsUpgrade},
 };
 
@@ -52,6 +58,101 @@ pub async fn stream_workspace_diff_ws(
     })
 }
 
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum FileWatchMessage {
+    /// Sent once the watcher is in place; changes after this are reported.
+    Ready,
+    Changes {
+        changes: Vec<FileChange>,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl FileWatchMessage {
+    fn to_ws_message(&self) -> Message {
+        Message::Text(serde_json::to_string(self).unwrap_or_default().into())
+    }
+}
+
+/// Stream create/modify/remove events for files in the workspace directory,
+/// skipping gitignored paths.
+pub async fn stream_workspace_files_ws(
+    ws: SignedWsUpgrade,
+    Extension(workspace): Extension<db::models::workspace::Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<impl IntoResponse, ApiError> {
+    let root = workspace
+        .container_ref
+        .as_deref()
+        .filter(|container_ref| !container_ref.is_empty())
+        .map(PathBuf::from)
+        .filter(|root| root.is_dir())
+        .ok_or_else(|| ApiError::BadRequest("Workspace directory does not exist".to_string()))?;
+    let _ = deployment.container().touch(&workspace).await;
+
+    Ok(ws.on_upgrade(move |socket| async move {
+        if let Err(e) = handle_workspace_files_ws(socket, root).await {
+            tracing::warn!("files WS closed: {}", e);
+        }
+    }))
+}
+
+async fn handle_workspace_files_ws(
+    mut socket: MaybeSignedWebSocket,
+    root: PathBuf,
+) -> anyhow::Result<()> {
+    use futures_util::StreamExt;
+
+    let watcher =
+        tokio::task::spawn_blocking(move || filesystem_watcher::async_watcher(root)).await?;
+    let (_debouncer, mut events, canonical_root) = match watcher {
+        Ok(components) => components,
+        Err(e) => {
+            let message = FileWatchMessage::Error {
+                message: e.to_string(),
+            };
+            socket.send(message.to_ws_message()).await?;
+            return Err(e.into());
+        }
+    };
+    socket.send(FileWatchMessage::Ready.to_ws_message()).await?;
+
+    loop {
+        tokio::select! {
+            item = events.next() => {
+                let message = match item {
+                    Some(Ok(events)) => {
+                        let changes = filesystem_watcher::file_changes(&events, &canonical_root);
+                        if changes.is_empty() {
+                            continue;
+                        }
+                        FileWatchMessage::Changes { changes }
+                    }
+                    Some(Err(errors)) => FileWatchMessage::Error {
+                        message: format!("{errors:?}"),
+                    },
+                    None => break,
+                };
+                if socket.send(message.to_ws_message()).await.is_err() {
+                    break;
+                }
+            }
+            msg = socket.recv() => {
+                match msg {
+                    Ok(Some(Message::Close(_))) => break,
+                    Ok(Some(_)) => {}
+                    Ok(None) => break,
+                    Err(_) => break,
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 async fn handle_workspace_diff_ws(
     mut socket: MaybeSignedWebSocket,
     deployment: DeploymentImpl,
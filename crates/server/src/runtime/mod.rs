@@ -1 +1,3 @@
+pub mod pipeline_orchestrator;
 pub mod relay_registration;
+pub mod scheduler;
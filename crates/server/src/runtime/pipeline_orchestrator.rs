@@ -0,0 +1,175 @@
+//! Pipeline orchestrator — starts the next stage of a pipeline run once the
+//! previous stage's workspace has finished.
+//!
+//! Stage outcomes are recorded when a workspace's action chain ends (see
+//! `ContainerService::try_advance_pipeline`), which wakes this loop. A slow
+//! poll catches anything recorded while the server was not listening.
+
+use std::time::Duration;
+
+use db::models::{
+    pipeline::{Pipeline, PipelineRun, PipelineRunStage, PipelineStatus},
+    requests::{CreateAndStartWorkspaceRequest, WorkspaceRepoInput},
+    workspace::Workspace,
+};
+use deployment::Deployment as _;
+use services::services::pipelines::{self, StageContext};
+use tokio_util::sync::CancellationToken;
+
+use crate::{DeploymentImpl, error::ApiError, routes::workspaces::create::create_and_start};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+pub fn spawn_pipeline_orchestrator(deployment: &DeploymentImpl, shutdown: CancellationToken) {
+    let deployment = deployment.clone();
+    tokio::spawn(async move {
+        tracing::debug!("Pipeline orchestrator started");
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = pipelines::stage_finished() => {}
+                _ = interval.tick() => {}
+            }
+            advance_runs(&deployment).await;
+        }
+        tracing::debug!("Pipeline orchestrator exited");
+    });
+}
+
+async fn advance_runs(deployment: &DeploymentImpl) {
+    let runs = match PipelineRun::find_awaiting_advance(&deployment.db().pool).await {
+        Ok(runs) => runs,
+        Err(e) => {
+            tracing::error!("Failed to load pipeline runs: {}", e);
+            return;
+        }
+    };
+    for run in runs {
+        if let Err(e) = advance_run(deployment, &run).await {
+            tracing::warn!("Pipeline run {} failed to advance: {}", run.id, e);
+            if let Err(e) = PipelineRun::finish(
+                &deployment.db().pool,
+                run.id,
+                PipelineStatus::Failed,
+                Some(&e.to_string()),
+            )
+            .await
+            {
+                tracing::error!("Failed to mark pipeline run {} failed: {}", run.id, e);
+            }
+        }
+    }
+}
+
+async fn advance_run(deployment: &DeploymentImpl, run: &PipelineRun) -> Result<(), ApiError> {
+    let pool = &deployment.db().pool;
+    let Some(last) = PipelineRunStage::find_by_run_id(pool, run.id)
+        .await?
+        .into_iter()
+        .next_back()
+    else {
+        return Ok(());
+    };
+
+    match last.status {
+        PipelineStatus::Running => return Ok(()),
+        PipelineStatus::Failed | PipelineStatus::Cancelled => {
+            let error = format!("Stage {} did not complete", last.stage_index + 1);
+            PipelineRun::finish(pool, run.id, last.status, Some(&error)).await?;
+            return Ok(());
+        }
+        PipelineStatus::Completed => {}
+    }
+
+    let Some(pipeline) = Pipeline::find_by_id(pool, run.pipeline_id).await? else {
+        PipelineRun::finish(
+            pool,
+            run.id,
+            PipelineStatus::Cancelled,
+            Some("Pipeline was deleted"),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let next_index = last.stage_index + 1;
+    if next_index as usize >= pipeline.stages.len() {
+        PipelineRun::finish(pool, run.id, PipelineStatus::Completed, None).await?;
+        return Ok(());
+    }
+
+    let previous = match last.workspace_id {
+        Some(workspace_id) => Workspace::find_by_id(pool, workspace_id).await?,
+        None => None,
+    };
+    start_stage(deployment, &pipeline, run, next_index, previous.as_ref()).await?;
+    Ok(())
+}
+
+/// Start stage `stage_index` of a run in a new workspace. Later stages branch
+/// from the previous stage's workspace so they build on its changes.
+pub(crate) async fn start_stage(
+    deployment: &DeploymentImpl,
+    pipeline: &Pipeline,
+    run: &PipelineRun,
+    stage_index: i64,
+    previous: Option<&Workspace>,
+) -> Result<PipelineRunStage, ApiError> {
+    let stage = pipeline
+        .stages
+        .get(stage_index as usize)
+        .ok_or_else(|| ApiError::BadRequest(format!("Pipeline has no stage {stage_index}")))?;
+
+    let previous_branch = previous.map(|workspace| workspace.branch.as_str());
+    let previous_workspace =
+        previous.map(|workspace| workspace.name.as_deref().unwrap_or(&workspace.branch));
+    let prompt = pipelines::render_stage_prompt(
+        &stage.prompt,
+        &StageContext {
+            pipeline: &pipeline.name,
+            stage: &stage.name,
+            prompt: &run.prompt,
+            previous_branch,
+            previous_workspace,
+        },
+    );
+
+    let repos = run
+        .repos
+        .iter()
+        .map(|repo| WorkspaceRepoInput {
+            repo_id: repo.repo_id,
+            target_branch: previous_branch
+                .map(str::to_string)
+                .unwrap_or_else(|| repo.target_branch.clone()),
+            package_id: None,
+        })
+        .collect();
+
+    let response = create_and_start(
+        deployment,
+        CreateAndStartWorkspaceRequest {
+            name: Some(format!("{}: {}", pipeline.name, stage.name)),
+            repos,
+            linked_issue: None,
+            executor_config: stage
+                .executor_config
+                .clone()
+                .unwrap_or_else(|| run.executor_config.clone()),
+            prompt,
+            attachment_ids: None,
+            idempotency_key: Some(format!("pipeline:{}:{}", run.id, stage_index)),
+            env_profile_ids: None,
+        },
+    )
+    .await?;
+
+    Ok(PipelineRunStage::create(
+        &deployment.db().pool,
+        run.id,
+        stage_index,
+        response.workspace.id,
+    )
+    .await?)
+}
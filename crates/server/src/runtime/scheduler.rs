@@ -0,0 +1,113 @@
+//! Scheduler loop — starts scheduled agent runs when their cron tick passes.
+//!
+//! Each due schedule is claimed by advancing its `next_run_at` before the run
+//! starts, so a slow or failing run never fires twice. Ticks missed while the
+//! server was down collapse into a single run on startup.
+
+use std::time::Duration;
+
+use chrono::{Local, Utc};
+use db::models::{
+    requests::{CreateAndStartWorkspaceRequest, WorkspaceRepoInput},
+    schedule::{Schedule, ScheduleRun, ScheduleRunStatus},
+};
+use deployment::Deployment as _;
+use services::services::schedules;
+use tokio_util::sync::CancellationToken;
+
+use crate::{DeploymentImpl, routes::workspaces::create::create_and_start};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+pub fn spawn_scheduler(deployment: &DeploymentImpl, shutdown: CancellationToken) {
+    let deployment = deployment.clone();
+    tokio::spawn(async move {
+        tracing::debug!("Scheduler loop started");
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = interval.tick() => run_due_schedules(&deployment).await,
+            }
+        }
+        tracing::debug!("Scheduler loop exited");
+    });
+}
+
+async fn run_due_schedules(deployment: &DeploymentImpl) {
+    let pool = &deployment.db().pool;
+    let now = Utc::now();
+    let due = match Schedule::find_due(pool, now).await {
+        Ok(due) => due,
+        Err(e) => {
+            tracing::error!("Failed to load due schedules: {}", e);
+            return;
+        }
+    };
+
+    for schedule in due {
+        let Some(scheduled_for) = schedule.next_run_at else {
+            continue;
+        };
+        let next_run_at = schedules::next_run_after(&schedule.cron_expression, now);
+        match Schedule::advance(pool, schedule.id, scheduled_for, next_run_at, now).await {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(e) => {
+                tracing::error!("Failed to claim run of schedule {}: {}", schedule.id, e);
+                continue;
+            }
+        }
+
+        tracing::info!("Starting scheduled run of {}", schedule.name);
+        let request = CreateAndStartWorkspaceRequest {
+            name: Some(format!(
+                "{} ({})",
+                schedule.name,
+                Local::now().format("%Y-%m-%d %H:%M")
+            )),
+            repos: vec![WorkspaceRepoInput {
+                repo_id: schedule.repo_id,
+                target_branch: schedule.target_branch.clone(),
+                package_id: None,
+            }],
+            linked_issue: None,
+            executor_config: schedule.executor_config.clone(),
+            prompt: schedule.prompt.clone(),
+            attachment_ids: None,
+            idempotency_key: Some(format!(
+                "schedule:{}:{}",
+                schedule.id,
+                scheduled_for.timestamp()
+            )),
+            env_profile_ids: None,
+        };
+
+        let run = match create_and_start(deployment, request).await {
+            Ok(response) => {
+                ScheduleRun::create(
+                    pool,
+                    schedule.id,
+                    Some(response.workspace.id),
+                    ScheduleRunStatus::Started,
+                    None,
+                )
+                .await
+            }
+            Err(e) => {
+                tracing::warn!("Scheduled run of {} failed: {}", schedule.name, e);
+                ScheduleRun::create(
+                    pool,
+                    schedule.id,
+                    None,
+                    ScheduleRunStatus::Failed,
+                    Some(&e.to_string()),
+                )
+                .await
+            }
+        };
+        if let Err(e) = run {
+            tracing::error!("Failed to record run of schedule {}: {}", schedule.id, e);
+        }
+    }
+}
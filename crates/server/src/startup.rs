@@ -127,8 +127,9 @@ pub async fn start_with_bind(
     })
 }
 
-/// Initialize the deployment: create asset directory, run migrations, backfill data,
-/// and pre-warm caches. Shared between the standalone server and the Tauri app.
+/// Initialize the deployment: create asset directory, run migrations, and pre-warm
+/// caches. Data backfills are started in the background by the deployment. Shared
+/// between the standalone server and the Tauri app.
 pub async fn initialize_deployment(
     shutdown: CancellationToken,
 ) -> Result<DeploymentImpl, DeploymentError> {
@@ -160,16 +161,6 @@ pub async fn initialize_deployment(
         .cleanup_orphan_executions()
         .await
         .map_err(DeploymentError::from)?;
-    deployment
-        .container()
-        .backfill_before_head_commits()
-        .await
-        .map_err(DeploymentError::from)?;
-    deployment
-        .container()
-        .backfill_repo_names()
-        .await
-        .map_err(DeploymentError::from)?;
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
@@ -1,9 +1,12 @@
 pub mod executor_approvals;
+pub mod links;
+pub mod policy;
 
 use std::{collections::HashSet, sync::Arc, time::Duration as StdDuration};
 
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
+use db::models::{approval_rule::ApprovalRuleApprovers, local_user::LocalUser};
 use futures::{
     StreamExt,
     future::{BoxFuture, FutureExt, Shared},
@@ -17,11 +20,14 @@ use ts_rs::TS;
 use utils::approvals::{ApprovalOutcome, ApprovalRequest, ApprovalResponse};
 use uuid::Uuid;
 
+use self::links::ApprovalLinks;
+
 #[derive(Debug)]
 struct PendingApproval {
     execution_process_id: Uuid,
     tool_name: String,
     is_question: bool,
+    approvers: Option<ApprovalRuleApprovers>,
     created_at: DateTime<Utc>,
     timeout_at: DateTime<Utc>,
     response_tx: oneshot::Sender<ApprovalOutcome>,
@@ -42,6 +48,8 @@ pub struct ApprovalInfo {
     pub tool_name: String,
     pub execution_process_id: Uuid,
     pub is_question: bool,
+    /// Who may answer, if the rule that asked for the approval names them.
+    pub approvers: Option<ApprovalRuleApprovers>,
     pub created_at: DateTime<Utc>,
     pub timeout_at: DateTime<Utc>,
 }
@@ -51,6 +59,7 @@ pub struct Approvals {
     pending: Arc<DashMap<String, PendingApproval>>,
     completed: Arc<DashMap<String, ApprovalOutcome>>,
     patches_tx: broadcast::Sender<Patch>,
+    links: ApprovalLinks,
 }
 
 #[derive(Debug, Error)]
@@ -63,6 +72,8 @@ pub enum ApprovalError {
     NoExecutorSession(String),
     #[error("invalid approval status for this tool type")]
     InvalidStatus,
+    #[error("only the designated approvers can answer this approval")]
+    NotAnApprover,
     #[error(transparent)]
     Custom(#[from] anyhow::Error),
 }
@@ -80,13 +91,20 @@ impl Approvals {
             pending: Arc::new(DashMap::new()),
             completed: Arc::new(DashMap::new()),
             patches_tx,
+            links: ApprovalLinks::new(),
         }
     }
 
+    /// Signer for the one-time links that answer approvals remotely.
+    pub fn links(&self) -> &ApprovalLinks {
+        &self.links
+    }
+
     pub(crate) async fn create_with_waiter(
         &self,
         request: ApprovalRequest,
         is_question: bool,
+        approvers: Option<ApprovalRuleApprovers>,
     ) -> Result<(ApprovalRequest, ApprovalWaiter), ApprovalError> {
         let (tx, rx) = oneshot::channel();
         let default_timeout = ApprovalOutcome::TimedOut;
@@ -101,6 +119,7 @@ impl Approvals {
             tool_name: request.tool_name.clone(),
             execution_process_id: request.execution_process_id,
             is_question,
+            approvers: approvers.clone(),
             created_at: request.created_at,
             timeout_at: request.timeout_at,
         };
@@ -109,6 +128,7 @@ impl Approvals {
             execution_process_id: request.execution_process_id,
             tool_name: request.tool_name.clone(),
             is_question,
+            approvers,
             created_at: request.created_at,
             timeout_at: request.timeout_at,
             response_tx: tx,
@@ -139,18 +159,34 @@ impl Approvals {
         }
     }
 
-    #[tracing::instrument(skip(self, id, req))]
+    /// Answer a pending approval. `responder` is the signed-in user answering,
+    /// or `None` for the machine's owner and approval link holders.
+    #[tracing::instrument(skip(self, id, req, responder))]
     pub async fn respond(
         &self,
         id: &str,
         req: ApprovalResponse,
+        responder: Option<&LocalUser>,
     ) -> Result<(ApprovalOutcome, ToolContext), ApprovalError> {
-        if let Some((_, p)) = self.pending.remove(id) {
-            if let Err(e) = Self::validate_approval_response(&req.status, p.is_question) {
-                self.pending.insert(id.to_string(), p);
-                return Err(e);
-            }
+        // Check the response while the entry is locked, so a rejected
+        // response never takes the approval out from under a valid one.
+        let mut rejection = None;
+        let removed = self.pending.remove_if(id, |_, p| {
+            rejection = Self::validate_approval_response(&req.status, p.is_question)
+                .err()
+                .or_else(|| {
+                    p.approvers
+                        .as_ref()
+                        .is_some_and(|approvers| !approvers.allows(responder))
+                        .then_some(ApprovalError::NotAnApprover)
+                });
+            rejection.is_none()
+        });
+        if let Some(e) = rejection {
+            return Err(e);
+        }
 
+        if let Some((_, p)) = removed {
             let outcome = req.status.clone();
             self.completed.insert(id.to_string(), outcome.clone());
             let _ = p.response_tx.send(outcome.clone());
@@ -272,20 +308,28 @@ impl Approvals {
             .collect()
     }
 
-    fn pending_infos(&self) -> Vec<ApprovalInfo> {
+    /// Approvals currently waiting for a response.
+    pub fn pending_infos(&self) -> Vec<ApprovalInfo> {
         self.pending
             .iter()
-            .map(|entry| {
-                let p = entry.value();
-                ApprovalInfo {
-                    approval_id: entry.key().clone(),
-                    tool_name: p.tool_name.clone(),
-                    execution_process_id: p.execution_process_id,
-                    is_question: p.is_question,
-                    created_at: p.created_at,
-                    timeout_at: p.timeout_at,
-                }
-            })
+            .map(|entry| Self::info(entry.key(), entry.value()))
             .collect()
     }
+
+    /// The approval `id` if it is still waiting for a response.
+    pub fn pending_info(&self, id: &str) -> Option<ApprovalInfo> {
+        self.pending.get(id).map(|p| Self::info(id, &p))
+    }
+
+    fn info(id: &str, p: &PendingApproval) -> ApprovalInfo {
+        ApprovalInfo {
+            approval_id: id.to_string(),
+            tool_name: p.tool_name.clone(),
+            execution_process_id: p.execution_process_id,
+            is_question: p.is_question,
+            approvers: p.approvers.clone(),
+            created_at: p.created_at,
+            timeout_at: p.timeout_at,
+        }
+    }
 }
@@ -1,17 +1,45 @@
 use std::{collections::HashMap, sync::Arc};
 
 use async_trait::async_trait;
-use db::{self, DBService, models::execution_process::ExecutionProcess};
+use db::{
+    self, DBService,
+    models::{
+        approval_rule::{ApprovalRule, ApprovalRuleApprovers},
+        audit_log::{AuditAction, AuditLogEntry, CreateAuditLogEntry},
+        execution_process::ExecutionProcess,
+        local_user::LocalUser,
+    },
+};
 use executors::approvals::{ExecutorApprovalError, ExecutorApprovalService};
+use futures::FutureExt;
+use serde_json::Value;
 use tokio::sync::Mutex;
 use tokio_util::sync::CancellationToken;
 use utils::approvals::{ApprovalOutcome, ApprovalRequest, ApprovalStatus, QuestionStatus};
 use uuid::Uuid;
 
-use crate::services::{approvals::Approvals, notification::NotificationService};
+use crate::services::{
+    approvals::{
+        Approvals,
+        policy::{self, PolicyDecision},
+    },
+    config::NotificationEvent,
+    notification::NotificationService,
+};
+
+/// Actor recorded in the audit log for decisions made by approval rules.
+const POLICY_ACTOR: &str = "policy";
 
 type ApprovalWaiter = futures::future::Shared<futures::future::BoxFuture<'static, ApprovalOutcome>>;
 
+enum PolicyResult {
+    /// A rule approved or denied the call; the id of the resolved approval.
+    Resolved(String),
+    /// Someone has to be asked: the rule's approvers if it names them,
+    /// otherwise anyone.
+    Prompt(Option<ApprovalRuleApprovers>),
+}
+
 pub struct ExecutorApprovalBridge {
     approvals: Approvals,
     db: DBService,
@@ -42,12 +70,13 @@ impl ExecutorApprovalBridge {
         tool_name: &str,
         is_question: bool,
         question_count: Option<usize>,
+        approvers: Option<ApprovalRuleApprovers>,
     ) -> Result<String, ExecutorApprovalError> {
         let request = ApprovalRequest::new(tool_name.to_string(), self.execution_process_id);
 
         let (request, waiter) = self
             .approvals
-            .create_with_waiter(request, is_question)
+            .create_with_waiter(request, is_question, approvers.clone())
             .await
             .map_err(ExecutorApprovalError::request_failed)?;
 
@@ -71,7 +100,7 @@ impl ExecutorApprovalBridge {
                 })
                 .unwrap_or_else(|_| ("Unknown workspace".to_string(), None));
 
-        let (title, message) = if let Some(count) = question_count {
+        let (title, mut message) = if let Some(count) = question_count {
             if count == 1 {
                 (
                     format!("Question Asked: {}", workspace_name),
@@ -90,13 +119,138 @@ impl ExecutorApprovalBridge {
             )
         };
 
+        let approver_users = match &approvers {
+            Some(approvers) => LocalUser::list(&self.db.pool)
+                .await
+                .map(|users| {
+                    users
+                        .into_iter()
+                        .filter(|user| approvers.designates(user))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_else(|err| {
+                    tracing::warn!("Failed to look up approvers: {err}");
+                    Vec::new()
+                }),
+            None => Vec::new(),
+        };
+        if approvers.is_some() {
+            let names: Vec<&str> = approver_users
+                .iter()
+                .map(|user| user.name.as_str())
+                .collect();
+            message = if names.is_empty() {
+                format!("{message}; only an admin can approve it")
+            } else {
+                format!("{message} from {}", names.join(", "))
+            };
+        }
+
+        let event = if is_question {
+            NotificationEvent::QuestionAsked
+        } else {
+            NotificationEvent::ApprovalNeeded
+        };
         self.notification_service
-            .notify(&title, &message, workspace_id)
+            .notify(event, &title, &message, workspace_id)
             .await;
 
+        // Questions need the full UI to answer; only tool approvals get a link.
+        let link = match self.notification_service.remote_approval_url().await {
+            Some(base_url) if !is_question => {
+                let token = self
+                    .approvals
+                    .links()
+                    .issue(&approval_id, request.timeout_at);
+                Some(format!("{base_url}/api/approval-links/{token}"))
+            }
+            _ => None,
+        };
+        if approvers.is_some() {
+            let emails: Vec<String> = approver_users
+                .into_iter()
+                .filter_map(|user| user.email)
+                .collect();
+            self.notification_service
+                .send_to_approvers(&emails, &title, &message, link.as_deref())
+                .await;
+        } else if let Some(link) = link {
+            self.notification_service
+                .send_approval_link(&title, &message, &link)
+                .await;
+        }
+
         Ok(approval_id)
     }
 
+    /// Decide a tool call from the approval rules of the workspace's repos.
+    async fn apply_policy(
+        &self,
+        tool_name: &str,
+        input: &Value,
+    ) -> Result<PolicyResult, ExecutorApprovalError> {
+        let pool = &self.db.pool;
+        let ctx = ExecutionProcess::load_context(pool, self.execution_process_id)
+            .await
+            .map_err(ExecutorApprovalError::request_failed)?;
+        let repo_ids: Vec<Uuid> = ctx.repos.iter().map(|repo| repo.id).collect();
+        let rules = ApprovalRule::find_applicable(pool, &repo_ids)
+            .await
+            .map_err(ExecutorApprovalError::request_failed)?;
+
+        let command = policy::command_from_input(input);
+        let (action, outcome, rule_id) =
+            match policy::evaluate(&rules, tool_name, command.as_deref()) {
+                PolicyDecision::Prompt { rule_id } => {
+                    let approvers = rule_id
+                        .and_then(|rule_id| rules.iter().find(|rule| rule.id == rule_id))
+                        .and_then(|rule| rule.approvers.clone())
+                        .map(|approvers| approvers.0);
+                    return Ok(PolicyResult::Prompt(approvers));
+                }
+                PolicyDecision::AutoApprove { rule_id } => (
+                    AuditAction::ApprovalAutoApproved,
+                    ApprovalOutcome::Approved,
+                    rule_id,
+                ),
+                PolicyDecision::Deny { rule_id } => (
+                    AuditAction::ApprovalDenied,
+                    ApprovalOutcome::Denied {
+                        reason: Some(format!("Tool '{tool_name}' is denied by an approval rule")),
+                    },
+                    rule_id,
+                ),
+            };
+
+        let request = ApprovalRequest::new(tool_name.to_string(), self.execution_process_id);
+        self.waiters.lock().await.insert(
+            request.id.clone(),
+            futures::future::ready(outcome).boxed().shared(),
+        );
+
+        if let Err(err) = AuditLogEntry::create(
+            pool,
+            &CreateAuditLogEntry {
+                action,
+                actor: POLICY_ACTOR.to_string(),
+                workspace_id: Some(ctx.workspace.id),
+                target_id: Some(self.execution_process_id),
+                details: Some(serde_json::json!({
+                    "approval_id": request.id,
+                    "tool_name": tool_name,
+                    "command": command,
+                    "rule_id": rule_id,
+                })),
+            },
+        )
+        .await
+        {
+            tracing::warn!("Failed to record policy approval decision: {err}");
+        }
+
+        Ok(PolicyResult::Resolved(request.id))
+    }
+
     async fn wait_internal(
         &self,
         approval_id: &str,
@@ -130,7 +284,25 @@ impl ExecutorApprovalBridge {
 #[async_trait]
 impl ExecutorApprovalService for ExecutorApprovalBridge {
     async fn create_tool_approval(&self, tool_name: &str) -> Result<String, ExecutorApprovalError> {
-        self.create_internal(tool_name, false, None).await
+        self.create_internal(tool_name, false, None, None).await
+    }
+
+    async fn create_tool_approval_with_input(
+        &self,
+        tool_name: &str,
+        input: &Value,
+    ) -> Result<String, ExecutorApprovalError> {
+        let approvers = match self.apply_policy(tool_name, input).await {
+            Ok(PolicyResult::Resolved(approval_id)) => return Ok(approval_id),
+            Ok(PolicyResult::Prompt(approvers)) => approvers,
+            // Fall back to asking the user rather than failing the tool call.
+            Err(err) => {
+                tracing::warn!("Failed to apply approval rules: {err}");
+                None
+            }
+        };
+        self.create_internal(tool_name, false, None, approvers)
+            .await
     }
 
     async fn create_question_approval(
@@ -138,7 +310,7 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
         tool_name: &str,
         question_count: usize,
     ) -> Result<String, ExecutorApprovalError> {
-        self.create_internal(tool_name, true, Some(question_count))
+        self.create_internal(tool_name, true, Some(question_count), None)
             .await
     }
 
@@ -0,0 +1,137 @@
+//! Signed one-time links for answering an approval without the app open.
+//!
+//! A token carries the approval id and its expiry, signed with a key that only
+//! lives as long as the process. Approvals themselves are in memory, so links
+//! outliving a restart would have nothing to resolve anyway.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use rand::RngCore;
+use thiserror::Error;
+use utils::tokens::{SignedTokenError, sign_expiring, verify_expiring};
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ApprovalLinkError {
+    #[error("This approval link is invalid.")]
+    Invalid,
+    #[error("This approval link has expired.")]
+    Expired,
+    #[error("This approval link has already been used.")]
+    AlreadyUsed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalLinkClaims {
+    pub approval_id: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+pub struct ApprovalLinks {
+    key: Arc<[u8; 32]>,
+    /// Tokens that have been used, kept until they expire.
+    used: Arc<DashMap<String, DateTime<Utc>>>,
+}
+
+impl Default for ApprovalLinks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ApprovalLinks {
+    pub fn new() -> Self {
+        let mut key = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut key);
+        Self {
+            key: Arc::new(key),
+            used: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Create a token for `approval_id` that is valid until `expires_at`.
+    pub fn issue(&self, approval_id: &str, expires_at: DateTime<Utc>) -> String {
+        sign_expiring(self.key.as_slice(), approval_id, expires_at)
+    }
+
+    /// Check a token without using it up.
+    pub fn verify(&self, token: &str) -> Result<ApprovalLinkClaims, ApprovalLinkError> {
+        let (approval_id, expires_at) =
+            verify_expiring(self.key.as_slice(), token).map_err(|error| match error {
+                SignedTokenError::Invalid => ApprovalLinkError::Invalid,
+                SignedTokenError::Expired => ApprovalLinkError::Expired,
+            })?;
+        if self.used.contains_key(token) {
+            return Err(ApprovalLinkError::AlreadyUsed);
+        }
+        Ok(ApprovalLinkClaims {
+            approval_id,
+            expires_at,
+        })
+    }
+
+    /// Check a token and mark it used, so it can't be used again.
+    pub fn consume(&self, token: &str) -> Result<ApprovalLinkClaims, ApprovalLinkError> {
+        let claims = self.verify(token)?;
+        let now = Utc::now();
+        self.used.retain(|_, expires_at| *expires_at > now);
+        if self
+            .used
+            .insert(token.to_string(), claims.expires_at)
+            .is_some()
+        {
+            return Err(ApprovalLinkError::AlreadyUsed);
+        }
+        Ok(claims)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn tokens_round_trip_once() {
+        let links = ApprovalLinks::new();
+        let expires_at =
+            DateTime::from_timestamp((Utc::now() + Duration::minutes(5)).timestamp(), 0).unwrap();
+        let token = links.issue("approval-1", expires_at);
+
+        let claims = links.verify(&token).unwrap();
+        assert_eq!(claims.approval_id, "approval-1");
+        assert_eq!(claims.expires_at, expires_at);
+        assert_eq!(links.consume(&token), Ok(claims));
+        assert_eq!(links.consume(&token), Err(ApprovalLinkError::AlreadyUsed));
+        assert_eq!(links.verify(&token), Err(ApprovalLinkError::AlreadyUsed));
+    }
+
+    #[test]
+    fn rejects_tampered_and_foreign_tokens() {
+        let links = ApprovalLinks::new();
+        let token = links.issue("approval-1", Utc::now() + Duration::minutes(5));
+        let (_, signature) = token.split_once('.').unwrap();
+        let forged = format!(
+            "{}.{signature}",
+            URL_SAFE_NO_PAD.encode(format!("approval-2.{}", Utc::now().timestamp() + 300))
+        );
+
+        assert_eq!(links.verify(&forged), Err(ApprovalLinkError::Invalid));
+        assert_eq!(links.verify("garbage"), Err(ApprovalLinkError::Invalid));
+        assert_eq!(
+            ApprovalLinks::new().verify(&token),
+            Err(ApprovalLinkError::Invalid)
+        );
+    }
+
+    #[test]
+    fn rejects_expired_tokens() {
+        let links = ApprovalLinks::new();
+        let token = links.issue("approval-1", Utc::now() - Duration::seconds(1));
+        assert_eq!(links.consume(&token), Err(ApprovalLinkError::Expired));
+    }
+}
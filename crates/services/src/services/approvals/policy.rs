@@ -0,0 +1,284 @@
+//! Approval policy: decides tool approval requests from configured rules.
+//!
+//! A shell command is split into its segments (`a && b | c`), and every
+//! segment is matched against the rules on its own. The command is only
+//! auto-approved if every segment is; a single segment that is denied or
+//! needs approval decides the whole command. Commands using substitutions or
+//! redirections are never auto-approved, since their effect cannot be read
+//! off the leading words.
+//!
+//! When several rules match, the most specific one wins: a longer command
+//! prefix beats a shorter one, a repo rule beats a global one, and between
+//! equally specific rules the more restrictive action wins.
+
+use db::models::approval_rule::{ApprovalRule, ApprovalRuleAction};
+use serde_json::Value;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyDecision {
+    AutoApprove {
+        rule_id: Uuid,
+    },
+    Deny {
+        rule_id: Uuid,
+    },
+    /// Ask the user, either because a rule requires it or because no rule
+    /// decided the request.
+    Prompt {
+        rule_id: Option<Uuid>,
+    },
+}
+
+/// The shell command of a tool call, for tools that take one.
+pub fn command_from_input(input: &Value) -> Option<String> {
+    match input.get("command")? {
+        Value::String(command) => Some(command.clone()),
+        Value::Array(parts) => Some(
+            parts
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        _ => None,
+    }
+}
+
+pub fn evaluate(rules: &[ApprovalRule], tool_name: &str, command: Option<&str>) -> PolicyDecision {
+    let Some(command) = command.map(str::trim).filter(|command| !command.is_empty()) else {
+        return match best_rule(rules, tool_name, None) {
+            Some(rule) => decision_for(rule),
+            None => PolicyDecision::Prompt { rule_id: None },
+        };
+    };
+
+    let opaque = ["$(", "`", ">", "<("]
+        .iter()
+        .any(|token| command.contains(token));
+    let mut approved_by = None;
+    let mut prompt_rule = None;
+    let mut undecided = opaque;
+
+    for segment in command_segments(command) {
+        match best_rule(rules, tool_name, Some(&segment)) {
+            Some(rule) => match rule.action {
+                ApprovalRuleAction::Deny => return PolicyDecision::Deny { rule_id: rule.id },
+                ApprovalRuleAction::RequireApproval => {
+                    prompt_rule.get_or_insert(rule.id);
+                }
+                ApprovalRuleAction::AutoApprove => {
+                    approved_by.get_or_insert(rule.id);
+                }
+            },
+            None => undecided = true,
+        }
+    }
+
+    match (prompt_rule, approved_by) {
+        (Some(rule_id), _) => PolicyDecision::Prompt {
+            rule_id: Some(rule_id),
+        },
+        (None, Some(rule_id)) if !undecided => PolicyDecision::AutoApprove { rule_id },
+        _ => PolicyDecision::Prompt { rule_id: None },
+    }
+}
+
+fn decision_for(rule: &ApprovalRule) -> PolicyDecision {
+    match rule.action {
+        ApprovalRuleAction::AutoApprove => PolicyDecision::AutoApprove { rule_id: rule.id },
+        ApprovalRuleAction::Deny => PolicyDecision::Deny { rule_id: rule.id },
+        ApprovalRuleAction::RequireApproval => PolicyDecision::Prompt {
+            rule_id: Some(rule.id),
+        },
+    }
+}
+
+fn restrictiveness(action: ApprovalRuleAction) -> u8 {
+    match action {
+        ApprovalRuleAction::AutoApprove => 0,
+        ApprovalRuleAction::RequireApproval => 1,
+        ApprovalRuleAction::Deny => 2,
+    }
+}
+
+/// The most specific rule matching a tool call or one command segment.
+fn best_rule<'a>(
+    rules: &'a [ApprovalRule],
+    tool_name: &str,
+    segment: Option<&str>,
+) -> Option<&'a ApprovalRule> {
+    let words: Vec<&str> = segment.map_or_else(Vec::new, program_words);
+    rules
+        .iter()
+        .filter(|rule| {
+            rule.tool_name
+                .as_deref()
+                .is_none_or(|name| name.eq_ignore_ascii_case(tool_name))
+        })
+        .filter_map(|rule| match rule.command_prefix.as_deref() {
+            // Tool-wide rules apply to every call of the tool.
+            None if rule.tool_name.is_some() => Some((rule, 0)),
+            None => None,
+            Some(prefix) => {
+                let prefix: Vec<&str> = prefix.split_whitespace().collect();
+                (!words.is_empty() && words.starts_with(&prefix)).then_some((rule, prefix.len()))
+            }
+        })
+        .max_by_key(|(rule, specificity)| {
+            (
+                *specificity,
+                rule.repo_id.is_some(),
+                restrictiveness(rule.action),
+            )
+        })
+        .map(|(rule, _)| rule)
+}
+
+/// Split a command line into the commands it runs.
+fn command_segments(command: &str) -> Vec<String> {
+    command
+        .replace("&&", "\n")
+        .replace("||", "\n")
+        .split(['\n', ';', '|', '&'])
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Words of a command segment with leading `VAR=value` assignments dropped and
+/// the program reduced to its file name, so `/bin/rm` matches `rm`.
+fn program_words(segment: &str) -> Vec<&str> {
+    let mut words: Vec<&str> = segment
+        .split_whitespace()
+        .skip_while(|word| {
+            word.split_once('=')
+                .is_some_and(|(name, _)| !name.is_empty() && !name.contains('/'))
+        })
+        .collect();
+    if let Some(program) = words.first_mut() {
+        *program = program.rsplit('/').next().unwrap_or(program);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn rule(
+        tool_name: Option<&str>,
+        command_prefix: Option<&str>,
+        action: ApprovalRuleAction,
+        repo_id: Option<Uuid>,
+    ) -> ApprovalRule {
+        ApprovalRule {
+            id: Uuid::new_v4(),
+            repo_id,
+            tool_name: tool_name.map(str::to_string),
+            command_prefix: command_prefix.map(str::to_string),
+            action,
+            description: None,
+            approvers: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn defaults() -> Vec<ApprovalRule> {
+        use ApprovalRuleAction::*;
+        vec![
+            rule(Some("Read"), None, AutoApprove, None),
+            rule(None, Some("ls"), AutoApprove, None),
+            rule(None, Some("git status"), AutoApprove, None),
+            rule(None, Some("rm"), RequireApproval, None),
+            rule(None, Some("git push"), RequireApproval, None),
+        ]
+    }
+
+    fn auto_approved(decision: PolicyDecision) -> bool {
+        matches!(decision, PolicyDecision::AutoApprove { .. })
+    }
+
+    #[test]
+    fn approves_read_only_calls() {
+        let rules = defaults();
+        assert!(auto_approved(evaluate(&rules, "read", None)));
+        assert!(auto_approved(evaluate(&rules, "Bash", Some("ls -la src"))));
+        assert!(auto_approved(evaluate(
+            &rules,
+            "Bash",
+            Some("git status && ls")
+        )));
+        assert!(!auto_approved(evaluate(&rules, "Edit", None)));
+        assert!(!auto_approved(evaluate(&rules, "Bash", Some("git commit"))));
+    }
+
+    #[test]
+    fn every_segment_must_be_approved() {
+        let rules = defaults();
+        assert!(matches!(
+            evaluate(&rules, "Bash", Some("ls && /bin/rm -rf build")),
+            PolicyDecision::Prompt { rule_id: Some(_) }
+        ));
+        assert!(!auto_approved(evaluate(
+            &rules,
+            "Bash",
+            Some("ls | xargs cat")
+        )));
+        assert!(!auto_approved(evaluate(
+            &rules,
+            "Bash",
+            Some("ls > files.txt")
+        )));
+        assert!(!auto_approved(evaluate(&rules, "Bash", Some("ls $(pwd)"))));
+        assert!(!auto_approved(evaluate(&rules, "Bash", Some("rmdir x"))));
+    }
+
+    #[test]
+    fn specific_rules_override_general_ones() {
+        let repo_id = Uuid::new_v4();
+        let mut rules = defaults();
+        rules.push(rule(
+            None,
+            Some("rm -rf target"),
+            ApprovalRuleAction::AutoApprove,
+            Some(repo_id),
+        ));
+        rules.push(rule(
+            Some("Bash"),
+            Some("git push"),
+            ApprovalRuleAction::Deny,
+            None,
+        ));
+        assert!(auto_approved(evaluate(
+            &rules,
+            "Bash",
+            Some("rm -rf target")
+        )));
+        assert!(!auto_approved(evaluate(&rules, "Bash", Some("rm -rf src"))));
+        assert!(matches!(
+            evaluate(&rules, "Bash", Some("FOO=1 git push origin main")),
+            PolicyDecision::Deny { .. }
+        ));
+    }
+
+    #[test]
+    fn reads_command_from_tool_input() {
+        assert_eq!(
+            command_from_input(&serde_json::json!({ "command": "ls -la" })).as_deref(),
+            Some("ls -la")
+        );
+        assert_eq!(
+            command_from_input(&serde_json::json!({ "command": ["git", "status"] })).as_deref(),
+            Some("git status")
+        );
+        assert_eq!(
+            command_from_input(&serde_json::json!({ "file_path": "a" })),
+            None
+        );
+    }
+}
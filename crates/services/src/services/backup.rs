@@ -0,0 +1,465 @@
+//! Backup and restore of local state.
+//!
+//! A backup is a gzipped tarball containing a consistent snapshot of the
+//! SQLite database (taken with `VACUUM INTO`, so the app keeps running), the
+//! execution log files, attachments, and the user config/profiles.
+//!
+//! Restoring replaces the live database, which cannot be done while the pool
+//! is open. Instead the archive is validated and staged next to the database,
+//! and [`apply_pending_restore`] swaps it in on the next startup before any
+//! connection is made. The replaced files are kept in a `pre-restore-*`
+//! directory.
+
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use utils::{assets::asset_dir, execution_logs::EXECUTION_LOGS_DIRNAME};
+use uuid::Uuid;
+
+/// Bumped when the archive layout changes incompatibly.
+const BACKUP_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const DB_FILENAME: &str = "db.v2.sqlite";
+/// Files SQLite keeps next to the database in WAL mode.
+const DB_SIDECAR_SUFFIXES: [&str; 2] = ["-wal", "-shm"];
+const ATTACHMENTS_ENTRY: &str = "attachments";
+const PENDING_RESTORE_FILENAME: &str = "pending_restore.tar.gz";
+/// Files from the asset dir included verbatim. Credentials and signing keys
+/// are machine-specific and deliberately left out.
+const ASSET_FILES: &[&str] = &["config.json", "profiles.json"];
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid backup archive: {0}")]
+    InvalidArchive(String),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupManifest {
+    format_version: u32,
+    app_version: String,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct BackupInfo {
+    pub path: String,
+    #[ts(type = "number")]
+    pub size_bytes: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Where the backed-up state lives. Tests point this at a temporary directory.
+#[derive(Clone)]
+struct StateDirs {
+    assets: PathBuf,
+    attachments: PathBuf,
+}
+
+impl StateDirs {
+    fn current() -> Self {
+        Self {
+            assets: asset_dir(),
+            attachments: utils::cache_dir().join(ATTACHMENTS_ENTRY),
+        }
+    }
+
+    fn backups(&self) -> PathBuf {
+        self.assets.join("backups")
+    }
+
+    fn pending_restore(&self) -> PathBuf {
+        self.assets.join(PENDING_RESTORE_FILENAME)
+    }
+}
+
+pub fn backups_dir() -> PathBuf {
+    StateDirs::current().backups()
+}
+
+/// Write a backup archive to [`backups_dir`].
+pub async fn create_backup(pool: &SqlitePool) -> Result<BackupInfo, BackupError> {
+    backup_into(pool, &StateDirs::current()).await
+}
+
+async fn backup_into(pool: &SqlitePool, dirs: &StateDirs) -> Result<BackupInfo, BackupError> {
+    let dir = dirs.backups();
+    tokio::fs::create_dir_all(&dir).await?;
+
+    let created_at = Utc::now();
+    let snapshot_path = dir.join(format!(".snapshot-{}.sqlite", Uuid::new_v4()));
+    sqlx::query("VACUUM INTO ?")
+        .bind(snapshot_path.to_string_lossy().into_owned())
+        .execute(pool)
+        .await?;
+
+    let archive_path = dir.join(format!(
+        "vibe-kanban-backup-{}.tar.gz",
+        created_at.format("%Y%m%d-%H%M%S")
+    ));
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        app_version: utils::version::APP_VERSION.to_string(),
+        created_at,
+    };
+
+    let result = tokio::task::spawn_blocking({
+        let snapshot_path = snapshot_path.clone();
+        let archive_path = archive_path.clone();
+        let dirs = dirs.clone();
+        move || write_archive(&dirs, &archive_path, &snapshot_path, &manifest)
+    })
+    .await
+    .map_err(std::io::Error::other)
+    .map_err(BackupError::from)
+    .and_then(|result| result);
+    // The snapshot only exists to be archived; remove it whether or not that
+    // succeeded.
+    let _ = tokio::fs::remove_file(&snapshot_path).await;
+    result?;
+
+    let size_bytes = tokio::fs::metadata(&archive_path).await?.len();
+    Ok(BackupInfo {
+        path: archive_path.to_string_lossy().into_owned(),
+        size_bytes,
+        created_at,
+    })
+}
+
+fn write_archive(
+    dirs: &StateDirs,
+    archive_path: &Path,
+    snapshot_path: &Path,
+    manifest: &BackupManifest,
+) -> Result<(), BackupError> {
+    let partial_path = archive_path.with_extension("partial");
+    let encoder = GzEncoder::new(
+        BufWriter::new(File::create(&partial_path)?),
+        Compression::default(),
+    );
+    let mut builder = tar::Builder::new(encoder);
+
+    let manifest_json = serde_json::to_vec_pretty(manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(manifest.created_at.timestamp() as u64);
+    builder.append_data(&mut header, MANIFEST_ENTRY, manifest_json.as_slice())?;
+
+    append_file(&mut builder, snapshot_path, Path::new(DB_FILENAME))?;
+
+    for name in ASSET_FILES {
+        let path = dirs.assets.join(name);
+        if path.is_file() {
+            append_file(&mut builder, &path, Path::new(name))?;
+        }
+    }
+    append_dir(
+        &mut builder,
+        &dirs.assets.join(EXECUTION_LOGS_DIRNAME),
+        Path::new(EXECUTION_LOGS_DIRNAME),
+    )?;
+    append_dir(
+        &mut builder,
+        &dirs.attachments,
+        Path::new(ATTACHMENTS_ENTRY),
+    )?;
+
+    builder.into_inner()?.finish()?;
+    std::fs::rename(&partial_path, archive_path)?;
+    Ok(())
+}
+
+/// Append a file, capping it at the size observed when it was opened so logs
+/// still being written cannot corrupt the archive.
+fn append_file<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    path: &Path,
+    name: &Path,
+) -> Result<(), BackupError> {
+    let file = File::open(path)?;
+    let metadata = file.metadata()?;
+    let mut header = tar::Header::new_gnu();
+    header.set_metadata(&metadata);
+    header.set_size(metadata.len());
+    builder.append_data(&mut header, name, file.take(metadata.len()))?;
+    Ok(())
+}
+
+fn append_dir<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    dir: &Path,
+    name: &Path,
+) -> Result<(), BackupError> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    for entry in entries {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let entry_name = name.join(entry.file_name());
+        if file_type.is_dir() {
+            append_dir(builder, &entry.path(), &entry_name)?;
+        } else if file_type.is_file() {
+            append_file(builder, &entry.path(), &entry_name)?;
+        }
+    }
+    Ok(())
+}
+
+fn read_manifest(archive_path: &Path) -> Result<BackupManifest, BackupError> {
+    let mut archive = tar::Archive::new(GzDecoder::new(BufReader::new(File::open(archive_path)?)));
+    let mut manifest = None;
+    let mut has_db = false;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        if path == Path::new(MANIFEST_ENTRY) {
+            manifest = Some(serde_json::from_reader::<_, BackupManifest>(&mut entry)?);
+        } else if path == Path::new(DB_FILENAME) {
+            has_db = true;
+        }
+    }
+
+    let manifest =
+        manifest.ok_or_else(|| BackupError::InvalidArchive("missing manifest".to_string()))?;
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        return Err(BackupError::InvalidArchive(format!(
+            "created by a newer version ({}); upgrade before restoring",
+            manifest.app_version
+        )));
+    }
+    if !has_db {
+        return Err(BackupError::InvalidArchive(
+            "missing database snapshot".to_string(),
+        ));
+    }
+    Ok(manifest)
+}
+
+/// Validate a backup archive and stage it to be restored on next startup.
+pub async fn stage_restore(archive_path: PathBuf) -> Result<(), BackupError> {
+    stage_restore_into(archive_path, &StateDirs::current()).await
+}
+
+async fn stage_restore_into(archive_path: PathBuf, dirs: &StateDirs) -> Result<(), BackupError> {
+    let manifest = tokio::task::spawn_blocking({
+        let archive_path = archive_path.clone();
+        move || read_manifest(&archive_path)
+    })
+    .await
+    .map_err(std::io::Error::other)??;
+
+    tokio::fs::copy(&archive_path, dirs.pending_restore()).await?;
+    tracing::info!(
+        "Staged restore of backup created {} (app version {}); restart to apply",
+        manifest.created_at,
+        manifest.app_version
+    );
+    Ok(())
+}
+
+/// Swap in a staged backup, if any. Must run before the database is opened.
+/// Returns whether a restore was applied.
+pub fn apply_pending_restore() -> Result<bool, BackupError> {
+    apply_pending_restore_in(&StateDirs::current())
+}
+
+fn apply_pending_restore_in(dirs: &StateDirs) -> Result<bool, BackupError> {
+    let archive_path = dirs.pending_restore();
+    if !archive_path.is_file() {
+        return Ok(false);
+    }
+    let manifest = read_manifest(&archive_path)?;
+
+    let assets = &dirs.assets;
+    let stamp = Utc::now().format("%Y%m%d-%H%M%S").to_string();
+    let staging_dir = assets.join(format!("restore-{stamp}"));
+    let previous_dir = assets.join(format!("pre-restore-{stamp}"));
+    std::fs::create_dir_all(&staging_dir)?;
+    std::fs::create_dir_all(&previous_dir)?;
+
+    let mut archive = tar::Archive::new(GzDecoder::new(BufReader::new(File::open(&archive_path)?)));
+    // `unpack` refuses entries that would escape the staging directory.
+    archive.unpack(&staging_dir)?;
+
+    let db_restored = staging_dir.join(DB_FILENAME).is_file();
+    let replaced: Vec<&str> = [DB_FILENAME, EXECUTION_LOGS_DIRNAME]
+        .into_iter()
+        .chain(ASSET_FILES.iter().copied())
+        .collect();
+    for name in replaced {
+        let restored = staging_dir.join(name);
+        if !restored.exists() {
+            continue;
+        }
+        let current = assets.join(name);
+        if current.exists() {
+            std::fs::rename(&current, previous_dir.join(name))?;
+        }
+        std::fs::rename(&restored, &current)?;
+    }
+    // A leftover write-ahead log of the replaced database would be replayed
+    // into the restored one.
+    if db_restored {
+        for suffix in DB_SIDECAR_SUFFIXES {
+            let sidecar = format!("{DB_FILENAME}{suffix}");
+            let current = assets.join(&sidecar);
+            if current.exists() {
+                std::fs::rename(&current, previous_dir.join(sidecar))?;
+            }
+        }
+    }
+
+    // Attachment files are content-addressed, so merging is safe and avoids
+    // moving the existing cache aside.
+    let restored_attachments = staging_dir.join(ATTACHMENTS_ENTRY);
+    if restored_attachments.is_dir() {
+        let target = &dirs.attachments;
+        std::fs::create_dir_all(target)?;
+        for entry in std::fs::read_dir(&restored_attachments)? {
+            let entry = entry?;
+            let destination = target.join(entry.file_name());
+            if entry.file_type()?.is_file() && !destination.exists() {
+                std::fs::copy(entry.path(), destination)?;
+            }
+        }
+    }
+
+    std::fs::remove_dir_all(&staging_dir)?;
+    std::fs::remove_file(&archive_path)?;
+    tracing::info!(
+        "Restored backup created {} (app version {}); previous state saved to {}",
+        manifest.created_at,
+        manifest.app_version,
+        previous_dir.display()
+    );
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use sqlx::sqlite::SqliteConnectOptions;
+
+    use super::*;
+
+    async fn connect(path: &Path) -> SqlitePool {
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true);
+        SqlitePool::connect_with(options).await.unwrap()
+    }
+
+    async fn notes(path: &Path) -> Vec<String> {
+        let pool = connect(path).await;
+        let notes = sqlx::query_scalar("SELECT body FROM notes ORDER BY rowid")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+        notes
+    }
+
+    #[tokio::test]
+    async fn restore_round_trip_keeps_the_replaced_state() {
+        let root = tempfile::tempdir().unwrap();
+        let dirs = StateDirs {
+            assets: root.path().join("assets"),
+            attachments: root.path().join("attachments"),
+        };
+        let logs = dirs.assets.join(EXECUTION_LOGS_DIRNAME);
+        fs::create_dir_all(&logs).unwrap();
+        fs::create_dir_all(&dirs.attachments).unwrap();
+        fs::write(dirs.assets.join("config.json"), "backed up config").unwrap();
+        fs::write(logs.join("run.log"), "backed up log").unwrap();
+        fs::write(dirs.attachments.join("abc.png"), "attachment").unwrap();
+
+        let db_path = dirs.assets.join(DB_FILENAME);
+        let pool = connect(&db_path).await;
+        sqlx::query("CREATE TABLE notes (body TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO notes (body) VALUES ('backed up row')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let info = backup_into(&pool, &dirs).await.unwrap();
+
+        // State that diverged after the backup, including a write-ahead log
+        // that must not be replayed into the restored database.
+        sqlx::query("INSERT INTO notes (body) VALUES ('live row')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+        fs::write(dirs.assets.join("db.v2.sqlite-wal"), "stale wal").unwrap();
+        fs::write(dirs.assets.join("db.v2.sqlite-shm"), "stale shm").unwrap();
+        fs::write(dirs.assets.join("config.json"), "live config").unwrap();
+        fs::write(logs.join("run.log"), "live log").unwrap();
+        fs::remove_file(dirs.attachments.join("abc.png")).unwrap();
+
+        stage_restore_into(PathBuf::from(&info.path), &dirs)
+            .await
+            .unwrap();
+        assert!(dirs.pending_restore().is_file());
+        assert!(apply_pending_restore_in(&dirs).unwrap());
+        assert!(!dirs.pending_restore().exists());
+
+        let read = |path: PathBuf| fs::read_to_string(path).unwrap();
+        assert_eq!(read(dirs.assets.join("config.json")), "backed up config");
+        assert_eq!(read(logs.join("run.log")), "backed up log");
+        assert_eq!(read(dirs.attachments.join("abc.png")), "attachment");
+        for suffix in DB_SIDECAR_SUFFIXES {
+            assert!(!dirs.assets.join(format!("{DB_FILENAME}{suffix}")).exists());
+        }
+        assert_eq!(notes(&db_path).await, ["backed up row"]);
+
+        let previous: Vec<PathBuf> = fs::read_dir(&dirs.assets)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                path.file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .starts_with("pre-restore-")
+            })
+            .collect();
+        assert_eq!(previous.len(), 1);
+        let previous = &previous[0];
+        assert_eq!(read(previous.join("db.v2.sqlite-wal")), "stale wal");
+        assert_eq!(read(previous.join("db.v2.sqlite-shm")), "stale shm");
+        // Opening the database consumes the sidecars, so check it last.
+        assert_eq!(
+            notes(&previous.join(DB_FILENAME)).await,
+            ["backed up row", "live row"]
+        );
+        assert_eq!(read(previous.join("config.json")), "live config");
+        assert_eq!(
+            read(previous.join(EXECUTION_LOGS_DIRNAME).join("run.log")),
+            "live log"
+        );
+
+        assert!(!apply_pending_restore_in(&dirs).unwrap());
+    }
+}
@@ -0,0 +1,434 @@
+//! Triage of failed GitHub Actions runs.
+//!
+//! A repo's GitHub webhook points at `/api/hooks/github/{repo_id}` and sends
+//! `workflow_run` events, signed with a secret issued here. When a run fails,
+//! a workspace is started on the run's branch with the failed steps' logs in
+//! the prompt. Once the agent finishes, its final message is posted to the
+//! run's pull request as a comment.
+
+use db::models::{
+    ci_triage::{
+        CiTriageError as CiTriageModelError, CiTriageRun, CiTriageSettings, CreateCiTriageRun,
+        UpsertCiTriageSettings,
+    },
+    coding_agent_turn::CodingAgentTurn,
+    execution_process::{ExecutionContext, ExecutionProcessStatus},
+    repo::Repo,
+};
+use git::{GitService, GitServiceError};
+use git_host::{GitHostError, GitHostService};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use utils::tokens::{hmac_sha256, new_token, verify_hmac_sha256};
+use uuid::Uuid;
+
+use crate::services::secrets::{self, SecretError};
+
+/// Failed step logs can be huge; the end is where the error usually is.
+const MAX_LOG_CHARS: usize = 30_000;
+/// GitHub rejects comments over 65536 characters.
+const MAX_COMMENT_CHARS: usize = 60_000;
+
+const DEFAULT_PROMPT: &str = "A GitHub Actions run failed. Find the root cause using the \
+     logs below and the code in this workspace. Don't push any changes. Finish with a short \
+     summary of what failed, why, and how to fix it; that final message is posted to the pull \
+     request.";
+
+#[derive(Debug, Error)]
+pub enum CiTriageError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Settings(#[from] CiTriageModelError),
+    #[error(transparent)]
+    Secret(#[from] SecretError),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error(transparent)]
+    GitHost(#[from] GitHostError),
+    #[error("Webhook signature is missing or doesn't match")]
+    InvalidSignature,
+    #[error("Invalid workflow_run payload: {0}")]
+    InvalidPayload(String),
+    #[error("CI triage only works with repos hosted on GitHub")]
+    NotGitHub,
+}
+
+/// Saved settings, with the webhook secret when one was just issued. The
+/// secret is stored encrypted and can't be shown again.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SavedCiTriageSettings {
+    pub settings: CiTriageSettings,
+    #[ts(optional)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+/// The parts of a `workflow_run` event used here.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkflowRunEvent {
+    pub action: String,
+    pub workflow_run: WorkflowRun,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkflowRun {
+    pub id: i64,
+    pub run_attempt: i64,
+    pub name: String,
+    pub conclusion: Option<String>,
+    pub head_branch: String,
+    pub html_url: String,
+    #[serde(default)]
+    pub pull_requests: Vec<WorkflowRunPullRequest>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkflowRunPullRequest {
+    pub number: i64,
+}
+
+/// Create or update a repo's settings. A secret is issued when the settings
+/// are first created.
+pub async fn save_settings(
+    pool: &SqlitePool,
+    repo_id: Uuid,
+    data: &UpsertCiTriageSettings,
+) -> Result<SavedCiTriageSettings, CiTriageError> {
+    if CiTriageSettings::find_by_repo_id(pool, repo_id)
+        .await?
+        .is_some()
+    {
+        let settings = CiTriageSettings::update(pool, repo_id, data).await?;
+        return Ok(SavedCiTriageSettings {
+            settings,
+            secret: None,
+        });
+    }
+    let secret = new_token();
+    let (nonce, ciphertext) = secrets::encrypt_value(&secret)?;
+    let settings = CiTriageSettings::create(pool, repo_id, data, &nonce, &ciphertext).await?;
+    Ok(SavedCiTriageSettings {
+        settings,
+        secret: Some(secret),
+    })
+}
+
+/// Issue a new webhook secret, revoking the old one.
+pub async fn rotate_secret(
+    pool: &SqlitePool,
+    repo_id: Uuid,
+) -> Result<SavedCiTriageSettings, CiTriageError> {
+    let secret = new_token();
+    let (nonce, ciphertext) = secrets::encrypt_value(&secret)?;
+    CiTriageSettings::set_secret(pool, repo_id, &nonce, &ciphertext).await?;
+    let settings = CiTriageSettings::find_by_repo_id(pool, repo_id)
+        .await?
+        .ok_or(CiTriageModelError::NotFound)?;
+    Ok(SavedCiTriageSettings {
+        settings,
+        secret: Some(secret),
+    })
+}
+
+/// Check a delivery's `X-Hub-Signature-256` header against the repo's secret.
+pub async fn verify_delivery(
+    pool: &SqlitePool,
+    repo_id: Uuid,
+    body: &[u8],
+    signature: Option<&str>,
+) -> Result<(), CiTriageError> {
+    let (nonce, ciphertext) = CiTriageSettings::find_secret(pool, repo_id)
+        .await?
+        .ok_or(CiTriageModelError::NotFound)?;
+    let secret = secrets::decrypt_value(&nonce, &ciphertext, "CI triage webhook secret")?;
+    verify_signature(&secret, body, signature)
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Verify a `sha256=<hex>` HMAC of `body`, as GitHub signs deliveries.
+pub fn verify_signature(
+    secret: &str,
+    body: &[u8],
+    signature: Option<&str>,
+) -> Result<(), CiTriageError> {
+    let signature = signature
+        .and_then(|value| value.strip_prefix("sha256="))
+        .and_then(decode_hex)
+        .ok_or(CiTriageError::InvalidSignature)?;
+    if verify_hmac_sha256(secret.as_bytes(), body, &signature) {
+        Ok(())
+    } else {
+        Err(CiTriageError::InvalidSignature)
+    }
+}
+
+pub fn parse_event(body: &[u8]) -> Result<WorkflowRunEvent, CiTriageError> {
+    serde_json::from_slice(body).map_err(|e| CiTriageError::InvalidPayload(e.to_string()))
+}
+
+/// The run to triage, if the event reports a finished run that failed.
+pub fn failed_run(repo_id: Uuid, event: &WorkflowRunEvent) -> Option<CreateCiTriageRun> {
+    let run = &event.workflow_run;
+    let failed = matches!(run.conclusion.as_deref(), Some("failure" | "timed_out"));
+    if event.action != "completed" || !failed {
+        return None;
+    }
+    Some(CreateCiTriageRun {
+        repo_id,
+        workflow_run_id: run.id,
+        run_attempt: run.run_attempt,
+        workflow_name: run.name.clone(),
+        head_branch: run.head_branch.clone(),
+        run_url: run.html_url.clone(),
+        pr_number: run.pull_requests.first().map(|pr| pr.number),
+    })
+}
+
+/// Logs of the run's failed steps, fetched with the GitHub CLI.
+pub async fn fetch_failed_log(
+    git: &GitService,
+    repo: &Repo,
+    workflow_run_id: i64,
+) -> Result<String, CiTriageError> {
+    let remote = git.get_default_remote(&repo.path)?;
+    let GitHostService::GitHub(github) = GitHostService::from_url(&remote.url)? else {
+        return Err(CiTriageError::NotGitHub);
+    };
+    Ok(github
+        .failed_run_log(&repo.path, &remote.url, workflow_run_id)
+        .await?)
+}
+
+/// The last `max` characters of `text`.
+fn tail(text: &str, max: usize) -> &str {
+    let count = text.chars().count();
+    if count <= max {
+        return text;
+    }
+    let start = text
+        .char_indices()
+        .nth(count - max)
+        .map_or(0, |(index, _)| index);
+    &text[start..]
+}
+
+pub fn build_prompt(custom: Option<&str>, run: &CiTriageRun, log: &str) -> String {
+    let instructions = custom
+        .map(str::trim)
+        .filter(|prompt| !prompt.is_empty())
+        .unwrap_or(DEFAULT_PROMPT);
+    let log = log.trim();
+    let truncated = if tail(log, MAX_LOG_CHARS).len() < log.len() {
+        " (truncated to the last lines)"
+    } else {
+        ""
+    };
+    format!(
+        "{instructions}\n\nWorkflow: {}\nBranch: {}\nRun: {} (attempt {})\n\n\
+         Logs of the failed steps{truncated}:\n```\n{}\n```",
+        run.workflow_name,
+        run.head_branch,
+        run.run_url,
+        run.run_attempt,
+        tail(log, MAX_LOG_CHARS)
+    )
+}
+
+pub fn format_comment(run: &CiTriageRun, findings: &str) -> String {
+    let header = format!(
+        "### CI triage: {} failed\n\n[Workflow run]({}) on `{}`\n\n",
+        run.workflow_name, run.run_url, run.head_branch
+    );
+    let findings = findings.trim();
+    if findings.chars().count() > MAX_COMMENT_CHARS {
+        let cut = findings
+            .char_indices()
+            .nth(MAX_COMMENT_CHARS)
+            .map_or(findings.len(), |(index, _)| index);
+        return format!("{header}{}\n\n_(truncated)_", &findings[..cut]);
+    }
+    format!("{header}{findings}")
+}
+
+async fn post_findings(
+    pool: &SqlitePool,
+    git: &GitService,
+    run: &CiTriageRun,
+    pr_number: i64,
+    findings: &str,
+) -> Result<String, CiTriageError> {
+    let repo = Repo::find_by_id(pool, run.repo_id)
+        .await?
+        .ok_or(CiTriageModelError::NotFound)?;
+    let remote = git.get_default_remote(&repo.path)?;
+    let GitHostService::GitHub(github) = GitHostService::from_url(&remote.url)? else {
+        return Err(CiTriageError::NotGitHub);
+    };
+    Ok(github
+        .comment_on_pr(
+            &repo.path,
+            &remote.url,
+            pr_number,
+            &format_comment(run, findings),
+        )
+        .await?)
+}
+
+/// A workspace's actions have finished; if it was triaging a failed run,
+/// post the agent's findings and record the outcome. Posting happens in the
+/// background.
+pub async fn report_outcome(
+    pool: &SqlitePool,
+    git: &GitService,
+    ctx: &ExecutionContext,
+) -> Result<(), CiTriageError> {
+    let Some(run) = CiTriageRun::find_running_by_workspace_id(pool, ctx.workspace.id).await? else {
+        return Ok(());
+    };
+    let error = match ctx.execution_process.status {
+        ExecutionProcessStatus::Running => return Ok(()),
+        ExecutionProcessStatus::Completed => None,
+        ExecutionProcessStatus::Failed => Some("The triage agent failed"),
+        ExecutionProcessStatus::Killed => Some("The triage agent was stopped"),
+    };
+    if let Some(error) = error {
+        CiTriageRun::mark_failed(pool, run.id, error).await?;
+        return Ok(());
+    }
+
+    let findings = CodingAgentTurn::find_latest_summary_for_session(pool, ctx.session.id)
+        .await?
+        .filter(|summary| !summary.trim().is_empty());
+    let post_comment = CiTriageSettings::find_by_repo_id(pool, run.repo_id)
+        .await?
+        .is_some_and(|settings| settings.post_comment);
+    let (Some(pr_number), Some(findings), true) = (run.pr_number, findings, post_comment) else {
+        CiTriageRun::mark_reported(pool, run.id, None).await?;
+        return Ok(());
+    };
+
+    let pool = pool.clone();
+    let git = git.clone();
+    tokio::spawn(async move {
+        let result = match post_findings(&pool, &git, &run, pr_number, &findings).await {
+            Ok(url) => CiTriageRun::mark_reported(&pool, run.id, Some(&url)).await,
+            Err(e) => {
+                tracing::warn!("Failed to post CI triage for run {}: {}", run.id, e);
+                CiTriageRun::mark_failed(&pool, run.id, &e.to_string()).await
+            }
+        };
+        if let Err(e) = result {
+            tracing::error!("Failed to record CI triage outcome {}: {}", run.id, e);
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use db::models::ci_triage::CiTriageRunStatus;
+    use serde_json::json;
+
+    use super::*;
+
+    fn sign(secret: &str, body: &[u8]) -> String {
+        let digest = hmac_sha256(secret.as_bytes(), body);
+        let hex: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+        format!("sha256={hex}")
+    }
+
+    fn event(action: &str, conclusion: &str) -> WorkflowRunEvent {
+        serde_json::from_value(json!({
+            "action": action,
+            "workflow_run": {
+                "id": 42,
+                "run_attempt": 2,
+                "name": "CI",
+                "conclusion": conclusion,
+                "head_branch": "fix-login",
+                "html_url": "https://github.com/acme/app/actions/runs/42",
+                "pull_requests": [{ "number": 7, "url": "..." }],
+                "status": "completed",
+            },
+            "repository": { "full_name": "acme/app" },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn verifies_github_signatures() {
+        let body = br#"{"action":"completed"}"#;
+        let signature = sign("s3cret", body);
+
+        assert!(verify_signature("s3cret", body, Some(&signature)).is_ok());
+        for bad in [
+            None,
+            Some(signature.trim_start_matches("sha256=")),
+            Some("sha256=zz"),
+        ] {
+            assert!(matches!(
+                verify_signature("s3cret", body, bad),
+                Err(CiTriageError::InvalidSignature)
+            ));
+        }
+        assert!(verify_signature("other", body, Some(&signature)).is_err());
+        assert!(verify_signature("s3cret", b"{}", Some(&signature)).is_err());
+    }
+
+    #[test]
+    fn only_finished_failures_are_triaged() {
+        let repo_id = Uuid::new_v4();
+        let run = failed_run(repo_id, &event("completed", "failure")).unwrap();
+        assert_eq!(run.workflow_run_id, 42);
+        assert_eq!(run.run_attempt, 2);
+        assert_eq!(run.head_branch, "fix-login");
+        assert_eq!(run.pr_number, Some(7));
+
+        assert!(failed_run(repo_id, &event("completed", "success")).is_none());
+        assert!(failed_run(repo_id, &event("completed", "cancelled")).is_none());
+        assert!(failed_run(repo_id, &event("requested", "failure")).is_none());
+    }
+
+    #[test]
+    fn prompt_keeps_the_end_of_long_logs() {
+        let run = CiTriageRun {
+            id: Uuid::new_v4(),
+            repo_id: Uuid::new_v4(),
+            workflow_run_id: 42,
+            run_attempt: 1,
+            workflow_name: "CI".to_string(),
+            head_branch: "main".to_string(),
+            run_url: "https://github.com/acme/app/actions/runs/42".to_string(),
+            pr_number: None,
+            workspace_id: None,
+            status: CiTriageRunStatus::Pending,
+            error: None,
+            comment_url: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let log = format!("{}error: test failed", "noise\n".repeat(10_000));
+
+        let prompt = build_prompt(None, &run, &log);
+        assert!(prompt.starts_with(DEFAULT_PROMPT));
+        assert!(prompt.contains("(truncated to the last lines)"));
+        assert!(prompt.contains("error: test failed\n```"));
+
+        let prompt = build_prompt(Some("Explain the failure."), &run, "boom");
+        assert!(prompt.starts_with("Explain the failure.\n\nWorkflow: CI"));
+        assert!(!prompt.contains("truncated"));
+    }
+}
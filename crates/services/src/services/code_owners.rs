@@ -0,0 +1,314 @@
+//! Code ownership from a repo's CODEOWNERS file.
+//!
+//! The file is looked up where GitHub looks for it and matched the same way:
+//! patterns follow gitignore rules and the last matching line wins, so a
+//! later line without owners leaves its paths unowned. Owners are kept as
+//! written (`@user`, `@org/team` or an email address).
+
+use std::path::Path;
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Where GitHub looks for the file, in order.
+pub const CODEOWNERS_LOCATIONS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+/// Paths picked out of free text, e.g. an issue description.
+const MAX_REFERENCED_PATHS: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct CodeOwnersRule {
+    /// 1-based line in the CODEOWNERS file.
+    pub line: usize,
+    pub pattern: String,
+    pub owners: Vec<String>,
+}
+
+/// A repo's parsed CODEOWNERS file. `path` is unset when the repo has none.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct CodeOwnersFile {
+    pub path: Option<String>,
+    pub rules: Vec<CodeOwnersRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct PathOwners {
+    pub path: String,
+    /// Empty when no rule matches or the matching rule lists no owners.
+    pub owners: Vec<String>,
+    /// The rule that decided the owners.
+    pub pattern: Option<String>,
+}
+
+/// An owner and the paths they own, for suggesting reviewers or assignees.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct OwnerSuggestion {
+    pub owner: String,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+pub struct CodeOwnersLookup {
+    #[serde(default)]
+    #[ts(optional)]
+    pub paths: Option<Vec<String>>,
+    /// Free text such as an issue description; file paths mentioned in it
+    /// are looked up too.
+    #[serde(default)]
+    #[ts(optional)]
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct CodeOwnersMatch {
+    /// CODEOWNERS file the owners come from, if the repo has one.
+    pub file: Option<String>,
+    pub paths: Vec<PathOwners>,
+    /// Owners of the paths, owning the most first.
+    pub owners: Vec<OwnerSuggestion>,
+}
+
+/// Parsed rules, ready to match paths against.
+#[derive(Debug, Clone, Default)]
+pub struct CodeOwners {
+    path: Option<String>,
+    rules: Vec<(CodeOwnersRule, Gitignore)>,
+}
+
+impl CodeOwners {
+    /// Parse a CODEOWNERS file. Lines GitHub would reject, such as
+    /// negations, are skipped.
+    pub fn parse(path: Option<String>, content: &str) -> Self {
+        let mut rules = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            // Owners may be followed by a trailing comment.
+            let line = line.split(" #").next().unwrap_or(line);
+            let mut parts = line.split_whitespace();
+            let Some(pattern) = parts.next() else {
+                continue;
+            };
+            if pattern.starts_with('!') {
+                continue;
+            }
+            let mut builder = GitignoreBuilder::new("");
+            let built = match builder.add_line(None, pattern) {
+                Ok(_) => builder.build(),
+                Err(e) => Err(e),
+            };
+            let matcher = match built {
+                Ok(matcher) => matcher,
+                Err(e) => {
+                    tracing::debug!("Skipping CODEOWNERS line {}: {}", index + 1, e);
+                    continue;
+                }
+            };
+            let rule = CodeOwnersRule {
+                line: index + 1,
+                pattern: pattern.to_string(),
+                owners: parts.map(str::to_string).collect(),
+            };
+            rules.push((rule, matcher));
+        }
+        Self { path, rules }
+    }
+
+    /// Read the repo's CODEOWNERS file from the first location that has one.
+    pub async fn load(repo_path: &Path) -> std::io::Result<Self> {
+        for location in CODEOWNERS_LOCATIONS {
+            match tokio::fs::read_to_string(repo_path.join(location)).await {
+                Ok(content) => return Ok(Self::parse(Some(location.to_string()), &content)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(Self::default())
+    }
+
+    pub fn file(&self) -> CodeOwnersFile {
+        CodeOwnersFile {
+            path: self.path.clone(),
+            rules: self.rules.iter().map(|(rule, _)| rule.clone()).collect(),
+        }
+    }
+
+    /// The rule that decides who owns `path`, if any.
+    fn rule_for(&self, path: &str) -> Option<&CodeOwnersRule> {
+        let path = path.trim_start_matches("./").trim_start_matches('/');
+        self.rules
+            .iter()
+            .rev()
+            .find(|(_, matcher)| matcher.matched_path_or_any_parents(path, false).is_ignore())
+            .map(|(rule, _)| rule)
+    }
+
+    pub fn owners_of(&self, path: &str) -> PathOwners {
+        let rule = self.rule_for(path);
+        PathOwners {
+            path: path.to_string(),
+            owners: rule.map(|rule| rule.owners.clone()).unwrap_or_default(),
+            pattern: rule.map(|rule| rule.pattern.clone()),
+        }
+    }
+
+    /// Owners of each path, and the owners ranked by how many of the paths
+    /// they own.
+    pub fn match_paths<I, S>(&self, paths: I) -> CodeOwnersMatch
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut matched: Vec<PathOwners> = Vec::new();
+        for path in paths {
+            let path = path.as_ref().trim();
+            if path.is_empty() || matched.iter().any(|existing| existing.path == path) {
+                continue;
+            }
+            matched.push(self.owners_of(path));
+        }
+
+        let mut owners: Vec<OwnerSuggestion> = Vec::new();
+        for path in &matched {
+            for owner in &path.owners {
+                match owners
+                    .iter_mut()
+                    .find(|suggestion| &suggestion.owner == owner)
+                {
+                    Some(suggestion) => suggestion.paths.push(path.path.clone()),
+                    None => owners.push(OwnerSuggestion {
+                        owner: owner.clone(),
+                        paths: vec![path.path.clone()],
+                    }),
+                }
+            }
+        }
+        // Stable, so owners with equal counts keep the order they were found.
+        owners.sort_by_key(|suggestion| std::cmp::Reverse(suggestion.paths.len()));
+
+        CodeOwnersMatch {
+            file: self.path.clone(),
+            paths: matched,
+            owners,
+        }
+    }
+
+    /// Look up the paths of a request, including those mentioned in its text.
+    pub fn lookup(&self, request: &CodeOwnersLookup) -> CodeOwnersMatch {
+        let mut paths = request.paths.clone().unwrap_or_default();
+        if let Some(text) = &request.text {
+            paths.extend(referenced_paths(text));
+        }
+        self.match_paths(paths)
+    }
+}
+
+fn is_path_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '.' | '/' | '-')
+}
+
+/// File paths mentioned in free text: words with a slash or a file
+/// extension, without surrounding quotes, punctuation or line numbers.
+pub fn referenced_paths(text: &str) -> Vec<String> {
+    let mut paths: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        if word.contains("://") {
+            continue;
+        }
+        let word = word
+            .trim_start_matches(|c: char| !is_path_char(c))
+            .trim_end_matches(|c: char| c == '.' || !is_path_char(c));
+        // `src/main.rs:42` and `src/main.rs:42:7` point at lines.
+        let word = word.split(':').next().unwrap_or(word);
+        let word = word.trim_end_matches('.').trim_start_matches("./");
+        let has_extension = word.rsplit_once('.').is_some_and(|(stem, ext)| {
+            !stem.is_empty()
+                && (2..=5).contains(&ext.len())
+                && ext.starts_with(|c: char| c.is_ascii_alphabetic())
+        });
+        if word.len() < 3
+            || !(word.contains('/') || has_extension)
+            || !word.chars().all(is_path_char)
+        {
+            continue;
+        }
+        if !paths.iter().any(|path| path == word) {
+            paths.push(word.to_string());
+        }
+        if paths.len() == MAX_REFERENCED_PATHS {
+            break;
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODEOWNERS: &str = "\
+# Default owners
+*                   @acme/core
+*.ts                @frontend-dev   # TypeScript
+/crates/            @rust-dev @acme/backend
+docs/               docs@example.com
+/crates/generated/
+!not-supported      @nobody
+";
+
+    #[test]
+    fn last_matching_rule_wins() {
+        let owners = CodeOwners::parse(Some("CODEOWNERS".to_string()), CODEOWNERS);
+        let of = |path: &str| owners.owners_of(path).owners;
+
+        assert_eq!(of("README.md"), vec!["@acme/core"]);
+        assert_eq!(of("frontend/src/app.ts"), vec!["@frontend-dev"]);
+        assert_eq!(
+            of("crates/server/src/main.rs"),
+            vec!["@rust-dev", "@acme/backend"]
+        );
+        assert_eq!(
+            of("/crates/server/src/main.rs"),
+            vec!["@rust-dev", "@acme/backend"]
+        );
+        assert_eq!(of("user/docs/intro.md"), vec!["docs@example.com"]);
+        assert!(of("crates/generated/types.rs").is_empty());
+        assert_eq!(
+            owners
+                .owners_of("crates/generated/types.rs")
+                .pattern
+                .as_deref(),
+            Some("/crates/generated/")
+        );
+        assert_eq!(owners.file().rules.len(), 5);
+    }
+
+    #[test]
+    fn ranks_owners_by_paths_owned() {
+        let owners = CodeOwners::parse(None, CODEOWNERS);
+        let matched = owners.match_paths(["crates/a.rs", "crates/b.rs", "web/c.ts", "crates/a.rs"]);
+
+        assert_eq!(matched.paths.len(), 3);
+        let ranked: Vec<_> = matched
+            .owners
+            .iter()
+            .map(|o| (o.owner.as_str(), o.paths.len()))
+            .collect();
+        assert_eq!(
+            ranked,
+            vec![("@rust-dev", 2), ("@acme/backend", 2), ("@frontend-dev", 1)]
+        );
+    }
+
+    #[test]
+    fn finds_paths_in_text() {
+        let text = "Crash in `crates/server/src/main.rs:42`, see https://example.com/a/b \
+                    and package.json. Happens since v1.2.3, e.g. (docs/setup.md).";
+        assert_eq!(
+            referenced_paths(text),
+            vec!["crates/server/src/main.rs", "package.json", "docs/setup.md"]
+        );
+    }
+}
@@ -0,0 +1,382 @@
+//! Content search over repo files, built on ripgrep's search crates.
+//!
+//! Files are walked in path order with gitignore rules applied, so results
+//! are stable and can be paged with a plain match offset.
+
+use std::path::Path;
+
+use grep_matcher::Matcher;
+use grep_regex::{RegexMatcher, RegexMatcherBuilder};
+use grep_searcher::{
+    BinaryDetection, Searcher, SearcherBuilder, Sink, SinkContext, SinkContextKind, SinkMatch,
+};
+use ignore::{WalkBuilder, overrides::OverrideBuilder};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ts_rs::TS;
+use utils::path::ALWAYS_SKIP_DIRS;
+use uuid::Uuid;
+
+use super::file_search::SearchRoot;
+
+pub const CODE_SEARCH_DEFAULT_LIMIT: usize = 100;
+pub const CODE_SEARCH_MAX_LIMIT: usize = 500;
+pub const CODE_SEARCH_MAX_CONTEXT: usize = 10;
+/// Longer lines are cut, as they are usually minified or generated.
+const MAX_LINE_CHARS: usize = 500;
+
+#[derive(Debug, Error)]
+pub enum CodeSearchError {
+    #[error("Invalid search pattern: {0}")]
+    InvalidPattern(String),
+    #[error("Invalid glob: {0}")]
+    InvalidGlob(String),
+    #[error(transparent)]
+    Join(#[from] tokio::task::JoinError),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CodeSearchOptions {
+    pub query: String,
+    /// Treat `query` as a regex rather than literal text.
+    pub regex: bool,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    /// Globs files must match; a leading `!` excludes matching files instead.
+    pub globs: Vec<String>,
+    /// Lines of context before and after each matching line.
+    pub context: usize,
+    /// Number of matching lines to skip, for paging.
+    pub offset: usize,
+    pub limit: usize,
+}
+
+/// A match's position within its line, as 1-based character columns with an
+/// exclusive end.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct CodeSearchRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct CodeSearchMatch {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    /// Relative to the repo root.
+    pub path: String,
+    #[ts(type = "number")]
+    pub line_number: u64,
+    /// Column of the first match on the line.
+    pub column: usize,
+    pub line: String,
+    pub ranges: Vec<CodeSearchRange>,
+    pub context_before: Vec<String>,
+    pub context_after: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct CodeSearchResponse {
+    pub matches: Vec<CodeSearchMatch>,
+    /// Offset of the next page, if there are more matches.
+    pub next_offset: Option<usize>,
+}
+
+/// A matching line found in a file, before it's tied to a repo.
+struct LineMatch {
+    line_number: u64,
+    line: String,
+    ranges: Vec<CodeSearchRange>,
+    context_before: Vec<String>,
+    context_after: Vec<String>,
+}
+
+/// Collects matching lines and their context for one file, stopping once
+/// `remaining` lines have been found.
+struct CollectSink<'a> {
+    matcher: &'a RegexMatcher,
+    remaining: usize,
+    pending_before: Vec<String>,
+    matches: Vec<LineMatch>,
+}
+
+fn line_text(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    let text = text.trim_end_matches(['\n', '\r']);
+    match text.char_indices().nth(MAX_LINE_CHARS) {
+        Some((cut, _)) => text[..cut].to_string(),
+        None => text.to_string(),
+    }
+}
+
+/// Convert a byte offset within `bytes` to a 1-based character column.
+fn char_column(bytes: &[u8], offset: usize) -> usize {
+    String::from_utf8_lossy(&bytes[..offset]).chars().count() + 1
+}
+
+impl Sink for CollectSink<'_> {
+    type Error = std::io::Error;
+
+    fn matched(&mut self, _searcher: &Searcher, mat: &SinkMatch<'_>) -> Result<bool, Self::Error> {
+        let bytes = mat.bytes();
+        let mut ranges = Vec::new();
+        self.matcher
+            .find_iter(bytes, |m| {
+                if m.start() < m.end() {
+                    ranges.push(CodeSearchRange {
+                        start: char_column(bytes, m.start()),
+                        end: char_column(bytes, m.end()),
+                    });
+                }
+                true
+            })
+            .map_err(std::io::Error::other)?;
+
+        self.matches.push(LineMatch {
+            line_number: mat.line_number().unwrap_or_default(),
+            line: line_text(bytes),
+            ranges,
+            context_before: std::mem::take(&mut self.pending_before),
+            context_after: Vec::new(),
+        });
+        self.remaining -= 1;
+        Ok(self.remaining > 0)
+    }
+
+    fn context(
+        &mut self,
+        _searcher: &Searcher,
+        context: &SinkContext<'_>,
+    ) -> Result<bool, Self::Error> {
+        let line = line_text(context.bytes());
+        match context.kind() {
+            SinkContextKind::Before => self.pending_before.push(line),
+            SinkContextKind::After => {
+                if let Some(last) = self.matches.last_mut() {
+                    last.context_after.push(line);
+                }
+            }
+            SinkContextKind::Other => {}
+        }
+        Ok(true)
+    }
+}
+
+fn build_matcher(options: &CodeSearchOptions) -> Result<RegexMatcher, CodeSearchError> {
+    let pattern = if options.regex {
+        options.query.clone()
+    } else {
+        regex::escape(&options.query)
+    };
+    RegexMatcherBuilder::new()
+        .case_insensitive(!options.case_sensitive)
+        .word(options.whole_word)
+        .line_terminator(Some(b'\n'))
+        .build(&pattern)
+        .map_err(|e| CodeSearchError::InvalidPattern(e.to_string()))
+}
+
+fn search_root(
+    root: &SearchRoot,
+    matcher: &RegexMatcher,
+    options: &CodeSearchOptions,
+    wanted: usize,
+    out: &mut Vec<CodeSearchMatch>,
+) -> Result<(), CodeSearchError> {
+    let mut overrides = OverrideBuilder::new(&root.path);
+    for glob in &options.globs {
+        overrides
+            .add(glob)
+            .map_err(|e| CodeSearchError::InvalidGlob(e.to_string()))?;
+    }
+    let overrides = overrides
+        .build()
+        .map_err(|e| CodeSearchError::InvalidGlob(e.to_string()))?;
+
+    let mut searcher = SearcherBuilder::new()
+        .line_number(true)
+        .binary_detection(BinaryDetection::quit(b'\x00'))
+        .before_context(options.context)
+        .after_context(options.context)
+        .build();
+
+    let walker = WalkBuilder::new(&root.path)
+        .hidden(false)
+        .git_ignore(true)
+        .overrides(overrides)
+        .sort_by_file_path(|a, b| a.cmp(b))
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            !ALWAYS_SKIP_DIRS.contains(&name.as_ref())
+        })
+        .build();
+
+    for entry in walker.filter_map(Result::ok) {
+        if out.len() >= wanted {
+            break;
+        }
+        if !entry.file_type().is_some_and(|ft| ft.is_file()) {
+            continue;
+        }
+        let mut sink = CollectSink {
+            matcher,
+            remaining: wanted - out.len(),
+            pending_before: Vec::new(),
+            matches: Vec::new(),
+        };
+        if let Err(e) = searcher.search_path(matcher, entry.path(), &mut sink) {
+            tracing::debug!("Skipping {:?} in code search: {}", entry.path(), e);
+            continue;
+        }
+        let path = relative_path(&root.path, entry.path());
+        out.extend(sink.matches.into_iter().map(|m| CodeSearchMatch {
+            repo_id: root.repo_id,
+            repo_name: root.repo_name.clone(),
+            path: path.clone(),
+            line_number: m.line_number,
+            column: m.ranges.first().map_or(1, |range| range.start),
+            line: m.line,
+            ranges: m.ranges,
+            context_before: m.context_before,
+            context_after: m.context_after,
+        }));
+    }
+    Ok(())
+}
+
+fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Search the files under `roots` for `options.query`. One match is reported
+/// per matching line.
+pub async fn search_code(
+    roots: Vec<SearchRoot>,
+    options: CodeSearchOptions,
+) -> Result<CodeSearchResponse, CodeSearchError> {
+    tokio::task::spawn_blocking(move || {
+        let matcher = build_matcher(&options)?;
+        let limit = options.limit.clamp(1, CODE_SEARCH_MAX_LIMIT);
+        // One extra match tells whether there is a next page.
+        let wanted = options.offset + limit + 1;
+        let mut matches = Vec::new();
+        for root in &roots {
+            search_root(root, &matcher, &options, wanted, &mut matches)?;
+            if matches.len() >= wanted {
+                break;
+            }
+        }
+
+        let has_more = matches.len() > options.offset + limit;
+        let matches: Vec<CodeSearchMatch> = matches
+            .into_iter()
+            .skip(options.offset)
+            .take(limit)
+            .collect();
+        Ok(CodeSearchResponse {
+            next_offset: has_more.then_some(options.offset + limit),
+            matches,
+        })
+    })
+    .await?
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn root(path: PathBuf) -> SearchRoot {
+        SearchRoot {
+            repo_id: Uuid::new_v4(),
+            repo_name: "repo".to_string(),
+            path,
+        }
+    }
+
+    fn options(query: &str) -> CodeSearchOptions {
+        CodeSearchOptions {
+            query: query.to_string(),
+            limit: 10,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn finds_matches_with_context_and_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("src/lib.rs"),
+            "fn a() {}\nfn auth_check() { auth() }\nfn b() {}\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("notes.md"), "Auth is handled in lib.rs\n").unwrap();
+
+        let mut opts = options("auth");
+        opts.context = 1;
+        let response = search_code(vec![root(dir.path().to_path_buf())], opts.clone())
+            .await
+            .unwrap();
+        assert_eq!(response.matches.len(), 2);
+        assert_eq!(response.next_offset, None);
+        let code = &response.matches[1];
+        assert_eq!(code.path, "src/lib.rs");
+        assert_eq!(code.line_number, 2);
+        assert_eq!(code.column, 4);
+        assert_eq!(
+            code.ranges,
+            vec![
+                CodeSearchRange { start: 4, end: 8 },
+                CodeSearchRange { start: 19, end: 23 },
+            ]
+        );
+        assert_eq!(code.context_before, vec!["fn a() {}"]);
+        assert_eq!(code.context_after, vec!["fn b() {}"]);
+
+        opts.limit = 1;
+        opts.globs = vec!["*.rs".to_string()];
+        let response = search_code(vec![root(dir.path().to_path_buf())], opts.clone())
+            .await
+            .unwrap();
+        assert_eq!(response.matches.len(), 1);
+        assert_eq!(response.matches[0].path, "src/lib.rs");
+        assert_eq!(response.next_offset, None);
+
+        opts.globs = Vec::new();
+        let response = search_code(vec![root(dir.path().to_path_buf())], opts)
+            .await
+            .unwrap();
+        assert_eq!(response.matches[0].path, "notes.md");
+        assert_eq!(response.next_offset, Some(1));
+    }
+
+    #[tokio::test]
+    async fn literal_queries_are_escaped() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a.b\naxb\n").unwrap();
+
+        let response = search_code(vec![root(dir.path().to_path_buf())], options("a.b"))
+            .await
+            .unwrap();
+        assert_eq!(response.matches.len(), 1);
+
+        let mut opts = options("a.b");
+        opts.regex = true;
+        let response = search_code(vec![root(dir.path().to_path_buf())], opts)
+            .await
+            .unwrap();
+        assert_eq!(response.matches.len(), 2);
+
+        let mut opts = options("(");
+        opts.regex = true;
+        assert!(matches!(
+            search_code(vec![root(dir.path().to_path_buf())], opts).await,
+            Err(CodeSearchError::InvalidPattern(_))
+        ));
+    }
+}
@@ -0,0 +1,156 @@
+//! Outbound webhook fired when a workspace run finishes, so external systems
+//! (CI, dashboards, chat bots) can react to it.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    coding_agent_turn::CodingAgentTurn,
+    execution_process::{
+        ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+    },
+    execution_process_repo_state::ExecutionProcessRepoState,
+};
+use git::GitService;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use super::diff_stream::{self, DiffStats};
+
+pub const COMPLETION_WEBHOOK_EVENT: &str = "workspace.completed";
+const COMPLETION_WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Commits made in one repo during the run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RepoCommitRange {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    /// HEAD before the coding agent started.
+    pub from_commit: Option<String>,
+    /// HEAD once the run finished.
+    pub to_commit: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompletionWebhookPayload {
+    pub event: &'static str,
+    pub workspace_id: Uuid,
+    pub workspace_name: Option<String>,
+    pub status: ExecutionProcessStatus,
+    pub branch: String,
+    pub executor: Option<String>,
+    pub repos: Vec<RepoCommitRange>,
+    /// Changes against each repo's target branch.
+    pub diff_stats: Option<DiffStats>,
+    /// Cost reported by the agent for its latest turn.
+    pub cost_usd: Option<f64>,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// The `total_cost_usd` of the last `result` message in an agent's JSON
+/// output, for agents that report one.
+pub fn reported_cost_usd(output: &str) -> Option<f64> {
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line.trim()).ok())
+        .filter(|msg| msg.get("type").and_then(|t| t.as_str()) == Some("result"))
+        .filter_map(|msg| msg.get("total_cost_usd").and_then(|cost| cost.as_f64()))
+        .last()
+}
+
+/// Pair up the HEADs before the coding agent ran with the HEADs at the end of
+/// the run, per repo.
+fn commit_ranges(
+    ctx: &ExecutionContext,
+    before: &[ExecutionProcessRepoState],
+    after: &[ExecutionProcessRepoState],
+) -> Vec<RepoCommitRange> {
+    ctx.repos
+        .iter()
+        .map(|repo| {
+            let from_commit = before
+                .iter()
+                .find(|state| state.repo_id == repo.id)
+                .and_then(|state| state.before_head_commit.clone());
+            let to_commit = after
+                .iter()
+                .find(|state| state.repo_id == repo.id)
+                .and_then(|state| {
+                    state
+                        .after_head_commit
+                        .clone()
+                        .or_else(|| state.before_head_commit.clone())
+                });
+            RepoCommitRange {
+                repo_id: repo.id,
+                repo_name: repo.name.clone(),
+                from_commit,
+                to_commit,
+            }
+        })
+        .collect()
+}
+
+pub async fn build_payload(
+    pool: &SqlitePool,
+    git: &GitService,
+    ctx: &ExecutionContext,
+) -> Result<CompletionWebhookPayload, sqlx::Error> {
+    let latest_agent_run = ExecutionProcess::find_by_session_id(pool, ctx.session.id, false)
+        .await?
+        .into_iter()
+        .rfind(|process| process.run_reason == ExecutionProcessRunReason::CodingAgent);
+    let start_process_id = latest_agent_run.map_or(ctx.execution_process.id, |p| p.id);
+    let before =
+        ExecutionProcessRepoState::find_by_execution_process_id(pool, start_process_id).await?;
+    let after =
+        ExecutionProcessRepoState::find_by_execution_process_id(pool, ctx.execution_process.id)
+            .await?;
+
+    Ok(CompletionWebhookPayload {
+        event: COMPLETION_WEBHOOK_EVENT,
+        workspace_id: ctx.workspace.id,
+        workspace_name: ctx.workspace.name.clone(),
+        status: ctx.execution_process.status.clone(),
+        branch: ctx.workspace.branch.clone(),
+        executor: ctx.session.executor.clone(),
+        repos: commit_ranges(ctx, &before, &after),
+        diff_stats: diff_stream::compute_diff_stats(pool, git, &ctx.workspace).await,
+        cost_usd: CodingAgentTurn::find_latest_cost_for_session(pool, ctx.session.id).await?,
+        completed_at: ctx.execution_process.completed_at.unwrap_or_else(Utc::now),
+    })
+}
+
+pub async fn send(url: &str, payload: &CompletionWebhookPayload) -> reqwest::Result<()> {
+    reqwest::Client::builder()
+        .timeout(COMPLETION_WEBHOOK_TIMEOUT)
+        .build()?
+        .post(url)
+        .json(payload)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_cost_from_last_result_message() {
+        let output = concat!(
+            r#"{"type":"system","subtype":"init"}"#,
+            "\n",
+            r#"{"type":"result","total_cost_usd":0.12}"#,
+            "\n",
+            "not json\n",
+            r#"{"type":"result","subtype":"success","total_cost_usd":0.4521}"#,
+            "\n",
+        );
+        assert_eq!(reported_cost_usd(output), Some(0.4521));
+        assert_eq!(reported_cost_usd(r#"{"type":"result"}"#), None);
+        assert_eq!(reported_cost_usd(""), None);
+    }
+}
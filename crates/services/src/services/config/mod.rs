@@ -42,6 +42,22 @@ pub type GitHubConfig = versions::v8::GitHubConfig;
 pub type UiLanguage = versions::v8::UiLanguage;
 pub type ShowcaseState = versions::v8::ShowcaseState;
 pub type SendMessageShortcut = versions::v8::SendMessageShortcut;
+pub type LogRedactionConfig = versions::v8::LogRedactionConfig;
+pub type RemoteApprovalConfig = versions::v8::RemoteApprovalConfig;
+pub type EmbeddingProvider = versions::v8::EmbeddingProvider;
+pub type SemanticSearchConfig = versions::v8::SemanticSearchConfig;
+pub type NotificationChannel = versions::v8::NotificationChannel;
+pub type NotificationEvent = versions::v8::NotificationEvent;
+pub type NotificationChannelsConfig = versions::v8::NotificationChannelsConfig;
+pub type QuietHoursConfig = versions::v8::QuietHoursConfig;
+pub type SessionSharingConfig = versions::v8::SessionSharingConfig;
+pub type WarmPoolConfig = versions::v8::WarmPoolConfig;
+pub type LogFsyncPolicy = versions::v8::LogFsyncPolicy;
+pub type LogWritePolicy = versions::v8::LogWritePolicy;
+pub type LogWritesConfig = versions::v8::LogWritesConfig;
+pub type DiffConfig = versions::v8::DiffConfig;
+pub type DatabaseConfig = versions::v8::DatabaseConfig;
+pub type UsersConfig = versions::v8::UsersConfig;
 
 /// Will always return config, trying old schemas or eventually returning default
 pub async fn load_config_from_file(config_path: &PathBuf) -> Config {
@@ -1,7 +1,11 @@
+use std::collections::HashMap;
+
 use anyhow::Error;
+use chrono::NaiveTime;
 use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
+use uuid::Uuid;
 pub use v7::{
     EditorConfig, EditorType, GitHubConfig, NotificationConfig, ShowcaseState, SoundFile,
     ThemeMode, UiLanguage,
@@ -25,6 +29,26 @@ fn default_relay_enabled() -> bool {
     true
 }
 
+fn default_warm_pool_repos() -> u32 {
+    3
+}
+
+fn default_warm_pool_worktrees_per_repo() -> u32 {
+    1
+}
+
+fn default_warm_pool_max_disk_mb() -> u32 {
+    10 * 1024
+}
+
+fn default_quiet_hours_start() -> String {
+    "22:00".to_string()
+}
+
+fn default_quiet_hours_end() -> String {
+    "07:00".to_string()
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
 pub enum SendMessageShortcut {
     #[default]
@@ -32,6 +56,332 @@ pub enum SendMessageShortcut {
     Enter,
 }
 
+fn default_log_redaction_enabled() -> bool {
+    true
+}
+
+/// Redaction applied to process output before it is persisted or streamed.
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct LogRedactionConfig {
+    /// Master switch; repos can additionally opt out individually.
+    #[serde(default = "default_log_redaction_enabled")]
+    pub enabled: bool,
+    /// Extra regexes applied on top of the built-in secret patterns. A named
+    /// `secret` group limits redaction to that group instead of the whole match.
+    #[serde(default)]
+    pub patterns: Vec<String>,
+}
+
+impl Default for LogRedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_log_redaction_enabled(),
+            patterns: Vec::new(),
+        }
+    }
+}
+
+/// Approval links sent off the machine, so approvals can be answered from a
+/// phone without the app open.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct RemoteApprovalConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Address the server is reachable at from other devices (e.g. through a
+    /// tunnel). Links are only sent when this is set.
+    #[serde(default)]
+    pub public_url: Option<String>,
+    /// Slack incoming webhook that receives approval links.
+    #[serde(default)]
+    pub slack_webhook_url: Option<String>,
+    /// Address that receives approval links by email, sent with `sendmail`.
+    #[serde(default)]
+    pub email_to: Option<String>,
+}
+
+/// Read-only public links to session transcripts, served by this server.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct SessionSharingConfig {
+    /// Off by default; turning it off also stops serving existing links.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Address the server is reachable at by the people links are shared
+    /// with. Links can only be created when this is set.
+    #[serde(default)]
+    pub public_url: Option<String>,
+}
+
+/// Pre-provisioned worktrees, with the setup script already run, that new
+/// workspaces of frequently used repos start from.
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct WarmPoolConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// How many of the most recently used repos to keep warm worktrees for.
+    #[serde(default = "default_warm_pool_repos")]
+    pub repos: u32,
+    #[serde(default = "default_warm_pool_worktrees_per_repo")]
+    pub worktrees_per_repo: u32,
+    /// Disk space the pool may take up, in megabytes. The pool stops
+    /// refilling once its worktrees reach it.
+    #[serde(default = "default_warm_pool_max_disk_mb")]
+    pub max_disk_mb: u32,
+}
+
+impl Default for WarmPoolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            repos: default_warm_pool_repos(),
+            worktrees_per_repo: default_warm_pool_worktrees_per_repo(),
+            max_disk_mb: default_warm_pool_max_disk_mb(),
+        }
+    }
+}
+
+/// When buffered process output is forced to disk with fsync.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFsyncPolicy {
+    /// Leave it to the OS.
+    Never,
+    /// Once, when the process has finished.
+    #[default]
+    OnFinish,
+    /// After every flush.
+    OnFlush,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct LogWritePolicy {
+    /// How long output may be buffered before it is written to the log
+    /// file, in milliseconds. 0 writes every line as it arrives.
+    pub flush_interval_ms: u32,
+    pub fsync: LogFsyncPolicy,
+}
+
+fn default_coding_agent_log_writes() -> LogWritePolicy {
+    LogWritePolicy {
+        flush_interval_ms: 250,
+        fsync: LogFsyncPolicy::OnFinish,
+    }
+}
+
+fn default_dev_server_log_writes() -> LogWritePolicy {
+    LogWritePolicy {
+        flush_interval_ms: 1000,
+        fsync: LogFsyncPolicy::Never,
+    }
+}
+
+fn default_script_log_writes() -> LogWritePolicy {
+    LogWritePolicy {
+        flush_interval_ms: 250,
+        fsync: LogFsyncPolicy::OnFinish,
+    }
+}
+
+/// How process output is written to log files, by what the process runs.
+/// Live output is streamed from memory, so buffering only delays the file.
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct LogWritesConfig {
+    #[serde(default = "default_coding_agent_log_writes")]
+    pub coding_agent: LogWritePolicy,
+    #[serde(default = "default_dev_server_log_writes")]
+    pub dev_server: LogWritePolicy,
+    /// Setup, cleanup, archive and verify scripts.
+    #[serde(default = "default_script_log_writes")]
+    pub scripts: LogWritePolicy,
+}
+
+impl Default for LogWritesConfig {
+    fn default() -> Self {
+        Self {
+            coding_agent: default_coding_agent_log_writes(),
+            dev_server: default_dev_server_log_writes(),
+            scripts: default_script_log_writes(),
+        }
+    }
+}
+
+fn default_diff_max_file_size_kb() -> u32 {
+    2048
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct DiffConfig {
+    /// Files larger than this, in KiB, are shown in the diff viewer without
+    /// their contents.
+    #[serde(default = "default_diff_max_file_size_kb")]
+    pub max_file_size_kb: u32,
+}
+
+impl DiffConfig {
+    pub fn max_file_bytes(&self) -> usize {
+        self.max_file_size_kb as usize * 1024
+    }
+}
+
+impl Default for DiffConfig {
+    fn default() -> Self {
+        Self {
+            max_file_size_kb: default_diff_max_file_size_kb(),
+        }
+    }
+}
+
+fn default_database_wal_enabled() -> bool {
+    true
+}
+
+fn default_database_busy_timeout_ms() -> u32 {
+    10_000
+}
+
+/// How the local database is opened. Applied at startup.
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct DatabaseConfig {
+    /// Write-ahead logging, so reads don't block writes.
+    #[serde(default = "default_database_wal_enabled")]
+    pub wal_enabled: bool,
+    /// How long a write waits for another to finish before failing with
+    /// `database is locked`, in milliseconds.
+    #[serde(default = "default_database_busy_timeout_ms")]
+    pub busy_timeout_ms: u32,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            wal_enabled: default_database_wal_enabled(),
+            busy_timeout_ms: default_database_busy_timeout_ms(),
+        }
+    }
+}
+
+/// User identities, for a team sharing one server.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct UsersConfig {
+    /// Reject API requests that aren't signed in as a user. Otherwise they
+    /// act as the machine's owner.
+    #[serde(default)]
+    pub require_sign_in: bool,
+    /// Remote organization whose members may sign in with their remote
+    /// account. Remote sign-in is off while unset.
+    #[serde(default)]
+    pub remote_organization_id: Option<Uuid>,
+}
+
+/// A way of delivering notifications.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, TS, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationChannel {
+    Desktop,
+    Sound,
+    Webhook,
+    Slack,
+    Email,
+}
+
+/// What a notification is about. Each event type is routed separately.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, TS, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEvent {
+    WorkspaceComplete,
+    ApprovalNeeded,
+    QuestionAsked,
+    /// Notifications requested by the UI.
+    General,
+}
+
+/// Channels beyond desktop and sound, and which channels each event type
+/// goes to. Desktop and sound are switched on and off by
+/// `NotificationConfig`; the other channels are on once configured.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct NotificationChannelsConfig {
+    /// URL that receives each notification as a JSON POST.
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// Slack incoming webhook.
+    #[serde(default)]
+    pub slack_webhook_url: Option<String>,
+    /// Address that receives notifications by email, sent with `sendmail`.
+    #[serde(default)]
+    pub email_to: Option<String>,
+    /// URL that receives a JSON summary (status, commits, diff stats, cost)
+    /// of each finished workspace run.
+    #[serde(default)]
+    pub completion_webhook_url: Option<String>,
+    /// Channels per event type. Event types not listed go to desktop and
+    /// sound.
+    #[serde(default)]
+    pub routes: HashMap<NotificationEvent, Vec<NotificationChannel>>,
+}
+
+/// Do-not-disturb window. Notifications raised inside it are held back and
+/// delivered as a summary once it ends.
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct QuietHoursConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Local time the window starts, as `HH:MM`.
+    #[serde(default = "default_quiet_hours_start")]
+    pub start: String,
+    /// Local time the window ends, as `HH:MM`. May be earlier than `start`
+    /// for windows that span midnight.
+    #[serde(default = "default_quiet_hours_end")]
+    pub end: String,
+}
+
+impl Default for QuietHoursConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: default_quiet_hours_start(),
+            end: default_quiet_hours_end(),
+        }
+    }
+}
+
+impl QuietHoursConfig {
+    /// Start and end times, if the window is enabled and both parse.
+    pub fn window(&self) -> Option<(NaiveTime, NaiveTime)> {
+        if !self.enabled {
+            return None;
+        }
+        let start = NaiveTime::parse_from_str(self.start.trim(), "%H:%M").ok()?;
+        let end = NaiveTime::parse_from_str(self.end.trim(), "%H:%M").ok()?;
+        Some((start, end))
+    }
+}
+
+/// Where semantic code search gets its embeddings from.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingProvider {
+    /// Built-in hashed embeddings: no setup, but only matches related
+    /// spellings of the same words, not synonyms.
+    #[default]
+    Local,
+    /// An OpenAI-compatible embeddings endpoint.
+    Endpoint,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct SemanticSearchConfig {
+    #[serde(default)]
+    pub provider: EmbeddingProvider,
+    /// Embeddings URL, e.g. `http://localhost:11434/v1/embeddings` for a
+    /// local Ollama.
+    #[serde(default)]
+    pub endpoint_url: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Environment variable holding the endpoint's API key, if it needs one.
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
 pub struct Config {
     pub config_version: String,
@@ -68,6 +418,28 @@ pub struct Config {
     pub relay_enabled: bool,
     #[serde(default)]
     pub host_nickname: Option<String>,
+    #[serde(default)]
+    pub log_redaction: LogRedactionConfig,
+    #[serde(default)]
+    pub remote_approvals: RemoteApprovalConfig,
+    #[serde(default)]
+    pub semantic_search: SemanticSearchConfig,
+    #[serde(default)]
+    pub notification_channels: NotificationChannelsConfig,
+    #[serde(default)]
+    pub quiet_hours: QuietHoursConfig,
+    #[serde(default)]
+    pub session_sharing: SessionSharingConfig,
+    #[serde(default)]
+    pub warm_pool: WarmPoolConfig,
+    #[serde(default)]
+    pub log_writes: LogWritesConfig,
+    #[serde(default)]
+    pub diff: DiffConfig,
+    #[serde(default)]
+    pub database: DatabaseConfig,
+    #[serde(default)]
+    pub users: UsersConfig,
 }
 
 impl Config {
@@ -99,6 +471,17 @@ impl Config {
             send_message_shortcut: SendMessageShortcut::default(),
             relay_enabled: true,
             host_nickname: None,
+            log_redaction: LogRedactionConfig::default(),
+            remote_approvals: RemoteApprovalConfig::default(),
+            semantic_search: SemanticSearchConfig::default(),
+            notification_channels: NotificationChannelsConfig::default(),
+            quiet_hours: QuietHoursConfig::default(),
+            session_sharing: SessionSharingConfig::default(),
+            warm_pool: WarmPoolConfig::default(),
+            log_writes: LogWritesConfig::default(),
+            diff: DiffConfig::default(),
+            database: DatabaseConfig::default(),
+            users: UsersConfig::default(),
         }
     }
 
@@ -155,6 +538,17 @@ impl Default for Config {
             send_message_shortcut: SendMessageShortcut::default(),
             relay_enabled: true,
             host_nickname: None,
+            log_redaction: LogRedactionConfig::default(),
+            remote_approvals: RemoteApprovalConfig::default(),
+            semantic_search: SemanticSearchConfig::default(),
+            notification_channels: NotificationChannelsConfig::default(),
+            quiet_hours: QuietHoursConfig::default(),
+            session_sharing: SessionSharingConfig::default(),
+            warm_pool: WarmPoolConfig::default(),
+            log_writes: LogWritesConfig::default(),
+            diff: DiffConfig::default(),
+            database: DatabaseConfig::default(),
+            users: UsersConfig::default(),
         }
     }
 }
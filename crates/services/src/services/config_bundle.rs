@@ -0,0 +1,330 @@
+//! Portable export of executor profiles, repo settings and prompt templates,
+//! for setting up another machine in one step.
+//!
+//! Secrets never leave the machine: the secret store isn't exported and
+//! environment variables are stripped from executor profiles. Importing
+//! keeps the local environment variables of matching profiles.
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    config_override::ConfigOverrides,
+    repo::{Repo, RepoError, RepoScriptSettings, UpdateRepo},
+    tag::{CreateTag, Tag, UpdateTag},
+};
+use executors::profile::{ExecutorConfigs, ProfileError};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+
+use super::config_overrides::{self, ConfigOverrideError};
+
+pub const CONFIG_BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum ConfigBundleError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Repo(#[from] RepoError),
+    #[error(transparent)]
+    Overrides(#[from] ConfigOverrideError),
+    #[error(transparent)]
+    Profiles(#[from] ProfileError),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Unsupported config bundle version {0}")]
+    UnsupportedVersion(u32),
+}
+
+/// A repo's settings. Repos are matched by name on import, as paths differ
+/// between machines.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RepoConfigExport {
+    pub name: String,
+    pub display_name: String,
+    pub setup_script: Option<String>,
+    pub cleanup_script: Option<String>,
+    pub archive_script: Option<String>,
+    pub copy_files: Option<String>,
+    pub parallel_setup_script: bool,
+    pub dev_server_script: Option<String>,
+    pub default_target_branch: Option<String>,
+    pub default_working_dir: Option<String>,
+    pub verify_script: Option<String>,
+    pub script_settings: RepoScriptSettings,
+    pub overrides: ConfigOverrides,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TemplateExport {
+    pub tag_name: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ConfigBundle {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    /// Left out of hand-written bundles to keep the local profiles.
+    #[serde(default)]
+    pub executor_profiles: Option<ExecutorConfigs>,
+    #[serde(default)]
+    pub repos: Vec<RepoConfigExport>,
+    #[serde(default)]
+    pub templates: Vec<TemplateExport>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct ConfigImportSummary {
+    pub executor_profiles_imported: bool,
+    pub repos_updated: Vec<String>,
+    /// Repos in the bundle with no local repo of the same name.
+    pub repos_not_found: Vec<String>,
+    pub templates_created: usize,
+    pub templates_updated: usize,
+}
+
+/// Call `f` on each executor configuration object in serialized profiles
+/// (`executors.<agent>.<variant>.<agent>`).
+fn for_each_executor_config(
+    profiles: &mut Value,
+    mut f: impl FnMut(&str, &str, &mut Map<String, Value>),
+) {
+    let Some(executors) = profiles.get_mut("executors").and_then(Value::as_object_mut) else {
+        return;
+    };
+    for (agent, profile) in executors.iter_mut() {
+        let Some(variants) = profile.as_object_mut() else {
+            continue;
+        };
+        for (variant, configs) in variants.iter_mut() {
+            if variant == "recently_used_models" {
+                continue;
+            }
+            let Some(configs) = configs.as_object_mut() else {
+                continue;
+            };
+            for config in configs.values_mut() {
+                if let Some(config) = config.as_object_mut() {
+                    f(agent, variant, config);
+                }
+            }
+        }
+    }
+}
+
+/// Profiles without environment variables or usage history.
+fn portable_profiles(profiles: &ExecutorConfigs) -> Result<ExecutorConfigs, serde_json::Error> {
+    let mut value = serde_json::to_value(profiles)?;
+    if let Some(executors) = value.get_mut("executors").and_then(Value::as_object_mut) {
+        for profile in executors.values_mut().filter_map(Value::as_object_mut) {
+            profile.remove("recently_used_models");
+        }
+    }
+    for_each_executor_config(&mut value, |_, _, config| {
+        config.remove("env");
+    });
+    serde_json::from_value(value)
+}
+
+/// `imported` with the environment variables of matching local profiles
+/// restored, unless the import sets its own.
+fn with_local_env(
+    imported: &ExecutorConfigs,
+    local: &ExecutorConfigs,
+) -> Result<ExecutorConfigs, serde_json::Error> {
+    let mut local_env = Vec::new();
+    let mut local_value = serde_json::to_value(local)?;
+    for_each_executor_config(&mut local_value, |agent, variant, config| {
+        if let Some(env) = config.get("env") {
+            local_env.push((agent.to_string(), variant.to_string(), env.clone()));
+        }
+    });
+
+    let mut value = serde_json::to_value(imported)?;
+    for_each_executor_config(&mut value, |agent, variant, config| {
+        if config.contains_key("env") {
+            return;
+        }
+        if let Some((_, _, env)) = local_env
+            .iter()
+            .find(|(a, v, _)| a == agent && v == variant)
+        {
+            config.insert("env".to_string(), env.clone());
+        }
+    });
+    serde_json::from_value(value)
+}
+
+pub async fn export_config(pool: &SqlitePool) -> Result<ConfigBundle, ConfigBundleError> {
+    let mut repos = Vec::new();
+    for repo in Repo::list_all(pool).await? {
+        repos.push(RepoConfigExport {
+            verify_script: Repo::verify_script(pool, repo.id).await?,
+            script_settings: Repo::script_settings(pool, repo.id).await?,
+            overrides: ConfigOverrides::find_for_repo(pool, repo.id).await?,
+            name: repo.name,
+            display_name: repo.display_name,
+            setup_script: repo.setup_script,
+            cleanup_script: repo.cleanup_script,
+            archive_script: repo.archive_script,
+            copy_files: repo.copy_files,
+            parallel_setup_script: repo.parallel_setup_script,
+            dev_server_script: repo.dev_server_script,
+            default_target_branch: repo.default_target_branch,
+            default_working_dir: repo.default_working_dir,
+        });
+    }
+    let templates = Tag::find_all(pool)
+        .await?
+        .into_iter()
+        .map(|tag| TemplateExport {
+            tag_name: tag.tag_name,
+            content: tag.content,
+        })
+        .collect();
+
+    Ok(ConfigBundle {
+        version: CONFIG_BUNDLE_VERSION,
+        exported_at: Utc::now(),
+        executor_profiles: Some(portable_profiles(&ExecutorConfigs::get_cached())?),
+        repos,
+        templates,
+    })
+}
+
+async fn import_repo(
+    pool: &SqlitePool,
+    repo: &Repo,
+    export: &RepoConfigExport,
+) -> Result<(), ConfigBundleError> {
+    Repo::update(
+        pool,
+        repo.id,
+        &UpdateRepo {
+            display_name: Some(Some(export.display_name.clone())),
+            setup_script: Some(export.setup_script.clone()),
+            cleanup_script: Some(export.cleanup_script.clone()),
+            archive_script: Some(export.archive_script.clone()),
+            copy_files: Some(export.copy_files.clone()),
+            parallel_setup_script: Some(Some(export.parallel_setup_script)),
+            dev_server_script: Some(export.dev_server_script.clone()),
+            default_target_branch: Some(export.default_target_branch.clone()),
+            default_working_dir: Some(export.default_working_dir.clone()),
+        },
+    )
+    .await?;
+    Repo::set_verify_script(pool, repo.id, export.verify_script.as_deref()).await?;
+    Repo::set_script_settings(pool, repo.id, &export.script_settings).await?;
+    config_overrides::set_repo_overrides(pool, repo.id, export.overrides.clone()).await?;
+    Ok(())
+}
+
+/// Apply `bundle` on top of the local setup. Existing templates with the same
+/// name are overwritten; nothing local is deleted.
+pub async fn import_config(
+    pool: &SqlitePool,
+    bundle: &ConfigBundle,
+) -> Result<ConfigImportSummary, ConfigBundleError> {
+    if bundle.version > CONFIG_BUNDLE_VERSION {
+        return Err(ConfigBundleError::UnsupportedVersion(bundle.version));
+    }
+    let mut summary = ConfigImportSummary::default();
+
+    if let Some(profiles) = &bundle.executor_profiles {
+        with_local_env(profiles, &ExecutorConfigs::get_cached())?.save_overrides()?;
+        ExecutorConfigs::reload();
+        summary.executor_profiles_imported = true;
+    }
+
+    let local_repos = Repo::list_all(pool).await?;
+    for export in &bundle.repos {
+        match local_repos.iter().find(|repo| repo.name == export.name) {
+            Some(repo) => {
+                import_repo(pool, repo, export).await?;
+                summary.repos_updated.push(export.name.clone());
+            }
+            None => summary.repos_not_found.push(export.name.clone()),
+        }
+    }
+
+    let local_tags = Tag::find_all(pool).await?;
+    for template in &bundle.templates {
+        match local_tags
+            .iter()
+            .find(|tag| tag.tag_name == template.tag_name)
+        {
+            Some(tag) if tag.content == template.content => {}
+            Some(tag) => {
+                Tag::update(
+                    pool,
+                    tag.id,
+                    &UpdateTag {
+                        tag_name: None,
+                        content: Some(template.content.clone()),
+                    },
+                )
+                .await?;
+                summary.templates_updated += 1;
+            }
+            None => {
+                Tag::create(
+                    pool,
+                    &CreateTag {
+                        tag_name: template.tag_name.clone(),
+                        content: template.content.clone(),
+                    },
+                )
+                .await?;
+                summary.templates_created += 1;
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profiles(json: Value) -> ExecutorConfigs {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn strips_env_on_export_and_restores_it_on_import() {
+        let local = profiles(serde_json::json!({
+            "executors": {
+                "CLAUDE_CODE": {
+                    "recently_used_models": { "models": ["opus"] },
+                    "DEFAULT": {
+                        "CLAUDE_CODE": {
+                            "dangerously_skip_permissions": true,
+                            "env": { "ANTHROPIC_API_KEY": "sk-local" }
+                        }
+                    }
+                }
+            }
+        }));
+
+        let exported = serde_json::to_value(portable_profiles(&local).unwrap()).unwrap();
+        let config = &exported["executors"]["CLAUDE_CODE"]["DEFAULT"]["CLAUDE_CODE"];
+        assert_eq!(config["dangerously_skip_permissions"], true);
+        assert!(config.get("env").is_none());
+        assert!(
+            exported["executors"]["CLAUDE_CODE"]
+                .get("recently_used_models")
+                .is_none()
+        );
+
+        let imported = with_local_env(&profiles(exported), &local).unwrap();
+        let imported = serde_json::to_value(imported).unwrap();
+        assert_eq!(
+            imported["executors"]["CLAUDE_CODE"]["DEFAULT"]["CLAUDE_CODE"]["env"]["ANTHROPIC_API_KEY"],
+            "sk-local"
+        );
+    }
+}
@@ -0,0 +1,314 @@
+//! Layered configuration: global config, then per-repo overrides, then
+//! per-workspace overrides. The repo layer's scripts are the repo's own
+//! script settings.
+
+use db::models::{config_override::ConfigOverrides, repo::Repo};
+use executors::profile::ExecutorProfileId;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::config::Config;
+
+#[derive(Debug, Error)]
+pub enum ConfigOverrideError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(
+        "Invalid git branch prefix. Must be a valid git branch name component without slashes."
+    )]
+    InvalidBranchPrefix,
+    #[error("Repo scripts are set in the repo's settings, not as overrides")]
+    RepoScripts,
+}
+
+/// The layer a config value came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum ConfigSource {
+    /// Not set at any layer.
+    Default,
+    Global,
+    Repo,
+    Workspace,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct EffectiveConfigSources {
+    pub executor_profile: ConfigSource,
+    pub git_branch_prefix: ConfigSource,
+    pub setup_script: ConfigSource,
+    pub cleanup_script: ConfigSource,
+    pub dev_server_script: ConfigSource,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct EffectiveConfig {
+    pub executor_profile: ExecutorProfileId,
+    pub git_branch_prefix: String,
+    pub setup_script: Option<String>,
+    pub cleanup_script: Option<String>,
+    pub dev_server_script: Option<String>,
+    /// Where each value above came from.
+    pub sources: EffectiveConfigSources,
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.trim().is_empty())
+}
+
+/// Drop blank scripts and check the branch prefix. A blank branch prefix is
+/// kept, as it means "no prefix".
+pub fn normalize_overrides(
+    overrides: ConfigOverrides,
+) -> Result<ConfigOverrides, ConfigOverrideError> {
+    let git_branch_prefix = overrides
+        .git_branch_prefix
+        .map(|prefix| prefix.trim().to_string());
+    if let Some(prefix) = &git_branch_prefix
+        && !git::is_valid_branch_prefix(prefix)
+    {
+        return Err(ConfigOverrideError::InvalidBranchPrefix);
+    }
+    Ok(ConfigOverrides {
+        executor_profile: overrides.executor_profile,
+        git_branch_prefix,
+        setup_script: non_empty(overrides.setup_script),
+        cleanup_script: non_empty(overrides.cleanup_script),
+        dev_server_script: non_empty(overrides.dev_server_script),
+    })
+}
+
+pub async fn set_repo_overrides(
+    pool: &SqlitePool,
+    repo_id: Uuid,
+    overrides: ConfigOverrides,
+) -> Result<ConfigOverrides, ConfigOverrideError> {
+    let overrides = normalize_overrides(overrides)?;
+    if overrides.setup_script.is_some()
+        || overrides.cleanup_script.is_some()
+        || overrides.dev_server_script.is_some()
+    {
+        return Err(ConfigOverrideError::RepoScripts);
+    }
+    ConfigOverrides::set_for_repo(pool, repo_id, &overrides).await?;
+    Ok(overrides)
+}
+
+pub async fn set_workspace_overrides(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+    overrides: ConfigOverrides,
+) -> Result<ConfigOverrides, ConfigOverrideError> {
+    let overrides = normalize_overrides(overrides)?;
+    ConfigOverrides::set_for_workspace(pool, workspace_id, &overrides).await?;
+    Ok(overrides)
+}
+
+/// The value of the highest layer that sets one.
+fn layered<T>(
+    global: Option<T>,
+    repo: Option<T>,
+    workspace: Option<T>,
+) -> (Option<T>, ConfigSource) {
+    match (workspace, repo, global) {
+        (Some(value), _, _) => (Some(value), ConfigSource::Workspace),
+        (None, Some(value), _) => (Some(value), ConfigSource::Repo),
+        (None, None, Some(value)) => (Some(value), ConfigSource::Global),
+        (None, None, None) => (None, ConfigSource::Default),
+    }
+}
+
+/// Merge the layers. `repo` is the repo the scripts are resolved for.
+pub fn merge_config(
+    config: &Config,
+    repo: Option<&Repo>,
+    repo_overrides: &ConfigOverrides,
+    workspace_overrides: &ConfigOverrides,
+) -> EffectiveConfig {
+    let ws = workspace_overrides.clone();
+    let (executor_profile, executor_profile_source) = layered(
+        Some(config.executor_profile.clone()),
+        repo_overrides.executor_profile.clone(),
+        ws.executor_profile,
+    );
+    let (git_branch_prefix, git_branch_prefix_source) = layered(
+        Some(config.git_branch_prefix.clone()),
+        repo_overrides.git_branch_prefix.clone(),
+        ws.git_branch_prefix,
+    );
+    let (setup_script, setup_script_source) = layered(
+        None,
+        non_empty(repo.and_then(|repo| repo.setup_script.clone())),
+        ws.setup_script,
+    );
+    let (cleanup_script, cleanup_script_source) = layered(
+        None,
+        non_empty(repo.and_then(|repo| repo.cleanup_script.clone())),
+        ws.cleanup_script,
+    );
+    let (dev_server_script, dev_server_script_source) = layered(
+        None,
+        non_empty(repo.and_then(|repo| repo.dev_server_script.clone())),
+        ws.dev_server_script,
+    );
+
+    EffectiveConfig {
+        executor_profile: executor_profile.unwrap_or_else(|| config.executor_profile.clone()),
+        git_branch_prefix: git_branch_prefix.unwrap_or_default(),
+        setup_script,
+        cleanup_script,
+        dev_server_script,
+        sources: EffectiveConfigSources {
+            executor_profile: executor_profile_source,
+            git_branch_prefix: git_branch_prefix_source,
+            setup_script: setup_script_source,
+            cleanup_script: cleanup_script_source,
+            dev_server_script: dev_server_script_source,
+        },
+    }
+}
+
+/// The effective config for `repo` (optional) within `workspace_id`
+/// (optional).
+pub async fn resolve_config(
+    pool: &SqlitePool,
+    config: &Config,
+    repo: Option<&Repo>,
+    workspace_id: Option<Uuid>,
+) -> Result<EffectiveConfig, ConfigOverrideError> {
+    let repo_overrides = match repo {
+        Some(repo) => ConfigOverrides::find_for_repo(pool, repo.id).await?,
+        None => ConfigOverrides::default(),
+    };
+    let workspace_overrides = match workspace_id {
+        Some(workspace_id) => ConfigOverrides::find_for_workspace(pool, workspace_id).await?,
+        None => ConfigOverrides::default(),
+    };
+    Ok(merge_config(
+        config,
+        repo,
+        &repo_overrides,
+        &workspace_overrides,
+    ))
+}
+
+/// Replace the scripts of `repos` with the workspace's script overrides, so
+/// processes started for the workspace run them.
+pub async fn apply_workspace_script_overrides(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+    repos: &mut [Repo],
+) -> Result<(), ConfigOverrideError> {
+    let overrides = ConfigOverrides::find_for_workspace(pool, workspace_id).await?;
+    for repo in repos {
+        if let Some(script) = &overrides.setup_script {
+            repo.setup_script = Some(script.clone());
+        }
+        if let Some(script) = &overrides.cleanup_script {
+            repo.cleanup_script = Some(script.clone());
+        }
+        if let Some(script) = &overrides.dev_server_script {
+            repo.dev_server_script = Some(script.clone());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::executors::BaseCodingAgent;
+
+    use super::*;
+
+    fn repo() -> Repo {
+        Repo {
+            id: Uuid::new_v4(),
+            path: "/tmp/app".into(),
+            name: "app".to_string(),
+            display_name: "app".to_string(),
+            setup_script: Some("npm install".to_string()),
+            cleanup_script: Some("  ".to_string()),
+            archive_script: None,
+            copy_files: None,
+            parallel_setup_script: false,
+            dev_server_script: None,
+            default_target_branch: None,
+            default_working_dir: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn higher_layers_win_and_report_their_source() {
+        let config = Config::default();
+        let repo_overrides = ConfigOverrides {
+            git_branch_prefix: Some("team".to_string()),
+            ..Default::default()
+        };
+        let workspace_overrides = ConfigOverrides {
+            executor_profile: Some(ExecutorProfileId::new(BaseCodingAgent::Codex)),
+            dev_server_script: Some("npm run dev".to_string()),
+            ..Default::default()
+        };
+
+        let effective = merge_config(
+            &config,
+            Some(&repo()),
+            &repo_overrides,
+            &workspace_overrides,
+        );
+        assert_eq!(
+            effective.executor_profile,
+            ExecutorProfileId::new(BaseCodingAgent::Codex)
+        );
+        assert_eq!(effective.git_branch_prefix, "team");
+        assert_eq!(effective.setup_script.as_deref(), Some("npm install"));
+        assert_eq!(effective.cleanup_script, None);
+        assert_eq!(effective.dev_server_script.as_deref(), Some("npm run dev"));
+        assert_eq!(
+            effective.sources,
+            EffectiveConfigSources {
+                executor_profile: ConfigSource::Workspace,
+                git_branch_prefix: ConfigSource::Repo,
+                setup_script: ConfigSource::Repo,
+                cleanup_script: ConfigSource::Default,
+                dev_server_script: ConfigSource::Workspace,
+            }
+        );
+
+        let effective = merge_config(
+            &config,
+            None,
+            &ConfigOverrides::default(),
+            &ConfigOverrides::default(),
+        );
+        assert_eq!(effective.git_branch_prefix, config.git_branch_prefix);
+        assert_eq!(effective.sources.git_branch_prefix, ConfigSource::Global);
+        assert_eq!(effective.sources.setup_script, ConfigSource::Default);
+    }
+
+    #[test]
+    fn normalizes_overrides() {
+        let overrides = normalize_overrides(ConfigOverrides {
+            git_branch_prefix: Some(" vk ".to_string()),
+            setup_script: Some("\n".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(overrides.git_branch_prefix.as_deref(), Some("vk"));
+        assert_eq!(overrides.setup_script, None);
+
+        assert!(matches!(
+            normalize_overrides(ConfigOverrides {
+                git_branch_prefix: Some("a/b".to_string()),
+                ..Default::default()
+            }),
+            Err(ConfigOverrideError::InvalidBranchPrefix)
+        ));
+    }
+}
@@ -2,6 +2,7 @@ use std::{
     collections::{HashMap, HashSet},
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
 use anyhow::{Error as AnyhowError, anyhow};
@@ -18,7 +19,8 @@ use db::{
             CreateExecutionProcessRepoState, ExecutionProcessRepoState,
         },
         idempotency::{is_unique_violation, normalize_idempotency_key},
-        repo::Repo,
+        pipeline::PipelineStatus,
+        repo::{Repo, RepoScriptSettings, ScriptFailurePolicy},
         session::{CreateSession, Session, SessionError},
         workspace::{Workspace, WorkspaceError},
         workspace_repo::WorkspaceRepo,
@@ -58,7 +60,21 @@ use utils::{
 use uuid::Uuid;
 use worktree_manager::WorktreeError;
 
-use crate::services::{execution_process, notification::NotificationService};
+use crate::services::{
+    ci_triage, completion_webhook,
+    config::{Config, NotificationEvent},
+    config_overrides, dev_server,
+    env_profiles::EnvProfileError,
+    execution_process,
+    file::FileService,
+    log_redaction::LogRedactor,
+    maintenance::MaintenanceMode,
+    notification::NotificationService,
+    pipelines, repo_knowledge, schedules,
+    secrets::SecretError,
+    startup_tasks::{StartupTask, StartupTasks},
+    test_results, workspace_context, workspace_undo,
+};
 pub type ContainerRef = String;
 
 pub enum ExecutionClaim {
@@ -87,19 +103,70 @@ pub enum ContainerError {
     #[error("Failed to kill process: {0}")]
     KillFailed(std::io::Error),
     #[error(transparent)]
+    Secret(#[from] SecretError),
+    #[error(transparent)]
+    EnvProfile(#[from] EnvProfileError),
+    #[error("Maintenance mode: {0}")]
+    Maintenance(String),
+    #[error(transparent)]
     Other(#[from] AnyhowError), // Catches any unclassified errors
 }
 
+/// Result of applying a repo's [`ScriptFailurePolicy`] to a failed script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptFailureOutcome {
+    /// Stop the action chain (the default behaviour).
+    Abort,
+    /// Start the next action in the chain as if the script had succeeded.
+    Continue,
+    /// The script was started again and now owns the rest of the chain.
+    Retried,
+}
+
+/// Timeout configured for a lifecycle script, if any. Verify scripts run
+/// alongside cleanup scripts and share their timeout.
+pub fn script_timeout(settings: &RepoScriptSettings, context: &ScriptContext) -> Option<Duration> {
+    let secs = match context {
+        ScriptContext::SetupScript => settings.setup_timeout_secs,
+        ScriptContext::CleanupScript | ScriptContext::VerifyScript => settings.cleanup_timeout_secs,
+        ScriptContext::ArchiveScript => settings.archive_timeout_secs,
+        ScriptContext::DevServer | ScriptContext::ToolInstallScript => None,
+    }?;
+    (secs > 0).then(|| Duration::from_secs(secs.into()))
+}
+
+/// What a repo's failure policy calls for after a script has failed
+/// `failures` times in a row. [`ScriptFailureOutcome::Retried`] means the
+/// script should be started again.
+fn script_failure_outcome(settings: &RepoScriptSettings, failures: usize) -> ScriptFailureOutcome {
+    match settings.on_failure {
+        ScriptFailurePolicy::Abort => ScriptFailureOutcome::Abort,
+        ScriptFailurePolicy::Continue => ScriptFailureOutcome::Continue,
+        ScriptFailurePolicy::Retry if failures > settings.max_retries as usize => {
+            ScriptFailureOutcome::Abort
+        }
+        ScriptFailurePolicy::Retry => ScriptFailureOutcome::Retried,
+    }
+}
+
 #[async_trait]
 pub trait ContainerService {
     fn msg_stores(&self) -> &Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>;
 
     fn db(&self) -> &DBService;
 
+    fn config(&self) -> &Arc<RwLock<Config>>;
+
     fn git(&self) -> &GitService;
 
     fn notification_service(&self) -> &NotificationService;
 
+    fn file_service(&self) -> &FileService;
+
+    fn startup_tasks(&self) -> &StartupTasks;
+
+    fn maintenance(&self) -> &MaintenanceMode;
+
     async fn touch(&self, workspace: &Workspace) -> Result<(), ContainerError>;
 
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf;
@@ -240,8 +307,150 @@ pub trait ContainerService {
         action.next_action.is_none()
     }
 
+    /// Script settings of the repo a lifecycle script runs in. Scripts run in
+    /// their repo's directory, so the repo is identified by the working dir.
+    async fn script_settings_for(
+        &self,
+        workspace_id: Uuid,
+        request: &ScriptRequest,
+    ) -> Option<RepoScriptSettings> {
+        let pool = &self.db().pool;
+        let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace_id)
+            .await
+            .ok()?;
+        let repo = match request.working_dir.as_deref() {
+            Some(dir) => repos.iter().find(|repo| repo.name == dir)?,
+            None if repos.len() == 1 => &repos[0],
+            None => return None,
+        };
+        Repo::script_settings(pool, repo.id)
+            .await
+            .inspect_err(|e| {
+                tracing::warn!("Failed to load script settings for repo {}: {}", repo.id, e)
+            })
+            .ok()
+    }
+
+    /// Apply the repo's failure policy to a failed setup, cleanup or archive
+    /// script. Retries re-run the same action (keeping its `next_action`), so
+    /// the chain resumes from where it failed.
+    async fn handle_script_failure(&self, ctx: &ExecutionContext) -> ScriptFailureOutcome {
+        if !matches!(ctx.execution_process.status, ExecutionProcessStatus::Failed) {
+            return ScriptFailureOutcome::Abort;
+        }
+        let Ok(action) = ctx.execution_process.executor_action() else {
+            return ScriptFailureOutcome::Abort;
+        };
+        let ExecutorActionType::ScriptRequest(request) = action.typ() else {
+            return ScriptFailureOutcome::Abort;
+        };
+        // Failing tests are the verify script's result, not a reason to skip
+        // verifying the remaining repos.
+        if request.context == ScriptContext::VerifyScript {
+            return ScriptFailureOutcome::Continue;
+        }
+        if !matches!(
+            request.context,
+            ScriptContext::SetupScript
+                | ScriptContext::CleanupScript
+                | ScriptContext::ArchiveScript
+        ) {
+            return ScriptFailureOutcome::Abort;
+        }
+        let Some(settings) = self.script_settings_for(ctx.workspace.id, request).await else {
+            return ScriptFailureOutcome::Abort;
+        };
+
+        // Consecutive failures of this exact script, newest first, including
+        // the one that just finished. Only the retry policy needs them.
+        let failures = if settings.on_failure == ScriptFailurePolicy::Retry {
+            match ExecutionProcess::find_by_session_id(&self.db().pool, ctx.session.id, false).await
+            {
+                Ok(processes) => processes
+                    .iter()
+                    .rev()
+                    .filter(|process| {
+                        matches!(
+                            process.executor_action().map(|a| a.typ()),
+                            Ok(ExecutorActionType::ScriptRequest(r)) if r == request
+                        )
+                    })
+                    .take_while(|process| matches!(process.status, ExecutionProcessStatus::Failed))
+                    .count(),
+                Err(e) => {
+                    tracing::warn!("Failed to count script attempts: {}", e);
+                    return ScriptFailureOutcome::Abort;
+                }
+            }
+        } else {
+            0
+        };
+
+        match script_failure_outcome(&settings, failures) {
+            ScriptFailureOutcome::Abort => {
+                if settings.on_failure == ScriptFailurePolicy::Retry {
+                    tracing::info!(
+                        "{:?} failed {} times in workspace {}; giving up",
+                        request.context,
+                        failures,
+                        ctx.workspace.id
+                    );
+                }
+                ScriptFailureOutcome::Abort
+            }
+            ScriptFailureOutcome::Continue => {
+                tracing::info!(
+                    "{:?} failed in workspace {}; continuing per repo policy",
+                    request.context,
+                    ctx.workspace.id
+                );
+                ScriptFailureOutcome::Continue
+            }
+            ScriptFailureOutcome::Retried => {
+                tracing::info!(
+                    "Retrying {:?} in workspace {} (retry {}/{})",
+                    request.context,
+                    ctx.workspace.id,
+                    failures,
+                    settings.max_retries
+                );
+                match self
+                    .start_execution(
+                        &ctx.workspace,
+                        &ctx.session,
+                        action,
+                        &ctx.execution_process.run_reason,
+                    )
+                    .await
+                {
+                    Ok(_) => ScriptFailureOutcome::Retried,
+                    Err(e) => {
+                        tracing::error!("Failed to retry script: {}", e);
+                        ScriptFailureOutcome::Abort
+                    }
+                }
+            }
+        }
+    }
+
     /// Finalize workspace execution by sending notifications
     async fn finalize_task(&self, ctx: &ExecutionContext) {
+        self.try_advance_pipeline(ctx).await;
+        if let Err(e) = ci_triage::report_outcome(&self.db().pool, self.git(), ctx).await {
+            tracing::warn!(
+                "Failed to report CI triage for workspace {}: {}",
+                ctx.workspace.id,
+                e
+            );
+        }
+        if let Err(e) = schedules::report_run_outcome(&self.db().pool, self.git(), ctx).await {
+            tracing::warn!(
+                "Failed to report scheduled run for workspace {}: {}",
+                ctx.workspace.id,
+                e
+            );
+        }
+
         // Skip notification if process was intentionally killed by user
         if matches!(ctx.execution_process.status, ExecutionProcessStatus::Killed) {
             return;
@@ -253,7 +462,7 @@ pub trait ContainerService {
             .as_deref()
             .unwrap_or(&ctx.workspace.branch);
         let title = format!("Workspace Complete: {}", workspace_name);
-        let message = match ctx.execution_process.status {
+        let mut message = match ctx.execution_process.status {
             ExecutionProcessStatus::Completed => format!(
                 "✅ '{}' completed successfully\nBranch: {:?}\nExecutor: {:?}",
                 workspace_name, ctx.workspace.branch, ctx.session.executor
@@ -270,11 +479,110 @@ pub trait ContainerService {
                 return;
             }
         };
+        self.send_completion_webhook(ctx).await;
+        if ctx.execution_process.run_reason == ExecutionProcessRunReason::VerifyScript
+            && let Ok(Some(tests)) =
+                ExecutionProcess::find_latest_turn_test_results(&self.db().pool, ctx.session.id)
+                    .await
+        {
+            message.push_str(&format!(
+                "\nTests: {} passed, {} failed, {} skipped",
+                tests.passed, tests.failed, tests.skipped
+            ));
+        }
         self.notification_service()
-            .notify(&title, &message, Some(ctx.workspace.id))
+            .notify(
+                NotificationEvent::WorkspaceComplete,
+                &title,
+                &message,
+                Some(ctx.workspace.id),
+            )
             .await;
     }
 
+    /// POST a summary of the finished run to the completion webhook, if one
+    /// is configured. Delivery happens in the background.
+    async fn send_completion_webhook(&self, ctx: &ExecutionContext) {
+        let Some(url) = self
+            .config()
+            .read()
+            .await
+            .notification_channels
+            .completion_webhook_url
+            .clone()
+            .filter(|url| !url.trim().is_empty())
+        else {
+            return;
+        };
+        let payload =
+            match completion_webhook::build_payload(&self.db().pool, self.git(), ctx).await {
+                Ok(payload) => payload,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to build completion webhook for workspace {}: {}",
+                        ctx.workspace.id,
+                        e
+                    );
+                    return;
+                }
+            };
+        tokio::spawn(async move {
+            if let Err(e) = completion_webhook::send(&url, &payload).await {
+                tracing::warn!("Failed to send completion webhook: {}", e);
+            }
+        });
+    }
+
+    /// Parse the test summary out of a finished verify script's output and
+    /// store the counts on its execution process.
+    async fn record_test_results(&self, ctx: &ExecutionContext) {
+        let execution_id = ctx.execution_process.id;
+        let Some(store) = self.msg_stores().read().await.get(&execution_id).cloned() else {
+            return;
+        };
+        let output: String = store
+            .get_history()
+            .into_iter()
+            .filter_map(|msg| match msg {
+                LogMsg::Stdout(content) | LogMsg::Stderr(content) => Some(content),
+                _ => None,
+            })
+            .collect();
+        let Some(results) = test_results::parse_test_output(&output) else {
+            tracing::debug!("No test summary found in verify script {}", execution_id);
+            return;
+        };
+        if let Err(e) =
+            ExecutionProcess::set_test_results(&self.db().pool, execution_id, &results).await
+        {
+            tracing::warn!(
+                "Failed to store test results for execution {}: {}",
+                execution_id,
+                e
+            );
+        }
+    }
+
+    /// The workspace's action chain has ended; if it runs a pipeline stage,
+    /// record the outcome so the orchestrator can start the next stage.
+    async fn try_advance_pipeline(&self, ctx: &ExecutionContext) {
+        let status = match ctx.execution_process.status {
+            ExecutionProcessStatus::Completed => PipelineStatus::Completed,
+            ExecutionProcessStatus::Failed => PipelineStatus::Failed,
+            ExecutionProcessStatus::Killed => PipelineStatus::Cancelled,
+            ExecutionProcessStatus::Running => return,
+        };
+        if let Err(e) =
+            pipelines::record_stage_outcome(&self.db().pool, ctx.workspace.id, status).await
+        {
+            tracing::warn!(
+                "Failed to record pipeline stage outcome for workspace {}: {}",
+                ctx.workspace.id,
+                e
+            );
+        }
+    }
+
     /// Cleanup executions marked as running in the db, call at startup
     async fn cleanup_orphan_executions(&self) -> Result<(), ContainerError> {
         let running_processes = ExecutionProcess::find_running(&self.db().pool).await?;
@@ -285,13 +593,18 @@ pub trait ContainerService {
                 process.session_id
             );
             // Update the execution process status first
-            if let Err(e) = ExecutionProcess::update_completion(
-                &self.db().pool,
-                process.id,
-                ExecutionProcessStatus::Failed,
-                None, // No exit code for orphaned processes
-            )
-            .await
+            if let Err(e) = self
+                .db()
+                .write(
+                    "execution_processes",
+                    ExecutionProcess::update_completion(
+                        &self.db().pool,
+                        process.id,
+                        ExecutionProcessStatus::Failed,
+                        None, // No exit code for orphaned processes
+                    ),
+                )
+                .await
             {
                 tracing::error!(
                     "Failed to update orphaned execution process {} status: {}",
@@ -339,7 +652,8 @@ pub trait ContainerService {
     async fn backfill_before_head_commits(&self) -> Result<(), ContainerError> {
         let pool = &self.db().pool;
         let rows = ExecutionProcess::list_missing_before_context(pool).await?;
-        for row in rows {
+        let total = rows.len() as u64;
+        for (done, row) in rows.into_iter().enumerate() {
             // Skip if no after commit at all (shouldn't happen due to WHERE)
             // Prefer previous process after-commit if present
             let mut before = row.prev_after_head_commit.clone();
@@ -378,6 +692,12 @@ pub trait ContainerService {
                     e
                 );
             }
+
+            self.startup_tasks().set_progress(
+                StartupTask::BeforeHeadCommits,
+                done as u64 + 1,
+                Some(total),
+            );
         }
 
         Ok(())
@@ -395,7 +715,8 @@ pub trait ContainerService {
 
         tracing::info!("Backfilling {} repo names", repos.len());
 
-        for repo in repos {
+        let total = repos.len() as u64;
+        for (done, repo) in repos.into_iter().enumerate() {
             let name = repo
                 .path
                 .file_name()
@@ -404,6 +725,8 @@ pub trait ContainerService {
                 .to_string();
 
             Repo::update_name(pool, repo.id, &name, &name).await?;
+            self.startup_tasks()
+                .set_progress(StartupTask::RepoNames, done as u64 + 1, Some(total));
         }
 
         Ok(())
@@ -446,6 +769,38 @@ pub trait ContainerService {
         Some(root_action)
     }
 
+    /// Actions that follow a coding agent turn: the cleanup scripts, then the
+    /// verify scripts of every repo that has one.
+    async fn post_agent_actions_for_repos(&self, repos: &[Repo]) -> Option<ExecutorAction> {
+        let mut verify_actions = Vec::new();
+        for repo in repos {
+            match Repo::verify_script(&self.db().pool, repo.id).await {
+                Ok(Some(script)) => verify_actions.push(ExecutorAction::new(
+                    ExecutorActionType::ScriptRequest(ScriptRequest {
+                        script,
+                        language: ScriptRequestLanguage::Bash,
+                        context: ScriptContext::VerifyScript,
+                        working_dir: Some(repo.name.clone()),
+                    }),
+                    None,
+                )),
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::warn!("Failed to load verify script for repo {}: {}", repo.id, e)
+                }
+            }
+        }
+
+        verify_actions
+            .into_iter()
+            .fold(self.cleanup_actions_for_repos(repos), |chain, action| {
+                Some(match chain {
+                    Some(chain) => chain.append_action(action),
+                    None => action,
+                })
+            })
+    }
+
     fn archive_actions_for_repos(&self, repos: &[Repo]) -> Option<ExecutorAction> {
         let repos_with_archive: Vec<_> = repos
             .iter()
@@ -645,6 +1000,9 @@ pub trait ContainerService {
         force_when_dirty: bool,
     ) -> Result<(), ContainerError> {
         let pool = &self.db().pool;
+        self.startup_tasks()
+            .wait_for(StartupTask::BeforeHeadCommits)
+            .await;
 
         let process = ExecutionProcess::find_by_id(pool, target_process_id)
             .await?
@@ -675,6 +1033,7 @@ pub trait ContainerService {
             .map(|is_clean| !is_clean)
             .unwrap_or(false);
 
+        let mut resets = Vec::new();
         for repo in &repos {
             let repo_state = repo_states.iter().find(|s| s.repo_id == repo.id);
             let target_oid = match repo_state.and_then(|s| s.before_head_commit.clone()) {
@@ -692,7 +1051,7 @@ pub trait ContainerService {
 
             let worktree_path = workspace_dir.join(&repo.name);
             if let Some(oid) = target_oid {
-                self.git().reconcile_worktree_to_commit(
+                let outcome = self.git().reconcile_worktree_to_commit(
                     &worktree_path,
                     &oid,
                     git::WorktreeResetOptions::new(
@@ -702,8 +1061,18 @@ pub trait ContainerService {
                         perform_git_reset,
                     ),
                 );
+                resets.extend(workspace_undo::applied_reset(repo, outcome));
             }
         }
+        if let Err(e) =
+            workspace_undo::record_git_reset(pool, self.git(), workspace.id, resets).await
+        {
+            tracing::warn!(
+                "Failed to record undo of git reset in workspace {}: {}",
+                workspace.id,
+                e
+            );
+        }
 
         self.try_stop(&workspace, false).await;
         ExecutionProcess::drop_at_and_after(pool, session_id, target_process_id).await?;
@@ -806,7 +1175,16 @@ pub trait ContainerService {
         id: &Uuid,
     ) -> Option<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>> {
         if let Some(store) = self.get_msg_store_by_id(id).await {
-            // First try in-memory store
+            // First try in-memory store. Persisted logs are already redacted;
+            // live output is redacted here so it matches what gets stored.
+            let redactor = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
+                Ok(Some(process)) => {
+                    let redaction_config = self.config().read().await.log_redaction.clone();
+                    LogRedactor::for_session(&self.db().pool, &redaction_config, process.session_id)
+                        .await
+                }
+                _ => None,
+            };
             return Some(
                 store
                     .history_plus_stream()
@@ -816,6 +1194,10 @@ pub trait ContainerService {
                             Ok(LogMsg::Stdout(..) | LogMsg::Stderr(..) | LogMsg::Finished)
                         ))
                     })
+                    .map(move |msg| match &redactor {
+                        Some(redactor) => msg.map(|msg| redactor.redact_msg(msg)),
+                        None => msg,
+                    })
                     .boxed(),
             );
         } else {
@@ -850,145 +1232,156 @@ pub trait ContainerService {
                     .boxed(),
             )
         } else {
-            let raw_messages =
-                execution_process::load_raw_log_messages(&self.db().pool, *id).await?;
-
-            // Create temporary store and populate
-            // Include JsonPatch messages (already normalized) and Stdout/Stderr (need normalization)
+            // Populate a temporary store with the normalized patches, followed
+            // by Ready. Processes with cached patches replay them; older ones
+            // are normalized again from the raw log.
             let temp_store = Arc::new(MsgStore::new());
-            for msg in raw_messages {
-                if matches!(
-                    msg,
-                    LogMsg::Stdout(_) | LogMsg::Stderr(_) | LogMsg::JsonPatch(_)
-                ) {
-                    temp_store.push(msg);
-                }
-            }
-            temp_store.push_finished();
-
-            let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
-                Ok(Some(process)) => process,
-                Ok(None) => {
-                    tracing::error!("No execution process found for ID: {}", id);
-                    return None;
+            if let Some(patches) =
+                execution_process::load_normalized_log_patches(&self.db().pool, *id).await
+            {
+                for patch in patches {
+                    temp_store.push_patch(patch);
                 }
-                Err(e) => {
-                    tracing::error!("Failed to fetch execution process {}: {}", id, e);
-                    return None;
+                temp_store.push(LogMsg::Ready);
+            } else {
+                let raw_messages =
+                    execution_process::load_raw_log_messages(&self.db().pool, *id).await?;
+
+                // Include JsonPatch messages (already normalized) and Stdout/Stderr (need normalization)
+                for msg in raw_messages {
+                    if matches!(
+                        msg,
+                        LogMsg::Stdout(_) | LogMsg::Stderr(_) | LogMsg::JsonPatch(_)
+                    ) {
+                        temp_store.push(msg);
+                    }
                 }
-            };
+                temp_store.push_finished();
 
-            // Get the workspace to determine correct directory
-            let (workspace, _session) =
-                match process.parent_workspace_and_session(&self.db().pool).await {
-                    Ok(Some((workspace, session))) => (workspace, session),
+                let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
+                    Ok(Some(process)) => process,
                     Ok(None) => {
-                        tracing::error!(
-                            "No workspace/session found for session ID: {}",
-                            process.session_id
-                        );
+                        tracing::error!("No execution process found for ID: {}", id);
                         return None;
                     }
                     Err(e) => {
-                        tracing::error!(
-                            "Failed to fetch workspace for session {}: {}",
-                            process.session_id,
-                            e
-                        );
+                        tracing::error!("Failed to fetch execution process {}: {}", id, e);
                         return None;
                     }
                 };
 
-            if let Err(err) = self.ensure_container_exists(&workspace).await {
-                tracing::warn!(
-                    "Failed to recreate worktree before log normalization for workspace {}: {}",
-                    workspace.id,
-                    err
-                );
-            }
+                // Get the workspace to determine correct directory
+                let (workspace, _session) =
+                    match process.parent_workspace_and_session(&self.db().pool).await {
+                        Ok(Some((workspace, session))) => (workspace, session),
+                        Ok(None) => {
+                            tracing::error!(
+                                "No workspace/session found for session ID: {}",
+                                process.session_id
+                            );
+                            return None;
+                        }
+                        Err(e) => {
+                            tracing::error!(
+                                "Failed to fetch workspace for session {}: {}",
+                                process.session_id,
+                                e
+                            );
+                            return None;
+                        }
+                    };
 
-            let current_dir = self.workspace_to_current_dir(&workspace);
+                if let Err(err) = self.ensure_container_exists(&workspace).await {
+                    tracing::warn!(
+                        "Failed to recreate worktree before log normalization for workspace {}: {}",
+                        workspace.id,
+                        err
+                    );
+                }
 
-            let executor_action = if let Ok(executor_action) = process.executor_action() {
-                executor_action
-            } else {
-                tracing::error!(
-                    "Failed to parse executor action: {:?}",
-                    process.executor_action()
-                );
-                return None;
-            };
+                let current_dir = self.workspace_to_current_dir(&workspace);
 
-            // Spawn normalizer on populated store and collect JoinHandles
-            let handles = match executor_action.typ() {
-                ExecutorActionType::CodingAgentInitialRequest(request) => {
-                    #[cfg(feature = "qa-mode")]
-                    {
-                        let executor = QaMockExecutor;
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        )
+                let executor_action = if let Ok(executor_action) = process.executor_action() {
+                    executor_action
+                } else {
+                    tracing::error!(
+                        "Failed to parse executor action: {:?}",
+                        process.executor_action()
+                    );
+                    return None;
+                };
+
+                // Spawn normalizer on populated store and collect JoinHandles
+                let handles = match executor_action.typ() {
+                    ExecutorActionType::CodingAgentInitialRequest(request) => {
+                        #[cfg(feature = "qa-mode")]
+                        {
+                            let executor = QaMockExecutor;
+                            executor.normalize_logs(
+                                temp_store.clone(),
+                                &request.effective_dir(&current_dir),
+                            )
+                        }
+                        #[cfg(not(feature = "qa-mode"))]
+                        {
+                            let executor = ExecutorConfigs::get_cached()
+                                .get_coding_agent_or_default(&request.executor_config.profile_id());
+                            executor.normalize_logs(
+                                temp_store.clone(),
+                                &request.effective_dir(&current_dir),
+                            )
+                        }
                     }
-                    #[cfg(not(feature = "qa-mode"))]
-                    {
-                        let executor = ExecutorConfigs::get_cached()
-                            .get_coding_agent_or_default(&request.executor_config.profile_id());
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        )
+                    ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                        #[cfg(feature = "qa-mode")]
+                        {
+                            let executor = QaMockExecutor;
+                            executor.normalize_logs(
+                                temp_store.clone(),
+                                &request.effective_dir(&current_dir),
+                            )
+                        }
+                        #[cfg(not(feature = "qa-mode"))]
+                        {
+                            let executor = ExecutorConfigs::get_cached()
+                                .get_coding_agent_or_default(&request.executor_config.profile_id());
+                            executor.normalize_logs(
+                                temp_store.clone(),
+                                &request.effective_dir(&current_dir),
+                            )
+                        }
                     }
-                }
-                ExecutorActionType::CodingAgentFollowUpRequest(request) => {
                     #[cfg(feature = "qa-mode")]
-                    {
+                    ExecutorActionType::ReviewRequest(_request) => {
                         let executor = QaMockExecutor;
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        )
+                        executor.normalize_logs(temp_store.clone(), &current_dir)
                     }
                     #[cfg(not(feature = "qa-mode"))]
-                    {
+                    ExecutorActionType::ReviewRequest(request) => {
                         let executor = ExecutorConfigs::get_cached()
                             .get_coding_agent_or_default(&request.executor_config.profile_id());
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        )
+                        executor.normalize_logs(temp_store.clone(), &current_dir)
                     }
-                }
-                #[cfg(feature = "qa-mode")]
-                ExecutorActionType::ReviewRequest(_request) => {
-                    let executor = QaMockExecutor;
-                    executor.normalize_logs(temp_store.clone(), &current_dir)
-                }
-                #[cfg(not(feature = "qa-mode"))]
-                ExecutorActionType::ReviewRequest(request) => {
-                    let executor = ExecutorConfigs::get_cached()
-                        .get_coding_agent_or_default(&request.executor_config.profile_id());
-                    executor.normalize_logs(temp_store.clone(), &current_dir)
-                }
-                _ => {
-                    tracing::debug!(
-                        "Executor action doesn't support log normalization: {:?}",
-                        process.executor_action()
-                    );
-                    return None;
-                }
-            };
-
-            // Await all normalizer tasks, then push Ready so the dedup
-            // stream knows when to flush its buffer and terminate.
-            {
-                let store = temp_store.clone();
-                tokio::spawn(async move {
-                    for handle in handles {
-                        let _ = handle.await;
+                    _ => {
+                        tracing::debug!(
+                            "Executor action doesn't support log normalization: {:?}",
+                            process.executor_action()
+                        );
+                        return None;
                     }
-                    store.push(LogMsg::Ready);
-                });
+                };
+
+                // Await all normalizer tasks, then push Ready so the dedup
+                // stream knows when to flush its buffer and terminate.
+                {
+                    let store = temp_store.clone();
+                    tokio::spawn(async move {
+                        for handle in handles {
+                            let _ = handle.await;
+                        }
+                        store.push(LogMsg::Ready);
+                    });
+                }
             }
 
             // Stream normalized patches, deduplicating consecutive patches
@@ -1070,7 +1463,21 @@ pub trait ContainerService {
         // returning an existing session or execution row.
         self.ensure_container_exists(workspace).await?;
 
-        let repos = WorkspaceRepo::find_repos_for_workspace(&self.db().pool, workspace.id).await?;
+        let mut repos =
+            WorkspaceRepo::find_repos_for_workspace(&self.db().pool, workspace.id).await?;
+        if let Err(e) = config_overrides::apply_workspace_script_overrides(
+            &self.db().pool,
+            workspace.id,
+            &mut repos,
+        )
+        .await
+        {
+            tracing::warn!(
+                "Failed to load config overrides for workspace {}: {}",
+                workspace.id,
+                e
+            );
+        }
 
         let workspace = Workspace::find_by_id(&self.db().pool, workspace.id)
             .await?
@@ -1127,11 +1534,16 @@ pub trait ContainerService {
             return Ok(existing);
         }
 
-        let repos_with_setup: Vec<_> = repos.iter().filter(|r| r.setup_script.is_some()).collect();
+        // Worktrees adopted from the warm pool have already been set up
+        let repos_with_setup: Vec<_> = if workspace.setup_completed_at.is_some() {
+            Vec::new()
+        } else {
+            repos.iter().filter(|r| r.setup_script.is_some()).collect()
+        };
 
         let all_parallel = repos_with_setup.iter().all(|r| r.parallel_setup_script);
 
-        let cleanup_action = self.cleanup_actions_for_repos(&repos);
+        let post_agent_action = self.post_agent_actions_for_repos(&repos).await;
 
         let working_dir = session
             .agent_working_dir
@@ -1139,13 +1551,38 @@ pub trait ContainerService {
             .filter(|dir| !dir.is_empty())
             .cloned();
 
+        let agent_dir = match &working_dir {
+            Some(dir) => self.workspace_to_current_dir(&workspace).join(dir),
+            None => self.workspace_to_current_dir(&workspace),
+        };
+        let prompt = match workspace_context::materialize_context(
+            &self.db().pool,
+            self.file_service(),
+            workspace.id,
+            &agent_dir,
+        )
+        .await
+        {
+            Ok(context) => workspace_context::render_context_prompt(&prompt, &context),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to materialize context for workspace {}: {}",
+                    workspace.id,
+                    e
+                );
+                prompt
+            }
+        };
+        let prompt =
+            repo_knowledge::with_repo_knowledge(&self.db().pool, workspace.id, prompt).await;
+
         let coding_action = ExecutorAction::new(
             ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
                 prompt,
                 executor_config: executor_config.clone(),
                 working_dir,
             }),
-            cleanup_action.map(Box::new),
+            post_agent_action.map(Box::new),
         );
 
         let execution_process = if all_parallel {
@@ -1245,6 +1682,9 @@ pub trait ContainerService {
         run_reason: &ExecutionProcessRunReason,
         idempotency_key: Option<String>,
     ) -> Result<ExecutionClaim, ContainerError> {
+        if let Some(reason) = self.maintenance().reason() {
+            return Err(ContainerError::Maintenance(reason));
+        }
         let idempotency_key = normalize_idempotency_key(idempotency_key);
         if let Some(key) = idempotency_key.as_deref()
             && let Some(existing) = ExecutionProcess::find_by_session_and_idempotency_key(
@@ -1291,13 +1731,18 @@ pub trait ContainerService {
             idempotency_key: idempotency_key.clone(),
         };
 
-        let execution_process = match ExecutionProcess::create(
-            &self.db().pool,
-            &create_execution_process,
-            Uuid::new_v4(),
-            &repo_states,
-        )
-        .await
+        let execution_process = match self
+            .db()
+            .write(
+                "execution_processes",
+                ExecutionProcess::create(
+                    &self.db().pool,
+                    &create_execution_process,
+                    Uuid::new_v4(),
+                    &repo_states,
+                ),
+            )
+            .await
         {
             Ok(execution_process) => execution_process,
             Err(err) => {
@@ -1358,12 +1803,17 @@ pub trait ContainerService {
 
             let coding_agent_turn_id = Uuid::new_v4();
 
-            if let Err(e) = CodingAgentTurn::create(
-                &self.db().pool,
-                &create_coding_agent_turn,
-                coding_agent_turn_id,
-            )
-            .await
+            if let Err(e) = self
+                .db()
+                .write(
+                    "coding_agent_turns",
+                    CodingAgentTurn::create(
+                        &self.db().pool,
+                        &create_coding_agent_turn,
+                        coding_agent_turn_id,
+                    ),
+                )
+                .await
             {
                 self.msg_stores()
                     .write()
@@ -1382,13 +1832,18 @@ pub trait ContainerService {
                 .await
                 .remove(&execution_process.id);
             // Mark process as failed
-            if let Err(update_error) = ExecutionProcess::update_completion(
-                &self.db().pool,
-                execution_process.id,
-                ExecutionProcessStatus::Failed,
-                None,
-            )
-            .await
+            if let Err(update_error) = self
+                .db()
+                .write(
+                    "execution_processes",
+                    ExecutionProcess::update_completion(
+                        &self.db().pool,
+                        execution_process.id,
+                        ExecutionProcessStatus::Failed,
+                        None,
+                    ),
+                )
+                .await
             {
                 tracing::error!(
                     "Failed to mark execution process {} as failed after start error: {}",
@@ -1443,6 +1898,19 @@ pub trait ContainerService {
             return Err(start_error);
         }
 
+        let (redaction_config, log_write_policy) = {
+            let config = self.config().read().await;
+            (
+                config.log_redaction.clone(),
+                execution_process::log_write_policy(
+                    &config.log_writes,
+                    &execution_process.run_reason,
+                ),
+            )
+        };
+        let redactor =
+            LogRedactor::for_session(&self.db().pool, &redaction_config, session.id).await;
+
         // Start processing normalised logs for executor requests and follow ups
         let workspace_root = self.workspace_to_current_dir(workspace);
         #[cfg_attr(feature = "qa-mode", allow(unused_variables))]
@@ -1475,22 +1943,27 @@ pub trait ContainerService {
                 }
             };
             #[cfg(feature = "qa-mode")]
-            {
-                let executor = QaMockExecutor;
-                let _ = executor.normalize_logs(msg_store, &working_dir);
-            }
+            let normalizers = QaMockExecutor.normalize_logs(msg_store.clone(), &working_dir);
             #[cfg(not(feature = "qa-mode"))]
+            let normalizers = if let Some(executor) =
+                ExecutorConfigs::get_cached().get_coding_agent(&executor_profile_id)
             {
-                if let Some(executor) =
-                    ExecutorConfigs::get_cached().get_coding_agent(&executor_profile_id)
-                {
-                    let _ = executor.normalize_logs(msg_store, &working_dir);
-                } else {
-                    tracing::error!(
-                        "Failed to resolve profile '{:?}' for normalization",
-                        executor_profile_id
-                    );
-                }
+                executor.normalize_logs(msg_store.clone(), &working_dir)
+            } else {
+                tracing::error!(
+                    "Failed to resolve profile '{:?}' for normalization",
+                    executor_profile_id
+                );
+                Vec::new()
+            };
+            if !normalizers.is_empty() {
+                execution_process::spawn_persist_normalized_logs(
+                    msg_store,
+                    normalizers,
+                    session.id,
+                    execution_process.id,
+                    redactor.clone(),
+                );
             }
         }
 
@@ -1499,7 +1972,18 @@ pub trait ContainerService {
             self.db().clone(),
             execution_process.id,
             session.id,
+            redactor,
+            log_write_policy,
         );
+        if execution_process.run_reason == ExecutionProcessRunReason::DevServer
+            && let Some(msg_store) = self.get_msg_store_by_id(&execution_process.id).await
+        {
+            dev_server::spawn_dev_server_url_detector(
+                msg_store,
+                self.db().pool.clone(),
+                execution_process.id,
+            );
+        }
         Ok(execution_process)
     }
 
@@ -1514,6 +1998,11 @@ pub trait ContainerService {
 
         // Determine the run reason of the next action
         let next_run_reason = match (action.typ(), next_action.typ()) {
+            (_, ExecutorActionType::ScriptRequest(request))
+                if request.context == ScriptContext::VerifyScript =>
+            {
+                ExecutionProcessRunReason::VerifyScript
+            }
             (ExecutorActionType::ScriptRequest(_), ExecutorActionType::ScriptRequest(_)) => {
                 ExecutionProcessRunReason::SetupScript
             }
@@ -1538,3 +2027,58 @@ pub trait ContainerService {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(on_failure: ScriptFailurePolicy, max_retries: u32) -> RepoScriptSettings {
+        RepoScriptSettings {
+            setup_timeout_secs: None,
+            cleanup_timeout_secs: None,
+            archive_timeout_secs: None,
+            on_failure,
+            max_retries,
+        }
+    }
+
+    #[test]
+    fn abort_and_continue_ignore_the_failure_count() {
+        for failures in [1, 5] {
+            assert_eq!(
+                script_failure_outcome(&settings(ScriptFailurePolicy::Abort, 3), failures),
+                ScriptFailureOutcome::Abort
+            );
+            assert_eq!(
+                script_failure_outcome(&settings(ScriptFailurePolicy::Continue, 3), failures),
+                ScriptFailureOutcome::Continue
+            );
+        }
+    }
+
+    #[test]
+    fn retry_stops_once_the_retries_are_used_up() {
+        let settings = settings(ScriptFailurePolicy::Retry, 2);
+        // The first failure is the original run; two retries follow it.
+        assert_eq!(
+            script_failure_outcome(&settings, 1),
+            ScriptFailureOutcome::Retried
+        );
+        assert_eq!(
+            script_failure_outcome(&settings, 2),
+            ScriptFailureOutcome::Retried
+        );
+        assert_eq!(
+            script_failure_outcome(&settings, 3),
+            ScriptFailureOutcome::Abort
+        );
+    }
+
+    #[test]
+    fn retry_without_retries_aborts_on_the_first_failure() {
+        assert_eq!(
+            script_failure_outcome(&settings(ScriptFailurePolicy::Retry, 0), 1),
+            ScriptFailureOutcome::Abort
+        );
+    }
+}
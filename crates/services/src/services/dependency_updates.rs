@@ -0,0 +1,132 @@
+//! Dependency update bot.
+//!
+//! A built-in schedule that asks an agent to upgrade a repo's dependencies on
+//! a cadence, Renovate style. It is an ordinary schedule with `open_pr` set:
+//! the repo's verify script runs after the agent, and a pull request is only
+//! opened when it passes.
+
+use db::models::schedule::{CreateSchedule, Schedule};
+use executors::profile::ExecutorConfig;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::services::schedules::{self, ScheduleError};
+
+const DEFAULT_NAME: &str = "Update dependencies";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum DependencyEcosystem {
+    Cargo,
+    Npm,
+}
+
+impl DependencyEcosystem {
+    fn instructions(self) -> &'static str {
+        match self {
+            Self::Cargo => {
+                "Rust: run `cargo update` for semver-compatible updates, then raise \
+                 requirements in Cargo.toml to the latest releases with `cargo upgrade` \
+                 (from cargo-edit) if it is installed, or by hand otherwise."
+            }
+            Self::Npm => {
+                "JavaScript: update packages with the repo's package manager (npm, pnpm or \
+                 yarn, going by the lockfile), to the latest releases allowed by package.json \
+                 and then to newer majors where the changelog shows no breaking changes that \
+                 affect this repo."
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateDependencyUpdateSchedule {
+    #[serde(default)]
+    #[ts(optional)]
+    pub name: Option<String>,
+    pub repo_id: Uuid,
+    pub cron_expression: String,
+    pub target_branch: String,
+    pub ecosystems: Vec<DependencyEcosystem>,
+    /// Added to the built-in prompt, e.g. packages to leave alone.
+    #[serde(default)]
+    #[ts(optional)]
+    pub instructions: Option<String>,
+    pub executor_config: ExecutorConfig,
+}
+
+pub fn build_prompt(ecosystems: &[DependencyEcosystem], instructions: Option<&str>) -> String {
+    let mut prompt =
+        String::from("Update this repository's dependencies to their latest versions.\n");
+    for ecosystem in ecosystems {
+        prompt.push_str(&format!("\n- {}", ecosystem.instructions()));
+    }
+    prompt.push_str(
+        "\n\nFix any code the updates break, and leave a dependency at its current version \
+         if upgrading needs more than small changes. Finish with a list of what was updated, \
+         from and to which versions, and anything held back and why; it becomes the pull \
+         request description.",
+    );
+    if let Some(instructions) = instructions.map(str::trim).filter(|text| !text.is_empty()) {
+        prompt.push_str("\n\n");
+        prompt.push_str(instructions);
+    }
+    prompt
+}
+
+/// Create a dependency update schedule for a repo. The repo needs a verify
+/// script, since pull requests are only opened when it passes.
+pub async fn create_schedule(
+    pool: &SqlitePool,
+    data: &CreateDependencyUpdateSchedule,
+) -> Result<Schedule, ScheduleError> {
+    if data.ecosystems.is_empty() {
+        return Err(ScheduleError::NoEcosystems);
+    }
+    let mut ecosystems = Vec::new();
+    for ecosystem in &data.ecosystems {
+        if !ecosystems.contains(ecosystem) {
+            ecosystems.push(*ecosystem);
+        }
+    }
+    schedules::create_schedule(
+        pool,
+        &CreateSchedule {
+            name: data
+                .name
+                .clone()
+                .filter(|name| !name.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_NAME.to_string()),
+            cron_expression: data.cron_expression.clone(),
+            repo_id: data.repo_id,
+            target_branch: data.target_branch.clone(),
+            prompt: build_prompt(&ecosystems, data.instructions.as_deref()),
+            executor_config: data.executor_config.clone(),
+            open_pr: true,
+        },
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prompt_covers_each_ecosystem_and_extra_instructions() {
+        let prompt = build_prompt(
+            &[DependencyEcosystem::Cargo, DependencyEcosystem::Npm],
+            Some("  Keep react on 18.  "),
+        );
+        assert!(prompt.contains("`cargo upgrade`"));
+        assert!(prompt.contains("package manager"));
+        assert!(prompt.ends_with("\n\nKeep react on 18."));
+
+        let prompt = build_prompt(&[DependencyEcosystem::Npm], Some(" "));
+        assert!(!prompt.contains("cargo"));
+        assert!(prompt.ends_with("pull request description."));
+    }
+}
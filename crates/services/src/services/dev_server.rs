@@ -0,0 +1,103 @@
+//! Detection of the local URL a dev server listens on.
+//!
+//! Dev servers announce where they are listening in their output (e.g.
+//! `Local: http://localhost:5173/`). The first loopback URL printed by a dev
+//! server process is stored on its execution process so the workspace preview
+//! can be reverse-proxied to it.
+
+use std::sync::Arc;
+
+use db::models::execution_process::ExecutionProcess;
+use futures::StreamExt;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use sqlx::SqlitePool;
+use tokio::task::JoinHandle;
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+static LOCAL_URL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\bhttps?://(?:localhost|127\.0\.0\.1|0\.0\.0\.0|\[::1?\]):(?P<port>\d{1,5})\b[^\s]*",
+    )
+    .expect("local url regex")
+});
+
+/// Find the first loopback URL with an explicit port in a line of output.
+pub fn detect_local_url(line: &str) -> Option<(String, u16)> {
+    let line = strip_ansi_escapes::strip_str(line);
+    let caps = LOCAL_URL.captures(&line)?;
+    let port = caps["port"].parse::<u16>().ok().filter(|port| *port != 0)?;
+    let url = caps[0].trim_end_matches(['.', ',', ')', '"', '\'']);
+    Some((url.to_string(), port))
+}
+
+/// Watch a dev server's output and record the first local URL it prints.
+pub fn spawn_dev_server_url_detector(
+    msg_store: Arc<MsgStore>,
+    pool: SqlitePool,
+    execution_id: Uuid,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut stream = msg_store.history_plus_stream();
+        // Output arrives in arbitrary chunks, so scan complete lines only.
+        let mut pending = String::new();
+        while let Some(Ok(msg)) = stream.next().await {
+            let chunk = match msg {
+                LogMsg::Stdout(content) | LogMsg::Stderr(content) => content,
+                LogMsg::Finished => break,
+                _ => continue,
+            };
+            pending.push_str(&chunk);
+            while let Some(newline) = pending.find('\n') {
+                let line: String = pending.drain(..=newline).collect();
+                if let Some((url, port)) = detect_local_url(&line) {
+                    if let Err(e) =
+                        ExecutionProcess::set_dev_server_url(&pool, execution_id, &url, port).await
+                    {
+                        tracing::warn!(
+                            "Failed to store dev server URL for execution {}: {}",
+                            execution_id,
+                            e
+                        );
+                    }
+                    return;
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_common_dev_server_banners() {
+        assert_eq!(
+            detect_local_url(
+                "  \u{1b}[32m➜\u{1b}[39m  Local:   \u{1b}[36mhttp://localhost:\u{1b}[1m5173\u{1b}[22m/\u{1b}[39m"
+            ),
+            Some(("http://localhost:5173/".to_string(), 5173))
+        );
+        assert_eq!(
+            detect_local_url("- Local:        http://127.0.0.1:3000"),
+            Some(("http://127.0.0.1:3000".to_string(), 3000))
+        );
+        assert_eq!(
+            detect_local_url("Listening on http://0.0.0.0:8080."),
+            Some(("http://0.0.0.0:8080".to_string(), 8080))
+        );
+        assert_eq!(
+            detect_local_url("Serving at http://[::1]:4000/app"),
+            Some(("http://[::1]:4000/app".to_string(), 4000))
+        );
+    }
+
+    #[test]
+    fn ignores_remote_urls_and_missing_ports() {
+        assert_eq!(detect_local_url("See https://vitejs.dev:443/guide"), None);
+        assert_eq!(detect_local_url("Open http://localhost/"), None);
+        assert_eq!(detect_local_url("http://localhost:99999"), None);
+    }
+}
@@ -21,18 +21,22 @@ use notify::{RecommendedWatcher, RecursiveMode};
 use notify_debouncer_full::{
     DebounceEventResult, DebouncedEvent, Debouncer, RecommendedCache, new_debouncer,
 };
+use serde::Serialize;
 use sqlx::SqlitePool;
 use thiserror::Error;
 use tokio::{sync::mpsc, task::JoinHandle};
 use tokio_stream::wrappers::{IntervalStream, ReceiverStream};
-use utils::{diff::Diff, log_msg::LogMsg};
+use utils::{
+    diff::{Diff, DiffOmittedReason},
+    log_msg::LogMsg,
+};
 use uuid::Uuid;
 
 use crate::services::filesystem_watcher::{self, FilesystemWatcherError};
 
 type SentFileStats = Arc<std::sync::RwLock<HashMap<String, (SystemTime, u64)>>>;
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct DiffStats {
     pub files_changed: usize,
     pub lines_added: usize,
@@ -164,6 +168,8 @@ pub struct DiffStreamArgs {
     pub base_commit: Commit,
     pub stats_only: bool,
     pub path_prefix: Option<String>,
+    /// Files larger than this are sent without contents.
+    pub max_file_bytes: usize,
 }
 
 struct DiffStreamManager {
@@ -339,13 +345,11 @@ impl DiffStreamManager {
                     .insert(raw_path.clone(), (mtime, meta.len()));
             }
 
-            if let Some(old) = diff.old_path {
-                diff.old_path = Some(prefix_path(old, self.args.path_prefix.as_deref()));
-            }
-            if let Some(new) = diff.new_path {
-                diff.new_path = Some(prefix_path(new, self.args.path_prefix.as_deref()));
-            }
-            diff.repo_id = Some(self.args.repo_id);
+            decorate_diff(
+                &mut diff,
+                self.args.path_prefix.as_deref(),
+                self.args.repo_id,
+            );
 
             entries.insert(raw_path, diff);
         }
@@ -362,10 +366,11 @@ impl DiffStreamManager {
         let worktree = self.args.worktree_path.clone();
         let base = self.current_base_commit.clone();
         let stats_only = self.args.stats_only;
+        let max_file_bytes = self.args.max_file_bytes;
         let cumulative = self.cumulative.clone();
 
         tokio::task::spawn_blocking(move || {
-            let diffs = git.get_diffs(&worktree, &base, None)?;
+            let diffs = git.get_diffs_with_max_file_size(&worktree, &base, None, max_file_bytes)?;
             let mut processed_diffs = Vec::with_capacity(diffs.len());
             for mut diff in diffs {
                 apply_stream_omit_policy(&mut diff, &cumulative, stats_only);
@@ -395,6 +400,7 @@ impl DiffStreamManager {
         let known_paths = self.known_paths.clone();
         let sent_file_stats = self.sent_file_stats.clone();
         let stats_only = self.args.stats_only;
+        let max_file_bytes = self.args.max_file_bytes;
         let prefix = self.args.path_prefix.clone();
         let repo_id = self.args.repo_id;
 
@@ -408,6 +414,7 @@ impl DiffStreamManager {
                 &known_paths,
                 &sent_file_stats,
                 stats_only,
+                max_file_bytes,
                 prefix.as_deref(),
                 repo_id,
             )
@@ -604,6 +611,7 @@ impl DiffStreamManager {
         let known_paths = self.known_paths.clone();
         let sent_file_stats = self.sent_file_stats.clone();
         let stats_only = self.args.stats_only;
+        let max_file_bytes = self.args.max_file_bytes;
         let prefix = self.args.path_prefix.clone();
         let repo_id = self.args.repo_id;
         let paths = paths.to_vec();
@@ -618,6 +626,7 @@ impl DiffStreamManager {
                 &known_paths,
                 &sent_file_stats,
                 stats_only,
+                max_file_bytes,
                 prefix.as_deref(),
                 repo_id,
             )
@@ -664,9 +673,52 @@ fn prefix_path(path: String, prefix: Option<&str>) -> String {
     }
 }
 
+/// Prefix the diff's paths and tag it with its repo, as sent in the stream.
+fn decorate_diff(diff: &mut Diff, path_prefix: Option<&str>, repo_id: Uuid) {
+    if let Some(old) = diff.old_path.take() {
+        diff.old_path = Some(prefix_path(old, path_prefix));
+    }
+    if let Some(new) = diff.new_path.take() {
+        diff.new_path = Some(prefix_path(new, path_prefix));
+    }
+    diff.repo_id = Some(repo_id);
+}
+
+/// Load one file's diff with its contents, for a file the stream sent without
+/// them. `path` is relative to the worktree. Returns `None` when the file no
+/// longer differs from `base_commit`.
+pub fn load_file_diff(
+    git: &GitService,
+    worktree_path: &Path,
+    base_commit: &Commit,
+    path: &str,
+    max_file_bytes: usize,
+    path_prefix: Option<&str>,
+    repo_id: Uuid,
+) -> Result<Option<Diff>, GitServiceError> {
+    let diffs = git.get_diffs_with_max_file_size(
+        worktree_path,
+        base_commit,
+        Some(&[path]),
+        max_file_bytes,
+    )?;
+    let Some(mut diff) = diffs
+        .into_iter()
+        .find(|diff| GitService::diff_path(diff) == path)
+    else {
+        return Ok(None);
+    };
+    decorate_diff(&mut diff, path_prefix, repo_id);
+    Ok(Some(diff))
+}
+
 pub fn apply_stream_omit_policy(diff: &mut Diff, sent_bytes: &Arc<AtomicUsize>, stats_only: bool) {
+    // Keep the reason contents can't be loaded at all
+    if diff.content_omitted {
+        return;
+    }
     if stats_only {
-        omit_diff_contents(diff);
+        omit_diff_contents(diff, DiffOmittedReason::StatsOnly);
         return;
     }
 
@@ -684,13 +736,13 @@ pub fn apply_stream_omit_policy(diff: &mut Diff, sent_bytes: &Arc<AtomicUsize>,
 
     let current = sent_bytes.load(Ordering::Relaxed);
     if current.saturating_add(size) > MAX_CUMULATIVE_DIFF_BYTES {
-        omit_diff_contents(diff);
+        omit_diff_contents(diff, DiffOmittedReason::StreamLimit);
     } else {
         let _ = sent_bytes.fetch_add(size, Ordering::Relaxed);
     }
 }
 
-fn omit_diff_contents(diff: &mut Diff) {
+fn omit_diff_contents(diff: &mut Diff, reason: DiffOmittedReason) {
     if diff.additions.is_none()
         && diff.deletions.is_none()
         && (diff.old_content.is_some() || diff.new_content.is_some())
@@ -705,6 +757,7 @@ fn omit_diff_contents(diff: &mut Diff) {
     diff.old_content = None;
     diff.new_content = None;
     diff.content_omitted = true;
+    diff.omitted_reason = Some(reason);
 }
 
 fn extract_changed_paths(
@@ -735,11 +788,17 @@ fn process_file_changes(
     known_paths: &Arc<std::sync::RwLock<HashSet<String>>>,
     sent_file_stats: &SentFileStats,
     stats_only: bool,
+    max_file_bytes: usize,
     path_prefix: Option<&str>,
     repo_id: Uuid,
 ) -> Result<Patch, DiffStreamError> {
     let path_filter: Vec<&str> = changed_paths.iter().map(|s| s.as_str()).collect();
-    let current_diffs = git_service.get_diffs(worktree_path, base_commit, Some(&path_filter))?;
+    let current_diffs = git_service.get_diffs_with_max_file_size(
+        worktree_path,
+        base_commit,
+        Some(&path_filter),
+        max_file_bytes,
+    )?;
 
     let mut ops = Vec::new();
     let mut files_with_diffs = HashSet::new();
@@ -760,14 +819,7 @@ fn process_file_changes(
         }
 
         apply_stream_omit_policy(&mut diff, cumulative_bytes, stats_only);
-
-        if let Some(old) = diff.old_path {
-            diff.old_path = Some(prefix_path(old, path_prefix));
-        }
-        if let Some(new) = diff.new_path {
-            diff.new_path = Some(prefix_path(new, path_prefix));
-        }
-        diff.repo_id = Some(repo_id);
+        decorate_diff(&mut diff, path_prefix, repo_id);
 
         let repo_key = path_prefix.unwrap_or("_");
         let patch = ConversationPatch::add_repo_diff(repo_key, &raw_file_path, diff);
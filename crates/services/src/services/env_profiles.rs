@@ -0,0 +1,234 @@
+//! Environment profiles: named sets of environment variables attached to a
+//! repo and selected per workspace.
+//!
+//! The variables of every profile selected for a workspace are injected into
+//! all script and agent processes started in it, in selection order (later
+//! profiles win on conflicts). Required variables are checked before a process
+//! is spawned so a misconfigured workspace fails fast with a clear error rather
+//! than part-way through a setup script.
+
+use std::collections::{HashMap, HashSet};
+
+use db::models::{
+    env_profile::{
+        CreateEnvProfile, EnvProfile, EnvProfileError as EnvProfileModelError, UpdateEnvProfile,
+    },
+    workspace_repo::WorkspaceRepo,
+};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use uuid::Uuid;
+
+const MAX_PROFILE_NAME_LEN: usize = 64;
+const MAX_VARIABLE_NAME_LEN: usize = 128;
+
+#[derive(Debug, Error)]
+pub enum EnvProfileError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Profile(#[from] EnvProfileModelError),
+    #[error("Invalid environment profile name: {0:?}")]
+    InvalidProfileName(String),
+    #[error("Invalid environment variable name: {0:?}")]
+    InvalidVariableName(String),
+    #[error("Environment profile {0} does not belong to a repository in this workspace")]
+    NotInWorkspace(Uuid),
+    #[error(
+        "Environment profile `{profile}` requires variables that are not set: {}",
+        .names.join(", ")
+    )]
+    MissingVariables { profile: String, names: Vec<String> },
+}
+
+fn validate_variable_name(name: &str) -> Result<(), EnvProfileError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_VARIABLE_NAME_LEN
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(EnvProfileError::InvalidVariableName(name.to_string()))
+    }
+}
+
+fn validate_profile(
+    name: &str,
+    variables: &HashMap<String, String>,
+    required_variables: &[String],
+) -> Result<(), EnvProfileError> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() || trimmed.len() > MAX_PROFILE_NAME_LEN {
+        return Err(EnvProfileError::InvalidProfileName(name.to_string()));
+    }
+    for key in variables.keys().chain(required_variables) {
+        validate_variable_name(key)?;
+    }
+    Ok(())
+}
+
+pub async fn create_profile(
+    pool: &SqlitePool,
+    repo_id: Uuid,
+    data: &CreateEnvProfile,
+) -> Result<EnvProfile, EnvProfileError> {
+    validate_profile(&data.name, &data.variables, &data.required_variables)?;
+    Ok(EnvProfile::create(pool, repo_id, data).await?)
+}
+
+pub async fn update_profile(
+    pool: &SqlitePool,
+    id: Uuid,
+    data: &UpdateEnvProfile,
+) -> Result<EnvProfile, EnvProfileError> {
+    let existing = EnvProfile::find_by_id(pool, id)
+        .await?
+        .ok_or(EnvProfileModelError::NotFound)?;
+    validate_profile(
+        data.name.as_deref().unwrap_or(&existing.name),
+        data.variables.as_ref().unwrap_or(&existing.variables),
+        data.required_variables
+            .as_deref()
+            .unwrap_or(&existing.required_variables),
+    )?;
+    Ok(EnvProfile::update(pool, id, data).await?)
+}
+
+/// Select the profiles used by a workspace, replacing any previous selection.
+/// Each profile must belong to one of the workspace's repos.
+pub async fn select_for_workspace(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+    profile_ids: &[Uuid],
+) -> Result<Vec<EnvProfile>, EnvProfileError> {
+    let repo_ids: HashSet<Uuid> = WorkspaceRepo::find_by_workspace_id(pool, workspace_id)
+        .await?
+        .into_iter()
+        .map(|workspace_repo| workspace_repo.repo_id)
+        .collect();
+
+    for &profile_id in profile_ids {
+        let profile = EnvProfile::find_by_id(pool, profile_id)
+            .await?
+            .ok_or(EnvProfileModelError::NotFound)?;
+        if !repo_ids.contains(&profile.repo_id) {
+            return Err(EnvProfileError::NotInWorkspace(profile_id));
+        }
+    }
+
+    EnvProfile::set_for_workspace(pool, workspace_id, profile_ids).await?;
+    Ok(EnvProfile::find_for_workspace(pool, workspace_id).await?)
+}
+
+/// Merge the variables of `profiles` in order and check that every required
+/// variable resolves to a non-empty value. `inherited` reports whether a
+/// variable is already set in the environment processes inherit.
+fn merge_profiles(
+    profiles: &[EnvProfile],
+    inherited: impl Fn(&str) -> bool,
+) -> Result<HashMap<String, String>, EnvProfileError> {
+    let mut env = HashMap::new();
+    for profile in profiles {
+        env.extend(
+            profile
+                .variables
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
+    }
+
+    for profile in profiles {
+        let missing: Vec<String> = profile
+            .required_variables
+            .iter()
+            .filter(|name| match env.get(name.as_str()) {
+                Some(value) => value.is_empty(),
+                None => !inherited(name),
+            })
+            .cloned()
+            .collect();
+        if !missing.is_empty() {
+            return Err(EnvProfileError::MissingVariables {
+                profile: profile.name.clone(),
+                names: missing,
+            });
+        }
+    }
+
+    Ok(env)
+}
+
+/// Environment variables contributed by the profiles selected for a
+/// workspace, failing if any required variable is unset.
+pub async fn resolve_workspace_env(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+) -> Result<HashMap<String, String>, EnvProfileError> {
+    let profiles = EnvProfile::find_for_workspace(pool, workspace_id).await?;
+    merge_profiles(&profiles, |name| {
+        std::env::var_os(name).is_some_and(|value| !value.is_empty())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn profile(name: &str, variables: &[(&str, &str)], required: &[&str]) -> EnvProfile {
+        EnvProfile {
+            id: Uuid::new_v4(),
+            repo_id: Uuid::new_v4(),
+            name: name.to_string(),
+            variables: variables
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            required_variables: required.iter().map(|s| s.to_string()).collect(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn later_profiles_override_earlier_ones() {
+        let profiles = vec![
+            profile(
+                "base",
+                &[("NODE_ENV", "development"), ("PORT", "3000")],
+                &[],
+            ),
+            profile("ci", &[("NODE_ENV", "test")], &["PORT"]),
+        ];
+        let env = merge_profiles(&profiles, |_| false).unwrap();
+        assert_eq!(env["NODE_ENV"], "test");
+        assert_eq!(env["PORT"], "3000");
+    }
+
+    #[test]
+    fn reports_missing_required_variables() {
+        let profiles = vec![profile(
+            "staging",
+            &[("API_URL", "")],
+            &["API_URL", "API_TOKEN", "HOME"],
+        )];
+        let err = merge_profiles(&profiles, |name| name == "HOME").unwrap_err();
+        match err {
+            EnvProfileError::MissingVariables { profile, names } => {
+                assert_eq!(profile, "staging");
+                assert_eq!(names, vec!["API_URL".to_string(), "API_TOKEN".to_string()]);
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_variable_names() {
+        let variables = HashMap::from([("MY-VAR".to_string(), "x".to_string())]);
+        assert!(validate_profile("dev", &variables, &[]).is_err());
+        assert!(validate_profile("dev", &HashMap::new(), &["1ABC".to_string()]).is_err());
+        assert!(validate_profile("  ", &HashMap::new(), &[]).is_err());
+    }
+}
@@ -135,6 +135,54 @@ pub mod scratch_patch {
     }
 }
 
+/// Helper functions for creating merge readiness patches, keyed by workspace.
+pub mod merge_readiness_patch {
+    use super::*;
+    use crate::services::merge_readiness::WorkspaceMergeReadiness;
+
+    fn merge_readiness_path(workspace_id: Uuid) -> String {
+        format!(
+            "/merge_readiness/{}",
+            escape_pointer_segment(&workspace_id.to_string())
+        )
+    }
+
+    /// Add replaces an existing member, so this covers both the first and later
+    /// publishes for a workspace.
+    pub fn set(readiness: &WorkspaceMergeReadiness) -> Patch {
+        Patch(vec![PatchOperation::Add(AddOperation {
+            path: merge_readiness_path(readiness.workspace_id)
+                .try_into()
+                .expect("Merge readiness path should be valid"),
+            value: serde_json::to_value(readiness)
+                .expect("Merge readiness serialization should not fail"),
+        })])
+    }
+}
+
+/// Helper functions for creating startup task patches, keyed by task.
+pub mod startup_task_patch {
+    use super::*;
+    use crate::services::startup_tasks::StartupTaskProgress;
+
+    fn startup_task_path(progress: &StartupTaskProgress) -> String {
+        format!(
+            "/startup_tasks/{}",
+            escape_pointer_segment(&progress.task.to_string())
+        )
+    }
+
+    pub fn set(progress: &StartupTaskProgress) -> Patch {
+        Patch(vec![PatchOperation::Add(AddOperation {
+            path: startup_task_path(progress)
+                .try_into()
+                .expect("Startup task path should be valid"),
+            value: serde_json::to_value(progress)
+                .expect("Startup task serialization should not fail"),
+        })])
+    }
+}
+
 /// Helper functions for creating approval-specific patches.
 pub mod approvals_patch {
     use super::*;
@@ -8,7 +8,8 @@ use anyhow::{Context, Result};
 use db::{
     DBService,
     models::{
-        coding_agent_turn::CodingAgentTurn, execution_process::ExecutionProcess,
+        coding_agent_turn::CodingAgentTurn,
+        execution_process::{ExecutionProcess, ExecutionProcessRunReason},
         execution_process_logs::ExecutionProcessLogs,
     },
 };
@@ -19,16 +20,32 @@ use tokio::{io::AsyncWriteExt, sync::RwLock, task::JoinHandle};
 use utils::{
     assets::prod_asset_dir_path,
     execution_logs::{
-        ExecutionLogWriter, process_log_file_path, process_log_file_path_in_root,
-        read_execution_log_file,
+        ExecutionLogWriter, normalized_log_file_path, process_log_file_path,
+        process_log_file_path_in_root, read_execution_log_file,
     },
     log_msg::LogMsg,
     msg_store::MsgStore,
 };
 use uuid::Uuid;
 
-pub async fn migrate_execution_logs_to_files() -> Result<()> {
-    let pool = DBService::new_migration_pool()
+use crate::services::{
+    config::{LogFsyncPolicy, LogWritePolicy, LogWritesConfig},
+    log_redaction::LogRedactor,
+    log_search::{INDEX_FLUSH_INTERVAL, LogSearchIndexer},
+    startup_tasks::{StartupTask, StartupTasks},
+};
+
+const MIGRATION_CONCURRENCY: usize = 8;
+
+/// Move execution logs stored by older versions from SQLite to log files.
+/// Runs in the background; readers fall back to the database rows until it's
+/// done.
+pub async fn migrate_execution_logs_to_files(
+    startup_tasks: &StartupTasks,
+    db: &DBService,
+) -> Result<()> {
+    let pool = db
+        .new_migration_pool()
         .await
         .map_err(|e| anyhow::anyhow!("Migration DB pool error: {}", e))?;
 
@@ -73,17 +90,21 @@ pub async fn migrate_execution_logs_to_files() -> Result<()> {
     };
 
     let completed = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let mut processed = 0u64;
+
+    // Collected up front rather than streamed, so the server's writers aren't
+    // locked out for the whole migration.
+    let processes = ExecutionProcessLogs::stream_distinct_processes(&pool)
+        .try_collect::<Vec<_>>()
+        .await?;
 
-    ExecutionProcessLogs::stream_distinct_processes(&pool)
-        .map_err(anyhow::Error::from)
-        .map(|res| {
+    futures::stream::iter(processes)
+        .map(|p| {
             let pool = pool.clone();
             let pb = pb.clone();
             let completed = completed.clone();
             let total_processes = total_processes.clone();
             async move {
-                let p = res?;
-
                 let path = process_log_file_path(p.session_id, p.execution_id);
                 if path.exists() {
                     if let Some(pb) = &pb {
@@ -148,7 +169,16 @@ pub async fn migrate_execution_logs_to_files() -> Result<()> {
                 Ok::<(), anyhow::Error>(())
             }
         })
-        .buffer_unordered(64)
+        .buffer_unordered(MIGRATION_CONCURRENCY)
+        .inspect_ok(|_| {
+            processed += 1;
+            let total = total_processes.load(std::sync::atomic::Ordering::Relaxed);
+            startup_tasks.set_progress(
+                StartupTask::LogMigration,
+                processed,
+                (total > 0).then_some(total as u64),
+            );
+        })
         .try_collect::<Vec<_>>()
         .await?;
 
@@ -168,7 +198,11 @@ pub async fn migrate_execution_logs_to_files() -> Result<()> {
     };
 
     ExecutionProcessLogs::delete_all(&pool).await?;
-    sqlx::query("VACUUM").execute(&pool).await?;
+    // The server is already serving requests, which can keep VACUUM from
+    // getting the exclusive lock it needs. SQLite reuses the freed pages anyway.
+    if let Err(e) = sqlx::query("VACUUM").execute(&pool).await {
+        tracing::warn!("Failed to compact database after log migration: {}", e);
+    }
 
     if let Some(pb) = vacuum_pb {
         pb.finish_and_clear();
@@ -250,14 +284,60 @@ pub async fn append_log_message(session_id: Uuid, execution_id: Uuid, msg: &LogM
         .append_jsonl_line(&json_line_with_newline)
         .await
         .with_context(|| format!("append log message for execution {}", execution_id))?;
+    log_writer
+        .flush()
+        .await
+        .with_context(|| format!("flush log message for execution {}", execution_id))?;
     Ok(())
 }
 
+pub fn log_write_policy(
+    config: &LogWritesConfig,
+    run_reason: &ExecutionProcessRunReason,
+) -> LogWritePolicy {
+    match run_reason {
+        ExecutionProcessRunReason::CodingAgent => config.coding_agent.clone(),
+        ExecutionProcessRunReason::DevServer => config.dev_server.clone(),
+        ExecutionProcessRunReason::SetupScript
+        | ExecutionProcessRunReason::CleanupScript
+        | ExecutionProcessRunReason::ArchiveScript
+        | ExecutionProcessRunReason::VerifyScript => config.scripts.clone(),
+    }
+}
+
+/// Write out buffered log lines, then persist the search index, which must
+/// never point past what is in the file. Returns whether the lines were
+/// written.
+async fn flush_log_writer(
+    log_writer: &mut ExecutionLogWriter,
+    indexer: &mut LogSearchIndexer,
+    sync: bool,
+    execution_id: Uuid,
+) -> bool {
+    let result = if sync {
+        log_writer.sync().await
+    } else {
+        log_writer.flush().await
+    };
+    if let Err(e) = result {
+        tracing::error!(
+            "Failed to flush log file for execution {}: {}",
+            execution_id,
+            e
+        );
+        return false;
+    }
+    indexer.flush().await;
+    true
+}
+
 pub fn spawn_stream_raw_logs_to_storage(
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
     db: DBService,
     execution_id: Uuid,
     session_id: Uuid,
+    redactor: Option<LogRedactor>,
+    policy: LogWritePolicy,
 ) -> JoinHandle<()> {
     tokio::spawn(async move {
         let mut log_writer =
@@ -273,15 +353,59 @@ pub fn spawn_stream_raw_logs_to_storage(
                 }
             };
 
+        let mut indexer =
+            LogSearchIndexer::resume(db.clone(), execution_id, log_writer.path()).await;
+
         let store = {
             let map = msg_stores.read().await;
             map.get(&execution_id).cloned()
         };
 
+        let sync_on_flush = policy.fsync == LogFsyncPolicy::OnFlush;
+        let write_delay = (policy.flush_interval_ms > 0)
+            .then(|| std::time::Duration::from_millis(policy.flush_interval_ms.into()));
+        // Whether lines were appended since the last flush.
+        let mut buffered = false;
+
         if let Some(store) = store {
             let mut stream = store.history_plus_stream();
+            let mut flush_interval = tokio::time::interval(INDEX_FLUSH_INTERVAL);
+            let mut write_interval =
+                tokio::time::interval(write_delay.unwrap_or(INDEX_FLUSH_INTERVAL));
+            write_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                let msg = tokio::select! {
+                    msg = stream.next() => match msg {
+                        Some(Ok(msg)) => msg,
+                        _ => break,
+                    },
+                    // With lines still buffered, the index catches up on the
+                    // next write instead.
+                    _ = flush_interval.tick(), if !buffered => {
+                        indexer.flush().await;
+                        continue;
+                    }
+                    _ = write_interval.tick(), if buffered => {
+                        if flush_log_writer(
+                            &mut log_writer,
+                            &mut indexer,
+                            sync_on_flush,
+                            execution_id,
+                        )
+                        .await
+                        {
+                            buffered = false;
+                        }
+                        continue;
+                    }
+                };
+
+                let msg = match &redactor {
+                    Some(redactor) => redactor.redact_msg(msg),
+                    None => msg,
+                };
 
-            while let Some(Ok(msg)) = stream.next().await {
                 match &msg {
                     LogMsg::Stdout(_) | LogMsg::Stderr(_) => match serde_json::to_string(&msg) {
                         Ok(jsonl_line) => {
@@ -296,6 +420,20 @@ pub fn spawn_stream_raw_logs_to_storage(
                                     execution_id,
                                     e
                                 );
+                            } else {
+                                indexer.push(&msg, jsonl_line_with_newline.len());
+                                buffered = true;
+                                if (write_delay.is_none() || indexer.should_flush())
+                                    && flush_log_writer(
+                                        &mut log_writer,
+                                        &mut indexer,
+                                        sync_on_flush,
+                                        execution_id,
+                                    )
+                                    .await
+                                {
+                                    buffered = false;
+                                }
                             }
                         }
                         Err(e) => {
@@ -307,12 +445,16 @@ pub fn spawn_stream_raw_logs_to_storage(
                         }
                     },
                     LogMsg::SessionId(agent_session_id) => {
-                        if let Err(e) = CodingAgentTurn::update_agent_session_id(
-                            &db.pool,
-                            execution_id,
-                            agent_session_id,
-                        )
-                        .await
+                        if let Err(e) = db
+                            .write(
+                                "coding_agent_turns",
+                                CodingAgentTurn::update_agent_session_id(
+                                    &db.pool,
+                                    execution_id,
+                                    agent_session_id,
+                                ),
+                            )
+                            .await
                         {
                             tracing::error!(
                                 "Failed to update agent_session_id {} for execution process {}: {}",
@@ -323,12 +465,16 @@ pub fn spawn_stream_raw_logs_to_storage(
                         }
                     }
                     LogMsg::MessageId(agent_message_id) => {
-                        if let Err(e) = CodingAgentTurn::update_agent_message_id(
-                            &db.pool,
-                            execution_id,
-                            agent_message_id,
-                        )
-                        .await
+                        if let Err(e) = db
+                            .write(
+                                "coding_agent_turns",
+                                CodingAgentTurn::update_agent_message_id(
+                                    &db.pool,
+                                    execution_id,
+                                    agent_message_id,
+                                ),
+                            )
+                            .await
                         {
                             tracing::error!(
                                 "Failed to update agent_message_id {} for execution process {}: {}",
@@ -345,9 +491,128 @@ pub fn spawn_stream_raw_logs_to_storage(
                 }
             }
         }
+
+        flush_log_writer(
+            &mut log_writer,
+            &mut indexer,
+            policy.fsync != LogFsyncPolicy::Never,
+            execution_id,
+        )
+        .await;
     })
 }
 
+/// Once the normalizers of a live process are done, write the patches they
+/// produced next to the raw log, so viewing the process later replays them
+/// instead of normalizing the raw log again. Skipped when the store dropped
+/// part of its history, as the patches would be incomplete.
+pub fn spawn_persist_normalized_logs(
+    store: Arc<MsgStore>,
+    normalizers: Vec<JoinHandle<()>>,
+    session_id: Uuid,
+    execution_id: Uuid,
+    redactor: Option<LogRedactor>,
+) {
+    tokio::spawn(async move {
+        for handle in normalizers {
+            let _ = handle.await;
+        }
+        if store.history_truncated() {
+            tracing::debug!(
+                "Not caching normalized logs for execution {}: history was truncated",
+                execution_id
+            );
+            return;
+        }
+
+        let mut contents = String::new();
+        for msg in store.get_history() {
+            let LogMsg::JsonPatch(patch) = msg else {
+                continue;
+            };
+            let patch = match &redactor {
+                Some(redactor) => redactor.redact_patch(patch),
+                None => patch,
+            };
+            match serde_json::to_string(&LogMsg::JsonPatch(patch)) {
+                Ok(line) => {
+                    contents.push_str(&line);
+                    contents.push('\n');
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to serialize normalized log for execution {}: {}",
+                        execution_id,
+                        e
+                    );
+                    return;
+                }
+            }
+        }
+
+        let path = normalized_log_file_path(session_id, execution_id);
+        let temp_path = path.with_extension("jsonl.tmp");
+        let result = async {
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(&temp_path, contents).await?;
+            tokio::fs::rename(&temp_path, &path).await
+        }
+        .await;
+        if let Err(e) = result {
+            tracing::warn!(
+                "Failed to cache normalized logs for execution {}: {}",
+                execution_id,
+                e
+            );
+            let _ = tokio::fs::remove_file(&temp_path).await;
+        }
+    });
+}
+
+/// Normalized patches cached by [`spawn_persist_normalized_logs`], or `None`
+/// for processes that predate the cache or whose cache can't be read.
+pub async fn load_normalized_log_patches(
+    pool: &SqlitePool,
+    execution_id: Uuid,
+) -> Option<Vec<json_patch::Patch>> {
+    let process = ExecutionProcess::find_by_id(pool, execution_id)
+        .await
+        .ok()
+        .flatten()?;
+    let path = normalized_log_file_path(process.session_id, execution_id);
+    let contents = match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to read normalized logs for execution {}: {}",
+                execution_id,
+                e
+            );
+            return None;
+        }
+    };
+
+    let mut patches = Vec::new();
+    for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+        match serde_json::from_str::<LogMsg>(line) {
+            Ok(LogMsg::JsonPatch(patch)) => patches.push(patch),
+            Ok(_) => {}
+            Err(e) => {
+                tracing::warn!(
+                    "Discarding unreadable normalized logs for execution {}: {}",
+                    execution_id,
+                    e
+                );
+                return None;
+            }
+        }
+    }
+    Some(patches)
+}
+
 async fn read_execution_logs_for_execution(
     pool: &SqlitePool,
     execution_id: Uuid,
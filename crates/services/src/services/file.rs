@@ -3,12 +3,17 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use chrono::{Duration, Utc};
 use db::models::file::{CreateFile, File};
 use mime_guess::MimeGuess;
 use sha2::{Digest, Sha256};
 use sqlx::SqlitePool;
 use uuid::Uuid;
 
+/// Uploads younger than this are kept by cleanups triggered while the app is
+/// running, as they may belong to a prompt that hasn't been sent yet.
+pub const ORPHANED_FILE_MIN_AGE: Duration = Duration::hours(1);
+
 #[derive(Debug, thiserror::Error)]
 pub enum FileError {
     #[error("IO error: {0}")]
@@ -130,8 +135,15 @@ impl FileService {
         Ok(file)
     }
 
-    pub async fn delete_orphaned_files(&self) -> Result<(), FileError> {
-        let orphaned_files = File::find_orphaned_files(&self.pool).await?;
+    /// Delete files no workspace references that were uploaded at least
+    /// `min_age` ago. Recent uploads may still be waiting in an unsent prompt.
+    pub async fn delete_orphaned_files(&self, min_age: Duration) -> Result<(), FileError> {
+        let cutoff = Utc::now() - min_age;
+        let orphaned_files: Vec<File> = File::find_orphaned_files(&self.pool)
+            .await?
+            .into_iter()
+            .filter(|file| file.created_at <= cutoff)
+            .collect();
         if orphaned_files.is_empty() {
             tracing::debug!("No orphaned files found during cleanup");
             return Ok(());
@@ -163,6 +175,20 @@ impl FileService {
         Ok(())
     }
 
+    /// Clean up files orphaned by deleting a workspace and its sessions, in
+    /// the background.
+    pub fn spawn_orphaned_file_cleanup(&self) {
+        let file_service = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = file_service
+                .delete_orphaned_files(ORPHANED_FILE_MIN_AGE)
+                .await
+            {
+                tracing::error!("Failed to clean up orphaned files: {}", e);
+            }
+        });
+    }
+
     pub fn get_absolute_path(&self, file: &File) -> PathBuf {
         self.resolve_cached_path(&file.file_path)
             .unwrap_or_else(|| self.cache_dir.join(&file.file_path))
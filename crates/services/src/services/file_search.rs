@@ -14,6 +14,7 @@ use thiserror::Error;
 use tokio::sync::mpsc;
 use tracing::{error, info, warn};
 use ts_rs::TS;
+use uuid::Uuid;
 
 use super::file_ranker::{FileRanker, FileStats};
 
@@ -35,6 +36,14 @@ pub struct SearchQuery {
     pub mode: SearchMode,
 }
 
+/// A directory to search, usually a repo checkout or a workspace worktree.
+#[derive(Debug, Clone)]
+pub struct SearchRoot {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub path: PathBuf,
+}
+
 /// FST-indexed file search result
 #[derive(Clone, Debug)]
 pub struct IndexedFile {
@@ -0,0 +1,273 @@
+//! One level of a workspace's directory tree at a time, for file browsers
+//! that expand directories lazily. Only the requested directory is read, so
+//! large ignored trees like `node_modules` are never walked.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use git::WorktreeStatus;
+use ignore::WalkBuilder;
+use serde::Serialize;
+use ts_rs::TS;
+
+/// Entries returned for a single directory; the rest are dropped.
+pub const MAX_TREE_ENTRIES: usize = 5_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum GitFileStatus {
+    Modified,
+    Added,
+    Deleted,
+    Renamed,
+    Untracked,
+    Conflicted,
+}
+
+impl GitFileStatus {
+    fn from_codes(staged: char, unstaged: char) -> Self {
+        match (staged, unstaged) {
+            ('U', _) | (_, 'U') | ('A', 'A') | ('D', 'D') => Self::Conflicted,
+            ('?', _) => Self::Untracked,
+            ('R', _) | (_, 'R') => Self::Renamed,
+            ('A', _) => Self::Added,
+            ('D', _) | (_, 'D') => Self::Deleted,
+            _ => Self::Modified,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct FileTreeEntry {
+    pub name: String,
+    /// Relative to the workspace directory, with `/` separators.
+    pub path: String,
+    pub is_dir: bool,
+    pub is_symlink: bool,
+    /// Size of files; None for directories.
+    #[ts(type = "number | null")]
+    pub size_bytes: Option<u64>,
+    pub is_ignored: bool,
+    pub git_status: Option<GitFileStatus>,
+    /// For directories: whether anything below has uncommitted changes.
+    pub has_changes: bool,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct FileTreeListing {
+    pub path: String,
+    pub entries: Vec<FileTreeEntry>,
+    /// True if the directory had more than `MAX_TREE_ENTRIES` entries.
+    pub truncated: bool,
+}
+
+/// Uncommitted changes of the workspace's repos, keyed by paths relative to
+/// the workspace directory.
+#[derive(Debug, Default)]
+pub struct GitStatusIndex {
+    files: HashMap<String, GitFileStatus>,
+    changed_dirs: HashSet<String>,
+    /// Untracked directories, reported by git as a whole (`dir/`).
+    untracked_dirs: Vec<String>,
+}
+
+impl GitStatusIndex {
+    /// Add the status of the repo checked out at `repo_dir` (relative to the
+    /// workspace directory).
+    pub fn add_repo(&mut self, repo_dir: &str, status: &WorktreeStatus) {
+        for entry in &status.entries {
+            let relative = String::from_utf8_lossy(&entry.path);
+            let path = join(repo_dir, relative.trim_end_matches('/'));
+            let file_status = GitFileStatus::from_codes(entry.staged, entry.unstaged);
+
+            let mut dir = Path::new(&path).parent();
+            while let Some(parent) = dir.filter(|parent| !parent.as_os_str().is_empty()) {
+                self.changed_dirs
+                    .insert(parent.to_string_lossy().replace('\\', "/"));
+                dir = parent.parent();
+            }
+            if relative.ends_with('/') {
+                self.changed_dirs.insert(path.clone());
+                self.untracked_dirs.push(path);
+            } else {
+                self.files.insert(path, file_status);
+            }
+        }
+    }
+
+    fn in_untracked_dir(&self, path: &str) -> bool {
+        self.untracked_dirs.iter().any(|dir| {
+            path == dir
+                || path
+                    .strip_prefix(dir.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+    }
+
+    fn status_of(&self, path: &str) -> Option<GitFileStatus> {
+        self.files.get(path).copied().or_else(|| {
+            self.in_untracked_dir(path)
+                .then_some(GitFileStatus::Untracked)
+        })
+    }
+
+    fn dir_has_changes(&self, path: &str) -> bool {
+        self.changed_dirs.contains(path) || self.in_untracked_dir(path)
+    }
+}
+
+fn join(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.to_string()
+    } else {
+        format!("{dir}/{name}")
+    }
+}
+
+/// List the directory at `relative` (already checked to be inside
+/// `workspace_dir`), directories first.
+pub fn list_tree_level(
+    workspace_dir: &Path,
+    relative: &str,
+    include_ignored: bool,
+    statuses: &GitStatusIndex,
+) -> std::io::Result<FileTreeListing> {
+    let relative = relative.trim_matches('/');
+    let dir = workspace_dir.join(relative);
+
+    // A one-level walk applies the gitignore rules of the directory and its
+    // parents without descending.
+    let visible: HashSet<std::ffi::OsString> = WalkBuilder::new(&dir)
+        .max_depth(Some(1))
+        .hidden(false)
+        .git_ignore(true)
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.depth() == 1)
+        .map(|entry| entry.file_name().to_os_string())
+        .collect();
+
+    let mut entries = Vec::new();
+    let mut truncated = false;
+    for dir_entry in std::fs::read_dir(&dir)? {
+        let dir_entry = dir_entry?;
+        let name = dir_entry.file_name();
+        let name_str = name.to_string_lossy().to_string();
+        if name_str == ".git" {
+            continue;
+        }
+        let is_ignored = !visible.contains(&name);
+        if is_ignored && !include_ignored {
+            continue;
+        }
+        if entries.len() >= MAX_TREE_ENTRIES {
+            truncated = true;
+            break;
+        }
+
+        let file_type = dir_entry.file_type()?;
+        // Follow symlinks for the type and size, but report them as links.
+        let metadata = std::fs::metadata(dir_entry.path()).ok();
+        let is_dir = metadata.as_ref().is_some_and(|m| m.is_dir());
+        let path = join(relative, &name_str);
+        entries.push(FileTreeEntry {
+            is_symlink: file_type.is_symlink(),
+            size_bytes: metadata.filter(|m| m.is_file()).map(|m| m.len()),
+            is_ignored,
+            git_status: statuses.status_of(&path),
+            has_changes: is_dir && statuses.dir_has_changes(&path),
+            name: name_str,
+            path,
+            is_dir,
+        });
+    }
+
+    entries.sort_by(|a, b| {
+        b.is_dir
+            .cmp(&a.is_dir)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    Ok(FileTreeListing {
+        path: relative.to_string(),
+        entries,
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use git::StatusEntry;
+
+    use super::*;
+
+    fn entry(staged: char, unstaged: char, path: &str) -> StatusEntry {
+        StatusEntry {
+            staged,
+            unstaged,
+            path: path.as_bytes().to_vec(),
+            orig_path: None,
+            is_untracked: staged == '?',
+        }
+    }
+
+    #[test]
+    fn indexes_statuses_under_the_repo_dir() {
+        let mut index = GitStatusIndex::default();
+        index.add_repo(
+            "app",
+            &WorktreeStatus {
+                uncommitted_tracked: 2,
+                untracked: 1,
+                entries: vec![
+                    entry(' ', 'M', "src/main.rs"),
+                    entry('A', ' ', "README.md"),
+                    entry('?', '?', "scratch/"),
+                ],
+            },
+        );
+
+        assert_eq!(
+            index.status_of("app/src/main.rs"),
+            Some(GitFileStatus::Modified)
+        );
+        assert_eq!(index.status_of("app/README.md"), Some(GitFileStatus::Added));
+        assert_eq!(
+            index.status_of("app/scratch/notes.txt"),
+            Some(GitFileStatus::Untracked)
+        );
+        assert_eq!(index.status_of("app/src/lib.rs"), None);
+        assert!(index.dir_has_changes("app"));
+        assert!(index.dir_has_changes("app/src"));
+        assert!(index.dir_has_changes("app/scratch/deep"));
+        assert!(!index.dir_has_changes("app/docs"));
+    }
+
+    #[test]
+    fn lists_one_level_with_ignored_entries_flagged() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("app");
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        std::fs::create_dir_all(repo.join("node_modules/pkg")).unwrap();
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        std::fs::write(repo.join(".gitignore"), "node_modules/\n").unwrap();
+        std::fs::write(repo.join("Cargo.toml"), "[package]\n").unwrap();
+
+        let statuses = GitStatusIndex::default();
+        let listing = list_tree_level(dir.path(), "app", false, &statuses).unwrap();
+        let names: Vec<&str> = listing.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["src", ".gitignore", "Cargo.toml"]);
+        assert_eq!(listing.entries[2].size_bytes, Some(10));
+        assert_eq!(listing.entries[2].path, "app/Cargo.toml");
+
+        let listing = list_tree_level(dir.path(), "app", true, &statuses).unwrap();
+        let node_modules = listing
+            .entries
+            .iter()
+            .find(|e| e.name == "node_modules")
+            .unwrap();
+        assert!(node_modules.is_ignored && node_modules.is_dir);
+    }
+}
@@ -20,7 +20,9 @@ use notify::{
 use notify_debouncer_full::{
     DebounceEventResult, DebouncedEvent, Debouncer, RecommendedCache, new_debouncer,
 };
+use serde::Serialize;
 use thiserror::Error;
+use ts_rs::TS;
 use utils::path::ALWAYS_SKIP_DIRS;
 
 pub type WatcherComponents = (
@@ -618,3 +620,182 @@ pub fn async_watcher(root: PathBuf) -> Result<WatcherComponents, FilesystemWatch
 
     Ok((debouncer, filtered_rx, canonical_root))
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum FileChangeKind {
+    Created,
+    Modified,
+    Removed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+pub struct FileChange {
+    pub kind: FileChangeKind,
+    /// Relative to the watched root, with `/` separators.
+    pub path: String,
+    /// Always false for removed paths, whose type can no longer be read.
+    pub is_dir: bool,
+}
+
+/// Collapse a batch of debounced events into at most one change per path,
+/// in the order the paths first changed. Renames become a removal of the old
+/// path and a creation of the new one.
+pub fn file_changes(events: &[DebouncedEvent], canonical_root: &Path) -> Vec<FileChange> {
+    let mut changes: Vec<(PathBuf, FileChangeKind)> = Vec::new();
+    let mut record = |path: &Path, kind: FileChangeKind| {
+        let Some(index) = changes.iter().position(|(seen, _)| seen == path) else {
+            changes.push((path.to_path_buf(), kind));
+            return;
+        };
+        let merged = match (changes[index].1, kind) {
+            (FileChangeKind::Created, FileChangeKind::Modified) => Some(FileChangeKind::Created),
+            (FileChangeKind::Created, FileChangeKind::Removed) => None,
+            (FileChangeKind::Removed, FileChangeKind::Created) => Some(FileChangeKind::Modified),
+            (_, kind) => Some(kind),
+        };
+        match merged {
+            Some(kind) => changes[index].1 = kind,
+            None => {
+                changes.remove(index);
+            }
+        }
+    };
+    let exists_kind = |path: &Path, kind: FileChangeKind| {
+        if path.exists() {
+            kind
+        } else {
+            FileChangeKind::Removed
+        }
+    };
+
+    for event in events {
+        match &event.kind {
+            EventKind::Access(_) => {}
+            EventKind::Create(_) => {
+                for path in &event.paths {
+                    record(path, FileChangeKind::Created);
+                }
+            }
+            EventKind::Remove(_) => {
+                for path in &event.paths {
+                    record(path, FileChangeKind::Removed);
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                for path in &event.paths {
+                    record(path, FileChangeKind::Removed);
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+                for path in &event.paths {
+                    record(path, FileChangeKind::Created);
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+                if let (Some(from), Some(to)) = (event.paths.first(), event.paths.last()) {
+                    record(from, FileChangeKind::Removed);
+                    record(to, FileChangeKind::Created);
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(_)) => {
+                for path in &event.paths {
+                    record(path, exists_kind(path, FileChangeKind::Created));
+                }
+            }
+            _ => {
+                for path in &event.paths {
+                    record(path, exists_kind(path, FileChangeKind::Modified));
+                }
+            }
+        }
+    }
+
+    changes
+        .into_iter()
+        .filter_map(|(path, kind)| {
+            let canonical_path = match kind {
+                FileChangeKind::Removed => path.clone(),
+                _ => canonicalize_lossy(&path),
+            };
+            let relative = canonical_path
+                .strip_prefix(canonical_root)
+                .ok()
+                .filter(|relative| !relative.as_os_str().is_empty())?;
+            Some(FileChange {
+                kind,
+                path: relative.to_string_lossy().replace('\\', "/"),
+                is_dir: kind != FileChangeKind::Removed && canonical_path.is_dir(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use notify::{
+        Event,
+        event::{CreateKind, DataChange, RemoveKind},
+    };
+
+    use super::*;
+
+    fn event(kind: EventKind, paths: &[&Path]) -> DebouncedEvent {
+        let mut event = Event::new(kind);
+        for path in paths {
+            event = event.add_path(path.to_path_buf());
+        }
+        DebouncedEvent::new(event, Instant::now())
+    }
+
+    #[test]
+    fn collapses_events_per_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = canonicalize_lossy(dir.path());
+        std::fs::create_dir(root.join("src")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "").unwrap();
+        std::fs::write(root.join("new.rs"), "").unwrap();
+
+        let modify = EventKind::Modify(ModifyKind::Data(DataChange::Content));
+        let events = [
+            event(EventKind::Create(CreateKind::File), &[&root.join("new.rs")]),
+            event(modify, &[&root.join("new.rs")]),
+            event(modify, &[&root.join("src/lib.rs")]),
+            event(EventKind::Create(CreateKind::File), &[&root.join("tmp.rs")]),
+            event(EventKind::Remove(RemoveKind::File), &[&root.join("tmp.rs")]),
+            event(
+                EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+                &[&root.join("old"), &root.join("src")],
+            ),
+        ];
+
+        assert_eq!(
+            file_changes(&events, &root),
+            vec![
+                FileChange {
+                    kind: FileChangeKind::Created,
+                    path: "new.rs".to_string(),
+                    is_dir: false,
+                },
+                FileChange {
+                    kind: FileChangeKind::Modified,
+                    path: "src/lib.rs".to_string(),
+                    is_dir: false,
+                },
+                FileChange {
+                    kind: FileChangeKind::Removed,
+                    path: "old".to_string(),
+                    is_dir: false,
+                },
+                FileChange {
+                    kind: FileChangeKind::Created,
+                    path: "src".to_string(),
+                    is_dir: true,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,366 @@
+//! Flaky test tracking.
+//!
+//! CI uploads test results for a repo, either as JUnit XML or as a small JSON
+//! format, to `/api/repos/{repo_id}/test-reports` or to the token URL
+//! `/api/hooks/test-reports/{token}`. Every failing test is tracked from then
+//! on, counting passes, failures and how often its outcome flipped between
+//! reports. Tests that keep flipping are quarantined, and an agent can be
+//! started to fix the flakiest one; the pull request from that workspace
+//! shows up on the test's record.
+
+use chrono::Utc;
+use db::models::flaky_test::{
+    FlakyTest, FlakyTestError as FlakyTestModelError, FlakyTestSettings, TestOutcome,
+    UpsertFlakyTestSettings,
+};
+use quick_xml::{
+    Reader,
+    events::{BytesStart, Event},
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use utils::tokens::{hash_token, new_token};
+use uuid::Uuid;
+
+/// Outcome changes after which a test is quarantined.
+pub const QUARANTINE_FLIPS: i64 = 3;
+/// Failure messages can include whole stack traces; the start is enough.
+const MAX_MESSAGE_CHARS: usize = 4_000;
+
+#[derive(Debug, Error)]
+pub enum FlakyTestError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Test(#[from] FlakyTestModelError),
+    #[error("Invalid test report: {0}")]
+    InvalidReport(String),
+    #[error("Invalid test report token")]
+    InvalidToken,
+    #[error("A target branch is required")]
+    MissingTargetBranch,
+    #[error("No quarantined test is waiting for a fix")]
+    NothingToFix,
+}
+
+/// Saved settings, with the report token when one was just issued. The token
+/// is stored hashed and can't be shown again.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SavedFlakyTestSettings {
+    pub settings: FlakyTestSettings,
+    #[ts(optional)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+/// The JSON report format, for runners without JUnit output.
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct TestReport {
+    pub results: Vec<TestCaseResult>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct TestCaseResult {
+    pub suite: String,
+    pub name: String,
+    pub outcome: TestOutcome,
+    #[serde(default)]
+    #[ts(optional)]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct TestReportSummary {
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    /// Tests this report pushed over the quarantine threshold.
+    pub newly_quarantined: usize,
+}
+
+/// Create or update a repo's settings. A report token is issued when the
+/// settings are first created.
+pub async fn save_settings(
+    pool: &SqlitePool,
+    repo_id: Uuid,
+    data: &UpsertFlakyTestSettings,
+) -> Result<SavedFlakyTestSettings, FlakyTestError> {
+    if data.target_branch.trim().is_empty() {
+        return Err(FlakyTestError::MissingTargetBranch);
+    }
+    if FlakyTestSettings::find_by_repo_id(pool, repo_id)
+        .await?
+        .is_some()
+    {
+        let settings = FlakyTestSettings::update(pool, repo_id, data).await?;
+        return Ok(SavedFlakyTestSettings {
+            settings,
+            token: None,
+        });
+    }
+    let token = new_token();
+    let settings = FlakyTestSettings::create(pool, repo_id, data, &hash_token(&token)).await?;
+    Ok(SavedFlakyTestSettings {
+        settings,
+        token: Some(token),
+    })
+}
+
+/// Issue a new report token, revoking the old one.
+pub async fn rotate_token(
+    pool: &SqlitePool,
+    repo_id: Uuid,
+) -> Result<SavedFlakyTestSettings, FlakyTestError> {
+    let token = new_token();
+    let settings = FlakyTestSettings::set_token_hash(pool, repo_id, &hash_token(&token)).await?;
+    Ok(SavedFlakyTestSettings {
+        settings,
+        token: Some(token),
+    })
+}
+
+/// The settings a report token belongs to.
+pub async fn resolve_token(
+    pool: &SqlitePool,
+    token: &str,
+) -> Result<FlakyTestSettings, FlakyTestError> {
+    FlakyTestSettings::find_by_token_hash(pool, &hash_token(token))
+        .await?
+        .ok_or(FlakyTestError::InvalidToken)
+}
+
+fn invalid(e: impl std::fmt::Display) -> FlakyTestError {
+    FlakyTestError::InvalidReport(e.to_string())
+}
+
+fn attribute(element: &BytesStart, name: &str) -> Result<Option<String>, FlakyTestError> {
+    match element.try_get_attribute(name).map_err(invalid)? {
+        Some(value) => Ok(Some(value.unescape_value().map_err(invalid)?.into_owned())),
+        None => Ok(None),
+    }
+}
+
+fn test_case(element: &BytesStart, suite: &str) -> Result<TestCaseResult, FlakyTestError> {
+    Ok(TestCaseResult {
+        suite: attribute(element, "classname")?.unwrap_or_else(|| suite.to_string()),
+        name: attribute(element, "name")?.unwrap_or_default(),
+        outcome: TestOutcome::Passed,
+        message: None,
+    })
+}
+
+/// Parse a JUnit XML report. A test case with a `failure` or `error` child
+/// failed, one with a `skipped` child was skipped, and any other passed.
+pub fn parse_junit(xml: &str) -> Result<Vec<TestCaseResult>, FlakyTestError> {
+    let mut reader = Reader::from_str(xml);
+    let mut results = Vec::new();
+    let mut suite = String::new();
+    let mut current: Option<TestCaseResult> = None;
+    loop {
+        match reader.read_event().map_err(invalid)? {
+            Event::Start(e) if e.name().as_ref() == b"testsuite" => {
+                suite = attribute(&e, "name")?.unwrap_or_default();
+            }
+            Event::Start(e) if e.name().as_ref() == b"testcase" => {
+                current = Some(test_case(&e, &suite)?);
+            }
+            Event::Empty(e) if e.name().as_ref() == b"testcase" => {
+                results.push(test_case(&e, &suite)?);
+            }
+            Event::Start(e) | Event::Empty(e) => {
+                let Some(case) = current.as_mut() else {
+                    continue;
+                };
+                match e.name().as_ref() {
+                    b"failure" | b"error" => {
+                        case.outcome = TestOutcome::Failed;
+                        case.message = match attribute(&e, "message")? {
+                            Some(message) => Some(message),
+                            None => attribute(&e, "type")?,
+                        };
+                    }
+                    b"skipped" if case.outcome != TestOutcome::Failed => {
+                        case.outcome = TestOutcome::Skipped;
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(e) if e.name().as_ref() == b"testcase" => {
+                results.extend(current.take());
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(results)
+}
+
+/// Parse an uploaded report: JUnit XML if it looks like XML, the JSON
+/// [`TestReport`] format otherwise.
+pub fn parse_report(body: &[u8]) -> Result<Vec<TestCaseResult>, FlakyTestError> {
+    let text = std::str::from_utf8(body).map_err(invalid)?;
+    if text.trim_start().starts_with('<') {
+        parse_junit(text)
+    } else {
+        let report: TestReport = serde_json::from_str(text).map_err(invalid)?;
+        Ok(report.results)
+    }
+}
+
+fn truncate(text: &str, max: usize) -> &str {
+    match text.char_indices().nth(max) {
+        Some((index, _)) => &text[..index],
+        None => text,
+    }
+}
+
+/// Record a report's results against the repo's tracked tests and quarantine
+/// the ones that now flip too often.
+pub async fn ingest(
+    pool: &SqlitePool,
+    repo_id: Uuid,
+    results: &[TestCaseResult],
+) -> Result<TestReportSummary, FlakyTestError> {
+    let failed_at = Utc::now();
+    let mut summary = TestReportSummary::default();
+    for result in results {
+        let (suite, name) = (result.suite.trim(), result.name.trim());
+        if name.is_empty() {
+            continue;
+        }
+        match result.outcome {
+            TestOutcome::Passed => {
+                FlakyTest::record_pass(pool, repo_id, suite, name).await?;
+                summary.passed += 1;
+            }
+            TestOutcome::Failed => {
+                let message = result
+                    .message
+                    .as_deref()
+                    .map(|message| truncate(message.trim(), MAX_MESSAGE_CHARS));
+                FlakyTest::record_failure(pool, repo_id, suite, name, message, failed_at).await?;
+                summary.failed += 1;
+            }
+            TestOutcome::Skipped => summary.skipped += 1,
+        }
+    }
+    summary.newly_quarantined =
+        FlakyTest::quarantine_flipping(pool, repo_id, QUARANTINE_FLIPS).await? as usize;
+    Ok(summary)
+}
+
+/// The test an agent should fix: the given one, or the flakiest quarantined
+/// test nobody is fixing yet.
+pub async fn pick_test_to_fix(
+    pool: &SqlitePool,
+    repo_id: Uuid,
+    test_id: Option<Uuid>,
+) -> Result<FlakyTest, FlakyTestError> {
+    let test = match test_id {
+        Some(id) => FlakyTest::find_by_id(pool, id)
+            .await?
+            .filter(|test| test.repo_id == repo_id)
+            .ok_or(FlakyTestModelError::NotFound)?,
+        None => FlakyTest::find_flakiest_unfixed(pool, repo_id)
+            .await?
+            .ok_or(FlakyTestError::NothingToFix)?,
+    };
+    Ok(test)
+}
+
+pub fn build_fix_prompt(test: &FlakyTest) -> String {
+    let mut prompt = format!(
+        "The test `{}` in `{}` is flaky: across recent CI runs it passed {} times and failed \
+         {} times, changing outcome {} times.\n",
+        test.name, test.suite, test.passes, test.failures, test.flips
+    );
+    if let Some(message) = &test.last_failure_message {
+        prompt.push_str(&format!("\nLast failure:\n```\n{message}\n```\n"));
+    }
+    prompt.push_str(
+        "\nFind the source of the nondeterminism, such as timing, ordering, shared state or \
+         reliance on the environment, and fix it in the test or the code under test. Don't \
+         add retries, longer timeouts or skips to hide it. Finish with a short explanation of \
+         the cause and the fix; it becomes the pull request description.",
+    );
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_junit_outcomes() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites>
+  <testsuite name="api">
+    <testcase classname="api.users" name="creates_user" time="0.1"/>
+    <testcase classname="api.users" name="deletes_user">
+      <failure message="expected 204, got 500" type="AssertionError">stack</failure>
+    </testcase>
+    <testcase name="lists &amp; pages">
+      <skipped/>
+    </testcase>
+    <testcase classname="api.auth" name="expires_token">
+      <error type="TimeoutError"/>
+    </testcase>
+  </testsuite>
+</testsuites>"#;
+        let results = parse_junit(xml).unwrap();
+        let summary: Vec<_> = results
+            .iter()
+            .map(|r| {
+                (
+                    r.suite.as_str(),
+                    r.name.as_str(),
+                    r.outcome,
+                    r.message.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("api.users", "creates_user", TestOutcome::Passed, None),
+                (
+                    "api.users",
+                    "deletes_user",
+                    TestOutcome::Failed,
+                    Some("expected 204, got 500")
+                ),
+                ("api", "lists & pages", TestOutcome::Skipped, None),
+                (
+                    "api.auth",
+                    "expires_token",
+                    TestOutcome::Failed,
+                    Some("TimeoutError")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_json_reports_and_rejects_garbage() {
+        let body = br#"{"results": [{"suite": "e2e", "name": "login", "outcome": "failed"}]}"#;
+        assert_eq!(
+            parse_report(body).unwrap(),
+            vec![TestCaseResult {
+                suite: "e2e".to_string(),
+                name: "login".to_string(),
+                outcome: TestOutcome::Failed,
+                message: None,
+            }]
+        );
+        assert!(matches!(
+            parse_report(b"not a report"),
+            Err(FlakyTestError::InvalidReport(_))
+        ));
+        assert!(matches!(
+            parse_report(b"<testsuite><testcase name=\"a\"></testsuite>"),
+            Err(FlakyTestError::InvalidReport(_))
+        ));
+    }
+}
@@ -0,0 +1,245 @@
+//! Git accounts selectable per repo.
+//!
+//! A repo can select one stored credential: an SSH key, a personal access
+//! token or an OAuth token. The selection is registered with
+//! [`git::credentials`], so fetches and pushes of the repo and its worktrees,
+//! and the host CLIs used for PR operations, authenticate as that account
+//! instead of the user's default git identity. Tokens are encrypted with the
+//! same machine key as [`crate::services::secrets`].
+
+use std::path::{Path, PathBuf};
+
+use db::models::git_credential::{
+    CreateGitCredential, GitCredential, GitCredentialKind, GitCredentialSummary,
+};
+use git::credentials::{self as git_credentials, GitCredential as RegisteredCredential};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::secrets::{self, SecretError};
+
+#[derive(Debug, Error)]
+pub enum GitCredentialError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Secret(#[from] SecretError),
+    #[error("Git credential not found")]
+    NotFound,
+    #[error("A git credential with this name already exists")]
+    DuplicateName,
+    #[error("Invalid git credential: {0}")]
+    Invalid(String),
+}
+
+pub async fn list_credentials(
+    pool: &SqlitePool,
+) -> Result<Vec<GitCredentialSummary>, GitCredentialError> {
+    Ok(GitCredential::list(pool).await?)
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+/// Check that a new credential has what its kind needs.
+fn validate(data: &CreateGitCredential) -> Result<(), GitCredentialError> {
+    if data.name.trim().is_empty() {
+        return Err(GitCredentialError::Invalid("name is required".to_string()));
+    }
+    if data.host.trim().is_empty() || data.host.contains(['/', ' ']) {
+        return Err(GitCredentialError::Invalid(
+            "host must be a hostname such as github.com".to_string(),
+        ));
+    }
+    match data.kind {
+        GitCredentialKind::SshKey => {
+            let path = non_empty(data.ssh_key_path.as_deref()).ok_or_else(|| {
+                GitCredentialError::Invalid("SSH keys need the path of the private key".to_string())
+            })?;
+            if !Path::new(path).is_file() {
+                return Err(GitCredentialError::Invalid(format!(
+                    "no private key found at {path}"
+                )));
+            }
+            if non_empty(data.token.as_deref()).is_some() {
+                return Err(GitCredentialError::Invalid(
+                    "SSH keys don't take a token".to_string(),
+                ));
+            }
+        }
+        GitCredentialKind::PersonalAccessToken | GitCredentialKind::OauthToken => {
+            if non_empty(data.token.as_deref()).is_none() {
+                return Err(GitCredentialError::Invalid(
+                    "tokens can't be empty".to_string(),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+pub async fn create_credential(
+    pool: &SqlitePool,
+    data: &CreateGitCredential,
+) -> Result<GitCredentialSummary, GitCredentialError> {
+    validate(data)?;
+    let encrypted = non_empty(data.token.as_deref())
+        .map(secrets::encrypt_value)
+        .transpose()?;
+    let (nonce, ciphertext) = match &encrypted {
+        Some((nonce, ciphertext)) => (Some(nonce.as_slice()), Some(ciphertext.as_slice())),
+        None => (None, None),
+    };
+
+    GitCredential::create(pool, data, nonce, ciphertext)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                GitCredentialError::DuplicateName
+            }
+            _ => GitCredentialError::Database(e),
+        })
+}
+
+/// Delete a credential. Repos that selected it fall back to the default git
+/// identity.
+pub async fn delete_credential(pool: &SqlitePool, id: Uuid) -> Result<(), GitCredentialError> {
+    let selections = GitCredential::find_repo_selections(pool, Some(id)).await?;
+    if GitCredential::delete(pool, id).await? == 0 {
+        return Err(GitCredentialError::NotFound);
+    }
+    for selection in selections {
+        git_credentials::set_repo_credential(Path::new(&selection.repo_path), None);
+    }
+    Ok(())
+}
+
+/// Select the credential `repo_path` uses, or clear the selection with `None`.
+pub async fn select_repo_credential(
+    pool: &SqlitePool,
+    repo_id: Uuid,
+    repo_path: &Path,
+    credential_id: Option<Uuid>,
+) -> Result<Option<GitCredentialSummary>, GitCredentialError> {
+    let registered = match credential_id {
+        Some(id) => {
+            let credential = GitCredential::find_by_id(pool, id)
+                .await?
+                .ok_or(GitCredentialError::NotFound)?;
+            Some(to_registered(&credential)?)
+        }
+        None => None,
+    };
+
+    GitCredential::select_for_repo(pool, repo_id, credential_id).await?;
+    git_credentials::set_repo_credential(repo_path, registered);
+    Ok(GitCredential::find_for_repo(pool, repo_id).await?)
+}
+
+/// Register the credentials every repo selected, so network operations use
+/// them from startup on. Credentials that can't be decrypted are skipped.
+pub async fn register_repo_credentials(pool: &SqlitePool) -> Result<(), GitCredentialError> {
+    for selection in GitCredential::find_repo_selections(pool, None).await? {
+        let Some(credential) = GitCredential::find_by_id(pool, selection.git_credential_id).await?
+        else {
+            continue;
+        };
+        match to_registered(&credential) {
+            Ok(registered) => git_credentials::set_repo_credential(
+                &PathBuf::from(&selection.repo_path),
+                Some(registered),
+            ),
+            Err(e) => tracing::warn!(
+                "Skipping git credential '{}' of repo {}: {}",
+                credential.name,
+                selection.repo_id,
+                e
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// The username git sends with a token. GitHub ignores it; GitLab expects
+/// `oauth2` for OAuth tokens.
+fn default_token_username(host: &str, kind: GitCredentialKind) -> &'static str {
+    if host.contains("gitlab") && kind == GitCredentialKind::OauthToken {
+        "oauth2"
+    } else {
+        "x-access-token"
+    }
+}
+
+fn to_registered(credential: &GitCredential) -> Result<RegisteredCredential, GitCredentialError> {
+    match credential.kind {
+        GitCredentialKind::SshKey => {
+            let path = credential.ssh_key_path.as_deref().ok_or_else(|| {
+                GitCredentialError::Invalid(format!("{} has no SSH key path", credential.name))
+            })?;
+            Ok(RegisteredCredential::SshKey {
+                private_key_path: PathBuf::from(path),
+            })
+        }
+        GitCredentialKind::PersonalAccessToken | GitCredentialKind::OauthToken => {
+            let (Some(nonce), Some(ciphertext)) = (&credential.nonce, &credential.ciphertext)
+            else {
+                return Err(GitCredentialError::Invalid(format!(
+                    "{} has no token",
+                    credential.name
+                )));
+            };
+            let token = secrets::decrypt_value(nonce, ciphertext, &credential.name)?;
+            let username = non_empty(credential.username.as_deref())
+                .unwrap_or(default_token_username(&credential.host, credential.kind))
+                .to_string();
+            Ok(RegisteredCredential::Token { username, token })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token_credential(kind: GitCredentialKind, token: Option<&str>) -> CreateGitCredential {
+        CreateGitCredential {
+            name: "work".to_string(),
+            host: "gitlab.example.com".to_string(),
+            kind,
+            username: None,
+            ssh_key_path: None,
+            token: token.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn validates_what_each_kind_needs() {
+        assert!(validate(&token_credential(GitCredentialKind::OauthToken, Some("t"))).is_ok());
+        assert!(
+            validate(&token_credential(
+                GitCredentialKind::PersonalAccessToken,
+                Some(" ")
+            ))
+            .is_err()
+        );
+        assert!(validate(&token_credential(GitCredentialKind::SshKey, None)).is_err());
+
+        let mut bad_host = token_credential(GitCredentialKind::OauthToken, Some("t"));
+        bad_host.host = "https://gitlab.example.com/".to_string();
+        assert!(validate(&bad_host).is_err());
+    }
+
+    #[test]
+    fn picks_the_username_hosts_expect_for_tokens() {
+        assert_eq!(
+            default_token_username("gitlab.com", GitCredentialKind::OauthToken),
+            "oauth2"
+        );
+        assert_eq!(
+            default_token_username("github.com", GitCredentialKind::OauthToken),
+            "x-access-token"
+        );
+    }
+}
@@ -0,0 +1,323 @@
+//! Full-text search over execution logs.
+//!
+//! The index lives in SQLite (FTS5) and is maintained incrementally by the
+//! raw-log writer: every stdout/stderr line appended to an execution's JSONL
+//! file is also queued here with its line number and byte offset. Logs written
+//! before the index existed are picked up by [`backfill_log_search_index`].
+
+use std::{path::Path, time::Duration};
+
+use db::{
+    DBService,
+    models::{
+        execution_log_search::{ExecutionLogSearch, IndexedLogLine},
+        execution_process::ExecutionProcessRunReason,
+    },
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tokio::io::{AsyncBufReadExt, AsyncSeekExt, BufReader};
+use ts_rs::TS;
+use utils::{execution_logs::process_log_file_path, log_msg::LogMsg};
+use uuid::Uuid;
+
+/// Upper bound on matching lines returned by a single search.
+pub const LOG_SEARCH_MAX_RESULTS: i64 = 200;
+
+/// Number of queued lines that triggers a write to the index.
+const INDEX_FLUSH_LINES: usize = 256;
+
+/// Maximum delay before lines from a quiet process become searchable.
+pub const INDEX_FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Executions processed per backfill batch.
+const BACKFILL_BATCH_SIZE: i64 = 50;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct LogSearchMatch {
+    pub line_number: i64,
+    pub byte_offset: i64,
+    pub stream: String,
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct LogSearchResult {
+    pub execution_id: Uuid,
+    pub session_id: Uuid,
+    pub workspace_id: Uuid,
+    pub run_reason: ExecutionProcessRunReason,
+    pub matches: Vec<LogSearchMatch>,
+}
+
+/// Tracks the write position of one execution's log file and batches lines
+/// into the search index.
+pub struct LogSearchIndexer {
+    db: DBService,
+    execution_id: Uuid,
+    next_line: i64,
+    next_offset: i64,
+    /// Line count last written to the index state, if any.
+    persisted_lines: Option<i64>,
+    pending: Vec<IndexedLogLine>,
+}
+
+impl LogSearchIndexer {
+    /// Resume from the persisted index state, first indexing any part of the
+    /// log file that was written but never indexed.
+    pub async fn resume(db: DBService, execution_id: Uuid, log_path: &Path) -> Self {
+        let state = ExecutionLogSearch::find_state(&db.pool, execution_id)
+            .await
+            .inspect_err(|e| {
+                tracing::warn!(
+                    "Failed to load log index state for execution {}: {}",
+                    execution_id,
+                    e
+                );
+            })
+            .ok()
+            .flatten();
+
+        let mut indexer = Self {
+            db,
+            execution_id,
+            next_line: state.map(|s| s.indexed_lines).unwrap_or(0),
+            next_offset: state.map(|s| s.indexed_bytes).unwrap_or(0),
+            persisted_lines: state.map(|s| s.indexed_lines),
+            pending: Vec::new(),
+        };
+
+        if let Err(e) = indexer.catch_up_from_file(log_path).await {
+            tracing::warn!(
+                "Failed to index existing log file for execution {}: {}",
+                execution_id,
+                e
+            );
+        }
+
+        indexer
+    }
+
+    async fn catch_up_from_file(&mut self, log_path: &Path) -> std::io::Result<()> {
+        let mut file = match tokio::fs::File::open(log_path).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        if file.metadata().await?.len() <= self.next_offset as u64 {
+            return Ok(());
+        }
+
+        file.seek(std::io::SeekFrom::Start(self.next_offset as u64))
+            .await?;
+        let mut reader = BufReader::new(file);
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).await? == 0 {
+                break;
+            }
+            // A trailing partial line is still being written; leave it for the
+            // live writer.
+            if !line.ends_with('\n') {
+                break;
+            }
+            match serde_json::from_str::<LogMsg>(line.trim_end()) {
+                Ok(msg) => self.push(&msg, line.len()),
+                Err(_) => self.skip(line.len()),
+            }
+            if self.should_flush() {
+                self.flush().await;
+            }
+        }
+        self.flush().await;
+        Ok(())
+    }
+
+    /// Record a line that was just appended to the log file. `line_len` is the
+    /// number of bytes written, including the trailing newline.
+    pub fn push(&mut self, msg: &LogMsg, line_len: usize) {
+        let (stream, content) = match msg {
+            LogMsg::Stdout(content) => ("stdout", content),
+            LogMsg::Stderr(content) => ("stderr", content),
+            _ => {
+                self.skip(line_len);
+                return;
+            }
+        };
+
+        let content = strip_ansi_escapes::strip_str(content);
+        if !content.trim().is_empty() {
+            self.pending.push(IndexedLogLine {
+                line_number: self.next_line,
+                byte_offset: self.next_offset,
+                stream: stream.to_string(),
+                content,
+            });
+        }
+        self.skip(line_len);
+    }
+
+    fn skip(&mut self, line_len: usize) {
+        self.next_line += 1;
+        self.next_offset += line_len as i64;
+    }
+
+    pub fn should_flush(&self) -> bool {
+        self.pending.len() >= INDEX_FLUSH_LINES
+    }
+
+    /// Write queued lines to the index and advance the persisted position.
+    /// Indexing is best-effort: failures are logged and the batch is dropped
+    /// rather than blocking log persistence. Returns whether the write
+    /// succeeded (or there was nothing to write).
+    pub async fn flush(&mut self) -> bool {
+        if self.pending.is_empty() && self.persisted_lines == Some(self.next_line) {
+            return true;
+        }
+
+        let lines = std::mem::take(&mut self.pending);
+        let write = ExecutionLogSearch::append_lines(
+            &self.db.pool,
+            self.execution_id,
+            &lines,
+            self.next_line,
+            self.next_offset,
+        );
+        match self.db.write("execution_log_search", write).await {
+            Ok(()) => {
+                self.persisted_lines = Some(self.next_line);
+                true
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to index {} log lines for execution {}: {}",
+                    lines.len(),
+                    self.execution_id,
+                    e
+                );
+                false
+            }
+        }
+    }
+}
+
+/// Turn free-form user input into an FTS5 query that matches all terms.
+///
+/// Each whitespace-separated term is quoted so FTS5 operators and punctuation
+/// in the input (`-`, `:`, `*`, `"`, ...) are treated literally.
+pub fn build_match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Search indexed logs, grouping matching lines by execution in relevance
+/// order of each execution's best match.
+pub async fn search_logs(
+    pool: &SqlitePool,
+    query: &str,
+    workspace_id: Option<Uuid>,
+    limit: i64,
+) -> Result<Vec<LogSearchResult>, sqlx::Error> {
+    let Some(match_expr) = build_match_expression(query) else {
+        return Ok(Vec::new());
+    };
+    let limit = limit.clamp(1, LOG_SEARCH_MAX_RESULTS);
+    let hits = ExecutionLogSearch::search(pool, &match_expr, workspace_id, limit).await?;
+
+    let mut results: Vec<LogSearchResult> = Vec::new();
+    for hit in hits {
+        let log_match = LogSearchMatch {
+            line_number: hit.line_number,
+            byte_offset: hit.byte_offset,
+            stream: hit.stream,
+            snippet: hit.snippet,
+        };
+        match results
+            .iter_mut()
+            .find(|result| result.execution_id == hit.execution_id)
+        {
+            Some(result) => result.matches.push(log_match),
+            None => results.push(LogSearchResult {
+                execution_id: hit.execution_id,
+                session_id: hit.session_id,
+                workspace_id: hit.workspace_id,
+                run_reason: hit.run_reason,
+                matches: vec![log_match],
+            }),
+        }
+    }
+
+    for result in &mut results {
+        result.matches.sort_by_key(|m| m.line_number);
+    }
+
+    Ok(results)
+}
+
+/// Index log files written before the search index existed and drop index
+/// rows for deleted executions. Intended to run once in the background at
+/// startup.
+pub async fn backfill_log_search_index(db: DBService) {
+    match ExecutionLogSearch::delete_orphaned(&db.pool).await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!("Removed {} orphaned log search rows", count),
+        Err(e) => tracing::warn!("Failed to remove orphaned log search rows: {}", e),
+    }
+
+    let mut indexed = 0usize;
+    loop {
+        let batch =
+            match ExecutionLogSearch::find_unindexed_processes(&db.pool, BACKFILL_BATCH_SIZE).await
+            {
+                Ok(batch) => batch,
+                Err(e) => {
+                    tracing::warn!("Failed to list unindexed execution logs: {}", e);
+                    return;
+                }
+            };
+        if batch.is_empty() {
+            break;
+        }
+
+        for (execution_id, session_id) in batch {
+            let path = process_log_file_path(session_id, execution_id);
+            let mut indexer = LogSearchIndexer::resume(db.clone(), execution_id, &path).await;
+            // Persist state even for executions without a log file so they are
+            // not revisited on every startup.
+            if !indexer.flush().await {
+                return;
+            }
+            indexed += 1;
+        }
+    }
+
+    if indexed > 0 {
+        tracing::info!("Indexed logs for {} executions", indexed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_match_expression;
+
+    #[test]
+    fn build_match_expression_quotes_each_term() {
+        assert_eq!(
+            build_match_expression("  error   code:42 "),
+            Some("\"error\" \"code:42\"".to_string())
+        );
+        assert_eq!(
+            build_match_expression("say \"hi\""),
+            Some("\"say\" \"\"\"hi\"\"\"".to_string())
+        );
+        assert_eq!(build_match_expression(" \t "), None);
+    }
+}
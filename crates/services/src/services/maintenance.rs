@@ -0,0 +1,125 @@
+//! Server-wide maintenance mode.
+//!
+//! Turned on around backups and disk migrations so nothing writes to the
+//! database or worktrees meanwhile. While it is on, the API rejects mutating
+//! requests with 503 and the reason, and the container refuses to start
+//! executions; reads such as logs and diffs keep working.
+
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+pub struct MaintenanceStatus {
+    pub enabled: bool,
+    pub reason: Option<String>,
+    #[ts(type = "Date | null")]
+    pub since: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+struct ActiveMaintenance {
+    reason: String,
+    since: DateTime<Utc>,
+}
+
+#[derive(Clone, Default)]
+pub struct MaintenanceMode {
+    active: Arc<RwLock<Option<ActiveMaintenance>>>,
+}
+
+/// Ends the maintenance started by [`MaintenanceMode::begin`] when dropped.
+#[must_use]
+pub struct MaintenanceGuard {
+    mode: Option<MaintenanceMode>,
+}
+
+impl Drop for MaintenanceGuard {
+    fn drop(&mut self) {
+        if let Some(mode) = self.mode.take() {
+            mode.disable();
+        }
+    }
+}
+
+impl MaintenanceMode {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn enable(&self, reason: impl Into<String>) {
+        let reason = reason.into();
+        tracing::info!("Entering maintenance mode: {}", reason);
+        *self.write() = Some(ActiveMaintenance {
+            reason,
+            since: Utc::now(),
+        });
+    }
+
+    pub fn disable(&self) {
+        if self.write().take().is_some() {
+            tracing::info!("Leaving maintenance mode");
+        }
+    }
+
+    /// Enable maintenance mode until the returned guard is dropped. Leaves it
+    /// alone if it is already on, so an operator's maintenance outlasts it.
+    pub fn begin(&self, reason: impl Into<String>) -> MaintenanceGuard {
+        if self.reason().is_some() {
+            return MaintenanceGuard { mode: None };
+        }
+        self.enable(reason);
+        MaintenanceGuard {
+            mode: Some(self.clone()),
+        }
+    }
+
+    /// Why maintenance mode is on, or `None` if it is off.
+    pub fn reason(&self) -> Option<String> {
+        self.read().as_ref().map(|active| active.reason.clone())
+    }
+
+    pub fn status(&self) -> MaintenanceStatus {
+        let active = self.read();
+        MaintenanceStatus {
+            enabled: active.is_some(),
+            reason: active.as_ref().map(|active| active.reason.clone()),
+            since: active.as_ref().map(|active| active.since),
+        }
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Option<ActiveMaintenance>> {
+        self.active
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Option<ActiveMaintenance>> {
+        self.active
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn begin_keeps_maintenance_that_was_already_on() {
+        let mode = MaintenanceMode::new();
+        drop(mode.begin("backup"));
+        assert!(!mode.status().enabled);
+
+        mode.enable("disk migration");
+        drop(mode.begin("backup"));
+        let status = mode.status();
+        assert!(status.enabled);
+        assert_eq!(status.reason.as_deref(), Some("disk migration"));
+
+        mode.disable();
+        assert_eq!(mode.reason(), None);
+    }
+}
@@ -0,0 +1,186 @@
+//! Ahead/behind counts and merge readiness of workspace branches.
+//!
+//! Computed from the repos' refs only, without fetching or touching worktrees,
+//! so it is cheap enough to refresh every active workspace in the background.
+//! Changes are published over the events stream under `/merge_readiness` to
+//! power board badges.
+
+use std::{collections::HashMap, path::Path, sync::Arc, time::Duration};
+
+use db::{
+    DBService,
+    models::{
+        workspace::{Workspace, WorkspaceError},
+        workspace_repo::WorkspaceRepo,
+    },
+};
+use git::GitService;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tokio::{sync::Mutex, time::interval};
+use ts_rs::TS;
+use utils::msg_store::MsgStore;
+use uuid::Uuid;
+
+use crate::services::events::patches::merge_readiness_patch;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum MergeReadinessState {
+    /// The branch has no commits the target branch lacks.
+    UpToDate,
+    Clean,
+    Conflicts,
+    /// The branches couldn't be compared, e.g. one of them is missing.
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct RepoMergeReadiness {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub target_branch: String,
+    pub state: MergeReadinessState,
+    pub commits_ahead: usize,
+    pub commits_behind: usize,
+    /// Files that would conflict when merging into the target branch.
+    pub conflicted_files: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct WorkspaceMergeReadiness {
+    pub workspace_id: Uuid,
+    pub repos: Vec<RepoMergeReadiness>,
+}
+
+fn repo_readiness(
+    git: &GitService,
+    repo_id: Uuid,
+    repo_name: &str,
+    repo_path: &Path,
+    branch: &str,
+    target_branch: &str,
+) -> RepoMergeReadiness {
+    let mut readiness = RepoMergeReadiness {
+        repo_id,
+        repo_name: repo_name.to_string(),
+        target_branch: target_branch.to_string(),
+        state: MergeReadinessState::Unknown,
+        commits_ahead: 0,
+        commits_behind: 0,
+        conflicted_files: Vec::new(),
+    };
+
+    let (ahead, behind) = match git.get_branch_status(repo_path, branch, target_branch) {
+        Ok(counts) => counts,
+        Err(e) => {
+            tracing::debug!("Can't compare {} with {}: {}", branch, target_branch, e);
+            return readiness;
+        }
+    };
+    readiness.commits_ahead = ahead;
+    readiness.commits_behind = behind;
+    if ahead == 0 {
+        readiness.state = MergeReadinessState::UpToDate;
+        return readiness;
+    }
+
+    match git.merge_conflicts(repo_path, branch, target_branch) {
+        Ok(files) if files.is_empty() => readiness.state = MergeReadinessState::Clean,
+        Ok(files) => {
+            readiness.state = MergeReadinessState::Conflicts;
+            readiness.conflicted_files = files;
+        }
+        Err(e) => tracing::debug!("Can't merge {} into {}: {}", branch, target_branch, e),
+    }
+    readiness
+}
+
+/// Compare the workspace branch with the target branch of each of its repos.
+pub async fn workspace_readiness(
+    pool: &SqlitePool,
+    git: &GitService,
+    workspace: &Workspace,
+) -> Result<WorkspaceMergeReadiness, sqlx::Error> {
+    let repos = WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id)
+        .await?
+        .into_iter()
+        .map(|r| {
+            repo_readiness(
+                git,
+                r.repo.id,
+                &r.repo.name,
+                &r.repo.path,
+                &workspace.branch,
+                &r.target_branch,
+            )
+        })
+        .collect();
+
+    Ok(WorkspaceMergeReadiness {
+        workspace_id: workspace.id,
+        repos,
+    })
+}
+
+/// Refreshes the merge readiness of active workspaces and publishes what
+/// changed since the last refresh.
+pub struct MergeReadinessService {
+    db: DBService,
+    git: GitService,
+    msg_store: Arc<MsgStore>,
+    last_published: Mutex<HashMap<Uuid, WorkspaceMergeReadiness>>,
+}
+
+impl MergeReadinessService {
+    pub fn spawn(
+        db: DBService,
+        git: GitService,
+        msg_store: Arc<MsgStore>,
+    ) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            git,
+            msg_store,
+            last_published: Mutex::new(HashMap::new()),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        let mut interval = interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.refresh_all().await {
+                tracing::error!("Error refreshing merge readiness: {}", e);
+            }
+        }
+    }
+
+    async fn refresh_all(&self) -> Result<(), WorkspaceError> {
+        let workspaces: Vec<Workspace> = Workspace::fetch_all(&self.db.pool)
+            .await?
+            .into_iter()
+            .filter(|workspace| !workspace.archived)
+            .collect();
+
+        let mut last_published = self.last_published.lock().await;
+        last_published.retain(|id, _| workspaces.iter().any(|w| w.id == *id));
+
+        for workspace in &workspaces {
+            let readiness = workspace_readiness(&self.db.pool, &self.git, workspace).await?;
+            if last_published.get(&workspace.id) == Some(&readiness) {
+                continue;
+            }
+            self.msg_store
+                .push_patch(merge_readiness_patch::set(&readiness));
+            last_published.insert(workspace.id, readiness);
+        }
+        Ok(())
+    }
+}
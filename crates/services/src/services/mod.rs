@@ -1,19 +1,38 @@
 pub mod analytics;
 pub mod approvals;
 pub mod auth;
+pub mod backup;
+pub mod ci_triage;
+pub mod code_owners;
+pub mod code_search;
+pub mod completion_webhook;
 pub mod config;
+pub mod config_bundle;
+pub mod config_overrides;
 pub mod container;
+pub mod dependency_updates;
+pub mod dev_server;
 pub mod diff_stream;
+pub mod env_profiles;
 pub mod events;
 pub mod execution_process;
 pub mod file;
 pub mod file_ranker;
 pub mod file_search;
+pub mod file_tree;
 pub mod filesystem;
 pub mod filesystem_watcher;
+pub mod flaky_tests;
+pub mod git_credentials;
+pub mod log_redaction;
+pub mod log_search;
+pub mod maintenance;
+pub mod merge_readiness;
 pub mod notification;
 pub mod oauth_credentials;
+pub mod pipelines;
 pub mod pr_monitor;
+pub mod push_preflight;
 
 #[cfg(feature = "qa-mode")]
 pub mod qa_repos;
@@ -21,3 +40,23 @@ pub mod queued_message;
 pub mod remote_client;
 pub mod remote_sync;
 pub mod repo;
+pub mod repo_analyzer;
+pub mod repo_index;
+pub mod repo_knowledge;
+pub mod schedules;
+pub mod secrets;
+pub mod semantic_search;
+pub mod session_checkpoints;
+pub mod session_fork;
+pub mod session_shares;
+pub mod session_transcript;
+pub mod shared_caches;
+pub mod startup_tasks;
+pub mod test_results;
+pub mod users;
+pub mod warm_pool;
+pub mod webhook_triggers;
+pub mod workspace_context;
+pub mod workspace_export;
+pub mod workspace_files;
+pub mod workspace_undo;
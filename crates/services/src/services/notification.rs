@@ -1,11 +1,18 @@
 use std::sync::{Arc, OnceLock};
 
 use async_trait::async_trait;
-use tokio::sync::RwLock;
+use chrono::{Duration, Local, NaiveDateTime};
+use tokio::{
+    io::AsyncWriteExt,
+    sync::{Mutex, RwLock},
+};
 use utils::{self, command_ext::NoWindowExt};
 use uuid::Uuid;
 
-use crate::services::config::{Config, SoundFile};
+use crate::services::config::{
+    Config, NotificationChannel, NotificationChannelsConfig, NotificationConfig, NotificationEvent,
+    QuietHoursConfig, SoundFile,
+};
 
 /// Trait for sending push notifications. Implementations can use
 /// platform-specific OS commands, Tauri's notification plugin, etc.
@@ -53,11 +60,22 @@ impl PushNotifier for DefaultPushNotifier {
     }
 }
 
-/// Service for handling cross-platform notifications including sound alerts and push notifications
+/// Service for sending notifications over desktop, sound, webhook, Slack and
+/// email channels, routed per event type
 #[derive(Clone)]
 pub struct NotificationService {
     config: Arc<RwLock<Config>>,
     push_notifier: Arc<dyn PushNotifier>,
+    /// Notifications held back during quiet hours.
+    held: Arc<Mutex<Vec<HeldNotification>>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HeldNotification {
+    event: NotificationEvent,
+    title: String,
+    message: String,
+    workspace_id: Option<Uuid>,
 }
 
 impl std::fmt::Debug for NotificationService {
@@ -73,21 +91,200 @@ impl NotificationService {
         Self {
             config,
             push_notifier: get_global_push_notifier(),
+            held: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
-    /// Send both sound and push notifications if enabled.
-    /// `workspace_id` is forwarded to the push notifier so Tauri can emit a
-    /// navigation event when the notification is clicked.
-    pub async fn notify(&self, title: &str, message: &str, workspace_id: Option<Uuid>) {
-        let config = self.config.read().await.notifications.clone();
+    /// Send a notification to the channels `event` is routed to, or hold it
+    /// until quiet hours end. `workspace_id` is forwarded to the push
+    /// notifier so Tauri can emit a navigation event when the notification
+    /// is clicked.
+    pub async fn notify(
+        &self,
+        event: NotificationEvent,
+        title: &str,
+        message: &str,
+        workspace_id: Option<Uuid>,
+    ) {
+        let quiet_hours = self.config.read().await.quiet_hours.clone();
+        let now = Local::now().naive_local();
+        if let Some(quiet_until) = quiet_hours_end(&quiet_hours, now) {
+            self.hold(
+                HeldNotification {
+                    event,
+                    title: title.to_string(),
+                    message: message.to_string(),
+                    workspace_id,
+                },
+                quiet_until - now,
+            )
+            .await;
+            return;
+        }
+
+        // Quiet hours may have been switched off before the scheduled flush.
+        self.release_held().await;
+        self.deliver(event, title, message, workspace_id).await;
+    }
 
-        if config.sound_enabled {
-            Self::play_sound_notification(&config.sound_file).await;
+    /// Queue a notification; the first one held schedules the summary for
+    /// when quiet hours end.
+    async fn hold(&self, notification: HeldNotification, remaining: Duration) {
+        let mut held = self.held.lock().await;
+        held.push(notification);
+        if held.len() > 1 {
+            return;
         }
+        let service = self.clone();
+        let delay = remaining.to_std().unwrap_or_default();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            service.release_held().await;
+        });
+    }
+
+    /// Deliver the notifications held during quiet hours, summarized per
+    /// event type.
+    async fn release_held(&self) {
+        let held = std::mem::take(&mut *self.held.lock().await);
+        for summary in summarize_held(held) {
+            self.deliver(
+                summary.event,
+                &summary.title,
+                &summary.message,
+                summary.workspace_id,
+            )
+            .await;
+        }
+    }
 
-        if config.push_enabled {
-            self.push_notifier.send(title, message, workspace_id).await;
+    async fn deliver(
+        &self,
+        event: NotificationEvent,
+        title: &str,
+        message: &str,
+        workspace_id: Option<Uuid>,
+    ) {
+        let (config, channels) = {
+            let config = self.config.read().await;
+            (
+                config.notifications.clone(),
+                config.notification_channels.clone(),
+            )
+        };
+
+        for channel in enabled_channels(&config, &channels, event) {
+            match channel {
+                NotificationChannel::Desktop => {
+                    self.push_notifier.send(title, message, workspace_id).await;
+                }
+                NotificationChannel::Sound => {
+                    Self::play_sound_notification(&config.sound_file).await;
+                }
+                NotificationChannel::Webhook => {
+                    let Some(url) = channels.webhook_url.clone() else {
+                        continue;
+                    };
+                    let payload = serde_json::json!({
+                        "event": event,
+                        "title": title,
+                        "message": message,
+                        "workspace_id": workspace_id,
+                    });
+                    tokio::spawn(async move {
+                        if let Err(e) = post_json(&url, &payload).await {
+                            tracing::warn!("Failed to send notification webhook: {}", e);
+                        }
+                    });
+                }
+                NotificationChannel::Slack => {
+                    let Some(url) = channels.slack_webhook_url.clone() else {
+                        continue;
+                    };
+                    let payload = serde_json::json!({ "text": format!("*{title}*\n{message}") });
+                    tokio::spawn(async move {
+                        if let Err(e) = post_json(&url, &payload).await {
+                            tracing::warn!("Failed to send notification to Slack: {}", e);
+                        }
+                    });
+                }
+                NotificationChannel::Email => {
+                    let Some(email_to) = channels.email_to.clone() else {
+                        continue;
+                    };
+                    let mail = format_mail(&email_to, title, message);
+                    tokio::spawn(async move {
+                        if let Err(e) = send_mail(&mail).await {
+                            tracing::warn!("Failed to email notification: {}", e);
+                        }
+                    });
+                }
+            }
+        }
+    }
+
+    /// Base URL for approval links, if remote approvals are enabled.
+    pub async fn remote_approval_url(&self) -> Option<String> {
+        let config = self.config.read().await;
+        let remote = &config.remote_approvals;
+        remote
+            .enabled
+            .then(|| remote.public_url.clone())
+            .flatten()
+            .map(|url| url.trim_end_matches('/').to_string())
+    }
+
+    /// Deliver an approval link to the configured Slack webhook and email
+    /// address. Delivery happens in the background; failures are logged.
+    pub async fn send_approval_link(&self, title: &str, message: &str, link: &str) {
+        let remote = self.config.read().await.remote_approvals.clone();
+
+        if let Some(webhook_url) = remote.slack_webhook_url.filter(|url| !url.is_empty()) {
+            let payload = serde_json::json!({
+                "text": format!("*{title}*\n{message}\n<{link}|Approve or deny>")
+            });
+            tokio::spawn(async move {
+                if let Err(e) = post_json(&webhook_url, &payload).await {
+                    tracing::warn!("Failed to send approval link to Slack: {}", e);
+                }
+            });
+        }
+
+        if let Some(email_to) = remote.email_to.filter(|to| !to.is_empty()) {
+            let mail = format_mail(
+                &email_to,
+                title,
+                &format!("{message}\n\nApprove or deny: {link}"),
+            );
+            tokio::spawn(async move {
+                if let Err(e) = send_mail(&mail).await {
+                    tracing::warn!("Failed to email approval link: {}", e);
+                }
+            });
+        }
+    }
+
+    /// Email an approval request to the approvers it is delegated to, with
+    /// the approval link when remote approvals are enabled. Delegated links
+    /// only go to the approvers, never to the shared channels.
+    pub async fn send_to_approvers(
+        &self,
+        emails: &[String],
+        title: &str,
+        message: &str,
+        link: Option<&str>,
+    ) {
+        let body = match link {
+            Some(link) => format!("{message}\n\nApprove or deny: {link}"),
+            None => format!("{message}\n\nOpen Vibe Kanban to approve or deny."),
+        };
+        for email in emails {
+            let mail = format_mail(email, title, &body);
+            tokio::spawn(async move {
+                if let Err(e) = send_mail(&mail).await {
+                    tracing::warn!("Failed to email approval request to an approver: {}", e);
+                }
+            });
         }
     }
 
@@ -151,6 +348,124 @@ impl NotificationService {
     }
 }
 
+/// When the quiet hours window containing `now` ends, or `None` if `now` is
+/// outside it.
+fn quiet_hours_end(quiet_hours: &QuietHoursConfig, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let (start, end) = quiet_hours.window()?;
+    let time = now.time();
+    let inside = if start <= end {
+        start <= time && time < end
+    } else {
+        time >= start || time < end
+    };
+    if !inside {
+        return None;
+    }
+    let end_today = now.date().and_time(end);
+    Some(if time < end {
+        end_today
+    } else {
+        end_today + Duration::days(1)
+    })
+}
+
+/// One notification per event type: the original if only one was held,
+/// otherwise a summary listing their titles.
+fn summarize_held(held: Vec<HeldNotification>) -> Vec<HeldNotification> {
+    let mut groups: Vec<(NotificationEvent, Vec<HeldNotification>)> = Vec::new();
+    for notification in held {
+        match groups
+            .iter_mut()
+            .find(|(event, _)| *event == notification.event)
+        {
+            Some((_, group)) => group.push(notification),
+            None => groups.push((notification.event, vec![notification])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .filter_map(|(event, mut group)| {
+            if group.len() == 1 {
+                return group.pop();
+            }
+            let message = group
+                .iter()
+                .map(|notification| format!("- {}", notification.title))
+                .collect::<Vec<_>>()
+                .join("\n");
+            Some(HeldNotification {
+                event,
+                title: format!("{} notifications during quiet hours", group.len()),
+                message,
+                workspace_id: None,
+            })
+        })
+        .collect()
+}
+
+/// The channels `event` is routed to that are switched on, in route order.
+fn enabled_channels(
+    config: &NotificationConfig,
+    channels: &NotificationChannelsConfig,
+    event: NotificationEvent,
+) -> Vec<NotificationChannel> {
+    let configured = |value: &Option<String>| value.as_deref().is_some_and(|v| !v.is_empty());
+    let route = channels
+        .routes
+        .get(&event)
+        .cloned()
+        .unwrap_or_else(|| vec![NotificationChannel::Desktop, NotificationChannel::Sound]);
+
+    let mut enabled: Vec<NotificationChannel> = Vec::new();
+    for channel in route {
+        let on = match channel {
+            NotificationChannel::Desktop => config.push_enabled,
+            NotificationChannel::Sound => config.sound_enabled,
+            NotificationChannel::Webhook => configured(&channels.webhook_url),
+            NotificationChannel::Slack => configured(&channels.slack_webhook_url),
+            NotificationChannel::Email => configured(&channels.email_to),
+        };
+        if on && !enabled.contains(&channel) {
+            enabled.push(channel);
+        }
+    }
+    enabled
+}
+
+async fn post_json(url: &str, payload: &serde_json::Value) -> reqwest::Result<()> {
+    reqwest::Client::new()
+        .post(url)
+        .json(payload)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+fn format_mail(to: &str, subject: &str, body: &str) -> String {
+    let subject = subject.replace(['\r', '\n'], " ");
+    format!("To: {to}\nSubject: {subject}\nContent-Type: text/plain; charset=utf-8\n\n{body}\n")
+}
+
+/// Hand a complete message (headers and body) to the local `sendmail`.
+async fn send_mail(mail: &str) -> std::io::Result<()> {
+    let mut child = tokio::process::Command::new("sendmail")
+        .arg("-t")
+        .stdin(std::process::Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(mail.as_bytes()).await?;
+    }
+    let status = child.wait().await?;
+    if !status.success() {
+        return Err(std::io::Error::other(format!(
+            "sendmail exited with {status}"
+        )));
+    }
+    Ok(())
+}
+
 // --- Platform-specific push notification helpers (used by DefaultPushNotifier) ---
 
 /// Send macOS notification using osascript
@@ -295,3 +610,102 @@ async fn wsl_to_windows_path(wsl_path: &std::path::Path) -> Option<String> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_events_to_enabled_channels() {
+        let mut config = NotificationConfig::default();
+        let mut channels = NotificationChannelsConfig::default();
+
+        assert_eq!(
+            enabled_channels(&config, &channels, NotificationEvent::ApprovalNeeded),
+            vec![NotificationChannel::Desktop, NotificationChannel::Sound]
+        );
+
+        channels.routes.insert(
+            NotificationEvent::WorkspaceComplete,
+            vec![NotificationChannel::Slack, NotificationChannel::Email],
+        );
+        channels.slack_webhook_url = Some("https://hooks.slack.com/services/x".to_string());
+        assert_eq!(
+            enabled_channels(&config, &channels, NotificationEvent::WorkspaceComplete),
+            vec![NotificationChannel::Slack]
+        );
+
+        config.sound_enabled = false;
+        assert_eq!(
+            enabled_channels(&config, &channels, NotificationEvent::ApprovalNeeded),
+            vec![NotificationChannel::Desktop]
+        );
+    }
+
+    fn at(date: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn quiet_hours_span_midnight() {
+        let quiet_hours = QuietHoursConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            quiet_hours_end(&quiet_hours, at("2026-03-01 23:30")),
+            Some(at("2026-03-02 07:00"))
+        );
+        assert_eq!(
+            quiet_hours_end(&quiet_hours, at("2026-03-02 06:59")),
+            Some(at("2026-03-02 07:00"))
+        );
+        assert_eq!(quiet_hours_end(&quiet_hours, at("2026-03-02 07:00")), None);
+        assert_eq!(quiet_hours_end(&quiet_hours, at("2026-03-02 12:00")), None);
+
+        let daytime = QuietHoursConfig {
+            enabled: true,
+            start: "12:00".to_string(),
+            end: "13:30".to_string(),
+        };
+        assert_eq!(
+            quiet_hours_end(&daytime, at("2026-03-02 12:15")),
+            Some(at("2026-03-02 13:30"))
+        );
+        assert_eq!(quiet_hours_end(&daytime, at("2026-03-02 23:00")), None);
+
+        let disabled = QuietHoursConfig::default();
+        assert_eq!(quiet_hours_end(&disabled, at("2026-03-01 23:30")), None);
+    }
+
+    #[test]
+    fn summarizes_held_notifications_per_event() {
+        let held = |event, title: &str| HeldNotification {
+            event,
+            title: title.to_string(),
+            message: String::new(),
+            workspace_id: Some(Uuid::new_v4()),
+        };
+        let approval = held(NotificationEvent::ApprovalNeeded, "Approval Needed: api");
+        let summaries = summarize_held(vec![
+            held(
+                NotificationEvent::WorkspaceComplete,
+                "Workspace Complete: a",
+            ),
+            approval.clone(),
+            held(
+                NotificationEvent::WorkspaceComplete,
+                "Workspace Complete: b",
+            ),
+        ]);
+
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].title, "2 notifications during quiet hours");
+        assert_eq!(
+            summaries[0].message,
+            "- Workspace Complete: a\n- Workspace Complete: b"
+        );
+        assert_eq!(summaries[0].workspace_id, None);
+        assert_eq!(summaries[1], approval);
+    }
+}
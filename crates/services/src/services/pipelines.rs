@@ -0,0 +1,191 @@
+//! Pipelines: declarative chains of workspaces.
+//!
+//! A pipeline is an ordered list of stages, each a prompt template. Running a
+//! pipeline starts the first stage in a new workspace. When that workspace's
+//! action chain ends (the same point at which `try_start_next_action` runs out
+//! of actions and the task is finalized), the outcome is recorded against the
+//! stage and the orchestrator is woken to start the next stage in a new
+//! workspace branched from the previous one. Workspace creation goes through
+//! the deployment, so the orchestrator loop itself lives in the server.
+
+use std::collections::HashMap;
+
+use db::models::pipeline::{
+    CreatePipeline, Pipeline, PipelineError as PipelineModelError, PipelineRunStage, PipelineStage,
+    PipelineStatus, UpdatePipeline,
+};
+use once_cell::sync::Lazy;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+const MAX_PIPELINE_STAGES: usize = 16;
+
+static STAGE_FINISHED: Lazy<Notify> = Lazy::new(Notify::new);
+
+#[derive(Debug, Error)]
+pub enum PipelineError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Pipeline(#[from] PipelineModelError),
+    #[error("Pipeline name must not be empty")]
+    InvalidName,
+    #[error("A pipeline needs between 1 and {} stages", MAX_PIPELINE_STAGES)]
+    InvalidStageCount,
+    #[error("Stage {0} needs a name and a prompt")]
+    InvalidStage(usize),
+}
+
+fn validate(name: &str, stages: &[PipelineStage]) -> Result<(), PipelineError> {
+    if name.trim().is_empty() {
+        return Err(PipelineError::InvalidName);
+    }
+    if stages.is_empty() || stages.len() > MAX_PIPELINE_STAGES {
+        return Err(PipelineError::InvalidStageCount);
+    }
+    for (index, stage) in stages.iter().enumerate() {
+        if stage.name.trim().is_empty() || stage.prompt.trim().is_empty() {
+            return Err(PipelineError::InvalidStage(index));
+        }
+    }
+    Ok(())
+}
+
+pub async fn create_pipeline(
+    pool: &SqlitePool,
+    data: &CreatePipeline,
+) -> Result<Pipeline, PipelineError> {
+    validate(&data.name, &data.stages)?;
+    Ok(Pipeline::create(pool, data).await?)
+}
+
+pub async fn update_pipeline(
+    pool: &SqlitePool,
+    id: Uuid,
+    data: &UpdatePipeline,
+) -> Result<Pipeline, PipelineError> {
+    let existing = Pipeline::find_by_id(pool, id)
+        .await?
+        .ok_or(PipelineModelError::NotFound)?;
+    validate(
+        data.name.as_deref().unwrap_or(&existing.name),
+        data.stages.as_deref().unwrap_or(&existing.stages),
+    )?;
+    Ok(Pipeline::update(pool, id, data).await?)
+}
+
+/// Values available to a stage's prompt template.
+pub struct StageContext<'a> {
+    pub pipeline: &'a str,
+    pub stage: &'a str,
+    pub prompt: &'a str,
+    pub previous_branch: Option<&'a str>,
+    pub previous_workspace: Option<&'a str>,
+}
+
+/// Fill `{{name}}` placeholders in a stage prompt. Unknown placeholders are
+/// left as written.
+pub fn render_stage_prompt(template: &str, ctx: &StageContext<'_>) -> String {
+    let values = HashMap::from([
+        ("pipeline", ctx.pipeline),
+        ("stage", ctx.stage),
+        ("prompt", ctx.prompt),
+        ("previous_branch", ctx.previous_branch.unwrap_or_default()),
+        (
+            "previous_workspace",
+            ctx.previous_workspace.unwrap_or_default(),
+        ),
+    ]);
+
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => {
+                let key = after[..end].trim();
+                match values.get(key) {
+                    Some(value) => rendered.push_str(value),
+                    None => rendered.push_str(&rest[start..start + 2 + end + 2]),
+                }
+                rest = &after[end + 2..];
+            }
+            None => {
+                rendered.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Record the end of the action chain in `workspace_id` against any pipeline
+/// stage running there, waking the orchestrator if one was.
+pub async fn record_stage_outcome(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+    status: PipelineStatus,
+) -> Result<(), sqlx::Error> {
+    if PipelineRunStage::finish_for_workspace(pool, workspace_id, status).await? {
+        STAGE_FINISHED.notify_one();
+    }
+    Ok(())
+}
+
+/// Resolves when a pipeline stage has finished since the last call.
+pub async fn stage_finished() {
+    STAGE_FINISHED.notified().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_known_placeholders() {
+        let ctx = StageContext {
+            pipeline: "ship-it",
+            stage: "write tests",
+            prompt: "Add CSV export",
+            previous_branch: Some("vk/1234-implement"),
+            previous_workspace: None,
+        };
+        assert_eq!(
+            render_stage_prompt(
+                "{{ stage }} for: {{prompt}} (building on {{previous_branch}}){{previous_workspace}}",
+                &ctx
+            ),
+            "write tests for: Add CSV export (building on vk/1234-implement)"
+        );
+        assert_eq!(
+            render_stage_prompt("keep {{unknown}} and {{unterminated", &ctx),
+            "keep {{unknown}} and {{unterminated"
+        );
+    }
+
+    #[test]
+    fn validates_stages() {
+        let stage = |name: &str, prompt: &str| PipelineStage {
+            name: name.to_string(),
+            prompt: prompt.to_string(),
+            executor_config: None,
+        };
+        assert!(validate("p", &[stage("implement", "{{prompt}}")]).is_ok());
+        assert!(matches!(
+            validate("p", &[]),
+            Err(PipelineError::InvalidStageCount)
+        ));
+        assert!(matches!(
+            validate("p", &[stage("a", "x"), stage("b", " ")]),
+            Err(PipelineError::InvalidStage(1))
+        ));
+        assert!(matches!(
+            validate(" ", &[stage("a", "x")]),
+            Err(PipelineError::InvalidName)
+        ));
+    }
+}
@@ -0,0 +1,296 @@
+//! Checks a workspace branch against the provider's branch protection before
+//! it is pushed or a PR is opened for it, so blocked pushes are explained up
+//! front instead of surfacing as raw git errors.
+
+use std::path::Path;
+
+use git::{GitService, GitServiceError};
+use git_host::{BranchProtection, GitHostProvider, GitHostService, ProviderKind};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// A rule of the workspace branch or the PR's target branch the user should
+/// know about. `branch` names the branch the rule applies to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum PreflightFinding {
+    ForcePushBlocked {
+        branch: String,
+    },
+    DirectPushBlocked {
+        branch: String,
+    },
+    UnsignedCommits {
+        branch: String,
+        count: usize,
+    },
+    RequiredReviews {
+        branch: String,
+        count: u32,
+        code_owners: bool,
+    },
+    RequiredChecks {
+        branch: String,
+        checks: Vec<String>,
+    },
+    LinearHistoryRequired {
+        branch: String,
+    },
+    RulesUnavailable {
+        branch: String,
+        message: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PushPreflight {
+    /// Unset when the remote isn't a supported provider.
+    pub provider: Option<ProviderKind>,
+    pub branch: String,
+    pub target_branch: String,
+    pub findings: Vec<PreflightFinding>,
+    pub can_push: bool,
+    pub can_force_push: bool,
+}
+
+/// Read the protection of `branch` on its push remote and of `target_branch`
+/// on the remote a PR would target. Rules that can't be read are reported as
+/// findings rather than errors.
+pub async fn preflight_push(
+    git: &GitService,
+    repo_path: &Path,
+    worktree_path: &Path,
+    branch: &str,
+    target_branch: &str,
+) -> Result<PushPreflight, GitServiceError> {
+    let push_remote = git.resolve_remote_for_branch(repo_path, branch)?;
+    let (target_remote, base_branch) =
+        match git.get_remote_from_branch_name(repo_path, target_branch) {
+            Ok(remote) => {
+                let base_branch = target_branch
+                    .strip_prefix(&format!("{}/", remote.name))
+                    .unwrap_or(target_branch)
+                    .to_string();
+                (remote, base_branch)
+            }
+            Err(_) => (push_remote.clone(), target_branch.to_string()),
+        };
+
+    let git_host = match GitHostService::from_url(&push_remote.url) {
+        Ok(host) => host,
+        Err(e) => {
+            return Ok(build_preflight(
+                None,
+                branch,
+                &base_branch,
+                Err(e.to_string()),
+                Err(e.to_string()),
+                None,
+            ));
+        }
+    };
+
+    let (head, target) = tokio::join!(
+        git_host.get_branch_protection(repo_path, &push_remote.url, branch),
+        git_host.get_branch_protection(repo_path, &target_remote.url, &base_branch),
+    );
+    let head = head.map_err(|e| e.to_string());
+    let target = target.map_err(|e| e.to_string());
+
+    let needs_signatures = [&head, &target]
+        .into_iter()
+        .any(|protection| protection.as_ref().is_ok_and(|p| p.requires_signed_commits));
+    let unsigned_commits = if needs_signatures {
+        git.count_unsigned_commits(worktree_path, target_branch)
+            .inspect_err(|e| tracing::warn!("Failed to count unsigned commits: {}", e))
+            .ok()
+    } else {
+        None
+    };
+
+    Ok(build_preflight(
+        Some(git_host.provider_kind()),
+        branch,
+        &base_branch,
+        head,
+        target,
+        unsigned_commits,
+    ))
+}
+
+/// Turn the protection of the pushed branch and of the PR's target branch
+/// into findings. Only rules of the pushed branch can block the push itself.
+pub fn build_preflight(
+    provider: Option<ProviderKind>,
+    branch: &str,
+    target_branch: &str,
+    head: Result<BranchProtection, String>,
+    target: Result<BranchProtection, String>,
+    unsigned_commits: Option<usize>,
+) -> PushPreflight {
+    let unsigned_commits = unsigned_commits.filter(|count| *count > 0);
+    let mut findings = Vec::new();
+    let mut can_push = true;
+    let mut can_force_push = true;
+
+    match head {
+        Ok(protection) => {
+            if protection.blocks_direct_pushes {
+                findings.push(PreflightFinding::DirectPushBlocked {
+                    branch: branch.to_string(),
+                });
+                can_push = false;
+            }
+            if protection.blocks_force_pushes {
+                findings.push(PreflightFinding::ForcePushBlocked {
+                    branch: branch.to_string(),
+                });
+                can_force_push = false;
+            }
+            if protection.requires_signed_commits
+                && let Some(count) = unsigned_commits
+            {
+                findings.push(PreflightFinding::UnsignedCommits {
+                    branch: branch.to_string(),
+                    count,
+                });
+                can_push = false;
+            }
+            if !protection.complete {
+                findings.push(incomplete_rules(branch));
+            }
+        }
+        Err(message) => findings.push(PreflightFinding::RulesUnavailable {
+            branch: branch.to_string(),
+            message,
+        }),
+    }
+
+    match target {
+        Ok(protection) => {
+            if protection.required_approving_reviews > 0 || protection.requires_code_owner_reviews {
+                findings.push(PreflightFinding::RequiredReviews {
+                    branch: target_branch.to_string(),
+                    count: protection.required_approving_reviews,
+                    code_owners: protection.requires_code_owner_reviews,
+                });
+            }
+            if !protection.required_status_checks.is_empty() {
+                findings.push(PreflightFinding::RequiredChecks {
+                    branch: target_branch.to_string(),
+                    checks: protection.required_status_checks,
+                });
+            }
+            if protection.requires_signed_commits
+                && let Some(count) = unsigned_commits
+            {
+                findings.push(PreflightFinding::UnsignedCommits {
+                    branch: target_branch.to_string(),
+                    count,
+                });
+            }
+            if protection.requires_linear_history {
+                findings.push(PreflightFinding::LinearHistoryRequired {
+                    branch: target_branch.to_string(),
+                });
+            }
+            if !protection.complete {
+                findings.push(incomplete_rules(target_branch));
+            }
+        }
+        Err(message) => findings.push(PreflightFinding::RulesUnavailable {
+            branch: target_branch.to_string(),
+            message,
+        }),
+    }
+
+    PushPreflight {
+        provider,
+        branch: branch.to_string(),
+        target_branch: target_branch.to_string(),
+        findings,
+        can_push,
+        can_force_push: can_push && can_force_push,
+    }
+}
+
+fn incomplete_rules(branch: &str) -> PreflightFinding {
+    PreflightFinding::RulesUnavailable {
+        branch: branch.to_string(),
+        message: "Some protection rules can only be read with admin access".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_rules_of_the_pushed_branch_block_the_push() {
+        let head = BranchProtection {
+            protected: true,
+            blocks_force_pushes: true,
+            complete: true,
+            ..Default::default()
+        };
+        let target = BranchProtection {
+            protected: true,
+            blocks_force_pushes: true,
+            blocks_direct_pushes: true,
+            required_approving_reviews: 1,
+            requires_signed_commits: true,
+            required_status_checks: vec!["ci".to_string()],
+            ..Default::default()
+        };
+
+        let preflight = build_preflight(
+            Some(ProviderKind::GitHub),
+            "vk/feature",
+            "main",
+            Ok(head),
+            Ok(target),
+            Some(2),
+        );
+
+        assert!(preflight.can_push);
+        assert!(!preflight.can_force_push);
+        assert_eq!(
+            preflight.findings,
+            vec![
+                PreflightFinding::ForcePushBlocked {
+                    branch: "vk/feature".to_string(),
+                },
+                PreflightFinding::RequiredReviews {
+                    branch: "main".to_string(),
+                    count: 1,
+                    code_owners: false,
+                },
+                PreflightFinding::RequiredChecks {
+                    branch: "main".to_string(),
+                    checks: vec!["ci".to_string()],
+                },
+                PreflightFinding::UnsignedCommits {
+                    branch: "main".to_string(),
+                    count: 2,
+                },
+                incomplete_rules("main"),
+            ]
+        );
+    }
+
+    #[test]
+    fn unreadable_rules_do_not_block_the_push() {
+        let preflight = build_preflight(
+            None,
+            "vk/feature",
+            "main",
+            Err("Unsupported git hosting provider".to_string()),
+            Err("Unsupported git hosting provider".to_string()),
+            None,
+        );
+
+        assert!(preflight.can_push && preflight.can_force_push);
+        assert_eq!(preflight.findings.len(), 2);
+    }
+}
@@ -13,10 +13,11 @@ use api_types::{
     ListIssueRelationshipsResponse, ListIssueTagsResponse, ListIssuesResponse, ListMembersResponse,
     ListOrganizationsResponse, ListProjectStatusesResponse, ListProjectsResponse,
     ListPullRequestsResponse, ListTagsResponse, LocalLoginRequest, LocalLoginResponse,
-    MutationResponse, Organization, ProfileResponse, PullRequest, RevokeInvitationRequest,
-    SearchIssuesRequest, Tag, TokenRefreshRequest, TokenRefreshResponse, UpdateIssueRequest,
-    UpdateMemberRoleRequest, UpdateMemberRoleResponse, UpdateOrganizationRequest,
-    UpdatePullRequestApiRequest, UpdateWorkspaceRequest, UpsertPullRequestRequest, Workspace,
+    MutationResponse, Organization, ProfileResponse, PullRequest, ReportAgentUsageRequest,
+    RevokeInvitationRequest, SearchIssuesRequest, Tag, TokenRefreshRequest, TokenRefreshResponse,
+    UpdateIssueRequest, UpdateMemberRoleRequest, UpdateMemberRoleResponse,
+    UpdateOrganizationRequest, UpdatePullRequestApiRequest, UpdateWorkspaceRequest,
+    UpsertPullRequestRequest, Workspace,
 };
 use backon::{ExponentialBuilder, Retryable};
 use chrono::Duration as ChronoDuration;
@@ -394,7 +395,20 @@ impl RemoteClient {
             let res = req.send().await.map_err(map_reqwest_error)?;
 
             match res.status() {
-                s if s.is_success() => Ok(res),
+                s if s.is_success() => {
+                    if res.headers().contains_key("deprecation") {
+                        warn!(
+                            "Remote endpoint {} {} is deprecated (sunset: {})",
+                            method,
+                            path,
+                            res.headers()
+                                .get("sunset")
+                                .and_then(|value| value.to_str().ok())
+                                .unwrap_or("not scheduled")
+                        );
+                    }
+                    Ok(res)
+                }
                 StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(RemoteClientError::Auth),
                 s => {
                     let status = s.as_u16();
@@ -475,6 +489,34 @@ impl RemoteClient {
             .map_err(|e| RemoteClientError::Serde(e.to_string()))
     }
 
+    /// Sends a request authenticated as the account `access_token` belongs to
+    /// instead of the one this client is signed in as.
+    async fn send_with_token(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        access_token: &str,
+    ) -> Result<reqwest::Response, RemoteClientError> {
+        self.send_internal_with_request(method, path, false, |req| req.bearer_auth(access_token))
+            .await
+    }
+
+    async fn get_with_token<T>(
+        &self,
+        path: &str,
+        access_token: &str,
+    ) -> Result<T, RemoteClientError>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let res = self
+            .send_with_token(reqwest::Method::GET, path, access_token)
+            .await?;
+        res.json::<T>()
+            .await
+            .map_err(|e| RemoteClientError::Serde(e.to_string()))
+    }
+
     async fn patch_authed<T, B>(&self, path: &str, body: &B) -> Result<T, RemoteClientError>
     where
         T: for<'de> Deserialize<'de>,
@@ -526,6 +568,29 @@ impl RemoteClient {
         self.delete_authed("/v1/oauth/logout").await
     }
 
+    /// Fetches the profile of the account `access_token` belongs to.
+    pub async fn profile_for_token(
+        &self,
+        access_token: &str,
+    ) -> Result<ProfileResponse, RemoteClientError> {
+        self.get_with_token("/v1/profile", access_token).await
+    }
+
+    /// Lists the organizations of the account `access_token` belongs to.
+    pub async fn list_organizations_for_token(
+        &self,
+        access_token: &str,
+    ) -> Result<ListOrganizationsResponse, RemoteClientError> {
+        self.get_with_token("/v1/organizations", access_token).await
+    }
+
+    /// Revokes the session `access_token` belongs to.
+    pub async fn logout_token(&self, access_token: &str) -> Result<(), RemoteClientError> {
+        self.send_with_token(reqwest::Method::DELETE, "/v1/oauth/logout", access_token)
+            .await?;
+        Ok(())
+    }
+
     /// Lists organizations for the authenticated user.
     pub async fn list_organizations(&self) -> Result<ListOrganizationsResponse, RemoteClientError> {
         self.get_authed("/v1/organizations").await
@@ -736,6 +801,22 @@ impl RemoteClient {
         Ok(())
     }
 
+    /// Reports a finished agent run in a linked workspace for usage metering.
+    pub async fn report_agent_usage(
+        &self,
+        local_workspace_id: Uuid,
+        request: &ReportAgentUsageRequest,
+    ) -> Result<(), RemoteClientError> {
+        self.send(
+            reqwest::Method::POST,
+            &format!("/v1/workspaces/{local_workspace_id}/agent_usage"),
+            true,
+            Some(request),
+        )
+        .await?;
+        Ok(())
+    }
+
     /// Creates a workspace on the remote server, linking it to a local workspace and an issue.
     pub async fn create_workspace(
         &self,
@@ -1,4 +1,4 @@
-use api_types::UpsertPullRequestRequest;
+use api_types::{ReportAgentUsageRequest, UpsertPullRequestRequest};
 use db::models::workspace::Workspace;
 use git::GitService;
 use sqlx::SqlitePool;
@@ -115,6 +115,40 @@ pub async fn sync_local_workspace_merge_to_remote(client: &RemoteClient, workspa
     }
 }
 
+/// Reports a finished agent run to remote for usage metering.
+pub async fn report_agent_usage_to_remote(
+    client: &RemoteClient,
+    workspace_id: Uuid,
+    request: &ReportAgentUsageRequest,
+) {
+    match client.report_agent_usage(workspace_id, request).await {
+        Ok(()) => {
+            debug!(
+                "Reported agent usage for execution {} to remote",
+                request.execution_process_id
+            );
+        }
+        Err(RemoteClientError::Auth) => {
+            debug!(
+                "Agent usage report skipped for workspace {}: not authenticated",
+                workspace_id
+            );
+        }
+        Err(RemoteClientError::Http { status: 404, .. }) => {
+            debug!(
+                "Agent usage report skipped for workspace {}: workspace not found on remote",
+                workspace_id
+            );
+        }
+        Err(e) => {
+            error!(
+                "Failed to report agent usage for workspace {}: {}",
+                workspace_id, e
+            );
+        }
+    }
+}
+
 async fn upsert_pr_on_remote(client: &RemoteClient, request: UpsertPullRequestRequest) {
     let number = request.number;
     let workspace_id = request.local_workspace_id;
@@ -0,0 +1,775 @@
+//! Suggests scripts for a newly added repo from the files in it.
+//!
+//! Package manifests and lockfiles decide the setup (install) and dev server
+//! commands, formatters make up the cleanup script, and Makefile targets win
+//! over both when they exist. When the repo root has no manifest but a single
+//! subdirectory does, that subdirectory is suggested as the agent working dir.
+//! Monorepo packages (pnpm/npm/yarn workspaces, cargo workspace members and
+//! bazel packages) are listed so one can be picked per workspace.
+//! Nothing is saved: the result is meant to prefill the repo settings form.
+
+use std::{
+    collections::BTreeSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+use ts_rs::TS;
+
+/// Most `run:` commands collected from CI workflows.
+const MAX_CI_COMMANDS: usize = 20;
+
+/// Most monorepo packages listed.
+const MAX_PACKAGES: usize = 100;
+
+/// How deep below the root bazel `BUILD` files are looked for.
+const MAX_BAZEL_DEPTH: usize = 3;
+
+const BAZEL_WORKSPACE_FILES: &[&str] = &["MODULE.bazel", "WORKSPACE", "WORKSPACE.bazel"];
+
+const MANIFESTS: &[&str] = &[
+    "package.json",
+    "Cargo.toml",
+    "pyproject.toml",
+    "requirements.txt",
+    "go.mod",
+    "Gemfile",
+    "Makefile",
+    "MODULE.bazel",
+    "WORKSPACE",
+    "WORKSPACE.bazel",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, TS)]
+pub struct RepoAnalysis {
+    pub setup_script: Option<String>,
+    pub dev_server_script: Option<String>,
+    pub cleanup_script: Option<String>,
+    /// Set when the project lives in a subdirectory of the repo.
+    pub default_working_dir: Option<String>,
+    /// Files the suggestions were derived from, relative to the repo root.
+    pub detected_files: Vec<String>,
+    /// Commands run by the repo's CI workflows, for reference.
+    pub ci_commands: Vec<String>,
+    /// Packages of a monorepo, relative to the project directory.
+    pub packages: Vec<DetectedPackage>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum PackageKind {
+    Node,
+    Cargo,
+    Bazel,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+pub struct DetectedPackage {
+    pub path: String,
+    pub name: String,
+    pub kind: PackageKind,
+    /// Runs in the package directory.
+    pub dev_server_script: Option<String>,
+}
+
+#[derive(Default)]
+struct Suggestions {
+    setup: Vec<String>,
+    dev_server: Option<String>,
+    cleanup: Vec<String>,
+}
+
+impl Suggestions {
+    fn add_setup(&mut self, command: impl Into<String>) {
+        let command = command.into();
+        if !self.setup.contains(&command) {
+            self.setup.push(command);
+        }
+    }
+
+    fn add_cleanup(&mut self, command: impl Into<String>) {
+        let command = command.into();
+        if !self.cleanup.contains(&command) {
+            self.cleanup.push(command);
+        }
+    }
+}
+
+pub fn analyze_repo(repo_path: &Path) -> io::Result<RepoAnalysis> {
+    if !repo_path.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Repository directory not found: {}", repo_path.display()),
+        ));
+    }
+
+    let working_dir = project_subdir(repo_path)?;
+    let project_dir = match &working_dir {
+        Some(dir) => repo_path.join(dir),
+        None => repo_path.to_path_buf(),
+    };
+
+    let mut detected = Vec::new();
+    let mut suggestions = Suggestions::default();
+    analyze_node(&project_dir, &mut suggestions, &mut detected);
+    analyze_rust(&project_dir, &mut suggestions, &mut detected);
+    analyze_python(&project_dir, &mut suggestions, &mut detected);
+    analyze_go(&project_dir, &mut suggestions, &mut detected);
+    analyze_ruby(&project_dir, &mut suggestions, &mut detected);
+    analyze_makefile(&project_dir, &mut suggestions, &mut detected);
+
+    let packages = detect_packages(&project_dir, &mut detected);
+    let ci_commands = ci_commands(repo_path, &mut detected);
+    let detected_files = detected
+        .into_iter()
+        .map(|name| match &working_dir {
+            Some(dir) if !name.starts_with(".github/") => format!("{dir}/{name}"),
+            _ => name,
+        })
+        .collect();
+
+    Ok(RepoAnalysis {
+        setup_script: join_lines(suggestions.setup),
+        dev_server_script: suggestions.dev_server,
+        cleanup_script: join_lines(suggestions.cleanup),
+        default_working_dir: working_dir,
+        detected_files,
+        ci_commands,
+        packages,
+    })
+}
+
+fn join_lines(commands: Vec<String>) -> Option<String> {
+    (!commands.is_empty()).then(|| commands.join("\n"))
+}
+
+fn has_manifest(dir: &Path) -> bool {
+    MANIFESTS.iter().any(|name| dir.join(name).is_file())
+}
+
+/// The only immediate subdirectory with a manifest, if the root has none.
+fn project_subdir(repo_path: &Path) -> io::Result<Option<String>> {
+    if has_manifest(repo_path) {
+        return Ok(None);
+    }
+    let mut candidates = Vec::new();
+    for entry in fs::read_dir(repo_path)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with('.') || !entry.file_type()?.is_dir() {
+            continue;
+        }
+        if has_manifest(&entry.path()) {
+            candidates.push(name);
+        }
+    }
+    Ok(match candidates.as_slice() {
+        [only] => Some(only.clone()),
+        _ => None,
+    })
+}
+
+fn analyze_node(dir: &Path, suggestions: &mut Suggestions, detected: &mut Vec<String>) {
+    let Ok(manifest) = fs::read_to_string(dir.join("package.json")) else {
+        return;
+    };
+    detected.push("package.json".to_string());
+
+    let (lockfile, package_manager) = node_package_manager(dir);
+    if let Some(lockfile) = lockfile {
+        detected.push(lockfile.to_string());
+    }
+    suggestions.add_setup(format!("{package_manager} install"));
+
+    let manifest = serde_json::from_str::<serde_json::Value>(&manifest).unwrap_or_default();
+    if suggestions.dev_server.is_none() {
+        suggestions.dev_server = node_dev_script(&manifest, package_manager);
+    }
+    if let Some(script) = ["format", "fmt", "lint:fix"]
+        .into_iter()
+        .find(|script| manifest["scripts"].get(script).is_some())
+    {
+        suggestions.add_cleanup(format!("{package_manager} run {script}"));
+    }
+}
+
+/// The lockfile found in `dir`, if any, and the package manager it implies.
+fn node_package_manager(dir: &Path) -> (Option<&'static str>, &'static str) {
+    [
+        ("pnpm-lock.yaml", "pnpm"),
+        ("yarn.lock", "yarn"),
+        ("bun.lock", "bun"),
+        ("bun.lockb", "bun"),
+        ("package-lock.json", "npm"),
+    ]
+    .into_iter()
+    .find(|(lockfile, _)| dir.join(lockfile).is_file())
+    .map_or((None, "npm"), |(lockfile, manager)| {
+        (Some(lockfile), manager)
+    })
+}
+
+fn node_dev_script(manifest: &serde_json::Value, package_manager: &str) -> Option<String> {
+    ["dev", "start", "serve"]
+        .into_iter()
+        .find(|script| manifest["scripts"].get(script).is_some())
+        .map(|script| format!("{package_manager} run {script}"))
+}
+
+fn analyze_rust(dir: &Path, suggestions: &mut Suggestions, detected: &mut Vec<String>) {
+    if !dir.join("Cargo.toml").is_file() {
+        return;
+    }
+    detected.push("Cargo.toml".to_string());
+    if dir.join("Cargo.lock").is_file() {
+        detected.push("Cargo.lock".to_string());
+    }
+    suggestions.add_setup("cargo build");
+    suggestions.add_cleanup("cargo fmt");
+}
+
+fn analyze_python(dir: &Path, suggestions: &mut Suggestions, detected: &mut Vec<String>) {
+    let setup = [
+        ("uv.lock", "uv sync"),
+        ("poetry.lock", "poetry install"),
+        ("Pipfile.lock", "pipenv install --dev"),
+        ("requirements.txt", "pip install -r requirements.txt"),
+        ("pyproject.toml", "pip install -e ."),
+    ]
+    .into_iter()
+    .find(|(file, _)| dir.join(file).is_file());
+    let Some((file, command)) = setup else {
+        return;
+    };
+    if file != "pyproject.toml" && dir.join("pyproject.toml").is_file() {
+        detected.push("pyproject.toml".to_string());
+    }
+    detected.push(file.to_string());
+    suggestions.add_setup(command);
+
+    if fs::read_to_string(dir.join("pyproject.toml"))
+        .is_ok_and(|pyproject| pyproject.contains("[tool.ruff"))
+    {
+        suggestions.add_cleanup("ruff format .");
+    }
+}
+
+fn analyze_go(dir: &Path, suggestions: &mut Suggestions, detected: &mut Vec<String>) {
+    if !dir.join("go.mod").is_file() {
+        return;
+    }
+    detected.push("go.mod".to_string());
+    suggestions.add_setup("go mod download");
+    suggestions.add_cleanup("gofmt -w .");
+}
+
+fn analyze_ruby(dir: &Path, suggestions: &mut Suggestions, detected: &mut Vec<String>) {
+    if !dir.join("Gemfile").is_file() {
+        return;
+    }
+    detected.push("Gemfile".to_string());
+    suggestions.add_setup("bundle install");
+    if suggestions.dev_server.is_none() && dir.join("bin/rails").is_file() {
+        suggestions.dev_server = Some("bin/rails server".to_string());
+    }
+}
+
+/// Makefile targets are the project's own entry points, so they replace what
+/// the manifests suggested.
+fn analyze_makefile(dir: &Path, suggestions: &mut Suggestions, detected: &mut Vec<String>) {
+    let Ok(makefile) = fs::read_to_string(dir.join("Makefile")) else {
+        return;
+    };
+    let targets = makefile_targets(&makefile);
+    let find = |names: &[&str]| {
+        names
+            .iter()
+            .find(|name| targets.contains(**name))
+            .map(|name| format!("make {name}"))
+    };
+
+    let setup = find(&["setup", "bootstrap", "install", "deps"]);
+    let dev_server = find(&["dev", "run", "serve", "start"]);
+    let cleanup = find(&["fmt", "format"]);
+    if setup.is_none() && dev_server.is_none() && cleanup.is_none() {
+        return;
+    }
+    detected.push("Makefile".to_string());
+    if let Some(setup) = setup {
+        suggestions.setup = vec![setup];
+    }
+    if dev_server.is_some() {
+        suggestions.dev_server = dev_server;
+    }
+    if let Some(cleanup) = cleanup {
+        suggestions.cleanup = vec![cleanup];
+    }
+}
+
+fn makefile_targets(makefile: &str) -> BTreeSet<String> {
+    makefile
+        .lines()
+        .filter(|line| !line.starts_with(['\t', ' ', '.', '#']))
+        .filter_map(|line| {
+            let (target, rest) = line.split_once(':')?;
+            // `NAME := value` and `NAME ::= value` are assignments.
+            if rest.starts_with('=') || rest.starts_with(":=") {
+                return None;
+            }
+            Some(
+                target
+                    .split_whitespace()
+                    .map(str::to_string)
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .flatten()
+        .filter(|target| !target.contains(['$', '%', '=']))
+        .collect()
+}
+
+/// Single-line `run:` commands of the GitHub Actions workflows.
+fn ci_commands(repo_path: &Path, detected: &mut Vec<String>) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(repo_path.join(".github/workflows")) else {
+        return Vec::new();
+    };
+    let mut workflows = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == "yml" || ext == "yaml")
+        })
+        .collect::<Vec<PathBuf>>();
+    workflows.sort();
+
+    let mut commands = Vec::new();
+    for workflow in workflows {
+        let Ok(content) = fs::read_to_string(&workflow) else {
+            continue;
+        };
+        let before = commands.len();
+        for line in content.lines() {
+            let line = line.trim_start().trim_start_matches("- ");
+            let Some(command) = line.strip_prefix("run:").map(str::trim) else {
+                continue;
+            };
+            if command.is_empty()
+                || command.starts_with(['|', '>'])
+                || commands.iter().any(|c| c == command)
+            {
+                continue;
+            }
+            commands.push(command.to_string());
+        }
+        if commands.len() > before
+            && let Some(name) = workflow.file_name()
+        {
+            detected.push(format!(".github/workflows/{}", name.to_string_lossy()));
+        }
+    }
+    commands.truncate(MAX_CI_COMMANDS);
+    commands
+}
+
+fn detect_packages(root: &Path, detected: &mut Vec<String>) -> Vec<DetectedPackage> {
+    let mut packages = Vec::new();
+    packages.extend(node_packages(root, detected));
+    packages.extend(cargo_packages(root, detected));
+    packages.extend(bazel_packages(root, detected));
+    packages.truncate(MAX_PACKAGES);
+    packages
+}
+
+/// Members of pnpm workspaces (`pnpm-workspace.yaml`) or npm/yarn/bun
+/// workspaces (`workspaces` in the root `package.json`).
+fn node_packages(root: &Path, detected: &mut Vec<String>) -> Vec<DetectedPackage> {
+    let patterns = if let Ok(config) = fs::read_to_string(root.join("pnpm-workspace.yaml")) {
+        detected.push("pnpm-workspace.yaml".to_string());
+        pnpm_workspace_patterns(&config)
+    } else {
+        let manifest = fs::read_to_string(root.join("package.json"))
+            .ok()
+            .and_then(|manifest| serde_json::from_str::<serde_json::Value>(&manifest).ok())
+            .unwrap_or_default();
+        // Either a list of globs or `{ "packages": [...] }`.
+        let workspaces = &manifest["workspaces"];
+        workspaces
+            .as_array()
+            .or_else(|| workspaces["packages"].as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|pattern| pattern.as_str().map(str::to_string))
+            .collect()
+    };
+
+    let (_, package_manager) = node_package_manager(root);
+    expand_patterns(root, &patterns, "package.json")
+        .into_iter()
+        .map(|path| {
+            let manifest = fs::read_to_string(root.join(&path).join("package.json"))
+                .ok()
+                .and_then(|manifest| serde_json::from_str::<serde_json::Value>(&manifest).ok())
+                .unwrap_or_default();
+            DetectedPackage {
+                name: manifest["name"].as_str().unwrap_or(&path).to_string(),
+                dev_server_script: node_dev_script(&manifest, package_manager),
+                kind: PackageKind::Node,
+                path,
+            }
+        })
+        .collect()
+}
+
+/// The `packages:` list of a `pnpm-workspace.yaml`.
+fn pnpm_workspace_patterns(config: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+    for line in config.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        if !line.starts_with([' ', '\t', '-']) {
+            in_packages = line.trim_end() == "packages:";
+            continue;
+        }
+        if in_packages && let Some(pattern) = line.trim().strip_prefix('-') {
+            patterns.push(pattern.trim().trim_matches(['\'', '"']).to_string());
+        }
+    }
+    patterns
+}
+
+/// Members of the `[workspace]` of the root `Cargo.toml`.
+fn cargo_packages(root: &Path, detected: &mut Vec<String>) -> Vec<DetectedPackage> {
+    let Ok(manifest) = fs::read_to_string(root.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    let patterns = cargo_workspace_members(&manifest);
+    if patterns.is_empty() {
+        return Vec::new();
+    }
+    if !detected.iter().any(|file| file == "Cargo.toml") {
+        detected.push("Cargo.toml".to_string());
+    }
+
+    expand_patterns(root, &patterns, "Cargo.toml")
+        .into_iter()
+        .map(|path| {
+            let dir = root.join(&path);
+            let name = fs::read_to_string(dir.join("Cargo.toml"))
+                .ok()
+                .and_then(|manifest| cargo_package_name(&manifest))
+                .unwrap_or_else(|| path.clone());
+            DetectedPackage {
+                name,
+                dev_server_script: dir
+                    .join("src/main.rs")
+                    .is_file()
+                    .then(|| "cargo run".to_string()),
+                kind: PackageKind::Cargo,
+                path,
+            }
+        })
+        .collect()
+}
+
+/// The quoted strings of `members = [...]` in a `[workspace]` section, which
+/// may span several lines.
+fn cargo_workspace_members(manifest: &str) -> Vec<String> {
+    let Some(workspace) = manifest
+        .split("\n[")
+        .find(|section| section.trim_start_matches('[').starts_with("workspace]"))
+    else {
+        return Vec::new();
+    };
+    let mut members = String::new();
+    for line in workspace.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        if members.is_empty() {
+            match line.split_once('=') {
+                Some((key, value)) if key.trim() == "members" => members.push_str(value),
+                _ => continue,
+            }
+        } else {
+            members.push_str(line);
+        }
+        if members.contains(']') {
+            break;
+        }
+    }
+    let Some(members) = members
+        .split_once('[')
+        .and_then(|(_, members)| members.split_once(']'))
+        .map(|(members, _)| members)
+    else {
+        return Vec::new();
+    };
+    members
+        .split(',')
+        .map(|member| member.trim().trim_matches('"').to_string())
+        .filter(|member| !member.is_empty())
+        .collect()
+}
+
+fn cargo_package_name(manifest: &str) -> Option<String> {
+    let package = manifest
+        .split("\n[")
+        .find(|section| section.trim_start_matches('[').starts_with("package]"))?;
+    package.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        (key.trim() == "name").then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Directories with a `BUILD` or `BUILD.bazel` file, when the root is a bazel
+/// workspace.
+fn bazel_packages(root: &Path, detected: &mut Vec<String>) -> Vec<DetectedPackage> {
+    let Some(workspace_file) = BAZEL_WORKSPACE_FILES
+        .iter()
+        .find(|file| root.join(file).is_file())
+    else {
+        return Vec::new();
+    };
+    detected.push(workspace_file.to_string());
+
+    let mut paths = Vec::new();
+    let mut pending = vec![(PathBuf::new(), 0)];
+    while let Some((relative, depth)) = pending.pop() {
+        let Ok(entries) = fs::read_dir(root.join(&relative)) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+            // `bazel-*` are output symlinks.
+            if !is_dir || name.starts_with(['.', '_']) || name.starts_with("bazel-") {
+                continue;
+            }
+            if name == "node_modules" || name == "target" {
+                continue;
+            }
+            let path = relative.join(&name);
+            let dir = root.join(&path);
+            if dir.join("BUILD").is_file() || dir.join("BUILD.bazel").is_file() {
+                paths.push(path.to_string_lossy().replace('\\', "/"));
+            }
+            if depth + 1 < MAX_BAZEL_DEPTH {
+                pending.push((path, depth + 1));
+            }
+        }
+    }
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| DetectedPackage {
+            name: format!("//{path}"),
+            dev_server_script: None,
+            kind: PackageKind::Bazel,
+            path,
+        })
+        .collect()
+}
+
+/// Directories matching workspace globs that contain `manifest`, sorted and
+/// without duplicates. `*` and `**` match a single path component; negated
+/// patterns are skipped.
+fn expand_patterns(root: &Path, patterns: &[String], manifest: &str) -> Vec<String> {
+    let mut paths = BTreeSet::new();
+    for pattern in patterns {
+        let pattern = pattern
+            .trim()
+            .trim_start_matches("./")
+            .trim_end_matches('/');
+        if pattern.is_empty() || pattern.starts_with('!') {
+            continue;
+        }
+        let mut matches = vec![String::new()];
+        for component in pattern.split('/') {
+            matches = matches
+                .into_iter()
+                .flat_map(|prefix| expand_component(root, &prefix, component))
+                .collect();
+        }
+        paths.extend(
+            matches
+                .into_iter()
+                .filter(|path| root.join(path).join(manifest).is_file()),
+        );
+    }
+    paths.into_iter().collect()
+}
+
+fn expand_component(root: &Path, prefix: &str, component: &str) -> Vec<String> {
+    let join = |name: &str| {
+        if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{prefix}/{name}")
+        }
+    };
+    if !component.contains('*') {
+        return vec![join(component)];
+    }
+    let Ok(entries) = fs::read_dir(root.join(prefix)) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| !name.starts_with('.') && name != "node_modules")
+        .filter(|name| wildcard_match(component, name))
+        .map(|name| join(&name))
+        .collect()
+}
+
+/// Match `name` against a pattern where `*` stands for any run of characters.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts = parts.collect::<Vec<_>>();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) {
+        let path = dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn suggests_scripts_from_manifests_and_lockfiles() {
+        let repo = TempDir::new().unwrap();
+        write(
+            repo.path(),
+            "package.json",
+            r#"{"scripts": {"dev": "vite", "format": "prettier -w ."}}"#,
+        );
+        write(repo.path(), "pnpm-lock.yaml", "");
+        write(repo.path(), "Cargo.toml", "[package]\nname = \"app\"\n");
+        write(
+            repo.path(),
+            ".github/workflows/ci.yml",
+            "steps:\n  - run: pnpm test\n  - run: |\n      cargo test\n",
+        );
+
+        let analysis = analyze_repo(repo.path()).unwrap();
+        assert_eq!(
+            analysis.setup_script.as_deref(),
+            Some("pnpm install\ncargo build")
+        );
+        assert_eq!(analysis.dev_server_script.as_deref(), Some("pnpm run dev"));
+        assert_eq!(
+            analysis.cleanup_script.as_deref(),
+            Some("pnpm run format\ncargo fmt")
+        );
+        assert_eq!(analysis.default_working_dir, None);
+        assert_eq!(analysis.ci_commands, vec!["pnpm test"]);
+        assert!(
+            analysis
+                .detected_files
+                .contains(&".github/workflows/ci.yml".to_string())
+        );
+    }
+
+    #[test]
+    fn prefers_makefile_targets_and_finds_project_subdir() {
+        let repo = TempDir::new().unwrap();
+        write(repo.path(), "README.md", "");
+        write(repo.path(), "backend/go.mod", "module example.com/app\n");
+        write(
+            repo.path(),
+            "backend/Makefile",
+            "GO := go\n.PHONY: dev\nsetup deps:\n\t$(GO) mod download\ndev:\n\tair\n",
+        );
+
+        let analysis = analyze_repo(repo.path()).unwrap();
+        assert_eq!(analysis.default_working_dir.as_deref(), Some("backend"));
+        assert_eq!(analysis.setup_script.as_deref(), Some("make setup"));
+        assert_eq!(analysis.dev_server_script.as_deref(), Some("make dev"));
+        assert_eq!(analysis.cleanup_script.as_deref(), Some("gofmt -w ."));
+        assert_eq!(
+            analysis.detected_files,
+            vec!["backend/go.mod", "backend/Makefile"]
+        );
+    }
+
+    #[test]
+    fn lists_monorepo_packages() {
+        let repo = TempDir::new().unwrap();
+        write(repo.path(), "package.json", r#"{"name": "root"}"#);
+        write(repo.path(), "pnpm-lock.yaml", "");
+        write(
+            repo.path(),
+            "pnpm-workspace.yaml",
+            "packages:\n  - 'apps/*'\n  - \"!apps/legacy\"\n\ncatalog:\n  - ignored\n",
+        );
+        write(
+            repo.path(),
+            "apps/web/package.json",
+            r#"{"name": "@acme/web", "scripts": {"dev": "vite"}}"#,
+        );
+        write(repo.path(), "apps/docs/README.md", "");
+        write(
+            repo.path(),
+            "Cargo.toml",
+            "[workspace]\ndefault-members = [\"crates/cli\"]\nmembers = [\n  \"crates/*\", # all crates\n]\n",
+        );
+        write(
+            repo.path(),
+            "crates/cli/Cargo.toml",
+            "[package]\nname = \"acme-cli\"\n",
+        );
+        write(repo.path(), "crates/cli/src/main.rs", "");
+        write(
+            repo.path(),
+            "crates/core/Cargo.toml",
+            "[package]\nname = \"acme-core\"\n",
+        );
+
+        let analysis = analyze_repo(repo.path()).unwrap();
+        assert_eq!(
+            analysis.packages,
+            vec![
+                DetectedPackage {
+                    path: "apps/web".to_string(),
+                    name: "@acme/web".to_string(),
+                    kind: PackageKind::Node,
+                    dev_server_script: Some("pnpm run dev".to_string()),
+                },
+                DetectedPackage {
+                    path: "crates/cli".to_string(),
+                    name: "acme-cli".to_string(),
+                    kind: PackageKind::Cargo,
+                    dev_server_script: Some("cargo run".to_string()),
+                },
+                DetectedPackage {
+                    path: "crates/core".to_string(),
+                    name: "acme-core".to_string(),
+                    kind: PackageKind::Cargo,
+                    dev_server_script: None,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! Background index of repo branches, recent commits and contributors.
+//!
+//! Branch pickers and base branch selectors read the index instead of the
+//! repo. Every repo is re-checked on an interval: a refresh stops after
+//! reading the refs if none of them moved, and otherwise only walks the
+//! commits added to HEAD since the last refresh. Reads of an indexed repo
+//! request a refresh in the background, so the next read sees what changed.
+
+use std::{
+    collections::HashSet,
+    mem,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use db::{
+    DBService,
+    models::{
+        repo::Repo,
+        repo_metadata::{RepoBranch, RepoCommit, RepoContributor, RepoIndexUpdate, RepoMetadata},
+    },
+};
+use git::{GitBranch, GitService, GitServiceError};
+use thiserror::Error;
+use tokio::{sync::Notify, time::interval};
+use uuid::Uuid;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Commits of HEAD kept per repo; contributors are counted from these.
+const MAX_INDEXED_COMMITS: usize = 1000;
+
+#[derive(Debug, Error)]
+pub enum RepoIndexError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error(transparent)]
+    Join(#[from] tokio::task::JoinError),
+}
+
+#[derive(Clone)]
+pub struct RepoIndexService {
+    db: DBService,
+    git: GitService,
+    requested: Arc<Mutex<HashSet<Uuid>>>,
+    notify: Arc<Notify>,
+}
+
+impl RepoIndexService {
+    pub fn spawn(db: DBService, git: GitService) -> Self {
+        let service = Self {
+            db,
+            git,
+            requested: Arc::new(Mutex::new(HashSet::new())),
+            notify: Arc::new(Notify::new()),
+        };
+        let background = service.clone();
+        tokio::spawn(async move {
+            background.start().await;
+        });
+        service
+    }
+
+    /// Refresh the index of `repo_id` in the background, e.g. after its refs
+    /// changed.
+    pub fn request_refresh(&self, repo_id: Uuid) {
+        self.requested
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(repo_id);
+        self.notify.notify_one();
+    }
+
+    /// Branches of `repo` as [`GitService::get_all_branches`] lists them.
+    pub async fn branches(&self, repo: &Repo) -> Result<Vec<GitBranch>, RepoIndexError> {
+        self.ensure_indexed(repo).await?;
+        Ok(RepoMetadata::branches(&self.db.pool, repo.id)
+            .await?
+            .into_iter()
+            .map(|branch| GitBranch {
+                name: branch.name,
+                is_current: branch.is_current,
+                is_remote: branch.is_remote,
+                last_commit_date: branch.last_commit_date,
+            })
+            .collect())
+    }
+
+    pub async fn recent_commits(
+        &self,
+        repo: &Repo,
+        limit: i64,
+    ) -> Result<Vec<RepoCommit>, RepoIndexError> {
+        self.ensure_indexed(repo).await?;
+        Ok(RepoMetadata::recent_commits(&self.db.pool, repo.id, limit).await?)
+    }
+
+    pub async fn contributors(&self, repo: &Repo) -> Result<Vec<RepoContributor>, RepoIndexError> {
+        self.ensure_indexed(repo).await?;
+        Ok(RepoMetadata::contributors(&self.db.pool, repo.id).await?)
+    }
+
+    /// Index `repo` now if it never was, otherwise have it refreshed in the
+    /// background.
+    async fn ensure_indexed(&self, repo: &Repo) -> Result<(), RepoIndexError> {
+        if RepoMetadata::state(&self.db.pool, repo.id).await?.is_some() {
+            self.request_refresh(repo.id);
+            return Ok(());
+        }
+        self.refresh(repo).await?;
+        Ok(())
+    }
+
+    async fn start(&self) {
+        let mut interval = interval(POLL_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if let Err(e) = self.refresh_all().await {
+                        tracing::error!("Error refreshing repo index: {}", e);
+                    }
+                }
+                _ = self.notify.notified() => self.refresh_requested().await,
+            }
+        }
+    }
+
+    async fn refresh_all(&self) -> Result<(), RepoIndexError> {
+        for repo in Repo::list_all(&self.db.pool).await? {
+            if let Err(e) = self.refresh(&repo).await {
+                // Repos can be moved or deleted behind our back.
+                tracing::debug!("Can't index repo {}: {}", repo.path.display(), e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn refresh_requested(&self) {
+        let requested = mem::take(
+            &mut *self
+                .requested
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        );
+        for repo_id in requested {
+            let result = match Repo::find_by_id(&self.db.pool, repo_id).await {
+                Ok(Some(repo)) => self.refresh(&repo).await.map(|_| ()),
+                Ok(None) => Ok(()),
+                Err(e) => Err(e.into()),
+            };
+            if let Err(e) = result {
+                tracing::warn!("Can't index repo {}: {}", repo_id, e);
+            }
+        }
+    }
+
+    /// Bring the index of `repo` up to date. Returns whether anything changed.
+    pub async fn refresh(&self, repo: &Repo) -> Result<bool, RepoIndexError> {
+        let state = RepoMetadata::state(&self.db.pool, repo.id).await?;
+        let (indexed_fingerprint, since) = match state {
+            Some(state) => (Some(state.refs_fingerprint), state.head_commit),
+            None => (None, None),
+        };
+
+        let git = self.git.clone();
+        let repo_path = repo.path.clone();
+        let update = tokio::task::spawn_blocking(move || {
+            // Taken first, so refs moving while we read them only cause
+            // another refresh.
+            let refs_fingerprint = git.refs_fingerprint(&repo_path)?;
+            if indexed_fingerprint.as_deref() == Some(refs_fingerprint.as_str()) {
+                return Ok::<_, GitServiceError>(None);
+            }
+
+            let branches = git
+                .get_all_branches(&repo_path)?
+                .into_iter()
+                .map(|branch| RepoBranch {
+                    name: branch.name,
+                    is_remote: branch.is_remote,
+                    is_current: branch.is_current,
+                    last_commit_date: branch.last_commit_date,
+                })
+                .collect();
+            let log = git.recent_commits(&repo_path, since.as_deref(), MAX_INDEXED_COMMITS)?;
+            let head_commit = match log.commits.first() {
+                Some(commit) => Some(commit.sha.clone()),
+                None if log.incremental => since,
+                None => None,
+            };
+            let commits = log
+                .commits
+                .into_iter()
+                .map(|commit| RepoCommit {
+                    sha: commit.sha,
+                    summary: commit.summary,
+                    author_name: commit.author_name,
+                    author_email: commit.author_email,
+                    committed_at: commit.committed_at,
+                })
+                .collect();
+
+            Ok(Some(RepoIndexUpdate {
+                refs_fingerprint,
+                head_commit,
+                branches,
+                commits,
+                incremental: log.incremental,
+            }))
+        })
+        .await??;
+
+        let Some(update) = update else {
+            return Ok(false);
+        };
+        RepoMetadata::apply(&self.db.pool, repo.id, &update, MAX_INDEXED_COMMITS).await?;
+        Ok(true)
+    }
+}
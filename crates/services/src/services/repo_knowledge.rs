@@ -0,0 +1,144 @@
+//! A per-repo knowledge base carried across sessions.
+//!
+//! Approved entries of every repo in a workspace are appended to the prompt
+//! of the first coding agent turn of each session, so agents start out
+//! knowing the build commands, gotchas and architecture notes earlier
+//! sessions learned.
+
+use db::models::{
+    repo_knowledge::{RepoKnowledge, RepoKnowledgeCategory},
+    workspace_repo::WorkspaceRepo,
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Append the approved knowledge of the workspace's repos to `prompt`. The
+/// prompt is returned unchanged if the knowledge can't be loaded.
+pub async fn with_repo_knowledge(pool: &SqlitePool, workspace_id: Uuid, prompt: String) -> String {
+    let loaded = async {
+        let entries = RepoKnowledge::find_approved_for_workspace(pool, workspace_id).await?;
+        if entries.is_empty() {
+            return Ok(Vec::new());
+        }
+        let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace_id).await?;
+        Ok::<_, sqlx::Error>(
+            repos
+                .into_iter()
+                .map(|repo| {
+                    let repo_entries = entries
+                        .iter()
+                        .filter(|entry| entry.repo_id == repo.id)
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    (repo.display_name, repo_entries)
+                })
+                .filter(|(_, repo_entries)| !repo_entries.is_empty())
+                .collect::<Vec<_>>(),
+        )
+    }
+    .await;
+
+    match loaded {
+        Ok(knowledge) => render_knowledge_prompt(&prompt, &knowledge),
+        Err(e) => {
+            tracing::warn!(
+                "Failed to load repo knowledge for workspace {}: {}",
+                workspace_id,
+                e
+            );
+            prompt
+        }
+    }
+}
+
+/// Append `knowledge`, grouped by repo and category, to `prompt`.
+pub fn render_knowledge_prompt(prompt: &str, knowledge: &[(String, Vec<RepoKnowledge>)]) -> String {
+    if knowledge.is_empty() {
+        return prompt.to_string();
+    }
+
+    let mut rendered =
+        format!("{prompt}\n\nNotes about this codebase saved from earlier sessions:\n");
+    for (repo_name, entries) in knowledge {
+        if knowledge.len() > 1 {
+            rendered.push_str(&format!("\n## {repo_name}\n"));
+        }
+        for category in [
+            RepoKnowledgeCategory::Build,
+            RepoKnowledgeCategory::Gotcha,
+            RepoKnowledgeCategory::Architecture,
+            RepoKnowledgeCategory::Other,
+        ] {
+            let contents = entries
+                .iter()
+                .filter(|entry| entry.category == category)
+                .map(|entry| entry.content.trim())
+                .collect::<Vec<_>>();
+            if contents.is_empty() {
+                continue;
+            }
+            rendered.push_str(&format!("\n{}:\n", category.title()));
+            for content in contents {
+                rendered.push_str(&format!("- {}\n", content.replace('\n', "\n  ")));
+            }
+        }
+    }
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use db::models::repo_knowledge::RepoKnowledgeStatus;
+
+    use super::*;
+
+    fn entry(category: RepoKnowledgeCategory, content: &str) -> RepoKnowledge {
+        RepoKnowledge {
+            id: Uuid::new_v4(),
+            repo_id: Uuid::new_v4(),
+            category,
+            content: content.to_string(),
+            status: RepoKnowledgeStatus::Approved,
+            session_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn appends_knowledge_grouped_by_category() {
+        assert_eq!(render_knowledge_prompt("Fix it", &[]), "Fix it");
+
+        let knowledge = vec![(
+            "app".to_string(),
+            vec![
+                entry(RepoKnowledgeCategory::Gotcha, "Run migrations first"),
+                entry(RepoKnowledgeCategory::Build, "pnpm test\nneeds node 20"),
+            ],
+        )];
+        assert_eq!(
+            render_knowledge_prompt("Fix it", &knowledge),
+            "Fix it\n\nNotes about this codebase saved from earlier sessions:\n\
+             \nBuild and test:\n- pnpm test\n  needs node 20\n\
+             \nGotchas:\n- Run migrations first\n"
+        );
+    }
+
+    #[test]
+    fn names_repos_when_there_are_several() {
+        let knowledge = vec![
+            (
+                "api".to_string(),
+                vec![entry(RepoKnowledgeCategory::Other, "Uses axum")],
+            ),
+            (
+                "web".to_string(),
+                vec![entry(RepoKnowledgeCategory::Other, "Uses React")],
+            ),
+        ];
+        let rendered = render_knowledge_prompt("Fix it", &knowledge);
+        assert!(rendered.contains("\n## api\n\nNotes:\n- Uses axum\n"));
+        assert!(rendered.contains("\n## web\n\nNotes:\n- Uses React\n"));
+    }
+}
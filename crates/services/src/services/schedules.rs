@@ -0,0 +1,368 @@
+//! Scheduled agent runs.
+//!
+//! A schedule pairs a cron expression with a repo, prompt and executor config.
+//! The server's scheduler loop picks up schedules whose `next_run_at` has
+//! passed, creates a workspace and starts the prompt in it. This module owns
+//! validation and the cron arithmetic; the loop itself lives in the server
+//! since workspace creation goes through the deployment.
+//!
+//! Schedules with `open_pr` set push the run's branch and open a pull request
+//! once the repo's verify script passes, which is what the dependency update
+//! bot (see [`super::dependency_updates`]) relies on.
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, Local, Utc};
+use croner::Cron;
+use db::models::{
+    coding_agent_turn::CodingAgentTurn,
+    execution_process::{ExecutionContext, ExecutionProcess, ExecutionProcessStatus},
+    pull_request::PullRequest,
+    repo::{Repo, RepoError},
+    schedule::{
+        CreateSchedule, Schedule, ScheduleError as ScheduleModelError, ScheduleRun,
+        ScheduleRunStatus, UpdateSchedule,
+    },
+    workspace_repo::WorkspaceRepo,
+};
+use git::{GitService, GitServiceError};
+use git_host::{CreatePrRequest, GitHostError, GitHostProvider, GitHostService};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use uuid::Uuid;
+
+const MAX_SCHEDULE_NAME_LEN: usize = 128;
+
+/// Schedules may not fire more often than this, since every run creates a
+/// workspace and starts an agent.
+pub const MIN_SCHEDULE_INTERVAL_MINUTES: i64 = 15;
+
+#[derive(Debug, Error)]
+pub enum ScheduleError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Schedule(#[from] ScheduleModelError),
+    #[error(transparent)]
+    Repo(#[from] RepoError),
+    #[error("Invalid schedule name")]
+    InvalidName,
+    #[error("A schedule prompt is required")]
+    EmptyPrompt,
+    #[error("Invalid cron expression `{expression}`: {reason}")]
+    InvalidCron { expression: String, reason: String },
+    #[error(
+        "Schedules may not run more often than every {} minutes",
+        MIN_SCHEDULE_INTERVAL_MINUTES
+    )]
+    TooFrequent,
+    #[error("Schedules that open pull requests need a verify script on the repo")]
+    VerifyScriptRequired,
+    #[error("Pick at least one package ecosystem to update")]
+    NoEcosystems,
+}
+
+/// Why a run of an `open_pr` schedule didn't open a pull request. Recorded on
+/// the run.
+#[derive(Debug, Error)]
+enum OpenPrError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error(transparent)]
+    GitHost(#[from] GitHostError),
+    #[error("The run was stopped")]
+    Stopped,
+    #[error("The verify script failed")]
+    VerifyFailed,
+    #[error("The verify script didn't run, so the changes weren't verified")]
+    NotVerified,
+    #[error("The agent made no changes")]
+    NoChanges,
+    #[error("The workspace has no worktree for repo {0}")]
+    MissingWorktree(String),
+}
+
+fn parse_cron(expression: &str) -> Result<Cron, ScheduleError> {
+    Cron::new(expression.trim())
+        .parse()
+        .map_err(|e| ScheduleError::InvalidCron {
+            expression: expression.to_string(),
+            reason: e.to_string(),
+        })
+}
+
+/// The first time strictly after `after` that `expression` fires, evaluated
+/// in local time.
+pub fn next_run_after(expression: &str, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let cron = parse_cron(expression).ok()?;
+    cron.find_next_occurrence(&after.with_timezone(&Local), false)
+        .ok()
+        .map(|next| next.with_timezone(&Utc))
+}
+
+/// Check that `expression` parses and does not fire more often than
+/// [`MIN_SCHEDULE_INTERVAL_MINUTES`].
+pub fn validate_cron(expression: &str) -> Result<(), ScheduleError> {
+    let cron = parse_cron(expression)?;
+    let mut time = Local::now();
+    // Sample a handful of consecutive runs; irregular expressions such as
+    // `0,5 * * * *` only show their shortest gap between some pairs.
+    let mut previous = None;
+    for _ in 0..32 {
+        let next = match cron.find_next_occurrence(&time, false) {
+            Ok(next) => next,
+            Err(e) => {
+                return Err(ScheduleError::InvalidCron {
+                    expression: expression.to_string(),
+                    reason: e.to_string(),
+                });
+            }
+        };
+        if let Some(previous) = previous
+            && next - previous < chrono::Duration::minutes(MIN_SCHEDULE_INTERVAL_MINUTES)
+        {
+            return Err(ScheduleError::TooFrequent);
+        }
+        previous = Some(next);
+        time = next;
+    }
+    Ok(())
+}
+
+fn validate_fields(name: &str, prompt: &str) -> Result<(), ScheduleError> {
+    let name = name.trim();
+    if name.is_empty() || name.len() > MAX_SCHEDULE_NAME_LEN {
+        return Err(ScheduleError::InvalidName);
+    }
+    if prompt.trim().is_empty() {
+        return Err(ScheduleError::EmptyPrompt);
+    }
+    Ok(())
+}
+
+/// Pull requests are only opened for verified changes, so the repo has to
+/// have a verify script.
+async fn ensure_verify_script(pool: &SqlitePool, repo_id: Uuid) -> Result<(), ScheduleError> {
+    match Repo::verify_script(pool, repo_id).await? {
+        Some(script) if !script.trim().is_empty() => Ok(()),
+        _ => Err(ScheduleError::VerifyScriptRequired),
+    }
+}
+
+pub async fn create_schedule(
+    pool: &SqlitePool,
+    data: &CreateSchedule,
+) -> Result<Schedule, ScheduleError> {
+    validate_fields(&data.name, &data.prompt)?;
+    validate_cron(&data.cron_expression)?;
+    if data.open_pr {
+        ensure_verify_script(pool, data.repo_id).await?;
+    }
+    let next_run_at = next_run_after(&data.cron_expression, Utc::now());
+    Ok(Schedule::create(pool, data, next_run_at).await?)
+}
+
+pub async fn update_schedule(
+    pool: &SqlitePool,
+    id: Uuid,
+    data: &UpdateSchedule,
+) -> Result<Schedule, ScheduleError> {
+    let existing = Schedule::find_by_id(pool, id)
+        .await?
+        .ok_or(ScheduleModelError::NotFound)?;
+    validate_fields(
+        data.name.as_deref().unwrap_or(&existing.name),
+        data.prompt.as_deref().unwrap_or(&existing.prompt),
+    )?;
+    let cron_expression = data
+        .cron_expression
+        .as_deref()
+        .unwrap_or(&existing.cron_expression);
+    validate_cron(cron_expression)?;
+    if data.open_pr.unwrap_or(existing.open_pr) {
+        ensure_verify_script(pool, existing.repo_id).await?;
+    }
+
+    let next_run_at = if existing.paused {
+        None
+    } else {
+        next_run_after(cron_expression, Utc::now())
+    };
+    Ok(Schedule::update(pool, id, data, next_run_at).await?)
+}
+
+/// Pause or resume a schedule. Resuming does not catch up on runs missed
+/// while paused; the next run is the next cron tick from now.
+pub async fn set_paused(
+    pool: &SqlitePool,
+    id: Uuid,
+    paused: bool,
+) -> Result<Schedule, ScheduleError> {
+    let existing = Schedule::find_by_id(pool, id)
+        .await?
+        .ok_or(ScheduleModelError::NotFound)?;
+    let next_run_at = if paused {
+        None
+    } else {
+        next_run_after(&existing.cron_expression, Utc::now())
+    };
+    Ok(Schedule::set_paused(pool, id, paused, next_run_at).await?)
+}
+
+/// A workspace's actions have finished; if a schedule that opens pull
+/// requests started it, open one for verified changes and record the outcome
+/// on the run. Pushing and opening the pull request happen in the background.
+pub async fn report_run_outcome(
+    pool: &SqlitePool,
+    git: &GitService,
+    ctx: &ExecutionContext,
+) -> Result<(), ScheduleError> {
+    let Some(run) = ScheduleRun::find_by_workspace_id(pool, ctx.workspace.id).await? else {
+        return Ok(());
+    };
+    // Later follow-ups in the workspace don't open another pull request.
+    if run.status != ScheduleRunStatus::Started || run.pr_url.is_some() || run.error.is_some() {
+        return Ok(());
+    }
+    let Some(schedule) = Schedule::find_by_id(pool, run.schedule_id).await? else {
+        return Ok(());
+    };
+    if !schedule.open_pr {
+        return Ok(());
+    }
+
+    let pool = pool.clone();
+    let git = git.clone();
+    let ctx = ctx.clone();
+    tokio::spawn(async move {
+        let (pr_url, error) = match open_pr(&pool, &git, &ctx, &schedule).await {
+            Ok(url) => (Some(url), None),
+            Err(e) => {
+                tracing::info!(
+                    "Scheduled run of {} opened no pull request: {}",
+                    schedule.name,
+                    e
+                );
+                (None, Some(e.to_string()))
+            }
+        };
+        if let Err(e) =
+            ScheduleRun::set_outcome(&pool, run.id, pr_url.as_deref(), error.as_deref()).await
+        {
+            tracing::error!("Failed to record outcome of schedule run {}: {}", run.id, e);
+        }
+    });
+    Ok(())
+}
+
+async fn open_pr(
+    pool: &SqlitePool,
+    git: &GitService,
+    ctx: &ExecutionContext,
+    schedule: &Schedule,
+) -> Result<String, OpenPrError> {
+    if ctx.execution_process.status == ExecutionProcessStatus::Killed {
+        return Err(OpenPrError::Stopped);
+    }
+    let verified = ExecutionProcess::latest_turn_verify_passed(pool, ctx.session.id).await?;
+    if verified == Some(false) {
+        return Err(OpenPrError::VerifyFailed);
+    }
+
+    let repo = ctx
+        .repos
+        .iter()
+        .find(|repo| repo.id == schedule.repo_id)
+        .ok_or_else(|| OpenPrError::MissingWorktree(schedule.repo_id.to_string()))?;
+    let base_branch = WorkspaceRepo::find_by_workspace_and_repo_id(pool, ctx.workspace.id, repo.id)
+        .await?
+        .map_or_else(
+            || schedule.target_branch.clone(),
+            |workspace_repo| workspace_repo.target_branch,
+        );
+    let (ahead, _) = git.get_branch_status(&repo.path, &ctx.workspace.branch, &base_branch)?;
+    if ahead == 0 {
+        return Err(OpenPrError::NoChanges);
+    }
+    if verified.is_none() {
+        return Err(OpenPrError::NotVerified);
+    }
+
+    let worktree_path = ctx
+        .workspace
+        .container_ref
+        .as_ref()
+        .map(|container_ref| PathBuf::from(container_ref).join(&repo.name))
+        .ok_or_else(|| OpenPrError::MissingWorktree(repo.name.clone()))?;
+    let remote = git.resolve_remote_for_branch(&repo.path, &ctx.workspace.branch)?;
+    git.push_to_remote(&worktree_path, &ctx.workspace.branch, false)?;
+
+    let findings = CodingAgentTurn::find_latest_summary_for_session(pool, ctx.session.id)
+        .await?
+        .unwrap_or_default();
+    let request = CreatePrRequest {
+        title: schedule.name.clone(),
+        body: Some(
+            format!(
+                "{}\n\n---\nOpened by the scheduled run `{}` after the verify script passed.",
+                findings.trim(),
+                schedule.name
+            )
+            .trim_start()
+            .to_string(),
+        ),
+        head_branch: ctx.workspace.branch.clone(),
+        base_branch: base_branch.clone(),
+        draft: None,
+        head_repo_url: Some(remote.url.clone()),
+    };
+    let pr = GitHostService::from_url(&remote.url)?
+        .create_pr(&repo.path, &remote.url, &request)
+        .await?;
+    PullRequest::create_for_workspace(
+        pool,
+        ctx.workspace.id,
+        repo.id,
+        &base_branch,
+        pr.number,
+        &pr.url,
+    )
+    .await?;
+    Ok(pr.url)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Timelike;
+
+    use super::*;
+
+    #[test]
+    fn computes_next_run() {
+        let now = Utc::now();
+        let next = next_run_after("30 3 * * *", now).unwrap();
+        assert!(next > now);
+        let local = next.with_timezone(&Local);
+        assert_eq!((local.hour(), local.minute()), (3, 30));
+    }
+
+    #[test]
+    fn rejects_invalid_and_too_frequent_expressions() {
+        assert!(matches!(
+            validate_cron("not a cron"),
+            Err(ScheduleError::InvalidCron { .. })
+        ));
+        assert!(matches!(
+            validate_cron("*/5 * * * *"),
+            Err(ScheduleError::TooFrequent)
+        ));
+        assert!(matches!(
+            validate_cron("0,10 * * * *"),
+            Err(ScheduleError::TooFrequent)
+        ));
+        assert!(validate_cron("0 2 * * 1-5").is_ok());
+        assert!(validate_cron("*/15 * * * *").is_ok());
+    }
+}
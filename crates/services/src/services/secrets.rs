@@ -0,0 +1,288 @@
+//! Encrypted secrets for script environments.
+//!
+//! Secrets are encrypted at rest with AES-256-GCM using a random key stored in
+//! the asset directory (readable only by the current user). Scripts reference
+//! them as `${secret:NAME}`; before spawning, each reference is rewritten to a
+//! shell environment variable reference and the decrypted value is passed via
+//! the process environment, so the value never appears in the stored script or
+//! the executor action. Values are also redacted from process output (see
+//! [`crate::services::log_redaction`]).
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use aes_gcm::{
+    Aes256Gcm, Key, Nonce,
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+};
+use db::models::secret::{Secret, SecretSummary};
+use once_cell::sync::{Lazy, OnceCell};
+use regex::Regex;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use utils::assets::secrets_key_path;
+
+const MAX_SECRET_NAME_LEN: usize = 128;
+const MAX_SECRET_VALUE_LEN: usize = 64 * 1024;
+
+static SECRET_REFERENCE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\$\{secret:([A-Za-z_][A-Za-z0-9_]*)\}").expect("secret reference regex")
+});
+
+static MACHINE_KEY: OnceCell<Key<Aes256Gcm>> = OnceCell::new();
+
+#[derive(Debug, Error)]
+pub enum SecretError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("Invalid secret name: {0}")]
+    InvalidName(String),
+    #[error("Secret value is empty or too large")]
+    InvalidValue,
+    #[error("Secret not found: {0}")]
+    NotFound(String),
+    #[error("Script references undefined secrets: {}", .0.join(", "))]
+    Missing(Vec<String>),
+    #[error("Failed to decrypt secret {0}; it may have been created on another machine")]
+    Decrypt(String),
+    #[error("Failed to encrypt secret")]
+    Encrypt,
+}
+
+fn load_or_create_key(path: &Path) -> io::Result<Key<Aes256Gcm>> {
+    match fs::read(path) {
+        Ok(bytes) => {
+            let arr: [u8; 32] = bytes.try_into().map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "secrets key file has invalid length (expected 32 bytes)",
+                )
+            })?;
+            return Ok(Key::<Aes256Gcm>::from(arr));
+        }
+        // Any other failure must not replace the key, or every stored secret
+        // becomes undecryptable.
+        Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+        Err(_) => {}
+    }
+
+    let key = Aes256Gcm::generate_key(OsRng);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("tmp");
+
+    let mut file = {
+        let mut opts = fs::OpenOptions::new();
+        opts.create(true).truncate(true).write(true);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            opts.mode(0o600);
+        }
+
+        opts.open(&tmp)?
+    };
+    file.write_all(key.as_slice())?;
+    file.sync_all()?;
+    drop(file);
+
+    fs::rename(&tmp, path)?;
+    Ok(key)
+}
+
+fn cipher() -> Result<Aes256Gcm, SecretError> {
+    let key = MACHINE_KEY.get_or_try_init(|| load_or_create_key(&secrets_key_path()))?;
+    Ok(Aes256Gcm::new(key))
+}
+
+pub fn validate_name(name: &str) -> Result<(), SecretError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_SECRET_NAME_LEN
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(SecretError::InvalidName(name.to_string()))
+    }
+}
+
+pub async fn list_secrets(pool: &SqlitePool) -> Result<Vec<SecretSummary>, SecretError> {
+    Ok(Secret::list(pool).await?)
+}
+
+pub async fn set_secret(
+    pool: &SqlitePool,
+    name: &str,
+    value: &str,
+) -> Result<SecretSummary, SecretError> {
+    validate_name(name)?;
+    if value.is_empty() || value.len() > MAX_SECRET_VALUE_LEN {
+        return Err(SecretError::InvalidValue);
+    }
+
+    let (nonce, ciphertext) = encrypt_value(value)?;
+    Ok(Secret::upsert(pool, name, &nonce, &ciphertext).await?)
+}
+
+/// Encrypt a value with the machine key, returning the nonce and ciphertext.
+/// Shared with other stores of sensitive values, such as git credentials.
+pub(crate) fn encrypt_value(value: &str) -> Result<(Vec<u8>, Vec<u8>), SecretError> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher()?
+        .encrypt(&nonce, value.as_bytes())
+        .map_err(|_| SecretError::Encrypt)?;
+    Ok((nonce.to_vec(), ciphertext))
+}
+
+/// Decrypt a value encrypted by [`encrypt_value`]. `label` names the value in
+/// the error.
+pub(crate) fn decrypt_value(
+    nonce: &[u8],
+    ciphertext: &[u8],
+    label: &str,
+) -> Result<String, SecretError> {
+    if nonce.len() != 12 {
+        return Err(SecretError::Decrypt(label.to_string()));
+    }
+    let plaintext = cipher()?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| SecretError::Decrypt(label.to_string()))?;
+    String::from_utf8(plaintext).map_err(|_| SecretError::Decrypt(label.to_string()))
+}
+
+pub async fn delete_secret(pool: &SqlitePool, name: &str) -> Result<(), SecretError> {
+    if Secret::delete_by_name(pool, name).await? == 0 {
+        return Err(SecretError::NotFound(name.to_string()));
+    }
+    Ok(())
+}
+
+fn decrypt(secret: &Secret) -> Result<String, SecretError> {
+    decrypt_value(&secret.nonce, &secret.ciphertext, &secret.name)
+}
+
+/// Decrypted values of every stored secret, for redaction. Secrets that fail
+/// to decrypt are skipped.
+pub async fn all_secret_values(pool: &SqlitePool) -> Result<Vec<String>, SecretError> {
+    let secrets = Secret::find_all(pool).await?;
+    Ok(secrets
+        .iter()
+        .filter_map(|secret| decrypt(secret).ok())
+        .collect())
+}
+
+/// Name of the environment variable a secret is passed through.
+pub fn secret_env_var(name: &str) -> String {
+    format!("VK_SECRET_{name}")
+}
+
+pub fn has_secret_references(script: &str) -> bool {
+    SECRET_REFERENCE.is_match(script)
+}
+
+/// Rewrite `${secret:NAME}` references into environment variable references
+/// for the platform shell, returning the script and the referenced names.
+fn rewrite_references(script: &str) -> (String, Vec<String>) {
+    let mut names = Vec::new();
+    let rewritten = SECRET_REFERENCE.replace_all(script, |caps: &regex::Captures| {
+        let name = caps[1].to_string();
+        let var = secret_env_var(&name);
+        if !names.contains(&name) {
+            names.push(name);
+        }
+        if cfg!(windows) {
+            format!("%{var}%")
+        } else {
+            format!("${{{var}}}")
+        }
+    });
+    (rewritten.into_owned(), names)
+}
+
+/// Prepare a script for spawning: rewrite secret references and return the
+/// environment variables that carry their values. Fails if any referenced
+/// secret is not defined, so scripts never run with silently empty values.
+pub async fn prepare_script(
+    pool: &SqlitePool,
+    script: &str,
+) -> Result<(String, HashMap<String, String>), SecretError> {
+    let (rewritten, names) = rewrite_references(script);
+
+    let mut env = HashMap::new();
+    let mut missing = Vec::new();
+    for name in names {
+        match Secret::find_by_name(pool, &name).await? {
+            Some(secret) => {
+                env.insert(secret_env_var(&name), decrypt(&secret)?);
+            }
+            None => missing.push(name),
+        }
+    }
+    if !missing.is_empty() {
+        return Err(SecretError::Missing(missing));
+    }
+
+    Ok((rewritten, env))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewrites_references_to_env_vars() {
+        let (script, names) =
+            rewrite_references("curl -H \"Auth: ${secret:API_TOKEN}\" ${secret:API_TOKEN} $HOME");
+        assert_eq!(names, vec!["API_TOKEN".to_string()]);
+        if cfg!(windows) {
+            assert_eq!(
+                script,
+                "curl -H \"Auth: %VK_SECRET_API_TOKEN%\" %VK_SECRET_API_TOKEN% $HOME"
+            );
+        } else {
+            assert_eq!(
+                script,
+                "curl -H \"Auth: ${VK_SECRET_API_TOKEN}\" ${VK_SECRET_API_TOKEN} $HOME"
+            );
+        }
+    }
+
+    #[test]
+    fn validates_names() {
+        assert!(validate_name("NPM_TOKEN").is_ok());
+        assert!(validate_name("_private2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("1TOKEN").is_err());
+        assert!(validate_name("MY-TOKEN").is_err());
+    }
+
+    #[test]
+    fn creates_the_key_once_and_keeps_it_on_read_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secrets.key");
+
+        let key = load_or_create_key(&path).unwrap();
+        assert_eq!(load_or_create_key(&path).unwrap(), key);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // A key path that can't be read is an error, not a missing key.
+        let unreadable = dir.path().join("unreadable");
+        fs::create_dir(&unreadable).unwrap();
+        assert!(load_or_create_key(&unreadable).is_err());
+        assert!(unreadable.is_dir());
+    }
+}
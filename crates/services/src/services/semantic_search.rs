@@ -0,0 +1,536 @@
+//! Embedding-backed code search over repo files.
+//!
+//! Files are split into overlapping windows of lines, and each window is
+//! embedded and kept in memory per directory. A search first brings the
+//! directory's index up to date, re-embedding only files whose size or
+//! modification time changed, so after the first search of a worktree later
+//! ones cost little more than embedding the query.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use dashmap::DashMap;
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::Mutex;
+use ts_rs::TS;
+use utils::path::ALWAYS_SKIP_DIRS;
+use uuid::Uuid;
+
+use super::{
+    config::{EmbeddingProvider, SemanticSearchConfig},
+    file_search::SearchRoot,
+};
+
+pub const SEMANTIC_SEARCH_MAX_RESULTS: usize = 100;
+/// Larger files are mostly generated or data, and are not indexed.
+const MAX_FILE_BYTES: u64 = 256 * 1024;
+const MAX_FILES_PER_ROOT: usize = 10_000;
+const CHUNK_LINES: usize = 40;
+const CHUNK_STRIDE: usize = 30;
+const SNIPPET_MAX_CHARS: usize = 2_000;
+const LOCAL_DIMENSIONS: usize = 512;
+const ENDPOINT_BATCH_SIZE: usize = 32;
+const ENDPOINT_TIMEOUT: Duration = Duration::from_secs(60);
+const SKIPPED_FILE_NAMES: &[&str] = &[
+    "Cargo.lock",
+    "package-lock.json",
+    "pnpm-lock.yaml",
+    "yarn.lock",
+];
+
+#[derive(Debug, Error)]
+pub enum SemanticSearchError {
+    #[error("Semantic search is not configured: {0}")]
+    NotConfigured(&'static str),
+    #[error("Embeddings request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Embeddings endpoint returned an unexpected response: {0}")]
+    InvalidResponse(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Join(#[from] tokio::task::JoinError),
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SemanticSearchResult {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    /// Relative to the repo root.
+    pub path: String,
+    /// First and last line of the match, 1-based and inclusive.
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Cosine similarity to the query.
+    pub score: f32,
+    pub snippet: String,
+}
+
+enum Embedder {
+    Local,
+    Endpoint {
+        client: reqwest::Client,
+        url: String,
+        model: String,
+        api_key: Option<String>,
+    },
+}
+
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+    #[serde(default)]
+    index: Option<usize>,
+}
+
+impl Embedder {
+    fn from_config(
+        config: &SemanticSearchConfig,
+        client: &reqwest::Client,
+    ) -> Result<Self, SemanticSearchError> {
+        match config.provider {
+            EmbeddingProvider::Local => Ok(Self::Local),
+            EmbeddingProvider::Endpoint => Ok(Self::Endpoint {
+                client: client.clone(),
+                url: config
+                    .endpoint_url
+                    .clone()
+                    .ok_or(SemanticSearchError::NotConfigured("no endpoint URL"))?,
+                model: config
+                    .model
+                    .clone()
+                    .ok_or(SemanticSearchError::NotConfigured("no embeddings model"))?,
+                api_key: config
+                    .api_key_env
+                    .as_deref()
+                    .and_then(|name| std::env::var(name).ok()),
+            }),
+        }
+    }
+
+    /// Identifies the embedding space; indexes built in another are discarded.
+    fn fingerprint(&self) -> String {
+        match self {
+            Self::Local => "local".to_string(),
+            Self::Endpoint { url, model, .. } => format!("{url}#{model}"),
+        }
+    }
+
+    /// Unit-length embeddings of `texts`, in order.
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, SemanticSearchError> {
+        let Self::Endpoint {
+            client,
+            url,
+            model,
+            api_key,
+        } = self
+        else {
+            return Ok(texts.iter().map(|text| local_embedding(text)).collect());
+        };
+
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(ENDPOINT_BATCH_SIZE) {
+            let mut request = client
+                .post(url)
+                .timeout(ENDPOINT_TIMEOUT)
+                .json(&EmbeddingRequest {
+                    model,
+                    input: batch,
+                });
+            if let Some(api_key) = api_key {
+                request = request.bearer_auth(api_key);
+            }
+            let mut response: EmbeddingResponse =
+                request.send().await?.error_for_status()?.json().await?;
+            if response.data.len() != batch.len() {
+                return Err(SemanticSearchError::InvalidResponse(format!(
+                    "expected {} embeddings, got {}",
+                    batch.len(),
+                    response.data.len()
+                )));
+            }
+            response.data.sort_by_key(|data| data.index);
+            vectors.extend(response.data.into_iter().map(|data| {
+                let mut vector = data.embedding;
+                normalize(&mut vector);
+                vector
+            }));
+        }
+        Ok(vectors)
+    }
+}
+
+struct Chunk {
+    start_line: usize,
+    end_line: usize,
+    vector: Vec<f32>,
+}
+
+struct IndexedFile {
+    modified: Option<SystemTime>,
+    len: u64,
+    chunks: Vec<Chunk>,
+}
+
+#[derive(Default)]
+struct RootIndex {
+    fingerprint: String,
+    files: HashMap<String, IndexedFile>,
+}
+
+struct ListedFile {
+    path: String,
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+#[derive(Clone, Default)]
+pub struct SemanticSearchService {
+    indexes: Arc<DashMap<PathBuf, Arc<Mutex<RootIndex>>>>,
+    client: reqwest::Client,
+}
+
+impl SemanticSearchService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn search(
+        &self,
+        config: &SemanticSearchConfig,
+        roots: &[SearchRoot],
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SemanticSearchResult>, SemanticSearchError> {
+        let embedder = Embedder::from_config(config, &self.client)?;
+        let query_vector = embedder
+            .embed(&[query.to_string()])
+            .await?
+            .pop()
+            .ok_or_else(|| SemanticSearchError::InvalidResponse("no embedding".to_string()))?;
+
+        let mut scored = Vec::new();
+        for (root_index, root) in roots.iter().enumerate() {
+            let index = self.indexes.entry(root.path.clone()).or_default().clone();
+            let mut index = index.lock().await;
+            refresh_index(&mut index, &root.path, &embedder).await?;
+            for (path, file) in &index.files {
+                for chunk in &file.chunks {
+                    let score = dot(&query_vector, &chunk.vector);
+                    scored.push((
+                        score,
+                        root_index,
+                        path.clone(),
+                        chunk.start_line,
+                        chunk.end_line,
+                    ));
+                }
+            }
+        }
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        scored.truncate(limit.min(SEMANTIC_SEARCH_MAX_RESULTS));
+
+        let mut results = Vec::with_capacity(scored.len());
+        for (score, root_index, path, start_line, end_line) in scored {
+            let root = &roots[root_index];
+            let snippet = tokio::fs::read_to_string(root.path.join(&path))
+                .await
+                .map(|content| snippet(&content, start_line, end_line))
+                .unwrap_or_default();
+            results.push(SemanticSearchResult {
+                repo_id: root.repo_id,
+                repo_name: root.repo_name.clone(),
+                path,
+                start_line,
+                end_line,
+                score,
+                snippet,
+            });
+        }
+        Ok(results)
+    }
+}
+
+/// Bring `index` in line with the files under `root`.
+async fn refresh_index(
+    index: &mut RootIndex,
+    root: &Path,
+    embedder: &Embedder,
+) -> Result<(), SemanticSearchError> {
+    let fingerprint = embedder.fingerprint();
+    if index.fingerprint != fingerprint {
+        index.files.clear();
+        index.fingerprint = fingerprint;
+    }
+
+    let listing = {
+        let root = root.to_path_buf();
+        tokio::task::spawn_blocking(move || list_files(&root)).await?
+    };
+    let present: HashSet<&str> = listing.iter().map(|file| file.path.as_str()).collect();
+    index
+        .files
+        .retain(|path, _| present.contains(path.as_str()));
+
+    let stale: Vec<&ListedFile> = listing
+        .iter()
+        .filter(|file| {
+            index
+                .files
+                .get(&file.path)
+                .is_none_or(|indexed| indexed.modified != file.modified || indexed.len != file.len)
+        })
+        .collect();
+    let mut pending: Vec<(&ListedFile, Vec<(usize, usize)>)> = Vec::new();
+    let mut texts = Vec::new();
+    for file in stale {
+        let content = match tokio::fs::read(root.join(&file.path)).await {
+            Ok(bytes) if !bytes.contains(&0) => String::from_utf8(bytes).ok(),
+            Ok(_) => None,
+            Err(e) => {
+                tracing::debug!("Skipping {} for semantic index: {}", file.path, e);
+                continue;
+            }
+        };
+        let mut ranges = Vec::new();
+        for (start_line, end_line, text) in content.as_deref().map(chunk_lines).unwrap_or_default()
+        {
+            ranges.push((start_line, end_line));
+            texts.push(format!("{}\n{}", file.path, text));
+        }
+        pending.push((file, ranges));
+
+        if texts.len() >= ENDPOINT_BATCH_SIZE {
+            store_embeddings(index, embedder, &mut pending, &mut texts).await?;
+        }
+    }
+    store_embeddings(index, embedder, &mut pending, &mut texts).await
+}
+
+/// Embed the pending chunks and add their files to the index. Files are only
+/// added once all their chunks are embedded, so a failed request leaves them
+/// to be retried by the next search.
+async fn store_embeddings(
+    index: &mut RootIndex,
+    embedder: &Embedder,
+    pending: &mut Vec<(&ListedFile, Vec<(usize, usize)>)>,
+    texts: &mut Vec<String>,
+) -> Result<(), SemanticSearchError> {
+    let mut vectors = embedder.embed(texts).await?.into_iter();
+    texts.clear();
+    for (file, ranges) in pending.drain(..) {
+        let chunks = ranges
+            .into_iter()
+            .zip(vectors.by_ref())
+            .map(|((start_line, end_line), vector)| Chunk {
+                start_line,
+                end_line,
+                vector,
+            })
+            .collect();
+        index.files.insert(
+            file.path.clone(),
+            IndexedFile {
+                modified: file.modified,
+                len: file.len,
+                chunks,
+            },
+        );
+    }
+    Ok(())
+}
+
+fn list_files(root: &Path) -> Vec<ListedFile> {
+    WalkBuilder::new(root)
+        .hidden(false)
+        .git_ignore(true)
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            !ALWAYS_SKIP_DIRS.contains(&name.as_ref())
+        })
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            !SKIPPED_FILE_NAMES.contains(&name.as_ref()) && !name.ends_with(".min.js")
+        })
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            if metadata.len() == 0 || metadata.len() > MAX_FILE_BYTES {
+                return None;
+            }
+            let path = entry.path().strip_prefix(root).ok()?;
+            Some(ListedFile {
+                path: path.to_string_lossy().replace('\\', "/"),
+                modified: metadata.modified().ok(),
+                len: metadata.len(),
+            })
+        })
+        .take(MAX_FILES_PER_ROOT)
+        .collect()
+}
+
+/// Overlapping windows of lines as `(start_line, end_line, text)`, with
+/// 1-based inclusive line numbers. Blank windows are skipped.
+fn chunk_lines(content: &str) -> Vec<(usize, usize, String)> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < lines.len() {
+        let end = (start + CHUNK_LINES).min(lines.len());
+        let text = lines[start..end].join("\n");
+        if !text.trim().is_empty() {
+            chunks.push((start + 1, end, text));
+        }
+        if end == lines.len() {
+            break;
+        }
+        start += CHUNK_STRIDE;
+    }
+    chunks
+}
+
+fn snippet(content: &str, start_line: usize, end_line: usize) -> String {
+    let text = content
+        .lines()
+        .skip(start_line.saturating_sub(1))
+        .take(end_line + 1 - start_line)
+        .collect::<Vec<_>>()
+        .join("\n");
+    match text.char_indices().nth(SNIPPET_MAX_CHARS) {
+        Some((cut, _)) => text[..cut].to_string(),
+        None => text,
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+/// Lowercased words of `text`, with identifiers split at case changes and
+/// underscores, so `authMiddleware` and `auth_middleware` share words.
+fn words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for token in text.split(|c: char| !c.is_alphanumeric()) {
+        let mut word = String::new();
+        let mut prev_lower = false;
+        for c in token.chars() {
+            if c.is_uppercase() && prev_lower && !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            prev_lower = c.is_lowercase() || c.is_ascii_digit();
+            word.extend(c.to_lowercase());
+        }
+        if !word.is_empty() {
+            words.push(word);
+        }
+    }
+    words
+        .into_iter()
+        .filter(|word| word.chars().count() > 1)
+        .map(|word| match word.strip_suffix('s') {
+            Some(stem) if stem.len() > 2 && !stem.ends_with('s') => stem.to_string(),
+            _ => word,
+        })
+        .collect()
+}
+
+fn feature_hash(feature: &str) -> u64 {
+    // FNV-1a; stable across runs, unlike the std hasher.
+    feature.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Hashed embedding of word and character-trigram counts.
+fn local_embedding(text: &str) -> Vec<f32> {
+    let mut counts: HashMap<String, f32> = HashMap::new();
+    for word in words(text) {
+        let padded: Vec<char> = format!("^{word}$").chars().collect();
+        for trigram in padded.windows(3) {
+            *counts.entry(trigram.iter().collect()).or_default() += 0.5;
+        }
+        *counts.entry(word).or_default() += 1.0;
+    }
+
+    let mut vector = vec![0.0; LOCAL_DIMENSIONS];
+    for (feature, count) in counts {
+        let hash = feature_hash(&feature);
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[(hash % LOCAL_DIMENSIONS as u64) as usize] += sign * (1.0 + count.ln_1p());
+    }
+    normalize(&mut vector);
+    vector
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_identifiers_into_words() {
+        assert_eq!(
+            words("fn authMiddleware(req: HttpRequest) -> auth_tokens"),
+            vec![
+                "fn",
+                "auth",
+                "middleware",
+                "req",
+                "http",
+                "request",
+                "auth",
+                "token"
+            ]
+        );
+    }
+
+    #[test]
+    fn chunks_overlap_and_cover_the_file() {
+        let content = (1..=75)
+            .map(|n| format!("line {n}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let ranges: Vec<(usize, usize)> = chunk_lines(&content)
+            .into_iter()
+            .map(|(start, end, _)| (start, end))
+            .collect();
+        assert_eq!(ranges, vec![(1, 40), (31, 70), (61, 75)]);
+        assert_eq!(snippet(&content, 74, 75), "line 74\nline 75");
+    }
+
+    #[test]
+    fn local_embeddings_match_identifier_variants() {
+        let query = local_embedding("auth middleware");
+        let middleware = local_embedding(
+            "pub async fn require_auth(req: Request, next: Next) -> Response {\n    \
+             // AuthMiddleware: reject requests without a session\n}",
+        );
+        let unrelated = local_embedding("fn render_sidebar(items: &[Item]) -> Html { todo!() }");
+        assert!(dot(&query, &middleware) > dot(&query, &unrelated));
+    }
+}
@@ -0,0 +1,190 @@
+//! Named restore points of a session.
+//!
+//! A checkpoint marks the state right after an execution process: the head
+//! commit that process left every workspace repo at, and the conversation up
+//! to and including it. Restoring drops the processes that ran after it (via
+//! [`ContainerService::reset_session_to_process`]) and resets the worktrees to
+//! the recorded commits. Uncommitted changes are not part of a checkpoint, so a
+//! restore refuses to discard them unless forced.
+
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    execution_process_repo_state::ExecutionProcessRepoState,
+    session::Session,
+    session_checkpoint::{CheckpointRepoState, SessionCheckpoint},
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use git::WorktreeResetOptions;
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{
+    container::{ContainerError, ContainerService},
+    workspace_undo,
+};
+
+const MAX_LABEL_LENGTH: usize = 100;
+
+#[derive(Debug, Error)]
+pub enum SessionCheckpointError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error("Checkpoint not found")]
+    NotFound,
+    #[error("Checkpoint labels must be between 1 and 100 characters")]
+    InvalidLabel,
+    #[error("Execution process not found in this session")]
+    ProcessNotFound,
+    #[error("Checkpoints can only be created after a process has finished")]
+    ProcessNotFinished,
+    #[error("This checkpoint was discarded by an earlier restore")]
+    Discarded,
+    #[error("The workspace has uncommitted changes. Commit or discard them, or force the restore.")]
+    UncommittedChanges,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateSessionCheckpointRequest {
+    pub label: String,
+    /// Defaults to the latest process of the session.
+    #[ts(optional)]
+    pub process_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+pub struct RestoreSessionCheckpointRequest {
+    /// Discard uncommitted changes in the workspace.
+    #[ts(optional)]
+    pub force_when_dirty: Option<bool>,
+}
+
+pub async fn create_checkpoint(
+    pool: &SqlitePool,
+    session: &Session,
+    request: &CreateSessionCheckpointRequest,
+) -> Result<SessionCheckpoint, SessionCheckpointError> {
+    let label = request.label.trim();
+    if label.is_empty() || label.chars().count() > MAX_LABEL_LENGTH {
+        return Err(SessionCheckpointError::InvalidLabel);
+    }
+
+    let processes = ExecutionProcess::find_by_session_id(pool, session.id, false).await?;
+    let process = match request.process_id {
+        Some(process_id) => processes.iter().find(|process| process.id == process_id),
+        None => processes.last(),
+    }
+    .ok_or(SessionCheckpointError::ProcessNotFound)?;
+    if process.status == ExecutionProcessStatus::Running {
+        return Err(SessionCheckpointError::ProcessNotFinished);
+    }
+
+    let repo_states = ExecutionProcessRepoState::find_by_execution_process_id(pool, process.id)
+        .await?
+        .into_iter()
+        .filter_map(|state| {
+            Some(CheckpointRepoState {
+                repo_id: state.repo_id,
+                head_commit: state.after_head_commit.or(state.before_head_commit)?,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(SessionCheckpoint::create(pool, session.id, process.id, label, &repo_states).await?)
+}
+
+pub async fn delete_checkpoint(
+    pool: &SqlitePool,
+    session_id: Uuid,
+    checkpoint_id: Uuid,
+) -> Result<(), SessionCheckpointError> {
+    if SessionCheckpoint::delete(pool, session_id, checkpoint_id).await? {
+        Ok(())
+    } else {
+        Err(SessionCheckpointError::NotFound)
+    }
+}
+
+/// Restore `session` to a checkpoint. Returns the restored checkpoint.
+pub async fn restore_checkpoint<C>(
+    container: &C,
+    session: &Session,
+    checkpoint_id: Uuid,
+    force_when_dirty: bool,
+) -> Result<SessionCheckpoint, SessionCheckpointError>
+where
+    C: ContainerService + Sync + ?Sized,
+{
+    let pool = &container.db().pool;
+    let checkpoint = SessionCheckpoint::find_by_id(pool, checkpoint_id)
+        .await?
+        .filter(|checkpoint| checkpoint.session_id == session.id)
+        .ok_or(SessionCheckpointError::NotFound)?;
+
+    let processes = ExecutionProcess::find_by_session_id(pool, session.id, false).await?;
+    let position = processes
+        .iter()
+        .position(|process| process.id == checkpoint.execution_process_id)
+        .ok_or(SessionCheckpointError::Discarded)?;
+
+    let workspace = Workspace::find_by_id(pool, session.workspace_id)
+        .await?
+        .ok_or_else(|| ContainerError::Other(anyhow!("Workspace not found")))?;
+    let workspace_dir = PathBuf::from(container.ensure_container_exists(&workspace).await?);
+    let workspace = Workspace::find_by_id(pool, workspace.id)
+        .await?
+        .ok_or_else(|| ContainerError::Other(anyhow!("Workspace not found")))?;
+    let is_dirty = !container.is_container_clean(&workspace).await?;
+    if is_dirty && !force_when_dirty {
+        return Err(SessionCheckpointError::UncommittedChanges);
+    }
+
+    // Drop everything that ran after the checkpoint. The worktrees are reset
+    // below, to the commits the checkpoint recorded.
+    if let Some(next) = processes.get(position + 1) {
+        container
+            .reset_session_to_process(session.id, next.id, false, force_when_dirty)
+            .await?;
+    }
+
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    let mut resets = Vec::new();
+    for state in checkpoint.repo_states.iter() {
+        let Some(repo) = repos.iter().find(|repo| repo.id == state.repo_id) else {
+            continue;
+        };
+        let outcome = container.git().reconcile_worktree_to_commit(
+            &workspace_dir.join(&repo.name),
+            &state.head_commit,
+            WorktreeResetOptions::new(true, force_when_dirty, is_dirty, true),
+        );
+        if outcome.needed && !outcome.applied {
+            tracing::warn!(
+                "Failed to restore {} to checkpoint {} of session {}",
+                repo.name,
+                checkpoint.id,
+                session.id
+            );
+        }
+        resets.extend(workspace_undo::applied_reset(repo, outcome));
+    }
+    if let Err(e) =
+        workspace_undo::record_git_reset(pool, container.git(), workspace.id, resets).await
+    {
+        tracing::warn!(
+            "Failed to record undo of checkpoint restore in workspace {}: {}",
+            workspace.id,
+            e
+        );
+    }
+
+    Ok(checkpoint)
+}
@@ -0,0 +1,170 @@
+//! Forking a session at one of its coding agent turns.
+//!
+//! A fork is a new workspace whose branch starts, in every repo, at the commit
+//! the repo was at right before the chosen turn ran. The conversation before
+//! that turn is carried into the fork's first prompt rather than resumed from
+//! the agent's own session, so the fork can run a different agent or model.
+
+use std::path::PathBuf;
+
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    execution_process_repo_state::ExecutionProcessRepoState,
+    repo_package::RepoPackage,
+    requests::WorkspaceRepoInput,
+    session::Session,
+    workspace_repo::WorkspaceRepo,
+};
+use git::{GitService, GitServiceError};
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::{container::ContainerService, session_transcript, startup_tasks::StartupTask};
+
+#[derive(Debug, Error)]
+pub enum SessionForkError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    GitService(#[from] GitServiceError),
+    #[error("Execution process not found in this session")]
+    ProcessNotFound,
+    #[error("Only coding agent turns can be forked")]
+    NotCodingAgent,
+    #[error("The state of {0} before this turn is unknown")]
+    MissingRepoState(String),
+}
+
+/// A repo of the forked workspace and the commit its branch starts at.
+#[derive(Debug, Clone)]
+pub struct ForkedRepo {
+    pub input: WorkspaceRepoInput,
+    pub repo_name: String,
+    pub repo_path: PathBuf,
+    pub start_commit: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ForkPoint {
+    pub process: ExecutionProcess,
+    pub repos: Vec<ForkedRepo>,
+    /// The conversation before the forked turn, as Markdown.
+    pub history: Option<String>,
+}
+
+/// Resolve the repo state and conversation `session` had right before
+/// `process_id` ran.
+pub async fn fork_point<C>(
+    container: &C,
+    session: &Session,
+    process_id: Uuid,
+) -> Result<ForkPoint, SessionForkError>
+where
+    C: ContainerService + Sync + ?Sized,
+{
+    let pool = &container.db().pool;
+    container
+        .startup_tasks()
+        .wait_for(StartupTask::BeforeHeadCommits)
+        .await;
+    let process = ExecutionProcess::find_by_id(pool, process_id)
+        .await?
+        .filter(|process| process.session_id == session.id && !process.dropped)
+        .ok_or(SessionForkError::ProcessNotFound)?;
+    if process.run_reason != ExecutionProcessRunReason::CodingAgent {
+        return Err(SessionForkError::NotCodingAgent);
+    }
+
+    let states = ExecutionProcessRepoState::find_by_execution_process_id(pool, process.id).await?;
+    let packages = RepoPackage::find_selected_for_workspace(pool, session.workspace_id).await?;
+    let mut repos = Vec::new();
+    for repo_with_branch in
+        WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, session.workspace_id)
+            .await?
+    {
+        let repo = repo_with_branch.repo;
+        let start_commit = states
+            .iter()
+            .find(|state| state.repo_id == repo.id)
+            .and_then(|state| state.before_head_commit.clone())
+            .ok_or_else(|| SessionForkError::MissingRepoState(repo.name.clone()))?;
+        repos.push(ForkedRepo {
+            input: WorkspaceRepoInput {
+                repo_id: repo.id,
+                target_branch: repo_with_branch.target_branch,
+                package_id: packages
+                    .iter()
+                    .find(|package| package.repo_id == repo.id)
+                    .map(|package| package.id),
+            },
+            repo_name: repo.name,
+            repo_path: repo.path,
+            start_commit,
+        });
+    }
+
+    let history = session_transcript::conversation_before(container, &process).await?;
+    Ok(ForkPoint {
+        process,
+        repos,
+        history,
+    })
+}
+
+/// Create `branch` at the start commit of every repo. Branches created before
+/// a failure are deleted again.
+pub fn create_fork_branches(
+    git: &GitService,
+    repos: &[ForkedRepo],
+    branch: &str,
+) -> Result<(), SessionForkError> {
+    for (index, repo) in repos.iter().enumerate() {
+        if let Err(e) = git.create_branch_at_commit(&repo.repo_path, branch, &repo.start_commit) {
+            for created in &repos[..index] {
+                if let Err(cleanup) = git.delete_branch(&created.repo_path, branch) {
+                    tracing::warn!(
+                        "Failed to delete fork branch {} in {}: {}",
+                        branch,
+                        created.repo_name,
+                        cleanup
+                    );
+                }
+            }
+            return Err(e.into());
+        }
+    }
+    Ok(())
+}
+
+/// The first prompt of a fork: the earlier conversation, then the new prompt.
+pub fn fork_prompt(history: Option<&str>, prompt: &str) -> String {
+    match history {
+        Some(history) => format!(
+            "This task continues an earlier conversation, reproduced below. The repository \
+             has been restored to the state it was in at the end of that conversation.\n\n\
+             <earlier_conversation>\n{}\n</earlier_conversation>\n\n{}",
+            history.trim(),
+            prompt.trim()
+        ),
+        None => prompt.trim().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fork_prompt_carries_history_before_the_new_prompt() {
+        assert_eq!(fork_prompt(None, "  Try again  "), "Try again");
+
+        let prompt = fork_prompt(
+            Some("# Earlier conversation\n\n## User\n\nHi\n"),
+            "Try again",
+        );
+        assert!(prompt.contains(
+            "<earlier_conversation>\n# Earlier conversation\n\n## User\n\nHi\n</earlier_conversation>"
+        ));
+        assert!(prompt.ends_with("</earlier_conversation>\n\nTry again"));
+    }
+}
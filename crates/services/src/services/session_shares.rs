@@ -0,0 +1,317 @@
+//! Read-only public links to a session's transcript and diff.
+//!
+//! Sharing is opt-in (`session_sharing` in the config) and links point at the
+//! configured public URL of this server. A token carries the share id and its
+//! expiry, signed with a key kept in the asset directory so links survive
+//! restarts. The share row is checked on every view as well, so revoking a
+//! share kills its link before it expires.
+
+use std::{fs, io, path::Path};
+
+use chrono::{DateTime, Duration, Utc};
+use db::models::{session::Session, session_share::SessionShare, workspace::Workspace};
+use git::GitService;
+use once_cell::sync::OnceCell;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use utils::{
+    assets::share_links_key_path,
+    tokens::{SignedTokenError, sign_expiring, verify_expiring},
+};
+use uuid::Uuid;
+
+use super::{
+    config::SessionSharingConfig,
+    container::ContainerService,
+    session_transcript::{self, TranscriptDiff, TranscriptFormat},
+    workspace_export,
+};
+
+pub const DEFAULT_SHARE_HOURS: u32 = 72;
+pub const MAX_SHARE_HOURS: u32 = 30 * 24;
+
+static SHARE_KEY: OnceCell<[u8; 32]> = OnceCell::new();
+
+#[derive(Debug, Error)]
+pub enum SessionShareError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("Session sharing is disabled. Enable it and set a public URL in settings.")]
+    Disabled,
+    #[error("Shares can last between 1 hour and 30 days")]
+    InvalidExpiry,
+    #[error("Share not found")]
+    NotFound,
+}
+
+/// Why a share link can't be shown. The messages are shown to whoever opened
+/// the link.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ShareLinkError {
+    #[error("This link is invalid.")]
+    Invalid,
+    #[error("This link has expired.")]
+    Expired,
+    #[error("This link has been revoked.")]
+    Revoked,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+pub struct CreateSessionShareRequest {
+    /// Defaults to 72 hours.
+    #[ts(optional)]
+    pub expires_in_hours: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SessionShareLink {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub share: SessionShare,
+    pub url: String,
+}
+
+fn load_or_create_key(path: &Path) -> io::Result<[u8; 32]> {
+    if let Ok(bytes) = fs::read(path) {
+        return bytes.try_into().map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "share links key file has invalid length (expected 32 bytes)",
+            )
+        });
+    }
+
+    let mut key = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut key);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, key)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&tmp, fs::Permissions::from_mode(0o600))?;
+    }
+
+    fs::rename(&tmp, path)?;
+    Ok(key)
+}
+
+fn share_key() -> io::Result<&'static [u8; 32]> {
+    SHARE_KEY.get_or_try_init(|| load_or_create_key(&share_links_key_path()))
+}
+
+fn issue_token(key: &[u8; 32], share_id: Uuid, expires_at: DateTime<Utc>) -> String {
+    sign_expiring(key, &share_id.to_string(), expires_at)
+}
+
+fn verify_token(key: &[u8; 32], token: &str) -> Result<Uuid, ShareLinkError> {
+    let (share_id, _) = verify_expiring(key, token).map_err(|error| match error {
+        SignedTokenError::Invalid => ShareLinkError::Invalid,
+        SignedTokenError::Expired => ShareLinkError::Expired,
+    })?;
+    share_id.parse().map_err(|_| ShareLinkError::Invalid)
+}
+
+/// Base URL links are built on, if sharing is enabled.
+fn public_url(config: &SessionSharingConfig) -> Result<String, SessionShareError> {
+    config
+        .enabled
+        .then(|| config.public_url.clone())
+        .flatten()
+        .map(|url| url.trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty())
+        .ok_or(SessionShareError::Disabled)
+}
+
+fn share_link(share: SessionShare, base_url: &str) -> Result<SessionShareLink, SessionShareError> {
+    let token = issue_token(share_key()?, share.id, share.expires_at);
+    Ok(SessionShareLink {
+        url: format!("{base_url}/api/shared-sessions/{token}"),
+        share,
+    })
+}
+
+pub async fn create_share(
+    pool: &SqlitePool,
+    config: &SessionSharingConfig,
+    session_id: Uuid,
+    request: &CreateSessionShareRequest,
+) -> Result<SessionShareLink, SessionShareError> {
+    let base_url = public_url(config)?;
+    let hours = request.expires_in_hours.unwrap_or(DEFAULT_SHARE_HOURS);
+    if !(1..=MAX_SHARE_HOURS).contains(&hours) {
+        return Err(SessionShareError::InvalidExpiry);
+    }
+    // Tokens carry whole seconds.
+    let expires_at =
+        DateTime::from_timestamp((Utc::now() + Duration::hours(hours.into())).timestamp(), 0)
+            .ok_or(SessionShareError::InvalidExpiry)?;
+
+    let share = SessionShare::create(pool, session_id, expires_at).await?;
+    share_link(share, &base_url)
+}
+
+pub async fn list_shares(
+    pool: &SqlitePool,
+    config: &SessionSharingConfig,
+    session_id: Uuid,
+) -> Result<Vec<SessionShareLink>, SessionShareError> {
+    let base_url = public_url(config)?;
+    SessionShare::find_active_by_session_id(pool, session_id)
+        .await?
+        .into_iter()
+        .map(|share| share_link(share, &base_url))
+        .collect()
+}
+
+pub async fn revoke_share(
+    pool: &SqlitePool,
+    session_id: Uuid,
+    share_id: Uuid,
+) -> Result<(), SessionShareError> {
+    if SessionShare::revoke(pool, session_id, share_id).await? {
+        Ok(())
+    } else {
+        Err(SessionShareError::NotFound)
+    }
+}
+
+/// The active share a link points at.
+pub async fn resolve_link(
+    pool: &SqlitePool,
+    config: &SessionSharingConfig,
+    token: &str,
+) -> Result<SessionShare, ShareLinkError> {
+    if !config.enabled {
+        return Err(ShareLinkError::Invalid);
+    }
+    let key = share_key().map_err(|e| {
+        tracing::error!("Failed to load share links key: {}", e);
+        ShareLinkError::Invalid
+    })?;
+    let share_id = verify_token(key, token)?;
+    let share = SessionShare::find_by_id(pool, share_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to load session share {}: {}", share_id, e);
+            ShareLinkError::Invalid
+        })?
+        .ok_or(ShareLinkError::Invalid)?;
+
+    if share.revoked_at.is_some() {
+        return Err(ShareLinkError::Revoked);
+    }
+    if !share.is_active(Utc::now()) {
+        return Err(ShareLinkError::Expired);
+    }
+    Ok(share)
+}
+
+/// The HTML page behind a share link: the session's transcript followed by
+/// the workspace's changes.
+pub async fn render_shared_session<C>(
+    container: &C,
+    git: &GitService,
+    share: &SessionShare,
+) -> Result<String, SessionShareError>
+where
+    C: ContainerService + Sync + ?Sized,
+{
+    let pool = &container.db().pool;
+    let session = Session::find_by_id(pool, share.session_id)
+        .await?
+        .ok_or(SessionShareError::NotFound)?;
+
+    let mut diffs = Vec::new();
+    if let Some(workspace) = Workspace::find_by_id(pool, session.workspace_id).await? {
+        match workspace_export::workspace_changes(pool, git, &workspace).await {
+            Ok(changes) => diffs.extend(changes.into_iter().filter_map(|changes| {
+                Some(TranscriptDiff {
+                    repo_name: changes.repo_name,
+                    diff: changes.diff?,
+                })
+            })),
+            Err(e) => tracing::warn!(
+                "Failed to diff workspace {} for shared session: {}",
+                workspace.id,
+                e
+            ),
+        }
+    }
+
+    Ok(session_transcript::render_session_transcript(
+        container,
+        &session,
+        TranscriptFormat::Html,
+        &diffs,
+    )
+    .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+
+    use super::*;
+
+    #[test]
+    fn tokens_round_trip_and_reject_tampering() {
+        let key = [7u8; 32];
+        let share_id = Uuid::new_v4();
+        let token = issue_token(&key, share_id, Utc::now() + Duration::hours(1));
+
+        assert_eq!(verify_token(&key, &token), Ok(share_id));
+        assert_eq!(
+            verify_token(&[8u8; 32], &token),
+            Err(ShareLinkError::Invalid)
+        );
+        assert_eq!(verify_token(&key, "garbage"), Err(ShareLinkError::Invalid));
+
+        let (_, signature) = token.split_once('.').unwrap();
+        let forged = format!(
+            "{}.{signature}",
+            URL_SAFE_NO_PAD.encode(format!(
+                "{}.{}",
+                Uuid::new_v4(),
+                Utc::now().timestamp() + 3600
+            ))
+        );
+        assert_eq!(verify_token(&key, &forged), Err(ShareLinkError::Invalid));
+    }
+
+    #[test]
+    fn rejects_expired_tokens() {
+        let key = [7u8; 32];
+        let token = issue_token(&key, Uuid::new_v4(), Utc::now() - Duration::seconds(1));
+        assert_eq!(verify_token(&key, &token), Err(ShareLinkError::Expired));
+    }
+
+    #[test]
+    fn links_need_sharing_enabled_with_a_public_url() {
+        let mut config = SessionSharingConfig {
+            enabled: false,
+            public_url: Some("https://example.com/".to_string()),
+        };
+        assert!(matches!(
+            public_url(&config),
+            Err(SessionShareError::Disabled)
+        ));
+
+        config.enabled = true;
+        assert_eq!(public_url(&config).unwrap(), "https://example.com");
+
+        config.public_url = None;
+        assert!(matches!(
+            public_url(&config),
+            Err(SessionShareError::Disabled)
+        ));
+    }
+}
@@ -0,0 +1,520 @@
+//! Rendering of a session's normalized conversation as a standalone Markdown
+//! or HTML document.
+//!
+//! Entries are first turned into format-agnostic [`TranscriptBlock`]s, so both
+//! formats show the same content: prompts, assistant messages, tool calls with
+//! their commands and diffs, and errors. Thinking, token usage and other UI
+//! state entries are left out.
+
+use std::collections::BTreeMap;
+
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    session::Session,
+};
+use executors::logs::{
+    ActionType, CommandExitStatus, FileChange, NormalizedEntry, NormalizedEntryType, ToolStatus,
+    utils::patch::extract_normalized_entry_from_patch,
+};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+use super::container::ContainerService;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+impl TranscriptFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Markdown => "text/markdown; charset=utf-8",
+            Self::Html => "text/html; charset=utf-8",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+        }
+    }
+}
+
+/// A repo's changes, appended after the conversation.
+#[derive(Debug, Clone)]
+pub struct TranscriptDiff {
+    pub repo_name: String,
+    pub diff: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TranscriptBlock {
+    Heading(String),
+    Text(String),
+    Code {
+        language: &'static str,
+        code: String,
+    },
+    Note(String),
+}
+
+/// The normalized entries of a finished execution process, in order. The
+/// normalized stream of a running process only ends when the process does,
+/// so running processes yield nothing.
+pub async fn normalized_entries<C>(
+    container: &C,
+    process: &ExecutionProcess,
+) -> Vec<NormalizedEntry>
+where
+    C: ContainerService + Sync + ?Sized,
+{
+    if process.status == ExecutionProcessStatus::Running {
+        return Vec::new();
+    }
+    let Some(mut stream) = container.stream_normalized_logs(&process.id).await else {
+        return Vec::new();
+    };
+
+    let mut entries = BTreeMap::new();
+    while let Some(msg) = stream.next().await {
+        if let Ok(LogMsg::JsonPatch(patch)) = msg
+            && let Some((index, entry)) = extract_normalized_entry_from_patch(&patch)
+        {
+            entries.insert(index, entry);
+        }
+    }
+    entries.into_values().collect()
+}
+
+/// Render the conversation of every finished coding agent turn of `session`,
+/// followed by `diffs`.
+pub async fn render_session_transcript<C>(
+    container: &C,
+    session: &Session,
+    format: TranscriptFormat,
+    diffs: &[TranscriptDiff],
+) -> Result<String, sqlx::Error>
+where
+    C: ContainerService + Sync + ?Sized,
+{
+    let processes =
+        ExecutionProcess::find_by_session_id(&container.db().pool, session.id, false).await?;
+    let mut entries = Vec::new();
+    for process in processes
+        .iter()
+        .filter(|process| process.run_reason == ExecutionProcessRunReason::CodingAgent)
+    {
+        entries.extend(normalized_entries(container, process).await);
+    }
+
+    let title = transcript_title(session);
+    let mut blocks = transcript_blocks(&entries);
+    blocks.extend(diff_blocks(diffs));
+    Ok(match format {
+        TranscriptFormat::Markdown => render_markdown(&title, &blocks),
+        TranscriptFormat::Html => render_html(&title, &blocks),
+    })
+}
+
+/// The conversation of the coding agent turns of a session that ran before
+/// `process`, as Markdown. None when `process` is the first turn.
+pub async fn conversation_before<C>(
+    container: &C,
+    process: &ExecutionProcess,
+) -> Result<Option<String>, sqlx::Error>
+where
+    C: ContainerService + Sync + ?Sized,
+{
+    let processes =
+        ExecutionProcess::find_by_session_id(&container.db().pool, process.session_id, false)
+            .await?;
+    let mut entries = Vec::new();
+    for earlier in processes.iter().filter(|earlier| {
+        earlier.run_reason == ExecutionProcessRunReason::CodingAgent
+            && earlier.created_at < process.created_at
+    }) {
+        entries.extend(normalized_entries(container, earlier).await);
+    }
+
+    let blocks = transcript_blocks(&entries);
+    Ok((!blocks.is_empty()).then(|| render_markdown("Earlier conversation", &blocks)))
+}
+
+pub fn transcript_file_name(session_id: Uuid, format: TranscriptFormat) -> String {
+    format!("session-{session_id}.{}", format.extension())
+}
+
+fn transcript_title(session: &Session) -> String {
+    match (&session.name, &session.executor) {
+        (Some(name), _) if !name.trim().is_empty() => name.trim().to_string(),
+        (_, Some(executor)) => format!("{executor} session"),
+        _ => format!("Session {}", session.id),
+    }
+}
+
+fn transcript_blocks(entries: &[NormalizedEntry]) -> Vec<TranscriptBlock> {
+    let mut blocks = Vec::new();
+    // Consecutive entries of the same speaker share one heading.
+    let mut speaker = None;
+    let mut heading = |blocks: &mut Vec<TranscriptBlock>, name: &'static str| {
+        if speaker != Some(name) {
+            blocks.push(TranscriptBlock::Heading(name.to_string()));
+            speaker = Some(name);
+        }
+    };
+
+    for entry in entries {
+        match &entry.entry_type {
+            NormalizedEntryType::UserMessage => {
+                heading(&mut blocks, "User");
+                blocks.push(TranscriptBlock::Text(entry.content.trim().to_string()));
+            }
+            NormalizedEntryType::UserFeedback { denied_tool } => {
+                heading(&mut blocks, "User");
+                blocks.push(TranscriptBlock::Note(format!("Denied {denied_tool}")));
+                if !entry.content.trim().is_empty() {
+                    blocks.push(TranscriptBlock::Text(entry.content.trim().to_string()));
+                }
+            }
+            NormalizedEntryType::UserAnsweredQuestions { answers } => {
+                heading(&mut blocks, "User");
+                for answer in answers {
+                    blocks.push(TranscriptBlock::Text(format!(
+                        "{}\n{}",
+                        answer.question,
+                        answer.answer.join(", ")
+                    )));
+                }
+            }
+            NormalizedEntryType::AssistantMessage => {
+                heading(&mut blocks, "Assistant");
+                blocks.push(TranscriptBlock::Text(entry.content.trim().to_string()));
+            }
+            NormalizedEntryType::ToolUse {
+                tool_name,
+                action_type,
+                status,
+            } => {
+                heading(&mut blocks, "Assistant");
+                tool_blocks(&mut blocks, tool_name, action_type, status, &entry.content);
+            }
+            NormalizedEntryType::ErrorMessage { .. } => {
+                blocks.push(TranscriptBlock::Note(format!(
+                    "Error: {}",
+                    entry.content.trim()
+                )));
+            }
+            NormalizedEntryType::SystemMessage
+            | NormalizedEntryType::Thinking
+            | NormalizedEntryType::Loading
+            | NormalizedEntryType::NextAction { .. }
+            | NormalizedEntryType::TokenUsageInfo(_) => {}
+        }
+    }
+    blocks
+}
+
+fn diff_blocks(diffs: &[TranscriptDiff]) -> Vec<TranscriptBlock> {
+    let diffs: Vec<_> = diffs
+        .iter()
+        .filter(|diff| !diff.diff.trim().is_empty())
+        .collect();
+    if diffs.is_empty() {
+        return Vec::new();
+    }
+
+    let mut blocks = vec![TranscriptBlock::Heading("Changes".to_string())];
+    for diff in &diffs {
+        if diffs.len() > 1 {
+            blocks.push(TranscriptBlock::Note(diff.repo_name.clone()));
+        }
+        blocks.push(TranscriptBlock::Code {
+            language: "diff",
+            code: diff.diff.trim_end().to_string(),
+        });
+    }
+    blocks
+}
+
+fn tool_blocks(
+    blocks: &mut Vec<TranscriptBlock>,
+    tool_name: &str,
+    action_type: &ActionType,
+    status: &ToolStatus,
+    content: &str,
+) {
+    let status = match status {
+        ToolStatus::Failed => " (failed)",
+        ToolStatus::Denied { .. } => " (denied)",
+        ToolStatus::TimedOut => " (timed out)",
+        _ => "",
+    };
+
+    match action_type {
+        ActionType::CommandRun {
+            command, result, ..
+        } => {
+            let exit = result
+                .as_ref()
+                .and_then(|result| result.exit_status.as_ref())
+                .and_then(|exit| match exit {
+                    CommandExitStatus::ExitCode { code } if *code != 0 => {
+                        Some(format!(", exit code {code}"))
+                    }
+                    CommandExitStatus::Success { success: false } => Some(", failed".to_string()),
+                    _ => None,
+                })
+                .unwrap_or_default();
+            blocks.push(TranscriptBlock::Note(format!("Ran command{status}{exit}")));
+            let mut code = format!("$ {command}");
+            if let Some(output) = result
+                .as_ref()
+                .and_then(|result| result.output.as_deref())
+                .filter(|output| !output.trim().is_empty())
+            {
+                code.push('\n');
+                code.push_str(output.trim_end());
+            }
+            blocks.push(TranscriptBlock::Code {
+                language: "sh",
+                code,
+            });
+        }
+        ActionType::FileEdit { path, changes } => {
+            for change in changes {
+                match change {
+                    FileChange::Edit { unified_diff, .. } => {
+                        blocks.push(TranscriptBlock::Note(format!("Edited {path}{status}")));
+                        blocks.push(TranscriptBlock::Code {
+                            language: "diff",
+                            code: unified_diff.trim_end().to_string(),
+                        });
+                    }
+                    FileChange::Write { .. } => {
+                        blocks.push(TranscriptBlock::Note(format!("Wrote {path}{status}")));
+                    }
+                    FileChange::Delete => {
+                        blocks.push(TranscriptBlock::Note(format!("Deleted {path}{status}")));
+                    }
+                    FileChange::Rename { new_path } => blocks.push(TranscriptBlock::Note(format!(
+                        "Renamed {path} to {new_path}{status}"
+                    ))),
+                }
+            }
+            if changes.is_empty() {
+                blocks.push(TranscriptBlock::Note(format!("Edited {path}{status}")));
+            }
+        }
+        ActionType::FileRead { path } => {
+            blocks.push(TranscriptBlock::Note(format!("Read {path}{status}")));
+        }
+        ActionType::Search { query } => {
+            blocks.push(TranscriptBlock::Note(format!(
+                "Searched for {query}{status}"
+            )));
+        }
+        ActionType::WebFetch { url } => {
+            blocks.push(TranscriptBlock::Note(format!("Fetched {url}{status}")));
+        }
+        ActionType::PlanPresentation { plan } => {
+            blocks.push(TranscriptBlock::Note(format!("Plan{status}")));
+            blocks.push(TranscriptBlock::Text(plan.trim().to_string()));
+        }
+        ActionType::TodoManagement { todos, .. } => {
+            blocks.push(TranscriptBlock::Note(format!("Updated todos{status}")));
+            let list = todos
+                .iter()
+                .map(|todo| format!("- [{}] {}", todo.status, todo.content))
+                .collect::<Vec<_>>()
+                .join("\n");
+            if !list.is_empty() {
+                blocks.push(TranscriptBlock::Text(list));
+            }
+        }
+        ActionType::Tool { .. }
+        | ActionType::TaskCreate { .. }
+        | ActionType::AskUserQuestion { .. }
+        | ActionType::Other { .. } => {
+            let summary = content.trim();
+            if summary.is_empty() {
+                blocks.push(TranscriptBlock::Note(format!("{tool_name}{status}")));
+            } else {
+                blocks.push(TranscriptBlock::Note(format!(
+                    "{tool_name}{status}: {summary}"
+                )));
+            }
+        }
+    }
+}
+
+/// A backtick fence longer than any run of backticks in `code`.
+fn code_fence(code: &str) -> String {
+    let longest = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn render_markdown(title: &str, blocks: &[TranscriptBlock]) -> String {
+    let mut out = format!("# {title}\n");
+    for block in blocks {
+        out.push('\n');
+        match block {
+            TranscriptBlock::Heading(heading) => out.push_str(&format!("## {heading}\n")),
+            TranscriptBlock::Text(text) => out.push_str(&format!("{text}\n")),
+            TranscriptBlock::Code { language, code } => {
+                let fence = code_fence(code);
+                out.push_str(&format!("{fence}{language}\n{code}\n{fence}\n"));
+            }
+            TranscriptBlock::Note(note) => out.push_str(&format!("_{}_\n", note.trim())),
+        }
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+const TRANSCRIPT_STYLES: &str = r#"<style>
+      body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; max-width: 860px; margin: 2rem auto; padding: 0 1rem; line-height: 1.5; color: #1f2328; }
+      h2 { border-bottom: 1px solid #d0d7de; padding-bottom: .25rem; margin-top: 2rem; }
+      .text { white-space: pre-wrap; }
+      .note { color: #59636e; font-style: italic; }
+      pre { background: #f6f8fa; padding: .75rem; border-radius: 6px; overflow-x: auto; }
+    </style>"#;
+
+fn render_html(title: &str, blocks: &[TranscriptBlock]) -> String {
+    let title = escape_html(title);
+    let mut body = String::new();
+    for block in blocks {
+        match block {
+            TranscriptBlock::Heading(heading) => {
+                body.push_str(&format!("    <h2>{}</h2>\n", escape_html(heading)));
+            }
+            TranscriptBlock::Text(text) => {
+                body.push_str(&format!(
+                    "    <div class=\"text\">{}</div>\n",
+                    escape_html(text)
+                ));
+            }
+            TranscriptBlock::Code { language, code } => body.push_str(&format!(
+                "    <pre><code class=\"language-{language}\">{}</code></pre>\n",
+                escape_html(code)
+            )),
+            TranscriptBlock::Note(note) => {
+                body.push_str(&format!(
+                    "    <p class=\"note\">{}</p>\n",
+                    escape_html(note)
+                ));
+            }
+        }
+    }
+
+    format!(
+        r#"<!doctype html>
+<html>
+  <head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <title>{title}</title>
+    {TRANSCRIPT_STYLES}
+  </head>
+  <body>
+    <h1>{title}</h1>
+{body}  </body>
+</html>
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::CommandRunResult;
+
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    fn sample_entries() -> Vec<NormalizedEntry> {
+        vec![
+            entry(NormalizedEntryType::UserMessage, "Fix the <b> tag"),
+            entry(NormalizedEntryType::Thinking, "hmm"),
+            entry(
+                NormalizedEntryType::ToolUse {
+                    tool_name: "Bash".to_string(),
+                    action_type: ActionType::CommandRun {
+                        command: "cargo test".to_string(),
+                        result: Some(CommandRunResult {
+                            exit_status: Some(CommandExitStatus::ExitCode { code: 1 }),
+                            output: Some("1 failed\n".to_string()),
+                        }),
+                        category: Default::default(),
+                    },
+                    status: ToolStatus::Failed,
+                },
+                "cargo test",
+            ),
+            entry(
+                NormalizedEntryType::ToolUse {
+                    tool_name: "Edit".to_string(),
+                    action_type: ActionType::FileEdit {
+                        path: "src/lib.rs".to_string(),
+                        changes: vec![FileChange::Edit {
+                            unified_diff: "-a\n+b\n".to_string(),
+                            has_line_numbers: true,
+                        }],
+                    },
+                    status: ToolStatus::Success,
+                },
+                "src/lib.rs",
+            ),
+            entry(NormalizedEntryType::AssistantMessage, "Done."),
+        ]
+    }
+
+    #[test]
+    fn renders_markdown_transcript() {
+        let markdown = render_markdown("Fix tags", &transcript_blocks(&sample_entries()));
+
+        assert_eq!(
+            markdown,
+            "# Fix tags\n\n## User\n\nFix the <b> tag\n\n## Assistant\n\n\
+             _Ran command (failed), exit code 1_\n\n```sh\n$ cargo test\n1 failed\n```\n\n\
+             _Edited src/lib.rs_\n\n```diff\n-a\n+b\n```\n\nDone.\n"
+        );
+    }
+
+    #[test]
+    fn escapes_html_transcript() {
+        let html = render_html("<Fix>", &transcript_blocks(&sample_entries()));
+
+        assert!(html.contains("<title>&lt;Fix&gt;</title>"));
+        assert!(html.contains("<div class=\"text\">Fix the &lt;b&gt; tag</div>"));
+        assert!(html.contains("<pre><code class=\"language-diff\">-a\n+b</code></pre>"));
+        assert!(!html.contains("hmm"));
+    }
+
+    #[test]
+    fn code_fence_outgrows_backticks_in_code() {
+        assert_eq!(code_fence("plain"), "```");
+        assert_eq!(code_fence("has ```` inside"), "`````");
+    }
+}
@@ -0,0 +1,358 @@
+//! Dependency caches shared by all worktrees of a repo.
+//!
+//! Every workspace gets fresh worktrees, so setup scripts would otherwise
+//! download and build dependencies from scratch for each attempt. A repo can
+//! opt into shared caches, kept under the app data dir per repo and cache:
+//! in-tree caches (cargo's `target`, custom paths) are symlinked into each
+//! worktree, and tool caches (cargo registry, pnpm store, pip cache) are
+//! pointed at through environment variables of every process the workspace
+//! runs. Removing a cache configuration deletes its directory.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use db::models::{
+    repo_shared_cache::{RepoSharedCache, SharedCacheKind},
+    workspace_repo::WorkspaceRepo,
+};
+use git::GitService;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Cargo files worth sharing with a cache that replaces `CARGO_HOME`.
+const CARGO_HOME_FILES: [&str; 4] = ["config.toml", "config", "credentials.toml", "credentials"];
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SharedCacheUsage {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub cache: RepoSharedCache,
+    #[ts(type = "number")]
+    pub size_bytes: u64,
+}
+
+fn cache_root() -> PathBuf {
+    utils::assets::asset_dir().join("dependency-caches")
+}
+
+/// Where the contents of `cache` are kept.
+pub fn cache_dir(cache: &RepoSharedCache) -> PathBuf {
+    cache_root()
+        .join(cache.repo_id.to_string())
+        .join(cache.id.to_string())
+}
+
+/// Symlink the in-tree caches of every repo of a workspace into its
+/// worktrees. Failures are logged: a missing link only costs a slower setup.
+pub async fn link_workspace_caches(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+    workspace_dir: &Path,
+) -> Result<(), sqlx::Error> {
+    let caches = RepoSharedCache::find_for_workspace(pool, workspace_id).await?;
+    if caches.iter().all(|cache| cache.link_path().is_none()) {
+        return Ok(());
+    }
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace_id).await?;
+    for cache in &caches {
+        let (Some(link_path), Some(repo)) = (
+            cache.link_path(),
+            repos.iter().find(|repo| repo.id == cache.repo_id),
+        ) else {
+            continue;
+        };
+        let worktree_path = workspace_dir.join(&repo.name);
+        if let Err(e) = link_into_worktree(&cache_dir(cache), &worktree_path, link_path) {
+            tracing::warn!(
+                "Failed to link shared cache {} into {}: {}",
+                link_path,
+                worktree_path.display(),
+                e
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Symlink the in-tree caches of a repo into one of its worktrees.
+pub async fn link_repo_caches(
+    pool: &SqlitePool,
+    repo_id: Uuid,
+    worktree_path: &Path,
+) -> Result<(), sqlx::Error> {
+    for cache in RepoSharedCache::find_by_repo_id(pool, repo_id).await? {
+        let Some(link_path) = cache.link_path() else {
+            continue;
+        };
+        if let Err(e) = link_into_worktree(&cache_dir(&cache), worktree_path, link_path) {
+            tracing::warn!(
+                "Failed to link shared cache {} into {}: {}",
+                link_path,
+                worktree_path.display(),
+                e
+            );
+        }
+    }
+    Ok(())
+}
+
+fn link_into_worktree(cache_dir: &Path, worktree_path: &Path, link_path: &str) -> io::Result<()> {
+    if !worktree_path.is_dir() {
+        return Ok(());
+    }
+    fs::create_dir_all(cache_dir)?;
+    let link = worktree_path.join(link_path);
+    match fs::symlink_metadata(&link) {
+        Ok(metadata) if metadata.file_type().is_symlink() => {
+            if fs::read_link(&link)? == cache_dir {
+                return Ok(());
+            }
+            remove_link(&link)?;
+        }
+        // Left behind by a checkout or a warm base clone
+        Ok(metadata) if metadata.is_dir() && fs::read_dir(&link)?.next().is_none() => {
+            fs::remove_dir(&link)?;
+        }
+        Ok(_) => {
+            tracing::warn!(
+                "Not linking shared cache at {}: the path already has contents",
+                link.display()
+            );
+            return Ok(());
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+    if let Some(parent) = link.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    symlink_dir(cache_dir, &link)?;
+    exclude_from_git(worktree_path, link_path)
+}
+
+#[cfg(unix)]
+fn symlink_dir(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink_dir(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_dir(target, link)
+}
+
+#[cfg(unix)]
+fn remove_link(link: &Path) -> io::Result<()> {
+    fs::remove_file(link)
+}
+
+#[cfg(windows)]
+fn remove_link(link: &Path) -> io::Result<()> {
+    fs::remove_dir(link)
+}
+
+/// Keep the link out of commits: `.gitignore` rules like `target/` don't match
+/// a symlink.
+fn exclude_from_git(worktree_path: &Path, link_path: &str) -> io::Result<()> {
+    let common_dir = GitService::new()
+        .get_common_dir(worktree_path)
+        .map_err(io::Error::other)?;
+    let exclude_path = common_dir.join("info").join("exclude");
+    let pattern = format!("/{link_path}");
+    let existing = fs::read_to_string(&exclude_path).unwrap_or_default();
+    if existing.lines().any(|line| line.trim() == pattern) {
+        return Ok(());
+    }
+    fs::create_dir_all(common_dir.join("info"))?;
+    let separator = if existing.is_empty() || existing.ends_with('\n') {
+        ""
+    } else {
+        "\n"
+    };
+    fs::write(&exclude_path, format!("{existing}{separator}{pattern}\n"))
+}
+
+/// Environment variables pointing the tools of a workspace's repos at their
+/// shared caches. With several repos sharing the same kind of cache, the
+/// first repo's wins.
+pub async fn workspace_env(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+) -> Result<HashMap<String, String>, sqlx::Error> {
+    let caches = RepoSharedCache::find_for_workspace(pool, workspace_id).await?;
+    Ok(cache_env(caches))
+}
+
+/// Environment variables pointing tools at the shared caches of one repo, for
+/// processes that run outside a workspace.
+pub async fn repo_env(
+    pool: &SqlitePool,
+    repo_id: Uuid,
+) -> Result<HashMap<String, String>, sqlx::Error> {
+    let caches = RepoSharedCache::find_by_repo_id(pool, repo_id).await?;
+    Ok(cache_env(caches))
+}
+
+fn cache_env(caches: Vec<RepoSharedCache>) -> HashMap<String, String> {
+    let mut env = HashMap::new();
+    for cache in caches {
+        let Some(var) = cache.kind.env_var() else {
+            continue;
+        };
+        if env.contains_key(var) {
+            continue;
+        }
+        let dir = cache_dir(&cache);
+        if let Err(e) = prepare_tool_cache(cache.kind, &dir) {
+            tracing::warn!("Failed to prepare shared cache {}: {}", dir.display(), e);
+            continue;
+        }
+        env.insert(var.to_string(), dir.to_string_lossy().to_string());
+    }
+    env
+}
+
+fn prepare_tool_cache(kind: SharedCacheKind, dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    if kind != SharedCacheKind::CargoRegistry {
+        return Ok(());
+    }
+    // A new CARGO_HOME would drop the user's registries and credentials
+    let Some(user_cargo_home) = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".cargo")))
+    else {
+        return Ok(());
+    };
+    for file in CARGO_HOME_FILES {
+        let source = user_cargo_home.join(file);
+        let link = dir.join(file);
+        if source.is_file() && fs::symlink_metadata(&link).is_err() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(&source, &link)?;
+            #[cfg(windows)]
+            fs::copy(&source, &link).map(|_| ())?;
+        }
+    }
+    Ok(())
+}
+
+/// Size of each cache on disk. Walks the caches, so run it off the async
+/// runtime.
+pub fn usage(caches: Vec<RepoSharedCache>) -> Vec<SharedCacheUsage> {
+    caches
+        .into_iter()
+        .map(|cache| SharedCacheUsage {
+            size_bytes: dir_size(&cache_dir(&cache)),
+            cache,
+        })
+        .collect()
+}
+
+/// Size of the files under `path`, without following symlinks.
+pub(crate) fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| match entry.metadata() {
+            // Doesn't follow symlinks, so linked cargo config isn't counted
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) if metadata.is_file() => metadata.len(),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Empty a cache. Worktrees keep their links; the next setup fills it again.
+pub fn clear(cache: &RepoSharedCache) -> io::Result<()> {
+    let dir = cache_dir(cache);
+    remove_dir(&dir)?;
+    fs::create_dir_all(&dir)
+}
+
+/// Delete the directory of a cache whose configuration was removed.
+pub fn remove(cache: &RepoSharedCache) -> io::Result<()> {
+    remove_dir(&cache_dir(cache))
+}
+
+fn remove_dir(dir: &Path) -> io::Result<()> {
+    match fs::remove_dir_all(dir) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Delete cache directories left behind by removed repos.
+pub async fn prune_orphaned(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let repo_ids: HashSet<String> = RepoSharedCache::find_repo_ids(pool)
+        .await?
+        .into_iter()
+        .map(|id| id.to_string())
+        .collect();
+    let Ok(entries) = fs::read_dir(cache_root()) else {
+        return Ok(());
+    };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if repo_ids.contains(&name) {
+            continue;
+        }
+        tracing::info!("Removing shared caches of removed repo {}", name);
+        if let Err(e) = remove_dir(&entry.path()) {
+            tracing::warn!("Failed to remove {}: {}", entry.path().display(), e);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn links_caches_and_keeps_them_out_of_git() {
+        let td = tempfile::TempDir::new().unwrap();
+        let repo_path = td.path().join("repo");
+        GitService::new()
+            .initialize_repo_with_main_branch(&repo_path)
+            .unwrap();
+        let cache = td.path().join("cache");
+        fs::create_dir_all(repo_path.join("target")).unwrap();
+
+        link_into_worktree(&cache, &repo_path, "target").unwrap();
+        // Linking again is a no-op
+        link_into_worktree(&cache, &repo_path, "target").unwrap();
+
+        assert_eq!(fs::read_link(repo_path.join("target")).unwrap(), cache);
+        let exclude = fs::read_to_string(repo_path.join(".git/info/exclude")).unwrap();
+        assert_eq!(exclude.matches("/target\n").count(), 1);
+
+        fs::write(cache.join("artifact"), "1234").unwrap();
+        assert_eq!(dir_size(&cache), 4);
+    }
+
+    #[test]
+    fn leaves_populated_paths_alone() {
+        let td = tempfile::TempDir::new().unwrap();
+        let repo_path = td.path().join("repo");
+        GitService::new()
+            .initialize_repo_with_main_branch(&repo_path)
+            .unwrap();
+        fs::create_dir_all(repo_path.join("target")).unwrap();
+        fs::write(repo_path.join("target/build.log"), "local").unwrap();
+
+        link_into_worktree(&td.path().join("cache"), &repo_path, "target").unwrap();
+
+        assert!(
+            !fs::symlink_metadata(repo_path.join("target"))
+                .unwrap()
+                .is_symlink()
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! Migrations and backfills that run in the background after startup.
+//!
+//! The server starts accepting requests right away; each task's progress is
+//! published over the events stream under `/startup_tasks` and reported by
+//! `/api/health/detail`. Code that depends on a task's result awaits it with
+//! [`StartupTasks::wait_for`], which returns immediately once the task is done,
+//! so only requests arriving during startup ever wait.
+
+use std::{collections::HashMap, fmt::Display, future::Future, sync::Arc, time::Instant};
+
+use db::DBService;
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+use tokio::sync::watch;
+use ts_rs::TS;
+use utils::msg_store::MsgStore;
+
+use crate::services::{
+    container::ContainerService, events::patches::startup_task_patch, execution_process, log_search,
+};
+
+/// Progress is published every this many items, besides state changes.
+const PROGRESS_PUBLISH_INTERVAL: u64 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum StartupTask {
+    /// Moves execution logs from SQLite to log files.
+    LogMigration,
+    BeforeHeadCommits,
+    RepoNames,
+    /// Indexes log files written before log search existed.
+    LogSearchIndex,
+}
+
+impl StartupTask {
+    pub const ALL: [StartupTask; 4] = [
+        StartupTask::LogMigration,
+        StartupTask::BeforeHeadCommits,
+        StartupTask::RepoNames,
+        StartupTask::LogSearchIndex,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum StartupTaskState {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl StartupTaskState {
+    fn is_finished(self) -> bool {
+        matches!(self, StartupTaskState::Completed | StartupTaskState::Failed)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct StartupTaskProgress {
+    pub task: StartupTask,
+    pub state: StartupTaskState,
+    #[ts(type = "number")]
+    pub completed: u64,
+    /// Unknown until the task has counted its work.
+    #[ts(type = "number | null")]
+    pub total: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct StartupStatus {
+    /// Whether every startup task has finished, successfully or not.
+    pub ready: bool,
+    pub tasks: Vec<StartupTaskProgress>,
+}
+
+#[derive(Clone)]
+pub struct StartupTasks {
+    progress: Arc<watch::Sender<HashMap<StartupTask, StartupTaskProgress>>>,
+    msg_store: Arc<MsgStore>,
+}
+
+impl StartupTasks {
+    pub fn new(msg_store: Arc<MsgStore>) -> Self {
+        let progress = StartupTask::ALL
+            .into_iter()
+            .map(|task| {
+                (
+                    task,
+                    StartupTaskProgress {
+                        task,
+                        state: StartupTaskState::Pending,
+                        completed: 0,
+                        total: None,
+                        error: None,
+                    },
+                )
+            })
+            .collect();
+        Self {
+            progress: Arc::new(watch::channel(progress).0),
+            msg_store,
+        }
+    }
+
+    pub fn status(&self) -> StartupStatus {
+        let progress = self.progress.borrow();
+        let tasks = StartupTask::ALL
+            .iter()
+            .filter_map(|task| progress.get(task).cloned())
+            .collect::<Vec<_>>();
+        StartupStatus {
+            ready: tasks.iter().all(|task| task.state.is_finished()),
+            tasks,
+        }
+    }
+
+    /// Wait until `task` has finished. Failed tasks count as finished, so
+    /// callers fall back to whatever data is there.
+    pub async fn wait_for(&self, task: StartupTask) {
+        let mut rx = self.progress.subscribe();
+        let _ = rx
+            .wait_for(|progress| progress.get(&task).is_some_and(|p| p.state.is_finished()))
+            .await;
+    }
+
+    pub fn set_progress(&self, task: StartupTask, completed: u64, total: Option<u64>) {
+        let publish = completed.is_multiple_of(PROGRESS_PUBLISH_INTERVAL)
+            || total.is_some_and(|total| completed >= total);
+        self.update(task, publish, |progress| {
+            progress.completed = completed;
+            progress.total = total;
+        });
+    }
+
+    /// Run `task` to completion, recording its state.
+    pub async fn run<F, E>(&self, task: StartupTask, fut: F)
+    where
+        F: Future<Output = Result<(), E>>,
+        E: Display,
+    {
+        self.update(task, true, |progress| {
+            progress.state = StartupTaskState::Running;
+        });
+        let started = Instant::now();
+
+        let result = fut.await;
+        let elapsed = started.elapsed();
+        self.update(task, true, |progress| match result {
+            Ok(()) => {
+                tracing::info!("Startup task {} finished in {:?}", task, elapsed);
+                progress.state = StartupTaskState::Completed;
+            }
+            Err(e) => {
+                tracing::error!("Startup task {} failed after {:?}: {}", task, elapsed, e);
+                progress.state = StartupTaskState::Failed;
+                progress.error = Some(e.to_string());
+            }
+        });
+    }
+
+    /// Spawn all startup tasks. Log search indexing waits for the log
+    /// migration, as it only reads log files.
+    pub fn spawn<C>(&self, container: C, db: DBService)
+    where
+        C: ContainerService + Send + Sync + 'static,
+    {
+        let tasks = self.clone();
+        tokio::spawn(async move {
+            tasks
+                .run(
+                    StartupTask::LogMigration,
+                    execution_process::migrate_execution_logs_to_files(&tasks, &db),
+                )
+                .await;
+            tasks
+                .run(StartupTask::LogSearchIndex, async {
+                    log_search::backfill_log_search_index(db.clone()).await;
+                    Ok::<(), std::convert::Infallible>(())
+                })
+                .await;
+        });
+
+        let tasks = self.clone();
+        tokio::spawn(async move {
+            tasks
+                .run(StartupTask::RepoNames, container.backfill_repo_names())
+                .await;
+            tasks
+                .run(
+                    StartupTask::BeforeHeadCommits,
+                    container.backfill_before_head_commits(),
+                )
+                .await;
+        });
+    }
+
+    fn update(&self, task: StartupTask, publish: bool, f: impl FnOnce(&mut StartupTaskProgress)) {
+        let mut snapshot = None;
+        self.progress.send_modify(|progress| {
+            if let Some(entry) = progress.get_mut(&task) {
+                f(entry);
+                snapshot = Some(entry.clone());
+            }
+        });
+        if publish && let Some(snapshot) = snapshot {
+            self.msg_store
+                .push_patch(startup_task_patch::set(&snapshot));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn wait_for_returns_once_the_task_finished() {
+        let tasks = StartupTasks::new(Arc::new(MsgStore::new()));
+        assert!(!tasks.status().ready);
+
+        let waiter = {
+            let tasks = tasks.clone();
+            tokio::spawn(async move { tasks.wait_for(StartupTask::RepoNames).await })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!waiter.is_finished());
+
+        tasks
+            .run(StartupTask::RepoNames, async {
+                Err::<(), _>("repo table locked")
+            })
+            .await;
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let status = tasks.status();
+        let repo_names = status
+            .tasks
+            .iter()
+            .find(|progress| progress.task == StartupTask::RepoNames)
+            .unwrap();
+        assert_eq!(repo_names.state, StartupTaskState::Failed);
+        assert_eq!(repo_names.error.as_deref(), Some("repo table locked"));
+        assert!(!status.ready);
+
+        // Already finished tasks don't block.
+        tasks.wait_for(StartupTask::RepoNames).await;
+    }
+}
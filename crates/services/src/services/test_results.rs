@@ -0,0 +1,136 @@
+//! Parsing of test runner summaries from verify script output.
+//!
+//! Recognises the summary lines printed by `cargo test`, jest and pytest.
+//! Counts from every recognised runner are added together, so a verify script
+//! may run several test suites.
+
+use db::models::execution_process::TestResults;
+
+/// Parse the pass/fail counts out of a test run's output. Returns `None` if no
+/// known summary line was found.
+pub fn parse_test_output(output: &str) -> Option<TestResults> {
+    let output = strip_ansi_escapes::strip_str(output);
+
+    // cargo prints one summary per test binary; jest and pytest print a single
+    // summary at the end, so only their last one counts.
+    let mut cargo: Option<TestResults> = None;
+    let mut jest: Option<TestResults> = None;
+    let mut pytest: Option<TestResults> = None;
+
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix("test result:") {
+            // `test result: ok. 3 passed; 0 failed; 1 ignored; ...`
+            let counts = rest.split_once('.').map_or(rest, |(_, counts)| counts);
+            if let Some(results) = parse_counts(counts, ';') {
+                let total = cargo.get_or_insert_with(TestResults::default);
+                total.passed += results.passed;
+                total.failed += results.failed;
+                total.skipped += results.skipped;
+            }
+        } else if let Some(rest) = line.strip_prefix("Tests:") {
+            // `Tests:       1 failed, 2 skipped, 10 passed, 13 total`
+            if let Some(results) = parse_counts(rest, ',') {
+                jest = Some(results);
+            }
+        } else if line.starts_with('=') && line.ends_with('=') {
+            // `===== 1 failed, 10 passed, 2 skipped in 0.12s =====`
+            let inner = line.trim_matches('=').trim();
+            if let Some((counts, _)) = inner.rsplit_once(" in ")
+                && let Some(results) = parse_counts(counts, ',')
+            {
+                pytest = Some(results);
+            }
+        }
+    }
+
+    [cargo, jest, pytest]
+        .into_iter()
+        .flatten()
+        .reduce(|total, results| TestResults {
+            passed: total.passed + results.passed,
+            failed: total.failed + results.failed,
+            skipped: total.skipped + results.skipped,
+        })
+}
+
+/// Parse `<count> <outcome>` pairs separated by `separator`. Returns `None`
+/// unless at least one pair names a known outcome.
+fn parse_counts(text: &str, separator: char) -> Option<TestResults> {
+    let mut results = TestResults::default();
+    let mut recognised = false;
+    for part in text.split(separator) {
+        let mut words = part.split_whitespace();
+        let (Some(count), Some(outcome)) = (words.next(), words.next()) else {
+            continue;
+        };
+        let Ok(count) = count.parse::<u32>() else {
+            continue;
+        };
+        let slot = match outcome {
+            "passed" | "xpassed" => &mut results.passed,
+            "failed" | "error" | "errors" => &mut results.failed,
+            "ignored" | "skipped" | "xfailed" | "pending" | "todo" => &mut results.skipped,
+            _ => continue,
+        };
+        *slot += count;
+        recognised = true;
+    }
+    recognised.then_some(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results(passed: u32, failed: u32, skipped: u32) -> Option<TestResults> {
+        Some(TestResults {
+            passed,
+            failed,
+            skipped,
+        })
+    }
+
+    #[test]
+    fn sums_cargo_test_binaries() {
+        let output = "\
+running 3 tests
+test a ... ok
+test result: ok. 3 passed; 0 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.01s
+
+running 2 tests
+test result: FAILED. 1 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s
+";
+        assert_eq!(parse_test_output(output), results(4, 1, 1));
+    }
+
+    #[test]
+    fn parses_jest_summary() {
+        let output = "\
+Test Suites: 1 failed, 3 passed, 4 total
+Tests:       2 failed, 1 skipped, 10 passed, 13 total
+Snapshots:   0 total
+";
+        assert_eq!(parse_test_output(output), results(10, 2, 1));
+    }
+
+    #[test]
+    fn parses_pytest_summary() {
+        let output = "\
+============================= test session starts ==============================
+=========================== short test summary info ============================
+FAILED tests/test_x.py::test_y - assert 1 == 2
+============== 1 failed, 10 passed, 2 skipped, 1 error in 0.12s ===============
+";
+        assert_eq!(parse_test_output(output), results(10, 2, 2));
+        assert_eq!(
+            parse_test_output("\u{1b}[32m==== 5 passed in 1.02s ====\u{1b}[0m"),
+            results(5, 0, 0)
+        );
+    }
+
+    #[test]
+    fn ignores_unrecognised_output() {
+        assert_eq!(parse_test_output("Compiling foo v0.1.0\nDone in 2s"), None);
+    }
+}
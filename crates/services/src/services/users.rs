@@ -0,0 +1,305 @@
+//! Lightweight user identities for a small team sharing one server.
+//!
+//! Users have no passwords. Creating a user or issuing a sign-in token returns
+//! a random token once; whoever holds it acts as that user, by sending it in
+//! the [`USER_SESSION_HEADER`] header or the [`USER_SESSION_COOKIE`] cookie.
+//! Requests without a token act as the machine's owner unless
+//! `users.require_sign_in` is set. Workspaces and executions remember who
+//! started them, so runs can be filtered by owner and only their owner or an
+//! admin can stop them.
+//!
+//! When `users.remote_organization_id` is set, members of that remote
+//! organization can also sign in with their remote account instead of being
+//! handed a token.
+
+use api_types::MemberRole;
+use db::models::{
+    idempotency::is_unique_violation,
+    local_user::{LocalUser, RemoteOrgRole},
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use utils::tokens::{hash_token, new_token};
+use uuid::Uuid;
+
+use crate::services::remote_client::{RemoteClient, RemoteClientError};
+
+pub const USER_SESSION_HEADER: &str = "x-vk-user-session";
+pub const USER_SESSION_COOKIE: &str = "vk_user_session";
+
+const MAX_NAME_LENGTH: usize = 64;
+
+#[derive(Debug, Error)]
+pub enum UserError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("User names must be 1 to {MAX_NAME_LENGTH} characters")]
+    InvalidName,
+    #[error("A user named {0} already exists")]
+    NameTaken(String),
+    #[error("User not found")]
+    NotFound,
+    #[error("That isn't an email address")]
+    InvalidEmail,
+    #[error("Only admins can {0}")]
+    Forbidden(&'static str),
+    #[error(transparent)]
+    Remote(#[from] RemoteClientError),
+    #[error("Signing in with a remote account isn't enabled on this server")]
+    RemoteSignInDisabled,
+    #[error("Your remote account isn't a member of this server's organization")]
+    NotOrganizationMember,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateLocalUserRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateLocalUserRequest {
+    /// `None` or an empty string clears it.
+    pub email: Option<String>,
+}
+
+/// A user with a freshly issued session token. The token isn't stored and
+/// can't be shown again.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct LocalUserToken {
+    pub user: LocalUser,
+    pub token: String,
+}
+
+/// Whether `caller` may manage users. Requests without a user act as the
+/// machine's owner.
+fn is_admin(caller: Option<&LocalUser>) -> bool {
+    caller.is_none_or(|user| user.is_admin)
+}
+
+/// Whether `caller` may stop or delete what `owner` started. What nobody
+/// owns, such as runs started anonymously, is left to admins.
+pub fn can_control(caller: Option<&LocalUser>, owner: Option<Uuid>) -> bool {
+    match (caller, owner) {
+        (Some(user), Some(owner)) => user.is_admin || user.id == owner,
+        (Some(user), None) => user.is_admin,
+        (None, _) => true,
+    }
+}
+
+async fn issue_token(pool: &SqlitePool, user: LocalUser) -> Result<LocalUserToken, UserError> {
+    let token = new_token();
+    LocalUser::create_session(pool, user.id, &hash_token(&token)).await?;
+    Ok(LocalUserToken { user, token })
+}
+
+async fn insert_user(
+    pool: &SqlitePool,
+    name: &str,
+    is_admin: bool,
+    remote_user_id: Option<Uuid>,
+) -> Result<LocalUser, UserError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+        return Err(UserError::InvalidName);
+    }
+    LocalUser::create(pool, Uuid::new_v4(), name, is_admin, remote_user_id)
+        .await
+        .map_err(|e| {
+            if is_unique_violation(&e) {
+                UserError::NameTaken(name.to_string())
+            } else {
+                e.into()
+            }
+        })
+}
+
+/// Create a user. The first user becomes an admin and may be created by
+/// anyone; after that only admins can add users.
+pub async fn create_user(
+    pool: &SqlitePool,
+    caller: Option<&LocalUser>,
+    name: &str,
+) -> Result<LocalUserToken, UserError> {
+    let first = LocalUser::count(pool).await? == 0;
+    if !first && !is_admin(caller) {
+        return Err(UserError::Forbidden("add users"));
+    }
+
+    let user = insert_user(pool, name, first, None).await?;
+    issue_token(pool, user).await
+}
+
+/// Sign in with a freshly issued remote access token, which is revoked once
+/// it has identified the account. Members of `organization_id` get a local
+/// user linked to their account on first sign-in; the organization's admins
+/// are admins here too.
+pub async fn sign_in_with_remote(
+    pool: &SqlitePool,
+    client: &RemoteClient,
+    organization_id: Option<Uuid>,
+    access_token: &str,
+) -> Result<LocalUserToken, UserError> {
+    let organization_id = organization_id.ok_or(UserError::RemoteSignInDisabled)?;
+    let identified = async {
+        let profile = client.profile_for_token(access_token).await?;
+        let role = client
+            .list_organizations_for_token(access_token)
+            .await?
+            .organizations
+            .into_iter()
+            .find(|organization| organization.id == organization_id)
+            .map(|organization| organization.user_role);
+        Ok::<_, RemoteClientError>((profile, role))
+    }
+    .await;
+    if let Err(e) = client.logout_token(access_token).await {
+        tracing::warn!("Failed to revoke remote sign-in token: {}", e);
+    }
+    let (profile, role) = identified?;
+    let role = match role {
+        Some(MemberRole::Admin) => RemoteOrgRole::Admin,
+        Some(MemberRole::Member) => RemoteOrgRole::Member,
+        None => return Err(UserError::NotOrganizationMember),
+    };
+    let org_admin = role == RemoteOrgRole::Admin;
+
+    let user = match LocalUser::find_by_remote_user_id(pool, profile.user_id).await? {
+        Some(mut user) => {
+            if org_admin && !user.is_admin {
+                LocalUser::set_admin(pool, user.id, true).await?;
+                user.is_admin = true;
+            }
+            user
+        }
+        None => {
+            let is_admin = org_admin || LocalUser::count(pool).await? == 0;
+            // Prefer the remote username, falling back to the email address
+            // when a local user already goes by it.
+            let names = profile
+                .username
+                .iter()
+                .chain(std::iter::once(&profile.email));
+            let mut created = Err(UserError::InvalidName);
+            for name in names {
+                created = insert_user(pool, name, is_admin, Some(profile.user_id)).await;
+                if !matches!(
+                    created,
+                    Err(UserError::NameTaken(_) | UserError::InvalidName)
+                ) {
+                    break;
+                }
+            }
+            created?
+        }
+    };
+    LocalUser::set_remote_details(pool, user.id, &profile.email, role).await?;
+    let user = LocalUser {
+        email: Some(profile.email),
+        remote_role: Some(role),
+        ..user
+    };
+    tracing::info!("User {} signed in with their remote account", user.name);
+    issue_token(pool, user).await
+}
+
+/// Issue another token for `user_id`, e.g. for a second device. Users can
+/// issue their own; admins anyone's.
+pub async fn issue_user_token(
+    pool: &SqlitePool,
+    caller: Option<&LocalUser>,
+    user_id: Uuid,
+) -> Result<LocalUserToken, UserError> {
+    if caller.is_some_and(|caller| caller.id != user_id) && !is_admin(caller) {
+        return Err(UserError::Forbidden("sign in as someone else"));
+    }
+    let user = LocalUser::find_by_id(pool, user_id)
+        .await?
+        .ok_or(UserError::NotFound)?;
+    issue_token(pool, user).await
+}
+
+/// Set where `user_id`'s notifications are emailed. Users can set their own;
+/// admins anyone's.
+pub async fn set_user_email(
+    pool: &SqlitePool,
+    caller: Option<&LocalUser>,
+    user_id: Uuid,
+    email: Option<&str>,
+) -> Result<LocalUser, UserError> {
+    if caller.is_some_and(|caller| caller.id != user_id) && !is_admin(caller) {
+        return Err(UserError::Forbidden("change someone else's email"));
+    }
+    let email = email.map(str::trim).filter(|email| !email.is_empty());
+    if email.is_some_and(|email| !email.contains('@') || email.contains(char::is_whitespace)) {
+        return Err(UserError::InvalidEmail);
+    }
+    LocalUser::set_email(pool, user_id, email)
+        .await?
+        .ok_or(UserError::NotFound)
+}
+
+pub async fn delete_user(
+    pool: &SqlitePool,
+    caller: Option<&LocalUser>,
+    user_id: Uuid,
+) -> Result<(), UserError> {
+    if !is_admin(caller) {
+        return Err(UserError::Forbidden("remove users"));
+    }
+    if !LocalUser::delete(pool, user_id).await? {
+        return Err(UserError::NotFound);
+    }
+    Ok(())
+}
+
+/// The user a session token belongs to, if it is valid.
+pub async fn authenticate(pool: &SqlitePool, token: &str) -> Result<Option<LocalUser>, UserError> {
+    Ok(LocalUser::find_by_session(pool, &hash_token(token)).await?)
+}
+
+pub async fn sign_out(pool: &SqlitePool, token: &str) -> Result<(), UserError> {
+    Ok(LocalUser::delete_session(pool, &hash_token(token)).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(is_admin: bool) -> LocalUser {
+        LocalUser {
+            id: Uuid::new_v4(),
+            name: "someone".to_string(),
+            is_admin,
+            remote_user_id: None,
+            email: None,
+            remote_role: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn only_owners_and_admins_control_owned_runs() {
+        let (member, admin) = (user(false), user(true));
+        let other = Uuid::new_v4();
+
+        assert!(can_control(Some(&member), Some(member.id)));
+        assert!(!can_control(Some(&member), Some(other)));
+        assert!(can_control(Some(&admin), Some(other)));
+        assert!(
+            !can_control(Some(&member), None),
+            "unowned runs are admin-only"
+        );
+        assert!(can_control(Some(&admin), None));
+        assert!(can_control(None, Some(other)), "the machine's owner");
+    }
+
+    #[test]
+    fn tokens_are_stored_hashed() {
+        let token = new_token();
+        assert_ne!(hash_token(&token), token);
+        assert_eq!(hash_token(&token), hash_token(&token));
+        assert_ne!(new_token(), token);
+    }
+}
@@ -0,0 +1,489 @@
+//! Warm workspace pool.
+//!
+//! When enabled (`warm_pool` in the config), keeps a few worktrees of the most
+//! recently used repos checked out on the target branch their last workspace
+//! used, with the setup script already run. A new workspace whose repos all
+//! have a ready worktree adopts them: each is moved into the workspace dir,
+//! its placeholder branch renamed to the workspace branch and fast-forwarded
+//! to the target branch's current head, and the setup scripts are skipped.
+//! The pool refills in the background, one worktree at a time, and stops
+//! growing once it takes up `max_disk_mb`.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use db::{
+    DBService,
+    models::{
+        repo::Repo,
+        warm_worktree::{CreateWarmWorktree, WarmWorktree, WarmWorktreeStatus},
+        workspace::Workspace,
+        workspace_repo::WorkspaceRepo,
+    },
+};
+use git::{GitService, GitServiceError};
+use thiserror::Error;
+use tokio::{
+    sync::{Mutex, Notify, RwLock},
+    time::interval,
+};
+use utils::shell::get_shell_command;
+use uuid::Uuid;
+use worktree_manager::{WorktreeCleanup, WorktreeError, WorktreeManager};
+
+use super::{
+    config::Config,
+    config_overrides,
+    secrets::{self, SecretError},
+    shared_caches,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const DEFAULT_SETUP_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+/// How long to leave a repo alone after its setup script failed in the pool.
+const FAILURE_BACKOFF: Duration = Duration::from_secs(60 * 60);
+const PLACEHOLDER_BRANCH_PREFIX: &str = "vk-warm/";
+
+#[derive(Debug, Error)]
+pub enum WarmPoolError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error(transparent)]
+    Worktree(#[from] WorktreeError),
+    #[error(transparent)]
+    Secret(#[from] SecretError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Setup script failed: {0}")]
+    SetupFailed(String),
+    #[error("Setup script timed out")]
+    SetupTimedOut,
+}
+
+#[derive(Clone)]
+pub struct WarmPool {
+    db: DBService,
+    git: GitService,
+    config: Arc<RwLock<Config>>,
+    refill_notify: Arc<Notify>,
+    /// Last measured size of each repo's pool worktrees, to tell whether
+    /// another one fits the quota before provisioning it.
+    sizes: Arc<Mutex<HashMap<Uuid, u64>>>,
+    failures: Arc<Mutex<HashMap<Uuid, Instant>>>,
+}
+
+impl WarmPool {
+    pub fn new(db: DBService, git: GitService, config: Arc<RwLock<Config>>) -> Self {
+        Self {
+            db,
+            git,
+            config,
+            refill_notify: Arc::new(Notify::new()),
+            sizes: Arc::new(Mutex::new(HashMap::new())),
+            failures: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Refill the pool periodically and whenever a worktree is claimed.
+    pub fn spawn(&self) -> tokio::task::JoinHandle<()> {
+        let pool = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = pool.cleanup_interrupted().await {
+                tracing::error!("Failed to clean up the warm pool: {}", e);
+            }
+            let mut interval = interval(POLL_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = pool.refill_notify.notified() => {}
+                }
+                if let Err(e) = pool.refill().await {
+                    tracing::error!("Failed to refill the warm pool: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Adopt pool worktrees for the repos of a workspace that has none yet.
+    /// Returns whether every repo got one, in which case the setup scripts
+    /// have already run.
+    pub async fn claim_for_workspace(
+        &self,
+        workspace: &Workspace,
+        workspace_dir: &Path,
+    ) -> Result<bool, WarmPoolError> {
+        if !self.config.read().await.warm_pool.enabled {
+            return Ok(false);
+        }
+        let pool = &self.db.pool;
+        let targets =
+            WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
+        if targets.is_empty() {
+            return Ok(false);
+        }
+        // The pool ran the repo's setup script; a workspace override of it
+        // needs a fresh setup.
+        let mut repos: Vec<Repo> = targets.iter().map(|target| target.repo.clone()).collect();
+        if let Err(e) =
+            config_overrides::apply_workspace_script_overrides(pool, workspace.id, &mut repos).await
+        {
+            tracing::warn!(
+                "Failed to load config overrides for workspace {}: {}",
+                workspace.id,
+                e
+            );
+        }
+
+        let mut all_claimed = true;
+        for (repo, target) in repos.iter().zip(&targets) {
+            if self
+                .git
+                .check_branch_exists(&repo.path, &workspace.branch)?
+            {
+                all_claimed = false;
+                continue;
+            }
+            let Ok(head) = self.git.get_branch_oid(&repo.path, &target.target_branch) else {
+                all_claimed = false;
+                continue;
+            };
+            let Some(entry) = WarmWorktree::claim(
+                pool,
+                repo.id,
+                &target.target_branch,
+                repo.setup_script.as_deref(),
+            )
+            .await?
+            else {
+                all_claimed = false;
+                continue;
+            };
+
+            let worktree_path = workspace_dir.join(&repo.name);
+            if let Err(e) = self
+                .adopt(repo, &entry, &worktree_path, &workspace.branch, &head)
+                .await
+            {
+                tracing::warn!(
+                    "Failed to adopt warm worktree of {} for workspace {}: {}",
+                    repo.name,
+                    workspace.id,
+                    e
+                );
+                let leftover = if worktree_path.exists() {
+                    worktree_path.as_path()
+                } else {
+                    Path::new(&entry.path)
+                };
+                self.remove_worktree(repo, leftover, &entry).await;
+                all_claimed = false;
+                continue;
+            }
+            tracing::info!(
+                "Workspace {} adopted warm worktree {} of {}",
+                workspace.id,
+                entry.id,
+                repo.name
+            );
+        }
+
+        self.refill_notify.notify_one();
+        Ok(all_claimed)
+    }
+
+    async fn adopt(
+        &self,
+        repo: &Repo,
+        entry: &WarmWorktree,
+        worktree_path: &Path,
+        branch: &str,
+        head: &str,
+    ) -> Result<(), WarmPoolError> {
+        if let Some(parent) = worktree_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        WorktreeManager::move_worktree(&repo.path, Path::new(&entry.path), worktree_path).await?;
+
+        let git = self.git.clone();
+        let worktree_path = worktree_path.to_path_buf();
+        let (placeholder, branch, head) =
+            (entry.branch.clone(), branch.to_string(), head.to_string());
+        let needs_sync = entry.base_commit != head;
+        tokio::task::spawn_blocking(move || {
+            git.rename_local_branch(&worktree_path, &placeholder, &branch)?;
+            if needs_sync {
+                git.move_worktree_branch_to_commit(&worktree_path, &head)?;
+            }
+            Ok::<_, GitServiceError>(())
+        })
+        .await
+        .map_err(std::io::Error::other)??;
+        Ok(())
+    }
+
+    async fn refill(&self) -> Result<(), WarmPoolError> {
+        let config = self.config.read().await.warm_pool.clone();
+        let pool = &self.db.pool;
+        let entries = WarmWorktree::find_all(pool).await?;
+        if !config.enabled {
+            for entry in &entries {
+                self.discard(entry).await;
+            }
+            return Ok(());
+        }
+
+        let mut wanted = Vec::new();
+        for (repo_id, target_branch) in
+            WorkspaceRepo::find_recent_repo_targets(pool, config.repos).await?
+        {
+            if let Some(repo) = Repo::find_by_id(pool, repo_id).await? {
+                wanted.push((repo, target_branch));
+            }
+        }
+
+        // Drop worktrees nobody is going to claim, then the newest ones over
+        // the quota
+        let max_bytes = u64::from(config.max_disk_mb) * 1024 * 1024;
+        let mut used_bytes = 0;
+        let mut kept: HashMap<(Uuid, String), u32> = HashMap::new();
+        for entry in &entries {
+            let still_wanted = wanted.iter().any(|(repo, target_branch)| {
+                repo.id == entry.repo_id
+                    && *target_branch == entry.target_branch
+                    && repo.setup_script == entry.setup_script
+            });
+            let count = kept
+                .entry((entry.repo_id, entry.target_branch.clone()))
+                .or_default();
+            let size = entry.size_bytes.max(0) as u64;
+            if !still_wanted || *count >= config.worktrees_per_repo || used_bytes + size > max_bytes
+            {
+                self.discard(entry).await;
+                continue;
+            }
+            *count += 1;
+            used_bytes += size;
+        }
+
+        for (repo, target_branch) in &wanted {
+            if self
+                .failures
+                .lock()
+                .await
+                .get(&repo.id)
+                .is_some_and(|failed_at| failed_at.elapsed() < FAILURE_BACKOFF)
+            {
+                continue;
+            }
+            let have = kept
+                .get(&(repo.id, target_branch.clone()))
+                .copied()
+                .unwrap_or(0);
+            for _ in have..config.worktrees_per_repo {
+                let expected = self.sizes.lock().await.get(&repo.id).copied().unwrap_or(0);
+                if used_bytes + expected > max_bytes {
+                    tracing::debug!("Warm pool is at its disk quota");
+                    return Ok(());
+                }
+                match self.provision(repo, target_branch).await {
+                    Ok(size) => used_bytes += size,
+                    Err(e) => {
+                        tracing::warn!(
+                            "Failed to provision a warm worktree of {}: {}",
+                            repo.name,
+                            e
+                        );
+                        self.failures.lock().await.insert(repo.id, Instant::now());
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check out a worktree of `repo` on `target_branch` and run the setup
+    /// script in it. Returns its size on disk.
+    async fn provision(&self, repo: &Repo, target_branch: &str) -> Result<u64, WarmPoolError> {
+        let pool = &self.db.pool;
+        let id = Uuid::new_v4();
+        let branch = format!("{PLACEHOLDER_BRANCH_PREFIX}{}", id.simple());
+        let worktree_path = WorktreeManager::get_warm_pool_dir().join(id.to_string());
+        let base_commit = self.git.get_branch_oid(&repo.path, target_branch)?;
+        let entry = WarmWorktree::create(
+            pool,
+            id,
+            &CreateWarmWorktree {
+                repo_id: repo.id,
+                target_branch: target_branch.to_string(),
+                branch: branch.clone(),
+                path: worktree_path.to_string_lossy().to_string(),
+                base_commit,
+                setup_script: repo.setup_script.clone(),
+            },
+        )
+        .await?;
+        tracing::info!("Provisioning warm worktree {} of {}", id, repo.name);
+
+        let result = async {
+            WorktreeManager::create_worktree(
+                &repo.path,
+                &branch,
+                &worktree_path,
+                target_branch,
+                true,
+            )
+            .await?;
+            shared_caches::link_repo_caches(pool, repo.id, &worktree_path).await?;
+            self.run_setup_script(repo, &worktree_path).await?;
+            let measured = worktree_path.clone();
+            let size = tokio::task::spawn_blocking(move || shared_caches::dir_size(&measured))
+                .await
+                .map_err(std::io::Error::other)?;
+            WarmWorktree::mark_ready(pool, id, size).await?;
+            Ok::<_, WarmPoolError>(size)
+        }
+        .await;
+
+        match result {
+            Ok(size) => {
+                self.sizes.lock().await.insert(repo.id, size);
+                Ok(size)
+            }
+            Err(e) => {
+                self.discard(&entry).await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn run_setup_script(
+        &self,
+        repo: &Repo,
+        worktree_path: &Path,
+    ) -> Result<(), WarmPoolError> {
+        let Some(script) = repo
+            .setup_script
+            .as_deref()
+            .filter(|script| !script.trim().is_empty())
+        else {
+            return Ok(());
+        };
+        let pool = &self.db.pool;
+        let mut env = shared_caches::repo_env(pool, repo.id).await?;
+        let (script, secret_env) = secrets::prepare_script(pool, script).await?;
+        env.extend(secret_env);
+        let timeout = Repo::script_settings(pool, repo.id)
+            .await
+            .ok()
+            .and_then(|settings| settings.setup_timeout_secs)
+            .map(|secs| Duration::from_secs(secs.into()))
+            .unwrap_or(DEFAULT_SETUP_TIMEOUT);
+
+        let (shell, shell_arg) = get_shell_command();
+        let mut command = tokio::process::Command::new(shell);
+        command
+            .arg(shell_arg)
+            .arg(&script)
+            .current_dir(worktree_path)
+            .envs(&env)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        let output = tokio::time::timeout(timeout, command.output())
+            .await
+            .map_err(|_| WarmPoolError::SetupTimedOut)??;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let tail: Vec<&str> = stderr.trim().lines().rev().take(5).collect();
+            return Err(WarmPoolError::SetupFailed(
+                tail.into_iter().rev().collect::<Vec<_>>().join("\n"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Remove a pool worktree, its placeholder branch and its record, unless
+    /// a workspace claimed it in the meantime.
+    async fn discard(&self, entry: &WarmWorktree) {
+        match WarmWorktree::delete(&self.db.pool, entry.id).await {
+            Ok(true) => {}
+            Ok(false) => return,
+            Err(e) => {
+                tracing::warn!("Failed to forget warm worktree {}: {}", entry.id, e);
+                return;
+            }
+        }
+        match Repo::find_by_id(&self.db.pool, entry.repo_id).await {
+            Ok(Some(repo)) => {
+                self.remove_worktree(&repo, Path::new(&entry.path), entry)
+                    .await
+            }
+            _ => {
+                let cleanup = WorktreeCleanup::new(PathBuf::from(&entry.path), None);
+                if let Err(e) = WorktreeManager::cleanup_worktree(&cleanup).await {
+                    tracing::warn!("Failed to remove warm worktree {}: {}", entry.path, e);
+                }
+            }
+        }
+    }
+
+    async fn remove_worktree(&self, repo: &Repo, worktree_path: &Path, entry: &WarmWorktree) {
+        let cleanup = WorktreeCleanup::new(worktree_path.to_path_buf(), Some(repo.path.clone()));
+        if let Err(e) = WorktreeManager::cleanup_worktree(&cleanup).await {
+            tracing::warn!(
+                "Failed to remove warm worktree {}: {}",
+                worktree_path.display(),
+                e
+            );
+        }
+        // The branch was renamed if adopting got that far
+        if self
+            .git
+            .check_branch_exists(&repo.path, &entry.branch)
+            .unwrap_or(false)
+            && let Err(e) = self.git.delete_branch(&repo.path, &entry.branch)
+        {
+            tracing::warn!("Failed to delete branch {}: {}", entry.branch, e);
+        }
+    }
+
+    /// Drop worktrees whose provisioning was interrupted, and directories no
+    /// record points at.
+    async fn cleanup_interrupted(&self) -> Result<(), WarmPoolError> {
+        let entries = WarmWorktree::find_all(&self.db.pool).await?;
+        let mut known = HashSet::new();
+        for entry in &entries {
+            if entry.status == WarmWorktreeStatus::Provisioning || !Path::new(&entry.path).exists()
+            {
+                self.discard(entry).await;
+            } else {
+                known.insert(PathBuf::from(&entry.path));
+            }
+        }
+
+        let Ok(mut dir) = tokio::fs::read_dir(WorktreeManager::get_warm_pool_dir()).await else {
+            return Ok(());
+        };
+        while let Some(dir_entry) = dir.next_entry().await? {
+            let path = dir_entry.path();
+            if known.contains(&path) {
+                continue;
+            }
+            tracing::info!("Removing orphaned warm worktree {}", path.display());
+            let cleanup = WorktreeCleanup::new(path.clone(), None);
+            if let Err(e) = WorktreeManager::cleanup_worktree(&cleanup).await {
+                tracing::warn!("Failed to remove {}: {}", path.display(), e);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,335 @@
+//! Agent runs started by inbound webhooks.
+//!
+//! A trigger gives an outside system, such as CI reporting a failed build, a
+//! URL it can POST JSON to. The caller names one of the trigger's templates
+//! and one of its repos; the template's prompt and branch are filled from the
+//! payload and started in a new workspace. The URL token is the only
+//! credential, so it is stored hashed and only shown when issued. Payload
+//! values end up in agent prompts, so only point triggers at systems whose
+//! payloads you trust.
+
+use std::collections::HashSet;
+
+use db::models::webhook_trigger::{
+    CreateWebhookTrigger, UpdateWebhookTrigger, WebhookTemplate, WebhookTrigger,
+    WebhookTriggerError as WebhookTriggerModelError,
+};
+use executors::profile::ExecutorConfig;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use utils::tokens::{hash_token, new_token};
+use uuid::Uuid;
+
+const MAX_TRIGGER_NAME_LEN: usize = 128;
+
+#[derive(Debug, Error)]
+pub enum WebhookTriggerError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Trigger(#[from] WebhookTriggerModelError),
+    #[error("Invalid webhook trigger name")]
+    InvalidName,
+    #[error("A webhook trigger needs at least one repo")]
+    NoRepos,
+    #[error("A webhook trigger needs at least one template")]
+    NoTemplates,
+    #[error("Invalid template `{0}`: templates need a name, prompt and target branch")]
+    InvalidTemplate(String),
+    #[error("Template names must be unique; `{0}` is used twice")]
+    DuplicateTemplate(String),
+    #[error("Unknown webhook")]
+    InvalidToken,
+    #[error("This webhook trigger is paused")]
+    Paused,
+    #[error("Name a template with `?template=`; this trigger has several")]
+    TemplateRequired,
+    #[error("Unknown template `{0}`")]
+    UnknownTemplate(String),
+    #[error("Name a repo with `?repo_id=`; this trigger has several")]
+    RepoRequired,
+    #[error("This webhook trigger may not start runs in repo {0}")]
+    RepoNotAllowed(Uuid),
+    #[error("Template `{0}` rendered an empty prompt or branch for this payload")]
+    EmptyRender(String),
+}
+
+/// A trigger with a freshly issued token. The token isn't stored and can't be
+/// shown again.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct WebhookTriggerToken {
+    pub trigger: WebhookTrigger,
+    pub token: String,
+}
+
+/// What a webhook call resolved to, ready to start.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookRun {
+    pub template: String,
+    pub repo_id: Uuid,
+    pub target_branch: String,
+    pub prompt: String,
+    pub executor_config: ExecutorConfig,
+}
+
+fn validate(
+    name: &str,
+    repo_ids: &[Uuid],
+    templates: &[WebhookTemplate],
+) -> Result<(), WebhookTriggerError> {
+    let name = name.trim();
+    if name.is_empty() || name.len() > MAX_TRIGGER_NAME_LEN {
+        return Err(WebhookTriggerError::InvalidName);
+    }
+    if repo_ids.is_empty() {
+        return Err(WebhookTriggerError::NoRepos);
+    }
+    if templates.is_empty() {
+        return Err(WebhookTriggerError::NoTemplates);
+    }
+    let mut names = HashSet::new();
+    for template in templates {
+        if template.name.trim().is_empty()
+            || template.prompt.trim().is_empty()
+            || template.target_branch.trim().is_empty()
+        {
+            return Err(WebhookTriggerError::InvalidTemplate(template.name.clone()));
+        }
+        if !names.insert(template.name.as_str()) {
+            return Err(WebhookTriggerError::DuplicateTemplate(
+                template.name.clone(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub async fn create_trigger(
+    pool: &SqlitePool,
+    data: &CreateWebhookTrigger,
+) -> Result<WebhookTriggerToken, WebhookTriggerError> {
+    validate(&data.name, &data.repo_ids, &data.templates)?;
+    let token = new_token();
+    let trigger = WebhookTrigger::create(pool, data, &hash_token(&token)).await?;
+    Ok(WebhookTriggerToken { trigger, token })
+}
+
+pub async fn update_trigger(
+    pool: &SqlitePool,
+    id: Uuid,
+    data: &UpdateWebhookTrigger,
+) -> Result<WebhookTrigger, WebhookTriggerError> {
+    let existing = WebhookTrigger::find_by_id(pool, id)
+        .await?
+        .ok_or(WebhookTriggerModelError::NotFound)?;
+    validate(
+        data.name.as_deref().unwrap_or(&existing.name),
+        data.repo_ids.as_deref().unwrap_or(&existing.repo_ids),
+        data.templates.as_deref().unwrap_or(&existing.templates),
+    )?;
+    Ok(WebhookTrigger::update(pool, id, data).await?)
+}
+
+/// Issue a new token for a trigger, revoking the old one.
+pub async fn rotate_token(
+    pool: &SqlitePool,
+    id: Uuid,
+) -> Result<WebhookTriggerToken, WebhookTriggerError> {
+    let token = new_token();
+    let trigger = WebhookTrigger::set_token_hash(pool, id, &hash_token(&token)).await?;
+    Ok(WebhookTriggerToken { trigger, token })
+}
+
+/// The active trigger a webhook token belongs to.
+pub async fn resolve_token(
+    pool: &SqlitePool,
+    token: &str,
+) -> Result<WebhookTrigger, WebhookTriggerError> {
+    let trigger = WebhookTrigger::find_by_token_hash(pool, &hash_token(token))
+        .await?
+        .ok_or(WebhookTriggerError::InvalidToken)?;
+    if trigger.paused {
+        return Err(WebhookTriggerError::Paused);
+    }
+    Ok(trigger)
+}
+
+/// Pick the template and repo a call asked for, checked against the
+/// trigger's allowlists, and fill the template from `payload`. Either may be
+/// left out when the trigger only has one.
+pub fn plan_run(
+    trigger: &WebhookTrigger,
+    template: Option<&str>,
+    repo_id: Option<Uuid>,
+    payload: &Value,
+) -> Result<WebhookRun, WebhookTriggerError> {
+    let template = match (template, trigger.templates.as_slice()) {
+        (Some(name), templates) => templates
+            .iter()
+            .find(|template| template.name == name)
+            .ok_or_else(|| WebhookTriggerError::UnknownTemplate(name.to_string()))?,
+        (None, [only]) => only,
+        (None, _) => return Err(WebhookTriggerError::TemplateRequired),
+    };
+    let repo_id = match (repo_id, trigger.repo_ids.as_slice()) {
+        (Some(repo_id), repo_ids) if repo_ids.contains(&repo_id) => repo_id,
+        (Some(repo_id), _) => return Err(WebhookTriggerError::RepoNotAllowed(repo_id)),
+        (None, [only]) => *only,
+        (None, _) => return Err(WebhookTriggerError::RepoRequired),
+    };
+
+    let prompt = render_template(&template.prompt, payload);
+    let target_branch = render_template(&template.target_branch, payload)
+        .trim()
+        .to_string();
+    if prompt.trim().is_empty() || target_branch.is_empty() {
+        return Err(WebhookTriggerError::EmptyRender(template.name.clone()));
+    }
+
+    Ok(WebhookRun {
+        template: template.name.clone(),
+        repo_id,
+        target_branch,
+        prompt,
+        executor_config: template
+            .executor_config
+            .clone()
+            .unwrap_or_else(|| trigger.executor_config.clone()),
+    })
+}
+
+/// Look up a dotted path such as `workflow_run.head_branch` or `commits.0.id`.
+fn lookup<'a>(payload: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(payload, |value, key| match value {
+        Value::Object(object) => object.get(key),
+        Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    })
+}
+
+/// Fill `{{path}}` placeholders from `payload`. Strings are inserted as is,
+/// other values as JSON. Placeholders missing from the payload are left as
+/// written.
+pub fn render_template(template: &str, payload: &Value) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => {
+                match lookup(payload, after[..end].trim()) {
+                    Some(Value::String(value)) => rendered.push_str(value),
+                    Some(value) => rendered.push_str(&value.to_string()),
+                    None => rendered.push_str(&rest[start..start + 2 + end + 2]),
+                }
+                rest = &after[end + 2..];
+            }
+            None => {
+                rendered.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use executors::executors::BaseCodingAgent;
+    use serde_json::json;
+
+    use super::*;
+
+    fn trigger(templates: Vec<WebhookTemplate>, repo_ids: Vec<Uuid>) -> WebhookTrigger {
+        WebhookTrigger {
+            id: Uuid::new_v4(),
+            name: "CI".to_string(),
+            repo_ids,
+            templates,
+            executor_config: ExecutorConfig::new(BaseCodingAgent::ClaudeCode),
+            paused: false,
+            last_triggered_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn template(name: &str) -> WebhookTemplate {
+        WebhookTemplate {
+            name: name.to_string(),
+            prompt: "Fix the failing `{{check.name}}` job on {{check.branch}}: {{check.url}}"
+                .to_string(),
+            target_branch: "{{check.branch}}".to_string(),
+            executor_config: None,
+        }
+    }
+
+    #[test]
+    fn renders_payload_paths() {
+        let payload = json!({
+            "check": { "name": "lint", "attempt": 2 },
+            "commits": [{ "message": "Bump deps" }],
+        });
+
+        assert_eq!(
+            render_template(
+                "{{ check.name }} #{{check.attempt}}: {{commits.0.message}} {{missing}}",
+                &payload
+            ),
+            "lint #2: Bump deps {{missing}}"
+        );
+    }
+
+    #[test]
+    fn plans_runs_within_allowlists() {
+        let repo = Uuid::new_v4();
+        let payload = json!({
+            "check": { "name": "test", "branch": "main", "url": "https://ci/1" }
+        });
+        let single = trigger(vec![template("ci_failure")], vec![repo]);
+
+        let run = plan_run(&single, None, None, &payload).unwrap();
+        assert_eq!(run.template, "ci_failure");
+        assert_eq!(run.repo_id, repo);
+        assert_eq!(run.target_branch, "main");
+        assert_eq!(
+            run.prompt,
+            "Fix the failing `test` job on main: https://ci/1"
+        );
+
+        let other_repo = Uuid::new_v4();
+        assert!(matches!(
+            plan_run(&single, None, Some(other_repo), &payload),
+            Err(WebhookTriggerError::RepoNotAllowed(id)) if id == other_repo
+        ));
+        assert!(matches!(
+            plan_run(&single, Some("deploy"), None, &payload),
+            Err(WebhookTriggerError::UnknownTemplate(_))
+        ));
+
+        let several = trigger(
+            vec![template("ci_failure"), template("flaky")],
+            vec![repo, other_repo],
+        );
+        assert!(matches!(
+            plan_run(&several, None, Some(repo), &payload),
+            Err(WebhookTriggerError::TemplateRequired)
+        ));
+        assert!(matches!(
+            plan_run(&several, Some("flaky"), None, &payload),
+            Err(WebhookTriggerError::RepoRequired)
+        ));
+        assert_eq!(
+            plan_run(&several, Some("flaky"), Some(other_repo), &payload)
+                .unwrap()
+                .repo_id,
+            other_repo
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! Files and URLs attached to a workspace as agent context.
+//!
+//! When the agent is launched, each item is written to `.vibe-context/` in
+//! its working directory and listed at the end of the prompt.
+
+use std::{path::Path, time::Duration};
+
+use db::models::{
+    file::{File, WorkspaceAttachment},
+    workspace_context::{WorkspaceContextItem, WorkspaceContextKind},
+};
+use futures::StreamExt;
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use utils::path::VIBE_CONTEXT_DIR;
+use uuid::Uuid;
+
+use super::file::FileService;
+
+/// Fetched pages are cut off after this many bytes.
+const MAX_URL_CONTENT_BYTES: usize = 1024 * 1024;
+const URL_FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+const MAX_CONTEXT_NAME_CHARS: usize = 100;
+
+#[derive(Debug, Error)]
+pub enum WorkspaceContextError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Context item not found")]
+    NotFound,
+    #[error("Attachment not found")]
+    AttachmentNotFound,
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
+    #[error("A file or a URL is required")]
+    MissingSource,
+    #[error("A context item named '{0}' already exists")]
+    NameTaken(String),
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct AddWorkspaceContext {
+    pub kind: WorkspaceContextKind,
+    /// File name in the context directory; derived from the source if omitted.
+    #[ts(optional)]
+    pub name: Option<String>,
+    /// Uploaded attachment, for `file` items.
+    #[ts(optional)]
+    pub attachment_id: Option<Uuid>,
+    /// Page to fetch, for `url` items.
+    #[ts(optional)]
+    pub url: Option<String>,
+}
+
+/// A context item as written to the worktree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaterializedContext {
+    /// Relative to the agent's working directory, or `None` if the item
+    /// couldn't be written.
+    pub path: Option<String>,
+    pub url: Option<String>,
+}
+
+/// Make `name` safe to use as a single file name.
+fn sanitize_name(name: &str) -> String {
+    let clean: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(MAX_CONTEXT_NAME_CHARS)
+        .collect();
+    let clean = clean.trim_start_matches('.');
+    if clean.is_empty() {
+        "context".to_string()
+    } else {
+        clean.to_string()
+    }
+}
+
+/// File name for a fetched page, e.g. `docs.rs_serde.md`.
+fn name_for_url(url: &reqwest::Url) -> String {
+    let host = url.host_str().unwrap_or("page");
+    let last_segment = url
+        .path_segments()
+        .and_then(|mut segments| segments.rfind(|segment| !segment.is_empty()));
+    let base = match last_segment {
+        Some(segment) => format!("{host}_{segment}"),
+        None => host.to_string(),
+    };
+    let name = sanitize_name(&base);
+    if last_segment.is_some_and(|segment| Path::new(segment).extension().is_some()) {
+        name
+    } else {
+        format!("{name}.md")
+    }
+}
+
+fn parse_url(url: &str) -> Result<reqwest::Url, WorkspaceContextError> {
+    let parsed = reqwest::Url::parse(url.trim())
+        .map_err(|e| WorkspaceContextError::InvalidUrl(e.to_string()))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(WorkspaceContextError::InvalidUrl(format!(
+            "unsupported scheme '{}'",
+            parsed.scheme()
+        )));
+    }
+    Ok(parsed)
+}
+
+pub async fn add_context_item(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+    request: &AddWorkspaceContext,
+) -> Result<WorkspaceContextItem, WorkspaceContextError> {
+    let (default_name, attachment_id, url) = match request.kind {
+        WorkspaceContextKind::File => {
+            let attachment_id = request
+                .attachment_id
+                .ok_or(WorkspaceContextError::MissingSource)?;
+            let file = File::find_by_id(pool, attachment_id)
+                .await?
+                .ok_or(WorkspaceContextError::AttachmentNotFound)?;
+            (file.original_name, Some(attachment_id), None)
+        }
+        WorkspaceContextKind::Url => {
+            let url = parse_url(
+                request
+                    .url
+                    .as_deref()
+                    .ok_or(WorkspaceContextError::MissingSource)?,
+            )?;
+            (name_for_url(&url), None, Some(url.to_string()))
+        }
+    };
+    let name = sanitize_name(
+        request
+            .name
+            .as_deref()
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or(&default_name),
+    );
+
+    let existing = WorkspaceContextItem::find_by_workspace_id(pool, workspace_id).await?;
+    if existing.iter().any(|item| item.name == name) {
+        return Err(WorkspaceContextError::NameTaken(name));
+    }
+    // Keeps the attachment from being cleaned up as orphaned.
+    if let Some(attachment_id) = attachment_id {
+        WorkspaceAttachment::associate_many_dedup(pool, workspace_id, &[attachment_id]).await?;
+    }
+
+    Ok(WorkspaceContextItem::create(
+        pool,
+        workspace_id,
+        request.kind,
+        &name,
+        attachment_id,
+        url.as_deref(),
+    )
+    .await?)
+}
+
+pub async fn remove_context_item(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+    id: Uuid,
+) -> Result<(), WorkspaceContextError> {
+    if WorkspaceContextItem::delete(pool, workspace_id, id).await? == 0 {
+        return Err(WorkspaceContextError::NotFound);
+    }
+    Ok(())
+}
+
+async fn fetch_url(client: &reqwest::Client, url: &str) -> Result<Vec<u8>, reqwest::Error> {
+    let response = client.get(url).send().await?.error_for_status()?;
+    let mut stream = response.bytes_stream();
+    let mut content = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        content.extend_from_slice(&chunk?);
+        if content.len() >= MAX_URL_CONTENT_BYTES {
+            content.truncate(MAX_URL_CONTENT_BYTES);
+            break;
+        }
+    }
+    Ok(content)
+}
+
+async fn write_item(
+    item: &WorkspaceContextItem,
+    target: &Path,
+    file_service: &FileService,
+    pool: &SqlitePool,
+    client: &reqwest::Client,
+) -> anyhow::Result<()> {
+    match item.kind {
+        WorkspaceContextKind::File => {
+            let attachment_id = item
+                .attachment_id
+                .ok_or(WorkspaceContextError::MissingSource)?;
+            let file = File::find_by_id(pool, attachment_id)
+                .await?
+                .ok_or(WorkspaceContextError::AttachmentNotFound)?;
+            tokio::fs::copy(file_service.get_absolute_path(&file), target).await?;
+        }
+        WorkspaceContextKind::Url => {
+            let url = item
+                .url
+                .as_deref()
+                .ok_or(WorkspaceContextError::MissingSource)?;
+            tokio::fs::write(target, fetch_url(client, url).await?).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Write the workspace's context items to `.vibe-context/` under `dir`.
+/// Items that fail are still returned, without a path, so URLs can be
+/// mentioned anyway.
+pub async fn materialize_context(
+    pool: &SqlitePool,
+    file_service: &FileService,
+    workspace_id: Uuid,
+    dir: &Path,
+) -> Result<Vec<MaterializedContext>, WorkspaceContextError> {
+    let items = WorkspaceContextItem::find_by_workspace_id(pool, workspace_id).await?;
+    if items.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let context_dir = dir.join(VIBE_CONTEXT_DIR);
+    if let Err(e) = tokio::fs::create_dir_all(&context_dir).await {
+        tracing::warn!("Failed to create {}: {}", context_dir.display(), e);
+    }
+    let gitignore = context_dir.join(".gitignore");
+    if !gitignore.exists()
+        && let Err(e) = tokio::fs::write(&gitignore, "*\n").await
+    {
+        tracing::warn!("Failed to write {}: {}", gitignore.display(), e);
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(URL_FETCH_TIMEOUT)
+        .build()
+        .unwrap_or_default();
+    let mut materialized = Vec::with_capacity(items.len());
+    for item in &items {
+        let target = context_dir.join(&item.name);
+        let path = match write_item(item, &target, file_service, pool, &client).await {
+            Ok(()) => Some(format!("{VIBE_CONTEXT_DIR}/{}", item.name)),
+            Err(e) => {
+                tracing::warn!("Failed to write context item '{}': {}", item.name, e);
+                None
+            }
+        };
+        materialized.push(MaterializedContext {
+            path,
+            url: item.url.clone(),
+        });
+    }
+    Ok(materialized)
+}
+
+/// Append a list of the context items to `prompt`.
+pub fn render_context_prompt(prompt: &str, context: &[MaterializedContext]) -> String {
+    if context.is_empty() {
+        return prompt.to_string();
+    }
+    let mut rendered =
+        format!("{prompt}\n\nContext for this task has been provided in `{VIBE_CONTEXT_DIR}/`:\n");
+    for item in context {
+        let line = match (&item.path, &item.url) {
+            (Some(path), Some(url)) => format!("- `{path}` (fetched from {url})"),
+            (Some(path), None) => format!("- `{path}`"),
+            (None, Some(url)) => format!("- {url} (could not be fetched)"),
+            (None, None) => continue,
+        };
+        rendered.push_str(&line);
+        rendered.push('\n');
+    }
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_safe_names() {
+        assert_eq!(sanitize_name("../../etc/passwd"), "_.._etc_passwd");
+        assert_eq!(sanitize_name("  API spec.md "), "API_spec.md");
+        assert_eq!(sanitize_name("..."), "context");
+
+        let url = parse_url("https://docs.rs/serde/latest/serde/").unwrap();
+        assert_eq!(name_for_url(&url), "docs.rs_serde.md");
+        let url = parse_url("https://example.com").unwrap();
+        assert_eq!(name_for_url(&url), "example.com.md");
+        let url = parse_url("https://example.com/spec.txt").unwrap();
+        assert_eq!(name_for_url(&url), "example.com_spec.txt");
+        assert!(matches!(
+            parse_url("file:///etc/passwd"),
+            Err(WorkspaceContextError::InvalidUrl(_))
+        ));
+    }
+
+    #[test]
+    fn lists_context_in_prompt() {
+        let context = vec![
+            MaterializedContext {
+                path: Some(".vibe-context/spec.md".to_string()),
+                url: None,
+            },
+            MaterializedContext {
+                path: Some(".vibe-context/example.com.md".to_string()),
+                url: Some("https://example.com/".to_string()),
+            },
+            MaterializedContext {
+                path: None,
+                url: Some("https://down.example.com/".to_string()),
+            },
+        ];
+        assert_eq!(render_context_prompt("Fix it", &[]), "Fix it");
+        assert_eq!(
+            render_context_prompt("Fix it", &context),
+            "Fix it\n\nContext for this task has been provided in `.vibe-context/`:\n\
+             - `.vibe-context/spec.md`\n\
+             - `.vibe-context/example.com.md` (fetched from https://example.com/)\n\
+             - https://down.example.com/ (could not be fetched)\n"
+        );
+    }
+}
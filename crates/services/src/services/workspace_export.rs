@@ -0,0 +1,352 @@
+//! Export of a workspace as a portable archive.
+//!
+//! The archive is a gzipped tarball holding everything needed to review an
+//! agent run away from this machine: a git bundle of the workspace branch and
+//! the diff against its base for every repo, plus the raw logs and the
+//! normalized conversation of every execution process.
+//!
+//! ```text
+//! manifest.json
+//! repos/<repo>/branch.bundle
+//! repos/<repo>/changes.diff
+//! processes/<process_id>/logs.jsonl
+//! processes/<process_id>/conversation.json
+//! ```
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    session::Session,
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use flate2::{Compression, write::GzEncoder};
+use git::{GitCli, GitService, GitServiceError};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::{
+    container::ContainerService, execution_process::load_raw_log_messages,
+    session_transcript::normalized_entries,
+};
+
+/// Bumped when the archive layout changes incompatibly.
+const EXPORT_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+
+#[derive(Debug, Error)]
+pub enum WorkspaceExportError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    GitService(#[from] GitServiceError),
+}
+
+#[derive(Debug, Serialize)]
+struct ExportManifest {
+    format_version: u32,
+    app_version: String,
+    created_at: DateTime<Utc>,
+    workspace_id: Uuid,
+    workspace_name: Option<String>,
+    branch: String,
+    repos: Vec<ExportedRepo>,
+    processes: Vec<ExportedProcess>,
+}
+
+#[derive(Debug, Serialize)]
+struct ExportedRepo {
+    name: String,
+    target_branch: String,
+    /// None when the branch no longer exists in the repo, in which case the
+    /// bundle and diff are left out.
+    base_commit: Option<String>,
+    bundle: Option<String>,
+    diff: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ExportedProcess {
+    id: Uuid,
+    session_id: Uuid,
+    run_reason: ExecutionProcessRunReason,
+    status: ExecutionProcessStatus,
+    exit_code: Option<i64>,
+    started_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+    logs: Option<String>,
+    conversation: Option<String>,
+}
+
+/// An archive entry, built up before the archive is written.
+struct ExportEntry {
+    name: String,
+    data: Vec<u8>,
+}
+
+/// Changes of one workspace repo against the commit the branch started from.
+pub struct RepoChanges {
+    pub repo_name: String,
+    pub repo_path: PathBuf,
+    pub target_branch: String,
+    /// None when the branch no longer exists in the repo, in which case there
+    /// is no diff either.
+    pub base_commit: Option<String>,
+    pub diff: Option<String>,
+}
+
+/// Diff every repo of `workspace` against its base commit. The worktree is
+/// diffed so uncommitted changes are included; once it is gone the branch
+/// head is used instead.
+pub async fn workspace_changes(
+    pool: &SqlitePool,
+    git: &GitService,
+    workspace: &Workspace,
+) -> Result<Vec<RepoChanges>, WorkspaceExportError> {
+    let mut changes = Vec::new();
+    let workspace_repos =
+        WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
+    for repo_with_branch in workspace_repos {
+        let repo_name = repo_with_branch.repo.name.clone();
+        let repo_path = repo_with_branch.repo.path.clone();
+        let worktree_path = workspace
+            .container_ref
+            .as_ref()
+            .map(|container_ref| PathBuf::from(container_ref).join(&repo_name))
+            .filter(|path| path.exists() && !workspace.worktree_deleted);
+
+        let diffed = tokio::task::spawn_blocking({
+            let git = git.clone();
+            let repo_path = repo_path.clone();
+            let branch = workspace.branch.clone();
+            let target_branch = repo_with_branch.target_branch.clone();
+            move || -> Result<_, GitServiceError> {
+                let base_commit = match git.get_base_commit(&repo_path, &branch, &target_branch) {
+                    Ok(commit) => commit.to_string(),
+                    Err(e) => {
+                        tracing::warn!(
+                            "Skipping changes of {} in workspace {}: {}",
+                            repo_path.display(),
+                            branch,
+                            e
+                        );
+                        return Ok(None);
+                    }
+                };
+                let cli = GitCli::new();
+                let diff = match worktree_path {
+                    Some(worktree_path) => cli.diff_patch(&worktree_path, &base_commit)?,
+                    None => cli.git(
+                        &repo_path,
+                        ["diff", "--binary", base_commit.as_str(), branch.as_str()],
+                    )?,
+                };
+                Ok(Some((base_commit, diff)))
+            }
+        })
+        .await
+        .map_err(std::io::Error::other)??;
+
+        let (base_commit, diff) = diffed.unzip();
+        changes.push(RepoChanges {
+            repo_name,
+            repo_path,
+            target_branch: repo_with_branch.target_branch,
+            base_commit,
+            diff,
+        });
+    }
+    Ok(changes)
+}
+
+/// File name for the export of `workspace`.
+pub fn export_file_name(workspace: &Workspace) -> String {
+    format!("workspace-{}.tar.gz", workspace.id)
+}
+
+/// Build the export archive of `workspace` and return its bytes.
+pub async fn export_workspace<C>(
+    container: &C,
+    git: &GitService,
+    workspace: &Workspace,
+) -> Result<Vec<u8>, WorkspaceExportError>
+where
+    C: ContainerService + Sync + ?Sized,
+{
+    let pool = &container.db().pool;
+    let created_at = Utc::now();
+    let mut entries = Vec::new();
+
+    let mut repos = Vec::new();
+    for changes in workspace_changes(pool, git, workspace).await? {
+        let name = changes.repo_name;
+        let mut repo = ExportedRepo {
+            name: name.clone(),
+            target_branch: changes.target_branch,
+            base_commit: None,
+            bundle: None,
+            diff: None,
+        };
+        if let (Some(base_commit), Some(diff)) = (changes.base_commit, changes.diff) {
+            let bundle = tokio::task::spawn_blocking({
+                let repo_path = changes.repo_path;
+                let branch = workspace.branch.clone();
+                move || GitCli::new().bundle_create(&repo_path, &branch)
+            })
+            .await
+            .map_err(std::io::Error::other)?
+            .map_err(GitServiceError::from)?;
+
+            let bundle_entry = format!("repos/{name}/branch.bundle");
+            let diff_entry = format!("repos/{name}/changes.diff");
+            entries.push(ExportEntry {
+                name: bundle_entry.clone(),
+                data: bundle,
+            });
+            entries.push(ExportEntry {
+                name: diff_entry.clone(),
+                data: diff.into_bytes(),
+            });
+            repo.base_commit = Some(base_commit);
+            repo.bundle = Some(bundle_entry);
+            repo.diff = Some(diff_entry);
+        }
+        repos.push(repo);
+    }
+
+    let mut processes = Vec::new();
+    let mut sessions = Session::find_by_workspace_id(pool, workspace.id).await?;
+    sessions.sort_by_key(|session| session.created_at);
+    for session in sessions {
+        for process in ExecutionProcess::find_by_session_id(pool, session.id, false).await? {
+            let mut exported = ExportedProcess {
+                id: process.id,
+                session_id: process.session_id,
+                run_reason: process.run_reason.clone(),
+                status: process.status.clone(),
+                exit_code: process.exit_code,
+                started_at: process.started_at,
+                completed_at: process.completed_at,
+                logs: None,
+                conversation: None,
+            };
+
+            if let Some(messages) = load_raw_log_messages(pool, process.id).await {
+                let mut jsonl = Vec::new();
+                for msg in &messages {
+                    serde_json::to_writer(&mut jsonl, msg)?;
+                    jsonl.push(b'\n');
+                }
+                let entry = format!("processes/{}/logs.jsonl", process.id);
+                entries.push(ExportEntry {
+                    name: entry.clone(),
+                    data: jsonl,
+                });
+                exported.logs = Some(entry);
+            }
+
+            let conversation = normalized_entries(container, &process).await;
+            if !conversation.is_empty() {
+                let entry = format!("processes/{}/conversation.json", process.id);
+                entries.push(ExportEntry {
+                    name: entry.clone(),
+                    data: serde_json::to_vec_pretty(&conversation)?,
+                });
+                exported.conversation = Some(entry);
+            }
+
+            processes.push(exported);
+        }
+    }
+
+    let manifest = ExportManifest {
+        format_version: EXPORT_FORMAT_VERSION,
+        app_version: utils::version::APP_VERSION.to_string(),
+        created_at,
+        workspace_id: workspace.id,
+        workspace_name: workspace.name.clone(),
+        branch: workspace.branch.clone(),
+        repos,
+        processes,
+    };
+    entries.insert(
+        0,
+        ExportEntry {
+            name: MANIFEST_ENTRY.to_string(),
+            data: serde_json::to_vec_pretty(&manifest)?,
+        },
+    );
+
+    tokio::task::spawn_blocking(move || write_archive(&entries, created_at))
+        .await
+        .map_err(std::io::Error::other)?
+}
+
+fn write_archive(
+    entries: &[ExportEntry],
+    created_at: DateTime<Utc>,
+) -> Result<Vec<u8>, WorkspaceExportError> {
+    let encoder = GzEncoder::new(Vec::new(), Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    for entry in entries {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(entry.data.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(created_at.timestamp() as u64);
+        builder.append_data(&mut header, &entry.name, entry.data.as_slice())?;
+    }
+    Ok(builder.into_inner()?.finish()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use flate2::read::GzDecoder;
+
+    use super::*;
+
+    #[test]
+    fn archive_contains_entries_in_order() {
+        let entries = vec![
+            ExportEntry {
+                name: MANIFEST_ENTRY.to_string(),
+                data: b"{}".to_vec(),
+            },
+            ExportEntry {
+                name: "repos/app/changes.diff".to_string(),
+                data: b"diff --git a/x b/x\n".to_vec(),
+            },
+        ];
+
+        let bytes = write_archive(&entries, Utc::now()).unwrap();
+        let mut archive = tar::Archive::new(GzDecoder::new(bytes.as_slice()));
+        let mut found = Vec::new();
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let mut data = String::new();
+            entry.read_to_string(&mut data).unwrap();
+            found.push((entry.path().unwrap().display().to_string(), data));
+        }
+
+        assert_eq!(
+            found,
+            vec![
+                (MANIFEST_ENTRY.to_string(), "{}".to_string()),
+                (
+                    "repos/app/changes.diff".to_string(),
+                    "diff --git a/x b/x\n".to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,330 @@
+//! Reading and editing single files inside a workspace directory, for quick
+//! manual fixes to agent output.
+//!
+//! Paths are relative to the workspace directory. They are resolved with
+//! symlinks followed and must stay inside it; `.git` directories are off
+//! limits so hooks and config can't be planted through this API.
+
+use std::{
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use ts_rs::TS;
+
+/// Largest file that can be read or written.
+pub const MAX_WORKSPACE_FILE_BYTES: u64 = 2 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum WorkspaceFileError {
+    #[error("Invalid path: {0}")]
+    InvalidPath(String),
+    #[error("File not found: {0}")]
+    NotFound(String),
+    #[error("File too large: {0} bytes (max: {1} bytes)")]
+    TooLarge(u64, u64),
+    #[error("File is not UTF-8 text")]
+    NotText,
+    #[error("File has changed since it was read")]
+    Modified,
+    #[error("Text to replace was not found: {0:?}")]
+    EditNotFound(String),
+    #[error("Text to replace is not unique ({1} matches): {0:?}")]
+    EditAmbiguous(String, usize),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct WorkspaceFile {
+    pub path: String,
+    pub content: String,
+    /// Hex SHA-256 of the content; pass it back as `expected_sha256` to
+    /// refuse a write if the file changed in the meantime.
+    pub sha256: String,
+    #[ts(type = "number")]
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct WriteWorkspaceFile {
+    pub path: String,
+    pub content: String,
+    #[ts(optional)]
+    pub expected_sha256: Option<String>,
+}
+
+/// Replace `old_text`, which must occur exactly once, with `new_text`.
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct FileEdit {
+    pub old_text: String,
+    pub new_text: String,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct PatchWorkspaceFile {
+    pub path: String,
+    /// Applied in order, each to the result of the previous one.
+    pub edits: Vec<FileEdit>,
+    #[ts(optional)]
+    pub expected_sha256: Option<String>,
+}
+
+fn sha256_hex(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+/// Resolve `path` inside `root`. The file itself need not exist, but its
+/// parent directory must.
+fn resolve_path(root: &Path, path: &str) -> Result<PathBuf, WorkspaceFileError> {
+    let invalid = || WorkspaceFileError::InvalidPath(path.to_string());
+    let relative = Path::new(path);
+    let components: Vec<Component> = relative.components().collect();
+    let file_name = match components.last() {
+        Some(Component::Normal(name)) => *name,
+        _ => return Err(invalid()),
+    };
+    if components.iter().any(|component| match component {
+        Component::Normal(name) => *name == ".git",
+        Component::CurDir => false,
+        _ => true,
+    }) {
+        return Err(invalid());
+    }
+
+    let root = std::fs::canonicalize(root)?;
+    let parent = relative.parent().unwrap_or(Path::new(""));
+    let parent = std::fs::canonicalize(root.join(parent)).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => WorkspaceFileError::NotFound(path.to_string()),
+        _ => e.into(),
+    })?;
+    if !parent.starts_with(&root) || !parent.is_dir() {
+        return Err(invalid());
+    }
+
+    let resolved = parent.join(file_name);
+    match std::fs::symlink_metadata(&resolved) {
+        // Nothing there yet; it will be created as a regular file.
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(resolved),
+        Err(e) => return Err(e.into()),
+        Ok(_) => {}
+    }
+    // Something exists, maybe a symlink, so check where it leads. A dangling
+    // symlink fails here rather than being written through.
+    match std::fs::canonicalize(&resolved) {
+        Ok(target) => {
+            let inside = target.starts_with(&root)
+                && target
+                    .strip_prefix(&root)
+                    .is_ok_and(|rest| !rest.components().any(|c| c.as_os_str() == ".git"));
+            if !inside || target.is_dir() {
+                return Err(invalid());
+            }
+            Ok(target)
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Err(invalid()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn read_resolved(
+    resolved: &Path,
+    path: &str,
+) -> Result<Option<WorkspaceFile>, WorkspaceFileError> {
+    let metadata = match tokio::fs::metadata(resolved).await {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    if metadata.len() > MAX_WORKSPACE_FILE_BYTES {
+        return Err(WorkspaceFileError::TooLarge(
+            metadata.len(),
+            MAX_WORKSPACE_FILE_BYTES,
+        ));
+    }
+    let bytes = tokio::fs::read(resolved).await?;
+    let content = String::from_utf8(bytes).map_err(|_| WorkspaceFileError::NotText)?;
+    Ok(Some(WorkspaceFile {
+        path: path.to_string(),
+        sha256: sha256_hex(&content),
+        size_bytes: content.len() as u64,
+        content,
+    }))
+}
+
+async fn write_resolved(
+    resolved: &Path,
+    path: &str,
+    content: String,
+) -> Result<WorkspaceFile, WorkspaceFileError> {
+    if content.len() as u64 > MAX_WORKSPACE_FILE_BYTES {
+        return Err(WorkspaceFileError::TooLarge(
+            content.len() as u64,
+            MAX_WORKSPACE_FILE_BYTES,
+        ));
+    }
+    tokio::fs::write(resolved, &content).await?;
+    Ok(WorkspaceFile {
+        path: path.to_string(),
+        sha256: sha256_hex(&content),
+        size_bytes: content.len() as u64,
+        content,
+    })
+}
+
+fn check_expected(
+    current: Option<&WorkspaceFile>,
+    expected_sha256: Option<&str>,
+) -> Result<(), WorkspaceFileError> {
+    match expected_sha256 {
+        Some(expected)
+            if current.is_none_or(|file| !file.sha256.eq_ignore_ascii_case(expected)) =>
+        {
+            Err(WorkspaceFileError::Modified)
+        }
+        _ => Ok(()),
+    }
+}
+
+pub async fn read_file(root: &Path, path: &str) -> Result<WorkspaceFile, WorkspaceFileError> {
+    let resolved = resolve_path(root, path)?;
+    read_resolved(&resolved, path)
+        .await?
+        .ok_or_else(|| WorkspaceFileError::NotFound(path.to_string()))
+}
+
+/// Create or overwrite a file.
+pub async fn write_file(
+    root: &Path,
+    request: WriteWorkspaceFile,
+) -> Result<WorkspaceFile, WorkspaceFileError> {
+    let resolved = resolve_path(root, &request.path)?;
+    if request.expected_sha256.is_some() {
+        let current = read_resolved(&resolved, &request.path).await?;
+        check_expected(current.as_ref(), request.expected_sha256.as_deref())?;
+    }
+    write_resolved(&resolved, &request.path, request.content).await
+}
+
+/// Apply search-and-replace edits to an existing file.
+pub async fn patch_file(
+    root: &Path,
+    request: PatchWorkspaceFile,
+) -> Result<WorkspaceFile, WorkspaceFileError> {
+    let resolved = resolve_path(root, &request.path)?;
+    let current = read_resolved(&resolved, &request.path)
+        .await?
+        .ok_or_else(|| WorkspaceFileError::NotFound(request.path.clone()))?;
+    check_expected(Some(&current), request.expected_sha256.as_deref())?;
+    let content = apply_edits(&current.content, &request.edits)?;
+    write_resolved(&resolved, &request.path, content).await
+}
+
+pub fn apply_edits(content: &str, edits: &[FileEdit]) -> Result<String, WorkspaceFileError> {
+    let mut content = content.to_string();
+    for edit in edits {
+        if edit.old_text.is_empty() {
+            return Err(WorkspaceFileError::EditNotFound(String::new()));
+        }
+        match content.matches(&edit.old_text).count() {
+            0 => return Err(WorkspaceFileError::EditNotFound(edit.old_text.clone())),
+            1 => content = content.replacen(&edit.old_text, &edit.new_text, 1),
+            n => return Err(WorkspaceFileError::EditAmbiguous(edit.old_text.clone(), n)),
+        }
+    }
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(old_text: &str, new_text: &str) -> FileEdit {
+        FileEdit {
+            old_text: old_text.to_string(),
+            new_text: new_text.to_string(),
+        }
+    }
+
+    #[test]
+    fn applies_edits_in_order() {
+        let edits = [edit("a = 1", "a = 2"), edit("a = 2\nb", "a = 3\nb")];
+        assert_eq!(
+            apply_edits("a = 1\nb = 1\n", &edits).unwrap(),
+            "a = 3\nb = 1\n"
+        );
+        assert!(matches!(
+            apply_edits("x x", &[edit("x", "y")]),
+            Err(WorkspaceFileError::EditAmbiguous(_, 2))
+        ));
+        assert!(matches!(
+            apply_edits("x", &[edit("z", "y")]),
+            Err(WorkspaceFileError::EditNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn keeps_paths_inside_the_workspace() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(root.path().join("repo/src")).unwrap();
+        std::fs::create_dir_all(root.path().join("repo/.git")).unwrap();
+        std::fs::write(root.path().join("repo/src/main.rs"), "").unwrap();
+
+        assert!(resolve_path(root.path(), "repo/src/main.rs").is_ok());
+        assert!(resolve_path(root.path(), "./repo/src/new.rs").is_ok());
+        for path in [
+            "",
+            "repo/src",
+            "../escape.txt",
+            "repo/../../escape.txt",
+            "repo/.git/config",
+            "/etc/passwd",
+        ] {
+            assert!(
+                matches!(
+                    resolve_path(root.path(), path),
+                    Err(WorkspaceFileError::InvalidPath(_))
+                ),
+                "{path} should be rejected"
+            );
+        }
+        assert!(matches!(
+            resolve_path(root.path(), "repo/missing/new.rs"),
+            Err(WorkspaceFileError::NotFound(_))
+        ));
+
+        #[cfg(unix)]
+        {
+            let outside = tempfile::tempdir().unwrap();
+            std::os::unix::fs::symlink(outside.path(), root.path().join("repo/link")).unwrap();
+            assert!(matches!(
+                resolve_path(root.path(), "repo/link/secret.txt"),
+                Err(WorkspaceFileError::InvalidPath(_))
+            ));
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn does_not_write_through_dangling_symlinks() {
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let target = outside.path().join("bashrc");
+        std::os::unix::fs::symlink(&target, root.path().join("evil")).unwrap();
+
+        let written = write_file(
+            root.path(),
+            WriteWorkspaceFile {
+                path: "evil".to_string(),
+                content: "echo pwned".to_string(),
+                expected_sha256: None,
+            },
+        )
+        .await;
+        assert!(matches!(written, Err(WorkspaceFileError::InvalidPath(_))));
+        assert!(!target.exists());
+    }
+}
@@ -0,0 +1,267 @@
+//! Undo for destructive workspace actions.
+//!
+//! Deleting a workspace and resetting its worktrees record a tombstone in the
+//! undo log, and keep the commits they moved away from alive with refs under
+//! `refs/vibe-kanban/undo/`, for [`UNDO_RETENTION_DAYS`]. Undoing the most
+//! recent action of a workspace brings back what git and the workspace record
+//! held: a deleted workspace gets its row, repos and branches back (its
+//! worktree is recreated on next use, its sessions are gone for good), and a
+//! reset worktree returns to its previous head with the uncommitted changes
+//! the reset snapshotted.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process::ExecutionProcess,
+    repo::Repo,
+    workspace::Workspace,
+    workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
+    workspace_undo::{UndoAction, UndoPayload, UndoRepoBranch, UndoRepoReset, WorkspaceUndoEntry},
+};
+use git::{GitService, GitServiceError, WorktreeResetOutcome};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::container::{ContainerError, ContainerService};
+
+/// How long a destructive action can be undone.
+pub const UNDO_RETENTION_DAYS: u32 = 7;
+
+#[derive(Debug, Error)]
+pub enum WorkspaceUndoError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error("Nothing to undo for this workspace")]
+    NothingToUndo,
+    #[error("Cannot undo while processes are running. Stop all processes first.")]
+    ProcessesRunning,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct UndoneAction {
+    pub workspace_id: Uuid,
+    pub action: UndoAction,
+    /// When the undone action was performed.
+    #[ts(type = "Date")]
+    pub performed_at: DateTime<Utc>,
+}
+
+/// Record the deletion of `workspace`, before its record is deleted.
+pub async fn record_workspace_deletion(
+    pool: &SqlitePool,
+    git: &GitService,
+    workspace: &Workspace,
+) -> Result<WorkspaceUndoEntry, WorkspaceUndoError> {
+    let id = Uuid::new_v4();
+    let repos = WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id)
+        .await?
+        .into_iter()
+        .map(|r| {
+            let head_commit = git.get_branch_oid(&r.repo.path, &workspace.branch).ok();
+            if let Some(commit) = &head_commit {
+                git.keep_undo_ref(&r.repo.path, &id.to_string(), commit)?;
+            }
+            Ok(UndoRepoBranch {
+                repo_id: r.repo.id,
+                repo_path: r.repo.path.to_string_lossy().to_string(),
+                target_branch: r.target_branch,
+                head_commit,
+            })
+        })
+        .collect::<Result<Vec<_>, GitServiceError>>()?;
+
+    let payload = UndoPayload::WorkspaceDeleted {
+        workspace: workspace.clone(),
+        repos,
+    };
+    record(pool, git, id, workspace.id, &payload).await
+}
+
+/// The undo record of a reset of `repo`'s worktree, if the reset was applied.
+pub fn applied_reset(repo: &Repo, outcome: WorktreeResetOutcome) -> Option<UndoRepoReset> {
+    if !outcome.applied {
+        return None;
+    }
+    Some(UndoRepoReset {
+        repo_id: repo.id,
+        repo_path: repo.path.to_string_lossy().to_string(),
+        previous_head: outcome.previous_head?,
+        snapshot_id: outcome.snapshot.map(|snapshot| snapshot.id),
+    })
+}
+
+/// Record worktree resets of a workspace. Returns `None` if there were none.
+pub async fn record_git_reset(
+    pool: &SqlitePool,
+    git: &GitService,
+    workspace_id: Uuid,
+    repos: Vec<UndoRepoReset>,
+) -> Result<Option<WorkspaceUndoEntry>, WorkspaceUndoError> {
+    if repos.is_empty() {
+        return Ok(None);
+    }
+    let id = Uuid::new_v4();
+    for repo in &repos {
+        git.keep_undo_ref(
+            Path::new(&repo.repo_path),
+            &id.to_string(),
+            &repo.previous_head,
+        )?;
+    }
+    let payload = UndoPayload::GitReset { repos };
+    record(pool, git, id, workspace_id, &payload)
+        .await
+        .map(Some)
+}
+
+async fn record(
+    pool: &SqlitePool,
+    git: &GitService,
+    id: Uuid,
+    workspace_id: Uuid,
+    payload: &UndoPayload,
+) -> Result<WorkspaceUndoEntry, WorkspaceUndoError> {
+    prune_expired(pool, git).await?;
+    Ok(WorkspaceUndoEntry::create(pool, id, workspace_id, payload, UNDO_RETENTION_DAYS).await?)
+}
+
+/// Forget expired entries and drop the refs keeping their commits alive.
+pub async fn prune_expired(pool: &SqlitePool, git: &GitService) -> Result<(), sqlx::Error> {
+    for entry in WorkspaceUndoEntry::delete_expired(pool).await? {
+        drop_refs(git, &entry);
+    }
+    Ok(())
+}
+
+fn drop_refs(git: &GitService, entry: &WorkspaceUndoEntry) {
+    for repo_path in entry.payload.repo_paths() {
+        if let Err(e) = git.drop_undo_ref(Path::new(repo_path), &entry.id.to_string()) {
+            tracing::warn!(
+                "Failed to drop undo ref {} in {}: {}",
+                entry.id,
+                repo_path,
+                e
+            );
+        }
+    }
+}
+
+/// Undo the most recent destructive action of a workspace.
+pub async fn undo_latest<C>(
+    container: &C,
+    workspace_id: Uuid,
+) -> Result<UndoneAction, WorkspaceUndoError>
+where
+    C: ContainerService + Sync + ?Sized,
+{
+    let pool = &container.db().pool;
+    let entry = WorkspaceUndoEntry::find_latest_undoable(pool, workspace_id)
+        .await?
+        .ok_or(WorkspaceUndoError::NothingToUndo)?;
+
+    match &entry.payload.0 {
+        UndoPayload::WorkspaceDeleted { workspace, repos } => {
+            restore_workspace(pool, container.git(), workspace, repos).await?
+        }
+        UndoPayload::GitReset { repos } => undo_git_reset(container, workspace_id, repos).await?,
+    }
+
+    WorkspaceUndoEntry::mark_undone(pool, entry.id).await?;
+    drop_refs(container.git(), &entry);
+    Ok(UndoneAction {
+        workspace_id,
+        action: entry.action,
+        performed_at: entry.created_at,
+    })
+}
+
+async fn restore_workspace(
+    pool: &SqlitePool,
+    git: &GitService,
+    workspace: &Workspace,
+    repos: &[UndoRepoBranch],
+) -> Result<(), WorkspaceUndoError> {
+    let mut workspace_repos = Vec::with_capacity(repos.len());
+    for repo in repos {
+        // The repo may have been removed since
+        if Repo::find_by_id(pool, repo.repo_id).await?.is_none() {
+            tracing::warn!(
+                "Not restoring repo {} of workspace {}: it no longer exists",
+                repo.repo_id,
+                workspace.id
+            );
+            continue;
+        }
+        let repo_path = PathBuf::from(&repo.repo_path);
+        if let Some(commit) = &repo.head_commit
+            && !git.check_branch_exists(&repo_path, &workspace.branch)?
+        {
+            git.create_branch_at_commit(&repo_path, &workspace.branch, commit)?;
+        }
+        workspace_repos.push(CreateWorkspaceRepo {
+            repo_id: repo.repo_id,
+            target_branch: repo.target_branch.clone(),
+        });
+    }
+
+    Workspace::restore(pool, workspace).await?;
+    WorkspaceRepo::create_many(pool, workspace.id, &workspace_repos).await?;
+    Ok(())
+}
+
+async fn undo_git_reset<C>(
+    container: &C,
+    workspace_id: Uuid,
+    resets: &[UndoRepoReset],
+) -> Result<(), WorkspaceUndoError>
+where
+    C: ContainerService + Sync + ?Sized,
+{
+    let pool = &container.db().pool;
+    let workspace = Workspace::find_by_id(pool, workspace_id)
+        .await?
+        .ok_or_else(|| ContainerError::Other(anyhow!("Workspace not found")))?;
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(WorkspaceUndoError::ProcessesRunning);
+    }
+
+    let workspace_dir = PathBuf::from(container.ensure_container_exists(&workspace).await?);
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    for reset in resets {
+        let Some(repo) = repos.iter().find(|repo| repo.id == reset.repo_id) else {
+            continue;
+        };
+        let worktree_path = workspace_dir.join(&repo.name);
+        // Changes made since the reset are snapshotted in turn, so undoing
+        // loses nothing either.
+        container
+            .git()
+            .reset_worktree_to_commit(&worktree_path, &reset.previous_head, true)?;
+        if let Some(snapshot_id) = &reset.snapshot_id {
+            match container
+                .git()
+                .restore_snapshot(&worktree_path, &workspace.branch, snapshot_id)
+            {
+                Ok(_) => {}
+                Err(GitServiceError::SnapshotNotFound(_)) => tracing::warn!(
+                    "Snapshot {} of {} was pruned; restored the commit only",
+                    snapshot_id,
+                    repo.name
+                ),
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,156 @@
+//! Writer for asciicast v2 recordings (<https://docs.asciinema.org/manual/asciicast/v2/>).
+//!
+//! A recording is a JSON header line followed by one `[time, code, data]`
+//! line per event, where `time` is seconds since the recording started.
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    time::Instant,
+};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AsciicastHeader {
+    pub version: u8,
+    pub width: u16,
+    pub height: u16,
+    /// Unix time the recording started at.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+}
+
+impl AsciicastHeader {
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            version: 2,
+            width,
+            height,
+            timestamp: None,
+            title: None,
+            env: HashMap::new(),
+        }
+    }
+
+    /// Read the header of a recording file.
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let mut line = String::new();
+        BufReader::new(File::open(path)?).read_line(&mut line)?;
+        serde_json::from_str(&line).map_err(io::Error::other)
+    }
+}
+
+pub struct AsciicastWriter<W: Write> {
+    out: W,
+    started: Instant,
+    /// Trailing bytes of an output chunk that end mid UTF-8 sequence.
+    pending: Vec<u8>,
+}
+
+impl AsciicastWriter<BufWriter<File>> {
+    /// Create (or truncate) a recording file at `path`.
+    pub fn create(path: &Path, header: &AsciicastHeader) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        Self::new(BufWriter::new(File::create(path)?), header)
+    }
+}
+
+impl<W: Write> AsciicastWriter<W> {
+    pub fn new(mut out: W, header: &AsciicastHeader) -> io::Result<Self> {
+        serde_json::to_writer(&mut out, header).map_err(io::Error::other)?;
+        out.write_all(b"\n")?;
+        out.flush()?;
+        Ok(Self {
+            out,
+            started: Instant::now(),
+            pending: Vec::new(),
+        })
+    }
+
+    /// Record terminal output. Multi-byte characters split across chunks are
+    /// held back until the rest arrives.
+    pub fn output(&mut self, data: &[u8]) -> io::Result<()> {
+        self.pending.extend_from_slice(data);
+        let complete = match std::str::from_utf8(&self.pending) {
+            Ok(_) => self.pending.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            // Not UTF-8 at all; write it out lossily rather than hold it forever.
+            Err(_) => self.pending.len(),
+        };
+        if complete == 0 {
+            return Ok(());
+        }
+        let chunk: Vec<u8> = self.pending.drain(..complete).collect();
+        self.event("o", &String::from_utf8_lossy(&chunk))
+    }
+
+    pub fn resize(&mut self, cols: u16, rows: u16) -> io::Result<()> {
+        self.event("r", &format!("{cols}x{rows}"))
+    }
+
+    fn event(&mut self, code: &str, data: &str) -> io::Result<()> {
+        let elapsed = (self.started.elapsed().as_secs_f64() * 1e6).round() / 1e6;
+        serde_json::to_writer(&mut self.out, &(elapsed, code, data)).map_err(io::Error::other)?;
+        self.out.write_all(b"\n")?;
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(bytes: &[u8]) -> Vec<(f64, String, String)> {
+        let text = String::from_utf8(bytes.to_vec()).unwrap();
+        text.lines()
+            .skip(1)
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn writes_header_and_events() {
+        let mut header = AsciicastHeader::new(80, 24);
+        header.title = Some("Terminal 1".to_string());
+        let mut out = Vec::new();
+        let mut writer = AsciicastWriter::new(&mut out, &header).unwrap();
+        writer.output(b"$ ls\r\n").unwrap();
+        writer.resize(120, 40).unwrap();
+        drop(writer);
+
+        let first_line = String::from_utf8(out.clone()).unwrap();
+        let first_line = first_line.lines().next().unwrap();
+        assert_eq!(
+            serde_json::from_str::<AsciicastHeader>(first_line).unwrap(),
+            header
+        );
+        let events = events(&out);
+        assert_eq!(events[0].1, "o");
+        assert_eq!(events[0].2, "$ ls\r\n");
+        assert_eq!(events[1].1, "r");
+        assert_eq!(events[1].2, "120x40");
+    }
+
+    #[test]
+    fn holds_back_split_characters() {
+        let mut out = Vec::new();
+        let mut writer = AsciicastWriter::new(&mut out, &AsciicastHeader::new(80, 24)).unwrap();
+        let bytes = "é!".as_bytes();
+        writer.output(&bytes[..1]).unwrap();
+        writer.output(&bytes[1..]).unwrap();
+        drop(writer);
+
+        let events = events(&out);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].2, "é!");
+    }
+}
@@ -48,6 +48,14 @@ pub fn server_signing_key_path() -> std::path::PathBuf {
     asset_dir().join("server_ed25519_signing_key")
 }
 
+pub fn secrets_key_path() -> std::path::PathBuf {
+    asset_dir().join("secrets.key")
+}
+
+pub fn share_links_key_path() -> std::path::PathBuf {
+    asset_dir().join("share_links.key")
+}
+
 pub fn relay_host_credentials_path() -> std::path::PathBuf {
     asset_dir().join("relay_host_credentials.json")
 }
@@ -18,6 +18,10 @@ pub struct Diff {
     pub new_content: Option<String>,
     /// True when file contents are intentionally omitted (e.g., too large)
     pub content_omitted: bool,
+    /// Why the contents were omitted, when they were
+    pub omitted_reason: Option<DiffOmittedReason>,
+    /// True when either side is a binary file
+    pub is_binary: bool,
     /// Optional precomputed stats for omitted content
     pub additions: Option<usize>,
     pub deletions: Option<usize>,
@@ -35,6 +39,19 @@ pub enum DiffChangeKind {
     PermissionChange,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffOmittedReason {
+    /// A side is larger than the maximum file size; contents can't be loaded.
+    TooLarge,
+    /// A side is binary; contents can't be loaded.
+    Binary,
+    /// The stream was opened stats-only; contents can be loaded per file.
+    StatsOnly,
+    /// The stream's content budget ran out; contents can be loaded per file.
+    StreamLimit,
+}
+
 // ==============================
 // Unified diff utility functions
 // ==============================
@@ -1,11 +1,12 @@
 use std::path::{Path, PathBuf};
 
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader, BufWriter};
 use uuid::Uuid;
 
 use crate::{assets::asset_dir, log_msg::LogMsg};
 
 pub const EXECUTION_LOGS_DIRNAME: &str = "sessions";
+pub const TERMINAL_RECORDINGS_DIRNAME: &str = "terminal_recordings";
 
 pub fn process_logs_session_dir(session_id: Uuid) -> PathBuf {
     resolve_process_logs_session_dir(&asset_dir(), session_id)
@@ -21,9 +22,36 @@ pub fn process_log_file_path_in_root(root: &Path, session_id: Uuid, process_id:
         .join(format!("{}.jsonl", process_id))
 }
 
+/// Normalized conversation patches of a finished coding agent process, cached
+/// next to its raw log.
+pub fn normalized_log_file_path(session_id: Uuid, process_id: Uuid) -> PathBuf {
+    resolve_process_logs_session_dir(&asset_dir(), session_id)
+        .join("processes")
+        .join(format!("{}.normalized.jsonl", process_id))
+}
+
+/// Directory holding the asciicast recordings of a workspace's terminals.
+pub fn terminal_recordings_dir(workspace_id: Uuid) -> PathBuf {
+    asset_dir()
+        .join(TERMINAL_RECORDINGS_DIRNAME)
+        .join(uuid_prefix2(workspace_id))
+        .join(workspace_id.to_string())
+}
+
+pub fn terminal_recording_path(workspace_id: Uuid, terminal_id: Uuid) -> PathBuf {
+    terminal_recordings_dir(workspace_id).join(format!("{terminal_id}.cast"))
+}
+
+/// Lines buffered before the writer writes them out on its own, regardless of
+/// when the caller flushes.
+const LOG_WRITE_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Buffered appender for an execution log file. Appended lines only reach the
+/// file on [`flush`](Self::flush) or once the buffer fills up, so callers must
+/// flush before dropping the writer.
 pub struct ExecutionLogWriter {
     path: PathBuf,
-    file: tokio::fs::File,
+    file: BufWriter<tokio::fs::File>,
 }
 
 impl ExecutionLogWriter {
@@ -36,7 +64,10 @@ impl ExecutionLogWriter {
             .append(true)
             .open(&path)
             .await?;
-        Ok(Self { path, file })
+        Ok(Self {
+            path,
+            file: BufWriter::with_capacity(LOG_WRITE_BUFFER_SIZE, file),
+        })
     }
 
     pub async fn new_for_execution(session_id: Uuid, execution_id: Uuid) -> std::io::Result<Self> {
@@ -50,12 +81,142 @@ impl ExecutionLogWriter {
     pub async fn append_jsonl_line(&mut self, jsonl_line: &str) -> std::io::Result<()> {
         self.file.write_all(jsonl_line.as_bytes()).await
     }
+
+    /// Write out buffered lines.
+    pub async fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush().await
+    }
+
+    /// Write out buffered lines and wait until they are on disk.
+    pub async fn sync(&mut self) -> std::io::Result<()> {
+        self.file.flush().await?;
+        self.file.get_ref().sync_data().await
+    }
 }
 
 pub async fn read_execution_log_file(path: &Path) -> std::io::Result<String> {
     tokio::fs::read_to_string(path).await
 }
 
+/// A complete JSONL line read from an execution log file, with the byte offset
+/// at which it starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionLogLine {
+    pub offset: u64,
+    pub line: String,
+}
+
+/// A contiguous run of complete lines from an execution log file.
+///
+/// `start_offset..end_offset` is the byte range covered by `lines`; passing
+/// `end_offset` to [`read_execution_log_lines_after`] or `start_offset` to
+/// [`read_execution_log_lines_before`] fetches the adjacent page.
+#[derive(Debug, Clone)]
+pub struct ExecutionLogChunk {
+    pub lines: Vec<ExecutionLogLine>,
+    pub start_offset: u64,
+    pub end_offset: u64,
+    /// Size of the file when it was read. The file may still be growing.
+    pub file_len: u64,
+}
+
+/// Read up to `max_lines` complete lines starting at byte `offset`, stopping
+/// early once `max_bytes` have been consumed. `offset` must be a line boundary
+/// (0 or an offset returned by a previous read). A trailing line that is still
+/// being written is never returned.
+pub async fn read_execution_log_lines_after(
+    path: &Path,
+    offset: u64,
+    max_lines: usize,
+    max_bytes: u64,
+) -> std::io::Result<ExecutionLogChunk> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let file_len = file.metadata().await?.len();
+    let start_offset = offset.min(file_len);
+    file.seek(std::io::SeekFrom::Start(start_offset)).await?;
+
+    let mut reader = BufReader::new(file);
+    let mut lines = Vec::new();
+    let mut end_offset = start_offset;
+    let mut buf = String::new();
+    while lines.len() < max_lines && end_offset - start_offset < max_bytes {
+        buf.clear();
+        let read = reader.read_line(&mut buf).await?;
+        if read == 0 || !buf.ends_with('\n') {
+            break;
+        }
+        lines.push(ExecutionLogLine {
+            offset: end_offset,
+            line: buf.trim_end_matches(['\r', '\n']).to_string(),
+        });
+        end_offset += read as u64;
+    }
+
+    Ok(ExecutionLogChunk {
+        lines,
+        start_offset,
+        end_offset,
+        file_len,
+    })
+}
+
+/// Read up to `max_lines` complete lines ending at byte `offset` (exclusive),
+/// looking back at most `max_bytes`. `offset` must be a line boundary; pass
+/// the file length (or `u64::MAX`) to tail the log.
+pub async fn read_execution_log_lines_before(
+    path: &Path,
+    offset: u64,
+    max_lines: usize,
+    max_bytes: u64,
+) -> std::io::Result<ExecutionLogChunk> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let file_len = file.metadata().await?.len();
+    let end = offset.min(file_len);
+    // Read one extra byte before the window so a line starting exactly at the
+    // window boundary can be told apart from the tail of a longer line.
+    let window_start = end.saturating_sub(max_bytes);
+    let read_start = window_start.saturating_sub(1);
+
+    file.seek(std::io::SeekFrom::Start(read_start)).await?;
+    let mut buf = Vec::with_capacity((end - read_start) as usize);
+    file.take(end - read_start).read_to_end(&mut buf).await?;
+
+    // Unless reading from the beginning of the file, skip up to the first
+    // newline: anything before it belongs to a line that starts outside the
+    // window and is returned by the next page instead.
+    let mut pos = 0usize;
+    if window_start > 0 {
+        pos = buf
+            .iter()
+            .position(|b| *b == b'\n')
+            .map(|newline| newline + 1)
+            .unwrap_or(buf.len());
+    }
+
+    let mut lines = Vec::new();
+    while let Some(len) = buf[pos..].iter().position(|b| *b == b'\n') {
+        let line = String::from_utf8_lossy(&buf[pos..pos + len]);
+        lines.push(ExecutionLogLine {
+            offset: read_start + pos as u64,
+            line: line.trim_end_matches('\r').to_string(),
+        });
+        pos += len + 1;
+    }
+    let end_offset = read_start + pos as u64;
+
+    if lines.len() > max_lines {
+        lines.drain(..lines.len() - max_lines);
+    }
+    let start_offset = lines.first().map(|l| l.offset).unwrap_or(end_offset);
+
+    Ok(ExecutionLogChunk {
+        lines,
+        start_offset,
+        end_offset,
+        file_len,
+    })
+}
+
 pub fn parse_log_jsonl_lossy(execution_id: Uuid, jsonl: &str) -> Vec<LogMsg> {
     let mut messages = Vec::new();
     let mut bad_lines = 0usize;
@@ -101,3 +262,97 @@ fn resolve_process_logs_session_dir(root: &Path, session_id: Uuid) -> PathBuf {
         .join(uuid_prefix2(session_id))
         .join(session_id.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn write_temp_log(contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("vk-log-chunk-{}.jsonl", Uuid::new_v4()));
+        tokio::fs::write(&path, contents).await.unwrap();
+        path
+    }
+
+    fn texts(chunk: &ExecutionLogChunk) -> Vec<&str> {
+        chunk.lines.iter().map(|l| l.line.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn reads_forward_pages_and_skips_partial_tail() {
+        let path = write_temp_log("a\nbb\nccc\npartial").await;
+
+        let first = read_execution_log_lines_after(&path, 0, 2, u64::MAX)
+            .await
+            .unwrap();
+        assert_eq!(texts(&first), ["a", "bb"]);
+        assert_eq!(first.lines[1].offset, 2);
+        assert_eq!(first.end_offset, 5);
+
+        let second = read_execution_log_lines_after(&path, first.end_offset, 10, u64::MAX)
+            .await
+            .unwrap();
+        assert_eq!(texts(&second), ["ccc"]);
+        assert_eq!(second.end_offset, 9);
+        assert_eq!(second.file_len, 16);
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reads_backward_pages_from_tail() {
+        let path = write_temp_log("a\nbb\nccc\ndddd\npartial").await;
+
+        let last = read_execution_log_lines_before(&path, u64::MAX, 2, u64::MAX)
+            .await
+            .unwrap();
+        assert_eq!(texts(&last), ["ccc", "dddd"]);
+        assert_eq!(last.start_offset, 5);
+        assert_eq!(last.end_offset, 14);
+
+        // A window that starts exactly on a line boundary keeps that line; one
+        // that cuts into a line drops the fragment.
+        let prev = read_execution_log_lines_before(&path, last.start_offset, 10, 3)
+            .await
+            .unwrap();
+        assert_eq!(texts(&prev), ["bb"]);
+        assert_eq!(prev.start_offset, 2);
+
+        let prev = read_execution_log_lines_before(&path, last.start_offset, 10, 2)
+            .await
+            .unwrap();
+        assert!(prev.lines.is_empty());
+
+        let prev = read_execution_log_lines_before(&path, last.start_offset, 10, u64::MAX)
+            .await
+            .unwrap();
+        assert_eq!(texts(&prev), ["a", "bb"]);
+        assert_eq!(prev.start_offset, 0);
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn writer_buffers_lines_until_flushed() {
+        let path = write_temp_log("a\n").await;
+
+        let mut writer = ExecutionLogWriter::new(path.clone()).await.unwrap();
+        writer.append_jsonl_line("bb\n").await.unwrap();
+        writer.append_jsonl_line("ccc\n").await.unwrap();
+        assert_eq!(tokio::fs::read_to_string(&path).await.unwrap(), "a\n");
+
+        writer.flush().await.unwrap();
+        assert_eq!(
+            tokio::fs::read_to_string(&path).await.unwrap(),
+            "a\nbb\nccc\n"
+        );
+
+        writer.append_jsonl_line("dddd\n").await.unwrap();
+        writer.sync().await.unwrap();
+        assert_eq!(
+            tokio::fs::read_to_string(&path).await.unwrap(),
+            "a\nbb\nccc\ndddd\n"
+        );
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+}
@@ -0,0 +1,94 @@
+//! Sparse responses for list endpoints.
+//!
+//! Clients pass `?fields=id,title,status_id` to get only the fields they
+//! render. Nested fields are named with dots (`executor_action.typ`). The `id`
+//! field is always kept so items can still be told apart, and names that
+//! don't match a field are ignored.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+/// The fields requested by a client, as a tree of field names.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldSelection {
+    fields: BTreeMap<String, FieldSelection>,
+}
+
+impl FieldSelection {
+    /// Parse a comma-separated `fields` parameter. Returns `None` when no
+    /// fields are named, meaning the full items should be returned.
+    pub fn parse(fields: Option<&str>) -> Option<Self> {
+        let mut selection = Self::default();
+        for path in fields?.split(',').map(str::trim) {
+            if path.is_empty() {
+                continue;
+            }
+            let mut node = &mut selection;
+            for name in path.split('.') {
+                node = node.fields.entry(name.to_string()).or_default();
+            }
+        }
+        if selection.fields.is_empty() {
+            return None;
+        }
+        selection.fields.entry("id".to_string()).or_default();
+        Some(selection)
+    }
+
+    /// Drop unselected fields from `value`, or from each item if it is an
+    /// array. A field selected without sub-fields is kept whole.
+    pub fn retain(&self, value: &mut Value) {
+        match value {
+            Value::Array(items) => items.iter_mut().for_each(|item| self.retain(item)),
+            Value::Object(object) => {
+                object.retain(|name, _| self.fields.contains_key(name));
+                for (name, field) in object.iter_mut() {
+                    if let Some(selection) = self.fields.get(name)
+                        && !selection.fields.is_empty()
+                    {
+                        selection.retain(field);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn empty_selection_keeps_everything() {
+        assert_eq!(FieldSelection::parse(None), None);
+        assert_eq!(FieldSelection::parse(Some(" , ")), None);
+    }
+
+    #[test]
+    fn keeps_selected_fields_and_id() {
+        let selection = FieldSelection::parse(Some("title, action.typ.type,missing")).unwrap();
+        let mut items = json!([
+            {
+                "id": 1,
+                "title": "Fix login",
+                "description": "A very long description",
+                "action": { "typ": { "type": "CodingAgent", "prompt": "..." }, "next": null },
+            },
+            { "id": 2, "title": "Ship", "action": "none" },
+        ]);
+
+        selection.retain(&mut items);
+
+        assert_eq!(
+            items,
+            json!([
+                { "id": 1, "title": "Fix login", "action": { "typ": { "type": "CodingAgent" } } },
+                { "id": 2, "title": "Ship", "action": "none" },
+            ])
+        );
+    }
+}
@@ -3,11 +3,13 @@ use std::{env, sync::OnceLock};
 use directories::ProjectDirs;
 
 pub mod approvals;
+pub mod asciicast;
 pub mod assets;
 pub mod browser;
 pub mod command_ext;
 pub mod diff;
 pub mod execution_logs;
+pub mod fields;
 pub mod http_headers;
 pub mod jwt;
 pub mod log_msg;
@@ -20,6 +22,7 @@ pub mod sentry;
 pub mod shell;
 pub mod stream_lines;
 pub mod text;
+pub mod tokens;
 pub mod tokio;
 pub mod version;
 
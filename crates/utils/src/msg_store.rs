@@ -21,6 +21,8 @@ struct StoredMsg {
 struct Inner {
     history: VecDeque<StoredMsg>,
     total_bytes: usize,
+    /// Whether messages were dropped from the front of the history.
+    truncated: bool,
 }
 
 pub struct MsgStore {
@@ -41,6 +43,7 @@ impl MsgStore {
             inner: RwLock::new(Inner {
                 history: VecDeque::with_capacity(32),
                 total_bytes: 0,
+                truncated: false,
             }),
             sender,
         }
@@ -54,6 +57,7 @@ impl MsgStore {
         while inner.total_bytes.saturating_add(bytes) > HISTORY_BYTES {
             if let Some(front) = inner.history.pop_front() {
                 inner.total_bytes = inner.total_bytes.saturating_sub(front.bytes);
+                inner.truncated = true;
             } else {
                 break;
             }
@@ -97,6 +101,12 @@ impl MsgStore {
             .collect()
     }
 
+    /// Whether the history no longer starts at the first message, because the
+    /// store outgrew its size limit.
+    pub fn history_truncated(&self) -> bool {
+        self.inner.read().unwrap().truncated
+    }
+
     /// History then live, as `LogMsg`.
     pub fn history_plus_stream(
         &self,
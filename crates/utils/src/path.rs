@@ -3,6 +3,9 @@ use std::path::{Path, PathBuf};
 /// Directory name for storing attachments in worktrees
 pub const VIBE_ATTACHMENTS_DIR: &str = ".vibe-attachments";
 
+/// Directory name for workspace context files in worktrees
+pub const VIBE_CONTEXT_DIR: &str = ".vibe-context";
+
 /// Directories that should always be skipped regardless of gitignore.
 /// .git is not in .gitignore but should never be watched.
 pub const ALWAYS_SKIP_DIRS: &[&str] = &[".git", "node_modules"];
@@ -0,0 +1,121 @@
+//! Random bearer tokens, the hashes they are stored and looked up by, and
+//! HMAC-signed tokens that carry their own expiry.
+
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// A URL-safe token of 32 random bytes.
+pub fn new_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Hex SHA-256 of `token`. Only the hash is stored, so a leaked database
+/// doesn't leak usable tokens.
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+fn mac(key: &[u8], message: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key size");
+    mac.update(message);
+    mac
+}
+
+/// HMAC-SHA256 of `message` under `key`.
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    mac(key, message).finalize().into_bytes().to_vec()
+}
+
+/// Whether `signature` is the HMAC-SHA256 of `message` under `key`, compared
+/// in constant time.
+pub fn verify_hmac_sha256(key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    mac(key, message).verify_slice(signature).is_ok()
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SignedTokenError {
+    #[error("token is malformed or its signature doesn't match")]
+    Invalid,
+    #[error("token has expired")]
+    Expired,
+}
+
+/// A token naming `subject` until `expires_at`, to the second. Both are
+/// readable by whoever holds the token; only the signature is secret.
+pub fn sign_expiring(key: &[u8], subject: &str, expires_at: DateTime<Utc>) -> String {
+    let payload = URL_SAFE_NO_PAD.encode(format!("{subject}.{}", expires_at.timestamp()));
+    let signature = URL_SAFE_NO_PAD.encode(hmac_sha256(key, payload.as_bytes()));
+    format!("{payload}.{signature}")
+}
+
+/// The subject and expiry of a token from [`sign_expiring`], once its
+/// signature checks out and it hasn't expired.
+pub fn verify_expiring(
+    key: &[u8],
+    token: &str,
+) -> Result<(String, DateTime<Utc>), SignedTokenError> {
+    let (payload, signature) = token.split_once('.').ok_or(SignedTokenError::Invalid)?;
+    let signature = URL_SAFE_NO_PAD
+        .decode(signature)
+        .map_err(|_| SignedTokenError::Invalid)?;
+    if !verify_hmac_sha256(key, payload.as_bytes(), &signature) {
+        return Err(SignedTokenError::Invalid);
+    }
+
+    let payload = URL_SAFE_NO_PAD
+        .decode(payload)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or(SignedTokenError::Invalid)?;
+    let (subject, expires_at) = payload.rsplit_once('.').ok_or(SignedTokenError::Invalid)?;
+    let expires_at = expires_at
+        .parse::<i64>()
+        .ok()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        .ok_or(SignedTokenError::Invalid)?;
+    if expires_at <= Utc::now() {
+        return Err(SignedTokenError::Expired);
+    }
+    Ok((subject.to_string(), expires_at))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn expiring_tokens_only_verify_with_their_key_until_they_expire() {
+        let expires_at =
+            DateTime::from_timestamp((Utc::now() + Duration::minutes(5)).timestamp(), 0).unwrap();
+        let token = sign_expiring(b"key", "subject.with.dots", expires_at);
+
+        assert_eq!(
+            verify_expiring(b"key", &token),
+            Ok(("subject.with.dots".to_string(), expires_at))
+        );
+        assert_eq!(
+            verify_expiring(b"other-key", &token),
+            Err(SignedTokenError::Invalid)
+        );
+        assert_eq!(
+            verify_expiring(b"key", &format!("{token}x")),
+            Err(SignedTokenError::Invalid)
+        );
+
+        let expired = sign_expiring(b"key", "subject", Utc::now() - Duration::seconds(1));
+        assert_eq!(
+            verify_expiring(b"key", &expired),
+            Err(SignedTokenError::Expired)
+        );
+    }
+}
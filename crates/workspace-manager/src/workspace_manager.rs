@@ -5,6 +5,7 @@ use db::{
     models::{
         file::WorkspaceAttachment,
         repo::{Repo, RepoError},
+        repo_package::RepoPackage,
         requests::WorkspaceRepoInput,
         session::Session,
         workspace::Workspace as DbWorkspace,
@@ -15,7 +16,9 @@ use git::{GitService, GitServiceError};
 use thiserror::Error;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
-use worktree_manager::{WorktreeCleanup, WorktreeError, WorktreeManager};
+use worktree_manager::{
+    WARM_BASES_DIR_NAME, WARM_POOL_DIR_NAME, WorktreeCleanup, WorktreeError, WorktreeManager,
+};
 
 #[derive(Debug, Clone)]
 pub struct RepoWorkspaceInput {
@@ -52,6 +55,8 @@ pub enum WorkspaceError {
     BranchNotFound { repo_name: String, branch: String },
     #[error("No repositories provided")]
     NoRepositories,
+    #[error("Package not found in repository '{repo_name}'")]
+    PackageNotFound { repo_name: String },
     #[error("Partial workspace creation failed: {0}")]
     PartialCreation(String),
 }
@@ -109,8 +114,18 @@ impl ManagedWorkspace {
             self.workspace.id,
             std::slice::from_ref(&create_repo),
         )
-        .await
-        .map(|_| ())
+        .await?;
+
+        if repo.package_id.is_some() {
+            RepoPackage::select_for_workspace(
+                &self.db.pool,
+                self.workspace.id,
+                repo.repo_id,
+                repo.package_id,
+            )
+            .await?;
+        }
+        Ok(())
     }
 
     async fn refresh(&mut self) -> Result<(), WorkspaceError> {
@@ -152,6 +167,16 @@ impl ManagedWorkspace {
             return Err(WorkspaceError::RepoAlreadyAttached);
         }
 
+        if let Some(package_id) = repo_ref.package_id
+            && !RepoPackage::find_by_id(&self.db.pool, package_id)
+                .await?
+                .is_some_and(|package| package.repo_id == repo.id)
+        {
+            return Err(WorkspaceError::PackageNotFound {
+                repo_name: repo.name,
+            });
+        }
+
         self.attach_repository(repo_ref).await?;
         self.refresh().await?;
         Ok(())
@@ -585,7 +610,10 @@ impl WorkspaceManager {
             };
 
             let path = entry.path();
-            if !path.is_dir() {
+            if !path.is_dir()
+                || entry.file_name() == WARM_BASES_DIR_NAME
+                || entry.file_name() == WARM_POOL_DIR_NAME
+            {
                 continue;
             }
 
@@ -0,0 +1,239 @@
+//! Worktree creation by cloning a warm base checkout on copy-on-write
+//! filesystems (btrfs, XFS, APFS, ZFS with block cloning).
+//!
+//! Each repo keeps a warm base: a copy of a worktree taken after its setup
+//! script succeeded, so it carries installed dependencies and build output
+//! (`node_modules`, `target`, ...) along with the checked out files. New
+//! worktrees of the repo are registered without a checkout, filled with a
+//! reflink clone of the base, and then synced to their branch, which only
+//! rewrites the files that differ. Clones share blocks with the base, so
+//! this costs neither time nor disk space proportional to the checkout.
+//!
+//! Whether the filesystem supports reflinks is probed once per directory.
+//! Without support, or without a base yet, worktrees are checked out as usual.
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{LazyLock, Mutex},
+};
+
+use git::GitService;
+use tracing::{debug, info};
+
+use crate::WorktreeError;
+
+/// Directory of the warm bases, inside the worktree base dir so clones stay on
+/// the same filesystem.
+pub const WARM_BASES_DIR_NAME: &str = ".warm-bases";
+
+static REFLINK_SUPPORT: LazyLock<Mutex<HashMap<PathBuf, bool>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Where the warm base of `repo_path` lives under `base_dir`.
+pub fn warm_base_path(base_dir: &Path, repo_path: &Path) -> PathBuf {
+    let name = repo_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "repo".to_string());
+    // Repos with the same directory name must not share a base
+    let hash = git2::Oid::hash_object(
+        git2::ObjectType::Blob,
+        repo_path.to_string_lossy().as_bytes(),
+    )
+    .map(|oid| oid.to_string()[..12].to_string())
+    .unwrap_or_default();
+    base_dir
+        .join(WARM_BASES_DIR_NAME)
+        .join(format!("{name}-{hash}"))
+}
+
+fn clone_command(sources: &[PathBuf], destination: &Path) -> Option<Command> {
+    let mut command = Command::new("cp");
+    if cfg!(target_os = "linux") {
+        command.args(["-a", "--reflink=always"]);
+    } else if cfg!(target_os = "macos") {
+        // `-c` clones with clonefile(2)
+        command.args(["-c", "-R", "-p"]);
+    } else {
+        return None;
+    }
+    command.args(sources).arg(destination);
+    Some(command)
+}
+
+/// Reflink-clone `sources` into the directory `destination`.
+fn clone_into(sources: &[PathBuf], destination: &Path) -> io::Result<()> {
+    if sources.is_empty() {
+        return Ok(());
+    }
+    let mut command = clone_command(sources, destination).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            "reflinks are not supported on this platform",
+        )
+    })?;
+    let output = command.output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "cp failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Clone the contents of `source` into `destination`, except its `.git`.
+fn clone_contents(source: &Path, destination: &Path) -> io::Result<()> {
+    let entries = fs::read_dir(source)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name() != ".git")
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+    clone_into(&entries, destination)
+}
+
+/// Whether files in `dir` can be reflink-cloned. Probed once per directory.
+pub fn supports_reflink(dir: &Path) -> bool {
+    if let Some(supported) = REFLINK_SUPPORT.lock().unwrap().get(dir) {
+        return *supported;
+    }
+    let supported = probe_reflink(dir)
+        .inspect_err(|e| debug!("Reflink probe in {} failed: {}", dir.display(), e))
+        .unwrap_or(false);
+    info!(
+        "Copy-on-write worktree cloning is {} in {}",
+        if supported {
+            "available"
+        } else {
+            "unavailable"
+        },
+        dir.display()
+    );
+    REFLINK_SUPPORT
+        .lock()
+        .unwrap()
+        .insert(dir.to_path_buf(), supported);
+    supported
+}
+
+fn probe_reflink(dir: &Path) -> io::Result<bool> {
+    fs::create_dir_all(dir)?;
+    let probe_dir = dir.join(format!(".reflink-probe-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&probe_dir);
+    fs::create_dir_all(probe_dir.join("clone"))?;
+    let probe_file = probe_dir.join("probe");
+    fs::write(&probe_file, b"reflink probe")?;
+    let result = clone_into(&[probe_file], &probe_dir.join("clone"));
+    let _ = fs::remove_dir_all(&probe_dir);
+    Ok(result.is_ok())
+}
+
+/// Create the worktree of `branch` at `worktree_path` from the warm base of
+/// `repo_path`. Returns `false`, without touching anything, when there is no
+/// base or the filesystem can't clone; on errors the caller cleans up and
+/// falls back to a regular checkout.
+pub fn create_from_warm_base(
+    base_dir: &Path,
+    repo_path: &Path,
+    branch: &str,
+    worktree_path: &Path,
+) -> Result<bool, WorktreeError> {
+    let warm_base = warm_base_path(base_dir, repo_path);
+    if !warm_base.is_dir() || !supports_reflink(base_dir) {
+        return Ok(false);
+    }
+
+    let git = GitService::new();
+    git.add_worktree_without_checkout(repo_path, worktree_path, branch)?;
+    clone_contents(&warm_base, worktree_path)?;
+    git.sync_worktree_to_head(worktree_path)?;
+    Ok(true)
+}
+
+/// Replace the warm base of `repo_path` with a clone of `worktree_path`.
+/// Returns `false` when the filesystem can't clone.
+pub fn refresh_warm_base(
+    base_dir: &Path,
+    repo_path: &Path,
+    worktree_path: &Path,
+) -> Result<bool, WorktreeError> {
+    if !worktree_path.is_dir() || !supports_reflink(base_dir) {
+        return Ok(false);
+    }
+
+    let warm_base = warm_base_path(base_dir, repo_path);
+    // Build the new base next to the old one, so a failure keeps the old one
+    let mut staging_name = warm_base.file_name().unwrap_or_default().to_os_string();
+    staging_name.push(format!(".staging-{}", std::process::id()));
+    let staging = warm_base.with_file_name(staging_name);
+    let _ = fs::remove_dir_all(&staging);
+    fs::create_dir_all(&staging)?;
+    if let Err(e) = clone_contents(worktree_path, &staging) {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e.into());
+    }
+    if warm_base.exists() {
+        fs::remove_dir_all(&warm_base)?;
+    }
+    fs::rename(&staging, &warm_base)?;
+    debug!(
+        "Refreshed warm base of {} from {}",
+        repo_path.display(),
+        worktree_path.display()
+    );
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warm_bases_are_per_repo_path() {
+        let base_dir = Path::new("/tmp/worktrees");
+        let a = warm_base_path(base_dir, Path::new("/src/a/app"));
+        let b = warm_base_path(base_dir, Path::new("/src/b/app"));
+        assert_ne!(a, b);
+        assert!(a.starts_with(base_dir.join(WARM_BASES_DIR_NAME)));
+        assert!(a.file_name().unwrap().to_string_lossy().starts_with("app-"));
+    }
+
+    #[test]
+    fn worktrees_cloned_from_a_warm_base_match_their_branch() {
+        let td = tempfile::TempDir::new().unwrap();
+        let base_dir = td.path().join("worktrees");
+        if !supports_reflink(&base_dir) {
+            // Nothing to test on filesystems without reflinks
+            return;
+        }
+
+        let repo_path = td.path().join("repo");
+        let git = GitService::new();
+        git.initialize_repo_with_main_branch(&repo_path).unwrap();
+        fs::write(repo_path.join("tracked.txt"), "main\n").unwrap();
+        fs::write(repo_path.join(".gitignore"), "deps/\n").unwrap();
+        git.commit(&repo_path, "add files").unwrap();
+        git.create_branch(&repo_path, "feature", "main").unwrap();
+
+        // A warm base with installed dependencies and a stray file
+        let source = td.path().join("source");
+        fs::create_dir_all(source.join("deps")).unwrap();
+        fs::write(source.join("deps/lib.js"), "dependency\n").unwrap();
+        fs::write(source.join("tracked.txt"), "stale\n").unwrap();
+        fs::write(source.join("stray.txt"), "untracked\n").unwrap();
+        assert!(refresh_warm_base(&base_dir, &repo_path, &source).unwrap());
+
+        let worktree_path = td.path().join("wt");
+        assert!(create_from_warm_base(&base_dir, &repo_path, "feature", &worktree_path).unwrap());
+        assert_eq!(
+            fs::read_to_string(worktree_path.join("tracked.txt")).unwrap(),
+            "main\n"
+        );
+        assert!(worktree_path.join("deps/lib.js").exists());
+        assert!(!worktree_path.join("stray.txt").exists());
+        assert_eq!(git.get_current_branch(&worktree_path).unwrap(), "feature");
+    }
+}
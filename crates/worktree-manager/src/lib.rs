@@ -1,3 +1,5 @@
+mod cow_clone;
 mod worktree_manager;
 
-pub use worktree_manager::{WorktreeCleanup, WorktreeError, WorktreeManager};
+pub use cow_clone::WARM_BASES_DIR_NAME;
+pub use worktree_manager::{WARM_POOL_DIR_NAME, WorktreeCleanup, WorktreeError, WorktreeManager};
@@ -10,9 +10,15 @@ static WORKSPACE_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
 use git::{GitService, GitServiceError};
 use git2::Repository;
 use thiserror::Error;
-use tracing::{debug, info, trace};
+use tracing::{debug, info, trace, warn};
 use utils::{path::normalize_macos_private_alias, shell::resolve_executable_path};
 
+use crate::cow_clone;
+
+/// Directory of the warm pool's pre-provisioned worktrees, inside the worktree
+/// base dir so claiming one moves it within the same filesystem.
+pub const WARM_POOL_DIR_NAME: &str = ".warm-pool";
+
 // Global synchronization for worktree creation to prevent race conditions
 static WORKTREE_CREATION_LOCKS: LazyLock<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> =
     LazyLock::new(|| Mutex::new(HashMap::new()));
@@ -247,9 +253,21 @@ impl WorktreeManager {
         let worktree_display_name = worktree_path.to_string_lossy().to_string();
         debug!("Performing cleanup for worktree: {worktree_display_name}");
 
+        // Step 0: Keep uncommitted changes recoverable; snapshot refs live in the
+        // main repository and survive the worktree.
+        let git_service = GitService::new();
+        if worktree_path.exists()
+            && let Err(e) =
+                git_service.snapshot_worktree(worktree_path, "Before deleting the worktree")
+        {
+            warn!(
+                "Could not snapshot {} before cleanup: {}",
+                worktree_display_name, e
+            );
+        }
+
         // Step 1: Use GitService to remove the worktree registration (force) if present
         // The Git CLI is more robust than libgit2 for mutable worktree operations
-        let git_service = GitService::new();
         if let Err(e) = git_service.remove_worktree(git_repo_path, worktree_path, true) {
             debug!("git worktree remove non-fatal error: {}", e);
         }
@@ -325,6 +343,34 @@ impl WorktreeManager {
         let path_str = path_str.to_string();
 
         tokio::task::spawn_blocking(move || -> Result<(), WorktreeError> {
+            // On copy-on-write filesystems, clone the repo's warm base instead
+            // of checking out every file
+            match cow_clone::create_from_warm_base(
+                &Self::get_worktree_base_dir(),
+                &git_repo_path,
+                &branch_name,
+                &worktree_path,
+            ) {
+                Ok(true) => {
+                    info!(
+                        "Successfully created worktree {} at {} (cloned warm base)",
+                        branch_name, path_str
+                    );
+                    return Ok(());
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    warn!(
+                        "Cloning warm base failed; falling back to a fresh checkout: {}",
+                        e
+                    );
+                    Self::force_cleanup_worktree_metadata(&git_repo_path, &worktree_path)?;
+                    if worktree_path.exists() {
+                        std::fs::remove_dir_all(&worktree_path).map_err(WorktreeError::Io)?;
+                    }
+                }
+            }
+
             // Prefer git CLI for worktree add to inherit sparse-checkout semantics
             let git_service = GitService::new();
             match git_service.add_worktree(&git_repo_path, &worktree_path, &branch_name, false) {
@@ -521,6 +567,23 @@ impl WorktreeManager {
     }
 
     /// Get the base directory for vibe-kanban worktrees
+    /// Make `worktree_path`, with whatever its setup installed, the warm base
+    /// new worktrees of `repo_path` are cloned from. No-op unless the
+    /// filesystem supports copy-on-write clones.
+    pub async fn refresh_warm_base(
+        repo_path: &Path,
+        worktree_path: &Path,
+    ) -> Result<(), WorktreeError> {
+        let repo_path = repo_path.to_path_buf();
+        let worktree_path = worktree_path.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            cow_clone::refresh_warm_base(&Self::get_worktree_base_dir(), &repo_path, &worktree_path)
+        })
+        .await
+        .map_err(|e| WorktreeError::TaskJoin(format!("{e}")))?
+        .map(|_| ())
+    }
+
     pub fn get_worktree_base_dir() -> std::path::PathBuf {
         if let Some(override_path) = WORKSPACE_DIR_OVERRIDE.get() {
             // Always use app-owned subdirectory within custom path for safety.
@@ -530,6 +593,11 @@ impl WorktreeManager {
         Self::get_default_worktree_base_dir()
     }
 
+    /// Get the directory of the warm pool's worktrees
+    pub fn get_warm_pool_dir() -> std::path::PathBuf {
+        Self::get_worktree_base_dir().join(WARM_POOL_DIR_NAME)
+    }
+
     /// Get the default base directory (ignoring any override)
     pub fn get_default_worktree_base_dir() -> std::path::PathBuf {
         utils::path::get_vibe_kanban_temp_dir().join("worktrees")
@@ -11,6 +11,7 @@ export type KanbanAssigneeUser = {
   first_name?: string | null;
   last_name?: string | null;
   username?: string | null;
+  display_name?: string | null;
   avatar_url?: string | null;
 };
 
@@ -46,6 +47,8 @@ const buildInitials = (user: KanbanAssigneeUser): string => {
 };
 
 const buildLabel = (user: KanbanAssigneeUser): string => {
+  if (user.display_name?.trim()) return user.display_name;
+
   const name = [user.first_name, user.last_name]
     .filter((value): value is string => Boolean(value && value.trim()))
     .join(' ');
@@ -7,6 +7,7 @@ export type UserAvatarUser = {
   first_name?: string | null;
   last_name?: string | null;
   username?: string | null;
+  display_name?: string | null;
   avatar_url?: string | null;
 };
 
@@ -42,6 +43,8 @@ const buildInitials = (user: UserAvatarUser): string => {
 };
 
 const buildLabel = (user: UserAvatarUser): string => {
+  if (user.display_name?.trim()) return user.display_name;
+
   const name = [user.first_name, user.last_name]
     .filter((value): value is string => Boolean(value && value.trim()))
     .join(' ');
@@ -28,10 +28,7 @@ import {
   useKanbanFilters,
   PRIORITY_ORDER,
 } from '../model/hooks/useKanbanFilters';
-import {
-  bulkUpdateIssues,
-  type BulkUpdateIssueItem,
-} from '@/shared/lib/remoteApi';
+import { reorderIssue } from '@/shared/lib/remoteApi';
 import { PlusIcon, DotsThreeIcon } from '@phosphor-icons/react';
 import { Actions } from '@/shared/actions';
 import {
@@ -419,15 +416,6 @@ export function KanbanContainer() {
     [sortedStatuses]
   );
 
-  // Map status ID to 1-based column index for sort_order calculation
-  const statusColumnIndexMap = useMemo(() => {
-    const map = new Map<string, number>();
-    visibleStatuses.forEach((status, index) => {
-      map.set(status.id, index + 1);
-    });
-    return map;
-  }, [visibleStatuses]);
-
   const hiddenStatuses = useMemo(
     () => sortedStatuses.filter((s) => s.hidden),
     [sortedStatuses]
@@ -668,16 +656,6 @@ export function KanbanContainer() {
     findWorkspace: findWorkspaceInKanban,
   });
 
-  // Calculate sort_order based on column index and issue position
-  // Formula: 1000 * [COLUMN_INDEX] + [ISSUE_INDEX] (both 1-based)
-  const calculateSortOrder = useCallback(
-    (statusId: string, issueIndex: number): number => {
-      const columnIndex = statusColumnIndexMap.get(statusId) ?? 1;
-      return 1000 * columnIndex + (issueIndex + 1);
-    },
-    [statusColumnIndexMap]
-  );
-
   // Simple onDragEnd handler - the library handles all visual movement
   const handleDragEnd = useCallback(
     (result: DropResult) => {
@@ -706,7 +684,7 @@ export function KanbanContainer() {
       const destId = destination.droppableId;
       const isCrossColumn = sourceId !== destId;
 
-      // Update local state and capture new items for bulk update
+      // Update local state and capture the new order
       let newItems: Record<string, string[]> = {};
       setItems((prev) => {
         const sourceItems = [...(prev[sourceId] ?? [])];
@@ -729,39 +707,22 @@ export function KanbanContainer() {
         return newItems;
       });
 
-      // Build bulk updates for all issues in affected columns
-      const updates: BulkUpdateIssueItem[] = [];
-
-      // Always update destination column
+      // The server ranks the moved issue between its new neighbours. Outside
+      // manual sort the visible order isn't the rank order, so append instead.
       const destIssueIds = newItems[destId] ?? [];
-      destIssueIds.forEach((issueId, index) => {
-        updates.push({
-          id: issueId,
-          changes: {
-            status_id: destId,
-            sort_order: calculateSortOrder(destId, index),
-          },
-        });
-      });
-
-      // Update source column if cross-column move
-      if (isCrossColumn) {
-        const sourceIssueIds = newItems[sourceId] ?? [];
-        sourceIssueIds.forEach((issueId, index) => {
-          updates.push({
-            id: issueId,
-            changes: {
-              sort_order: calculateSortOrder(sourceId, index),
-            },
-          });
-        });
-      }
+      const movedId = destIssueIds[destination.index];
+      if (!movedId) return;
+      const neighbours = isManualSort
+        ? {
+            previous_issue_id: destIssueIds[destination.index - 1],
+            next_issue_id: destIssueIds[destination.index + 1],
+          }
+        : {};
 
-      // Perform bulk update
       isSyncingRef.current = true;
-      bulkUpdateIssues(updates)
+      reorderIssue(movedId, { status_id: destId, ...neighbours })
         .catch((err) => {
-          console.error('Failed to bulk update sort order:', err);
+          console.error('Failed to reorder issue:', err);
         })
         .finally(() => {
           // Delay clearing flag to let Electric sync complete
@@ -770,7 +731,7 @@ export function KanbanContainer() {
           }, 500);
         });
     },
-    [kanbanFilters.sortField, calculateSortOrder]
+    [kanbanFilters.sortField]
   );
 
   // Multi-select support
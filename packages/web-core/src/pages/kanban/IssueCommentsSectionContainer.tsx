@@ -233,7 +233,8 @@ function IssueCommentsSectionContent() {
           authorId: comment.author_id,
           authorName: comment.author_id
             ? author
-              ? `${author.first_name ?? ''} ${author.last_name ?? ''}`.trim() ||
+              ? author.display_name?.trim() ||
+                `${author.first_name ?? ''} ${author.last_name ?? ''}`.trim() ||
                 author.email ||
                 t('kanban.unknownUser')
               : t('kanban.unknownUser')
@@ -302,7 +303,8 @@ function IssueCommentsSectionContent() {
           userNames: data.userIds.map((userId) => {
             const member = membersWithProfilesById.get(userId);
             return member
-              ? `${member.first_name ?? ''} ${member.last_name ?? ''}`.trim() ||
+              ? member.display_name?.trim() ||
+                  `${member.first_name ?? ''} ${member.last_name ?? ''}`.trim() ||
                   member.email ||
                   t('kanban.unknownUser')
               : t('kanban.unknownUser');
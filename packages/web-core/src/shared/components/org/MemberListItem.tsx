@@ -38,9 +38,9 @@ export function MemberListItem({
   const canChangeRole = isAdmin && !isSelf;
 
   const displayName = member.username || member.user_id;
-  const fullName = [member.first_name, member.last_name]
-    .filter(Boolean)
-    .join(' ');
+  const fullName =
+    member.display_name?.trim() ||
+    [member.first_name, member.last_name].filter(Boolean).join(' ');
 
   return (
     <div className="flex items-center justify-between p-3 border rounded-lg">
@@ -285,6 +285,10 @@ const CreatePRDialogImpl = create<CreatePRDialogProps>(
           );
           setGhCliHelp(null);
           return;
+        } else if (result.error.type === 'branch_protected') {
+          setError(result.error.message);
+          setGhCliHelp(null);
+          return;
         }
       }
 
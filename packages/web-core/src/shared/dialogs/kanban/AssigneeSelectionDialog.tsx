@@ -40,6 +40,7 @@ export interface AssigneeSelectionDialogProps {
 
 const getUserDisplayName = (user: OrganizationMemberWithProfile): string => {
   return (
+    user.display_name?.trim() ||
     [user.first_name, user.last_name].filter(Boolean).join(' ') ||
     user.username ||
     'User'
@@ -6,6 +6,7 @@ import {
   Config,
   CreateFollowUpAttempt,
   ResetProcessRequest,
+  ForkSessionRequest,
   EditorType,
   CreatePrApiRequest,
   CreateTag,
@@ -17,6 +18,15 @@ import {
   Repo,
   RepoWithTargetBranch,
   UpdateRepo,
+  RepoAnalysis,
+  RepoKnowledge,
+  CreateRepoKnowledge,
+  UpdateRepoKnowledge,
+  RepoCommit,
+  RepoContributor,
+  RepoPackage,
+  CreateRepoPackage,
+  UpdateRepoPackage,
   SearchMode,
   SearchResult,
   Tag,
@@ -65,6 +75,16 @@ import {
   CreateScratch,
   UpdateScratch,
   PushError,
+  PushPreflight,
+  PushWithLeaseRequest,
+  LeasedPush,
+  WorkspaceMergeReadiness,
+  WorktreeSnapshot,
+  Diff,
+  RestoreWorktreeSnapshotRequest,
+  GitCredentialSummary,
+  CreateGitCredential,
+  SetRepoGitCredential,
   TokenResponse,
   CurrentUserResponse,
   QueueStatus,
@@ -75,6 +95,12 @@ import {
   AbortConflictsRequest,
   ContinueRebaseRequest,
   Session,
+  SessionShareLink,
+  CreateSessionShareRequest,
+  SessionCheckpoint,
+  CreateSessionCheckpointRequest,
+  RestoreSessionCheckpointRequest,
+  UndoneAction,
   Workspace,
   StartReviewRequest,
   ReviewError,
@@ -100,8 +126,14 @@ import {
   OpenRemoteWorkspaceInEditorRequest,
   OpenRemoteEditorResponse,
   ProfileResponse,
+  LocalUser,
+  LocalUserToken,
 } from 'shared/types';
-import type { Project as RemoteProject } from 'shared/remote-types';
+import type {
+  PlanEntitlements,
+  Project as RemoteProject,
+  UpgradeRequiredError,
+} from 'shared/remote-types';
 import type { WorkspaceWithSession } from '@/shared/types/attempt';
 import { createWorkspaceWithSession } from '@/shared/types/attempt';
 import { resolveHostRequestScope } from '@/shared/lib/hostRequestScope';
@@ -208,6 +240,51 @@ export interface OrganizationBillingStatusResponse {
   } | null;
 }
 
+export interface OrganizationUsageResponse {
+  organization_id: string;
+  period_start: string;
+  period_end: string;
+  agent_minutes: number;
+  active_seats: number;
+  reported_agent_minutes: number;
+  members: {
+    user_id: string;
+    agent_runs: number;
+    agent_minutes: number;
+  }[];
+}
+
+export interface BillingInvoice {
+  id: string;
+  number: string | null;
+  status: string | null;
+  currency: string;
+  total: number;
+  amount_paid: number;
+  amount_due: number;
+  created_at: string;
+  period_start: string;
+  period_end: string;
+  hosted_invoice_url: string | null;
+  has_pdf: boolean;
+}
+
+export interface BillingReceipt {
+  id: string;
+  receipt_number: string | null;
+  amount: number;
+  amount_refunded: number;
+  currency: string;
+  paid: boolean;
+  created_at: string;
+  receipt_url: string | null;
+}
+
+export interface BillingDocumentsPage {
+  limit?: number;
+  startingAfter?: string;
+}
+
 // Special handler for Result-returning endpoints
 const handleApiResponseAsResult = async <T, E>(
   response: Response
@@ -373,6 +450,17 @@ export const sessionsApi = {
     return handleApiResponse<void>(response);
   },
 
+  fork: async (
+    sessionId: string,
+    data: ForkSessionRequest
+  ): Promise<CreateAndStartWorkspaceResponse> => {
+    const response = await makeRequest(`/api/sessions/${sessionId}/fork`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<CreateAndStartWorkspaceResponse>(response);
+  },
+
   runSetupScript: async (
     sessionId: string
   ): Promise<Result<ExecutionProcess, RunScriptError>> => {
@@ -394,6 +482,77 @@ export const sessionsApi = {
     });
     return handleApiResponse<Session>(response);
   },
+
+  listShares: async (sessionId: string): Promise<SessionShareLink[]> => {
+    const response = await makeRequest(`/api/sessions/${sessionId}/shares`);
+    return handleApiResponse<SessionShareLink[]>(response);
+  },
+
+  createShare: async (
+    sessionId: string,
+    data: CreateSessionShareRequest
+  ): Promise<SessionShareLink> => {
+    const response = await makeRequest(`/api/sessions/${sessionId}/shares`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<SessionShareLink>(response);
+  },
+
+  revokeShare: async (sessionId: string, shareId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/shares/${shareId}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  listCheckpoints: async (sessionId: string): Promise<SessionCheckpoint[]> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/checkpoints`
+    );
+    return handleApiResponse<SessionCheckpoint[]>(response);
+  },
+
+  createCheckpoint: async (
+    sessionId: string,
+    data: CreateSessionCheckpointRequest
+  ): Promise<SessionCheckpoint> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/checkpoints`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<SessionCheckpoint>(response);
+  },
+
+  restoreCheckpoint: async (
+    sessionId: string,
+    checkpointId: string,
+    data: RestoreSessionCheckpointRequest
+  ): Promise<SessionCheckpoint> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/checkpoints/${checkpointId}/restore`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<SessionCheckpoint>(response);
+  },
+
+  deleteCheckpoint: async (
+    sessionId: string,
+    checkpointId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/checkpoints/${checkpointId}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
 };
 
 // Workspace APIs
@@ -472,6 +631,14 @@ export const workspacesApi = {
     return handleApiResponse<void>(response);
   },
 
+  /** Undo the most recent deletion or git reset of a workspace */
+  undo: async (workspaceId: string): Promise<UndoneAction> => {
+    const response = await makeRequest(`/api/workspaces/${workspaceId}/undo`, {
+      method: 'POST',
+    });
+    return handleApiResponse<UndoneAction>(response);
+  },
+
   linkToIssue: async (
     workspaceId: string,
     projectId: string,
@@ -535,6 +702,15 @@ export const workspacesApi = {
     return handleApiResponse<RepoBranchStatus[]>(response);
   },
 
+  getMergeReadiness: async (
+    workspaceId: string
+  ): Promise<WorkspaceMergeReadiness> => {
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/git/merge-readiness`
+    );
+    return handleApiResponse<WorkspaceMergeReadiness>(response);
+  },
+
   getRepos: async (workspaceId: string): Promise<RepoWithTargetBranch[]> => {
     const response = await makeRequest(`/api/workspaces/${workspaceId}/repos`);
     return handleApiResponse<RepoWithTargetBranch[]>(response);
@@ -589,6 +765,66 @@ export const workspacesApi = {
     return handleApiResponseAsResult<void, PushError>(response);
   },
 
+  pushWithLease: async (
+    workspaceId: string,
+    data: PushWithLeaseRequest
+  ): Promise<Result<LeasedPush, PushError>> => {
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/git/push/lease`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponseAsResult<LeasedPush, PushError>(response);
+  },
+
+  pushPreflight: async (
+    workspaceId: string,
+    repoId: string
+  ): Promise<PushPreflight> => {
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/git/push/preflight?repo_id=${encodeURIComponent(repoId)}`
+    );
+    return handleApiResponse<PushPreflight>(response);
+  },
+
+  getDiffFile: async (
+    workspaceId: string,
+    repoId: string,
+    path: string
+  ): Promise<Diff | null> => {
+    const params = new URLSearchParams({ repo_id: repoId, path });
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/git/diff/file?${params.toString()}`
+    );
+    return handleApiResponse<Diff | null>(response);
+  },
+
+  listSnapshots: async (
+    workspaceId: string,
+    repoId: string
+  ): Promise<WorktreeSnapshot[]> => {
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/git/snapshots?repo_id=${encodeURIComponent(repoId)}`
+    );
+    return handleApiResponse<WorktreeSnapshot[]>(response);
+  },
+
+  restoreSnapshot: async (
+    workspaceId: string,
+    data: RestoreWorktreeSnapshotRequest
+  ): Promise<WorktreeSnapshot> => {
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/git/snapshots/restore`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<WorktreeSnapshot>(response);
+  },
+
   rebase: async (
     workspaceId: string,
     data: RebaseWorkspaceRequest
@@ -787,6 +1023,21 @@ export const workspacesApi = {
 
 // Execution Process APIs
 export const executionProcessesApi = {
+  /**
+   * List a session's execution processes, trimmed to `fields` when given.
+   */
+  listBySession: async <K extends keyof ExecutionProcess>(
+    sessionId: string,
+    fields?: K[]
+  ): Promise<Pick<ExecutionProcess, K | 'id'>[]> => {
+    const params = new URLSearchParams({ session_id: sessionId });
+    if (fields?.length) {
+      params.set('fields', fields.join(','));
+    }
+    const response = await makeRequest(`/api/execution-processes?${params}`);
+    return handleApiResponse<Pick<ExecutionProcess, K | 'id'>[]>(response);
+  },
+
   getDetails: async (processId: string): Promise<ExecutionProcess> => {
     const response = await makeRequest(`/api/execution-processes/${processId}`);
     return handleApiResponse<ExecutionProcess>(response);
@@ -900,6 +1151,17 @@ export const repoApi = {
     return handleApiResponse<GitBranch[]>(response);
   },
 
+  getCommits: async (repoId: string, limit?: number): Promise<RepoCommit[]> => {
+    const query = limit !== undefined ? `?limit=${limit}` : '';
+    const response = await makeRequest(`/api/repos/${repoId}/commits${query}`);
+    return handleApiResponse<RepoCommit[]>(response);
+  },
+
+  getContributors: async (repoId: string): Promise<RepoContributor[]> => {
+    const response = await makeRequest(`/api/repos/${repoId}/contributors`);
+    return handleApiResponse<RepoContributor[]>(response);
+  },
+
   init: async (
     data: {
       parent_path: string;
@@ -964,6 +1226,123 @@ export const repoApi = {
     const response = await makeRequest(`/api/repos/${repoId}/remotes`);
     return handleApiResponse<GitRemote[]>(response);
   },
+
+  analyze: async (repoId: string): Promise<RepoAnalysis> => {
+    const response = await makeRequest(`/api/repos/${repoId}/analyze`, {
+      method: 'POST',
+    });
+    return handleApiResponse<RepoAnalysis>(response);
+  },
+
+  listKnowledge: async (repoId: string): Promise<RepoKnowledge[]> => {
+    const response = await makeRequest(`/api/repos/${repoId}/knowledge`);
+    return handleApiResponse<RepoKnowledge[]>(response);
+  },
+
+  createKnowledge: async (
+    repoId: string,
+    data: CreateRepoKnowledge
+  ): Promise<RepoKnowledge> => {
+    const response = await makeRequest(`/api/repos/${repoId}/knowledge`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<RepoKnowledge>(response);
+  },
+
+  updateKnowledge: async (
+    repoId: string,
+    knowledgeId: string,
+    data: UpdateRepoKnowledge
+  ): Promise<RepoKnowledge> => {
+    const response = await makeRequest(
+      `/api/repos/${repoId}/knowledge/${knowledgeId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<RepoKnowledge>(response);
+  },
+
+  approveKnowledge: async (
+    repoId: string,
+    knowledgeId: string
+  ): Promise<RepoKnowledge> => {
+    const response = await makeRequest(
+      `/api/repos/${repoId}/knowledge/${knowledgeId}/approve`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<RepoKnowledge>(response);
+  },
+
+  deleteKnowledge: async (
+    repoId: string,
+    knowledgeId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/repos/${repoId}/knowledge/${knowledgeId}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  listPackages: async (repoId: string): Promise<RepoPackage[]> => {
+    const response = await makeRequest(`/api/repos/${repoId}/packages`);
+    return handleApiResponse<RepoPackage[]>(response);
+  },
+
+  createPackage: async (
+    repoId: string,
+    data: CreateRepoPackage
+  ): Promise<RepoPackage> => {
+    const response = await makeRequest(`/api/repos/${repoId}/packages`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<RepoPackage>(response);
+  },
+
+  updatePackage: async (
+    repoId: string,
+    packageId: string,
+    data: UpdateRepoPackage
+  ): Promise<RepoPackage> => {
+    const response = await makeRequest(
+      `/api/repos/${repoId}/packages/${packageId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<RepoPackage>(response);
+  },
+
+  deletePackage: async (repoId: string, packageId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/repos/${repoId}/packages/${packageId}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getGitCredential: async (
+    repoId: string
+  ): Promise<GitCredentialSummary | null> => {
+    const response = await makeRequest(`/api/repos/${repoId}/git-credential`);
+    return handleApiResponse<GitCredentialSummary | null>(response);
+  },
+
+  setGitCredential: async (
+    repoId: string,
+    data: SetRepoGitCredential
+  ): Promise<GitCredentialSummary | null> => {
+    const response = await makeRequest(`/api/repos/${repoId}/git-credential`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<GitCredentialSummary | null>(response);
+  },
 };
 
 // Issue PR linking APIs
@@ -1020,6 +1399,90 @@ export const configApi = {
     );
     return handleApiResponse<AvailabilityInfo>(response);
   },
+  listGitCredentials: async (): Promise<GitCredentialSummary[]> => {
+    const response = await makeRequest('/api/config/git-credentials');
+    return handleApiResponse<GitCredentialSummary[]>(response);
+  },
+  createGitCredential: async (
+    data: CreateGitCredential
+  ): Promise<GitCredentialSummary> => {
+    const response = await makeRequest('/api/config/git-credentials', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<GitCredentialSummary>(response);
+  },
+  deleteGitCredential: async (credentialId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/config/git-credentials/${credentialId}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Local users sharing this server
+export const usersApi = {
+  list: async (): Promise<LocalUser[]> => {
+    const response = await makeRequest('/api/users');
+    return handleApiResponse<LocalUser[]>(response);
+  },
+  me: async (): Promise<LocalUser | null> => {
+    const response = await makeRequest('/api/users/me');
+    return handleApiResponse<LocalUser | null>(response);
+  },
+  create: async (name: string): Promise<LocalUserToken> => {
+    const response = await makeRequest('/api/users', {
+      method: 'POST',
+      body: JSON.stringify({ name }),
+    });
+    return handleApiResponse<LocalUserToken>(response);
+  },
+  issueToken: async (userId: string): Promise<LocalUserToken> => {
+    const response = await makeRequest(`/api/users/${userId}/tokens`, {
+      method: 'POST',
+    });
+    return handleApiResponse<LocalUserToken>(response);
+  },
+  setEmail: async (
+    userId: string,
+    email: string | null
+  ): Promise<LocalUser> => {
+    const response = await makeRequest(`/api/users/${userId}`, {
+      method: 'PATCH',
+      body: JSON.stringify({ email }),
+    });
+    return handleApiResponse<LocalUser>(response);
+  },
+  delete: async (userId: string): Promise<void> => {
+    const response = await makeRequest(`/api/users/${userId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+  signIn: async (token: string): Promise<LocalUser> => {
+    const response = await makeRequest('/api/users/sign-in', {
+      method: 'POST',
+      body: JSON.stringify({ token }),
+    });
+    return handleApiResponse<LocalUser>(response);
+  },
+  signInWithRemote: async (
+    email: string,
+    password: string
+  ): Promise<LocalUser> => {
+    const response = await makeRequest('/api/users/sign-in/remote', {
+      method: 'POST',
+      body: JSON.stringify({ email, password }),
+    });
+    return handleApiResponse<LocalUser>(response);
+  },
+  signOut: async (): Promise<void> => {
+    const response = await makeRequest('/api/users/sign-out', {
+      method: 'POST',
+    });
+    return handleApiResponse<void>(response);
+  },
 };
 
 // Task Tags APIs (all tags are global)
@@ -1253,11 +1716,16 @@ export const oauthApi = {
 
   handoffInit: async (
     provider: string,
-    returnTo: string
+    returnTo: string,
+    userSignIn = false
   ): Promise<{ handoff_id: string; authorize_url: string }> => {
     const response = await makeRequest('/api/auth/handoff/init', {
       method: 'POST',
-      body: JSON.stringify({ provider, return_to: returnTo }),
+      body: JSON.stringify({
+        provider,
+        return_to: returnTo,
+        user_sign_in: userSignIn,
+      }),
     });
     return handleApiResponse<{ handoff_id: string; authorize_url: string }>(
       response
@@ -1321,21 +1789,35 @@ export async function getCachedToken(): Promise<string | null> {
   return tokenManager.getToken();
 }
 
+const billingDocumentsQuery = ({
+  limit,
+  startingAfter,
+}: BillingDocumentsPage): string => {
+  const params = new URLSearchParams();
+  if (limit !== undefined) params.set('limit', String(limit));
+  if (startingAfter) params.set('starting_after', startingAfter);
+  const query = params.toString();
+  return query ? `?${query}` : '';
+};
+
 const handleRemoteResponse = async <T>(response: Response): Promise<T> => {
   if (!response.ok) {
     let errorMessage = `Request failed with status ${response.status}`;
+    let upgrade: UpgradeRequiredError | undefined;
 
     try {
       const body = (await response.json()) as {
         error?: string;
         message?: string;
+        upgrade?: UpgradeRequiredError;
       };
       errorMessage = body.error || body.message || errorMessage;
+      upgrade = body.upgrade;
     } catch {
       errorMessage = response.statusText || errorMessage;
     }
 
-    throw new ApiError(errorMessage, response.status, response);
+    throw new ApiError(errorMessage, response.status, response, upgrade);
   }
 
   if (response.status === 204) {
@@ -1439,6 +1921,13 @@ export const organizationsApi = {
     return handleRemoteResponse<void>(response);
   },
 
+  getEntitlements: async (orgId: string): Promise<PlanEntitlements> => {
+    const response = await makeRemoteRequest(
+      `/v1/organizations/${orgId}/entitlements`
+    );
+    return handleRemoteResponse<PlanEntitlements>(response);
+  },
+
   getBillingStatus: async (
     orgId: string
   ): Promise<OrganizationBillingStatusResponse> => {
@@ -1448,6 +1937,52 @@ export const organizationsApi = {
     return handleRemoteResponse<OrganizationBillingStatusResponse>(response);
   },
 
+  getBillingUsage: async (orgId: string): Promise<OrganizationUsageResponse> => {
+    const response = await makeRemoteRequest(
+      `/v1/organizations/${orgId}/billing/usage`
+    );
+    return handleRemoteResponse<OrganizationUsageResponse>(response);
+  },
+
+  listInvoices: async (
+    orgId: string,
+    page: BillingDocumentsPage = {}
+  ): Promise<{ invoices: BillingInvoice[]; has_more: boolean }> => {
+    const response = await makeRemoteRequest(
+      `/v1/organizations/${orgId}/billing/invoices${billingDocumentsQuery(page)}`
+    );
+    return handleRemoteResponse<{
+      invoices: BillingInvoice[];
+      has_more: boolean;
+    }>(response);
+  },
+
+  listReceipts: async (
+    orgId: string,
+    page: BillingDocumentsPage = {}
+  ): Promise<{ receipts: BillingReceipt[]; has_more: boolean }> => {
+    const response = await makeRemoteRequest(
+      `/v1/organizations/${orgId}/billing/receipts${billingDocumentsQuery(page)}`
+    );
+    return handleRemoteResponse<{
+      receipts: BillingReceipt[];
+      has_more: boolean;
+    }>(response);
+  },
+
+  downloadInvoicePdf: async (
+    orgId: string,
+    invoiceId: string
+  ): Promise<Blob> => {
+    const response = await makeRemoteRequest(
+      `/v1/organizations/${orgId}/billing/invoices/${encodeURIComponent(invoiceId)}/pdf`
+    );
+    if (!response.ok) {
+      await handleRemoteResponse<void>(response);
+    }
+    return response.blob();
+  },
+
   createPortalSession: async (
     orgId: string,
     returnUrl: string
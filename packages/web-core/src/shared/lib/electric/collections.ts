@@ -3,7 +3,13 @@ import { createCollection } from '@tanstack/react-db';
 
 import { getAuthRuntime } from '@/shared/lib/auth/runtime';
 import { getRemoteApiUrl, makeRequest } from '@/shared/lib/remoteApi';
-import type { MutationDefinition, ShapeDefinition } from 'shared/remote-types';
+import type {
+  MutationConflict,
+  MutationDefinition,
+  ShapeDefinition,
+  StatusTransitionError,
+  UpgradeRequiredError,
+} from 'shared/remote-types';
 import type { CollectionConfig, SyncError } from '@/shared/lib/electric/types';
 
 type ElectricRow = Record<string, unknown> & { [key: string]: unknown };
@@ -435,6 +441,112 @@ async function parseResponseError(
   }
 }
 
+/**
+ * An update was made against an outdated version of a row. Carries the
+ * stored row and the update so the caller can merge them.
+ */
+export class MutationConflictError extends Error {
+  constructor(
+    message: string,
+    readonly conflict: MutationConflict<unknown>
+  ) {
+    super(message);
+    this.name = 'MutationConflictError';
+  }
+}
+
+/** An issue was moved to a status its current status doesn't lead to. */
+export class StatusTransitionRejectedError extends Error {
+  constructor(
+    message: string,
+    readonly transition: StatusTransitionError
+  ) {
+    super(message);
+    this.name = 'StatusTransitionRejectedError';
+  }
+}
+
+/** The organization's plan doesn't allow the change; it needs an upgrade. */
+export class PlanUpgradeRequiredError extends Error {
+  constructor(
+    message: string,
+    readonly upgrade: UpgradeRequiredError
+  ) {
+    super(message);
+    this.name = 'PlanUpgradeRequiredError';
+  }
+}
+
+async function parseMutationError(
+  response: Response,
+  fallbackMessage: string
+): Promise<Error> {
+  if (![402, 409, 422].includes(response.status)) {
+    return new Error(await parseResponseError(response, fallbackMessage));
+  }
+  try {
+    const body = (await response.json()) as {
+      error?: string;
+      conflict?: MutationConflict<unknown>;
+      transition?: StatusTransitionError;
+      upgrade?: UpgradeRequiredError;
+    };
+    if (body.upgrade) {
+      return new PlanUpgradeRequiredError(
+        body.error || fallbackMessage,
+        body.upgrade
+      );
+    }
+    if (body.conflict) {
+      return new MutationConflictError(
+        body.error || fallbackMessage,
+        body.conflict
+      );
+    }
+    if (body.transition) {
+      return new StatusTransitionRejectedError(
+        body.error || fallbackMessage,
+        body.transition
+      );
+    }
+    return new Error(body.error || fallbackMessage);
+  } catch {
+    return new Error(fallbackMessage);
+  }
+}
+
+/**
+ * Electric sends timestamps in Postgres text format
+ * (`2026-01-01 10:00:00.123456+00`); the API expects RFC 3339. Converted as
+ * text to keep the microseconds the version is compared at.
+ */
+function toRfc3339(value: unknown): unknown {
+  if (typeof value !== 'string') return value;
+  const match = value.match(
+    /^(\d{4}-\d{2}-\d{2}) (\d{2}:\d{2}:\d{2}(?:\.\d+)?)([+-]\d{2})(?::?(\d{2}))?$/
+  );
+  if (!match) return value;
+  const [, date, time, offsetHours, offsetMinutes] = match;
+  return `${date}T${time}${offsetHours}:${offsetMinutes ?? '00'}`;
+}
+
+/**
+ * The changes of an update, plus the version of the row they were made
+ * against for versioned mutations.
+ */
+function versionedChanges(
+  mutation: MutationDefinition<unknown, unknown, unknown>,
+  mutationItem: MutationFnParams['transaction']['mutations'][number]
+): Record<string, unknown> {
+  const changes = { ...(mutationItem.changes as Record<string, unknown>) };
+  const column = mutation.versionColumn;
+  const original = mutationItem.original as Record<string, unknown> | undefined;
+  if (column && original && original[column] !== undefined) {
+    changes[`expected_${column}`] = toRfc3339(original[column]);
+  }
+  return changes;
+}
+
 function createFallbackSync(args: {
   sourceKey: string;
   shape: ShapeDefinition<unknown>;
@@ -630,17 +742,21 @@ function buildMutationHandlers(
       const txids = await Promise.all(
         transaction.mutations.map(async (mutationItem) => {
           const data = mutationItem.modified as Record<string, unknown>;
+          // The row's client-generated key makes retries replay the first
+          // response instead of creating a duplicate.
           const response = await makeRequest(mutation.url, {
             method: 'POST',
+            headers: {
+              'Idempotency-Key': `${mutation.name}:${mutationItem.key}`,
+            },
             body: JSON.stringify(data),
           });
 
           if (!response.ok) {
-            const message = await parseResponseError(
+            throw await parseMutationError(
               response,
               `Failed to create ${mutation.name}`
             );
-            throw new Error(message);
           }
 
           const result = (await response.json()) as { txid: number };
@@ -670,7 +786,7 @@ function buildMutationHandlers(
 
           return {
             id: String(mutationItem.key),
-            ...(mutationItem.changes as Record<string, unknown>),
+            ...versionedChanges(mutation, mutationItem),
           };
         });
 
@@ -680,11 +796,10 @@ function buildMutationHandlers(
         });
 
         if (!response.ok) {
-          const message = await parseResponseError(
+          throw await parseMutationError(
             response,
             `Failed to bulk update ${mutation.name}`
           );
-          throw new Error(message);
         }
 
         const result = (await response.json()) as { txid: number };
@@ -699,16 +814,15 @@ function buildMutationHandlers(
           `${mutation.url}/${mutationItem.key}`,
           {
             method: 'PATCH',
-            body: JSON.stringify(mutationItem.changes),
+            body: JSON.stringify(versionedChanges(mutation, mutationItem)),
           }
         );
 
         if (!response.ok) {
-          const message = await parseResponseError(
+          throw await parseMutationError(
             response,
             `Failed to update ${mutation.name}`
           );
-          throw new Error(message);
         }
 
         const result = (await response.json()) as { txid: number };
@@ -26,6 +26,7 @@ function user(userId: string): MessageSegment {
 
 function getMemberLabel(member?: OrganizationMemberWithProfile): string | null {
   if (!member) return null;
+  if (member.display_name?.trim()) return member.display_name;
 
   const fullName = [member.first_name, member.last_name]
     .filter((value): value is string => Boolean(value && value.trim()))
@@ -46,6 +47,20 @@ function formatPriority(priority?: string | null): string | null {
     .join(' ');
 }
 
+// Due dates are calendar days stored as midnight UTC.
+function formatDueDate(targetDate?: string | null): string | null {
+  if (!targetDate) return null;
+
+  const date = new Date(targetDate);
+  if (Number.isNaN(date.getTime())) return null;
+
+  return date.toLocaleDateString(undefined, {
+    month: 'long',
+    day: 'numeric',
+    timeZone: 'UTC',
+  });
+}
+
 function getActorSegments(group: GroupedNotification): MessageSegment[] {
   const actorId = getPayload(group.latest).actor_user_id;
   return actorId ? [user(actorId)] : [text('Someone')];
@@ -204,6 +219,37 @@ export function getGroupedNotificationSegments(
     }
     case 'issue_deleted':
       return [...actor, text(' deleted '), ...issueSegments];
+    case 'issue_entered_filter':
+    case 'issue_left_filter': {
+      const matchText =
+        group.latest.notification_type === 'issue_entered_filter'
+          ? ' now matches '
+          : ' no longer matches ';
+      const name = payload.subscription_name;
+      return [
+        ...issueSegments,
+        text(matchText),
+        ...(name
+          ? [text('your filter '), emphasis(name)]
+          : [text('one of your filters')]),
+      ];
+    }
+    case 'issue_due_soon': {
+      const dueDate = formatDueDate(payload.target_date);
+      if (dueDate) {
+        return [...issueSegments, text(' is due on '), emphasis(dueDate)];
+      }
+      return [...issueSegments, text(' is due soon')];
+    }
+    case 'issue_sla_breached': {
+      const name = payload.sla_policy_name;
+      return [
+        ...issueSegments,
+        ...(name
+          ? [text(' breached the SLA '), emphasis(name)]
+          : [text(' breached an SLA')]),
+      ];
+    }
     default:
       return [text('New notification')];
   }
@@ -1,17 +1,25 @@
 import type {
   AttachmentUrlResponse,
   AttachmentWithBlob,
+  CalendarFeedResponse,
   CommitAttachmentsRequest,
   CommitAttachmentsResponse,
   ConfirmUploadRequest,
   InitUploadRequest,
   InitUploadResponse,
-  ListRelayHostsResponse,
+  MarkNotificationsRequest,
+  MarkNotificationsResponse,
+  MergeTagsRequest,
+  MergeTagsResponse,
+  ProjectConcurrencySettings,
+  QueryDefinition,
   RelayHost,
+  ReorderIssueRequest,
   UpdateIssueRequest,
   UpdateProjectRequest,
   UpdateProjectStatusRequest,
 } from 'shared/remote-types';
+import { LIST_RELAY_HOSTS_QUERY } from 'shared/remote-types';
 import { getAuthRuntime } from '@/shared/lib/auth/runtime';
 import { syncRelayApiBaseWithRemote } from '@/shared/lib/relayBackendApi';
 
@@ -137,6 +145,130 @@ export async function bulkUpdateIssues(
   }
 }
 
+/**
+ * Move an issue next to its new neighbours; the server computes its
+ * `sort_order`.
+ */
+export async function reorderIssue(
+  issueId: string,
+  request: ReorderIssueRequest
+): Promise<void> {
+  const response = await makeRequest(`/v1/issues/${issueId}/reorder`, {
+    method: 'POST',
+    body: JSON.stringify(request),
+  });
+  if (!response.ok) {
+    throw await parseErrorResponse(response, 'Failed to reorder issue');
+  }
+}
+
+/**
+ * Replace project tags with one organization tag, retagging their issues.
+ */
+export async function mergeTags(
+  request: MergeTagsRequest
+): Promise<MergeTagsResponse> {
+  const response = await makeRequest('/v1/organization_tags/merge', {
+    method: 'POST',
+    body: JSON.stringify(request),
+  });
+  if (!response.ok) {
+    throw await parseErrorResponse(response, 'Failed to merge tags');
+  }
+  const body: { data: MergeTagsResponse } = await response.json();
+  return body.data;
+}
+
+/**
+ * Mark the current user's notifications read or unread in one request.
+ */
+export async function markNotifications(
+  request: MarkNotificationsRequest
+): Promise<MarkNotificationsResponse> {
+  const response = await makeRequest('/v1/notifications/mark', {
+    method: 'POST',
+    body: JSON.stringify(request),
+  });
+  if (!response.ok) {
+    throw await parseErrorResponse(response, 'Failed to mark notifications');
+  }
+  const body: { data: MarkNotificationsResponse } = await response.json();
+  return body.data;
+}
+
+/**
+ * Get the current user's secret iCalendar feed URL of due dates.
+ */
+export async function getCalendarFeed(): Promise<CalendarFeedResponse> {
+  const response = await makeRequest('/v1/calendar/feed');
+  if (!response.ok) {
+    throw await parseErrorResponse(response, 'Failed to load calendar feed');
+  }
+  return response.json();
+}
+
+/**
+ * Replace the calendar feed URL, revoking the previous one.
+ */
+export async function rotateCalendarFeed(): Promise<CalendarFeedResponse> {
+  const response = await makeRequest('/v1/calendar/feed/rotate', {
+    method: 'POST',
+  });
+  if (!response.ok) {
+    throw await parseErrorResponse(response, 'Failed to reset calendar feed');
+  }
+  return response.json();
+}
+
+export async function getProjectConcurrencySettings(
+  projectId: string
+): Promise<ProjectConcurrencySettings> {
+  const response = await makeRequest(`/v1/projects/${projectId}/concurrency`);
+  if (!response.ok) {
+    throw await parseErrorResponse(
+      response,
+      'Failed to load concurrency settings'
+    );
+  }
+  return response.json();
+}
+
+/**
+ * Choose whether issue and comment updates must name the version they were
+ * made against. Organization admins only.
+ */
+export async function updateProjectConcurrencySettings(
+  projectId: string,
+  settings: ProjectConcurrencySettings
+): Promise<ProjectConcurrencySettings> {
+  const response = await makeRequest(`/v1/projects/${projectId}/concurrency`, {
+    method: 'PUT',
+    body: JSON.stringify(settings),
+  });
+  if (!response.ok) {
+    throw await parseErrorResponse(
+      response,
+      'Failed to update concurrency settings'
+    );
+  }
+  return response.json();
+}
+
+export async function setIssueNotificationsMuted(
+  issueId: string,
+  muted: boolean
+): Promise<void> {
+  const response = await makeRequest(`/v1/notifications/mutes/${issueId}`, {
+    method: muted ? 'PUT' : 'DELETE',
+  });
+  if (!response.ok) {
+    throw await parseErrorResponse(
+      response,
+      muted ? 'Failed to mute issue' : 'Failed to unmute issue'
+    );
+  }
+}
+
 export interface BulkUpdateProjectStatusItem {
   id: string;
   changes: Partial<UpdateProjectStatusRequest>;
@@ -158,16 +290,52 @@ export async function bulkUpdateProjectStatuses(
 }
 
 // ---------------------------------------------------------------------------
-// Relay host API functions (served by remote backend)
+// Generated query definitions
 // ---------------------------------------------------------------------------
 
-export async function listRelayHosts(): Promise<RelayHost[]> {
-  const response = await makeRequest('/v1/hosts', { method: 'GET' });
+/**
+ * Run a read endpoint from the generated query definitions. GET queries send
+ * `params` as a query string, POST queries as a JSON body.
+ */
+export async function runQuery<TParams, TResponse>(
+  query: QueryDefinition<TParams, TResponse>,
+  params: TParams,
+  errorMessage = `Failed to run ${query.name}`
+): Promise<TResponse> {
+  let response: Response;
+  if (query.method === 'GET') {
+    const search = new URLSearchParams();
+    for (const [key, value] of Object.entries(params ?? {})) {
+      if (value !== undefined && value !== null) {
+        search.append(key, String(value));
+      }
+    }
+    const qs = search.toString();
+    response = await makeRequest(qs ? `${query.url}?${qs}` : query.url, {
+      method: 'GET',
+    });
+  } else {
+    response = await makeRequest(query.url, {
+      method: 'POST',
+      body: JSON.stringify(params),
+    });
+  }
   if (!response.ok) {
-    throw await parseErrorResponse(response, 'Failed to list relay hosts');
+    throw await parseErrorResponse(response, errorMessage);
   }
+  return (await response.json()) as TResponse;
+}
+
+// ---------------------------------------------------------------------------
+// Relay host API functions (served by remote backend)
+// ---------------------------------------------------------------------------
 
-  const body = (await response.json()) as ListRelayHostsResponse;
+export async function listRelayHosts(): Promise<RelayHost[]> {
+  const body = await runQuery(
+    LIST_RELAY_HOSTS_QUERY,
+    undefined,
+    'Failed to list relay hosts'
+  );
   return body.hosts;
 }
 
@@ -283,6 +451,7 @@ export async function confirmAttachmentUpload(
 ): Promise<AttachmentWithBlob> {
   const response = await makeRequest('/v1/attachments/confirm', {
     method: 'POST',
+    headers: { 'Idempotency-Key': `attachment-confirm:${params.upload_id}` },
     body: JSON.stringify(params),
   });
   if (!response.ok) {
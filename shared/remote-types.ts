@@ -10,9 +10,15 @@ export type Notification = { id: string, organization_id: string, user_id: strin
 
 export type NotificationGroupKind = "single" | "issue_changes" | "status_changes" | "comments" | "reactions" | "issue_deleted";
 
-export type NotificationPayload = { deeplink_path?: string | null, issue_id?: string | null, issue_simple_id?: string | null, issue_title?: string | null, actor_user_id?: string | null, comment_preview?: string | null, old_status_id?: string | null, new_status_id?: string | null, old_status_name?: string | null, new_status_name?: string | null, new_title?: string | null, old_priority?: IssuePriority | null, new_priority?: IssuePriority | null, assignee_user_id?: string | null, emoji?: string | null, };
+export type NotificationPayload = { deeplink_path?: string | null, issue_id?: string | null, issue_simple_id?: string | null, issue_title?: string | null, actor_user_id?: string | null, comment_preview?: string | null, old_status_id?: string | null, new_status_id?: string | null, old_status_name?: string | null, new_status_name?: string | null, new_title?: string | null, old_priority?: IssuePriority | null, new_priority?: IssuePriority | null, assignee_user_id?: string | null, emoji?: string | null, subscription_id?: string | null, subscription_name?: string | null, target_date?: string | null, sla_policy_id?: string | null, sla_policy_name?: string | null, };
 
-export type NotificationType = "issue_comment_added" | "issue_status_changed" | "issue_assignee_changed" | "issue_priority_changed" | "issue_unassigned" | "issue_comment_reaction" | "issue_deleted" | "issue_title_changed" | "issue_description_changed";
+export type NotificationType = "issue_comment_added" | "issue_status_changed" | "issue_assignee_changed" | "issue_priority_changed" | "issue_unassigned" | "issue_comment_reaction" | "issue_deleted" | "issue_title_changed" | "issue_description_changed" | "issue_entered_filter" | "issue_left_filter" | "issue_due_soon" | "issue_sla_breached";
+
+/**
+ * Unread notifications of a user in one organization, kept up to date by the
+ * database as notifications are created, read or deleted.
+ */
+export type NotificationUnreadCount = { id: string, user_id: string, organization_id: string, unread_count: number, updated_at: string, };
 
 export type Workspace = { id: string, project_id: string, owner_user_id: string, issue_id: string | null, local_workspace_id: string | null, name: string | null, archived: boolean, files_changed: number | null, lines_added: number | null, lines_removed: number | null, created_at: string, updated_at: string, };
 
@@ -20,20 +26,41 @@ export type ProjectStatus = { id: string, project_id: string, name: string, colo
 
 export type Tag = { id: string, project_id: string, name: string, color: string, };
 
+/**
+ * A tag shared by every project in an organization.
+ */
+export type OrganizationTag = { id: string, organization_id: string, name: string, color: string, };
+
 export type Issue = { id: string, project_id: string, issue_number: number, simple_id: string, status_id: string, title: string, description: string | null, priority: IssuePriority | null, start_date: string | null, target_date: string | null, completed_at: string | null, sort_order: number, parent_issue_id: string | null, parent_issue_sort_order: number | null, extension_metadata: JsonValue, creator_user_id: string | null, created_at: string, updated_at: string, };
 
 export type IssueAssignee = { id: string, issue_id: string, user_id: string, assigned_at: string, };
 
-export type Blob = { id: string, project_id: string, blob_path: string, thumbnail_blob_path: string | null, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, width: number | null, height: number | null, created_at: string, updated_at: string, };
+export type Blob = { id: string, project_id: string, blob_path: string, thumbnail_blob_path: string | null, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, width: number | null, height: number | null, 
+/**
+ * Playback length of audio and video, in milliseconds.
+ */
+duration_ms: number | null, created_at: string, updated_at: string, };
 
 export type Attachment = { id: string, blob_id: string, issue_id: string | null, comment_id: string | null, created_at: string, expires_at: string | null, };
 
-export type AttachmentWithBlob = { id: string, blob_id: string, issue_id: string | null, comment_id: string | null, created_at: string, expires_at: string | null, blob_path: string, thumbnail_blob_path: string | null, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, width: number | null, height: number | null, };
+/**
+ * An attachment with its associated blob data (for API responses).
+ *
+ * `mime_type` is detected from the file's content where it is recognized,
+ * `width` and `height` are set for images and video, and `duration_ms` for
+ * audio and video, so clients can pick a preview or player up front.
+ */
+export type AttachmentWithBlob = { id: string, blob_id: string, issue_id: string | null, comment_id: string | null, created_at: string, expires_at: string | null, blob_path: string, thumbnail_blob_path: string | null, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, width: number | null, height: number | null, duration_ms: number | null, };
 
 export type IssueFollower = { id: string, issue_id: string, user_id: string, };
 
 export type IssueTag = { id: string, issue_id: string, tag_id: string, };
 
+/**
+ * An issue tagged with an organization tag.
+ */
+export type IssueOrganizationTag = { id: string, issue_id: string, organization_tag_id: string, };
+
 export type IssueRelationship = { id: string, issue_id: string, related_issue_id: string, relationship_type: IssueRelationshipType, created_at: string, };
 
 export type IssueRelationshipType = "blocking" | "related" | "has_duplicate";
@@ -46,12 +73,120 @@ export type IssuePriority = "urgent" | "high" | "medium" | "low";
 
 export type IssueSortField = "sort_order" | "priority" | "created_at" | "updated_at" | "title";
 
-export type ListIssuesQuery = { project_id: string, };
+export type ListIssuesQuery = { project_id: string, 
+/**
+ * Comma-separated issue fields to return, e.g. `title,status_id`. All
+ * fields are returned when unset.
+ */
+fields?: string, };
 
-export type SearchIssuesRequest = { project_id: string, status_id?: string, status_ids?: Array<string>, priority?: IssuePriority, parent_issue_id?: string, search?: string, simple_id?: string, assignee_user_id?: string, tag_id?: string, tag_ids?: Array<string>, sort_field?: IssueSortField, sort_direction?: SortDirection, limit?: number, offset?: number, };
+export type SearchIssuesRequest = { project_id: string, status_id?: string, status_ids?: Array<string>, priority?: IssuePriority, parent_issue_id?: string, search?: string, simple_id?: string, assignee_user_id?: string, tag_id?: string, tag_ids?: Array<string>, sort_field?: IssueSortField, sort_direction?: SortDirection, limit?: number, offset?: number, 
+/**
+ * Comma-separated issue fields to return, e.g. `title,status_id`. All
+ * fields are returned when unset.
+ */
+fields?: string, };
 
 export type ListIssuesResponse = { issues: Array<Issue>, total_count: number, limit: number, offset: number, };
 
+export type ListProjectsQuery = { organization_id: string, };
+
+export type ListProjectsResponse = { projects: Array<Project>, };
+
+export type ListTagsQuery = { project_id: string, };
+
+export type ListTagsResponse = { tags: Array<Tag>, };
+
+export type ListOrganizationTagsQuery = { organization_id: string, };
+
+export type ListOrganizationTagsResponse = { organization_tags: Array<OrganizationTag>, };
+
+/**
+ * Project tags sharing a name across more than one project of an
+ * organization - candidates for merging into an organization tag.
+ */
+export type DuplicateTagGroup = { name: string, tags: Array<Tag>, };
+
+export type ListDuplicateTagsResponse = { groups: Array<DuplicateTagGroup>, };
+
+export type ListProjectStatusesQuery = { project_id: string, };
+
+export type ListProjectStatusesResponse = { project_statuses: Array<ProjectStatus>, };
+
+/**
+ * Where a status sits in a project's workflow.
+ */
+export type ProjectStatusCategory = "backlog" | "in_progress" | "done";
+
+/**
+ * Workflow rules of a status.
+ */
+export type ProjectStatusWorkflow = { status_id: string, category: ProjectStatusCategory | null, 
+/**
+ * Statuses issues in this status may move to. Empty allows any status.
+ */
+allowed_next_status_ids: Array<string>, };
+
+export type ListProjectStatusWorkflowsQuery = { project_id: string, };
+
+export type ListProjectStatusWorkflowsResponse = { workflows: Array<ProjectStatusWorkflow>, };
+
+export type UpdateProjectStatusWorkflowRequest = { category: ProjectStatusCategory | null | null, 
+/**
+ * Replaces the allowed transitions; an empty list allows any status.
+ */
+allowed_next_status_ids: Array<string> | null, };
+
+/**
+ * Sent as `transition` in the body of a 422 response when an issue is
+ * moved to a status its current status doesn't lead to.
+ */
+export type StatusTransitionError = { issue_id: string, from_status_id: string, to_status_id: string, allowed_status_ids: Array<string>, };
+
+export type ListIssueAssigneesQuery = { issue_id: string, };
+
+export type ListIssueAssigneesResponse = { issue_assignees: Array<IssueAssignee>, };
+
+export type ListIssueFollowersQuery = { issue_id: string, };
+
+export type ListIssueFollowersResponse = { issue_followers: Array<IssueFollower>, };
+
+export type ListIssueTagsQuery = { issue_id: string, };
+
+export type ListIssueTagsResponse = { issue_tags: Array<IssueTag>, };
+
+export type ListIssueOrganizationTagsQuery = { issue_id: string, };
+
+export type ListIssueOrganizationTagsResponse = { issue_organization_tags: Array<IssueOrganizationTag>, };
+
+export type ListIssueRelationshipsQuery = { issue_id: string, };
+
+export type ListIssueRelationshipsResponse = { issue_relationships: Array<IssueRelationship>, };
+
+export type ListIssueCommentsQuery = { issue_id: string, };
+
+export type ListIssueCommentsResponse = { issue_comments: Array<IssueComment>, };
+
+export type ListIssueCommentReactionsQuery = { comment_id: string, };
+
+export type ListIssueCommentReactionsResponse = { issue_comment_reactions: Array<IssueCommentReaction>, };
+
+export type UnreadNotificationCountQuery = { 
+/**
+ * Count only this organization's notifications; all organizations if
+ * not provided.
+ */
+organization_id?: string, };
+
+export type UnreadNotificationCountResponse = { unread_count: number, };
+
+/**
+ * An issue the user gets no notifications about.
+ */
+export type NotificationIssueMute = { issue_id: string, user_id: string, created_at: string, };
+
+export type ListNotificationIssueMutesResponse = { mutes: Array<NotificationIssueMute>, };
+
 export type PullRequestStatus = "open" | "merged" | "closed";
 
 export type PullRequest = { id: string, url: string, number: number, status: PullRequestStatus, merged_at: string | null, merge_commit_sha: string | null, target_branch_name: string, project_id: string, issue_id: string, workspace_id: string | null, created_at: string, updated_at: string, };
@@ -71,6 +206,38 @@ export type UserData = { user_id: string, first_name: string | null, last_name:
 
 export type User = { id: string, email: string, first_name: string | null, last_name: string | null, username: string | null, created_at: string, updated_at: string, };
 
+/**
+ * The requester's profile settings.
+ */
+export type UserProfile = { user_id: string, email: string, first_name: string | null, last_name: string | null, username: string | null, 
+/**
+ * Shown instead of the first and last name when set.
+ */
+display_name: string | null, avatar_attachment_id: string | null, 
+/**
+ * Signed URL of the uploaded avatar, or the OAuth provider's avatar.
+ */
+avatar_url: string | null, 
+/**
+ * IANA time zone such as `Europe/Berlin`. Due-date reminders are sent
+ * in it, or in UTC when unset.
+ */
+timezone: string | null, 
+/**
+ * BCP 47 tag selecting notification and email templates.
+ */
+locale: string | null, };
+
+/**
+ * Fields left out are unchanged; `null` clears them.
+ */
+export type UpdateUserProfileRequest = { display_name?: string | null | null, 
+/**
+ * An image uploaded through the attachments API and not yet attached
+ * to anything.
+ */
+avatar_attachment_id?: string | null | null, timezone?: string | null | null, locale?: string | null | null, };
+
 export type RelayHost = { id: string, owner_user_id: string, machine_id: string, name: string, status: string, last_seen_at: string | null, agent_version: string | null, created_at: string, updated_at: string, access_role: string, };
 
 export type ListRelayHostsResponse = { hosts: Array<RelayHost>, };
@@ -81,6 +248,25 @@ export enum MemberRole { ADMIN = "ADMIN", MEMBER = "MEMBER" }
 
 export type OrganizationMember = { organization_id: string, user_id: string, role: MemberRole, joined_at: string, last_seen_at: string | null, };
 
+/**
+ * The billing plan an organization's entitlements come from. `unlimited`
+ * when billing is not enabled on the server.
+ */
+export type BillingPlan = "free" | "paid" | "unlimited";
+
+export type PlanEntitlement = "max_projects" | "max_members" | "webhooks";
+
+/**
+ * What an organization's plan allows. Limits are absent when unlimited.
+ */
+export type PlanEntitlements = { plan: BillingPlan, max_projects?: number, max_members?: number, webhooks: boolean, };
+
+/**
+ * Sent as `upgrade` in the body of a 402 response when an action needs a
+ * higher plan.
+ */
+export type UpgradeRequiredError = { entitlement: PlanEntitlement, plan: BillingPlan, limit?: number, current?: number, };
+
 export type CreateProjectRequest = { 
 /**
  * Optional client-generated ID. If not provided, server generates one.
@@ -90,8 +276,26 @@ id?: string, organization_id: string, name: string, color: string, };
 
 export type UpdateProjectRequest = { name: string | null, color: string | null, sort_order: number | null, };
 
+/**
+ * How a project guards against concurrent edits.
+ */
+export type ProjectConcurrencySettings = { 
+/**
+ * Reject issue and comment updates that don't name the version they
+ * were made against, through `If-Match` or `expected_updated_at`.
+ */
+require_if_match: boolean, };
+
 export type UpdateNotificationRequest = { seen: boolean | null, };
 
+/**
+ * Mark the caller's notifications read or unread. Without
+ * `notification_ids`, applies to every notification matching the filters.
+ */
+export type MarkNotificationsRequest = { seen: boolean, notification_ids?: Array<string>, organization_id?: string, issue_id?: string, };
+
+export type MarkNotificationsResponse = { updated_count: number, };
+
 export type CreateTagRequest = { 
 /**
  * Optional client-generated ID. If not provided, server generates one.
@@ -101,6 +305,42 @@ id?: string, project_id: string, name: string, color: string, };
 
 export type UpdateTagRequest = { name: string | null, color: string | null, };
 
+export type CreateOrganizationTagRequest = { 
+/**
+ * Optional client-generated ID. If not provided, server generates one.
+ * Using client-generated IDs enables stable optimistic updates.
+ */
+id?: string, organization_id: string, name: string, color: string, };
+
+export type UpdateOrganizationTagRequest = { name: string | null, color: string | null, };
+
+/**
+ * Replace project tags with one organization tag. Issues tagged with any of
+ * the project tags are tagged with the organization tag instead.
+ */
+export type MergeTagsRequest = { organization_id: string, 
+/**
+ * Project tags to merge; they may belong to any project of the
+ * organization.
+ */
+tag_ids: Array<string>, 
+/**
+ * Organization tag to merge into. If not provided, one is created (or
+ * reused, if it exists) named after the first project tag.
+ */
+organization_tag_id?: string, 
+/**
+ * Color for a newly created organization tag. Defaults to the color of
+ * the first project tag.
+ */
+color?: string, };
+
+export type MergeTagsResponse = { organization_tag: OrganizationTag, removed_tag_ids: Array<string>, 
+/**
+ * Issues newly tagged with the organization tag.
+ */
+retagged_issue_count: number, };
+
 export type CreateProjectStatusRequest = { 
 /**
  * Optional client-generated ID. If not provided, server generates one.
@@ -117,7 +357,31 @@ export type CreateIssueRequest = {
  */
 id?: string, project_id: string, status_id: string, title: string, description: string | null, priority: IssuePriority | null, start_date: string | null, target_date: string | null, completed_at: string | null, sort_order: number, parent_issue_id: string | null, parent_issue_sort_order: number | null, extension_metadata: JsonValue, };
 
-export type UpdateIssueRequest = { status_id?: string | null, title?: string | null, description?: string | null | null, priority?: IssuePriority | null | null, start_date?: string | null | null, target_date?: string | null | null, completed_at?: string | null | null, sort_order?: number | null, parent_issue_id?: string | null | null, parent_issue_sort_order?: number | null | null, extension_metadata?: JsonValue | null, };
+export type UpdateIssueRequest = { status_id?: string | null, title?: string | null, description?: string | null | null, priority?: IssuePriority | null | null, start_date?: string | null | null, target_date?: string | null | null, completed_at?: string | null | null, sort_order?: number | null, parent_issue_id?: string | null | null, parent_issue_sort_order?: number | null | null, extension_metadata?: JsonValue | null, 
+/**
+ * `updated_at` of the issue the update was made against. If the issue
+ * has changed since, the update is rejected with a `MutationConflict`.
+ */
+expected_updated_at?: string | null, };
+
+/**
+ * Move an issue within or between status columns. The server picks the new
+ * `sort_order` from the issue's neighbours in the target column.
+ */
+export type ReorderIssueRequest = { 
+/**
+ * Column to move the issue to; defaults to its current status.
+ */
+status_id?: string, 
+/**
+ * Issue to place it directly after.
+ */
+previous_issue_id?: string, 
+/**
+ * Issue to place it directly before, used when `previous_issue_id` is
+ * not set. With neither, the issue goes to the end of the column.
+ */
+next_issue_id?: string, };
 
 export type CreateIssueAssigneeRequest = { 
 /**
@@ -140,6 +404,13 @@ export type CreateIssueTagRequest = {
  */
 id?: string, issue_id: string, tag_id: string, };
 
+export type CreateIssueOrganizationTagRequest = { 
+/**
+ * Optional client-generated ID. If not provided, server generates one.
+ * Using client-generated IDs enables stable optimistic updates.
+ */
+id?: string, issue_id: string, organization_tag_id: string, };
+
 export type CreateIssueRelationshipRequest = { 
 /**
  * Optional client-generated ID. If not provided, server generates one.
@@ -154,7 +425,13 @@ export type CreateIssueCommentRequest = {
  */
 id?: string, issue_id: string, message: string, parent_id: string | null, };
 
-export type UpdateIssueCommentRequest = { message: string | null, parent_id: string | null | null, };
+export type UpdateIssueCommentRequest = { message: string | null, parent_id: string | null | null, 
+/**
+ * `updated_at` of the comment the update was made against. If the
+ * comment has changed since, the update is rejected with a
+ * `MutationConflict`.
+ */
+expected_updated_at?: string | null, };
 
 export type CreateIssueCommentReactionRequest = { 
 /**
@@ -165,6 +442,41 @@ id?: string, comment_id: string, emoji: string, };
 
 export type UpdateIssueCommentReactionRequest = { emoji: string | null, };
 
+/**
+ * Sent as `conflict` in the body of a 409 response when an update was made
+ * against an outdated version of a row, so the client can merge instead of
+ * overwriting.
+ */
+export type MutationConflict<T> = { 
+/**
+ * The row as currently stored.
+ */
+server: T, 
+/**
+ * The update as sent.
+ */
+client: JsonValue, 
+/**
+ * Fields the update sets to values that differ from the stored row.
+ */
+conflicting_fields: Array<string>, };
+
+/**
+ * One update in a batch, tagged with the table it applies to.
+ */
+export type BatchMutation = { "table": "issues", id: string, changes: UpdateIssueRequest, } | { "table": "projects", id: string, changes: UpdateProjectRequest, } | { "table": "project_statuses", id: string, changes: UpdateProjectStatusRequest, };
+
+/**
+ * Mutations applied in one transaction: either all of them succeed or none
+ * are applied.
+ */
+export type BatchMutationRequest = { mutations: Array<BatchMutation>, };
+
+/**
+ * The updated row of one mutation, in request order.
+ */
+export type BatchMutationResult = { "table": "issues", "data": Issue } | { "table": "projects", "data": Project } | { "table": "project_statuses", "data": ProjectStatus };
+
 export type InitUploadRequest = { project_id: string, filename: string, size_bytes: number, hash: string, };
 
 export type InitUploadResponse = { upload_url: string, upload_id: string, expires_at: string, skip_upload: boolean, existing_blob_id: string | null, };
@@ -183,6 +495,495 @@ export type ExportRequest = { organization_id: string,
  */
 project_ids: Array<string>, include_attachments: boolean, };
 
+export type CalendarFeedResponse = { 
+/**
+ * Secret iCalendar URL of the user's assigned issues' due dates.
+ */
+url: string, };
+
+export type IssueTriageRequest = { project_id: string, title: string, description?: string, 
+/**
+ * Set when triaging an issue that already exists, so it isn't suggested
+ * as a duplicate of itself.
+ */
+issue_id?: string, };
+
+/**
+ * Suggestions for the user to confirm; nothing is applied to the issue.
+ */
+export type IssueTriageSuggestions = { tags: Array<SuggestedTag>, assignee: SuggestedAssignee | null, priority: SuggestedPriority | null, duplicates: Array<SuggestedDuplicate>, };
+
+export type SuggestedTag = { tag_id: string, name: string, reason: string, };
+
+export type SuggestedAssignee = { user_id: string, reason: string, };
+
+export type SuggestedPriority = { priority: IssuePriority, reason: string, };
+
+export type SuggestedDuplicate = { issue_id: string, simple_id: string, title: string, reason: string, };
+
+export type ReleaseNotesRequest = { project_id: string, 
+/**
+ * Issues completed at or after this time are included.
+ */
+from: string, 
+/**
+ * Issues completed before this time are included.
+ */
+to: string, 
+/**
+ * Tags to group by, in section order. When left out, each issue goes
+ * under its first tag by name.
+ */
+tag_ids?: Array<string>, };
+
+export type ReleaseNotes = { sections: Array<ReleaseNotesSection>, 
+/**
+ * The notes rendered as Markdown.
+ */
+markdown: string, };
+
+/**
+ * Issues grouped under a tag; `tag_id` is unset for issues without one.
+ */
+export type ReleaseNotesSection = { tag_id: string | null, title: string, issues: Array<ReleaseNotesIssue>, };
+
+export type ReleaseNotesIssue = { issue_id: string, simple_id: string, title: string, completed_at: string, 
+/**
+ * Pull requests linked to the issue, leaving out closed ones.
+ */
+pull_requests: Array<ReleaseNotesPullRequest>, };
+
+export type ReleaseNotesPullRequest = { number: number, url: string, };
+
+/**
+ * Scheduling data for a project's roadmap view.
+ */
+export type ProjectTimeline = { project_id: string, 
+/**
+ * When the schedule was computed; open issues without a start date
+ * are scheduled from then.
+ */
+computed_at: string, items: Array<TimelineItem>, dependencies: Array<TimelineDependency>, 
+/**
+ * Open issues that would delay the project's end if they slipped, in
+ * schedule order.
+ */
+critical_path: Array<string>, 
+/**
+ * Open issues in or behind a cycle of blocking relationships, which
+ * can't be scheduled.
+ */
+cyclic_issue_ids: Array<string>, };
+
+export type TimelineItem = { issue_id: string, simple_id: string, title: string, parent_issue_id: string | null, start_date: string | null, target_date: string | null, completed_at: string | null, 
+/**
+ * Earliest the issue can start given its start date and blockers.
+ * Scheduling fields are unset for completed and cyclic issues.
+ */
+scheduled_start: string | null, scheduled_end: string | null, 
+/**
+ * Latest the issue can start without delaying the project's end.
+ */
+latest_start: string | null, critical: boolean, 
+/**
+ * The scheduled end is after the target date.
+ */
+late: boolean, };
+
+/**
+ * `blocker_id` has to be done before `blocked_id` can start.
+ */
+export type TimelineDependency = { blocker_id: string, blocked_id: string, };
+
+export type WorkLog = { id: string, issue_id: string, user_id: string, minutes: number, note: string | null, 
+/**
+ * Day the work was done.
+ */
+work_date: string, created_at: string, updated_at: string, };
+
+export type CreateWorkLogRequest = { minutes: number, note?: string, 
+/**
+ * Defaults to today (UTC).
+ */
+work_date?: string, };
+
+export type UpdateWorkLogRequest = { minutes?: number | null, note?: string | null | null, work_date?: string | null, };
+
+export type ListWorkLogsResponse = { work_logs: Array<WorkLog>, total_minutes: bigint, };
+
+/**
+ * Filters for summing logged time across a project. `from` and `to` are
+ * inclusive work dates, e.g. a sprint's first and last day.
+ */
+export type WorkLogSummaryQuery = { project_id: string, from?: string, to?: string, 
+/**
+ * Only time logged by this user.
+ */
+user_id?: string, };
+
+export type WorkLogSummary = { total_minutes: bigint, 
+/**
+ * Most time first.
+ */
+by_issue: Array<IssueWorkTotal>, 
+/**
+ * Most time first.
+ */
+by_user: Array<UserWorkTotal>, };
+
+export type IssueWorkTotal = { issue_id: string, simple_id: string, title: string, minutes: bigint, };
+
+export type UserWorkTotal = { user_id: string, name: string, minutes: bigint, };
+
+export type WorkTimer = { user_id: string, issue_id: string, note: string | null, started_at: string, };
+
+export type StartWorkTimerRequest = { note?: string, };
+
+/**
+ * A saved issue filter; its owner is notified when issues enter or leave
+ * its results.
+ *
+ * Filters are space-separated terms that all have to match: `tag:`,
+ * `status:`, `priority:` and `assignee:` take comma-separated values of
+ * which any may match, `is:open` and `is:completed` match on completion, and
+ * other words match the title or ID. Quote values with spaces
+ * (`status:"In review"`) and prefix a term with `-` to negate it.
+ * `assignee:` takes a username, an email, `me` or `none`.
+ */
+export type QuerySubscription = { id: string, project_id: string, user_id: string, name: string, query: string, created_at: string, updated_at: string, };
+
+export type CreateQuerySubscriptionRequest = { project_id: string, name: string, query: string, };
+
+export type UpdateQuerySubscriptionRequest = { name?: string | null, query?: string | null, };
+
+export type ListQuerySubscriptionsQuery = { project_id?: string, };
+
+export type ListQuerySubscriptionsResponse = { subscriptions: Array<QuerySubscription>, };
+
+export type QuerySubscriptionIssuesResponse = { issue_ids: Array<string>, };
+
+/**
+ * A markdown page in a project.
+ *
+ * Issues mentioned by simple ID (`APP-12`) are linked to the document, and
+ * attachments embedded as `attachment://<id>` are kept for as long as the
+ * content embeds them.
+ */
+export type Document = { id: string, project_id: string, title: string, content: string, 
+/**
+ * Incremented on every save.
+ */
+revision: number, created_by: string | null, updated_by: string | null, created_at: string, updated_at: string, issue_ids: Array<string>, attachment_ids: Array<string>, };
+
+/**
+ * A document without its content, for listings.
+ */
+export type DocumentSummary = { id: string, project_id: string, title: string, revision: number, updated_by: string | null, updated_at: string, };
+
+export type CreateDocumentRequest = { project_id: string, title: string, content?: string, };
+
+export type UpdateDocumentRequest = { 
+/**
+ * The revision the edit was made against. The update is rejected if the
+ * document has been saved since.
+ */
+revision: number, title?: string | null, content?: string | null, };
+
+export type ListDocumentsResponse = { documents: Array<DocumentSummary>, };
+
+export type SearchDocumentsRequest = { project_id: string, 
+/**
+ * Matched against titles and content.
+ */
+search: string, limit?: bigint, };
+
+export type DocumentRevision = { document_id: string, revision: number, title: string, content: string, author_user_id: string | null, created_at: string, };
+
+export type DocumentRevisionSummary = { revision: number, title: string, author_user_id: string | null, created_at: string, };
+
+export type ListDocumentRevisionsResponse = { 
+/**
+ * Newest first.
+ */
+revisions: Array<DocumentRevisionSummary>, };
+
+/**
+ * Markdown to render for display in a project.
+ *
+ * `#123` links to the project's issue number 123 and `@username` mentions an
+ * organization member. References that don't resolve are left as text.
+ */
+export type RenderMarkdownRequest = { project_id: string, markdown: string, };
+
+export type RenderedMarkdown = { 
+/**
+ * Sanitized HTML. Raw HTML in the source is escaped, and code blocks are
+ * highlighted with `hl-`-prefixed classes for the client's theme.
+ */
+html: string, 
+/**
+ * Issues linked from `#123` references.
+ */
+issue_ids: Array<string>, 
+/**
+ * Users mentioned with `@username`.
+ */
+mentioned_user_ids: Array<string>, };
+
+export type IssueCodeReferenceKind = "commit" | "pull_request";
+
+/**
+ * A commit or pull request on GitHub whose message, title or body mentions
+ * the issue by simple ID.
+ */
+export type IssueCodeReference = { id: string, issue_id: string, kind: IssueCodeReferenceKind, 
+/**
+ * `owner/name` of the repository.
+ */
+repo_full_name: string, 
+/**
+ * The commit SHA or pull request number.
+ */
+reference: string, url: string, 
+/**
+ * The commit's first line or the pull request's title.
+ */
+title: string, author_login: string | null, 
+/**
+ * When the commit was made or the pull request opened.
+ */
+referenced_at: string, created_at: string, };
+
+export type ListIssueCodeReferencesResponse = { 
+/**
+ * Most recent first.
+ */
+references: Array<IssueCodeReference>, };
+
+/**
+ * A project's copy for one notification type in one locale, used in place
+ * of the built-in English text for recipients whose locale selects it.
+ *
+ * Templates may use `{actor}`, `{issue}` and `{issue_title}`, plus
+ * placeholders specific to the notification type: `{comment}` for
+ * comments, `{old_status}` and `{new_status}`, `{old_priority}` and
+ * `{new_priority}`, `{new_title}`, `{emoji}` for reactions, `{filter}`
+ * for saved filters, `{due_date}` for reminders and `{sla_policy}` for SLA
+ * breaches. Write `{{` and `}}` for literal braces.
+ */
+export type NotificationTemplate = { id: string, project_id: string, notification_type: NotificationType, 
+/**
+ * BCP 47 tag such as `de` or `pt-BR`.
+ */
+locale: string, title: string, body: string | null, created_at: string, updated_at: string, };
+
+/**
+ * Create the template for the type and locale, or replace it.
+ */
+export type UpsertNotificationTemplateRequest = { notification_type: NotificationType, locale: string, title: string, body?: string, };
+
+export type ListNotificationTemplatesResponse = { 
+/**
+ * Locale for members who have not chosen one.
+ */
+default_locale: string | null, templates: Array<NotificationTemplate>, };
+
+export type UpdateProjectNotificationLocaleRequest = { 
+/**
+ * `null` clears the default.
+ */
+default_locale: string | null, };
+
+export type ProjectNotificationLocale = { project_id: string, default_locale: string | null, };
+
+/**
+ * The hours SLA targets are counted in. Organizations that never set them
+ * work Monday to Friday, 09:00 to 17:00 UTC.
+ */
+export type OrganizationWorkingHours = { organization_id: string, 
+/**
+ * IANA time zone such as `Europe/Berlin`.
+ */
+timezone: string, 
+/**
+ * Minutes after local midnight the working day starts.
+ */
+start_minute: number, 
+/**
+ * Minutes after local midnight the working day ends, at most 1440.
+ */
+end_minute: number, 
+/**
+ * ISO weekdays, 1 for Monday through 7 for Sunday.
+ */
+working_days: Array<number>, };
+
+export type UpdateOrganizationWorkingHoursRequest = { timezone: string, start_minute: number, end_minute: number, working_days: Array<number>, };
+
+/**
+ * `first_response` is met when someone other than the creator comments or
+ * the issue is completed; `resolution` when it is completed.
+ */
+export type SlaMetric = "first_response" | "resolution";
+
+/**
+ * A target for issues matching `query`, written in the saved filter syntax
+ * (see [`crate::QuerySubscription`]); `assignee:me` matches nothing. The
+ * target is counted in working minutes from when the issue was created.
+ */
+export type SlaPolicy = { id: string, project_id: string, name: string, query: string, metric: SlaMetric, target_minutes: number, created_at: string, updated_at: string, };
+
+export type CreateSlaPolicyRequest = { name: string, query: string, metric: SlaMetric, target_minutes: number, };
+
+export type UpdateSlaPolicyRequest = { name?: string | null, query?: string | null, metric?: SlaMetric | null, target_minutes?: number | null, };
+
+export type ListSlaPoliciesResponse = { policies: Array<SlaPolicy>, };
+
+/**
+ * Issues created in `[from, to)` are reported; the last 30 days when not
+ * given.
+ */
+export type SlaReportQuery = { from?: string, to?: string, };
+
+/**
+ * `pending` issues haven't met the target, and their deadline hasn't passed.
+ */
+export type SlaState = "met" | "breached" | "pending";
+
+export type SlaIssueStatus = { issue_id: string, simple_id: string, title: string, created_at: string, deadline: string, 
+/**
+ * When the issue was first responded to or completed, per the metric.
+ */
+met_at: string | null, state: SlaState, };
+
+export type SlaPolicyReport = { policy: SlaPolicy, met: number, breached: number, pending: number, issues: Array<SlaIssueStatus>, };
+
+/**
+ * Policies are evaluated against the issues' current tags, status and
+ * priority.
+ */
+export type SlaReport = { project_id: string, from: string, to: string, working_hours: OrganizationWorkingHours, policies: Array<SlaPolicyReport>, };
+
+/**
+ * How long an organization keeps data, in years. Closed issues are deleted
+ * that long after they were completed, attachments that long after they were
+ * uploaded; `None` keeps them forever, which is also the default.
+ */
+export type OrganizationRetentionPolicy = { organization_id: string, closed_issue_retention_years: number | null, attachment_retention_years: number | null, };
+
+export type UpdateRetentionPolicyRequest = { closed_issue_retention_years: number | null, attachment_retention_years: number | null, };
+
+/**
+ * Nothing in a project on legal hold is deleted, by retention policies or
+ * by anyone, until the hold is released.
+ */
+export type LegalHold = { project_id: string, reason: string, placed_by: string | null, placed_at: string, };
+
+export type PlaceLegalHoldRequest = { reason: string, };
+
+export type ListLegalHoldsResponse = { holds: Array<LegalHold>, };
+
+/**
+ * What the retention policy would delete from a project now. Attachments
+ * include those of the closed issues being deleted. Nothing is deleted
+ * from projects on legal hold.
+ */
+export type RetentionProjectReport = { project_id: string, project_name: string, legal_hold: boolean, closed_issues: number, attachments: number, };
+
+/**
+ * A dry run of the retention policy. Closed issues completed and
+ * attachments uploaded before the cutoffs are due for deletion; the totals
+ * leave out projects on legal hold.
+ */
+export type RetentionReport = { policy: OrganizationRetentionPolicy, generated_at: string, closed_issues_cutoff: string | null, attachments_cutoff: string | null, closed_issues: number, attachments: number, projects: Array<RetentionProjectReport>, };
+
+/**
+ * `export` archives the user's personal data; `deletion` deletes their
+ * account and anonymizes what they authored.
+ */
+export type AccountDataJobKind = "export" | "deletion";
+
+export type AccountDataJobStatus = "pending" | "running" | "completed" | "failed";
+
+export type AccountDataJob = { id: string, kind: AccountDataJobKind, status: AccountDataJobStatus, 
+/**
+ * Why the job failed.
+ */
+error: string | null, created_at: string, started_at: string | null, completed_at: string | null, 
+/**
+ * A short-lived link to a completed export's archive, until it expires.
+ */
+download_url: string | null, archive_expires_at: string | null, };
+
+export type ListAccountDataJobsResponse = { jobs: Array<AccountDataJob>, };
+
+/**
+ * The account's email address, to confirm the deletion.
+ */
+export type DeleteAccountRequest = { email: string, };
+
+/**
+ * CIDR ranges, such as `203.0.113.0/24`, the organization's data may be
+ * reached from. Empty allows any address.
+ */
+export type OrganizationIpAllowlist = { organization_id: string, ranges: Array<string>, };
+
+/**
+ * Single addresses are accepted as ranges of one.
+ */
+export type UpdateIpAllowlistRequest = { ranges: Array<string>, };
+
+/**
+ * Sent with a 403 when the request's address isn't on the allowlist of the
+ * organization it reaches.
+ */
+export type IpNotAllowedError = { organization_id: string, 
+/**
+ * `None` when the address couldn't be determined.
+ */
+ip_address: string | null, };
+
+/**
+ * `location_change` is a session used from a country it wasn't used from
+ * before; `rate_spike` is a session making far more requests than usual.
+ */
+export type SessionAnomalyKind = "location_change" | "rate_spike";
+
+export type SessionFlagStatus = "open" | "dismissed" | "revoked";
+
+/**
+ * Unusual use of a member's session, open until an admin reviews it.
+ */
+export type SessionFlag = { id: string, session_id: string, user_id: string, kind: SessionAnomalyKind, status: SessionFlagStatus, description: string, 
+/**
+ * Whether the session was suspended until the flag is reviewed.
+ */
+suspended: boolean, detected_at: string, reviewed_by: string | null, reviewed_at: string | null, };
+
+export type ListSessionFlagsQuery = { 
+/**
+ * Only flags with this status; all flags if not provided.
+ */
+status?: SessionFlagStatus, };
+
+export type ListSessionFlagsResponse = { flags: Array<SessionFlag>, };
+
+/**
+ * `dismiss` lifts the suspension the flag caused; `revoke` signs the
+ * session out for good.
+ */
+export type SessionFlagResolution = "dismiss" | "revoke";
+
+export type ReviewSessionFlagRequest = { resolution: SessionFlagResolution, };
+
+/**
+ * With `auto_suspend`, a member's flagged session stops working until an
+ * admin reviews the flag.
+ */
+export type SessionAnomalySettings = { organization_id: string, auto_suspend: boolean, };
+
+export type UpdateSessionAnomalySettingsRequest = { auto_suspend: boolean, };
+
 // Shape definition interface
 export interface ShapeDefinition<T> {
   readonly table: string;
@@ -217,6 +1018,13 @@ export const NOTIFICATIONS_SHAPE = defineShape<Notification>(
   '/v1/fallback/notifications'
 );
 
+export const NOTIFICATION_UNREAD_COUNTS_SHAPE = defineShape<NotificationUnreadCount>(
+  'notification_unread_counts',
+  ['user_id'] as const,
+  '/v1/shape/notification_unread_counts',
+  '/v1/fallback/notification_unread_counts'
+);
+
 export const ORGANIZATION_MEMBERS_SHAPE = defineShape<OrganizationMember>(
   'organization_member_metadata',
   ['organization_id'] as const,
@@ -231,6 +1039,13 @@ export const USERS_SHAPE = defineShape<User>(
   '/v1/fallback/users'
 );
 
+export const ORGANIZATION_TAGS_SHAPE = defineShape<OrganizationTag>(
+  'organization_tags',
+  ['organization_id'] as const,
+  '/v1/shape/organization_tags',
+  '/v1/fallback/organization_tags'
+);
+
 export const PROJECT_TAGS_SHAPE = defineShape<Tag>(
   'tags',
   ['project_id'] as const,
@@ -287,6 +1102,13 @@ export const PROJECT_ISSUE_TAGS_SHAPE = defineShape<IssueTag>(
   '/v1/fallback/issue_tags'
 );
 
+export const PROJECT_ISSUE_ORGANIZATION_TAGS_SHAPE = defineShape<IssueOrganizationTag>(
+  'issue_organization_tags',
+  ['project_id'] as const,
+  '/v1/shape/project/{project_id}/issue_organization_tags',
+  '/v1/fallback/issue_organization_tags'
+);
+
 export const PROJECT_ISSUE_RELATIONSHIPS_SHAPE = defineShape<IssueRelationship>(
   'issue_relationships',
   ['project_id'] as const,
@@ -330,6 +1152,7 @@ export const ISSUE_REACTIONS_SHAPE = defineShape<IssueCommentReaction>(
 export interface MutationDefinition<TRow, TCreate = unknown, TUpdate = unknown> {
   readonly name: string;
   readonly url: string;
+  readonly versionColumn: string | null;  // Updates must send expected_<column>
   readonly _rowType: TRow;  // Phantom field for type inference (not present at runtime)
   readonly _createType: TCreate;  // Phantom field for type inference (not present at runtime)
   readonly _updateType: TUpdate;  // Phantom field for type inference (not present at runtime)
@@ -338,73 +1161,239 @@ export interface MutationDefinition<TRow, TCreate = unknown, TUpdate = unknown>
 // Helper to create type-safe mutation definitions
 function defineMutation<TRow, TCreate, TUpdate>(
   name: string,
-  url: string
+  url: string,
+  versionColumn: string | null
 ): MutationDefinition<TRow, TCreate, TUpdate> {
-  return { name, url } as MutationDefinition<TRow, TCreate, TUpdate>;
+  return { name, url, versionColumn } as MutationDefinition<TRow, TCreate, TUpdate>;
 }
 
 // Individual mutation definitions
 export const PROJECT_MUTATION = defineMutation<Project, CreateProjectRequest, UpdateProjectRequest>(
   'Project',
-  '/v1/projects'
+  '/v1/projects',
+  null
 );
 
 export const NOTIFICATION_MUTATION = defineMutation<Notification, unknown, UpdateNotificationRequest>(
   'Notification',
-  '/v1/notifications'
+  '/v1/notifications',
+  null
 );
 
 export const TAG_MUTATION = defineMutation<Tag, CreateTagRequest, UpdateTagRequest>(
   'Tag',
-  '/v1/tags'
+  '/v1/tags',
+  null
+);
+
+export const ORGANIZATION_TAG_MUTATION = defineMutation<OrganizationTag, CreateOrganizationTagRequest, UpdateOrganizationTagRequest>(
+  'OrganizationTag',
+  '/v1/organization_tags',
+  null
 );
 
 export const PROJECT_STATUS_MUTATION = defineMutation<ProjectStatus, CreateProjectStatusRequest, UpdateProjectStatusRequest>(
   'ProjectStatus',
-  '/v1/project_statuses'
+  '/v1/project_statuses',
+  null
 );
 
 export const ISSUE_MUTATION = defineMutation<Issue, CreateIssueRequest, UpdateIssueRequest>(
   'Issue',
-  '/v1/issues'
+  '/v1/issues',
+  'updated_at'
 );
 
 export const ISSUE_ASSIGNEE_MUTATION = defineMutation<IssueAssignee, CreateIssueAssigneeRequest, unknown>(
   'IssueAssignee',
-  '/v1/issue_assignees'
+  '/v1/issue_assignees',
+  null
 );
 
 export const ISSUE_FOLLOWER_MUTATION = defineMutation<IssueFollower, CreateIssueFollowerRequest, unknown>(
   'IssueFollower',
-  '/v1/issue_followers'
+  '/v1/issue_followers',
+  null
 );
 
 export const ISSUE_TAG_MUTATION = defineMutation<IssueTag, CreateIssueTagRequest, unknown>(
   'IssueTag',
-  '/v1/issue_tags'
+  '/v1/issue_tags',
+  null
+);
+
+export const ISSUE_ORGANIZATION_TAG_MUTATION = defineMutation<IssueOrganizationTag, CreateIssueOrganizationTagRequest, unknown>(
+  'IssueOrganizationTag',
+  '/v1/issue_organization_tags',
+  null
 );
 
 export const ISSUE_RELATIONSHIP_MUTATION = defineMutation<IssueRelationship, CreateIssueRelationshipRequest, unknown>(
   'IssueRelationship',
-  '/v1/issue_relationships'
+  '/v1/issue_relationships',
+  null
 );
 
 export const ISSUE_COMMENT_MUTATION = defineMutation<IssueComment, CreateIssueCommentRequest, UpdateIssueCommentRequest>(
   'IssueComment',
-  '/v1/issue_comments'
+  '/v1/issue_comments',
+  'updated_at'
 );
 
 export const ISSUE_COMMENT_REACTION_MUTATION = defineMutation<IssueCommentReaction, CreateIssueCommentReactionRequest, UpdateIssueCommentReactionRequest>(
   'IssueCommentReaction',
-  '/v1/issue_comment_reactions'
+  '/v1/issue_comment_reactions',
+  null
 );
 
 export const PULL_REQUEST_ISSUE_MUTATION = defineMutation<PullRequestIssue, CreatePullRequestIssueRequest, unknown>(
   'PullRequestIssue',
-  '/v1/pull_request_issues'
+  '/v1/pull_request_issues',
+  null
+);
+
+export const BATCH_MUTATION_RESULT_MUTATION = defineMutation<BatchMutationResult, BatchMutationRequest, unknown>(
+  'BatchMutationResult',
+  '/v1/mutations/batch',
+  null
 );
 
 // Type helpers to extract types from a mutation definition
 export type MutationRowType<M extends MutationDefinition<unknown>> = M extends MutationDefinition<infer R> ? R : never;
 export type MutationCreateType<M extends MutationDefinition<unknown, unknown>> = M extends MutationDefinition<unknown, infer C> ? C : never;
 export type MutationUpdateType<M extends MutationDefinition<unknown, unknown, unknown>> = M extends MutationDefinition<unknown, unknown, infer U> ? U : never;
+
+// =============================================================================
+// Query Definitions
+// =============================================================================
+
+// Query definition interface
+export interface QueryDefinition<TParams, TResponse> {
+  readonly name: string;
+  readonly method: 'GET' | 'POST';  // GET sends params as a query string, POST as a JSON body
+  readonly url: string;
+  readonly _paramsType: TParams;  // Phantom field for type inference (not present at runtime)
+  readonly _responseType: TResponse;  // Phantom field for type inference (not present at runtime)
+}
+
+// Helper to create type-safe query definitions
+function defineQuery<TParams, TResponse>(
+  name: string,
+  method: 'GET' | 'POST',
+  url: string
+): QueryDefinition<TParams, TResponse> {
+  return { name, method, url } as QueryDefinition<TParams, TResponse>;
+}
+
+// Individual query definitions
+export const LIST_PROJECTS_QUERY = defineQuery<ListProjectsQuery, ListProjectsResponse>(
+  'list_projects',
+  'GET',
+  '/v1/projects'
+);
+
+export const LIST_TAGS_QUERY = defineQuery<ListTagsQuery, ListTagsResponse>(
+  'list_tags',
+  'GET',
+  '/v1/tags'
+);
+
+export const LIST_ORGANIZATION_TAGS_QUERY = defineQuery<ListOrganizationTagsQuery, ListOrganizationTagsResponse>(
+  'list_organization_tags',
+  'GET',
+  '/v1/organization_tags'
+);
+
+export const LIST_DUPLICATE_TAGS_QUERY = defineQuery<ListOrganizationTagsQuery, ListDuplicateTagsResponse>(
+  'list_duplicate_tags',
+  'GET',
+  '/v1/organization_tags/duplicates'
+);
+
+export const LIST_PROJECT_STATUSES_QUERY = defineQuery<ListProjectStatusesQuery, ListProjectStatusesResponse>(
+  'list_project_statuses',
+  'GET',
+  '/v1/project_statuses'
+);
+
+export const LIST_PROJECT_STATUS_WORKFLOWS_QUERY = defineQuery<ListProjectStatusWorkflowsQuery, ListProjectStatusWorkflowsResponse>(
+  'list_project_status_workflows',
+  'GET',
+  '/v1/project_status_workflows'
+);
+
+export const LIST_ISSUES_QUERY = defineQuery<ListIssuesQuery, ListIssuesResponse>(
+  'list_issues',
+  'GET',
+  '/v1/issues'
+);
+
+export const SEARCH_ISSUES_QUERY = defineQuery<SearchIssuesRequest, ListIssuesResponse>(
+  'search_issues',
+  'POST',
+  '/v1/issues/search'
+);
+
+export const LIST_ISSUE_ASSIGNEES_QUERY = defineQuery<ListIssueAssigneesQuery, ListIssueAssigneesResponse>(
+  'list_issue_assignees',
+  'GET',
+  '/v1/issue_assignees'
+);
+
+export const LIST_ISSUE_FOLLOWERS_QUERY = defineQuery<ListIssueFollowersQuery, ListIssueFollowersResponse>(
+  'list_issue_followers',
+  'GET',
+  '/v1/issue_followers'
+);
+
+export const LIST_ISSUE_TAGS_QUERY = defineQuery<ListIssueTagsQuery, ListIssueTagsResponse>(
+  'list_issue_tags',
+  'GET',
+  '/v1/issue_tags'
+);
+
+export const LIST_ISSUE_ORGANIZATION_TAGS_QUERY = defineQuery<ListIssueOrganizationTagsQuery, ListIssueOrganizationTagsResponse>(
+  'list_issue_organization_tags',
+  'GET',
+  '/v1/issue_organization_tags'
+);
+
+export const LIST_ISSUE_RELATIONSHIPS_QUERY = defineQuery<ListIssueRelationshipsQuery, ListIssueRelationshipsResponse>(
+  'list_issue_relationships',
+  'GET',
+  '/v1/issue_relationships'
+);
+
+export const LIST_ISSUE_COMMENTS_QUERY = defineQuery<ListIssueCommentsQuery, ListIssueCommentsResponse>(
+  'list_issue_comments',
+  'GET',
+  '/v1/issue_comments'
+);
+
+export const LIST_ISSUE_COMMENT_REACTIONS_QUERY = defineQuery<ListIssueCommentReactionsQuery, ListIssueCommentReactionsResponse>(
+  'list_issue_comment_reactions',
+  'GET',
+  '/v1/issue_comment_reactions'
+);
+
+export const UNREAD_NOTIFICATION_COUNT_QUERY = defineQuery<UnreadNotificationCountQuery, UnreadNotificationCountResponse>(
+  'unread_notification_count',
+  'GET',
+  '/v1/notifications/unread_count'
+);
+
+export const LIST_NOTIFICATION_ISSUE_MUTES_QUERY = defineQuery<void, ListNotificationIssueMutesResponse>(
+  'list_notification_issue_mutes',
+  'GET',
+  '/v1/notifications/mutes'
+);
+
+export const LIST_RELAY_HOSTS_QUERY = defineQuery<void, ListRelayHostsResponse>(
+  'list_relay_hosts',
+  'GET',
+  '/v1/hosts'
+);
+
+// Type helpers to extract types from a query definition
+export type QueryParamsType<Q extends QueryDefinition<unknown, unknown>> = Q extends QueryDefinition<infer P, unknown> ? P : never;
+export type QueryResponseType<Q extends QueryDefinition<unknown, unknown>> = Q extends QueryDefinition<unknown, infer R> ? R : never;
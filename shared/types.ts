@@ -30,6 +30,27 @@ export type CreateTag = { tag_name: string, content: string, };
 
 export type UpdateTag = { tag_name: string | null, content: string | null, };
 
+export enum AuditAction { workspace_deleted = "workspace_deleted", workspace_stopped = "workspace_stopped", process_killed = "process_killed", git_reset = "git_reset", approval_granted = "approval_granted", approval_denied = "approval_denied", approval_auto_approved = "approval_auto_approved", command_executed = "command_executed", undo_applied = "undo_applied" }
+
+export type AuditLogEntry = { id: string, action: AuditAction, 
+/**
+ * Who performed the action: `user:<name>` for signed-in users, `local`
+ * for other same-machine requests, or `relay:<signing_session_id>` for
+ * requests arriving through the relay.
+ */
+actor: string, workspace_id: string | null, 
+/**
+ * The primary object acted upon (execution process, session, ...), if
+ * different from the workspace.
+ */
+target_id: string | null, details: JsonValue | null, created_at: string, };
+
+export type AuditLogQuery = { action: AuditAction | null, workspace_id: string | null, 
+/**
+ * Only return entries created strictly before this timestamp (for paging).
+ */
+before: string | null, limit: bigint | null, };
+
 export type DraftFollowUpData = { message: string, executor_config: ExecutorConfig, };
 
 export type DraftWorkspaceData = { message: string, repos: Array<DraftWorkspaceRepo>, executor_config: ExecutorConfig | null, linked_issue: DraftWorkspaceLinkedIssue | null, attachments: Array<DraftWorkspaceAttachment>, };
@@ -148,12 +169,67 @@ export type CreateScratch = { payload: ScratchPayload, };
 
 export type UpdateScratch = { payload: ScratchPayload, };
 
+export type DuplicateScratch = { 
+/**
+ * Id of the copy; a new one is generated if omitted.
+ */
+id?: string, };
+
+export type ScratchToTemplate = { tag_name: string, };
+
+export type AttachScratchToWorkspace = { workspace_id: string, 
+/**
+ * Defaults to `scratch.md`.
+ */
+file_name?: string, };
+
+export type ScratchExportFormat = "markdown" | "json";
+
 export type Workspace = { id: string, task_id: string | null, container_ref: string | null, branch: string, setup_completed_at: string | null, created_at: string, updated_at: string, archived: boolean, pinned: boolean, name: string | null, worktree_deleted: boolean, };
 
 export type WorkspaceWithStatus = { is_running: boolean, is_errored: boolean, id: string, task_id: string | null, container_ref: string | null, branch: string, setup_completed_at: string | null, created_at: string, updated_at: string, archived: boolean, pinned: boolean, name: string | null, worktree_deleted: boolean, };
 
 export type Session = { id: string, workspace_id: string, name: string | null, executor: string | null, agent_working_dir: string | null, created_at: string, updated_at: string, };
 
+export type TranscriptFormat = "markdown" | "html";
+
+/**
+ * A read-only public link to a session's transcript and diff.
+ */
+export type SessionShare = { id: string, session_id: string, expires_at: Date, revoked_at: Date | null, created_at: Date, };
+
+export type CreateSessionShareRequest = { 
+/**
+ * Defaults to 72 hours.
+ */
+expires_in_hours?: number, };
+
+export type SessionShareLink = { url: string, id: string, session_id: string, expires_at: Date, revoked_at: Date | null, created_at: Date, };
+
+export type CheckpointRepoState = { repo_id: string, head_commit: string, };
+
+export type SessionCheckpoint = { id: string, session_id: string, execution_process_id: string, label: string, repo_states: Array<CheckpointRepoState>, created_at: Date, };
+
+export type CreateSessionCheckpointRequest = { label: string, 
+/**
+ * Defaults to the latest process of the session.
+ */
+process_id?: string, };
+
+export type RestoreSessionCheckpointRequest = { 
+/**
+ * Discard uncommitted changes in the workspace.
+ */
+force_when_dirty?: boolean, };
+
+export enum UndoAction { workspace_deleted = "workspace_deleted", git_reset = "git_reset" }
+
+export type UndoneAction = { workspace_id: string, action: UndoAction, 
+/**
+ * When the undone action was performed.
+ */
+performed_at: Date, };
+
 export type ExecutionProcess = { id: string, session_id: string, run_reason: ExecutionProcessRunReason, executor_action: ExecutorAction, status: ExecutionProcessStatus, exit_code: bigint | null, 
 /**
  * dropped: true if this process is excluded from the current
@@ -164,7 +240,14 @@ dropped: boolean, started_at: string, completed_at: string | null, created_at: s
 
 export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed" }
 
-export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "archivescript" | "codingagent" | "devserver";
+export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "archivescript" | "verifyscript" | "codingagent" | "devserver";
+
+export type DevServerTarget = { execution_process_id: string, url: string, port: number, };
+
+/**
+ * Test counts parsed from the output of a verify script.
+ */
+export type TestResults = { passed: number, failed: number, skipped: number, };
 
 export type ExecutionProcessRepoState = { id: string, execution_process_id: string, repo_id: string, before_head_commit: string | null, after_head_commit: string | null, merge_commit: string | null, created_at: Date, updated_at: Date, };
 
@@ -178,7 +261,53 @@ export type MergeStatus = "open" | "merged" | "closed" | "unknown";
 
 export type PullRequestInfo = { number: bigint, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, };
 
-export type ApprovalInfo = { approval_id: string, tool_name: string, execution_process_id: string, is_question: boolean, created_at: string, timeout_at: string, };
+export type ApprovalInfo = { approval_id: string, tool_name: string, execution_process_id: string, is_question: boolean, 
+/**
+ * Who may answer, if the rule that asked for the approval names them.
+ */
+approvers: ApprovalRuleApprovers | null, created_at: string, timeout_at: string, };
+
+export enum ApprovalRuleAction { auto_approve = "auto_approve", require_approval = "require_approval", deny = "deny" }
+
+/**
+ * Who may answer the approvals a rule asks for. Admins always may.
+ */
+export type ApprovalRuleApprovers = { user_ids: Array<string>, 
+/**
+ * Everyone with this role in the remote organization; `member` covers
+ * all members.
+ */
+role: RemoteOrgRole | null, };
+
+/**
+ * Decides matching tool approval requests without (or always with) a prompt.
+ */
+export type ApprovalRule = { id: string, 
+/**
+ * Restricts the rule to one repo; `None` applies it everywhere.
+ */
+repo_id: string | null, 
+/**
+ * Tool the rule applies to (case-insensitive); `None` matches any tool.
+ */
+tool_name: string | null, 
+/**
+ * Leading words of a shell command, e.g. `git push`.
+ */
+command_prefix: string | null, action: ApprovalRuleAction, description: string | null, 
+/**
+ * Who may answer the approvals a `require_approval` rule asks for;
+ * `None` leaves them to anyone.
+ */
+approvers: ApprovalRuleApprovers | null, created_at: Date, updated_at: Date, };
+
+export type CreateApprovalRule = { repo_id: string | null, tool_name: string | null, command_prefix: string | null, action: ApprovalRuleAction, description: string | null, approvers: ApprovalRuleApprovers | null, };
+
+export type UpdateApprovalRule = { tool_name: string | null, command_prefix: string | null, action: ApprovalRuleAction | null, description: string | null, 
+/**
+ * Replaces the approvers; empty approvers leave approvals to anyone.
+ */
+approvers: ApprovalRuleApprovers | null, };
 
 export type ApprovalStatus = { "status": "pending" } | { "status": "approved" } | { "status": "denied", reason?: string, } | { "status": "timed_out" };
 
@@ -195,6 +324,14 @@ export type Diff = { change: DiffChangeKind, oldPath: string | null, newPath: st
  * True when file contents are intentionally omitted (e.g., too large)
  */
 contentOmitted: boolean, 
+/**
+ * Why the contents were omitted, when they were
+ */
+omittedReason: DiffOmittedReason | null, 
+/**
+ * True when either side is a binary file
+ */
+isBinary: boolean, 
 /**
  * Optional precomputed stats for omitted content
  */
@@ -202,6 +339,8 @@ additions: number | null, deletions: number | null, repoId: string | null, };
 
 export type DiffChangeKind = "added" | "deleted" | "modified" | "renamed" | "copied" | "permissionChange";
 
+export type DiffOmittedReason = "tooLarge" | "binary" | "statsOnly" | "streamLimit";
+
 export type ApiResponse<T, E = T> = { success: boolean, data: T | null, error_data: E | null, message: string | null, };
 
 export type LoginStatus = { "status": "loggedout" } | { "status": "loggedin", profile: ProfileResponse | null, };
@@ -246,7 +385,7 @@ export type RevokeInvitationRequest = { invitation_id: string, };
 
 export type OrganizationMemberInfo = { user_id: string, role: MemberRole, joined_at: string, };
 
-export type OrganizationMemberWithProfile = { user_id: string, role: MemberRole, joined_at: string, first_name: string | null, last_name: string | null, username: string | null, email: string | null, avatar_url: string | null, };
+export type OrganizationMemberWithProfile = { user_id: string, role: MemberRole, joined_at: string, first_name: string | null, last_name: string | null, username: string | null, email: string | null, display_name: string | null, avatar_url: string | null, };
 
 export type ListMembersResponse = { members: Array<OrganizationMemberWithProfile>, };
 
@@ -256,156 +395,974 @@ export type UpdateMemberRoleResponse = { user_id: string, role: MemberRole, };
 
 export type RegisterRepoRequest = { path: string, display_name: string | null, };
 
-export type InitRepoRequest = { parent_path: string, folder_name: string, };
-
-export type TagSearchParams = { search: string | null, };
-
-export type TokenResponse = { access_token: string, expires_at: string | null, };
+export type RepoLogRedaction = { enabled: boolean, };
 
-export type UserSystemInfo = { version: string, config: Config, machine_id: string, login_status: LoginStatus, remote_auth_degraded: string | null, environment: Environment, 
+export type EnvProfile = { id: string, repo_id: string, name: string, variables: { [key in string]?: string }, 
 /**
- * Capabilities supported per executor (e.g., { "CLAUDE_CODE": ["SESSION_FORK"] })
+ * Variables that must resolve to a non-empty value, either from this
+ * profile or from the server's own environment, before a process starts.
  */
-capabilities: { [key in string]?: Array<BaseAgentCapability> }, shared_api_base: string | null, preview_proxy_port: number | null, executors: { [key in BaseCodingAgent]?: ExecutorProfile }, };
+required_variables: Array<string>, created_at: Date, updated_at: Date, };
 
-export type Environment = { os_type: string, os_version: string, os_architecture: string, bitness: string, };
+export type CreateEnvProfile = { name: string, variables: { [key in string]?: string }, required_variables: Array<string>, };
 
-export type McpServerQuery = { executor: BaseCodingAgent, };
+export type UpdateEnvProfile = { name: string | null, variables: { [key in string]?: string } | null, required_variables: Array<string> | null, };
 
-export type UpdateMcpServersBody = { servers: { [key in string]?: JsonValue }, };
+export enum WorkspaceContextKind { file = "file", url = "url" }
 
-export type GetMcpServerResponse = { mcp_config: McpConfig, config_path: string, };
+export type WorkspaceContextItem = { id: string, workspace_id: string, kind: WorkspaceContextKind, 
+/**
+ * File name inside the context directory.
+ */
+name: string, attachment_id: string | null, url: string | null, created_at: string, };
 
-export type CheckEditorAvailabilityQuery = { editor_type: EditorType, };
+export type AddWorkspaceContext = { kind: WorkspaceContextKind, 
+/**
+ * File name in the context directory; derived from the source if omitted.
+ */
+name?: string, 
+/**
+ * Uploaded attachment, for `file` items.
+ */
+attachment_id?: string, 
+/**
+ * Page to fetch, for `url` items.
+ */
+url?: string, };
 
-export type CheckEditorAvailabilityResponse = { available: boolean, };
+export enum ScriptFailurePolicy { abort = "abort", continue = "continue", retry = "retry" }
 
-export type CheckAgentAvailabilityQuery = { executor: BaseCodingAgent, };
+export type RepoScriptSettings = { setup_timeout_secs: number | null, cleanup_timeout_secs: number | null, archive_timeout_secs: number | null, on_failure: ScriptFailurePolicy, max_retries: number, };
 
-export type AgentPresetOptionsQuery = { executor: BaseCodingAgent, variant: string | null, };
+export type RepoVerifyScript = { 
+/**
+ * Runs after the coding agent and cleanup scripts; test counts are parsed
+ * from its output.
+ */
+script: string | null, };
 
-export type CurrentUserResponse = { user_id: string, };
+export type SetRepoGitCredential = { 
+/**
+ * `null` goes back to the default git identity.
+ */
+git_credential_id: string | null, };
 
-export type StartSpake2EnrollmentRequest = { enrollment_code: string, client_message_b64: string, };
+export type RepoAnalysis = { setup_script: string | null, dev_server_script: string | null, cleanup_script: string | null, 
+/**
+ * Set when the project lives in a subdirectory of the repo.
+ */
+default_working_dir: string | null, 
+/**
+ * Files the suggestions were derived from, relative to the repo root.
+ */
+detected_files: Array<string>, 
+/**
+ * Commands run by the repo's CI workflows, for reference.
+ */
+ci_commands: Array<string>, 
+/**
+ * Packages of a monorepo, relative to the project directory.
+ */
+packages: Array<DetectedPackage>, };
 
-export type FinishSpake2EnrollmentRequest = { enrollment_id: string, client_id: string, client_name: string, client_browser: string, client_os: string, client_device: string, public_key_b64: string, client_proof_b64: string, };
+export type PackageKind = "node" | "cargo" | "bazel";
 
-export type StartSpake2EnrollmentResponse = { enrollment_id: string, server_message_b64: string, };
+export type DetectedPackage = { path: string, name: string, kind: PackageKind, 
+/**
+ * Runs in the package directory.
+ */
+dev_server_script: string | null, };
 
-export type FinishSpake2EnrollmentResponse = { signing_session_id: string, server_public_key_b64: string, server_proof_b64: string, };
+export enum RepoScriptKind { setup = "setup", cleanup = "cleanup", archive = "archive", dev_server = "dev_server" }
 
-export type RelayPairedClient = { client_id: string, client_name: string, client_browser: string, client_os: string, client_device: string, };
+export type LibraryScript = { id: string, name: string, description: string | null, script: string, created_at: Date, updated_at: Date, };
 
-export type ListRelayPairedClientsResponse = { clients: Array<RelayPairedClient>, };
+export type CreateLibraryScript = { name: string, description: string | null, script: string, };
 
-export type RemoveRelayPairedClientResponse = { removed: boolean, };
+export type UpdateLibraryScript = { name: string | null, description: string | null, script: string | null, };
 
-export type RefreshRelaySigningSessionRequest = { client_id: string, timestamp: bigint, nonce: string, signature_b64: string, };
+export type RepoScriptRef = { repo_id: string, kind: RepoScriptKind, library_script_id: string, };
 
-export type RefreshRelaySigningSessionResponse = { signing_session_id: string, };
+export type SetRepoScriptRefRequest = { 
+/**
+ * Library script to use, or `null` to stop referencing the library.
+ */
+library_script_id: string | null, };
 
-export type CreateFollowUpAttempt = { prompt: string, executor_config: ExecutorConfig, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, idempotency_key: string | null, };
+export type RepoDevServer = { id: string, repo_id: string, name: string, script: string, created_at: Date, updated_at: Date, };
 
-export type ResetProcessRequest = { process_id: string, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
+export type CreateRepoDevServer = { name: string, script: string, };
 
-export type ChangeTargetBranchRequest = { repo_id: string, new_target_branch: string, };
+export type UpdateRepoDevServer = { name: string | null, script: string | null, };
 
-export type ChangeTargetBranchResponse = { repo_id: string, new_target_branch: string, status: [number, number], };
+export enum RepoKnowledgeCategory { build = "build", gotcha = "gotcha", architecture = "architecture", other = "other" }
 
-export type AddWorkspaceRepoRequest = { repo_id: string, target_branch: string, };
+/**
+ * Only approved entries are added to agent prompts. Entries suggested by
+ * agents wait for approval.
+ */
+export enum RepoKnowledgeStatus { approved = "approved", suggested = "suggested" }
 
-export type AddWorkspaceRepoResponse = { workspace: Workspace, repo: RepoWithTargetBranch, };
+/**
+ * A learning about a repo, kept across sessions.
+ */
+export type RepoKnowledge = { id: string, repo_id: string, category: RepoKnowledgeCategory, content: string, status: RepoKnowledgeStatus, 
+/**
+ * The session the entry was saved from, if any.
+ */
+session_id: string | null, created_at: Date, updated_at: Date, };
 
-export type MergeWorkspaceRequest = { repo_id: string, };
+export type CreateRepoKnowledge = { category: RepoKnowledgeCategory, content: string, session_id?: string | null, 
+/**
+ * Saves the entry as a suggestion that must be approved before agents see
+ * it. Used for entries proposed by agents.
+ */
+suggested?: boolean | null, };
 
-export type PushWorkspaceRequest = { repo_id: string, };
+export type UpdateRepoKnowledge = { category: RepoKnowledgeCategory | null, content: string | null, };
 
-export type RenameBranchRequest = { new_branch_name: string, };
+export type RepoCommit = { sha: string, summary: string, author_name: string, author_email: string, committed_at: Date, };
 
-export type RenameBranchResponse = { branch: string, };
+/**
+ * An author of the indexed commits of a repo.
+ */
+export type RepoContributor = { name: string, email: string, commit_count: number, last_commit_at: Date, };
 
-export type StartReviewRequest = { executor_config: ExecutorConfig, additional_prompt: string | null, use_all_workspace_commits: boolean, };
+/**
+ * A package of a monorepo. Workspaces that select it start agents in `path`
+ * and, if set, run `dev_server_script` there instead of the repo's script.
+ */
+export type RepoPackage = { id: string, repo_id: string, 
+/**
+ * Relative to the repo root.
+ */
+path: string, name: string, dev_server_script: string | null, created_at: Date, updated_at: Date, };
 
-export type ReviewError = { "type": "process_already_running" };
+export type CreateRepoPackage = { path: string, name: string, dev_server_script?: string | null, };
 
-export type OpenEditorRequest = { editor_type: string | null, file_path: string | null, };
+export type UpdateRepoPackage = { name: string | null, 
+/**
+ * An empty script clears it.
+ */
+dev_server_script: string | null, };
 
-export type OpenEditorResponse = { url: string | null, };
+export enum SharedCacheKind { cargo_target = "cargo_target", cargo_registry = "cargo_registry", pnpm_store = "pnpm_store", pip_cache = "pip_cache", custom = "custom" }
 
-export type OpenRemoteEditorResponse = { url: string, local_port: number, ssh_alias: string, };
+export type RepoSharedCache = { id: string, repo_id: string, kind: SharedCacheKind, 
+/**
+ * Worktree-relative path of custom caches.
+ */
+path: string | null, created_at: Date, };
 
-export type OpenRemoteWorkspaceInEditorRequest = { host_id: string, workspace_id: string, editor_type: string | null, file_path: string | null, };
+export type CreateRepoSharedCache = { kind: SharedCacheKind, 
+/**
+ * Required for custom caches, e.g. `node_modules/.cache`.
+ */
+path?: string | null, };
 
-export type PairRelayHostRequest = { host_id: string, host_name: string, enrollment_code: string, };
+export type SharedCacheUsage = { size_bytes: number, id: string, repo_id: string, kind: SharedCacheKind, 
+/**
+ * Worktree-relative path of custom caches.
+ */
+path: string | null, created_at: Date, };
 
-export type PairRelayHostResponse = { paired: boolean, };
+export type WorkspaceDevServer = { dev_server: RepoDevServer, repo_name: string, execution_process_id: string | null, };
 
-export type RelayPairedHost = { host_id: string, host_name: string | null, paired_at: string | null, };
+export type ExecRequest = { 
+/**
+ * Run through the platform shell (`sh -c`, `cmd /C`).
+ */
+command: string, 
+/**
+ * Directory to run in, relative to the workspace directory. Defaults to
+ * the repo directory for single-repo workspaces.
+ */
+cwd?: string, 
+/**
+ * Defaults to 60 seconds, at most 600.
+ */
+timeout_secs?: number, };
 
-export type ListRelayPairedHostsResponse = { hosts: Array<RelayPairedHost>, };
+export type ExecResult = { 
+/**
+ * None if the command was killed by a signal or timed out.
+ */
+exit_code: number | null, stdout: string, stderr: string, timed_out: boolean, 
+/**
+ * True if either stream exceeded the output limit and was cut short.
+ */
+truncated: boolean, duration_ms: number, };
 
-export type RemoveRelayPairedHostResponse = { removed: boolean, };
+export enum GitFileStatus { modified = "modified", added = "added", deleted = "deleted", renamed = "renamed", untracked = "untracked", conflicted = "conflicted" }
 
-export type CreateWorkspaceApiRequest = { name: string | null, idempotency_key: string | null, };
+export type FileTreeEntry = { name: string, 
+/**
+ * Relative to the workspace directory, with `/` separators.
+ */
+path: string, is_dir: boolean, is_symlink: boolean, 
+/**
+ * Size of files; None for directories.
+ */
+size_bytes: number | null, is_ignored: boolean, git_status: GitFileStatus | null, 
+/**
+ * For directories: whether anything below has uncommitted changes.
+ */
+has_changes: boolean, };
 
-export type LinkedIssueInfo = { remote_project_id: string, issue_id: string, };
+export type FileTreeListing = { path: string, entries: Array<FileTreeEntry>, 
+/**
+ * True if the directory had more than `MAX_TREE_ENTRIES` entries.
+ */
+truncated: boolean, };
 
-export type CreatePrApiRequest = { title: string, body: string | null, target_branch: string | null, draft: boolean | null, repo_id: string, auto_generate_description: boolean, squash_merge_after_description: boolean, };
+export type Schedule = { id: string, name: string, 
+/**
+ * Standard five-field cron expression, evaluated in the server's local
+ * time zone.
+ */
+cron_expression: string, repo_id: string, target_branch: string, prompt: string, executor_config: ExecutorConfig, 
+/**
+ * Push the run's branch and open a pull request when the repo's verify
+ * script passes.
+ */
+open_pr: boolean, paused: boolean, next_run_at: Date | null, last_run_at: Date | null, created_at: Date, updated_at: Date, };
 
-export type AttachmentResponse = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
+export type CreateSchedule = { name: string, cron_expression: string, repo_id: string, target_branch: string, prompt: string, executor_config: ExecutorConfig, open_pr: boolean, };
 
-export type AttachmentMetadata = { exists: boolean, file_name: string | null, path: string | null, size_bytes: bigint | null, format: string | null, proxy_url: string | null, };
+export type UpdateSchedule = { name: string | null, cron_expression: string | null, target_branch: string | null, prompt: string | null, executor_config: ExecutorConfig | null, open_pr: boolean | null, };
 
-export type WorkspaceRepoInput = { repo_id: string, target_branch: string, };
+export enum ScheduleRunStatus { started = "started", failed = "failed" }
 
-export type RunAgentSetupRequest = { executor_profile_id: ExecutorProfileId, };
+export type ScheduleRun = { id: string, schedule_id: string, workspace_id: string | null, status: ScheduleRunStatus, 
+/**
+ * Why the run failed to start, or why it didn't open a pull request.
+ */
+error: string | null, pr_url: string | null, created_at: Date, };
 
-export type RunAgentSetupResponse = Record<string, never>;
+export enum DependencyEcosystem { cargo = "cargo", npm = "npm" }
 
-export type GhCliSetupError = "BREW_MISSING" | "SETUP_HELPER_NOT_SUPPORTED" | { "OTHER": { message: string, } };
+export type CreateDependencyUpdateSchedule = { name?: string, repo_id: string, cron_expression: string, target_branch: string, ecosystems: Array<DependencyEcosystem>, 
+/**
+ * Added to the built-in prompt, e.g. packages to leave alone.
+ */
+instructions?: string, executor_config: ExecutorConfig, };
 
-export type RebaseWorkspaceRequest = { repo_id: string, old_base_branch: string | null, new_base_branch: string | null, };
+/**
+ * An agent run a webhook may start.
+ */
+export type WebhookTemplate = { 
+/**
+ * Callers pick the template with `?template=`.
+ */
+name: string, 
+/**
+ * Prompt with `{{path}}` placeholders filled from the JSON payload, e.g.
+ * `{{workflow_run.head_branch}}` or `{{commits.0.message}}`.
+ */
+prompt: string, 
+/**
+ * Branch the workspace starts from. Supports the same placeholders.
+ */
+target_branch: string, 
+/**
+ * Executor for runs of this template; defaults to the trigger's.
+ */
+executor_config?: ExecutorConfig, };
 
-export type ContinueRebaseRequest = { repo_id: string, };
+/**
+ * An inbound webhook that starts agent runs, limited to its own templates
+ * and repos. The token it is called with is only shown when issued.
+ */
+export type WebhookTrigger = { id: string, name: string, 
+/**
+ * Repos runs may be started in.
+ */
+repo_ids: Array<string>, templates: Array<WebhookTemplate>, executor_config: ExecutorConfig, paused: boolean, last_triggered_at: Date | null, created_at: Date, updated_at: Date, };
 
-export type AbortConflictsRequest = { repo_id: string, };
+export type CreateWebhookTrigger = { name: string, repo_ids: Array<string>, templates: Array<WebhookTemplate>, executor_config: ExecutorConfig, };
 
-export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, conflicted_files: Array<string>, target_branch: string, } | { "type": "rebase_in_progress" };
+export type UpdateWebhookTrigger = { name: string | null, repo_ids: Array<string> | null, templates: Array<WebhookTemplate> | null, executor_config: ExecutorConfig | null, paused: boolean | null, };
 
-export type PushError = { "type": "force_push_required" };
+export enum WebhookTriggerRunStatus { started = "started", failed = "failed" }
 
-export type PrError = { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "cli_not_logged_in", provider: ProviderKind, } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, } | { "type": "unsupported_provider" };
+export type WebhookTriggerRun = { id: string, trigger_id: string, template: string, workspace_id: string | null, status: WebhookTriggerRunStatus, error: string | null, created_at: Date, };
 
-export type RunScriptError = { "type": "no_script_configured" } | { "type": "process_already_running" };
+/**
+ * A trigger with a freshly issued token. The token isn't stored and can't be
+ * shown again.
+ */
+export type WebhookTriggerToken = { trigger: WebhookTrigger, token: string, };
 
-export type AssociateWorkspaceAttachmentsRequest = { attachment_ids: Array<string>, };
+/**
+ * How failed CI runs of a repo are triaged. The webhook secret is kept out
+ * of this struct; see [`CiTriageSettings::find_secret`].
+ */
+export type CiTriageSettings = { repo_id: string, enabled: boolean, 
+/**
+ * Replaces the default triage instructions. The failure details and
+ * logs are appended either way.
+ */
+prompt: string | null, executor_config: ExecutorConfig, 
+/**
+ * Post the agent's findings to the run's pull request.
+ */
+post_comment: boolean, created_at: Date, updated_at: Date, };
 
-export type ImportIssueAttachmentsRequest = { issue_id: string, };
+export type UpsertCiTriageSettings = { enabled: boolean, prompt: string | null, executor_config: ExecutorConfig, post_comment: boolean, };
 
-export type ImportIssueAttachmentsResponse = { attachment_ids: Array<string>, };
+/**
+ * A run is `pending` while its logs are fetched and the workspace starts,
+ * `running` while the agent works, and `reported` once the agent finished
+ * and its findings were posted, if there was a pull request to post them to.
+ */
+export enum CiTriageRunStatus { pending = "pending", running = "running", reported = "reported", failed = "failed" }
 
-export type AttachPrResponse = { pr_attached: boolean, pr_url: string | null, pr_number: bigint | null, pr_status: MergeStatus | null, };
+/**
+ * One failed workflow run attempt and its triage.
+ */
+export type CiTriageRun = { id: string, repo_id: string, workflow_run_id: bigint, run_attempt: bigint, workflow_name: string, head_branch: string, run_url: string, pr_number: bigint | null, workspace_id: string | null, status: CiTriageRunStatus, error: string | null, comment_url: string | null, created_at: Date, updated_at: Date, };
 
-export type AttachExistingPrRequest = { repo_id: string, };
+/**
+ * Saved settings, with the webhook secret when one was just issued. The
+ * secret is stored encrypted and can't be shown again.
+ */
+export type SavedCiTriageSettings = { settings: CiTriageSettings, secret?: string, };
 
-export type PrCommentsResponse = { comments: Array<UnifiedPrComment>, };
+export enum TestOutcome { passed = "passed", failed = "failed", skipped = "skipped" }
 
-export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "cli_not_logged_in", provider: ProviderKind, };
+/**
+ * A test that has failed in a reported run. `flips` counts how often its
+ * outcome changed between reports; a test that only ever fails is broken
+ * rather than flaky.
+ */
+export type FlakyTest = { id: string, repo_id: string, suite: string, name: string, passes: bigint, failures: bigint, flips: bigint, last_outcome: TestOutcome, last_failure_message: string | null, last_failed_at: Date | null, quarantined: boolean, 
+/**
+ * Workspace an agent was started in to fix the test.
+ */
+fix_workspace_id: string | null, 
+/**
+ * Latest pull request opened from the fix workspace.
+ */
+fix_pr_url: string | null, fix_pr_status: MergeStatus | null, created_at: Date, updated_at: Date, };
 
-export type GetPrCommentsQuery = { repo_id: string, };
+/**
+ * How a repo's test reports are handled. The token CI reports with is only
+ * shown when issued.
+ */
+export type FlakyTestSettings = { repo_id: string, 
+/**
+ * Start an agent on the flakiest quarantined test when a report comes
+ * in and no other fix is in progress.
+ */
+auto_fix: boolean, 
+/**
+ * Branch fix workspaces start from.
+ */
+target_branch: string, executor_config: ExecutorConfig, created_at: Date, updated_at: Date, };
 
-export type CreateAndStartWorkspaceRequest = { name: string | null, repos: Array<WorkspaceRepoInput>, linked_issue: LinkedIssueInfo | null, executor_config: ExecutorConfig, prompt: string, attachment_ids: Array<string> | null, idempotency_key: string | null, };
+export type UpsertFlakyTestSettings = { auto_fix: boolean, target_branch: string, executor_config: ExecutorConfig, };
 
-export type CreateAndStartWorkspaceResponse = { workspace: Workspace, execution_process: ExecutionProcess, };
+/**
+ * Saved settings, with the report token when one was just issued. The token
+ * is stored hashed and can't be shown again.
+ */
+export type SavedFlakyTestSettings = { settings: FlakyTestSettings, token?: string, };
 
-export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string | null, body: string, created_at: string, url: string | null, } | { "comment_type": "review", id: bigint, author: string, author_association: string | null, body: string, created_at: string, url: string | null, path: string, line: bigint | null, side: string | null, diff_hunk: string | null, };
+/**
+ * The JSON report format, for runners without JUnit output.
+ */
+export type TestReport = { results: Array<TestCaseResult>, };
 
-export type ProviderKind = "git_hub" | "azure_dev_ops" | "unknown";
+export type TestCaseResult = { suite: string, name: string, outcome: TestOutcome, message?: string, };
 
-export type PullRequestDetail = { number: bigint, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, title: string, base_branch: string, head_branch: string, };
+export type TestReportSummary = { passed: number, failed: number, skipped: number, 
+/**
+ * Tests this report pushed over the quarantine threshold.
+ */
+newly_quarantined: number, };
+
+export type UpdateFlakyTest = { quarantined: boolean, };
+
+export type FixFlakyTestRequest = { 
+/**
+ * Test to fix; the flakiest quarantined test nobody is fixing yet if
+ * left out.
+ */
+test_id?: string, };
+
+export type CodeOwnersRule = { 
+/**
+ * 1-based line in the CODEOWNERS file.
+ */
+line: number, pattern: string, owners: Array<string>, };
+
+/**
+ * A repo's parsed CODEOWNERS file. `path` is unset when the repo has none.
+ */
+export type CodeOwnersFile = { path: string | null, rules: Array<CodeOwnersRule>, };
+
+export type PathOwners = { path: string, 
+/**
+ * Empty when no rule matches or the matching rule lists no owners.
+ */
+owners: Array<string>, 
+/**
+ * The rule that decided the owners.
+ */
+pattern: string | null, };
+
+/**
+ * An owner and the paths they own, for suggesting reviewers or assignees.
+ */
+export type OwnerSuggestion = { owner: string, paths: Array<string>, };
+
+export type CodeOwnersLookup = { paths?: Array<string>, 
+/**
+ * Free text such as an issue description; file paths mentioned in it
+ * are looked up too.
+ */
+text?: string, };
+
+export type CodeOwnersMatch = { 
+/**
+ * CODEOWNERS file the owners come from, if the repo has one.
+ */
+file: string | null, paths: Array<PathOwners>, 
+/**
+ * Owners of the paths, owning the most first.
+ */
+owners: Array<OwnerSuggestion>, };
+
+export type PipelineStage = { name: string, 
+/**
+ * Prompt template for the stage. Supports `{{prompt}}`, `{{stage}}`,
+ * `{{pipeline}}`, `{{previous_branch}}` and `{{previous_workspace}}`.
+ */
+prompt: string, 
+/**
+ * Executor for this stage; defaults to the run's executor.
+ */
+executor_config?: ExecutorConfig, };
+
+export type Pipeline = { id: string, name: string, description: string | null, stages: Array<PipelineStage>, created_at: Date, updated_at: Date, };
+
+export type CreatePipeline = { name: string, description: string | null, stages: Array<PipelineStage>, };
+
+export type UpdatePipeline = { name: string | null, description: string | null, stages: Array<PipelineStage> | null, };
+
+export enum PipelineStatus { running = "running", completed = "completed", failed = "failed", cancelled = "cancelled" }
+
+export type PipelineRun = { id: string, pipeline_id: string, status: PipelineStatus, prompt: string, repos: Array<WorkspaceRepoInput>, executor_config: ExecutorConfig, error: string | null, created_at: Date, updated_at: Date, };
+
+export type PipelineRunStage = { pipeline_run_id: string, stage_index: bigint, workspace_id: string | null, status: PipelineStatus, created_at: Date, updated_at: Date, };
+
+export type StartPipelineRunRequest = { repos: Array<WorkspaceRepoInput>, 
+/**
+ * Substituted for `{{prompt}}` in every stage.
+ */
+prompt: string, 
+/**
+ * Executor for stages that do not set their own.
+ */
+executor_config: ExecutorConfig, };
+
+export type PipelineRunWithStages = { run: PipelineRun, stages: Array<PipelineRunStage>, };
+
+export type AttemptGroup = { id: string, name: string, prompt: string, created_at: Date, };
+
+export type AttemptGroupWorkspace = { attempt_group_id: string, workspace_id: string, executor_config: ExecutorConfig, created_at: Date, };
+
+export type CreateAttemptGroupRequest = { 
+/**
+ * Defaults to the first line of the prompt.
+ */
+name?: string, repos: Array<WorkspaceRepoInput>, prompt: string, 
+/**
+ * One attempt is started per executor config.
+ */
+executor_configs: Array<ExecutorConfig>, env_profile_ids?: Array<string>, };
+
+export type AttemptGroupWithWorkspaces = { group: AttemptGroup, attempts: Array<AttemptGroupWorkspace>, };
+
+/**
+ * Outcome of the latest cleanup script run, which is where repos run their
+ * tests and checks.
+ */
+export type AttemptChecks = { status: ExecutionProcessStatus, exit_code: bigint | null, };
+
+export type AttemptComparison = { workspace_id: string, workspace_name: string | null, branch: string, executor_config: ExecutorConfig, 
+/**
+ * Status of the latest coding agent run.
+ */
+agent_status: ExecutionProcessStatus | null, 
+/**
+ * Number of coding agent runs, including follow-ups.
+ */
+agent_runs: bigint, 
+/**
+ * Wall-clock seconds spent by coding agents; the cost of the attempt.
+ */
+agent_seconds: number, diff_stats: DiffStats | null, checks: AttemptChecks | null, 
+/**
+ * Test counts from the verify scripts of the latest turn.
+ */
+tests: TestResults | null, };
+
+export type CreateTerminalRequest = { workspace_id: string, 
+/**
+ * Defaults to "Terminal N".
+ */
+name?: string, 
+/**
+ * Directory to start in, relative to the workspace directory. Defaults
+ * to the repo directory for single-repo workspaces.
+ */
+cwd?: string, cols: number, rows: number, 
+/**
+ * Record the terminal's output as an asciicast file.
+ */
+record: boolean, };
+
+export type UpdateTerminalRequest = { name?: string, cols?: number, rows?: number, };
+
+/**
+ * A named terminal of a workspace. Its shell keeps running while no client
+ * is attached, until the terminal is closed.
+ */
+export type TerminalInfo = { id: string, workspace_id: string, name: string, cwd: string, cols: number, rows: number, 
+/**
+ * False once the shell has exited.
+ */
+running: boolean, recording: boolean, created_at: string, };
+
+/**
+ * An asciicast v2 recording of a workspace terminal. Recordings outlive the
+ * terminal they were made in.
+ */
+export type TerminalRecording = { terminal_id: string, workspace_id: string, title: string | null, started_at: string | null, size_bytes: number, };
+
+export type BackupInfo = { path: string, size_bytes: number, created_at: string, };
+
+export type RestoreBackupRequest = { 
+/**
+ * Path to a backup archive on the machine running the server.
+ */
+path: string, };
+
+export type MaintenanceStatus = { enabled: boolean, reason: string | null, since: Date | null, };
+
+export type SetMaintenanceRequest = { enabled: boolean, 
+/**
+ * Returned with every rejected request.
+ */
+reason: string | null, };
+
+/**
+ * A role in the remote organization whose members sign in here.
+ */
+export type RemoteOrgRole = "admin" | "member";
+
+/**
+ * A person using this server, for telling apart whose workspaces and runs
+ * are whose when a team shares it.
+ */
+export type LocalUser = { id: string, name: string, 
+/**
+ * Admins manage users and may stop anyone's runs.
+ */
+is_admin: boolean, 
+/**
+ * The remote account this user signs in with, if any.
+ */
+remote_user_id: string | null, 
+/**
+ * Where notifications meant for this user are emailed.
+ */
+email: string | null, 
+/**
+ * The user's role in the remote organization, as of their last sign-in.
+ */
+remote_role: RemoteOrgRole | null, created_at: Date, };
+
+/**
+ * A user with a freshly issued session token. The token isn't stored and
+ * can't be shown again.
+ */
+export type LocalUserToken = { user: LocalUser, token: string, };
+
+export type CreateLocalUserRequest = { name: string, };
+
+export type UpdateLocalUserRequest = { 
+/**
+ * `None` or an empty string clears it.
+ */
+email: string | null, };
+
+export type UserSignInRequest = { token: string, };
+
+/**
+ * Whose workspaces to list. Anonymous callers own the unowned ones.
+ */
+export type WorkspaceOwnerFilter = "mine" | "others";
+
+export type InitRepoRequest = { parent_path: string, folder_name: string, };
+
+export type TagSearchParams = { search: string | null, };
+
+export type RawLogPageQuery = { 
+/**
+ * Return lines starting at this byte offset.
+ */
+after: number | null, 
+/**
+ * Return lines ending just before this byte offset.
+ */
+before: number | null, limit: number | null, 
+/**
+ * Remove ANSI escape sequences from line content.
+ */
+strip_ansi: boolean, };
+
+export type RawLogLine = { 
+/**
+ * Byte offset of this line in the log; usable as `before`/`after`.
+ */
+offset: number, stream: string, content: string, };
+
+export type RawLogPage = { lines: Array<RawLogLine>, start_offset: number, end_offset: number, 
+/**
+ * Size of the log when it was read; grows while the process is running.
+ */
+total_bytes: number, has_more_before: boolean, has_more_after: boolean, running: boolean, };
+
+export type TokenResponse = { access_token: string, expires_at: string | null, };
+
+export type UserSystemInfo = { version: string, config: Config, machine_id: string, login_status: LoginStatus, remote_auth_degraded: string | null, environment: Environment, 
+/**
+ * Capabilities supported per executor (e.g., { "CLAUDE_CODE": ["SESSION_FORK"] })
+ */
+capabilities: { [key in string]?: Array<BaseAgentCapability> }, shared_api_base: string | null, preview_proxy_port: number | null, executors: { [key in BaseCodingAgent]?: ExecutorProfile }, };
+
+export type Environment = { os_type: string, os_version: string, os_architecture: string, bitness: string, };
+
+export type McpServerQuery = { executor: BaseCodingAgent, };
+
+export type UpdateMcpServersBody = { servers: { [key in string]?: JsonValue }, };
+
+export type GetMcpServerResponse = { mcp_config: McpConfig, config_path: string, };
+
+export type CheckEditorAvailabilityQuery = { editor_type: EditorType, };
+
+export type SecretSummary = { name: string, created_at: string, updated_at: string, };
+
+export type SetSecretRequest = { value: string, };
+
+export enum GitCredentialKind { ssh_key = "ssh_key", personal_access_token = "personal_access_token", oauth_token = "oauth_token" }
+
+/**
+ * Credential metadata safe to return to clients; never includes the token.
+ */
+export type GitCredentialSummary = { id: string, name: string, 
+/**
+ * e.g. `github.com` or `gitlab.example.com`.
+ */
+host: string, kind: GitCredentialKind, username: string | null, ssh_key_path: string | null, created_at: Date, updated_at: Date, };
+
+export type CreateGitCredential = { name: string, host: string, kind: GitCredentialKind, username?: string | null, 
+/**
+ * Required for SSH keys.
+ */
+ssh_key_path?: string | null, 
+/**
+ * Required for tokens. Write-only: never returned by any endpoint.
+ */
+token?: string | null, };
+
+export type ConfigOverrides = { executor_profile: ExecutorProfileId | null, git_branch_prefix: string | null, setup_script: string | null, cleanup_script: string | null, dev_server_script: string | null, };
+
+export enum ConfigSource { default = "default", global = "global", repo = "repo", workspace = "workspace" }
+
+export type EffectiveConfigSources = { executor_profile: ConfigSource, git_branch_prefix: ConfigSource, setup_script: ConfigSource, cleanup_script: ConfigSource, dev_server_script: ConfigSource, };
+
+export type EffectiveConfig = { executor_profile: ExecutorProfileId, git_branch_prefix: string, setup_script: string | null, cleanup_script: string | null, dev_server_script: string | null, 
+/**
+ * Where each value above came from.
+ */
+sources: EffectiveConfigSources, };
+
+export type RepoConfigExport = { name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, archive_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, default_working_dir: string | null, verify_script: string | null, script_settings: RepoScriptSettings, overrides: ConfigOverrides, };
+
+export type TemplateExport = { tag_name: string, content: string, };
+
+export type ConfigBundle = { version: number, exported_at: string, 
+/**
+ * Left out of hand-written bundles to keep the local profiles.
+ */
+executor_profiles: ExecutorConfigs | null, repos: Array<RepoConfigExport>, templates: Array<TemplateExport>, };
+
+export type ConfigImportSummary = { executor_profiles_imported: boolean, repos_updated: Array<string>, 
+/**
+ * Repos in the bundle with no local repo of the same name.
+ */
+repos_not_found: Array<string>, templates_created: number, templates_updated: number, };
+
+export type CheckEditorAvailabilityResponse = { available: boolean, };
+
+export type CheckAgentAvailabilityQuery = { executor: BaseCodingAgent, };
+
+export type AgentPresetOptionsQuery = { executor: BaseCodingAgent, variant: string | null, };
+
+export type CurrentUserResponse = { user_id: string, };
+
+export type StartSpake2EnrollmentRequest = { enrollment_code: string, client_message_b64: string, };
+
+export type FinishSpake2EnrollmentRequest = { enrollment_id: string, client_id: string, client_name: string, client_browser: string, client_os: string, client_device: string, public_key_b64: string, client_proof_b64: string, };
+
+export type StartSpake2EnrollmentResponse = { enrollment_id: string, server_message_b64: string, };
+
+export type FinishSpake2EnrollmentResponse = { signing_session_id: string, server_public_key_b64: string, server_proof_b64: string, };
+
+export type RelayPairedClient = { client_id: string, client_name: string, client_browser: string, client_os: string, client_device: string, };
+
+export type ListRelayPairedClientsResponse = { clients: Array<RelayPairedClient>, };
+
+export type RemoveRelayPairedClientResponse = { removed: boolean, };
+
+export type RefreshRelaySigningSessionRequest = { client_id: string, timestamp: bigint, nonce: string, signature_b64: string, };
+
+export type RefreshRelaySigningSessionResponse = { signing_session_id: string, };
+
+export type CreateFollowUpAttempt = { prompt: string, executor_config: ExecutorConfig, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, idempotency_key: string | null, };
+
+export type ResetProcessRequest = { process_id: string, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
+
+export type ForkSessionRequest = { 
+/**
+ * The coding agent turn to fork at. The fork starts from the repo state
+ * and conversation before it, and runs `prompt` in its place.
+ */
+process_id: string, prompt: string, executor_config: ExecutorConfig, name: string | null, };
+
+export type ChangeTargetBranchRequest = { repo_id: string, new_target_branch: string, };
+
+export type ChangeTargetBranchResponse = { repo_id: string, new_target_branch: string, status: [number, number], };
+
+export type AddWorkspaceRepoRequest = { repo_id: string, target_branch: string, package_id?: string | null, };
+
+export type SetWorkspaceEnvProfilesRequest = { profile_ids: Array<string>, };
+
+export type AddWorkspaceRepoResponse = { workspace: Workspace, repo: RepoWithTargetBranch, };
+
+export type MergeWorkspaceRequest = { repo_id: string, };
+
+export type PushWorkspaceRequest = { repo_id: string, };
+
+export type PushWithLeaseRequest = { repo_id: string, 
+/**
+ * Track the pushed branch, so later pulls and status checks use it.
+ * Defaults to true.
+ */
+set_upstream: boolean | null, };
+
+export type RenameBranchRequest = { new_branch_name: string, };
+
+export type RenameBranchResponse = { branch: string, };
+
+export type StartReviewRequest = { executor_config: ExecutorConfig, additional_prompt: string | null, use_all_workspace_commits: boolean, };
+
+export type ReviewError = { "type": "process_already_running" };
+
+export type OpenEditorRequest = { editor_type: string | null, file_path: string | null, };
+
+export type OpenEditorResponse = { url: string | null, };
+
+export type OpenRemoteEditorResponse = { url: string, local_port: number, ssh_alias: string, };
+
+export type OpenRemoteWorkspaceInEditorRequest = { host_id: string, workspace_id: string, editor_type: string | null, file_path: string | null, };
+
+export type PairRelayHostRequest = { host_id: string, host_name: string, enrollment_code: string, };
+
+export type PairRelayHostResponse = { paired: boolean, };
+
+export type RelayPairedHost = { host_id: string, host_name: string | null, paired_at: string | null, };
+
+export type ListRelayPairedHostsResponse = { hosts: Array<RelayPairedHost>, };
+
+export type RemoveRelayPairedHostResponse = { removed: boolean, };
+
+export type CreateWorkspaceApiRequest = { name: string | null, idempotency_key: string | null, };
+
+export type LinkedIssueInfo = { remote_project_id: string, issue_id: string, };
+
+export type CreatePrApiRequest = { title: string, body: string | null, target_branch: string | null, draft: boolean | null, repo_id: string, auto_generate_description: boolean, squash_merge_after_description: boolean, };
+
+export type AttachmentResponse = { id: string, file_path: string, 
+/**
+ * Stable URL serving the file, independent of any workspace.
+ */
+url: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
+
+export type AttachmentMetadata = { exists: boolean, file_name: string | null, path: string | null, size_bytes: bigint | null, format: string | null, proxy_url: string | null, };
+
+export type WorkspaceRepoInput = { repo_id: string, target_branch: string, 
+/**
+ * The monorepo package of the repo to work in.
+ */
+package_id?: string | null, };
+
+export type RunAgentSetupRequest = { executor_profile_id: ExecutorProfileId, };
+
+export type RunAgentSetupResponse = Record<string, never>;
+
+export type GhCliSetupError = "BREW_MISSING" | "SETUP_HELPER_NOT_SUPPORTED" | { "OTHER": { message: string, } };
+
+export type RebaseWorkspaceRequest = { repo_id: string, old_base_branch: string | null, new_base_branch: string | null, };
+
+export type ContinueRebaseRequest = { repo_id: string, };
+
+export type AbortConflictsRequest = { repo_id: string, };
+
+export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, conflicted_files: Array<string>, target_branch: string, } | { "type": "rebase_in_progress" };
+
+export type PushError = { "type": "force_push_required" } | { "type": "force_push_blocked", findings: Array<PreflightFinding>, } | { "type": "branch_protected", message: string, } | { "type": "remote_branch_moved", ahead: number, behind: number, };
+
+export type PushPreflightQuery = { repo_id: string, };
+
+export type WorktreeSnapshotsQuery = { repo_id: string, };
+
+export type DiffFileQuery = { repo_id: string, 
+/**
+ * Path relative to the repository root.
+ */
+path: string, };
+
+export type RestoreWorktreeSnapshotRequest = { repo_id: string, snapshot_id: string, };
+
+/**
+ * A rule of the workspace branch or the PR's target branch the user should
+ * know about. `branch` names the branch the rule applies to.
+ */
+export type PreflightFinding = { "type": "force_push_blocked", branch: string, } | { "type": "direct_push_blocked", branch: string, } | { "type": "unsigned_commits", branch: string, count: number, } | { "type": "required_reviews", branch: string, count: number, code_owners: boolean, } | { "type": "required_checks", branch: string, checks: Array<string>, } | { "type": "linear_history_required", branch: string, } | { "type": "rules_unavailable", branch: string, message: string, };
+
+export type PushPreflight = { 
+/**
+ * Unset when the remote isn't a supported provider.
+ */
+provider: ProviderKind | null, branch: string, target_branch: string, findings: Array<PreflightFinding>, can_push: boolean, can_force_push: boolean, };
+
+export enum MergeReadinessState { up_to_date = "up_to_date", clean = "clean", conflicts = "conflicts", unknown = "unknown" }
+
+export type RepoMergeReadiness = { repo_id: string, repo_name: string, target_branch: string, state: MergeReadinessState, commits_ahead: number, commits_behind: number, 
+/**
+ * Files that would conflict when merging into the target branch.
+ */
+conflicted_files: Array<string>, };
+
+export type WorkspaceMergeReadiness = { workspace_id: string, repos: Array<RepoMergeReadiness>, };
+
+export enum StartupTask { log_migration = "log_migration", before_head_commits = "before_head_commits", repo_names = "repo_names", log_search_index = "log_search_index" }
+
+export enum StartupTaskState { pending = "pending", running = "running", completed = "completed", failed = "failed" }
+
+export type StartupTaskProgress = { task: StartupTask, state: StartupTaskState, completed: number, 
+/**
+ * Unknown until the task has counted its work.
+ */
+total: number | null, error: string | null, };
+
+export type StartupStatus = { 
+/**
+ * Whether every startup task has finished, successfully or not.
+ */
+ready: boolean, tasks: Array<StartupTaskProgress>, };
+
+/**
+ * Lock wait metrics of one repository since startup.
+ */
+export type RepoLockStats = { 
+/**
+ * The repository's common git dir.
+ */
+repo: string, acquisitions: number, 
+/**
+ * Acquisitions that had to wait for another operation.
+ */
+contended: number, total_wait_ms: number, max_wait_ms: number, 
+/**
+ * Operations currently waiting.
+ */
+waiting: number, };
+
+export type QueuedWriterStats = { writer: string, writes: number, total_wait_ms: number, max_wait_ms: number, };
+
+export type WriteQueueStats = { 
+/**
+ * Writes waiting or running right now.
+ */
+depth: number, max_depth: number, writers: Array<QueuedWriterStats>, };
+
+export type PrError = { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "cli_not_logged_in", provider: ProviderKind, } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, } | { "type": "unsupported_provider" } | { "type": "branch_protected", message: string, };
+
+export type RunScriptError = { "type": "no_script_configured" } | { "type": "process_already_running" };
+
+export type AssociateWorkspaceAttachmentsRequest = { attachment_ids: Array<string>, };
+
+export type ImportIssueAttachmentsRequest = { issue_id: string, };
+
+export type ImportIssueAttachmentsResponse = { attachment_ids: Array<string>, };
+
+export type AttachPrResponse = { pr_attached: boolean, pr_url: string | null, pr_number: bigint | null, pr_status: MergeStatus | null, };
+
+export type AttachExistingPrRequest = { repo_id: string, };
+
+export type PrCommentsResponse = { comments: Array<UnifiedPrComment>, };
+
+export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "cli_not_logged_in", provider: ProviderKind, };
+
+export type GetPrCommentsQuery = { repo_id: string, };
+
+export type CreateAndStartWorkspaceRequest = { name: string | null, repos: Array<WorkspaceRepoInput>, linked_issue: LinkedIssueInfo | null, executor_config: ExecutorConfig, prompt: string, attachment_ids: Array<string> | null, idempotency_key: string | null, 
+/**
+ * Environment profiles (from the selected repos) to inject into every
+ * process started in the workspace.
+ */
+env_profile_ids?: Array<string>, };
+
+export type CreateAndStartWorkspaceResponse = { workspace: Workspace, execution_process: ExecutionProcess, };
+
+export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string | null, body: string, created_at: string, url: string | null, } | { "comment_type": "review", id: bigint, author: string, author_association: string | null, body: string, created_at: string, url: string | null, path: string, line: bigint | null, side: string | null, diff_hunk: string | null, };
+
+export type ProviderKind = "git_hub" | "azure_dev_ops" | "unknown";
+
+export type PullRequestDetail = { number: bigint, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, title: string, base_branch: string, head_branch: string, };
 
 export type GitRemote = { name: string, url: string, };
 
+/**
+ * Outcome of a push with `--force-with-lease`. `ahead` and `behind` compare
+ * the pushed branch with the remote branch it replaced; `behind` counts the
+ * remote commits the push dropped.
+ */
+export type LeasedPush = { remote: string, 
+/**
+ * Set when upstream tracking was configured, e.g. `origin/vk/feature`.
+ */
+upstream: string | null, 
+/**
+ * The branch did not exist on the remote before.
+ */
+created: boolean, ahead: number, behind: number, };
+
+/**
+ * Uncommitted changes of a worktree, kept as a commit on top of the HEAD they
+ * were made against.
+ */
+export type WorktreeSnapshot = { id: string, branch: string, commit: string, base_commit: string, 
+/**
+ * Why the snapshot was taken, e.g. "Before rebase onto main".
+ */
+reason: string, created_at: Date, };
+
 export type ListPrsError = { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "auth_failed", message: string, } | { "type": "unsupported_provider" };
 
 export type LinkPrToIssueRequest = { pr_url: string, pr_number: number, base_branch: string, };
@@ -482,9 +1439,81 @@ export type DirectoryEntry = { name: string, path: string, is_directory: boolean
 
 export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_path: string, };
 
+export type WorkspaceFile = { path: string, content: string, 
+/**
+ * Hex SHA-256 of the content; pass it back as `expected_sha256` to
+ * refuse a write if the file changed in the meantime.
+ */
+sha256: string, size_bytes: number, };
+
+export type WriteWorkspaceFile = { path: string, content: string, expected_sha256?: string, };
+
+/**
+ * Replace `old_text`, which must occur exactly once, with `new_text`.
+ */
+export type FileEdit = { old_text: string, new_text: string, };
+
+export type PatchWorkspaceFile = { path: string, 
+/**
+ * Applied in order, each to the result of the previous one.
+ */
+edits: Array<FileEdit>, expected_sha256?: string, };
+
+export enum FileChangeKind { created = "created", modified = "modified", removed = "removed" }
+
+export type FileChange = { kind: FileChangeKind, 
+/**
+ * Relative to the watched root, with `/` separators.
+ */
+path: string, 
+/**
+ * Always false for removed paths, whose type can no longer be read.
+ */
+is_dir: boolean, };
+
 export type SearchMode = "taskform" | "settings";
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, remote_onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, commit_reminder_enabled: boolean, commit_reminder_prompt: string | null, send_message_shortcut: SendMessageShortcut, relay_enabled: boolean, host_nickname: string | null, };
+export type LogSearchMatch = { line_number: bigint, byte_offset: bigint, stream: string, snippet: string, };
+
+export type LogSearchResult = { execution_id: string, session_id: string, workspace_id: string, run_reason: ExecutionProcessRunReason, matches: Array<LogSearchMatch>, };
+
+export type SemanticSearchResult = { repo_id: string, repo_name: string, 
+/**
+ * Relative to the repo root.
+ */
+path: string, 
+/**
+ * First and last line of the match, 1-based and inclusive.
+ */
+start_line: number, end_line: number, 
+/**
+ * Cosine similarity to the query.
+ */
+score: number, snippet: string, };
+
+/**
+ * A match's position within its line, as 1-based character columns with an
+ * exclusive end.
+ */
+export type CodeSearchRange = { start: number, end: number, };
+
+export type CodeSearchMatch = { repo_id: string, repo_name: string, 
+/**
+ * Relative to the repo root.
+ */
+path: string, line_number: number, 
+/**
+ * Column of the first match on the line.
+ */
+column: number, line: string, ranges: Array<CodeSearchRange>, context_before: Array<string>, context_after: Array<string>, };
+
+export type CodeSearchResponse = { matches: Array<CodeSearchMatch>, 
+/**
+ * Offset of the next page, if there are more matches.
+ */
+next_offset: number | null, };
+
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, remote_onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, commit_reminder_enabled: boolean, commit_reminder_prompt: string | null, send_message_shortcut: SendMessageShortcut, relay_enabled: boolean, host_nickname: string | null, log_redaction: LogRedactionConfig, remote_approvals: RemoteApprovalConfig, semantic_search: SemanticSearchConfig, notification_channels: NotificationChannelsConfig, quiet_hours: QuietHoursConfig, session_sharing: SessionSharingConfig, warm_pool: WarmPoolConfig, log_writes: LogWritesConfig, diff: DiffConfig, database: DatabaseConfig, users: UsersConfig, };
 
 export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 
@@ -506,6 +1535,196 @@ export type ShowcaseState = { seen_features: Array<string>, };
 
 export type SendMessageShortcut = "ModifierEnter" | "Enter";
 
+export type LogRedactionConfig = { 
+/**
+ * Master switch; repos can additionally opt out individually.
+ */
+enabled: boolean, 
+/**
+ * Extra regexes applied on top of the built-in secret patterns. A named
+ * `secret` group limits redaction to that group instead of the whole match.
+ */
+patterns: Array<string>, };
+
+/**
+ * Approval links sent off the machine, so approvals can be answered from a
+ * phone without the app open.
+ */
+export type RemoteApprovalConfig = { enabled: boolean, 
+/**
+ * Address the server is reachable at from other devices (e.g. through a
+ * tunnel). Links are only sent when this is set.
+ */
+public_url: string | null, 
+/**
+ * Slack incoming webhook that receives approval links.
+ */
+slack_webhook_url: string | null, 
+/**
+ * Address that receives approval links by email, sent with `sendmail`.
+ */
+email_to: string | null, };
+
+/**
+ * Where semantic code search gets its embeddings from.
+ */
+export type EmbeddingProvider = "local" | "endpoint";
+
+export type SemanticSearchConfig = { provider: EmbeddingProvider, 
+/**
+ * Embeddings URL, e.g. `http://localhost:11434/v1/embeddings` for a
+ * local Ollama.
+ */
+endpoint_url: string | null, model: string | null, 
+/**
+ * Environment variable holding the endpoint's API key, if it needs one.
+ */
+api_key_env: string | null, };
+
+/**
+ * A way of delivering notifications.
+ */
+export type NotificationChannel = "desktop" | "sound" | "webhook" | "slack" | "email";
+
+/**
+ * What a notification is about. Each event type is routed separately.
+ */
+export type NotificationEvent = "workspace_complete" | "approval_needed" | "question_asked" | "general";
+
+/**
+ * Channels beyond desktop and sound, and which channels each event type
+ * goes to. Desktop and sound are switched on and off by
+ * `NotificationConfig`; the other channels are on once configured.
+ */
+export type NotificationChannelsConfig = { 
+/**
+ * URL that receives each notification as a JSON POST.
+ */
+webhook_url: string | null, 
+/**
+ * Slack incoming webhook.
+ */
+slack_webhook_url: string | null, 
+/**
+ * Address that receives notifications by email, sent with `sendmail`.
+ */
+email_to: string | null, 
+/**
+ * URL that receives a JSON summary (status, commits, diff stats, cost)
+ * of each finished workspace run.
+ */
+completion_webhook_url: string | null, 
+/**
+ * Channels per event type. Event types not listed go to desktop and
+ * sound.
+ */
+routes: { [key in NotificationEvent]?: Array<NotificationChannel> }, };
+
+/**
+ * Do-not-disturb window. Notifications raised inside it are held back and
+ * delivered as a summary once it ends.
+ */
+export type QuietHoursConfig = { enabled: boolean, 
+/**
+ * Local time the window starts, as `HH:MM`.
+ */
+start: string, 
+/**
+ * Local time the window ends, as `HH:MM`. May be earlier than `start`
+ * for windows that span midnight.
+ */
+end: string, };
+
+/**
+ * Read-only public links to session transcripts, served by this server.
+ */
+export type SessionSharingConfig = { 
+/**
+ * Off by default; turning it off also stops serving existing links.
+ */
+enabled: boolean, 
+/**
+ * Address the server is reachable at by the people links are shared
+ * with. Links can only be created when this is set.
+ */
+public_url: string | null, };
+
+/**
+ * Pre-provisioned worktrees, with the setup script already run, that new
+ * workspaces of frequently used repos start from.
+ */
+export type WarmPoolConfig = { enabled: boolean, 
+/**
+ * How many of the most recently used repos to keep warm worktrees for.
+ */
+repos: number, worktrees_per_repo: number, 
+/**
+ * Disk space the pool may take up, in megabytes. The pool stops
+ * refilling once its worktrees reach it.
+ */
+max_disk_mb: number, };
+
+/**
+ * When buffered process output is forced to disk with fsync.
+ */
+export type LogFsyncPolicy = "never" | "on_finish" | "on_flush";
+
+export type LogWritePolicy = { 
+/**
+ * How long output may be buffered before it is written to the log
+ * file, in milliseconds. 0 writes every line as it arrives.
+ */
+flush_interval_ms: number, fsync: LogFsyncPolicy, };
+
+/**
+ * How process output is written to log files, by what the process runs.
+ * Live output is streamed from memory, so buffering only delays the file.
+ */
+export type LogWritesConfig = { coding_agent: LogWritePolicy, dev_server: LogWritePolicy, 
+/**
+ * Setup, cleanup, archive and verify scripts.
+ */
+scripts: LogWritePolicy, };
+
+export type DiffConfig = { 
+/**
+ * Files larger than this, in KiB, are shown in the diff viewer without
+ * their contents.
+ */
+max_file_size_kb: number, };
+
+/**
+ * How the local database is opened. Applied at startup.
+ */
+export type DatabaseConfig = { 
+/**
+ * Write-ahead logging, so reads don't block writes.
+ */
+wal_enabled: boolean, 
+/**
+ * How long a write waits for another to finish before failing with
+ * `database is locked`, in milliseconds.
+ */
+busy_timeout_ms: number, };
+
+/**
+ * User identities, for a team sharing one server.
+ */
+/**
+ * User identities, for a team sharing one server.
+ */
+export type UsersConfig = { 
+/**
+ * Reject API requests that aren't signed in as a user. Otherwise they
+ * act as the machine's owner.
+ */
+require_sign_in: boolean, 
+/**
+ * Remote organization whose members may sign in with their remote
+ * account. Remote sign-in is off while unset.
+ */
+remote_organization_id: string | null, };
+
 export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
 
 export type QueuedMessage = { 
@@ -558,7 +1777,7 @@ reasoning_id?: string | null,
  */
 permission_policy?: PermissionPolicy | null, };
 
-export type ScriptContext = "SetupScript" | "CleanupScript" | "ArchiveScript" | "DevServer" | "ToolInstallScript";
+export type ScriptContext = "SetupScript" | "CleanupScript" | "ArchiveScript" | "VerifyScript" | "DevServer" | "ToolInstallScript";
 
 export type ScriptRequest = { script: string, language: ScriptRequestLanguage, context: ScriptContext, 
 /**